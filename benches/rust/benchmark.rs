@@ -17,7 +17,7 @@ use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use expressions::ALL_EXPRESSIONS;
 use std::collections::HashSet;
 use std::hint::black_box;
-use symb_anafis::{CompiledEvaluator, Diff, Simplify, parse, symb};
+use symb_anafis::{CompiledEvaluator, Diff, Expr, Simplify, parse, symb};
 
 // =============================================================================
 // Parsing Benchmarks
@@ -120,6 +120,34 @@ fn bench_simplify_only(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark simplification of one large expression: the raw derivatives of all
+/// expressions summed, where most rules are tried on nodes they cannot rewrite
+fn bench_simplify_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("4_simplify_large");
+    let empty = HashSet::new();
+
+    let terms: Vec<Expr> = ALL_EXPRESSIONS
+        .iter()
+        .map(|(_name, expr_str, var, _fixed)| {
+            let expr = parse(expr_str, &empty, &empty, None).unwrap();
+            Diff::new()
+                .skip_simplification(true)
+                .differentiate(&expr, &symb(var))
+                .unwrap()
+        })
+        .collect();
+    let large = Expr::sum(terms);
+    let simplify_builder = Simplify::new();
+
+    group.bench_with_input(
+        BenchmarkId::new("symb_anafis", large.node_count()),
+        &large,
+        |b, expr| b.iter(|| simplify_builder.simplify(black_box(expr))),
+    );
+
+    group.finish();
+}
+
 // =============================================================================
 // Compilation Benchmarks
 // =============================================================================
@@ -368,6 +396,7 @@ criterion_group!(
     bench_diff,
     bench_diff_simplified,
     bench_simplify_only,
+    bench_simplify_large,
     bench_compile,
     bench_eval,
     bench_full_pipeline,
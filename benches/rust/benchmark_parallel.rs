@@ -14,6 +14,8 @@
 //! - `eval_f64` (high-perf batch, columnar data)
 //! - `eval_batch` (low-level `CompiledEvaluator` method)
 //! - `evaluate` loop (baseline, single-point calls)
+//! - `eval_table` (column tables, SIMD lanes read in place)
+//! - `eval_batch_parallel` (SIMD chunks on Rayon pools of 1, 2, 4, ... threads)
 //!
 //! Run with: cargo bench --bench `benchmark_parallel` --features parallel
 
@@ -362,6 +364,139 @@ fn bench_eval_apis(c: &mut Criterion) {
     group.finish();
 }
 
+// =============================================================================
+// Column Tables (eval_table vs eval_batch)
+// =============================================================================
+
+/// Benchmark a 1M-row, three-parameter table stored as rows against the same table
+/// stored as columns
+fn bench_eval_table(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval_table_1m_rows");
+    group.sample_size(10);
+    let empty = HashSet::new();
+
+    let expr = parse("a*sin(x) + b*x^2 - exp(-a*x)", &empty, &empty, None).unwrap();
+    let evaluator = CompiledEvaluator::compile(&expr, &["x", "a", "b"], None).unwrap();
+
+    let n_rows: u32 = 1_000_000;
+    let rows: Vec<[f64; 3]> = (0..n_rows)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(n_rows);
+            [4.0_f64.mul_add(t, -2.0), t + 0.5, 1.5 - t]
+        })
+        .collect();
+    let columns: Vec<Vec<f64>> = (0..3)
+        .map(|col| rows.iter().map(|row| row[col]).collect())
+        .collect();
+    let column_refs: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+    let mut output = vec![0.0; rows.len()];
+
+    // ---------------------------------------------------------------------
+    // Row-major data: transpose into columns, then eval_batch
+    // ---------------------------------------------------------------------
+    group.bench_function("transpose_eval_batch", |b| {
+        b.iter(|| {
+            let transposed: Vec<Vec<f64>> = (0..3)
+                .map(|col| rows.iter().map(|row| row[col]).collect())
+                .collect();
+            let refs: Vec<&[f64]> = transposed.iter().map(Vec::as_slice).collect();
+            evaluator.eval_batch(&refs, &mut output, None).unwrap();
+            black_box(output[0])
+        });
+    });
+
+    // ---------------------------------------------------------------------
+    // Column data, scalar eval_batch
+    // ---------------------------------------------------------------------
+    group.bench_function("eval_batch", |b| {
+        b.iter(|| {
+            evaluator
+                .eval_batch(&column_refs, &mut output, None)
+                .unwrap();
+            black_box(output[0])
+        });
+    });
+
+    // ---------------------------------------------------------------------
+    // Column data, SIMD lanes in place
+    // ---------------------------------------------------------------------
+    group.bench_function("eval_table", |b| {
+        b.iter(|| {
+            evaluator.eval_table(&column_refs, &mut output).unwrap();
+            black_box(output[0])
+        });
+    });
+
+    for chunk_size in [1024, 16_384] {
+        group.bench_with_input(
+            BenchmarkId::new("eval_table_parallel", chunk_size),
+            &chunk_size,
+            |b, &chunk_size| {
+                b.iter(|| {
+                    evaluator
+                        .eval_table_parallel(&column_refs, &mut output, chunk_size)
+                        .unwrap();
+                    black_box(output[0])
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// =============================================================================
+// Thread Scaling (eval_batch_parallel)
+// =============================================================================
+
+/// Benchmark `eval_batch_parallel` on Rayon pools of 1, 2, 4, ... threads, up to the
+/// available cores; with enough points the time should drop almost linearly
+fn bench_eval_batch_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval_batch_parallel_threads");
+    group.sample_size(10);
+    let empty = HashSet::new();
+
+    let expr = parse("a*sin(x) + b*x^2 - exp(-a*x)", &empty, &empty, None).unwrap();
+    let evaluator = CompiledEvaluator::compile(&expr, &["x", "a", "b"], None).unwrap();
+
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+    let threads: Vec<usize> = std::iter::successors(Some(1), |&n| Some(n * 2))
+        .take_while(|&n| n < cores)
+        .chain(std::iter::once(cores))
+        .collect();
+
+    for n_points in [100_000_u32, 1_000_000] {
+        let xs: Vec<f64> = (0..n_points)
+            .map(|i| 4.0_f64.mul_add(f64::from(i) / f64::from(n_points), -2.0))
+            .collect();
+        let columns: [&[f64]; 3] = [&xs, &[0.7], &[1.3]];
+        let mut output = vec![0.0; xs.len()];
+
+        for &n_threads in &threads {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(n_threads)
+                .build()
+                .unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("{n_points}_points"), n_threads),
+                &n_threads,
+                |b, _| {
+                    b.iter(|| {
+                        pool.install(|| {
+                            evaluator
+                                .eval_batch_parallel(&columns, &mut output)
+                                .unwrap();
+                        });
+                        black_box(output[0])
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 // =============================================================================
 // Criterion Setup
 // =============================================================================
@@ -372,6 +507,8 @@ criterion_group!(
     bench_eval_scaling,
     bench_multi_expr,
     bench_eval_apis,
+    bench_eval_table,
+    bench_eval_batch_parallel,
 );
 
 criterion_main!(benches);
@@ -0,0 +1,238 @@
+// Benchmark requirements: unwrap for setup, stdout for progress, similar names for math variables
+#![allow(
+    clippy::unwrap_used,
+    clippy::print_stdout,
+    clippy::similar_names,
+    reason = "Benchmark requirements: unwrap for setup, stdout for progress, similar names for math variables"
+)]
+//! Compiled Gradient Benchmarks
+
+//!
+//! Compares one `CompiledGradient` (all partials in a shared program) against
+//! N independent `CompiledEvaluator`s, one per partial derivative:
+//! - compilation of the full gradient
+//! - single-point evaluation over 1000 points
+//! - batch evaluation over 1000 points (SIMD with the `parallel` feature)
+//!
+//! Also compares the dense `hessian` with `hessian_sparse` on spring networks.
+//!
+//! Run with: cargo bench --bench gradient [--features parallel]
+
+mod expressions;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use expressions::ALL_EXPRESSIONS;
+use std::collections::HashSet;
+use std::hint::black_box;
+use symb_anafis::{
+    CompiledEvaluator, CompiledGradient, Diff, Expr, Symbol, hessian, hessian_sparse, parse, symb,
+};
+
+const N_POINTS: usize = 1000;
+
+/// Every free variable of the expression, sorted (`pi` parses as the constant)
+fn gradient_params(var: &'static str, fixed: &[&'static str]) -> Vec<&'static str> {
+    let mut params: Vec<&str> = std::iter::once(var)
+        .chain(fixed.iter().copied())
+        .filter(|p| *p != "pi")
+        .collect();
+    params.sort_unstable();
+    params
+}
+
+fn separate_evaluators(expr: &Expr, params: &[&str]) -> Vec<CompiledEvaluator> {
+    let diff = Diff::new();
+    params
+        .iter()
+        .map(|p| {
+            let partial = diff.differentiate(expr, &symb(p)).unwrap();
+            CompiledEvaluator::compile(&partial, params, None).unwrap()
+        })
+        .collect()
+}
+
+/// Points with every coordinate in `[0.5, 1.5)`, away from the expressions' poles
+fn test_points(dim: usize) -> Vec<Vec<f64>> {
+    (0..N_POINTS)
+        .map(|i| {
+            (0..dim)
+                .map(|j| {
+                    let seed = u32::try_from(i * 31 + j * 17).unwrap() % 1000;
+                    f64::from(seed).mul_add(0.001, 0.5)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// =============================================================================
+// Compilation
+// =============================================================================
+
+fn bench_gradient_compile(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gradient_compile");
+    let empty = HashSet::new();
+
+    for (name, expr_str, var, fixed) in ALL_EXPRESSIONS {
+        let expr = parse(expr_str, &empty, &empty, None).unwrap();
+        let params = gradient_params(var, fixed);
+
+        group.bench_with_input(BenchmarkId::new("shared", name), &expr, |b, expr| {
+            b.iter(|| CompiledGradient::compile(black_box(expr), &params, None).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("separate", name), &expr, |b, expr| {
+            b.iter(|| separate_evaluators(black_box(expr), &params));
+        });
+    }
+
+    group.finish();
+}
+
+// =============================================================================
+// Evaluation
+// =============================================================================
+
+fn bench_gradient_eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gradient_eval_1000pts");
+    let empty = HashSet::new();
+
+    for (name, expr_str, var, fixed) in ALL_EXPRESSIONS {
+        let expr = parse(expr_str, &empty, &empty, None).unwrap();
+        let params = gradient_params(var, fixed);
+        let dim = params.len();
+        let gradient = CompiledGradient::compile(&expr, &params, None).unwrap();
+        let separate = separate_evaluators(&expr, &params);
+
+        let separate_count: usize = separate
+            .iter()
+            .map(CompiledEvaluator::instruction_count)
+            .sum();
+        println!(
+            "{name}: {dim} partials, {} shared vs {separate_count} separate instructions",
+            gradient.instruction_count()
+        );
+
+        let points = test_points(dim);
+        let point_refs: Vec<&[f64]> = points.iter().map(Vec::as_slice).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("shared_evaluate", name),
+            &point_refs,
+            |b, refs| {
+                let mut out = vec![0.0; dim];
+                b.iter(|| {
+                    let mut sum = 0.0;
+                    for point in refs {
+                        gradient.evaluate(point, &mut out);
+                        sum += out.iter().sum::<f64>();
+                    }
+                    sum
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("separate_evaluate", name),
+            &point_refs,
+            |b, refs| {
+                b.iter(|| {
+                    let mut sum = 0.0;
+                    for point in refs {
+                        for evaluator in &separate {
+                            sum += evaluator.evaluate(point);
+                        }
+                    }
+                    sum
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("shared_eval_batch", name),
+            &point_refs,
+            |b, refs| {
+                let mut out = vec![0.0; N_POINTS * dim];
+                b.iter(|| {
+                    gradient.eval_batch(black_box(refs), &mut out).unwrap();
+                    out[0]
+                });
+            },
+        );
+
+        // The single-expression batch API is columnar and SIMD-only with `parallel`
+        #[cfg(feature = "parallel")]
+        {
+            let columns: Vec<Vec<f64>> = (0..dim)
+                .map(|j| points.iter().map(|p| p[j]).collect())
+                .collect();
+            let column_refs: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+            group.bench_with_input(
+                BenchmarkId::new("separate_eval_batch", name),
+                &column_refs,
+                |b, cols| {
+                    let mut out = vec![0.0; N_POINTS];
+                    b.iter(|| {
+                        let mut sum = 0.0;
+                        for evaluator in &separate {
+                            let mut workspace =
+                                vec![wide::f64x4::splat(0.0); evaluator.workspace_size()];
+                            evaluator
+                                .eval_batch(black_box(cols), &mut out, Some(&mut workspace))
+                                .unwrap();
+                            sum += out[0];
+                        }
+                        sum
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// =============================================================================
+// Sparse Hessian
+// =============================================================================
+
+/// Energy of a ring of `n` springs: `sum (x_i - x_{i+1})^2`, one term per neighbour pair
+fn spring_ring(n: usize) -> (Expr, Vec<Symbol>) {
+    let empty = HashSet::new();
+    let formula = (0..n)
+        .map(|i| format!("(x{i} - x{})^2", (i + 1) % n))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let symbols = (0..n).map(|i| symb(&format!("x{i}"))).collect();
+    (parse(&formula, &empty, &empty, None).unwrap(), symbols)
+}
+
+fn bench_hessian_sparse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hessian_spring_ring");
+
+    for n in [10, 25, 50] {
+        let (energy, symbols) = spring_ring(n);
+        let vars: Vec<&Symbol> = symbols.iter().collect();
+
+        group.bench_with_input(BenchmarkId::new("dense", n), &energy, |b, energy| {
+            b.iter(|| hessian(black_box(energy), &vars).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("sparse", n), &energy, |b, energy| {
+            b.iter(|| hessian_sparse(black_box(energy), &vars).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+// =============================================================================
+// Criterion Setup
+// =============================================================================
+
+criterion_group!(
+    benches,
+    bench_gradient_compile,
+    bench_gradient_eval,
+    bench_hessian_sparse
+);
+
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! Device side of an embedded controller that runs a control law compiled on the host.
+//!
+//! The host compiles the formula once and ships the bytes of
+//! `CompiledEvaluator::to_bytes`, for instance as a file baked into the firmware:
+//!
+//! ```ignore
+//! let law = parse("kp*e + ki*i - kd*de/(1 + tau)", &params, &HashSet::new(), None)?;
+//! std::fs::write("law.bin", CompiledEvaluator::compile(&law, &["e", "i", "de"], None)?.to_bytes())?;
+//! ```
+//!
+//! This crate is `no_std` and only needs the evaluation core and `alloc` (the firmware
+//! provides the global allocator). Check it for a build without `std` with:
+//!
+//! ```text
+//! cargo check --no-default-features --features eval-core --example embedded_eval
+//! ```
+#![no_std]
+
+use symb_anafis::eval_core::{DecodeError, Program};
+
+/// Registers set aside for the control law, so a step never allocates
+const REGISTERS: usize = 64;
+
+/// A loaded control law with its register workspace
+pub struct Controller {
+    law: Program,
+    registers: [f64; REGISTERS],
+}
+
+impl Controller {
+    /// Load the law from the bytes written on the host.
+    ///
+    /// # Errors
+    /// Returns a [`DecodeError`] if the bytes are not a valid program, call a function
+    /// the evaluation core does not provide, or need more than the reserved registers.
+    pub fn new(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let law = Program::from_bytes(bytes)?;
+        if law.workspace_size() > REGISTERS {
+            return Err(DecodeError {
+                offset: 0,
+                reason: "control law needs more registers than reserved",
+            });
+        }
+        Ok(Self {
+            law,
+            registers: [0.0; REGISTERS],
+        })
+    }
+
+    /// Actuator output for the current error, its integral and its derivative
+    pub fn step(&mut self, error: f64, integral: f64, derivative: f64) -> f64 {
+        self.law
+            .evaluate_with(&[error, integral, derivative], &mut self.registers)
+    }
+}
@@ -68,6 +68,17 @@ fn print_structure(expr: &Expr, indent: usize) {
             println!("{prefix}Derivative: d^{order}/d{var}^{order}");
             print_structure(inner, indent + 1);
         }
+        ExprView::Piecewise { branches, default } => {
+            println!("{prefix}Piecewise ({} branches):", branches.len());
+            for (condition, value) in branches {
+                println!("{prefix}  When {condition}:");
+                print_structure(value, indent + 2);
+            }
+            println!("{prefix}  Otherwise:");
+            print_structure(default, indent + 2);
+        }
+        // `ExprView` is non-exhaustive: new node kinds may be added
+        _ => println!("{prefix}Other: {expr}"),
     }
 }
 
@@ -121,6 +132,23 @@ fn to_json_like(expr: &Expr) -> String {
                 to_json_like(inner)
             )
         }
+        ExprView::Piecewise { branches, default } => {
+            let children: Vec<String> = branches
+                .iter()
+                .map(|(condition, value)| {
+                    format!(
+                        r#"{{"when": "{condition}", "then": {}}}"#,
+                        to_json_like(value)
+                    )
+                })
+                .collect();
+            format!(
+                r#"{{"kind": "Piecewise", "branches": [{}], "default": {}}}"#,
+                children.join(", "),
+                to_json_like(default)
+            )
+        }
+        _ => format!(r#"{{"kind": "Other", "text": "{expr}"}}"#),
     }
 }
 
@@ -150,6 +178,8 @@ fn main() {
             ExprView::Pow(_, _) => "Pow",
             ExprView::Function { .. } => "Function",
             ExprView::Derivative { .. } => "Derivative",
+            ExprView::Piecewise { .. } => "Piecewise",
+            _ => "Other",
         }
     );
     println!("\nStructure:");
@@ -659,8 +659,34 @@ class Expr:
     def zeta_deriv(self, n: "Expr | Symbol | int | float") -> "Expr": ...
 
     # Output formats
-    def to_latex(self) -> str:
-        """Convert expression to LaTeX string."""
+    def to_latex(
+        self,
+        implicit_multiplication: bool = False,
+        parenthesize_negative_exponents: bool = False,
+    ) -> str:
+        """Convert expression to LaTeX string.
+
+        ``implicit_multiplication`` joins factors with a thin space instead of
+        ``\\cdot``; ``parenthesize_negative_exponents`` writes ``x^{\\left(-2\\right)}``.
+        """
+        ...
+
+    def to_mathml(self, presentation: bool = False) -> str:
+        """Convert expression to MathML.
+
+        Content MathML (the default) encodes the expression tree and is read back
+        exactly by ``Expr.from_mathml``; ``presentation=True`` emits layout markup
+        for rendering instead.
+        """
+        ...
+
+    @staticmethod
+    def from_mathml(xml: str) -> "Expr":
+        """Parse Content MathML, as written by ``to_mathml()``.
+
+        Raises:
+            ValueError: For malformed XML, Presentation MathML or unknown elements.
+        """
         ...
 
     def to_unicode(self) -> str:
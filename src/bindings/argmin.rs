@@ -0,0 +1,90 @@
+//! [`argmin`] integration: use a symbolic expression as an optimization problem.
+//!
+//! [`ArgminProblem`] compiles an expression and its symbolic gradient once and
+//! implements argmin's [`CostFunction`] and [`Gradient`] traits on `Vec<f64>` parameters.
+
+use argmin::core::{CostFunction, Error, Gradient};
+
+use crate::core::{DiffError, Expr, Symbol};
+use crate::evaluator::CompiledEvaluator;
+use crate::gradient;
+
+/// Compiled cost function and gradient for argmin solvers.
+///
+/// Parameters are ordered as the `vars` slice passed to [`ArgminProblem::new`].
+///
+/// # Example
+/// ```
+/// use argmin::core::{CostFunction, Gradient};
+/// use symb_anafis::{ArgminProblem, parse, symb};
+/// use std::collections::HashSet;
+///
+/// let (x, y) = (symb("x"), symb("y"));
+/// let expr = parse("(x - 1)^2 + y^2", &HashSet::new(), &HashSet::new(), None).unwrap();
+/// let problem = ArgminProblem::new(&expr, &[&x, &y]).unwrap();
+///
+/// assert_eq!(problem.cost(&vec![1.0, 2.0]).unwrap(), 4.0);
+/// assert_eq!(problem.gradient(&vec![1.0, 2.0]).unwrap(), vec![0.0, 4.0]);
+/// ```
+#[derive(Clone)]
+pub struct ArgminProblem {
+    /// Compiled objective
+    cost: CompiledEvaluator,
+    /// One compiled partial derivative per parameter
+    gradient: Vec<CompiledEvaluator>,
+}
+
+impl ArgminProblem {
+    /// Compile `expr` and its gradient with respect to `vars`.
+    ///
+    /// # Errors
+    /// Returns `DiffError` if differentiation fails or an expression cannot be compiled
+    /// (e.g. it contains a free variable not listed in `vars`).
+    pub fn new(expr: &Expr, vars: &[&Symbol]) -> Result<Self, DiffError> {
+        let cost = CompiledEvaluator::compile(expr, vars, None)?;
+        let gradient = gradient(expr, vars)?
+            .iter()
+            .map(|partial| CompiledEvaluator::compile(partial, vars, None))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { cost, gradient })
+    }
+
+    /// Number of parameters the problem expects.
+    #[must_use]
+    pub const fn dimension(&self) -> usize {
+        self.gradient.len()
+    }
+
+    /// Reject parameter vectors of the wrong length instead of reading garbage.
+    fn check_len(&self, param: &[f64]) -> Result<(), Error> {
+        if param.len() == self.dimension() {
+            Ok(())
+        } else {
+            Err(Error::msg(format!(
+                "expected {} parameters, got {}",
+                self.dimension(),
+                param.len()
+            )))
+        }
+    }
+}
+
+impl CostFunction for ArgminProblem {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        self.check_len(param)?;
+        Ok(self.cost.evaluate(param))
+    }
+}
+
+impl Gradient for ArgminProblem {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error> {
+        self.check_len(param)?;
+        Ok(self.gradient.iter().map(|g| g.evaluate(param)).collect())
+    }
+}
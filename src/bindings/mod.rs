@@ -1,4 +1,6 @@
-//! External bindings Python
+//! External bindings: Python and optional numerics-crate integrations
 
+#[cfg(feature = "argmin")]
+pub mod argmin;
 #[cfg(feature = "python")]
 pub mod python;
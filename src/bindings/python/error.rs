@@ -10,38 +10,59 @@ use pyo3::PyErr;
 // - ValueError: semantic/validation errors
 // - SyntaxError: parsing errors
 // - RuntimeError: compilation/evaluation errors
+// - ZeroDivisionError: results divided by an expression that simplifies to zero
+// A located error maps like the error it wraps, with the caret snippet in its message.
 
 impl From<DiffError> for PyErr {
     fn from(err: DiffError) -> Self {
-        match &err {
+        match err.unlocated() {
             // Semantic/validation errors → ValueError
             DiffError::EmptyFormula
             | DiffError::InvalidSyntax { .. }
             | DiffError::InvalidNumber { .. }
             | DiffError::InvalidFunctionCall { .. }
             | DiffError::VariableInBothFixedAndDiff { .. }
+            | DiffError::UnknownRule { .. }
+            | DiffError::DuplicateRule { .. }
+            | DiffError::InvalidRename { .. }
+            | DiffError::NonDifferentiable { .. }
             | DiffError::MaxDepthExceeded
             | DiffError::MaxNodesExceeded
+            | DiffError::DerivativeOrderLimit { .. }
+            | DiffError::ExpansionTooLarge { .. }
             | DiffError::EvalColumnMismatch { .. }
             | DiffError::EvalColumnLengthMismatch
+            | DiffError::EvalBroadcastMismatch { .. }
             | DiffError::EvalOutputTooSmall { .. }
-            | DiffError::InvalidPartialIndex { .. } => {
+            | DiffError::InvalidDataSeries { .. }
+            | DiffError::InvalidEvaluatorBytes { .. }
+            | DiffError::InvalidPartialIndex { .. }
+            | DiffError::CyclicFunctionDefinition { .. }
+            | DiffError::UncertaintyAlreadyPropagated { .. } => {
                 Self::new::<pyo3::exceptions::PyValueError, _>(err.to_string())
             }
+            DiffError::DivisionByZero { .. } => {
+                Self::new::<pyo3::exceptions::PyZeroDivisionError, _>(err.to_string())
+            }
             // Parse errors → SyntaxError
             DiffError::InvalidToken { .. }
             | DiffError::UnexpectedToken { .. }
             | DiffError::UnexpectedEndOfInput
-            | DiffError::AmbiguousSequence { .. } => {
+            | DiffError::AmbiguousSequence { .. }
+            | DiffError::CircularDefinition { .. } => {
                 Self::new::<pyo3::exceptions::PySyntaxError, _>(err.to_string())
             }
             // Compile/runtime errors → RuntimeError
             DiffError::UnsupportedOperation(_)
+            | DiffError::CannotIntegrate { .. }
+            | DiffError::LimitUndetermined { .. }
             | DiffError::UnsupportedExpression(_)
             | DiffError::UnsupportedFunction(_)
             | DiffError::UnboundVariable(_)
             | DiffError::StackOverflow { .. }
-            | DiffError::NameCollision { .. } => {
+            | DiffError::NameCollision { .. }
+            | DiffError::ScopeMismatch { .. }
+            | DiffError::Located { .. } => {
                 Self::new::<pyo3::exceptions::PyRuntimeError, _>(err.to_string())
             }
         }
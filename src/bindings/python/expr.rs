@@ -14,6 +14,8 @@ use super::view::PyExprView;
 use crate::EPSILON;
 use crate::core::Expr as RustExpr;
 use crate::core::ExprKind;
+use crate::core::LatexConfig;
+use crate::core::MathmlConfig;
 use crate::core::symb;
 use crate::diff::Diff;
 use crate::simplification::Simplify;
@@ -741,8 +743,29 @@ impl PyExpr {
 
     // Output formats
     /// Convert expression to LaTeX string
-    fn to_latex(&self) -> String {
-        self.0.to_latex()
+    #[pyo3(signature = (implicit_multiplication = false, parenthesize_negative_exponents = false))]
+    fn to_latex(
+        &self,
+        implicit_multiplication: bool,
+        parenthesize_negative_exponents: bool,
+    ) -> String {
+        self.0.to_latex_with(&LatexConfig {
+            implicit_multiplication,
+            parenthesize_negative_exponents,
+            show_labels: false,
+        })
+    }
+
+    /// Convert expression to Content `MathML`, or Presentation `MathML` for display
+    #[pyo3(signature = (presentation = false))]
+    fn to_mathml(&self, presentation: bool) -> String {
+        self.0.to_mathml_with(&MathmlConfig { presentation })
+    }
+
+    /// Parse Content `MathML`, as written by `to_mathml()`
+    #[staticmethod]
+    fn from_mathml(xml: &str) -> PyResult<Self> {
+        Ok(Self(RustExpr::from_mathml(xml)?))
     }
 
     /// Convert expression to Unicode string (with Greek symbols, superscripts)
@@ -873,8 +896,8 @@ fn is_lambert_w_domain_error(x: f64) -> bool {
 }
 
 /// Check if value is outside the domain of Bessel Y and K functions
-fn is_bessel_yk_domain_error(x: f64) -> bool {
-    x <= 0.0 + EPSILON
+const fn is_bessel_yk_domain_error(x: f64) -> bool {
+    x <= 0.0
 }
 
 /// Check if value is outside the domain of elliptic K function
@@ -918,8 +941,8 @@ fn is_log_base_domain_error(base: f64) -> bool {
 }
 
 /// Checks if the logarithm value is invalid (non-positive).
-fn is_log_value_domain_error(x: f64) -> bool {
-    x <= 0.0 + EPSILON
+const fn is_log_value_domain_error(x: f64) -> bool {
+    x <= 0.0
 }
 
 /// Extracts the numeric value from an expression if it is a number.
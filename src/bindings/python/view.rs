@@ -33,11 +33,12 @@ use pyo3::prelude::*;
 #[pyclass(name = "ExprView", from_py_object)]
 #[derive(Clone)]
 pub struct PyExprView {
-    /// Expression kind: "Number", "Symbol", "Sum", "Product", "Div", "Pow", "Function", or "Derivative"
+    /// Expression kind: "Number", "Symbol", "Sum", "Product", "Div", "Pow", "Function", "Derivative" or "Piecewise"
     kind: String,
     /// Numeric value (only set for Number nodes)
     value: Option<f64>,
-    /// Symbol or function name (only set for Symbol/Function nodes)
+    /// Symbol or function name (only set for Symbol/Function nodes), or the branch
+    /// comparisons of a Piecewise node
     name: Option<String>,
     /// Child expression nodes
     children: Vec<PyExpr>,
@@ -52,7 +53,8 @@ impl PyExprView {
     /// Get the type of expression node
     ///
     /// Returns:
-    ///     One of: "Number", "Symbol", "Sum", "Product", "Div", "Pow", "Function", "Derivative"
+    ///     One of: "Number", "Symbol", "Sum", "Product", "Div", "Pow", "Function", "Derivative",
+    ///     "Piecewise"
     #[getter]
     fn kind(&self) -> String {
         self.kind.clone()
@@ -66,7 +68,8 @@ impl PyExprView {
 
     /// Get name (for Symbol or Function nodes)
     ///
-    /// For anonymous symbols, returns their "$ID" representation.
+    /// For anonymous symbols, returns their "$ID" representation. For Piecewise nodes,
+    /// returns the comparison of every branch separated by spaces, e.g. "< >=".
     #[getter]
     fn name(&self) -> Option<String> {
         self.name.clone()
@@ -81,6 +84,7 @@ impl PyExprView {
     ///     - Pow: [base, exponent]
     ///     - Function: List of arguments
     ///     - Derivative: [`inner_expr`]
+    ///     - Piecewise: [lhs, rhs, value] for each branch, then the default
     ///     - Number/Symbol: []
     #[getter]
     fn children(&self) -> Vec<PyExpr> {
@@ -240,6 +244,26 @@ impl PyExprView {
                 derivative_var: Some(var.to_owned()),
                 derivative_order: Some(order),
             },
+            ExprView::Piecewise { branches, default } => Self {
+                kind: "Piecewise".to_owned(),
+                value: None,
+                name: Some(
+                    branches
+                        .iter()
+                        .map(|(condition, _)| condition.op.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                children: branches
+                    .iter()
+                    .flat_map(|(condition, value)| {
+                        [&condition.lhs, &condition.rhs, value].map(|part| PyExpr((**part).clone()))
+                    })
+                    .chain(std::iter::once(PyExpr(default.clone())))
+                    .collect(),
+                derivative_var: None,
+                derivative_order: None,
+            },
         }
     }
 }
@@ -0,0 +1,207 @@
+//! Public emitters on `Expr`.
+
+use super::logic::{
+    C, Fortran90, Glsl, Julia, Python, Rust, Target, Wgsl, emit_function, emit_tuple_function,
+};
+use crate::core::known_symbols::is_known_constant;
+use crate::core::{Context, DiffError, Expr};
+use crate::evaluator::ToParamName;
+
+/// A host language [`Expr::to_code`] can write a function in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CodeGenLanguage {
+    /// C11 over `double`, with the functions of `<math.h>` (link with `-lm`)
+    C,
+    /// Python 3 over `NumPy` (`import numpy as np`), so the function also takes arrays
+    Python,
+    /// Julia, with the functions of `Base.Math`
+    Julia,
+    /// Fortran 90 free form, as a `pure function` over `real(8)`
+    Fortran90,
+    /// Rust over `f64`, calling the methods of `f64`
+    Rust,
+}
+
+impl CodeGenLanguage {
+    const fn target(self) -> &'static dyn Target {
+        match self {
+            Self::C => &C,
+            Self::Python => &Python,
+            Self::Julia => &Julia,
+            Self::Fortran90 => &Fortran90,
+            Self::Rust => &Rust,
+        }
+    }
+}
+
+impl Expr {
+    /// Emit this expression as a self-contained WGSL function over `f32`.
+    ///
+    /// Parameters are declared in the order of `params`; `pi` and `e` become literals.
+    /// Subexpressions used more than once are bound to `let` temporaries, integer powers
+    /// are unrolled into multiplications, and functions without a WGSL builtin (`sec`,
+    /// `log10`, `acoth`, ...) are written in terms of ones that have one. Bodies of
+    /// functions registered in `context` are inlined.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    ///
+    /// let x = symb("x");
+    /// let y = symb("y");
+    /// let expr = Expr::from(x).pow(2.0) + Expr::from(x).sin() * y;
+    /// let wgsl = expr.to_wgsl("surface", &[x, y], None)?;
+    /// assert_eq!(wgsl, "fn surface(x: f32, y: f32) -> f32 {\n    return y * sin(x) + x * x;\n}\n");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError::UnsupportedExpression` listing every construct WGSL cannot
+    /// express (derivatives, special functions, custom functions without a body,
+    /// literals outside the `f32` range) and every invalid or reserved identifier, and
+    /// `DiffError::UnboundVariable` for a symbol missing from `params`.
+    pub fn to_wgsl<P: ToParamName>(
+        &self,
+        fn_name: &str,
+        params: &[P],
+        context: Option<&Context>,
+    ) -> Result<String, DiffError> {
+        emit_function(&Wgsl, self, fn_name, params, context)
+    }
+
+    /// Emit this expression as a self-contained GLSL function over `float`.
+    ///
+    /// Works like [`Expr::to_wgsl`], with temporaries declared as `float` locals. The
+    /// output needs GLSL 1.30 (or GLSL ES 3.00) for the hyperbolic builtins.
+    ///
+    /// # Errors
+    /// Same as [`Expr::to_wgsl`].
+    pub fn to_glsl<P: ToParamName>(
+        &self,
+        fn_name: &str,
+        params: &[P],
+        context: Option<&Context>,
+    ) -> Result<String, DiffError> {
+        emit_function(&Glsl, self, fn_name, params, context)
+    }
+
+    /// Emit this expression as a function `f` in `lang`, over its variables in
+    /// alphabetical order.
+    ///
+    /// `pi` and `e` become literals. See [`Expr::to_code_with`] to choose the function
+    /// name and parameter order or to inline functions of a context.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CodeGenLanguage, Expr, symb};
+    ///
+    /// let x = symb("x");
+    /// let y = symb("y");
+    /// let expr = Expr::from(x).sin() * y;
+    /// let c = expr.to_code(CodeGenLanguage::C)?;
+    /// assert_eq!(c, "#include <math.h>\n\ndouble f(double x, double y) {\n    return y * sin(x);\n}\n");
+    /// let python = expr.to_code(CodeGenLanguage::Python)?;
+    /// assert_eq!(python, "import numpy as np\n\n\ndef f(x, y):\n    return y * np.sin(x)\n");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Same as [`Expr::to_wgsl`], with the constructs and identifiers of `lang`.
+    pub fn to_code(&self, lang: CodeGenLanguage) -> Result<String, DiffError> {
+        let mut params: Vec<String> = self
+            .variables()
+            .into_iter()
+            .filter(|name| !is_known_constant(name))
+            .collect();
+        params.sort_unstable();
+        let params: Vec<&str> = params.iter().map(String::as_str).collect();
+        self.to_code_with(lang, "f", &params, None)
+    }
+
+    /// Emit this expression as a function `fn_name` over `params` in `lang`.
+    ///
+    /// Works like [`Expr::to_wgsl`] over `f64`: repeated subexpressions become
+    /// temporaries, integer powers are unrolled and bodies of functions registered in
+    /// `context` are inlined. Fortran lines are continued past 100 characters.
+    ///
+    /// # Errors
+    /// Same as [`Expr::to_wgsl`], with the constructs and identifiers of `lang`.
+    pub fn to_code_with<P: ToParamName>(
+        &self,
+        lang: CodeGenLanguage,
+        fn_name: &str,
+        params: &[P],
+        context: Option<&Context>,
+    ) -> Result<String, DiffError> {
+        emit_function(lang.target(), self, fn_name, params, context)
+    }
+
+    /// Emit this expression as a Rust function `fn_name` over `params`, all `f64`.
+    ///
+    /// Works like [`Expr::to_code_with`] with [`CodeGenLanguage::Rust`]: functions are
+    /// `f64` methods (`x.sin()`), integer exponents use `powi` and others `powf`, and
+    /// `pi`, `e` and `tau` are the constants of `core::f64::consts`. Repeated
+    /// subexpressions become `let` bindings. Without `std`, bring `num_traits::Float`
+    /// (with its `libm` feature) into scope for the methods.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    ///
+    /// let x = symb("x");
+    /// let y = symb("y");
+    /// let expr = Expr::from(x).pow(3.0) * Expr::from(y).sin() + Expr::from(x).pow(0.3);
+    /// let rust = expr.to_rust_code("f", &[x, y], None)?;
+    /// assert_eq!(rust, "fn f(x: f64, y: f64) -> f64 {\n    x.powi(3) * y.sin() + x.powf(0.3)\n}\n");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Same as [`Expr::to_wgsl`], with the identifiers of Rust.
+    pub fn to_rust_code<P: ToParamName>(
+        &self,
+        fn_name: &str,
+        params: &[P],
+        context: Option<&Context>,
+    ) -> Result<String, DiffError> {
+        emit_function(&Rust, self, fn_name, params, context)
+    }
+}
+
+/// Emit several expressions as one Rust function `fn_name` over `params` returning the
+/// tuple of their values, such as the components of a gradient.
+///
+/// Each output is bound with `let` to its name before the tuple is returned, and
+/// subexpressions shared between outputs are computed once. The expressions are
+/// written as in [`Expr::to_rust_code`].
+///
+/// # Example
+/// ```
+/// use symb_anafis::{Diff, symb, to_rust_code_multi};
+///
+/// let x = symb("x");
+/// let y = symb("y");
+/// let f = x.sin() * y;
+/// let diff = Diff::new();
+/// let (dx, dy) = (diff.differentiate(&f, &x)?, diff.differentiate(&f, &y)?);
+/// let rust = to_rust_code_multi("grad", &[x, y], &[("df_dx", &dx), ("df_dy", &dy)], None)?;
+/// assert_eq!(
+///     rust,
+///     "fn grad(x: f64, y: f64) -> (f64, f64) {\n    let df_dx = y * x.cos();\n    \
+///      let df_dy = x.sin();\n    (df_dx, df_dy)\n}\n"
+/// );
+/// # Ok::<(), symb_anafis::DiffError>(())
+/// ```
+///
+/// # Errors
+/// Same as [`Expr::to_rust_code`], with output names checked like parameters, and
+/// `DiffError::UnsupportedExpression` if `outputs` is empty.
+pub fn to_rust_code_multi<P: ToParamName>(
+    fn_name: &str,
+    params: &[P],
+    outputs: &[(&str, &Expr)],
+    context: Option<&Context>,
+) -> Result<String, DiffError> {
+    emit_tuple_function(&Rust, outputs, fn_name, params, context)
+}
@@ -0,0 +1,792 @@
+//! Target-independent lowering shared by every emitter.
+//!
+//! An expression is checked for constructs the target cannot express, then lowered into a
+//! small code tree. Repeated subtrees become temporaries, integer powers are unrolled into
+//! multiplications (unless the target has an integer power) and derived functions (`sec`, `log10`, `cbrt`, ...) are rewritten in terms
+//! of the [`Intrinsic`] set, so a target only has to spell things.
+
+use super::target::{Intrinsic, Target};
+use crate::core::known_symbols::{KS, get_constant_value_by_id};
+use crate::core::{Context, DiffError, Expr, ExprKind, piecewise_parts};
+use crate::evaluator::{ToParamName, expand_user_functions, substitute_constants};
+use crate::functions::Registry;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::f64::consts::{FRAC_PI_2, LOG10_E, PI};
+
+/// Integer exponents up to this magnitude are unrolled into multiplications.
+const UNROLL_LIMIT: f64 = 1024.0;
+
+/// Products of up to this many equal factors are written out instead of squared.
+const INLINE_POWER: u64 = 4;
+
+/// Indentation of statements inside the emitted function.
+const INDENT: &str = "    ";
+
+/// Functions the lowering can express, with their arity.
+const SUPPORTED: &[(&str, usize)] = &[
+    ("abs", 1),
+    ("signum", 1),
+    ("floor", 1),
+    ("ceil", 1),
+    ("round", 1),
+    ("sqrt", 1),
+    ("cbrt", 1),
+    ("exp", 1),
+    ("ln", 1),
+    ("log2", 1),
+    ("log10", 1),
+    ("log", 2),
+    ("sin", 1),
+    ("cos", 1),
+    ("tan", 1),
+    ("cot", 1),
+    ("sec", 1),
+    ("csc", 1),
+    ("asin", 1),
+    ("acos", 1),
+    ("atan", 1),
+    ("acot", 1),
+    ("asec", 1),
+    ("acsc", 1),
+    ("sind", 1),
+    ("cosd", 1),
+    ("tand", 1),
+    ("asind", 1),
+    ("acosd", 1),
+    ("atand", 1),
+    ("atan2", 2),
+    ("hypot", 2),
+    ("sinh", 1),
+    ("cosh", 1),
+    ("tanh", 1),
+    ("coth", 1),
+    ("sech", 1),
+    ("csch", 1),
+    ("asinh", 1),
+    ("acosh", 1),
+    ("atanh", 1),
+    ("acoth", 1),
+    ("asech", 1),
+    ("acsch", 1),
+];
+
+/// Lowered code; names and literals are leaves.
+#[derive(Debug, Clone)]
+enum Code {
+    Num(f64),
+    Name(String),
+    Neg(Box<Self>),
+    Sum(Vec<Self>),
+    Product(Vec<Self>),
+    Div(Box<Self>, Box<Self>),
+    Call(Intrinsic, Vec<Self>),
+}
+
+/// Binding strength of printed code, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+    Sum,
+    Product,
+    Unary,
+    Atom,
+}
+
+impl Code {
+    const fn prec(&self) -> Prec {
+        match self {
+            Self::Sum(_) => Prec::Sum,
+            Self::Product(_) | Self::Div(..) => Prec::Product,
+            Self::Neg(_) => Prec::Unary,
+            Self::Num(n) if n.is_sign_negative() => Prec::Unary,
+            Self::Num(_) | Self::Name(_) | Self::Call(..) => Prec::Atom,
+        }
+    }
+
+    /// Whether the code can be repeated without recomputing anything.
+    const fn is_atom(&self) -> bool {
+        matches!(self, Self::Name(_)) || matches!(self, Self::Num(n) if !n.is_sign_negative())
+    }
+
+    fn recip(self) -> Self {
+        Self::Div(Box::new(Self::Num(1.0)), Box::new(self))
+    }
+
+    fn call(f: Intrinsic, arg: Self) -> Self {
+        Self::Call(f, vec![arg])
+    }
+
+    fn print(&self, target: &dyn Target) -> String {
+        match self {
+            Self::Num(n) => target.number(*n),
+            Self::Name(name) => name.clone(),
+            Self::Neg(inner) => format!("-{}", inner.print_at(target, Prec::Atom)),
+            Self::Sum(terms) => {
+                let mut out = String::new();
+                for (i, term) in terms.iter().enumerate() {
+                    match term {
+                        Self::Neg(inner) if i > 0 => {
+                            out.push_str(" - ");
+                            out.push_str(&inner.print_at(target, Prec::Product));
+                        }
+                        Self::Num(n) if i > 0 && n.is_sign_negative() => {
+                            out.push_str(" - ");
+                            out.push_str(&target.number(-n));
+                        }
+                        _ => {
+                            if i > 0 {
+                                out.push_str(" + ");
+                            }
+                            out.push_str(&term.print_at(target, Prec::Sum));
+                        }
+                    }
+                }
+                out
+            }
+            Self::Product(factors) => factors
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    f.print_at(target, if i == 0 { Prec::Product } else { right(target) })
+                })
+                .collect::<Vec<_>>()
+                .join(" * "),
+            Self::Div(num, den) => format!(
+                "{} / {}",
+                num.print_at(target, Prec::Product),
+                den.print_at(target, right(target))
+            ),
+            Self::Call(f, args) => {
+                // A method receiver binds like an atom: `(x + 1.0).sin()`, and so do
+                // both operands of an infix power: `(a + b) ^ (x * x)`
+                let infix = *f == Intrinsic::Pow && target.infix_power();
+                let args: Vec<String> = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, a)| {
+                        if infix || (i == 0 && target.calls_are_methods()) {
+                            a.print_at(target, Prec::Atom)
+                        } else {
+                            a.print(target)
+                        }
+                    })
+                    .collect();
+                target.call(*f, &args)
+            }
+        }
+    }
+
+    /// Print, parenthesised if the code binds weaker than `min`.
+    fn print_at(&self, target: &dyn Target, min: Prec) -> String {
+        if self.prec() < min {
+            format!("({})", self.print(target))
+        } else {
+            self.print(target)
+        }
+    }
+}
+
+/// Weakest binding allowed for the right operand of `*` and `/`.
+fn right(target: &dyn Target) -> Prec {
+    if target.allows_unary_after_operator() {
+        Prec::Unary
+    } else {
+        Prec::Atom
+    }
+}
+
+/// Whether `name` is an ASCII identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn children(expr: &Expr) -> Vec<&Expr> {
+    match &expr.kind {
+        ExprKind::Number(_) | ExprKind::Symbol(_) => Vec::new(),
+        ExprKind::Sum(items) | ExprKind::Product(items) => {
+            items.iter().map(AsRef::as_ref).collect()
+        }
+        ExprKind::FunctionCall { args, .. } => args.iter().map(AsRef::as_ref).collect(),
+        ExprKind::Div(a, b) | ExprKind::Pow(a, b) => vec![a.as_ref(), b.as_ref()],
+        ExprKind::Derivative { inner, .. } => vec![inner.as_ref()],
+        ExprKind::Poly(poly) => vec![poly.base().as_ref()],
+        ExprKind::Piecewise { branches, default } => piecewise_parts(branches, default)
+            .map(AsRef::as_ref)
+            .collect(),
+    }
+}
+
+/// Count how often each distinct subtree is referenced, descending into a subtree only
+/// the first time it is seen.
+fn count_uses<'expr>(expr: &'expr Expr, counts: &mut FxHashMap<&'expr Expr, usize>) {
+    let seen = counts.entry(expr).or_insert(0);
+    *seen += 1;
+    if *seen == 1 {
+        for child in children(expr) {
+            count_uses(child, counts);
+        }
+    }
+}
+
+struct Lowerer<'expr, 'target> {
+    target: &'target dyn Target,
+    params: FxHashMap<u64, String>,
+    uses: FxHashMap<&'expr Expr, usize>,
+    bound: FxHashMap<&'expr Expr, String>,
+    keys: FxHashMap<&'expr Expr, String>,
+    taken: FxHashSet<String>,
+    bindings: Vec<String>,
+    temps: Vec<String>,
+    next_temp: usize,
+}
+
+impl<'expr> Lowerer<'expr, '_> {
+    /// Bind `code` to a fresh temporary and return its name.
+    fn bind(&mut self, code: &Code) -> String {
+        let name = loop {
+            let candidate = format!("t{}", self.next_temp);
+            self.next_temp += 1;
+            if !self.taken.contains(&candidate) {
+                break candidate;
+            }
+        };
+        let value = code.print(self.target);
+        self.bindings.push(self.target.binding(&name, &value));
+        self.temps.push(name.clone());
+        name
+    }
+
+    /// `code` itself if it is cheap to repeat, otherwise a temporary holding it.
+    fn atom(&mut self, code: Code) -> Code {
+        if code.is_atom() {
+            code
+        } else {
+            Code::Name(self.bind(&code))
+        }
+    }
+
+    /// Sort key that depends only on names and structure.
+    ///
+    /// The canonical order of sums and products follows symbol ids, which change with
+    /// interning order; emitting in this order keeps the output stable across runs.
+    fn stable_key(&mut self, expr: &'expr Expr) -> String {
+        if let Some(key) = self.keys.get(expr) {
+            return key.clone();
+        }
+        let key = match &expr.kind {
+            ExprKind::Number(n) => format!("0{n:e}"),
+            ExprKind::Symbol(s) => format!("1{}", s.as_str()),
+            kind => {
+                let mut parts: Vec<String> = children(expr)
+                    .into_iter()
+                    .map(|c| self.stable_key(c))
+                    .collect();
+                if matches!(kind, ExprKind::Sum(_) | ExprKind::Product(_)) {
+                    parts.sort_unstable();
+                }
+                let head = match kind {
+                    ExprKind::Sum(_) => "+",
+                    ExprKind::Product(_) => "*",
+                    ExprKind::Div(..) => "/",
+                    ExprKind::Pow(..) => "^",
+                    ExprKind::FunctionCall { name, .. } => name.as_str(),
+                    _ => "?",
+                };
+                format!("2{head}({})", parts.join(","))
+            }
+        };
+        self.keys.insert(expr, key.clone());
+        key
+    }
+
+    /// `items` in [`Self::stable_key`] order.
+    fn stable_order(&mut self, items: &'expr [std::sync::Arc<Expr>]) -> Vec<&'expr Expr> {
+        let mut keyed: Vec<(String, &'expr Expr)> = items
+            .iter()
+            .map(|item| (self.stable_key(item), item.as_ref()))
+            .collect();
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+        keyed.into_iter().map(|(_, item)| item).collect()
+    }
+
+    fn lower(&mut self, expr: &'expr Expr) -> Result<Code, DiffError> {
+        if let Some(name) = self.bound.get(expr) {
+            return Ok(Code::Name(name.clone()));
+        }
+        let code = self.lower_node(expr)?;
+        let shared = self.uses.get(expr).is_some_and(|&n| n > 1);
+        if shared && !code.is_atom() {
+            let name = self.bind(&code);
+            self.bound.insert(expr, name.clone());
+            return Ok(Code::Name(name));
+        }
+        Ok(code)
+    }
+
+    fn lower_node(&mut self, expr: &'expr Expr) -> Result<Code, DiffError> {
+        match &expr.kind {
+            ExprKind::Number(n) => Ok(Code::Num(*n)),
+            ExprKind::Symbol(s) => self.params.get(&s.id()).map_or_else(
+                || {
+                    get_constant_value_by_id(s.id())
+                        .map(Code::Num)
+                        .ok_or_else(|| DiffError::UnboundVariable(s.clone()))
+                },
+                |name| Ok(Code::Name(name.clone())),
+            ),
+            ExprKind::Sum(terms) => Ok(Code::Sum(
+                self.stable_order(terms)
+                    .into_iter()
+                    .map(|t| self.lower(t))
+                    .collect::<Result<_, _>>()?,
+            )),
+            ExprKind::Product(factors) => self.lower_product(factors),
+            ExprKind::Div(num, den) => Ok(Code::Div(
+                Box::new(self.lower(num)?),
+                Box::new(self.lower(den)?),
+            )),
+            ExprKind::Pow(base, exp) => {
+                if let ExprKind::Symbol(s) = &base.kind
+                    && s.id() == KS.e
+                    && !self.params.contains_key(&s.id())
+                {
+                    return Ok(Code::call(Intrinsic::Exp, self.lower(exp)?));
+                }
+                if let ExprKind::Number(k) = exp.kind {
+                    return self.lower_pow(base, k);
+                }
+                Ok(Code::Call(
+                    Intrinsic::Pow,
+                    vec![self.lower(base)?, self.lower(exp)?],
+                ))
+            }
+            ExprKind::FunctionCall { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|a| self.lower(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.lower_call(name.as_str(), args)
+                    .ok_or_else(|| self.unsupported(&format!("function '{}'", name.as_str())))
+            }
+            ExprKind::Derivative { .. } | ExprKind::Poly(_) | ExprKind::Piecewise { .. } => {
+                Err(self.unsupported(&format!("'{expr}'")))
+            }
+        }
+    }
+
+    fn unsupported(&self, what: &str) -> DiffError {
+        DiffError::UnsupportedExpression(format!("cannot emit {}: {what}", self.target.name()))
+    }
+
+    /// Products gather their numeric coefficient up front and negative powers into a
+    /// single denominator.
+    fn lower_product(&mut self, factors: &'expr [std::sync::Arc<Expr>]) -> Result<Code, DiffError> {
+        let mut coeff = 1.0;
+        let mut num = Vec::new();
+        let mut den = Vec::new();
+        for factor in self.stable_order(factors) {
+            match &factor.kind {
+                ExprKind::Number(n) => coeff *= n,
+                ExprKind::Pow(base, exp) if matches!(exp.kind, ExprKind::Number(k) if k < 0.0) => {
+                    let k = exp.as_number().unwrap_or(-1.0);
+                    den.push(self.lower_pow(base, -k)?);
+                }
+                _ => num.push(self.lower(factor)?),
+            }
+        }
+
+        let negative = coeff < 0.0;
+        #[allow(clippy::float_cmp, reason = "A unit coefficient is exactly 1.0")]
+        if coeff.abs() != 1.0 || num.is_empty() {
+            num.insert(0, Code::Num(coeff.abs()));
+        }
+        let join = |mut items: Vec<Code>| {
+            if items.len() == 1 {
+                items.pop().unwrap_or(Code::Num(1.0))
+            } else {
+                Code::Product(items)
+            }
+        };
+        let mut code = join(num);
+        if !den.is_empty() {
+            code = Code::Div(Box::new(code), Box::new(join(den)));
+        }
+        Ok(if negative {
+            Code::Neg(Box::new(code))
+        } else {
+            code
+        })
+    }
+
+    fn lower_pow(&mut self, base: &'expr Expr, k: f64) -> Result<Code, DiffError> {
+        #[allow(clippy::float_cmp, reason = "Square roots are exact 0.5 exponents")]
+        if k.abs() == 0.5 {
+            let root = Code::call(Intrinsic::Sqrt, self.lower(base)?);
+            return Ok(if k < 0.0 { root.recip() } else { root });
+        }
+        if k.fract() != 0.0 || self.target.has_integer_power() {
+            return Ok(Code::Call(
+                Intrinsic::Pow,
+                vec![self.lower(base)?, Code::Num(k)],
+            ));
+        }
+
+        let base = self.lower(base)?;
+        let base = self.atom(base);
+        let power = if k.abs() <= UNROLL_LIMIT {
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "k is an integer of magnitude at most UNROLL_LIMIT"
+            )]
+            let n = k.abs() as u64;
+            self.unroll(base, n)
+        } else {
+            // pow() is undefined for negative bases; restore the sign of odd powers
+            let magnitude = Code::Call(
+                Intrinsic::Pow,
+                vec![Code::call(Intrinsic::Abs, base.clone()), Code::Num(k.abs())],
+            );
+            if k % 2.0 == 0.0 {
+                magnitude
+            } else {
+                Code::Product(vec![Code::call(Intrinsic::Sign, base), magnitude])
+            }
+        };
+        Ok(if k < 0.0 { power.recip() } else { power })
+    }
+
+    /// `base^n` as multiplications, squaring into temporaries for larger `n`.
+    fn unroll(&mut self, base: Code, n: u64) -> Code {
+        match n {
+            0 => return Code::Num(1.0),
+            1 => return base,
+            _ if n <= INLINE_POWER => {
+                #[allow(clippy::cast_possible_truncation, reason = "n is at most INLINE_POWER")]
+                return Code::Product(vec![base; n as usize]);
+            }
+            _ => {}
+        }
+        let mut factors = Vec::new();
+        let mut square = base;
+        let mut rest = n;
+        loop {
+            if rest & 1 == 1 {
+                factors.push(square.clone());
+            }
+            rest >>= 1;
+            if rest == 0 {
+                break;
+            }
+            square = Code::Name(self.bind(&Code::Product(vec![square.clone(), square])));
+        }
+        if factors.len() == 1 {
+            factors.pop().unwrap_or(Code::Num(1.0))
+        } else {
+            Code::Product(factors)
+        }
+    }
+
+    /// Built-in function on lowered arguments, or `None` if it has no lowering.
+    fn lower_call(&mut self, name: &str, mut args: Vec<Code>) -> Option<Code> {
+        use Intrinsic as I;
+
+        let direct = match name {
+            "abs" => Some(I::Abs),
+            "signum" => Some(I::Sign),
+            "floor" => Some(I::Floor),
+            "ceil" => Some(I::Ceil),
+            "sqrt" => Some(I::Sqrt),
+            "exp" => Some(I::Exp),
+            "ln" => Some(I::Ln),
+            "log2" => Some(I::Log2),
+            "sin" => Some(I::Sin),
+            "cos" => Some(I::Cos),
+            "tan" => Some(I::Tan),
+            "asin" => Some(I::Asin),
+            "acos" => Some(I::Acos),
+            "atan" => Some(I::Atan),
+            "atan2" => Some(I::Atan2),
+            "sinh" => Some(I::Sinh),
+            "cosh" => Some(I::Cosh),
+            "tanh" => Some(I::Tanh),
+            "asinh" => Some(I::Asinh),
+            "acosh" => Some(I::Acosh),
+            "atanh" => Some(I::Atanh),
+            _ => None,
+        };
+        if !SUPPORTED.contains(&(name, args.len())) {
+            return None;
+        }
+        if let Some(f) = direct {
+            return Some(Code::Call(f, args));
+        }
+
+        if name == "log" {
+            let x = args.pop()?;
+            let base = args.pop()?;
+            return Some(Code::Div(
+                Box::new(Code::call(I::Ln, x)),
+                Box::new(Code::call(I::Ln, base)),
+            ));
+        }
+        if name == "hypot" {
+            let y = self.atom(args.pop()?);
+            let x = self.atom(args.pop()?);
+            return Some(Code::call(
+                I::Sqrt,
+                Code::Sum(vec![
+                    Code::Product(vec![x.clone(), x]),
+                    Code::Product(vec![y.clone(), y]),
+                ]),
+            ));
+        }
+        let x = args.pop()?;
+        Some(match name {
+            "cot" => Code::call(I::Tan, x).recip(),
+            "sec" => Code::call(I::Cos, x).recip(),
+            "csc" => Code::call(I::Sin, x).recip(),
+            "coth" => Code::call(I::Tanh, x).recip(),
+            "sech" => Code::call(I::Cosh, x).recip(),
+            "csch" => Code::call(I::Sinh, x).recip(),
+            "asec" => Code::call(I::Acos, x.recip()),
+            "acsc" => Code::call(I::Asin, x.recip()),
+            "acoth" => Code::call(I::Atanh, x.recip()),
+            "asech" => Code::call(I::Acosh, x.recip()),
+            "acsch" => Code::call(I::Asinh, x.recip()),
+            "acot" => Code::Sum(vec![
+                Code::Num(FRAC_PI_2),
+                Code::Neg(Box::new(Code::call(I::Atan, x))),
+            ]),
+            "sind" => Code::call(I::Sin, Code::Product(vec![x, Code::Num(PI / 180.0)])),
+            "cosd" => Code::call(I::Cos, Code::Product(vec![x, Code::Num(PI / 180.0)])),
+            "tand" => Code::call(I::Tan, Code::Product(vec![x, Code::Num(PI / 180.0)])),
+            "asind" => Code::Product(vec![Code::call(I::Asin, x), Code::Num(180.0 / PI)]),
+            "acosd" => Code::Product(vec![Code::call(I::Acos, x), Code::Num(180.0 / PI)]),
+            "atand" => Code::Product(vec![Code::call(I::Atan, x), Code::Num(180.0 / PI)]),
+            "log10" => Code::Product(vec![Code::call(I::Ln, x), Code::Num(LOG10_E)]),
+            "cbrt" => {
+                let x = self.atom(x);
+                Code::Product(vec![
+                    Code::call(I::Sign, x.clone()),
+                    Code::Call(I::Pow, vec![Code::call(I::Abs, x), Code::Num(1.0 / 3.0)]),
+                ])
+            }
+            // Half away from zero, like f64::round
+            "round" => {
+                let x = self.atom(x);
+                Code::Product(vec![
+                    Code::call(I::Sign, x.clone()),
+                    Code::call(
+                        I::Floor,
+                        Code::Sum(vec![Code::call(I::Abs, x), Code::Num(0.5)]),
+                    ),
+                ])
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Everything in `exprs` and the signature that `target` cannot express.
+fn problems(
+    target: &dyn Target,
+    exprs: &[Expr],
+    fn_name: &str,
+    params: &[String],
+    outputs: &[&str],
+) -> Vec<String> {
+    let lang = target.name();
+    let mut found: Vec<String> = Vec::new();
+    let mut report = |problem: String| {
+        if !found.contains(&problem) {
+            found.push(problem);
+        }
+    };
+
+    let names = std::iter::once(("function name", fn_name))
+        .chain(params.iter().map(|p| ("parameter", p.as_str())))
+        .chain(outputs.iter().map(|&o| ("output", o)));
+    let mut declared: Vec<&str> = Vec::new();
+    for (role, name) in names {
+        if !is_identifier(name) {
+            report(format!("{role} '{name}' is not a valid identifier"));
+        } else if target.is_reserved(name) {
+            report(format!("{role} '{name}' is reserved in {lang}"));
+        } else if declared.contains(&name) {
+            report(format!("{role} '{name}' is declared twice"));
+        }
+        declared.push(name);
+    }
+
+    // Depth-first in source order, so the report reads left to right
+    let mut seen = FxHashSet::default();
+    let mut stack: Vec<&Expr> = exprs.iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        match &node.kind {
+            ExprKind::Derivative { .. } => {
+                report(format!("derivative '{node}'"));
+                continue;
+            }
+            ExprKind::FunctionCall { name, args }
+                if !SUPPORTED.contains(&(name.as_str(), args.len())) =>
+            {
+                if Registry::get_by_symbol(name).is_some() {
+                    report(format!(
+                        "function '{}' has no {lang} equivalent",
+                        name.as_str()
+                    ));
+                } else {
+                    report(format!("custom function '{}' has no body", name.as_str()));
+                }
+            }
+            ExprKind::Number(n) if !target.can_represent(*n) => {
+                report(format!("literal {n} cannot be represented"));
+            }
+            _ => {}
+        }
+        stack.extend(children(node).into_iter().rev());
+    }
+    found
+}
+
+/// Emit `expr` as a function `fn_name` over `params` in `target`'s language.
+pub fn emit_function<P: ToParamName>(
+    target: &dyn Target,
+    expr: &Expr,
+    fn_name: &str,
+    params: &[P],
+    context: Option<&Context>,
+) -> Result<String, DiffError> {
+    emit(target, &[expr], None, fn_name, params, context)
+}
+
+/// Emit `outputs` as one function `fn_name` over `params` returning a tuple of their
+/// values; subexpressions shared between outputs are computed once.
+pub fn emit_tuple_function<P: ToParamName>(
+    target: &dyn Target,
+    outputs: &[(&str, &Expr)],
+    fn_name: &str,
+    params: &[P],
+    context: Option<&Context>,
+) -> Result<String, DiffError> {
+    let (names, exprs): (Vec<&str>, Vec<&Expr>) = outputs.iter().copied().unzip();
+    emit(target, &exprs, Some(&names), fn_name, params, context)
+}
+
+/// Emit `exprs` as a function returning the single value or, with `outputs`, a tuple
+/// of values bound to those names.
+fn emit<P: ToParamName>(
+    target: &dyn Target,
+    exprs: &[&Expr],
+    outputs: Option<&[&str]>,
+    fn_name: &str,
+    params: &[P],
+    context: Option<&Context>,
+) -> Result<String, DiffError> {
+    let (ids, names): (Vec<u64>, Vec<String>) =
+        params.iter().map(ToParamName::to_param_id_and_name).unzip();
+    let header = match outputs {
+        None => target.header(fn_name, &names),
+        Some([]) => {
+            return Err(DiffError::UnsupportedExpression(format!(
+                "cannot emit {}: a function needs at least one output",
+                target.name()
+            )));
+        }
+        Some(outputs) => target
+            .tuple_header(fn_name, &names, outputs.len())
+            .ok_or_else(|| {
+                DiffError::UnsupportedExpression(format!(
+                    "cannot emit {}: functions returning several values",
+                    target.name()
+                ))
+            })?,
+    };
+
+    let expanded: Vec<Expr> = exprs
+        .iter()
+        .map(|expr| {
+            context
+                .map_or_else(
+                    || (*expr).clone(),
+                    |ctx| substitute_constants(&expand_user_functions(expr, ctx), ctx, &ids),
+                )
+                .from_poly()
+        })
+        .collect();
+    let mut uses = FxHashMap::default();
+    for expr in &expanded {
+        count_uses(expr, &mut uses);
+    }
+
+    let found = problems(
+        target,
+        &expanded,
+        fn_name,
+        &names,
+        outputs.unwrap_or_default(),
+    );
+    if !found.is_empty() {
+        return Err(DiffError::UnsupportedExpression(format!(
+            "cannot emit {}: {}",
+            target.name(),
+            found.join("; ")
+        )));
+    }
+
+    let mut taken: FxHashSet<String> = names.iter().cloned().collect();
+    taken.insert(fn_name.to_owned());
+    taken.extend(outputs.unwrap_or_default().iter().map(|&o| o.to_owned()));
+    let mut lowerer = Lowerer {
+        target,
+        params: ids.into_iter().zip(names.iter().cloned()).collect(),
+        uses,
+        bound: FxHashMap::default(),
+        keys: FxHashMap::default(),
+        taken,
+        bindings: Vec::new(),
+        temps: Vec::new(),
+        next_temp: 0,
+    };
+    let result = match outputs {
+        None => lowerer.lower(&expanded[0])?.print(target),
+        Some(outputs) => {
+            for (name, expr) in outputs.iter().zip(&expanded) {
+                let value = lowerer.lower(expr)?.print(target);
+                lowerer.bindings.push(target.binding(name, &value));
+                lowerer.temps.push((*name).to_owned());
+            }
+            let values: Vec<String> = outputs.iter().map(|&o| o.to_owned()).collect();
+            target.tuple(&values)
+        }
+    };
+
+    let mut out = target.prelude().to_owned();
+    out.push_str(&header);
+    out.push('\n');
+    for line in target
+        .declarations(&lowerer.temps)
+        .iter()
+        .chain(&lowerer.bindings)
+    {
+        out.push_str(INDENT);
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(INDENT);
+    out.push_str(&target.ret(&result));
+    out.push('\n');
+    let footer = target.footer(fn_name);
+    if !footer.is_empty() {
+        out.push_str(&footer);
+        out.push('\n');
+    }
+    Ok(out)
+}
@@ -0,0 +1,11 @@
+//! Internal implementation of the source emitters.
+
+pub(super) mod lower;
+pub(super) mod shader;
+pub(super) mod source;
+pub(super) mod target;
+
+pub(super) use lower::{emit_function, emit_tuple_function};
+pub(super) use shader::{Glsl, Wgsl};
+pub(super) use source::{C, Fortran90, Julia, Python, Rust};
+pub(super) use target::Target;
@@ -0,0 +1,458 @@
+//! Shader targets: WGSL and GLSL functions over `f32`.
+
+use super::target::{Intrinsic, Target};
+
+/// WGSL keywords, predeclared types and reserved words.
+const WGSL_RESERVED: &[&str] = &[
+    "alias",
+    "break",
+    "case",
+    "const",
+    "const_assert",
+    "continue",
+    "continuing",
+    "default",
+    "diagnostic",
+    "discard",
+    "else",
+    "enable",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "let",
+    "loop",
+    "override",
+    "requires",
+    "return",
+    "struct",
+    "switch",
+    "true",
+    "var",
+    "while",
+    "bool",
+    "f16",
+    "f32",
+    "i32",
+    "u32",
+    "vec2",
+    "vec3",
+    "vec4",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "array",
+    "atomic",
+    "ptr",
+    "sampler",
+    "sampler_comparison",
+    "NULL",
+    "Self",
+    "abstract",
+    "active",
+    "alignas",
+    "alignof",
+    "as",
+    "asm",
+    "asm_fragment",
+    "async",
+    "attribute",
+    "auto",
+    "await",
+    "become",
+    "binding_array",
+    "cast",
+    "catch",
+    "class",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "coherent",
+    "column_major",
+    "common",
+    "compile",
+    "compile_fragment",
+    "concept",
+    "const_cast",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "crate",
+    "debugger",
+    "decltype",
+    "delete",
+    "demote",
+    "demote_to_helper",
+    "do",
+    "dynamic_cast",
+    "enum",
+    "explicit",
+    "export",
+    "extends",
+    "extern",
+    "external",
+    "fallthrough",
+    "filter",
+    "final",
+    "finally",
+    "friend",
+    "from",
+    "fxgroup",
+    "get",
+    "goto",
+    "groupshared",
+    "highp",
+    "impl",
+    "implements",
+    "import",
+    "inline",
+    "instanceof",
+    "interface",
+    "layout",
+    "lowp",
+    "macro",
+    "macro_rules",
+    "match",
+    "mediump",
+    "meta",
+    "mod",
+    "module",
+    "move",
+    "mut",
+    "mutable",
+    "namespace",
+    "new",
+    "nil",
+    "noexcept",
+    "noinline",
+    "nointerpolation",
+    "noperspective",
+    "null",
+    "nullptr",
+    "of",
+    "operator",
+    "package",
+    "packoffset",
+    "partition",
+    "pass",
+    "patch",
+    "pixelfragment",
+    "precise",
+    "precision",
+    "premerge",
+    "priv",
+    "protected",
+    "pub",
+    "public",
+    "readonly",
+    "ref",
+    "regardless",
+    "register",
+    "reinterpret_cast",
+    "require",
+    "resource",
+    "restrict",
+    "self",
+    "set",
+    "shared",
+    "sizeof",
+    "smooth",
+    "snorm",
+    "static",
+    "static_assert",
+    "static_cast",
+    "std",
+    "subroutine",
+    "super",
+    "target",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "trait",
+    "try",
+    "type",
+    "typedef",
+    "typeid",
+    "typename",
+    "typeof",
+    "union",
+    "unless",
+    "unorm",
+    "unsafe",
+    "unsized",
+    "use",
+    "using",
+    "varying",
+    "virtual",
+    "volatile",
+    "wgsl",
+    "where",
+    "with",
+    "writeonly",
+    "yield",
+];
+
+/// GLSL keywords and reserved words.
+const GLSL_RESERVED: &[&str] = &[
+    "attribute",
+    "const",
+    "uniform",
+    "varying",
+    "buffer",
+    "shared",
+    "coherent",
+    "volatile",
+    "restrict",
+    "readonly",
+    "writeonly",
+    "layout",
+    "centroid",
+    "flat",
+    "smooth",
+    "noperspective",
+    "patch",
+    "sample",
+    "break",
+    "continue",
+    "do",
+    "for",
+    "while",
+    "switch",
+    "case",
+    "default",
+    "if",
+    "else",
+    "subroutine",
+    "in",
+    "out",
+    "inout",
+    "float",
+    "double",
+    "int",
+    "void",
+    "bool",
+    "true",
+    "false",
+    "invariant",
+    "precise",
+    "discard",
+    "return",
+    "mat2",
+    "mat3",
+    "mat4",
+    "dmat2",
+    "dmat3",
+    "dmat4",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "vec2",
+    "vec3",
+    "vec4",
+    "ivec2",
+    "ivec3",
+    "ivec4",
+    "bvec2",
+    "bvec3",
+    "bvec4",
+    "dvec2",
+    "dvec3",
+    "dvec4",
+    "uint",
+    "uvec2",
+    "uvec3",
+    "uvec4",
+    "lowp",
+    "mediump",
+    "highp",
+    "precision",
+    "sampler1D",
+    "sampler2D",
+    "sampler3D",
+    "samplerCube",
+    "struct",
+    "common",
+    "partition",
+    "active",
+    "asm",
+    "class",
+    "union",
+    "enum",
+    "typedef",
+    "template",
+    "this",
+    "resource",
+    "goto",
+    "inline",
+    "noinline",
+    "public",
+    "static",
+    "extern",
+    "external",
+    "interface",
+    "long",
+    "short",
+    "half",
+    "fixed",
+    "unsigned",
+    "superp",
+    "input",
+    "output",
+    "hvec2",
+    "hvec3",
+    "hvec4",
+    "fvec2",
+    "fvec3",
+    "fvec4",
+    "filter",
+    "sizeof",
+    "cast",
+    "namespace",
+    "using",
+];
+
+/// Intrinsic names shared by both shading languages.
+const fn shader_intrinsic(f: Intrinsic) -> &'static str {
+    match f {
+        Intrinsic::Abs => "abs",
+        Intrinsic::Sign => "sign",
+        Intrinsic::Floor => "floor",
+        Intrinsic::Ceil => "ceil",
+        Intrinsic::Sqrt => "sqrt",
+        Intrinsic::Exp => "exp",
+        Intrinsic::Ln => "log",
+        Intrinsic::Log2 => "log2",
+        Intrinsic::Pow => "pow",
+        Intrinsic::Sin => "sin",
+        Intrinsic::Cos => "cos",
+        Intrinsic::Tan => "tan",
+        Intrinsic::Asin => "asin",
+        Intrinsic::Acos => "acos",
+        Intrinsic::Atan => "atan",
+        Intrinsic::Atan2 => "atan2",
+        Intrinsic::Sinh => "sinh",
+        Intrinsic::Cosh => "cosh",
+        Intrinsic::Tanh => "tanh",
+        Intrinsic::Asinh => "asinh",
+        Intrinsic::Acosh => "acosh",
+        Intrinsic::Atanh => "atanh",
+    }
+}
+
+/// `f32` literal with the shortest digits that round-trip, always with a `.` or exponent.
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "Shaders compute in f32; can_represent rejects values out of range"
+)]
+fn f32_literal(value: f64) -> String {
+    let v = value as f32;
+    if v.fract() == 0.0 && v.abs() < 1e7 {
+        format!("{v:.1}")
+    } else {
+        format!("{v:?}")
+    }
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "Checking whether the f32 conversion overflows"
+)]
+const fn fits_f32(value: f64) -> bool {
+    (value as f32).is_finite()
+}
+
+/// Whether `ident` names one of the intrinsics used by the emitted code.
+fn is_intrinsic(ident: &str, target: &dyn Target) -> bool {
+    Intrinsic::ALL.iter().any(|&f| target.intrinsic(f) == ident)
+}
+
+/// WGSL (WebGPU Shading Language).
+pub struct Wgsl;
+
+impl Target for Wgsl {
+    fn name(&self) -> &'static str {
+        "WGSL"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        let params: Vec<String> = params.iter().map(|p| format!("{p}: f32")).collect();
+        format!("fn {name}({}) -> f32 {{", params.join(", "))
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("let {name} = {value};")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        shader_intrinsic(f)
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        ident == "_"
+            || ident.starts_with("__")
+            || WGSL_RESERVED.contains(&ident)
+            || is_intrinsic(ident, self)
+    }
+
+    fn number(&self, value: f64) -> String {
+        f32_literal(value)
+    }
+
+    fn can_represent(&self, value: f64) -> bool {
+        fits_f32(value)
+    }
+}
+
+/// GLSL (OpenGL Shading Language, 1.30 and later).
+pub struct Glsl;
+
+impl Target for Glsl {
+    fn name(&self) -> &'static str {
+        "GLSL"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        let params: Vec<String> = params.iter().map(|p| format!("float {p}")).collect();
+        format!("float {name}({}) {{", params.join(", "))
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("float {name} = {value};")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            // GLSL spells the two-argument arctangent as an overload of atan
+            Intrinsic::Atan2 => "atan",
+            _ => shader_intrinsic(f),
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        ident.starts_with("gl_")
+            || ident.contains("__")
+            || GLSL_RESERVED.contains(&ident)
+            || is_intrinsic(ident, self)
+    }
+
+    fn number(&self, value: f64) -> String {
+        f32_literal(value)
+    }
+
+    fn can_represent(&self, value: f64) -> bool {
+        fits_f32(value)
+    }
+}
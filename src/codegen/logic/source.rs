@@ -0,0 +1,608 @@
+//! Host-language targets: C, Python (`NumPy`), Julia, Fortran 90 and Rust functions over `f64`.
+
+use super::target::{Intrinsic, Target};
+
+/// C11 keywords.
+const C_RESERVED: &[&str] = &[
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "_Alignas",
+    "_Alignof",
+    "_Atomic",
+    "_Bool",
+    "_Complex",
+    "_Generic",
+    "_Imaginary",
+    "_Noreturn",
+    "_Static_assert",
+    "_Thread_local",
+    "main",
+];
+
+/// Python keywords, soft keywords and the `np` module alias.
+const PYTHON_RESERVED: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield", "match", "case", "type", "np",
+];
+
+/// Julia keywords and the names of the intrinsics used by the emitted code.
+const JULIA_RESERVED: &[&str] = &[
+    "baremodule",
+    "begin",
+    "break",
+    "catch",
+    "const",
+    "continue",
+    "do",
+    "else",
+    "elseif",
+    "end",
+    "export",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "global",
+    "if",
+    "import",
+    "let",
+    "local",
+    "macro",
+    "module",
+    "quote",
+    "return",
+    "struct",
+    "true",
+    "try",
+    "using",
+    "while",
+    "abstract",
+    "mutable",
+    "primitive",
+    "type",
+    "where",
+    "in",
+    "isa",
+];
+
+/// Fortran 90 statement keywords and the intrinsics used by the emitted code.
+///
+/// Fortran has no reserved words in the strict sense, but naming a variable after an
+/// intrinsic it calls hides the intrinsic, so those are refused too.
+const FORTRAN_RESERVED: &[&str] = &[
+    "function", "end", "pure", "result", "implicit", "none", "real", "intent", "in", "res",
+    "merge", "modulo", "sign",
+];
+
+/// Rust strict and reserved keywords.
+const RUST_RESERVED: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Constants of `core::f64::consts` the lowering produces, written by name.
+const RUST_CONSTS: &[(f64, &str)] = &[
+    (std::f64::consts::PI, "PI"),
+    (std::f64::consts::TAU, "TAU"),
+    (std::f64::consts::E, "E"),
+    (std::f64::consts::FRAC_PI_2, "FRAC_PI_2"),
+    (std::f64::consts::LOG10_E, "LOG10_E"),
+];
+
+/// Fortran 90 limits names to 31 characters.
+const FORTRAN_NAME_LIMIT: usize = 31;
+
+/// Fortran free-form lines are broken before this many characters.
+const FORTRAN_LINE_LIMIT: usize = 100;
+
+/// `f64` literal with the shortest digits that round-trip.
+fn f64_literal(value: f64) -> String {
+    format!("{value:?}")
+}
+
+/// Whether `ident` names one of the intrinsics used by the emitted code.
+fn is_intrinsic(ident: &str, target: &dyn Target) -> bool {
+    Intrinsic::ALL.iter().any(|&f| target.intrinsic(f) == ident)
+}
+
+/// C11 with `<math.h>`.
+pub struct C;
+
+impl Target for C {
+    fn name(&self) -> &'static str {
+        "C"
+    }
+
+    fn prelude(&self) -> &'static str {
+        "#include <math.h>\n\n"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        let params: Vec<String> = params.iter().map(|p| format!("double {p}")).collect();
+        let params = if params.is_empty() {
+            "void".to_owned()
+        } else {
+            params.join(", ")
+        };
+        format!("double {name}({params}) {{")
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("const double {name} = {value};")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            Intrinsic::Abs => "fabs",
+            // Never printed: math.h has no signum, so `call` writes it out
+            Intrinsic::Sign => "copysign",
+            Intrinsic::Floor => "floor",
+            Intrinsic::Ceil => "ceil",
+            Intrinsic::Sqrt => "sqrt",
+            Intrinsic::Exp => "exp",
+            Intrinsic::Ln => "log",
+            Intrinsic::Log2 => "log2",
+            Intrinsic::Pow => "pow",
+            Intrinsic::Sin => "sin",
+            Intrinsic::Cos => "cos",
+            Intrinsic::Tan => "tan",
+            Intrinsic::Asin => "asin",
+            Intrinsic::Acos => "acos",
+            Intrinsic::Atan => "atan",
+            Intrinsic::Atan2 => "atan2",
+            Intrinsic::Sinh => "sinh",
+            Intrinsic::Cosh => "cosh",
+            Intrinsic::Tanh => "tanh",
+            Intrinsic::Asinh => "asinh",
+            Intrinsic::Acosh => "acosh",
+            Intrinsic::Atanh => "atanh",
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        ident.starts_with("__")
+            || ident
+                .strip_prefix('_')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+            || C_RESERVED.contains(&ident)
+            || is_intrinsic(ident, self)
+    }
+
+    fn number(&self, value: f64) -> String {
+        f64_literal(value)
+    }
+
+    fn call(&self, f: Intrinsic, args: &[String]) -> String {
+        match (f, args) {
+            // math.h has no signum; comparisons give 1 or 0
+            (Intrinsic::Sign, [x]) => format!("((double)(({x}) > 0.0) - (double)(({x}) < 0.0))"),
+            _ => format!("{}({})", self.intrinsic(f), args.join(", ")),
+        }
+    }
+}
+
+/// Python 3 over `NumPy`, imported as `np`; the function also accepts arrays.
+pub struct Python;
+
+impl Target for Python {
+    fn name(&self) -> &'static str {
+        "Python"
+    }
+
+    fn prelude(&self) -> &'static str {
+        "import numpy as np\n\n\n"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        format!("def {name}({}):", params.join(", "))
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("{name} = {value}")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            Intrinsic::Abs => "np.abs",
+            Intrinsic::Sign => "np.sign",
+            Intrinsic::Floor => "np.floor",
+            Intrinsic::Ceil => "np.ceil",
+            Intrinsic::Sqrt => "np.sqrt",
+            Intrinsic::Exp => "np.exp",
+            Intrinsic::Ln => "np.log",
+            Intrinsic::Log2 => "np.log2",
+            Intrinsic::Pow => "np.power",
+            Intrinsic::Sin => "np.sin",
+            Intrinsic::Cos => "np.cos",
+            Intrinsic::Tan => "np.tan",
+            Intrinsic::Asin => "np.arcsin",
+            Intrinsic::Acos => "np.arccos",
+            Intrinsic::Atan => "np.arctan",
+            Intrinsic::Atan2 => "np.arctan2",
+            Intrinsic::Sinh => "np.sinh",
+            Intrinsic::Cosh => "np.cosh",
+            Intrinsic::Tanh => "np.tanh",
+            Intrinsic::Asinh => "np.arcsinh",
+            Intrinsic::Acosh => "np.arccosh",
+            Intrinsic::Atanh => "np.arctanh",
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        PYTHON_RESERVED.contains(&ident)
+    }
+
+    fn number(&self, value: f64) -> String {
+        f64_literal(value)
+    }
+
+    fn ret(&self, value: &str) -> String {
+        format!("return {value}")
+    }
+
+    fn footer(&self, _name: &str) -> String {
+        String::new()
+    }
+}
+
+/// Julia, with the functions of `Base.Math`.
+pub struct Julia;
+
+impl Target for Julia {
+    fn name(&self) -> &'static str {
+        "Julia"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        format!("function {name}({})", params.join(", "))
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("{name} = {value}")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            Intrinsic::Abs => "abs",
+            Intrinsic::Sign => "sign",
+            Intrinsic::Floor => "floor",
+            Intrinsic::Ceil => "ceil",
+            Intrinsic::Sqrt => "sqrt",
+            Intrinsic::Exp => "exp",
+            Intrinsic::Ln => "log",
+            Intrinsic::Log2 => "log2",
+            Intrinsic::Pow => "^",
+            Intrinsic::Sin => "sin",
+            Intrinsic::Cos => "cos",
+            Intrinsic::Tan => "tan",
+            Intrinsic::Asin => "asin",
+            // Julia spells the two-argument arctangent as a method of atan
+            Intrinsic::Atan | Intrinsic::Atan2 => "atan",
+            Intrinsic::Acos => "acos",
+            Intrinsic::Sinh => "sinh",
+            Intrinsic::Cosh => "cosh",
+            Intrinsic::Tanh => "tanh",
+            Intrinsic::Asinh => "asinh",
+            Intrinsic::Acosh => "acosh",
+            Intrinsic::Atanh => "atanh",
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        JULIA_RESERVED.contains(&ident) || is_intrinsic(ident, self)
+    }
+
+    fn number(&self, value: f64) -> String {
+        f64_literal(value)
+    }
+
+    fn ret(&self, value: &str) -> String {
+        format!("return {value}")
+    }
+
+    fn footer(&self, _name: &str) -> String {
+        "end".to_owned()
+    }
+
+    fn infix_power(&self) -> bool {
+        true
+    }
+
+    fn call(&self, f: Intrinsic, args: &[String]) -> String {
+        match (f, args) {
+            (Intrinsic::Pow, [base, exp]) => format!("({base} ^ {exp})"),
+            _ => format!("{}({})", self.intrinsic(f), args.join(", ")),
+        }
+    }
+}
+
+/// Fortran 90 free form, as a `pure` function over `real(8)`.
+pub struct Fortran90;
+
+impl Fortran90 {
+    /// `text` broken at spaces into continuation lines that fit the line limit
+    fn continued(text: &str) -> String {
+        let mut out = String::new();
+        let mut line = 0;
+        for (i, word) in text.split(' ').enumerate() {
+            if i > 0 {
+                if line + word.len() + 1 > FORTRAN_LINE_LIMIT {
+                    out.push_str(" &\n        ");
+                    line = 8;
+                } else {
+                    out.push(' ');
+                    line += 1;
+                }
+            }
+            out.push_str(word);
+            line += word.len();
+        }
+        out
+    }
+}
+
+impl Target for Fortran90 {
+    fn name(&self) -> &'static str {
+        "Fortran 90"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        let mut out = format!("pure function {name}({}) result(res)", params.join(", "));
+        out.push_str("\n    implicit none");
+        if !params.is_empty() {
+            out.push_str("\n    ");
+            out.push_str(&Self::continued(&format!(
+                "real(8), intent(in) :: {}",
+                params.join(", ")
+            )));
+        }
+        out.push_str("\n    real(8) :: res");
+        out
+    }
+
+    fn declarations(&self, temps: &[String]) -> Vec<String> {
+        if temps.is_empty() {
+            return Vec::new();
+        }
+        vec![Self::continued(&format!("real(8) :: {}", temps.join(", ")))]
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        Self::continued(&format!("{name} = {value}"))
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            Intrinsic::Abs => "abs",
+            Intrinsic::Sign => "sign",
+            Intrinsic::Floor => "floor",
+            Intrinsic::Ceil => "ceiling",
+            Intrinsic::Sqrt => "sqrt",
+            Intrinsic::Exp => "exp",
+            Intrinsic::Ln | Intrinsic::Log2 => "log",
+            Intrinsic::Pow => "**",
+            Intrinsic::Sin => "sin",
+            Intrinsic::Cos => "cos",
+            Intrinsic::Tan => "tan",
+            Intrinsic::Asin => "asin",
+            Intrinsic::Acos => "acos",
+            Intrinsic::Atan => "atan",
+            Intrinsic::Atan2 => "atan2",
+            Intrinsic::Sinh => "sinh",
+            Intrinsic::Cosh => "cosh",
+            Intrinsic::Tanh => "tanh",
+            Intrinsic::Asinh => "asinh",
+            Intrinsic::Acosh => "acosh",
+            Intrinsic::Atanh => "atanh",
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        let lower = ident.to_ascii_lowercase();
+        ident.starts_with('_')
+            || ident.len() > FORTRAN_NAME_LIMIT
+            || FORTRAN_RESERVED.contains(&lower.as_str())
+            || is_intrinsic(&lower, self)
+    }
+
+    fn number(&self, value: f64) -> String {
+        // Double precision literals need a `d` exponent
+        let text = f64_literal(value);
+        text.split_once('e').map_or_else(
+            || format!("{text}d0"),
+            |(mantissa, exponent)| format!("{mantissa}d{exponent}"),
+        )
+    }
+
+    fn ret(&self, value: &str) -> String {
+        Self::continued(&format!("res = {value}"))
+    }
+
+    fn footer(&self, name: &str) -> String {
+        format!("end function {name}")
+    }
+
+    fn allows_unary_after_operator(&self) -> bool {
+        false
+    }
+
+    fn infix_power(&self) -> bool {
+        true
+    }
+
+    fn call(&self, f: Intrinsic, args: &[String]) -> String {
+        // floor and ceiling return integers, and the inverse hyperbolic functions
+        // only arrived in Fortran 2008, so those are written out
+        match (f, args) {
+            (Intrinsic::Pow, [base, exp]) => format!("({base} ** {exp})"),
+            (Intrinsic::Sign, [x]) => {
+                format!("merge(0.0d0, sign(1.0d0, {x}), {x} == 0.0d0)")
+            }
+            (Intrinsic::Floor, [x]) => format!("(({x}) - modulo({x}, 1.0d0))"),
+            (Intrinsic::Ceil, [x]) => format!("(({x}) + modulo(-({x}), 1.0d0))"),
+            (Intrinsic::Log2, [x]) => format!("(log({x}) / log(2.0d0))"),
+            (Intrinsic::Asinh, [x]) => format!("log(({x}) + sqrt(({x}) * ({x}) + 1.0d0))"),
+            (Intrinsic::Acosh, [x]) => format!("log(({x}) + sqrt(({x}) * ({x}) - 1.0d0))"),
+            (Intrinsic::Atanh, [x]) => {
+                format!("(0.5d0 * log((1.0d0 + ({x})) / (1.0d0 - ({x}))))")
+            }
+            _ => format!("{}({})", self.intrinsic(f), args.join(", ")),
+        }
+    }
+}
+
+/// Rust over `f64`, with the methods of `f64` (or `num_traits::Float` without `std`).
+pub struct Rust;
+
+impl Rust {
+    /// Whether `text` is an untyped float literal, possibly negated or parenthesised,
+    /// on which a method call would not compile
+    fn is_literal(text: &str) -> bool {
+        let text = text.trim_start_matches('(').trim_end_matches(')');
+        text.trim_start_matches('-').parse::<f64>().is_ok()
+    }
+}
+
+impl Target for Rust {
+    fn name(&self) -> &'static str {
+        "Rust"
+    }
+
+    fn header(&self, name: &str, params: &[String]) -> String {
+        let params: Vec<String> = params.iter().map(|p| format!("{p}: f64")).collect();
+        format!("fn {name}({}) -> f64 {{", params.join(", "))
+    }
+
+    fn tuple_header(&self, name: &str, params: &[String], outputs: usize) -> Option<String> {
+        let params: Vec<String> = params.iter().map(|p| format!("{p}: f64")).collect();
+        let types = self.tuple(&vec!["f64".to_owned(); outputs]);
+        Some(format!("fn {name}({}) -> {types} {{", params.join(", ")))
+    }
+
+    fn tuple(&self, values: &[String]) -> String {
+        match values {
+            [single] => format!("({single},)"),
+            _ => format!("({})", values.join(", ")),
+        }
+    }
+
+    fn has_integer_power(&self) -> bool {
+        true
+    }
+
+    fn calls_are_methods(&self) -> bool {
+        true
+    }
+
+    fn binding(&self, name: &str, value: &str) -> String {
+        format!("let {name} = {value};")
+    }
+
+    fn intrinsic(&self, f: Intrinsic) -> &'static str {
+        match f {
+            Intrinsic::Abs => "abs",
+            Intrinsic::Sign => "signum",
+            Intrinsic::Floor => "floor",
+            Intrinsic::Ceil => "ceil",
+            Intrinsic::Sqrt => "sqrt",
+            Intrinsic::Exp => "exp",
+            Intrinsic::Ln => "ln",
+            Intrinsic::Log2 => "log2",
+            Intrinsic::Pow => "powf",
+            Intrinsic::Sin => "sin",
+            Intrinsic::Cos => "cos",
+            Intrinsic::Tan => "tan",
+            Intrinsic::Asin => "asin",
+            Intrinsic::Acos => "acos",
+            Intrinsic::Atan => "atan",
+            Intrinsic::Atan2 => "atan2",
+            Intrinsic::Sinh => "sinh",
+            Intrinsic::Cosh => "cosh",
+            Intrinsic::Tanh => "tanh",
+            Intrinsic::Asinh => "asinh",
+            Intrinsic::Acosh => "acosh",
+            Intrinsic::Atanh => "atanh",
+        }
+    }
+
+    fn is_reserved(&self, ident: &str) -> bool {
+        ident == "_" || RUST_RESERVED.contains(&ident)
+    }
+
+    fn number(&self, value: f64) -> String {
+        let sign = if value.is_sign_negative() { "-" } else { "" };
+        RUST_CONSTS
+            .iter()
+            .find(|&&(constant, _)| constant.to_bits() == value.abs().to_bits())
+            .map_or_else(
+                || f64_literal(value),
+                |(_, name)| format!("{sign}core::f64::consts::{name}"),
+            )
+    }
+
+    fn ret(&self, value: &str) -> String {
+        value.to_owned()
+    }
+
+    fn call(&self, f: Intrinsic, args: &[String]) -> String {
+        let Some((receiver, rest)) = args.split_first() else {
+            return format!("{}()", self.intrinsic(f));
+        };
+        let (method, rest) = match (f, rest) {
+            // Integer exponents that fit an i32 use powi
+            (Intrinsic::Pow, [exp]) => match exp.parse::<f64>() {
+                Ok(k) if k.fract() == 0.0 && k.abs() <= f64::from(i32::MAX) => {
+                    ("powi", vec![format!("{k}")])
+                }
+                _ => ("powf", rest.to_vec()),
+            },
+            _ => (self.intrinsic(f), rest.to_vec()),
+        };
+        if Self::is_literal(receiver) {
+            // `2.0.sin()` cannot infer f64, so literals are passed to the function
+            let receiver = receiver.trim_start_matches('(').trim_end_matches(')');
+            let all: Vec<&str> = std::iter::once(receiver)
+                .chain(rest.iter().map(String::as_str))
+                .collect();
+            return format!("f64::{method}({})", all.join(", "));
+        }
+        format!("{receiver}.{method}({})", rest.join(", "))
+    }
+}
@@ -0,0 +1,143 @@
+//! The per-language surface of the emitters: syntax of declarations and native names.
+
+/// Math operations the lowering emits as calls; each target maps them to native functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intrinsic {
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Sqrt,
+    Exp,
+    Ln,
+    Log2,
+    Pow,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Asinh,
+    Acosh,
+    Atanh,
+}
+
+impl Intrinsic {
+    /// Every intrinsic, for building reserved-name lists
+    pub const ALL: [Self; 22] = [
+        Self::Abs,
+        Self::Sign,
+        Self::Floor,
+        Self::Ceil,
+        Self::Sqrt,
+        Self::Exp,
+        Self::Ln,
+        Self::Log2,
+        Self::Pow,
+        Self::Sin,
+        Self::Cos,
+        Self::Tan,
+        Self::Asin,
+        Self::Acos,
+        Self::Atan,
+        Self::Atan2,
+        Self::Sinh,
+        Self::Cosh,
+        Self::Tanh,
+        Self::Asinh,
+        Self::Acosh,
+        Self::Atanh,
+    ];
+}
+
+/// A language the emitters can write a scalar function in.
+///
+/// The shared lowering decides what to compute; a target only decides how each piece
+/// is spelled.
+pub trait Target {
+    /// Language name used in error messages
+    fn name(&self) -> &'static str;
+
+    /// Opening line of a function `name` over `params`, including the opening brace
+    fn header(&self, name: &str, params: &[String]) -> String;
+
+    /// Lines emitted before the function, such as includes or imports
+    fn prelude(&self) -> &'static str {
+        ""
+    }
+
+    /// Declarations of the temporaries `temps`, for languages that need them up front
+    fn declarations(&self, _temps: &[String]) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Closing line of the function `name`, empty when indentation closes it
+    fn footer(&self, _name: &str) -> String {
+        "}".to_owned()
+    }
+
+    /// Whether a unary minus may directly follow a binary operator, as in `x * -y`
+    fn allows_unary_after_operator(&self) -> bool {
+        true
+    }
+
+    /// Whether [`Intrinsic::Pow`] is an infix operator, whose operands are parenthesised
+    /// unless atomic
+    fn infix_power(&self) -> bool {
+        false
+    }
+
+    /// Whether integer powers are left to the target's power function instead of being
+    /// unrolled into multiplications
+    fn has_integer_power(&self) -> bool {
+        false
+    }
+
+    /// Whether calls are written as methods of their first argument, as in `x.sin()`
+    fn calls_are_methods(&self) -> bool {
+        false
+    }
+
+    /// Opening line of a function `name` over `params` returning a tuple of `outputs`
+    /// values, or `None` if the language has no tuples
+    fn tuple_header(&self, _name: &str, _params: &[String], _outputs: usize) -> Option<String> {
+        None
+    }
+
+    /// Tuple of the already-printed `values`
+    fn tuple(&self, values: &[String]) -> String {
+        format!("({})", values.join(", "))
+    }
+
+    /// Statement binding the temporary `name` to `value`, without indentation
+    fn binding(&self, name: &str, value: &str) -> String;
+
+    /// Native function name for `f`
+    fn intrinsic(&self, f: Intrinsic) -> &'static str;
+
+    /// Whether `ident` cannot be used as a parameter or function name
+    fn is_reserved(&self, ident: &str) -> bool;
+
+    /// Literal for a `value` the target can represent
+    fn number(&self, value: f64) -> String;
+
+    /// Whether `value` has a literal in this target
+    fn can_represent(&self, value: f64) -> bool {
+        value.is_finite()
+    }
+
+    /// Statement returning `value`, without indentation
+    fn ret(&self, value: &str) -> String {
+        format!("return {value};")
+    }
+
+    /// Call of `f` on already-printed arguments
+    fn call(&self, f: Intrinsic, args: &[String]) -> String {
+        format!("{}({})", self.intrinsic(f), args.join(", "))
+    }
+}
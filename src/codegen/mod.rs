@@ -0,0 +1,11 @@
+//! Source code generation: expressions emitted as functions in other languages.
+//!
+//! - [`api`] — public entry points on [`Expr`](crate::Expr): `to_wgsl`, `to_glsl`,
+//!   `to_code`, `to_rust_code` and [`CodeGenLanguage`], and `to_rust_code_multi`
+//! - `logic/` — the shared lowering (validation, common subexpressions, power unrolling,
+//!   operator precedence) and one `Target` per language
+
+mod api;
+mod logic;
+
+pub use api::{CodeGenLanguage, to_rust_code_multi};
@@ -1,24 +1,65 @@
 use super::logic::{
-    evaluate_str as do_evaluate_str, gradient as do_gradient, gradient_str as do_gradient_str,
-    hessian as do_hessian, hessian_str as do_hessian_str, jacobian as do_jacobian,
-    jacobian_str as do_jacobian_str,
+    eval_partial_str as do_eval_partial_str, evaluate_str as do_evaluate_str, expand as do_expand,
+    gradient as do_gradient, gradient_str as do_gradient_str, hessian as do_hessian,
+    hessian_sparse as do_hessian_sparse, hessian_sparsity as do_hessian_sparsity,
+    hessian_str as do_hessian_str, jacobian as do_jacobian, jacobian_sparse as do_jacobian_sparse,
+    jacobian_sparsity as do_jacobian_sparsity, jacobian_str as do_jacobian_str,
+    suggest_scaling as do_suggest_scaling,
 };
 use crate::core::{DiffError, Expr, Symbol};
+use crate::diff::Diff;
+use std::ops::RangeInclusive;
 
 /// Compute the gradient of an expression with respect to multiple variables.
 ///
 /// # Errors
 /// Returns `DiffError` if differentiation fails for any variable.
 pub fn gradient(expr: &Expr, vars: &[&Symbol]) -> Result<Vec<Expr>, DiffError> {
-    do_gradient(expr, vars)
+    do_gradient(&Diff::new(), expr, vars)
 }
 
 /// Compute the Hessian matrix of an expression.
 ///
+/// The matrix is symmetric: each second partial above the diagonal is computed once
+/// and mirrored below it. Use [`hessian_with_gradient`] to keep the gradient too.
+///
 /// # Errors
 /// Returns `DiffError` if any second partial derivative fails.
 pub fn hessian(expr: &Expr, vars: &[&Symbol]) -> Result<Vec<Vec<Expr>>, DiffError> {
-    do_hessian(expr, vars)
+    do_hessian(&Diff::new(), expr, vars).map(|(_, matrix)| matrix)
+}
+
+/// Gradient and Hessian matrix of an expression, from [`hessian_with_gradient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hessian {
+    /// Simplified first partials, one per variable
+    pub gradient: Vec<Expr>,
+    /// Second partials; `matrix[i][j]` differentiates `gradient[i]` by `vars[j]`
+    pub matrix: Vec<Vec<Expr>>,
+}
+
+/// Compute the Hessian matrix of an expression together with its gradient.
+///
+/// The gradient is simplified once and the second partials are differentiated from
+/// it, for the upper triangle only; the lower triangle mirrors it. Rows with the
+/// same first partial are shared. This is how [`hessian`] computes the matrix, so
+/// asking for both costs no more than the Hessian alone.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{hessian_with_gradient, symb};
+///
+/// let (x, y) = (symb("x"), symb("y"));
+/// let result = hessian_with_gradient(&(x.pow(2.0) * y), &[&x, &y]).unwrap();
+/// assert_eq!(result.gradient[1].to_string(), "x^2");
+/// assert_eq!(result.matrix[0][1], result.matrix[1][0]);
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if any first or second partial derivative fails.
+pub fn hessian_with_gradient(expr: &Expr, vars: &[&Symbol]) -> Result<Hessian, DiffError> {
+    let (gradient, matrix) = do_hessian(&Diff::new(), expr, vars)?;
+    Ok(Hessian { gradient, matrix })
 }
 
 /// Compute the Jacobian matrix of a vector of expressions.
@@ -26,7 +67,133 @@ pub fn hessian(expr: &Expr, vars: &[&Symbol]) -> Result<Vec<Vec<Expr>>, DiffErro
 /// # Errors
 /// Returns `DiffError` if any partial derivative fails.
 pub fn jacobian(exprs: &[Expr], vars: &[&Symbol]) -> Result<Vec<Vec<Expr>>, DiffError> {
-    do_jacobian(exprs, vars)
+    do_jacobian(&Diff::new(), exprs, vars)
+}
+
+/// Structural sparsity pattern of the Hessian: upper-triangle entries `(i, j)`, `i <= j`.
+///
+/// Entry `(i, j)` is listed when some top-level term of `expr` contains both `vars[i]`
+/// and `vars[j]`; every other second partial is structurally zero. The analysis only
+/// collects the variables of each term, so it is much cheaper than differentiating.
+/// Entries are in row-major order.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{hessian_sparsity, symb};
+///
+/// let (x, y, z) = (symb("x"), symb("y"), symb("z"));
+/// let energy = (x.to_expr() - y.to_expr()).pow(2.0) + (y.to_expr() - z.to_expr()).pow(2.0);
+/// assert_eq!(
+///     hessian_sparsity(&energy, &[&x, &y, &z]),
+///     [(0, 0), (0, 1), (1, 1), (1, 2), (2, 2)]
+/// );
+/// ```
+#[must_use]
+pub fn hessian_sparsity(expr: &Expr, vars: &[&Symbol]) -> Vec<(usize, usize)> {
+    do_hessian_sparsity(expr, vars)
+}
+
+/// Compute the structurally nonzero second partials of an expression.
+///
+/// Returns `(i, j, d²expr/dvars[i]dvars[j])` for the upper triangle (`i <= j`) of the
+/// [`hessian_sparsity`] pattern; the lower triangle is the mirror image. Row `i` only
+/// differentiates the terms that contain `vars[i]`, so for sums of terms that each
+/// involve a few variables the cost grows with the number of nonzeros rather than with
+/// the square of the number of variables. Entries that simplify to zero are omitted.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{hessian_sparse, symb};
+///
+/// let (x, y, z) = (symb("x"), symb("y"), symb("z"));
+/// let energy = (x.to_expr() - y.to_expr()).pow(2.0) + (y.to_expr() - z.to_expr()).pow(2.0);
+/// let entries = hessian_sparse(&energy, &[&x, &y, &z]).unwrap();
+/// let values: Vec<_> = entries.iter().map(|(i, j, e)| (*i, *j, e.to_string())).collect();
+/// assert_eq!(values[1], (0, 1, "-2".to_owned()));
+/// assert_eq!(values[2], (1, 1, "4".to_owned()));
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if any first or second partial derivative fails.
+pub fn hessian_sparse(
+    expr: &Expr,
+    vars: &[&Symbol],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    do_hessian_sparse(&Diff::new(), expr, vars)
+}
+
+/// Structural sparsity pattern of the Jacobian: entries `(row, col)` where
+/// `exprs[row]` contains `vars[col]`, in row-major order.
+#[must_use]
+pub fn jacobian_sparsity(exprs: &[Expr], vars: &[&Symbol]) -> Vec<(usize, usize)> {
+    do_jacobian_sparsity(exprs, vars)
+}
+
+/// Compute the structurally nonzero entries of the Jacobian matrix.
+///
+/// Returns `(row, col, dexprs[row]/dvars[col])` for each [`jacobian_sparsity`] entry,
+/// differentiating only by the variables each expression contains. Entries that
+/// simplify to zero are omitted.
+///
+/// # Errors
+/// Returns `DiffError` if any partial derivative fails.
+pub fn jacobian_sparse(
+    exprs: &[Expr],
+    vars: &[&Symbol],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    do_jacobian_sparse(&Diff::new(), exprs, vars)
+}
+
+/// Compute the gradient of an expression with a configured [`Diff`] builder.
+///
+/// Like [`gradient`], but each partial derivative is taken with `builder`, so its
+/// context, fixed variables, user functions and limits apply. The partials are
+/// simplified `Expr` values ready for further rewriting or for
+/// [`CompiledEvaluator::compile`](crate::CompiledEvaluator::compile).
+///
+/// # Example
+/// ```
+/// use symb_anafis::{CompiledEvaluator, Diff, gradient_expr, symb};
+///
+/// let (x, y) = (symb("x"), symb("y"));
+/// let grad = gradient_expr(&(x.pow(2.0) * y), &[x, y], &Diff::new()).unwrap();
+/// let dx = CompiledEvaluator::compile(&grad[0], &["x", "y"], None).unwrap();
+/// assert_eq!(dx.evaluate(&[3.0, 2.0]), 12.0);
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if differentiation fails for any variable.
+pub fn gradient_expr(expr: &Expr, vars: &[Symbol], builder: &Diff) -> Result<Vec<Expr>, DiffError> {
+    let refs: Vec<&Symbol> = vars.iter().collect();
+    do_gradient(builder, expr, &refs)
+}
+
+/// Compute the Jacobian matrix of a vector of expressions with a configured [`Diff`]
+/// builder; row `i` is [`gradient_expr`] of `exprs[i]`.
+///
+/// # Errors
+/// Returns `DiffError` if any partial derivative fails.
+pub fn jacobian_expr(
+    exprs: &[Expr],
+    vars: &[Symbol],
+    builder: &Diff,
+) -> Result<Vec<Vec<Expr>>, DiffError> {
+    let refs: Vec<&Symbol> = vars.iter().collect();
+    do_jacobian(builder, exprs, &refs)
+}
+
+/// Compute the Hessian matrix of an expression with a configured [`Diff`] builder,
+/// as [`hessian`] does.
+///
+/// # Errors
+/// Returns `DiffError` if any second partial derivative fails.
+pub fn hessian_expr(
+    expr: &Expr,
+    vars: &[Symbol],
+    builder: &Diff,
+) -> Result<Vec<Vec<Expr>>, DiffError> {
+    let refs: Vec<&Symbol> = vars.iter().collect();
+    do_hessian(builder, expr, &refs).map(|(_, matrix)| matrix)
 }
 
 /// Compute gradient from a formula string.
@@ -62,3 +229,114 @@ pub fn jacobian_str(formulas: &[&str], vars: &[&str]) -> Result<Vec<Vec<String>>
 pub fn evaluate_str(formula: &str, vars: &[(&str, f64)]) -> Result<String, DiffError> {
     do_evaluate_str(formula, vars)
 }
+
+/// Bind some variables of a formula string and simplify what remains.
+///
+/// Unlike [`evaluate_str`], the folded result is re-simplified (see [`Expr::partial_eval`]).
+///
+/// # Example
+/// ```
+/// use symb_anafis::eval_partial_str;
+/// let reduced = eval_partial_str("a*x^2 + b*x", &[("a", 2.0)]).unwrap();
+/// assert_eq!(reduced, "2*x^2 + b*x");
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if the formula cannot be parsed.
+pub fn eval_partial_str(formula: &str, bindings: &[(&str, f64)]) -> Result<String, DiffError> {
+    do_eval_partial_str(formula, bindings)
+}
+
+/// Fully expand a formula string (see [`Expr::expand`]).
+///
+/// # Example
+/// ```
+/// use symb_anafis::expand;
+/// assert_eq!(expand("(x+1)*(x-1)", &[], None).unwrap(), "-1 + x^2");
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if the formula cannot be parsed.
+pub fn expand(
+    formula: &str,
+    known_symbols: &[&str],
+    custom_functions: Option<&[&str]>,
+) -> Result<String, DiffError> {
+    do_expand(formula, known_symbols, custom_functions)
+}
+
+/// Coefficient spread (in decades) below which an expression counts as well scaled.
+pub const WELL_SCALED_DECADES: f64 = 4.0;
+
+/// Scaling applied to one variable: `var = factor * scaled`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableScaling {
+    /// The original variable
+    pub var: Symbol,
+    /// The dimensionless replacement variable
+    pub scaled: Symbol,
+    /// Multiplier such that `var = factor * scaled`
+    pub factor: f64,
+}
+
+/// Result of [`suggest_scaling`]. See the function docs for the meaning of each field.
+#[derive(Debug, Clone)]
+pub struct ScalingReport {
+    /// Largest absolute value of each top-level term over the sampled ranges
+    pub term_magnitudes: Vec<f64>,
+    /// Decades between the largest and smallest nonzero term magnitude
+    pub dynamic_range: f64,
+    /// Decades between the largest and smallest nonzero numeric coefficient
+    pub coefficient_spread: f64,
+    /// Coefficient spread of the rescaled expression
+    pub scaled_coefficient_spread: f64,
+    /// Suggested substitutions, one per analyzed variable
+    pub scalings: Vec<VariableScaling>,
+    /// The expression rewritten in terms of the scaled variables
+    pub scaled_expr: Expr,
+}
+
+impl ScalingReport {
+    /// Whether the rescaled expression's coefficient spread is under
+    /// [`WELL_SCALED_DECADES`].
+    #[must_use]
+    pub fn is_well_scaled(&self) -> bool {
+        self.scaled_coefficient_spread < WELL_SCALED_DECADES
+    }
+}
+
+/// Analyze the numeric conditioning of an expression over variable ranges.
+///
+/// Each top-level term of the sum (polynomial nodes are split into their monomials)
+/// is compiled and sampled across the given ranges; the report lists the largest
+/// magnitude of every term and the spread between them in decades.
+///
+/// For each variable a power-of-ten factor `s` close to the range's typical
+/// magnitude is proposed, with the substitution `var = s * var_scaled`. The
+/// rewritten expression is returned in [`ScalingReport::scaled_expr`] together with
+/// the coefficient spread before and after. A spread under
+/// [`WELL_SCALED_DECADES`] is considered well scaled.
+///
+/// Evaluating `scaled_expr` at `var_scaled = var / s` reproduces the original.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{parse, suggest_scaling, symb};
+/// use std::collections::HashSet;
+///
+/// let t = symb("T");
+/// let expr = parse("2 + 3e-3*T + 4e-6*T^2", &HashSet::new(), &HashSet::new(), None).unwrap();
+/// let report = suggest_scaling(&expr, &[(t, 300.0..=3000.0)]).unwrap();
+/// assert_eq!(report.scalings[0].factor, 1000.0);
+/// assert!(report.is_well_scaled());
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if a term cannot be compiled (e.g. a free variable that
+/// has no range) or the rescaled expression fails to simplify.
+pub fn suggest_scaling(
+    expr: &Expr,
+    var_ranges: &[(Symbol, RangeInclusive<f64>)],
+) -> Result<ScalingReport, DiffError> {
+    do_suggest_scaling(expr, var_ranges)
+}
@@ -1,11 +1,11 @@
 //! Convenience calculus internals built on top of [`crate::Diff`].
 
 use crate::core::DiffError;
-use crate::core::Expr;
 use crate::core::Symbol;
+use crate::core::{Expr, ExprKind};
 use crate::diff::Diff;
 use crate::parser::parse;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 // ============================================================================
 // General Helpers
@@ -30,55 +30,246 @@ fn var_names_to_str_refs(var_names: &[String]) -> Vec<&str> {
 // Expression-based API
 // ============================================================================
 
-fn gradient_internal(expr: &Expr, vars: &[&str]) -> Result<Vec<Expr>, DiffError> {
-    let diff = Diff::new();
+#[cfg(test)]
+thread_local! {
+    /// Passes over the expression tree made by `gradient_internal` on this thread
+    pub(super) static TREE_PASSES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(test)]
+fn record_tree_passes(passes: usize) {
+    TREE_PASSES.with(|count| count.set(count.get() + passes));
+}
+
+#[cfg(not(test))]
+#[inline]
+const fn record_tree_passes(_passes: usize) {}
+
+/// Expressions affine in every variable (`Σ c_i * f_i(t)` with respect to the `c_i`)
+/// take one pass that reads off the coefficients; anything else differentiates once
+/// per variable.
+fn gradient_internal(diff: &Diff, expr: &Expr, vars: &[&str]) -> Result<Vec<Expr>, DiffError> {
+    if let Some(partials) = diff.affine_gradient(expr, vars)? {
+        record_tree_passes(1);
+        return Ok(partials);
+    }
+    record_tree_passes(vars.len());
     vars.iter()
         .map(|var| diff.differentiate_by_name(expr, var))
         .collect()
 }
 
-fn hessian_internal(expr: &Expr, vars: &[&str]) -> Result<Vec<Vec<Expr>>, DiffError> {
-    let diff = Diff::new();
-    let grad = gradient_internal(expr, vars)?;
+/// Simplified gradient and Hessian matrix.
+///
+/// Each first partial is differentiated and simplified once, and the second partials
+/// are taken from those simplified forms: row `i` only differentiates by `vars[i..]`
+/// and the lower triangle mirrors the upper one. A row whose first partial repeats an
+/// earlier one reuses that row instead of differentiating again.
+fn hessian_internal(
+    diff: &Diff,
+    expr: &Expr,
+    vars: &[&str],
+) -> Result<(Vec<Expr>, Vec<Vec<Expr>>), DiffError> {
+    let grad = gradient_internal(diff, expr, vars)?;
 
-    grad.iter()
-        .map(|partial| {
-            vars.iter()
-                .map(|var| diff.differentiate_by_name(partial, var))
-                .collect::<Result<Vec<_>, _>>()
+    // upper[i][k] is the second partial by vars[i] and vars[i + k]
+    let mut upper: Vec<Vec<Expr>> = Vec::with_capacity(grad.len());
+    for (i, partial) in grad.iter().enumerate() {
+        let row = match grad[..i].iter().position(|earlier| earlier == partial) {
+            Some(k) => upper[k][i - k..].to_vec(),
+            None => gradient_internal(diff, partial, &vars[i..])?,
+        };
+        upper.push(row);
+    }
+
+    let matrix = (0..grad.len())
+        .map(|i| {
+            (0..grad.len())
+                .map(|j| {
+                    let (row, col) = if j >= i { (i, j) } else { (j, i) };
+                    upper[row][col - row].clone()
+                })
+                .collect()
         })
+        .collect();
+    Ok((grad, matrix))
+}
+
+fn jacobian_internal(
+    diff: &Diff,
+    exprs: &[Expr],
+    vars: &[&str],
+) -> Result<Vec<Vec<Expr>>, DiffError> {
+    exprs
+        .iter()
+        .map(|expr| gradient_internal(diff, expr, vars))
         .collect()
 }
 
-fn jacobian_internal(exprs: &[Expr], vars: &[&str]) -> Result<Vec<Vec<Expr>>, DiffError> {
+// ============================================================================
+// Sparse API
+// ============================================================================
+
+/// Indices of the `vars` each additive term of `expr` contains
+///
+/// A non-sum expression is a single term.
+fn term_supports(expr: &Expr, vars: &[&str]) -> Vec<Vec<usize>> {
+    let support = |term: &Expr| {
+        let names = term.variables();
+        vars.iter()
+            .enumerate()
+            .filter(|(_, var)| names.contains(**var))
+            .map(|(i, _)| i)
+            .collect()
+    };
+    match &expr.kind {
+        ExprKind::Sum(terms) => terms.iter().map(|term| support(term)).collect(),
+        _ => vec![support(expr)],
+    }
+}
+
+/// Upper-triangle Hessian entries `(i, j)`, `i <= j`, that some term depends on through
+/// both `vars[i]` and `vars[j]`, in row-major order
+fn sparsity_of_supports(supports: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let mut pattern = BTreeSet::new();
+    // Supports are ascending, so pairing each index with the ones after it stays upper
+    for support in supports {
+        for (k, &i) in support.iter().enumerate() {
+            pattern.extend(support[k..].iter().map(|&j| (i, j)));
+        }
+    }
+    pattern.into_iter().collect()
+}
+
+/// Structurally nonzero second partials.
+///
+/// Row `i` differentiates only the terms containing `vars[i]`, and then only by the
+/// variables its pattern entries name. Entries that simplify to zero are dropped.
+fn hessian_sparse_internal(
+    diff: &Diff,
+    expr: &Expr,
+    vars: &[&str],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    let supports = term_supports(expr, vars);
+    let pattern = sparsity_of_supports(&supports);
+    let terms: Vec<Expr> = match &expr.kind {
+        ExprKind::Sum(terms) => terms.iter().map(|term| (**term).clone()).collect(),
+        _ => vec![expr.clone()],
+    };
+
+    let mut entries = Vec::with_capacity(pattern.len());
+    for row in pattern.chunk_by(|a, b| a.0 == b.0) {
+        let i = row[0].0;
+        let row_terms = terms
+            .iter()
+            .zip(&supports)
+            .filter(|(_, support)| support.contains(&i))
+            .map(|(term, _)| term.clone())
+            .collect();
+        let partial = diff.differentiate_by_name(&Expr::sum(row_terms), vars[i])?;
+        let columns: Vec<&str> = row.iter().map(|&(_, j)| vars[j]).collect();
+        let seconds = gradient_internal(diff, &partial, &columns)?;
+        entries.extend(
+            row.iter()
+                .zip(seconds)
+                .filter(|(_, second)| !second.is_zero_num())
+                .map(|(&(_, j), second)| (i, j, second)),
+        );
+    }
+    Ok(entries)
+}
+
+/// Entries `(row, col)` of the Jacobian where `exprs[row]` contains `vars[col]`
+fn jacobian_sparsity_internal(exprs: &[Expr], vars: &[&str]) -> Vec<(usize, usize)> {
     exprs
         .iter()
-        .map(|expr| gradient_internal(expr, vars))
+        .enumerate()
+        .flat_map(|(row, expr)| {
+            let names = expr.variables();
+            vars.iter()
+                .enumerate()
+                .filter(move |(_, var)| names.contains(**var))
+                .map(move |(col, _)| (row, col))
+        })
         .collect()
 }
 
-pub(in super::super) fn gradient(expr: &Expr, vars: &[&Symbol]) -> Result<Vec<Expr>, DiffError> {
+/// Structurally nonzero first partials, dropping those that simplify to zero.
+fn jacobian_sparse_internal(
+    diff: &Diff,
+    exprs: &[Expr],
+    vars: &[&str],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    let mut entries = Vec::new();
+    for (row, col) in jacobian_sparsity_internal(exprs, vars) {
+        let partial = diff.differentiate_by_name(&exprs[row], vars[col])?;
+        if !partial.is_zero_num() {
+            entries.push((row, col, partial));
+        }
+    }
+    Ok(entries)
+}
+
+pub(in super::super) fn hessian_sparsity(expr: &Expr, vars: &[&Symbol]) -> Vec<(usize, usize)> {
     let var_names = extract_var_names(vars);
     let var_refs = var_names_to_str_refs(&var_names);
-    gradient_internal(expr, &var_refs)
+    sparsity_of_supports(&term_supports(expr, &var_refs))
+}
+
+pub(in super::super) fn hessian_sparse(
+    diff: &Diff,
+    expr: &Expr,
+    vars: &[&Symbol],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    let var_names = extract_var_names(vars);
+    let var_refs = var_names_to_str_refs(&var_names);
+    hessian_sparse_internal(diff, expr, &var_refs)
+}
+
+pub(in super::super) fn jacobian_sparsity(exprs: &[Expr], vars: &[&Symbol]) -> Vec<(usize, usize)> {
+    let var_names = extract_var_names(vars);
+    let var_refs = var_names_to_str_refs(&var_names);
+    jacobian_sparsity_internal(exprs, &var_refs)
+}
+
+pub(in super::super) fn jacobian_sparse(
+    diff: &Diff,
+    exprs: &[Expr],
+    vars: &[&Symbol],
+) -> Result<Vec<(usize, usize, Expr)>, DiffError> {
+    let var_names = extract_var_names(vars);
+    let var_refs = var_names_to_str_refs(&var_names);
+    jacobian_sparse_internal(diff, exprs, &var_refs)
+}
+
+pub(in super::super) fn gradient(
+    diff: &Diff,
+    expr: &Expr,
+    vars: &[&Symbol],
+) -> Result<Vec<Expr>, DiffError> {
+    let var_names = extract_var_names(vars);
+    let var_refs = var_names_to_str_refs(&var_names);
+    gradient_internal(diff, expr, &var_refs)
 }
 
 pub(in super::super) fn hessian(
+    diff: &Diff,
     expr: &Expr,
     vars: &[&Symbol],
-) -> Result<Vec<Vec<Expr>>, DiffError> {
+) -> Result<(Vec<Expr>, Vec<Vec<Expr>>), DiffError> {
     let var_names = extract_var_names(vars);
     let var_refs = var_names_to_str_refs(&var_names);
-    hessian_internal(expr, &var_refs)
+    hessian_internal(diff, expr, &var_refs)
 }
 
 pub(in super::super) fn jacobian(
+    diff: &Diff,
     exprs: &[Expr],
     vars: &[&Symbol],
 ) -> Result<Vec<Vec<Expr>>, DiffError> {
     let var_names = extract_var_names(vars);
     let var_refs = var_names_to_str_refs(&var_names);
-    jacobian_internal(exprs, &var_refs)
+    jacobian_internal(diff, exprs, &var_refs)
 }
 
 // ============================================================================
@@ -105,7 +296,7 @@ pub(in super::super) fn gradient_str(
     vars: &[&str],
 ) -> Result<Vec<String>, DiffError> {
     let expr = parse_formula(formula)?;
-    let grad = gradient_internal(&expr, vars)?;
+    let grad = gradient_internal(&Diff::new(), &expr, vars)?;
     Ok(grad.iter().map(ToString::to_string).collect())
 }
 
@@ -114,7 +305,7 @@ pub(in super::super) fn hessian_str(
     vars: &[&str],
 ) -> Result<Vec<Vec<String>>, DiffError> {
     let expr = parse_formula(formula)?;
-    let hess = hessian_internal(&expr, vars)?;
+    let (_, hess) = hessian_internal(&Diff::new(), &expr, vars)?;
     Ok(hess
         .iter()
         .map(|row| row.iter().map(ToString::to_string).collect())
@@ -126,7 +317,7 @@ pub(in super::super) fn jacobian_str(
     vars: &[&str],
 ) -> Result<Vec<Vec<String>>, DiffError> {
     let exprs = parse_formulas(formulas)?;
-    let jac = jacobian_internal(&exprs, vars)?;
+    let jac = jacobian_internal(&Diff::new(), &exprs, vars)?;
     Ok(jac
         .iter()
         .map(|row| row.iter().map(ToString::to_string).collect())
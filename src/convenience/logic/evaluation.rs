@@ -20,3 +20,29 @@ pub(in super::super) fn evaluate_str(
     let result = expr.evaluate(&var_map, &HashMap::new());
     Ok(result.to_string())
 }
+
+pub(in super::super) fn eval_partial_str(
+    formula: &str,
+    bindings: &[(&str, f64)],
+) -> Result<String, DiffError> {
+    let (fixed_vars, custom_fns) = empty_context();
+    let expr = parse(formula, &fixed_vars, &custom_fns, None)?;
+
+    let var_map: HashMap<&str, f64> = bindings.iter().copied().collect();
+    Ok(expr.partial_eval(&var_map).to_string())
+}
+
+pub(in super::super) fn expand(
+    formula: &str,
+    known_symbols: &[&str],
+    custom_functions: Option<&[&str]>,
+) -> Result<String, DiffError> {
+    let symbols: HashSet<String> = known_symbols.iter().map(ToString::to_string).collect();
+    let functions: HashSet<String> = custom_functions
+        .unwrap_or_default()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let expr = parse(formula, &symbols, &functions, None)?;
+    Ok(expr.expand().to_string())
+}
@@ -2,9 +2,14 @@
 
 pub(super) mod calculus;
 pub(super) mod evaluation;
+pub(super) mod scaling;
 
-pub(super) use calculus::{gradient, gradient_str, hessian, hessian_str, jacobian, jacobian_str};
-pub(super) use evaluation::evaluate_str;
+pub(super) use calculus::{
+    gradient, gradient_str, hessian, hessian_sparse, hessian_sparsity, hessian_str, jacobian,
+    jacobian_sparse, jacobian_sparsity, jacobian_str,
+};
+pub(super) use evaluation::{eval_partial_str, evaluate_str, expand};
+pub(super) use scaling::suggest_scaling;
 
 #[cfg(test)]
 mod tests;
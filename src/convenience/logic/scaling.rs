@@ -0,0 +1,126 @@
+//! Numeric conditioning analysis: term magnitudes and variable scaling suggestions.
+//!
+//! This is advisory tooling. Nothing here rewrites the user's expression in place;
+//! [`suggest_scaling`] only reports what it measured and returns a rescaled copy.
+
+use crate::convenience::{ScalingReport, VariableScaling};
+use crate::core::{DiffError, Expr, ExprKind, Symbol, symb};
+use crate::evaluator::CompiledEvaluator;
+use std::ops::RangeInclusive;
+
+/// Number of sample points used to measure term magnitudes.
+const SAMPLE_COUNT: u32 = 33;
+
+/// Splits the top level of `expr` into additive terms, expanding polynomial nodes.
+fn top_level_terms(expr: &Expr) -> Vec<Expr> {
+    let mut terms = Vec::new();
+    let mut push = |term: &Expr| match &term.kind {
+        ExprKind::Poly(poly) => terms.extend(poly.to_expr_terms()),
+        _ => terms.push(term.clone()),
+    };
+    match &expr.kind {
+        ExprKind::Sum(children) => children.iter().for_each(|c| push(c)),
+        _ => push(expr),
+    }
+    terms
+}
+
+/// Product of the numeric factors of a term (1 when it has none).
+fn numeric_coefficient(term: &Expr) -> f64 {
+    match &term.kind {
+        ExprKind::Number(n) => *n,
+        ExprKind::Product(factors) => factors.iter().map(|f| numeric_coefficient(f)).product(),
+        ExprKind::Div(num, den) => match &den.kind {
+            ExprKind::Number(d) => numeric_coefficient(num) / d,
+            _ => numeric_coefficient(num),
+        },
+        _ => 1.0,
+    }
+}
+
+/// Decades spanned by the nonzero finite magnitudes in `values`.
+fn spread_in_decades(values: impl Iterator<Item = f64>) -> f64 {
+    let (min, max) = values
+        .map(f64::abs)
+        .filter(|v| *v > 0.0 && v.is_finite())
+        .fold((f64::INFINITY, 0.0_f64), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if max > 0.0 { (max / min).log10() } else { 0.0 }
+}
+
+/// Power of ten closest (in log space) to the geometric mean of a range's endpoints.
+fn characteristic_scale(range: &RangeInclusive<f64>) -> f64 {
+    let lo = range.start().abs();
+    let hi = range.end().abs();
+    let typical = match (lo > 0.0, hi > 0.0) {
+        (true, true) => (lo * hi).sqrt(),
+        (false, true) => hi,
+        (true, false) => lo,
+        (false, false) => return 1.0,
+    };
+    10.0_f64.powf(typical.log10().round())
+}
+
+pub(in super::super) fn suggest_scaling(
+    expr: &Expr,
+    var_ranges: &[(Symbol, RangeInclusive<f64>)],
+) -> Result<ScalingReport, DiffError> {
+    let params: Vec<&Symbol> = var_ranges.iter().map(|(sym, _)| sym).collect();
+    let terms = top_level_terms(expr);
+
+    // Sample term magnitudes along the diagonal of the range box.
+    let samples: Vec<Vec<f64>> = (0..SAMPLE_COUNT)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(SAMPLE_COUNT - 1);
+            var_ranges
+                .iter()
+                .map(|(_, r)| (r.end() - r.start()).mul_add(t, *r.start()))
+                .collect()
+        })
+        .collect();
+
+    let term_magnitudes = terms
+        .iter()
+        .map(|term| {
+            let compiled = CompiledEvaluator::compile(term, &params, None)?;
+            Ok(samples
+                .iter()
+                .map(|point| compiled.evaluate(point).abs())
+                .filter(|v| v.is_finite())
+                .fold(0.0_f64, f64::max))
+        })
+        .collect::<Result<Vec<f64>, DiffError>>()?;
+
+    let scalings: Vec<VariableScaling> = var_ranges
+        .iter()
+        .map(|(sym, range)| {
+            let name = sym.name().unwrap_or_else(|| format!("${}", sym.id()));
+            VariableScaling {
+                var: *sym,
+                scaled: symb(&format!("{name}_scaled")),
+                factor: characteristic_scale(range),
+            }
+        })
+        .collect();
+
+    let scaled_terms = terms
+        .iter()
+        .map(|term| {
+            let substituted = scalings.iter().fold(term.clone(), |acc, s| {
+                let name = s.var.name().unwrap_or_default();
+                acc.substitute(&name, &(s.factor * s.scaled.to_expr()))
+            });
+            substituted.simplified()
+        })
+        .collect::<Result<Vec<Expr>, DiffError>>()?;
+
+    Ok(ScalingReport {
+        dynamic_range: spread_in_decades(term_magnitudes.iter().copied()),
+        coefficient_spread: spread_in_decades(terms.iter().map(numeric_coefficient)),
+        scaled_coefficient_spread: spread_in_decades(scaled_terms.iter().map(numeric_coefficient)),
+        term_magnitudes,
+        scalings,
+        scaled_expr: Expr::sum(scaled_terms),
+    })
+}
@@ -1,4 +1,32 @@
-use crate::convenience::{evaluate_str, gradient_str, hessian_str, jacobian_str};
+use super::calculus::TREE_PASSES;
+use crate::convenience::{
+    WELL_SCALED_DECADES, evaluate_str, gradient, gradient_expr, gradient_str, hessian,
+    hessian_expr, hessian_sparse, hessian_sparsity, hessian_str, hessian_with_gradient, jacobian,
+    jacobian_expr, jacobian_sparse, jacobian_sparsity, jacobian_str, suggest_scaling,
+};
+use crate::{CompiledEvaluator, Diff, Expr, Symbol, UserFunction, parse, symb};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+fn parse_plain(formula: &str) -> Expr {
+    parse(formula, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Tree passes made while running `f`
+fn count_passes<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    TREE_PASSES.with(|count| count.set(0));
+    let result = f();
+    (result, TREE_PASSES.with(std::cell::Cell::get))
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+fn generic_partials(expr: &Expr, vars: &[&Symbol]) -> Vec<Expr> {
+    let diff = Diff::new();
+    vars.iter()
+        .map(|var| diff.differentiate(expr, var).unwrap())
+        .collect()
+}
 
 #[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
 #[test]
@@ -41,3 +69,299 @@ fn test_evaluate_str_full() {
     let result = evaluate_str("x * y", &[("x", 3.0), ("y", 2.0)]).unwrap();
     assert_eq!(result, "6");
 }
+
+/// NASA 7-coefficient heat capacity fit for CH4 (high-temperature range), `cp/R` in `T`.
+const NASA7_CP: &str =
+    "7.4851495e-2 + 1.33909467e-2*T - 5.73285809e-6*T^2 + 1.22292535e-9*T^3 - 1.0181523e-13*T^4";
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_suggest_scaling_nasa7() {
+    let t = symb("T");
+    let expr = parse(NASA7_CP, &HashSet::new(), &HashSet::new(), None).unwrap();
+    let report = suggest_scaling(&expr, &[(t, 300.0..=3000.0)]).unwrap();
+
+    assert_eq!(report.term_magnitudes.len(), 5);
+    assert_eq!(report.scalings.len(), 1);
+    assert_eq!(report.scalings[0].var, t);
+    assert!((report.scalings[0].factor - 1000.0).abs() < f64::EPSILON);
+    assert!(report.coefficient_spread > 11.0);
+    assert!(report.scaled_coefficient_spread < WELL_SCALED_DECADES);
+    assert!(report.is_well_scaled());
+
+    let original = CompiledEvaluator::compile(&expr, &[&t], None).unwrap();
+    let scaled =
+        CompiledEvaluator::compile(&report.scaled_expr, &[&report.scalings[0].scaled], None)
+            .unwrap();
+    for temp in [300.0, 1000.0, 1750.0, 3000.0] {
+        let expected = original.evaluate(&[temp]);
+        let actual = scaled.evaluate(&[temp / report.scalings[0].factor]);
+        assert!((expected - actual).abs() < 1e-12 * expected.abs());
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_suggest_scaling_reports_dynamic_range() {
+    let x = symb("x");
+    let expr = parse("1e6*x + 1e-3", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let report = suggest_scaling(&expr, &[(x, 1.0..=10.0)]).unwrap();
+    assert_eq!(report.term_magnitudes.len(), 2);
+    assert!((report.dynamic_range - 10.0).abs() < 1e-9);
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_suggest_scaling_unranged_variable() {
+    let x = symb("x");
+    let expr = parse("x + y", &HashSet::new(), &HashSet::new(), None).unwrap();
+    assert!(suggest_scaling(&expr, &[(x, 0.0..=1.0)]).is_err());
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_linear_design_gradient_takes_one_pass() {
+    let templates = ["sin(#*t)", "t^#", "exp(-t/#)", "ln(t + #)", "#"];
+    let basis = |i: usize| templates[i % templates.len()].replace('#', &i.to_string());
+    let formula = (1..=100)
+        .map(|i| format!("c{i}*{}", basis(i)))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let expr = parse_plain(&formula);
+    let coefficients: Vec<Symbol> = (1..=100).map(|i| symb(&format!("c{i}"))).collect();
+    let vars: Vec<&Symbol> = coefficients.iter().collect();
+
+    let (grad, passes) = count_passes(|| gradient(&expr, &vars).unwrap());
+    assert_eq!(passes, 1);
+    assert_eq!(grad, generic_partials(&expr, &vars));
+    for (i, partial) in (1..=100).zip(&grad) {
+        assert_eq!(partial, &parse_plain(&basis(i)).simplified().unwrap());
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_nonlinear_variable_falls_back() {
+    let (a, b, t) = (symb("a"), symb("b"), symb("t"));
+    let expr = parse_plain("a*sin(t) + 2*(b - a*t)/3 + t^2");
+
+    let (linear, linear_passes) = count_passes(|| gradient(&expr, &[&a, &b]).unwrap());
+    assert_eq!(linear_passes, 1);
+    assert_eq!(linear, generic_partials(&expr, &[&a, &b]));
+
+    let (mixed, mixed_passes) = count_passes(|| gradient(&expr, &[&a, &b, &t]).unwrap());
+    assert_eq!(mixed_passes, 3);
+    assert_eq!(mixed, generic_partials(&expr, &[&a, &b, &t]));
+
+    let rows = [expr, parse_plain("a*b")];
+    let (jac, jacobian_passes) = count_passes(|| jacobian(&rows, &[&a, &b]).unwrap());
+    assert_eq!(jacobian_passes, 3);
+    assert_eq!(jac[1], generic_partials(&rows[1], &[&a, &b]));
+}
+
+#[test]
+fn test_is_affine_in() {
+    let c = symb("c");
+    for (formula, affine) in [
+        ("c", true),
+        ("3*c*sin(t) + t^2", true),
+        ("2*(c + t)/exp(t)", true),
+        ("c/t - c", true),
+        ("sin(t)", true),
+        ("c*c", false),
+        ("c^2", false),
+        ("t/c", false),
+        ("sin(c)", false),
+        ("c*(c + 1)", false),
+    ] {
+        assert_eq!(parse_plain(formula).is_affine_in(&c), affine, "{formula}");
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_hessian_differentiates_simplified_gradient_once() {
+    let formula = (0..10)
+        .map(|i| format!("x{i}^2*x{}", (i + 1) % 10))
+        .chain(["sin(x0*x9)".into(), "exp(x3 - x5)".into(), "x2/x7".into()])
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let expr = parse_plain(&formula);
+    let symbols: Vec<Symbol> = (0..10).map(|i| symb(&format!("x{i}"))).collect();
+    let vars: Vec<&Symbol> = symbols.iter().collect();
+
+    // Every row differentiated in full, as before
+    let (full, full_passes) = count_passes(|| {
+        gradient(&expr, &vars)
+            .unwrap()
+            .iter()
+            .map(|partial| gradient(partial, &vars).unwrap())
+            .collect::<Vec<_>>()
+    });
+    let (result, passes) = count_passes(|| hessian_with_gradient(&expr, &vars).unwrap());
+    assert_eq!(full_passes, 10 + 100);
+    assert_eq!(passes, 10 + 55);
+
+    assert_eq!(result.gradient, gradient(&expr, &vars).unwrap());
+    assert_eq!(result.matrix, hessian(&expr, &vars).unwrap());
+    for i in 0..10 {
+        for j in 0..10 {
+            let (lo, hi) = (i.min(j), i.max(j));
+            assert_eq!(result.matrix[i][j], full[lo][hi], "entry ({i}, {j})");
+            assert_eq!(
+                result.matrix[i][j].simplified().unwrap(),
+                full[i][j].simplified().unwrap(),
+                "entry ({i}, {j})"
+            );
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_hessian_shares_repeated_partials() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = parse_plain("(x + y)^3");
+    let (result, passes) = count_passes(|| hessian_with_gradient(&expr, &[&x, &y]).unwrap());
+    assert_eq!(result.gradient[0], result.gradient[1]);
+    // The gradient and the first row; the second row is the first one's tail
+    assert_eq!(passes, 2 + 2);
+    assert_eq!(result.matrix[1][1], result.matrix[0][0]);
+    assert_eq!(result.matrix[0][1], result.matrix[1][0]);
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_expr_forms_match_strings_and_compile() {
+    let formula = "x^2*sin(y) + exp(x*y)";
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = parse_plain(formula);
+    let diff = Diff::new();
+
+    let grad = gradient_expr(&expr, &[x, y], &diff).unwrap();
+    let as_strings: Vec<String> = grad.iter().map(ToString::to_string).collect();
+    assert_eq!(as_strings, gradient_str(formula, &["x", "y"]).unwrap());
+
+    let hess = hessian_expr(&expr, &[x, y], &diff).unwrap();
+    assert_eq!(hess, hessian(&expr, &[&x, &y]).unwrap());
+    let rows = [expr, x * y];
+    let jac = jacobian_expr(&rows, &[x, y], &diff).unwrap();
+    assert_eq!(jac[0], grad);
+    let jac_strings: Vec<Vec<String>> = jac
+        .iter()
+        .map(|row| row.iter().map(ToString::to_string).collect())
+        .collect();
+    assert_eq!(
+        jac_strings,
+        jacobian_str(&[formula, "x*y"], &["x", "y"]).unwrap()
+    );
+
+    let (xv, yv) = (0.7_f64, -1.3_f64);
+    let dx = CompiledEvaluator::compile(&grad[0], &["x", "y"], None).unwrap();
+    let expected = (2.0 * xv).mul_add(yv.sin(), yv * (xv * yv).exp());
+    assert!((dx.evaluate(&[xv, yv]) - expected).abs() < 1e-12);
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_expr_forms_use_the_builder() {
+    let x = symb("x");
+    // ∂f/∂u = 2*u, known only to the builder
+    let f = UserFunction::new(1..=1)
+        .partial(0, |args: &[Arc<Expr>]| {
+            Expr::number(2.0) * Expr::from(&args[0])
+        })
+        .unwrap();
+    let diff = Diff::new().user_fn("grad_expr_f", f);
+    let expr = Expr::call::<1>("grad_expr_f", [Expr::symbol("x")]);
+
+    let grad = gradient_expr(&expr, &[x], &diff).unwrap();
+    assert_eq!(grad[0], diff.differentiate(&expr, &x).unwrap());
+    let hess = hessian_expr(&expr, &[x], &diff).unwrap();
+    assert_eq!(hess[0][0].as_number(), Some(2.0));
+}
+
+/// Energy of a ring of `n` springs with stiffness `1..=n`: `sum k_i*(x_i - x_{i+1})^2`
+fn spring_ring(n: usize) -> (Expr, Vec<Symbol>) {
+    let formula = (0..n)
+        .map(|i| format!("{}*(x{i} - x{})^2", i + 1, (i + 1) % n))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let symbols = (0..n).map(|i| symb(&format!("x{i}"))).collect();
+    (parse_plain(&formula), symbols)
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_hessian_sparse_matches_dense_on_spring_ring() {
+    let n = 30;
+    let (energy, symbols) = spring_ring(n);
+    let vars: Vec<&Symbol> = symbols.iter().collect();
+
+    // Diagonal plus one neighbour per row (and the wrap-around corner)
+    let pattern = hessian_sparsity(&energy, &vars);
+    assert_eq!(pattern.len(), 2 * n);
+    assert!(pattern.contains(&(0, n - 1)));
+    assert!(pattern.iter().all(|&(i, j)| i <= j));
+
+    let sparse = hessian_sparse(&energy, &vars).unwrap();
+    let dense = hessian(&energy, &vars).unwrap();
+    assert_eq!(sparse.len(), pattern.len());
+    for (i, j, entry) in &sparse {
+        assert_eq!(
+            entry.as_number(),
+            dense[*i][*j].as_number(),
+            "entry ({i}, {j})"
+        );
+    }
+    for (i, row) in dense.iter().enumerate() {
+        for (j, entry) in row.iter().enumerate().skip(i) {
+            if !pattern.contains(&(i, j)) {
+                assert!(entry.is_zero_num(), "entry ({i}, {j}) should be zero");
+            }
+        }
+    }
+    // k_0 + k_{n-1} on the first diagonal entry, -k_0 next to it
+    assert_eq!(sparse[0], (0, 0, Expr::number(2.0 * (1.0 + 30.0))));
+    assert_eq!(sparse[1].2, Expr::number(-2.0));
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_hessian_sparse_drops_structural_zeros_that_vanish() {
+    let (x, y) = (symb("x"), symb("y"));
+    // x*y is linear in each variable, so its diagonal entries vanish
+    let expr = parse_plain("x*y + sin(y)");
+    assert_eq!(hessian_sparsity(&expr, &[&x, &y]), [(0, 0), (0, 1), (1, 1)]);
+    let entries: Vec<(usize, usize, String)> = hessian_sparse(&expr, &[&x, &y])
+        .unwrap()
+        .into_iter()
+        .map(|(i, j, e)| (i, j, e.to_string()))
+        .collect();
+    assert_eq!(
+        entries,
+        [(0, 1, "1".to_owned()), (1, 1, "-sin(y)".to_owned())]
+    );
+}
+
+#[allow(clippy::unwrap_used, reason = "Standard test relaxations")]
+#[test]
+fn test_jacobian_sparse_matches_dense() {
+    let n = 12;
+    let symbols: Vec<Symbol> = (0..n).map(|i| symb(&format!("x{i}"))).collect();
+    let vars: Vec<&Symbol> = symbols.iter().collect();
+    // Spring forces: each row touches a node and its two neighbours
+    let rows: Vec<Expr> = (0..n)
+        .map(|i| parse_plain(&format!("x{} - 2*x{i} + x{}", (i + n - 1) % n, (i + 1) % n)))
+        .collect();
+
+    assert_eq!(jacobian_sparsity(&rows, &vars).len(), 3 * n);
+    let sparse = jacobian_sparse(&rows, &vars).unwrap();
+    let dense = jacobian(&rows, &vars).unwrap();
+    assert_eq!(sparse.len(), 3 * n);
+    for (row, col, entry) in &sparse {
+        assert_eq!(entry, &dense[*row][*col]);
+    }
+    let nonzero = dense.iter().flatten().filter(|e| !e.is_zero_num()).count();
+    assert_eq!(nonzero, sparse.len());
+}
@@ -9,9 +9,11 @@ pub use super::helpers::known_symbols;
 pub use super::helpers::traits;
 
 // Re-export shared internal symbol types at the core level
+#[cfg(feature = "experimental")]
+pub use super::symbol::uncertainty_target;
 pub use super::symbol::{InternedSymbol, lookup_by_id, symb_interned, symb_new_isolated};
 
-pub use super::expr::{CustomEvalMap, arc_number};
+pub use super::expr::{CustomEvalMap, arc_number, map_piecewise, piecewise_parts};
 
 pub mod error {
     pub use super::helpers::DiffError;
@@ -5,11 +5,18 @@
 //! interface; everything else is an implementation detail.
 
 // --- Error types ---
-pub use super::helpers::{DiffError, Span};
+pub use super::helpers::{DiffError, SourceLocation, Span};
 pub use super::symbol::SymbolError;
 
 // --- Expression types ---
-pub use super::expr::{ArcExprExt, Expr, ExprKind, Polynomial};
+pub use super::expr::{
+    ArcExprExt, Expr, ExprKind, LatexConfig, LimitPoint, MathmlConfig, Polynomial,
+};
+pub use super::expr::{CompareOp, Condition};
+pub use super::expr::{Constraint, Domain, Enclosure, EnclosureMap, Interval};
+pub use super::expr::{FormatOptions, FunctionCallStyle, MulStyle, PowerOperator};
+pub use super::expr::{PolyConversion, poly_conversion, set_poly_conversion, with_poly_conversion};
+pub use super::expr::{RenameReport, rename_symbols, rename_symbols_dry_run};
 
 // --- Visitor pattern ---
 /// Expression visitor utilities
@@ -17,12 +24,12 @@ pub use super::helpers::ExprView;
 
 // --- Symbol management ---
 pub use super::symbol::{
-    Symbol, clear_symbols, remove_symbol, symb, symb_get, symb_new, symbol_count, symbol_exists,
-    symbol_names,
+    Symbol, SymbolScope, clear_symbols, remove_symbol, symb, symb_get, symb_new, symbol_count,
+    symbol_exists, symbol_names,
 };
 
 // --- Context types ---
-pub use super::context::{BodyFn, Context, UserFunction};
+pub use super::context::{BodyFn, Context, InverseCaveat, NumericFn, UserFunction};
 
 // --- Traits ---
 pub use super::helpers::traits::MathScalar;
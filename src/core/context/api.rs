@@ -14,3 +14,21 @@ pub type BodyFn = Arc<dyn Fn(&[Arc<Expr>]) -> Expr + Send + Sync>;
 /// Thread-safe partial derivative function.
 /// Takes argument expressions and returns the partial derivative as an `Expr`.
 pub type PartialFn = Arc<dyn Fn(&[Arc<Expr>]) -> Expr + Send + Sync>;
+
+/// Thread-safe numeric implementation of a user function.
+/// Takes the argument values and returns the function value.
+pub type NumericFn = Arc<dyn Fn(&[f64]) -> f64 + Send + Sync>;
+
+/// When collapsing `f(g(x))` to `x` is valid, for a pair of inverse functions `f` and `g`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InverseCaveat {
+    /// Holds wherever `g(x)` is defined, e.g. `sinh(asinh(x))`.
+    None,
+    /// Holds only where `g` is defined, so collapsing widens the domain,
+    /// e.g. `exp(ln(x))` needs `x > 0`.
+    RestrictedDomain,
+    /// Holds only when `x` lies in the principal range of `f`,
+    /// e.g. `asin(sin(x))` needs `-π/2 ≤ x ≤ π/2`.
+    PrincipalRange,
+}
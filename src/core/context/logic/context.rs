@@ -1,6 +1,6 @@
 //! Implementation details for `Context` and `UserFunction`.
 use super::PartialFn;
-use crate::core::BodyFn;
+use crate::core::{BodyFn, InverseCaveat, NumericFn};
 use crate::core::{lookup_by_id, symb_get, symb_new_isolated};
 use std::collections::HashSet;
 use std::collections::hash_map::Entry;
@@ -11,7 +11,10 @@ use std::sync::{Arc, RwLock};
 
 use rustc_hash::FxHashMap;
 
-use crate::core::{DiffError, Expr, InternedSymbol, Symbol, symb_interned};
+use super::library;
+use crate::core::{DiffError, Expr, InternedSymbol, Symbol, SymbolScope, symb_interned};
+use crate::diff::Diff;
+use crate::parser::parse;
 
 // =============================================================================
 // UserFunction
@@ -32,6 +35,8 @@ pub struct UserFunction {
     pub(crate) arity: RangeInclusive<usize>,
     pub(crate) body: Option<BodyFn>,
     pub(crate) partials: FxHashMap<usize, PartialFn>,
+    pub(crate) inverse: Option<(String, InverseCaveat)>,
+    pub(crate) numeric: Option<NumericFn>,
 }
 
 impl Default for UserFunction {
@@ -40,6 +45,8 @@ impl Default for UserFunction {
             arity: 0..=usize::MAX,
             body: None,
             partials: FxHashMap::default(),
+            inverse: None,
+            numeric: None,
         }
     }
 }
@@ -57,6 +64,8 @@ impl UserFunction {
             arity,
             body: None,
             partials: FxHashMap::default(),
+            inverse: None,
+            numeric: None,
         }
     }
 
@@ -123,6 +132,48 @@ impl UserFunction {
         Ok(self)
     }
 
+    /// Declare `name` as the inverse of this function, so that both `f(name(x))` and
+    /// `name(f(x))` simplify to `x`.
+    ///
+    /// Pairs with a caveat other than [`InverseCaveat::None`] are left alone in
+    /// domain-safe mode.
+    ///
+    /// ```
+    /// use symb_anafis::{InverseCaveat, UserFunction};
+    /// let f = UserFunction::new(1..=1).inverse("finv", InverseCaveat::None);
+    /// ```
+    #[must_use]
+    pub fn inverse(mut self, name: impl Into<String>, caveat: InverseCaveat) -> Self {
+        self.inverse = Some((name.into(), caveat));
+        self
+    }
+
+    /// Set a native implementation used by the compiled evaluator.
+    ///
+    /// Calls that are not expanded through a [`body`](Self::body) compile to a call of
+    /// `f` with the argument values, so a function known only numerically can still be
+    /// evaluated. `f` must be pure: equal calls may be merged.
+    ///
+    /// ```
+    /// use symb_anafis::UserFunction;
+    /// let f = UserFunction::new(2..=2).numeric(|args| args[0].hypot(args[1]));
+    /// ```
+    #[must_use]
+    pub fn numeric<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&[f64]) -> f64 + Send + Sync + 'static,
+    {
+        self.numeric = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the native implementation using a pre-wrapped `NumericFn` Arc (for FFI/Python bindings).
+    #[must_use]
+    pub fn numeric_arc(mut self, f: NumericFn) -> Self {
+        self.numeric = Some(f);
+        self
+    }
+
     /// Returns `true` if this function has a body expression defined.
     #[inline]
     #[must_use]
@@ -130,6 +181,13 @@ impl UserFunction {
         self.body.is_some()
     }
 
+    /// Returns `true` if this function has a native implementation.
+    #[inline]
+    #[must_use]
+    pub fn has_numeric(&self) -> bool {
+        self.numeric.is_some()
+    }
+
     /// Returns `true` if this function has a partial for the given argument index.
     #[inline]
     #[must_use]
@@ -151,6 +209,8 @@ impl Debug for UserFunction {
             .field("arity", &self.arity)
             .field("has_body", &self.body.is_some())
             .field("partials", &self.partials.keys().collect::<Vec<_>>())
+            .field("inverse", &self.inverse)
+            .field("has_numeric", &self.numeric.is_some())
             .finish()
     }
 }
@@ -161,11 +221,34 @@ impl Debug for UserFunction {
 
 static NEXT_CONTEXT_ID: AtomicU64 = AtomicU64::new(1);
 
+/// CODATA 2018 values (SI units) of the constants registered by
+/// [`Context::load_physics_constants`].
+const PHYSICS_CONSTANTS: [(&str, f64); 6] = [
+    ("c", 299_792_458.0),        // m/s
+    ("h", 6.626_070_15e-34),     // J*s
+    ("hbar", 1.054_571_817e-34), // J*s
+    ("k_B", 1.380_649e-23),      // J/K
+    ("N_A", 6.022_140_76e23),    // 1/mol
+    ("G", 6.674_30e-11),         // m^3/(kg*s^2)
+];
+
+/// A named constant registered in a context.
+#[derive(Debug, Clone)]
+struct ContextConstant {
+    /// Numeric value substituted during evaluation
+    value: f64,
+    /// Exact definition in terms of other symbols (`hbar = h/(2*pi)`)
+    definition: Option<Expr>,
+}
+
 #[derive(Debug, Default)]
 struct ContextInner {
     symbols: FxHashMap<String, InternedSymbol>,
     user_functions: FxHashMap<u64, UserFunction>,
     fn_name_to_id: FxHashMap<String, u64>,
+    constants: FxHashMap<u64, ContextConstant>,
+    /// Symbols declared as data series, in declaration order
+    data: Vec<u64>,
 }
 
 /// Unified context for all `symb_anafis` operations.
@@ -185,6 +268,8 @@ struct ContextInner {
 pub struct Context {
     id: u64,
     inner: Arc<RwLock<ContextInner>>,
+    /// Scope that [`Context::symb`] resolves names in, if any
+    scope: Option<SymbolScope>,
 }
 
 impl Default for Context {
@@ -199,9 +284,29 @@ impl Context {
         Self {
             id: NEXT_CONTEXT_ID.fetch_add(1, Ordering::Relaxed),
             inner: Arc::new(RwLock::new(ContextInner::default())),
+            scope: None,
         }
     }
 
+    /// This context, resolving symbol names in `scope` (builder pattern).
+    ///
+    /// [`symb`](Self::symb), and so parsing with this context, then returns the
+    /// scope's symbols instead of global ones. Functions, constants and registered
+    /// symbol names are shared with the context this was built from.
+    #[must_use]
+    pub fn with_scope(mut self, scope: &SymbolScope) -> Self {
+        self.scope = Some(scope.clone());
+        self
+    }
+
+    /// The scope symbol names are resolved in, if one was set with
+    /// [`with_scope`](Self::with_scope).
+    #[inline]
+    #[must_use]
+    pub const fn scope(&self) -> Option<&SymbolScope> {
+        self.scope.as_ref()
+    }
+
     /// Get this context's unique ID.
     #[inline]
     #[must_use]
@@ -235,10 +340,19 @@ impl Context {
 
     /// Get or create a symbol in this context.
     ///
+    /// With a [scope](Self::with_scope), this is the scope's symbol `name`.
+    ///
     /// # Panics
     /// Panics if the internal lock is poisoned.
     #[must_use]
     pub fn symb(&self, name: &str) -> Symbol {
+        self.scope
+            .as_ref()
+            .map_or_else(|| self.intern(name), |scope| scope.symb(name))
+    }
+
+    /// Get or create the context's own symbol `name`, ignoring any scope
+    fn intern(&self, name: &str) -> Symbol {
         let mut inner = self.inner.write().expect("Context lock poisoned");
         if let Some(existing) = inner.symbols.get(name) {
             return Symbol::from_id(existing.id());
@@ -250,8 +364,7 @@ impl Context {
     }
 
     fn register_symbol(&self, name: &str) {
-        #[allow(clippy::let_underscore_must_use, reason = "Side-effect only")]
-        let _ = self.symb(name);
+        self.intern(name);
     }
 
     /// Check if a symbol is registered in this context.
@@ -269,10 +382,15 @@ impl Context {
 
     /// Get a symbol by name, or `None` if not registered.
     ///
+    /// With a [scope](Self::with_scope), the scope's symbol `name` comes first.
+    ///
     /// # Panics
     /// Panics if the internal lock is poisoned.
     #[must_use]
     pub fn get_symbol(&self, name: &str) -> Option<Symbol> {
+        if let Some(symbol) = self.scope.as_ref().and_then(|scope| scope.get(name)) {
+            return Some(symbol);
+        }
         self.inner
             .read()
             .expect("Context lock poisoned")
@@ -311,6 +429,204 @@ impl Context {
             .collect()
     }
 
+    // =========================================================================
+    // Constant registration
+    // =========================================================================
+
+    /// Register a named constant with the value used during evaluation (builder pattern).
+    ///
+    /// The constant stays a symbol during differentiation and simplification; a
+    /// [`CompiledEvaluator`](crate::CompiledEvaluator) compiled with this context
+    /// substitutes `value` unless the name is passed as a parameter.
+    ///
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, Context, parse};
+    /// use std::collections::HashSet;
+    ///
+    /// let ctx = Context::new().with_constant("g0", 9.806_65);
+    /// let weight = parse("m*g0", &HashSet::new(), &HashSet::new(), Some(&ctx)).unwrap();
+    /// let eval = CompiledEvaluator::compile(&weight, &[ctx.symb("m")], Some(&ctx)).unwrap();
+    /// assert!((eval.evaluate(&[2.0]) - 19.6133).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn with_constant(self, name: &str, value: f64) -> Self {
+        self.register_constant(name, value, None);
+        self
+    }
+
+    /// Register a named constant together with its exact definition (builder pattern).
+    ///
+    /// The definition is only used when requested, e.g. by
+    /// [`Simplify::expand_constants`](crate::Simplify::expand_constants); otherwise the
+    /// constant behaves like one registered with [`with_constant`](Self::with_constant).
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn with_defined_constant(self, name: &str, value: f64, definition: Expr) -> Self {
+        self.register_constant(name, value, Some(definition));
+        self
+    }
+
+    /// Register the physical constants `c`, `h`, `hbar`, `k_B`, `N_A` and `G` with their
+    /// CODATA 2018 values in SI units (builder pattern).
+    ///
+    /// `hbar` is defined as `h/(2*pi)`. Nothing is registered globally: contexts
+    /// that do not load the set keep these names as ordinary symbols.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn load_physics_constants(self) -> Self {
+        for (name, value) in PHYSICS_CONSTANTS {
+            let definition = (name == "hbar").then(|| {
+                Expr::div_expr(
+                    self.symb("h").to_expr(),
+                    Expr::mul_expr(Expr::number(2.0), Expr::symbol("pi")),
+                )
+            });
+            self.register_constant(name, value, definition);
+        }
+        self
+    }
+
+    /// The value of a constant registered in this context, or `None`.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn constant_value(&self, name: &str) -> Option<f64> {
+        let id = self.get_symbol(name)?.id();
+        self.constant_value_by_id(id)
+    }
+
+    /// The value of the constant with symbol ID `id`, or `None`.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn constant_value_by_id(&self, id: u64) -> Option<f64> {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .constants
+            .get(&id)
+            .map(|c| c.value)
+    }
+
+    /// `(symbol, value)` pairs for every constant of this context.
+    pub(crate) fn constant_values(&self) -> Vec<(Expr, Expr)> {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .constants
+            .iter()
+            .map(|(&id, c)| (Symbol::from_id(id).to_expr(), Expr::number(c.value)))
+            .collect()
+    }
+
+    /// `(symbol, definition)` pairs for the constants that have a definition.
+    pub(crate) fn constant_definitions(&self) -> Vec<(Expr, Expr)> {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .constants
+            .iter()
+            .filter_map(|(&id, c)| {
+                let definition = c.definition.clone()?;
+                Some((Symbol::from_id(id).to_expr(), definition))
+            })
+            .collect()
+    }
+
+    fn register_constant(&self, name: &str, value: f64, definition: Option<Expr>) {
+        let id = self.symb(name).id();
+        self.inner
+            .write()
+            .expect("Context lock poisoned")
+            .constants
+            .insert(id, ContextConstant { value, definition });
+    }
+
+    // =========================================================================
+    // Data series
+    // =========================================================================
+
+    /// Declare `name` as a data series and return its symbol.
+    ///
+    /// Inside [`sum_data`](crate::Expr::sum_data) a data series stands for its value at
+    /// the current point; the values are passed at evaluation time to
+    /// [`CompiledEvaluator::evaluate_with_data`](crate::CompiledEvaluator::evaluate_with_data).
+    /// Data series cannot be differentiated with respect to.
+    ///
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, Context, parse};
+    /// use std::collections::HashSet;
+    ///
+    /// let ctx = Context::new();
+    /// ctx.declare_data("xs");
+    /// let expr = parse("sum_data((xs - m)^2)", &HashSet::new(), &HashSet::new(), Some(&ctx)).unwrap();
+    /// let eval = CompiledEvaluator::compile(&expr, &[ctx.symb("m")], Some(&ctx)).unwrap();
+    /// let xs = [1.0, 2.0, 3.0];
+    /// assert_eq!(eval.evaluate_with_data(&[2.0], &[("xs", &xs)]).unwrap(), 2.0);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[allow(
+        clippy::must_use_candidate,
+        reason = "Declaring is the point; the symbol is a convenience"
+    )]
+    pub fn declare_data(&self, name: &str) -> Symbol {
+        let symbol = self.symb(name);
+        let mut inner = self.inner.write().expect("Context lock poisoned");
+        if !inner.data.contains(&symbol.id()) {
+            inner.data.push(symbol.id());
+        }
+        symbol
+    }
+
+    /// Check if `name` is a data series of this context.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn is_data(&self, name: &str) -> bool {
+        self.get_symbol(name)
+            .is_some_and(|s| self.is_data_id(s.id()))
+    }
+
+    /// Check if the symbol with ID `id` is a data series of this context.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn is_data_id(&self, id: u64) -> bool {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .data
+            .contains(&id)
+    }
+
+    /// Symbols of the data series of this context, in declaration order.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn data_series(&self) -> Vec<Symbol> {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .data
+            .iter()
+            .map(|&id| Symbol::from_id(id))
+            .collect()
+    }
+
     // =========================================================================
     // User function registration
     // =========================================================================
@@ -321,15 +637,17 @@ impl Context {
     /// Panics if the internal lock is poisoned.
     #[must_use]
     pub fn with_function(self, name: &str, func: UserFunction) -> Self {
-        let id = symb_interned(name).id();
-        {
-            let mut inner = self.inner.write().expect("Context lock poisoned");
-            inner.user_functions.insert(id, func);
-            inner.fn_name_to_id.insert(name.to_owned(), id);
-        }
+        self.insert_function(name, func);
         self
     }
 
+    fn insert_function(&self, name: &str, func: UserFunction) {
+        let id = symb_interned(name).id();
+        let mut inner = self.inner.write().expect("Context lock poisoned");
+        inner.user_functions.insert(id, func);
+        inner.fn_name_to_id.insert(name.to_owned(), id);
+    }
+
     /// Register a function name only (for parsing, no eval/partial).
     ///
     /// # Panics
@@ -363,6 +681,149 @@ impl Context {
         self
     }
 
+    /// Register the functions defined in a library spec (builder pattern).
+    ///
+    /// The spec is a TOML subset with one `[[function]]` table per function:
+    ///
+    /// ```toml
+    /// [[function]]
+    /// name = "psat"
+    /// params = ["T"]
+    /// body = "exp(A - B/(T + C))"
+    ///
+    /// [function.partials]   # optional, one formula per parameter
+    /// T = "B*psat(T)/(T + C)^2"
+    /// ```
+    ///
+    /// Formulas see the parameters, this context's symbols and functions, and every
+    /// function of the library, in any order. Each function takes exactly its
+    /// parameters, has the body for expansion at compile time, and has a partial for
+    /// every parameter: the given formula, or else the derivative of the body. Functions
+    /// are registered callees first, so derived partials differentiate through them.
+    ///
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, Context, Diff, parse, symb};
+    /// use std::collections::HashSet;
+    ///
+    /// let ctx = Context::new()
+    ///     .load_function_library(
+    ///         r#"
+    ///         [[function]]
+    ///         name = "sq"
+    ///         params = ["u"]
+    ///         body = "u^2"
+    ///         "#,
+    ///     )
+    ///     .unwrap();
+    /// let x = symb("x");
+    /// let expr = parse("sq(x)", &HashSet::new(), &HashSet::new(), Some(&ctx)).unwrap();
+    /// let derivative = Diff::new().context(&ctx).differentiate(&expr, &x).unwrap();
+    /// assert_eq!(derivative.to_string(), "2*x");
+    /// let eval = CompiledEvaluator::compile(&expr, &[&x], Some(&ctx)).unwrap();
+    /// assert_eq!(eval.evaluate(&[3.0]), 9.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError::InvalidSyntax` for a malformed spec,
+    /// [`DiffError::NameCollision`] for a parameter named like a function,
+    /// [`DiffError::CyclicFunctionDefinition`] naming the cycle when functions call each
+    /// other recursively, and parse or differentiation errors of the formulas. Nothing
+    /// is registered on error.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    pub fn load_function_library(self, spec: &str) -> Result<Self, DiffError> {
+        let specs = library::parse_library(spec)?;
+        let names: Vec<&str> = specs.iter().map(|s| s.name.as_str()).collect();
+        let mut functions = self.function_names();
+        functions.extend(names.iter().map(|&n| n.to_owned()));
+
+        let mut parsed = Vec::with_capacity(specs.len());
+        let mut calls = Vec::with_capacity(specs.len());
+        for function in &specs {
+            if let Some(param) = function.params.iter().find(|p| functions.contains(*p)) {
+                return Err(DiffError::NameCollision {
+                    name: param.clone(),
+                });
+            }
+            let scope = self.library_scope(&function.params);
+            let known = scope.symbol_names_set();
+            let parse_formula = |formula: &str| parse(formula, &known, &functions, Some(&scope));
+            let body = parse_formula(&function.body)?;
+            let partials = function
+                .partials
+                .iter()
+                .map(|(param, formula)| Ok((param.as_str(), parse_formula(formula)?)))
+                .collect::<Result<Vec<_>, DiffError>>()?;
+
+            let mut called = HashSet::new();
+            library::called_functions(&body, &mut called);
+            for (_, partial) in &partials {
+                library::called_functions(partial, &mut called);
+            }
+            calls.push(
+                names
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, name)| called.contains(**name))
+                    .map(|(i, _)| i)
+                    .collect(),
+            );
+            let params: Vec<Symbol> = function.params.iter().map(|p| scope.symb(p)).collect();
+            parsed.push((body, partials, params));
+        }
+
+        let order = library::definition_order(&names, &calls)?;
+        // Derived partials differentiate through the functions registered before them,
+        // so build everything in a scratch copy and only touch `self` once all succeeded
+        let staging = Self::new();
+        {
+            let source = self.inner.read().expect("Context lock poisoned");
+            let mut target = staging.inner.write().expect("Context lock poisoned");
+            target.user_functions.clone_from(&source.user_functions);
+            target.fn_name_to_id.clone_from(&source.fn_name_to_id);
+        }
+        let diff = Diff::new().context(&staging);
+        let mut loaded = Vec::with_capacity(order.len());
+        for index in order {
+            let (body, given, params) = &parsed[index];
+            let arity = params.len();
+            let mut func = UserFunction::new(arity..=arity).body(substituting(body, params));
+            for (i, (param, name)) in params.iter().zip(&specs[index].params).enumerate() {
+                let partial = match given.iter().find(|(p, _)| p == name) {
+                    Some((_, formula)) => formula.clone(),
+                    None => diff.differentiate(body, param)?,
+                };
+                func = func.partial(i, substituting(&partial, params))?;
+            }
+            staging.insert_function(names[index], func.clone());
+            loaded.push((names[index], func));
+        }
+
+        for (name, func) in loaded {
+            self.insert_function(name, func);
+        }
+        Ok(self)
+    }
+
+    /// A context with this context's symbols plus `params`, for parsing library formulas
+    ///
+    /// Parameters shadow context symbols of the same name and use the global symbol,
+    /// since differentiation looks variables up by name.
+    fn library_scope(&self, params: &[String]) -> Self {
+        let scope = Self::new();
+        {
+            let mut inner = scope.inner.write().expect("Context lock poisoned");
+            inner
+                .symbols
+                .clone_from(&self.inner.read().expect("Context lock poisoned").symbols);
+            for param in params {
+                inner.symbols.insert(param.clone(), symb_interned(param));
+            }
+        }
+        scope
+    }
+
     /// Get a user function by name.
     ///
     /// # Panics
@@ -499,6 +960,20 @@ impl Context {
             .any(|f| f.body.is_some())
     }
 
+    /// Arity and native implementation of every function that has one, by symbol ID.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    pub(crate) fn numeric_functions(&self) -> FxHashMap<u64, (RangeInclusive<usize>, NumericFn)> {
+        self.inner
+            .read()
+            .expect("Context lock poisoned")
+            .user_functions
+            .iter()
+            .filter_map(|(&id, f)| Some((id, (f.arity.clone(), f.numeric.clone()?))))
+            .collect()
+    }
+
     // =========================================================================
     // Removal / clearing
     // =========================================================================
@@ -508,12 +983,13 @@ impl Context {
     /// # Panics
     /// Panics if the internal lock is poisoned.
     pub fn remove_symbol(&mut self, name: &str) -> bool {
-        self.inner
-            .write()
-            .expect("Context lock poisoned")
-            .symbols
-            .remove(name)
-            .is_some()
+        let mut inner = self.inner.write().expect("Context lock poisoned");
+        let Some(symbol) = inner.symbols.remove(name) else {
+            return false;
+        };
+        inner.constants.remove(&symbol.id());
+        inner.data.retain(|&id| id != symbol.id());
+        true
     }
 
     /// Remove a user function. Returns `true` if it was present.
@@ -533,11 +1009,10 @@ impl Context {
     /// # Panics
     /// Panics if the internal lock is poisoned.
     pub fn clear_symbols(&mut self) {
-        self.inner
-            .write()
-            .expect("Context lock poisoned")
-            .symbols
-            .clear();
+        let mut inner = self.inner.write().expect("Context lock poisoned");
+        inner.symbols.clear();
+        inner.constants.clear();
+        inner.data.clear();
     }
 
     /// Clear all user functions.
@@ -596,3 +1071,17 @@ impl Debug for Context {
             .finish_non_exhaustive()
     }
 }
+
+/// A body or partial that replaces the parameters of a library function by its arguments
+fn substituting(expr: &Expr, params: &[Symbol]) -> impl Fn(&[Arc<Expr>]) -> Expr + use<> {
+    let expr = expr.clone();
+    let params: Vec<Expr> = params.iter().map(Symbol::to_expr).collect();
+    move |args| {
+        let pairs: Vec<(Expr, Expr)> = params
+            .iter()
+            .cloned()
+            .zip(args.iter().map(|arg| (**arg).clone()))
+            .collect();
+        expr.substitute_many(&pairs)
+    }
+}
@@ -0,0 +1,304 @@
+//! Function library specs for [`Context::load_function_library`](super::Context::load_function_library).
+//!
+//! The format is the subset of TOML needed to list functions: one `[[function]]` table
+//! per function with `name`, `params` and `body` keys, and an optional
+//! `[function.partials]` table mapping parameter names to derivative formulas.
+//! Strings are single-line, basic (`"..."`) or literal (`'...'`); arrays hold strings
+//! and stay on one line.
+
+use std::collections::HashSet;
+
+use crate::core::{DiffError, Expr, ExprKind, piecewise_parts};
+
+/// One `[[function]]` entry, with formulas still unparsed
+#[derive(Debug, Default)]
+pub struct FunctionSpec {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: String,
+    /// `(parameter, formula)` pairs in source order
+    pub partials: Vec<(String, String)>,
+}
+
+/// Which table the following `key = value` lines belong to
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Top,
+    Function,
+    Partials,
+}
+
+/// Read every `[[function]]` entry of `spec`
+///
+/// # Errors
+/// Returns `DiffError::InvalidSyntax` naming the line for malformed lines, unknown keys,
+/// duplicate keys or functions, partials of unknown parameters, and entries without a
+/// `name` or `body`.
+pub fn parse_library(spec: &str) -> Result<Vec<FunctionSpec>, DiffError> {
+    let mut functions: Vec<FunctionSpec> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut section = Section::Top;
+    let mut keys: HashSet<String> = HashSet::new();
+
+    for (index, raw) in spec.lines().enumerate() {
+        let line_no = index + 1;
+        let error = |msg: &str| DiffError::invalid_syntax(format!("library line {line_no}: {msg}"));
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match line {
+            "[[function]]" => {
+                if let Some(last) = functions.last() {
+                    check_complete(last)?;
+                }
+                functions.push(FunctionSpec::default());
+                section = Section::Function;
+                keys.clear();
+                continue;
+            }
+            "[function.partials]" => {
+                if section != Section::Function {
+                    return Err(error(
+                        "[function.partials] must follow a [[function]] table",
+                    ));
+                }
+                section = Section::Partials;
+                keys.clear();
+                continue;
+            }
+            _ if line.starts_with('[') => {
+                return Err(error(&format!("unknown table {line}")));
+            }
+            _ => {}
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected `key = value`"))?;
+        let key = unquote_key(key.trim()).ok_or_else(|| error("invalid key"))?;
+        let value = value.trim();
+        if !keys.insert(key.clone()) {
+            return Err(error(&format!("duplicate key '{key}'")));
+        }
+        let Some(function) = functions.last_mut() else {
+            return Err(error("keys must be inside a [[function]] table"));
+        };
+
+        match (section, key.as_str()) {
+            (Section::Function, "name") => {
+                let name = parse_string(value).ok_or_else(|| error("name must be a string"))?;
+                if !seen.insert(name.clone()) {
+                    return Err(error(&format!("function '{name}' is defined twice")));
+                }
+                function.name = name;
+            }
+            (Section::Function, "params") => {
+                function.params = parse_array(value)
+                    .ok_or_else(|| error("params must be an array of strings"))?;
+            }
+            (Section::Function, "body") => {
+                function.body =
+                    parse_string(value).ok_or_else(|| error("body must be a string"))?;
+            }
+            (Section::Partials, _) => {
+                if !function.params.contains(&key) {
+                    return Err(error(&format!("partial of unknown parameter '{key}'")));
+                }
+                let formula =
+                    parse_string(value).ok_or_else(|| error("partials must be strings"))?;
+                function.partials.push((key, formula));
+            }
+            _ => return Err(error(&format!("unknown key '{key}'"))),
+        }
+    }
+
+    if let Some(last) = functions.last() {
+        check_complete(last)?;
+    }
+    Ok(functions)
+}
+
+fn check_complete(function: &FunctionSpec) -> Result<(), DiffError> {
+    if function.name.is_empty() {
+        return Err(DiffError::invalid_syntax("library function without a name"));
+    }
+    if function.body.is_empty() {
+        return Err(DiffError::invalid_syntax(format!(
+            "library function '{}' has no body",
+            function.name
+        )));
+    }
+    Ok(())
+}
+
+/// `line` up to a `#` that is not inside a string
+#[allow(
+    clippy::string_slice,
+    reason = "`i` comes from `char_indices`, so it is a char boundary"
+)]
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            Some(_) | None => {}
+        }
+    }
+    line
+}
+
+fn unquote_key(key: &str) -> Option<String> {
+    if key.starts_with(['"', '\'']) {
+        return parse_string(key);
+    }
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    bare.then(|| key.to_owned())
+}
+
+/// A whole basic or literal string, or `None`
+fn parse_string(value: &str) -> Option<String> {
+    let (parsed, rest) = split_string(value)?;
+    rest.trim().is_empty().then_some(parsed)
+}
+
+/// The leading string of `value` and whatever follows it
+#[allow(
+    clippy::string_slice,
+    reason = "Every index is that of an ASCII quote, so it is a char boundary"
+)]
+fn split_string(value: &str) -> Option<(String, &str)> {
+    let mut chars = value.char_indices();
+    let (_, quote) = chars.next()?;
+    if quote == '\'' {
+        let end = value[1..].find('\'')? + 1;
+        return Some((value[1..end].to_owned(), &value[end + 1..]));
+    }
+    if quote != '"' {
+        return None;
+    }
+    let mut parsed = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((parsed, &value[i + 1..])),
+            '\\' => parsed.push(match chars.next()?.1 {
+                'n' => '\n',
+                't' => '\t',
+                escaped @ ('"' | '\\') => escaped,
+                _ => return None,
+            }),
+            _ => parsed.push(c),
+        }
+    }
+    None
+}
+
+/// A one-line array of strings, allowing a trailing comma
+fn parse_array(value: &str) -> Option<Vec<String>> {
+    let mut rest = value.strip_prefix('[')?.trim_start();
+    let mut items = Vec::new();
+    loop {
+        if let Some(tail) = rest.strip_prefix(']') {
+            return tail.trim().is_empty().then_some(items);
+        }
+        let (item, after) = split_string(rest)?;
+        items.push(item);
+        rest = after.trim_start();
+        if let Some(after_comma) = rest.strip_prefix(',') {
+            rest = after_comma.trim_start();
+        } else if !rest.starts_with(']') {
+            return None;
+        }
+    }
+}
+
+/// Names of the functions called anywhere in `expr`, including polynomial bases
+pub fn called_functions(expr: &Expr, out: &mut HashSet<String>) {
+    let mut stack = vec![expr];
+    while let Some(node) = stack.pop() {
+        match &node.kind {
+            ExprKind::Number(_) | ExprKind::Symbol(_) => {}
+            ExprKind::FunctionCall { name, args } => {
+                if let Some(name) = name.name() {
+                    out.insert(name.to_owned());
+                }
+                stack.extend(args.iter().map(AsRef::as_ref));
+            }
+            ExprKind::Sum(args) | ExprKind::Product(args) => {
+                stack.extend(args.iter().map(AsRef::as_ref));
+            }
+            ExprKind::Div(l, r) | ExprKind::Pow(l, r) => {
+                stack.push(l);
+                stack.push(r);
+            }
+            ExprKind::Derivative { inner, .. } => stack.push(inner),
+            ExprKind::Poly(poly) => stack.push(poly.base()),
+            ExprKind::Piecewise { branches, default } => {
+                stack.extend(piecewise_parts(branches, default).map(AsRef::as_ref));
+            }
+        }
+    }
+}
+
+/// Indices of `calls` ordered so that every function comes after the ones it calls
+///
+/// `calls[i]` lists the indices function `i` calls.
+///
+/// # Errors
+/// Returns [`DiffError::CyclicFunctionDefinition`] with the first cycle found.
+pub fn definition_order(names: &[&str], calls: &[Vec<usize>]) -> Result<Vec<usize>, DiffError> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        Open,
+        Done,
+    }
+
+    fn visit(
+        node: usize,
+        calls: &[Vec<usize>],
+        marks: &mut [Mark],
+        path: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), Vec<usize>> {
+        match marks[node] {
+            Mark::Done => return Ok(()),
+            Mark::Open => {
+                let start = path.iter().position(|&n| n == node).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(node);
+                return Err(cycle);
+            }
+            Mark::New => {}
+        }
+        marks[node] = Mark::Open;
+        path.push(node);
+        for &callee in &calls[node] {
+            visit(callee, calls, marks, path, order)?;
+        }
+        path.pop();
+        marks[node] = Mark::Done;
+        order.push(node);
+        Ok(())
+    }
+
+    let mut marks = vec![Mark::New; calls.len()];
+    let mut order = Vec::with_capacity(calls.len());
+    for node in 0..calls.len() {
+        visit(node, calls, &mut marks, &mut Vec::new(), &mut order).map_err(|cycle| {
+            DiffError::CyclicFunctionDefinition {
+                cycle: cycle.into_iter().map(|i| names[i].to_owned()).collect(),
+            }
+        })?;
+    }
+    Ok(order)
+}
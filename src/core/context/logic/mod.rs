@@ -1,6 +1,7 @@
 //! Internal implementation details for the context module.
 
 pub mod context;
+mod library;
 
 // Staircase re-exports — Public API items (exported by lib.rs)
 pub use context::{Context, UserFunction};
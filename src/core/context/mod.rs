@@ -1,6 +1,6 @@
 //! Unified context module — symbol registry + user-defined functions.
 //!
-//! - [`api_user`] — public types: `Context`, `UserFunction`, `BodyFn`, `PartialFn`
+//! - [`api_user`] — public types: `Context`, `UserFunction`, `BodyFn`, `PartialFn`, `NumericFn`
 //! - [`api_crate`] — crate-internal type aliases: `BodyFn`, `PartialFn`
 //! - `logic/` — Implementation details
 
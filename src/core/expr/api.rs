@@ -13,10 +13,22 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use rustc_hash::FxHasher;
 
 pub use super::logic::ArcExprExt;
+pub use super::logic::LatexConfig;
+pub use super::logic::LimitPoint;
+pub use super::logic::MathmlConfig;
 pub use super::logic::Polynomial;
+pub use super::logic::{CompareOp, Condition};
+pub use super::logic::{Constraint, Domain, Enclosure, EnclosureMap, Interval};
+pub use super::logic::{FormatOptions, FunctionCallStyle, MulStyle, PowerOperator};
+pub use super::logic::{
+    PolyConversion, poly_conversion, set_poly_conversion, with_poly_conversion,
+};
+pub use super::logic::{RenameReport, rename_symbols, rename_symbols_dry_run};
 pub use super::logic::{compute_expr_hash, compute_term_hash};
+pub use super::logic::{map_piecewise, piecewise_parts};
 pub use crate::EPSILON;
 use crate::core::InternedSymbol;
+use crate::core::traits::unsigned_zero;
 
 // ============================================================================
 // Type aliases
@@ -54,6 +66,8 @@ pub struct Expr {
     pub(crate) hash: u64,
     /// Coefficient-insensitive term hash for like-term grouping.
     pub(crate) term_hash: u64,
+    /// Report label set by [`Expr::labeled`]; ignored by equality and hashing.
+    pub(crate) label: Option<Arc<str>>,
     pub(crate) kind: ExprKind,
 }
 
@@ -70,7 +84,11 @@ impl PartialEq for Expr {
         if self.hash != other.hash {
             return false;
         }
-        self.kind == other.kind
+        match (&self.kind, &other.kind) {
+            // NaN literals compare equal to each other so `Eq` stays reflexive
+            (ExprKind::Number(a), ExprKind::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+            _ => self.kind == other.kind,
+        }
     }
 }
 
@@ -124,6 +142,13 @@ pub enum ExprKind {
     },
     /// Sparse polynomial (efficient for differentiation)
     Poly(Polynomial),
+    /// Piecewise definition: the first branch whose condition holds, else `default`
+    Piecewise {
+        /// Guarded branches, tried in order.
+        branches: Vec<(Condition, Arc<Expr>)>,
+        /// Value when no condition holds.
+        default: Arc<Expr>,
+    },
 }
 
 // ============================================================================
@@ -151,6 +176,14 @@ impl Drop for Expr {
                     queue.push(replace(inner, DUMMY_ARC.clone()));
                 }
                 ExprKind::Poly(poly) => queue.push(poly.take_base()),
+                ExprKind::Piecewise { branches, default } => {
+                    for (condition, value) in take(branches) {
+                        queue.push(condition.lhs);
+                        queue.push(condition.rhs);
+                        queue.push(value);
+                    }
+                    queue.push(replace(default, DUMMY_ARC.clone()));
+                }
                 ExprKind::Number(_) | ExprKind::Symbol(_) => {}
             }
         }
@@ -174,7 +207,11 @@ impl Hash for ExprKind {
         discriminant(self).hash(state);
         match self {
             Self::Number(n) => {
-                let normalized = if *n == 0.0 { 0.0 } else { *n };
+                let normalized = if n.is_nan() {
+                    f64::NAN
+                } else {
+                    unsigned_zero(*n)
+                };
                 normalized.to_bits().hash(state);
             }
             Self::Symbol(s) => s.hash(state),
@@ -216,6 +253,15 @@ impl Hash for ExprKind {
                 }
                 terms_hash.hash(state);
             }
+            Self::Piecewise { branches, default } => {
+                for (condition, value) in branches {
+                    condition.op.hash(state);
+                    condition.lhs.hash(state);
+                    condition.rhs.hash(state);
+                    value.hash(state);
+                }
+                default.hash(state);
+            }
         }
     }
 }
@@ -230,6 +276,7 @@ pub(super) static EXPR_ONE: LazyLock<Expr> = LazyLock::new(|| {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     }
 });
@@ -240,6 +287,7 @@ pub(super) static CACHED_ZERO: LazyLock<Expr> = LazyLock::new(|| {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     }
 });
@@ -250,6 +298,7 @@ pub(super) static CACHED_NEG_ONE: LazyLock<Expr> = LazyLock::new(|| {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     }
 });
@@ -260,6 +309,7 @@ pub(super) static CACHED_TWO: LazyLock<Expr> = LazyLock::new(|| {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     }
 });
@@ -271,6 +321,7 @@ pub(super) static DUMMY_ARC: LazyLock<Arc<Expr>> = LazyLock::new(|| {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     })
 });
@@ -281,6 +332,7 @@ fn make_arc_number(n: f64) -> Arc<Expr> {
         id: 0,
         hash: compute_expr_hash(&kind),
         term_hash: compute_term_hash(&kind),
+        label: None,
         kind,
     })
 }
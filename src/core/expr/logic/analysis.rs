@@ -6,15 +6,22 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use rustc_hash::FxHashMap;
+
 use crate::core::DiffError;
 use crate::core::ExprView;
+use crate::core::InternedSymbol;
+use crate::core::Symbol;
+use crate::core::known_symbols::is_known_constant_by_id;
 use crate::core::symb;
-use crate::core::{symb_get, symb_interned};
+use crate::core::symb_get;
 use crate::diff::Diff;
 use crate::evaluator::{CompiledEvaluator, ToParamName};
+use crate::integrate::Integrate;
 use crate::simplification::Simplify;
 
-use super::{Expr, ExprKind};
+use super::piecewise::{map_piecewise, piecewise_parts};
+use super::{Expr, ExprKind, Polynomial};
 
 impl Expr {
     // -------------------------------------------------------------------------
@@ -83,6 +90,7 @@ impl Expr {
                     poly.to_expr_terms().into_iter().map(Arc::new).collect();
                 ExprView::Sum(Cow::Owned(terms))
             }
+            ExprKind::Piecewise { branches, default } => ExprView::Piecewise { branches, default },
         }
     }
 
@@ -92,7 +100,7 @@ impl Expr {
 
     /// Push all children of a node onto the stack (including Poly base).
     /// Used by iterative analysis traversals.
-    fn push_children<'expr>(node: &'expr Self, stack: &mut Vec<&'expr Self>) {
+    pub(super) fn push_children<'expr>(node: &'expr Self, stack: &mut Vec<&'expr Self>) {
         match &node.kind {
             ExprKind::Number(_) | ExprKind::Symbol(_) => {}
             ExprKind::FunctionCall { args, .. } | ExprKind::Sum(args) | ExprKind::Product(args) => {
@@ -108,6 +116,9 @@ impl Expr {
             ExprKind::Poly(poly) => {
                 stack.push(poly.base());
             }
+            ExprKind::Piecewise { branches, default } => {
+                stack.extend(piecewise_parts(branches, default).rev().map(AsRef::as_ref));
+            }
         }
     }
 
@@ -136,6 +147,9 @@ impl Expr {
                 ExprKind::Poly(poly) => {
                     count += poly.terms().len();
                 }
+                ExprKind::Piecewise { branches, default } => {
+                    stack.extend(piecewise_parts(branches, default).map(AsRef::as_ref));
+                }
             }
         }
         count
@@ -168,6 +182,11 @@ impl Expr {
                 ExprKind::Poly(_) => {
                     result = result.max(depth + 1);
                 }
+                ExprKind::Piecewise { branches, default } => {
+                    stack.extend(
+                        piecewise_parts(branches, default).map(|part| (&**part, depth + 1)),
+                    );
+                }
             }
         }
         result
@@ -189,6 +208,24 @@ impl Expr {
         false
     }
 
+    /// First symbol or derivative variable, in pre-order, for which `pred` holds
+    pub(crate) fn find_symbol(
+        &self,
+        mut pred: impl FnMut(&InternedSymbol) -> bool,
+    ) -> Option<InternedSymbol> {
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            match &node.kind {
+                ExprKind::Symbol(s) | ExprKind::Derivative { var: s, .. } if pred(s) => {
+                    return Some(s.clone());
+                }
+                _ => {}
+            }
+            Self::push_children(node, &mut stack);
+        }
+        None
+    }
+
     /// Check if the expression contains a specific variable (by name)
     /// Uses ID comparison when possible, falls back to string matching
     #[inline]
@@ -217,6 +254,95 @@ impl Expr {
         false
     }
 
+    /// Degree of `self` as a polynomial in `var`, or `None` if it is not one
+    ///
+    /// Sums, products, division by expressions free of `var`, and powers with a
+    /// non-negative integer exponent are polynomial; any other dependence on `var`,
+    /// such as `sin(var)` or `var^0.5`, is not. Other symbols count as constants.
+    /// The degree is read off the expression as written, without simplifying, so
+    /// terms that would cancel still count.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, y) = (symb("degree_in_doc_x"), symb("degree_in_doc_y"));
+    /// assert_eq!((x.pow(2.0) * y.pow(3.0)).degree_in(&x), Some(2));
+    /// assert_eq!(x.sin().degree_in(&x), None);
+    /// assert_eq!(x.sin().degree_in(&y), Some(0));
+    /// ```
+    #[must_use]
+    pub fn degree_in(&self, var: &Symbol) -> Option<u32> {
+        super::poly_coefficients::degree_in(self, var.id())
+    }
+
+    /// Whether `self` is a polynomial in `var`; see [`degree_in`](Self::degree_in)
+    #[must_use]
+    pub fn is_polynomial_in(&self, var: &Symbol) -> bool {
+        self.degree_in(var).is_some()
+    }
+
+    /// Check if the expression calls the function with the given symbol ID
+    #[must_use]
+    pub(crate) fn contains_function_id(&self, func_id: u64) -> bool {
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            if matches!(&node.kind, ExprKind::FunctionCall { name, .. } if name.id() == func_id) {
+                return true;
+            }
+            Self::push_children(node, &mut stack);
+        }
+        false
+    }
+
+    /// The outermost calls of the function with the given symbol ID, in pre-order
+    #[must_use]
+    pub(crate) fn calls_of(&self, func_id: u64) -> Vec<&Self> {
+        let mut calls = Vec::new();
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            if matches!(&node.kind, ExprKind::FunctionCall { name, .. } if name.id() == func_id) {
+                calls.push(node);
+            } else {
+                Self::push_children_rev(node, &mut stack);
+            }
+        }
+        calls
+    }
+
+    /// Check if the expression calls the function with the given symbol ID on an
+    /// argument that depends on `var`
+    #[must_use]
+    pub(crate) fn calls_function_of(&self, func_id: u64, var: &str) -> bool {
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            if let ExprKind::FunctionCall { name, args } = &node.kind
+                && name.id() == func_id
+                && args.iter().any(|arg| arg.contains_var(var))
+            {
+                return true;
+            }
+            Self::push_children(node, &mut stack);
+        }
+        false
+    }
+
+    /// Check if the expression contains a NaN literal, including polynomial coefficients
+    #[must_use]
+    pub fn contains_nan(&self) -> bool {
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            match &node.kind {
+                ExprKind::Number(n) if n.is_nan() => return true,
+                ExprKind::Poly(poly) if poly.terms().iter().any(|&(_, c)| c.is_nan()) => {
+                    return true;
+                }
+                _ => {}
+            }
+            Self::push_children(node, &mut stack);
+        }
+        false
+    }
+
     /// Check if the expression contains any free variables
     #[must_use]
     pub fn has_free_variables(&self, excluded: &HashSet<String>) -> bool {
@@ -287,11 +413,144 @@ impl Expr {
                 ExprKind::Poly(poly) => {
                     stack.push(poly.base());
                 }
+                ExprKind::Piecewise { branches, default } => {
+                    stack.extend(piecewise_parts(branches, default).rev().map(AsRef::as_ref));
+                }
             }
         }
         vars
     }
 
+    /// Check whether `self` and `other` are the same expression up to renaming variables
+    ///
+    /// Looks for a one-to-one renaming of the free variables of `self` (symbols and
+    /// derivative variables, without built-in constants such as `pi`) onto those of
+    /// `other` under which the trees match. The renaming is built while matching, so
+    /// terms of sums and products may match in any order, even when the renaming changes
+    /// their canonical order. Function names are not renamed, and expressions with
+    /// different numbers of free variables are never equivalent.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (a, z) = (symb("alpha_doc_a"), symb("alpha_doc_z"));
+    /// assert!((a.sin() + a.cos()).alpha_equivalent(&(z.sin() + z.cos())));
+    /// assert!(!(a.sin() + a.cos()).alpha_equivalent(&(a.sin() + z.cos())));
+    /// ```
+    #[must_use]
+    pub fn alpha_equivalent(&self, other: &Self) -> bool {
+        if self.free_symbol_ids().len() != other.free_symbol_ids().len() {
+            return false;
+        }
+        self.matches_renamed(other, &mut Renaming::default())
+    }
+
+    /// IDs of the free variables, in order of first occurrence (pre-order)
+    fn free_symbol_ids(&self) -> Vec<u64> {
+        let mut ids = Vec::new();
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            if let ExprKind::Symbol(s) | ExprKind::Derivative { var: s, .. } = &node.kind
+                && !is_known_constant_by_id(s.id())
+                && !ids.contains(&s.id())
+            {
+                ids.push(s.id());
+            }
+            Self::push_children(node, &mut stack);
+        }
+        ids
+    }
+
+    /// Whether `self` matches `other` under `renaming`, extending it with the pairs the
+    /// match needs
+    ///
+    /// On failure `renaming` may hold pairs from the partial match; callers that try
+    /// alternatives roll it back with [`Renaming::undo`].
+    fn matches_renamed(&self, other: &Self, renaming: &mut Renaming) -> bool {
+        match (&self.kind, &other.kind) {
+            (ExprKind::Number(_), ExprKind::Number(_)) => self == other,
+            (ExprKind::Symbol(a), ExprKind::Symbol(b)) => renaming.pair(a.id(), b.id()),
+            (
+                ExprKind::FunctionCall { name: f, args: a },
+                ExprKind::FunctionCall { name: g, args: b },
+            ) => {
+                f.id() == g.id()
+                    && a.len() == b.len()
+                    && a.iter().zip(b).all(|(a, b)| a.matches_renamed(b, renaming))
+            }
+            (ExprKind::Sum(a), ExprKind::Sum(b)) | (ExprKind::Product(a), ExprKind::Product(b)) => {
+                let mut unmatched: Vec<&Arc<Self>> = b.iter().collect();
+                a.len() == b.len() && Self::match_unordered(a, &mut unmatched, renaming)
+            }
+            (ExprKind::Div(a1, a2), ExprKind::Div(b1, b2))
+            | (ExprKind::Pow(a1, a2), ExprKind::Pow(b1, b2)) => {
+                a1.matches_renamed(b1, renaming) && a2.matches_renamed(b2, renaming)
+            }
+            (
+                ExprKind::Derivative {
+                    inner: inner_a,
+                    var: var_a,
+                    order: order_a,
+                },
+                ExprKind::Derivative {
+                    inner: inner_b,
+                    var: var_b,
+                    order: order_b,
+                },
+            ) => {
+                order_a == order_b
+                    && renaming.pair(var_a.id(), var_b.id())
+                    && inner_a.matches_renamed(inner_b, renaming)
+            }
+            (ExprKind::Poly(p), ExprKind::Poly(q)) => {
+                p.terms() == q.terms() && p.base().matches_renamed(q.base(), renaming)
+            }
+            (
+                ExprKind::Piecewise {
+                    branches: a,
+                    default: a_default,
+                },
+                ExprKind::Piecewise {
+                    branches: b,
+                    default: b_default,
+                },
+            ) => {
+                a.len() == b.len()
+                    && a.iter().zip(b).all(|(a, b)| a.0.op == b.0.op)
+                    && piecewise_parts(a, a_default)
+                        .zip(piecewise_parts(b, b_default))
+                        .all(|(a, b)| a.matches_renamed(b, renaming))
+            }
+            _ => false,
+        }
+    }
+
+    /// Match every term of `terms` to a distinct member of `unmatched`, backtracking over
+    /// the choices: a pairing that fits one term can rule out the only fit for a later one
+    fn match_unordered(
+        terms: &[Arc<Self>],
+        unmatched: &mut Vec<&Arc<Self>>,
+        renaming: &mut Renaming,
+    ) -> bool {
+        let Some((term, rest)) = terms.split_first() else {
+            return true;
+        };
+        for index in 0..unmatched.len() {
+            let mark = renaming.mark();
+            if term.matches_renamed(unmatched[index], renaming) {
+                let candidate = unmatched.swap_remove(index);
+                if Self::match_unordered(rest, unmatched, renaming) {
+                    return true;
+                }
+                unmatched.push(candidate);
+                let last = unmatched.len() - 1;
+                unmatched.swap(index, last);
+            }
+            renaming.undo(mark);
+        }
+        false
+    }
+
     /// Collect all variable names used in this expression
     fn collect_variables(&self, vars: &mut HashSet<String>) {
         let mut stack: Vec<&Self> = vec![self];
@@ -347,6 +606,9 @@ impl Expr {
                 // This is safe as Polynomial is designed to be immutable.
                 Self::new(ExprKind::Poly(poly.clone()))
             }
+            ExprKind::Piecewise { branches, default } => {
+                map_piecewise(branches, default, |part| Arc::new(part.deep_clone()))
+            }
         }
     }
 
@@ -370,6 +632,22 @@ impl Expr {
         Simplify::new().simplify(self)
     }
 
+    /// Integrate with respect to a variable, without an integration constant
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let x = symb("x");
+    /// let primitive = x.sin().integrate(&x).unwrap();
+    /// assert_eq!(primitive.to_string(), "-cos(x)");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` if integration fails, as in [`Integrate::integrate`].
+    pub fn integrate(&self, var: &Symbol) -> Result<Self, DiffError> {
+        Integrate::new().integrate(self, var)
+    }
+
     /// Compile this expression for fast numerical evaluation
     ///
     /// Creates a compiled evaluator that can be reused for many evaluations.
@@ -452,6 +730,9 @@ impl Expr {
             ExprKind::Derivative { inner, .. } => {
                 stack.push(inner);
             }
+            ExprKind::Piecewise { branches, default } => {
+                stack.extend(piecewise_parts(branches, default).rev().map(AsRef::as_ref));
+            }
         }
     }
 
@@ -488,21 +769,277 @@ impl Expr {
                 // Poly is opaque for mapping - just clone
                 Self::new(ExprKind::Poly(poly.clone()))
             }
+            ExprKind::Piecewise { branches, default } => {
+                map_piecewise(branches, default, |part| Arc::new(part.map(f)))
+            }
         };
         f(&transformed)
     }
 
-    /// Substitute a variable with another expression
+    /// Substitute every occurrence of the variable `var` with `replacement`
+    ///
+    /// Symbols are matched by name, so symbols created inside a [`Context`] are replaced as
+    /// well. The replacement is inserted once per occurrence and not walked again, so it may
+    /// contain `var` itself (`x -> x + 1`). Subtrees without `var` are shared with `self`,
+    /// polynomials over `var` are rebuilt from their terms, and the variable of an
+    /// unevaluated derivative is renamed when `replacement` is a symbol.
+    ///
+    /// The result is assembled with the usual constructors but not simplified.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, a, t, b) = (symb("x"), symb("a"), symb("t"), symb("b"));
+    /// let expr = x.pow(2.0) + x.sin();
+    /// let result = expr.substitute("x", &(a * t + b));
+    /// assert!(!result.contains_var("x"));
+    /// assert!(result.contains_var("t"));
+    /// ```
+    ///
+    /// [`Context`]: crate::Context
     #[must_use]
     pub fn substitute(&self, var: &str, replacement: &Self) -> Self {
-        let var_id = symb_interned(var).id();
-        self.map(|node| {
-            if let ExprKind::Symbol(s) = &node.kind
-                && s.id() == var_id
-            {
-                return replacement.clone();
+        let lookup = |node: &Self| match &node.kind {
+            ExprKind::Symbol(s) if s.name() == Some(var) => Some(replacement),
+            _ => None,
+        };
+        self.substitute_changed(&lookup, false)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Replace every subexpression structurally equal to `target` with `replacement`
+    ///
+    /// Matching is structural (`Expr` equality), so `target` should be in the same
+    /// canonical form as it appears in `self`: build it with the same constructors or
+    /// compare against a simplified tree. Polynomial nodes are expanded to their general
+    /// form when `target` is not a plain symbol, so a power such as `x^2` is found inside
+    /// `3*x^2 + 1`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, u) = (symb("x"), symb("u"));
+    /// let expr = x.sin().pow(2.0) + x.sin();
+    /// let result = expr.substitute_expr(&x.sin(), &u.to_expr());
+    /// assert!(!result.contains_var("x"));
+    /// ```
+    #[must_use]
+    pub fn substitute_expr(&self, target: &Self, replacement: &Self) -> Self {
+        self.substitute_many(&[(target.clone(), replacement.clone())])
+    }
+
+    /// Replace every occurrence of the symbol `sym` with `replacement`
+    ///
+    /// Unlike [`substitute`](Self::substitute), the symbol is matched by identity rather
+    /// than by name, so a same-named symbol from another [`Context`] is left alone.
+    ///
+    /// [`Context`]: crate::Context
+    #[must_use]
+    pub fn substitute_symbol(&self, sym: &Symbol, replacement: &Self) -> Self {
+        self.substitute_expr(&sym.to_expr(), replacement)
+    }
+
+    /// Replace symbols by name, all pairs at once, sharing the untouched subtrees
+    pub(super) fn substitute_names(&self, pairs: &[(&str, Self)]) -> Self {
+        let lookup = |node: &Self| match &node.kind {
+            ExprKind::Symbol(s) => s.name().and_then(|name| {
+                pairs
+                    .iter()
+                    .find(|(from, _)| *from == name)
+                    .map(|(_, replacement)| replacement)
+            }),
+            _ => None,
+        };
+        self.substitute_changed(&lookup, false)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Apply several replacements simultaneously
+    ///
+    /// Each node is compared against every target before its children are visited, and
+    /// inserted replacements are not walked again, so `[(x, y), (y, x)]` swaps the two
+    /// symbols instead of mapping both to `x`. When two targets match the same node, the
+    /// first pair wins.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, y) = (symb("x"), symb("y"));
+    /// let expr = x.pow(2.0) / y;
+    /// let swapped = expr.substitute_many(&[(x.into(), y.into()), (y.into(), x.into())]);
+    /// assert_eq!(swapped, y.pow(2.0) / x);
+    /// ```
+    #[must_use]
+    pub fn substitute_many(&self, pairs: &[(Self, Self)]) -> Self {
+        let lookup = |node: &Self| {
+            pairs
+                .iter()
+                .find(|(target, _)| target == node)
+                .map(|(_, replacement)| replacement)
+        };
+        let expand_poly = pairs
+            .iter()
+            .any(|(target, _)| !matches!(target.kind, ExprKind::Symbol(_)));
+        self.substitute_changed(&lookup, expand_poly)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// The substituted expression, or `None` when `lookup` matches nothing in `self`
+    ///
+    /// `lookup` returns the replacement for a node, if any. With `expand_poly`,
+    /// polynomials whose base does not match are also searched in their general form.
+    fn substitute_changed<'rep, F>(&self, lookup: &F, expand_poly: bool) -> Option<Self>
+    where
+        F: Fn(&Self) -> Option<&'rep Self>,
+    {
+        if let Some(replacement) = lookup(self) {
+            return Some(replacement.clone());
+        }
+
+        // Replace children, keeping the untouched ones; `None` when no child changed
+        let children = |items: &[Arc<Self>]| -> Option<Vec<Arc<Self>>> {
+            let replaced: Vec<Option<Self>> = items
+                .iter()
+                .map(|item| item.substitute_changed(lookup, expand_poly))
+                .collect();
+            replaced.iter().any(Option::is_some).then(|| {
+                replaced
+                    .into_iter()
+                    .zip(items)
+                    .map(|(new, old)| new.map_or_else(|| Arc::clone(old), Arc::new))
+                    .collect()
+            })
+        };
+        let pair = |a: &Arc<Self>, b: &Arc<Self>| -> Option<(Arc<Self>, Arc<Self>)> {
+            match (
+                a.substitute_changed(lookup, expand_poly),
+                b.substitute_changed(lookup, expand_poly),
+            ) {
+                (None, None) => None,
+                (new_a, new_b) => Some((
+                    new_a.map_or_else(|| Arc::clone(a), Arc::new),
+                    new_b.map_or_else(|| Arc::clone(b), Arc::new),
+                )),
+            }
+        };
+
+        match &self.kind {
+            ExprKind::Number(_) | ExprKind::Symbol(_) => None,
+            ExprKind::Sum(terms) => children(terms).map(Self::sum_from_arcs),
+            ExprKind::Product(factors) => children(factors).map(Self::product_from_arcs),
+            ExprKind::FunctionCall { name, args } => {
+                children(args).map(|args| Self::func_multi_from_arcs_symbol(name.clone(), args))
+            }
+            ExprKind::Div(num, den) => pair(num, den).map(|(n, d)| Self::div_from_arcs(n, d)),
+            ExprKind::Pow(base, exp) => pair(base, exp).map(|(b, e)| Self::pow_from_arcs(b, e)),
+            ExprKind::Derivative {
+                inner,
+                var: wrt,
+                order,
+            } => {
+                let renamed =
+                    lookup(&Self::from_interned(wrt.clone())).and_then(|new| match &new.kind {
+                        ExprKind::Symbol(new) => Some(new.clone()),
+                        _ => None,
+                    });
+                let new_inner = inner.substitute_changed(lookup, expand_poly);
+                if renamed.is_none() && new_inner.is_none() {
+                    return None;
+                }
+                Some(Self::derivative_interned(
+                    new_inner.unwrap_or_else(|| inner.as_ref().clone()),
+                    renamed.unwrap_or_else(|| wrt.clone()),
+                    *order,
+                ))
             }
-            node.clone()
-        })
+            ExprKind::Poly(poly) => {
+                if let Some(base) = poly.base().substitute_changed(lookup, expand_poly) {
+                    return Some(Self::sum(Self::poly_terms(poly, &Arc::new(base))));
+                }
+                if !expand_poly {
+                    return None;
+                }
+                // Search the general form term by term: summing it back up would
+                // rebuild the same polynomial
+                let terms = Self::poly_terms(poly, poly.base());
+                let terms: Vec<Arc<Self>> = terms.into_iter().map(Arc::new).collect();
+                children(&terms).map(Self::sum_from_arcs)
+            }
+            ExprKind::Piecewise { branches, default } => {
+                let changed: Vec<Option<Self>> = piecewise_parts(branches, default)
+                    .map(|part| part.substitute_changed(lookup, expand_poly))
+                    .collect();
+                if changed.iter().all(Option::is_none) {
+                    return None;
+                }
+                let mut changed = changed.into_iter();
+                Some(map_piecewise(branches, default, |old| {
+                    changed
+                        .next()
+                        .flatten()
+                        .map_or_else(|| Arc::clone(old), Arc::new)
+                }))
+            }
+        }
+    }
+
+    /// The terms `coeff * base^pow` of `poly` over a (possibly new) `base`
+    fn poly_terms(poly: &Polynomial, base: &Arc<Self>) -> Vec<Self> {
+        poly.terms()
+            .iter()
+            .map(|&(pow, coeff)| {
+                let power = match pow {
+                    0 => Self::number(1.0),
+                    1 => Self::unwrap_arc(Arc::clone(base)),
+                    _ => Self::pow_from_arcs(
+                        Arc::clone(base),
+                        Arc::new(Self::number(f64::from(pow))),
+                    ),
+                };
+                Self::mul_expr(Self::number(coeff), power)
+            })
+            .collect()
+    }
+}
+
+/// One-to-one pairing of symbol IDs built up during [`Expr::alpha_equivalent`], with a
+/// trail of the pairs added so a failed branch can be rolled back
+#[derive(Default)]
+struct Renaming {
+    forward: FxHashMap<u64, u64>,
+    reverse: FxHashMap<u64, u64>,
+    trail: Vec<u64>,
+}
+
+impl Renaming {
+    /// Pair `from` with `to`, or check an existing pairing; constants pair only with
+    /// themselves
+    fn pair(&mut self, from: u64, to: u64) -> bool {
+        if is_known_constant_by_id(from) || is_known_constant_by_id(to) {
+            return from == to;
+        }
+        match (self.forward.get(&from), self.reverse.get(&to)) {
+            (Some(&mapped), _) => mapped == to,
+            (None, Some(_)) => false,
+            (None, None) => {
+                self.forward.insert(from, to);
+                self.reverse.insert(to, from);
+                self.trail.push(from);
+                true
+            }
+        }
+    }
+
+    const fn mark(&self) -> usize {
+        self.trail.len()
+    }
+
+    /// Drop the pairs added since `mark`
+    fn undo(&mut self, mark: usize) {
+        for from in self.trail.drain(mark..) {
+            if let Some(to) = self.forward.remove(&from) {
+                self.reverse.remove(&to);
+            }
+        }
     }
 }
@@ -7,20 +7,26 @@ use super::{
     CACHED_NEG_ONE, CACHED_TWO, CACHED_ZERO, EXPR_ONE, Expr, ExprKind, Polynomial,
     compute_expr_hash, compute_term_hash, next_id,
 };
-use crate::core::traits::{is_neg_one, is_one, is_zero};
+use crate::core::traits::{is_neg_one, is_one, is_zero, unsigned_zero};
 use crate::core::{InternedSymbol, symb_interned};
 
 impl Expr {
     /// Create a new expression with fresh ID
+    ///
+    /// A `-0.0` number is stored as `0.0`.
     #[inline]
     #[must_use]
-    pub fn new(kind: ExprKind) -> Self {
+    pub fn new(mut kind: ExprKind) -> Self {
+        if let ExprKind::Number(n) = &mut kind {
+            *n = unsigned_zero(*n);
+        }
         let hash = compute_expr_hash(&kind);
         let term_hash = compute_term_hash(&kind);
         Self {
             id: next_id(),
             hash,
             term_hash,
+            label: None,
             kind,
         }
     }
@@ -78,12 +84,26 @@ impl Expr {
         self.as_number().is_some_and(is_neg_one)
     }
 
+    /// Check if this expression is a NaN literal
+    #[inline]
+    pub fn is_nan_num(&self) -> bool {
+        self.as_number().is_some_and(f64::is_nan)
+    }
+
+    /// Check if this expression is a positive or negative infinity literal
+    #[inline]
+    pub fn is_infinite_num(&self) -> bool {
+        self.as_number().is_some_and(f64::is_infinite)
+    }
+
     // -------------------------------------------------------------------------
     // Basic constructors
     // -------------------------------------------------------------------------
 
     /// Create a number expression
     ///
+    /// `-0.0` becomes `0.0`, like every zero the simplifier folds.
+    ///
     /// Optimized: returns clones of cached constants for 0.0 and 1.0
     /// to avoid repeated `hash`/`term_hash` computation in hot paths
     /// (e.g., differentiation returns 0.0 for every constant node).
@@ -113,6 +133,7 @@ impl Expr {
             id: next_id(),
             hash: template.hash,
             term_hash: template.term_hash,
+            label: None,
             kind: template.kind.clone(),
         }
     }
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use super::nary::opposite_infinities;
 use super::{EPSILON, Expr, ExprKind};
 
 impl Expr {
@@ -14,7 +15,9 @@ impl Expr {
         if right.is_zero_num() {
             return left;
         }
-        if let (Some(l), Some(r)) = (left.as_number(), right.as_number()) {
+        if let (Some(l), Some(r)) = (left.as_number(), right.as_number())
+            && !opposite_infinities(l, r)
+        {
             return Self::number(l + r);
         }
         Self::sum(vec![left, right])
@@ -23,7 +26,9 @@ impl Expr {
     /// Create subtraction: a - b → Sum([a, Product([-1, b])])
     #[must_use]
     pub fn sub_expr(left: Self, right: Self) -> Self {
-        if let (Some(l), Some(r)) = (left.as_number(), right.as_number()) {
+        if let (Some(l), Some(r)) = (left.as_number(), right.as_number())
+            && !opposite_infinities(l, -r)
+        {
             return Self::number(l - r);
         }
         if left.is_zero_num() {
@@ -39,6 +44,12 @@ impl Expr {
     /// Create multiplication: a * b → Product([a, b])
     #[must_use]
     pub fn mul_expr(left: Self, right: Self) -> Self {
+        // Non-finite numbers fold exactly, so `0 * inf` and `0 * nan` give NaN
+        if let (Some(l), Some(r)) = (left.as_number(), right.as_number())
+            && !(l.is_finite() && r.is_finite())
+        {
+            return Self::number(l * r);
+        }
         if left.is_zero_num() || right.is_zero_num() {
             return Self::number(0.0);
         }
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use super::{Expr, ExprKind};
+use crate::core::known_symbols::{KS, get_symbol};
 use crate::core::{InternedSymbol, symb_interned};
 
 impl Expr {
@@ -48,6 +49,12 @@ impl Expr {
         Self::func_multi(name, args.into())
     }
 
+    /// Create `sum_data(body)`: the sum of `body` over the points of the data series
+    /// it reads, see [`Context::declare_data`](crate::Context::declare_data)
+    pub fn sum_data(body: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(get_symbol(KS.sum_data), vec![body.into()])
+    }
+
     /// Create a partial derivative expression
     pub fn derivative(inner: Self, var: impl AsRef<str>, order: u32) -> Self {
         Self::new(ExprKind::Derivative {
@@ -8,5 +8,5 @@ mod nary;
 pub(super) use super::EPSILON;
 pub(super) use super::{
     CACHED_NEG_ONE, CACHED_TWO, CACHED_ZERO, EXPR_ONE, Expr, ExprKind, Polynomial,
-    compute_expr_hash, compute_term_hash, expr_cmp, next_id,
+    compute_expr_hash, compute_term_hash, expr_cmp, next_id, poly_conversion,
 };
@@ -3,7 +3,8 @@
 use std::cmp::Ordering;
 use std::sync::Arc;
 
-use super::{EPSILON, Expr, ExprKind, Polynomial, expr_cmp};
+use super::{EPSILON, Expr, ExprKind, Polynomial, expr_cmp, poly_conversion};
+use crate::core::traits::cancels;
 
 impl Expr {
     // -------------------------------------------------------------------------
@@ -11,10 +12,13 @@ impl Expr {
     // -------------------------------------------------------------------------
 
     /// Create a sum expression from terms.
-    /// Flattens nested sums and sorts terms into a canonical order.
+    /// Flattens nested sums and sorts terms into a canonical order. Labeled terms (see
+    /// [`Expr::labeled`]) are kept whole.
     ///
-    /// Auto-optimization: If 3+ terms form a pure polynomial (only numbers, symbols,
-    /// products of coeff*symbol^n), converts to Poly for O(N) differentiation.
+    /// Auto-optimization: like terms over a common base (numbers, symbols,
+    /// products of coeff*symbol^n) are merged into a Poly for O(N) differentiation.
+    /// The thresholds come from the thread's [`PolyConversion`](crate::PolyConversion)
+    /// policy; use [`Expr::sum_no_poly`] to bypass it.
     ///
     /// # Panics
     /// Panics only if internal invariants are violated (never in normal use).
@@ -32,12 +36,15 @@ impl Expr {
 
         let mut flat: Vec<Arc<Self>> = Vec::with_capacity(terms.len());
         let mut numeric_sum: f64 = 0.0;
+        let mut numeric_scale: f64 = 0.0;
 
         for t in terms {
-            if matches!(t.kind, ExprKind::Sum(_) | ExprKind::Number(_)) {
+            if t.label.is_none() && matches!(t.kind, ExprKind::Sum(_) | ExprKind::Number(_)) {
                 match t.into_kind() {
                     ExprKind::Sum(inner) => flat.extend(inner),
-                    ExprKind::Number(n) => numeric_sum += n,
+                    ExprKind::Number(n) => {
+                        fold_summand(&mut numeric_sum, &mut numeric_scale, n, &mut flat);
+                    }
                     _ => {}
                 }
             } else {
@@ -45,7 +52,7 @@ impl Expr {
             }
         }
 
-        if numeric_sum.abs() > EPSILON {
+        if numeric_sum.is_nan() || !cancels(numeric_sum, numeric_scale) {
             flat.push(Arc::new(Self::number(numeric_sum)));
         }
 
@@ -85,17 +92,23 @@ impl Expr {
 
         if !terms
             .iter()
-            .any(|t| matches!(t.kind, ExprKind::Sum(_) | ExprKind::Number(_)))
+            .any(|t| t.label.is_none() && matches!(t.kind, ExprKind::Sum(_) | ExprKind::Number(_)))
         {
             return finalize_sum(terms);
         }
 
         let mut flat: Vec<Arc<Self>> = Vec::with_capacity(terms.len());
         let mut numeric_sum: f64 = 0.0;
+        let mut numeric_scale: f64 = 0.0;
 
         for t in terms {
+            if t.label.is_some() {
+                flat.push(t);
+                continue;
+            }
+
             if let ExprKind::Number(n) = t.kind {
-                numeric_sum += n;
+                fold_summand(&mut numeric_sum, &mut numeric_scale, n, &mut flat);
                 continue;
             }
 
@@ -118,7 +131,7 @@ impl Expr {
             flat.push(t);
         }
 
-        if numeric_sum.abs() > EPSILON {
+        if numeric_sum.is_nan() || !cancels(numeric_sum, numeric_scale) {
             flat.push(Arc::new(Self::number(numeric_sum)));
         }
 
@@ -183,6 +196,7 @@ impl Expr {
         }
 
         if factors.len() == 2
+            && factors.iter().all(|f| f.label.is_none())
             && matches!(factors[0].kind, ExprKind::Product(_))
             && matches!(factors[1].kind, ExprKind::Product(_))
             && let (ExprKind::Product(a_factors), ExprKind::Product(b_factors)) =
@@ -210,10 +224,9 @@ impl Expr {
             return finalize_product(merged);
         }
 
-        if !factors
-            .iter()
-            .any(|f| matches!(f.kind, ExprKind::Product(_) | ExprKind::Number(_)))
-        {
+        if !factors.iter().any(|f| {
+            f.label.is_none() && matches!(f.kind, ExprKind::Product(_) | ExprKind::Number(_))
+        }) {
             let mut flat = factors;
             if flat
                 .windows(2)
@@ -235,6 +248,7 @@ impl Expr {
 
         for f in factors {
             match &f.kind {
+                _ if f.label.is_some() => flat.push(f),
                 ExprKind::Product(_) => match Arc::try_unwrap(f) {
                     Ok(expr) => {
                         if let ExprKind::Product(inner) = expr.into_kind() {
@@ -247,17 +261,16 @@ impl Expr {
                         }
                     }
                 },
-                ExprKind::Number(n) => {
-                    if *n == 0.0 {
-                        return Self::number(0.0);
-                    }
-                    numeric_prod *= *n;
-                }
+                ExprKind::Number(n) => numeric_prod *= *n,
                 _ => flat.push(f),
             }
         }
 
-        if (numeric_prod - 1.0).abs() > EPSILON {
+        // Zero absorbs the symbolic factors, but `0 * inf` and `0 * nan` are NaN
+        if numeric_prod == 0.0 {
+            return Self::number(0.0);
+        }
+        if numeric_prod.is_nan() || (numeric_prod - 1.0).abs() > EPSILON {
             flat.push(Arc::new(Self::number(numeric_prod)));
         }
 
@@ -290,8 +303,28 @@ impl Expr {
 // HELPER FUNCTIONS
 // =============================================================================
 
+/// Whether `a + b` is the indeterminate form `inf + (-inf)`.
+pub(super) const fn opposite_infinities(a: f64, b: f64) -> bool {
+    a.is_infinite() && b.is_infinite() && a.is_sign_positive() != b.is_sign_positive()
+}
+
+/// Add `n` to the running numeric sum of a sum constructor.
+///
+/// Opposite infinities are not folded into NaN; `n` is kept as its own term so that
+/// `inf - inf` stays visible to simplification. `scale` tracks the largest summand so
+/// that only a genuine cancellation drops the folded constant.
+fn fold_summand(numeric_sum: &mut f64, scale: &mut f64, n: f64, flat: &mut Vec<Arc<Expr>>) {
+    if opposite_infinities(*numeric_sum, n) {
+        flat.push(Arc::new(Expr::number(n)));
+    } else {
+        *numeric_sum += n;
+        *scale = scale.max(n.abs());
+    }
+}
+
 /// Finalize a sum expression from a flattened list of terms
 fn finalize_sum(mut flat: Vec<Arc<Expr>>) -> Expr {
+    let policy = poly_conversion();
     let len = flat.len();
     if len == 2 {
         let cmp = expr_cmp(&flat[0], &flat[1]);
@@ -309,6 +342,7 @@ fn finalize_sum(mut flat: Vec<Arc<Expr>>) -> Expr {
                 Polynomial::try_from_expr(&flat[1]),
             )
             && poly.try_add_assign(&next_poly)
+            && policy.allows(2, poly.degree())
         {
             if poly.is_zero() {
                 return Expr::number(0.0);
@@ -339,32 +373,37 @@ fn finalize_sum(mut flat: Vec<Arc<Expr>>) -> Expr {
 
         if let Some(bh) = h
             && bh != 0
+            && policy.enabled
             && it
                 .peek()
                 .is_some_and(|next| get_poly_base_hash(next) == Some(bh))
         {
-            if let Some(mut poly) = Polynomial::try_from_expr(&term) {
+            let mut run = vec![term];
+            while let Some(next_term) = it.next_if(|next| get_poly_base_hash(next) == Some(bh)) {
+                run.push(next_term);
+            }
+
+            if let Some(mut poly) = Polynomial::try_from_expr(&run[0]) {
                 let mut unmerged: Vec<Arc<Expr>> = Vec::new();
-                while it
-                    .peek()
-                    .is_some_and(|next| get_poly_base_hash(next) == Some(bh))
-                {
-                    let next_term = it.next().expect("Iterator peeked successfully");
-                    if let Some(next_poly) = Polynomial::try_from_expr(&next_term) {
-                        if !poly.try_add_assign(&next_poly) {
-                            unmerged.push(next_term);
-                        }
+                let mut merged = 1;
+                for next_term in &run[1..] {
+                    if let Some(next_poly) = Polynomial::try_from_expr(next_term)
+                        && poly.try_add_assign(&next_poly)
+                    {
+                        merged += 1;
                     } else {
-                        unmerged.push(next_term);
+                        unmerged.push(Arc::clone(next_term));
                     }
                 }
-                if !poly.is_zero() {
-                    result.push(Arc::new(Expr::poly(poly)));
+                if policy.allows(merged, poly.degree()) {
+                    if !poly.is_zero() {
+                        result.push(Arc::new(Expr::poly(poly)));
+                    }
+                    result.extend(unmerged);
+                    continue;
                 }
-                result.extend(unmerged);
-                continue;
             }
-            result.push(term);
+            result.extend(run);
             continue;
         }
         result.push(term);
@@ -382,6 +421,10 @@ fn finalize_sum(mut flat: Vec<Arc<Expr>>) -> Expr {
 }
 
 fn get_poly_base_hash(expr: &Expr) -> Option<u64> {
+    // Labeled terms are kept whole rather than merged into a polynomial
+    if expr.label.is_some() {
+        return None;
+    }
     match &expr.kind {
         ExprKind::Symbol(_) | ExprKind::FunctionCall { .. } => Some(expr.structural_hash()),
         ExprKind::Poly(p) => Some(p.base().structural_hash()),
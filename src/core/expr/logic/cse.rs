@@ -0,0 +1,174 @@
+//! Common subexpression extraction into let-bindings.
+//!
+//! Occurrences are counted on the shared form of the trees: a subtree seen before is
+//! counted again but not entered again, so the parts of a repeated subtree count once
+//! for the whole subtree rather than once per copy. Subtrees referenced often enough
+//! become temporaries, bound in post-order so every definition refers only to earlier
+//! temporaries.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use rustc_hash::FxHashMap;
+
+use super::piecewise::map_piecewise;
+use super::{Expr, ExprKind};
+use crate::core::{Symbol, symb};
+
+impl Expr {
+    /// Factor out the subexpressions occurring at least `min_occurrences` times
+    ///
+    /// Returns `(temporary, definition)` bindings in dependency order, where a definition
+    /// may refer to earlier temporaries, and `self` rewritten in terms of them.
+    /// Substituting the definitions back, last binding first, recovers `self`.
+    /// Temporaries are named `t0`, `t1`, … skipping names that already occur in `self`.
+    /// Numbers and symbols are never extracted, and `min_occurrences` below 2 counts as 2.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, y) = (symb("cse_doc_x"), symb("cse_doc_y"));
+    /// let shared = (x + y).sin();
+    /// let expr = shared.clone() * x + shared.pow(2.0);
+    /// let (bindings, rewritten) = expr.extract_cse(2);
+    /// assert_eq!(bindings.len(), 1);
+    /// assert_eq!(bindings[0].1, (x + y).sin());
+    /// let t0 = bindings[0].0;
+    /// assert_eq!(rewritten, t0 * x + t0.pow(2.0));
+    /// ```
+    #[must_use]
+    pub fn extract_cse(&self, min_occurrences: usize) -> (Vec<(Symbol, Self)>, Self) {
+        let (bindings, mut rewritten) =
+            Self::extract_cse_many(std::slice::from_ref(self), min_occurrences);
+        let rewritten = rewritten.pop().unwrap_or_else(|| self.clone());
+        (bindings, rewritten)
+    }
+
+    /// [`extract_cse`](Self::extract_cse) over several expressions at once
+    ///
+    /// Occurrences are counted across all of `exprs`, so the components of a gradient
+    /// share one set of temporaries. The rewritten expressions come back in input order.
+    #[must_use]
+    pub fn extract_cse_many(
+        exprs: &[Self],
+        min_occurrences: usize,
+    ) -> (Vec<(Symbol, Self)>, Vec<Self>) {
+        let mut extractor = Extractor {
+            counts: FxHashMap::default(),
+            min_occurrences: min_occurrences.max(2),
+            rewritten: FxHashMap::default(),
+            bindings: Vec::new(),
+            taken: exprs.iter().flat_map(Self::variables).collect(),
+            next_index: 0,
+        };
+        for expr in exprs {
+            extractor.count(expr);
+        }
+        let rewritten = exprs
+            .iter()
+            .map(|expr| Self::unwrap_arc(extractor.rewrite(expr)))
+            .collect();
+        (extractor.bindings, rewritten)
+    }
+}
+
+struct Extractor<'expr> {
+    /// References to each distinct compound subtree
+    counts: FxHashMap<&'expr Expr, usize>,
+    min_occurrences: usize,
+    /// Each distinct subtree after rewriting; a temporary when it was extracted
+    rewritten: FxHashMap<&'expr Expr, Arc<Expr>>,
+    bindings: Vec<(Symbol, Expr)>,
+    /// Symbol names the temporaries must not take
+    taken: HashSet<String>,
+    next_index: usize,
+}
+
+impl<'expr> Extractor<'expr> {
+    fn count(&mut self, expr: &'expr Expr) {
+        if matches!(expr.kind, ExprKind::Number(_) | ExprKind::Symbol(_)) {
+            return;
+        }
+        let seen = self.counts.entry(expr).or_insert(0);
+        *seen += 1;
+        if *seen > 1 {
+            return;
+        }
+        let mut children = Vec::new();
+        Expr::push_children(expr, &mut children);
+        for child in children {
+            self.count(child);
+        }
+    }
+
+    fn rewrite_child(&mut self, child: &'expr Arc<Expr>) -> Arc<Expr> {
+        if matches!(child.kind, ExprKind::Number(_) | ExprKind::Symbol(_)) {
+            return Arc::clone(child);
+        }
+        self.rewrite(child)
+    }
+
+    fn rewrite_children(&mut self, items: &'expr [Arc<Expr>]) -> Vec<Arc<Expr>> {
+        items.iter().map(|item| self.rewrite_child(item)).collect()
+    }
+
+    fn rewrite(&mut self, expr: &'expr Expr) -> Arc<Expr> {
+        if let Some(done) = self.rewritten.get(expr) {
+            return Arc::clone(done);
+        }
+
+        let node = match &expr.kind {
+            ExprKind::Number(_) | ExprKind::Symbol(_) => expr.clone(),
+            ExprKind::Sum(terms) => Expr::sum_from_arcs(self.rewrite_children(terms)),
+            ExprKind::Product(factors) => Expr::product_from_arcs(self.rewrite_children(factors)),
+            ExprKind::FunctionCall { name, args } => {
+                Expr::func_multi_from_arcs_symbol(name.clone(), self.rewrite_children(args))
+            }
+            ExprKind::Div(num, den) => {
+                let num = self.rewrite_child(num);
+                Expr::div_from_arcs(num, self.rewrite_child(den))
+            }
+            ExprKind::Pow(base, exp) => {
+                let base = self.rewrite_child(base);
+                Expr::pow_from_arcs(base, self.rewrite_child(exp))
+            }
+            ExprKind::Derivative { inner, var, order } => Expr::derivative_interned(
+                Expr::unwrap_arc(self.rewrite_child(inner)),
+                var.clone(),
+                *order,
+            ),
+            ExprKind::Poly(poly) => {
+                let base = self.rewrite_child(poly.base());
+                Expr::new(ExprKind::Poly(poly.with_base(base)))
+            }
+            ExprKind::Piecewise { branches, default } => {
+                map_piecewise(branches, default, |part| self.rewrite_child(part))
+            }
+        }
+        .inherit_label(expr);
+
+        let extracted = self
+            .counts
+            .get(expr)
+            .is_some_and(|&count| count >= self.min_occurrences);
+        let result = if extracted {
+            let temp = self.fresh_temporary();
+            self.bindings.push((temp, node));
+            Arc::new(temp.to_expr())
+        } else {
+            Arc::new(node)
+        };
+        self.rewritten.insert(expr, Arc::clone(&result));
+        result
+    }
+
+    fn fresh_temporary(&mut self) -> Symbol {
+        loop {
+            let name = format!("t{}", self.next_index);
+            self.next_index += 1;
+            if !self.taken.contains(&name) {
+                return symb(&name);
+            }
+        }
+    }
+}
@@ -18,12 +18,18 @@
 //! - `x² + 2·x + 1`
 //! - `sin(x) + cos(x)` with π, α, β, etc. for Greek variables
 //!
+//! ## Configurable Text (`format_with()`)
+//! Standard notation with a chosen power operator, multiplication sign, float
+//! precision and call brackets, e.g. `x**2 + 2 * x + 1` for Python.
+//!
 //! # Display Behavior Notes for N-ary AST
 //! - Sum displays terms with +/- signs based on leading coefficients
 //! - Product displays with explicit `*` or `·` multiplication
 //! - `e^x` is always displayed as `exp(x)` for consistency
 //! - Derivatives use ∂ notation
 
+use super::piecewise::piecewise_parts;
+use super::piecewise::{CompareOp, Condition};
 use super::poly::Polynomial;
 use super::{Expr, ExprKind};
 use crate::EPSILON;
@@ -43,9 +49,113 @@ enum FormatMode {
     /// Standard mathematical notation
     Standard,
     /// LaTeX mathematical notation
-    Latex,
+    Latex(LatexConfig),
     /// Unicode mathematical notation
     Unicode,
+    /// Standard notation with [`FormatOptions`]
+    Text(FormatOptions),
+}
+
+/// Options for [`Expr::to_latex_with`].
+///
+/// The default matches [`Expr::to_latex`]: `\cdot` between factors (numeric
+/// coefficients are juxtaposed), bare negative exponents and no labels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LatexConfig {
+    /// Juxtapose factors with a thin space (`x\,y`) instead of `\cdot`, keeping
+    /// `\cdot` only where the next factor starts with a digit
+    pub implicit_multiplication: bool,
+    /// Wrap negative exponents in parentheses: `x^{\left(-2\right)}`
+    pub parenthesize_negative_exponents: bool,
+    /// Mark subexpressions labeled with [`Expr::labeled`] as
+    /// `\underbrace{\ldots}_{\text{label}}`
+    pub show_labels: bool,
+}
+
+/// Operator written between the base and the exponent of a power
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PowerOperator {
+    /// `x^2`, as the parser reads it
+    #[default]
+    Caret,
+    /// `x**2`, as Python reads it
+    DoubleStar,
+}
+
+/// How the factors of a product are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MulStyle {
+    /// `2*x*y`
+    #[default]
+    Explicit,
+    /// `2 * x * y`
+    Spaced,
+    /// `2x*y`: a numeric coefficient is juxtaposed with a following symbol or call
+    /// whose name does not start with `e` or `E` (which would read as an exponent)
+    Implicit,
+}
+
+/// Brackets around the arguments of function calls
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FunctionCallStyle {
+    /// `sin(x)`
+    #[default]
+    Parentheses,
+    /// `sin[x]`, Wolfram style; the parser does not read it back
+    SquareBrackets,
+}
+
+/// Options for [`Expr::format_with`].
+///
+/// The default writes what [`Display`] writes, except that a power used as the base of
+/// another is parenthesized (`(x^2)^3`), so the output with [`PowerOperator::Caret`]
+/// and without `float_precision` parses back to an equal expression.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Operator between base and exponent
+    pub power_operator: PowerOperator,
+    /// Multiplication sign between factors
+    pub mul_style: MulStyle,
+    /// Write non-integer numbers (and integers of 10^10 or more) in scientific notation
+    /// with this many significant digits, e.g. `3.58652e-18` for 6; `None` writes the
+    /// shortest decimal that reads back as the same float
+    pub float_precision: Option<usize>,
+    /// Brackets around call arguments, including `diff(...)` and `piecewise(...)`
+    pub function_call_style: FunctionCallStyle,
+}
+
+impl FormatOptions {
+    /// Brackets opening and closing an argument list
+    const fn call_brackets(self) -> (&'static str, &'static str) {
+        match self.function_call_style {
+            FunctionCallStyle::Parentheses => ("(", ")"),
+            FunctionCallStyle::SquareBrackets => ("[", "]"),
+        }
+    }
+
+    /// Operator written between base and exponent
+    const fn power(self) -> &'static str {
+        match self.power_operator {
+            PowerOperator::Caret => "^",
+            PowerOperator::DoubleStar => "**",
+        }
+    }
+
+    /// Separator between product factors
+    const fn mul(self) -> &'static str {
+        match self.mul_style {
+            MulStyle::Explicit | MulStyle::Implicit => "*",
+            MulStyle::Spaced => " * ",
+        }
+    }
+}
+
+/// Brackets opening and closing an argument list in `mode`
+const fn call_brackets(mode: FormatMode) -> (&'static str, &'static str) {
+    match mode {
+        FormatMode::Text(options) => options.call_brackets(),
+        _ => ("(", ")"),
+    }
 }
 
 /// Cache for symbol names to avoid repetitive global registry lookups
@@ -77,6 +187,9 @@ fn collect_symbol_names(expr: &Expr, cache: &mut SymbolCache) {
             ExprKind::Poly(poly) => {
                 stack.push(poly.base());
             }
+            ExprKind::Piecewise { branches, default } => {
+                stack.extend(piecewise_parts(branches, default).map(AsRef::as_ref));
+            }
             ExprKind::Number(_) => {}
         }
     }
@@ -207,9 +320,84 @@ fn format_recursive(
 ) -> Result {
     match mode {
         FormatMode::Standard => write!(f, "{expr}"),
-        FormatMode::Latex => write!(f, "{}", LatexFormatter { expr, cache }),
+        FormatMode::Latex(config) => write!(
+            f,
+            "{}",
+            LatexFormatter {
+                expr,
+                cache,
+                config
+            }
+        ),
         FormatMode::Unicode => write!(f, "{}", UnicodeFormatter { expr, cache }),
+        FormatMode::Text(options) => write!(
+            f,
+            "{}",
+            TextFormatter {
+                expr,
+                cache,
+                options
+            }
+        ),
+    }
+}
+
+/// Piecewise formatting: call syntax in text modes, a `cases` environment in LaTeX.
+/// A NaN default (no branch applies) is left out.
+fn format_piecewise_expr(
+    f: &mut Formatter<'_>,
+    branches: &[(Condition, Arc<Expr>)],
+    default: &Expr,
+    mode: FormatMode,
+    cache: Option<&SymbolCache>,
+) -> Result {
+    let op = |op: CompareOp| match (mode, op) {
+        (FormatMode::Latex(_), CompareOp::Le) => r"\leq",
+        (FormatMode::Latex(_), CompareOp::Ge) => r"\geq",
+        (FormatMode::Latex(_), CompareOp::Eq) => "=",
+        (FormatMode::Unicode, CompareOp::Le) => "\u{2264}",
+        (FormatMode::Unicode, CompareOp::Ge) => "\u{2265}",
+        _ => op.as_str(),
+    };
+    let has_default = !default.as_number().is_some_and(f64::is_nan);
+
+    if let FormatMode::Latex(_) = mode {
+        write!(f, r"\begin{{cases}}")?;
+        for (index, (condition, value)) in branches.iter().enumerate() {
+            if index > 0 {
+                write!(f, r" \\ ")?;
+            }
+            format_recursive(f, value, mode, cache)?;
+            write!(f, r" & \text{{if }} ")?;
+            format_recursive(f, &condition.lhs, mode, cache)?;
+            write!(f, " {} ", op(condition.op))?;
+            format_recursive(f, &condition.rhs, mode, cache)?;
+        }
+        if has_default {
+            write!(f, r" \\ ")?;
+            format_recursive(f, default, mode, cache)?;
+            write!(f, r" & \text{{otherwise}}")?;
+        }
+        return write!(f, r"\end{{cases}}");
     }
+
+    let (open, close) = call_brackets(mode);
+    write!(f, "piecewise{open}")?;
+    for (index, (condition, value)) in branches.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        format_recursive(f, &condition.lhs, mode, cache)?;
+        write!(f, " {} ", op(condition.op))?;
+        format_recursive(f, &condition.rhs, mode, cache)?;
+        write!(f, ": ")?;
+        format_recursive(f, value, mode, cache)?;
+    }
+    if has_default {
+        write!(f, ", ")?;
+        format_recursive(f, default, mode, cache)?;
+    }
+    write!(f, "{close}")
 }
 
 /// Consolidated symbol formatting
@@ -234,8 +422,8 @@ fn format_symbol_expr(
     }
 
     match mode {
-        FormatMode::Standard => write!(f, "{name_str}"),
-        FormatMode::Latex => {
+        FormatMode::Standard | FormatMode::Text(_) => write!(f, "{name_str}"),
+        FormatMode::Latex(_) => {
             if let Some(greek) = greek_to_latex(name_str) {
                 write!(f, "{greek}")
             } else if let Some((prefix, subscript)) = name_str.split_once('_') {
@@ -268,13 +456,22 @@ fn format_wrapped(
 ) -> Result {
     let needs = match context {
         ParenContext::SumOrProduct => matches!(expr.kind, ExprKind::Sum(_) | ExprKind::Poly(_)),
-        ParenContext::PowerBase => needs_parens_as_base(expr),
+        ParenContext::PowerBase => match mode {
+            // `(x^2)^3`: a bare `x^2^3` would read back as `x^(2^3)`
+            FormatMode::Text(_) => {
+                needs_parens_as_base(expr) || matches!(expr.kind, ExprKind::Pow(..))
+            }
+            FormatMode::Standard => needs_parens_as_base(expr),
+            FormatMode::Latex(_) | FormatMode::Unicode => {
+                needs_parens_as_base(expr) || matches!(expr.kind, ExprKind::Derivative { .. })
+            }
+        },
     };
 
     if needs {
         let (open, close) = match mode {
-            FormatMode::Standard | FormatMode::Unicode => ("(", ")"),
-            FormatMode::Latex => (r"\left(", r"\right)"),
+            FormatMode::Standard | FormatMode::Unicode | FormatMode::Text(_) => ("(", ")"),
+            FormatMode::Latex(_) => (r"\left(", r"\right)"),
         };
         write!(f, "{open}")?;
         format_recursive(f, expr, mode, cache)?;
@@ -297,7 +494,7 @@ fn format_sum_expr(
 
     let plus = " + ";
     let (minus, minus_sep) = match mode {
-        FormatMode::Standard | FormatMode::Latex => ("-", " - "),
+        FormatMode::Standard | FormatMode::Latex(_) | FormatMode::Text(_) => ("-", " - "),
         FormatMode::Unicode => ("\u{2212}", " \u{2212} "),
     };
 
@@ -339,20 +536,13 @@ fn format_negative_part(
         // If there are other factors, print 2 * rest
         if let Some(factors) = neg.rest_factors {
             format_number_expr(f, abs_coeff, mode)?;
-            let sep = match mode {
-                FormatMode::Unicode => "\u{b7}",
-                FormatMode::Latex => r" \cdot ",
-                FormatMode::Standard => "*",
-            };
-            write!(f, "{sep}")?;
+            let sep = mul_sep(mode);
             // Print remaining factors
-            let mut first = true;
+            let mut prev_is_number = true;
             for fac in factors {
-                if !first {
-                    write!(f, "{sep}")?;
-                }
+                write!(f, "{}", factor_sep(sep, mode, prev_is_number, fac))?;
                 format_wrapped(f, fac, mode, ParenContext::SumOrProduct, cache)?;
-                first = false;
+                prev_is_number = matches!(fac.kind, ExprKind::Number(_));
             }
         } else if neg.rest.is_none() {
             // Just a number -2 -> print "2"
@@ -369,18 +559,13 @@ fn format_negative_part(
         format_wrapped(f, rest, mode, context, cache)?;
     } else if let Some(factors) = neg.rest_factors {
         // e.g. -(a*b) -> print "a*b"
-        let sep = match mode {
-            FormatMode::Unicode => "\u{b7}",
-            FormatMode::Latex => r" \cdot ",
-            FormatMode::Standard => "*",
-        };
-        let mut first = true;
-        for fac in factors {
-            if !first {
-                write!(f, "{sep}")?;
+        let sep = mul_sep(mode);
+        for (i, fac) in factors.iter().enumerate() {
+            if i > 0 {
+                let prev_is_number = matches!(factors[i - 1].kind, ExprKind::Number(_));
+                write!(f, "{}", factor_sep(sep, mode, prev_is_number, fac))?;
             }
             format_wrapped(f, fac, mode, ParenContext::SumOrProduct, cache)?;
-            first = false;
         }
     }
     Ok(())
@@ -397,19 +582,10 @@ fn format_product_expr(
         return write!(f, "1");
     }
 
-    let sep = match mode {
-        FormatMode::Standard | FormatMode::Unicode => {
-            if matches!(mode, FormatMode::Unicode) {
-                "\u{b7}"
-            } else {
-                "*"
-            }
-        }
-        FormatMode::Latex => r" \cdot ",
-    };
+    let sep = mul_sep(mode);
 
     let minus = match mode {
-        FormatMode::Standard | FormatMode::Latex => "-",
+        FormatMode::Standard | FormatMode::Latex(_) | FormatMode::Text(_) => "-",
         FormatMode::Unicode => "\u{2212}",
     };
 
@@ -457,33 +633,103 @@ fn format_product_expr(
             } else {
                 // -n * X = -n*X
                 format_number_expr(f, abs_val, mode)?;
-                write!(f, "{sep}")?;
+                write!(f, "{}", factor_sep(sep, mode, true, &factors[1]))?;
             }
             // Print remaining factors
-            let mut first = true;
-            for fac in &factors[1..] {
-                if !first {
-                    write!(f, "{sep}")?;
+            for (i, fac) in factors.iter().enumerate().skip(1) {
+                if i > 1 {
+                    let prev_is_number = matches!(factors[i - 1].kind, ExprKind::Number(_));
+                    write!(f, "{}", factor_sep(sep, mode, prev_is_number, fac))?;
                 }
                 format_wrapped(f, fac, mode, ParenContext::SumOrProduct, cache)?;
-                first = false;
             }
         }
         return Ok(());
     }
 
     // Standard formatting: print factors separated by *
-    let mut first = true;
-    for fac in factors {
-        if !first {
-            write!(f, "{sep}")?;
+    for (i, fac) in factors.iter().enumerate() {
+        if i > 0 {
+            let prev_is_number = matches!(factors[i - 1].kind, ExprKind::Number(_));
+            write!(f, "{}", factor_sep(sep, mode, prev_is_number, fac))?;
         }
         format_wrapped(f, fac, mode, ParenContext::SumOrProduct, cache)?;
-        first = false;
     }
     Ok(())
 }
 
+/// Default separator between product factors in `mode`
+const fn mul_sep(mode: FormatMode) -> &'static str {
+    match mode {
+        FormatMode::Standard => "*",
+        FormatMode::Unicode => "\u{b7}",
+        FormatMode::Latex(_) => r" \cdot ",
+        FormatMode::Text(options) => options.mul(),
+    }
+}
+
+/// Separator between two adjacent product factors.
+///
+/// LaTeX juxtaposes a numeric coefficient with a following factor that cannot be
+/// misread as more digits (`3x^{2}` rather than `3 \cdot x^{2}`). With
+/// [`LatexConfig::implicit_multiplication`] other factors are set apart by a thin space
+/// (`x\,\sin\left(x\right)`), keeping `\cdot` only before a leading digit.
+fn factor_sep<'sep>(
+    sep: &'sep str,
+    mode: FormatMode,
+    prev_is_number: bool,
+    next: &Expr,
+) -> &'sep str {
+    if let FormatMode::Text(options) = mode {
+        return if options.mul_style == MulStyle::Implicit
+            && prev_is_number
+            && juxtaposes_in_text(next)
+        {
+            ""
+        } else {
+            sep
+        };
+    }
+    let FormatMode::Latex(config) = mode else {
+        return sep;
+    };
+    let leads_with_digit = match &next.kind {
+        ExprKind::Number(_) => true,
+        ExprKind::Pow(base, _) => matches!(base.kind, ExprKind::Number(_)),
+        _ => false,
+    };
+    if prev_is_number {
+        let juxtapose = !leads_with_digit
+            && matches!(
+                next.kind,
+                ExprKind::Symbol(_)
+                    | ExprKind::FunctionCall { .. }
+                    | ExprKind::Derivative { .. }
+                    | ExprKind::Sum(_)
+                    | ExprKind::Poly(_)
+                    | ExprKind::Pow(..)
+            );
+        return if juxtapose { "" } else { sep };
+    }
+    if config.implicit_multiplication && !leads_with_digit {
+        r"\,"
+    } else {
+        sep
+    }
+}
+
+/// Whether a numeric coefficient can be written right before `next` in text
+///
+/// Names starting with `e` or `E` are kept apart, as `2e` would read as an exponent.
+fn juxtaposes_in_text(next: &Expr) -> bool {
+    let name = match &next.kind {
+        ExprKind::Symbol(s) => s.as_str(),
+        ExprKind::FunctionCall { name, .. } => name.as_str(),
+        _ => return false,
+    };
+    name.starts_with(|c: char| c.is_alphabetic() && c != 'e' && c != 'E')
+}
+
 /// Unified Division formatting
 fn format_div_expr(
     f: &mut Formatter<'_>,
@@ -492,7 +738,7 @@ fn format_div_expr(
     mode: FormatMode,
     cache: Option<&SymbolCache>,
 ) -> Result {
-    if matches!(mode, FormatMode::Latex) {
+    if matches!(mode, FormatMode::Latex(_)) {
         write!(f, r"\frac{{")?;
         format_recursive(f, u, mode, cache)?;
         write!(f, "}}{{")?;
@@ -544,16 +790,17 @@ fn format_pow_expr(
     cache: Option<&SymbolCache>,
 ) -> Result {
     // Special case: e^x displays as exp(x) (except in LaTeX which uses e^{x})
-    if !matches!(mode, FormatMode::Latex)
+    if !matches!(mode, FormatMode::Latex(_))
         && let ExprKind::Symbol(s) = &u.kind
         && s.id() == KS.e
     {
-        write!(f, "exp(")?;
+        let (open, close) = call_brackets(mode);
+        write!(f, "exp{open}")?;
         format_recursive(f, v, mode, cache)?;
-        return write!(f, ")");
+        return write!(f, "{close}");
     }
 
-    if matches!(mode, FormatMode::Latex) {
+    if matches!(mode, FormatMode::Latex(_)) {
         if let ExprKind::Symbol(s) = &u.kind
             && s.id() == KS.e
         {
@@ -562,7 +809,7 @@ fn format_pow_expr(
             return write!(f, "}}");
         }
 
-        if needs_parens_as_base(u) {
+        if needs_parens_as_base(u) || matches!(u.kind, ExprKind::Derivative { .. }) {
             write!(f, r"\left(")?;
             format_recursive(f, u, mode, cache)?;
             write!(f, r"\right)^{{")?;
@@ -570,6 +817,14 @@ fn format_pow_expr(
             format_recursive(f, u, mode, cache)?;
             write!(f, "^{{")?;
         }
+        if let FormatMode::Latex(config) = mode
+            && config.parenthesize_negative_exponents
+            && analyze_negative(v).is_negative
+        {
+            write!(f, r"\left(")?;
+            format_recursive(f, v, mode, cache)?;
+            return write!(f, r"\right)}}");
+        }
         format_recursive(f, v, mode, cache)?;
         return write!(f, "}}");
     }
@@ -589,12 +844,16 @@ fn format_pow_expr(
             write!(f, ")")
         }
     } else {
+        let power = match mode {
+            FormatMode::Text(options) => options.power(),
+            _ => "^",
+        };
         let exp_simple = matches!(v.kind, ExprKind::Number(_) | ExprKind::Symbol(_));
         if exp_simple {
-            write!(f, "^")?;
+            write!(f, "{power}")?;
             format_recursive(f, v, mode, cache)
         } else {
-            write!(f, "^(")?;
+            write!(f, "{power}(")?;
             format_recursive(f, v, mode, cache)?;
             write!(f, ")")
         }
@@ -613,7 +872,7 @@ fn format_function_call_expr(
     mode: FormatMode,
     cache: Option<&SymbolCache>,
 ) -> Result {
-    if matches!(mode, FormatMode::Latex) {
+    if let FormatMode::Latex(config) = mode {
         // Special formatting for specific functions in LaTeX
         match name {
             // === ROOTS ===
@@ -623,7 +882,8 @@ fn format_function_call_expr(
                     r"\sqrt{{{}}}",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -633,7 +893,8 @@ fn format_function_call_expr(
                     r"\sqrt[3]{{{}}}",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -645,7 +906,8 @@ fn format_function_call_expr(
                     r"\left|{}\right|",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -657,7 +919,8 @@ fn format_function_call_expr(
                     r"\lfloor{}\rfloor",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -667,7 +930,8 @@ fn format_function_call_expr(
                     r"\lceil{}\rceil",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -679,11 +943,13 @@ fn format_function_call_expr(
                     r"J_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -693,11 +959,13 @@ fn format_function_call_expr(
                     r"Y_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -707,11 +975,13 @@ fn format_function_call_expr(
                     r"I_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -721,11 +991,13 @@ fn format_function_call_expr(
                     r"K_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -737,11 +1009,13 @@ fn format_function_call_expr(
                     r"H_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -751,15 +1025,18 @@ fn format_function_call_expr(
                     r"P_{{{}}}^{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[2],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -769,19 +1046,23 @@ fn format_function_call_expr(
                     r"Y_{{{}}}^{{{}}}\left({}, {}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[2],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[3],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -793,7 +1074,8 @@ fn format_function_call_expr(
                     r"\psi\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -803,7 +1085,8 @@ fn format_function_call_expr(
                     r"\psi_1\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -813,7 +1096,8 @@ fn format_function_call_expr(
                     r"\psi_2\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -823,11 +1107,13 @@ fn format_function_call_expr(
                     r"\psi^{{({})}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -839,7 +1125,8 @@ fn format_function_call_expr(
                     r"K\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -849,7 +1136,8 @@ fn format_function_call_expr(
                     r"E\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -861,7 +1149,8 @@ fn format_function_call_expr(
                     r"\zeta\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -871,11 +1160,13 @@ fn format_function_call_expr(
                     r"\zeta^{{({})}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -887,7 +1178,8 @@ fn format_function_call_expr(
                     r"W\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -899,11 +1191,13 @@ fn format_function_call_expr(
                     r"\mathrm{{B}}\left({}, {}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -915,11 +1209,13 @@ fn format_function_call_expr(
                     r"\log_{{{}}}\left({}\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     },
                     LatexFormatter {
                         expr: &args[1],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -930,7 +1226,8 @@ fn format_function_call_expr(
                     r"\ln\left(\Gamma\left({}\right)\right)",
                     LatexFormatter {
                         expr: &args[0],
-                        cache
+                        cache,
+                        config
                     }
                 );
             }
@@ -965,6 +1262,11 @@ fn format_function_call_expr(
             "erf" => r"\operatorname{erf}".to_owned(),
             "erfc" => r"\operatorname{erfc}".to_owned(),
             "signum" => r"\operatorname{sgn}".to_owned(),
+            "heaviside" => r"\theta".to_owned(),
+            "dirac" => r"\delta".to_owned(),
+            "min" => r"\min".to_owned(),
+            "max" => r"\max".to_owned(),
+            "clamp" => r"\operatorname{clamp}".to_owned(),
             "sinc" => r"\operatorname{sinc}".to_owned(),
             "round" => r"\operatorname{round}".to_owned(),
             // Default: wrap in \text{}
@@ -984,19 +1286,16 @@ fn format_function_call_expr(
             write!(f, r"\right)")
         }
     } else {
-        // Standard/Unicode logic
-        if args.is_empty() {
-            write!(f, "{name}()")
-        } else {
-            write!(f, "{name}(")?;
-            for (i, arg) in args.iter().enumerate() {
-                if i > 0 {
-                    write!(f, ", ")?;
-                }
-                format_recursive(f, arg, mode, cache)?;
+        // Standard/Unicode/Text logic
+        let (open, close) = call_brackets(mode);
+        write!(f, "{name}{open}")?;
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
             }
-            write!(f, ")")
+            format_recursive(f, arg, mode, cache)?;
         }
+        write!(f, "{close}")
     }
 }
 
@@ -1045,7 +1344,7 @@ fn greek_to_latex(name: &str) -> Option<&'static str> {
 }
 
 /// Map symbol name to Unicode Greek letter
-fn greek_to_unicode(name: &str) -> Option<&'static str> {
+pub(super) fn greek_to_unicode(name: &str) -> Option<&'static str> {
     GREEK_LETTERS
         .iter()
         .find(|(n, _, _)| *n == name)
@@ -1053,23 +1352,27 @@ fn greek_to_unicode(name: &str) -> Option<&'static str> {
 }
 
 /// Format a number based on the display mode
+///
+/// Non-finite values print as `inf`, `-inf` and `nan` in standard mode, which the
+/// parser reads back as the same literals.
 fn format_number_expr(f: &mut Formatter<'_>, n: f64, mode: FormatMode) -> Result {
     if n.is_nan() {
         return match mode {
-            FormatMode::Standard | FormatMode::Unicode => write!(f, "NaN"),
-            FormatMode::Latex => write!(f, r"\text{{NaN}}"),
+            FormatMode::Standard | FormatMode::Text(_) => write!(f, "nan"),
+            FormatMode::Unicode => write!(f, "NaN"),
+            FormatMode::Latex(_) => write!(f, r"\text{{NaN}}"),
         };
     }
     if n.is_infinite() {
         return match mode {
-            FormatMode::Standard => {
+            FormatMode::Standard | FormatMode::Text(_) => {
                 if n > 0.0 {
-                    write!(f, "Infinity")
+                    write!(f, "inf")
                 } else {
-                    write!(f, "-Infinity")
+                    write!(f, "-inf")
                 }
             }
-            FormatMode::Latex => {
+            FormatMode::Latex(_) => {
                 if n > 0.0 {
                     write!(f, r"\infty")
                 } else {
@@ -1091,6 +1394,12 @@ fn format_number_expr(f: &mut Formatter<'_>, n: f64, mode: FormatMode) -> Result
         #[allow(clippy::cast_possible_truncation, reason = "Checked is_int above")]
         let n_int = n as i64;
         write!(f, "{n_int}")
+    } else if let FormatMode::Text(FormatOptions {
+        float_precision: Some(digits),
+        ..
+    }) = mode
+    {
+        write!(f, "{n:.*e}", digits.max(1) - 1)
     } else {
         write!(f, "{n}")
     }
@@ -1128,14 +1437,24 @@ impl Display for Expr {
 
             ExprKind::Pow(u, v) => format_pow_expr(f, u, v, FormatMode::Standard, None),
 
+            // Re-parseable call syntax: diff(f(x), x) or diff(f(x), x, n)
             ExprKind::Derivative { inner, var, order } => {
-                write!(f, "\u{2202}^{order}_{inner}/\u{2202}_{var}^{order}")
+                if *order == 1 {
+                    write!(f, "diff({inner}, {var})")
+                } else {
+                    write!(f, "diff({inner}, {var}, {order})")
+                }
             }
 
             // Poly: display inline using Polynomial's Display
             ExprKind::Poly(poly) => {
                 write!(f, "{poly}")
             }
+
+            // Re-parseable call syntax: piecewise(x < 0: 0, x)
+            ExprKind::Piecewise { branches, default } => {
+                format_piecewise_expr(f, branches, default, FormatMode::Standard, None)
+            }
         }
     }
 }
@@ -1150,11 +1469,20 @@ pub(super) struct LatexFormatter<'expr> {
     pub(crate) expr: &'expr Expr,
     /// Optional symbol cache for formatting
     pub(crate) cache: Option<&'expr SymbolCache>,
+    /// Output options
+    pub(crate) config: LatexConfig,
 }
 
 impl Display for LatexFormatter<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        format_latex(self.expr, f, self.cache)
+        if self.config.show_labels
+            && let Some(label) = self.expr.label()
+        {
+            write!(f, r"\underbrace{{")?;
+            format_latex(self.expr, f, self.cache, self.config)?;
+            return write!(f, r"}}_{{\text{{{label}}}}}");
+        }
+        format_latex(self.expr, f, self.cache, self.config)
     }
 }
 
@@ -1164,46 +1492,113 @@ impl Display for LatexFormatter<'_> {
     reason = "Display format naturally lengthy due to many expr kinds"
 )]
 /// Format an expression in LaTeX
-fn format_latex(expr: &Expr, f: &mut Formatter<'_>, cache: Option<&SymbolCache>) -> Result {
+fn format_latex(
+    expr: &Expr,
+    f: &mut Formatter<'_>,
+    cache: Option<&SymbolCache>,
+    config: LatexConfig,
+) -> Result {
+    let mode = FormatMode::Latex(config);
     match &expr.kind {
-        ExprKind::Number(n) => format_number_expr(f, *n, FormatMode::Latex),
+        ExprKind::Number(n) => format_number_expr(f, *n, mode),
 
-        ExprKind::Symbol(_) => format_symbol_expr(f, expr, FormatMode::Latex, cache),
+        ExprKind::Symbol(_) => format_symbol_expr(f, expr, mode, cache),
 
         ExprKind::FunctionCall { name, args } => {
-            format_function_call_expr(f, name.as_str(), args, FormatMode::Latex, cache)
+            format_function_call_expr(f, name.as_str(), args, mode, cache)
         }
 
-        ExprKind::Sum(terms) => format_sum_expr(f, terms, FormatMode::Latex, cache),
+        ExprKind::Sum(terms) => format_sum_expr(f, terms, mode, cache),
 
-        ExprKind::Product(factors) => format_product_expr(f, factors, FormatMode::Latex, cache),
+        ExprKind::Product(factors) => format_product_expr(f, factors, mode, cache),
 
-        ExprKind::Div(u, v) => format_div_expr(f, u, v, FormatMode::Latex, cache),
+        ExprKind::Div(u, v) => format_div_expr(f, u, v, mode, cache),
 
-        ExprKind::Pow(u, v) => format_pow_expr(f, u, v, FormatMode::Latex, cache),
+        ExprKind::Pow(u, v) => format_pow_expr(f, u, v, mode, cache),
 
         ExprKind::Derivative { inner, var, order } => {
-            if *order == 1 {
-                write!(
-                    f,
-                    r"\frac{{\partial {}}}{{\partial {}}}",
-                    LatexFormatter { expr: inner, cache },
-                    var
-                )
-            } else {
-                write!(
-                    f,
-                    r"\frac{{\partial^{} {}}}{{\partial {}^{}}}",
-                    order,
-                    LatexFormatter { expr: inner, cache },
-                    var,
-                    order
-                )
-            }
+            format_latex_derivative(f, inner, var.as_str(), *order, cache, config)
         }
 
         // Poly: display inline in LaTeX
         ExprKind::Poly(poly) => write!(f, "{poly}"),
+
+        ExprKind::Piecewise { branches, default } => {
+            format_piecewise_expr(f, branches, default, mode, cache)
+        }
+    }
+}
+
+/// LaTeX name of a function or variable inside Leibniz notation
+fn latex_leibniz_name(name: &str) -> String {
+    greek_to_latex(name).map_or_else(
+        || {
+            if name.chars().count() == 1 {
+                name.to_owned()
+            } else {
+                format!(r"\operatorname{{{name}}}")
+            }
+        },
+        str::to_owned,
+    )
+}
+
+/// Format a derivative node in Leibniz notation.
+///
+/// A function of plain symbols is written by name (`\frac{dg}{dx}`), using `d` when
+/// the differentiation variable is its only argument and `\partial` otherwise. Any
+/// other operand is applied to the operator: `\frac{\partial}{\partial x}\left(...\right)`.
+fn format_latex_derivative(
+    f: &mut Formatter<'_>,
+    inner: &Expr,
+    var: &str,
+    order: u32,
+    cache: Option<&SymbolCache>,
+    config: LatexConfig,
+) -> Result {
+    let named_function = match &inner.kind {
+        ExprKind::FunctionCall { name, args }
+            if args.iter().all(|a| matches!(a.kind, ExprKind::Symbol(_))) =>
+        {
+            let total = args.len() == 1
+                && matches!(&args[0].kind, ExprKind::Symbol(s) if s.as_str() == var);
+            Some((name.as_str(), total))
+        }
+        _ => None,
+    };
+
+    let d = match named_function {
+        Some((_, true)) => "d",
+        _ => r"\partial ",
+    };
+    let var_name = latex_leibniz_name(var);
+    let (num_d, den) = if order == 1 {
+        (d.trim_end().to_owned(), format!("{d}{var_name}"))
+    } else {
+        (
+            format!("{}^{{{order}}}", d.trim_end()),
+            format!("{d}{var_name}^{{{order}}}"),
+        )
+    };
+
+    match named_function {
+        Some((name, _)) => {
+            let sep = if d == "d" { "" } else { " " };
+            write!(
+                f,
+                r"\frac{{{num_d}{sep}{}}}{{{den}}}",
+                latex_leibniz_name(name)
+            )
+        }
+        None => write!(
+            f,
+            r"\frac{{{num_d}}}{{{den}}}\left({}\right)",
+            LatexFormatter {
+                expr: inner,
+                cache,
+                config
+            }
+        ),
     }
 }
 
@@ -1302,6 +1697,78 @@ fn format_unicode(expr: &Expr, f: &mut Formatter<'_>, cache: Option<&SymbolCache
 
         // Poly: display inline in unicode
         ExprKind::Poly(poly) => write!(f, "{poly}"),
+
+        ExprKind::Piecewise { branches, default } => {
+            format_piecewise_expr(f, branches, default, FormatMode::Unicode, cache)
+        }
+    }
+}
+
+// =============================================================================
+// TEXT FORMATTER
+// =============================================================================
+
+/// Standard-notation formatter following [`FormatOptions`]
+pub(super) struct TextFormatter<'expr> {
+    /// The expression to format
+    pub(crate) expr: &'expr Expr,
+    /// Optional symbol cache for formatting
+    pub(crate) cache: Option<&'expr SymbolCache>,
+    /// Output options
+    pub(crate) options: FormatOptions,
+}
+
+impl Display for TextFormatter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        format_text(self.expr, f, self.cache, self.options)
+    }
+}
+
+/// Format an expression in standard notation with `options`
+fn format_text(
+    expr: &Expr,
+    f: &mut Formatter<'_>,
+    cache: Option<&SymbolCache>,
+    options: FormatOptions,
+) -> Result {
+    let mode = FormatMode::Text(options);
+    match &expr.kind {
+        ExprKind::Number(n) => format_number_expr(f, *n, mode),
+
+        ExprKind::Symbol(_) => format_symbol_expr(f, expr, mode, cache),
+
+        ExprKind::FunctionCall { name, args } => {
+            format_function_call_expr(f, name.as_str(), args, mode, cache)
+        }
+
+        ExprKind::Sum(terms) => format_sum_expr(f, terms, mode, cache),
+
+        ExprKind::Product(factors) => format_product_expr(f, factors, mode, cache),
+
+        ExprKind::Div(u, v) => format_div_expr(f, u, v, mode, cache),
+
+        ExprKind::Pow(u, v) => format_pow_expr(f, u, v, mode, cache),
+
+        ExprKind::Derivative { inner, var, order } => {
+            let (open, close) = options.call_brackets();
+            write!(f, "diff{open}")?;
+            format_recursive(f, inner, mode, cache)?;
+            if *order == 1 {
+                write!(f, ", {var}{close}")
+            } else {
+                write!(f, ", {var}, {order}{close}")
+            }
+        }
+
+        // Poly: its terms as a sum, so coefficients follow the options too
+        ExprKind::Poly(poly) => {
+            let terms: Vec<Arc<Expr>> = poly.to_expr_terms().into_iter().map(Arc::new).collect();
+            format_sum_expr(f, &terms, mode, cache)
+        }
+
+        ExprKind::Piecewise { branches, default } => {
+            format_piecewise_expr(f, branches, default, mode, cache)
+        }
     }
 }
 
@@ -1315,13 +1782,64 @@ impl Expr {
     /// Returns a string suitable for rendering in LaTeX math environments.
     #[must_use]
     pub fn to_latex(&self) -> String {
+        self.to_latex_with(&LatexConfig::default())
+    }
+
+    /// Convert the expression to LaTeX format with the given options.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{LatexConfig, symb};
+    /// let x = symb("x");
+    /// let expr = x * x.sin();
+    /// let config = LatexConfig {
+    ///     implicit_multiplication: true,
+    ///     ..LatexConfig::default()
+    /// };
+    /// assert_eq!(expr.to_latex_with(&config), r"x\,\sin\left(x\right)");
+    /// ```
+    #[must_use]
+    pub fn to_latex_with(&self, config: &LatexConfig) -> String {
         let mut cache = SymbolCache::default();
         collect_symbol_names(self, &mut cache);
         format!(
             "{}",
             LatexFormatter {
                 expr: self,
-                cache: Some(&cache)
+                cache: Some(&cache),
+                config: *config
+            }
+        )
+    }
+
+    /// Convert the expression to text with the given options.
+    ///
+    /// With the default options this matches [`Display`] except that nested powers are
+    /// parenthesized, and the result parses back to an equal expression.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{FormatOptions, MulStyle, PowerOperator, symb};
+    /// let x = symb("x");
+    /// let expr = x.pow(-2.0) * 3.58652e-18 + x.sin();
+    /// let python = FormatOptions {
+    ///     power_operator: PowerOperator::DoubleStar,
+    ///     mul_style: MulStyle::Spaced,
+    ///     float_precision: Some(6),
+    ///     ..FormatOptions::default()
+    /// };
+    /// assert_eq!(expr.format_with(&python), "3.58652e-18 * x**-2 + sin(x)");
+    /// ```
+    #[must_use]
+    pub fn format_with(&self, options: &FormatOptions) -> String {
+        let mut cache = SymbolCache::default();
+        collect_symbol_names(self, &mut cache);
+        format!(
+            "{}",
+            TextFormatter {
+                expr: self,
+                cache: Some(&cache),
+                options: *options
             }
         )
     }
@@ -0,0 +1,767 @@
+//! Real domain of an expression in one variable.
+//!
+//! Every node that is only real on part of the line contributes a sign constraint on
+//! one of its arguments: `ln(u)` needs `u > 0`, `sqrt(u)` needs `u >= 0`, a denominator
+//! must not vanish, and so on. Constraints are first reduced through the functions whose
+//! sign is known (`sqrt(u) != 0` is `u != 0`, `ln(u) > 0` is `u > 1`), then solved when
+//! what remains is a ratio of polynomials in the variable with numeric coefficients:
+//! real roots split the line and the sign is sampled between them. The zeros of `sin`
+//! and `cos` of a linear argument become a periodic family of excluded points. Anything
+//! else is kept as a condition, checked pointwise by [`Domain::contains`].
+
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::poly_coefficients::{degree_in, simplified_coefficients};
+use super::{Expr, ExprKind};
+use crate::core::{CustomEvalMap, Symbol};
+use crate::simplification::Simplify;
+
+/// Iterations of bisection when isolating a root of a cubic or higher
+const BISECTION_STEPS: usize = 200;
+
+/// Relative distance under which two points count as the same excluded point
+const POINT_TOLERANCE: f64 = 1e-12;
+
+// =============================================================================
+// INTERVAL
+// =============================================================================
+
+/// A connected piece of the real line, each end open or closed
+///
+/// Infinite ends are always open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    /// Lower end, possibly `-inf`
+    pub lo: f64,
+    /// Upper end, possibly `inf`
+    pub hi: f64,
+    /// Whether `lo` itself belongs to the interval
+    pub lo_closed: bool,
+    /// Whether `hi` itself belongs to the interval
+    pub hi_closed: bool,
+}
+
+impl Interval {
+    /// The whole real line
+    pub const REALS: Self = Self::open(f64::NEG_INFINITY, f64::INFINITY);
+
+    const fn open(lo: f64, hi: f64) -> Self {
+        Self {
+            lo,
+            hi,
+            lo_closed: false,
+            hi_closed: false,
+        }
+    }
+
+    const fn point(at: f64) -> Self {
+        Self {
+            lo: at,
+            hi: at,
+            lo_closed: true,
+            hi_closed: true,
+        }
+    }
+
+    /// Whether `x` lies in the interval
+    #[must_use]
+    #[allow(clippy::float_cmp, reason = "Closed ends are hit exactly")]
+    pub fn contains(&self, x: f64) -> bool {
+        (x > self.lo || (self.lo_closed && x == self.lo))
+            && (x < self.hi || (self.hi_closed && x == self.hi))
+    }
+
+    /// A point strictly inside the interval, used to sample a sign
+    fn sample(&self) -> f64 {
+        match (self.lo.is_finite(), self.hi.is_finite()) {
+            (true, true) => 0.5 * (self.lo + self.hi),
+            (true, false) => self.lo + 1.0,
+            (false, true) => self.hi - 1.0,
+            (false, false) => 0.0,
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let open = if self.lo_closed { '[' } else { '(' };
+        let close = if self.hi_closed { ']' } else { ')' };
+        write!(f, "{open}{}, {}{close}", self.lo, self.hi)
+    }
+}
+
+// =============================================================================
+// CONSTRAINT
+// =============================================================================
+
+/// A sign condition on an expression that the domain could not solve in closed form
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Constraint {
+    /// The expression is `> 0`
+    Positive(Expr),
+    /// The expression is `>= 0`
+    NonNegative(Expr),
+    /// The expression is `!= 0`
+    NonZero(Expr),
+}
+
+impl Constraint {
+    /// The constrained expression
+    #[must_use]
+    pub const fn expr(&self) -> &Expr {
+        match self {
+            Self::Positive(expr) | Self::NonNegative(expr) | Self::NonZero(expr) => expr,
+        }
+    }
+
+    const fn new(sign: Sign, expr: Expr) -> Self {
+        match sign {
+            Sign::Positive => Self::Positive(expr),
+            Sign::NonNegative => Self::NonNegative(expr),
+            Sign::NonZero => Self::NonZero(expr),
+        }
+    }
+
+    const fn sign(&self) -> Sign {
+        match self {
+            Self::Positive(_) => Sign::Positive,
+            Self::NonNegative(_) => Sign::NonNegative,
+            Self::NonZero(_) => Sign::NonZero,
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let relation = match self.sign() {
+            Sign::Positive => ">",
+            Sign::NonNegative => ">=",
+            Sign::NonZero => "!=",
+        };
+        write!(f, "{} {relation} 0", self.expr())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sign {
+    Positive,
+    NonNegative,
+    NonZero,
+}
+
+impl Sign {
+    #[allow(clippy::float_cmp, reason = "Sign tests compare against exact zero")]
+    fn holds(self, value: f64) -> bool {
+        match self {
+            Self::Positive => value > 0.0,
+            Self::NonNegative => value >= 0.0,
+            Self::NonZero => value != 0.0 && !value.is_nan(),
+        }
+    }
+}
+
+// =============================================================================
+// DOMAIN
+// =============================================================================
+
+/// The real points at which an expression in one variable is defined
+///
+/// The points of [`intervals`](Self::intervals), minus the
+/// [`excluded_points`](Self::excluded_points) and the
+/// [`periodic_exclusions`](Self::periodic_exclusions), that also satisfy every
+/// unsolved condition in [`conditions`](Self::conditions).
+/// Built by [`Expr::domain`].
+#[derive(Debug, Clone)]
+pub struct Domain {
+    var: Symbol,
+    intervals: Vec<Interval>,
+    excluded: Vec<f64>,
+    periodic: Vec<(f64, f64)>,
+    conditions: Vec<Constraint>,
+}
+
+impl Domain {
+    /// Whether the expression is defined at `var = x`
+    ///
+    /// Excluded points match within a relative `1e-12`. A condition that does not
+    /// evaluate to a number at `x`, because it holds other free symbols, is taken to hold.
+    #[must_use]
+    pub fn contains(&self, x: f64) -> bool {
+        if x.is_nan() || !self.intervals.iter().any(|interval| interval.contains(x)) {
+            return false;
+        }
+        if self.excluded.iter().any(|&point| same_point(point, x)) {
+            return false;
+        }
+        if self.periodic.iter().any(|&(offset, period)| {
+            let nearest = ((x - offset) / period).round().mul_add(period, offset);
+            same_point(nearest, x)
+        }) {
+            return false;
+        }
+        let point: FxHashMap<u64, f64> = std::iter::once((self.var.id(), x)).collect();
+        self.conditions.iter().all(|condition| {
+            condition
+                .expr()
+                .evaluate(&point, &CustomEvalMap::default())
+                .as_number()
+                .is_none_or(|value| condition.sign().holds(value))
+        })
+    }
+
+    /// Whether no point satisfies the solved constraints
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Sorted, disjoint intervals on which the solved inequalities hold
+    #[must_use]
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Sorted isolated points removed from inside the intervals, such as the zeros of
+    /// a denominator
+    #[must_use]
+    pub fn excluded_points(&self) -> &[f64] {
+        &self.excluded
+    }
+
+    /// Families `(offset, period)` of excluded points `offset + k*period` for every
+    /// integer `k`, from `tan`, `sec`, `cot` and `csc` of a linear argument
+    ///
+    /// The offset lies in `[0, period)`.
+    #[must_use]
+    pub fn periodic_exclusions(&self) -> &[(f64, f64)] {
+        &self.periodic
+    }
+
+    /// Constraints that are not polynomial in the variable, left to pointwise checks
+    #[must_use]
+    pub fn conditions(&self) -> &[Constraint] {
+        &self.conditions
+    }
+
+    fn reals(var: Symbol) -> Self {
+        Self {
+            var,
+            intervals: vec![Interval::REALS],
+            excluded: Vec::new(),
+            periodic: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    fn make_empty(&mut self) {
+        self.intervals.clear();
+        self.excluded.clear();
+        self.periodic.clear();
+        self.conditions.clear();
+    }
+
+    /// Fold excluded points that sit on an interval end into the interval, and drop
+    /// the ones that lie outside every interval
+    #[allow(clippy::float_cmp, reason = "Points and ends come from the same roots")]
+    fn normalize(&mut self) {
+        self.excluded.sort_by(f64::total_cmp);
+        self.excluded.dedup_by(|a, b| same_point(*a, *b));
+        let mut interior = Vec::with_capacity(self.excluded.len());
+        for &point in &self.excluded {
+            let mut on_end = false;
+            for interval in &mut self.intervals {
+                if interval.lo_closed && same_point(interval.lo, point) {
+                    interval.lo_closed = false;
+                    on_end = true;
+                }
+                if interval.hi_closed && same_point(interval.hi, point) {
+                    interval.hi_closed = false;
+                    on_end = true;
+                }
+            }
+            if !on_end
+                && self
+                    .intervals
+                    .iter()
+                    .any(|interval| interval.contains(point))
+            {
+                interior.push(point);
+            }
+        }
+        self.excluded = interior;
+        self.intervals
+            .retain(|interval| interval.lo < interval.hi || interval.lo_closed);
+        if self.intervals.is_empty() {
+            self.make_empty();
+        }
+    }
+}
+
+impl Display for Domain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.intervals.is_empty() {
+            return write!(f, "{{}}");
+        }
+        for (index, interval) in self.intervals.iter().enumerate() {
+            if index > 0 {
+                write!(f, " U ")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        if !self.excluded.is_empty() {
+            write!(f, " \\ {{")?;
+            for (index, point) in self.excluded.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{point}")?;
+            }
+            write!(f, "}}")?;
+        }
+        for (offset, period) in &self.periodic {
+            write!(f, " \\ {{{offset} + {period}*k}}")?;
+        }
+        for (index, condition) in self.conditions.iter().enumerate() {
+            let joint = if index == 0 { " where " } else { " and " };
+            write!(f, "{joint}{condition}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Expr {
+    /// The real points at which `self`, as a function of `var`, is defined
+    ///
+    /// Walks the tree collecting the constraints of each node:
+    ///
+    /// | Node | Constraint |
+    /// |------|------------|
+    /// | `a / d` | `d != 0` |
+    /// | `b^n` | `b != 0` for a negative integer `n`, `b >= 0` for a positive fraction, `b > 0` for a negative fraction or an exponent in `var` |
+    /// | `ln`, `log10`, `log2`, `log(b, u)` | `u > 0` (and `b > 0`, `b != 1`) |
+    /// | `sqrt` | `u >= 0` |
+    /// | `asin`, `acos` | `1 - u^2 >= 0` |
+    /// | `acosh` / `atanh` / `acoth` | `u >= 1` / `1 - u^2 > 0` / `u^2 - 1 > 0` |
+    /// | `asec`, `acsc` | `u^2 - 1 >= 0` |
+    /// | `asech` / `acsch`, `coth`, `csch` | `0 < u <= 1` / `u != 0` |
+    /// | `tan`, `sec` / `cot`, `csc` | `cos(u) != 0` / `sin(u) != 0` |
+    ///
+    /// Constraints that do not involve `var` are assumed to hold, unless they are
+    /// plain numbers that violate them, in which case the domain is empty. Constraints on
+    /// ratios of polynomials in `var` with numeric coefficients are solved, and so are
+    /// `cos(u) != 0` and `sin(u) != 0` for `u` linear in `var`; the rest are kept as
+    /// [`Domain::conditions`].
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let x = symb("domain_doc_x");
+    /// let domain = ((x - 2.0).ln() / (5.0 - x).sqrt()).domain(&x);
+    /// assert_eq!(domain.to_string(), "(2, 5)");
+    /// assert!(domain.contains(3.0));
+    /// assert!(!domain.contains(5.0));
+    ///
+    /// let poles = (1.0 / (x.pow(2.0) - 1.0)).domain(&x);
+    /// assert_eq!(poles.excluded_points(), &[-1.0, 1.0]);
+    /// ```
+    #[must_use]
+    pub fn domain(&self, var: &Symbol) -> Domain {
+        let mut walker = DomainWalker {
+            var_id: var.id(),
+            domain: Domain::reals(*var),
+            seen: FxHashSet::default(),
+        };
+        walker.visit(self);
+        walker.domain.normalize();
+        walker.domain
+    }
+}
+
+// =============================================================================
+// CONSTRAINT COLLECTION
+// =============================================================================
+
+struct DomainWalker<'expr> {
+    var_id: u64,
+    domain: Domain,
+    /// Subtrees already constrained, so shared ones are walked once
+    seen: FxHashSet<&'expr Expr>,
+}
+
+impl<'expr> DomainWalker<'expr> {
+    fn visit(&mut self, expr: &'expr Expr) {
+        if !self.seen.insert(expr) {
+            return;
+        }
+        self.constrain_node(expr);
+        let mut children = Vec::new();
+        Expr::push_children(expr, &mut children);
+        for child in children {
+            self.visit(child);
+        }
+    }
+
+    fn constrain_node(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Div(_, den) => self.require(Sign::NonZero, den),
+            ExprKind::Pow(base, exp) => match exp.as_number() {
+                Some(n) if n.fract() == 0.0 && n < 0.0 => self.require(Sign::NonZero, base),
+                Some(n) if n.fract() == 0.0 => {}
+                Some(n) if n > 0.0 => self.require(Sign::NonNegative, base),
+                Some(_) => self.require(Sign::Positive, base),
+                None if exp.contains_var_id(self.var_id) => self.require(Sign::Positive, base),
+                None => {}
+            },
+            ExprKind::FunctionCall { name, args } => match (name.as_str(), args.as_slice()) {
+                ("ln" | "log10" | "log2", [u]) | ("log", [_, u]) => {
+                    self.require(Sign::Positive, u);
+                    if let [base, _] = args.as_slice() {
+                        self.require(Sign::Positive, base);
+                        self.require(Sign::NonZero, &(&**base - 1.0));
+                    }
+                }
+                ("sqrt", [u]) => self.require(Sign::NonNegative, u),
+                ("asin" | "acos", [u]) => {
+                    self.require(Sign::NonNegative, &(1.0 - (**u).clone().pow(2.0)));
+                }
+                ("acosh", [u]) => self.require(Sign::NonNegative, &(&**u - 1.0)),
+                ("atanh", [u]) => self.require(Sign::Positive, &(1.0 - (**u).clone().pow(2.0))),
+                ("acoth", [u]) => self.require(Sign::Positive, &((**u).clone().pow(2.0) - 1.0)),
+                ("asec" | "acsc", [u]) => {
+                    self.require(Sign::NonNegative, &((**u).clone().pow(2.0) - 1.0));
+                }
+                ("asech", [u]) => {
+                    self.require(Sign::Positive, u);
+                    self.require(Sign::NonNegative, &(1.0 - &**u));
+                }
+                ("acsch" | "coth" | "csch", [u]) => self.require(Sign::NonZero, u),
+                ("tan" | "sec", [u]) => {
+                    self.require(Sign::NonZero, &Expr::func("cos", (**u).clone()));
+                }
+                ("cot" | "csc", [u]) => {
+                    self.require(Sign::NonZero, &Expr::func("sin", (**u).clone()));
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Restrict the domain to the points where `expr` has the sign `sign`
+    fn require(&mut self, sign: Sign, expr: &Expr) {
+        if !expr.contains_var_id(self.var_id) {
+            if expr.as_number().is_some_and(|value| !sign.holds(value)) {
+                self.domain.make_empty();
+            }
+            return;
+        }
+        if self.reduce(sign, expr) {
+            return;
+        }
+        let Some((num, den)) = self.rational_coefficients(expr) else {
+            let condition = Constraint::new(sign, expr.clone());
+            if !self.domain.conditions.contains(&condition) {
+                self.domain.conditions.push(condition);
+            }
+            return;
+        };
+        if sign == Sign::NonZero {
+            if trimmed(&num).is_empty() {
+                self.domain.make_empty();
+            } else {
+                self.domain.excluded.extend(real_roots(&num));
+                self.domain.excluded.extend(real_roots(&den));
+            }
+        } else {
+            let allowed = sign_set(&num, &den, sign);
+            self.domain.intervals = intersect(&self.domain.intervals, &allowed);
+        }
+    }
+
+    /// Pass a constraint through functions of known sign; `true` once it is handled
+    fn reduce(&mut self, sign: Sign, expr: &Expr) -> bool {
+        match &expr.kind {
+            ExprKind::FunctionCall { name, args } => match (name.as_str(), args.as_slice()) {
+                ("exp" | "cosh", [_]) => true,
+                // Where sqrt is defined it is never negative
+                ("sqrt", [u]) => {
+                    if sign != Sign::NonNegative {
+                        self.require(sign, u);
+                    }
+                    true
+                }
+                ("ln", [u]) => {
+                    self.require(sign, &(&**u - 1.0));
+                    true
+                }
+                ("sin", [u]) if sign == Sign::NonZero => self.exclude_trig_zeros(u, 0.0),
+                ("cos", [u]) if sign == Sign::NonZero => self.exclude_trig_zeros(u, FRAC_PI_2),
+                _ => false,
+            },
+            ExprKind::Pow(base, exp) if sign == Sign::NonZero => {
+                let positive = exp.as_number().is_some_and(|n| n > 0.0);
+                if positive {
+                    self.require(sign, base);
+                }
+                positive
+            }
+            ExprKind::Product(factors) if sign == Sign::NonZero => {
+                for factor in factors {
+                    self.require(sign, factor);
+                }
+                true
+            }
+            // The denominator is constrained by the division itself
+            ExprKind::Div(num, _) if sign == Sign::NonZero => {
+                self.require(sign, num);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Exclude the points where `arg` hits `zero + k*pi`, when `arg` is linear in the
+    /// variable; `false` leaves the constraint to the caller
+    fn exclude_trig_zeros(&mut self, arg: &Expr, zero: f64) -> bool {
+        let Some(&[b, a]) = self.polynomial_coefficients(arg).as_deref() else {
+            return false;
+        };
+        if a == 0.0 {
+            return false;
+        }
+        let period = PI / a.abs();
+        let offset = ((zero - b) / a).rem_euclid(period);
+        if !self
+            .domain
+            .periodic
+            .iter()
+            .any(|&(seen, step)| same_point(seen, offset) && same_point(step, period))
+        {
+            self.domain.periodic.push((offset, period));
+        }
+        true
+    }
+
+    /// Numeric coefficients of `expr` as `num / den`, ascending in the variable
+    fn rational_coefficients(&self, expr: &Expr) -> Option<(Vec<f64>, Vec<f64>)> {
+        if let ExprKind::Div(num, den) = &expr.kind {
+            return Some((
+                self.polynomial_coefficients(num)?,
+                self.polynomial_coefficients(den)?,
+            ));
+        }
+        Some((self.polynomial_coefficients(expr)?, vec![1.0]))
+    }
+
+    /// Numeric coefficients of `expr`, ascending in the variable
+    fn polynomial_coefficients(&self, expr: &Expr) -> Option<Vec<f64>> {
+        degree_in(expr, self.var_id)?;
+        simplified_coefficients(expr, self.var_id, &Simplify::new())
+            .ok()?
+            .iter()
+            // Rational coefficients such as `1/4` stay divisions after simplification
+            .map(|c| c.evaluate(&(), &CustomEvalMap::default()).as_number())
+            .collect()
+    }
+}
+
+// =============================================================================
+// POLYNOMIAL SIGNS
+// =============================================================================
+
+fn same_point(a: f64, b: f64) -> bool {
+    (a - b).abs() <= POINT_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+/// `coeffs` without vanishing leading coefficients; empty for the zero polynomial
+fn trimmed(coeffs: &[f64]) -> &[f64] {
+    let len = coeffs
+        .iter()
+        .rposition(|&c| c != 0.0)
+        .map_or(0, |last| last + 1);
+    &coeffs[..len]
+}
+
+fn horner(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().rev().fold(0.0, |acc, &c| acc.mul_add(x, c))
+}
+
+/// Whether `p(x)` is zero up to the rounding of evaluating it
+fn negligible(coeffs: &[f64], x: f64) -> bool {
+    let scale = coeffs
+        .iter()
+        .rev()
+        .fold(0.0, |acc: f64, &c| acc.mul_add(x.abs(), c.abs()));
+    horner(coeffs, x).abs() <= POINT_TOLERANCE * scale
+}
+
+/// Sorted distinct real roots of the polynomial with ascending `coeffs`
+fn real_roots(coeffs: &[f64]) -> Vec<f64> {
+    let coeffs = trimmed(coeffs);
+    // Factor out `x^k` so a root at zero is exact rather than bisected towards
+    let zeros = coeffs.iter().take_while(|&&c| c == 0.0).count();
+    let (mut roots, coeffs) = match coeffs.get(zeros..) {
+        Some(rest) if zeros > 0 && !rest.is_empty() => (vec![0.0], rest),
+        _ => (Vec::new(), coeffs),
+    };
+    roots.extend(match *coeffs {
+        [] | [_] => Vec::new(),
+        [c0, c1] => vec![-c0 / c1],
+        [c0, c1, c2] => quadratic_roots(c0, c1, c2),
+        _ => isolated_roots(coeffs),
+    });
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|a, b| same_point(*a, *b));
+    roots
+}
+
+/// Roots of `c0 + c1*x + c2*x^2`, `c0 != 0`, by the cancellation-free form of the formula
+fn quadratic_roots(c0: f64, c1: f64, c2: f64) -> Vec<f64> {
+    let discriminant = c1.mul_add(c1, -4.0 * c2 * c0);
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    let q = -0.5 * c1.signum().mul_add(discriminant.sqrt(), c1);
+    vec![q / c2, c0 / q]
+}
+
+/// Roots of a cubic or higher: the critical points split the line into monotone
+/// pieces, each holding at most one root, found by bisection
+fn isolated_roots(coeffs: &[f64]) -> Vec<f64> {
+    let (&lead, lower) = coeffs.split_last().unwrap_or((&1.0, &[]));
+    let bound = 1.0
+        + lower
+            .iter()
+            .fold(0.0, |max: f64, c| max.max((c / lead).abs()));
+    let mut power = 0.0;
+    let derivative: Vec<f64> = coeffs[1..]
+        .iter()
+        .map(|c| {
+            power += 1.0;
+            c * power
+        })
+        .collect();
+
+    let mut breaks = vec![-bound];
+    breaks.extend(
+        real_roots(&derivative)
+            .into_iter()
+            .filter(|critical| critical.abs() < bound),
+    );
+    breaks.push(bound);
+
+    let mut roots = Vec::new();
+    for pair in breaks.windows(2) {
+        let (mut lo, mut hi) = (pair[0], pair[1]);
+        if negligible(coeffs, lo) {
+            roots.push(lo);
+            continue;
+        }
+        let lo_negative = horner(coeffs, lo) < 0.0;
+        if negligible(coeffs, hi) || lo_negative == (horner(coeffs, hi) < 0.0) {
+            continue;
+        }
+        for _ in 0..BISECTION_STEPS {
+            let mid = 0.5 * (lo + hi);
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            if (horner(coeffs, mid) < 0.0) == lo_negative {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        roots.push(0.5 * (lo + hi));
+    }
+    roots
+}
+
+/// Where `num / den` has the sign `sign`, as sorted disjoint intervals
+fn sign_set(num: &[f64], den: &[f64], sign: Sign) -> Vec<Interval> {
+    // Each root, marked when it is a pole
+    let mut roots: Vec<(f64, bool)> = real_roots(num)
+        .into_iter()
+        .map(|root| (root, false))
+        .chain(real_roots(den).into_iter().map(|root| (root, true)))
+        .collect();
+    roots.sort_by(|a, b| a.0.total_cmp(&b.0).then(b.1.cmp(&a.1)));
+    roots.dedup_by(|later, kept| same_point(later.0, kept.0));
+
+    let mut pieces = Vec::with_capacity(2 * roots.len() + 1);
+    let mut lower = f64::NEG_INFINITY;
+    for &(root, pole) in &roots {
+        pieces.push(Interval::open(lower, root));
+        if sign == Sign::NonNegative && !pole {
+            pieces.push(Interval::point(root));
+        }
+        lower = root;
+    }
+    pieces.push(Interval::open(lower, f64::INFINITY));
+
+    let mut allowed: Vec<Interval> = Vec::new();
+    for piece in pieces {
+        let included = piece.lo_closed || {
+            let at = piece.sample();
+            sign.holds(horner(num, at) / horner(den, at))
+        };
+        if !included {
+            continue;
+        }
+        match allowed.last_mut() {
+            Some(last)
+                if last.hi.total_cmp(&piece.lo).is_eq() && (last.hi_closed || piece.lo_closed) =>
+            {
+                last.hi = piece.hi;
+                last.hi_closed = piece.hi_closed;
+            }
+            _ => allowed.push(piece),
+        }
+    }
+    allowed
+}
+
+/// Intersection of two sorted lists of disjoint intervals
+#[allow(
+    clippy::float_cmp,
+    reason = "Ends shared by both lists are compared exactly"
+)]
+fn intersect(lhs: &[Interval], rhs: &[Interval]) -> Vec<Interval> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        let (a, b) = (lhs[i], rhs[j]);
+        let (lo, lo_closed) = if a.lo == b.lo {
+            (a.lo, a.lo_closed && b.lo_closed)
+        } else if a.lo > b.lo {
+            (a.lo, a.lo_closed)
+        } else {
+            (b.lo, b.lo_closed)
+        };
+        let (hi, hi_closed) = if a.hi == b.hi {
+            (a.hi, a.hi_closed && b.hi_closed)
+        } else if a.hi < b.hi {
+            (a.hi, a.hi_closed)
+        } else {
+            (b.hi, b.hi_closed)
+        };
+        if lo < hi || (lo == hi && lo_closed && hi_closed) {
+            out.push(Interval {
+                lo,
+                hi,
+                lo_closed,
+                hi_closed,
+            });
+        }
+        if a.hi < b.hi || (a.hi == b.hi && !a.hi_closed) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,241 @@
+//! Interval enclosures of the nodes of an expression.
+//!
+//! Every node gets a closed range that contains each value it takes when the free
+//! symbols range over declared domains. The bounds come from the interval arithmetic
+//! of [`CompiledEvaluator::eval_interval`](crate::CompiledEvaluator::eval_interval),
+//! applied to the tree: `+`, `-`, `*`, `/` and integer powers are rounded outward,
+//! monotone builtins map their endpoints, and everything else (unknown functions,
+//! derivatives, builtins without an interval rule) encloses to the whole line.
+//!
+//! Interval arithmetic does not see that two occurrences of `x` are the same value, so
+//! `sin(x)^2 + cos(x)^2` alone would enclose to `[0, 2]`. Each composite node is
+//! therefore also simplified (domain-safely) and, when that changes it, its enclosure
+//! is intersected with the enclosure of the simplified form. Both contain every value
+//! of the node, so the intersection does too.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Mul, RangeInclusive};
+
+use rustc_hash::FxHashMap;
+
+use super::{Expr, ExprKind};
+use crate::core::Symbol;
+use crate::core::known_symbols::get_constant_value_by_id;
+use crate::evaluator::interval::{Interval as Bounds, call_interval};
+use crate::simplification::Simplify;
+
+/// Closed range `[lo, hi]` enclosing every value of a node
+///
+/// Infinite bounds mean the node is unbounded on that side, for instance a
+/// denominator that can vanish. NaN bounds mark the empty enclosure: no point of the
+/// declared domains is in the node's own domain, as for `ln(x)` with `x` in `[-2, -1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Enclosure {
+    /// Lower bound, possibly `-inf`
+    pub lo: f64,
+    /// Upper bound, possibly `inf`
+    pub hi: f64,
+}
+
+impl Enclosure {
+    const fn from_bounds(bounds: Bounds) -> Self {
+        Self {
+            lo: bounds.lo,
+            hi: bounds.hi,
+        }
+    }
+
+    /// Whether no value is enclosed
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.lo.is_nan()
+    }
+
+    /// Whether both bounds are finite
+    #[must_use]
+    pub const fn is_bounded(&self) -> bool {
+        self.lo.is_finite() && self.hi.is_finite()
+    }
+
+    /// Whether `x` lies in `[lo, hi]`
+    #[must_use]
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// `hi - lo`, infinite for an unbounded enclosure and NaN for an empty one
+    #[must_use]
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Whether every enclosed value is nonzero, so the node is safe as a denominator
+    #[must_use]
+    pub fn excludes_zero(&self) -> bool {
+        self.lo > 0.0 || self.hi < 0.0
+    }
+}
+
+impl Display for Enclosure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+/// Enclosures of the nodes of an expression, built by [`Expr::compute_enclosures`]
+///
+/// Nodes are keyed by [`Expr::id`], so a subexpression shared between several parents
+/// has one entry. The map is external to the tree: the expression is not changed.
+#[derive(Debug, Clone)]
+pub struct EnclosureMap {
+    root: u64,
+    nodes: FxHashMap<u64, Enclosure>,
+}
+
+impl EnclosureMap {
+    /// Enclosure of the whole expression
+    #[must_use]
+    pub fn root(&self) -> Enclosure {
+        self.nodes[&self.root]
+    }
+
+    /// Enclosure of a node of the expression, or `None` if `node` is not part of it
+    #[must_use]
+    pub fn get(&self, node: &Expr) -> Option<Enclosure> {
+        self.nodes.get(&node.id()).copied()
+    }
+
+    /// Number of distinct nodes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the map has no nodes (never the case for a computed map)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Walks the tree bottom-up, recording each node's bounds
+struct Encloser<'dom> {
+    domains: &'dom [(Symbol, RangeInclusive<f64>)],
+    nodes: FxHashMap<u64, Enclosure>,
+    simplify: Simplify,
+}
+
+impl Encloser<'_> {
+    fn enclose(&mut self, expr: &Expr) -> Bounds {
+        if let Some(known) = self.nodes.get(&expr.id()) {
+            return Bounds::new(known.lo, known.hi);
+        }
+        let mut bounds = self.direct(expr, true);
+        if !matches!(expr.kind, ExprKind::Number(_) | ExprKind::Symbol(_))
+            && !bounds.is_empty()
+            && let Ok(simplified) = self.simplify.simplify(expr)
+            && simplified != *expr
+        {
+            let other = self.direct(&simplified, false);
+            bounds = if other.is_empty() {
+                other
+            } else {
+                bounds.restrict(other.lo, other.hi)
+            };
+        }
+        self.nodes.insert(expr.id(), Enclosure::from_bounds(bounds));
+        bounds
+    }
+
+    /// Bounds from the children of `expr`; children are recorded in the map only when
+    /// `record` is set, so the simplified form of a node leaves no entries behind
+    fn direct(&mut self, expr: &Expr, record: bool) -> Bounds {
+        let child = |this: &mut Self, e: &Expr| {
+            if record {
+                this.enclose(e)
+            } else {
+                this.direct(e, false)
+            }
+        };
+        match &expr.kind {
+            ExprKind::Number(n) => Bounds::point(*n),
+            ExprKind::Symbol(s) => {
+                if let Some((_, range)) = self.domains.iter().find(|(sym, _)| sym.id() == s.id()) {
+                    Bounds::new(*range.start(), *range.end())
+                } else if let Some(value) = get_constant_value_by_id(s.id()) {
+                    // The constant's f64 is rounded, so keep its neighbours
+                    Bounds::new(value.next_down(), value.next_up())
+                } else {
+                    Bounds::ENTIRE
+                }
+            }
+            ExprKind::FunctionCall { name, args } => {
+                let args: Vec<Bounds> = args.iter().map(|arg| child(self, arg)).collect();
+                call_interval(name.id(), &args)
+            }
+            ExprKind::Sum(terms) => terms
+                .iter()
+                .map(|term| child(self, term))
+                .reduce(Add::add)
+                .unwrap_or(Bounds::point(0.0)),
+            ExprKind::Product(factors) => factors
+                .iter()
+                .map(|factor| child(self, factor))
+                .reduce(Mul::mul)
+                .unwrap_or(Bounds::point(1.0)),
+            ExprKind::Div(num, den) => child(self, num) / child(self, den),
+            ExprKind::Pow(base, exp) => child(self, base).pow(child(self, exp)),
+            ExprKind::Poly(poly) => child(self, &poly.to_expr()),
+            ExprKind::Piecewise { branches, default } => {
+                let mut bounds = child(self, default);
+                for (_, branch) in branches {
+                    bounds = bounds.hull(child(self, branch));
+                }
+                bounds
+            }
+            ExprKind::Derivative { .. } => Bounds::ENTIRE,
+        }
+    }
+}
+
+impl Expr {
+    /// Enclosure of every node over declared variable domains
+    ///
+    /// Each symbol in `domains` ranges over its interval; other symbols are unbounded
+    /// and known constants (`pi`, `e`, ...) are fixed. The bounds are conservative: each
+    /// value a node takes for inputs in the domains lies in its enclosure, also with
+    /// exact real arithmetic. They are not always tight, because separate occurrences of
+    /// a symbol are treated independently (`x - x` over `[0, 1]` encloses `[-1, 1]`) and
+    /// functions without an interval rule (`tan`, `gamma`, `erf`, ...) give the whole
+    /// line. Each composite node is also simplified with
+    /// [`Simplify::domain_safe`] and its enclosure intersected with that of the
+    /// simplified form, which removes identities such as `sin(x)^2 + cos(x)^2`; that
+    /// costs one simplification per node.
+    ///
+    /// Use the result to prove bounds on an operating range, or to check that a
+    /// denominator cannot vanish with [`Enclosure::excludes_zero`].
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, y) = (symb("x"), symb("y"));
+    /// let den = 1.0 + x.pow(2.0);
+    /// let expr = y / den.clone();
+    /// let bounds = expr.compute_enclosures(&[(x, -1.0..=1.0), (y, 0.0..=1.0)]);
+    /// assert!(bounds.root().hi <= 1.0 + 1e-12);
+    /// assert!(bounds.get(&den).unwrap().excludes_zero());
+    /// ```
+    #[must_use]
+    pub fn compute_enclosures(&self, domains: &[(Symbol, RangeInclusive<f64>)]) -> EnclosureMap {
+        let mut encloser = Encloser {
+            domains,
+            nodes: FxHashMap::default(),
+            simplify: Simplify::new().domain_safe(true),
+        };
+        encloser.enclose(self);
+        EnclosureMap {
+            root: self.id(),
+            nodes: encloser.nodes,
+        }
+    }
+}
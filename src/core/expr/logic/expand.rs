@@ -0,0 +1,364 @@
+//! Full algebraic expansion of products and integer powers.
+//!
+//! An expression is expanded into a map from monomials to numeric coefficients, so like
+//! terms are combined as they appear and `(x+1)^10` never builds a nested product tree.
+//! Powers of univariate sums go through [`Polynomial`] multiplication.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use crate::core::DiffError;
+use crate::core::traits::cancels;
+
+use super::{Expr, ExprKind, Polynomial, expr_cmp, map_piecewise, poly_conversion};
+
+impl Expr {
+    /// Fully expand products and integer powers of sums
+    ///
+    /// Products are distributed over sums, `(a+b)^n` is expanded by its multinomial
+    /// coefficients and powers of products become products of powers, all the way down
+    /// into function arguments. Like terms are combined; nothing else is simplified.
+    /// Negative powers and divisions by sums keep the (expanded) sum as a denominator.
+    ///
+    /// Unlike [`Simplify`](crate::Simplify), which only expands when it expects
+    /// cancellation, this always expands, and the result is often larger than `self`.
+    /// Use [`expand_with_limit`](Self::expand_with_limit) to bound its size.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let (x, y) = (symb("expand_doc_x"), symb("expand_doc_y"));
+    /// let expanded = (x + y).pow(2.0).expand();
+    /// assert_eq!(expanded.coefficient_of(&(x * y)), Expr::number(2.0));
+    /// ```
+    #[must_use]
+    pub fn expand(&self) -> Self {
+        // The term count can never exceed `usize::MAX`
+        self.expand_with_limit(usize::MAX)
+            .unwrap_or_else(|_| self.clone())
+    }
+
+    /// [`expand`](Self::expand), failing once a sum has more than `max_terms` terms
+    ///
+    /// The limit applies to every intermediate sum after like terms are combined, so
+    /// `((x+y+z)^3)` needs a limit of at least 10.
+    ///
+    /// # Errors
+    /// Returns [`DiffError::ExpansionTooLarge`] when a sum exceeds `max_terms` terms.
+    pub fn expand_with_limit(&self, max_terms: usize) -> Result<Self, DiffError> {
+        Ok(expand_terms(self, max_terms)?.into_expr())
+    }
+}
+
+/// Symbolic factors of a term, sorted by base, each with a nonzero exponent
+type Monomial = Vec<(Expr, f64)>;
+
+/// Expanded sum: each distinct monomial with its numeric coefficient
+struct Terms {
+    /// Coefficient of each monomial, keyed by its factors with exponent bits
+    terms: HashMap<Vec<(Expr, u64)>, (Monomial, f64)>,
+    max_terms: usize,
+}
+
+impl Terms {
+    fn new(max_terms: usize) -> Self {
+        Self {
+            terms: HashMap::new(),
+            max_terms,
+        }
+    }
+
+    fn constant(c: f64, max_terms: usize) -> Self {
+        let mut terms = Self::new(max_terms);
+        terms.add(Vec::new(), c);
+        terms
+    }
+
+    /// `base^exp` as a single term
+    fn factor(base: Expr, exp: f64, max_terms: usize) -> Self {
+        let mut terms = Self::new(max_terms);
+        terms.add(vec![(base, exp)], 1.0);
+        terms
+    }
+
+    fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Add `coeff * monomial`, dropping the monomial if its coefficient cancels
+    fn add(&mut self, monomial: Monomial, coeff: f64) {
+        if coeff == 0.0 {
+            return;
+        }
+        let key = monomial
+            .iter()
+            .map(|(base, exp)| (base.clone(), (exp + 0.0).to_bits()))
+            .collect();
+        match self.terms.entry(key) {
+            Entry::Vacant(slot) => {
+                slot.insert((monomial, coeff));
+            }
+            Entry::Occupied(mut slot) => {
+                let old = slot.get().1;
+                let sum = old + coeff;
+                if cancels(sum, old.abs().max(coeff.abs())) {
+                    slot.remove();
+                } else {
+                    slot.get_mut().1 = sum;
+                }
+            }
+        }
+    }
+
+    fn check(self) -> Result<Self, DiffError> {
+        if self.len() > self.max_terms {
+            return Err(DiffError::ExpansionTooLarge {
+                terms: self.len(),
+                limit: self.max_terms,
+            });
+        }
+        Ok(self)
+    }
+
+    fn add_all(mut self, other: Self) -> Result<Self, DiffError> {
+        for (_, (monomial, coeff)) in other.terms {
+            self.add(monomial, coeff);
+        }
+        self.check()
+    }
+
+    fn mul(&self, other: &Self) -> Result<Self, DiffError> {
+        let mut out = Self::new(self.max_terms);
+        for (lhs, a) in self.terms.values() {
+            for (rhs, b) in other.terms.values() {
+                out.add(mul_monomials(lhs, rhs), a * b);
+            }
+            if out.len() > self.max_terms {
+                break;
+            }
+        }
+        out.check()
+    }
+
+    /// `self^n` for an integer `n`; negative powers of sums stay as a factor
+    fn pow(self, n: i64) -> Result<Self, DiffError> {
+        let max_terms = self.max_terms;
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Exponents come from f64 values that were already integers"
+        )]
+        let exp = n as f64;
+        if self.len() == 1 {
+            let mut terms = Self::new(max_terms);
+            for (monomial, coeff) in self.terms.into_values() {
+                let monomial = monomial.into_iter().map(|(b, e)| (b, e * exp)).collect();
+                terms.add(monomial, coeff.powf(exp));
+            }
+            return Ok(terms);
+        }
+        let Ok(n) = u32::try_from(n) else {
+            return Ok(Self::factor(self.into_expr(), exp, max_terms));
+        };
+        let Some(poly) = self.univariate() else {
+            return (0..n).try_fold(Self::constant(1.0, max_terms), |acc, _| acc.mul(&self));
+        };
+        let mut one = Polynomial::zero(poly.base_arc());
+        one.add_term(0, 1.0);
+        let power = (0..n).try_fold(one, |acc, _| {
+            let next = acc.mul(&poly);
+            match next.term_count() {
+                terms if terms > max_terms => Err(DiffError::ExpansionTooLarge {
+                    terms,
+                    limit: max_terms,
+                }),
+                _ => Ok(next),
+            }
+        })?;
+        let mut terms = Self::new(max_terms);
+        for &(pow, coeff) in power.terms() {
+            let monomial = match pow {
+                0 => Vec::new(),
+                _ => vec![(poly.base().as_ref().clone(), f64::from(pow))],
+            };
+            terms.add(monomial, coeff);
+        }
+        terms.check()
+    }
+
+    /// `self` as a polynomial in a single base with natural exponents, if it is one
+    fn univariate(&self) -> Option<Polynomial> {
+        let base = self
+            .terms
+            .values()
+            .find_map(|(monomial, _)| monomial.first().map(|(base, _)| base))?;
+        let mut poly = Polynomial::zero(Arc::new(base.clone()));
+        for (monomial, coeff) in self.terms.values() {
+            let pow = match monomial.as_slice() {
+                [] => 0,
+                [(b, exp)] if b == base => natural(*exp)?,
+                _ => return None,
+            };
+            poly.add_term(pow, *coeff);
+        }
+        Some(poly)
+    }
+
+    /// The expanded sum, as a `Poly` node when it is a univariate polynomial that the
+    /// current [`PolyConversion`](crate::PolyConversion) policy allows
+    fn into_expr(self) -> Expr {
+        if let Some(poly) = self.univariate()
+            && poly_conversion().allows(poly.term_count(), poly.degree())
+        {
+            return Expr::poly(poly);
+        }
+        let terms = self
+            .terms
+            .into_values()
+            .map(|(monomial, coeff)| monomial_expr(monomial, coeff))
+            .collect();
+        Expr::sum(terms)
+    }
+}
+
+/// An exponent that is a natural number
+fn natural(exp: f64) -> Option<u32> {
+    if exp < 1.0 || exp.fract() != 0.0 || exp > f64::from(u32::MAX) {
+        return None;
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Checked to be a positive integer within u32 range"
+    )]
+    Some(exp as u32)
+}
+
+/// An integer exponent, as `i64`
+fn integer(exp: &Expr) -> Option<i64> {
+    let n = exp.as_number()?;
+    if n.fract() != 0.0 || n.abs() > f64::from(u32::MAX) {
+        return None;
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Checked to be an integer within u32 range"
+    )]
+    Some(n as i64)
+}
+
+/// Product of two sorted monomials, adding exponents of equal bases
+fn mul_monomials(lhs: &[(Expr, f64)], rhs: &[(Expr, f64)]) -> Monomial {
+    let mut out: Monomial = Vec::with_capacity(lhs.len() + rhs.len());
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        match expr_cmp(&lhs[i].0, &rhs[j].0) {
+            Ordering::Less => {
+                out.push(lhs[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => {
+                out.push(rhs[j].clone());
+                j += 1;
+            }
+            Ordering::Equal => {
+                let exp = lhs[i].1 + rhs[j].1;
+                if exp != 0.0 {
+                    out.push((lhs[i].0.clone(), exp));
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&lhs[i..]);
+    out.extend_from_slice(&rhs[j..]);
+    out
+}
+
+/// `coeff * prod(base^exp)`, with negative exponents moved to a denominator
+fn monomial_expr(monomial: Monomial, coeff: f64) -> Expr {
+    #[allow(clippy::float_cmp, reason = "Only an exact 1 can be left off")]
+    let power = |base: Expr, exp: f64| {
+        if exp == 1.0 {
+            base
+        } else {
+            Expr::pow_static(base, Expr::number(exp))
+        }
+    };
+    let mut num = vec![Expr::number(coeff)];
+    let mut den = Vec::new();
+    for (base, exp) in monomial {
+        if exp > 0.0 {
+            num.push(power(base, exp));
+        } else {
+            den.push(power(base, -exp));
+        }
+    }
+    let num = Expr::product(num);
+    if den.is_empty() {
+        num
+    } else {
+        Expr::div_expr(num, Expr::product(den))
+    }
+}
+
+/// Expand `expr` into its terms
+fn expand_terms(expr: &Expr, max_terms: usize) -> Result<Terms, DiffError> {
+    let expand = |e: &Expr| expand_terms(e, max_terms);
+    match &expr.kind {
+        ExprKind::Number(n) => Ok(Terms::constant(*n, max_terms)),
+        ExprKind::Symbol(_) => Ok(Terms::factor(expr.clone(), 1.0, max_terms)),
+        ExprKind::Sum(terms) => terms.iter().try_fold(Terms::new(max_terms), |acc, term| {
+            acc.add_all(expand(term)?)
+        }),
+        ExprKind::Product(factors) => factors
+            .iter()
+            .try_fold(Terms::constant(1.0, max_terms), |acc, factor| {
+                acc.mul(&expand(factor)?)
+            }),
+        ExprKind::Div(num, den) => expand(num)?.mul(&expand(den)?.pow(-1)?),
+        ExprKind::Pow(base, exp) => {
+            if let Some(n) = integer(exp) {
+                return expand(base)?.pow(n);
+            }
+            let base = expand(base)?.into_expr();
+            if let Some(exp) = exp.as_number() {
+                return Ok(Terms::factor(base, exp, max_terms));
+            }
+            let exp = expand(exp)?.into_expr();
+            Ok(Terms::factor(Expr::pow_static(base, exp), 1.0, max_terms))
+        }
+        // Expand term by term: rebuilding the sum would merge it back into a `Poly`
+        ExprKind::Poly(poly) => poly
+            .to_expr_terms()
+            .iter()
+            .try_fold(Terms::new(max_terms), |acc, term| {
+                acc.add_all(expand(term)?)
+            }),
+        ExprKind::FunctionCall { name, args } => {
+            let args = args
+                .iter()
+                .map(|arg| Ok(Arc::new(expand(arg)?.into_expr())))
+                .collect::<Result<Vec<_>, DiffError>>()?;
+            let call = Expr::func_multi_from_arcs(name, args);
+            Ok(Terms::factor(call, 1.0, max_terms))
+        }
+        ExprKind::Derivative { inner, var, order } => {
+            let derivative = Expr::derivative(expand(inner)?.into_expr(), var, *order);
+            Ok(Terms::factor(derivative, 1.0, max_terms))
+        }
+        ExprKind::Piecewise { branches, default } => {
+            let mut failure = None;
+            let piecewise = map_piecewise(branches, default, |part| match expand(part) {
+                Ok(terms) => Arc::new(terms.into_expr()),
+                Err(err) => {
+                    failure.get_or_insert(err);
+                    Arc::clone(part)
+                }
+            });
+            failure.map_or_else(|| Ok(Terms::factor(piecewise, 1.0, max_terms)), Err)
+        }
+    }
+}
@@ -4,6 +4,7 @@
 //! coefficient-insensitive term hashing used for like-term grouping.
 
 use super::ExprKind;
+use crate::core::traits::unsigned_zero;
 use rustc_hash::FxHasher;
 use std::hash::{Hash, Hasher};
 
@@ -31,7 +32,7 @@ fn term_hash_u64(mut hash: u64, n: u64) -> u64 {
 
 #[inline]
 fn term_hash_f64(hash: u64, n: f64) -> u64 {
-    term_hash_u64(hash, n.to_bits())
+    term_hash_u64(hash, unsigned_zero(n).to_bits())
 }
 
 #[inline]
@@ -96,6 +97,16 @@ fn hash_term_inner(hash: u64, kind: &ExprKind) -> u64 {
             }
             term_hash_u64(h, acc)
         }
+        ExprKind::Piecewise { branches, default } => {
+            let h = term_hash_byte(hash, b'W');
+            let h = branches.iter().fold(h, |acc, (condition, value)| {
+                let acc = term_hash_byte(acc, condition.op as u8);
+                let acc = term_hash_u64(acc, condition.lhs.hash);
+                let acc = term_hash_u64(acc, condition.rhs.hash);
+                term_hash_u64(acc, value.hash)
+            });
+            term_hash_u64(h, default.hash)
+        }
     }
 }
 
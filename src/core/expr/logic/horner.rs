@@ -0,0 +1,43 @@
+//! Horner form of a polynomial in one symbol.
+
+use crate::core::DiffError;
+use crate::core::Symbol;
+
+use super::Expr;
+
+impl Expr {
+    /// Rewrite `self`, a polynomial in `var`, in Horner form
+    ///
+    /// `3*x^3 + 2*x^2 + x + 5` becomes `((3*x + 2)*x + 1)*x + 5`, which takes one
+    /// multiplication and one addition per degree. Coefficients come from
+    /// [`to_poly_coefficients`](Self::to_poly_coefficients), so they may hold other
+    /// symbols; zero coefficients add nothing.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let x = symb("horner_doc_x");
+    /// let poly = 3.0 * x.pow(3.0) + 2.0 * x.pow(2.0) + x + 5.0;
+    /// let horner = poly.to_horner(&x).unwrap();
+    /// assert_eq!(horner.to_string(), "5 + horner_doc_x*(1 + horner_doc_x*(2 + 3*horner_doc_x))");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnsupportedExpression`] if the expression is not a polynomial
+    /// in `var`.
+    pub fn to_horner(&self, var: &Symbol) -> Result<Self, DiffError> {
+        let mut coeffs = self.to_poly_coefficients(var)?;
+        let Some(leading) = coeffs.pop() else {
+            return Ok(Self::number(0.0));
+        };
+        let x = var.to_expr();
+        Ok(coeffs.into_iter().rev().fold(leading, |acc, coeff| {
+            let scaled = Self::product(vec![acc, x.clone()]);
+            if coeff.is_zero_num() {
+                scaled
+            } else {
+                Self::sum_no_poly(vec![scaled, coeff])
+            }
+        }))
+    }
+}
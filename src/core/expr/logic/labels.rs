@@ -0,0 +1,77 @@
+//! Report labels on subexpressions.
+//!
+//! A label is metadata on a node: equality, hashing, evaluation and code generation
+//! never see it. Differentiation labels the derivative of a labeled node with the
+//! same label, and simplification keeps the label on whatever the node rewrites to.
+//! When a rule merges the node into its parent (`x + 2*x` → `3*x`) the label is gone,
+//! though rules that fold equal factors together (`x*x` → `x^2`) may keep it on the
+//! shared part.
+
+use std::sync::Arc;
+
+use super::Expr;
+
+impl Expr {
+    /// Attach `label` to `expr`, replacing any label it already has
+    ///
+    /// The result is equal to `expr` and evaluates the same way; only
+    /// [`find_labeled`](Self::find_labeled), [`label`](Self::label) and LaTeX output with
+    /// [`LatexConfig::show_labels`](crate::LatexConfig::show_labels) see it.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let x = symb("labeled_doc_x");
+    /// let expr = Expr::labeled("kinetic", x.pow(2.0) / 2.0) + Expr::labeled("potential", x.cos());
+    /// let kinetic = expr.find_labeled("kinetic");
+    /// assert_eq!(kinetic, vec![&(x.pow(2.0) / 2.0)]);
+    /// ```
+    #[must_use]
+    pub fn labeled(label: &str, mut expr: Self) -> Self {
+        expr.label = Some(Arc::from(label));
+        expr
+    }
+
+    /// The label attached with [`labeled`](Self::labeled), if any
+    #[inline]
+    #[must_use]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Every subexpression currently carrying `label`, in pre-order
+    ///
+    /// Polynomial nodes are searched through their base only, since their terms hold
+    /// plain coefficients.
+    #[must_use]
+    pub fn find_labeled(&self, label: &str) -> Vec<&Self> {
+        let mut found = Vec::new();
+        let mut stack: Vec<&Self> = vec![self];
+        while let Some(node) = stack.pop() {
+            if node.label() == Some(label) {
+                found.push(node);
+            }
+            Self::push_children(node, &mut stack);
+        }
+        found
+    }
+
+    /// `expr` without its label, sharing the node when it has none
+    pub(crate) fn unlabeled_arc(expr: Arc<Self>) -> Arc<Self> {
+        if expr.label.is_none() {
+            return expr;
+        }
+        let mut node = Self::unwrap_arc(expr);
+        node.label = None;
+        Arc::new(node)
+    }
+
+    /// Give `self` the label of `source` unless it already has one
+    #[inline]
+    pub(crate) fn inherit_label(mut self, source: &Self) -> Self {
+        if self.label.is_none() {
+            self.label.clone_from(&source.label);
+        }
+        self
+    }
+}
@@ -0,0 +1,383 @@
+//! Limits of expressions at a finite point or at infinity.
+//!
+//! The expression is simplified and the point substituted. When that gives no clean
+//! value, indeterminate forms are rewritten: `0/0` and `∞/∞` quotients by L'Hôpital's
+//! rule, `0·∞` products as quotients, `∞ - ∞` sums by expanding them, and powers with
+//! the variable in the exponent as `exp(g·ln b)`. Every candidate is compared with the
+//! expression evaluated ever closer to the point (at growing magnitudes for infinity),
+//! other symbols held at fixed generic values, so a heuristic that goes wrong gives up
+//! instead of answering.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::Arc;
+
+use rustc_hash::FxHashMap;
+
+use crate::core::known_symbols::is_known_constant_by_id;
+use crate::core::{CustomEvalMap, DiffError, Symbol};
+use crate::diff::Diff;
+use crate::simplification::Simplify;
+
+use super::{Expr, ExprKind};
+
+/// Rewrites (L'Hôpital steps and decompositions) tried before giving up
+const MAX_REWRITES: usize = 8;
+
+/// Terms a sum may expand to when its term limits cancel
+const MAX_EXPANDED_TERMS: usize = 64;
+
+/// Relative distance between a candidate and the settled samples that accepts it
+const TOLERANCE: f64 = 1e-4;
+
+/// Point a limit is taken at, for [`Expr::limit`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum LimitPoint {
+    /// A finite point, approached from both sides where the expression is defined
+    Finite(f64),
+    /// Positive infinity
+    PosInf,
+    /// Negative infinity
+    NegInf,
+}
+
+impl LimitPoint {
+    /// The point as a number, infinities included
+    const fn value(self) -> f64 {
+        match self {
+            Self::Finite(a) => a,
+            Self::PosInf => f64::INFINITY,
+            Self::NegInf => f64::NEG_INFINITY,
+        }
+    }
+
+    /// Samples approaching the point, one sequence per side, farthest first
+    fn approaches(self) -> Vec<Vec<f64>> {
+        let steps = (1..=8).map(|k| 10_f64.powi(k));
+        match self {
+            Self::Finite(a) => {
+                let scale = a.abs().max(1.0);
+                let offsets: Vec<f64> = steps.map(|step| scale / step).collect();
+                vec![
+                    offsets.iter().map(|h| a + h).collect(),
+                    offsets.iter().map(|h| a - h).collect(),
+                ]
+            }
+            Self::PosInf => vec![steps.collect()],
+            Self::NegInf => vec![steps.map(|step| -step).collect()],
+        }
+    }
+}
+
+impl Display for LimitPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Finite(a) => write!(f, "{a}"),
+            Self::PosInf => write!(f, "inf"),
+            Self::NegInf => write!(f, "-inf"),
+        }
+    }
+}
+
+impl Expr {
+    /// Limit of this expression as `var` approaches `point`
+    ///
+    /// Substitutes the point into the simplified expression when that gives a value
+    /// the expression approaches; otherwise rewrites `0/0` and `∞/∞` quotients with
+    /// L'Hôpital's rule, `0·∞` products as quotients and variable exponents through
+    /// `exp(g·ln b)`, taking limits of subexpressions on the way. Infinite limits are
+    /// `Number(±inf)`. A finite point is approached from both sides, except where the
+    /// expression is undefined on one side, as `x*ln(x)` left of `0`. Other symbols are
+    /// treated as generic constants.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, LimitPoint, symb};
+    ///
+    /// let x = symb("x");
+    /// let sinc = Expr::from(x).sin() / x;
+    /// assert_eq!(sinc.limit(&x, LimitPoint::Finite(0.0))?, Expr::number(1.0));
+    ///
+    /// let ratio = (x.pow(2.0) + 1.0) / (2.0 * x.pow(2.0) - 3.0);
+    /// assert_eq!(ratio.limit(&x, LimitPoint::PosInf)?.to_string(), "1/2");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::LimitUndetermined`] when no rewrite finds a value the
+    /// expression approaches, including limits that do not exist (`1/x` at `0`),
+    /// [`DiffError::UnsupportedOperation`] for a non-finite `Finite` point, and
+    /// propagates simplification and differentiation errors.
+    pub fn limit(&self, var: &Symbol, point: LimitPoint) -> Result<Self, DiffError> {
+        if !point.value().is_finite() && matches!(point, LimitPoint::Finite(_)) {
+            return Err(DiffError::UnsupportedOperation(format!(
+                "limit at {point}: use LimitPoint::PosInf or LimitPoint::NegInf"
+            )));
+        }
+        let limiter = Limiter::new(self, *var, point);
+        limiter.limit(self, MAX_REWRITES)?.ok_or_else(|| {
+            let name = var.name().unwrap_or_else(|| var.to_expr().to_string());
+            DiffError::LimitUndetermined {
+                expr: self.to_string(),
+                point: format!("{name} -> {point}"),
+            }
+        })
+    }
+}
+
+/// One limit computation
+struct Limiter {
+    var: Symbol,
+    point: LimitPoint,
+    /// Generic values of the other symbols, by id
+    others: FxHashMap<u64, f64>,
+    simplify: Simplify,
+    diff: Diff,
+}
+
+impl Limiter {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Symbol counts are far below 2^52"
+    )]
+    fn new(expr: &Expr, var: Symbol, point: LimitPoint) -> Self {
+        let mut ids = expr.fold(Vec::new(), |mut ids, node| {
+            if let ExprKind::Symbol(s) = &node.kind
+                && s.id() != var.id()
+                && !is_known_constant_by_id(s.id())
+            {
+                ids.push(s.id());
+            }
+            ids
+        });
+        ids.sort_unstable();
+        ids.dedup();
+        // Distinct values in [1, 2) keep factors like `a - b` away from zero
+        let others = ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                (
+                    id,
+                    1.0 + (i as f64).mul_add(0.618_033_988_749_895, 0.1).fract(),
+                )
+            })
+            .collect();
+        Self {
+            var,
+            point,
+            others,
+            simplify: Simplify::new(),
+            diff: Diff::new(),
+        }
+    }
+
+    /// Value of `expr` with the variable at `at` and other symbols at their generic values
+    fn probe(&self, expr: &Expr, at: f64) -> f64 {
+        let mut values = self.others.clone();
+        values.insert(self.var.id(), at);
+        expr.evaluate(&values, &CustomEvalMap::default())
+            .as_number()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// Value of an expression free of the variable
+    fn value(&self, expr: &Expr) -> f64 {
+        self.probe(expr, f64::NAN)
+    }
+
+    /// Whether `expr` approaches `target` on every side where it is defined
+    fn approaches(&self, expr: &Expr, target: f64) -> bool {
+        let mut defined = false;
+        for side in self.point.approaches() {
+            let samples: Vec<f64> = side.iter().map(|&at| self.probe(expr, at)).collect();
+            if samples.iter().all(|v| v.is_nan()) {
+                continue;
+            }
+            defined = true;
+            let accepted = if target.is_finite() {
+                settled(&samples)
+                    .is_some_and(|v| (v - target).abs() <= TOLERANCE * target.abs().max(1.0))
+            } else {
+                // Growing without bound in the direction of `target`
+                let growing: Vec<f64> = samples.into_iter().filter(|v| !v.is_nan()).collect();
+                growing.windows(2).all(|w| w[1].abs() >= w[0].abs())
+                    && growing.last().is_some_and(|last| {
+                        last.signum() == target.signum()
+                            && last.abs() >= 2.0 * growing[0].abs().max(1.0)
+                    })
+            };
+            if !accepted {
+                return false;
+            }
+        }
+        defined
+    }
+
+    /// `candidate` if `expr` approaches its value
+    fn accept(&self, expr: &Expr, candidate: Expr) -> Option<Expr> {
+        let value = self.value(&candidate);
+        if value.is_nan() || !self.approaches(expr, value) {
+            return None;
+        }
+        if value.is_infinite() || !is_clean(&candidate) {
+            return Some(Expr::number(value));
+        }
+        Some(candidate)
+    }
+
+    fn limit(&self, expr: &Expr, budget: usize) -> Result<Option<Expr>, DiffError> {
+        let expr = self.simplify.simplify(expr)?;
+        if !expr.contains_var_id(self.var.id()) {
+            return Ok(Some(expr));
+        }
+        let at = Expr::number(self.point.value());
+        let substituted = self
+            .simplify
+            .simplify(&expr.substitute_symbol(&self.var, &at))?;
+        if let Some(found) = self.accept(&expr, substituted) {
+            return Ok(Some(found));
+        }
+        let Some(budget) = budget.checked_sub(1) else {
+            return Ok(None);
+        };
+        match &expr.kind {
+            ExprKind::Div(num, den) => self.quotient(&expr, num, den, budget),
+            ExprKind::Product(factors) => self.product(&expr, factors, budget),
+            ExprKind::Pow(base, exponent) if exponent.contains_var_id(self.var.id()) => {
+                let log = (**exponent).clone() * Expr::func("ln", (**base).clone());
+                let Some(inner) = self.limit(&log, budget)? else {
+                    return Ok(None);
+                };
+                Ok(self.accept(&expr, Expr::func("exp", inner)))
+            }
+            ExprKind::Pow(base, exponent) => {
+                let Some(base) = self.limit(base, budget)? else {
+                    return Ok(None);
+                };
+                Ok(self.accept(&expr, Expr::pow_static(base, (**exponent).clone())))
+            }
+            ExprKind::Sum(terms) => {
+                if let Some(limits) = self.limits(terms, budget)?
+                    && let Some(found) = self.accept(&expr, Expr::sum(limits))
+                {
+                    return Ok(Some(found));
+                }
+                // `∞ - ∞` may cancel once the terms are multiplied out
+                match expr.expand_with_limit(MAX_EXPANDED_TERMS) {
+                    Ok(expanded) if expanded != expr => self.limit(&expanded, budget),
+                    _ => Ok(None),
+                }
+            }
+            ExprKind::FunctionCall { name, args } => {
+                let Some(limits) = self.limits(args, budget)? else {
+                    return Ok(None);
+                };
+                let call = Expr::new(ExprKind::FunctionCall {
+                    name: name.clone(),
+                    args: limits.into_iter().map(Arc::new).collect(),
+                });
+                Ok(self.accept(&expr, call))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Limits of every expression in `parts`, or `None` if one is undetermined
+    fn limits(&self, parts: &[Arc<Expr>], budget: usize) -> Result<Option<Vec<Expr>>, DiffError> {
+        let mut limits = Vec::with_capacity(parts.len());
+        for part in parts {
+            let Some(found) = self.limit(part, budget)? else {
+                return Ok(None);
+            };
+            limits.push(found);
+        }
+        Ok(Some(limits))
+    }
+
+    /// Limit of `num / den`, by L'Hôpital's rule for `0/0` and `∞/∞`
+    #[allow(clippy::float_cmp, reason = "Limits of zero are exact zeros")]
+    fn quotient(
+        &self,
+        expr: &Expr,
+        num: &Expr,
+        den: &Expr,
+        budget: usize,
+    ) -> Result<Option<Expr>, DiffError> {
+        let (Some(top), Some(bottom)) = (self.limit(num, budget)?, self.limit(den, budget)?) else {
+            return Ok(None);
+        };
+        let (t, b) = (self.value(&top), self.value(&bottom));
+        let indeterminate = (t == 0.0 && b == 0.0) || (t.is_infinite() && b.is_infinite());
+        if indeterminate {
+            let ratio = Expr::div_expr(
+                self.diff.differentiate(num, &self.var)?,
+                self.diff.differentiate(den, &self.var)?,
+            );
+            return self.limit(&ratio, budget);
+        }
+        if b == 0.0 {
+            // A nonzero numerator over a vanishing denominator diverges, if with one sign
+            return Ok([f64::INFINITY, f64::NEG_INFINITY]
+                .into_iter()
+                .find(|&sign| self.approaches(expr, sign))
+                .map(Expr::number));
+        }
+        Ok(self.accept(expr, Expr::div_expr(top, bottom)))
+    }
+
+    /// Limit of a product, with `0·∞` rewritten as the quotient `∞ / (1/0)`
+    #[allow(clippy::float_cmp, reason = "Limits of zero are exact zeros")]
+    fn product(
+        &self,
+        expr: &Expr,
+        factors: &[Arc<Expr>],
+        budget: usize,
+    ) -> Result<Option<Expr>, DiffError> {
+        let Some(limits) = self.limits(factors, budget)? else {
+            return Ok(None);
+        };
+        let values: Vec<f64> = limits.iter().map(|found| self.value(found)).collect();
+        if !(values.contains(&0.0) && values.iter().any(|v| v.is_infinite())) {
+            return Ok(self.accept(expr, Expr::product(limits)));
+        }
+        let (vanishing, rest): (Vec<_>, Vec<_>) = factors
+            .iter()
+            .zip(&values)
+            .partition(|&(_, &value)| value == 0.0);
+        let gather = |part: Vec<(&Arc<Expr>, &f64)>| {
+            Expr::product(part.into_iter().map(|(f, _)| (**f).clone()).collect())
+        };
+        let (zero, rest) = (gather(vanishing), gather(rest));
+        // Either factor may go under the bar; which one L'Hôpital handles depends on the shape
+        let reciprocal = |e: &Expr| Expr::div_expr(Expr::number(1.0), e.clone());
+        if let Some(found) = self.quotient(expr, &zero, &reciprocal(&rest), budget)? {
+            return Ok(Some(found));
+        }
+        self.quotient(expr, &rest, &reciprocal(&zero), budget)
+    }
+}
+
+/// The sample after the smallest step between consecutive finite samples
+///
+/// Samples too close to the point lose their digits to cancellation (`1 - cos(x)`
+/// rounds to `0`), so the value is read where the sequence has settled, not at its end.
+fn settled(samples: &[f64]) -> Option<f64> {
+    samples
+        .windows(2)
+        .filter(|w| w[0].is_finite() && w[1].is_finite())
+        .min_by(|a, b| (a[1] - a[0]).abs().total_cmp(&(b[1] - b[0]).abs()))
+        .map(|w| w[1])
+}
+
+/// Whether `expr` holds no non-finite number and no division by zero
+fn is_clean(expr: &Expr) -> bool {
+    expr.fold(true, |clean, node| {
+        clean
+            && match &node.kind {
+                ExprKind::Number(n) => n.is_finite(),
+                ExprKind::Div(_, den) => den.as_number() != Some(0.0),
+                _ => true,
+            }
+    })
+}
@@ -0,0 +1,152 @@
+//! Syntactic linearity analysis.
+//!
+//! One pass over the tree collects, for every summand that contains exactly one of the
+//! requested variables as a plain factor, the rest of that summand as a piece of the
+//! variable's coefficient. Any other occurrence of a variable (a power, a function
+//! argument, a denominator, a product of two of them) makes the expression nonlinear.
+
+use std::sync::Arc;
+
+use super::piecewise::piecewise_parts;
+use super::{Expr, ExprKind};
+use crate::core::Symbol;
+
+/// `(variable index, coefficient piece)` pairs; empty when the subtree is free of all
+/// variables
+type Pieces = Vec<(usize, Expr)>;
+
+impl Expr {
+    /// Whether this expression is affine in `var`, i.e. `a*var + b` with `a` and `b`
+    /// free of `var`
+    ///
+    /// The check is syntactic and takes one pass. Forms that are only affine after
+    /// simplification, such as `var^2/var`, count as nonlinear.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (c, t) = (symb("affine_doc_c"), symb("affine_doc_t"));
+    /// assert!((c * t.sin() + t.pow(2.0)).is_affine_in(&c));
+    /// assert!(!(c * t.sin() + t.pow(2.0)).is_affine_in(&t));
+    /// ```
+    #[must_use]
+    pub fn is_affine_in(&self, var: &Symbol) -> bool {
+        var.name()
+            .is_none_or(|name| affine_pieces(self, &[name.as_str()]).is_some())
+    }
+
+    /// The coefficient of each of `vars` (matched by name) if this expression is affine
+    /// in all of them, or `None`
+    ///
+    /// Coefficients are unsimplified sums of the collected pieces; a variable that does
+    /// not occur gets `0`.
+    pub(crate) fn affine_coefficients(&self, vars: &[&str]) -> Option<Vec<Self>> {
+        let mut coefficients = vec![Vec::new(); vars.len()];
+        for (index, piece) in affine_pieces(self, vars)? {
+            coefficients[index].push(piece);
+        }
+        Some(coefficients.into_iter().map(Self::sum).collect())
+    }
+}
+
+fn affine_pieces(expr: &Expr, vars: &[&str]) -> Option<Pieces> {
+    let all_free = |children: &[Arc<Expr>]| -> Option<Pieces> {
+        for child in children {
+            if !affine_pieces(child, vars)?.is_empty() {
+                return None;
+            }
+        }
+        Some(Vec::new())
+    };
+
+    match &expr.kind {
+        ExprKind::Number(_) => Some(Vec::new()),
+        ExprKind::Symbol(symbol) => Some(
+            symbol
+                .name()
+                .and_then(|name| vars.iter().position(|&var| var == name))
+                .map(|index| (index, Expr::number(1.0)))
+                .into_iter()
+                .collect(),
+        ),
+        ExprKind::Sum(terms) => {
+            let mut pieces = Vec::new();
+            for term in terms {
+                pieces.extend(affine_pieces(term, vars)?);
+            }
+            Some(pieces)
+        }
+        ExprKind::Product(factors) => {
+            let mut linear: Option<(usize, Pieces)> = None;
+            for (position, factor) in factors.iter().enumerate() {
+                let pieces = affine_pieces(factor, vars)?;
+                if pieces.is_empty() {
+                    continue;
+                }
+                if linear.is_some() {
+                    return None;
+                }
+                linear = Some((position, pieces));
+            }
+            let Some((position, pieces)) = linear else {
+                return Some(Vec::new());
+            };
+            let others: Vec<Arc<Expr>> = factors
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != position)
+                .map(|(_, factor)| Arc::clone(factor))
+                .collect();
+            Some(
+                pieces
+                    .into_iter()
+                    .map(|(index, piece)| {
+                        let mut scaled = others.clone();
+                        scaled.push(Arc::new(piece));
+                        (index, Expr::product_from_arcs(scaled))
+                    })
+                    .collect(),
+            )
+        }
+        ExprKind::Div(num, den) => {
+            if !affine_pieces(den, vars)?.is_empty() {
+                return None;
+            }
+            let pieces = affine_pieces(num, vars)?;
+            Some(
+                pieces
+                    .into_iter()
+                    .map(|(index, piece)| (index, Expr::div_expr(piece, (**den).clone())))
+                    .collect(),
+            )
+        }
+        ExprKind::Pow(base, exp) => all_free(&[Arc::clone(base), Arc::clone(exp)]),
+        ExprKind::FunctionCall { args, .. } => all_free(args),
+        ExprKind::Derivative { inner, .. } => all_free(std::slice::from_ref(inner)),
+        ExprKind::Piecewise { branches, default } => all_free(
+            &piecewise_parts(branches, default)
+                .cloned()
+                .collect::<Vec<_>>(),
+        ),
+        ExprKind::Poly(poly) => {
+            let pieces = affine_pieces(poly.base(), vars)?;
+            if pieces.is_empty() {
+                return Some(Vec::new());
+            }
+            if poly.terms().iter().any(|&(pow, _)| pow > 1) {
+                return None;
+            }
+            let slope = poly
+                .terms()
+                .iter()
+                .find(|&&(pow, _)| pow == 1)
+                .map_or(0.0, |&(_, coeff)| coeff);
+            Some(
+                pieces
+                    .into_iter()
+                    .map(|(index, piece)| (index, Expr::mul_expr(Expr::number(slope), piece)))
+                    .collect(),
+            )
+        }
+    }
+}
@@ -52,6 +52,7 @@ impl_math_functions_expr! {
     floor => KS.floor, ceil => KS.ceil, round => KS.round,
     // Special functions (single-argument only)
     abs => KS.abs, signum => KS.signum, sinc => KS.sinc,
+    heaviside => KS.heaviside, dirac => KS.dirac,
     erf => KS.erf, erfc => KS.erfc, gamma => KS.gamma, lgamma => KS.lgamma,
     digamma => KS.digamma, trigamma => KS.trigamma, tetragamma => KS.tetragamma,
     zeta => KS.zeta, lambertw => KS.lambertw,
@@ -63,8 +64,17 @@ impl_math_functions_expr! {
 // ArcExprExt trait for ergonomic Arc<Expr> operations
 // =============================================================================
 
+mod sealed {
+    /// Implemented only for `Arc<Expr>`
+    pub trait Sealed {}
+
+    impl Sealed for std::sync::Arc<super::Expr> {}
+}
+
 /// Extension trait for `Arc<Expr>` providing ergonomic math operations.
-pub trait ArcExprExt {
+///
+/// Sealed: it exists to add methods to `Arc<Expr>` and cannot be implemented elsewhere.
+pub trait ArcExprExt: sealed::Sealed {
     /// Raise to a power
     fn pow(&self, exp: impl Into<Expr>) -> Expr;
     /// Sine
@@ -386,6 +396,35 @@ impl Expr {
         Self::func_multi_symbol(get_interned(KS.hermite), vec![n.into(), self])
     }
 
+    /// Smaller of `self` and `other`: `min(self, other)`
+    #[must_use]
+    pub fn min(self, other: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(get_interned(KS.min), vec![self, other.into()])
+    }
+
+    /// Larger of `self` and `other`: `max(self, other)`
+    #[must_use]
+    pub fn max(self, other: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(get_interned(KS.max), vec![self, other.into()])
+    }
+
+    /// `self` limited to `[lo, hi]`: `clamp(self, lo, hi)`
+    #[must_use]
+    pub fn clamp(self, lo: impl Into<Self>, hi: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(get_interned(KS.clamp), vec![self, lo.into(), hi.into()])
+    }
+
+    /// `if_nonzero` where `self` is nonzero, else `if_zero`: `select(self, if_nonzero, if_zero)`
+    ///
+    /// Meant for a 0/1 condition such as `heaviside(x - a)`.
+    #[must_use]
+    pub fn select(self, if_nonzero: impl Into<Self>, if_zero: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(
+            get_interned(KS.select),
+            vec![self, if_nonzero.into(), if_zero.into()],
+        )
+    }
+
     /// Associated Legendre polynomial `P_l^m(self)`
     #[must_use]
     pub fn assoc_legendre(self, l: impl Into<Self>, m: impl Into<Self>) -> Self {
@@ -423,4 +462,10 @@ impl Expr {
     pub fn atan2(self, x: impl Into<Self>) -> Self {
         Self::func_multi_symbol(get_interned(KS.atan2), vec![self, x.into()])
     }
+
+    /// Euclidean norm: hypot(self, y) = sqrt(self^2 + y^2) without overflow
+    #[must_use]
+    pub fn hypot(self, y: impl Into<Self>) -> Self {
+        Self::func_multi_symbol(get_interned(KS.hypot), vec![self, y.into()])
+    }
 }
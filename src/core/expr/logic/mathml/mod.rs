@@ -0,0 +1,139 @@
+//! `MathML` output and input.
+//!
+//! - **Content `MathML`** (`<apply><plus/>…</apply>`) encodes the tree itself and is read
+//!   back by [`Expr::from_mathml`], making it a lossless interchange format.
+//! - **Presentation `MathML`** (`<mrow><mi>x</mi><mo>+</mo>…</mrow>`) encodes the rendered
+//!   layout, with parentheses inserted from operator precedence. It is output only.
+
+mod read;
+mod write;
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::Expr;
+use crate::core::DiffError;
+
+/// Options for [`Expr::to_mathml_with`].
+///
+/// The default matches [`Expr::to_mathml`]: Content `MathML`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MathmlConfig {
+    /// Emit Presentation `MathML` (layout for rendering) instead of Content `MathML`
+    /// (semantics, readable by [`Expr::from_mathml`])
+    pub presentation: bool,
+}
+
+/// Functions with a dedicated Content `MathML` element: (function name, element)
+///
+/// `sqrt`, `log10` and two-argument `log` map to `<root/>` and `<log/>` separately.
+const CONTENT_FUNCTIONS: &[(&str, &str)] = &[
+    ("sin", "sin"),
+    ("cos", "cos"),
+    ("tan", "tan"),
+    ("sec", "sec"),
+    ("csc", "csc"),
+    ("cot", "cot"),
+    ("sinh", "sinh"),
+    ("cosh", "cosh"),
+    ("tanh", "tanh"),
+    ("sech", "sech"),
+    ("csch", "csch"),
+    ("coth", "coth"),
+    ("asin", "arcsin"),
+    ("acos", "arccos"),
+    ("atan", "arctan"),
+    ("asec", "arcsec"),
+    ("acsc", "arccsc"),
+    ("acot", "arccot"),
+    ("asinh", "arcsinh"),
+    ("acosh", "arccosh"),
+    ("atanh", "arctanh"),
+    ("asech", "arcsech"),
+    ("acsch", "arccsch"),
+    ("acoth", "arccoth"),
+    ("exp", "exp"),
+    ("ln", "ln"),
+    ("abs", "abs"),
+    ("floor", "floor"),
+    ("ceil", "ceiling"),
+];
+
+/// `class` attribute marking a sum of `c*base^k` terms that encodes a [`Polynomial`](super::Polynomial)
+const POLY_CLASS: &str = "poly";
+
+const MATH_OPEN: &str = r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#;
+const MATH_CLOSE: &str = "</math>";
+
+/// `MathML` formatter for expressions
+struct MathmlFormatter<'expr> {
+    expr: &'expr Expr,
+    config: MathmlConfig,
+}
+
+impl Display for MathmlFormatter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{MATH_OPEN}")?;
+        if self.config.presentation {
+            write::presentation(f, self.expr)?;
+        } else {
+            write::content(f, self.expr)?;
+        }
+        write!(f, "{MATH_CLOSE}")
+    }
+}
+
+impl Expr {
+    /// Convert the expression to Content `MathML`.
+    ///
+    /// The result is a `<math>` element that [`Expr::from_mathml`] reads back into an
+    /// identical tree.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let x = symb("mathml_doc_x");
+    /// let expr = x.pow(2.0);
+    /// assert_eq!(
+    ///     expr.to_mathml(),
+    ///     r#"<math xmlns="http://www.w3.org/1998/Math/MathML"><apply><power/><ci>mathml_doc_x</ci><cn>2</cn></apply></math>"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_mathml(&self) -> String {
+        self.to_mathml_with(&MathmlConfig::default())
+    }
+
+    /// Convert the expression to Content or Presentation `MathML`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{MathmlConfig, symb};
+    /// let x = symb("mathml_doc_y");
+    /// let config = MathmlConfig { presentation: true };
+    /// assert_eq!(
+    ///     x.sin().to_mathml_with(&config),
+    ///     r#"<math xmlns="http://www.w3.org/1998/Math/MathML"><mrow><mi>sin</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mi>mathml_doc_y</mi><mo>)</mo></mrow></mrow></math>"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_mathml_with(&self, config: &MathmlConfig) -> String {
+        MathmlFormatter {
+            expr: self,
+            config: *config,
+        }
+        .to_string()
+    }
+
+    /// Parse Content `MathML`, as written by [`Expr::to_mathml`].
+    ///
+    /// Accepts a document with or without the `<math>` root, namespace prefixes,
+    /// comments and `<semantics>` wrappers (the first child is used). Nodes are rebuilt
+    /// as written, without re-sorting or folding, so the round trip is exact.
+    ///
+    /// # Errors
+    /// Returns `DiffError` for malformed XML, Presentation `MathML`, unknown elements,
+    /// bad numbers or wrong operand counts.
+    pub fn from_mathml(xml: &str) -> Result<Self, DiffError> {
+        read::parse(xml)
+    }
+}
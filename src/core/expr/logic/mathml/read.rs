@@ -0,0 +1,596 @@
+//! Content `MathML` reader: a small SAX-style scanner feeding a recursive builder.
+//!
+//! The builder recreates nodes with [`Expr::new`] so a tree written by
+//! [`Expr::to_mathml`] comes back unchanged (no re-sorting or folding).
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use super::super::{Expr, ExprKind, Polynomial};
+use super::{CONTENT_FUNCTIONS, POLY_CLASS};
+use crate::DEFAULT_MAX_DEPTH;
+use crate::core::{DiffError, Span};
+
+// ============================================================================
+// Scanner
+// ============================================================================
+
+/// One markup event; element names have any namespace prefix (`m:`) removed
+#[derive(Debug)]
+enum Event<'src> {
+    /// `<name attrs>` or `<name attrs/>` (`empty`)
+    Start {
+        name: &'src str,
+        attrs: &'src str,
+        empty: bool,
+    },
+    /// `</name>`
+    End { name: &'src str },
+    /// Non-blank character data with entities decoded
+    Text(Cow<'src, str>),
+}
+
+struct Scanner<'src> {
+    src: &'src str,
+    pos: usize,
+    /// Byte range of the last event returned by `next`
+    span: Span,
+    peeked: Option<(Event<'src>, Span)>,
+}
+
+impl<'src> Scanner<'src> {
+    const fn new(src: &'src str) -> Self {
+        Self {
+            src,
+            pos: 0,
+            span: Span::empty(),
+            peeked: None,
+        }
+    }
+
+    fn error(&self, msg: impl Into<String>) -> DiffError {
+        DiffError::invalid_syntax_at(msg, self.span)
+    }
+
+    fn peek(&mut self) -> Result<Option<&Event<'src>>, DiffError> {
+        if self.peeked.is_none() {
+            self.peeked = self.scan()?;
+        }
+        Ok(self.peeked.as_ref().map(|(event, _)| event))
+    }
+
+    fn next(&mut self) -> Result<Option<Event<'src>>, DiffError> {
+        let next = match self.peeked.take() {
+            Some(event) => Some(event),
+            None => self.scan()?,
+        };
+        Ok(next.map(|(event, span)| {
+            self.span = span;
+            event
+        }))
+    }
+
+    /// Next event, treating end of input as an error
+    fn expect_event(&mut self) -> Result<Event<'src>, DiffError> {
+        self.next()?.ok_or(DiffError::UnexpectedEndOfInput)
+    }
+
+    #[allow(
+        clippy::string_slice,
+        reason = "Every index comes from `find` on an ASCII delimiter, so it is a char boundary"
+    )]
+    fn scan(&mut self) -> Result<Option<(Event<'src>, Span)>, DiffError> {
+        loop {
+            let rest = &self.src[self.pos..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+            let start = self.pos;
+            if !rest.starts_with('<') {
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+                let raw = rest[..len].trim();
+                if raw.is_empty() {
+                    continue;
+                }
+                let span = Span::new(start, self.pos);
+                return decode_entities(raw)
+                    .map(|text| Some((Event::Text(text), span)))
+                    .ok_or_else(|| DiffError::invalid_syntax_at("Unknown XML entity", span));
+            }
+
+            // Declarations, processing instructions and comments carry no content
+            let skip_to = if rest.starts_with("<!--") {
+                Some("-->")
+            } else if rest.starts_with("<?") {
+                Some("?>")
+            } else if rest.starts_with("<!") {
+                Some(">")
+            } else {
+                None
+            };
+            if let Some(terminator) = skip_to {
+                let len = rest
+                    .find(terminator)
+                    .ok_or(DiffError::UnexpectedEndOfInput)?;
+                self.pos += len + terminator.len();
+                continue;
+            }
+
+            let len = rest.find('>').ok_or(DiffError::UnexpectedEndOfInput)? + 1;
+            self.pos += len;
+            let span = Span::new(start, self.pos);
+            let tag = &rest[1..len - 1];
+            let event = if let Some(name) = tag.strip_prefix('/') {
+                Event::End {
+                    name: local_name(name.trim()),
+                }
+            } else {
+                let (tag, empty) = tag
+                    .strip_suffix('/')
+                    .map_or((tag, false), |inner| (inner, true));
+                let name_len = tag.find(char::is_whitespace).unwrap_or(tag.len());
+                if name_len == 0 {
+                    return Err(DiffError::invalid_syntax_at("Missing element name", span));
+                }
+                Event::Start {
+                    name: local_name(&tag[..name_len]),
+                    attrs: &tag[name_len..],
+                    empty,
+                }
+            };
+            return Ok(Some((event, span)));
+        }
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+/// Value of attribute `key` in a raw attribute list (`a="1" b='2'`)
+#[allow(
+    clippy::string_slice,
+    reason = "Every index comes from `find` on an ASCII delimiter, so it is a char boundary"
+)]
+fn attribute<'src>(attrs: &'src str, key: &str) -> Option<&'src str> {
+    let mut rest = attrs.trim_start();
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let end = value[1..].find(quote)? + 1;
+        if local_name(name) == key {
+            return Some(&value[1..end]);
+        }
+        rest = value[end + 1..].trim_start();
+    }
+    None
+}
+
+/// Decode the predefined and numeric character references; `None` on an unknown one
+#[allow(
+    clippy::string_slice,
+    reason = "Every index comes from `find` on an ASCII delimiter, so it is a char boundary"
+)]
+fn decode_entities(raw: &str) -> Option<Cow<'_, str>> {
+    if !raw.contains('&') {
+        return Some(Cow::Borrowed(raw));
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let semi = rest[amp..].find(';')? + amp;
+        let entity = &rest[amp + 1..semi];
+        let decoded = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else {
+                    entity.strip_prefix('#')?.parse().ok()?
+                };
+                char::from_u32(code)?
+            }
+        };
+        out.push(decoded);
+        rest = &rest[semi + 1..];
+    }
+    out.push_str(rest);
+    Some(Cow::Owned(out))
+}
+
+// ============================================================================
+// Builder
+// ============================================================================
+
+/// Parse a Content `MathML` document (with or without the `<math>` root)
+pub(super) fn parse(xml: &str) -> Result<Expr, DiffError> {
+    let mut reader = Reader {
+        scanner: Scanner::new(xml),
+        depth: 0,
+    };
+    let expr = match reader.scanner.peek()? {
+        None => return Err(DiffError::EmptyFormula),
+        Some(Event::Start {
+            name: "math",
+            empty: false,
+            ..
+        }) => {
+            reader.scanner.next()?;
+            let expr = reader.expr()?;
+            reader.expect_end("math")?;
+            expr
+        }
+        Some(_) => reader.expr()?,
+    };
+    match reader.scanner.next()? {
+        None => Ok(expr),
+        Some(_) => Err(reader.scanner.error("Unexpected content after expression")),
+    }
+}
+
+/// Layout elements that [`Expr::to_mathml_with`] emits in presentation mode
+const PRESENTATION_ELEMENTS: &[&str] = &["mrow", "mi", "mn", "mo", "mfrac", "msup", "msqrt"];
+
+struct Reader<'src> {
+    scanner: Scanner<'src>,
+    depth: usize,
+}
+
+impl<'src> Reader<'src> {
+    fn expect_end(&mut self, element: &str) -> Result<(), DiffError> {
+        match self.scanner.expect_event()? {
+            Event::End { name } if name == element => Ok(()),
+            other => Err(DiffError::UnexpectedToken {
+                expected: format!("</{element}>"),
+                got: describe(&other),
+                span: Some(self.scanner.span),
+            }),
+        }
+    }
+
+    /// Text content up to the closing tag of `element`
+    fn text_of(&mut self, element: &str) -> Result<Cow<'src, str>, DiffError> {
+        match self.scanner.expect_event()? {
+            Event::Text(text) => {
+                self.expect_end(element)?;
+                Ok(text)
+            }
+            other => Err(DiffError::UnexpectedToken {
+                expected: format!("text inside <{element}>"),
+                got: describe(&other),
+                span: Some(self.scanner.span),
+            }),
+        }
+    }
+
+    fn number(&self, text: &str) -> Result<f64, DiffError> {
+        text.trim().parse().map_err(|_e| DiffError::InvalidNumber {
+            value: text.to_owned(),
+            span: Some(self.scanner.span),
+        })
+    }
+
+    /// One expression element
+    fn expr(&mut self) -> Result<Expr, DiffError> {
+        self.depth += 1;
+        if self.depth > DEFAULT_MAX_DEPTH {
+            return Err(DiffError::MaxDepthExceeded);
+        }
+        let result = self.element();
+        self.depth -= 1;
+        result
+    }
+
+    fn element(&mut self) -> Result<Expr, DiffError> {
+        let (name, attrs, empty) = match self.scanner.expect_event()? {
+            Event::Start { name, attrs, empty } => (name, attrs, empty),
+            other => {
+                return Err(DiffError::UnexpectedToken {
+                    expected: "an expression element".to_owned(),
+                    got: describe(&other),
+                    span: Some(self.scanner.span),
+                });
+            }
+        };
+        match (name, empty) {
+            ("cn", false) => {
+                let text = self.text_of("cn")?;
+                Ok(Expr::number(self.number(&text)?))
+            }
+            ("ci", false) => Ok(Expr::symbol(self.text_of("ci")?.trim())),
+            ("pi", true) => Ok(Expr::symbol("pi")),
+            ("exponentiale", true) => Ok(Expr::symbol("e")),
+            ("infinity", true) => Ok(Expr::number(f64::INFINITY)),
+            ("notanumber", true) => Ok(Expr::number(f64::NAN)),
+            ("apply", false) => self.apply(attribute(attrs, "class") == Some(POLY_CLASS)),
+            ("semantics", false) => {
+                let expr = self.expr()?;
+                self.skip_to_end("semantics")?;
+                Ok(expr)
+            }
+            _ if PRESENTATION_ELEMENTS.contains(&name) => Err(self
+                .scanner
+                .error("Presentation MathML describes layout only; expected Content MathML")),
+            _ => Err(DiffError::InvalidToken {
+                token: format!("<{name}>"),
+                span: Some(self.scanner.span),
+            }),
+        }
+    }
+
+    /// Skip annotations and anything else until `</element>`
+    fn skip_to_end(&mut self, element: &str) -> Result<(), DiffError> {
+        let mut open = 0_usize;
+        loop {
+            match self.scanner.expect_event()? {
+                Event::Start { empty: false, .. } => open += 1,
+                Event::End { name } if open == 0 && name == element => return Ok(()),
+                Event::End { .. } => open = open.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Operands until `</apply>`
+    fn operands(&mut self) -> Result<Vec<Expr>, DiffError> {
+        let mut args = Vec::new();
+        while !matches!(self.scanner.peek()?, Some(Event::End { .. }) | None) {
+            args.push(self.expr()?);
+        }
+        self.expect_end("apply")?;
+        Ok(args)
+    }
+
+    fn apply(&mut self, poly: bool) -> Result<Expr, DiffError> {
+        let head_event = self.scanner.expect_event()?;
+        let head_span = self.scanner.span;
+        let (head, empty) = match head_event {
+            Event::Start { name, empty, .. } => (name, empty),
+            other => {
+                return Err(DiffError::UnexpectedToken {
+                    expected: "an operator element".to_owned(),
+                    got: describe(&other),
+                    span: Some(head_span),
+                });
+            }
+        };
+
+        if !empty {
+            return match head {
+                "ci" | "csymbol" => {
+                    let name = self.text_of(head)?;
+                    let args = self.operands()?;
+                    Ok(Expr::func_multi(name.trim(), args))
+                }
+                _ => Err(DiffError::InvalidToken {
+                    token: format!("<{head}>"),
+                    span: Some(head_span),
+                }),
+            };
+        }
+
+        match head {
+            "plus" if poly => self.poly(),
+            "plus" => self.nary(ExprKind::Sum, 0.0),
+            "times" => self.nary(ExprKind::Product, 1.0),
+            "divide" => {
+                let [num, den] = self.exact_operands("divide")?;
+                Ok(Expr::new(ExprKind::Div(Arc::new(num), Arc::new(den))))
+            }
+            "power" => {
+                let [base, exp] = self.exact_operands("power")?;
+                Ok(Expr::new(ExprKind::Pow(Arc::new(base), Arc::new(exp))))
+            }
+            "minus" => self.minus(),
+            "root" => self.root(),
+            "log" => self.log(),
+            "partialdiff" => self.partial_diff(head_span),
+            _ => {
+                let Some((fn_name, _)) = CONTENT_FUNCTIONS.iter().find(|(_, el)| *el == head)
+                else {
+                    return Err(DiffError::InvalidToken {
+                        token: format!("<{head}/>"),
+                        span: Some(head_span),
+                    });
+                };
+                let [arg] = self.exact_operands(head)?;
+                Ok(Expr::func(fn_name, arg))
+            }
+        }
+    }
+
+    /// Exactly `N` operands until `</apply>`
+    fn exact_operands<const N: usize>(&mut self, head: &str) -> Result<[Expr; N], DiffError> {
+        let args = self.operands()?;
+        let got = args.len();
+        args.try_into()
+            .map_err(|_e| DiffError::InvalidFunctionCall {
+                name: head.to_owned(),
+                expected: N,
+                got,
+            })
+    }
+
+    fn nary(
+        &mut self,
+        kind: fn(Vec<Arc<Expr>>) -> ExprKind,
+        identity: f64,
+    ) -> Result<Expr, DiffError> {
+        let mut args = self.operands()?;
+        Ok(match args.len() {
+            0 => Expr::number(identity),
+            1 => args.pop().expect("one operand"),
+            _ => Expr::new(kind(args.into_iter().map(Arc::new).collect())),
+        })
+    }
+
+    fn minus(&mut self) -> Result<Expr, DiffError> {
+        let mut args = self.operands()?;
+        match args.len() {
+            1 => {
+                let arg = args.pop().expect("one operand");
+                Ok(arg
+                    .as_number()
+                    .map_or_else(|| arg.clone().negate(), |n| Expr::number(-n)))
+            }
+            2 => {
+                let rhs = args.pop().expect("two operands");
+                let lhs = args.pop().expect("two operands");
+                Ok(Expr::sum(vec![lhs, rhs.negate()]))
+            }
+            got => Err(DiffError::InvalidFunctionCall {
+                name: "minus".to_owned(),
+                expected: 1,
+                got,
+            }),
+        }
+    }
+
+    /// Qualifier element `<qualifier>expr</qualifier>` if one comes next
+    fn qualifier(&mut self, qualifier: &str) -> Result<Option<Expr>, DiffError> {
+        if !matches!(self.scanner.peek()?, Some(Event::Start { name, empty: false, .. }) if *name == qualifier)
+        {
+            return Ok(None);
+        }
+        self.scanner.next()?;
+        let expr = self.expr()?;
+        self.expect_end(qualifier)?;
+        Ok(Some(expr))
+    }
+
+    fn root(&mut self) -> Result<Expr, DiffError> {
+        let degree = self.qualifier("degree")?;
+        let [arg] = self.exact_operands("root")?;
+        Ok(match degree {
+            None => Expr::func("sqrt", arg),
+            Some(degree) if degree.as_number() == Some(2.0) => Expr::func("sqrt", arg),
+            Some(degree) if degree.as_number() == Some(3.0) => Expr::func("cbrt", arg),
+            Some(degree) => Expr::pow_static(arg, Expr::div_expr(Expr::number(1.0), degree)),
+        })
+    }
+
+    fn log(&mut self) -> Result<Expr, DiffError> {
+        let base = self.qualifier("logbase")?;
+        let [arg] = self.exact_operands("log")?;
+        Ok(match base {
+            None => Expr::func("log10", arg),
+            Some(base) => Expr::func_multi("log", vec![base, arg]),
+        })
+    }
+
+    fn partial_diff(&mut self, head_span: Span) -> Result<Expr, DiffError> {
+        let mut vars = Vec::new();
+        while matches!(
+            self.scanner.peek()?,
+            Some(Event::Start {
+                name: "bvar",
+                empty: false,
+                ..
+            })
+        ) {
+            self.scanner.next()?;
+            let var = match self.scanner.expect_event()? {
+                Event::Start {
+                    name: "ci",
+                    empty: false,
+                    ..
+                } => self.text_of("ci")?.trim().to_owned(),
+                other => {
+                    return Err(DiffError::UnexpectedToken {
+                        expected: "<ci>".to_owned(),
+                        got: describe(&other),
+                        span: Some(self.scanner.span),
+                    });
+                }
+            };
+            let order = match self.qualifier("degree")? {
+                None => 1,
+                Some(degree) => degree
+                    .as_number()
+                    .filter(|n| n.fract() == 0.0 && *n >= 1.0 && *n <= f64::from(u32::MAX))
+                    .map(|n| {
+                        #[allow(
+                            clippy::cast_possible_truncation,
+                            clippy::cast_sign_loss,
+                            reason = "Checked to be a positive integer within u32 range"
+                        )]
+                        let order = n as u32;
+                        order
+                    })
+                    .ok_or_else(|| {
+                        self.scanner
+                            .error("Derivative degree must be a positive integer")
+                    })?,
+            };
+            self.expect_end("bvar")?;
+            vars.push((var, order));
+        }
+        if vars.is_empty() {
+            return Err(DiffError::invalid_syntax_at(
+                "<partialdiff/> requires a <bvar>",
+                head_span,
+            ));
+        }
+        let [inner] = self.exact_operands("partialdiff")?;
+        // Innermost first: the last bound variable applies to the operand directly
+        Ok(vars.into_iter().rev().fold(inner, |inner, (var, order)| {
+            Expr::derivative(inner, var, order)
+        }))
+    }
+
+    /// Terms of a polynomial written as `c*base^k`, each base identical
+    fn poly(&mut self) -> Result<Expr, DiffError> {
+        let span = self.scanner.span;
+        let malformed = || DiffError::invalid_syntax_at("Malformed polynomial term", span);
+        let mut poly: Option<Polynomial> = None;
+        for term in self.operands()? {
+            let ExprKind::Product(factors) = &term.kind else {
+                return Err(malformed());
+            };
+            let [coeff, power] = factors.as_slice() else {
+                return Err(malformed());
+            };
+            let (Some(coeff), ExprKind::Pow(base, pow)) = (coeff.as_number(), &power.kind) else {
+                return Err(malformed());
+            };
+            let pow = pow
+                .as_number()
+                .filter(|p| p.fract() == 0.0 && *p >= 0.0 && *p <= f64::from(u32::MAX))
+                .ok_or_else(malformed)?;
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "Checked to be a non-negative integer within u32 range"
+            )]
+            let pow = pow as u32;
+            let poly = poly.get_or_insert_with(|| Polynomial::zero(Arc::clone(base)));
+            if **poly.base() != **base {
+                return Err(malformed());
+            }
+            poly.add_term(pow, coeff);
+        }
+        poly.map(Expr::poly).ok_or_else(malformed)
+    }
+}
+
+/// Short description of an event for error messages
+fn describe(event: &Event<'_>) -> String {
+    match event {
+        Event::Start {
+            name, empty: true, ..
+        } => format!("<{name}/>"),
+        Event::Start { name, .. } => format!("<{name}>"),
+        Event::End { name } => format!("</{name}>"),
+        Event::Text(text) => format!("text '{text}'"),
+    }
+}
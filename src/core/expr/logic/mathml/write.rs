@@ -0,0 +1,439 @@
+//! `MathML` writers: Content markup (lossless) and Presentation markup (for display).
+
+use std::fmt::{Formatter, Result};
+use std::sync::Arc;
+
+use super::super::piecewise::{CompareOp, Condition};
+use super::super::{Expr, ExprKind, Polynomial};
+use super::{CONTENT_FUNCTIONS, POLY_CLASS};
+use crate::core::InternedSymbol;
+
+// ============================================================================
+// Shared helpers
+// ============================================================================
+
+/// Shortest text that parses back to exactly `n` (`2`, `0.1`, `1e-30`)
+pub(super) fn number_text(n: f64) -> String {
+    let text = format!("{n:?}");
+    text.strip_suffix(".0")
+        .map_or_else(|| text.clone(), str::to_owned)
+}
+
+/// Write `text` with the XML special characters escaped
+fn write_escaped(f: &mut Formatter<'_>, text: &str) -> Result {
+    for c in text.chars() {
+        match c {
+            '&' => write!(f, "&amp;")?,
+            '<' => write!(f, "&lt;")?,
+            '>' => write!(f, "&gt;")?,
+            '"' => write!(f, "&quot;")?,
+            _ => write!(f, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Name of a symbol, `$id` for anonymous ones (same as `Display`)
+fn symbol_name(symbol: &InternedSymbol) -> String {
+    symbol.to_string()
+}
+
+// ============================================================================
+// Content MathML
+// ============================================================================
+
+/// Write `expr` as Content `MathML`
+pub(super) fn content(f: &mut Formatter<'_>, expr: &Expr) -> Result {
+    match &expr.kind {
+        ExprKind::Number(n) => content_number(f, *n),
+        ExprKind::Symbol(s) => match s.name() {
+            Some("pi") => write!(f, "<pi/>"),
+            Some("e") => write!(f, "<exponentiale/>"),
+            _ => {
+                write!(f, "<ci>")?;
+                write_escaped(f, &symbol_name(s))?;
+                write!(f, "</ci>")
+            }
+        },
+        ExprKind::FunctionCall { name, args } => content_call(f, name, args),
+        ExprKind::Sum(terms) => content_apply(f, "<plus/>", terms),
+        ExprKind::Product(factors) => content_apply(f, "<times/>", factors),
+        ExprKind::Div(num, den) => {
+            content_apply(f, "<divide/>", &[Arc::clone(num), Arc::clone(den)])
+        }
+        ExprKind::Pow(base, exp) => {
+            content_apply(f, "<power/>", &[Arc::clone(base), Arc::clone(exp)])
+        }
+        ExprKind::Derivative { inner, var, order } => {
+            write!(f, "<apply><partialdiff/><bvar><ci>")?;
+            write_escaped(f, &symbol_name(var))?;
+            write!(f, "</ci>")?;
+            if *order != 1 {
+                write!(f, "<degree><cn>{order}</cn></degree>")?;
+            }
+            write!(f, "</bvar>")?;
+            content(f, inner)?;
+            write!(f, "</apply>")
+        }
+        ExprKind::Poly(poly) => content_poly(f, poly),
+        ExprKind::Piecewise { branches, default } => content_piecewise(f, branches, default),
+    }
+}
+
+fn content_piecewise(
+    f: &mut Formatter<'_>,
+    branches: &[(Condition, Arc<Expr>)],
+    default: &Expr,
+) -> Result {
+    write!(f, "<piecewise>")?;
+    for (condition, value) in branches {
+        let op = match condition.op {
+            CompareOp::Lt => "<lt/>",
+            CompareOp::Le => "<leq/>",
+            CompareOp::Gt => "<gt/>",
+            CompareOp::Ge => "<geq/>",
+            CompareOp::Eq => "<eq/>",
+        };
+        write!(f, "<piece>")?;
+        content(f, value)?;
+        write!(f, "<apply>{op}")?;
+        content(f, &condition.lhs)?;
+        content(f, &condition.rhs)?;
+        write!(f, "</apply></piece>")?;
+    }
+    if !default.as_number().is_some_and(f64::is_nan) {
+        write!(f, "<otherwise>")?;
+        content(f, default)?;
+        write!(f, "</otherwise>")?;
+    }
+    write!(f, "</piecewise>")
+}
+
+fn content_number(f: &mut Formatter<'_>, n: f64) -> Result {
+    if n.is_nan() {
+        write!(f, "<notanumber/>")
+    } else if n == f64::INFINITY {
+        write!(f, "<infinity/>")
+    } else if n == f64::NEG_INFINITY {
+        write!(f, "<apply><minus/><infinity/></apply>")
+    } else {
+        write!(f, "<cn>{}</cn>", number_text(n))
+    }
+}
+
+fn content_apply(f: &mut Formatter<'_>, head: &str, args: &[Arc<Expr>]) -> Result {
+    write!(f, "<apply>{head}")?;
+    for arg in args {
+        content(f, arg)?;
+    }
+    write!(f, "</apply>")
+}
+
+fn content_call(f: &mut Formatter<'_>, name: &InternedSymbol, args: &[Arc<Expr>]) -> Result {
+    match (name.as_str(), args) {
+        ("sqrt", [arg]) => content_apply(f, "<root/>", std::slice::from_ref(arg)),
+        ("log10", [arg]) => content_apply(f, "<log/>", std::slice::from_ref(arg)),
+        ("log", [base, arg]) => {
+            write!(f, "<apply><log/><logbase>")?;
+            content(f, base)?;
+            write!(f, "</logbase>")?;
+            content(f, arg)?;
+            write!(f, "</apply>")
+        }
+        (fn_name, [arg]) if let Some((_, element)) = content_function(fn_name) => {
+            content_apply(f, &format!("<{element}/>"), std::slice::from_ref(arg))
+        }
+        (fn_name, _) => {
+            write!(f, "<apply><ci type=\"function\">")?;
+            write_escaped(f, fn_name)?;
+            write!(f, "</ci>")?;
+            for arg in args {
+                content(f, arg)?;
+            }
+            write!(f, "</apply>")
+        }
+    }
+}
+
+fn content_function(name: &str) -> Option<&'static (&'static str, &'static str)> {
+    CONTENT_FUNCTIONS
+        .iter()
+        .find(|(fn_name, _)| *fn_name == name)
+}
+
+/// A polynomial is a tagged sum of `c*base^k` terms, so the reader can rebuild it exactly
+fn content_poly(f: &mut Formatter<'_>, poly: &Polynomial) -> Result {
+    write!(f, "<apply class=\"{POLY_CLASS}\"><plus/>")?;
+    // The zero polynomial still records its base through a zero term
+    let zero = [(0, 0.0)];
+    let terms = if poly.terms().is_empty() {
+        &zero[..]
+    } else {
+        poly.terms()
+    };
+    for &(pow, coeff) in terms {
+        write!(f, "<apply><times/>")?;
+        content_number(f, coeff)?;
+        write!(f, "<apply><power/>")?;
+        content(f, poly.base())?;
+        write!(f, "<cn>{pow}</cn></apply></apply>")?;
+    }
+    write!(f, "</apply>")
+}
+
+// ============================================================================
+// Presentation MathML
+// ============================================================================
+
+/// Write `expr` as Presentation `MathML`
+pub(super) fn presentation(f: &mut Formatter<'_>, expr: &Expr) -> Result {
+    match &expr.kind {
+        ExprKind::Number(n) => presentation_number(f, *n),
+        ExprKind::Symbol(s) => {
+            write!(f, "<mi>")?;
+            match s.name() {
+                Some("pi") => write!(f, "&#x3C0;")?,
+                Some(name) => write_escaped(
+                    f,
+                    super::super::display::greek_to_unicode(name).unwrap_or(name),
+                )?,
+                None => write_escaped(f, &symbol_name(s))?,
+            }
+            write!(f, "</mi>")
+        }
+        ExprKind::FunctionCall { name, args } => presentation_call(f, name.as_str(), args),
+        ExprKind::Sum(terms) => presentation_sum(f, terms),
+        ExprKind::Product(factors) => presentation_product(f, factors),
+        ExprKind::Div(num, den) => {
+            write!(f, "<mfrac>")?;
+            presentation(f, num)?;
+            presentation(f, den)?;
+            write!(f, "</mfrac>")
+        }
+        ExprKind::Pow(base, exp) => {
+            write!(f, "<msup>")?;
+            if needs_parens_as_base(base) {
+                parenthesized(f, base)?;
+            } else {
+                presentation(f, base)?;
+            }
+            presentation(f, exp)?;
+            write!(f, "</msup>")
+        }
+        ExprKind::Derivative { inner, var, order } => {
+            write!(f, "<mrow><mfrac>")?;
+            if *order == 1 {
+                write!(f, "<mo>&#x2202;</mo><mrow><mo>&#x2202;</mo><mi>")?;
+                write_escaped(f, &symbol_name(var))?;
+                write!(f, "</mi></mrow>")?;
+            } else {
+                write!(f, "<msup><mo>&#x2202;</mo><mn>{order}</mn></msup>")?;
+                write!(f, "<mrow><mo>&#x2202;</mo><msup><mi>")?;
+                write_escaped(f, &symbol_name(var))?;
+                write!(f, "</mi><mn>{order}</mn></msup></mrow>")?;
+            }
+            write!(f, "</mfrac>")?;
+            if is_atom(inner) {
+                presentation(f, inner)?;
+            } else {
+                parenthesized(f, inner)?;
+            }
+            write!(f, "</mrow>")
+        }
+        ExprKind::Poly(poly) => presentation(f, &poly.to_expr()),
+        ExprKind::Piecewise { branches, default } => presentation_piecewise(f, branches, default),
+    }
+}
+
+fn presentation_piecewise(
+    f: &mut Formatter<'_>,
+    branches: &[(Condition, Arc<Expr>)],
+    default: &Expr,
+) -> Result {
+    write!(f, "<mrow><mo>{{</mo><mtable>")?;
+    for (condition, value) in branches {
+        let op = match condition.op {
+            CompareOp::Lt => "&lt;",
+            CompareOp::Le => "&#x2264;",
+            CompareOp::Gt => "&gt;",
+            CompareOp::Ge => "&#x2265;",
+            CompareOp::Eq => "=",
+        };
+        write!(f, "<mtr><mtd>")?;
+        presentation(f, value)?;
+        write!(f, "</mtd><mtd><mrow><mtext>if&#xA0;</mtext>")?;
+        presentation(f, &condition.lhs)?;
+        write!(f, "<mo>{op}</mo>")?;
+        presentation(f, &condition.rhs)?;
+        write!(f, "</mrow></mtd></mtr>")?;
+    }
+    if !default.as_number().is_some_and(f64::is_nan) {
+        write!(f, "<mtr><mtd>")?;
+        presentation(f, default)?;
+        write!(f, "</mtd><mtd><mtext>otherwise</mtext></mtd></mtr>")?;
+    }
+    write!(f, "</mtable></mrow>")
+}
+
+fn presentation_number(f: &mut Formatter<'_>, n: f64) -> Result {
+    if n.is_nan() {
+        write!(f, "<mi>NaN</mi>")
+    } else if n.is_infinite() {
+        let sign = if n < 0.0 { "<mo>-</mo>" } else { "" };
+        write!(f, "<mrow>{sign}<mi>&#x221E;</mi></mrow>")
+    } else if n < 0.0 {
+        write!(f, "<mrow><mo>-</mo><mn>{}</mn></mrow>", number_text(-n))
+    } else {
+        write!(f, "<mn>{}</mn>", number_text(n))
+    }
+}
+
+fn parenthesized(f: &mut Formatter<'_>, expr: &Expr) -> Result {
+    write!(f, "<mrow><mo>(</mo>")?;
+    presentation(f, expr)?;
+    write!(f, "<mo>)</mo></mrow>")
+}
+
+/// Nodes that never need parentheses as an operand
+fn is_atom(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Number(n) => *n >= 0.0 || n.is_nan(),
+        ExprKind::Symbol(_) | ExprKind::FunctionCall { .. } => true,
+        _ => false,
+    }
+}
+
+fn needs_parens_as_base(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Number(n) => *n < 0.0,
+        ExprKind::Symbol(_) | ExprKind::FunctionCall { .. } => false,
+        _ => true,
+    }
+}
+
+/// Split a negative sum term into `(|coefficient|, remaining factors)`
+fn split_sign(expr: &Expr) -> Option<(f64, &[Arc<Expr>])> {
+    match &expr.kind {
+        ExprKind::Number(n) if *n < 0.0 => Some((-n, &[])),
+        ExprKind::Product(factors) => split_sign_factors(factors),
+        _ => None,
+    }
+}
+
+fn presentation_sum(f: &mut Formatter<'_>, terms: &[Arc<Expr>]) -> Result {
+    write!(f, "<mrow>")?;
+    for (i, term) in terms.iter().enumerate() {
+        if let Some((magnitude, rest)) = split_sign(term) {
+            write!(f, "<mo>-</mo>")?;
+            magnitude_product(f, magnitude, rest)?;
+        } else {
+            if i > 0 {
+                write!(f, "<mo>+</mo>")?;
+            }
+            if matches!(term.kind, ExprKind::Sum(_) | ExprKind::Poly(_)) {
+                parenthesized(f, term)?;
+            } else {
+                presentation(f, term)?;
+            }
+        }
+    }
+    write!(f, "</mrow>")
+}
+
+fn presentation_product(f: &mut Formatter<'_>, factors: &[Arc<Expr>]) -> Result {
+    if let Some((magnitude, rest)) = split_sign_factors(factors) {
+        write!(f, "<mrow><mo>-</mo>")?;
+        magnitude_product(f, magnitude, rest)?;
+        return write!(f, "</mrow>");
+    }
+    write!(f, "<mrow>")?;
+    factor_list(f, factors)?;
+    write!(f, "</mrow>")
+}
+
+/// Split a leading negative coefficient off a product's factors
+fn split_sign_factors(factors: &[Arc<Expr>]) -> Option<(f64, &[Arc<Expr>])> {
+    match factors.first().map(|c| &c.kind) {
+        Some(ExprKind::Number(n)) if *n < 0.0 => Some((-n, &factors[1..])),
+        _ => None,
+    }
+}
+
+/// Write `magnitude * rest`, dropping a unit magnitude
+#[allow(
+    clippy::float_cmp,
+    reason = "Only an exact unit coefficient is implicit"
+)]
+fn magnitude_product(f: &mut Formatter<'_>, magnitude: f64, rest: &[Arc<Expr>]) -> Result {
+    match rest {
+        [] => write!(f, "<mn>{}</mn>", number_text(magnitude)),
+        [single] if magnitude == 1.0 => factor(f, single),
+        _ if magnitude == 1.0 => {
+            write!(f, "<mrow>")?;
+            factor_list(f, rest)?;
+            write!(f, "</mrow>")
+        }
+        _ => {
+            write!(f, "<mrow><mn>{}</mn>", number_text(magnitude))?;
+            write!(f, "{}", separator(rest.first().map(AsRef::as_ref), true))?;
+            factor_list(f, rest)?;
+            write!(f, "</mrow>")
+        }
+    }
+}
+
+/// Numeric coefficients are juxtaposed (invisible times); other factors get a dot,
+/// and a number following anything gets an explicit times sign
+fn separator(next: Option<&Expr>, after_number: bool) -> &'static str {
+    match next.map(|e| &e.kind) {
+        Some(ExprKind::Number(_)) => "<mo>&#xD7;</mo>",
+        _ if after_number => "<mo>&#x2062;</mo>",
+        _ => "<mo>&#x22C5;</mo>",
+    }
+}
+
+fn factor_list(f: &mut Formatter<'_>, factors: &[Arc<Expr>]) -> Result {
+    for (i, item) in factors.iter().enumerate() {
+        if i > 0 {
+            let after_number = matches!(factors[i - 1].kind, ExprKind::Number(_));
+            write!(f, "{}", separator(Some(item), after_number))?;
+        }
+        factor(f, item)?;
+    }
+    Ok(())
+}
+
+fn factor(f: &mut Formatter<'_>, item: &Expr) -> Result {
+    match &item.kind {
+        ExprKind::Sum(_) | ExprKind::Product(_) | ExprKind::Poly(_) => parenthesized(f, item),
+        ExprKind::Number(n) if *n < 0.0 => parenthesized(f, item),
+        _ => presentation(f, item),
+    }
+}
+
+fn presentation_call(f: &mut Formatter<'_>, name: &str, args: &[Arc<Expr>]) -> Result {
+    match (name, args) {
+        ("sqrt", [arg]) => {
+            write!(f, "<msqrt>")?;
+            presentation(f, arg)?;
+            write!(f, "</msqrt>")
+        }
+        ("abs", [arg]) => {
+            write!(f, "<mrow><mo>|</mo>")?;
+            presentation(f, arg)?;
+            write!(f, "<mo>|</mo></mrow>")
+        }
+        _ => {
+            write!(f, "<mrow><mi>")?;
+            write_escaped(f, name)?;
+            write!(f, "</mi><mo>&#x2061;</mo><mrow><mo>(</mo>")?;
+            for (i, arg) in args.iter().enumerate() {
+                if i > 0 {
+                    write!(f, "<mo>,</mo>")?;
+                }
+                presentation(f, arg)?;
+            }
+            write!(f, "<mo>)</mo></mrow></mrow>")
+        }
+    }
+}
@@ -12,17 +12,46 @@ pub(super) mod operators;
 pub(super) mod ordering;
 
 // display is pub(in crate::core) so upper modules can wire the Display impl
+mod cse;
 pub(in crate::core) mod display;
+mod domain;
+mod enclosure;
+mod expand;
+mod horner;
+mod labels;
+mod limit;
+mod linearity;
+mod mathml;
+mod piecewise;
 pub(super) mod poly;
+mod poly_coefficients;
+pub(super) mod poly_conversion;
+mod poly_division;
+mod rename;
+#[cfg(feature = "serde")]
+mod serialization;
+mod solve;
 
 // Staircase re-exports — one hop up to api.rs
 pub(super) use super::{
     CACHED_NEG_ONE, CACHED_TWO, CACHED_ZERO, EPSILON, EXPR_ONE, Expr, ExprKind, next_id,
 };
+pub use display::LatexConfig;
+pub use display::{FormatOptions, FunctionCallStyle, MulStyle, PowerOperator};
+pub use domain::{Constraint, Domain, Interval};
+pub use enclosure::{Enclosure, EnclosureMap};
 pub use hash::{compute_expr_hash, compute_term_hash};
+pub use limit::LimitPoint;
 pub use math_methods::ArcExprExt;
+pub use mathml::MathmlConfig;
 pub(super) use ordering::expr_cmp;
+pub use piecewise::{CompareOp, Condition};
+pub use piecewise::{map_piecewise, piecewise_parts};
 pub use poly::Polynomial;
+pub use poly_conversion::{
+    PolyConversion, poly_conversion, set_poly_conversion, with_poly_conversion,
+};
+pub use rename::{RenameReport, rename_symbols, rename_symbols_dry_run};
 
 #[cfg(test)]
 mod tests;
@@ -5,6 +5,7 @@
 use std::cmp::Ordering;
 use std::ptr::eq;
 
+use super::piecewise::piecewise_parts;
 use super::{EXPR_ONE, Expr, ExprKind};
 
 /// Compare expressions for canonical ordering.
@@ -209,7 +210,33 @@ pub fn expr_cmp_type_strict(a: &Expr, b: &Expr) -> Ordering {
                 })
             })
         }
-        (ExprKind::Poly(_), _) => Ordering::Less,
-        (_, ExprKind::Poly(_)) => Ordering::Greater,
+
+        // Piecewise definitions come after everything else
+        (
+            ExprKind::Piecewise {
+                branches: b1,
+                default: d1,
+            },
+            ExprKind::Piecewise {
+                branches: b2,
+                default: d2,
+            },
+        ) => b1.len().cmp(&b2.len()).then_with(|| {
+            for ((c1, _), (c2, _)) in b1.iter().zip(b2) {
+                let ops = (c1.op as u8).cmp(&(c2.op as u8));
+                if ops != Ordering::Equal {
+                    return ops;
+                }
+            }
+            for (x, y) in piecewise_parts(b1, d1).zip(piecewise_parts(b2, d2)) {
+                match expr_cmp(x, y) {
+                    Ordering::Equal => {}
+                    other => return other,
+                }
+            }
+            Ordering::Equal
+        }),
+        (ExprKind::Poly(_), _) | (_, ExprKind::Piecewise { .. }) => Ordering::Less,
+        (_, ExprKind::Poly(_)) | (ExprKind::Piecewise { .. }, _) => Ordering::Greater,
     }
 }
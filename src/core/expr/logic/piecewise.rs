@@ -0,0 +1,199 @@
+//! Piecewise expressions: guarded branches with a default.
+//!
+//! A piecewise node holds `(condition, value)` branches and a default value. The value of
+//! the first branch whose condition holds is taken, and the default when none does.
+//! Conditions compare two expressions; a comparison involving NaN never holds.
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::{Expr, ExprKind};
+
+/// The comparison of a piecewise [`Condition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompareOp {
+    /// `lhs < rhs`
+    Lt,
+    /// `lhs <= rhs`
+    Le,
+    /// `lhs > rhs`
+    Gt,
+    /// `lhs >= rhs`
+    Ge,
+    /// `lhs == rhs`
+    Eq,
+}
+
+impl CompareOp {
+    /// Whether `lhs op rhs` holds for two numbers
+    #[must_use]
+    #[allow(
+        clippy::float_cmp,
+        reason = "Eq conditions compare exactly, as the operator is written"
+    )]
+    pub fn holds(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Eq => lhs == rhs,
+        }
+    }
+
+    /// The operator as written in source: `<`, `<=`, `>`, `>=` or `==`
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A comparison `lhs op rhs` guarding one branch of a piecewise expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// The comparison
+    pub op: CompareOp,
+    /// Left-hand side
+    pub lhs: Arc<Expr>,
+    /// Right-hand side
+    pub rhs: Arc<Expr>,
+}
+
+impl Condition {
+    /// Create the condition `lhs op rhs`
+    pub fn new(op: CompareOp, lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self {
+            op,
+            lhs: Arc::new(lhs.into()),
+            rhs: Arc::new(rhs.into()),
+        }
+    }
+
+    /// `lhs < rhs`
+    pub fn lt(lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self::new(CompareOp::Lt, lhs, rhs)
+    }
+
+    /// `lhs <= rhs`
+    pub fn le(lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self::new(CompareOp::Le, lhs, rhs)
+    }
+
+    /// `lhs > rhs`
+    pub fn gt(lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self::new(CompareOp::Gt, lhs, rhs)
+    }
+
+    /// `lhs >= rhs`
+    pub fn ge(lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self::new(CompareOp::Ge, lhs, rhs)
+    }
+
+    /// `lhs == rhs`
+    pub fn equals(lhs: impl Into<Expr>, rhs: impl Into<Expr>) -> Self {
+        Self::new(CompareOp::Eq, lhs, rhs)
+    }
+
+    /// Whether the condition is decided: `Some` when both sides are numbers
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        Some(self.op.holds(self.lhs.as_number()?, self.rhs.as_number()?))
+    }
+
+    /// The same comparison between the sides rewritten by `f`, left then right
+    pub(crate) fn map_sides<'expr>(
+        &'expr self,
+        mut f: impl FnMut(&'expr Arc<Expr>) -> Arc<Expr>,
+    ) -> Self {
+        Self {
+            op: self.op,
+            lhs: f(&self.lhs),
+            rhs: f(&self.rhs),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.op, self.rhs)
+    }
+}
+
+impl Expr {
+    /// Create a piecewise expression: the value of the first branch whose condition
+    /// holds, or `default` when none does
+    ///
+    /// Without branches this is `default` itself.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Condition, Expr, symb};
+    /// let x = symb("x");
+    /// let relu = Expr::piecewise(vec![(Condition::lt(x, 0.0), Expr::number(0.0))], x.into());
+    /// assert_eq!(relu.to_string(), "piecewise(x < 0: 0, x)");
+    /// ```
+    #[must_use]
+    pub fn piecewise(branches: Vec<(Condition, Self)>, default: Self) -> Self {
+        if branches.is_empty() {
+            return default;
+        }
+        Self::new(ExprKind::Piecewise {
+            branches: branches
+                .into_iter()
+                .map(|(condition, value)| (condition, Arc::new(value)))
+                .collect(),
+            default: Arc::new(default),
+        })
+    }
+
+    /// Create a piecewise expression from shared parts
+    pub(crate) fn piecewise_from_arcs(
+        branches: Vec<(Condition, Arc<Self>)>,
+        default: Arc<Self>,
+    ) -> Self {
+        if branches.is_empty() {
+            return Arc::unwrap_or_clone(default);
+        }
+        Self::new(ExprKind::Piecewise { branches, default })
+    }
+}
+
+/// Every child of a piecewise node in order: each branch's condition sides and value,
+/// then the default
+pub fn piecewise_parts<'expr>(
+    branches: &'expr [(Condition, Arc<Expr>)],
+    default: &'expr Arc<Expr>,
+) -> impl DoubleEndedIterator<Item = &'expr Arc<Expr>> {
+    branches
+        .iter()
+        .flat_map(|(condition, value)| [&condition.lhs, &condition.rhs, value])
+        .chain(std::iter::once(default))
+}
+
+/// Rebuild a piecewise node with every child rewritten by `f`, visited in the order of
+/// [`piecewise_parts`]
+pub fn map_piecewise<'expr>(
+    branches: &'expr [(Condition, Arc<Expr>)],
+    default: &'expr Arc<Expr>,
+    mut f: impl FnMut(&'expr Arc<Expr>) -> Arc<Expr>,
+) -> Expr {
+    let branches = branches
+        .iter()
+        .map(|(condition, value)| (condition.map_sides(&mut f), f(value)))
+        .collect();
+    let default = f(default);
+    Expr::piecewise_from_arcs(branches, default)
+}
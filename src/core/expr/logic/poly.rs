@@ -9,6 +9,7 @@ use std::sync::Arc;
 
 use super::{Expr, ExprKind};
 use crate::EPSILON;
+use crate::core::traits::{cancels, is_zero, near_integer};
 
 // =============================================================================
 // POLYNOMIAL
@@ -126,7 +127,7 @@ impl Polynomial {
 
     /// Create a constant polynomial
     pub(super) fn constant(c: f64) -> Self {
-        if c.abs() < EPSILON {
+        if is_zero(c) {
             // Zero polynomial - use dummy base
             Self {
                 base: Arc::new(Expr::number(1.0)),
@@ -236,13 +237,14 @@ impl Polynomial {
 
     /// Add a term (power, coeff) to the polynomial
     pub(crate) fn add_term(&mut self, pow: u32, coeff: f64) {
-        if coeff.abs() < EPSILON {
+        if is_zero(coeff) {
             return;
         }
         match self.terms.binary_search_by_key(&pow, |(p, _)| *p) {
             Ok(i) => {
+                let scale = self.terms[i].1.abs().max(coeff.abs());
                 self.terms[i].1 += coeff;
-                if self.terms[i].1.abs() < EPSILON {
+                if cancels(self.terms[i].1, scale) {
                     self.terms.remove(i);
                 }
             }
@@ -274,9 +276,7 @@ impl Polynomial {
             let my_const = self.as_constant().unwrap_or(0.0);
             self.base = Arc::clone(&other.base);
             self.terms.clone_from(&other.terms);
-            if my_const.abs() >= EPSILON {
-                self.add_term(0, my_const);
-            }
+            self.add_term(0, my_const);
             return true;
         }
 
@@ -320,18 +320,21 @@ impl Polynomial {
         // Merge terms with same power
         let mut merged_terms = Vec::with_capacity(all_terms.len());
         if let Some(&(mut current_pow, mut current_coeff)) = all_terms.first() {
+            let mut scale = current_coeff.abs();
             for &(next_pow, next_coeff) in all_terms.iter().skip(1) {
                 if next_pow == current_pow {
                     current_coeff += next_coeff;
+                    scale = scale.max(next_coeff.abs());
                 } else {
-                    if current_coeff.abs() >= EPSILON {
+                    if !cancels(current_coeff, scale) {
                         merged_terms.push((current_pow, current_coeff));
                     }
                     current_pow = next_pow;
                     current_coeff = next_coeff;
+                    scale = next_coeff.abs();
                 }
             }
-            if current_coeff.abs() >= EPSILON {
+            if !cancels(current_coeff, scale) {
                 merged_terms.push((current_pow, current_coeff));
             }
         }
@@ -344,7 +347,7 @@ impl Polynomial {
 
     /// Multiply by a scalar
     pub(super) fn scale(&self, scalar: f64) -> Self {
-        if scalar.abs() < EPSILON {
+        if is_zero(scalar) {
             return Self::zero(Arc::clone(&self.base));
         }
         Self {
@@ -353,6 +356,14 @@ impl Polynomial {
         }
     }
 
+    /// Divide by a non-zero scalar (exact when it divides every coefficient)
+    pub(crate) fn div_scalar(&self, divisor: f64) -> Self {
+        Self {
+            base: Arc::clone(&self.base),
+            terms: self.terms.iter().map(|&(p, c)| (p, c / divisor)).collect(),
+        }
+    }
+
     /// Differentiate polynomial: d/d(base)
     /// Each term c*base^n becomes n*c*base^(n-1)
     pub(super) fn derivative(&self) -> Self {
@@ -365,10 +376,23 @@ impl Polynomial {
         result
     }
 
+    /// `n`-th derivative with respect to the base in a single pass over the terms
+    /// Each term c*base^k becomes c*k!/(k-n)!*base^(k-n)
+    pub(crate) fn nth_derivative(&self, n: u32) -> Self {
+        let mut result = Self::zero(Arc::clone(&self.base));
+        for &(pow, coeff) in &self.terms {
+            if pow >= n {
+                let scale = (pow - n + 1..=pow).fold(coeff, |acc, k| acc * f64::from(k));
+                result.add_term(pow - n, scale);
+            }
+        }
+        result
+    }
+
     /// Make polynomial monic (leading coefficient = 1)
     pub(super) fn make_monic(&self) -> Self {
         let lc = self.leading_coeff();
-        if lc.abs() < EPSILON || (lc - 1.0).abs() < EPSILON {
+        if is_zero(lc) || (lc - 1.0).abs() < EPSILON {
             return self.clone();
         }
         self.scale(1.0 / lc)
@@ -419,7 +443,40 @@ impl Polynomial {
         Some((quotient, remainder))
     }
 
-    /// GCD using Euclidean algorithm
+    /// Content: the GCD of the coefficients when they are all integers, otherwise the
+    /// leading coefficient, with the sign of the leading coefficient either way
+    pub(super) fn content(&self) -> f64 {
+        let lc = self.leading_coeff();
+        let integral: Option<Vec<f64>> = self
+            .terms
+            .iter()
+            .map(|&(_, c)| near_integer(c).filter(|c| c.abs() < MAX_EXACT_INTEGER))
+            .collect();
+        let content = integral.map_or_else(
+            || lc.abs(),
+            |coeffs| coeffs.iter().fold(0.0, |a, &b| integer_gcd(a, b.abs())),
+        );
+        content.copysign(lc)
+    }
+
+    /// Primitive part: the polynomial divided by its [`content`](Self::content), with
+    /// coefficients within rounding error of an integer snapped to it
+    pub(super) fn primitive_part(&self) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let mut primitive = self.div_scalar(self.content());
+        for (_, c) in &mut primitive.terms {
+            *c = near_integer(*c).unwrap_or(*c);
+        }
+        primitive
+    }
+
+    /// GCD using the Euclidean algorithm on primitive parts
+    ///
+    /// Every remainder is replaced by its primitive part, which keeps integer
+    /// coefficients integral and stops the coefficients from growing or shrinking from
+    /// one step to the next. The result is monic.
     /// Returns None if polynomials have different bases (cannot compute GCD)
     pub(crate) fn gcd(&self, other: &Self) -> Option<Self> {
         // GCD only makes sense for polynomials with same base
@@ -427,17 +484,21 @@ impl Polynomial {
             return None;
         }
 
-        let mut r0 = self.clone();
-        let mut r1 = other.clone();
+        let mut r0 = self.primitive_part();
+        let mut r1 = other.primitive_part();
 
         loop {
             if r1.is_zero() {
-                return Some(r0.make_monic());
+                let mut gcd = r0.make_monic();
+                for (_, c) in &mut gcd.terms {
+                    *c = near_integer(*c).unwrap_or(*c);
+                }
+                return Some(gcd);
             }
 
             let (_, rem) = r0.div_rem(&r1)?;
             r0 = r1;
-            r1 = rem;
+            r1 = rem.primitive_part();
         }
     }
 
@@ -453,9 +514,10 @@ impl Polynomial {
             ExprKind::Number(n) => Some(Self::constant(*n)),
 
             // Symbols, function calls, or derivatives become base^1
-            ExprKind::Symbol(_) | ExprKind::FunctionCall { .. } | ExprKind::Derivative { .. } => {
-                Some(Self::from_base(expr.clone()))
-            }
+            ExprKind::Symbol(_)
+            | ExprKind::FunctionCall { .. }
+            | ExprKind::Derivative { .. }
+            | ExprKind::Piecewise { .. } => Some(Self::from_base(expr.clone())),
             ExprKind::Sum(terms) => {
                 if terms.is_empty() {
                     return Some(Self::constant(0.0));
@@ -522,7 +584,7 @@ impl Polynomial {
             // Division by constant
             ExprKind::Div(num, den) => {
                 if let ExprKind::Number(d) = &den.kind
-                    && d.abs() > EPSILON
+                    && !is_zero(*d)
                 {
                     let mut result = Self::try_from_expr(num)?;
                     result = result.scale(1.0 / d);
@@ -537,6 +599,80 @@ impl Polynomial {
         }
     }
 
+    /// Expand `expr` into a polynomial in the symbol `var` with numeric coefficients
+    ///
+    /// Sums, products, natural powers and division by numbers are multiplied out, and
+    /// polynomial nodes are composed with their base. Returns `None` when `expr` holds
+    /// anything else (another symbol or a function, say) or when a degree would pass
+    /// `max_degree`.
+    pub(crate) fn expand_in(expr: &Expr, var: &Arc<Expr>, max_degree: u32) -> Option<Self> {
+        let constant = |c: f64| {
+            let mut poly = Self::zero(Arc::clone(var));
+            poly.add_term(0, c);
+            poly
+        };
+        let power = |base: &Self, n: u32| -> Option<Self> {
+            (base.degree().checked_mul(n)? <= max_degree)
+                .then(|| (0..n).fold(constant(1.0), |acc, _| acc.mul(base)))
+        };
+        let poly = match &expr.kind {
+            ExprKind::Number(n) if n.is_finite() => constant(*n),
+            ExprKind::Symbol(_) if expr == &**var => Self {
+                base: Arc::clone(var),
+                terms: vec![(1, 1.0)],
+            },
+            ExprKind::Sum(terms) => {
+                let mut sum = Self::zero(Arc::clone(var));
+                for term in terms {
+                    for &(pow, coeff) in &Self::expand_in(term, var, max_degree)?.terms {
+                        sum.add_term(pow, coeff);
+                    }
+                }
+                sum
+            }
+            ExprKind::Product(factors) => {
+                let mut product = constant(1.0);
+                for factor in factors {
+                    let factor = Self::expand_in(factor, var, max_degree)?;
+                    if product.degree() + factor.degree() > max_degree {
+                        return None;
+                    }
+                    product = product.mul(&factor);
+                }
+                product
+            }
+            ExprKind::Pow(base, exp) => {
+                let n = exp.as_number().filter(|n| n.fract() == 0.0 && *n >= 0.0)?;
+                if n > f64::from(max_degree) {
+                    return None;
+                }
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    reason = "Checked to be a natural number no larger than max_degree"
+                )]
+                let n = n as u32;
+                power(&Self::expand_in(base, var, max_degree)?, n)?
+            }
+            ExprKind::Div(num, den) => {
+                let d = den.as_number().filter(|d| d.is_finite() && !is_zero(*d))?;
+                Self::expand_in(num, var, max_degree)?.div_scalar(d)
+            }
+            ExprKind::Poly(poly) => {
+                let base = Self::expand_in(&poly.base, var, max_degree)?;
+                let mut sum = Self::zero(Arc::clone(var));
+                for &(pow, coeff) in &poly.terms {
+                    for &(p, c) in &power(&base, pow)?.terms {
+                        sum.add_term(p, coeff * c);
+                    }
+                }
+                sum
+            }
+            _ => return None,
+        };
+        Some(poly)
+    }
+
     /// Try to multiply two polynomials, handling base compatibility
     fn try_mul(a: &Self, b: &Self) -> Option<Self> {
         // Multiply by constant
@@ -638,6 +774,17 @@ impl Polynomial {
     }
 }
 
+/// Integers above this lose exactness as `f64`
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// GCD of two non-negative integers held exactly in `f64`
+fn integer_gcd(mut a: f64, mut b: f64) -> f64 {
+    while !is_zero(b) {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 impl Default for Polynomial {
     fn default() -> Self {
         Self::constant(0.0)
@@ -0,0 +1,360 @@
+//! Coefficients of an expression viewed as a polynomial in one symbol.
+//!
+//! Unlike [`Polynomial`](super::Polynomial), whose coefficients are numbers, the
+//! coefficients here are expressions that may hold any symbol other than the variable.
+//! Shared by [`Expr::solve`] and [`Expr::div_poly`], and behind [`Expr::coefficient_of`],
+//! [`Expr::coefficient`], [`Expr::collect`] and [`Expr::degree_in`].
+
+use std::sync::Arc;
+
+use crate::core::DiffError;
+use crate::core::Symbol;
+use crate::simplification::Simplify;
+
+use super::{Expr, ExprKind};
+
+impl Expr {
+    /// Coefficients `[c0, c1, .., cn]` of `self` as a polynomial in `var`
+    ///
+    /// Entry `i` multiplies `var^i`; every other symbol is part of the coefficients.
+    /// Coefficients are simplified and trailing zeros dropped, so the length is one more
+    /// than the degree and the zero polynomial gives an empty `Vec`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let x = symb("to_poly_doc_x");
+    /// let a = symb("to_poly_doc_a");
+    /// let coeffs = (a * x.pow(2.0) + 3.0).to_poly_coefficients(&x).unwrap();
+    /// assert_eq!(coeffs, vec![Expr::number(3.0), Expr::number(0.0), a.to_expr()]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnsupportedExpression`] if the expression is not a polynomial
+    /// in `var`, and propagates simplification errors.
+    pub fn to_poly_coefficients(&self, var: &Symbol) -> Result<Vec<Self>, DiffError> {
+        if degree_in(self, var.id()).is_none() {
+            return Err(DiffError::UnsupportedExpression(format!(
+                "{self} is not a polynomial in {}",
+                var.to_expr()
+            )));
+        }
+        simplified_coefficients(self, var.id(), &Simplify::new())
+    }
+
+    /// Coefficient of `monomial` in `self`
+    ///
+    /// `monomial` is a product of non-negative integer powers of symbols, optionally with a
+    /// numeric factor that the result is divided by. Symbols outside the monomial are part
+    /// of the coefficient, so the coefficient of `x` in `a*x*y + x` is `1 + a*y`; products
+    /// and powers of sums count as expanded. Terms in which a monomial symbol appears
+    /// other than as such a power contribute nothing. A missing monomial, or one that is
+    /// not a product of symbol powers, gives `0`; the monomial `1` gives `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let (x, y) = (symb("coeff_doc_x"), symb("coeff_doc_y"));
+    /// let expr = 3.0 * x.pow(2.0) + 5.0 * x - 1.0 + 4.0 * x.pow(2.0) * y;
+    /// assert_eq!(expr.coefficient_of(&x.pow(2.0)), 3.0 + 4.0 * y);
+    /// assert_eq!(expr.coefficient_of(&(x.pow(2.0) * y)), Expr::number(4.0));
+    /// ```
+    #[must_use]
+    pub fn coefficient_of(&self, monomial: &Self) -> Self {
+        let Some((scale, powers)) = monomial_powers(monomial) else {
+            return Self::number(0.0);
+        };
+        if scale == 0.0 {
+            return Self::number(0.0);
+        }
+        let coeff = powers.iter().fold(self.clone(), |acc, &(var_id, pow)| {
+            coefficient_in(&acc, var_id, pow)
+        });
+        let coeff = Self::div_expr(coeff, Self::number(scale));
+        Simplify::new().simplify(&coeff).unwrap_or(coeff)
+    }
+
+    /// `(coefficient, power)` pairs of `self` in `var`, highest degree first
+    ///
+    /// Each power is `var^k` (`var` itself for `k = 1`, `1` for `k = 0`) and each
+    /// coefficient is simplified and nonzero. Terms that are not a polynomial in `var`,
+    /// such as `sin(var)`, stay in the coefficient of `1`, so the products of the pairs
+    /// always add back up to `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let (x, a) = (symb("collect_terms_doc_x"), symb("collect_terms_doc_a"));
+    /// let pairs = (a * x.pow(2.0) + x * 2.0 + x.pow(2.0)).collect_terms(&x);
+    /// assert_eq!(pairs, vec![(a + 1.0, x.pow(2.0)), (Expr::number(2.0), x.to_expr())]);
+    /// ```
+    #[must_use]
+    pub fn collect_terms(&self, var: &Symbol) -> Vec<(Self, Self)> {
+        let simplify = Simplify::new();
+        collected_coefficients(self, var.id())
+            .into_iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(pow, coeff)| {
+                let coeff = simplify.simplify(&coeff).unwrap_or(coeff);
+                (!coeff.is_zero_num()).then(|| (coeff, var_power(*var, pow)))
+            })
+            .collect()
+    }
+
+    /// `self` rewritten as a polynomial in `var` with expression coefficients
+    ///
+    /// `a*x^2 + b*x + c*x^2 + d` collected in `x` becomes `(a + c)*x^2 + b*x + d`. The
+    /// terms are those of [`collect_terms`](Self::collect_terms): non-polynomial
+    /// dependence on `var`, such as `sin(var)`, is part of the constant term.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let (x, a, c) = (symb("collect_doc_x"), symb("collect_doc_a"), symb("collect_doc_c"));
+    /// let collected = (a * x.pow(2.0) + c * x.pow(2.0) + x).collect(&x);
+    /// assert_eq!(collected, (a + c) * x.pow(2.0) + x);
+    /// ```
+    #[must_use]
+    pub fn collect(&self, var: &Symbol) -> Self {
+        let terms = self
+            .collect_terms(var)
+            .into_iter()
+            .map(|(coeff, power)| Self::mul_expr(coeff, power))
+            .collect();
+        Self::sum_no_poly(terms)
+    }
+
+    /// Simplified coefficient of `var^power` in `self`
+    ///
+    /// Read off the same collected form as [`collect`](Self::collect), so the coefficient
+    /// of `var^0` also holds every term that is not a polynomial in `var`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let (x, a) = (symb("coefficient_doc_x"), symb("coefficient_doc_a"));
+    /// let expr = a * x.pow(2.0) + 3.0 * x.pow(2.0) + x.sin();
+    /// assert_eq!(expr.coefficient(&x, 2), a + 3.0);
+    /// assert_eq!(expr.coefficient(&x, 0), x.sin());
+    /// assert_eq!(expr.coefficient(&x, 5), Expr::number(0.0));
+    /// ```
+    #[must_use]
+    pub fn coefficient(&self, var: &Symbol, power: u32) -> Self {
+        let coeff = collected_coefficients(self, var.id())
+            .into_iter()
+            .nth(power as usize)
+            .unwrap_or_else(|| Self::number(0.0));
+        Simplify::new().simplify(&coeff).unwrap_or(coeff)
+    }
+}
+
+/// Unsimplified coefficients `[c0, c1, ..]` of `expr` in `var_id`, with every term that
+/// is not a polynomial in `var_id` added to `c0`
+fn collected_coefficients(expr: &Expr, var_id: u64) -> Vec<Expr> {
+    let terms: Vec<&Expr> = match &expr.kind {
+        ExprKind::Sum(terms) => terms.iter().map(AsRef::as_ref).collect(),
+        _ => vec![expr],
+    };
+    let (polynomial, rest): (Vec<&Expr>, Vec<&Expr>) = terms
+        .into_iter()
+        .partition(|term| degree_in(term, var_id).is_some());
+
+    let coeffs = polynomial
+        .into_iter()
+        .fold(Vec::new(), |acc, term| add(acc, coefficients(term, var_id)));
+    if rest.is_empty() {
+        return coeffs;
+    }
+    let rest = Expr::sum(rest.into_iter().cloned().collect());
+    add(coeffs, vec![rest])
+}
+
+/// Numeric factor and `(symbol id, power)` pairs of a product of symbol powers
+fn monomial_powers(monomial: &Expr) -> Option<(f64, Vec<(u64, u32)>)> {
+    let factors: Vec<&Expr> = match &monomial.kind {
+        ExprKind::Product(factors) => factors.iter().map(AsRef::as_ref).collect(),
+        _ => vec![monomial],
+    };
+    let mut scale = 1.0;
+    let mut powers: Vec<(u64, u32)> = Vec::new();
+    for factor in factors {
+        let (var_id, pow) = match &factor.kind {
+            ExprKind::Number(n) => {
+                scale *= n;
+                continue;
+            }
+            ExprKind::Symbol(s) => (s.id(), 1),
+            ExprKind::Pow(base, exp) => match &base.kind {
+                ExprKind::Symbol(s) => (s.id(), natural_exponent(exp)?),
+                _ => return None,
+            },
+            ExprKind::Poly(poly) => match (&poly.base().kind, poly.terms()) {
+                (ExprKind::Symbol(s), &[(pow, coeff)]) => {
+                    scale *= coeff;
+                    (s.id(), pow)
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match powers.iter_mut().find(|(id, _)| *id == var_id) {
+            Some((_, total)) => *total = total.saturating_add(pow),
+            None => powers.push((var_id, pow)),
+        }
+    }
+    Some((scale, powers))
+}
+
+/// Unsimplified coefficient of `var^pow` in `expr`, skipping terms that are not
+/// polynomial in `var`
+fn coefficient_in(expr: &Expr, var_id: u64, pow: u32) -> Expr {
+    let nth = |term: &Expr| {
+        degree_in(term, var_id)
+            .and_then(|_| coefficients(term, var_id).into_iter().nth(pow as usize))
+    };
+    if let Some(coeff) = nth(expr) {
+        return coeff;
+    }
+    match &expr.kind {
+        ExprKind::Sum(terms) => Expr::sum(terms.iter().filter_map(|term| nth(term)).collect()),
+        _ => Expr::number(0.0),
+    }
+}
+
+/// `var^pow`, written as `1` and `var` for the first two powers
+fn var_power(var: Symbol, pow: usize) -> Expr {
+    match pow {
+        0 => Expr::number(1.0),
+        1 => var.to_expr(),
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Polynomial degrees stay far below 2^52"
+        )]
+        _ => var.to_expr().pow(pow as f64),
+    }
+}
+
+/// Degree of `expr` as a polynomial in the symbol `var_id`, or `None` if it is not one
+///
+/// This is the degree as written: terms that cancel still count.
+pub(super) fn degree_in(expr: &Expr, var_id: u64) -> Option<u32> {
+    if !expr.contains_var_id(var_id) {
+        return Some(0);
+    }
+    match &expr.kind {
+        ExprKind::Symbol(_) => Some(1),
+        ExprKind::Sum(terms) => terms
+            .iter()
+            .try_fold(0, |max, term| Some(max.max(degree_in(term, var_id)?))),
+        ExprKind::Product(factors) => factors.iter().try_fold(0_u32, |sum, factor| {
+            Some(sum.saturating_add(degree_in(factor, var_id)?))
+        }),
+        ExprKind::Div(num, den) if !den.contains_var_id(var_id) => degree_in(num, var_id),
+        ExprKind::Pow(base, exp) => {
+            let n = natural_exponent(exp)?;
+            Some(degree_in(base, var_id)?.saturating_mul(n))
+        }
+        ExprKind::Poly(poly) => Some(degree_in(poly.base(), var_id)?.saturating_mul(poly.degree())),
+        _ => None,
+    }
+}
+
+/// Simplified coefficients `[c0, c1, ..]` of a polynomial in `var_id`, without trailing zeros
+///
+/// `expr` must already have passed [`degree_in`]. The zero polynomial gives an empty `Vec`.
+pub(super) fn simplified_coefficients(
+    expr: &Expr,
+    var_id: u64,
+    simplify: &Simplify,
+) -> Result<Vec<Expr>, DiffError> {
+    let mut coeffs = coefficients(expr, var_id)
+        .iter()
+        .map(|c| simplify.simplify(c))
+        .collect::<Result<Vec<_>, _>>()?;
+    while coeffs.last().is_some_and(Expr::is_zero_num) {
+        coeffs.pop();
+    }
+    Ok(coeffs)
+}
+
+/// Coefficients `[c0, c1, ..]` of an expression accepted by [`degree_in`]
+///
+/// The entries are unsimplified; trailing entries may simplify to zero.
+fn coefficients(expr: &Expr, var_id: u64) -> Vec<Expr> {
+    if !expr.contains_var_id(var_id) {
+        return vec![expr.clone()];
+    }
+    match &expr.kind {
+        ExprKind::Symbol(_) => vec![Expr::number(0.0), Expr::number(1.0)],
+        ExprKind::Sum(terms) => terms
+            .iter()
+            .fold(Vec::new(), |acc, term| add(acc, coefficients(term, var_id))),
+        ExprKind::Product(factors) => {
+            factors.iter().fold(vec![Expr::number(1.0)], |acc, factor| {
+                mul(&acc, &coefficients(factor, var_id))
+            })
+        }
+        ExprKind::Div(num, den) => coefficients(num, var_id)
+            .into_iter()
+            .map(|c| Expr::div_from_arcs(Arc::new(c), Arc::clone(den)))
+            .collect(),
+        ExprKind::Pow(base, exp) => power(
+            &coefficients(base, var_id),
+            natural_exponent(exp).unwrap_or(0),
+        ),
+        // Read the terms directly: `to_expr` would rebuild the same `Poly` node
+        ExprKind::Poly(poly) => {
+            let base = coefficients(poly.base(), var_id);
+            poly.terms().iter().fold(Vec::new(), |acc, &(pow, coeff)| {
+                let term = power(&base, pow)
+                    .into_iter()
+                    .map(|c| Expr::mul_expr(Expr::number(coeff), c))
+                    .collect();
+                add(acc, term)
+            })
+        }
+        // `degree_in` has already rejected everything else
+        _ => vec![expr.clone()],
+    }
+}
+
+/// A non-negative integer exponent
+fn natural_exponent(exp: &Expr) -> Option<u32> {
+    let n = exp.as_number()?;
+    if n < 0.0 || n.fract() != 0.0 || n > f64::from(u32::MAX) {
+        return None;
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Checked to be a non-negative integer within u32 range"
+    )]
+    Some(n as u32)
+}
+
+fn add(lhs: Vec<Expr>, rhs: Vec<Expr>) -> Vec<Expr> {
+    let (mut long, short) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    for (slot, c) in long.iter_mut().zip(short) {
+        *slot = Expr::add_expr(std::mem::replace(slot, Expr::number(0.0)), c);
+    }
+    long
+}
+
+fn power(base: &[Expr], n: u32) -> Vec<Expr> {
+    (0..n).fold(vec![Expr::number(1.0)], |acc, _| mul(&acc, base))
+}
+
+fn mul(lhs: &[Expr], rhs: &[Expr]) -> Vec<Expr> {
+    let mut out = vec![Vec::new(); (lhs.len() + rhs.len()).saturating_sub(1)];
+    for (i, a) in lhs.iter().enumerate() {
+        for (j, b) in rhs.iter().enumerate() {
+            out[i + j].push(Expr::mul_expr(a.clone(), b.clone()));
+        }
+    }
+    out.into_iter().map(Expr::sum).collect()
+}
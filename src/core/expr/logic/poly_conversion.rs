@@ -0,0 +1,128 @@
+//! Thread-local policy for automatic `Sum` → `Poly` conversion, plus explicit conversions.
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use super::{Expr, ExprKind, Polynomial};
+
+/// Controls when [`Expr::sum`] merges like terms into an `ExprKind::Poly` node.
+///
+/// The policy is thread-local: it applies to expressions built on the current thread.
+/// The default reproduces the historical behaviour (any two or more summands over
+/// a common base are merged, with no degree cap).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolyConversion {
+    /// Whether automatic conversion happens at all
+    pub enabled: bool,
+    /// Minimum number of summands over a common base before they are merged
+    pub min_terms: usize,
+    /// Largest degree a merged polynomial may have; higher degrees stay a `Sum`
+    pub max_degree: u32,
+}
+
+impl PolyConversion {
+    /// Default policy: merge two or more like terms of any degree.
+    pub const DEFAULT: Self = Self {
+        enabled: true,
+        min_terms: 2,
+        max_degree: u32::MAX,
+    };
+
+    /// Never convert sums automatically.
+    pub const DISABLED: Self = Self {
+        enabled: false,
+        ..Self::DEFAULT
+    };
+
+    /// Whether a run of `terms` summands with merged degree `degree` should become a `Poly`.
+    pub(crate) const fn allows(&self, terms: usize, degree: u32) -> bool {
+        self.enabled && terms >= self.min_terms && degree <= self.max_degree
+    }
+}
+
+impl Default for PolyConversion {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+thread_local! {
+    static POLICY: Cell<PolyConversion> = const { Cell::new(PolyConversion::DEFAULT) };
+}
+
+/// Current thread's automatic polynomial conversion policy.
+#[must_use]
+pub fn poly_conversion() -> PolyConversion {
+    POLICY.with(Cell::get)
+}
+
+/// Set the current thread's polynomial conversion policy, returning the previous one.
+#[allow(
+    clippy::must_use_candidate,
+    reason = "Callers commonly set the policy without keeping the old one"
+)]
+pub fn set_poly_conversion(policy: PolyConversion) -> PolyConversion {
+    POLICY.with(|cell| cell.replace(policy))
+}
+
+/// Run `f` with `policy` in effect on this thread, restoring the previous policy afterwards
+/// (also when `f` panics).
+pub fn with_poly_conversion<R>(policy: PolyConversion, f: impl FnOnce() -> R) -> R {
+    /// Restores the saved policy on drop.
+    struct Restore(PolyConversion);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            set_poly_conversion(self.0);
+        }
+    }
+
+    let _restore = Restore(set_poly_conversion(policy));
+    f()
+}
+
+impl Expr {
+    /// Create a sum without merging like terms into a polynomial node,
+    /// regardless of the current [`PolyConversion`] policy.
+    ///
+    /// Nested sums are still flattened, numbers folded and terms sorted.
+    #[must_use]
+    pub fn sum_no_poly(terms: Vec<Self>) -> Self {
+        with_poly_conversion(PolyConversion::DISABLED, || Self::sum(terms))
+    }
+
+    /// Convert every sum that is a polynomial in a single base into an `ExprKind::Poly` node.
+    ///
+    /// Sums that mix bases (e.g. `x + y`) are left as they are. The conversion ignores the
+    /// current [`PolyConversion`] policy.
+    #[must_use]
+    pub fn to_poly(&self) -> Self {
+        self.map(|node| {
+            if let ExprKind::Sum(_) = &node.kind
+                && let Some(poly) = Polynomial::try_from_expr(node)
+                && poly.term_count() > 1
+            {
+                return Self::poly(poly);
+            }
+            node.clone()
+        })
+    }
+
+    /// Expand every `ExprKind::Poly` node back into a plain sum of monomials.
+    ///
+    /// The result contains no polynomial nodes, regardless of the current
+    /// [`PolyConversion`] policy.
+    #[must_use]
+    pub fn from_poly(&self) -> Self {
+        self.map(|node| match &node.kind {
+            ExprKind::Poly(poly) => {
+                let base = poly.base().from_poly();
+                Self::sum_no_poly(poly.with_base(Arc::new(base)).to_expr_terms())
+            }
+            // Re-flatten sums whose polynomial children were just expanded
+            ExprKind::Sum(terms) if terms.iter().any(|t| matches!(t.kind, ExprKind::Sum(_))) => {
+                Self::sum_no_poly(terms.iter().map(|t| t.as_ref().clone()).collect())
+            }
+            _ => node.clone(),
+        })
+    }
+}
@@ -0,0 +1,84 @@
+//! Polynomial long division in one variable.
+//!
+//! Both operands are simplified and collected into coefficients, which may hold other
+//! symbols. Each step divides the leading coefficient of the running remainder by the
+//! leading coefficient of the divisor, so divisors need not be monic.
+
+use crate::core::DiffError;
+use crate::core::Symbol;
+use crate::simplification::Simplify;
+
+use super::Expr;
+
+impl Expr {
+    /// Divide `numerator` by `denominator` as polynomials in `var`
+    ///
+    /// Returns `(quotient, remainder)` with `numerator = quotient * denominator + remainder`
+    /// and the degree of `remainder` below that of `denominator`. Both are simplified;
+    /// exact division gives a remainder of `0`, and a denominator of degree zero divides
+    /// every coefficient. Other symbols are treated as coefficients, and a symbolic
+    /// leading coefficient of the denominator is assumed to be nonzero.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    /// let x = symb("div_poly_doc_x");
+    /// let (q, r) = Expr::div_poly(&(x.pow(2.0) - 1.0), &(x - 1.0), &x).unwrap();
+    /// assert_eq!(q, (x + 1.0).simplified().unwrap());
+    /// assert!(r.is_zero_num());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnsupportedExpression`] if either operand is not a polynomial
+    /// in `var` or the denominator simplifies to zero, and propagates simplification
+    /// errors.
+    pub fn div_poly(
+        numerator: &Self,
+        denominator: &Self,
+        var: &Symbol,
+    ) -> Result<(Self, Self), DiffError> {
+        let simplify = Simplify::new();
+        let mut remainder = simplify.simplify(numerator)?.to_poly_coefficients(var)?;
+        let divisor = simplify.simplify(denominator)?.to_poly_coefficients(var)?;
+        let Some((lead, lower)) = divisor.split_last() else {
+            return Err(DiffError::UnsupportedExpression(format!(
+                "cannot divide {numerator} by the zero polynomial {denominator}"
+            )));
+        };
+
+        let mut quotient =
+            vec![Self::number(0.0); (remainder.len() + 1).saturating_sub(divisor.len())];
+        for k in (0..quotient.len()).rev() {
+            // The leading term cancels exactly, so drop it instead of subtracting
+            let Some(top) = remainder.pop() else { break };
+            let q = simplify.simplify(&Self::div_expr(top, lead.clone()))?;
+            for (slot, d) in remainder[k..].iter_mut().zip(lower) {
+                let reduced = Self::sub_expr(slot.clone(), Self::mul_expr(q.clone(), d.clone()));
+                *slot = simplify.simplify(&reduced)?;
+            }
+            quotient[k] = q;
+        }
+        while remainder.last().is_some_and(Self::is_zero_num) {
+            remainder.pop();
+        }
+
+        Ok((
+            from_coefficients(&quotient, *var, &simplify)?,
+            from_coefficients(&remainder, *var, &simplify)?,
+        ))
+    }
+}
+
+/// Rebuild `c0 + c1*var + c2*var^2 + ..` and simplify it
+fn from_coefficients(coeffs: &[Expr], var: Symbol, simplify: &Simplify) -> Result<Expr, DiffError> {
+    let terms = coeffs
+        .iter()
+        .zip(0_u32..)
+        .filter(|(c, _)| !c.is_zero_num())
+        .map(|(c, pow)| {
+            let power = Expr::pow_static(var.to_expr(), Expr::number(f64::from(pow)));
+            Expr::mul_expr(c.clone(), power)
+        })
+        .collect();
+    simplify.simplify(&Expr::sum(terms))
+}
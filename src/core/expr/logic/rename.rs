@@ -0,0 +1,177 @@
+//! Renaming symbols across a set of expressions.
+//!
+//! The whole mapping is checked against every expression before anything is rewritten,
+//! so a refused rename leaves the set untouched. Renames are applied all at once through
+//! the substitution machinery: untouched subtrees stay shared, and the variable of an
+//! unevaluated derivative is renamed along with the symbols.
+
+use std::collections::HashSet;
+
+use crate::core::DiffError;
+use crate::core::known_symbols::is_known_constant;
+use crate::parser::is_builtin;
+
+use super::{Expr, ExprKind};
+
+/// How many symbol occurrences a rename changed in each expression of a set
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RenameReport {
+    changes: Vec<usize>,
+}
+
+impl RenameReport {
+    /// Renamed occurrences per expression, in the order of the set; the derivative
+    /// variable of a `Derivative` node counts as one occurrence
+    #[must_use]
+    pub fn changes(&self) -> &[usize] {
+        &self.changes
+    }
+
+    /// Indices of the expressions with at least one renamed occurrence
+    pub fn affected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changes
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(index, _)| index)
+    }
+
+    /// Renamed occurrences over the whole set
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.changes.iter().sum()
+    }
+}
+
+/// Rename symbols across `exprs`, all pairs `(from, to)` at once
+///
+/// Symbols are matched by name, in the arguments of function calls and as the variable
+/// of unevaluated derivatives too; function names are left alone. The mapping is
+/// validated against the whole set first and nothing is modified when it is refused.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{rename_symbols, symb};
+/// let (t, p) = (symb("T"), symb("p"));
+/// let mut exprs = vec![t.pow(2.0) * p, t.exp(), p.to_expr()];
+/// let report = rename_symbols(&mut exprs, &[("T", "T_gas")]).unwrap();
+/// assert_eq!(report.changes(), &[1, 1, 0]);
+/// assert_eq!(exprs[1].to_string(), "exp(T_gas)");
+/// ```
+///
+/// # Errors
+/// Returns [`DiffError::InvalidRename`] if a symbol is mapped twice, two symbols are
+/// mapped to the same name, a new name is empty, a new name is a built-in constant or
+/// function name (such as `pi` or `sin`), a new name is itself renamed by the mapping
+/// (a chain or swap whose order would be ambiguous), or a new name is already used by a
+/// distinct symbol somewhere in the set.
+pub fn rename_symbols(
+    exprs: &mut [Expr],
+    mapping: &[(&str, &str)],
+) -> Result<RenameReport, DiffError> {
+    let report = rename_symbols_dry_run(exprs, mapping)?;
+    let pairs: Vec<(&str, Expr)> = mapping
+        .iter()
+        .filter(|(from, to)| from != to)
+        .map(|&(from, to)| (from, Expr::symbol(to)))
+        .collect();
+    for (expr, &count) in exprs.iter_mut().zip(report.changes()) {
+        if count > 0 {
+            *expr = expr.substitute_names(&pairs);
+        }
+    }
+    Ok(report)
+}
+
+/// Validate a rename as [`rename_symbols`] does and report what it would change,
+/// without modifying anything
+///
+/// # Errors
+/// Returns [`DiffError::InvalidRename`] in the same cases as [`rename_symbols`].
+pub fn rename_symbols_dry_run(
+    exprs: &[Expr],
+    mapping: &[(&str, &str)],
+) -> Result<RenameReport, DiffError> {
+    let refuse = |from: &str, to: &str, reason: &str| DiffError::InvalidRename {
+        from: from.to_owned(),
+        reason: format!("{reason} (new name '{to}')"),
+    };
+
+    let used: HashSet<String> = exprs.iter().flat_map(Expr::variables).collect();
+    let renames: Vec<(&str, &str)> = mapping
+        .iter()
+        .copied()
+        .filter(|(from, to)| from != to)
+        .collect();
+
+    for (index, &(from, to)) in mapping.iter().enumerate() {
+        if to.is_empty() {
+            return Err(refuse(from, to, "the new name is empty"));
+        }
+        if from != to && is_known_constant(to) {
+            return Err(refuse(from, to, &format!("'{to}' is a built-in constant")));
+        }
+        if from != to && is_builtin(to) {
+            return Err(refuse(from, to, &format!("'{to}' is a built-in function")));
+        }
+        if let Some(&(_, other)) = mapping[..index].iter().find(|(f, _)| *f == from) {
+            return Err(refuse(
+                from,
+                to,
+                &format!("'{from}' is already mapped to '{other}'"),
+            ));
+        }
+        if from == to {
+            continue;
+        }
+        if let Some(&(other, _)) = renames.iter().find(|(f, t)| *t == to && *f != from) {
+            return Err(refuse(
+                from,
+                to,
+                &format!("'{other}' is renamed to '{to}' as well"),
+            ));
+        }
+        if renames.iter().any(|(f, _)| *f == to) {
+            return Err(refuse(
+                from,
+                to,
+                &format!("'{to}' is itself renamed, so the order of the renames is ambiguous"),
+            ));
+        }
+        if used.contains(to) {
+            return Err(refuse(
+                from,
+                to,
+                &format!("'{to}' is already a distinct symbol in the expressions"),
+            ));
+        }
+    }
+
+    let sources: HashSet<&str> = renames.iter().map(|(from, _)| *from).collect();
+    Ok(RenameReport {
+        changes: exprs
+            .iter()
+            .map(|expr| count_occurrences(expr, &sources))
+            .collect(),
+    })
+}
+
+/// Symbol and derivative-variable occurrences in `expr` whose name is in `names`
+fn count_occurrences(expr: &Expr, names: &HashSet<&str>) -> usize {
+    if names.is_empty() {
+        return 0;
+    }
+    let mut count = 0;
+    let mut stack = vec![expr];
+    while let Some(node) = stack.pop() {
+        match &node.kind {
+            ExprKind::Symbol(s) if s.name().is_some_and(|name| names.contains(name)) => {
+                count += 1;
+            }
+            ExprKind::Derivative { var, .. } if names.contains(var.as_str()) => count += 1,
+            _ => {}
+        }
+        Expr::push_children(node, &mut stack);
+    }
+    count
+}
@@ -0,0 +1,324 @@
+//! Serde support for expressions (`serde` feature).
+//!
+//! An `Expr` is stored as a versioned document, `{"schema": 1, "expr": <node>}`, where a
+//! node is its [`ExprKind`] in serde's externally tagged form:
+//!
+//! | Kind           | Node                                                       |
+//! | -------------- | ---------------------------------------------------------- |
+//! | `Number`       | `{"Number": 2.5}`                                          |
+//! | `Symbol`       | `{"Symbol": "x"}`                                          |
+//! | `FunctionCall` | `{"FunctionCall": {"name": "sin", "args": [<node>, ..]}}`  |
+//! | `Sum`          | `{"Sum": [<node>, ..]}`                                    |
+//! | `Product`      | `{"Product": [<node>, ..]}`                                |
+//! | `Div`, `Pow`   | `{"Div": [<node>, <node>]}`                                |
+//! | `Derivative`   | `{"Derivative": {"inner": <node>, "var": "x", "order": 2}}`|
+//! | `Poly`         | `{"Poly": {"base": <node>, "terms": [[pow, coeff], ..]}}`  |
+//! | `Piecewise`    | `{"Piecewise": {"branches": [<branch>, ..], "default": <node>}}` |
+//!
+//! A branch is `{"when": {"op": "Lt", "lhs": <node>, "rhs": <node>}, "then": <node>}`.
+//!
+//! IDs and hashes are session-local and are recomputed on deserialization. Symbols and
+//! function names are stored by name (see the symbol module) and re-interned when read
+//! back. Deserialization rejects trees deeper than [`DEFAULT_MAX_DEPTH`] or larger than
+//! [`DEFAULT_MAX_NODES`] while reading them, so untrusted input cannot exhaust the stack.
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{CompareOp, Condition, Expr, ExprKind, Polynomial};
+use crate::core::InternedSymbol;
+use crate::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_NODES};
+
+/// Version written into every serialized expression; bump on incompatible changes
+const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct DocumentRef<'expr> {
+    schema: u32,
+    expr: Node<'expr>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    schema: u32,
+    expr: OwnedNode,
+}
+
+impl Serialize for Expr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DocumentRef {
+            schema: SCHEMA_VERSION,
+            expr: Node(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = Document::deserialize(deserializer)?;
+        if document.schema != SCHEMA_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported expression schema {}, expected {SCHEMA_VERSION}",
+                document.schema
+            )));
+        }
+        Ok(Self::unwrap_arc(document.expr.0))
+    }
+}
+
+impl Serialize for ExprKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ExprKindDef::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExprKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ExprKindDef::deserialize(deserializer)
+    }
+}
+
+/// Mirror of [`ExprKind`] whose children are written as bare nodes
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ExprKind")]
+enum ExprKindDef {
+    Number(f64),
+    Symbol(InternedSymbol),
+    FunctionCall {
+        name: InternedSymbol,
+        #[serde(with = "nodes")]
+        args: Vec<Arc<Expr>>,
+    },
+    Sum(#[serde(with = "nodes")] Vec<Arc<Expr>>),
+    Product(#[serde(with = "nodes")] Vec<Arc<Expr>>),
+    Div(
+        #[serde(with = "node")] Arc<Expr>,
+        #[serde(with = "node")] Arc<Expr>,
+    ),
+    Pow(
+        #[serde(with = "node")] Arc<Expr>,
+        #[serde(with = "node")] Arc<Expr>,
+    ),
+    Derivative {
+        #[serde(with = "node")]
+        inner: Arc<Expr>,
+        var: InternedSymbol,
+        order: u32,
+    },
+    Poly(Polynomial),
+    Piecewise {
+        #[serde(with = "branches")]
+        branches: Vec<(Condition, Arc<Expr>)>,
+        #[serde(with = "node")]
+        default: Arc<Expr>,
+    },
+}
+
+/// An expression written as a node, without the document header
+struct Node<'expr>(&'expr Expr);
+
+impl Serialize for Node<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ExprKindDef::serialize(&self.0.kind, serializer)
+    }
+}
+
+/// A node read back, counted against the depth and node limits
+struct OwnedNode(Arc<Expr>);
+
+impl<'de> Deserialize<'de> for OwnedNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let _level = TreeBudget::enter::<D::Error>()?;
+        let kind = ExprKindDef::deserialize(deserializer)?;
+        Ok(Self(Arc::new(Expr::new(kind))))
+    }
+}
+
+thread_local! {
+    /// `(depth, nodes)` of the tree being deserialized on this thread
+    static TREE_SIZE: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+/// One level of nesting; the node count resets when the outermost level closes
+struct TreeBudget;
+
+impl TreeBudget {
+    fn enter<E: serde::de::Error>() -> Result<Self, E> {
+        let (depth, nodes) = TREE_SIZE.get();
+        if depth >= DEFAULT_MAX_DEPTH {
+            return Err(E::custom(format!(
+                "expression is nested deeper than {DEFAULT_MAX_DEPTH} levels"
+            )));
+        }
+        if nodes >= DEFAULT_MAX_NODES {
+            return Err(E::custom(format!(
+                "expression has more than {DEFAULT_MAX_NODES} nodes"
+            )));
+        }
+        TREE_SIZE.set((depth + 1, nodes + 1));
+        Ok(Self)
+    }
+}
+
+impl Drop for TreeBudget {
+    fn drop(&mut self) {
+        let (depth, nodes) = TREE_SIZE.get();
+        let depth = depth - 1;
+        TREE_SIZE.set((depth, if depth == 0 { 0 } else { nodes }));
+    }
+}
+
+mod node {
+    use super::{Arc, Deserialize, Deserializer, Expr, Node, OwnedNode, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(expr: &Arc<Expr>, serializer: S) -> Result<S::Ok, S::Error> {
+        Node(expr).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<Expr>, D::Error> {
+        OwnedNode::deserialize(deserializer).map(|node| node.0)
+    }
+}
+
+mod nodes {
+    use super::{Arc, Deserialize, Deserializer, Expr, Node, OwnedNode, Serializer};
+
+    #[allow(
+        clippy::ptr_arg,
+        reason = "serde's `with` passes a reference to the field type"
+    )]
+    pub fn serialize<S: Serializer>(
+        exprs: &Vec<Arc<Expr>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(exprs.iter().map(|expr| Node(expr)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Arc<Expr>>, D::Error> {
+        let nodes = Vec::<OwnedNode>::deserialize(deserializer)?;
+        Ok(nodes.into_iter().map(|node| node.0).collect())
+    }
+}
+
+mod branches {
+    use super::{
+        Arc, Condition, Deserialize, Deserializer, Expr, Node, OwnedNode, Serialize, Serializer,
+    };
+
+    #[derive(Serialize)]
+    struct BranchRef<'expr> {
+        when: &'expr Condition,
+        then: Node<'expr>,
+    }
+
+    #[derive(Deserialize)]
+    struct BranchData {
+        when: Condition,
+        then: OwnedNode,
+    }
+
+    #[allow(
+        clippy::ptr_arg,
+        reason = "serde's `with` passes a reference to the field type"
+    )]
+    pub fn serialize<S: Serializer>(
+        branches: &Vec<(Condition, Arc<Expr>)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(branches.iter().map(|(when, then)| BranchRef {
+            when,
+            then: Node(then),
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Condition, Arc<Expr>)>, D::Error> {
+        let branches = Vec::<BranchData>::deserialize(deserializer)?;
+        Ok(branches
+            .into_iter()
+            .map(|branch| (branch.when, branch.then.0))
+            .collect())
+    }
+}
+
+/// Borrowed condition layout used for serialization
+#[derive(Serialize)]
+struct ConditionRef<'expr> {
+    op: CompareOp,
+    lhs: Node<'expr>,
+    rhs: Node<'expr>,
+}
+
+/// Owned condition layout used for deserialization
+#[derive(Deserialize)]
+struct ConditionData {
+    op: CompareOp,
+    lhs: OwnedNode,
+    rhs: OwnedNode,
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConditionRef {
+            op: self.op,
+            lhs: Node(&self.lhs),
+            rhs: Node(&self.rhs),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ConditionData::deserialize(deserializer)?;
+        Ok(Self {
+            op: data.op,
+            lhs: data.lhs.0,
+            rhs: data.rhs.0,
+        })
+    }
+}
+
+/// Borrowed polynomial layout used for serialization
+#[derive(Serialize)]
+struct PolynomialRef<'poly> {
+    base: Node<'poly>,
+    terms: &'poly [(u32, f64)],
+}
+
+/// Owned polynomial layout used for deserialization
+#[derive(Deserialize)]
+struct PolynomialData {
+    base: OwnedNode,
+    terms: Vec<(u32, f64)>,
+}
+
+impl Serialize for Polynomial {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PolynomialRef {
+            base: Node(self.base()),
+            terms: self.terms(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Polynomial {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = PolynomialData::deserialize(deserializer)?;
+        // Re-insert term by term so hand-written input keeps the sorted, non-zero invariant
+        let mut poly = Self::zero(data.base.0);
+        for (pow, coeff) in data.terms {
+            poly.add_term(pow, coeff);
+        }
+        Ok(poly)
+    }
+}
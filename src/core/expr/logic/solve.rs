@@ -0,0 +1,99 @@
+//! Symbolic roots of expressions that are linear or quadratic in one variable.
+//!
+//! The expression is simplified, then collected into coefficients `c0 + c1*x + c2*x^2`
+//! whose entries may hold any other symbols. Leading coefficients that simplify to zero
+//! lower the degree, so `(x + 1)^2 - x^2` is solved as a linear equation.
+
+use crate::core::DiffError;
+use crate::core::Symbol;
+use crate::simplification::Simplify;
+
+use super::Expr;
+use super::poly_coefficients::{degree_in, simplified_coefficients};
+
+/// Highest degree that [`Expr::solve`] handles
+const MAX_DEGREE: u32 = 2;
+
+impl Expr {
+    /// Solve `self = 0` for `var`
+    ///
+    /// Handles expressions that are polynomial of degree 1 or 2 in `var`; every other
+    /// symbol is treated as a coefficient. Linear equations give `[-b/a]`; quadratics
+    /// give the two roots of the quadratic formula, with the discriminant kept symbolic.
+    /// A discriminant that is a negative number gives no real roots (an empty `Vec`),
+    /// and one that is zero gives the double root once. Two roots come as
+    /// `(-b - √Δ)/(2a)` then `(-b + √Δ)/(2a)`, swapped when `a` is a negative number,
+    /// so numeric roots are ascending.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::symb;
+    /// let x = symb("solve_doc_x");
+    /// let roots = (x.pow(2.0) - 4.0).solve(&x).unwrap();
+    /// assert_eq!(roots, vec![(-2.0).into(), 2.0.into()]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnsupportedExpression`] if the expression is not a polynomial
+    /// in `var` or its degree is 0 or above 2 (the message names the degree), and
+    /// propagates simplification errors.
+    pub fn solve(&self, var: &Symbol) -> Result<Vec<Self>, DiffError> {
+        let simplify = Simplify::new();
+        let expr = simplify.simplify(self)?;
+        let name = var.to_expr();
+
+        let degree = degree_in(&expr, var.id()).ok_or_else(|| {
+            DiffError::UnsupportedExpression(format!("{expr} is not a polynomial in {name}"))
+        })?;
+        let unsupported = |found: u32| {
+            DiffError::UnsupportedExpression(format!(
+                "{expr} has degree {found} in {name}; only linear and quadratic equations can be solved"
+            ))
+        };
+        if degree > MAX_DEGREE {
+            return Err(unsupported(degree));
+        }
+
+        // Cancelling leading terms lower the degree
+        let coeffs = simplified_coefficients(&expr, var.id(), &simplify)?;
+        let roots = match coeffs.as_slice() {
+            [c, b] => vec![Self::div_expr(c.clone().negate(), b.clone())],
+            [c, b, a] => quadratic_roots(a, b, c, &simplify)?,
+            _ => return Err(unsupported(0)),
+        };
+
+        roots.iter().map(|root| simplify.simplify(root)).collect()
+    }
+}
+
+/// Roots of `a*x^2 + b*x + c` from the quadratic formula
+fn quadratic_roots(
+    a: &Expr,
+    b: &Expr,
+    c: &Expr,
+    simplify: &Simplify,
+) -> Result<Vec<Expr>, DiffError> {
+    let discriminant = simplify.simplify(&Expr::sub_expr(
+        Expr::pow_static(b.clone(), Expr::number(2.0)),
+        Expr::product(vec![Expr::number(4.0), a.clone(), c.clone()]),
+    ))?;
+    let two_a = Expr::mul_expr(Expr::number(2.0), a.clone());
+    let neg_b = b.clone().negate();
+    if discriminant.is_zero_num() {
+        return Ok(vec![Expr::div_expr(neg_b, two_a)]);
+    }
+    Ok(match discriminant.as_number() {
+        Some(d) if d < 0.0 => Vec::new(),
+        _ => {
+            let root = discriminant.sqrt();
+            let mut roots = vec![
+                Expr::div_expr(Expr::sub_expr(neg_b.clone(), root.clone()), two_a.clone()),
+                Expr::div_expr(Expr::add_expr(neg_b, root), two_a),
+            ];
+            if a.as_number().is_some_and(|a| a < 0.0) {
+                roots.reverse();
+            }
+            roots
+        }
+    })
+}
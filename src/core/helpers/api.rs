@@ -8,7 +8,7 @@
 // Error types — public API
 // ============================================================================
 
-pub use super::logic::{DiffError, Span};
+pub use super::logic::{DiffError, SourceLocation, Span};
 
 // ============================================================================
 // Known symbol IDs — re-export the logic submodule.
@@ -3,18 +3,36 @@
 //! This module provides:
 //! - `DiffError` - The main error enum for all parsing/differentiation failures
 //! - `Span` - Source location tracking for precise error messages
+//! - `SourceLocation` - A span together with the text it points into
 
+use crate::core::InternedSymbol;
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::Arc;
 
 /// Source location span for error reporting
 /// Represents a range of characters in the input string
+///
+/// Offsets are stored as `u32` (saturating) to keep [`DiffError`] small.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Span {
     /// Start position (0-indexed byte offset)
-    start: usize,
+    start: u32,
     /// End position (exclusive, 0-indexed byte offset)
-    end: usize,
+    end: u32,
+}
+
+/// Narrow a byte offset for storage in a [`Span`], saturating at `u32::MAX`
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "Offsets above u32::MAX are clamped before the cast"
+)]
+const fn offset(pos: usize) -> u32 {
+    if pos > u32::MAX as usize {
+        u32::MAX
+    } else {
+        pos as u32
+    }
 }
 
 impl Span {
@@ -24,11 +42,14 @@ impl Span {
     pub const fn new(start: usize, end: usize) -> Self {
         if end < start {
             Self {
-                start: end,
-                end: start,
+                start: offset(end),
+                end: offset(start),
             }
         } else {
-            Self { start, end }
+            Self {
+                start: offset(start),
+                end: offset(end),
+            }
         }
     }
 
@@ -36,10 +57,7 @@ impl Span {
     #[inline]
     #[must_use]
     pub const fn at(pos: usize) -> Self {
-        Self {
-            start: pos,
-            end: pos + 1,
-        }
+        Self::new(pos, pos.saturating_add(1))
     }
 
     /// Create an empty/unknown span
@@ -53,14 +71,14 @@ impl Span {
     #[inline]
     #[must_use]
     pub const fn start(&self) -> usize {
-        self.start
+        self.start as usize
     }
 
     /// Get the end position
     #[inline]
     #[must_use]
     pub const fn end(&self) -> usize {
-        self.end
+        self.end as usize
     }
 
     /// Check if this span has valid location info
@@ -78,11 +96,88 @@ impl Span {
         if !self.is_valid() {
             String::new()
         } else if self.end - self.start == 1 {
-            format!(" at position {}", self.start + 1)
+            format!(" at position {}", self.start() + 1)
         } else {
-            format!(" at positions {}-{}", self.start + 1, self.end)
+            format!(" at positions {}-{}", self.start() + 1, self.end)
+        }
+    }
+}
+
+/// Where in a parsed formula an error raised after parsing comes from
+///
+/// Holds the whole source, so the error can quote the line with a caret under
+/// the span. See [`DiffError::Located`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    source: Arc<str>,
+    span: Span,
+}
+
+impl SourceLocation {
+    /// Create a location of `span` in `source`
+    pub fn new(source: impl Into<Arc<str>>, span: Span) -> Self {
+        Self {
+            source: source.into(),
+            span,
         }
     }
+
+    /// The span, as byte offsets into [`source`](Self::source)
+    #[inline]
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The formula the span points into
+    #[inline]
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Byte offset of the start of the line containing the span
+    fn line_start(&self) -> usize {
+        let before = self.source.get(..self.span.start()).unwrap_or_default();
+        before.rfind('\n').map_or(0, |newline| newline + 1)
+    }
+
+    /// Line of the start of the span (1-indexed)
+    #[must_use]
+    pub fn line(&self) -> usize {
+        let before = self.source.get(..self.span.start()).unwrap_or_default();
+        before.matches('\n').count() + 1
+    }
+
+    /// Column of the start of the span, in characters (1-indexed)
+    #[must_use]
+    pub fn column(&self) -> usize {
+        let line = self
+            .source
+            .get(self.line_start()..self.span.start())
+            .unwrap_or_default();
+        line.chars().count() + 1
+    }
+
+    /// The source line containing the span, and a line of carets under the span
+    ///
+    /// A span running past the end of its line is underlined to the end of the line.
+    #[must_use]
+    pub fn snippet(&self) -> (&str, String) {
+        let rest = self.source.get(self.line_start()..).unwrap_or_default();
+        let text = rest.lines().next().unwrap_or_default();
+        let underlined = self
+            .source
+            .get(self.span.start()..self.span.end())
+            .unwrap_or_default();
+        let underlined = underlined.lines().next().unwrap_or_default();
+        let carets = format!(
+            "{}{}",
+            " ".repeat(self.column() - 1),
+            "^".repeat(underlined.chars().count().max(1))
+        );
+        (text, carets)
+    }
 }
 
 /// Errors that can occur during parsing and differentiation
@@ -147,8 +242,58 @@ pub enum DiffError {
         /// The conflicting name.
         name: String,
     },
+    /// A symbol from another [`SymbolScope`](crate::SymbolScope), or a global symbol
+    /// named like one of the scope's, reached an operation bound to a scope.
+    ScopeMismatch {
+        /// Name of the foreign symbol.
+        name: String,
+    },
+    /// A simplification rule was selected by a name no rule has.
+    UnknownRule {
+        /// The unknown rule name.
+        name: String,
+    },
+    /// A user rule was registered under a name another rule already has.
+    DuplicateRule {
+        /// The duplicated rule name.
+        name: String,
+    },
+    /// A symbol rename mapping is inconsistent with itself or with the expressions.
+    InvalidRename {
+        /// The symbol being renamed.
+        from: String,
+        /// Why the rename was refused, naming the new name.
+        reason: String,
+    },
     /// An operation is not supported (e.g., unsupported function).
     UnsupportedOperation(String),
+    /// A function is not differentiable everywhere its argument can go, and the
+    /// builder was asked to reject such derivatives (`Diff::domain_safe`).
+    NonDifferentiable {
+        /// The function, such as `abs`.
+        function: String,
+        /// Where and why the derivative does not exist.
+        note: String,
+    },
+    /// A result would divide by an expression that simplifies to zero.
+    DivisionByZero {
+        /// The denominator, as written before it simplified to zero.
+        denominator: String,
+    },
+    /// No rewrite found the limit of an expression, or the limit does not exist.
+    LimitUndetermined {
+        /// The expression.
+        expr: String,
+        /// The variable and the point it approaches, such as `x -> 0`.
+        point: String,
+    },
+    /// No antiderivative rule applies to a term of the integrand.
+    CannotIntegrate {
+        /// The term that could not be integrated.
+        term: String,
+        /// The integration variable.
+        var: String,
+    },
     /// An ambiguous token sequence was found.
     AmbiguousSequence {
         /// The ambiguous sequence.
@@ -158,20 +303,48 @@ pub enum DiffError {
         /// Location of the error in the source.
         span: Option<Span>,
     },
+    /// Definitions of a `where` clause refer to each other in a cycle.
+    CircularDefinition {
+        /// The names on the cycle, starting and ending with the same name.
+        cycle: Vec<String>,
+        /// Location of the name the cycle starts and ends with.
+        span: Option<Span>,
+    },
 
     // Safety limits
     /// The expression exceeded the maximum allowed AST depth.
     MaxDepthExceeded,
     /// The expression exceeded the maximum allowed node count.
     MaxNodesExceeded,
+    /// An intermediate result of a higher-order derivative exceeded a safety limit.
+    DerivativeOrderLimit {
+        /// Order of the derivative whose result exceeded the limit.
+        order: usize,
+        /// The limit that was exceeded ([`MaxDepthExceeded`](Self::MaxDepthExceeded) or
+        /// [`MaxNodesExceeded`](Self::MaxNodesExceeded)).
+        limit: Box<Self>,
+    },
+    /// An expansion produced more terms than its limit allows.
+    ExpansionTooLarge {
+        /// Number of terms the expansion reached.
+        terms: usize,
+        /// Maximum allowed number of terms.
+        limit: usize,
+    },
 
     // Compilation errors (for CompiledEvaluator)
-    /// Expression contains unsupported constructs for numeric evaluation.
+    /// Expression contains constructs the operation does not support (numeric
+    /// evaluation, code generation, or polynomial operations such as
+    /// [`Expr::solve`](crate::Expr::solve) and [`Expr::div_poly`](crate::Expr::div_poly)).
     UnsupportedExpression(String),
     /// Function not supported in compiled evaluation.
-    UnsupportedFunction(String),
+    ///
+    /// Holds the interned name, so raising it only bumps a reference count.
+    UnsupportedFunction(InternedSymbol),
     /// Variable not found in parameter list during compilation.
-    UnboundVariable(String),
+    ///
+    /// Holds the interned symbol, so raising it only bumps a reference count.
+    UnboundVariable(InternedSymbol),
     /// Expression requires too much stack depth.
     StackOverflow {
         /// Current stack depth.
@@ -190,6 +363,11 @@ pub enum DiffError {
     },
     /// Column lengths are not all equal.
     EvalColumnLengthMismatch,
+    /// Parameter arrays whose lengths do not broadcast to one batch length.
+    EvalBroadcastMismatch {
+        /// Length of every parameter array, in parameter order.
+        lengths: Vec<usize>,
+    },
     /// Output buffer is too small.
     EvalOutputTooSmall {
         /// Number of data points needed.
@@ -197,6 +375,21 @@ pub enum DiffError {
         /// Output buffer size.
         got: usize,
     },
+    /// Data series passed to `CompiledEvaluator::evaluate_with_data` do not match the
+    /// series the program reads, or a data series was used as a variable.
+    InvalidDataSeries {
+        /// The data series.
+        name: String,
+        /// What was wrong with it.
+        reason: String,
+    },
+    /// Bytes passed to `CompiledEvaluator::from_bytes` are not a valid program.
+    InvalidEvaluatorBytes {
+        /// Byte offset at which decoding failed.
+        offset: usize,
+        /// What was wrong at that offset.
+        reason: &'static str,
+    },
 
     // UserFunction errors
     /// Partial derivative index exceeds function arity.
@@ -206,8 +399,38 @@ pub enum DiffError {
         /// Maximum allowed arity.
         max_arity: usize,
     },
+    /// Functions of a library call each other in a cycle.
+    CyclicFunctionDefinition {
+        /// The functions on the cycle, starting and ending with the same name.
+        cycle: Vec<String>,
+    },
+
+    // Uncertainty errors
+    /// The expression already contains the uncertainty of a propagation variable.
+    UncertaintyAlreadyPropagated {
+        /// The propagation variable.
+        var: String,
+        /// The uncertainty symbol found in the expression.
+        symbol: String,
+    },
+
+    // Source locations
+    /// An error raised after parsing, with where its cause appears in the formula.
+    ///
+    /// The string entry points (`diff_str`, `simplify_str`, ...) attach it, and
+    /// [`SourceMap::locate`](crate::SourceMap::locate) does for expressions parsed by
+    /// [`parse_configured`](crate::parse_configured) with a source map.
+    Located {
+        /// The error itself.
+        error: Box<Self>,
+        /// The name or subexpression of the formula the error is about.
+        location: Box<SourceLocation>,
+    },
 }
 
+// Every fallible parse/diff/compile call returns this enum, so keep it to eight words.
+const _: () = assert!(size_of::<DiffError>() <= 64);
+
 impl DiffError {
     // Convenience constructors for backward compatibility
 
@@ -242,6 +465,33 @@ impl DiffError {
             span: None,
         }
     }
+
+    /// Where in the source formula this error comes from, if it is
+    /// [`Located`](Self::Located)
+    #[must_use]
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            Self::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// This error without its source location, for matching on the variant
+    ///
+    /// ```
+    /// use symb_anafis::{Diff, DiffError};
+    ///
+    /// let err = Diff::new().max_depth(2).diff_str("sin(cos(x))", "x", &[]).unwrap_err();
+    /// assert!(err.location().is_some());
+    /// assert!(matches!(err.unlocated(), DiffError::MaxDepthExceeded));
+    /// ```
+    #[must_use]
+    pub fn unlocated(&self) -> &Self {
+        match self {
+            Self::Located { error, .. } => error.unlocated(),
+            _ => self,
+        }
+    }
 }
 
 impl Display for DiffError {
@@ -313,9 +563,36 @@ impl Display for DiffError {
                     "Name '{name}' appears in both fixed_vars and custom_functions"
                 )
             }
+            Self::ScopeMismatch { name } => {
+                write!(f, "Symbol '{name}' belongs to a different symbol scope")
+            }
+            Self::UnknownRule { name } => {
+                write!(
+                    f,
+                    "Unknown simplification rule '{name}' (see Simplify::list_rules)"
+                )
+            }
+            Self::DuplicateRule { name } => {
+                write!(f, "Simplification rule '{name}' is already registered")
+            }
+            Self::InvalidRename { from, reason } => {
+                write!(f, "Cannot rename '{from}': {reason}")
+            }
             Self::UnsupportedOperation(msg) => {
                 write!(f, "Unsupported operation: {msg}")
             }
+            Self::NonDifferentiable { function, note } => {
+                write!(f, "'{function}' is not differentiable: {note}")
+            }
+            Self::DivisionByZero { denominator } => {
+                write!(f, "Division by zero: {denominator} simplifies to 0")
+            }
+            Self::LimitUndetermined { expr, point } => {
+                write!(f, "Cannot determine the limit of '{expr}' as {point}")
+            }
+            Self::CannotIntegrate { term, var } => {
+                write!(f, "Cannot integrate '{term}' with respect to '{var}'")
+            }
             Self::AmbiguousSequence {
                 sequence,
                 suggestion,
@@ -337,6 +614,15 @@ impl Display for DiffError {
             Self::MaxNodesExceeded => {
                 write!(f, "Expression size exceeds maximum node count limit")
             }
+            Self::DerivativeOrderLimit { order, limit } => {
+                write!(f, "{limit} at derivative order {order}")
+            }
+            Self::ExpansionTooLarge { terms, limit } => {
+                write!(
+                    f,
+                    "Expansion reached {terms} terms, exceeding the limit of {limit}"
+                )
+            }
             // Compile errors
             Self::UnsupportedExpression(msg) => {
                 write!(f, "Unsupported expression: {msg}")
@@ -363,18 +649,65 @@ impl Display for DiffError {
             Self::EvalColumnLengthMismatch => {
                 write!(f, "All columns must have the same length")
             }
+            Self::EvalBroadcastMismatch { lengths } => {
+                let lengths: Vec<String> = lengths.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "Cannot broadcast parameter arrays of lengths [{}]: each must have length 1 or the batch length",
+                    lengths.join(", ")
+                )
+            }
             Self::EvalOutputTooSmall { needed, got } => {
                 write!(
                     f,
                     "Output buffer too small: need {needed} elements, got {got}"
                 )
             }
+            Self::InvalidDataSeries { name, reason } => {
+                write!(f, "Data series '{name}' {reason}")
+            }
+            Self::InvalidEvaluatorBytes { offset, reason } => {
+                write!(
+                    f,
+                    "Invalid compiled evaluator data at byte {offset}: {reason}"
+                )
+            }
             Self::InvalidPartialIndex { index, max_arity } => {
                 write!(
                     f,
                     "Partial derivative index {index} exceeds maximum arity {max_arity}"
                 )
             }
+            Self::CircularDefinition { cycle, span } => write!(
+                f,
+                "Circular definition: {}{}",
+                cycle.join(" -> "),
+                span.map_or(String::new(), |s| s.display())
+            ),
+            Self::CyclicFunctionDefinition { cycle } => {
+                write!(f, "Cyclic function definition: {}", cycle.join(" -> "))
+            }
+            Self::UncertaintyAlreadyPropagated { var, symbol } => {
+                write!(
+                    f,
+                    "Expression already contains '{symbol}', the uncertainty of '{var}'; \
+                     propagating again would count it twice"
+                )
+            }
+            Self::Located { error, location } => {
+                let position = if location.line() == 1 {
+                    format!("column {}", location.column())
+                } else {
+                    format!("line {}, column {}", location.line(), location.column())
+                };
+                let message = format!("{error} at {position}: ");
+                let (text, carets) = location.snippet();
+                write!(
+                    f,
+                    "{message}{text}\n{}{carets}",
+                    " ".repeat(message.chars().count())
+                )
+            }
         }
     }
 }
@@ -4,7 +4,7 @@
 //! Comparison is O(1) - just a u64 integer comparison.
 
 use crate::core::{InternedSymbol, lookup_by_id, symb_interned};
-use std::f64::consts::{E, PI};
+use std::f64::consts::{E, PI, TAU};
 use std::sync::LazyLock;
 
 /// Get the ID for an interned symbol (helper for the macro)
@@ -55,6 +55,8 @@ pub struct KnownSymbols {
     pub atan: u64,
     /// Two-argument inverse tangent
     pub atan2: u64,
+    /// Euclidean norm of two values
+    pub hypot: u64,
     /// Inverse cotangent
     pub acot: u64,
     /// Inverse secant
@@ -62,6 +64,20 @@ pub struct KnownSymbols {
     /// Inverse cosecant
     pub acsc: u64,
 
+    // Trigonometric in degrees
+    /// Sine of an angle in degrees
+    pub sind: u64,
+    /// Cosine of an angle in degrees
+    pub cosd: u64,
+    /// Tangent of an angle in degrees
+    pub tand: u64,
+    /// Inverse sine, in degrees
+    pub asind: u64,
+    /// Inverse cosine, in degrees
+    pub acosd: u64,
+    /// Inverse tangent, in degrees
+    pub atand: u64,
+
     // Hyperbolic
     /// Hyperbolic sine
     pub sinh: u64,
@@ -95,6 +111,22 @@ pub struct KnownSymbols {
     pub abs: u64,
     /// Sign function
     pub signum: u64,
+    /// Heaviside step function
+    pub heaviside: u64,
+    /// Dirac delta distribution
+    pub dirac: u64,
+    /// Derivative of the Dirac delta distribution
+    pub dirac_prime: u64,
+    /// Smaller of two values
+    pub min: u64,
+    /// Larger of two values
+    pub max: u64,
+    /// Value limited to a closed interval
+    pub clamp: u64,
+    /// Branchless select on a 0/1 condition
+    pub select: u64,
+    /// Sum over the points of declared data series
+    pub sum_data: u64,
 
     // Rounding functions
     /// Floor function
@@ -171,6 +203,8 @@ pub struct KnownSymbols {
     pub e: u64,
     /// Euler's number (uppercase variant)
     pub e_upper: u64,
+    /// Tau constant (`2*pi`)
+    pub tau: u64,
 }
 
 impl KnownSymbols {
@@ -194,9 +228,16 @@ impl KnownSymbols {
             acos: intern_id("acos"),
             atan: intern_id("atan"),
             atan2: intern_id("atan2"),
+            hypot: intern_id("hypot"),
             acot: intern_id("acot"),
             asec: intern_id("asec"),
             acsc: intern_id("acsc"),
+            sind: intern_id("sind"),
+            cosd: intern_id("cosd"),
+            tand: intern_id("tand"),
+            asind: intern_id("asind"),
+            acosd: intern_id("acosd"),
+            atand: intern_id("atand"),
             sinh: intern_id("sinh"),
             cosh: intern_id("cosh"),
             tanh: intern_id("tanh"),
@@ -211,6 +252,14 @@ impl KnownSymbols {
             acsch: intern_id("acsch"),
             abs: intern_id("abs"),
             signum: intern_id("signum"),
+            heaviside: intern_id("heaviside"),
+            dirac: intern_id("dirac"),
+            dirac_prime: intern_id("dirac_prime"),
+            min: intern_id("min"),
+            max: intern_id("max"),
+            clamp: intern_id("clamp"),
+            select: intern_id("select"),
+            sum_data: intern_id("sum_data"),
             floor: intern_id("floor"),
             ceil: intern_id("ceil"),
             round: intern_id("round"),
@@ -245,6 +294,7 @@ impl KnownSymbols {
             pi_title: intern_id("Pi"),
             e: intern_id("e"),
             e_upper: intern_id("E"),
+            tau: intern_id("tau"),
         }
     }
 }
@@ -269,12 +319,17 @@ pub fn get_symbol(id: u64) -> InternedSymbol {
     get_interned(id)
 }
 
-/// Check if a symbol ID is a known mathematical constant (pi, e, etc.)
+/// Check if a symbol ID is a known mathematical constant (pi, e, tau, etc.)
 /// O(1) comparison using pre-interned UIDs.
 #[inline]
 pub fn is_known_constant_by_id(id: u64) -> bool {
     let ks = &*KS;
-    id == ks.pi || id == ks.pi_upper || id == ks.pi_title || id == ks.e || id == ks.e_upper
+    id == ks.pi
+        || id == ks.pi_upper
+        || id == ks.pi_title
+        || id == ks.e
+        || id == ks.e_upper
+        || id == ks.tau
 }
 
 /// Get the numeric value of a known constant by symbol ID.
@@ -287,18 +342,20 @@ pub fn get_constant_value_by_id(id: u64) -> Option<f64> {
         Some(PI)
     } else if id == ks.e || id == ks.e_upper {
         Some(E)
+    } else if id == ks.tau {
+        Some(TAU)
     } else {
         None
     }
 }
 
-/// Check if a name is a known mathematical constant (pi, e, etc.)
-/// Returns true for any case variation: "pi", "PI", "Pi", "e", "E"
+/// Check if a name is a known mathematical constant (pi, e, tau, etc.)
+/// Returns true for any case variation: "pi", "PI", "Pi", "e", "E", and for "tau"
 ///
 /// Prefer `is_known_constant_by_id` when you already have a symbol ID.
 #[inline]
 pub fn is_known_constant(name: &str) -> bool {
-    matches!(name, "pi" | "PI" | "Pi" | "e" | "E")
+    matches!(name, "pi" | "PI" | "Pi" | "e" | "E" | "tau")
 }
 
 /// Get the numeric value of a known constant, if it matches.
@@ -310,6 +367,7 @@ pub fn get_constant_value(name: &str) -> Option<f64> {
     match name {
         "pi" | "PI" | "Pi" => Some(PI),
         "e" | "E" => Some(E),
+        "tau" => Some(TAU),
         _ => None,
     }
 }
@@ -6,7 +6,7 @@ pub mod traits;
 pub mod view;
 
 // Staircase re-exports: public API items → bare pub use; crate-internal → pub(crate) use
-pub use error::{DiffError, Span};
+pub use error::{DiffError, SourceLocation, Span};
 pub use view::ExprView;
 
 #[cfg(test)]
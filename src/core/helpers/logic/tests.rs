@@ -75,6 +75,15 @@ mod error_tests {
             format!("{err4}"),
             "Expression nesting depth exceeds maximum limit"
         );
+
+        let err5 = DiffError::DerivativeOrderLimit {
+            order: 3,
+            limit: Box::new(DiffError::MaxNodesExceeded),
+        };
+        assert_eq!(
+            format!("{err5}"),
+            "Expression size exceeds maximum node count limit at derivative order 3"
+        );
     }
 
     #[test]
@@ -97,6 +106,160 @@ mod error_tests {
             _ => panic!("Wrong error type"),
         }
     }
+
+    #[test]
+    fn test_symbol_error_display() {
+        let x = crate::core::symb_interned("err_display_x");
+        let f = crate::core::symb_interned("err_display_f");
+        assert_eq!(
+            DiffError::UnboundVariable(x).to_string(),
+            "Unbound variable: err_display_x"
+        );
+        assert_eq!(
+            DiffError::UnsupportedFunction(f).to_string(),
+            "Unsupported function for evaluation: err_display_f"
+        );
+    }
+
+    #[test]
+    fn test_span_saturates() {
+        let span = Span::new(usize::MAX, 3);
+        assert_eq!(span.start(), 3);
+        assert_eq!(span.end(), u32::MAX as usize);
+    }
+
+    #[test]
+    fn test_diff_error_size() {
+        assert!(size_of::<DiffError>() <= 64, "{}", size_of::<DiffError>());
+        assert!(size_of::<Result<f64, DiffError>>() <= 64);
+    }
+}
+
+// Allocation counting is per thread, so parallel tests do not see each other's allocations
+#[allow(
+    unsafe_code,
+    clippy::unwrap_used,
+    clippy::panic,
+    reason = "A counting global allocator needs unsafe forwarding to System"
+)]
+mod alloc_tests {
+    use super::super::error::DiffError;
+    use crate::{CompiledEvaluator, ParseScratch, parse, parse_reuse};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::collections::HashSet;
+    use std::hint::black_box;
+
+    struct CountingAlloc;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // SAFETY: defers to `System`; only bumps a const-initialized thread-local counter
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.try_with(|n| n.set(n.get() + 1)).ok();
+            // SAFETY: forwarded unchanged
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            // SAFETY: forwarded unchanged
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.try_with(|n| n.set(n.get() + 1)).ok();
+            // SAFETY: forwarded unchanged
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    fn allocations_during(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    #[test]
+    fn test_unbound_variable_does_not_allocate() {
+        let sym = crate::core::symb_interned("alloc_unbound_x");
+        let allocations = allocations_during(|| {
+            for _ in 0..1_000_000 {
+                drop(black_box(DiffError::UnboundVariable(sym.clone())));
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn test_evaluate_ok_path_does_not_allocate() {
+        let x = crate::symb("alloc_eval_x");
+        let expr = x.sin() * x.to_expr() + 1.0;
+        let eval = CompiledEvaluator::compile(&expr, &[&x], None).unwrap();
+        let mut total = 0.0;
+        let allocations = allocations_during(|| {
+            for i in 0..10_000 {
+                total += eval.evaluate(black_box(&[f64::from(i) * 1e-3]));
+            }
+        });
+        assert!(total.is_finite());
+        assert_eq!(allocations, 0);
+    }
+
+    const REPARSED: &str = "alloc_rate * exp(-alloc_decay * t) + alloc_offset * sin(2 t)";
+
+    #[test]
+    fn test_reparse_identifiers_do_not_allocate() {
+        let (symbols, functions) = (HashSet::new(), HashSet::new());
+        let mut scratch = ParseScratch::new();
+        // The buffers settle after the second call, once both have been swapped in
+        for _ in 0..2 {
+            let warmup = scratch.intern_identifiers(REPARSED, &symbols, &functions);
+            assert_eq!(warmup.unwrap(), 5);
+        }
+
+        let allocations = allocations_during(|| {
+            for _ in 0..1_000 {
+                let count = scratch.intern_identifiers(black_box(REPARSED), &symbols, &functions);
+                assert_eq!(count.unwrap(), 5);
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn test_reparse_allocates_only_the_tree() {
+        let (symbols, functions) = (HashSet::new(), HashSet::new());
+        let mut scratch = ParseScratch::new();
+        let expected = parse(REPARSED, &symbols, &functions, None).unwrap();
+        let mut per_call = Vec::new();
+        for _ in 0..1_000 {
+            let allocations = allocations_during(|| {
+                let expr = parse_reuse(
+                    &mut scratch,
+                    black_box(REPARSED),
+                    &symbols,
+                    &functions,
+                    None,
+                );
+                assert_eq!(expr.unwrap(), expected);
+            });
+            per_call.push(allocations);
+        }
+        let fresh = allocations_during(|| {
+            drop(parse(black_box(REPARSED), &symbols, &functions, None).unwrap());
+        });
+
+        // Once the buffers have settled, every call allocates the same amount, and less
+        // than parsing with fresh buffers
+        assert!(per_call[2..].iter().all(|&n| n == per_call[2]));
+        assert!(per_call[2] < fresh);
+    }
 }
 
 #[allow(
@@ -114,12 +277,34 @@ mod traits_tests {
     #[test]
     fn test_is_zero() {
         assert!(is_zero(0.0));
-        assert!(is_zero(1e-15));
-        assert!(is_zero(-1e-15));
+        assert!(is_zero(-0.0));
+        assert!(!is_zero(1e-15));
+        assert!(!is_zero(-1.38e-23));
         assert!(!is_zero(0.1));
         assert!(!is_zero(-0.1));
     }
 
+    #[test]
+    fn test_cancels() {
+        assert!(cancels(0.1 + 0.2 - 0.3, 0.3));
+        assert!(cancels(1e-30 * (0.1 + 0.2 - 0.3), 3e-31));
+        assert!(!cancels(1e-30, 1e-30));
+        assert!(!cancels(1e-17, 1e-17));
+        assert!(cancels(0.0, 0.0));
+        assert!(!cancels(f64::INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_small_rational() {
+        assert_eq!(small_rational(0.1), Some((1, 10)));
+        assert_eq!(small_rational(-2.75), Some((-11, 4)));
+        assert_eq!(small_rational(2.0 / 3.0), Some((2, 3)));
+        assert_eq!(small_rational(0.1 + 0.2), None);
+        assert_eq!(small_rational(std::f64::consts::PI), None);
+        assert_eq!(small_rational(3.0), None);
+        assert_eq!(small_rational(f64::NAN), None);
+    }
+
     #[test]
     fn test_is_one() {
         assert!(is_one(1.0));
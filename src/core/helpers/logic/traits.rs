@@ -94,15 +94,120 @@ impl<T> MathScalar for T where
 // These functions provide safe floating-point comparisons to avoid
 // precision issues like `1.0/3.0 * 3.0 != 1.0`.
 
-/// Check if a floating point number is effectively 0.0
+/// Check if a floating point number is 0.0
 ///
-/// Uses a tolerance of `1e-15` for comparison.
+/// The comparison is exact: coefficients such as `1.38e-23` are routine in scientific
+/// formulas and must not be mistaken for zero. Use [`cancels`] to detect a sum whose
+/// operands cancelled up to rounding.
 /// This is an internal utility function for algebraic simplification.
 #[inline]
 pub fn is_zero(n: f64) -> bool {
-    n.abs() < EPSILON
+    n == 0.0
+}
+
+/// Map `-0.0` to `0.0`, leaving every other value, NaN included, unchanged
+///
+/// Expressions never hold a negative zero: it would print as `0` yet hash, compare by
+/// bits and propagate through `signum` or `1/x` differently from the zero it displays as.
+/// Runtime evaluation is not affected; compiled code keeps IEEE semantics for its inputs.
+#[inline]
+pub fn unsigned_zero(n: f64) -> f64 {
+    if n == 0.0 { 0.0 } else { n }
+}
+
+/// Check if `sum` is zero relative to `scale`, the largest magnitude it was computed from
+///
+/// `0.1 + 0.2 - 0.3` leaves a residue around `1e-17` that should count as an exact
+/// cancellation, while `1e-17` written by the user is a genuine coefficient; the tolerance
+/// is therefore relative to the operands rather than absolute.
+#[inline]
+pub fn cancels(sum: f64, scale: f64) -> bool {
+    sum.is_finite() && sum.abs() <= EPSILON * scale.abs()
 }
 
+/// The integer nearest to `n`, if `n` differs from it only by rounding error
+///
+/// A nonzero `n` never snaps to zero: `1e-20` is a small coefficient, not a rounded `0`.
+#[inline]
+pub fn near_integer(n: f64) -> Option<f64> {
+    let rounded = n.round();
+    ((n - rounded).abs() < EPSILON && (rounded != 0.0 || n == 0.0)).then_some(rounded)
+}
+
+/// The fraction `(numerator, denominator)` a non-integral float stands for, if any
+///
+/// The shortest decimal form is read first, so `0.1` gives `(1, 10)` and `2.75` gives
+/// `(11, 4)`: a decimal literal round-trips through `f64`, which recovers what was written
+/// rather than its binary approximation. Floats needing more than 15 significant digits
+/// (the float nearest `PI`, say) fall back to the
+/// smallest fraction with a denominator up to [`MAX_SMALL_DENOMINATOR`] that rounds to
+/// exactly the same float (`2.0 / 3.0` gives `(2, 3)`). Rounding residue such as
+/// `0.30000000000000004` matches neither and is rejected. The fraction is reduced and
+/// its denominator is positive.
+pub fn small_rational(n: f64) -> Option<(i64, i64)> {
+    /// Most decimals accepted; `10^15` is the largest power of ten below 2^53.
+    const MAX_DECIMALS: usize = 15;
+    /// Exclusive bound on the digits read, i.e. at most 15 significant digits.
+    const MAX_DIGITS: i64 = 1_000_000_000_000_000;
+    if !n.is_finite() || n.fract() == 0.0 {
+        return None;
+    }
+    let sign = if n < 0.0 { -1 } else { 1 };
+    // `Display` for f64 prints the shortest round-trip form without an exponent
+    let text = format!("{}", n.abs());
+    if let Some((int_part, frac_part)) = text.split_once('.')
+        && frac_part.len() <= MAX_DECIMALS
+        && let Ok(digits) = format!("{int_part}{frac_part}").parse::<i64>()
+        && digits < MAX_DIGITS
+    {
+        let mut den = 10_i64.pow(u32::try_from(frac_part.len()).ok()?);
+        let mut num = digits;
+        // Strip common factors of 2 and 5, the only primes in a power of ten
+        for p in [2, 5] {
+            while num % p == 0 && den % p == 0 {
+                num /= p;
+                den /= p;
+            }
+        }
+        return Some((sign * num, den));
+    }
+    // Continued fraction convergents of |n|, stopping at the first exact match
+    let target = n.abs();
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::float_cmp,
+        reason = "Convergents are small integers; the match must be exact"
+    )]
+    let matches = |h: i64, k: i64| (h as f64) / (k as f64) == target;
+    // Non-integral floats are below 2^52, so the integer part fits
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Integer part below 2^52, see above"
+    )]
+    let (mut h_prev, mut h) = (1_i64, target.floor() as i64);
+    let (mut k_prev, mut k) = (0_i64, 1_i64);
+    let mut rest = target - target.floor();
+    while k <= MAX_SMALL_DENOMINATOR && rest != 0.0 {
+        if matches(h, k) {
+            return Some((sign * h, k));
+        }
+        let inverse = rest.recip();
+        let a = inverse.floor();
+        rest = inverse - a;
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "Overflowing partial quotients fail the checked products below"
+        )]
+        let a = a as i64;
+        (h_prev, h) = (h, a.checked_mul(h)?.checked_add(h_prev)?);
+        (k_prev, k) = (k, a.checked_mul(k)?.checked_add(k_prev)?);
+    }
+    (k <= MAX_SMALL_DENOMINATOR && matches(h, k)).then_some((sign * h, k))
+}
+
+/// Largest denominator [`small_rational`] accepts for floats without a short decimal form
+pub const MAX_SMALL_DENOMINATOR: i64 = 10_000;
+
 /// Check if a floating point number is effectively 1.0
 ///
 /// Uses a tolerance of `1e-15` for comparison.
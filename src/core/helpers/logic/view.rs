@@ -20,7 +20,7 @@
 //! }
 //! ```
 
-use crate::core::Expr;
+use crate::core::{Condition, Expr};
 use std::borrow::Cow;
 use std::sync::Arc;
 // =============================================================================
@@ -56,6 +56,7 @@ use std::sync::Arc;
 /// }
 /// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ExprView<'expr> {
     /// Number literal
     Number(f64),
@@ -98,6 +99,14 @@ pub enum ExprView<'expr> {
         /// Derivative order (1 = first derivative, 2 = second, etc.)
         order: u32,
     },
+
+    /// Piecewise definition: the first branch whose condition holds, else the default
+    Piecewise {
+        /// Guarded branches, in the order they are tried
+        branches: &'expr [(Condition, Arc<Expr>)],
+        /// Value when no condition holds
+        default: &'expr Expr,
+    },
 }
 
 impl ExprView<'_> {
@@ -144,4 +153,78 @@ impl ExprView<'_> {
             None
         }
     }
+
+    /// Get the terms if this is a sum (including expanded polynomials)
+    #[must_use]
+    pub fn as_sum(&self) -> Option<&[Arc<Expr>]> {
+        if let Self::Sum(terms) = self {
+            Some(terms.as_ref())
+        } else {
+            None
+        }
+    }
+
+    /// Get the factors if this is a product
+    #[must_use]
+    pub fn as_product(&self) -> Option<&[Arc<Expr>]> {
+        if let Self::Product(factors) = self {
+            Some(factors.as_ref())
+        } else {
+            None
+        }
+    }
+
+    /// Get the numerator and denominator if this is a division
+    #[must_use]
+    pub const fn as_div(&self) -> Option<(&Expr, &Expr)> {
+        if let Self::Div(num, den) = self {
+            Some((num, den))
+        } else {
+            None
+        }
+    }
+
+    /// Get the base and exponent if this is a power
+    #[must_use]
+    pub const fn as_pow(&self) -> Option<(&Expr, &Expr)> {
+        if let Self::Pow(base, exp) = self {
+            Some((base, exp))
+        } else {
+            None
+        }
+    }
+
+    /// Get the name and arguments if this is a function call
+    #[must_use]
+    pub const fn as_function(&self) -> Option<(&str, &[Arc<Expr>])> {
+        if let Self::Function { name, args } = self {
+            Some((name, args))
+        } else {
+            None
+        }
+    }
+
+    /// Get the inner expression, variable and order if this is a derivative
+    #[must_use]
+    pub const fn as_derivative(&self) -> Option<(&Expr, &str, u32)> {
+        if let Self::Derivative { inner, var, order } = self {
+            Some((inner, var, *order))
+        } else {
+            None
+        }
+    }
+
+    /// Get the branches and default if this is a piecewise expression
+    #[must_use]
+    #[allow(
+        clippy::type_complexity,
+        reason = "Mirrors the borrowed fields of the Piecewise variant"
+    )]
+    pub const fn as_piecewise(&self) -> Option<(&[(Condition, Arc<Expr>)], &Expr)> {
+        if let Self::Piecewise { branches, default } = self {
+            Some((branches, default))
+        } else {
+            None
+        }
+    }
 }
@@ -17,7 +17,11 @@ use slotmap::{DefaultKey, Key};
 pub use super::logic::InternedSymbol;
 
 /// Internal registry functions for crate-wide use.
-pub use super::logic::{key_from_id, lookup_by_id, symb_interned, symb_new_isolated};
+pub use super::logic::{
+    key_from_id, lookup_by_id, symb_interned, symb_new_isolated, uncertainty_target,
+};
+
+use super::logic::mark_uncertainty_of;
 
 // ============================================================================
 // Public API (re-exported to crate surface and library users)
@@ -25,6 +29,7 @@ pub use super::logic::{key_from_id, lookup_by_id, symb_interned, symb_new_isolat
 
 /// Errors that can occur during symbol operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SymbolError {
     /// Attempted to create a symbol with a name that's already registered.
     DuplicateName(String),
@@ -63,6 +68,9 @@ pub use super::logic::{
     symbol_names,
 };
 
+/// Private symbol namespaces outside the global table.
+pub use super::logic::SymbolScope;
+
 use crate::core::Expr;
 
 // ============================================================================
@@ -125,6 +133,30 @@ impl Symbol {
         lookup_by_id(self.id()).and_then(|s| s.name_arc())
     }
 
+    /// Mark this symbol as carrying the uncertainty of the variable `var`.
+    ///
+    /// Uncertainty propagation refuses expressions that already contain the
+    /// uncertainty of one of their propagation variables, since propagating
+    /// again would count it twice.
+    ///
+    /// ```
+    /// use symb_anafis::symb;
+    /// let u = symb("symbol_doc_u_x").as_uncertainty_of("x");
+    /// assert_eq!(u.uncertainty_of().as_deref(), Some("x"));
+    /// ```
+    #[must_use]
+    pub fn as_uncertainty_of(self, var: &str) -> Self {
+        mark_uncertainty_of(self.0, var);
+        self
+    }
+
+    /// The variable whose uncertainty this symbol carries, if it was marked with
+    /// [`as_uncertainty_of`](Self::as_uncertainty_of).
+    #[must_use]
+    pub fn uncertainty_of(&self) -> Option<String> {
+        uncertainty_target(self.0).map(|var| var.to_string())
+    }
+
     /// Convert to an `Expr`.
     #[must_use]
     pub fn to_expr(&self) -> Expr {
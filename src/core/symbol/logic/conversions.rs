@@ -1,5 +1,6 @@
 //! Type conversions for Expressions and Symbols.
 
+use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
 use std::sync::Arc;
 
 use crate::core::Expr;
@@ -34,3 +35,54 @@ impl From<&Arc<Self>> for Expr {
         (**arc).clone()
     }
 }
+
+// num-traits interop: numeric literals behave like primitives, everything else is symbolic.
+
+impl Zero for Expr {
+    fn zero() -> Self {
+        Self::number(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.is_zero_num()
+    }
+}
+
+impl One for Expr {
+    fn one() -> Self {
+        Self::number(1.0)
+    }
+
+    fn is_one(&self) -> bool {
+        self.is_one_num()
+    }
+}
+
+/// Numeric literals convert to primitives; any other expression yields `None`.
+impl ToPrimitive for Expr {
+    fn to_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|n| n.to_i64())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|n| n.to_u64())
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.as_number()
+    }
+}
+
+impl FromPrimitive for Expr {
+    fn from_i64(n: i64) -> Option<Self> {
+        n.to_f64().map(Self::number)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        n.to_f64().map(Self::number)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(Self::number(n))
+    }
+}
@@ -49,6 +49,7 @@ impl_math_functions_symbol! {
     floor => KS.floor, ceil => KS.ceil, round => KS.round,
     // Special functions (single-argument only)
     abs => KS.abs, signum => KS.signum, sinc => KS.sinc,
+    heaviside => KS.heaviside, dirac => KS.dirac,
     erf => KS.erf, erfc => KS.erfc, gamma => KS.gamma, lgamma => KS.lgamma,
     digamma => KS.digamma, trigamma => KS.trigamma, tetragamma => KS.tetragamma,
     zeta => KS.zeta, lambertw => KS.lambertw,
@@ -106,6 +107,11 @@ impl Symbol {
         Expr::func_multi_symbol(get_symbol(KS.atan2), vec![self.to_expr(), x.into()])
     }
 
+    /// Euclidean norm on this symbol: hypot(self, y)
+    pub fn hypot(&self, y: impl Into<Expr>) -> Expr {
+        Expr::func_multi_symbol(get_symbol(KS.hypot), vec![self.to_expr(), y.into()])
+    }
+
     /// Hermite polynomial on this symbol: `H_n(x)`
     pub fn hermite(&self, n: impl Into<Expr>) -> Expr {
         Expr::func_multi_symbol(get_symbol(KS.hermite), vec![n.into(), self.to_expr()])
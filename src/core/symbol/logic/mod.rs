@@ -8,12 +8,19 @@ pub(super) mod interned;
 pub(super) mod math_methods;
 pub(super) mod operators;
 pub(super) mod registry;
+pub(super) mod scope;
+#[cfg(feature = "serde")]
+mod serialization;
 
 // Staircase re-exports — one hop up to api.rs
 pub use registry::{
     clear_symbols, remove_symbol, symb, symb_anon, symb_get, symb_new, symbol_count, symbol_exists,
     symbol_names,
 };
+pub use scope::SymbolScope;
 
 pub use interned::InternedSymbol;
-pub use registry::{key_from_id, lookup_by_id, symb_interned, symb_new_isolated};
+pub use registry::{
+    key_from_id, lookup_by_id, mark_uncertainty_of, symb_interned, symb_new_isolated,
+    uncertainty_target,
+};
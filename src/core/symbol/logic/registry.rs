@@ -7,7 +7,7 @@
 use std::array::from_fn;
 use std::cell::RefCell;
 use std::hash::Hash;
-use std::sync::{LazyLock, Mutex, RwLock};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 use rustc_hash::{FxHashMap, FxHasher};
 use slotmap::{DefaultKey, KeyData, SlotMap};
@@ -52,6 +52,86 @@ pub fn symb_new_isolated(name: &str) -> Symbol {
     Symbol(key)
 }
 
+/// Create a named symbol owned by the symbol scope `scope`, registered by ID only
+///
+/// Scoped symbols are left out of [`symbol_count`] and kept by [`clear_symbols`]; the
+/// scope frees them with [`release_scoped`].
+///
+/// # Panics
+///
+/// Panics if any global registry lock is poisoned.
+pub fn symb_new_scoped(name: &str, scope: u64) -> Symbol {
+    // Held across the insertion so `clear_symbols` never sees the symbol unowned
+    let mut scope_of = REGISTRY
+        .scope_of
+        .write()
+        .expect("Global scope registry poisoned");
+    let key = REGISTRY
+        .id_to_data
+        .write()
+        .expect("Global ID registry poisoned")
+        .insert_with_key(|k| InternedSymbol::new_named(name, k));
+    scope_of.insert(key, scope);
+    drop(scope_of);
+    Symbol(key)
+}
+
+/// ID of the symbol scope owning `key`, or `None` for a symbol outside every scope
+///
+/// # Panics
+///
+/// Panics if the global scope registry lock is poisoned.
+pub fn scope_of(key: DefaultKey) -> Option<u64> {
+    REGISTRY
+        .scope_of
+        .read()
+        .expect("Global scope registry poisoned")
+        .get(&key)
+        .copied()
+}
+
+/// Remove the scoped symbols `keys` from the registry
+///
+/// Other threads may still hold them in their lookup cache; since removed slots come
+/// back with a new version, a stale entry never answers for a different symbol.
+///
+/// # Panics
+///
+/// Panics if any global registry lock is poisoned.
+pub fn release_scoped(keys: &[DefaultKey]) {
+    ID_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        for key in keys {
+            cache.remove(key);
+        }
+    });
+    {
+        let mut scope_of = REGISTRY
+            .scope_of
+            .write()
+            .expect("Global scope registry poisoned");
+        for key in keys {
+            scope_of.remove(key);
+        }
+    }
+    {
+        let mut uncertainty_of = REGISTRY
+            .uncertainty_of
+            .write()
+            .expect("Global uncertainty registry poisoned");
+        for key in keys {
+            uncertainty_of.remove(key);
+        }
+    }
+    let mut id_data = REGISTRY
+        .id_to_data
+        .write()
+        .expect("Global ID registry poisoned");
+    for &key in keys {
+        id_data.remove(key);
+    }
+}
+
 // ============================================================================
 // Global Symbol Registry
 // ============================================================================
@@ -71,6 +151,10 @@ struct SymbolRegistry {
     shards: [Mutex<RegistryShard>; NUM_SHARDS],
     /// ID -> Symbol mapping using `SlotMap` for memory efficiency and safe key generation
     id_to_data: RwLock<SlotMap<DefaultKey, InternedSymbol>>,
+    /// Uncertainty symbols -> name of the variable whose uncertainty they carry
+    uncertainty_of: RwLock<FxHashMap<DefaultKey, Arc<str>>>,
+    /// Scoped symbols -> ID of the symbol scope that owns them
+    scope_of: RwLock<FxHashMap<DefaultKey, u64>>,
 }
 
 impl SymbolRegistry {
@@ -85,6 +169,8 @@ impl SymbolRegistry {
         Self {
             shards,
             id_to_data: RwLock::new(SlotMap::with_key()),
+            uncertainty_of: RwLock::new(FxHashMap::default()),
+            scope_of: RwLock::new(FxHashMap::default()),
         }
     }
 
@@ -149,6 +235,33 @@ pub fn lookup_by_id(id: u64) -> Option<InternedSymbol> {
     symbol
 }
 
+/// Record that the symbol `key` carries the uncertainty of the variable `var`
+///
+/// # Panics
+///
+/// Panics if the global uncertainty registry lock is poisoned.
+pub fn mark_uncertainty_of(key: DefaultKey, var: &str) {
+    REGISTRY
+        .uncertainty_of
+        .write()
+        .expect("Global uncertainty registry poisoned")
+        .insert(key, Arc::from(var));
+}
+
+/// Name of the variable whose uncertainty the symbol `key` carries, if it was marked
+///
+/// # Panics
+///
+/// Panics if the global uncertainty registry lock is poisoned.
+pub fn uncertainty_target(key: DefaultKey) -> Option<Arc<str>> {
+    REGISTRY
+        .uncertainty_of
+        .read()
+        .expect("Global uncertainty registry poisoned")
+        .get(&key)
+        .cloned()
+}
+
 // ============================================================================
 // Public API Functions
 // ============================================================================
@@ -288,6 +401,11 @@ pub fn remove_symbol(name: &str) -> bool {
     shard.name_to_symbol_key.remove(name).is_some_and(|key| {
         // Explicitly drop shard lock before taking id_data lock to avoid deadlocks
         drop(shard);
+        REGISTRY
+            .uncertainty_of
+            .write()
+            .expect("Global uncertainty registry poisoned")
+            .remove(&key);
         REGISTRY
             .id_to_data
             .write()
@@ -299,6 +417,8 @@ pub fn remove_symbol(name: &str) -> bool {
 
 /// Clear all symbols from the global registry
 ///
+/// Symbols of live symbol scopes are kept; each scope frees its own when dropped.
+///
 /// # Panics
 ///
 /// Panics if any global registry lock is poisoned.
@@ -314,24 +434,42 @@ pub fn clear_symbols() {
         shard.name_to_symbol_key.clear();
     }
 
+    let scoped = REGISTRY
+        .scope_of
+        .read()
+        .expect("Global scope registry poisoned")
+        .clone();
+
+    REGISTRY
+        .uncertainty_of
+        .write()
+        .expect("Global uncertainty registry poisoned")
+        .retain(|key, _| scoped.contains_key(key));
+
     let mut id_data = REGISTRY
         .id_to_data
         .write()
         .expect("Global ID registry poisoned");
-    id_data.clear();
+    id_data.retain(|key, _| scoped.contains_key(&key));
 }
 
-/// Get the number of registered symbols
+/// Get the number of registered symbols, not counting those of symbol scopes
 ///
 /// # Panics
 ///
 /// Panics if any global registry shard lock is poisoned.
 pub fn symbol_count() -> usize {
-    let id_data = REGISTRY
+    let total = REGISTRY
         .id_to_data
         .read()
-        .expect("Global ID registry poisoned");
-    id_data.len()
+        .expect("Global ID registry poisoned")
+        .len();
+    let scoped = REGISTRY
+        .scope_of
+        .read()
+        .expect("Global scope registry poisoned")
+        .len();
+    total.saturating_sub(scoped)
 }
 
 /// Get a list of all registered symbol names (unsorted for performance)
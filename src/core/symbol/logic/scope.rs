@@ -0,0 +1,198 @@
+//! Symbol scopes: private namespaces of symbols outside the global name table.
+//!
+//! A scope hands out one symbol per name, like [`symb`](super::registry::symb), but its
+//! symbols are registered by ID only, so `"t"` in two scopes (or in a scope and the
+//! global table) are different variables. The names are freed when the last clone of
+//! the scope is dropped.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rustc_hash::FxHashMap;
+use slotmap::DefaultKey;
+
+use super::registry::{release_scoped, scope_of, symb, symb_new_scoped};
+use crate::core::known_symbols::is_known_constant;
+use crate::core::{DiffError, Expr, Symbol};
+
+/// Next scope ID to hand out
+static NEXT_SCOPE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Shared state of a scope and its clones
+struct ScopeInner {
+    id: u64,
+    symbols: Mutex<FxHashMap<String, Symbol>>,
+}
+
+impl Drop for ScopeInner {
+    fn drop(&mut self) {
+        let symbols = self
+            .symbols
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let keys: Vec<DefaultKey> = symbols.values().map(Symbol::key).collect();
+        release_scoped(&keys);
+    }
+}
+
+/// A namespace of symbols that never collide with the global symbol table
+///
+/// `scope.symb("x")` returns the same symbol on every call within the scope, and a
+/// different one from the global `symb("x")` or another scope's `"x"`. Built-in
+/// constants (`pi`, `e`, `tau`) stay shared. [`clear_symbols`](crate::clear_symbols)
+/// leaves scoped symbols alone, and dropping the last clone of a scope releases its
+/// names from the registry without touching global symbols. Expressions built from a
+/// dropped scope still print, but its symbols can no longer be looked up by ID.
+///
+/// Pass a scope to [`parse_configured`](crate::parse_configured) in
+/// [`ParserOptions::scope`](crate::ParserOptions::scope),
+/// [`Diff::scope`](crate::Diff::scope) or [`Simplify::scope`](crate::Simplify::scope)
+/// to resolve names in it and to reject expressions with symbols of another scope.
+///
+/// ```
+/// use symb_anafis::{SymbolScope, symb};
+///
+/// let (a, b) = (SymbolScope::new(), SymbolScope::new());
+/// assert_ne!(a.symb("t"), b.symb("t"));
+/// assert_ne!(a.symb("t"), symb("t"));
+/// assert_eq!(a.symb("t"), a.symb("t"));
+/// ```
+#[derive(Clone)]
+pub struct SymbolScope {
+    inner: Arc<ScopeInner>,
+}
+
+impl Default for SymbolScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SymbolScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("SymbolScope")
+            .field("id", &self.inner.id)
+            .field("names", &self.names())
+            .finish()
+    }
+}
+
+impl SymbolScope {
+    /// Create a new empty scope
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(ScopeInner {
+                id: NEXT_SCOPE_ID.fetch_add(1, Ordering::Relaxed),
+                symbols: Mutex::new(FxHashMap::default()),
+            }),
+        }
+    }
+
+    /// This scope's unique ID, shared by its clones
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+
+    /// Get or create the symbol `name` of this scope
+    ///
+    /// # Panics
+    /// Panics if the scope's lock is poisoned.
+    #[must_use]
+    pub fn symb(&self, name: &str) -> Symbol {
+        if is_known_constant(name) {
+            return symb(name);
+        }
+        let mut symbols = self.inner.symbols.lock().expect("Symbol scope poisoned");
+        if let Some(&existing) = symbols.get(name) {
+            return existing;
+        }
+        let symbol = symb_new_scoped(name, self.inner.id);
+        symbols.insert(name.to_owned(), symbol);
+        symbol
+    }
+
+    /// The symbol `name` of this scope, if it was created
+    ///
+    /// # Panics
+    /// Panics if the scope's lock is poisoned.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.inner
+            .symbols
+            .lock()
+            .expect("Symbol scope poisoned")
+            .get(name)
+            .copied()
+    }
+
+    /// Whether `symbol` belongs to this scope
+    #[must_use]
+    pub fn contains(&self, symbol: &Symbol) -> bool {
+        scope_of(symbol.key()) == Some(self.inner.id)
+    }
+
+    /// Names of the symbols created in this scope (unsorted)
+    ///
+    /// # Panics
+    /// Panics if the scope's lock is poisoned.
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        self.inner
+            .symbols
+            .lock()
+            .expect("Symbol scope poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Number of symbols created in this scope
+    ///
+    /// # Panics
+    /// Panics if the scope's lock is poisoned.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner
+            .symbols
+            .lock()
+            .expect("Symbol scope poisoned")
+            .len()
+    }
+
+    /// Whether no symbol was created in this scope yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check that `expr` only uses symbols this scope can tell apart
+    ///
+    /// Symbols of this scope and global symbols pass. A symbol of another scope fails,
+    /// and so does a global symbol named like one of this scope's: operations that go by
+    /// name, such as differentiation, could not keep the two apart.
+    ///
+    /// # Errors
+    /// Returns [`DiffError::ScopeMismatch`] naming the first such symbol.
+    ///
+    /// # Panics
+    /// Panics if the scope's lock is poisoned.
+    pub fn check(&self, expr: &Expr) -> Result<(), DiffError> {
+        let symbols = self.inner.symbols.lock().expect("Symbol scope poisoned");
+        let foreign = expr.find_symbol(|s| {
+            scope_of(s.key()).map_or_else(
+                || s.name().is_some_and(|name| symbols.contains_key(name)),
+                |owner| owner != self.inner.id,
+            )
+        });
+        drop(symbols);
+        foreign.map_or(Ok(()), |s| {
+            Err(DiffError::ScopeMismatch {
+                name: s.to_string(),
+            })
+        })
+    }
+}
@@ -0,0 +1,43 @@
+//! Serde support for symbols (`serde` feature).
+//!
+//! Symbols are stored by name: the registry keys behind IDs depend on the order in
+//! which symbols were created, so they are meaningless in another session. Names are
+//! re-interned in the global registry on deserialization. Anonymous symbols have no
+//! name and cannot be serialized.
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Error, Serialize, Serializer};
+
+use super::super::api::Symbol;
+use super::interned::InternedSymbol;
+use super::registry::{symb, symb_interned};
+
+impl Serialize for InternedSymbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name()
+            .ok_or_else(|| S::Error::custom("anonymous symbols cannot be serialized"))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedSymbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(symb_interned(&name))
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name_arc()
+            .ok_or_else(|| S::Error::custom("anonymous symbols cannot be serialized"))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(symb(&name))
+    }
+}
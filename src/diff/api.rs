@@ -1,12 +1,18 @@
 //! User-facing differentiation API.
 //!
-//! This module provides the [`Diff`] builder and the convenience [`diff`] function.
+//! This module provides the [`Diff`] builder and the convenience [`diff`] and
+//! [`diff_implicit_str`] functions.
 
-use crate::core::{Context, UserFunction, symb_interned};
-use crate::core::{DiffError, Expr, Symbol, symb};
+use crate::core::known_symbols::KS;
+use crate::core::{Context, SymbolScope, UserFunction, symb_interned};
+use crate::core::{DiffError, Expr, ExprKind, FormatOptions, Polynomial, Symbol, symb};
 use crate::evaluator::ToParamName;
-use crate::parser::parse;
-use crate::simplification::{CustomBodyMap, simplify_expr};
+use crate::integrate::Integrate;
+use crate::parser::{ParserOptions, SourceMap, parse, parse_configured, parse_equation};
+use crate::simplification::{
+    CustomBodyMap, rationalize_decimals, simplify_expr, simplify_expr_exact,
+    simplify_holding_groups,
+};
 use crate::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_NODES};
 use rustc_hash::FxHashMap;
 use std::collections::HashSet;
@@ -14,11 +20,21 @@ use std::sync::Arc;
 
 /// Builder for differentiation operations
 #[derive(Clone, Default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Each flag is an independent builder switch"
+)]
 pub struct Diff {
     /// Whether to apply only domain-safe transformations
     domain_safe: bool,
     /// Whether to skip simplification after differentiation
     skip_simplification: bool,
+    /// Whether decimals are read as exact fractions and numeric folding stays exact
+    exact_arithmetic: bool,
+    /// Whether labeled subexpressions stay atomic while the derivative simplifies
+    preserve_groups: bool,
+    /// Input conventions for formulas given as strings
+    parser_options: ParserOptions,
     /// User-defined functions
     user_fns: FxHashMap<String, UserFunction>,
     max_depth: Option<usize>,
@@ -28,6 +44,10 @@ pub struct Diff {
     context: Option<Context>,
     /// Known symbols for parsing
     known_symbols: HashSet<String>,
+    /// Scope that formulas are parsed in and inputs are checked against
+    scope: Option<SymbolScope>,
+    /// How the string entry points write their result, if not as `Display` does
+    output_format: Option<FormatOptions>,
 }
 
 impl Diff {
@@ -38,6 +58,10 @@ impl Diff {
     }
 
     /// Enable or disable domain-safe mode (skips domain-altering rules)
+    ///
+    /// Domain-safe differentiation also refuses derivatives that do not exist
+    /// everywhere: `abs` of an argument depending on the variable fails with
+    /// [`DiffError::NonDifferentiable`] instead of returning `signum(u)*u'`.
     #[inline]
     #[must_use]
     pub const fn domain_safe(mut self, safe: bool) -> Self {
@@ -53,6 +77,66 @@ impl Diff {
         self
     }
 
+    /// Keep coefficients and exponents exact (see [`Simplify::exact_arithmetic`])
+    ///
+    /// Decimal literals are read as fractions before differentiating, so the derivative
+    /// of `x^0.5` is `1/(2*sqrt(x))` and `0.1*x^2` gives `x/5`.
+    ///
+    /// [`Simplify::exact_arithmetic`]: crate::Simplify::exact_arithmetic
+    #[inline]
+    #[must_use]
+    pub const fn exact_arithmetic(mut self, exact: bool) -> Self {
+        self.exact_arithmetic = exact;
+        self
+    }
+
+    /// Keep labeled subexpressions atomic while simplifying the derivative (see
+    /// [`Simplify::preserve_groups`])
+    ///
+    /// A group is differentiated through the chain rule like any other subexpression, so
+    /// `group(rho*v*L/mu, Re)^0.8` gives `0.8*Re^(-0.2)` times the derivative of `Re`, with
+    /// `Re` still whole in the result.
+    ///
+    /// [`Simplify::preserve_groups`]: crate::Simplify::preserve_groups
+    #[inline]
+    #[must_use]
+    pub const fn preserve_groups(mut self, preserve: bool) -> Self {
+        self.preserve_groups = preserve;
+        self
+    }
+
+    /// Accept decimal commas or Unicode operators in formulas passed as strings (see
+    /// [`parse_configured`](crate::parse_configured))
+    ///
+    /// Only the notation settings apply: exactness and the scope come from
+    /// [`exact_arithmetic`](Self::exact_arithmetic) and [`scope`](Self::scope).
+    #[inline]
+    #[must_use]
+    pub fn parser_options(mut self, options: ParserOptions) -> Self {
+        self.parser_options = options;
+        self
+    }
+
+    /// Write the results of the string entry points with
+    /// [`Expr::format_with`] instead of `Display`
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Diff, FormatOptions, PowerOperator};
+    /// let python = FormatOptions {
+    ///     power_operator: PowerOperator::DoubleStar,
+    ///     ..FormatOptions::default()
+    /// };
+    /// let result = Diff::new().output_format(python).diff_str("x^3", "x", &[]).unwrap();
+    /// assert_eq!(result, "3*x**2");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn output_format(mut self, options: FormatOptions) -> Self {
+        self.output_format = Some(options);
+        self
+    }
+
     /// Set the Context for parsing and differentiation.
     #[inline]
     #[must_use]
@@ -61,6 +145,28 @@ impl Diff {
         self
     }
 
+    /// Parse string formulas in `scope`, and refuse inputs with symbols it cannot tell
+    /// apart from its own (see [`SymbolScope::check`])
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Diff, DiffError, SymbolScope};
+    ///
+    /// let (a, b) = (SymbolScope::new(), SymbolScope::new());
+    /// let t = a.symb("t");
+    /// assert_eq!(Diff::new().scope(&a).differentiate(&t.pow(2.0), &t)?.to_string(), "2*t");
+    ///
+    /// let mixed = t * b.symb("t");
+    /// let err = Diff::new().scope(&a).differentiate(&mixed, &t).unwrap_err();
+    /// assert!(matches!(err, DiffError::ScopeMismatch { .. }));
+    /// # Ok::<(), DiffError>(())
+    /// ```
+    #[must_use]
+    pub fn scope(mut self, scope: &SymbolScope) -> Self {
+        self.scope = Some(scope.clone());
+        self
+    }
+
     /// Register a user-defined function with explicit partial derivatives
     #[must_use]
     pub fn user_fn(mut self, name: impl Into<String>, def: UserFunction) -> Self {
@@ -111,11 +217,54 @@ impl Diff {
     /// - The variable is also in the fixed variables set
     /// - Expression depth exceeds `max_depth`
     /// - Expression node count exceeds `max_nodes`
+    /// - Domain-safe mode is on and the result needs the derivative of `dirac`
+    /// - Domain-safe mode is on and `abs` is applied to an argument depending on `var`
+    /// - A [scope](Self::scope) is set and `expr` or `var` has a symbol foreign to it
     pub fn differentiate(&self, expr: &Expr, var: &Symbol) -> Result<Expr, DiffError> {
+        self.check_scope(&var.to_expr())?;
         let var_name = var.name().unwrap_or_default();
         self.differentiate_by_name(expr, &var_name)
     }
 
+    /// Slope `dy/dx` of the curve defined implicitly by `relation = 0`
+    ///
+    /// The dependent variable `y` comes first, as in
+    /// [`Equation::implicit_diff`](crate::Equation::implicit_diff).
+    /// Computes the partial derivatives `dF/dx` and `dF/dy` of `F = relation`, each
+    /// holding the other variable constant, and returns `-(dF/dx)/(dF/dy)` simplified
+    /// with the builder's settings. The result is only meaningful where `dF/dy` is
+    /// nonzero.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Diff, symb};
+    /// let (x, y) = (symb("x"), symb("y"));
+    /// let circle = x.pow(2.0) + y.pow(2.0) - 1.0;
+    /// let slope = Diff::new().implicit(&circle, &y, &x).unwrap();
+    /// assert_eq!(slope.to_string(), "-x/y");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::DivisionByZero`] if `dF/dy` simplifies to zero (the relation
+    /// does not depend on `y`), [`DiffError::UnsupportedOperation`] if `x` and `y` are
+    /// the same symbol, and otherwise fails like [`differentiate`](Self::differentiate).
+    pub fn implicit(&self, relation: &Expr, y: &Symbol, x: &Symbol) -> Result<Expr, DiffError> {
+        if x.id() == y.id() {
+            return Err(DiffError::UnsupportedOperation(format!(
+                "implicit differentiation needs two distinct variables, got '{}' twice",
+                x.to_expr()
+            )));
+        }
+        let by_x = self.differentiate(relation, x)?;
+        let by_y = self.differentiate(relation, y)?;
+        if by_y.is_zero_num() {
+            return Err(DiffError::DivisionByZero {
+                denominator: format!("d({relation})/d{}", y.to_expr()),
+            });
+        }
+        Ok(self.finish(Expr::div_expr(by_x.negate(), by_y), &self.build_context()))
+    }
+
     /// Get custom function names for parsing
     fn custom_function_names(&self) -> HashSet<String> {
         self.user_fns.keys().cloned().collect()
@@ -136,6 +285,15 @@ impl Diff {
 
     /// Build context from builder state
     fn build_context(&self) -> Context {
+        let context = self.build_unscoped_context();
+        match &self.scope {
+            Some(scope) => context.with_scope(scope),
+            None => context,
+        }
+    }
+
+    /// [`build_context`](Self::build_context) without the builder's scope
+    fn build_unscoped_context(&self) -> Context {
         self.context.as_ref().map_or_else(
             || {
                 let mut ctx = Context::new();
@@ -154,14 +312,41 @@ impl Diff {
         )
     }
 
-    /// Differentiates an expression with respect to a variable by name.
-    pub(crate) fn differentiate_by_name(&self, expr: &Expr, var: &str) -> Result<Expr, DiffError> {
-        if self.known_symbols.contains(var) {
-            return Err(DiffError::VariableInBothFixedAndDiff {
-                var: var.to_owned(),
-            });
+    /// Text of a result of the string entry points
+    fn render(&self, result: &Expr) -> String {
+        self.output_format
+            .as_ref()
+            .map_or_else(|| result.to_string(), |options| result.format_with(options))
+    }
+
+    /// Context that string formulas are parsed with: the builder's, in its scope
+    fn parse_context(&self) -> Option<Context> {
+        self.scope.as_ref().map_or_else(
+            || self.context.clone(),
+            |scope| Some(self.context.clone().unwrap_or_default().with_scope(scope)),
+        )
+    }
+
+    /// Parser settings for string formulas, which are parsed in [`parse_context`](Self::parse_context)
+    fn string_parser_options(&self) -> ParserOptions {
+        ParserOptions {
+            exact: self.exact_arithmetic,
+            scope: None,
+            warnings: false,
+            source_map: false,
+            ..self.parser_options.clone()
         }
+    }
 
+    /// Reject `expr` if a scope is set and `expr` has symbols foreign to it
+    fn check_scope(&self, expr: &Expr) -> Result<(), DiffError> {
+        self.scope
+            .as_ref()
+            .map_or(Ok(()), |scope| scope.check(expr))
+    }
+
+    /// Reject `expr` if it exceeds `max_depth` or `max_nodes`
+    fn check_limits(&self, expr: &Expr) -> Result<(), DiffError> {
         if let Some(max_d) = self.max_depth
             && expr.max_depth() > max_d
         {
@@ -172,52 +357,211 @@ impl Diff {
         {
             return Err(DiffError::MaxNodesExceeded);
         }
+        Ok(())
+    }
 
-        let context = self.build_context();
-        let derivative = expr.derive(var, Some(&context));
+    /// Validate the inputs of a differentiation with respect to `var`
+    fn check_inputs(&self, expr: &Expr, var: &str) -> Result<(), DiffError> {
+        if self.known_symbols.contains(var) {
+            return Err(DiffError::VariableInBothFixedAndDiff {
+                var: var.to_owned(),
+            });
+        }
+        if self.context.as_ref().is_some_and(|ctx| ctx.is_data(var)) {
+            return Err(DiffError::InvalidDataSeries {
+                name: var.to_owned(),
+                reason: "is data and cannot be differentiated with respect to".to_owned(),
+            });
+        }
+        self.check_scope(expr)?;
+        self.check_limits(expr)
+    }
 
+    /// Simplify a raw derivative according to the builder settings
+    fn finish(&self, derivative: Expr, context: &Context) -> Expr {
         if self.skip_simplification {
-            return Ok(derivative);
+            return derivative;
         }
+        if self.preserve_groups {
+            return simplify_holding_groups(derivative, &mut |held| {
+                self.simplify_derivative(held, context)
+            });
+        }
+        self.simplify_derivative(derivative, context)
+    }
 
-        let simplified = simplify_expr(
+    /// Simplify `derivative` with the builder's bodies, limits and arithmetic
+    fn simplify_derivative(&self, derivative: Expr, context: &Context) -> Expr {
+        if self.exact_arithmetic {
+            return simplify_expr_exact(
+                derivative,
+                self.build_bodies_map(),
+                self.max_depth,
+                Some(context),
+                self.domain_safe,
+            );
+        }
+
+        simplify_expr(
             derivative,
             self.known_symbols.clone(),
             self.build_bodies_map(),
             self.max_depth,
             None,
-            None,
+            Some(context),
             self.domain_safe,
-        );
+        )
+    }
 
-        Ok(simplified)
+    /// Differentiates an expression with respect to a variable by name.
+    pub(crate) fn differentiate_by_name(&self, expr: &Expr, var: &str) -> Result<Expr, DiffError> {
+        self.check_inputs(expr, var)?;
+        if self.domain_safe && expr.calls_function_of(KS.abs, var) {
+            return Err(DiffError::NonDifferentiable {
+                function: "abs".to_owned(),
+                note: "abs(u) has no derivative where u = 0; disable domain_safe to use the \
+                       subgradient signum(u)*u'"
+                    .to_owned(),
+            });
+        }
+
+        let context = self.build_context();
+        let derivative = if self.exact_arithmetic {
+            rationalize_decimals(expr).derive(var, Some(&context))
+        } else {
+            expr.derive(var, Some(&context))
+        };
+
+        let derivative = self.finish(derivative, &context);
+        if self.domain_safe && derivative.contains_function_id(KS.dirac_prime) {
+            return Err(DiffError::UnsupportedOperation(
+                "the derivative of dirac is only defined as a distribution; disable domain_safe \
+                 to keep it as dirac_prime"
+                    .to_owned(),
+            ));
+        }
+        Ok(derivative)
     }
 
-    /// Parse and differentiate a string formula
+    /// Partial derivatives with respect to each of `vars`, read off in one pass when
+    /// `expr` is affine in all of them, or `None` when it is not
     ///
-    /// # Arguments
-    /// * `formula` - The mathematical expression to differentiate
-    /// * `var` - The variable to differentiate with respect to
-    /// * `known_symbols` - Known multi-character symbol names for parsing
+    /// Each coefficient is simplified like a derivative from
+    /// [`differentiate_by_name`](Self::differentiate_by_name), so the results match.
+    pub(crate) fn affine_gradient(
+        &self,
+        expr: &Expr,
+        vars: &[&str],
+    ) -> Result<Option<Vec<Expr>>, DiffError> {
+        if let Some(var) = vars.iter().find(|var| self.known_symbols.contains(**var)) {
+            return Err(DiffError::VariableInBothFixedAndDiff {
+                var: (*var).to_owned(),
+            });
+        }
+        self.check_scope(expr)?;
+        self.check_limits(expr)?;
+
+        let Some(coefficients) = expr.affine_coefficients(vars) else {
+            return Ok(None);
+        };
+        let context = self.build_context();
+        Ok(Some(
+            coefficients
+                .into_iter()
+                .map(|coefficient| {
+                    let coefficient = if self.exact_arithmetic {
+                        rationalize_decimals(&coefficient)
+                    } else {
+                        coefficient
+                    };
+                    self.finish(coefficient, &context)
+                })
+                .collect(),
+        ))
+    }
+
+    /// Differentiate an expression `order` times with respect to a variable
+    ///
+    /// Each pass is simplified (unless [`skip_simplification`](Self::skip_simplification)
+    /// is set) before the next one, which keeps repeated product and chain rules from
+    /// blowing up. A polynomial with numeric coefficients in `var` skips the intermediate
+    /// orders entirely, so the 10th derivative of a degree-20 polynomial is a single pass
+    /// over its terms. `order == 0` returns `expr` unchanged.
     ///
     /// # Example
     /// ```
-    /// use symb_anafis::Diff;
-    /// let result = Diff::new().diff_str("alpha*x", "x", &["alpha"]).unwrap();
-    /// assert_eq!(result, "alpha");
+    /// use symb_anafis::{Diff, symb};
+    /// let x = symb("x");
+    /// let d3 = Diff::new().diff_n(&x.sin(), &x, 3).unwrap();
+    /// assert_eq!(d3.to_string(), "-cos(x)");
     /// ```
     ///
     /// # Errors
-    /// Returns `DiffError` if:
-    /// - Parsing fails
-    /// - The variable is in the known symbols set
-    /// - A name collision between symbols and functions is detected
-    pub fn diff_str(
+    /// Returns `DiffError` if any pass fails as in [`differentiate`](Self::differentiate),
+    /// or [`DiffError::DerivativeOrderLimit`] naming the order whose result grew beyond
+    /// `max_nodes` or `max_depth`.
+    pub fn diff_n(&self, expr: &Expr, var: &Symbol, order: usize) -> Result<Expr, DiffError> {
+        if order == 0 {
+            return Ok(expr.clone());
+        }
+        self.check_scope(&var.to_expr())?;
+        let var_name = var.name().unwrap_or_default();
+
+        if let Some(derivative) = self.diff_n_polynomial(expr, *var, &var_name, order)? {
+            return Ok(derivative);
+        }
+
+        (1..=order).try_fold(expr.clone(), |current, k| {
+            let next = self.differentiate_by_name(&current, &var_name)?;
+            self.check_limits(&next)
+                .map_err(|limit| DiffError::DerivativeOrderLimit {
+                    order: k,
+                    limit: Box::new(limit),
+                })?;
+            Ok(next)
+        })
+    }
+
+    /// Closed-form `order`-th derivative of a polynomial in `var` with numeric coefficients
+    ///
+    /// Returns `None` when `expr` is not such a polynomial (or exact arithmetic is on, since
+    /// polynomial coefficients are floats).
+    fn diff_n_polynomial(
+        &self,
+        expr: &Expr,
+        var: Symbol,
+        var_name: &str,
+        order: usize,
+    ) -> Result<Option<Expr>, DiffError> {
+        if self.exact_arithmetic {
+            return Ok(None);
+        }
+        let Some(poly) = Polynomial::try_from_expr(expr) else {
+            return Ok(None);
+        };
+        if !matches!(&poly.base().kind, ExprKind::Symbol(s) if s.id() == var.id()) {
+            return Ok(None);
+        }
+        self.check_inputs(expr, var_name)?;
+
+        let derivative = u32::try_from(order)
+            .map_or_else(|_| Expr::number(0.0), |n| poly.nth_derivative(n).to_expr());
+        let derivative = self.finish(derivative, &self.build_context());
+        self.check_limits(&derivative)
+            .map_err(|limit| DiffError::DerivativeOrderLimit {
+                order,
+                limit: Box::new(limit),
+            })?;
+        Ok(Some(derivative))
+    }
+
+    /// Parse `formula` and resolve `var` the way the string entry points do
+    fn parse_formula(
         &self,
         formula: &str,
         var: &str,
         known_symbols: &[&str],
-    ) -> Result<String, DiffError> {
+    ) -> Result<(Expr, Symbol), DiffError> {
         let mut symbols: HashSet<String> = known_symbols.iter().map(ToString::to_string).collect();
         symbols.extend(self.known_symbols.clone());
 
@@ -241,15 +585,134 @@ impl Diff {
             }
         }
 
-        let ast = parse(formula, &symbols, &custom_functions, self.context.as_ref())?;
-
-        let var_sym = self
-            .context
+        let context = self.parse_context();
+        let ast = parse_configured(
+            formula,
+            &symbols,
+            &custom_functions,
+            context.as_ref(),
+            &self.string_parser_options(),
+        )?
+        .expr;
+
+        let var_sym = context
             .as_ref()
             .map_or_else(|| symb(var), |ctx| ctx.symb(var));
 
-        let result = self.differentiate(&ast, &var_sym)?;
-        Ok(format!("{result}"))
+        Ok((ast, var_sym))
+    }
+
+    /// Parse and differentiate a string formula
+    ///
+    /// # Arguments
+    /// * `formula` - The mathematical expression to differentiate
+    /// * `var` - The variable to differentiate with respect to
+    /// * `known_symbols` - Known multi-character symbol names for parsing
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::Diff;
+    /// let result = Diff::new().diff_str("alpha*x", "x", &["alpha"]).unwrap();
+    /// assert_eq!(result, "alpha");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` if:
+    /// - Parsing fails
+    /// - The variable is in the known symbols set
+    /// - A name collision between symbols and functions is detected
+    pub fn diff_str(
+        &self,
+        formula: &str,
+        var: &str,
+        known_symbols: &[&str],
+    ) -> Result<String, DiffError> {
+        let (ast, var_sym) = self.parse_formula(formula, var, known_symbols)?;
+        let result = self
+            .differentiate(&ast, &var_sym)
+            .map_err(|err| SourceMap::new(formula).locate(err))?;
+        Ok(self.render(&result))
+    }
+
+    /// Integrate an expression with respect to a variable
+    ///
+    /// Uses the builder's context, user functions, fixed variables, limits and domain
+    /// safety; see [`Integrate::integrate`] for the rules applied.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Diff, symb};
+    /// let x = symb("x");
+    /// let primitive = Diff::new().integrate(&(x.exp() * 3.0), &x).unwrap();
+    /// assert_eq!(primitive.to_string(), "3*exp(x)");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`Integrate::integrate`], and
+    /// [`DiffError::ScopeMismatch`] as in [`differentiate`](Self::differentiate).
+    pub fn integrate(&self, expr: &Expr, var: &Symbol) -> Result<Expr, DiffError> {
+        self.check_scope(expr)?;
+        self.check_scope(&var.to_expr())?;
+        let fixed: Vec<&str> = self.known_symbols.iter().map(String::as_str).collect();
+        let mut integrate = Integrate::new()
+            .domain_safe(self.domain_safe)
+            .context(&self.build_context())
+            .fixed_vars(&fixed);
+        if let Some(depth) = self.max_depth {
+            integrate = integrate.max_depth(depth);
+        }
+        if let Some(nodes) = self.max_nodes {
+            integrate = integrate.max_nodes(nodes);
+        }
+        integrate.integrate(expr, var)
+    }
+
+    /// Parse and integrate a string formula
+    ///
+    /// Multi-character symbols are the builder's fixed variables.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::Diff;
+    /// let result = Diff::new().fixed_var(&"alpha").integrate_str("alpha*cos(x)", "x").unwrap();
+    /// assert_eq!(result, "alpha*sin(x)");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`diff_str`](Self::diff_str) and
+    /// [`integrate`](Self::integrate).
+    pub fn integrate_str(&self, formula: &str, var: &str) -> Result<String, DiffError> {
+        let (ast, var_sym) = self.parse_formula(formula, var, &[])?;
+        let result = self
+            .integrate(&ast, &var_sym)
+            .map_err(|err| SourceMap::new(formula).locate(err))?;
+        Ok(self.render(&result))
+    }
+
+    /// Parse a string formula and take its `order`-th derivative
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::Diff;
+    /// let result = Diff::new().diff_str_n("x^4", "x", &[], 4).unwrap();
+    /// assert_eq!(result, "24");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`diff_str`](Self::diff_str) and
+    /// [`diff_n`](Self::diff_n).
+    pub fn diff_str_n(
+        &self,
+        formula: &str,
+        var: &str,
+        known_symbols: &[&str],
+        order: usize,
+    ) -> Result<String, DiffError> {
+        let (ast, var_sym) = self.parse_formula(formula, var, known_symbols)?;
+        let result = self
+            .diff_n(&ast, &var_sym, order)
+            .map_err(|err| SourceMap::new(formula).locate(err))?;
+        Ok(self.render(&result))
     }
 }
 
@@ -286,3 +749,32 @@ pub fn diff(
         .max_nodes(DEFAULT_MAX_NODES)
         .diff_str(formula, var_to_diff, known_symbols)
 }
+
+/// Slope `dy/dx` of a curve given implicitly as a string
+///
+/// `relation` is either an equation such as `x^2 + y^2 = 1` or an expression `F`
+/// read as `F = 0`. The dependent variable `y_var` comes first. See [`Diff::implicit`]
+/// for the formula and [`Diff`] for more control over parsing and simplification.
+///
+/// # Example
+/// ```
+/// use symb_anafis::diff_implicit_str;
+/// assert_eq!(diff_implicit_str("x^2 + y^2 = 1", "y", "x").unwrap(), "-x/y");
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if parsing fails or for the reasons of [`Diff::implicit`].
+pub fn diff_implicit_str(relation: &str, y_var: &str, x_var: &str) -> Result<String, DiffError> {
+    let none = HashSet::new();
+    let expr = if relation.contains('=') {
+        parse_equation(relation, &none, &none, None)?.as_expr_zero()
+    } else {
+        parse(relation, &none, &none, None)?
+    };
+    let slope = Diff::new()
+        .max_depth(DEFAULT_MAX_DEPTH)
+        .max_nodes(DEFAULT_MAX_NODES)
+        .implicit(&expr, &symb(y_var), &symb(x_var))
+        .map_err(|err| SourceMap::new(relation).locate(err))?;
+    Ok(slope.to_string())
+}
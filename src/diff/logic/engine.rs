@@ -16,8 +16,8 @@
 //! The simplification engine then handles any remaining optimization opportunities.
 
 use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::{Condition, Expr, ExprKind};
 use crate::core::{Context, InternedSymbol, symb_interned};
-use crate::core::{Expr, ExprKind};
 use crate::functions::Registry;
 use std::sync::{Arc, OnceLock};
 
@@ -53,18 +53,33 @@ impl Expr {
         static EMPTY_CONTEXT: OnceLock<Context> = OnceLock::new();
         let ctx = context.unwrap_or_else(|| EMPTY_CONTEXT.get_or_init(Context::new));
 
-        let var_id = symb_interned(var).id();
+        // Symbols of the context may be isolated from the global registry
+        let var_id = ctx
+            .get_symbol(var)
+            .map_or_else(|| symb_interned(var).id(), |symbol| symbol.id());
 
         self.derive_impl(var, var_id, ctx)
     }
 
     /// Inner recursive implementation that carries pre-computed `var_id`
     /// to avoid re-interning the variable name at each node.
+    ///
+    /// The derivative of a labeled node carries the same label.
+    fn derive_impl(&self, var: &str, var_id: u64, ctx: &Context) -> Self {
+        let derivative = self.derive_node(var, var_id, ctx);
+        if self.label().is_some() {
+            derivative.inherit_label(self)
+        } else {
+            derivative
+        }
+    }
+
+    /// Apply the differentiation rule for this node's kind
     #[allow(
         clippy::too_many_lines,
         reason = "Comprehensive differentiation logic handles many expression types"
     )]
-    fn derive_impl(&self, var: &str, var_id: u64, ctx: &Context) -> Self {
+    fn derive_node(&self, var: &str, var_id: u64, ctx: &Context) -> Self {
         match &self.kind {
             ExprKind::Number(_) => Self::number(0.0),
 
@@ -81,6 +96,15 @@ impl Expr {
                     return Self::number(0.0);
                 }
 
+                // The sum over data points is linear: differentiate term by term
+                if name.id() == KS.sum_data && args.len() == 1 {
+                    let body_prime = args[0].derive_impl(var, var_id, ctx);
+                    if body_prime.is_zero_num() {
+                        return Self::number(0.0);
+                    }
+                    return Self::func_symbol(get_symbol(KS.sum_data), body_prime);
+                }
+
                 if name.id() == KS.exp && args.len() == 1 {
                     let inner_deriv = args[0].derive_impl(var, var_id, ctx);
                     return Self::mul_expr(
@@ -89,6 +113,25 @@ impl Expr {
                     );
                 }
 
+                // d/dx ln(|u|) = u'/u on both sides of u = 0, without the signum(u)/|u|
+                // that the chain rule through abs leaves behind
+                if name.id() == KS.ln
+                    && let [arg] = args.as_slice()
+                    && let Self {
+                        kind:
+                            ExprKind::FunctionCall {
+                                name: inner,
+                                args: abs_args,
+                            },
+                        ..
+                    } = arg.as_ref()
+                    && inner.id() == KS.abs
+                    && let [u] = abs_args.as_slice()
+                {
+                    let u_prime = u.derive_impl(var, var_id, ctx);
+                    return Self::div_expr(u_prime, (**u).clone());
+                }
+
                 if let Some(def) = Registry::get_by_symbol(name)
                     && def.validate_arity(args.len())
                 {
@@ -379,6 +422,22 @@ impl Expr {
             }
 
             ExprKind::Poly(poly) => poly.derivative_expr(var),
+
+            // Branch by branch under the same conditions; the jumps and kinks at the
+            // breakpoints (distributional terms) are not represented
+            ExprKind::Piecewise { branches, default } => {
+                let derived: Vec<(Condition, Self)> = branches
+                    .iter()
+                    .map(|(condition, value)| {
+                        (condition.clone(), value.derive_impl(var, var_id, ctx))
+                    })
+                    .collect();
+                let default = default.derive_impl(var, var_id, ctx);
+                if default.is_zero_num() && derived.iter().all(|(_, d)| d.is_zero_num()) {
+                    return Self::number(0.0);
+                }
+                Self::piecewise(derived, default)
+            }
         }
     }
 
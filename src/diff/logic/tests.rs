@@ -243,7 +243,7 @@ mod engine_tests {
         let d2f_dxdy = df_dx.derive("y", None);
         let display = format!("{d2f_dxdy}");
         assert!(
-            display.contains("\u{2202}^"),
+            display.contains("diff("),
             "Display should contain derivative notation, got: {display}"
         );
     }
@@ -0,0 +1,196 @@
+//! Builtin functions of the `no_std` interpreter, computed with `libm`.
+//!
+//! Only builtins with a closed form in `libm` are available. Programs calling the
+//! special functions implemented in the host's `math` module (Bessel, zeta, polygamma,
+//! elliptic integrals, ...) are refused when they are loaded rather than evaluated
+//! differently on the device.
+
+use core::f64::consts::FRAC_PI_2;
+
+use super::functions::FnOp;
+use crate::EPSILON;
+
+/// Whether [`builtin1`]..[`builtin3`] implement `op`
+pub const fn supports(op: FnOp) -> bool {
+    !matches!(
+        op,
+        FnOp::Gamma
+            | FnOp::Digamma
+            | FnOp::Trigamma
+            | FnOp::Tetragamma
+            | FnOp::LambertW
+            | FnOp::EllipticK
+            | FnOp::EllipticE
+            | FnOp::Zeta
+            | FnOp::BesselJ
+            | FnOp::BesselY
+            | FnOp::BesselI
+            | FnOp::BesselK
+            | FnOp::Polygamma
+            | FnOp::Beta
+            | FnOp::ZetaDeriv
+            | FnOp::Hermite
+            | FnOp::AssocLegendre
+            | FnOp::SphericalHarmonic
+    )
+}
+
+/// Smaller of `a` and `b`; ties keep `a` and a NaN in either operand propagates
+pub fn min(a: f64, b: f64) -> f64 {
+    if b < a || b.is_nan() { b } else { a }
+}
+
+/// Larger of `a` and `b`; ties keep `a` and a NaN in either operand propagates
+pub fn max(a: f64, b: f64) -> f64 {
+    if b > a || b.is_nan() { b } else { a }
+}
+
+/// `a` where `cond` is nonzero, `b` where it is zero; NaN propagates from `cond`
+pub fn select(cond: f64, a: f64, b: f64) -> f64 {
+    if cond.is_nan() {
+        cond
+    } else if cond == 0.0 {
+        b
+    } else {
+        a
+    }
+}
+
+/// `a` where `x >= 0`, `b` where it is negative; NaN propagates from `x`
+pub fn select_non_neg(x: f64, a: f64, b: f64) -> f64 {
+    select(heaviside(x), a, b)
+}
+
+fn heaviside(x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x >= 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn signum(x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else {
+        libm::copysign(1.0, x)
+    }
+}
+
+/// Integer power by repeated squaring
+pub fn powi(x: f64, n: i32) -> f64 {
+    let mut base = x;
+    let mut exp = n.unsigned_abs();
+    let mut acc = 1.0;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc *= base;
+        }
+        base *= base;
+        exp >>= 1;
+    }
+    if n < 0 { 1.0 / acc } else { acc }
+}
+
+/// Value of the one-argument builtin `op` at `x`
+pub fn builtin1(op: FnOp, x: f64) -> f64 {
+    match op {
+        FnOp::Sin => libm::sin(x),
+        FnOp::Cos => libm::cos(x),
+        FnOp::Tan => libm::tan(x),
+        FnOp::Cot => 1.0 / libm::tan(x),
+        FnOp::Sec => 1.0 / libm::cos(x),
+        FnOp::Csc => 1.0 / libm::sin(x),
+        FnOp::Asin => libm::asin(x),
+        FnOp::Acos => libm::acos(x),
+        FnOp::Atan => libm::atan(x),
+        FnOp::Acot => FRAC_PI_2 - libm::atan(x),
+        FnOp::Asec => libm::acos(1.0 / x),
+        FnOp::Acsc => libm::asin(1.0 / x),
+        FnOp::Sinh => libm::sinh(x),
+        FnOp::Cosh => libm::cosh(x),
+        FnOp::Tanh => libm::tanh(x),
+        FnOp::Coth => 1.0 / libm::tanh(x),
+        FnOp::Sech => 1.0 / libm::cosh(x),
+        FnOp::Csch => 1.0 / libm::sinh(x),
+        FnOp::Asinh => libm::asinh(x),
+        FnOp::Acosh => libm::acosh(x),
+        FnOp::Atanh => libm::atanh(x),
+        FnOp::Acoth => libm::atanh(1.0 / x),
+        FnOp::Acsch => libm::asinh(1.0 / x),
+        FnOp::Asech => libm::acosh(1.0 / x),
+        FnOp::Exp | FnOp::ExpPolar => libm::exp(x),
+        FnOp::Expm1 => libm::expm1(x),
+        FnOp::ExpNeg => libm::exp(-x),
+        FnOp::Ln => libm::log(x),
+        FnOp::Log1p => libm::log1p(x),
+        FnOp::Sqrt => libm::sqrt(x),
+        FnOp::Cbrt => libm::cbrt(x),
+        FnOp::Abs => libm::fabs(x),
+        FnOp::Signum => signum(x),
+        FnOp::Heaviside => heaviside(x),
+        FnOp::Dirac => {
+            if x.is_nan() {
+                x
+            } else if x == 0.0 {
+                f64::INFINITY
+            } else {
+                0.0
+            }
+        }
+        FnOp::Floor => libm::floor(x),
+        FnOp::Ceil => libm::ceil(x),
+        FnOp::Round => libm::round(x),
+        FnOp::Erf => libm::erf(x),
+        FnOp::Erfc => libm::erfc(x),
+        FnOp::Lgamma => libm::lgamma(x),
+        FnOp::Sinc => {
+            if libm::fabs(x) < EPSILON {
+                1.0
+            } else {
+                libm::sin(x) / x
+            }
+        }
+        _ => f64::NAN,
+    }
+}
+
+/// Value of the two-argument builtin `op` at `(a, b)`
+pub fn builtin2(op: FnOp, a: f64, b: f64) -> f64 {
+    match op {
+        FnOp::Atan2 => libm::atan2(a, b),
+        FnOp::Hypot => libm::hypot(a, b),
+        FnOp::Log =>
+        {
+            #[allow(
+                clippy::float_cmp,
+                reason = "Base 1 is excluded exactly, as in the host interpreter"
+            )]
+            if a <= 0.0 || a == 1.0 || b < 0.0 {
+                f64::NAN
+            } else {
+                libm::log(b) / libm::log(a)
+            }
+        }
+        FnOp::Min => min(a, b),
+        FnOp::Max => max(a, b),
+        FnOp::Less => f64::from(u8::from(a < b)),
+        FnOp::LessEq => f64::from(u8::from(a <= b)),
+        FnOp::Greater => f64::from(u8::from(a > b)),
+        FnOp::GreaterEq => f64::from(u8::from(a >= b)),
+        #[allow(clippy::float_cmp, reason = "The equality indicator compares exactly")]
+        FnOp::Equal => f64::from(u8::from(a == b)),
+        _ => f64::NAN,
+    }
+}
+
+/// Value of the three-argument builtin `op` at `(a, b, c)`
+pub fn builtin3(op: FnOp, a: f64, b: f64, c: f64) -> f64 {
+    match op {
+        FnOp::Clamp => min(max(a, b), c),
+        FnOp::Select => select(a, b, c),
+        _ => f64::NAN,
+    }
+}
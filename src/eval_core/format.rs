@@ -0,0 +1,268 @@
+//! Reading the compact binary format of compiled programs.
+//!
+//! All integers are little-endian. A program is written as:
+//! - the magic bytes `SAEV` and a `u16` format version
+//! - parameter names: a `u32` count, then each name as a `u32` byte length and UTF-8
+//! - constant pool: a `u32` count, then one `f64` per constant
+//! - `u32` workspace size and `u32` result register
+//! - argument pool: a `u32` count, then one `u32` register per entry
+//! - instructions: a `u32` count, then each instruction as its `u8` tag followed by its
+//!   operands (`u32` registers and pool indices, a `u8` builtin, an `i32` exponent)
+//!
+//! Interpreters read registers by index, so decoding validates every register, pool
+//! range and builtin before a program is accepted.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::functions::FnOp;
+use super::instruction::Instruction;
+
+/// Leading bytes of every serialized program
+pub const MAGIC: &[u8; 4] = b"SAEV";
+
+/// Format version, bumped whenever the layout or the instruction set changes
+pub const VERSION: u16 = 2;
+
+/// Bytes that are not a valid compiled program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Byte offset at which decoding failed
+    pub offset: usize,
+    /// What was wrong at that offset
+    pub reason: &'static str,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "Invalid compiled evaluator data at byte {}: {}",
+            self.offset, self.reason
+        )
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Cursor over serialized bytes that reports failures with their offset
+pub struct ByteReader<'data> {
+    data: &'data [u8],
+    pos: usize,
+}
+
+impl<'data> ByteReader<'data> {
+    const fn new(data: &'data [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// An error located `len` bytes before the cursor, at the start of the last read
+    pub const fn error_before(&self, len: usize, reason: &'static str) -> DecodeError {
+        DecodeError {
+            offset: self.pos.saturating_sub(len),
+            reason,
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(N))
+            .ok_or(DecodeError {
+                offset: self.pos,
+                reason: "unexpected end of data",
+            })?;
+        self.pos += N;
+        let mut out = [0; N];
+        out.copy_from_slice(bytes);
+        Ok(out)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn read_len(&mut self) -> Result<usize, DecodeError> {
+        self.read_u32().map(|len| len as usize)
+    }
+
+    fn read_f64(&mut self) -> Result<f64, DecodeError> {
+        self.take().map(f64::from_le_bytes)
+    }
+
+    fn read_str(&mut self) -> Result<String, DecodeError> {
+        let len = self.read_len()?;
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or_else(|| self.error_before(4, "string runs past the end of data"))?;
+        let text = core::str::from_utf8(bytes).map_err(|err| DecodeError {
+            offset: self.pos + err.valid_up_to(),
+            reason: "parameter name is not UTF-8",
+        })?;
+        self.pos += len;
+        Ok(text.to_owned())
+    }
+}
+
+/// An instruction operand as stored in the binary format
+pub trait Operand: Sized {
+    /// Append the operand to `out`
+    #[cfg(feature = "std")]
+    fn encode(self, out: &mut Vec<u8>);
+
+    /// Read one operand written by `encode`
+    ///
+    /// # Errors
+    /// Returns a [`DecodeError`] on truncated or unknown data.
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, DecodeError>;
+}
+
+impl Operand for u32 {
+    #[cfg(feature = "std")]
+    fn encode(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, DecodeError> {
+        reader.read_u32()
+    }
+}
+
+impl Operand for i32 {
+    #[cfg(feature = "std")]
+    fn encode(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, DecodeError> {
+        reader.take().map(Self::from_le_bytes)
+    }
+}
+
+impl Operand for FnOp {
+    #[cfg(feature = "std")]
+    fn encode(self, out: &mut Vec<u8>) {
+        out.push(self as u8);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, DecodeError> {
+        let tag = reader.read_u8()?;
+        Self::from_u8(tag).ok_or_else(|| reader.error_before(1, "unknown builtin function"))
+    }
+}
+
+/// Why `instr` cannot run in a workspace of `workspace` registers with `pool_len` pool
+/// entries by an interpreter implementing the builtins accepted by `supported`, if it cannot
+fn check_instruction(
+    instr: &Instruction,
+    workspace: u32,
+    pool_len: usize,
+    supported: &impl Fn(FnOp) -> bool,
+) -> Option<&'static str> {
+    if matches!(instr, Instruction::CallExternal { .. }) {
+        return Some("native callbacks cannot be loaded");
+    }
+    let mut in_range = true;
+    instr.for_each_reg(|reg| in_range &= reg < workspace);
+    if !in_range {
+        return Some("register out of range");
+    }
+    if let Some((start, count)) = instr.arg_pool_range() {
+        let end = start as usize + count as usize;
+        if count == 0 || end > pool_len {
+            return Some("argument pool range out of bounds");
+        }
+    }
+    let arity = match *instr {
+        Instruction::Builtin1 { op, .. } => Some((op, 1)),
+        Instruction::Builtin2 { op, .. } => Some((op, 2)),
+        Instruction::Builtin3 { op, .. } => Some((op, 3)),
+        Instruction::Builtin4 { op, .. } => Some((op, 4)),
+        _ => None,
+    };
+    match arity {
+        Some((op, arity)) if op.arity() != arity => Some("builtin called with the wrong arity"),
+        Some((op, _)) if !supported(op) => Some("builtin not available in this interpreter"),
+        _ => None,
+    }
+}
+
+/// The sections of a decoded and validated program
+pub struct RawProgram {
+    pub param_names: Vec<String>,
+    pub constants: Vec<f64>,
+    pub workspace_size: usize,
+    pub result_reg: u32,
+    pub arg_pool: Vec<u32>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl RawProgram {
+    /// Decode and validate a program whose builtins are all accepted by `supported`
+    pub fn decode(data: &[u8], supported: impl Fn(FnOp) -> bool) -> Result<Self, DecodeError> {
+        let mut reader = ByteReader::new(data);
+        if reader.take::<4>().ok().as_ref() != Some(MAGIC) {
+            return Err(reader.error_before(reader.pos, "not a compiled evaluator"));
+        }
+        if u16::from_le_bytes(reader.take()?) != VERSION {
+            return Err(reader.error_before(2, "unsupported format version"));
+        }
+
+        let mut param_names = Vec::new();
+        for _ in 0..reader.read_len()? {
+            param_names.push(reader.read_str()?);
+        }
+        let mut constants = Vec::new();
+        for _ in 0..reader.read_len()? {
+            constants.push(reader.read_f64()?);
+        }
+
+        let workspace = reader.read_u32()?;
+        let workspace_size = workspace as usize;
+        if workspace_size < param_names.len() + constants.len() {
+            return Err(reader.error_before(4, "workspace smaller than parameters and constants"));
+        }
+        let result_reg = reader.read_u32()?;
+        if result_reg >= workspace {
+            return Err(reader.error_before(4, "result register out of range"));
+        }
+
+        let mut arg_pool = Vec::new();
+        for _ in 0..reader.read_len()? {
+            let reg = reader.read_u32()?;
+            if reg >= workspace {
+                return Err(reader.error_before(4, "argument pool register out of range"));
+            }
+            arg_pool.push(reg);
+        }
+
+        let mut instructions = Vec::new();
+        for _ in 0..reader.read_len()? {
+            let start = reader.pos;
+            let instr = Instruction::decode(&mut reader)?;
+            if let Some(reason) = check_instruction(&instr, workspace, arg_pool.len(), &supported) {
+                return Err(reader.error_before(reader.pos - start, reason));
+            }
+            instructions.push(instr);
+        }
+        if reader.pos != data.len() {
+            return Err(reader.error_before(0, "trailing bytes after the program"));
+        }
+
+        Ok(Self {
+            param_names,
+            constants,
+            workspace_size,
+            result_reg,
+            arg_pool,
+            instructions,
+        })
+    }
+}
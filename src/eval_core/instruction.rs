@@ -1,6 +1,9 @@
 //! Bytecode instruction set for the register-based expression evaluator.
-use std::fmt::{Display, Formatter, Result as FmtResult};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
 
+use super::format::{ByteReader, DecodeError, Operand};
 use super::functions::FnOp;
 
 macro_rules! define_isa {
@@ -21,6 +24,7 @@ macro_rules! define_isa {
         /// - Registers `param_count + const_count..` are temporaries.
         #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
         #[repr(u8)]
+        #[non_exhaustive]
         pub enum Instruction {
             $(
                 $( #[$attr] )*
@@ -39,7 +43,7 @@ macro_rules! define_isa {
                     reason = "High-performance opcode extraction via pointer cast is safe for repr(u8) enums"
                 )]
                 // SAFETY: Instruction is repr(u8), ensuring the discriminant is the first byte.
-                unsafe { *std::ptr::from_ref::<Self>(self).cast::<u8>() as u32 }
+                unsafe { *core::ptr::from_ref::<Self>(self).cast::<u8>() as u32 }
             }
 
             /// Range in the arg-pool used by this instruction, if any.
@@ -219,6 +223,40 @@ macro_rules! define_isa {
             }
         }
 
+        impl Instruction {
+            /// Append the tag byte and operands of this instruction to `out`.
+            #[cfg(feature = "std")]
+            pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    reason = "Instruction is repr(u8), so its opcode fits a byte"
+                )]
+                out.push(self.opcode() as u8);
+                match self {
+                    $(
+                        Self::$name { $( $field, )* } => {
+                            $( Operand::encode(*$field, out); )*
+                        }
+                    )*
+                }
+            }
+
+            /// Read one instruction written by [`encode`](Self::encode).
+            pub(crate) fn decode(reader: &mut ByteReader<'_>) -> Result<Self, DecodeError> {
+                let tag = reader.read_u8()?;
+                // Discriminants count up from zero in declaration order
+                let mut next = 0_u8;
+                $(
+                    if tag == next {
+                        return Ok(Self::$name { $( $field: <$type as Operand>::decode(reader)?, )* });
+                    }
+                    next += 1;
+                )*
+                let _ = next;
+                Err(reader.error_before(1, "unknown instruction tag"))
+            }
+        }
+
         impl Display for Instruction {
             fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
                 match self {
@@ -346,25 +384,25 @@ define_isa! {
     NegMulSub { dest: u32, @dest, a: u32, @read, b: u32, @read, c: u32, @read } => ("R{} = -(R{} * R{}) - R{}", dest, a, b, c),
 
     /// Square: `dest = src^2`
-    Square { dest: u32, @dest, src: u32, @read } => ("R{}^2", dest),
+    Square { dest: u32, @dest, src: u32, @read } => ("R{} = R{}^2", dest, src),
     /// Cube: `dest = src^3`
-    Cube { dest: u32, @dest, src: u32, @read } => ("R{}^3", dest),
+    Cube { dest: u32, @dest, src: u32, @read } => ("R{} = R{}^3", dest, src),
     /// Fourth Power: `dest = src^4`
-    Pow4 { dest: u32, @dest, src: u32, @read } => ("R{}^4", dest),
+    Pow4 { dest: u32, @dest, src: u32, @read } => ("R{} = R{}^4", dest, src),
     /// Power 3/2: `dest = src^(3/2)`
-    Pow3_2 { dest: u32, @dest, src: u32, @read } => ("R{}^(3/2)", dest),
+    Pow3_2 { dest: u32, @dest, src: u32, @read } => ("R{} = R{}^(3/2)", dest, src),
     /// Inverse Power 3/2: `dest = src^(-3/2)`
-    InvPow3_2 { dest: u32, @dest, src: u32, @read } => ("R{}^(-3/2)", dest),
+    InvPow3_2 { dest: u32, @dest, src: u32, @read } => ("R{} = R{}^(-3/2)", dest, src),
     /// Inverse Square Root: `dest = 1/sqrt(src)`
-    InvSqrt { dest: u32, @dest, src: u32, @read } => ("1/sqrt(R{})", src),
+    InvSqrt { dest: u32, @dest, src: u32, @read } => ("R{} = 1/sqrt(R{})", dest, src),
     /// Inverse Square: `dest = 1/src^2`
-    InvSquare { dest: u32, @dest, src: u32, @read } => ("1/R{}^2", src),
+    InvSquare { dest: u32, @dest, src: u32, @read } => ("R{} = 1/R{}^2", dest, src),
     /// Inverse Cube: `dest = 1/src^3`
-    InvCube { dest: u32, @dest, src: u32, @read } => ("1/R{}^3", src),
+    InvCube { dest: u32, @dest, src: u32, @read } => ("R{} = 1/R{}^3", dest, src),
     /// Reciprocal: `dest = 1/src`
-    Recip { dest: u32, @dest, src: u32, @read } => ("1/R{}", src),
+    Recip { dest: u32, @dest, src: u32, @read } => ("R{} = 1/R{}", dest, src),
     /// Integer Power: `dest = src^n`
-    Powi { dest: u32, @dest, src: u32, @read, n: i32 } => ("R{}^{}", dest, n),
+    Powi { dest: u32, @dest, src: u32, @read, n: i32 } => ("R{} = R{}^{}", dest, src, n),
 
     /// Sine: `dest = sin(arg)`
     Sin { dest: u32, @dest, arg: u32, @read } => ("R{} = sin(R{})", dest, arg),
@@ -392,4 +430,18 @@ define_isa! {
     Builtin3 { dest: u32, @dest, op: FnOp, arg1: u32, @read, arg2: u32, @read, arg3: u32, @read } => ("R{} = {}(R{}, R{}, R{})", dest, op, arg1, arg2, arg3),
     /// Quaternary Builtin: `dest = u32, op: FnOp, arg1: u32, arg2: u32, arg3: u32, arg4: u32`
     Builtin4 { dest: u32, @dest, op: FnOp, arg1: u32, @read, arg2: u32, @read, arg3: u32, @read, arg4: u32, @read } => ("R{} = {}(R{}, R{}, R{}, R{})", dest, op, arg1, arg2, arg3, arg4),
+
+    /// Minimum against a constant-pool register: `dest = min(src, bound)`
+    MinConst { dest: u32, @dest, src: u32, @read, bound: u32, @read } => ("R{} = min(R{}, R{})", dest, src, bound),
+    /// Maximum against a constant-pool register: `dest = max(src, bound)`
+    MaxConst { dest: u32, @dest, src: u32, @read, bound: u32, @read } => ("R{} = max(R{}, R{})", dest, src, bound),
+    /// Clamp: `dest = min(max(src, lo), hi)`
+    Clamp { dest: u32, @dest, src: u32, @read, lo: u32, @read, hi: u32, @read } => ("R{} = clamp(R{}, R{}, R{})", dest, src, lo, hi),
+    /// Conditional Select: `dest = cond != 0 ? a : b`, NaN when `cond` is NaN
+    Select { dest: u32, @dest, cond: u32, @read, a: u32, @read, b: u32, @read } => ("R{} = R{} ? R{} : R{}", dest, cond, a, b),
+    /// Sign-tested Select: `dest = src >= 0 ? a : b`, NaN when `src` is NaN
+    SelectNonNeg { dest: u32, @dest, src: u32, @read, a: u32, @read, b: u32, @read } => ("R{} = R{} >= 0 ? R{} : R{}", dest, src, a, b),
+
+    /// Native Callback: `dest = externals[callback](pool[start_idx..start_idx + count])`
+    CallExternal { dest: u32, @dest, callback: u32, start_idx: u32, @pool_start, count: u32, @pool_count } => ("R{} = ext{}(pool[{}..{}])", dest, callback, start_idx, start_idx + count),
 }
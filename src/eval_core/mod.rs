@@ -0,0 +1,33 @@
+//! Evaluation core that runs precompiled programs without `std`.
+//!
+//! The instruction set, the builtin table and the decoder of the binary program format
+//! live here so the host compiler and a device-side interpreter share one definition.
+//! With the `eval-core` feature this module also provides [`Program`]: a `no_std` +
+//! `alloc` interpreter for bytes written by
+//! [`CompiledEvaluator::to_bytes`](crate::CompiledEvaluator::to_bytes), with math from
+//! `libm`. It holds no thread-local or global state, so the rest of the crate can be
+//! compiled out:
+//!
+//! ```text
+//! cargo check --no-default-features --features eval-core
+//! ```
+
+#[cfg(feature = "eval-core")]
+mod builtins;
+pub(crate) mod format;
+pub(crate) mod functions;
+#[cfg_attr(
+    not(feature = "std"),
+    allow(
+        dead_code,
+        reason = "Register mapping helpers are only used by the host compiler"
+    )
+)]
+pub(crate) mod instruction;
+#[cfg(feature = "eval-core")]
+mod program;
+
+#[cfg(feature = "eval-core")]
+pub use format::DecodeError;
+#[cfg(feature = "eval-core")]
+pub use program::Program;
@@ -0,0 +1,197 @@
+//! Device-side interpreter for programs compiled on the host.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::builtins::{
+    builtin1, builtin2, builtin3, max, min, powi, select, select_non_neg, supports,
+};
+use super::format::{DecodeError, RawProgram};
+use super::instruction::Instruction;
+
+/// A compiled program loaded for evaluation without `std`.
+///
+/// Load the bytes of [`CompiledEvaluator::to_bytes`](crate::CompiledEvaluator::to_bytes)
+/// with [`from_bytes`](Self::from_bytes), then evaluate with
+/// [`evaluate`](Self::evaluate), or with [`evaluate_with`](Self::evaluate_with) to reuse
+/// a register buffer and avoid allocating on every call.
+///
+/// Results match the host evaluator up to the rounding of the `libm` functions. The
+/// host's singularity fallback needs the expression tree and is not available here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    param_names: Vec<String>,
+    constants: Vec<f64>,
+    workspace_size: usize,
+    result_reg: u32,
+    arg_pool: Vec<u32>,
+    instructions: Vec<Instruction>,
+}
+
+impl Program {
+    /// Load a program written by
+    /// [`CompiledEvaluator::to_bytes`](crate::CompiledEvaluator::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] with the offending byte offset if the data is
+    /// truncated, has the wrong magic number or format version, describes an invalid
+    /// program, or calls a special function the `no_std` core does not implement.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DecodeError> {
+        let RawProgram {
+            param_names,
+            constants,
+            workspace_size,
+            result_reg,
+            arg_pool,
+            instructions,
+        } = RawProgram::decode(data, supports)?;
+        Ok(Self {
+            param_names,
+            constants,
+            workspace_size,
+            result_reg,
+            arg_pool,
+            instructions,
+        })
+    }
+
+    /// Parameter names, in the order [`evaluate`](Self::evaluate) expects their values
+    #[must_use]
+    pub fn param_names(&self) -> &[String] {
+        &self.param_names
+    }
+
+    /// Number of registers [`evaluate_with`](Self::evaluate_with) needs
+    #[must_use]
+    pub const fn workspace_size(&self) -> usize {
+        self.workspace_size
+    }
+
+    /// Evaluate at `params`, one value per parameter; missing values count as `0`.
+    #[must_use]
+    pub fn evaluate(&self, params: &[f64]) -> f64 {
+        let mut registers = vec![0.0; self.workspace_size];
+        self.evaluate_with(params, &mut registers)
+    }
+
+    /// Evaluate at `params` using `registers` as the workspace.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `registers` is shorter than [`workspace_size`](Self::workspace_size).
+    #[must_use]
+    pub fn evaluate_with(&self, params: &[f64], registers: &mut [f64]) -> f64 {
+        let regs = &mut registers[..self.workspace_size];
+        let param_count = self.param_names.len();
+        for (i, reg) in regs[..param_count].iter_mut().enumerate() {
+            *reg = params.get(i).copied().unwrap_or(0.0);
+        }
+        regs[param_count..param_count + self.constants.len()].copy_from_slice(&self.constants);
+        for instr in &self.instructions {
+            if !self.exec(*instr, regs) {
+                break;
+            }
+        }
+        regs[self.result_reg as usize]
+    }
+
+    /// Run one instruction; `false` at the end of the program
+    #[allow(
+        clippy::too_many_lines,
+        reason = "One arm per instruction, mirroring the host dispatch loop"
+    )]
+    fn exec(&self, instr: Instruction, regs: &mut [f64]) -> bool {
+        let r = |reg: u32| regs[reg as usize];
+        let (dest, value) = match instr {
+            Instruction::End {} => return false,
+            Instruction::Copy { dest, src } => (dest, r(src)),
+            Instruction::Neg { dest, src } => (dest, -r(src)),
+            Instruction::SinCos { sin_dest, arg, .. } => (sin_dest, libm::sin(r(arg))),
+            Instruction::Add { dest, a, b } => (dest, r(a) + r(b)),
+            Instruction::Add3 { dest, a, b, c } => (dest, r(a) + r(b) + r(c)),
+            Instruction::Add4 { dest, a, b, c, d } => (dest, r(a) + r(b) + r(c) + r(d)),
+            Instruction::AddN {
+                dest,
+                start_idx,
+                count,
+            } => (dest, self.pooled(start_idx, count).map(r).sum()),
+            Instruction::Mul { dest, a, b } => (dest, r(a) * r(b)),
+            Instruction::Mul3 { dest, a, b, c } => (dest, r(a) * r(b) * r(c)),
+            Instruction::Mul4 { dest, a, b, c, d } => (dest, r(a) * r(b) * r(c) * r(d)),
+            Instruction::MulN {
+                dest,
+                start_idx,
+                count,
+            } => (dest, self.pooled(start_idx, count).map(r).product()),
+            Instruction::Sub { dest, a, b } => (dest, r(a) - r(b)),
+            Instruction::Div { dest, num, den } => (dest, r(num) / r(den)),
+            Instruction::Pow { dest, base, exp } => (dest, libm::pow(r(base), r(exp))),
+            Instruction::MulAdd { dest, a, b, c } => (dest, libm::fma(r(a), r(b), r(c))),
+            Instruction::MulSub { dest, a, b, c } => (dest, libm::fma(r(a), r(b), -r(c))),
+            Instruction::NegMul { dest, a, b } => (dest, -(r(a) * r(b))),
+            Instruction::NegMulAdd { dest, a, b, c } => (dest, libm::fma(-r(a), r(b), r(c))),
+            Instruction::NegMulSub { dest, a, b, c } => (dest, libm::fma(-r(a), r(b), -r(c))),
+            Instruction::Square { dest, src } => (dest, r(src) * r(src)),
+            Instruction::Cube { dest, src } => (dest, r(src) * r(src) * r(src)),
+            Instruction::Pow4 { dest, src } => {
+                let sq = r(src) * r(src);
+                (dest, sq * sq)
+            }
+            Instruction::Pow3_2 { dest, src } => (dest, r(src) * libm::sqrt(r(src))),
+            Instruction::InvPow3_2 { dest, src } => (dest, 1.0 / (r(src) * libm::sqrt(r(src)))),
+            Instruction::InvSqrt { dest, src } => (dest, 1.0 / libm::sqrt(r(src))),
+            Instruction::InvSquare { dest, src } => (dest, 1.0 / (r(src) * r(src))),
+            Instruction::InvCube { dest, src } => (dest, 1.0 / (r(src) * r(src) * r(src))),
+            Instruction::Recip { dest, src } => (dest, 1.0 / r(src)),
+            Instruction::Powi { dest, src, n } => (dest, powi(r(src), n)),
+            Instruction::Sin { dest, arg } => (dest, libm::sin(r(arg))),
+            Instruction::Cos { dest, arg } => (dest, libm::cos(r(arg))),
+            Instruction::Exp { dest, arg } => (dest, libm::exp(r(arg))),
+            Instruction::Ln { dest, arg } => (dest, libm::log(r(arg))),
+            Instruction::Sqrt { dest, arg } => (dest, libm::sqrt(r(arg))),
+            Instruction::RecipExpm1 { dest, src } => (dest, 1.0 / libm::expm1(r(src))),
+            Instruction::ExpSqr { dest, src } => (dest, libm::exp(r(src) * r(src))),
+            Instruction::ExpSqrNeg { dest, src } => (dest, libm::exp(-(r(src) * r(src)))),
+            Instruction::Builtin1 { dest, op, arg } => (dest, builtin1(op, r(arg))),
+            Instruction::Builtin2 {
+                dest,
+                op,
+                arg1,
+                arg2,
+            } => (dest, builtin2(op, r(arg1), r(arg2))),
+            Instruction::Builtin3 {
+                dest,
+                op,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, builtin3(op, r(arg1), r(arg2), r(arg3))),
+            // Every four-argument builtin and native callback is refused when the program
+            // is loaded
+            Instruction::Builtin4 { dest, .. } | Instruction::CallExternal { dest, .. } => {
+                (dest, f64::NAN)
+            }
+            Instruction::MinConst { dest, src, bound } => (dest, min(r(src), r(bound))),
+            Instruction::MaxConst { dest, src, bound } => (dest, max(r(src), r(bound))),
+            Instruction::Clamp { dest, src, lo, hi } => (dest, min(max(r(src), r(lo)), r(hi))),
+            Instruction::Select { dest, cond, a, b } => (dest, select(r(cond), r(a), r(b))),
+            Instruction::SelectNonNeg { dest, src, a, b } => {
+                (dest, select_non_neg(r(src), r(a), r(b)))
+            }
+        };
+        if let Instruction::SinCos { cos_dest, arg, .. } = instr {
+            regs[cos_dest as usize] = libm::cos(regs[arg as usize]);
+        }
+        regs[dest as usize] = value;
+        true
+    }
+
+    /// Registers of the argument pool entries `start..start + count`
+    fn pooled(&self, start: u32, count: u32) -> impl Iterator<Item = u32> + '_ {
+        self.arg_pool[start as usize..(start + count) as usize]
+            .iter()
+            .copied()
+    }
+}
@@ -4,31 +4,75 @@
 //! - [`EvaluatorBuilder`] — builder for [`CompiledEvaluator`]
 //! - [`CompiledEvaluator`] — compiled, thread-safe expression evaluator
 //! - [`ToParamName`] — trait for types usable as parameter names
-//! - [`eval_f64`] — parallel batch evaluation over multiple expressions (requires `parallel` feature)
+//! - [`eval_f64`] — batch evaluation over multiple expressions (parallel with the `parallel` feature)
+//!
+//! The batch entry points ([`eval_f64`], [`evaluate_parallel`]) have the same names and
+//! signatures with and without the `parallel` feature; without it they run sequentially.
+//! Parallel builds additionally provide `*_with_pool` variants taking a `rayon::ThreadPool`.
 
-use std::cmp::Reverse;
-use std::collections::BTreeMap;
-use std::fmt::Write;
 use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Arc;
 
+use super::data::DataSums;
+use super::logic::SingularityFallback;
 pub use super::logic::VarLookup;
 #[cfg(feature = "parallel")]
+pub use super::logic::evaluate_parallel_with_pool;
+pub use super::logic::interval;
 pub use super::logic::{EvalResult, ExprInput, SKIP, Value, VarInput, evaluate_parallel};
 pub use super::logic::{
     FnOp, Instruction, VirGenerator, assemble_flat_bytecode, expand_user_functions,
+    substitute_constants,
 };
 
-#[cfg(feature = "parallel")]
 use super::logic::eval_single_expr_chunked;
 #[cfg(all(feature = "parallel", feature = "python"))]
 pub use super::logic::evaluate_parallel_with_hint;
 
 use crate::{
     Expr, Symbol,
-    core::{Context, error::DiffError, known_symbols::is_known_constant_by_id, symb_interned},
+    core::{
+        Context, NumericFn,
+        error::DiffError,
+        known_symbols::{KS, is_known_constant_by_id},
+        symb_interned,
+    },
     symb,
 };
 
+// ============================================================================
+// EvalOptions
+// ============================================================================
+
+/// Compilation options for [`CompiledEvaluator::compile_with_options`].
+///
+/// # Example
+///
+/// ```
+/// use symb_anafis::{parse, CompiledEvaluator, EvalOptions};
+/// use std::collections::HashSet;
+///
+/// let expr = parse("(1 - cos(x))/x^2", &HashSet::new(), &HashSet::new(), None).expect("Should parse");
+/// let options = EvalOptions { singularity_fallback: true, ..EvalOptions::default() };
+/// let compiled = CompiledEvaluator::compile_with_options(&expr, &["x"], None, options)
+///     .expect("Should compile");
+/// assert!((compiled.evaluate(&[0.0]) - 0.5).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalOptions {
+    /// Allow NaN literals in the expression, which are rejected by default.
+    pub allow_nan: bool,
+    /// When a point evaluates to NaN or ±inf, retry on the expression tree and return
+    /// the limit if the singularity is removable (`sin(x)/x`, `x*ln(x)`, ... at `0`).
+    ///
+    /// The retry differentiates and simplifies the offending quotients, so it is far
+    /// slower than the bytecode; points that evaluate to finite values are unaffected.
+    pub singularity_fallback: bool,
+    /// Accumulate [`sum_data`](crate::Expr::sum_data) with compensated (Neumaier)
+    /// summation, which keeps the rounding error independent of the number of points.
+    pub compensated_sums: bool,
+}
+
 // ============================================================================
 // EvaluatorBuilder
 // ============================================================================
@@ -53,6 +97,7 @@ pub struct EvaluatorBuilder<'ctx> {
     pub(crate) expr: &'ctx Expr,
     pub(crate) param_order: Option<Vec<String>>,
     pub(crate) context: Option<&'ctx Context>,
+    pub(crate) options: EvalOptions,
 }
 
 impl<'ctx> EvaluatorBuilder<'ctx> {
@@ -64,6 +109,11 @@ impl<'ctx> EvaluatorBuilder<'ctx> {
             expr,
             param_order: None,
             context: None,
+            options: EvalOptions {
+                allow_nan: false,
+                singularity_fallback: false,
+                compensated_sums: false,
+            },
         }
     }
 
@@ -92,17 +142,42 @@ impl<'ctx> EvaluatorBuilder<'ctx> {
         self
     }
 
+    /// Allow NaN literals in the expression, which are rejected by default.
+    #[inline]
+    #[must_use]
+    pub const fn allow_nan(mut self, allow: bool) -> Self {
+        self.options.allow_nan = allow;
+        self
+    }
+
+    /// Resolve removable singularities at evaluation time (see
+    /// [`EvalOptions::singularity_fallback`]).
+    #[inline]
+    #[must_use]
+    pub const fn singularity_fallback(mut self, enable: bool) -> Self {
+        self.options.singularity_fallback = enable;
+        self
+    }
+
+    /// Accumulate data sums with compensated summation (see
+    /// [`EvalOptions::compensated_sums`]).
+    #[inline]
+    #[must_use]
+    pub const fn compensated_sums(mut self, enable: bool) -> Self {
+        self.options.compensated_sums = enable;
+        self
+    }
+
     /// Build the `CompiledEvaluator`.
     ///
     /// # Errors
     ///
     /// Returns `DiffError` if compilation fails.
     pub fn build(self) -> Result<CompiledEvaluator, DiffError> {
-        if let Some(params) = self.param_order {
-            CompiledEvaluator::compile(self.expr, &params, self.context)
-        } else {
-            CompiledEvaluator::compile_auto(self.expr, self.context)
-        }
+        let params = self
+            .param_order
+            .unwrap_or_else(|| CompiledEvaluator::auto_param_order(self.expr, self.context));
+        CompiledEvaluator::compile_with_options(self.expr, &params, self.context, self.options)
     }
 }
 
@@ -139,6 +214,12 @@ pub struct CompiledEvaluator {
     pub(crate) param_count: usize,
     /// Register index where the final result is stored.
     pub(crate) result_reg: u32,
+    /// Native callbacks of user functions, indexed by `CallExternal`
+    pub(crate) externals: Box<[NumericFn]>,
+    /// Expression tree for limit evaluation of non-finite results, if enabled
+    pub(crate) singularity_fallback: Option<Arc<SingularityFallback>>,
+    /// Programs of the `sum_data` calls, whose values follow the parameters
+    pub(crate) data_sums: Option<Arc<DataSums>>,
 }
 
 impl CompiledEvaluator {
@@ -186,83 +267,21 @@ impl CompiledEvaluator {
 
     /// Disassemble the compiled bytecode into a readable string format,
     /// including execution statistics to aid in performance analysis.
+    ///
+    /// Each instruction line shows its register form, the value it computes
+    /// with parameters and constants substituted, the constant-pool slots it
+    /// reads (`k0`, `k1`, ...) and the number of live temporaries after it.
+    /// See [`disassemble_to_writer`](Self::disassemble_to_writer) to stream
+    /// the listing elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// Never panics in practice: writing to a `String` cannot fail.
     #[must_use]
     pub fn disassemble(&self) -> String {
         let mut s = String::new();
-        writeln!(&mut s, "=== Bytecode Disassembly ===")
-            .expect("Failed to write to disassembly string");
-        writeln!(&mut s, "Parameters ({}): {}", self.param_count, {
-            let mut param_str = String::new();
-            write!(&mut param_str, "[").expect("Failed to write to disassembly string");
-            for (i, name) in self.param_names.iter().enumerate() {
-                write!(
-                    &mut param_str,
-                    "{}{}",
-                    name,
-                    if i < self.param_names.len() - 1 {
-                        ", "
-                    } else {
-                        ""
-                    }
-                )
-                .expect("Failed to write to disassembly string");
-            }
-            write!(&mut param_str, "]").expect("Failed to write to disassembly string");
-            param_str
-        })
-        .expect("Failed to write to disassembly string");
-
-        if !self.constants.is_empty() {
-            writeln!(&mut s, "\n=== Constants ({}) ===", self.constants.len())
-                .expect("Failed to write to disassembly string");
-            for (i, c) in self.constants.iter().enumerate() {
-                writeln!(&mut s, "  R{:<4} = {}", self.param_count + i, c)
-                    .expect("Failed to write to disassembly string");
-            }
-        }
-
-        writeln!(&mut s, "\n=== Memory Layout ===").expect("Failed to write to disassembly string");
-        writeln!(&mut s, "Workspace Size: {} registers", self.workspace_size)
-            .expect("Failed to write to disassembly string");
-        writeln!(&mut s, "Arg Pool Size:  {} entries", self.arg_pool.len())
+        self.disassemble_to_writer(&mut s)
             .expect("Failed to write to disassembly string");
-
-        writeln!(
-            &mut s,
-            "\n=== Instructions ({}) ===",
-            self.instructions.len()
-        )
-        .expect("Failed to write to disassembly string");
-
-        let mut instr_counts = BTreeMap::new();
-        for (i, instr) in self.instructions.iter().enumerate() {
-            let debug_str = format!("{instr:?}");
-            let variant_name = debug_str
-                .split_once(['{', '(', ' '])
-                .map_or(debug_str.as_str(), |(v, _)| v);
-            *instr_counts.entry(variant_name.to_owned()).or_insert(0) += 1;
-
-            writeln!(&mut s, "  [{i:04}] {instr}").expect("Failed to write to disassembly string");
-        }
-
-        if !self.instructions.is_empty() {
-            writeln!(&mut s, "\n=== Instruction Summary ===")
-                .expect("Failed to write to disassembly string");
-            let mut sorted_counts: Vec<_> = instr_counts.into_iter().collect();
-            sorted_counts.sort_by_key(|b| Reverse(b.1));
-
-            #[allow(
-                clippy::cast_precision_loss,
-                reason = "Instruction count unlikely to exceed f64 mantissa precision"
-            )]
-            let total_instrs = self.instructions.len() as f64;
-            for (name, count) in sorted_counts {
-                let percentage = (f64::from(count) / total_instrs) * 100.0;
-                writeln!(&mut s, "  {name:<15}: {count:>6} ({percentage:>5.1}%)")
-                    .expect("Failed to write to disassembly string");
-            }
-        }
-
         s
     }
 }
@@ -277,7 +296,10 @@ impl Debug for CompiledEvaluator {
             .field("arg_pool_count", &self.arg_pool.len())
             .field("workspace_size", &self.workspace_size)
             .field("result_reg", &self.result_reg)
-            .field("constant_count", &self.constants.len());
+            .field("constant_count", &self.constants.len())
+            .field("external_count", &self.externals.len())
+            .field("singularity_fallback", &self.singularity_fallback.is_some())
+            .field("data_sums", &self.data_sums.is_some());
         s.finish()
     }
 }
@@ -289,6 +311,7 @@ impl Debug for CompiledEvaluator {
 /// Trait for types that can be used as parameter names in compile methods.
 ///
 /// This allows `compile` to accept `&[&str]`, `&[&Symbol]`, or mixed types.
+/// The trait is sealed: string types and symbols are the only parameter names.
 ///
 /// # Example
 ///
@@ -306,11 +329,20 @@ impl Debug for CompiledEvaluator {
 /// // Using symbols
 /// let c2 = CompiledEvaluator::compile(&expr, &[&x, &y], None).expect("Should compile");
 /// ```
-pub trait ToParamName {
+pub trait ToParamName: sealed::Sealed {
     /// Get the parameter as a symbol ID (for fast lookup) and name (for storage/error messages).
     fn to_param_id_and_name(&self) -> (u64, String);
 }
 
+mod sealed {
+    /// Implemented exactly for the types that implement `ToParamName`
+    pub trait Sealed {}
+
+    impl<T: AsRef<str>> Sealed for T {}
+    impl Sealed for crate::Symbol {}
+    impl Sealed for &crate::Symbol {}
+}
+
 // Blanket impl for anything that can convert to &str
 impl<T: AsRef<str>> ToParamName for T {
     fn to_param_id_and_name(&self) -> (u64, String) {
@@ -342,8 +374,10 @@ impl ToParamName for &Symbol {
 // Free functions
 // ============================================================================
 
-#[cfg(feature = "parallel")]
-/// High-performance parallel batch evaluation for pure numeric workloads.
+/// High-performance batch evaluation for pure numeric workloads.
+///
+/// With the `parallel` feature, expressions and chunks are evaluated on Rayon's
+/// global pool with SIMD; without it, the same call runs sequentially.
 ///
 /// # Errors
 ///
@@ -354,25 +388,49 @@ pub fn eval_f64<V: ToParamName + Sync>(
     var_names: &[&[V]],
     data: &[&[&[f64]]],
 ) -> Result<Vec<Vec<f64>>, DiffError> {
-    use rayon::prelude::*;
-
     if exprs.len() != var_names.len() || exprs.len() != data.len() {
         return Err(DiffError::invalid_syntax(
             "exprs, var_names, and data must have the same length",
         ));
     }
 
-    (0..exprs.len())
-        .into_par_iter()
-        .map(|expr_idx| {
-            eval_single_expr_chunked(
-                exprs[expr_idx],
-                var_names[expr_idx],
-                data[expr_idx],
-                expr_idx,
-            )
-        })
-        .collect()
+    let eval_one = |expr_idx: usize| {
+        eval_single_expr_chunked(
+            exprs[expr_idx],
+            var_names[expr_idx],
+            data[expr_idx],
+            expr_idx,
+        )
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let results = (0..exprs.len()).map(eval_one).collect();
+
+    #[cfg(feature = "parallel")]
+    let results = {
+        use rayon::prelude::*;
+        (0..exprs.len()).into_par_iter().map(eval_one).collect()
+    };
+
+    results
+}
+
+/// [`eval_f64`] running on a caller-provided Rayon thread pool.
+///
+/// With `pool = None` this is identical to [`eval_f64`], which uses the global pool.
+///
+/// # Errors
+///
+/// Same as [`eval_f64`].
+#[cfg(feature = "parallel")]
+pub fn eval_f64_with_pool<V: ToParamName + Sync>(
+    pool: Option<&rayon::ThreadPool>,
+    exprs: &[&Expr],
+    var_names: &[&[V]],
+    data: &[&[&[f64]]],
+) -> Result<Vec<Vec<f64>>, DiffError> {
+    let run = || eval_f64(exprs, var_names, data);
+    pool.map_or_else(run, |pool| pool.install(run))
 }
 // ============================================================================
 // Compilation entry-points (impl on CompiledEvaluator)
@@ -404,41 +462,134 @@ impl CompiledEvaluator {
     ///
     /// Returns `DiffError` if:
     /// - `UnboundVariable`: Symbol not in parameter list and not a known constant
-    /// - `UnsupportedFunction`: Unknown function name
-    /// - `UnsupportedExpression`: Unevaluated derivatives
+    /// - `UnsupportedFunction`: Unknown function name, or a user function of `context`
+    ///   with neither a body nor a [`numeric`](crate::UserFunction::numeric) implementation
+    /// - `UnsupportedExpression`: Unevaluated derivatives, or a NaN literal (see
+    ///   [`EvaluatorBuilder::allow_nan`])
     pub fn compile<P: ToParamName>(
         expr: &Expr,
         param_order: &[P],
         context: Option<&Context>,
+    ) -> Result<Self, DiffError> {
+        Self::compile_with_options(expr, param_order, context, EvalOptions::default())
+    }
+
+    /// Compile an expression to bytecode with explicit [`EvalOptions`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symb_anafis::{parse, CompiledEvaluator, EvalOptions};
+    /// use std::collections::HashSet;
+    ///
+    /// let expr = parse("x*ln(x)", &HashSet::new(), &HashSet::new(), None).expect("Should parse");
+    /// let options = EvalOptions { singularity_fallback: true, ..EvalOptions::default() };
+    /// let compiled = CompiledEvaluator::compile_with_options(&expr, &["x"], None, options)
+    ///     .expect("Should compile");
+    /// assert_eq!(compiled.evaluate(&[0.0]), 0.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`compile`](Self::compile).
+    pub fn compile_with_options<P: ToParamName>(
+        expr: &Expr,
+        param_order: &[P],
+        context: Option<&Context>,
+        options: EvalOptions,
     ) -> Result<Self, DiffError> {
         let params: Vec<(u64, String)> = param_order
             .iter()
             .map(ToParamName::to_param_id_and_name)
             .collect();
+        let fallback_params = options.singularity_fallback.then(|| params.clone());
         let (param_ids, param_names): (Vec<u64>, Vec<String>) = params.into_iter().unzip();
 
-        let expanded_expr =
-            context.map_or_else(|| expr.clone(), |ctx| expand_user_functions(expr, ctx));
+        let expanded_expr = Self::prepare_expr(expr, &param_ids, context, options)?;
+        let (mut compiled, _) = Self::compile_program(
+            std::slice::from_ref(&expanded_expr),
+            &param_ids,
+            param_names,
+            context,
+            options,
+        )?;
+        compiled.singularity_fallback =
+            fallback_params.map(|ids| Arc::new(SingularityFallback::new(expanded_expr, ids)));
+        Ok(compiled)
+    }
 
-        let mut compiler = VirGenerator::new(&param_ids);
-        compiler.compile_expr(&expanded_expr)?;
+    /// Inline user functions and constants of `context`, then reject NaN literals
+    /// unless `options` allows them.
+    pub(crate) fn prepare_expr(
+        expr: &Expr,
+        param_ids: &[u64],
+        context: Option<&Context>,
+        options: EvalOptions,
+    ) -> Result<Expr, DiffError> {
+        let expanded_expr = context.map_or_else(
+            || expr.clone(),
+            |ctx| substitute_constants(&expand_user_functions(expr, ctx), ctx, param_ids),
+        );
+        if !options.allow_nan && expanded_expr.contains_nan() {
+            return Err(DiffError::UnsupportedExpression(
+                "NaN literal (use EvaluatorBuilder::allow_nan to compile it)".to_owned(),
+            ));
+        }
+        Ok(expanded_expr)
+    }
+
+    /// Compile prepared expressions into one program, sharing their common
+    /// subexpressions.
+    ///
+    /// Returns the program, whose `result_reg` holds the first expression, together with
+    /// the register of every expression in order. Functions of `context` with a native
+    /// implementation compile to calls of it, and `sum_data` calls over its data series
+    /// to programs of their own (see [`DataSums`]).
+    pub(crate) fn compile_program(
+        exprs: &[Expr],
+        param_ids: &[u64],
+        param_names: Vec<String>,
+        context: Option<&Context>,
+        options: EvalOptions,
+    ) -> Result<(Self, Vec<u32>), DiffError> {
+        if let Some(ctx) = context
+            && exprs
+                .iter()
+                .any(|expr| expr.contains_function_id(KS.sum_data))
+        {
+            return Self::compile_with_data_sums(exprs, param_ids, param_names, ctx, options);
+        }
 
-        let (vinstrs, mut constants, const_map, mut arg_pool, param_count, max_phys, result_reg) =
+        let mut compiler = VirGenerator::new(param_ids);
+        if let Some(ctx) = context {
+            compiler.register_numeric_functions(ctx);
+        }
+        for expr in exprs {
+            compiler.compile_expr(expr)?;
+        }
+        let externals = compiler.take_externals();
+
+        let (vinstrs, mut constants, const_map, mut arg_pool, param_count, max_phys, mut outputs) =
             compiler.into_parts();
 
-        let (optimized_instructions, max_stack, result_reg) = Self::optimize_instructions(
+        let (optimized_instructions, max_stack) = Self::optimize_program(
             vinstrs,
             &mut constants,
             const_map,
             &mut arg_pool,
             param_count,
             max_phys,
-            result_reg,
+            &mut outputs,
         )?;
+        // Without outputs, point at the 0.0 constant that directly follows the parameters
+        let result_reg = outputs
+            .first()
+            .copied()
+            .unwrap_or_else(|| u32::try_from(param_count).expect("Param count too large"));
 
         let flat_bytecode = assemble_flat_bytecode(&optimized_instructions);
 
-        Ok(Self {
+        let program = Self {
             instructions: Box::from(optimized_instructions),
             flat_bytecode: flat_bytecode.into_boxed_slice(),
             constants: Box::from(constants),
@@ -447,7 +598,11 @@ impl CompiledEvaluator {
             workspace_size: max_stack,
             param_count,
             result_reg,
-        })
+            externals: externals.into_boxed_slice(),
+            singularity_fallback: None,
+            data_sums: None,
+        };
+        Ok((program, outputs))
     }
 
     /// Compile an expression, automatically determining parameter order from variables.
@@ -458,16 +613,23 @@ impl CompiledEvaluator {
     ///
     /// Returns `DiffError` if compilation fails.
     pub fn compile_auto(expr: &Expr, context: Option<&Context>) -> Result<Self, DiffError> {
+        Self::compile(expr, &Self::auto_param_order(expr, context), context)
+    }
+
+    /// Free variables of `expr` that are neither known constants nor constants of
+    /// `context`, sorted alphabetically.
+    fn auto_param_order(expr: &Expr, context: Option<&Context>) -> Vec<String> {
         let vars = expr.variables_ordered();
         let mut param_order: Vec<String> = vars
             .into_iter()
             .filter(|v| {
                 let id = symb_interned(v.as_str()).id();
                 !is_known_constant_by_id(id)
+                    && context.is_none_or(|ctx| ctx.constant_value(v).is_none() && !ctx.is_data(v))
             })
             .collect();
 
         param_order.sort();
-        Self::compile(expr, &param_order, context)
+        param_order
     }
 }
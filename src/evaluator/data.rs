@@ -0,0 +1,276 @@
+//! Sums over data series: `sum_data(body)` evaluated against arrays passed at call time.
+//!
+//! Each distinct `sum_data` call is compiled into a program of its own whose parameters
+//! are those of the expression followed by the data series it reads. In the enclosing
+//! program the call becomes a trailing parameter, named after the call, that
+//! [`CompiledEvaluator::evaluate_with_data`] fills by looping over the points.
+
+use std::sync::Arc;
+
+use super::{CompiledEvaluator, EvalOptions};
+use crate::core::ExprKind;
+use crate::core::known_symbols::KS;
+use crate::{Context, DiffError, Expr, Symbol};
+
+/// Programs of the `sum_data` calls of a compiled expression
+pub struct DataSums {
+    /// Names of the data series read by the bodies, in the order of their columns
+    series: Box<[String]>,
+    /// One program per distinct call, over the parameters and then the series
+    bodies: Box<[CompiledEvaluator]>,
+    /// Number of parameters of the expression, which the sums follow
+    param_count: usize,
+    /// Accumulate with Neumaier summation
+    compensated: bool,
+}
+
+/// `DiffError::InvalidDataSeries` for `name`
+fn invalid(name: &str, reason: impl Into<String>) -> DiffError {
+    DiffError::InvalidDataSeries {
+        name: name.to_owned(),
+        reason: reason.into(),
+    }
+}
+
+/// Running sum, optionally with Neumaier's compensation term
+#[derive(Default)]
+struct Accumulator {
+    sum: f64,
+    compensation: f64,
+}
+
+impl Accumulator {
+    fn add(&mut self, value: f64, compensated: bool) {
+        if !compensated {
+            self.sum += value;
+            return;
+        }
+        let total = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - total) + value
+        } else {
+            (value - total) + self.sum
+        };
+        self.sum = total;
+    }
+
+    fn total(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+impl DataSums {
+    /// `params`, padded or cut to the parameter count, followed by every sum over `data`
+    fn point(&self, params: &[f64], data: &[(&str, &[f64])]) -> Result<Vec<f64>, DiffError> {
+        if let Some((name, _)) = data
+            .iter()
+            .find(|(name, _)| !self.series.iter().any(|series| series == name))
+        {
+            return Err(invalid(name, "is not read by this program"));
+        }
+        let columns = self
+            .series
+            .iter()
+            .map(|name| {
+                data.iter()
+                    .find(|(given, _)| given == name)
+                    .map(|&(_, column)| column)
+                    .ok_or_else(|| invalid(name, "was not passed"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let points = columns[0].len();
+        for (name, column) in self.series.iter().zip(&columns).skip(1) {
+            if column.len() != points {
+                return Err(invalid(
+                    name,
+                    format!(
+                        "has {} points, expected {points} like '{}'",
+                        column.len(),
+                        self.series[0]
+                    ),
+                ));
+            }
+        }
+
+        let mut point = params.to_vec();
+        point.resize(self.param_count, 0.0);
+        let mut row = point.clone();
+        row.resize(self.param_count + columns.len(), 0.0);
+        for body in &self.bodies {
+            let mut acc = Accumulator::default();
+            for i in 0..points {
+                for (slot, column) in row[self.param_count..].iter_mut().zip(&columns) {
+                    *slot = column[i];
+                }
+                acc.add(body.evaluate(&row), self.compensated);
+            }
+            point.push(acc.total());
+        }
+        Ok(point)
+    }
+}
+
+impl CompiledEvaluator {
+    /// Compile `exprs`, which call `sum_data` over data series of `context`.
+    ///
+    /// The calls are replaced by trailing parameters and their bodies compiled apart.
+    pub(crate) fn compile_with_data_sums(
+        exprs: &[Expr],
+        param_ids: &[u64],
+        param_names: Vec<String>,
+        context: &Context,
+        options: EvalOptions,
+    ) -> Result<(Self, Vec<u32>), DiffError> {
+        let data = context.data_series();
+        if let Some(series) = data.iter().find(|s| param_ids.contains(&s.id())) {
+            return Err(invalid(
+                &series.name().unwrap_or_default(),
+                "is data and cannot be a parameter",
+            ));
+        }
+
+        let mut calls: Vec<&Expr> = Vec::new();
+        for call in exprs.iter().flat_map(|expr| expr.calls_of(KS.sum_data)) {
+            if !calls.contains(&call) {
+                calls.push(call);
+            }
+        }
+        let mut bodies = Vec::with_capacity(calls.len());
+        for call in &calls {
+            let body = match &call.kind {
+                ExprKind::FunctionCall { args, .. }
+                    if args.len() == 1 && !args[0].contains_function_id(KS.sum_data) =>
+                {
+                    &args[0]
+                }
+                _ => {
+                    return Err(DiffError::UnsupportedExpression(format!(
+                        "{call}: sum_data takes one argument and cannot be nested"
+                    )));
+                }
+            };
+            bodies.push(body);
+        }
+
+        let series: Vec<&Symbol> = data
+            .iter()
+            .filter(|s| bodies.iter().any(|body| body.contains_var_id(s.id())))
+            .collect();
+        if series.is_empty() {
+            return Err(DiffError::UnsupportedExpression(format!(
+                "{}: sum_data reads no data series",
+                calls[0]
+            )));
+        }
+
+        // The enclosing program, with every call as a parameter
+        let anon: Vec<Symbol> = calls.iter().map(|_| Symbol::anon()).collect();
+        let placeholders: Vec<(Expr, Expr)> = calls
+            .iter()
+            .zip(&anon)
+            .map(|(&call, symbol)| (call.clone(), symbol.to_expr()))
+            .collect();
+        let outer: Vec<Expr> = exprs
+            .iter()
+            .map(|expr| expr.substitute_many(&placeholders))
+            .collect();
+        if let Some(stray) = data
+            .iter()
+            .find(|s| outer.iter().any(|expr| expr.contains_var_id(s.id())))
+        {
+            return Err(invalid(
+                &stray.name().unwrap_or_default(),
+                "is only defined inside sum_data",
+            ));
+        }
+        let mut outer_ids = param_ids.to_vec();
+        let mut outer_names = param_names.clone();
+        outer_ids.extend(anon.iter().map(Symbol::id));
+        outer_names.extend(calls.iter().map(ToString::to_string));
+
+        let mut body_ids = param_ids.to_vec();
+        let mut body_names = param_names;
+        for s in &series {
+            body_ids.push(s.id());
+            body_names.push(s.name().unwrap_or_default());
+        }
+        let body_programs = bodies
+            .into_iter()
+            .map(|body| {
+                Self::compile_program(
+                    std::slice::from_ref(&**body),
+                    &body_ids,
+                    body_names.clone(),
+                    Some(context),
+                    options,
+                )
+                .map(|(program, _)| program)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (mut program, outputs) =
+            Self::compile_program(&outer, &outer_ids, outer_names, Some(context), options)?;
+        program.data_sums = Some(Arc::new(DataSums {
+            series: body_names.split_off(param_ids.len()).into_boxed_slice(),
+            bodies: body_programs.into_boxed_slice(),
+            param_count: param_ids.len(),
+            compensated: options.compensated_sums,
+        }));
+        Ok((program, outputs))
+    }
+
+    /// Evaluate at `params` with the data series of `sum_data` taken from `data`.
+    ///
+    /// `data` pairs each series declared with
+    /// [`Context::declare_data`](crate::Context::declare_data) with its values; every
+    /// series the program reads must be passed, all with the same number of points.
+    /// Each `sum_data` call of the expression is a trailing parameter of the program,
+    /// so [`evaluate`](Self::evaluate) without data takes it as `0`, the sum over no
+    /// points.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, Context, parse};
+    /// use std::collections::HashSet;
+    ///
+    /// let ctx = Context::new();
+    /// ctx.declare_data("t");
+    /// ctx.declare_data("obs");
+    /// let residuals = parse("sum_data((obs - a*t)^2)", &HashSet::new(), &HashSet::new(), Some(&ctx))
+    ///     .unwrap();
+    /// let eval = CompiledEvaluator::compile(&residuals, &[ctx.symb("a")], Some(&ctx)).unwrap();
+    ///
+    /// let (t, obs) = ([1.0, 2.0], [2.0, 5.0]);
+    /// let value = eval.evaluate_with_data(&[2.0], &[("t", &t), ("obs", &obs)]).unwrap();
+    /// assert_eq!(value, 1.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiffError::InvalidDataSeries` if a series the program reads is missing,
+    /// has a different number of points than the others, or if `data` names a series
+    /// the program does not read.
+    pub fn evaluate_with_data(
+        &self,
+        params: &[f64],
+        data: &[(&str, &[f64])],
+    ) -> Result<f64, DiffError> {
+        let point = self.point_with_data(params, data)?;
+        Ok(self.evaluate(&point))
+    }
+
+    /// `params` extended by the value of every data sum
+    pub(crate) fn point_with_data(
+        &self,
+        params: &[f64],
+        data: &[(&str, &[f64])],
+    ) -> Result<Vec<f64>, DiffError> {
+        if let Some(sums) = &self.data_sums {
+            return sums.point(params, data);
+        }
+        data.first().map_or_else(
+            || Ok(params.to_vec()),
+            |(name, _)| Err(invalid(name, "is not read by this program")),
+        )
+    }
+}
@@ -0,0 +1,199 @@
+//! Compiled gradients: every partial derivative of an expression in one bytecode program.
+//!
+//! Partials of the same expression repeat most of their work (`exp(x*y)` appears in both
+//! `∂/∂x` and `∂/∂y` of `exp(x*y)`). Compiling them together lets the AST-level and VIR
+//! value numbering passes compute each shared subexpression once for all components.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use super::{CompiledEvaluator, EvalOptions, ToParamName};
+use crate::{Context, Diff, DiffError, Expr, Symbol};
+
+/// Compiled gradient of an expression - thread-safe, reusable.
+///
+/// Component `i` is the partial derivative with respect to parameter `i`, and all
+/// components come from the same bytecode program.
+///
+/// # Example
+///
+/// ```
+/// use symb_anafis::{symb, CompiledGradient};
+///
+/// let x = symb("grad_doc_x");
+/// let y = symb("grad_doc_y");
+/// let expr = (x * y).exp();
+///
+/// let gradient = CompiledGradient::compile(&expr, &[&x, &y], None).expect("Should compile");
+/// let mut out = [0.0; 2];
+/// gradient.evaluate(&[1.0, 2.0], &mut out);
+/// assert!((out[0] - 2.0 * 2.0_f64.exp()).abs() < 1e-12);
+/// assert!((out[1] - 2.0_f64.exp()).abs() < 1e-12);
+/// ```
+#[derive(Clone)]
+pub struct CompiledGradient {
+    /// Shared program; its `result_reg` is only the first component
+    program: CompiledEvaluator,
+    /// Register holding each component, in parameter order
+    output_regs: Box<[u32]>,
+}
+
+impl CompiledGradient {
+    /// Differentiate `expr` with respect to every parameter and compile the partials.
+    ///
+    /// * `params` — Parameters in evaluation order, which is also the component order.
+    ///   Accepts `&[&str]` or `&[&Symbol]`.
+    /// * `context` — Optional context for custom function definitions, used both for
+    ///   differentiation and compilation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiffError` if differentiation fails, or for the same reasons as
+    /// [`CompiledEvaluator::compile`].
+    pub fn compile<P: ToParamName>(
+        expr: &Expr,
+        params: &[P],
+        context: Option<&Context>,
+    ) -> Result<Self, DiffError> {
+        let (param_ids, param_names): (Vec<u64>, Vec<String>) =
+            params.iter().map(ToParamName::to_param_id_and_name).unzip();
+
+        let diff = context.map_or_else(Diff::new, |ctx| Diff::new().context(ctx));
+        let components = param_ids
+            .iter()
+            .map(|&id| {
+                let partial = diff.differentiate(expr, &Symbol::from_id(id))?;
+                CompiledEvaluator::prepare_expr(
+                    &partial,
+                    &param_ids,
+                    context,
+                    EvalOptions::default(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (program, output_regs) = CompiledEvaluator::compile_program(
+            &components,
+            &param_ids,
+            param_names,
+            context,
+            EvalOptions::default(),
+        )?;
+        Ok(Self {
+            program,
+            output_regs: output_regs.into_boxed_slice(),
+        })
+    }
+
+    /// Get the parameter names in order.
+    #[inline]
+    #[must_use]
+    pub fn param_names(&self) -> &[String] {
+        &self.program.param_names()[..self.output_regs.len()]
+    }
+
+    /// Get the number of parameters, which is also the number of components.
+    ///
+    /// Data sums, which the program takes after the parameters, are not counted.
+    #[inline]
+    #[must_use]
+    pub fn param_count(&self) -> usize {
+        self.output_regs.len()
+    }
+
+    /// Get the number of compiled instructions shared by all components.
+    #[inline]
+    #[must_use]
+    pub fn instruction_count(&self) -> usize {
+        self.program.instruction_count()
+    }
+
+    /// Evaluate the gradient at a single point, writing component `i` to `out[i]`.
+    ///
+    /// Missing parameters default to `0.0`, as in [`CompiledEvaluator::evaluate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than [`param_count`](Self::param_count).
+    #[inline]
+    pub fn evaluate(&self, point: &[f64], out: &mut [f64]) {
+        assert!(
+            out.len() >= self.output_regs.len(),
+            "gradient output has {} slots, expected {}",
+            out.len(),
+            self.output_regs.len()
+        );
+        self.program.evaluate_outputs(point, &self.output_regs, out);
+    }
+
+    /// Evaluate the gradient at `params` with the data series of `sum_data` taken
+    /// from `data`, writing component `i` to `out[i]`.
+    ///
+    /// See [`CompiledEvaluator::evaluate_with_data`] for how `data` is matched.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`CompiledEvaluator::evaluate_with_data`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than [`param_count`](Self::param_count).
+    pub fn evaluate_with_data(
+        &self,
+        params: &[f64],
+        data: &[(&str, &[f64])],
+        out: &mut [f64],
+    ) -> Result<(), DiffError> {
+        let point = self.program.point_with_data(params, data)?;
+        self.evaluate(&point, out);
+        Ok(())
+    }
+
+    /// Evaluate the gradient at many points.
+    ///
+    /// `out` is row-major: the gradient at `points[i]` fills
+    /// `out[i * n..(i + 1) * n]` with `n` = [`param_count`](Self::param_count). With the
+    /// `parallel` feature, points are evaluated four at a time on the SIMD engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiffError` if `out.len()` is not `points.len() * param_count()`.
+    pub fn eval_batch(&self, points: &[&[f64]], out: &mut [f64]) -> Result<(), DiffError> {
+        let width = self.output_regs.len();
+        if out.len() != points.len() * width {
+            return Err(DiffError::invalid_syntax(format!(
+                "gradient batch output has {} slots, expected {} points x {} components",
+                out.len(),
+                points.len(),
+                width
+            )));
+        }
+        if width == 0 {
+            return Ok(());
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            let mut workspace = vec![wide::f64x4::splat(0.0); self.program.workspace_size()];
+            self.program
+                .eval_points_simd(points, &self.output_regs, out, &mut workspace);
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for (point, row) in points.iter().zip(out.chunks_exact_mut(width)) {
+            self.program.evaluate_outputs(point, &self.output_regs, row);
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for CompiledGradient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("CompiledGradient")
+            .field("param_names", &self.program.param_names())
+            .field("instruction_count", &self.program.instruction_count())
+            .field("workspace_size", &self.program.workspace_size())
+            .field("output_regs", &self.output_regs)
+            .finish()
+    }
+}
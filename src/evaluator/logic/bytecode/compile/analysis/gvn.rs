@@ -11,10 +11,11 @@ use crate::EPSILON;
 use crate::core::Expr;
 use crate::evaluator::FnOp;
 use crate::math::{
-    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_digamma,
-    eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc, eval_exp_polar, eval_gamma,
-    eval_hermite, eval_lambert_w, eval_lgamma, eval_polygamma, eval_spherical_harmonic,
-    eval_tetragamma, eval_trigamma, eval_zeta, eval_zeta_deriv,
+    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_clamp,
+    eval_digamma, eval_dirac, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc,
+    eval_exp_polar, eval_gamma, eval_heaviside, eval_hermite, eval_lambert_w, eval_lgamma,
+    eval_max, eval_min, eval_polygamma, eval_select, eval_spherical_harmonic, eval_tetragamma,
+    eval_trigamma, eval_zeta, eval_zeta_deriv,
 };
 
 /// Key for the AST-level GVN cache used during VIR generation.
@@ -90,7 +91,7 @@ impl Hash for GvnKey {
 )]
 pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
     vinstrs: &mut Vec<VInstruction>,
-    final_vreg: &mut Option<VReg>,
+    outputs: &mut [VReg],
     constants: &mut Vec<f64>,
     const_map: &mut FxHashMap<u64, u32>,
     param_count: u32,
@@ -346,6 +347,8 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                         FnOp::Ceil => Some(v.ceil()),
                         FnOp::Round => Some(v.round()),
                         FnOp::Signum => Some(v.signum()),
+                        FnOp::Heaviside => Some(eval_heaviside(v)),
+                        FnOp::Dirac => Some(eval_dirac(v)),
                         FnOp::Cot => Some(1.0 / v.tan()),
                         FnOp::Sec => Some(1.0 / v.cos()),
                         FnOp::Csc => Some(1.0 / v.sin()),
@@ -378,6 +381,7 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                         // We must match them here to satisfy Rust's exhaustive pattern matching rules,
                         // even though they should realistically never appear inside a Builtin1 instruction.
                         FnOp::Atan2
+                        | FnOp::Hypot
                         | FnOp::Log
                         | FnOp::BesselJ
                         | FnOp::BesselY
@@ -387,8 +391,17 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                         | FnOp::Beta
                         | FnOp::ZetaDeriv
                         | FnOp::Hermite
+                        | FnOp::Min
+                        | FnOp::Max
                         | FnOp::AssocLegendre
-                        | FnOp::SphericalHarmonic => None,
+                        | FnOp::Clamp
+                        | FnOp::Select
+                        | FnOp::SphericalHarmonic
+                        | FnOp::Less
+                        | FnOp::LessEq
+                        | FnOp::Greater
+                        | FnOp::GreaterEq
+                        | FnOp::Equal => None,
                     };
                     result.map(|val| emplace_const!(val))
                 })
@@ -399,8 +412,16 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                 {
                     let result = match *op {
                         FnOp::Atan2 => Some(v1.atan2(v2)),
+                        FnOp::Hypot => Some(v1.hypot(v2)),
                         FnOp::Log => Some(v2.log(v1)),
                         FnOp::Beta => Some(eval_beta(v1, v2)),
+                        FnOp::Min => Some(eval_min(v1, v2)),
+                        FnOp::Max => Some(eval_max(v1, v2)),
+                        op @ (FnOp::Less
+                        | FnOp::LessEq
+                        | FnOp::Greater
+                        | FnOp::GreaterEq
+                        | FnOp::Equal) => op.eval_compare(v1, v2),
                         op @ (FnOp::BesselJ
                         | FnOp::BesselY
                         | FnOp::BesselI
@@ -468,6 +489,8 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                         | FnOp::Cbrt
                         | FnOp::Abs
                         | FnOp::Signum
+                        | FnOp::Heaviside
+                        | FnOp::Dirac
                         | FnOp::Floor
                         | FnOp::Ceil
                         | FnOp::Round
@@ -485,6 +508,8 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                         | FnOp::Zeta
                         | FnOp::ExpPolar
                         | FnOp::AssocLegendre
+                        | FnOp::Clamp
+                        | FnOp::Select
                         | FnOp::SphericalHarmonic => None,
                     };
                     result.map(|val| emplace_const!(val))
@@ -492,6 +517,15 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                     None
                 }
             }
+            VInstruction::BuiltinFun {
+                op: FnOp::Select,
+                args,
+                ..
+            } if get_const_val(args[0], &pool).is_some_and(|cond| !cond.is_nan()) => {
+                // A known condition picks its branch outright
+                let picks_first = get_const_val(args[0], &pool).is_some_and(|cond| cond != 0.0);
+                Some(if picks_first { args[1] } else { args[2] })
+            }
             VInstruction::BuiltinFun { op, args, .. } => {
                 let mut all_consts = true;
                 let mut c_args = Vec::with_capacity(args.len());
@@ -521,6 +555,8 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                                 eval_assoc_legendre(lr as i32, mr as i32, x)
                             })
                         }
+                        (FnOp::Clamp, &[x, lo, hi]) => Some(eval_clamp(x, lo, hi)),
+                        (FnOp::Select, &[cond, a, b]) => Some(eval_select(cond, a, b)),
                         (FnOp::SphericalHarmonic, &[l, m, theta, phi]) => {
                             let lr = l.round();
                             let mr = m.round();
@@ -543,6 +579,8 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
                     None
                 }
             }
+            // Callbacks are only available at evaluation time
+            VInstruction::CallExternal { .. } => None,
             VInstruction::MulAdd { a, b, c, .. } => {
                 if let (Some(va), Some(vb), Some(vc)) = (
                     get_const_val(*a, &pool),
@@ -609,7 +647,7 @@ pub(in crate::evaluator::logic::bytecode::compile) fn optimize_vir_gvn(
     }
     *vinstrs = optimized;
 
-    if let Some(f) = final_vreg {
+    for f in outputs {
         while let Some(&canonical) = alias.get(f) {
             *f = canonical;
         }
@@ -5,7 +5,7 @@ use super::vir::{VInstruction, VReg}; // Assuming vir is in the same parent modu
 /// Returns the optimized instruction stream and the maximum temporary register index + 1.
 pub fn eliminate_vir_dead_code(
     vinstrs: Vec<VInstruction>,
-    final_vreg: Option<VReg>,
+    outputs: &[VReg],
     next_vreg: u32,
 ) -> (Vec<VInstruction>, usize) {
     if vinstrs.is_empty() {
@@ -14,8 +14,10 @@ pub fn eliminate_vir_dead_code(
 
     // Temp IDs are dense u32s — Vec<bool> is O(1) lookup with no hashing.
     let mut live = vec![false; next_vreg as usize];
-    if let Some(VReg::Temp(t)) = final_vreg {
-        live[t as usize] = true;
+    for &output in outputs {
+        if let VReg::Temp(t) = output {
+            live[t as usize] = true;
+        }
     }
 
     let mut optimized = Vec::with_capacity(vinstrs.len());
@@ -45,8 +47,10 @@ pub fn eliminate_vir_dead_code(
     }
     optimized.reverse();
 
-    if let Some(VReg::Temp(t)) = final_vreg {
-        max_temp = max_temp.max(t + 1);
+    for &output in outputs {
+        if let VReg::Temp(t) = output {
+            max_temp = max_temp.max(t + 1);
+        }
     }
 
     (optimized, max_temp as usize)
@@ -1,5 +1,5 @@
 use crate::core::Context;
-use crate::core::{Expr, ExprKind, InternedSymbol};
+use crate::core::{Expr, ExprKind, InternedSymbol, map_piecewise};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 
@@ -17,6 +17,22 @@ pub fn expand_user_functions(expr: &Expr, ctx: &Context) -> Expr {
     .0
 }
 
+/// Replaces the constants registered in `ctx` by their values, except those that are
+/// passed as parameters (`param_ids`).
+pub fn substitute_constants(expr: &Expr, ctx: &Context, param_ids: &[u64]) -> Expr {
+    let values: Vec<(Expr, Expr)> = ctx
+        .constant_values()
+        .into_iter()
+        .filter(|(symbol, _)| {
+            !matches!(&symbol.kind, ExprKind::Symbol(s) if param_ids.contains(&s.id()))
+        })
+        .collect();
+    if values.is_empty() {
+        return expr.clone();
+    }
+    expr.substitute_many(&values)
+}
+
 /// Expands user-defined functions in an expression tree.
 ///
 /// Returns `(expanded_expr, is_pure)`, where `is_pure` is true if all user functions
@@ -105,6 +121,12 @@ fn expand_user_functions_impl(
             is_subtree_pure &= pure;
             Expr::derivative_interned(expanded_inner, var.clone(), *order)
         }
+        ExprKind::Piecewise { branches, default } => map_piecewise(branches, default, |part| {
+            let (expanded, pure) =
+                expand_user_functions_impl(part, ctx, expanding, cache, depth + 1);
+            is_subtree_pure &= pure;
+            Arc::new(expanded)
+        }),
     };
 
     cache.insert(expr.id, (res.clone(), is_subtree_pure));
@@ -26,6 +26,9 @@ impl VirGenerator {
                 self.compile_function_node(name, args, node_map)
             }
             ExprKind::Poly(poly) => self.compile_poly_node(poly, node_map),
+            ExprKind::Piecewise { branches, default } => {
+                self.compile_piecewise_node(branches, default, node_map)
+            }
             ExprKind::Derivative { .. } => Err(DiffError::UnsupportedExpression(
                 "Derivatives cannot be numerically evaluated - simplify first".to_owned(),
             )),
@@ -8,6 +8,8 @@ use crate::core::InternedSymbol;
 use crate::core::known_symbols::KS;
 use crate::core::{DiffError, Expr};
 use rustc_hash::FxHashMap;
+use std::collections::hash_map::Entry;
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 impl VirGenerator {
@@ -45,7 +47,7 @@ impl VirGenerator {
         if let Some(&op) = FN_MAP.get(&id) {
             let accepts_arity = (id == ks.log && args.len() == 1) || op.arity() == args.len();
             if !accepts_arity {
-                return Err(DiffError::UnsupportedFunction(name.as_str().to_owned()));
+                return Err(DiffError::UnsupportedFunction(name.clone()));
             }
 
             let dest = self.alloc_vreg();
@@ -84,6 +86,12 @@ impl VirGenerator {
             return Ok(dest);
         }
 
+        if args.len() == 1
+            && let Some(dest) = self.try_compile_degree_trig(id, args[0].as_ref(), node_map)?
+        {
+            return Ok(dest);
+        }
+
         if args.len() == 1 {
             let base_val = if id == ks.log2 {
                 Some(2.0)
@@ -106,6 +114,88 @@ impl VirGenerator {
             }
         }
 
-        Err(DiffError::UnsupportedFunction(name.as_str().to_owned()))
+        if self
+            .numeric_fns
+            .get(&id)
+            .is_some_and(|(arity, _)| arity.contains(&args.len()))
+        {
+            return self.compile_external_call(id, args, node_map);
+        }
+
+        Err(DiffError::UnsupportedFunction(name.clone()))
+    }
+
+    /// Lowers `sind`, `cosd` and `tand` to the radian builtin of the scaled argument, and
+    /// `asind`, `acosd` and `atand` to the scaled result of the radian builtin.
+    fn try_compile_degree_trig(
+        &mut self,
+        id: u64,
+        arg: &Expr,
+        node_map: &FxHashMap<*const Expr, NodeData>,
+    ) -> Result<Option<VReg>, DiffError> {
+        let ks = &*KS;
+        let (op, scales_argument) = match id {
+            _ if id == ks.sind => (FnOp::Sin, true),
+            _ if id == ks.cosd => (FnOp::Cos, true),
+            _ if id == ks.tand => (FnOp::Tan, true),
+            _ if id == ks.asind => (FnOp::Asin, false),
+            _ if id == ks.acosd => (FnOp::Acos, false),
+            _ if id == ks.atand => (FnOp::Atan, false),
+            _ => return Ok(None),
+        };
+        let arg = Self::vreg_from_map(node_map, arg)?;
+        let (scaled, dest) = (self.alloc_vreg(), self.alloc_vreg());
+        if scales_argument {
+            let factor = VReg::Const(self.add_const(PI / 180.0));
+            self.emit(VInstruction::Mul2 {
+                dest: scaled,
+                a: arg,
+                b: factor,
+            });
+            self.emit(VInstruction::Builtin1 {
+                dest,
+                op,
+                arg: scaled,
+            });
+        } else {
+            let factor = VReg::Const(self.add_const(180.0 / PI));
+            self.emit(VInstruction::Builtin1 {
+                dest: scaled,
+                op,
+                arg,
+            });
+            self.emit(VInstruction::Mul2 {
+                dest,
+                a: scaled,
+                b: factor,
+            });
+        }
+        Ok(Some(dest))
+    }
+
+    /// Lowers a call of a user function with a native implementation to `CallExternal`.
+    fn compile_external_call(
+        &mut self,
+        id: u64,
+        args: &[Arc<Expr>],
+        node_map: &FxHashMap<*const Expr, NodeData>,
+    ) -> Result<VReg, DiffError> {
+        let callback = match self.external_index.entry(id) {
+            Entry::Occupied(o) => *o.get(),
+            Entry::Vacant(v) => {
+                let idx = u32::try_from(self.externals.len())
+                    .expect("more than u32::MAX native callbacks");
+                self.externals.push(Arc::clone(&self.numeric_fns[&id].1));
+                *v.insert(idx)
+            }
+        };
+        let args = Self::map_args_vregs(args, node_map)?;
+        let dest = self.alloc_vreg();
+        self.emit(VInstruction::CallExternal {
+            dest,
+            callback,
+            args,
+        });
+        Ok(dest)
     }
 }
@@ -1,12 +1,13 @@
+use super::FnOp;
 use super::VirGenerator;
 use super::analysis::GvnKey;
-use super::vir::VReg;
 use super::vir::node::NodeData;
+use super::vir::{VInstruction, VReg};
 use crate::core::InternedSymbol;
 use crate::core::Polynomial;
 use crate::core::error::DiffError;
 use crate::core::known_symbols::get_constant_value_by_id;
-use crate::core::{Expr, ExprKind};
+use crate::core::{Condition, Expr, ExprKind, piecewise_parts};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
@@ -21,7 +22,7 @@ impl VirGenerator {
             let idx = self.add_const(val);
             Ok(VReg::Const(idx))
         } else {
-            Err(DiffError::UnboundVariable(sym.as_str().to_owned()))
+            Err(DiffError::UnboundVariable(sym.clone()))
         }
     }
 
@@ -45,6 +46,36 @@ impl VirGenerator {
         Ok(self.compile_polynomial_with_base(poly, base_v))
     }
 
+    /// Lower a piecewise node to a chain of selects, from the last branch back
+    ///
+    /// Every condition and value is computed, then each branch picks its value or
+    /// the result of the branches after it, so no lane ever branches.
+    pub(super) fn compile_piecewise_node(
+        &mut self,
+        branches: &[(Condition, Arc<Expr>)],
+        default: &Expr,
+        node_map: &FxHashMap<*const Expr, NodeData>,
+    ) -> Result<VReg, DiffError> {
+        let mut result = Self::vreg_from_map(node_map, default)?;
+        for (condition, value) in branches.iter().rev() {
+            let cond = self.alloc_vreg();
+            self.emit(VInstruction::Builtin2 {
+                dest: cond,
+                op: FnOp::compare(condition.op),
+                arg1: Self::vreg_from_map(node_map, condition.lhs.as_ref())?,
+                arg2: Self::vreg_from_map(node_map, condition.rhs.as_ref())?,
+            });
+            let dest = self.alloc_vreg();
+            self.emit(VInstruction::BuiltinFun {
+                dest,
+                op: FnOp::Select,
+                args: vec![cond, Self::vreg_from_map(node_map, value.as_ref())?, result],
+            });
+            result = dest;
+        }
+        Ok(result)
+    }
+
     pub(in crate::evaluator::logic::bytecode::compile) fn lookup_cse(
         &self,
         expr: &Expr,
@@ -81,6 +112,11 @@ impl VirGenerator {
             ExprKind::Derivative { inner, .. } => {
                 stack.push((Arc::as_ptr(inner), false));
             }
+            ExprKind::Piecewise { branches, default } => {
+                for part in piecewise_parts(branches, default).rev() {
+                    stack.push((Arc::as_ptr(part), false));
+                }
+            }
             ExprKind::Number(_) | ExprKind::Symbol(_) => {}
         }
     }
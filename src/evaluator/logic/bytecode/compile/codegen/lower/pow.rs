@@ -18,46 +18,47 @@ impl VirGenerator {
     ) -> VReg {
         let terms = poly.terms();
         let degree = terms.last().map_or(0, |t| t.0);
+        let mut coeffs = vec![0.0; (degree + 1) as usize];
+        for &(p, c) in terms {
+            coeffs[p as usize] = c;
+        }
+        self.compile_dense_polynomial(&coeffs, base_vreg)
+    }
 
-        if degree >= 4 {
-            let mut coeffs = vec![0.0; (degree + 1) as usize];
-            for &(p, c) in terms {
-                coeffs[p as usize] = c;
-            }
+    /// Evaluates `coeffs[0] + coeffs[1]*x + ...`: Estrin's scheme from degree 4 on,
+    /// Horner's below.
+    pub(super) fn compile_dense_polynomial(&mut self, coeffs: &[f64], x: VReg) -> VReg {
+        if coeffs.len() > 4 {
             let mut powers = FxHashMap::default();
-            powers.insert(1, base_vreg);
-            return self.compile_poly_estrin(&coeffs, base_vreg, &mut powers);
+            powers.insert(1, x);
+            return self.compile_poly_estrin(coeffs, x, &mut powers);
         }
+        self.emit_horner(coeffs, x)
+    }
 
-        let mut term_idx = terms.len();
-        let mut current_vreg = VReg::Const(self.add_const(0.0));
-        let mut first = true;
-
-        for current_pow in (0..=degree).rev() {
-            let coeff = if term_idx > 0 && terms[term_idx - 1].0 == current_pow {
-                term_idx -= 1;
-                terms[term_idx].1
-            } else {
-                0.0
-            };
-
-            if first {
-                let idx = self.add_const(coeff);
-                current_vreg = VReg::Const(idx);
-                first = false;
+    /// Emits `(..(c[n]*x + c[n-1])*x + ..)*x + c[0]`, one `MulAdd` per degree (a plain
+    /// `Mul` where the coefficient is zero).
+    pub(super) fn emit_horner(&mut self, coeffs: &[f64], x: VReg) -> VReg {
+        let Some((&leading, lower)) = coeffs.split_last() else {
+            return VReg::Const(self.add_const(0.0));
+        };
+        let mut acc = VReg::Const(self.add_const(leading));
+        for &coeff in lower.iter().rev() {
+            let dest = self.alloc_vreg();
+            if coeff == 0.0 {
+                self.emit(VInstruction::Mul2 { dest, a: acc, b: x });
             } else {
-                let next_vreg = self.alloc_vreg();
-                let c_idx = self.add_const(coeff);
+                let c = VReg::Const(self.add_const(coeff));
                 self.emit(VInstruction::MulAdd {
-                    dest: next_vreg,
-                    a: current_vreg,
-                    b: base_vreg,
-                    c: VReg::Const(c_idx),
+                    dest,
+                    a: acc,
+                    b: x,
+                    c,
                 });
-                current_vreg = next_vreg;
             }
+            acc = dest;
         }
-        current_vreg
+        acc
     }
 
     pub(super) fn compile_poly_estrin(
@@ -4,6 +4,7 @@ use super::vir::node::{NodeData, const_from_map};
 use super::vir::{VInstruction, VReg};
 use crate::EPSILON;
 use crate::core::error::DiffError;
+use crate::core::traits::is_zero;
 use crate::core::{Expr, ExprKind};
 use rustc_hash::FxHashMap;
 use std::ptr::from_ref;
@@ -18,6 +19,96 @@ fn try_both_orderings<T>(
     f(a, b).or_else(|| f(b, a))
 }
 
+/// A sum of numeric multiples of powers of a single base, as `(base, [c0, c1, ..])`
+///
+/// `None` unless at least two positive powers occur and the degree is below twice the
+/// number of distinct powers, so that one multiply-add per degree beats the
+/// term-by-term evaluation.
+fn single_base_polynomial<'expr>(
+    terms: &'expr [Arc<Expr>],
+    node_map: &FxHashMap<*const Expr, NodeData>,
+) -> Option<(&'expr Expr, Vec<f64>)> {
+    let mut base = None;
+    let mut monomials = Vec::with_capacity(terms.len());
+    for term in terms {
+        push_monomials(term, node_map, &mut base, &mut monomials)?;
+    }
+    let base = base?;
+
+    let mut powers: Vec<u32> = monomials.iter().map(|&(pow, _)| pow).collect();
+    powers.sort_unstable();
+    powers.dedup();
+    let degree = *powers.last()?;
+    let positive = powers.iter().filter(|&&pow| pow > 0).count();
+    if positive < 2 || degree as usize >= 2 * powers.len() {
+        return None;
+    }
+
+    let mut coeffs = vec![0.0; degree as usize + 1];
+    for (pow, coeff) in monomials {
+        coeffs[pow as usize] += coeff;
+    }
+    Some((base, coeffs))
+}
+
+/// Appends the `(power, coefficient)` pairs of `term`, failing if its base differs from
+/// the one already seen
+fn push_monomials<'expr>(
+    term: &'expr Expr,
+    node_map: &FxHashMap<*const Expr, NodeData>,
+    base: &mut Option<&'expr Expr>,
+    out: &mut Vec<(u32, f64)>,
+) -> Option<()> {
+    if let Some(value) = const_from_map(node_map, term) {
+        out.push((0, value));
+        return Some(());
+    }
+    let (coeff, power) = match &term.kind {
+        ExprKind::Poly(poly) => {
+            same_base(base, poly.base())?;
+            out.extend_from_slice(poly.terms());
+            return Some(());
+        }
+        ExprKind::Product(factors) if factors.len() == 2 => {
+            match (
+                const_from_map(node_map, &factors[0]),
+                const_from_map(node_map, &factors[1]),
+            ) {
+                (Some(c), None) => (c, factors[1].as_ref()),
+                (None, Some(c)) => (c, factors[0].as_ref()),
+                _ => (1.0, term),
+            }
+        }
+        _ => (1.0, term),
+    };
+    let (monomial_base, pow) = match &power.kind {
+        ExprKind::Pow(b, exp) => match const_from_map(node_map, exp) {
+            Some(n) if n >= 1.0 && n.fract() == 0.0 && n <= f64::from(u32::MAX) =>
+            {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    reason = "Checked to be a positive integer within u32 range"
+                )]
+                (b.as_ref(), n as u32)
+            }
+            _ => (power, 1),
+        },
+        _ => (power, 1),
+    };
+    same_base(base, monomial_base)?;
+    out.push((pow, coeff));
+    Some(())
+}
+
+fn same_base<'expr>(base: &mut Option<&'expr Expr>, candidate: &'expr Expr) -> Option<()> {
+    if let Some(seen) = base {
+        return (*seen == candidate).then_some(());
+    }
+    *base = Some(candidate);
+    Some(())
+}
+
 impl VirGenerator {
     pub(super) fn vreg_from_map(
         node_map: &FxHashMap<*const Expr, NodeData>,
@@ -86,10 +177,10 @@ impl VirGenerator {
                     return Some(Ok(VReg::Const(idx)));
                 }
             }
-            (Some(v0), None) if v0.is_finite() && v0.abs() < EPSILON => {
+            (Some(v0), None) if is_zero(v0) => {
                 return Some(Self::vreg_from_map(node_map, t1));
             }
-            (None, Some(v1)) if v1.is_finite() && v1.abs() < EPSILON => {
+            (None, Some(v1)) if is_zero(v1) => {
                 return Some(Self::vreg_from_map(node_map, t0));
             }
             _ => {}
@@ -174,6 +265,11 @@ impl VirGenerator {
             return Self::vreg_from_map(node_map, terms[0].as_ref());
         }
 
+        if let Some((base, coeffs)) = single_base_polynomial(terms, node_map) {
+            let x = Self::vreg_from_map(node_map, base)?;
+            return Ok(self.compile_dense_polynomial(&coeffs, x));
+        }
+
         // --- 2-term fast path ---
         if terms.len() == 2 {
             let t0 = terms[0].as_ref();
@@ -243,7 +339,7 @@ impl VirGenerator {
         neg_vregs: &mut Vec<VReg>,
     ) {
         if constant_acc.is_finite() {
-            if constant_acc.abs() > EPSILON {
+            if !is_zero(constant_acc) {
                 if constant_acc > 0.0 {
                     let idx = self.add_const(constant_acc);
                     pos_vregs.push(VReg::Const(idx));
@@ -2,6 +2,6 @@ pub mod expand;
 pub mod lower;
 pub mod traverse;
 
-pub use expand::expand_user_functions;
+pub use expand::{expand_user_functions, substitute_constants};
 
 pub use super::{FnOp, VirGenerator, analysis, vir};
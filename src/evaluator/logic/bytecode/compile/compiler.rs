@@ -12,10 +12,11 @@ use super::analysis::{
 use super::emit::RegAllocator;
 use super::optimize::schedule::greedy_schedule;
 use super::vir::{VInstruction, VReg};
-use crate::core::Expr;
 use crate::core::error::DiffError;
+use crate::core::{Context, Expr, NumericFn};
 use rustc_hash::FxHashMap;
 use std::collections::hash_map::Entry;
+use std::ops::RangeInclusive;
 
 pub struct VirGenerator {
     pub(super) vinstrs: Vec<VInstruction>,
@@ -25,7 +26,14 @@ pub struct VirGenerator {
     pub(super) constants: Vec<f64>,
     pub(super) const_map: FxHashMap<u64, u32>,
     pub(super) next_vreg: u32,
-    pub(super) final_vreg: Option<VReg>,
+    /// Registers holding the compiled results, one per [`compile_expr`](Self::compile_expr) call
+    pub(super) outputs: Vec<VReg>,
+    /// Arity and native implementation of the user functions that may be called
+    pub(super) numeric_fns: FxHashMap<u64, (RangeInclusive<usize>, NumericFn)>,
+    /// Callbacks referenced by `CallExternal`, in order of first use
+    pub(super) externals: Vec<NumericFn>,
+    /// Index in `externals` of each function called so far
+    pub(super) external_index: FxHashMap<u64, u32>,
 }
 
 impl VirGenerator {
@@ -43,13 +51,27 @@ impl VirGenerator {
             constants: Vec::new(),
             const_map: FxHashMap::default(),
             next_vreg: 0,
-            final_vreg: None,
+            outputs: Vec::new(),
+            numeric_fns: FxHashMap::default(),
+            externals: Vec::new(),
+            external_index: FxHashMap::default(),
         };
         // Pre-add 0.0 so it's always available (e.g. for empty expressions)
         compiler.add_const(0.0);
         compiler
     }
 
+    /// Let calls of the functions of `ctx` that have a native implementation compile
+    /// to `CallExternal`.
+    pub(crate) fn register_numeric_functions(&mut self, ctx: &Context) {
+        self.numeric_fns = ctx.numeric_functions();
+    }
+
+    /// Callbacks referenced by the compiled `CallExternal` instructions, by index.
+    pub(crate) fn take_externals(&mut self) -> Vec<NumericFn> {
+        std::mem::take(&mut self.externals)
+    }
+
     #[inline]
     pub(super) const fn alloc_vreg(&mut self) -> VReg {
         let r = self.next_vreg;
@@ -90,12 +112,12 @@ impl VirGenerator {
         Vec<u32>,
         usize,
         usize,
-        u32,
+        Vec<u32>,
     ) {
         let param_count = u32::try_from(self.param_ids.len()).expect("Param count too large");
         optimize_vir_gvn(
             &mut self.vinstrs,
-            &mut self.final_vreg,
+            &mut self.outputs,
             &mut self.constants,
             &mut self.const_map,
             param_count,
@@ -111,17 +133,12 @@ impl VirGenerator {
 
         // VIR Backward Dead Code Elimination
         let (vinstrs, num_temps) =
-            eliminate_vir_dead_code(self.vinstrs, self.final_vreg, self.next_vreg);
+            eliminate_vir_dead_code(self.vinstrs, &self.outputs, self.next_vreg);
 
-        let allocator = RegAllocator::new(
-            param_count,
-            const_count,
-            num_temps,
-            &vinstrs,
-            self.final_vreg,
-        );
-        let (instructions, arg_pool, max_phys, result_reg) =
-            allocator.allocate(vinstrs, self.final_vreg);
+        let allocator =
+            RegAllocator::new(param_count, const_count, num_temps, &vinstrs, &self.outputs);
+        let (instructions, arg_pool, max_phys, output_regs) =
+            allocator.allocate(vinstrs, &self.outputs);
 
         (
             instructions,
@@ -130,10 +147,15 @@ impl VirGenerator {
             arg_pool,
             param_count as usize,
             max_phys,
-            result_reg,
+            output_regs,
         )
     }
 
+    /// Compiles `expr` and adds its value as the next output of the program.
+    ///
+    /// Compiling several expressions into one generator shares their common
+    /// subexpressions. The AST-level GVN cache keeps pointers into every tree compiled
+    /// so far, so all of them must outlive the generator.
     pub(crate) fn compile_expr(&mut self, expr: &Expr) -> Result<VReg, DiffError> {
         let node_count = expr.node_count();
         self.vinstrs.reserve(node_count);
@@ -150,7 +172,7 @@ impl VirGenerator {
         )]
         self.gvn_cache.reserve(node_count / 8);
         let vreg = self.compile_expr_iterative(expr, node_count)?;
-        self.final_vreg = Some(vreg);
+        self.outputs.push(vreg);
         Ok(vreg)
     }
 }
@@ -6,6 +6,10 @@ use super::Instruction;
     clippy::cast_sign_loss,
     reason = "Two's complement bit pattern for i32 is safely preserved in u32 flat bytecode"
 )]
+#[allow(
+    clippy::too_many_lines,
+    reason = "Single dispatch over the full instruction set"
+)]
 pub fn assemble_flat_bytecode(instructions: &[Instruction]) -> Vec<u32> {
     let mut bc = Vec::with_capacity(instructions.len() * 4 + 1);
     for instr in instructions {
@@ -96,6 +100,26 @@ pub fn assemble_flat_bytecode(instructions: &[Instruction]) -> Vec<u32> {
                 arg3,
                 arg4,
             } => bc.extend_from_slice(&[op, dest, func_op as u32, arg1, arg2, arg3, arg4]),
+            Instruction::MinConst { dest, src, bound }
+            | Instruction::MaxConst { dest, src, bound } => {
+                bc.extend_from_slice(&[op, dest, src, bound]);
+            }
+            Instruction::Clamp { dest, src, lo, hi } => {
+                bc.extend_from_slice(&[op, dest, src, lo, hi]);
+            }
+            Instruction::Select { dest, cond, a, b }
+            | Instruction::SelectNonNeg {
+                dest,
+                src: cond,
+                a,
+                b,
+            } => bc.extend_from_slice(&[op, dest, cond, a, b]),
+            Instruction::CallExternal {
+                dest,
+                callback,
+                start_idx,
+                count,
+            } => bc.extend_from_slice(&[op, dest, callback, start_idx, count]),
         }
     }
     bc.push(0); // End opcode to terminate execution loop without pointer length checks
@@ -28,7 +28,7 @@ impl RegAllocator {
         const_count: u32,
         num_temps: usize,
         vinstrs: &[VInstruction],
-        final_vreg: Option<VReg>,
+        outputs: &[VReg],
     ) -> Self {
         let mut last_use = vec![None; num_temps];
         let mut last_phys_0_read = None;
@@ -54,14 +54,13 @@ impl RegAllocator {
             });
         }
 
-        // Ensure the final result register is kept alive until the very end
-        // so its physical register is not overwritten by intermediate computations.
-        if let Some(VReg::Temp(t)) = final_vreg {
-            let last_idx = vinstrs.len().saturating_sub(1);
-            if let Some(lu) = last_use[t as usize] {
-                last_use[t as usize] = Some(lu.max(last_idx));
-            } else {
-                last_use[t as usize] = Some(last_idx);
+        // Ensure the output registers are kept alive until the very end
+        // so their physical registers are not overwritten by intermediate computations.
+        let last_idx = vinstrs.len().saturating_sub(1);
+        for &output in outputs {
+            if let VReg::Temp(t) = output {
+                let lu = last_use[t as usize].map_or(last_idx, |lu| lu.max(last_idx));
+                last_use[t as usize] = Some(lu);
             }
         }
 
@@ -95,8 +94,8 @@ impl RegAllocator {
     pub(crate) fn allocate(
         mut self,
         vinstrs: Vec<VInstruction>,
-        final_vreg: Option<VReg>,
-    ) -> (Vec<Instruction>, Vec<u32>, usize, u32) {
+        outputs: &[VReg],
+    ) -> (Vec<Instruction>, Vec<u32>, usize, Vec<u32>) {
         let n_instrs = vinstrs.len();
         let mut max_phys = self.param_count + self.const_count;
         let mut temp_to_phys: Vec<u32> = vec![u32::MAX; self.num_temps];
@@ -105,7 +104,7 @@ impl RegAllocator {
 
         for (idx, instr) in vinstrs.into_iter().enumerate() {
             let dest_vreg = instr.dest();
-            let is_final_prod = final_vreg == Some(dest_vreg);
+            let is_final_prod = outputs == [dest_vreg];
 
             let dest_phys = match dest_vreg {
                 VReg::Param(p) => p,
@@ -258,6 +257,20 @@ impl RegAllocator {
                     dest: dest_phys,
                     src: map_vreg_to_phys!(src),
                 }),
+                VInstruction::CallExternal { callback, args, .. } => {
+                    let start_idx = u32::try_from(self.arg_pool.len())
+                        .expect("Arg pool too large for u32 index");
+                    for &a in &args {
+                        self.arg_pool.push(map_vreg_to_phys!(a));
+                    }
+                    instructions.push(Instruction::CallExternal {
+                        dest: dest_phys,
+                        callback,
+                        start_idx,
+                        count: u32::try_from(args.len())
+                            .expect("Too many arguments for CallExternal"),
+                    });
+                }
                 VInstruction::BuiltinFun { op, args, .. } => match args.len() {
                     1 => match op {
                         FnOp::Sin => instructions.push(Instruction::Sin {
@@ -286,21 +299,23 @@ impl RegAllocator {
                             arg: map_vreg_to_phys!(args[0]),
                         }),
                     },
-                    2 => instructions.push(Instruction::Builtin2 {
-                        dest: dest_phys,
+                    2 => self.emit_builtin2(
+                        dest_phys,
                         op,
-                        arg1: map_vreg_to_phys!(args[0]),
-                        arg2: map_vreg_to_phys!(args[1]),
-                    }),
+                        args[0],
+                        args[1],
+                        &temp_to_phys,
+                        &mut instructions,
+                    ),
                     _ => {
                         if args.len() == 3 {
-                            instructions.push(Instruction::Builtin3 {
-                                dest: dest_phys,
+                            self.emit_builtin3(
+                                dest_phys,
                                 op,
-                                arg1: map_vreg_to_phys!(args[0]),
-                                arg2: map_vreg_to_phys!(args[1]),
-                                arg3: map_vreg_to_phys!(args[2]),
-                            });
+                                [args[0], args[1], args[2]],
+                                &temp_to_phys,
+                                &mut instructions,
+                            );
                         } else if args.len() == 4 {
                             instructions.push(Instruction::Builtin4 {
                                 dest: dest_phys,
@@ -349,12 +364,7 @@ impl RegAllocator {
                     }),
                 },
                 VInstruction::Builtin2 { op, arg1, arg2, .. } => {
-                    instructions.push(Instruction::Builtin2 {
-                        dest: dest_phys,
-                        op,
-                        arg1: map_vreg_to_phys!(arg1),
-                        arg2: map_vreg_to_phys!(arg2),
-                    });
+                    self.emit_builtin2(dest_phys, op, arg1, arg2, &temp_to_phys, &mut instructions);
                 }
                 VInstruction::Square { src, .. } => instructions.push(Instruction::Square {
                     dest: dest_phys,
@@ -449,19 +459,12 @@ impl RegAllocator {
             }
         }
 
-        let result_phys = if let Some(f_vreg) = final_vreg {
-            match f_vreg {
-                VReg::Param(p) => p,
-                VReg::Const(c) => self.param_count + c,
-                VReg::Temp(t) => temp_to_phys[t as usize],
-            }
-        } else {
-            // No result produced (e.g. empty program), use default zero (first constant)
-            // In the unified layout, constant 0 (0.0) is at param_count + 0.
-            self.param_count
-        };
+        let output_phys = outputs
+            .iter()
+            .map(|&vreg| map_vreg(vreg, self.param_count, &temp_to_phys))
+            .collect();
 
-        (instructions, self.arg_pool, max_phys as usize, result_phys)
+        (instructions, self.arg_pool, max_phys as usize, output_phys)
     }
 
     fn emit_add2(
@@ -493,4 +496,78 @@ impl RegAllocator {
             b: map_vreg(b, self.param_count, t2p),
         });
     }
+
+    /// Binary builtins, with `min`/`max` against a constant lowered to a dedicated pick
+    ///
+    /// The constant moves to the `bound` operand, which changes nothing but the
+    /// sign of a zero result on a tie.
+    fn emit_builtin2(
+        &self,
+        dest_phys: u32,
+        op: FnOp,
+        arg1: VReg,
+        arg2: VReg,
+        t2p: &[u32],
+        instrs: &mut Vec<Instruction>,
+    ) {
+        let (src, bound) = match (arg1, arg2) {
+            (_, VReg::Const(_)) => (arg1, arg2),
+            (VReg::Const(_), _) => (arg2, arg1),
+            _ => (arg1, arg2),
+        };
+        let is_const_bound = matches!(bound, VReg::Const(_));
+        let src = map_vreg(src, self.param_count, t2p);
+        let bound = map_vreg(bound, self.param_count, t2p);
+        instrs.push(match op {
+            FnOp::Min if is_const_bound => Instruction::MinConst {
+                dest: dest_phys,
+                src,
+                bound,
+            },
+            FnOp::Max if is_const_bound => Instruction::MaxConst {
+                dest: dest_phys,
+                src,
+                bound,
+            },
+            _ => Instruction::Builtin2 {
+                dest: dest_phys,
+                op,
+                arg1: map_vreg(arg1, self.param_count, t2p),
+                arg2: map_vreg(arg2, self.param_count, t2p),
+            },
+        });
+    }
+
+    /// Ternary builtins, with `clamp` and `select` lowered to their own instructions
+    fn emit_builtin3(
+        &self,
+        dest_phys: u32,
+        op: FnOp,
+        args: [VReg; 3],
+        t2p: &[u32],
+        instrs: &mut Vec<Instruction>,
+    ) {
+        let [x, y, z] = args.map(|arg| map_vreg(arg, self.param_count, t2p));
+        instrs.push(match op {
+            FnOp::Clamp => Instruction::Clamp {
+                dest: dest_phys,
+                src: x,
+                lo: y,
+                hi: z,
+            },
+            FnOp::Select => Instruction::Select {
+                dest: dest_phys,
+                cond: x,
+                a: y,
+                b: z,
+            },
+            _ => Instruction::Builtin3 {
+                dest: dest_phys,
+                op,
+                arg1: x,
+                arg2: y,
+                arg3: z,
+            },
+        });
+    }
 }
@@ -7,7 +7,7 @@ pub mod emit;
 pub mod optimize;
 pub mod vir;
 
-pub use codegen::expand_user_functions;
+pub use codegen::{expand_user_functions, substitute_constants};
 pub use compiler::VirGenerator;
 pub use emit::assemble_flat_bytecode;
 
@@ -13,8 +13,8 @@ pub(super) fn compact_constants(
     arg_pool: &mut [u32],
     param_count: usize,
     old_const_count: usize,
-    output_reg: u32,
-) -> (Vec<Instruction>, usize, u32) {
+    output_regs: &mut [u32],
+) -> (Vec<Instruction>, usize) {
     let param_count_u32 = u32::try_from(param_count).expect("Param count overflow");
     let const_limit_u32 =
         u32::try_from(param_count + old_const_count).expect("Register index overflow");
@@ -25,7 +25,7 @@ pub(super) fn compact_constants(
         param_count_u32,
         const_limit_u32,
         old_const_count,
-        output_reg,
+        output_regs,
     );
 
     // If all constants are used, just return the current state
@@ -34,21 +34,20 @@ pub(super) fn compact_constants(
         return (
             out,
             (final_max_reg as usize + 1).max(param_count + old_const_count),
-            output_reg,
         );
     }
 
     // Compact the constant vector and create a map from old register index to new register index
     let index_map = compact_constant_pool(constants, &all_used_indices, param_count_u32);
 
-    // Remap all instructions and the output register
-    let new_output_reg = remap_after_constant_compaction(
+    // Remap all instructions and the output registers
+    remap_after_constant_compaction(
         &mut out,
         arg_pool,
         &index_map,
         param_count_u32,
         const_limit_u32,
-        output_reg,
+        output_regs,
         constants.len(),
     );
 
@@ -57,7 +56,6 @@ pub(super) fn compact_constants(
     (
         out,
         (final_max_reg as usize + 1).max(param_count + constants.len()),
-        new_output_reg,
     )
 }
 
@@ -67,7 +65,7 @@ fn collect_used_constant_indices(
     param_count_u32: u32,
     const_limit_u32: u32,
     constant_count: usize,
-    output_reg: u32,
+    output_regs: &[u32],
 ) -> Vec<bool> {
     let mut used_pool_indices = vec![false; constant_count];
     for instr in instructions {
@@ -82,9 +80,11 @@ fn collect_used_constant_indices(
             }
         });
     }
-    // The final result might be a literal constant
-    if output_reg >= param_count_u32 && output_reg < const_limit_u32 {
-        used_pool_indices[(output_reg - param_count_u32) as usize] = true;
+    // An output might be a literal constant
+    for &reg in output_regs {
+        if reg >= param_count_u32 && reg < const_limit_u32 {
+            used_pool_indices[(reg - param_count_u32) as usize] = true;
+        }
     }
     used_pool_indices
 }
@@ -121,9 +121,9 @@ fn remap_after_constant_compaction(
     index_map: &[Option<u32>],
     param_count_u32: u32,
     const_limit_u32: u32,
-    output_reg: u32,
+    output_regs: &mut [u32],
     new_const_count: usize,
-) -> u32 {
+) {
     let temp_start =
         param_count_u32 + u32::try_from(new_const_count).expect("New constant count overflow");
     let next_temp = RefCell::new(temp_start);
@@ -150,7 +150,9 @@ fn remap_after_constant_compaction(
         instr.map_all_regs(arg_pool, &mut remap_register);
     }
 
-    remap_register(output_reg)
+    for reg in output_regs {
+        *reg = remap_register(*reg);
+    }
 }
 
 fn max_register_index(instrs: &[Instruction], arg_pool: &[u32]) -> u32 {
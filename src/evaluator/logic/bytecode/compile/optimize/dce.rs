@@ -38,7 +38,7 @@ pub(super) fn eliminate_dead_code(
     param_count: usize,
     const_count: usize,
     max_reg_idx: u32,
-    output_reg: u32,
+    output_regs: &[u32],
     scratch: &mut DceScratch,
 ) -> Vec<Instruction> {
     let max_reg_len = (max_reg_idx + 1) as usize;
@@ -88,7 +88,7 @@ pub(super) fn eliminate_dead_code(
         u32::try_from(param_count + const_count).expect("Register index overflow");
     for (i, instr) in out.iter().enumerate() {
         if let Instruction::Copy { dest, src } = *instr {
-            if dest == output_reg || dest == src {
+            if output_regs.contains(&dest) || dest == src {
                 continue;
             }
 
@@ -131,8 +131,10 @@ pub(super) fn eliminate_dead_code(
     // This pass identifies and removes them, combining the final retain operations.
     lives.clear();
     lives.resize(max_reg_len, false);
-    // Seed liveness with the required output register
-    lives[output_reg as usize] = true;
+    // Seed liveness with the required output registers
+    for &reg in output_regs {
+        lives[reg as usize] = true;
+    }
 
     // We need to build `instr_lives` on the instructions *after* copy forwarding.
     // This array will store whether each instruction is live.
@@ -33,7 +33,7 @@ impl FuseResult {
 )]
 pub(super) fn fuse_instructions(
     instructions: &[Instruction],
-    pool: &mut ConstantPool<'_>,
+    pool: &ConstantPool<'_>,
     use_count: &[usize],
     arg_pool: &[u32],
 ) -> (Vec<Instruction>, bool) {
@@ -90,6 +90,12 @@ pub(super) fn fuse_instructions(
                 changed = true;
                 continue;
             }
+            if let Some(fused) = try_fuse_clamp(prev, next, pool, &single_use) {
+                fused.push_to(&mut out);
+                instr_idx += 2;
+                changed = true;
+                continue;
+            }
         }
 
         out.push(instructions[instr_idx]);
@@ -99,7 +105,10 @@ pub(super) fn fuse_instructions(
     // P2: Non-adjacent SinCos fusion
     let sin_cos_changed = fuse_sin_cos(&mut out, arg_pool);
 
-    (out, changed || sin_cos_changed)
+    // P3: Non-adjacent step-into-Select fusion
+    let select_changed = fuse_step_select(&mut out, use_count);
+
+    (out, changed || sin_cos_changed || select_changed)
 }
 
 fn fuse_sin_cos(instructions: &mut [Instruction], arg_pool: &[u32]) -> bool {
@@ -186,6 +195,49 @@ fn fuse_sin_cos(instructions: &mut [Instruction], arg_pool: &[u32]) -> bool {
     changed
 }
 
+/// Folds a single-use `heaviside(x)` into the `Select` it feeds: `x >= 0 ? a : b`.
+///
+/// The step may sit anywhere earlier as long as `x` is not overwritten before the
+/// select; like the `SinCos` fusion, the step is left behind as a self-copy for DCE.
+fn fuse_step_select(instructions: &mut [Instruction], use_count: &[usize]) -> bool {
+    use rustc_hash::FxHashMap;
+    let mut changed = false;
+
+    // Step result register -> (instruction index, step argument)
+    let mut steps: FxHashMap<u32, (usize, u32)> = FxHashMap::default();
+
+    for idx in 0..instructions.len() {
+        if let Instruction::Select { dest, cond, a, b } = instructions[idx]
+            && use_count[cond as usize] == 1
+            && let Some(&(step_idx, src)) = steps.get(&cond)
+        {
+            instructions[idx] = Instruction::SelectNonNeg { dest, src, a, b };
+            instructions[step_idx] = Instruction::Copy {
+                dest: cond,
+                src: cond,
+            };
+            steps.remove(&cond);
+            changed = true;
+        }
+
+        instructions[idx].for_each_write(|written| {
+            steps.remove(&written);
+            steps.retain(|_, &mut (_, src)| src != written);
+        });
+
+        if let Instruction::Builtin1 {
+            dest,
+            op: FnOp::Heaviside,
+            arg,
+        } = instructions[idx]
+            && dest != arg
+        {
+            steps.insert(dest, (idx, arg));
+        }
+    }
+    changed
+}
+
 #[allow(
     clippy::float_cmp,
     reason = "Exact comparison is intended for algebraic identity matching of constants"
@@ -470,7 +522,7 @@ fn try_fuse_mul_add(
 fn try_fuse_power(
     prev: &Instruction,
     next: &Instruction,
-    pool: &mut ConstantPool<'_>,
+    pool: &ConstantPool<'_>,
     single_use: &impl Fn(&u32) -> bool,
 ) -> Option<FuseResult> {
     match (prev, next) {
@@ -539,7 +591,7 @@ fn try_fuse_power(
             },
         ) if *sq_src == *exp_dest && single_use(exp_dest) => {
             // Exp(x)^2 = Exp(2x)
-            let c_2 = pool.get_or_insert(2.0);
+            let c_2 = pool.lookup(2.0)?;
             return Some(FuseResult::Two(
                 Instruction::Mul {
                     dest: *exp_dest,
@@ -706,7 +758,7 @@ fn try_fuse_inverse(
 fn try_fuse_logarithmic(
     prev: &Instruction,
     next: &Instruction,
-    pool: &mut ConstantPool<'_>,
+    pool: &ConstantPool<'_>,
     single_use: &impl Fn(&u32) -> bool,
 ) -> Option<FuseResult> {
     match (prev, next) {
@@ -743,7 +795,7 @@ fn try_fuse_logarithmic(
                 dest: *final_dest,
                 arg: *src_x,
             };
-            let c_n = pool.get_or_insert(n);
+            let c_n = pool.lookup(n)?;
             Some(FuseResult::Two(
                 ln_x,
                 Instruction::Mul {
@@ -768,7 +820,7 @@ fn try_fuse_logarithmic(
                 dest: *dest_reg,
                 arg: *src_reg,
             };
-            let c_0_5 = pool.get_or_insert(0.5);
+            let c_0_5 = pool.lookup(0.5)?;
             Some(FuseResult::Two(
                 ln_x,
                 Instruction::Mul {
@@ -1005,7 +1057,7 @@ fn try_fuse_power_idempotent(
 fn try_fuse_const_chain(
     prev: &Instruction,
     next: &Instruction,
-    pool: &mut ConstantPool<'_>,
+    pool: &ConstantPool<'_>,
     single_use: &impl Fn(&u32) -> bool,
 ) -> Option<FuseResult> {
     match (prev, next) {
@@ -1049,7 +1101,7 @@ fn try_fuse_const_chain(
             if sum == 0.0 {
                 Some(FuseResult::One(Instruction::Copy { dest: *f_d, src }))
             } else {
-                let c_sum = pool.get_or_insert(sum);
+                let c_sum = pool.lookup(sum)?;
                 Some(FuseResult::One(Instruction::Add {
                     dest: *f_d,
                     a: src,
@@ -1092,7 +1144,7 @@ fn try_fuse_const_chain(
 
             let prod = pool.get(c1_reg) * pool.get(c2_reg);
             if prod == 0.0 {
-                let c_zero = pool.get_or_insert(0.0);
+                let c_zero = pool.lookup(0.0)?;
                 Some(FuseResult::One(Instruction::Copy {
                     dest: *f_d,
                     src: c_zero,
@@ -1100,7 +1152,7 @@ fn try_fuse_const_chain(
             } else if prod == 1.0 {
                 Some(FuseResult::One(Instruction::Copy { dest: *f_d, src }))
             } else {
-                let c_prod = pool.get_or_insert(prod);
+                let c_prod = pool.lookup(prod)?;
                 Some(FuseResult::One(Instruction::Mul {
                     dest: *f_d,
                     a: src,
@@ -1112,6 +1164,64 @@ fn try_fuse_const_chain(
     }
 }
 
+/// `min(max(x, lo), hi)` against constants -> `clamp(x, lo, hi)`.
+///
+/// The reversed nesting `max(min(x, hi), lo)` only agrees with the clamp when
+/// `lo <= hi`, so it is fused only then.
+fn try_fuse_clamp(
+    prev: &Instruction,
+    next: &Instruction,
+    pool: &ConstantPool<'_>,
+    single_use: &impl Fn(&u32) -> bool,
+) -> Option<FuseResult> {
+    match (prev, next) {
+        (
+            Instruction::MaxConst {
+                dest: tmp_dest,
+                src,
+                bound: lo,
+            },
+            Instruction::MinConst {
+                dest,
+                src: tmp_src,
+                bound: hi,
+            },
+        ) if *tmp_src == *tmp_dest && single_use(tmp_dest) => {
+            Some(FuseResult::One(Instruction::Clamp {
+                dest: *dest,
+                src: *src,
+                lo: *lo,
+                hi: *hi,
+            }))
+        }
+        (
+            Instruction::MinConst {
+                dest: tmp_dest,
+                src,
+                bound: hi,
+            },
+            Instruction::MaxConst {
+                dest,
+                src: tmp_src,
+                bound: lo,
+            },
+        ) if *tmp_src == *tmp_dest
+            && single_use(tmp_dest)
+            && pool.is_constant(*lo)
+            && pool.is_constant(*hi)
+            && pool.get(*lo) <= pool.get(*hi) =>
+        {
+            Some(FuseResult::One(Instruction::Clamp {
+                dest: *dest,
+                src: *src,
+                lo: *lo,
+                hi: *hi,
+            }))
+        }
+        _ => None,
+    }
+}
+
 fn try_fuse_transcendental_idempotent(
     prev: &Instruction,
     next: &Instruction,
@@ -27,6 +27,8 @@ impl<'pool> ConstantPool<'pool> {
     }
 
     /// Return the REGISTER INDEX of `val`; insert it if not already present.
+    ///
+    /// Only valid before register allocation (VIR passes), see [`Self::lookup`].
     pub fn get_or_insert(&mut self, val: f64) -> u32 {
         let bits = val.to_bits();
         if let Some(&rel_idx) = self.index.get(&bits) {
@@ -38,6 +40,17 @@ impl<'pool> ConstantPool<'pool> {
         self.param_count + rel_idx
     }
 
+    /// Return the REGISTER INDEX of `val` if it is already in the pool.
+    ///
+    /// Passes over physical instructions must use this instead of [`Self::get_or_insert`]:
+    /// temporaries are allocated right after the last constant, so a new constant would
+    /// alias a live temporary. Rewrites that need a constant the pool lacks are skipped.
+    pub fn lookup(&self, val: f64) -> Option<u32> {
+        self.index
+            .get(&val.to_bits())
+            .map(|&rel_idx| self.param_count + rel_idx)
+    }
+
     /// Direct constant lookup by REGISTER INDEX.
     pub fn get(&self, register: u32) -> f64 {
         let offset = register - self.param_count;
@@ -19,6 +19,7 @@ impl CompiledEvaluator {
     /// - **Inverse Fusions**: `[Sqrt, Recip]` → `InvSqrt`, `[Square, Recip]` → `InvSquare`, `[Cube, Recip]` → `InvCube`
     /// - **Power Fusions**: `[Square, Mul]` → `Cube`, `[Pow4, Recip]` → `Square + InvSquare` (for $x^{-4}$)
     /// - **Exponential Fusions**: `[Neg, Exp]` → `ExpNeg`
+    /// - **Piecewise Fusions**: `[MaxConst, MinConst]` → `Clamp`, `[Heaviside, Select]` → `SelectNonNeg`
     ///
     ///
     /// # Errors
@@ -38,14 +39,38 @@ impl CompiledEvaluator {
         max_phys: usize,
         result_reg: u32,
     ) -> Result<(Vec<Instruction>, usize, u32), DiffError> {
+        let mut output_regs = [result_reg];
+        let (out, rc) = Self::optimize_program(
+            instructions,
+            constants,
+            const_map,
+            arg_pool,
+            param_count,
+            max_phys,
+            &mut output_regs,
+        )?;
+        Ok((out, rc, output_regs[0]))
+    }
+
+    /// [`optimize_instructions`](Self::optimize_instructions) for a program with several
+    /// output registers, which are remapped in place.
+    pub(crate) fn optimize_program(
+        instructions: Vec<Instruction>,
+        constants: &mut Vec<f64>,
+        const_map: FxHashMap<u64, u32>,
+        arg_pool: &mut [u32],
+        param_count: usize,
+        max_phys: usize,
+        output_regs: &mut [u32],
+    ) -> Result<(Vec<Instruction>, usize), DiffError> {
         if instructions.is_empty() {
             let rc = param_count + constants.len();
-            return Ok((instructions, rc, result_reg));
+            return Ok((instructions, rc));
         }
 
         let max_reg_idx = u32::try_from(max_phys)
             .expect("Register index overflow")
-            .max(result_reg)
+            .max(output_regs.iter().copied().max().unwrap_or(0))
             .saturating_sub(1);
         let old_const_count = constants.len();
 
@@ -54,7 +79,7 @@ impl CompiledEvaluator {
         let mut use_count = vec![0_usize; (max_reg_idx + 1) as usize];
 
         // Single owned pool for all mutation-safe constant interning throughout the pipeline.
-        let mut pool = ConstantPool::with_index(
+        let pool = ConstantPool::with_index(
             constants,
             const_map,
             u32::try_from(param_count).expect("Param count overflow"),
@@ -63,7 +88,7 @@ impl CompiledEvaluator {
         // 1. Initial strength reduction and power chain analysis
         // These passes may convert neutral math to 'Copy' or rewrite power sequences.
         let mut out = instructions;
-        reduce_strength(&mut out, &mut pool);
+        reduce_strength(&mut out, &pool);
         optimize_power_chains(&mut out);
 
         // 2. Main DCE / Copy Forwarding pass
@@ -77,14 +102,24 @@ impl CompiledEvaluator {
             param_count,
             old_const_count,
             max_reg_idx,
-            result_reg,
+            output_regs,
             &mut dce_scratch,
         );
 
         // 3. Final fusion pass: Catch FMA/Pow patterns on the cleaned instruction stream
         loop {
             calculate_use_count(&out, &mut use_count, &mut dce_scratch.dirty_uses, arg_pool);
-            let (new_out, changed) = fuse_instructions(&out, &mut pool, &use_count, arg_pool);
+            // A lone output is written last; with several, an output may also feed later
+            // instructions and must not be fused into them
+            if output_regs.len() > 1 {
+                for &reg in output_regs.iter() {
+                    if use_count[reg as usize] == 0 {
+                        dce_scratch.dirty_uses.push(reg);
+                    }
+                    use_count[reg as usize] += 1;
+                }
+            }
+            let (new_out, changed) = fuse_instructions(&out, &pool, &use_count, arg_pool);
             out = new_out;
             if !changed {
                 break;
@@ -99,7 +134,7 @@ impl CompiledEvaluator {
             param_count,
             old_const_count,
             max_reg_idx,
-            result_reg,
+            output_regs,
             &mut dce_scratch,
         );
 
@@ -107,18 +142,18 @@ impl CompiledEvaluator {
         // This is the final pass. It removes unused constants and shifts all
         // registers down to create a dense, minimal workspace.
         let (const_vec, _) = pool.into_parts();
-        let (out, rc, result_reg) = compact_constants(
+        let (out, rc) = compact_constants(
             out,
             const_vec,
             arg_pool,
             param_count,
             old_const_count,
-            result_reg,
+            output_regs,
         );
 
         #[cfg(debug_assertions)]
         validate_program(&out, const_vec, arg_pool, rc, param_count)?;
 
-        Ok((out, rc, result_reg))
+        Ok((out, rc))
     }
 }
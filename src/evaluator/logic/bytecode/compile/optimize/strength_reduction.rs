@@ -11,23 +11,23 @@ use super::helper::ConstantPool;
     clippy::too_many_lines,
     reason = "Strength reduction pass covering many instruction variants"
 )]
-pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut ConstantPool<'_>) {
+pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &ConstantPool<'_>) {
     for instr in instructions {
         match *instr {
             Instruction::Mul { dest, a, b } if a == b => {
                 if pool.is_constant(a) {
-                    let v = pool.get(a);
-                    let sq = pool.get_or_insert(v * v);
-                    *instr = Instruction::Copy { dest, src: sq };
+                    if let Some(sq) = pool.lookup(pool.get(a) * pool.get(a)) {
+                        *instr = Instruction::Copy { dest, src: sq };
+                    }
                 } else {
                     *instr = Instruction::Square { dest, src: a };
                 }
             }
             Instruction::Mul { dest, a, b } => {
                 if pool.is_constant(a) && pool.is_constant(b) {
-                    let v = pool.get(a) * pool.get(b);
-                    let c = pool.get_or_insert(v);
-                    *instr = Instruction::Copy { dest, src: c };
+                    if let Some(c) = pool.lookup(pool.get(a) * pool.get(b)) {
+                        *instr = Instruction::Copy { dest, src: c };
+                    }
                     continue;
                 }
                 let (c_reg, v_reg) = if pool.is_constant(b) {
@@ -56,9 +56,9 @@ pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut Const
             }
             Instruction::Div { dest, num, den } => {
                 if pool.is_constant(num) && pool.is_constant(den) {
-                    let v = pool.get(num) / pool.get(den);
-                    let c = pool.get_or_insert(v);
-                    *instr = Instruction::Copy { dest, src: c };
+                    if let Some(c) = pool.lookup(pool.get(num) / pool.get(den)) {
+                        *instr = Instruction::Copy { dest, src: c };
+                    }
                     continue;
                 }
                 // x / const -> x * (1/const)
@@ -66,9 +66,10 @@ pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut Const
                     let divisor = pool.get(den);
                     if divisor == 1.0 {
                         *instr = Instruction::Copy { dest, src: num };
-                    } else if divisor != 0.0 && divisor.is_finite() {
-                        let recip = 1.0 / divisor;
-                        let recip_reg = pool.get_or_insert(recip);
+                    } else if divisor != 0.0
+                        && divisor.is_finite()
+                        && let Some(recip_reg) = pool.lookup(1.0 / divisor)
+                    {
                         *instr = Instruction::Mul {
                             dest,
                             a: num,
@@ -84,9 +85,9 @@ pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut Const
             }
             Instruction::Add { dest, a, b } => {
                 if pool.is_constant(a) && pool.is_constant(b) {
-                    let v = pool.get(a) + pool.get(b);
-                    let c = pool.get_or_insert(v);
-                    *instr = Instruction::Copy { dest, src: c };
+                    if let Some(c) = pool.lookup(pool.get(a) + pool.get(b)) {
+                        *instr = Instruction::Copy { dest, src: c };
+                    }
                     continue;
                 }
                 let (c_reg, v_reg) = if pool.is_constant(b) {
@@ -104,9 +105,9 @@ pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut Const
             }
             Instruction::Sub { dest, a, b } => {
                 if pool.is_constant(a) && pool.is_constant(b) {
-                    let v = pool.get(a) - pool.get(b);
-                    let c = pool.get_or_insert(v);
-                    *instr = Instruction::Copy { dest, src: c };
+                    if let Some(c) = pool.lookup(pool.get(a) - pool.get(b)) {
+                        *instr = Instruction::Copy { dest, src: c };
+                    }
                     continue;
                 }
                 // x - 0.0 -> Copy x
@@ -133,9 +134,9 @@ pub(super) fn reduce_strength(instructions: &mut [Instruction], pool: &mut Const
             Instruction::Pow { dest, base, exp }
                 if pool.is_constant(base) && pool.is_constant(exp) =>
             {
-                let v = pool.get(base).powf(pool.get(exp));
-                let c = pool.get_or_insert(v);
-                *instr = Instruction::Copy { dest, src: c };
+                if let Some(c) = pool.lookup(pool.get(base).powf(pool.get(exp))) {
+                    *instr = Instruction::Copy { dest, src: c };
+                }
             }
             _ => {}
         }
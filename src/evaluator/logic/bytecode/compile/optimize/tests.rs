@@ -33,7 +33,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -70,7 +70,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -146,7 +146,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -172,7 +172,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -200,7 +200,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -252,10 +252,10 @@ mod unit_tests {
             instrs,
             &mut arg_pool,
             &mut use_count,
-            2,  // param_count
-            0,  // const_count
-            10, // max_reg_idx
-            0,  // output_reg
+            2,    // param_count
+            0,    // const_count
+            10,   // max_reg_idx
+            &[0], // output_reg
             &mut crate::evaluator::logic::bytecode::compile::optimize::dce::DceScratch::new(),
         );
 
@@ -287,8 +287,8 @@ mod unit_tests {
         let use_count = vec![0, 2, 2, 0, 0, 0, 0, 0, 0, 0, 1, 1]; // R10 used once
 
         let mut constants = vec![];
-        let mut pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 0);
-        let (fused, _) = fuse_instructions(&instrs, &mut pool, &use_count, &[]);
+        let pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 0);
+        let (fused, _) = fuse_instructions(&instrs, &pool, &use_count, &[]);
 
         assert_eq!(fused.len(), 1);
         if let Instruction::MulAdd { dest, a, b, c } = fused[0] {
@@ -301,6 +301,85 @@ mod unit_tests {
         }
     }
 
+    #[test]
+    fn test_fusion_reversed_clamp_requires_ordered_bounds() {
+        // max(min(x, hi), lo) with x in R0, constants 2.0 in R1 and 1.0 in R2
+        let nested = |hi, lo| {
+            vec![
+                Instruction::MinConst {
+                    dest: 3,
+                    src: 0,
+                    bound: hi,
+                },
+                Instruction::MaxConst {
+                    dest: 4,
+                    src: 3,
+                    bound: lo,
+                },
+            ]
+        };
+        let use_count = vec![1, 1, 1, 1, 1];
+        let mut constants = vec![2.0, 1.0];
+        let pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 1);
+
+        let (fused, _) = fuse_instructions(&nested(1, 2), &pool, &use_count, &[]);
+        assert_eq!(
+            fused,
+            vec![Instruction::Clamp {
+                dest: 4,
+                src: 0,
+                lo: 2,
+                hi: 1
+            }]
+        );
+
+        // With lo > hi the nesting yields lo, not clamp's hi
+        let (kept, changed) = fuse_instructions(&nested(2, 1), &pool, &use_count, &[]);
+        assert!(!changed);
+        assert_eq!(kept, nested(2, 1));
+    }
+
+    #[test]
+    fn test_fusion_step_feeding_select_tests_sign() {
+        let instrs = vec![
+            Instruction::Builtin1 {
+                dest: 3,
+                op: FnOp::Heaviside,
+                arg: 0,
+            },
+            Instruction::Sin { dest: 4, arg: 1 },
+            Instruction::Select {
+                dest: 5,
+                cond: 3,
+                a: 4,
+                b: 2,
+            },
+        ];
+        let use_count = vec![1, 1, 1, 1, 1, 1];
+        let mut constants = vec![];
+        let pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 3);
+        let (fused, fused_any) = fuse_instructions(&instrs, &pool, &use_count, &[]);
+
+        assert!(fused_any);
+        assert_eq!(fused[0], Instruction::Copy { dest: 3, src: 3 });
+        assert_eq!(
+            fused[2],
+            Instruction::SelectNonNeg {
+                dest: 5,
+                src: 0,
+                a: 4,
+                b: 2
+            }
+        );
+
+        // Overwriting the step's argument before the select blocks the fusion
+        let mut clobbered = instrs;
+        clobbered[1] = Instruction::Sin { dest: 0, arg: 1 };
+        let (kept, changed) = fuse_instructions(&clobbered, &pool, &use_count, &[]);
+        assert!(!changed);
+        assert_eq!(kept, clobbered);
+    }
+
     #[test]
     fn test_fusion_add_const_chain_to_copy_when_constants_cancel() {
         let instrs = vec![
@@ -318,8 +397,8 @@ mod unit_tests {
         let use_count = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1];
 
         let mut constants = vec![2.5, -2.5];
-        let mut pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 2);
-        let (fused, _) = fuse_instructions(&instrs, &mut pool, &use_count, &[]);
+        let pool = ConstantPool::with_index(&mut constants, FxHashMap::default(), 2);
+        let (fused, _) = fuse_instructions(&instrs, &pool, &use_count, &[]);
 
         assert_eq!(fused, vec![Instruction::Copy { dest: 0, src: 1 }]);
     }
@@ -344,8 +423,8 @@ mod unit_tests {
         let mut index = FxHashMap::default();
         index.insert(0.0_f64.to_bits(), 0);
         index.insert(4.0_f64.to_bits(), 1);
-        let mut pool = ConstantPool::with_index(&mut constants, index, 2);
-        let (fused, _) = fuse_instructions(&instrs, &mut pool, &use_count, &[]);
+        let pool = ConstantPool::with_index(&mut constants, index, 2);
+        let (fused, _) = fuse_instructions(&instrs, &pool, &use_count, &[]);
 
         assert_eq!(
             fused,
@@ -362,10 +441,38 @@ mod unit_tests {
         let mut index = FxHashMap::default();
         index.insert(3.0_f64.to_bits(), 0);
         index.insert(7.0_f64.to_bits(), 1);
-        let mut pool = ConstantPool::with_index(&mut constants, index, 2);
+        let pool = ConstantPool::with_index(&mut constants, index, 2);
 
-        assert_eq!(pool.get_or_insert(3.0), 2);
-        assert_eq!(pool.get_or_insert(7.0), 3);
+        assert_eq!(pool.lookup(3.0), Some(2));
+        assert_eq!(pool.lookup(7.0), Some(3));
+    }
+
+    #[test]
+    fn test_fusion_mul_const_chain_skipped_when_product_not_pooled() {
+        // R4 is the first temporary after the two constants; interning -0.5/3 as a new
+        // constant would have handed out R4 again and read it before its definition
+        let instrs = vec![
+            Instruction::Mul {
+                dest: 4,
+                a: 0,
+                b: 2, // Constant -0.5
+            },
+            Instruction::Mul {
+                dest: 5,
+                a: 3, // Constant 1/3
+                b: 4,
+            },
+        ];
+        let use_count = vec![1, 0, 1, 1, 1, 0];
+
+        let mut constants = vec![-0.5, 1.0 / 3.0];
+        let mut index = FxHashMap::default();
+        index.insert((-0.5_f64).to_bits(), 0);
+        index.insert((1.0_f64 / 3.0).to_bits(), 1);
+        let pool = ConstantPool::with_index(&mut constants, index, 2);
+        let (fused, _) = fuse_instructions(&instrs, &pool, &use_count, &[]);
+
+        assert_eq!(fused, instrs);
     }
 
     #[test]
@@ -393,7 +500,7 @@ mod unit_tests {
             3,
             0,
             6,
-            0,
+            &[0],
             &mut crate::evaluator::logic::bytecode::compile::optimize::dce::DceScratch::new(),
         );
 
@@ -431,13 +538,14 @@ mod unit_tests {
 
         let mut arg_pool = vec![];
 
-        let (out, rc, result_reg) = compact_constants(
+        let mut output_regs = [3]; // Temp 3
+        let (out, rc) = compact_constants(
             std::mem::take(&mut instructions),
             &mut constants,
             &mut arg_pool,
             0, // param_count
             2, // old_const_count
-            3, // output_reg (Temp 3)
+            &mut output_regs,
         );
 
         // 10.0 (reg 0) is unused and should be removed.
@@ -457,7 +565,7 @@ mod unit_tests {
         );
         assert_eq!(constants, vec![20.0]);
         assert_eq!(rc, 3);
-        assert_eq!(result_reg, 2);
+        assert_eq!(output_regs, [2]);
     }
 
     #[test]
@@ -477,7 +585,7 @@ mod unit_tests {
             let mut final_vreg = Some(VReg::Temp(0));
             optimize_vir_gvn(
                 &mut vinstrs,
-                &mut final_vreg,
+                final_vreg.as_mut_slice(),
                 &mut constants,
                 &mut const_map,
                 0,
@@ -495,7 +603,7 @@ mod unit_tests {
             let mut final_vreg = Some(VReg::Temp(0));
             optimize_vir_gvn(
                 &mut vinstrs,
-                &mut final_vreg,
+                final_vreg.as_mut_slice(),
                 &mut constants,
                 &mut const_map,
                 0,
@@ -513,7 +621,7 @@ mod unit_tests {
             let mut final_vreg = Some(VReg::Temp(0));
             optimize_vir_gvn(
                 &mut vinstrs,
-                &mut final_vreg,
+                final_vreg.as_mut_slice(),
                 &mut constants,
                 &mut const_map,
                 0,
@@ -540,7 +648,7 @@ mod unit_tests {
         let mut final_vreg = Some(VReg::Temp(0));
         optimize_vir_gvn(
             &mut vinstrs,
-            &mut final_vreg,
+            final_vreg.as_mut_slice(),
             &mut constants,
             &mut const_map,
             0,
@@ -590,10 +698,10 @@ mod unit_tests {
             instrs,
             &mut arg_pool,
             &mut use_count,
-            4, // param_count (R0..R3 are params)
-            0, // const_count
-            6, // max_reg_idx
-            0, // output_reg
+            4,    // param_count (R0..R3 are params)
+            0,    // const_count
+            6,    // max_reg_idx
+            &[0], // output_reg
             &mut crate::evaluator::logic::bytecode::compile::optimize::dce::DceScratch::new(),
         );
 
@@ -636,10 +744,10 @@ mod unit_tests {
             instrs,
             &mut arg_pool,
             &mut use_count,
-            2, // param_count (R0, R1)
-            0, // const_count
-            5, // max_reg_idx
-            5, // output_reg
+            2,    // param_count (R0, R1)
+            0,    // const_count
+            5,    // max_reg_idx
+            &[5], // output_reg
             &mut crate::evaluator::logic::bytecode::compile::optimize::dce::DceScratch::new(),
         );
 
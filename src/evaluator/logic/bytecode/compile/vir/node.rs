@@ -8,6 +8,7 @@ use super::types::VReg;
 use crate::core::known_symbols::KS;
 use crate::core::known_symbols::get_constant_value_by_id;
 use crate::core::{Expr, ExprKind};
+use crate::math::{eval_clamp, eval_max, eval_min, eval_select};
 use rustc_hash::FxHashMap;
 use std::ptr::from_ref;
 
@@ -117,8 +118,28 @@ pub fn compute_const_from_children(
                 let ks = &*KS;
                 if id == ks.atan2 {
                     Some(a.atan2(b))
+                } else if id == ks.hypot {
+                    Some(a.hypot(b))
                 } else if id == ks.log {
                     Some(b.log(a))
+                } else if id == ks.min {
+                    Some(eval_min(a, b))
+                } else if id == ks.max {
+                    Some(eval_max(a, b))
+                } else {
+                    None
+                }
+            }
+            3 => {
+                let a = const_from_map(node_map, args[0].as_ref())?;
+                let b = const_from_map(node_map, args[1].as_ref())?;
+                let c = const_from_map(node_map, args[2].as_ref())?;
+                let id = name.id();
+                let ks = &*KS;
+                if id == ks.clamp {
+                    Some(eval_clamp(a, b, c))
+                } else if id == ks.select {
+                    Some(eval_select(a, b, c))
                 } else {
                     None
                 }
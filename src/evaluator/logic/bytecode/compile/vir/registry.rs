@@ -2,8 +2,9 @@ use super::FnOp;
 use crate::EPSILON;
 use crate::core::known_symbols::KS;
 use crate::math::{
-    eval_digamma, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc, eval_exp_polar,
-    eval_gamma, eval_lambert_w, eval_lgamma, eval_tetragamma, eval_trigamma, eval_zeta,
+    eval_digamma, eval_dirac, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc,
+    eval_exp_polar, eval_gamma, eval_heaviside, eval_lambert_w, eval_lgamma, eval_tetragamma,
+    eval_trigamma, eval_zeta,
 };
 use rustc_hash::FxHashMap;
 use std::f64::consts::FRAC_PI_2;
@@ -47,6 +48,8 @@ pub static FN_MAP: LazyLock<FxHashMap<u64, FnOp>> = LazyLock::new(|| {
     m.insert(ks.signum, FnOp::Signum);
     m.insert(ks.sign, FnOp::Signum);
     m.insert(ks.sgn, FnOp::Signum);
+    m.insert(ks.heaviside, FnOp::Heaviside);
+    m.insert(ks.dirac, FnOp::Dirac);
     m.insert(ks.floor, FnOp::Floor);
     m.insert(ks.ceil, FnOp::Ceil);
     m.insert(ks.round, FnOp::Round);
@@ -66,6 +69,7 @@ pub static FN_MAP: LazyLock<FxHashMap<u64, FnOp>> = LazyLock::new(|| {
 
     // Arity 2
     m.insert(ks.atan2, FnOp::Atan2);
+    m.insert(ks.hypot, FnOp::Hypot);
     m.insert(ks.log, FnOp::Log);
     m.insert(ks.besselj, FnOp::BesselJ);
     m.insert(ks.bessely, FnOp::BesselY);
@@ -75,9 +79,13 @@ pub static FN_MAP: LazyLock<FxHashMap<u64, FnOp>> = LazyLock::new(|| {
     m.insert(ks.beta, FnOp::Beta);
     m.insert(ks.zeta_deriv, FnOp::ZetaDeriv);
     m.insert(ks.hermite, FnOp::Hermite);
+    m.insert(ks.min, FnOp::Min);
+    m.insert(ks.max, FnOp::Max);
 
     // Arity 3
     m.insert(ks.assoc_legendre, FnOp::AssocLegendre);
+    m.insert(ks.clamp, FnOp::Clamp);
+    m.insert(ks.select, FnOp::Select);
 
     // Arity 4
     m.insert(ks.spherical_harmonic, FnOp::SphericalHarmonic);
@@ -110,6 +118,8 @@ pub static CONST_FOLD_MAP: LazyLock<FxHashMap<u64, ConstFoldFn>> = LazyLock::new
     m.insert(ks.signum, f64::signum as ConstFoldFn);
     m.insert(ks.sign, f64::signum as ConstFoldFn);
     m.insert(ks.sgn, f64::signum as ConstFoldFn);
+    m.insert(ks.heaviside, eval_heaviside as ConstFoldFn);
+    m.insert(ks.dirac, eval_dirac as ConstFoldFn);
     m.insert(ks.log2, f64::log2 as ConstFoldFn);
     m.insert(ks.log10, f64::log10 as ConstFoldFn);
 
@@ -67,6 +67,11 @@ pub enum VInstruction {
         op: FnOp,
         args: Vec<VReg>,
     },
+    CallExternal {
+        dest: VReg,
+        callback: u32,
+        args: Vec<VReg>,
+    },
     Builtin1 {
         dest: VReg,
         op: FnOp,
@@ -165,6 +170,7 @@ impl VInstruction {
             | Self::Pow { dest, .. }
             | Self::Neg { dest, .. }
             | Self::BuiltinFun { dest, .. }
+            | Self::CallExternal { dest, .. }
             | Self::Builtin1 { dest, .. }
             | Self::Builtin2 { dest, .. }
             | Self::Square { dest, .. }
@@ -204,7 +210,7 @@ impl VInstruction {
                 f(*a);
                 f(*b);
             }
-            Self::BuiltinFun { args, .. } => {
+            Self::BuiltinFun { args, .. } | Self::CallExternal { args, .. } => {
                 for &a in args {
                     f(a);
                 }
@@ -250,6 +256,7 @@ impl VInstruction {
             | Self::Pow { dest, .. }
             | Self::Neg { dest, .. }
             | Self::BuiltinFun { dest, .. }
+            | Self::CallExternal { dest, .. }
             | Self::Builtin1 { dest, .. }
             | Self::Builtin2 { dest, .. }
             | Self::Square { dest, .. }
@@ -289,7 +296,7 @@ impl VInstruction {
                 f(a);
                 f(b);
             }
-            Self::BuiltinFun { args, .. } => {
+            Self::BuiltinFun { args, .. } | Self::CallExternal { args, .. } => {
                 for a in args {
                     f(a);
                 }
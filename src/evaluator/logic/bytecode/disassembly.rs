@@ -0,0 +1,316 @@
+//! Annotated bytecode listings for [`CompiledEvaluator`].
+//!
+//! Every instruction is printed with its register form, the value it computes
+//! in terms of the parameters and constants, the constant-pool slots it reads,
+//! and the number of temporaries still live once it has executed. The register
+//! machine has no operand stack, so the live-temporary count plays the role of
+//! the stack depth.
+
+use super::{CompiledEvaluator, Instruction};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{Result as FmtResult, Write};
+
+/// Rendered operands longer than this are referred to by their register.
+const MAX_OPERAND_LEN: usize = 40;
+
+impl CompiledEvaluator {
+    /// Write the annotated disassembly listing to `out`.
+    ///
+    /// The listing only depends on the compiled program, so it is stable
+    /// enough to be compared in snapshot tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by `out`.
+    pub fn disassemble_to_writer<W: Write>(&self, out: &mut W) -> FmtResult {
+        writeln!(out, "=== Bytecode Disassembly ===")?;
+        writeln!(
+            out,
+            "Parameters ({}): [{}]",
+            self.param_count,
+            self.param_names.join(", ")
+        )?;
+        writeln!(out, "Result: R{}", self.result_reg)?;
+
+        if !self.constants.is_empty() {
+            writeln!(out, "\n=== Constants ({}) ===", self.constants.len())?;
+            for (slot, value) in self.constants.iter().enumerate() {
+                writeln!(
+                    out,
+                    "  k{slot:<4} R{:<4} = {value}",
+                    self.param_count + slot
+                )?;
+            }
+        }
+
+        writeln!(out, "\n=== Memory Layout ===")?;
+        writeln!(out, "Workspace Size: {} registers", self.workspace_size)?;
+        writeln!(out, "Arg Pool Size:  {} entries", self.arg_pool.len())?;
+
+        writeln!(out, "\n=== Instructions ({}) ===", self.instructions.len())?;
+        let live = self.live_temporaries();
+        let mut operands = self.initial_operands();
+        let mut instr_counts = BTreeMap::new();
+        for (i, instr) in self.instructions.iter().enumerate() {
+            let debug_str = format!("{instr:?}");
+            let variant_name = debug_str
+                .split_once(['{', '(', ' '])
+                .map_or(debug_str.as_str(), |(v, _)| v);
+            *instr_counts.entry(variant_name.to_owned()).or_insert(0) += 1;
+
+            let listing = instr.to_string();
+            write!(out, "  [{i:04}] {variant_name:<10} {listing:<28}")?;
+            if let Some(effect) = self.annotate(instr, &listing, &mut operands) {
+                write!(out, " ; {effect}")?;
+            }
+            let slots = self.constant_slots(instr);
+            if !slots.is_empty() {
+                write!(out, " [{}]", slots.join(", "))?;
+            }
+            writeln!(out, " (live: {})", live.get(i).copied().unwrap_or_default())?;
+        }
+
+        if !self.instructions.is_empty() {
+            writeln!(out, "\n=== Instruction Summary ===")?;
+            let mut sorted_counts: Vec<_> = instr_counts.into_iter().collect();
+            sorted_counts.sort_by_key(|b| Reverse(b.1));
+
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Instruction count unlikely to exceed f64 mantissa precision"
+            )]
+            let total_instrs = self.instructions.len() as f64;
+            for (name, count) in sorted_counts {
+                let percentage = (f64::from(count) / total_instrs) * 100.0;
+                writeln!(out, "  {name:<15}: {count:>6} ({percentage:>5.1}%)")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Symbolic text of every register before execution: parameter names and
+    /// constant values, with temporaries still unknown.
+    fn initial_operands(&self) -> Vec<Option<String>> {
+        let mut operands = vec![None; self.workspace_size];
+        for (slot, name) in operands.iter_mut().zip(self.param_names.iter()) {
+            *slot = Some(name.clone());
+        }
+        for (slot, value) in operands
+            .iter_mut()
+            .skip(self.param_count)
+            .zip(self.constants.iter())
+        {
+            *slot = Some(value.to_string());
+        }
+        operands
+    }
+
+    /// Render what `instr` computes with its operands substituted, and record
+    /// the result as the new symbolic text of its destination registers.
+    fn annotate(
+        &self,
+        instr: &Instruction,
+        listing: &str,
+        operands: &mut [Option<String>],
+    ) -> Option<String> {
+        let (lhs, rhs) = listing.split_once(" = ")?;
+        let rendered = render_operands(rhs, operands, &self.arg_pool);
+        let keep = |text: String| (text.len() <= MAX_OPERAND_LEN).then_some(text);
+        if let Instruction::SinCos {
+            sin_dest,
+            cos_dest,
+            arg,
+        } = *instr
+        {
+            let arg_text = operand_text(operands, arg);
+            let (sin_text, cos_text) = (format!("sin({arg_text})"), format!("cos({arg_text})"));
+            set_operand(operands, sin_dest, keep(sin_text));
+            set_operand(operands, cos_dest, keep(cos_text));
+        } else if let Some(dest) = instr.primary_dest() {
+            set_operand(operands, dest, keep(rendered.clone()));
+        }
+        Some(format!("{lhs} = {rendered}"))
+    }
+
+    /// Constant-pool slots read by `instr`, in operand order.
+    fn constant_slots(&self, instr: &Instruction) -> Vec<String> {
+        let first_const = self.param_count;
+        let end_const = first_const + self.constants.len();
+        let mut slots = Vec::new();
+        let mut record = |reg: u32| {
+            let reg = reg as usize;
+            if (first_const..end_const).contains(&reg) {
+                let slot = format!("k{}", reg - first_const);
+                if !slots.contains(&slot) {
+                    slots.push(slot);
+                }
+            }
+        };
+        instr.for_each_read(&mut record);
+        instr.for_each_pooled_reg(&self.arg_pool, &mut record);
+        slots
+    }
+
+    /// Number of live temporaries after each instruction, from a backward
+    /// liveness pass that keeps the result register alive until the end.
+    fn live_temporaries(&self) -> Vec<usize> {
+        let first_temp = self.param_count + self.constants.len();
+        let mut live = vec![false; self.workspace_size.max(self.result_reg as usize + 1)];
+        let mut live_count = 0;
+        let mark = |flags: &mut [bool], count: &mut usize, reg: u32, alive: bool| {
+            let reg = reg as usize;
+            if reg >= first_temp
+                && let Some(flag) = flags.get_mut(reg)
+                && *flag != alive
+            {
+                *flag = alive;
+                if alive {
+                    *count += 1;
+                } else {
+                    *count -= 1;
+                }
+            }
+        };
+        mark(&mut live, &mut live_count, self.result_reg, true);
+
+        let mut counts = vec![0; self.instructions.len()];
+        for (i, instr) in self.instructions.iter().enumerate().rev() {
+            counts[i] = live_count;
+            instr.for_each_write(|reg| mark(&mut live, &mut live_count, reg, false));
+            instr.for_each_read(|reg| mark(&mut live, &mut live_count, reg, true));
+            instr.for_each_pooled_reg(&self.arg_pool, |reg| {
+                mark(&mut live, &mut live_count, reg, true);
+            });
+        }
+        counts
+    }
+}
+
+/// Symbolic text of `reg`, falling back to the register name.
+fn operand_text(operands: &[Option<String>], reg: u32) -> String {
+    operands
+        .get(reg as usize)
+        .and_then(Clone::clone)
+        .unwrap_or_else(|| format!("R{reg}"))
+}
+
+fn set_operand(operands: &mut [Option<String>], reg: u32, text: Option<String>) {
+    if let Some(slot) = operands.get_mut(reg as usize) {
+        *slot = text;
+    }
+}
+
+/// Binding strength of the loosest top-level operator in `text`: 1 for sums
+/// and leading negations, 2 for products, 3 for powers, 4 for atoms.
+fn precedence(text: &str) -> u8 {
+    let mut depth = 0_i32;
+    let mut loosest = 4;
+    let mut prev = None;
+    for c in text.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '+' if depth == 0 => loosest = 1,
+            '-' if depth == 0 && matches!(prev, None | Some(' ')) => loosest = 1,
+            '*' | '/' if depth == 0 => loosest = loosest.min(2),
+            '^' if depth == 0 => loosest = loosest.min(3),
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    loosest
+}
+
+/// Precedence an operand needs to sit between the operators around
+/// `chars[start..end]` without parentheses.
+fn required_precedence(chars: &[char], start: usize, end: usize) -> u8 {
+    let before = |idx: usize| chars[..idx].iter().rposition(|&c| c != ' ');
+    let left = before(start).map_or(0, |j| match chars[j] {
+        '+' => 1,
+        '-' if before(j).is_none_or(|k| matches!(chars[k], '(' | ',')) => 3,
+        '*' | '-' => 2,
+        '/' => 3,
+        '^' => 4,
+        _ => 0,
+    });
+    let right = match chars[end..].iter().find(|&&c| c != ' ') {
+        Some('+' | '-') => 1,
+        Some('*' | '/') => 2,
+        Some('^') => 4,
+        _ => 0,
+    };
+    left.max(right)
+}
+
+/// Replace the register and pool references of an instruction's right-hand
+/// side with their symbolic operand texts.
+fn render_operands(rhs: &str, operands: &[Option<String>], arg_pool: &[u32]) -> String {
+    let chars: Vec<char> = rhs.chars().collect();
+    let mut out = String::with_capacity(rhs.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let prev = i.checked_sub(1).and_then(|p| chars.get(p)).copied();
+        let starts_token = !prev.is_some_and(|p| p.is_alphanumeric() || p == '_');
+
+        if starts_token && chars[i..].starts_with(&['p', 'o', 'o', 'l', '[']) {
+            let start = read_number(&chars, i + 5);
+            let end = start.and_then(|(_, next)| {
+                chars[next..]
+                    .starts_with(&['.', '.'])
+                    .then(|| read_number(&chars, next + 2))
+                    .flatten()
+            });
+            if let (Some((start, _)), Some((end, next))) = (start, end)
+                && chars.get(next) == Some(&']')
+            {
+                let texts: Vec<String> = arg_pool
+                    .get(start as usize..end as usize)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|&reg| operand_text(operands, reg))
+                    .collect();
+                out.push_str(&texts.join(", "));
+                i = next + 1;
+                continue;
+            }
+        }
+
+        if starts_token
+            && chars[i] == 'R'
+            && let Some((reg, next)) = read_number(&chars, i + 1)
+        {
+            let text = operand_text(operands, reg);
+            if precedence(&text) >= required_precedence(&chars, i, next) {
+                out.push_str(&text);
+            } else {
+                write!(out, "({text})").expect("Writing to a String cannot fail");
+            }
+            i = next;
+            continue;
+        }
+
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Parse the decimal number starting at `start`, returning it with the index
+/// just past its last digit.
+fn read_number(chars: &[char], start: usize) -> Option<(u32, usize)> {
+    let digits = chars
+        .get(start..)?
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return None;
+    }
+    let value = chars[start..start + digits]
+        .iter()
+        .try_fold(0_u32, |acc, c| {
+            acc.checked_mul(10)?.checked_add(c.to_digit(10)?)
+        })?;
+    Some((value, start + digits))
+}
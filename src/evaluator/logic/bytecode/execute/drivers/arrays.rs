@@ -0,0 +1,126 @@
+//! Batch evaluation over `ndarray` and `nalgebra` inputs (`ndarray` / `nalgebra` features).
+//!
+//! Each parameter is one array. Arrays of length 1 broadcast across the batch, as a
+//! scalar parameter would; every other array must have the batch length. Contiguous
+//! arrays are read in place, and only strided ones are copied into a column first.
+
+use std::borrow::Cow;
+
+use super::CompiledEvaluator;
+use super::batch::{broadcast_len, run_broadcast_evaluator};
+use crate::core::DiffError;
+
+#[cfg(feature = "nalgebra")]
+use nalgebra::{DMatrix, DVector};
+#[cfg(feature = "ndarray")]
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+
+impl CompiledEvaluator {
+    /// Evaluate over one batch of columns, broadcasting length-1 columns
+    fn eval_broadcast(&self, columns: &[Cow<'_, [f64]>]) -> Result<Vec<f64>, DiffError> {
+        if columns.len() != self.param_count {
+            return Err(DiffError::EvalColumnMismatch {
+                expected: self.param_count,
+                got: columns.len(),
+            });
+        }
+        let lengths: Vec<usize> = columns.iter().map(|c| c.len()).collect();
+        let n_points = broadcast_len(&lengths)?;
+        let columns: Vec<&[f64]> = columns.iter().map(AsRef::as_ref).collect();
+        let mut output = vec![0.0; n_points];
+        run_broadcast_evaluator(self, &columns, &mut output)?;
+        Ok(output)
+    }
+
+    /// Evaluate at every point of a batch given as one `ndarray` array per parameter
+    ///
+    /// Arrays of length 1 are broadcast across the batch, so scalar parameters can be
+    /// passed as `array![value]`; all other arrays must share one length. A program
+    /// without parameters evaluates once.
+    ///
+    /// # Example
+    /// ```
+    /// use ndarray::array;
+    /// use symb_anafis::{CompiledEvaluator, symb};
+    ///
+    /// let (x, k) = (symb("x"), symb("k"));
+    /// let eval = CompiledEvaluator::compile(&(k * x), &["x", "k"], None).unwrap();
+    /// let xs = array![1.0, 2.0, 3.0];
+    /// let out = eval.eval_ndarray(&[xs.view(), array![10.0].view()]).unwrap();
+    /// assert_eq!(out, array![10.0, 20.0, 30.0]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::EvalColumnMismatch`] if the number of arrays is not the
+    /// parameter count, and [`DiffError::EvalBroadcastMismatch`] (naming every length)
+    /// if two arrays longer than 1 differ in length.
+    #[cfg(feature = "ndarray")]
+    pub fn eval_ndarray(&self, params: &[ArrayView1<'_, f64>]) -> Result<Array1<f64>, DiffError> {
+        self.eval_broadcast(&ndarray_columns(params))
+            .map(Array1::from_vec)
+    }
+
+    /// Evaluate at every row of `points`, whose columns are the parameters in order
+    ///
+    /// Contiguous columns (column-major, or a single parameter) are read in place;
+    /// row-major columns are gathered into a buffer each.
+    ///
+    /// # Errors
+    /// Returns [`DiffError::EvalColumnMismatch`] if the column count is not the
+    /// parameter count.
+    #[cfg(feature = "ndarray")]
+    #[allow(
+        clippy::needless_pass_by_value,
+        reason = "Views are cheap handles and ndarray passes them by value"
+    )]
+    pub fn eval_ndarray_2d(&self, points: ArrayView2<'_, f64>) -> Result<Array1<f64>, DiffError> {
+        let columns: Vec<ArrayView1<'_, f64>> = (0..points.ncols())
+            .map(|col| points.index_axis_move(Axis(1), col))
+            .collect();
+        self.eval_ndarray(&columns)
+    }
+
+    /// Evaluate at every point of a batch given as one `nalgebra` vector per parameter
+    ///
+    /// Broadcasts length-1 vectors like [`eval_ndarray`](Self::eval_ndarray); vectors
+    /// are always contiguous, so they are read in place.
+    ///
+    /// # Errors
+    /// Same as [`eval_ndarray`](Self::eval_ndarray).
+    #[cfg(feature = "nalgebra")]
+    pub fn eval_nalgebra(&self, params: &[&DVector<f64>]) -> Result<DVector<f64>, DiffError> {
+        let columns: Vec<Cow<'_, [f64]>> = params
+            .iter()
+            .map(|param| Cow::Borrowed(param.as_slice()))
+            .collect();
+        self.eval_broadcast(&columns).map(DVector::from_vec)
+    }
+
+    /// Evaluate at every row of `points`, whose columns are the parameters in order
+    ///
+    /// `nalgebra` stores matrices column-major, so every column is read in place.
+    ///
+    /// # Errors
+    /// Returns [`DiffError::EvalColumnMismatch`] if the column count is not the
+    /// parameter count.
+    #[cfg(feature = "nalgebra")]
+    pub fn eval_nalgebra_matrix(&self, points: &DMatrix<f64>) -> Result<DVector<f64>, DiffError> {
+        let rows = points.nrows();
+        let columns: Vec<Cow<'_, [f64]>> = (0..points.ncols())
+            .map(|col| Cow::Borrowed(&points.as_slice()[col * rows..(col + 1) * rows]))
+            .collect();
+        self.eval_broadcast(&columns).map(DVector::from_vec)
+    }
+}
+
+/// The parameter columns, borrowed when contiguous and copied when strided
+#[cfg(feature = "ndarray")]
+pub(super) fn ndarray_columns<'data>(params: &[ArrayView1<'data, f64>]) -> Vec<Cow<'data, [f64]>> {
+    params
+        .iter()
+        .map(|view| {
+            view.to_slice()
+                .map_or_else(|| Cow::Owned(view.to_vec()), Cow::Borrowed)
+        })
+        .collect()
+}
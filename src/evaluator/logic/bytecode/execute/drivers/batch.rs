@@ -1,13 +1,23 @@
-//! Internal batch helpers for compiled evaluator execution.
+//! Batch helpers for compiled evaluator execution.
+//!
+//! With the `parallel` feature this also holds
+//! [`CompiledEvaluator::eval_batch_parallel`], which spreads `eval_batch` over Rayon.
 
 use super::{CompiledEvaluator, ToParamName};
 use crate::core::{DiffError, Expr};
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
+#[cfg(feature = "parallel")]
 use wide::f64x4;
 
 // 256 points × 4 lanes × 8 bytes ≈ 8 KB register traffic per chunk, fits in L1
+#[cfg(feature = "parallel")]
 const CHUNK_SIZE: usize = 256;
 
+/// Points per SIMD lane group; parallel chunks are rounded up to a multiple of it
+#[cfg(feature = "parallel")]
+const LANES: usize = 4;
+
 /// Evaluates a single expression in chunks for parallel processing.
 pub fn eval_single_expr_chunked<V: ToParamName>(
     expr: &Expr,
@@ -34,6 +44,10 @@ pub fn eval_single_expr_chunked<V: ToParamName>(
     Ok(output)
 }
 
+/// Evaluates `evaluator` over equal-length columns into `output`.
+///
+/// Parallel builds split the work into SIMD chunks on Rayon; serial builds run the
+/// scalar batch loop.
 pub fn run_chunked_evaluator(
     evaluator: &CompiledEvaluator,
     columns: &[&[f64]],
@@ -49,30 +63,189 @@ pub fn run_chunked_evaluator(
         return Err(DiffError::EvalColumnLengthMismatch);
     }
 
-    if n_points < CHUNK_SIZE {
+    run_chunks(evaluator, columns, output)
+}
+
+/// Evaluates `evaluator` into `output`, repeating length-1 columns across every point.
+///
+/// # Errors
+/// Returns `EvalColumnMismatch` if the column count is not the parameter count, and
+/// `EvalBroadcastMismatch` if a column longer than one point is not `output.len()` long.
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+pub fn run_broadcast_evaluator(
+    evaluator: &CompiledEvaluator,
+    columns: &[&[f64]],
+    output: &mut [f64],
+) -> Result<(), DiffError> {
+    if columns.len() != evaluator.param_count {
+        return Err(DiffError::EvalColumnMismatch {
+            expected: evaluator.param_count,
+            got: columns.len(),
+        });
+    }
+    if columns
+        .iter()
+        .any(|c| c.len() != 1 && c.len() != output.len())
+    {
+        return Err(DiffError::EvalBroadcastMismatch {
+            lengths: columns.iter().map(|c| c.len()).collect(),
+        });
+    }
+    if output.is_empty() {
+        return Ok(());
+    }
+
+    run_chunks(evaluator, columns, output)
+}
+
+/// Length of the batch that `lengths` broadcast to: the one length other than 1, or 1
+///
+/// # Errors
+/// Returns `EvalBroadcastMismatch` naming every length when two lengths other than 1
+/// differ.
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+pub fn broadcast_len(lengths: &[usize]) -> Result<usize, DiffError> {
+    let mut long = lengths.iter().copied().filter(|&len| len != 1);
+    let n_points = long.next().unwrap_or(1);
+    if long.any(|len| len != n_points) {
+        return Err(DiffError::EvalBroadcastMismatch {
+            lengths: lengths.to_vec(),
+        });
+    }
+    Ok(n_points)
+}
+
+/// The shared chunk loop; columns are either `output.len()` long or a single point.
+#[cfg_attr(
+    not(feature = "parallel"),
+    allow(
+        clippy::unnecessary_wraps,
+        reason = "Only the parallel engine can fail"
+    )
+)]
+fn run_chunks(
+    evaluator: &CompiledEvaluator,
+    columns: &[&[f64]],
+    output: &mut [f64],
+) -> Result<(), DiffError> {
+    #[cfg(not(feature = "parallel"))]
+    evaluator.eval_batch_scalar(columns, output);
+
+    #[cfg(feature = "parallel")]
+    if output.len() < CHUNK_SIZE {
         evaluator.eval_batch(columns, output, None)?;
     } else {
-        output
-            .par_chunks_mut(CHUNK_SIZE)
-            .enumerate()
-            .try_for_each_init(
-                || {
-                    (
-                        vec![f64x4::splat(0.0); evaluator.workspace_size],
-                        Vec::with_capacity(columns.len()),
-                    )
-                },
-                |(simd_buffer, col_slices), (chunk_idx, chunk_out)| {
-                    let start = chunk_idx * CHUNK_SIZE;
-                    let end = start + chunk_out.len();
-                    col_slices.clear();
-                    for col in columns {
-                        col_slices.push(&col[start..end]);
-                    }
-                    evaluator.eval_batch(col_slices, chunk_out, Some(simd_buffer))
-                },
-            )?;
+        run_parallel_chunks(evaluator, columns, output, CHUNK_SIZE)?;
     }
 
     Ok(())
 }
+
+/// Evaluates `output` in `chunk_size`-point chunks on Rayon, one SIMD workspace per thread.
+///
+/// Columns are either `output.len()` long or a single point.
+#[cfg(feature = "parallel")]
+pub(super) fn run_parallel_chunks(
+    evaluator: &CompiledEvaluator,
+    columns: &[&[f64]],
+    output: &mut [f64],
+    chunk_size: usize,
+) -> Result<(), DiffError> {
+    output
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .try_for_each_init(
+            || {
+                (
+                    vec![f64x4::splat(0.0); evaluator.workspace_size],
+                    Vec::with_capacity(columns.len()),
+                )
+            },
+            |(simd_buffer, col_slices), (chunk_idx, chunk_out)| {
+                let start = chunk_idx * chunk_size;
+                let end = start + chunk_out.len();
+                col_slices.clear();
+                for col in columns {
+                    // A single point is repeated by the batch loops as it is
+                    col_slices.push(if col.len() == 1 {
+                        col
+                    } else {
+                        &col[start..end]
+                    });
+                }
+                evaluator.eval_batch(col_slices, chunk_out, Some(simd_buffer))
+            },
+        )
+}
+
+#[cfg(feature = "parallel")]
+impl CompiledEvaluator {
+    /// [`eval_batch`](Self::eval_batch) over the Rayon thread pool, in chunks of 256 points
+    ///
+    /// `inputs[i]` holds parameter `i` at every point, or a single value shared by all
+    /// points, and `output[p]` receives the result at point `p`. Each chunk runs on the
+    /// SIMD engine with its thread's own workspace; the results are the same as one
+    /// `eval_batch` call over the whole range.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, symb};
+    ///
+    /// let (x, a) = (symb("x"), symb("a"));
+    /// let eval = CompiledEvaluator::compile(&(a * x + 1.0), &["x", "a"], None)?;
+    /// let xs: Vec<f64> = (0..100_000).map(f64::from).collect();
+    /// let mut out = vec![0.0; xs.len()];
+    /// eval.eval_batch_parallel(&[&xs, &[2.0]], &mut out)?;
+    /// assert_eq!(out[99_999], 199_999.0);
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::EvalColumnMismatch`] if the input count is not the
+    /// parameter count, and [`DiffError::EvalColumnLengthMismatch`] if an input is
+    /// neither `output.len()` long nor a single value.
+    pub fn eval_batch_parallel(
+        &self,
+        inputs: &[&[f64]],
+        output: &mut [f64],
+    ) -> Result<(), DiffError> {
+        self.eval_batch_parallel_with_chunk_size(inputs, output, CHUNK_SIZE)
+    }
+
+    /// [`eval_batch_parallel`](Self::eval_batch_parallel) with `chunk_size` points per
+    /// task
+    ///
+    /// `chunk_size` is rounded up to a whole number of SIMD lane groups (a multiple of
+    /// 4). Small chunks balance expensive points across threads; large ones keep the
+    /// per-chunk overhead down. A batch of at most one chunk runs on the calling thread.
+    ///
+    /// # Errors
+    /// As [`eval_batch_parallel`](Self::eval_batch_parallel).
+    pub fn eval_batch_parallel_with_chunk_size(
+        &self,
+        inputs: &[&[f64]],
+        output: &mut [f64],
+        chunk_size: usize,
+    ) -> Result<(), DiffError> {
+        if inputs.len() != self.param_count {
+            return Err(DiffError::EvalColumnMismatch {
+                expected: self.param_count,
+                got: inputs.len(),
+            });
+        }
+        if inputs
+            .iter()
+            .any(|input| input.len() != output.len() && input.len() != 1)
+        {
+            return Err(DiffError::EvalColumnLengthMismatch);
+        }
+
+        let chunk_size = chunk_size.max(1).next_multiple_of(LANES);
+        if output.len() > chunk_size {
+            run_parallel_chunks(self, inputs, output, chunk_size)
+        } else {
+            let mut workspace = vec![f64x4::splat(0.0); self.workspace_size];
+            self.eval_batch(inputs, output, Some(&mut workspace))
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Serial/parallel iteration helpers selected by the `parallel` feature.
+//!
+//! Drivers express their loops through these helpers so the public entry points keep
+//! the same names and signatures with and without Rayon; only the execution strategy
+//! changes.
+
+use crate::core::DiffError;
+#[cfg(feature = "parallel")]
+use rayon::ThreadPool;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Maps `0..n` through a fallible function and collects the results in order.
+pub fn try_map_range<T, F>(n: usize, f: F) -> Result<Vec<T>, DiffError>
+where
+    T: Send,
+    F: Fn(usize) -> Result<T, DiffError> + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..n).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..n).map(f).collect()
+    }
+}
+
+/// Like [`try_map_range`], with per-worker scratch state created by `init`.
+///
+/// `min_len` is the minimum number of items handed to a worker in parallel builds.
+pub fn try_map_range_init<T, S, I, F>(
+    n: usize,
+    min_len: usize,
+    init: I,
+    f: F,
+) -> Result<Vec<T>, DiffError>
+where
+    T: Send,
+    I: Fn() -> S + Sync + Send,
+    F: Fn(&mut S, usize) -> Result<T, DiffError> + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..n)
+            .into_par_iter()
+            .with_min_len(min_len)
+            .map_init(init, f)
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let _ = min_len;
+        let mut state = init();
+        (0..n).map(|i| f(&mut state, i)).collect()
+    }
+}
+
+/// Runs `op` inside `pool` when given, otherwise on Rayon's global pool.
+#[cfg(feature = "parallel")]
+pub fn in_pool<R, OP>(pool: Option<&ThreadPool>, op: OP) -> R
+where
+    R: Send,
+    OP: FnOnce() -> R + Send,
+{
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
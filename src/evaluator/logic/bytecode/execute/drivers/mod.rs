@@ -1,22 +1,26 @@
 //! Sub-module for bulk execution drivers (batch processing, multi-threading).
+//!
+//! Every driver is available with and without the `parallel` feature; the feature
+//! only switches the execution strategy (see [`dispatch`]).
 
-#[cfg(feature = "parallel")]
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+mod arrays;
 pub mod batch;
-
-#[cfg(feature = "parallel")]
+pub mod dispatch;
 pub mod parallel;
+mod table;
 
-#[cfg(feature = "parallel")]
 pub use batch::eval_single_expr_chunked;
 
-#[cfg(feature = "parallel")]
 pub use parallel::{EvalResult, ExprInput, SKIP, Value, VarInput, evaluate_parallel};
 
+#[cfg(feature = "parallel")]
+pub use parallel::evaluate_parallel_with_pool;
+
 #[cfg(all(feature = "parallel", feature = "python"))]
 pub use parallel::evaluate_parallel_with_hint;
 
-#[cfg(feature = "parallel")]
 pub use super::{CompiledEvaluator, ToParamName};
 
-#[cfg(all(test, feature = "parallel"))]
+#[cfg(test)]
 mod tests;
@@ -3,15 +3,16 @@
 //! This module provides parallel evaluation of multiple expressions
 //! with flexible input types (Expr or string) and type-preserving output.
 //!
-//! Enable with the `parallel` feature:
+//! The API is available with or without the `parallel` feature and has the same
+//! shape in both builds. Without the feature every loop runs sequentially; with it
+//! the work is spread over Rayon, and the `*_with_pool` variants accept an explicit
+//! `rayon::ThreadPool`:
 //! ```toml
 //! symb_anafis = { version = "0.3", features = ["parallel"] }
 //! ```
 //!
 //! # Example
 //! ```rust
-//! # #[cfg(feature = "parallel")]
-//! # {
 //! use symb_anafis::{eval_parallel, symb};
 //! use symb_anafis::SKIP;
 //!
@@ -26,7 +27,6 @@
 //!         [[1.0, 2.0, 3.0]]
 //!     ]
 //! );
-//! # }
 //! ```
 
 #![allow(
@@ -36,10 +36,13 @@
 
 use super::CompiledEvaluator;
 use super::batch::run_chunked_evaluator;
+#[cfg(feature = "parallel")]
+use super::dispatch::in_pool;
+use super::dispatch::{try_map_range, try_map_range_init};
 use crate::core::{DiffError, Expr, Symbol, symb};
 use crate::parser::parse;
 #[cfg(feature = "parallel")]
-use rayon::prelude::*;
+use rayon::ThreadPool;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter, Result as FmtResult};
@@ -323,6 +326,23 @@ pub fn evaluate_parallel(
     evaluate_parallel_with_hint(exprs, var_names, values, None)
 }
 
+/// [`evaluate_parallel`] running on a caller-provided Rayon thread pool.
+///
+/// With `pool = None` this is identical to [`evaluate_parallel`], which uses the
+/// global pool.
+///
+/// # Errors
+/// Same as [`evaluate_parallel`].
+#[cfg(feature = "parallel")]
+pub fn evaluate_parallel_with_pool(
+    pool: Option<&ThreadPool>,
+    exprs: Vec<ExprInput>,
+    var_names: Vec<Vec<VarInput>>,
+    values: Vec<Vec<Vec<Value>>>,
+) -> Result<Vec<Vec<EvalResult>>, DiffError> {
+    in_pool(pool, || evaluate_parallel(exprs, var_names, values))
+}
+
 /// Parallel evaluation with optional pre-computed numeric hints.
 ///
 /// When `is_fully_numeric` is provided, it tells the Rust side whether each expression's
@@ -496,17 +516,69 @@ pub fn evaluate_parallel_with_hint(
                 .map(|(i, _)| i)
                 .collect();
 
-            // Use Rayon directly on the range to avoid allocating index vectors
+            // Map directly over the point range to avoid allocating index vectors
             let results: Vec<EvalResult> = if mixed_cols.is_empty() {
                 // Pure fast path — no per-point check needed at all
-                (0..n_points)
-                    .into_par_iter()
-                    .with_min_len(256)
-                    .map_init(
-                        || (vec![0.0; n_vars], vec![0.0; evaluator.workspace_size]),
-                        |buffers, point_idx| {
-                            let (params, workspace) = buffers;
+                try_map_range_init(
+                    n_points,
+                    256,
+                    || (vec![0.0; n_vars], vec![0.0; evaluator.workspace_size]),
+                    |buffers, point_idx| {
+                        let (params, workspace) = buffers;
+
+                        for (i, var_vals) in expr_values.iter().take(n_vars).enumerate() {
+                            let val = var_vals.get(point_idx).unwrap_or_else(|| {
+                                var_vals
+                                    .last()
+                                    .expect("Column cannot be empty (validated earlier)")
+                            });
+
+                            if let Value::Num(n) = val {
+                                params[i] = *n;
+                            } else {
+                                debug_assert!(false, "Non-numeric value in pure numeric path");
+                                // SAFETY: mixed_cols.is_empty() guarantees all values are Value::Num
+                                unsafe {
+                                    unreachable_unchecked();
+                                }
+                            }
+                        }
 
+                        let r = evaluator.evaluate_heap(params, workspace);
+
+                        Ok(if *was_string {
+                            EvalResult::String(format_float(r))
+                        } else {
+                            EvalResult::Expr(Expr::number(r))
+                        })
+                    },
+                )?
+            } else {
+                try_map_range_init(
+                    n_points,
+                    256,
+                    || (vec![0.0; n_vars], vec![0.0; evaluator.workspace_size]),
+                    |buffers, point_idx| {
+                        let (params, workspace) = buffers;
+
+                        // Check if this specific point has all numeric inputs
+                        let mut all_numeric = true;
+                        for &col_idx in &mixed_cols {
+                            let var_vals = &expr_values[col_idx];
+                            let val = var_vals.get(point_idx).unwrap_or_else(|| {
+                                var_vals
+                                    .last()
+                                    .expect("Column cannot be empty (validated earlier)")
+                            });
+
+                            if !matches!(val, Value::Num(_)) {
+                                all_numeric = false;
+                                break;
+                            }
+                        }
+
+                        if all_numeric {
+                            // FAST PATH: Run compiled code
                             for (i, var_vals) in expr_values.iter().take(n_vars).enumerate() {
                                 let val = var_vals.get(point_idx).unwrap_or_else(|| {
                                     var_vals
@@ -517,11 +589,9 @@ pub fn evaluate_parallel_with_hint(
                                 if let Value::Num(n) = val {
                                     params[i] = *n;
                                 } else {
-                                    debug_assert!(false, "Non-numeric value in pure numeric path");
-                                    // SAFETY: mixed_cols.is_empty() guarantees all values are Value::Num
-                                    unsafe {
-                                        unreachable_unchecked();
-                                    }
+                                    return Err(DiffError::UnsupportedOperation(
+                                        "Value must be Num after all_numeric check".to_owned(),
+                                    ));
                                 }
                             }
 
@@ -532,83 +602,20 @@ pub fn evaluate_parallel_with_hint(
                             } else {
                                 EvalResult::Expr(Expr::number(r))
                             })
-                        },
-                    )
-                    .collect::<Result<Vec<_>, DiffError>>()?
-            } else {
-                (0..n_points)
-                    .into_par_iter()
-                    .with_min_len(256)
-                    .map_init(
-                        || (vec![0.0; n_vars], vec![0.0; evaluator.workspace_size]),
-                        |buffers, point_idx| {
-                            let (params, workspace) = buffers;
-
-                            // Check if this specific point has all numeric inputs
-                            let mut all_numeric = true;
-                            for &col_idx in &mixed_cols {
-                                let var_vals = &expr_values[col_idx];
-                                let val = var_vals.get(point_idx).unwrap_or_else(|| {
-                                    var_vals
-                                        .last()
-                                        .expect("Column cannot be empty (validated earlier)")
-                                });
-
-                                if !matches!(val, Value::Num(_)) {
-                                    all_numeric = false;
-                                    break;
-                                }
-                            }
-
-                            if all_numeric {
-                                // FAST PATH: Run compiled code
-                                for (i, var_vals) in expr_values.iter().take(n_vars).enumerate() {
-                                    let val = var_vals.get(point_idx).unwrap_or_else(|| {
-                                        var_vals
-                                            .last()
-                                            .expect("Column cannot be empty (validated earlier)")
-                                    });
-
-                                    if let Value::Num(n) = val {
-                                        params[i] = *n;
-                                    } else {
-                                        return Err(DiffError::UnsupportedOperation(
-                                            "Value must be Num after all_numeric check".to_owned(),
-                                        ));
-                                    }
-                                }
-
-                                let r = evaluator.evaluate_heap(params, workspace);
-
-                                Ok(if *was_string {
-                                    EvalResult::String(format_float(r))
-                                } else {
-                                    EvalResult::Expr(Expr::number(r))
-                                })
-                            } else {
-                                // SLOW PATH: Fallback for this point
-                                evaluate_slow_point(
-                                    expr,
-                                    &vars,
-                                    expr_values,
-                                    point_idx,
-                                    *was_string,
-                                )
-                            }
-                        },
-                    )
-                    .collect::<Result<Vec<_>, _>>()?
+                        } else {
+                            // SLOW PATH: Fallback for this point
+                            evaluate_slow_point(expr, &vars, expr_values, point_idx, *was_string)
+                        }
+                    },
+                )?
             };
             Ok(results)
         } else {
             // SLOW PATH: Compilation failed (unsupported function, etc.)
             // Evaluate entirely using substitution
-            let pts: Vec<EvalResult> = (0..n_points)
-                .into_par_iter()
-                .map(|point_idx| {
-                    evaluate_slow_point(expr, &vars, expr_values, point_idx, *was_string)
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            let pts: Vec<EvalResult> = try_map_range(n_points, |point_idx| {
+                evaluate_slow_point(expr, &vars, expr_values, point_idx, *was_string)
+            })?;
             Ok(pts)
         }
     };
@@ -617,10 +624,7 @@ pub fn evaluate_parallel_with_hint(
     let results: Vec<Vec<EvalResult>> = if n_exprs == 1 {
         vec![process_expr(0)?]
     } else {
-        (0..n_exprs)
-            .into_par_iter()
-            .map(process_expr)
-            .collect::<Result<Vec<Vec<_>>, _>>()?
+        try_map_range(n_exprs, process_expr)?
     };
 
     Ok(results)
@@ -728,8 +732,6 @@ macro_rules! __parse_values_inner {
 ///
 /// # Example
 /// ```rust
-/// # #[cfg(feature = "parallel")]
-/// # {
 /// use symb_anafis::{eval_parallel, symb};
 /// use symb_anafis::SKIP;
 ///
@@ -747,7 +749,6 @@ macro_rules! __parse_values_inner {
 ///
 /// // results[0] is Vec<EvalResult::String>
 /// // results[1] is Vec<EvalResult::Expr>
-/// # }
 /// ```
 #[macro_export]
 macro_rules! eval_parallel {
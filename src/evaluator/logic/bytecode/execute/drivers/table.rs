@@ -0,0 +1,116 @@
+//! Evaluation over a table of parameter columns (struct-of-arrays layout).
+//!
+//! `columns[i]` holds every value of parameter `i`, so rows are read in place and no
+//! point is ever gathered into its own slice. With the `parallel` feature rows run four
+//! at a time on the SIMD engine, and [`CompiledEvaluator::eval_table_parallel`] spreads
+//! chunks of rows over Rayon; without it both run the scalar batch loop.
+
+use super::CompiledEvaluator;
+use crate::core::DiffError;
+#[cfg(feature = "parallel")]
+use wide::f64x4;
+
+/// Points per SIMD lane group; parallel chunks are rounded up to a multiple of it
+#[cfg(feature = "parallel")]
+const LANES: usize = 4;
+
+impl CompiledEvaluator {
+    /// Number of rows in `columns`, checked against the parameters and `out`
+    fn table_rows(&self, columns: &[&[f64]], out: &[f64]) -> Result<usize, DiffError> {
+        if columns.len() != self.param_count {
+            return Err(DiffError::EvalColumnMismatch {
+                expected: self.param_count,
+                got: columns.len(),
+            });
+        }
+        // A program without parameters fills the whole output
+        let rows = columns.first().map_or(out.len(), |c| c.len());
+        if columns.iter().any(|c| c.len() != rows) {
+            return Err(DiffError::EvalColumnLengthMismatch);
+        }
+        if out.len() < rows {
+            return Err(DiffError::EvalOutputTooSmall {
+                needed: rows,
+                got: out.len(),
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Evaluate at every row of a table given as one column per parameter
+    ///
+    /// `columns[i]` holds all values of parameter `i`, and `out[row]` receives the
+    /// result for that row; a longer `out` keeps its values past the last row. Rows are
+    /// evaluated in place, four at a time with the `parallel` feature, with the last
+    /// `rows % 4` handled as a partial lane group.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, symb};
+    ///
+    /// let (x, y) = (symb("x"), symb("y"));
+    /// let eval = CompiledEvaluator::compile(&(x * y + 1.0), &["x", "y"], None)?;
+    /// let mut out = [0.0; 5];
+    /// eval.eval_table(&[&[1.0, 2.0, 3.0, 4.0, 5.0], &[10.0; 5]], &mut out)?;
+    /// assert_eq!(out, [11.0, 21.0, 31.0, 41.0, 51.0]);
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::EvalColumnMismatch`] if the column count is not the
+    /// parameter count, [`DiffError::EvalColumnLengthMismatch`] if the columns differ in
+    /// length, and [`DiffError::EvalOutputTooSmall`] if `out` is shorter than a column.
+    pub fn eval_table(&self, columns: &[&[f64]], out: &mut [f64]) -> Result<(), DiffError> {
+        let rows = self.table_rows(columns, out)?;
+        let out = &mut out[..rows];
+
+        #[cfg(feature = "parallel")]
+        self.eval_batch(
+            columns,
+            out,
+            Some(&mut vec![f64x4::splat(0.0); self.workspace_size]),
+        )?;
+
+        #[cfg(not(feature = "parallel"))]
+        if rows > 0 {
+            self.eval_batch_scalar(columns, out);
+        }
+
+        Ok(())
+    }
+
+    /// [`eval_table`](Self::eval_table) split into chunks of `chunk_size` rows, evaluated
+    /// on the Rayon thread pool
+    ///
+    /// `chunk_size` is rounded up to a whole number of SIMD lane groups (a multiple of
+    /// 4). Small chunks balance uneven rows across threads; large ones keep the per-chunk
+    /// overhead down. A table of at most one chunk runs on the calling thread. Without
+    /// the `parallel` feature this is [`eval_table`](Self::eval_table).
+    ///
+    /// # Errors
+    /// As [`eval_table`](Self::eval_table).
+    pub fn eval_table_parallel(
+        &self,
+        columns: &[&[f64]],
+        out: &mut [f64],
+        chunk_size: usize,
+    ) -> Result<(), DiffError> {
+        #[cfg(feature = "parallel")]
+        {
+            let rows = self.table_rows(columns, out)?;
+            let chunk_size = chunk_size.max(1).next_multiple_of(LANES);
+            if rows > chunk_size {
+                return super::batch::run_parallel_chunks(
+                    self,
+                    columns,
+                    &mut out[..rows],
+                    chunk_size,
+                );
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        let _ = chunk_size;
+
+        self.eval_table(columns, out)
+    }
+}
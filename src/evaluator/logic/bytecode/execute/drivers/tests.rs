@@ -1,3 +1,4 @@
+use super::parallel::{ExprInput, SKIP, Value, VarInput};
 use crate::{Expr, core::ExprKind, eval_parallel, symb};
 
 const fn get_num(expr: &Expr) -> f64 {
@@ -108,3 +109,102 @@ fn test_skip_value() {
     assert!(result_str.contains('x'));
     assert!(result_str.contains('5'));
 }
+
+// =============================================================================
+// Serial / parallel parity
+//
+// These tests compile with and without the `parallel` feature. Parallel builds
+// inject a dedicated thread pool; serial builds call the identically named
+// sequential functions.
+// =============================================================================
+
+#[cfg(feature = "parallel")]
+fn test_pool() -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .expect("Should build pool")
+}
+
+fn eval_numeric(exprs: &[&Expr], vars: &[&[&str]], data: &[&[&[f64]]]) -> Vec<Vec<f64>> {
+    #[cfg(feature = "parallel")]
+    let results = crate::eval_f64_with_pool(Some(&test_pool()), exprs, vars, data);
+    #[cfg(not(feature = "parallel"))]
+    let results = crate::eval_f64(exprs, vars, data);
+    results.expect("Should evaluate")
+}
+
+#[test]
+fn test_eval_f64_matches_scalar_evaluation() {
+    let x = symb("x");
+    let y = symb("y");
+    let expr = x.pow(2.0) + y.sin();
+
+    let xs: Vec<f64> = (0..1000).map(|i| f64::from(i) * 0.01).collect();
+    let ys: Vec<f64> = (0..1000).map(|i| f64::from(i) * -0.003).collect();
+
+    let pooled = eval_numeric(&[&expr], &[&["x", "y"]], &[&[&xs, &ys]]);
+    let global = crate::eval_f64(&[&expr], &[&["x", "y"]], &[&[&xs, &ys]]).expect("Should pass");
+    assert_eq!(pooled, global);
+
+    let compiled = crate::CompiledEvaluator::compile(&expr, &["x", "y"], None).expect("compile");
+    for (i, value) in pooled[0].iter().enumerate() {
+        assert_eq!(
+            value.to_bits(),
+            compiled.evaluate(&[xs[i], ys[i]]).to_bits()
+        );
+    }
+}
+
+#[test]
+fn test_eval_f64_rejects_mismatched_lengths() {
+    let x = symb("x");
+    let expr = x.pow(2.0);
+    let data = [1.0, 2.0];
+    let result = crate::eval_f64(&[&expr, &expr], &[&["x"]], &[&[&data]]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_evaluate_parallel_same_shape_in_every_build() {
+    let x = symb("x");
+    let exprs = vec![ExprInput::from("x*y"), ExprInput::from(x.pow(2.0))];
+    let vars = vec![
+        vec![VarInput::from("x"), VarInput::from("y")],
+        vec![VarInput::from("x")],
+    ];
+    let values = vec![
+        vec![
+            vec![Value::from(2.0), Value::from(3.0)],
+            vec![Value::from(4.0), Value::from(5.0)],
+        ],
+        vec![vec![Value::from(1.5), SKIP]],
+    ];
+
+    #[cfg(feature = "parallel")]
+    let results =
+        super::parallel::evaluate_parallel_with_pool(Some(&test_pool()), exprs, vars, values);
+    #[cfg(not(feature = "parallel"))]
+    let results = super::parallel::evaluate_parallel(exprs, vars, values);
+    let results = results.expect("Should pass");
+
+    assert_eq!(results[0][0].to_string(), "8");
+    assert_eq!(results[0][1].to_string(), "15");
+    assert!((get_num(&results[1][0].clone().unwrap_expr()) - 2.25).abs() < 1e-12);
+    assert_eq!(results[1][1].to_string(), "x^2");
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray_columns_borrow_contiguous_inputs() {
+    use std::borrow::Cow;
+
+    let xs = ndarray::array![1.0, 2.0, 3.0, 4.0];
+    let grid = ndarray::Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0]).expect("shape");
+    let strided = grid.column(0);
+    let columns = super::arrays::ndarray_columns(&[xs.view(), strided]);
+
+    assert!(matches!(&columns[0], Cow::Borrowed(slice) if slice.as_ptr() == xs.as_ptr()));
+    // A column of a row-major matrix is strided and has to be gathered
+    assert!(matches!(&columns[1], Cow::Owned(column) if column == &[1.0, 3.0]));
+}
@@ -4,12 +4,14 @@
 //! of each mathematical operation, improving modularity and maintainability.
 
 use super::helpers::{eval_sinc, round_to_i32};
+use crate::core::NumericFn;
 use crate::evaluator::logic::bytecode::FnOp;
 use crate::math::{
-    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_digamma,
-    eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc, eval_exp_polar, eval_gamma,
-    eval_hermite, eval_lambert_w, eval_lgamma, eval_polygamma, eval_spherical_harmonic,
-    eval_tetragamma, eval_trigamma, eval_zeta, eval_zeta_deriv,
+    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_clamp,
+    eval_digamma, eval_dirac, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc,
+    eval_exp_polar, eval_gamma, eval_heaviside, eval_hermite, eval_lambert_w, eval_lgamma,
+    eval_max, eval_min, eval_polygamma, eval_select, eval_spherical_harmonic, eval_tetragamma,
+    eval_trigamma, eval_zeta, eval_zeta_deriv,
 };
 #[cfg(feature = "parallel")]
 use std::array::from_fn;
@@ -65,6 +67,8 @@ pub fn eval_builtin1(op: FnOp, x: f64) -> f64 {
         FnOp::Cbrt => x.cbrt(),
         FnOp::Abs => x.abs(),
         FnOp::Signum => x.signum(),
+        FnOp::Heaviside => eval_heaviside(x),
+        FnOp::Dirac => eval_dirac(x),
         FnOp::Floor => x.floor(),
         FnOp::Ceil => x.ceil(),
         FnOp::Round => x.round(),
@@ -90,6 +94,7 @@ pub fn eval_builtin1(op: FnOp, x: f64) -> f64 {
 pub fn eval_builtin2(op: FnOp, x1: f64, x2: f64) -> f64 {
     match op {
         FnOp::Atan2 => x1.atan2(x2),
+        FnOp::Hypot => x1.hypot(x2),
         FnOp::Log =>
         {
             #[allow(
@@ -110,6 +115,11 @@ pub fn eval_builtin2(op: FnOp, x1: f64, x2: f64) -> f64 {
         FnOp::Beta => eval_beta(x1, x2),
         FnOp::ZetaDeriv => round_to_i32(x1).map_or(f64::NAN, |n| eval_zeta_deriv(n, x2)),
         FnOp::Hermite => round_to_i32(x1).map_or(f64::NAN, |n| eval_hermite(n, x2)),
+        FnOp::Min => eval_min(x1, x2),
+        FnOp::Max => eval_max(x1, x2),
+        FnOp::Less | FnOp::LessEq | FnOp::Greater | FnOp::GreaterEq | FnOp::Equal => {
+            op.eval_compare(x1, x2).unwrap_or(f64::NAN)
+        }
         _ => unreachable_builtin(2, op),
     }
 }
@@ -122,6 +132,8 @@ pub fn eval_builtin3(op: FnOp, x1: f64, x2: f64, x3: f64) -> f64 {
             (Some(l), Some(m)) => eval_assoc_legendre(l, m, x3),
             _ => f64::NAN,
         },
+        FnOp::Clamp => eval_clamp(x1, x2, x3),
+        FnOp::Select => eval_select(x1, x2, x3),
         _ => unreachable_builtin(3, op),
     }
 }
@@ -179,6 +191,8 @@ pub fn eval_builtin1_simd(op: FnOp, x: f64x4) -> f64x4 {
         FnOp::Log1p => f64x4::new(arr.map(f64::ln_1p)),
         FnOp::Cbrt => f64x4::new(arr.map(f64::cbrt)),
         FnOp::Signum => f64x4::new(arr.map(f64::signum)),
+        FnOp::Heaviside => f64x4::new(arr.map(eval_heaviside)),
+        FnOp::Dirac => f64x4::new(arr.map(eval_dirac)),
         FnOp::Floor => f64x4::new(arr.map(f64::floor)),
         FnOp::Ceil => f64x4::new(arr.map(f64::ceil)),
         FnOp::Round => f64x4::new(arr.map(f64::round)),
@@ -203,10 +217,22 @@ pub fn eval_builtin1_simd(op: FnOp, x: f64x4) -> f64x4 {
 #[cfg(feature = "parallel")]
 #[inline]
 pub fn eval_builtin2_simd(op: FnOp, x1: f64x4, x2: f64x4) -> f64x4 {
+    match op {
+        FnOp::Min => return simd_min(x1, x2),
+        FnOp::Max => return simd_max(x1, x2),
+        FnOp::Less => return simd_indicator(x1.simd_lt(x2)),
+        FnOp::LessEq => return simd_indicator(x1.simd_le(x2)),
+        FnOp::Greater => return simd_indicator(x1.simd_gt(x2)),
+        FnOp::GreaterEq => return simd_indicator(x1.simd_ge(x2)),
+        FnOp::Equal => return simd_indicator(x1.simd_eq(x2)),
+        _ => {}
+    }
+
     let arr1 = x1.to_array();
     let arr2 = x2.to_array();
     match op {
         FnOp::Atan2 => f64x4::new(from_fn(|i| arr1[i].atan2(arr2[i]))),
+        FnOp::Hypot => f64x4::new(from_fn(|i| arr1[i].hypot(arr2[i]))),
         FnOp::Log => {
             let l = |base: f64, val: f64| {
                 #[allow(
@@ -262,6 +288,12 @@ pub fn eval_builtin2_simd(op: FnOp, x1: f64x4, x2: f64x4) -> f64x4 {
 #[cfg(feature = "parallel")]
 #[inline]
 pub fn eval_builtin3_simd(op: FnOp, x1: f64x4, x2: f64x4, x3: f64x4) -> f64x4 {
+    match op {
+        FnOp::Clamp => return simd_clamp(x1, x2, x3),
+        FnOp::Select => return simd_select(x1, x2, x3),
+        _ => {}
+    }
+
     let arr1 = x1.to_array();
     let arr2 = x2.to_array();
     let arr3 = x3.to_array();
@@ -297,3 +329,80 @@ pub fn eval_builtin4_simd(op: FnOp, x1: f64x4, x2: f64x4, x3: f64x4, x4: f64x4)
         _ => unreachable_simd_builtin(4, op),
     }
 }
+
+/// Arguments of a native callback passed on the stack; longer calls allocate
+const INLINE_EXTERNAL_ARGS: usize = 8;
+
+/// Calls a native user function on `count` arguments, the `i`-th given by `arg(i)`.
+#[inline]
+pub fn call_external(f: &NumericFn, count: usize, arg: impl Fn(usize) -> f64) -> f64 {
+    if count <= INLINE_EXTERNAL_ARGS {
+        let mut args = [0.0; INLINE_EXTERNAL_ARGS];
+        for (i, slot) in args[..count].iter_mut().enumerate() {
+            *slot = arg(i);
+        }
+        f(&args[..count])
+    } else {
+        let args: Vec<f64> = (0..count).map(arg).collect();
+        f(&args)
+    }
+}
+
+/// Calls a native user function once per lane, as it has no SIMD form.
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn call_external_simd(f: &NumericFn, count: usize, arg: impl Fn(usize) -> f64x4) -> f64x4 {
+    f64x4::new(from_fn(|lane| {
+        call_external(f, count, |i| arg(i).to_array()[lane])
+    }))
+}
+
+/// `a` when `x >= 0`, `b` when `x < 0`, and `x` itself when it is NaN
+#[inline]
+pub fn select_non_neg(x: f64, a: f64, b: f64) -> f64 {
+    eval_select(eval_heaviside(x), a, b)
+}
+
+/// Lanewise [`eval_min`]: ties keep `a`, a NaN in either operand propagates
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn simd_min(a: f64x4, b: f64x4) -> f64x4 {
+    (b.simd_lt(a) | b.is_nan()).select(b, a)
+}
+
+/// Lanewise [`eval_max`]: ties keep `a`, a NaN in either operand propagates
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn simd_max(a: f64x4, b: f64x4) -> f64x4 {
+    (b.simd_gt(a) | b.is_nan()).select(b, a)
+}
+
+/// `1` in the lanes of `mask` that are set and `0` in the others
+#[cfg(feature = "parallel")]
+#[inline]
+fn simd_indicator(mask: f64x4) -> f64x4 {
+    mask.select(f64x4::ONE, f64x4::ZERO)
+}
+
+/// Lanewise [`eval_clamp`]
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn simd_clamp(x: f64x4, lo: f64x4, hi: f64x4) -> f64x4 {
+    simd_min(simd_max(x, lo), hi)
+}
+
+/// Lanewise [`eval_select`], blending both branches by the condition mask
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn simd_select(cond: f64x4, a: f64x4, b: f64x4) -> f64x4 {
+    let picked = cond.simd_eq(f64x4::ZERO).select(b, a);
+    cond.is_nan().select(cond, picked)
+}
+
+/// Lanewise [`select_non_neg`]
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn simd_select_non_neg(x: f64x4, a: f64x4, b: f64x4) -> f64x4 {
+    let picked = x.simd_ge(f64x4::ZERO).select(a, b);
+    x.is_nan().select(x, picked)
+}
@@ -0,0 +1,631 @@
+//! Double-double validation engine for the register-based evaluator.
+//!
+//! This is a second, deliberately slow interpreter over the same [`Instruction`]
+//! stream used by the scalar engine. Every register holds an unevaluated sum
+//! `hi + lo` of two `f64`s (about 106 bits of mantissa) together with a running
+//! absolute error bound:
+//!
+//! - `+`, `-`, `*`, `/`, `sqrt` and integer powers are carried out in
+//!   double-double arithmetic, so they only add `~2^-104` relative error.
+//! - Transcendental and special functions fall back to the `f64` builtins. Their
+//!   result is charged one `f64` rounding plus a first-order sensitivity term
+//!   for the precision lost on the input.
+//! - `min`, `max`, `clamp` and the selects compare exactly and pass the chosen
+//!   register through with its own error bound.
+//!
+//! The engine exists to quantify how much accuracy the fast path loses; it is not
+//! meant to be fast.
+
+use super::CompiledEvaluator;
+use super::builtins::{eval_builtin1, eval_builtin2, eval_builtin3, eval_builtin4};
+use crate::evaluator::{FnOp, Instruction};
+use crate::math::eval_heaviside;
+
+/// Relative rounding error of a double-double operation (`2^-104`).
+const DD_EPS: f64 = f64::EPSILON * f64::EPSILON;
+
+/// Relative error charged to an `f64` transcendental fallback (two ulps).
+const F64_FALLBACK_EPS: f64 = 2.0 * f64::EPSILON;
+
+/// Unevaluated sum `hi + lo` with `|lo| <= ulp(hi) / 2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+/// Error-free transformation `a + b = s + e`.
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Error-free transformation `a + b = s + e`, assuming `|a| >= |b|`.
+#[inline]
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// Error-free transformation `a * b = p + e` via a fused multiply-add.
+#[inline]
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl DoubleDouble {
+    pub const ONE: Self = Self { hi: 1.0, lo: 0.0 };
+
+    #[inline]
+    pub const fn from_f64(value: f64) -> Self {
+        Self { hi: value, lo: 0.0 }
+    }
+
+    /// Renormalizes `(hi, lo)`, collapsing non-finite results to a plain `f64`.
+    #[inline]
+    fn renorm(hi: f64, lo: f64) -> Self {
+        let (s, e) = quick_two_sum(hi, lo);
+        if s.is_finite() {
+            Self { hi: s, lo: e }
+        } else {
+            Self::from_f64(s)
+        }
+    }
+
+    /// Best `f64` approximation of the value.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    #[inline]
+    pub fn abs(self) -> Self {
+        if self.hi.is_sign_negative() {
+            self.neg()
+        } else {
+            self
+        }
+    }
+
+    #[inline]
+    pub fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+
+    #[inline]
+    pub fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        if !s.is_finite() {
+            return Self::from_f64(s);
+        }
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s2, e2) = quick_two_sum(s, e + t);
+        Self::renorm(s2, e2 + f)
+    }
+
+    #[inline]
+    pub fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    #[inline]
+    pub fn mul(self, other: Self) -> Self {
+        let (p, e) = two_prod(self.hi, other.hi);
+        if !p.is_finite() {
+            return Self::from_f64(p);
+        }
+        let e2 = self.hi.mul_add(other.lo, self.lo.mul_add(other.hi, e));
+        Self::renorm(p, e2)
+    }
+
+    #[inline]
+    pub fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        if !q1.is_finite() || q1 == 0.0 {
+            return Self::from_f64(q1);
+        }
+        let r1 = self.sub(other.mul(Self::from_f64(q1)));
+        let q2 = r1.hi / other.hi;
+        let r2 = r1.sub(other.mul(Self::from_f64(q2)));
+        let q3 = r2.hi / other.hi;
+        let (s, e) = quick_two_sum(q1, q2);
+        Self { hi: s, lo: e }.add(Self::from_f64(q3))
+    }
+
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        if self.hi <= 0.0 || !self.hi.is_finite() {
+            return Self::from_f64(self.hi.sqrt());
+        }
+        let q = self.hi.sqrt();
+        let (p, e) = two_prod(q, q);
+        let residual = self.sub(Self { hi: p, lo: e });
+        Self::renorm(q, residual.hi / (2.0 * q))
+    }
+
+    /// Integer power by repeated squaring.
+    pub fn powi(self, n: i32) -> Self {
+        let mut base = self;
+        let mut exp = n.unsigned_abs();
+        let mut acc = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.mul(base);
+            }
+            base = base.mul(base);
+            exp >>= 1;
+        }
+        if n < 0 { Self::ONE.div(acc) } else { acc }
+    }
+}
+
+/// A double-double register value together with its absolute error bound.
+#[derive(Clone, Copy, Debug, Default)]
+struct DdReg {
+    value: DoubleDouble,
+    err: f64,
+}
+
+impl DdReg {
+    const fn exact(value: DoubleDouble) -> Self {
+        Self { value, err: 0.0 }
+    }
+
+    /// Charges the `2^-104` rounding of a double-double operation.
+    const fn rounded(value: DoubleDouble, propagated: f64) -> Self {
+        Self {
+            value,
+            err: DD_EPS.mul_add(value.hi.abs(), propagated),
+        }
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            value: self.value.neg(),
+            err: self.err,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::rounded(self.value.add(other.value), self.err + other.err)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self::rounded(self.value.sub(other.value), self.err + other.err)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let a = self.value.hi.abs();
+        let b = other.value.hi.abs();
+        let propagated = a.mul_add(other.err, b.mul_add(self.err, self.err * other.err));
+        Self::rounded(self.value.mul(other.value), propagated)
+    }
+
+    fn div(self, other: Self) -> Self {
+        let value = self.value.div(other.value);
+        let den = other.value.hi.abs() - other.err;
+        let propagated = if den > 0.0 {
+            value.hi.abs().mul_add(other.err, self.err) / den
+        } else if self.err == 0.0 && other.err == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY
+        };
+        Self::rounded(value, propagated)
+    }
+
+    fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+        let root = value.hi.abs();
+        let propagated = if self.err == 0.0 {
+            0.0
+        } else if root > 0.0 {
+            self.err / (2.0 * root)
+        } else {
+            self.err.sqrt()
+        };
+        Self::rounded(value, propagated)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let value = self.value.powi(n);
+        let base = self.value.hi.abs();
+        // First-order propagation: |d(x^n)| = |n| |x^(n-1)| |dx|.
+        let propagated = if self.err == 0.0 {
+            0.0
+        } else if base > 0.0 {
+            f64::from(n.unsigned_abs()) * value.hi.abs() / base * self.err
+        } else {
+            f64::INFINITY
+        };
+        Self::rounded(value, propagated)
+    }
+
+    fn pow(self, exp: Self) -> Self {
+        let e = exp.value;
+        if exp.err == 0.0
+            && e.lo == 0.0
+            && e.hi.fract() == 0.0
+            && e.hi >= f64::from(i32::MIN)
+            && e.hi <= f64::from(i32::MAX)
+        {
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "Range and integrality checked above"
+            )]
+            return self.powi(e.hi as i32);
+        }
+        fallback(&[self, exp], |x| x[0].powf(x[1]))
+    }
+}
+
+/// Evaluates `f` in plain `f64` and charges it a rounding error plus a
+/// central-difference sensitivity estimate for the precision lost on its inputs.
+fn fallback<const N: usize>(args: &[DdReg; N], f: impl Fn(&[f64; N]) -> f64) -> DdReg {
+    fallback_at(args, &std::array::from_fn(|i| args[i].value.to_f64()), f)
+}
+
+/// [`fallback`] for any number of arguments, with `point` holding their `f64` values.
+fn fallback_at<P>(args: &[DdReg], point: &P, f: impl Fn(&P) -> f64) -> DdReg
+where
+    P: AsRef<[f64]> + AsMut<[f64]> + Clone,
+{
+    let value = f(point);
+
+    let mut propagated = 0.0;
+    for (i, arg) in args.iter().enumerate() {
+        let x = point.as_ref()[i];
+        let uncertainty = arg.err + (arg.value.lo - (x - arg.value.hi)).abs();
+        if uncertainty == 0.0 {
+            continue;
+        }
+        let step = f64::EPSILON.sqrt() * x.abs().max(1.0);
+        let mut forward = point.clone();
+        let mut backward = point.clone();
+        forward.as_mut()[i] += step;
+        backward.as_mut()[i] -= step;
+        let slope = ((f(&forward) - f(&backward)) / (2.0 * step)).abs();
+        propagated += if slope.is_finite() {
+            slope * uncertainty
+        } else {
+            f64::INFINITY
+        };
+    }
+
+    DdReg {
+        value: DoubleDouble::from_f64(value),
+        err: F64_FALLBACK_EPS.mul_add(value.abs(), propagated),
+    }
+}
+
+/// Result of a double-double evaluation.
+#[derive(Clone, Copy, Debug)]
+pub struct DdEvaluation {
+    pub value: DoubleDouble,
+    pub error: f64,
+}
+
+impl CompiledEvaluator {
+    /// Evaluates the compiled expression in double-double precision.
+    ///
+    /// Returns `(value, error_estimate)`, where `value` is the double-double
+    /// result rounded to `f64` and `error_estimate` bounds `|value - exact|`.
+    ///
+    /// Arithmetic (`+`, `-`, `*`, `/`, `sqrt`, integer powers) runs over pairs of
+    /// `f64` for roughly 32 significant digits; transcendental functions fall back
+    /// to `f64` with a propagated error bound. Comparing the result with
+    /// [`evaluate`](Self::evaluate) quantifies the precision lost by the fast path.
+    ///
+    /// This mode is intended for validation runs and is much slower than `evaluate`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symb_anafis::{parse, CompiledEvaluator};
+    /// use std::collections::HashSet;
+    ///
+    /// let expr = parse("x^2 - y^2", &HashSet::new(), &HashSet::new(), None).expect("Should parse");
+    /// let compiled = CompiledEvaluator::compile(&expr, &["x", "y"], None).expect("Should compile");
+    ///
+    /// let (value, error) = compiled.evaluate_dd(&[1e8 + 1.0, 1e8]);
+    /// assert_eq!(value, 2e8 + 1.0);
+    /// assert!(error < 1e-15);
+    /// ```
+    #[must_use]
+    pub fn evaluate_dd(&self, params: &[f64]) -> (f64, f64) {
+        let result = self.evaluate_dd_full(params);
+        // Rounding the double-double down to f64 discards `lo`.
+        let rounded = result.value.to_f64();
+        let rounding = (result.value.lo - (rounded - result.value.hi)).abs();
+        (rounded, result.error + rounding)
+    }
+
+    /// Runs the double-double interpreter and returns the unrounded result.
+    pub(crate) fn evaluate_dd_full(&self, params: &[f64]) -> DdEvaluation {
+        let mut regs = vec![DdReg::default(); self.workspace_size.max(1)];
+
+        let provided = self.param_count.min(params.len());
+        for (reg, &p) in regs.iter_mut().zip(&params[..provided]) {
+            *reg = DdReg::exact(DoubleDouble::from_f64(p));
+        }
+        for (reg, &c) in regs[self.param_count..]
+            .iter_mut()
+            .zip(self.constants.iter())
+        {
+            *reg = DdReg::exact(DoubleDouble::from_f64(c));
+        }
+
+        for instr in &self.instructions {
+            if matches!(instr, Instruction::End {}) {
+                break;
+            }
+            self.exec_dd(instr, &mut regs);
+        }
+
+        let result = regs[self.result_reg as usize];
+        DdEvaluation {
+            value: result.value,
+            error: result.err,
+        }
+    }
+
+    #[allow(
+        clippy::too_many_lines,
+        reason = "Single dispatch over the full instruction set"
+    )]
+    fn exec_dd(&self, instr: &Instruction, regs: &mut [DdReg]) {
+        let r = |i: &u32| regs[*i as usize];
+        let (dest, value) = match instr {
+            Instruction::End {} => return,
+            Instruction::Copy { dest, src } => (dest, r(src)),
+            Instruction::Neg { dest, src } => (dest, r(src).neg()),
+            Instruction::SinCos {
+                sin_dest,
+                cos_dest,
+                arg,
+            } => {
+                let x = r(arg);
+                regs[*sin_dest as usize] = fallback(&[x], |v| v[0].sin());
+                regs[*cos_dest as usize] = fallback(&[x], |v| v[0].cos());
+                return;
+            }
+            Instruction::Add { dest, a, b } => (dest, r(a).add(r(b))),
+            Instruction::Add3 { dest, a, b, c } => (dest, r(a).add(r(b)).add(r(c))),
+            Instruction::Add4 { dest, a, b, c, d } => (dest, r(a).add(r(b)).add(r(c)).add(r(d))),
+            Instruction::AddN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let sum = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(DdReg::add)
+                    .unwrap_or_default();
+                (dest, sum)
+            }
+            Instruction::Mul { dest, a, b } => (dest, r(a).mul(r(b))),
+            Instruction::Mul3 { dest, a, b, c } => (dest, r(a).mul(r(b)).mul(r(c))),
+            Instruction::Mul4 { dest, a, b, c, d } => (dest, r(a).mul(r(b)).mul(r(c)).mul(r(d))),
+            Instruction::MulN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let prod = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(DdReg::mul)
+                    .unwrap_or(DdReg::exact(DoubleDouble::ONE));
+                (dest, prod)
+            }
+            Instruction::Sub { dest, a, b } => (dest, r(a).sub(r(b))),
+            Instruction::Div { dest, num, den } => (dest, r(num).div(r(den))),
+            Instruction::Pow { dest, base, exp } => (dest, r(base).pow(r(exp))),
+            Instruction::MulAdd { dest, a, b, c } => (dest, r(a).mul(r(b)).add(r(c))),
+            Instruction::MulSub { dest, a, b, c } => (dest, r(a).mul(r(b)).sub(r(c))),
+            Instruction::NegMul { dest, a, b } => (dest, r(a).mul(r(b)).neg()),
+            Instruction::NegMulAdd { dest, a, b, c } => (dest, r(c).sub(r(a).mul(r(b)))),
+            Instruction::NegMulSub { dest, a, b, c } => (dest, r(a).mul(r(b)).add(r(c)).neg()),
+            Instruction::Square { dest, src } => (dest, r(src).powi(2)),
+            Instruction::Cube { dest, src } => (dest, r(src).powi(3)),
+            Instruction::Pow4 { dest, src } => (dest, r(src).powi(4)),
+            Instruction::Pow3_2 { dest, src } => {
+                let x = r(src);
+                (dest, x.mul(x.sqrt()))
+            }
+            Instruction::InvPow3_2 { dest, src } => {
+                let x = r(src);
+                (dest, DdReg::exact(DoubleDouble::ONE).div(x.mul(x.sqrt())))
+            }
+            Instruction::InvSqrt { dest, src } => {
+                (dest, DdReg::exact(DoubleDouble::ONE).div(r(src).sqrt()))
+            }
+            Instruction::InvSquare { dest, src } => (dest, r(src).powi(-2)),
+            Instruction::InvCube { dest, src } => (dest, r(src).powi(-3)),
+            Instruction::Recip { dest, src } => (dest, r(src).powi(-1)),
+            Instruction::Powi { dest, src, n } => (dest, r(src).powi(*n)),
+            Instruction::Sin { dest, arg } => (dest, fallback(&[r(arg)], |v| v[0].sin())),
+            Instruction::Cos { dest, arg } => (dest, fallback(&[r(arg)], |v| v[0].cos())),
+            Instruction::Exp { dest, arg } => (dest, fallback(&[r(arg)], |v| v[0].exp())),
+            Instruction::Ln { dest, arg } => (dest, fallback(&[r(arg)], |v| v[0].ln())),
+            Instruction::Sqrt { dest, arg } => (dest, r(arg).sqrt()),
+            Instruction::RecipExpm1 { dest, src } => {
+                (dest, fallback(&[r(src)], |v| 1.0 / v[0].exp_m1()))
+            }
+            Instruction::ExpSqr { dest, src } => {
+                let x = r(src);
+                (dest, fallback(&[x.mul(x)], |v| v[0].exp()))
+            }
+            Instruction::ExpSqrNeg { dest, src } => {
+                let x = r(src);
+                (dest, fallback(&[x.mul(x).neg()], |v| v[0].exp()))
+            }
+            Instruction::Builtin1 { dest, op, arg } => (dest, builtin1_dd(*op, r(arg))),
+            Instruction::Builtin2 {
+                dest,
+                op: FnOp::Min,
+                arg1,
+                arg2,
+            } => (dest, pick_min(r(arg1), r(arg2))),
+            Instruction::Builtin2 {
+                dest,
+                op: FnOp::Max,
+                arg1,
+                arg2,
+            } => (dest, pick_max(r(arg1), r(arg2))),
+            Instruction::Builtin2 {
+                dest,
+                op,
+                arg1,
+                arg2,
+            } => {
+                let op = *op;
+                (
+                    dest,
+                    fallback(&[r(arg1), r(arg2)], |v| eval_builtin2(op, v[0], v[1])),
+                )
+            }
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Clamp,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, pick_min(pick_max(r(arg1), r(arg2)), r(arg3))),
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Select,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, pick_branch(r(arg1).value.to_f64(), r(arg2), r(arg3))),
+            Instruction::Builtin3 {
+                dest,
+                op,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let op = *op;
+                (
+                    dest,
+                    fallback(&[r(arg1), r(arg2), r(arg3)], |v| {
+                        eval_builtin3(op, v[0], v[1], v[2])
+                    }),
+                )
+            }
+            Instruction::Builtin4 {
+                dest,
+                op,
+                arg1,
+                arg2,
+                arg3,
+                arg4,
+            } => {
+                let op = *op;
+                (
+                    dest,
+                    fallback(&[r(arg1), r(arg2), r(arg3), r(arg4)], |v| {
+                        eval_builtin4(op, v[0], v[1], v[2], v[3])
+                    }),
+                )
+            }
+            Instruction::MinConst { dest, src, bound } => (dest, pick_min(r(src), r(bound))),
+            Instruction::MaxConst { dest, src, bound } => (dest, pick_max(r(src), r(bound))),
+            Instruction::Clamp { dest, src, lo, hi } => {
+                (dest, pick_min(pick_max(r(src), r(lo)), r(hi)))
+            }
+            Instruction::Select { dest, cond, a, b } => {
+                (dest, pick_branch(r(cond).value.to_f64(), r(a), r(b)))
+            }
+            Instruction::SelectNonNeg { dest, src, a, b } => {
+                let cond = eval_heaviside(r(src).value.to_f64());
+                (dest, pick_branch(cond, r(a), r(b)))
+            }
+            Instruction::CallExternal {
+                dest,
+                callback,
+                start_idx,
+                count,
+            } => {
+                let callback = &self.externals[*callback as usize];
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let args: Vec<DdReg> = pool.iter().map(r).collect();
+                let point: Vec<f64> = args.iter().map(|arg| arg.value.to_f64()).collect();
+                (dest, fallback_at(&args, &point, |v| callback(v)))
+            }
+        };
+        regs[*dest as usize] = value;
+    }
+}
+
+/// Exact `a < b` on the full double-double value.
+#[allow(
+    clippy::float_cmp,
+    reason = "Equal high words are what makes the low words decide the order"
+)]
+fn dd_less(a: DoubleDouble, b: DoubleDouble) -> bool {
+    a.hi < b.hi || (a.hi == b.hi && a.lo < b.lo)
+}
+
+/// `min` with ties keeping `a`; either input's error can reach the result.
+fn pick_min(a: DdReg, b: DdReg) -> DdReg {
+    let value = if dd_less(b.value, a.value) || b.value.hi.is_nan() {
+        b.value
+    } else {
+        a.value
+    };
+    DdReg {
+        value,
+        err: a.err.max(b.err),
+    }
+}
+
+/// `max` with ties keeping `a`; either input's error can reach the result.
+fn pick_max(a: DdReg, b: DdReg) -> DdReg {
+    let value = if dd_less(a.value, b.value) || b.value.hi.is_nan() {
+        b.value
+    } else {
+        a.value
+    };
+    DdReg {
+        value,
+        err: a.err.max(b.err),
+    }
+}
+
+/// `a` for a nonzero condition, `b` for zero, NaN for a NaN condition.
+fn pick_branch(cond: f64, a: DdReg, b: DdReg) -> DdReg {
+    if cond.is_nan() {
+        DdReg::exact(DoubleDouble::from_f64(cond))
+    } else if cond == 0.0 {
+        b
+    } else {
+        a
+    }
+}
+
+/// Unary builtins that are exact in double-double; everything else falls back to `f64`.
+fn builtin1_dd(op: FnOp, x: DdReg) -> DdReg {
+    match op {
+        FnOp::Sqrt => x.sqrt(),
+        FnOp::Abs => DdReg {
+            value: x.value.abs(),
+            err: x.err,
+        },
+        FnOp::Sin => fallback(&[x], |v| v[0].sin()),
+        FnOp::Cos => fallback(&[x], |v| v[0].cos()),
+        FnOp::Exp => fallback(&[x], |v| v[0].exp()),
+        FnOp::Ln => fallback(&[x], |v| v[0].ln()),
+        _ => fallback(&[x], |v| eval_builtin1(op, v[0])),
+    }
+}
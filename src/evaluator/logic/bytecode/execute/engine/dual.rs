@@ -0,0 +1,523 @@
+//! Forward-mode automatic differentiation engine for the register-based evaluator.
+//!
+//! A third interpreter over the same [`Instruction`] stream as the scalar engine.
+//! Every register holds a dual number `re + eps * ε` with `ε² = 0`, so running
+//! the program once yields the value together with its directional derivative
+//! along the seed vector:
+//!
+//! - Arithmetic, powers and the elementary functions propagate `eps` with their
+//!   exact derivative rules.
+//! - `min`, `max`, `clamp` and the selects pass the chosen operand's tangent
+//!   through, so the derivative is one-sided exactly where the value is.
+//! - Builtins without a closed-form derivative here (elliptic integrals, Bessel
+//!   functions, `zeta`, ...) are differentiated by central differences in `f64`.
+
+use super::CompiledEvaluator;
+use super::builtins::{eval_builtin1, eval_builtin2, eval_builtin3, eval_builtin4};
+use crate::evaluator::{FnOp, Instruction};
+use crate::math::eval_heaviside;
+use std::f64::consts::FRAC_2_SQRT_PI;
+
+/// Dual number `re + eps * ε` with `ε² = 0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Dual {
+    re: f64,
+    eps: f64,
+}
+
+impl Dual {
+    const ONE: Self = Self::constant(1.0);
+
+    const fn constant(re: f64) -> Self {
+        Self { re, eps: 0.0 }
+    }
+
+    /// Applies a function with value `value` and derivative `slope` at `self.re`.
+    ///
+    /// A zero tangent stays zero even where the slope is not finite, so terms
+    /// that do not depend on the seeded parameters never poison the result.
+    fn chain(self, value: f64, slope: f64) -> Self {
+        Self {
+            re: value,
+            eps: if self.eps == 0.0 {
+                0.0
+            } else {
+                slope * self.eps
+            },
+        }
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            re: -self.re,
+            eps: -self.eps,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            re: self.re + other.re,
+            eps: self.eps + other.eps,
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            re: self.re - other.re,
+            eps: self.eps - other.eps,
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            re: self.re * other.re,
+            eps: self.re.mul_add(other.eps, self.eps * other.re),
+        }
+    }
+
+    fn div(self, other: Self) -> Self {
+        let re = self.re / other.re;
+        Self {
+            re,
+            eps: re.mul_add(-other.eps, self.eps) / other.re,
+        }
+    }
+
+    fn recip(self) -> Self {
+        let re = 1.0 / self.re;
+        self.chain(re, -re * re)
+    }
+
+    fn sqrt(self) -> Self {
+        let re = self.re.sqrt();
+        self.chain(re, 0.5 / re)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let slope = f64::from(n) * self.re.powi(n - 1);
+        self.chain(self.re.powi(n), slope)
+    }
+
+    fn powf(self, n: f64) -> Self {
+        let slope = n * self.re.powf(n - 1.0);
+        self.chain(self.re.powf(n), slope)
+    }
+
+    fn pow(self, exp: Self) -> Self {
+        if exp.eps == 0.0 {
+            return self.powf(exp.re);
+        }
+        // d(a^b) = b a^(b-1) da + a^b ln(a) db
+        let re = self.re.powf(exp.re);
+        let base_term = if self.eps == 0.0 {
+            0.0
+        } else {
+            exp.re * self.re.powf(exp.re - 1.0) * self.eps
+        };
+        Self {
+            re,
+            eps: (re * self.re.ln()).mul_add(exp.eps, base_term),
+        }
+    }
+
+    fn exp(self) -> Self {
+        let re = self.re.exp();
+        self.chain(re, re)
+    }
+}
+
+/// Evaluates `f` at the primal point and propagates each argument's tangent
+/// through a central-difference estimate of the partial derivative.
+fn numeric<const N: usize>(args: &[Dual; N], f: impl Fn(&[f64; N]) -> f64) -> Dual {
+    central_difference(args, &std::array::from_fn(|i| args[i].re), f)
+}
+
+/// [`numeric`] for any number of arguments, with `point` holding their primal values.
+fn central_difference<P>(args: &[Dual], point: &P, f: impl Fn(&P) -> f64) -> Dual
+where
+    P: AsRef<[f64]> + AsMut<[f64]> + Clone,
+{
+    let mut eps = 0.0;
+    for (i, arg) in args.iter().enumerate() {
+        if arg.eps == 0.0 {
+            continue;
+        }
+        let step = f64::EPSILON.cbrt() * point.as_ref()[i].abs().max(1.0);
+        let mut forward = point.clone();
+        let mut backward = point.clone();
+        forward.as_mut()[i] += step;
+        backward.as_mut()[i] -= step;
+        let slope = (f(&forward) - f(&backward)) / (2.0 * step);
+        eps = slope.mul_add(arg.eps, eps);
+    }
+    Dual { re: f(point), eps }
+}
+
+impl CompiledEvaluator {
+    /// Evaluates the expression and its directional derivative in one pass.
+    ///
+    /// Runs the bytecode over dual numbers: parameter `i` starts as
+    /// `primals[i] + seeds[i] * ε`, and the result is `(value, derivative)`,
+    /// where `derivative` is the derivative along `seeds`. Seeding a single
+    /// parameter with `1.0` and the rest with `0.0` gives that partial
+    /// derivative; see [`eval_gradient_forward`](Self::eval_gradient_forward).
+    ///
+    /// Missing primals and seeds are treated as `0.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symb_anafis::{parse, CompiledEvaluator};
+    /// use std::collections::HashSet;
+    ///
+    /// let expr = parse("x^2 * sin(y)", &HashSet::new(), &HashSet::new(), None).expect("Should parse");
+    /// let compiled = CompiledEvaluator::compile(&expr, &["x", "y"], None).expect("Should compile");
+    ///
+    /// // d/dx at (3, 0.5) is 2 * 3 * sin(0.5)
+    /// let (value, dx) = compiled.eval_dual(&[3.0, 0.5], &[1.0, 0.0]);
+    /// assert!((value - 9.0 * 0.5_f64.sin()).abs() < 1e-12);
+    /// assert!((dx - 6.0 * 0.5_f64.sin()).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn eval_dual(&self, primals: &[f64], seeds: &[f64]) -> (f64, f64) {
+        let mut regs = vec![Dual::default(); self.workspace_size.max(1)];
+
+        for (i, reg) in regs.iter_mut().take(self.param_count).enumerate() {
+            *reg = Dual {
+                re: primals.get(i).copied().unwrap_or_default(),
+                eps: seeds.get(i).copied().unwrap_or_default(),
+            };
+        }
+        for (reg, &c) in regs[self.param_count..]
+            .iter_mut()
+            .zip(self.constants.iter())
+        {
+            *reg = Dual::constant(c);
+        }
+
+        for instr in &self.instructions {
+            if matches!(instr, Instruction::End {}) {
+                break;
+            }
+            self.exec_dual(instr, &mut regs);
+        }
+
+        let result = regs[self.result_reg as usize];
+        (result.re, result.eps)
+    }
+
+    /// Computes the gradient with respect to every parameter by forward mode.
+    ///
+    /// Calls [`eval_dual`](Self::eval_dual) once per parameter with a unit
+    /// seed, so the cost grows linearly with the parameter count.
+    #[must_use]
+    pub fn eval_gradient_forward(&self, primals: &[f64]) -> Vec<f64> {
+        let mut seeds = vec![0.0; self.param_count];
+        (0..self.param_count)
+            .map(|i| {
+                seeds[i] = 1.0;
+                let (_, derivative) = self.eval_dual(primals, &seeds);
+                seeds[i] = 0.0;
+                derivative
+            })
+            .collect()
+    }
+
+    #[allow(
+        clippy::too_many_lines,
+        reason = "Single dispatch over the full instruction set"
+    )]
+    fn exec_dual(&self, instr: &Instruction, regs: &mut [Dual]) {
+        let r = |i: &u32| regs[*i as usize];
+        let (dest, value) = match instr {
+            Instruction::End {} => return,
+            Instruction::Copy { dest, src } => (dest, r(src)),
+            Instruction::Neg { dest, src } => (dest, r(src).neg()),
+            Instruction::SinCos {
+                sin_dest,
+                cos_dest,
+                arg,
+            } => {
+                let x = r(arg);
+                let (sin, cos) = x.re.sin_cos();
+                regs[*sin_dest as usize] = x.chain(sin, cos);
+                regs[*cos_dest as usize] = x.chain(cos, -sin);
+                return;
+            }
+            Instruction::Add { dest, a, b } => (dest, r(a).add(r(b))),
+            Instruction::Add3 { dest, a, b, c } => (dest, r(a).add(r(b)).add(r(c))),
+            Instruction::Add4 { dest, a, b, c, d } => (dest, r(a).add(r(b)).add(r(c)).add(r(d))),
+            Instruction::AddN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let sum = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(Dual::add)
+                    .unwrap_or_default();
+                (dest, sum)
+            }
+            Instruction::Mul { dest, a, b } => (dest, r(a).mul(r(b))),
+            Instruction::Mul3 { dest, a, b, c } => (dest, r(a).mul(r(b)).mul(r(c))),
+            Instruction::Mul4 { dest, a, b, c, d } => (dest, r(a).mul(r(b)).mul(r(c)).mul(r(d))),
+            Instruction::MulN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let prod = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(Dual::mul)
+                    .unwrap_or(Dual::ONE);
+                (dest, prod)
+            }
+            Instruction::Sub { dest, a, b } => (dest, r(a).sub(r(b))),
+            Instruction::Div { dest, num, den } => (dest, r(num).div(r(den))),
+            Instruction::Pow { dest, base, exp } => (dest, r(base).pow(r(exp))),
+            Instruction::MulAdd { dest, a, b, c } => (dest, r(a).mul(r(b)).add(r(c))),
+            Instruction::MulSub { dest, a, b, c } => (dest, r(a).mul(r(b)).sub(r(c))),
+            Instruction::NegMul { dest, a, b } => (dest, r(a).mul(r(b)).neg()),
+            Instruction::NegMulAdd { dest, a, b, c } => (dest, r(c).sub(r(a).mul(r(b)))),
+            Instruction::NegMulSub { dest, a, b, c } => (dest, r(a).mul(r(b)).add(r(c)).neg()),
+            Instruction::Square { dest, src } => (dest, r(src).powi(2)),
+            Instruction::Cube { dest, src } => (dest, r(src).powi(3)),
+            Instruction::Pow4 { dest, src } => (dest, r(src).powi(4)),
+            Instruction::Pow3_2 { dest, src } => (dest, r(src).powf(1.5)),
+            Instruction::InvPow3_2 { dest, src } => (dest, r(src).powf(-1.5)),
+            Instruction::InvSqrt { dest, src } => (dest, r(src).powf(-0.5)),
+            Instruction::InvSquare { dest, src } => (dest, r(src).powi(-2)),
+            Instruction::InvCube { dest, src } => (dest, r(src).powi(-3)),
+            Instruction::Recip { dest, src } => (dest, r(src).recip()),
+            Instruction::Powi { dest, src, n } => (dest, r(src).powi(*n)),
+            Instruction::Sin { dest, arg } => (dest, builtin1_dual(FnOp::Sin, r(arg))),
+            Instruction::Cos { dest, arg } => (dest, builtin1_dual(FnOp::Cos, r(arg))),
+            Instruction::Exp { dest, arg } => (dest, r(arg).exp()),
+            Instruction::Ln { dest, arg } => (dest, builtin1_dual(FnOp::Ln, r(arg))),
+            Instruction::Sqrt { dest, arg } => (dest, r(arg).sqrt()),
+            Instruction::RecipExpm1 { dest, src } => {
+                (dest, builtin1_dual(FnOp::Expm1, r(src)).recip())
+            }
+            Instruction::ExpSqr { dest, src } => (dest, r(src).powi(2).exp()),
+            Instruction::ExpSqrNeg { dest, src } => (dest, r(src).powi(2).neg().exp()),
+            Instruction::Builtin1 { dest, op, arg } => (dest, builtin1_dual(*op, r(arg))),
+            Instruction::Builtin2 {
+                dest,
+                op,
+                arg1,
+                arg2,
+            } => (dest, builtin2_dual(*op, r(arg1), r(arg2))),
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Clamp,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, pick_min(pick_max(r(arg1), r(arg2)), r(arg3))),
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Select,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, pick_branch(r(arg1).re, r(arg2), r(arg3))),
+            Instruction::Builtin3 {
+                dest,
+                op,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let op = *op;
+                (
+                    dest,
+                    numeric(&[r(arg1), r(arg2), r(arg3)], |v| {
+                        eval_builtin3(op, v[0], v[1], v[2])
+                    }),
+                )
+            }
+            Instruction::Builtin4 {
+                dest,
+                op,
+                arg1,
+                arg2,
+                arg3,
+                arg4,
+            } => {
+                let op = *op;
+                (
+                    dest,
+                    numeric(&[r(arg1), r(arg2), r(arg3), r(arg4)], |v| {
+                        eval_builtin4(op, v[0], v[1], v[2], v[3])
+                    }),
+                )
+            }
+            Instruction::MinConst { dest, src, bound } => (dest, pick_min(r(src), r(bound))),
+            Instruction::MaxConst { dest, src, bound } => (dest, pick_max(r(src), r(bound))),
+            Instruction::Clamp { dest, src, lo, hi } => {
+                (dest, pick_min(pick_max(r(src), r(lo)), r(hi)))
+            }
+            Instruction::Select { dest, cond, a, b } => (dest, pick_branch(r(cond).re, r(a), r(b))),
+            Instruction::SelectNonNeg { dest, src, a, b } => {
+                (dest, pick_branch(eval_heaviside(r(src).re), r(a), r(b)))
+            }
+            Instruction::CallExternal {
+                dest,
+                callback,
+                start_idx,
+                count,
+            } => {
+                let callback = &self.externals[*callback as usize];
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let args: Vec<Dual> = pool.iter().map(r).collect();
+                let point: Vec<f64> = args.iter().map(|arg| arg.re).collect();
+                (dest, central_difference(&args, &point, |v| callback(v)))
+            }
+        };
+        regs[*dest as usize] = value;
+    }
+}
+
+/// Unary builtins with their closed-form derivatives.
+fn builtin1_dual(op: FnOp, x: Dual) -> Dual {
+    let v = x.re;
+    // The dedicated instructions' ops are not dispatched by `eval_builtin1`
+    let value = match op {
+        FnOp::Sin => v.sin(),
+        FnOp::Cos => v.cos(),
+        FnOp::Exp => v.exp(),
+        FnOp::Ln => v.ln(),
+        FnOp::Sqrt => v.sqrt(),
+        _ => eval_builtin1(op, v),
+    };
+    let slope = match op {
+        FnOp::Sin => v.cos(),
+        FnOp::Cos => -v.sin(),
+        FnOp::Tan => value.mul_add(value, 1.0),
+        FnOp::Cot => -value.mul_add(value, 1.0),
+        FnOp::Sec => value * v.tan(),
+        FnOp::Csc => -value / v.tan(),
+        FnOp::Asin => 1.0 / (-v).mul_add(v, 1.0).sqrt(),
+        FnOp::Acos => -1.0 / (-v).mul_add(v, 1.0).sqrt(),
+        FnOp::Atan => 1.0 / v.mul_add(v, 1.0),
+        FnOp::Acot => -1.0 / v.mul_add(v, 1.0),
+        FnOp::Asec => 1.0 / (v.abs() * v.mul_add(v, -1.0).sqrt()),
+        FnOp::Acsc => -1.0 / (v.abs() * v.mul_add(v, -1.0).sqrt()),
+        FnOp::Sinh => v.cosh(),
+        FnOp::Cosh => v.sinh(),
+        FnOp::Tanh | FnOp::Coth => (-value).mul_add(value, 1.0),
+        FnOp::Sech => -value * v.tanh(),
+        FnOp::Csch => -value / v.tanh(),
+        FnOp::Asinh => 1.0 / v.mul_add(v, 1.0).sqrt(),
+        FnOp::Acosh => 1.0 / v.mul_add(v, -1.0).sqrt(),
+        FnOp::Atanh | FnOp::Acoth => 1.0 / (-v).mul_add(v, 1.0),
+        FnOp::Acsch => -1.0 / (v.abs() * v.mul_add(v, 1.0).sqrt()),
+        FnOp::Asech => -1.0 / (v * (-v).mul_add(v, 1.0).sqrt()),
+        FnOp::Exp => value,
+        FnOp::Expm1 => v.exp(),
+        FnOp::ExpNeg => -value,
+        FnOp::Ln => 1.0 / v,
+        FnOp::Log1p => 1.0 / (1.0 + v),
+        FnOp::Sqrt => 0.5 / value,
+        FnOp::Cbrt => 1.0 / (3.0 * value * value),
+        FnOp::Abs => {
+            if v == 0.0 {
+                0.0
+            } else {
+                v.signum()
+            }
+        }
+        FnOp::Signum | FnOp::Heaviside | FnOp::Dirac | FnOp::Floor | FnOp::Ceil | FnOp::Round => {
+            0.0
+        }
+        FnOp::Erf => FRAC_2_SQRT_PI * (-v * v).exp(),
+        FnOp::Erfc => -FRAC_2_SQRT_PI * (-v * v).exp(),
+        FnOp::Gamma => value * eval_builtin1(FnOp::Digamma, v),
+        FnOp::Lgamma => eval_builtin1(FnOp::Digamma, v),
+        FnOp::Digamma => eval_builtin1(FnOp::Trigamma, v),
+        FnOp::Trigamma => eval_builtin1(FnOp::Tetragamma, v),
+        FnOp::Tetragamma => eval_builtin2(FnOp::Polygamma, 3.0, v),
+        FnOp::Sinc => {
+            if v == 0.0 {
+                0.0
+            } else {
+                (v.cos() - value) / v
+            }
+        }
+        FnOp::LambertW => {
+            if v == 0.0 {
+                1.0
+            } else {
+                value / (v * (1.0 + value))
+            }
+        }
+        _ => return numeric(&[x], |a| eval_builtin1(op, a[0])),
+    };
+    x.chain(value, slope)
+}
+
+/// `min` on the primal, ties keeping `a`, as [`eval_min`](crate::math::eval_min) does.
+fn pick_min(a: Dual, b: Dual) -> Dual {
+    if b.re < a.re || b.re.is_nan() { b } else { a }
+}
+
+/// `max` on the primal, ties keeping `a`, as [`eval_max`](crate::math::eval_max) does.
+fn pick_max(a: Dual, b: Dual) -> Dual {
+    if b.re > a.re || b.re.is_nan() { b } else { a }
+}
+
+/// `a` for a nonzero condition, `b` for zero, NaN for a NaN condition.
+fn pick_branch(cond: f64, a: Dual, b: Dual) -> Dual {
+    if cond.is_nan() {
+        Dual::constant(cond)
+    } else if cond == 0.0 {
+        b
+    } else {
+        a
+    }
+}
+
+/// Binary builtins: closed forms where cheap, central differences otherwise.
+fn builtin2_dual(op: FnOp, a: Dual, b: Dual) -> Dual {
+    match op {
+        FnOp::Atan2 => {
+            // atan2(y, x): d = (x dy - y dx) / (x^2 + y^2)
+            let (y, x) = (a.re, b.re);
+            let norm = x.mul_add(x, y * y);
+            Dual {
+                re: y.atan2(x),
+                eps: x.mul_add(a.eps, -y * b.eps) / norm,
+            }
+        }
+        FnOp::Hypot => {
+            // hypot(a, b): d = (a da + b db) / hypot(a, b)
+            let re = a.re.hypot(b.re);
+            Dual {
+                re,
+                eps: a.re.mul_add(a.eps, b.re * b.eps) / re,
+            }
+        }
+        FnOp::Log => {
+            // log(base, x) = ln(x) / ln(base)
+            let (base, x) = (a.re, b.re);
+            let re = eval_builtin2(op, base, x);
+            let d_base = if a.eps == 0.0 { 0.0 } else { re / base * a.eps };
+            let d_x = if b.eps == 0.0 { 0.0 } else { b.eps / x };
+            Dual {
+                re,
+                eps: (d_x - d_base) / base.ln(),
+            }
+        }
+        FnOp::Min => pick_min(a, b),
+        FnOp::Max => pick_max(a, b),
+        // Indicators are flat away from the breakpoint
+        FnOp::Less | FnOp::LessEq | FnOp::Greater | FnOp::GreaterEq | FnOp::Equal => {
+            Dual::constant(eval_builtin2(op, a.re, b.re))
+        }
+        _ => numeric(&[a, b], |v| eval_builtin2(op, v[0], v[1])),
+    }
+}
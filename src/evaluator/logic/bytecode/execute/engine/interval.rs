@@ -0,0 +1,600 @@
+//! Interval arithmetic engine for the register-based evaluator.
+//!
+//! A third interpreter over the same [`Instruction`] stream as the scalar engine.
+//! Every register holds a closed interval `[lo, hi]` that encloses every value the
+//! instruction can produce for inputs drawn from the parameter intervals:
+//!
+//! - `+`, `-`, `*`, `/` and integer powers follow the textbook interval rules and
+//!   round their bounds outward by one ulp.
+//! - Monotone functions (`exp`, `ln`, `sqrt`, `atan`, ...) map the endpoints, and
+//!   `sin`/`cos` also take the extrema reached inside the interval. Transcendental
+//!   bounds are widened by two ulps to cover libm rounding.
+//! - Functions without an interval rule yet (`erf`, `gamma`, most binary
+//!   builtins, ...) give the whole real line, which is always a valid enclosure.
+//!
+//! An empty interval (NaN bounds) means no input in the box is in the domain, as for
+//! `ln([-2, -1])`. Registers never track correlations between inputs, so `x - x`
+//! encloses more than `0`; the result is valid but not always tight.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::CompiledEvaluator;
+use crate::evaluator::logic::bytecode::compile::vir::registry::FN_MAP;
+use crate::evaluator::{FnOp, Instruction};
+
+/// Closed interval `[lo, hi]`; NaN bounds mark the empty interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+/// Next representable value towards `-inf`, keeping infinities.
+#[inline]
+const fn down(x: f64) -> f64 {
+    if x.is_finite() { x.next_down() } else { x }
+}
+
+/// Next representable value towards `+inf`, keeping infinities.
+#[inline]
+const fn up(x: f64) -> f64 {
+    if x.is_finite() { x.next_up() } else { x }
+}
+
+/// Product that takes `0 * inf` as `0`, as interval bounds require.
+#[inline]
+fn bound_mul(a: f64, b: f64) -> f64 {
+    if a == 0.0 || b == 0.0 { 0.0 } else { a * b }
+}
+
+impl Interval {
+    pub const ENTIRE: Self = Self {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+    };
+
+    pub const EMPTY: Self = Self {
+        lo: f64::NAN,
+        hi: f64::NAN,
+    };
+
+    const ZERO: Self = Self::point(0.0);
+
+    const ONE: Self = Self::point(1.0);
+
+    /// The interval between `a` and `b` in either order; NaN gives the empty interval.
+    #[inline]
+    pub const fn new(a: f64, b: f64) -> Self {
+        if a.is_nan() || b.is_nan() {
+            Self::EMPTY
+        } else {
+            Self {
+                lo: a.min(b),
+                hi: a.max(b),
+            }
+        }
+    }
+
+    #[inline]
+    pub const fn point(x: f64) -> Self {
+        Self { lo: x, hi: x }
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.lo.is_nan()
+    }
+
+    #[inline]
+    pub fn contains(self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// `[lo, hi]` rounded outward by `ulps` ulps.
+    #[inline]
+    fn widen(lo: f64, hi: f64, ulps: u32) -> Self {
+        let (lo, hi) = (0..ulps).fold((lo, hi), |(lo, hi), _| (down(lo), up(hi)));
+        Self::new(lo, hi)
+    }
+
+    /// Smallest interval containing both.
+    pub const fn hull(self, other: Self) -> Self {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return self;
+        }
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// `self` restricted to `[lo, hi]`.
+    pub fn restrict(self, lo: f64, hi: f64) -> Self {
+        let (lo, hi) = (self.lo.max(lo), self.hi.min(hi));
+        if lo <= hi {
+            Self { lo, hi }
+        } else {
+            Self::EMPTY
+        }
+    }
+
+    /// Image under a non-decreasing function, widened for libm rounding.
+    fn increasing(self, f: impl Fn(f64) -> f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::widen(f(self.lo), f(self.hi), 2)
+    }
+
+    /// Image under a non-increasing function, widened for libm rounding.
+    fn decreasing(self, f: impl Fn(f64) -> f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::widen(f(self.hi), f(self.lo), 2)
+    }
+
+    fn abs(self) -> Self {
+        if self.lo >= 0.0 {
+            self
+        } else if self.hi <= 0.0 {
+            -self
+        } else {
+            Self {
+                lo: 0.0,
+                hi: (-self.lo).max(self.hi),
+            }
+        }
+    }
+
+    const fn min(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            return Self::EMPTY;
+        }
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.min(other.hi),
+        }
+    }
+
+    const fn max(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            return Self::EMPTY;
+        }
+        Self {
+            lo: self.lo.max(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    fn recip(self) -> Self {
+        Self::ONE / self
+    }
+
+    fn sqrt(self) -> Self {
+        self.restrict(0.0, f64::INFINITY)
+            .increasing(f64::sqrt)
+            .restrict(0.0, f64::INFINITY)
+    }
+
+    /// `ln` over the positive part; a box reaching `0` has no lower bound.
+    fn ln(self) -> Self {
+        self.restrict(0.0, f64::INFINITY).increasing(f64::ln)
+    }
+
+    fn exp(self) -> Self {
+        self.increasing(f64::exp).restrict(0.0, f64::INFINITY)
+    }
+
+    fn sin(self) -> Self {
+        (self - Self::point(FRAC_PI_2)).cos()
+    }
+
+    fn cos(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        if self.hi - self.lo >= TAU {
+            return Self::new(-1.0, 1.0);
+        }
+        let (a, b) = (self.lo.cos(), self.hi.cos());
+        // Extrema sit at even (max) and odd (min) multiples of pi
+        let first = |offset: f64| ((self.lo - offset) / TAU).ceil().mul_add(TAU, offset);
+        let hi = if self.contains(first(0.0)) {
+            1.0
+        } else {
+            a.max(b)
+        };
+        let lo = if self.contains(first(PI)) {
+            -1.0
+        } else {
+            a.min(b)
+        };
+        Self::widen(lo, hi, 2).restrict(-1.0, 1.0)
+    }
+
+    /// Integer power, exact in shape: even powers of a box around `0` start at `0`.
+    fn powi(self, n: i32) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let magnitude = match n.unsigned_abs() {
+            0 => return Self::ONE,
+            1 => self,
+            k => {
+                // `powi` multiplies repeatedly, rounding up to once per step
+                let rel = f64::from(k) * f64::EPSILON;
+                let k = i32::try_from(k).unwrap_or(i32::MAX);
+                let (lo, hi) = if k % 2 == 1 {
+                    (self.lo.powi(k), self.hi.powi(k))
+                } else {
+                    let abs = self.abs();
+                    (abs.lo.powi(k), abs.hi.powi(k))
+                };
+                let widened = Self::widen(lo.abs().mul_add(-rel, lo), hi.abs().mul_add(rel, hi), 1);
+                if k % 2 == 1 {
+                    widened
+                } else {
+                    widened.restrict(0.0, f64::INFINITY)
+                }
+            }
+        };
+        if n < 0 { magnitude.recip() } else { magnitude }
+    }
+
+    /// `base^exp`; an integer point exponent uses [`powi`](Self::powi), anything else
+    /// `exp(exp * ln(base))` over the positive part of the base.
+    #[allow(clippy::float_cmp, reason = "A point exponent has equal bounds")]
+    pub fn pow(self, exp: Self) -> Self {
+        if exp.lo == exp.hi
+            && exp.lo.fract() == 0.0
+            && exp.lo >= f64::from(i32::MIN)
+            && exp.lo <= f64::from(i32::MAX)
+        {
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "Range and integrality checked above"
+            )]
+            return self.powi(exp.lo as i32);
+        }
+        let positive = (exp * self.ln()).exp();
+        if self.contains(0.0) && exp.lo > 0.0 {
+            positive.hull(Self::ZERO)
+        } else {
+            positive
+        }
+    }
+
+    /// `cond != 0 ? a : b`, taking both branches when `cond` may be either.
+    #[allow(clippy::float_cmp, reason = "Only the exact point 0 picks `b` alone")]
+    fn select(cond: Self, a: Self, b: Self) -> Self {
+        if cond.is_empty() {
+            cond
+        } else if cond.lo == 0.0 && cond.hi == 0.0 {
+            b
+        } else if cond.contains(0.0) {
+            a.hull(b)
+        } else {
+            a
+        }
+    }
+
+    /// `src >= 0 ? a : b`, taking both branches when `src` straddles `0`.
+    fn select_non_neg(src: Self, a: Self, b: Self) -> Self {
+        if src.is_empty() {
+            src
+        } else if src.lo >= 0.0 {
+            a
+        } else if src.hi < 0.0 {
+            b
+        } else {
+            a.hull(b)
+        }
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::widen(self.lo + other.lo, self.hi + other.hi, 1)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            return Self::EMPTY;
+        }
+        let products = [
+            bound_mul(self.lo, other.lo),
+            bound_mul(self.lo, other.hi),
+            bound_mul(self.hi, other.lo),
+            bound_mul(self.hi, other.hi),
+        ];
+        let lo = products.into_iter().fold(f64::INFINITY, f64::min);
+        let hi = products.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        Self::widen(lo, hi, 1)
+    }
+}
+
+impl Div for Interval {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            return Self::EMPTY;
+        }
+        if other.contains(0.0) {
+            return Self::ENTIRE;
+        }
+        let inv = Self::widen(1.0 / other.hi, 1.0 / other.lo, 1);
+        self * inv
+    }
+}
+
+impl CompiledEvaluator {
+    /// Evaluates the compiled expression over a box of inputs.
+    ///
+    /// Each parameter is given as a `(lo, hi)` pair (in either order), and the result
+    /// `(lo, hi)` encloses the value of the expression at every point of the box.
+    /// Bounds are rounded outward, so the enclosure also holds for the exact
+    /// real-number result. Functions without an interval rule yet, such as `erf`, give
+    /// `(-inf, inf)`; `(NaN, NaN)` means no point of the box is in the domain.
+    ///
+    /// Each occurrence of a parameter is treated independently, so the enclosure can
+    /// be wider than the true range (`x - x` over `[0, 1]` gives `[-1, 1]`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symb_anafis::{parse, CompiledEvaluator};
+    /// use std::collections::HashSet;
+    ///
+    /// let expr = parse("x^2 + sin(y)", &HashSet::new(), &HashSet::new(), None).expect("Should parse");
+    /// let compiled = CompiledEvaluator::compile(&expr, &["x", "y"], None).expect("Should compile");
+    ///
+    /// let (lo, hi) = compiled.eval_interval(&[(-1.0, 2.0), (0.0, 3.0)]);
+    /// assert!(lo <= 0.0 && 0.0 - lo < 1e-12);
+    /// assert!(hi >= 5.0 && hi - 5.0 < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn eval_interval(&self, intervals: &[(f64, f64)]) -> (f64, f64) {
+        let mut regs = vec![Interval::ZERO; self.workspace_size.max(1)];
+
+        let provided = self.param_count.min(intervals.len());
+        for (reg, &(a, b)) in regs.iter_mut().zip(&intervals[..provided]) {
+            *reg = Interval::new(a, b);
+        }
+        for (reg, &c) in regs[self.param_count..]
+            .iter_mut()
+            .zip(self.constants.iter())
+        {
+            *reg = Interval::point(c);
+        }
+
+        for instr in &self.instructions {
+            if matches!(instr, Instruction::End {}) {
+                break;
+            }
+            self.exec_interval(instr, &mut regs);
+        }
+
+        let result = regs[self.result_reg as usize];
+        (result.lo, result.hi)
+    }
+
+    #[allow(
+        clippy::too_many_lines,
+        reason = "Single dispatch over the full instruction set"
+    )]
+    fn exec_interval(&self, instr: &Instruction, regs: &mut [Interval]) {
+        let r = |i: &u32| regs[*i as usize];
+        let (dest, value) = match instr {
+            Instruction::End {} => return,
+            Instruction::Copy { dest, src } => (dest, r(src)),
+            Instruction::Neg { dest, src } => (dest, -r(src)),
+            Instruction::SinCos {
+                sin_dest,
+                cos_dest,
+                arg,
+            } => {
+                let x = r(arg);
+                regs[*sin_dest as usize] = x.sin();
+                regs[*cos_dest as usize] = x.cos();
+                return;
+            }
+            Instruction::Add { dest, a, b } => (dest, r(a) + r(b)),
+            Instruction::Add3 { dest, a, b, c } => (dest, r(a) + r(b) + r(c)),
+            Instruction::Add4 { dest, a, b, c, d } => (dest, r(a) + r(b) + r(c) + r(d)),
+            Instruction::AddN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let sum = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(Add::add)
+                    .unwrap_or(Interval::ZERO);
+                (dest, sum)
+            }
+            Instruction::Mul { dest, a, b } => (dest, r(a) * r(b)),
+            Instruction::Mul3 { dest, a, b, c } => (dest, r(a) * r(b) * r(c)),
+            Instruction::Mul4 { dest, a, b, c, d } => (dest, r(a) * r(b) * r(c) * r(d)),
+            Instruction::MulN {
+                dest,
+                start_idx,
+                count,
+            } => {
+                let pool = &self.arg_pool[*start_idx as usize..(*start_idx + *count) as usize];
+                let prod = pool
+                    .iter()
+                    .map(|i| regs[*i as usize])
+                    .reduce(Mul::mul)
+                    .unwrap_or(Interval::ONE);
+                (dest, prod)
+            }
+            Instruction::Sub { dest, a, b } => (dest, r(a) - r(b)),
+            Instruction::Div { dest, num, den } => (dest, r(num) / r(den)),
+            Instruction::Pow { dest, base, exp } => (dest, r(base).pow(r(exp))),
+            Instruction::MulAdd { dest, a, b, c } => (dest, r(a) * r(b) + r(c)),
+            Instruction::MulSub { dest, a, b, c } => (dest, r(a) * r(b) - r(c)),
+            Instruction::NegMul { dest, a, b } => (dest, -(r(a) * r(b))),
+            Instruction::NegMulAdd { dest, a, b, c } => (dest, r(c) - r(a) * r(b)),
+            Instruction::NegMulSub { dest, a, b, c } => (dest, -(r(a) * r(b) + r(c))),
+            Instruction::Square { dest, src } => (dest, r(src).powi(2)),
+            Instruction::Cube { dest, src } => (dest, r(src).powi(3)),
+            Instruction::Pow4 { dest, src } => (dest, r(src).powi(4)),
+            Instruction::Pow3_2 { dest, src } => (dest, r(src).sqrt().powi(3)),
+            Instruction::InvPow3_2 { dest, src } => (dest, r(src).sqrt().powi(3).recip()),
+            Instruction::InvSqrt { dest, src } => (dest, r(src).sqrt().recip()),
+            Instruction::InvSquare { dest, src } => (dest, r(src).powi(-2)),
+            Instruction::InvCube { dest, src } => (dest, r(src).powi(-3)),
+            Instruction::Recip { dest, src } => (dest, r(src).recip()),
+            Instruction::Powi { dest, src, n } => (dest, r(src).powi(*n)),
+            Instruction::Sin { dest, arg } => (dest, r(arg).sin()),
+            Instruction::Cos { dest, arg } => (dest, r(arg).cos()),
+            Instruction::Exp { dest, arg } => (dest, r(arg).exp()),
+            Instruction::Ln { dest, arg } => (dest, r(arg).ln()),
+            Instruction::Sqrt { dest, arg } => (dest, r(arg).sqrt()),
+            Instruction::RecipExpm1 { dest, src } => {
+                (dest, builtin1_interval(FnOp::Expm1, r(src)).recip())
+            }
+            Instruction::ExpSqr { dest, src } => (dest, r(src).powi(2).exp()),
+            Instruction::ExpSqrNeg { dest, src } => (dest, (-r(src).powi(2)).exp()),
+            Instruction::Builtin1 { dest, op, arg } => (dest, builtin1_interval(*op, r(arg))),
+            Instruction::Builtin2 {
+                dest,
+                op: FnOp::Min,
+                arg1,
+                arg2,
+            } => (dest, r(arg1).min(r(arg2))),
+            Instruction::Builtin2 {
+                dest,
+                op: FnOp::Max,
+                arg1,
+                arg2,
+            } => (dest, r(arg1).max(r(arg2))),
+            Instruction::Builtin2 {
+                dest,
+                op: FnOp::Hypot,
+                arg1,
+                arg2,
+            } => (dest, hypot_interval(r(arg1), r(arg2))),
+            Instruction::Builtin2 { dest, .. } | Instruction::Builtin4 { dest, .. } => {
+                (dest, Interval::ENTIRE)
+            }
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Clamp,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, r(arg1).max(r(arg2)).min(r(arg3))),
+            Instruction::Builtin3 {
+                dest,
+                op: FnOp::Select,
+                arg1,
+                arg2,
+                arg3,
+            } => (dest, Interval::select(r(arg1), r(arg2), r(arg3))),
+            Instruction::Builtin3 { dest, .. } => (dest, Interval::ENTIRE),
+            Instruction::MinConst { dest, src, bound } => (dest, r(src).min(r(bound))),
+            Instruction::MaxConst { dest, src, bound } => (dest, r(src).max(r(bound))),
+            Instruction::Clamp { dest, src, lo, hi } => (dest, r(src).max(r(lo)).min(r(hi))),
+            Instruction::Select { dest, cond, a, b } => {
+                (dest, Interval::select(r(cond), r(a), r(b)))
+            }
+            Instruction::SelectNonNeg { dest, src, a, b } => {
+                (dest, Interval::select_non_neg(r(src), r(a), r(b)))
+            }
+            #[allow(
+                clippy::match_same_arms,
+                reason = "Native callbacks are opaque, unlike builtins without a rule yet"
+            )]
+            Instruction::CallExternal { dest, .. } => (dest, Interval::ENTIRE),
+        };
+        regs[*dest as usize] = value;
+    }
+}
+
+/// Enclosure of `hypot(a, b)` over the boxes `a` and `b`
+fn hypot_interval(a: Interval, b: Interval) -> Interval {
+    (a.powi(2) + b.powi(2)).sqrt()
+}
+
+/// Unary builtins with an interval rule; everything else encloses to the whole line.
+fn builtin1_interval(op: FnOp, x: Interval) -> Interval {
+    match op {
+        FnOp::Sin => x.sin(),
+        FnOp::Cos => x.cos(),
+        FnOp::Exp => x.exp(),
+        FnOp::ExpNeg => (-x).exp(),
+        FnOp::Expm1 => x.increasing(f64::exp_m1),
+        FnOp::Ln => x.ln(),
+        FnOp::Log1p => x.restrict(-1.0, f64::INFINITY).increasing(f64::ln_1p),
+        FnOp::Sqrt => x.sqrt(),
+        FnOp::Cbrt => x.increasing(f64::cbrt),
+        FnOp::Abs => x.abs(),
+        FnOp::Atan => x.increasing(f64::atan),
+        FnOp::Asin => x.restrict(-1.0, 1.0).increasing(f64::asin),
+        FnOp::Acos => x.restrict(-1.0, 1.0).decreasing(f64::acos),
+        FnOp::Sinh => x.increasing(f64::sinh),
+        FnOp::Cosh => x.abs().increasing(f64::cosh),
+        FnOp::Tanh => x.increasing(f64::tanh),
+        FnOp::Asinh => x.increasing(f64::asinh),
+        FnOp::Floor => x.increasing(f64::floor),
+        FnOp::Ceil => x.increasing(f64::ceil),
+        FnOp::Signum if !x.is_empty() => Interval::new(
+            if x.lo < 0.0 { -1.0 } else { x.lo.min(0.0) },
+            if x.hi > 0.0 { 1.0 } else { x.hi.max(0.0) },
+        ),
+        FnOp::Heaviside => Interval::select_non_neg(x, Interval::ONE, Interval::ZERO),
+        _ => Interval::ENTIRE,
+    }
+}
+
+/// Enclosure of a call to the builtin with interned name `name_id`, for callers that
+/// walk an expression tree instead of the instruction stream. Unknown names, and
+/// builtins without an interval rule, enclose to the whole line.
+pub fn call_interval(name_id: u64, args: &[Interval]) -> Interval {
+    let Some(&op) = FN_MAP.get(&name_id) else {
+        return Interval::ENTIRE;
+    };
+    match (op, args) {
+        (_, &[x]) => builtin1_interval(op, x),
+        (FnOp::Min, &[a, b]) => a.min(b),
+        (FnOp::Max, &[a, b]) => a.max(b),
+        (FnOp::Hypot, &[a, b]) => hypot_interval(a, b),
+        (FnOp::Clamp, &[x, lo, hi]) => x.max(lo).min(hi),
+        (FnOp::Select, &[cond, a, b]) => Interval::select(cond, a, b),
+        _ => Interval::ENTIRE,
+    }
+}
@@ -1,5 +1,5 @@
 macro_rules! dispatch_loop {
-    ($bytecode:ident, $regs:ident, $arg_pool:ident, $mode:tt, $one:ident, $b1:ident, $b2:ident, $b3:ident, $b4:ident) => {
+    ($bytecode:ident, $regs:ident, $arg_pool:ident, $externals:ident, $mode:tt, $one:ident, $b1:ident, $b2:ident, $b3:ident, $b4:ident) => {
         let mut pc = $bytecode.as_ptr();
 
         loop {
@@ -351,6 +351,60 @@ macro_rules! dispatch_loop {
                         *($regs.add(arg4)),
                     );
                 }
+                42 /* MinConst */ => {
+                    let dest = *pc as usize;
+                    let src = *pc.add(1) as usize;
+                    let bound = *pc.add(2) as usize;
+                    pc = pc.add(3);
+                    let (v, c) = (*($regs.add(src)), *($regs.add(bound)));
+                    *($regs.add(dest)) = dispatch_loop!(@min v, c, $mode);
+                }
+                43 /* MaxConst */ => {
+                    let dest = *pc as usize;
+                    let src = *pc.add(1) as usize;
+                    let bound = *pc.add(2) as usize;
+                    pc = pc.add(3);
+                    let (v, c) = (*($regs.add(src)), *($regs.add(bound)));
+                    *($regs.add(dest)) = dispatch_loop!(@max v, c, $mode);
+                }
+                44 /* Clamp */ => {
+                    let dest = *pc as usize;
+                    let src = *pc.add(1) as usize;
+                    let lo = *pc.add(2) as usize;
+                    let hi = *pc.add(3) as usize;
+                    pc = pc.add(4);
+                    let (v, l, h) = (*($regs.add(src)), *($regs.add(lo)), *($regs.add(hi)));
+                    *($regs.add(dest)) = dispatch_loop!(@clamp v, l, h, $mode);
+                }
+                45 /* Select */ => {
+                    let dest = *pc as usize;
+                    let cond = *pc.add(1) as usize;
+                    let a = *pc.add(2) as usize;
+                    let b = *pc.add(3) as usize;
+                    pc = pc.add(4);
+                    let (c, x, y) = (*($regs.add(cond)), *($regs.add(a)), *($regs.add(b)));
+                    *($regs.add(dest)) = dispatch_loop!(@select c, x, y, $mode);
+                }
+                46 /* SelectNonNeg */ => {
+                    let dest = *pc as usize;
+                    let src = *pc.add(1) as usize;
+                    let a = *pc.add(2) as usize;
+                    let b = *pc.add(3) as usize;
+                    pc = pc.add(4);
+                    let (v, x, y) = (*($regs.add(src)), *($regs.add(a)), *($regs.add(b)));
+                    *($regs.add(dest)) = dispatch_loop!(@select_non_neg v, x, y, $mode);
+                }
+                47 /* CallExternal */ => {
+                    let dest = *pc as usize;
+                    let callback = $externals.get_unchecked(*pc.add(1) as usize);
+                    let start_idx = *pc.add(2) as usize;
+                    let count = *pc.add(3) as usize;
+                    pc = pc.add(4);
+                    let pool = $arg_pool.get_unchecked(start_idx..start_idx + count);
+                    *($regs.add(dest)) = dispatch_loop!(@call_external callback, count, |i| {
+                        *($regs.add(*pool.get_unchecked(i) as usize))
+                    }, $mode);
+                }
                 _ => unsafe { std::hint::unreachable_unchecked() },
             }
         }
@@ -409,6 +463,25 @@ macro_rules! dispatch_loop {
 
     (@sqrt $v:ident, scalar) => { $v.sqrt() };
     (@sqrt $v:ident, simd) => { $v.sqrt() };
+
+    // Branchless picks: scalar compares, SIMD blends lanes by a comparison mask
+    (@min $a:ident, $b:ident, scalar) => { eval_min($a, $b) };
+    (@min $a:ident, $b:ident, simd) => { simd_min($a, $b) };
+
+    (@max $a:ident, $b:ident, scalar) => { eval_max($a, $b) };
+    (@max $a:ident, $b:ident, simd) => { simd_max($a, $b) };
+
+    (@clamp $v:ident, $lo:ident, $hi:ident, scalar) => { eval_clamp($v, $lo, $hi) };
+    (@clamp $v:ident, $lo:ident, $hi:ident, simd) => { simd_clamp($v, $lo, $hi) };
+
+    (@select $c:ident, $a:ident, $b:ident, scalar) => { eval_select($c, $a, $b) };
+    (@select $c:ident, $a:ident, $b:ident, simd) => { simd_select($c, $a, $b) };
+
+    (@select_non_neg $v:ident, $a:ident, $b:ident, scalar) => { select_non_neg($v, $a, $b) };
+    (@select_non_neg $v:ident, $a:ident, $b:ident, simd) => { simd_select_non_neg($v, $a, $b) };
+
+    (@call_external $f:ident, $count:ident, $arg:expr, scalar) => { call_external($f, $count, $arg) };
+    (@call_external $f:ident, $count:ident, $arg:expr, simd) => { call_external_simd($f, $count, $arg) };
 }
 
 /// Macro to handle the dispatch staircase for stack-allocated register files.
@@ -3,7 +3,10 @@
 #[macro_use]
 pub mod macros;
 pub mod builtins;
+pub mod double_double;
+pub mod dual;
 pub mod helpers;
+pub mod interval;
 pub mod scalar;
 
 #[cfg(feature = "parallel")]
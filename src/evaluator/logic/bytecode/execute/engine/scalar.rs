@@ -10,8 +10,12 @@
 )]
 
 use super::CompiledEvaluator;
-use super::builtins::{eval_builtin1, eval_builtin2, eval_builtin3, eval_builtin4};
+use super::builtins::{
+    call_external, eval_builtin1, eval_builtin2, eval_builtin3, eval_builtin4, select_non_neg,
+};
+use crate::core::NumericFn;
 use crate::evaluator::FnOp;
+use crate::math::{eval_clamp, eval_max, eval_min, eval_select};
 use std::cell::RefCell;
 use std::ptr::{copy_nonoverlapping, write_bytes};
 
@@ -40,12 +44,14 @@ impl CompiledEvaluator {
         bytecode: &[u32],
         registers: *mut f64,
         arg_pool: &[u32],
+        externals: &[NumericFn],
     ) {
         let one = 1.0_f64;
         dispatch_loop!(
             bytecode,
             registers,
             arg_pool,
+            externals,
             scalar,
             one,
             eval_builtin1,
@@ -56,9 +62,22 @@ impl CompiledEvaluator {
     }
 
     /// Evaluates the compiled expression at a single point.
+    ///
+    /// With [`EvalOptions::singularity_fallback`](crate::EvalOptions::singularity_fallback)
+    /// enabled, a non-finite result is replaced by the limit at a removable singularity.
     #[inline]
     #[must_use]
     pub fn evaluate(&self, params: &[f64]) -> f64 {
+        let value = self.evaluate_bytecode(params);
+        match &self.singularity_fallback {
+            Some(fallback) if !value.is_finite() => fallback.resolve(params, value),
+            _ => value,
+        }
+    }
+
+    /// Runs the bytecode at a single point, without the singularity fallback.
+    #[inline]
+    fn evaluate_bytecode(&self, params: &[f64]) -> f64 {
         evaluate_staircase!(
             self,
             params,
@@ -121,7 +140,7 @@ impl CompiledEvaluator {
         let ptr = raw.as_mut_ptr().cast::<f64>();
         self.setup_registers(params, ptr);
         unsafe {
-            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool);
+            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool, &self.externals);
             *ptr.add(self.result_reg as usize)
         }
     }
@@ -135,11 +154,98 @@ impl CompiledEvaluator {
         let ptr = registers.as_mut_ptr();
         self.setup_registers(params, ptr);
         unsafe {
-            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool);
+            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool, &self.externals);
             *ptr.add(self.result_reg as usize)
         }
     }
 
+    /// Runs the bytecode at a single point and copies `output_regs` into `out`.
+    ///
+    /// Used by programs with several results, such as
+    /// [`CompiledGradient`](crate::CompiledGradient).
+    pub(crate) fn evaluate_outputs(&self, params: &[f64], output_regs: &[u32], out: &mut [f64]) {
+        if self.workspace_size <= INLINE_REGISTER_SIZE_SMALL {
+            return self.evaluate_outputs_inline::<INLINE_REGISTER_SIZE_SMALL>(
+                params,
+                output_regs,
+                out,
+            );
+        }
+        if self.workspace_size <= INLINE_REGISTER_SIZE_MEDIUM {
+            return self.evaluate_outputs_inline::<INLINE_REGISTER_SIZE_MEDIUM>(
+                params,
+                output_regs,
+                out,
+            );
+        }
+        if self.workspace_size <= INLINE_REGISTER_SIZE_LARGE {
+            return self.evaluate_outputs_inline::<INLINE_REGISTER_SIZE_LARGE>(
+                params,
+                output_regs,
+                out,
+            );
+        }
+
+        HEAP_REGISTERS.with(|heap_registers| {
+            if let Ok(mut registers) = heap_registers.try_borrow_mut() {
+                if registers.len() < self.workspace_size {
+                    registers.resize(self.workspace_size, 0.0);
+                }
+                self.evaluate_outputs_heap(
+                    params,
+                    output_regs,
+                    out,
+                    &mut registers[..self.workspace_size],
+                );
+            } else {
+                let mut fallback_registers = vec![0.0; self.workspace_size];
+                self.evaluate_outputs_heap(params, output_regs, out, &mut fallback_registers);
+            }
+        });
+    }
+
+    #[allow(
+        clippy::inline_always,
+        reason = "Staircase dispatch relies on forced inlining to avoid call overhead"
+    )]
+    #[inline(always)]
+    fn evaluate_outputs_inline<const N: usize>(
+        &self,
+        params: &[f64],
+        output_regs: &[u32],
+        out: &mut [f64],
+    ) {
+        use std::mem::MaybeUninit;
+
+        let mut raw = [MaybeUninit::<f64>::uninit(); N];
+        // SAFETY: As in `evaluate_inline`, every register is written before it is read.
+        let ptr = raw.as_mut_ptr().cast::<f64>();
+        self.setup_registers(params, ptr);
+        unsafe {
+            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool, &self.externals);
+            for (slot, &reg) in out.iter_mut().zip(output_regs) {
+                *slot = *ptr.add(reg as usize);
+            }
+        }
+    }
+
+    fn evaluate_outputs_heap(
+        &self,
+        params: &[f64],
+        output_regs: &[u32],
+        out: &mut [f64],
+        registers: &mut [f64],
+    ) {
+        let ptr = registers.as_mut_ptr();
+        self.setup_registers(params, ptr);
+        unsafe {
+            Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool, &self.externals);
+            for (slot, &reg) in out.iter_mut().zip(output_regs) {
+                *slot = *ptr.add(reg as usize);
+            }
+        }
+    }
+
     /// Evaluate a batch of data points.
     ///
     /// # Errors
@@ -165,10 +271,26 @@ impl CompiledEvaluator {
             self.eval_batch_scalar(columns, output);
         }
 
+        if let Some(fallback) = &self.singularity_fallback {
+            let mut point = vec![0.0; self.param_count];
+            for (i, out) in output.iter_mut().enumerate() {
+                if !out.is_finite() {
+                    for (value, column) in point.iter_mut().zip(columns) {
+                        // Short columns repeat their last point, as the batch loops do
+                        *value = column
+                            .get(i)
+                            .or_else(|| column.last())
+                            .copied()
+                            .unwrap_or(0.0);
+                    }
+                    *out = fallback.resolve(&point, *out);
+                }
+            }
+        }
+
         Ok(())
     }
 
-    #[cfg(feature = "parallel")]
     pub(crate) fn eval_batch_scalar(&self, columns: &[&[f64]], output: &mut [f64]) {
         let mut eval_inner = |workspace: &mut [f64]| {
             let ptr = workspace.as_mut_ptr();
@@ -210,7 +332,12 @@ impl CompiledEvaluator {
                     }
                 }
                 unsafe {
-                    Self::exec_instructions(&self.flat_bytecode, ptr, &self.arg_pool);
+                    Self::exec_instructions(
+                        &self.flat_bytecode,
+                        ptr,
+                        &self.arg_pool,
+                        &self.externals,
+                    );
                     *out = *ptr.add(self.result_reg as usize);
                 }
             }
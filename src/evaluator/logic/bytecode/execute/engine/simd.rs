@@ -10,8 +10,10 @@
 
 use super::CompiledEvaluator;
 use super::builtins::{
-    eval_builtin1_simd, eval_builtin2_simd, eval_builtin3_simd, eval_builtin4_simd,
+    call_external_simd, eval_builtin1_simd, eval_builtin2_simd, eval_builtin3_simd,
+    eval_builtin4_simd, simd_clamp, simd_max, simd_min, simd_select, simd_select_non_neg,
 };
+use crate::core::NumericFn;
 use crate::evaluator::FnOp;
 use wide::f64x4;
 
@@ -28,12 +30,14 @@ impl CompiledEvaluator {
         bytecode: &[u32],
         registers: *mut f64x4,
         arg_pool: &[u32],
+        externals: &[NumericFn],
     ) {
         let one = f64x4::splat(1.0);
         dispatch_loop!(
             bytecode,
             registers,
             arg_pool,
+            externals,
             simd,
             one,
             eval_builtin1_simd,
@@ -85,6 +89,7 @@ impl CompiledEvaluator {
                     &self.flat_bytecode,
                     workspace.as_mut_ptr(),
                     &self.arg_pool,
+                    &self.externals,
                 );
             }
 
@@ -112,4 +117,62 @@ impl CompiledEvaluator {
             self.eval_batch_scalar(&tail_cols, &mut output[i..]);
         }
     }
+
+    /// Evaluates a program with several results at many points, four points at a time.
+    ///
+    /// `out` is row-major: point `i` writes `output_regs.len()` values starting at
+    /// `i * output_regs.len()`. Missing parameters read as `0.0`, and the points left
+    /// over after the last full group of four run on the scalar engine.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn eval_points_simd(
+        &self,
+        points: &[&[f64]],
+        output_regs: &[u32],
+        out: &mut [f64],
+        workspace: &mut [f64x4],
+    ) {
+        const LANES: usize = 4;
+        let width = output_regs.len();
+        if width == 0 {
+            return;
+        }
+
+        for (i, &val) in self.constants.iter().enumerate() {
+            workspace[self.param_count + i] = f64x4::splat(val);
+        }
+
+        let mut groups = points.chunks_exact(LANES);
+        let mut blocks = out.chunks_exact_mut(LANES * width);
+        for (group, rows) in (&mut groups).zip(&mut blocks) {
+            for (param, slot) in workspace[..self.param_count].iter_mut().enumerate() {
+                *slot = f64x4::from(
+                    [0, 1, 2, 3].map(|lane: usize| group[lane].get(param).copied().unwrap_or(0.0)),
+                );
+            }
+
+            unsafe {
+                Self::exec_simd_instructions(
+                    &self.flat_bytecode,
+                    workspace.as_mut_ptr(),
+                    &self.arg_pool,
+                    &self.externals,
+                );
+            }
+
+            for (k, &reg) in output_regs.iter().enumerate() {
+                let lanes: [f64; 4] = workspace[reg as usize].to_array();
+                for (lane, value) in lanes.into_iter().enumerate() {
+                    rows[lane * width + k] = value;
+                }
+            }
+        }
+
+        for (point, row) in groups
+            .remainder()
+            .iter()
+            .zip(blocks.into_remainder().chunks_exact_mut(width))
+        {
+            self.evaluate_outputs(point, output_regs, row);
+        }
+    }
 }
@@ -2,12 +2,11 @@ pub mod drivers;
 pub mod engine;
 
 // Staircase re-exports
-pub use super::CompiledEvaluator;
-#[cfg(feature = "parallel")]
-pub use super::ToParamName;
+pub use super::{CompiledEvaluator, ToParamName};
 #[cfg(all(feature = "parallel", feature = "python"))]
 pub use drivers::evaluate_parallel_with_hint;
 #[cfg(feature = "parallel")]
+pub use drivers::evaluate_parallel_with_pool;
 pub use drivers::{
     EvalResult, ExprInput, SKIP, Value, VarInput, eval_single_expr_chunked, evaluate_parallel,
 };
@@ -1,23 +1,27 @@
 pub mod compile;
+pub mod disassembly;
 pub mod execute;
 pub mod functions;
-pub mod instruction;
+pub mod serialize;
 
 // --- Core API ---
 pub use super::CompiledEvaluator;
-pub use functions::FnOp;
-pub use instruction::Instruction;
+pub use crate::eval_core::functions::FnOp;
+pub use crate::eval_core::instruction::Instruction;
 
 // --- Compilation ---
-pub use compile::{VirGenerator, assemble_flat_bytecode, expand_user_functions};
+pub use compile::{
+    VirGenerator, assemble_flat_bytecode, expand_user_functions, substitute_constants,
+};
 
 // --- Execution & Parallelism ---
+pub use execute::engine::interval;
 #[cfg(all(feature = "parallel", feature = "python"))]
 pub use execute::evaluate_parallel_with_hint;
 #[cfg(feature = "parallel")]
+pub use execute::evaluate_parallel_with_pool;
 pub use execute::{
     EvalResult, ExprInput, SKIP, Value, VarInput, eval_single_expr_chunked, evaluate_parallel,
 };
 
-#[cfg(feature = "parallel")]
 pub use super::ToParamName;
@@ -0,0 +1,113 @@
+//! Compact binary format for [`CompiledEvaluator`].
+//!
+//! The layout is described in `eval_core::format`, which also reads it so that the
+//! `no_std` evaluation core and the host share one decoder. This module writes programs
+//! and turns decoded ones back into evaluators.
+
+use super::{CompiledEvaluator, assemble_flat_bytecode};
+use crate::core::DiffError;
+use crate::eval_core::format::{DecodeError, MAGIC, Operand, RawProgram, VERSION};
+
+impl From<DecodeError> for DiffError {
+    fn from(err: DecodeError) -> Self {
+        Self::InvalidEvaluatorBytes {
+            offset: err.offset,
+            reason: err.reason,
+        }
+    }
+}
+
+/// Append a length as the `u32` that prefixes every list
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    u32::try_from(len)
+        .expect("Evaluator sections are indexed by u32")
+        .encode(out);
+}
+
+impl CompiledEvaluator {
+    /// Serialize the compiled program to a compact binary form.
+    ///
+    /// The bytes can be stored and turned back into an evaluator with
+    /// [`from_bytes`](Self::from_bytes), skipping parsing, simplification and
+    /// compilation. The singularity fallback of
+    /// [`EvalOptions`](crate::EvalOptions) keeps an expression tree and is not stored.
+    /// Neither are the native callbacks of [`UserFunction::numeric`](crate::UserFunction::numeric),
+    /// so a program that calls one is written but refused by [`from_bytes`](Self::from_bytes).
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, symb};
+    ///
+    /// let x = symb("x");
+    /// let eval = CompiledEvaluator::compile(&x.sin().pow(2.0), &["x"], None).unwrap();
+    /// let loaded = CompiledEvaluator::from_bytes(&eval.to_bytes()).unwrap();
+    /// assert_eq!(loaded.evaluate(&[0.5]), eval.evaluate(&[0.5]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Never panics in practice: every section of a compiled program is indexed by `u32`.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + 8 * self.constants.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+
+        write_len(&mut out, self.param_names.len());
+        for name in &self.param_names {
+            write_len(&mut out, name.len());
+            out.extend_from_slice(name.as_bytes());
+        }
+        write_len(&mut out, self.constants.len());
+        for value in &self.constants {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        write_len(&mut out, self.workspace_size);
+        self.result_reg.encode(&mut out);
+        write_len(&mut out, self.arg_pool.len());
+        for &reg in &self.arg_pool {
+            reg.encode(&mut out);
+        }
+        write_len(&mut out, self.instructions.len());
+        for instr in &self.instructions {
+            instr.encode(&mut out);
+        }
+        out
+    }
+
+    /// Load an evaluator written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// The program is validated before it is accepted, so untrusted bytes cannot make
+    /// evaluation read outside its workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiffError::InvalidEvaluatorBytes`] with the offending byte offset if
+    /// the data is truncated, has the wrong magic number or format version, or
+    /// describes an invalid program or one that calls a native callback.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DiffError> {
+        let RawProgram {
+            param_names,
+            constants,
+            workspace_size,
+            result_reg,
+            arg_pool,
+            instructions,
+        } = RawProgram::decode(data, |_| true)?;
+
+        let flat_bytecode = assemble_flat_bytecode(&instructions);
+        Ok(Self {
+            instructions: instructions.into_boxed_slice(),
+            flat_bytecode: flat_bytecode.into_boxed_slice(),
+            constants: constants.into_boxed_slice(),
+            arg_pool: arg_pool.into_boxed_slice(),
+            param_count: param_names.len(),
+            param_names: param_names.into_boxed_slice(),
+            workspace_size,
+            result_reg,
+            externals: Box::default(),
+            singularity_fallback: None,
+            data_sums: None,
+        })
+    }
+}
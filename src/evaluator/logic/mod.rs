@@ -7,9 +7,11 @@ pub(super) mod tree;
 // Crate-internal re-exports (for other modules like diff/compiler)
 pub use bytecode::{
     FnOp, Instruction, VirGenerator, assemble_flat_bytecode, expand_user_functions,
+    substitute_constants,
 };
 
 #[cfg(feature = "parallel")]
+pub use bytecode::evaluate_parallel_with_pool;
 pub use bytecode::{
     EvalResult, ExprInput, SKIP, Value, VarInput, eval_single_expr_chunked, evaluate_parallel,
 };
@@ -17,11 +19,11 @@ pub use bytecode::{
 #[cfg(all(feature = "parallel", feature = "python"))]
 pub use bytecode::evaluate_parallel_with_hint;
 
-pub use tree::VarLookup;
+pub use bytecode::interval;
+pub use tree::{SingularityFallback, VarLookup};
 
 pub use super::CompiledEvaluator;
 
-#[cfg(feature = "parallel")]
 pub use super::ToParamName;
 
 #[cfg(test)]
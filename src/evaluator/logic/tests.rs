@@ -757,6 +757,26 @@ fn test_fused_sqrt() {
     assert!((eval.evaluate(&[9.0]) - 3.0).abs() < 1e-10);
 }
 
+#[test]
+fn test_const_chain_with_unpooled_product_compiles() {
+    // Folding the two constants of `(c*x)/3` needs a constant that is not in the pool
+    for (formula, expected) in [
+        ("0.5*x/3", 0.5 * 300.0 / 3.0),
+        ("-0.5*x/3", -0.5 * 300.0 / 3.0),
+        ("(x + 0.25) + 0.125", 300.375),
+        ("ln(sqrt(x))", 300.0_f64.sqrt().ln()),
+    ] {
+        let expr = parse_expr(formula);
+        let eval = CompiledEvaluator::compile(&expr, &["x"], None)
+            .unwrap_or_else(|e| panic!("{formula} should compile: {e:?}"));
+        let got = eval.evaluate(&[300.0]);
+        assert!(
+            (got - expected).abs() <= 1e-12 * expected.abs(),
+            "{formula}: got {got}, expected {expected}"
+        );
+    }
+}
+
 #[test]
 fn test_ln_exp_preserves_overflow_behavior() {
     let expr = parse_expr("ln(exp(x))");
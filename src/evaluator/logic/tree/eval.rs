@@ -6,6 +6,9 @@
 //! The `evaluate` method accepts any type implementing `VarLookup`, including:
 //! - `HashMap<&str, f64>` - string-based keys (convenient)
 //! - `FxHashMap<u64, f64>` - ID-based keys (fast, use `symbol.id()`)
+//! - `HashMap<Symbol, f64>` - symbol keys, matched by identity
+//!
+//! [`Expr::partial_eval`] binds a subset of variables and re-simplifies the rest.
 
 use std::collections::HashMap;
 use std::hash::BuildHasher;
@@ -13,6 +16,7 @@ use std::sync::Arc;
 
 use crate::core::CustomEvalMap;
 use crate::core::Expr;
+use crate::core::Symbol;
 use crate::core::known_symbols::get_constant_value;
 use crate::core::{ExprKind, InternedSymbol};
 use crate::functions::Registry;
@@ -42,6 +46,14 @@ impl<S: BuildHasher> VarLookup for HashMap<u64, f64, S> {
     }
 }
 
+// Symbol-based lookup (identity, so symbols of a `Context` match too)
+impl<S: BuildHasher> VarLookup for HashMap<Symbol, f64, S> {
+    #[inline]
+    fn get_value(&self, symbol: &InternedSymbol) -> Option<f64> {
+        self.get(&Symbol::from_id(symbol.id())).copied()
+    }
+}
+
 // Support empty map (no variables)
 impl VarLookup for () {
     #[inline]
@@ -51,6 +63,38 @@ impl VarLookup for () {
 }
 
 impl Expr {
+    /// Bind some variables to numbers and simplify what remains symbolic.
+    ///
+    /// Every symbol found in `assignments` becomes a `Number`, numeric subexpressions
+    /// are folded as in [`evaluate`](Self::evaluate), and the result is re-simplified.
+    /// Function calls with numeric arguments, powers and polynomials whose variable is
+    /// bound fold to numbers, and the constants `e` and `pi` take their values.
+    /// Variables not in `assignments` are left free, so applying `partial_eval` with
+    /// disjoint sets one after the other matches a single call with their union.
+    ///
+    /// `assignments` is any [`VarLookup`]: keyed by name, by `symbol.id()`, or by
+    /// [`Symbol`], which matches symbols of a [`Context`](crate::Context) by identity.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{parse, symb};
+    /// use std::collections::{HashMap, HashSet};
+    ///
+    /// let expr = parse("a*x^2 + b*x", &HashSet::new(), &HashSet::new(), None).unwrap();
+    /// let bound = expr.partial_eval(&HashMap::from([("a", 2.0)]));
+    /// assert_eq!(bound.to_string(), "2*x^2 + b*x");
+    ///
+    /// let (k, t, a) = (symb("k"), symb("T"), symb("A"));
+    /// let expr = k * t.pow(2.0) + a * (-1.0 / (k * t)).exp();
+    /// let reduced = expr.partial_eval(&HashMap::from([(k, 2.0), (t, 0.5)]));
+    /// assert_eq!(reduced.to_string(), "0.5 + 0.36787944117144233*A");
+    /// ```
+    #[must_use]
+    pub fn partial_eval<V: VarLookup>(&self, assignments: &V) -> Self {
+        let folded = self.evaluate(assignments, &CustomEvalMap::default());
+        folded.simplified().unwrap_or(folded)
+    }
+
     /// Evaluate expression by substituting known variable values.
     ///
     /// This substitutes numeric values for variables and evaluates any subexpressions
@@ -249,6 +293,24 @@ impl Expr {
                     Self::poly(new_poly)
                 }
             }
+            ExprKind::Piecewise { branches, default } => {
+                // Branches whose condition is decided false drop out, and the first one
+                // decided true replaces the default; undecided ones stay symbolic
+                let mut remaining = Vec::new();
+                for (condition, value) in branches {
+                    let condition =
+                        condition.map_sides(|side| Arc::new(side.evaluate(vars, custom_evals)));
+                    match condition.as_bool() {
+                        Some(false) => {}
+                        Some(true) => {
+                            let value = value.evaluate(vars, custom_evals);
+                            return Self::piecewise(remaining, value);
+                        }
+                        None => remaining.push((condition, value.evaluate(vars, custom_evals))),
+                    }
+                }
+                Self::piecewise(remaining, default.evaluate(vars, custom_evals))
+            }
         }
     }
 }
@@ -0,0 +1,205 @@
+//! Limit evaluation at removable singularities.
+//!
+//! Used by [`CompiledEvaluator`](crate::CompiledEvaluator) when it was compiled with
+//! `singularity_fallback` and the bytecode produced a non-finite value. The expression
+//! tree is re-evaluated at the same point, approaching it along a fixed line
+//! `x = p + t·d`:
+//!
+//! - a quotient whose numerator and denominator both vanish (or both diverge) is
+//!   resolved with L'Hôpital's rule, differentiating both sides along `d`;
+//! - a product `0·∞` is rewritten as the quotient `∞ / (1/0)` (or `0 / (1/∞)`);
+//! - the powers `0^0`, `∞^0` and `1^∞` are rewritten as `exp(e·ln b)`.
+//!
+//! Everything else evaluates exactly as the bytecode would, so genuine poles such as
+//! `1/x` at `0` stay infinite.
+
+use std::sync::Arc;
+
+use rustc_hash::FxHashMap;
+
+use crate::core::known_symbols::{KS, get_constant_value, get_symbol};
+use crate::core::{Expr, ExprKind};
+use crate::functions::Registry;
+
+/// Maximum number of nested rewrites (L'Hôpital steps) per evaluation.
+const MAX_REWRITES: usize = 8;
+
+/// Weight of parameter `index` in the direction `d` of the line `x = p + t·d`.
+///
+/// The weights `1 + frac(i·φ⁻¹)` are pairwise distinct, which keeps the line off
+/// diagonals such as `x = y` along which `x - y` would vanish identically.
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "Parameter indices are far below 2^52"
+)]
+fn direction(index: usize) -> f64 {
+    1.0 + (index as f64 * 0.618_033_988_749_895).fract()
+}
+
+/// Expression kept alongside the bytecode to resolve removable singularities.
+#[derive(Clone, Debug)]
+pub struct SingularityFallback {
+    /// The expression the bytecode was compiled from (user functions expanded)
+    expr: Expr,
+    /// Parameter `(id, name)` pairs in evaluation order
+    params: Vec<(u64, String)>,
+}
+
+impl SingularityFallback {
+    /// Keep `expr` for limit evaluation over `params`.
+    pub const fn new(expr: Expr, params: Vec<(u64, String)>) -> Self {
+        Self { expr, params }
+    }
+
+    /// Limit of the expression at `point`, or `fast` if no finite limit is found.
+    ///
+    /// `fast` is the non-finite value the bytecode produced; it is returned unchanged
+    /// for genuine singularities.
+    pub fn resolve(&self, point: &[f64], fast: f64) -> f64 {
+        let values = self
+            .params
+            .iter()
+            .zip(point)
+            .map(|((id, _), &value)| (*id, value))
+            .collect();
+        let limit = Limit {
+            values,
+            params: &self.params,
+        }
+        .eval(&self.expr, MAX_REWRITES);
+        if limit.is_finite() { limit } else { fast }
+    }
+}
+
+/// One limit evaluation at a fixed point.
+struct Limit<'fallback> {
+    values: FxHashMap<u64, f64>,
+    params: &'fallback [(u64, String)],
+}
+
+impl Limit<'_> {
+    /// Value of `expr` at the point, resolving indeterminate forms with up to `budget`
+    /// nested rewrites.
+    fn eval(&self, expr: &Expr, budget: usize) -> f64 {
+        match &expr.kind {
+            ExprKind::Number(n) => *n,
+            ExprKind::Symbol(s) => self
+                .values
+                .get(&s.id())
+                .copied()
+                .or_else(|| s.name().and_then(get_constant_value))
+                .unwrap_or(f64::NAN),
+            ExprKind::Sum(terms) => terms.iter().map(|t| self.eval(t, budget)).sum(),
+            ExprKind::Product(factors) => self.product(factors, budget),
+            ExprKind::Div(num, den) => {
+                let (n, d) = (self.eval(num, budget), self.eval(den, budget));
+                if (n == 0.0 && d == 0.0) || (n.is_infinite() && d.is_infinite()) {
+                    self.lhopital(num, den, budget)
+                } else {
+                    n / d
+                }
+            }
+            ExprKind::Pow(base, exp) => {
+                let (b, e) = (self.eval(base, budget), self.eval(exp, budget));
+                #[allow(
+                    clippy::float_cmp,
+                    reason = "1^inf is only indeterminate for an exact 1"
+                )]
+                let indeterminate =
+                    (e == 0.0 && (b == 0.0 || b.is_infinite())) || (b == 1.0 && e.is_infinite());
+                if indeterminate && budget > 0 {
+                    let ln_base = Expr::func_symbol(get_symbol(KS.ln), (**base).clone());
+                    self.eval(&Expr::mul_expr((**exp).clone(), ln_base), budget - 1)
+                        .exp()
+                } else {
+                    b.powf(e)
+                }
+            }
+            ExprKind::FunctionCall { name, args } => {
+                let args: Vec<f64> = args.iter().map(|a| self.eval(a, budget)).collect();
+                Registry::get_by_symbol(name).map_or(f64::NAN, |def| (def.eval)(&args))
+            }
+            ExprKind::Poly(poly) => {
+                let base = self.eval(poly.base(), budget);
+                poly.terms().iter().fold(0.0, |acc, &(pow, coeff)| {
+                    #[allow(
+                        clippy::cast_possible_wrap,
+                        reason = "Polynomial powers are small positive integers"
+                    )]
+                    coeff.mul_add(base.powi(pow as i32), acc)
+                })
+            }
+            ExprKind::Derivative { .. } => f64::NAN,
+            ExprKind::Piecewise { branches, default } => branches
+                .iter()
+                .find(|(condition, _)| {
+                    let lhs = self.eval(&condition.lhs, budget);
+                    condition.op.holds(lhs, self.eval(&condition.rhs, budget))
+                })
+                .map_or_else(
+                    || self.eval(default, budget),
+                    |(_, value)| self.eval(value, budget),
+                ),
+        }
+    }
+
+    /// Product of `factors`, resolving `0·∞` as a quotient.
+    fn product(&self, factors: &[Arc<Expr>], budget: usize) -> f64 {
+        let values: Vec<f64> = factors.iter().map(|f| self.eval(f, budget)).collect();
+        let has_zero = values.contains(&0.0);
+        let has_infinite = values.iter().any(|v| v.is_infinite());
+        if !has_zero || !has_infinite || values.iter().any(|v| v.is_nan()) {
+            return values.iter().product();
+        }
+
+        let mut finite = 1.0;
+        let mut zeros = Vec::new();
+        let mut infinite = Vec::new();
+        for (factor, &value) in factors.iter().zip(&values) {
+            if value == 0.0 {
+                zeros.push((**factor).clone());
+            } else if value.is_infinite() {
+                infinite.push((**factor).clone());
+            } else {
+                finite *= value;
+            }
+        }
+        let zeros = Expr::product(zeros);
+        let infinite = Expr::product(infinite);
+
+        // ∞ / (1/0) suits logarithmic factors (x·ln x); 0 / (1/∞) suits the rest
+        let reciprocal = |e: &Expr| Expr::div_expr(Expr::number(1.0), e.clone());
+        let limit = self.lhopital(&infinite, &reciprocal(&zeros), budget);
+        let limit = if limit.is_finite() {
+            limit
+        } else {
+            self.lhopital(&zeros, &reciprocal(&infinite), budget)
+        };
+        finite * limit
+    }
+
+    /// L'Hôpital's rule: the limit of `num/den` as the limit of `D num / D den`.
+    fn lhopital(&self, num: &Expr, den: &Expr, budget: usize) -> f64 {
+        if budget == 0 {
+            return f64::NAN;
+        }
+        let quotient = Expr::div_expr(self.directional(num), self.directional(den));
+        quotient
+            .simplified()
+            .map_or(f64::NAN, |q| self.eval(&q, budget - 1))
+    }
+
+    /// Derivative of `expr` along the line direction: `Σᵢ dᵢ ∂expr/∂xᵢ`.
+    fn directional(&self, expr: &Expr) -> Expr {
+        let terms = self
+            .params
+            .iter()
+            .enumerate()
+            .filter(|(_, (id, _))| expr.contains_var_id(*id))
+            .map(|(i, (_, name))| {
+                Expr::mul_expr(Expr::number(direction(i)), expr.derive(name, None))
+            })
+            .collect();
+        Expr::sum(terms)
+    }
+}
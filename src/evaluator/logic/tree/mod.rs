@@ -1,2 +1,4 @@
 mod eval;
+mod limit;
 pub use eval::VarLookup;
+pub use limit::SingularityFallback;
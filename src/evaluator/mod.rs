@@ -7,6 +7,9 @@
 //! SIMD, and parallel execution.
 
 mod api;
+mod data;
+mod gradient;
 mod logic;
 
 pub use api::*;
+pub use gradient::CompiledGradient;
@@ -123,7 +123,7 @@ pub fn get_inverse_hyperbolic_definitions() -> Vec<FunctionDefinition> {
             name: "acsch",
             arity: 1..=1,
             eval: |args| {
-                if args[0].abs() < 1e-15 {
+                if args[0] == 0.0 {
                     f64::NAN
                 } else {
                     (1.0_f64 / args[0]).asinh()
@@ -184,5 +184,77 @@ pub fn get_inverse_trig_definitions() -> Vec<FunctionDefinition> {
                 )
             },
         },
+        // Inverse trigonometric in degrees
+        FunctionDefinition {
+            name: "asind",
+            arity: 1..=1,
+            eval: |args| args[0].asin().to_degrees(),
+            derivative: |args, arg_primes| {
+                // d/dx asind(u) = (180/pi) * u' / sqrt(1 - u^2)
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::div_expr(
+                        degrees_per_radian(),
+                        Expr::func_symbol(
+                            get_symbol(KS.sqrt),
+                            Expr::sub_expr(
+                                Expr::number(1.0),
+                                Expr::pow_from_arcs(u, Arc::new(Expr::number(2.0))),
+                            ),
+                        ),
+                    ),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "acosd",
+            arity: 1..=1,
+            eval: |args| args[0].acos().to_degrees(),
+            derivative: |args, arg_primes| {
+                // d/dx acosd(u) = -(180/pi) * u' / sqrt(1 - u^2)
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::negate(Expr::div_expr(
+                        degrees_per_radian(),
+                        Expr::func_symbol(
+                            get_symbol(KS.sqrt),
+                            Expr::sub_expr(
+                                Expr::number(1.0),
+                                Expr::pow_from_arcs(u, Arc::new(Expr::number(2.0))),
+                            ),
+                        ),
+                    )),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "atand",
+            arity: 1..=1,
+            eval: |args| args[0].atan().to_degrees(),
+            derivative: |args, arg_primes| {
+                // d/dx atand(u) = (180/pi) * u' / (1 + u^2)
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::div_expr(
+                        degrees_per_radian(),
+                        Expr::add_expr(
+                            Expr::number(1.0),
+                            Expr::pow_from_arcs(u, Arc::new(Expr::number(2.0))),
+                        ),
+                    ),
+                    u_prime,
+                )
+            },
+        },
     ]
 }
+
+/// `180/pi`, the chain-rule factor of an angle returned in degrees
+fn degrees_per_radian() -> Expr {
+    Expr::div_expr(Expr::number(180.0), Expr::symbol("pi"))
+}
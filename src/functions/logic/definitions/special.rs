@@ -2,10 +2,11 @@ use super::FunctionDefinition;
 use crate::core::Expr;
 use crate::core::known_symbols::{KS, get_symbol};
 use crate::math::{
-    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_digamma,
-    eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc, eval_exp_polar, eval_gamma,
-    eval_hermite, eval_lambert_w, eval_lgamma, eval_polygamma, eval_spherical_harmonic,
-    eval_tetragamma, eval_trigamma, eval_zeta_deriv,
+    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_clamp,
+    eval_digamma, eval_dirac, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc,
+    eval_exp_polar, eval_gamma, eval_heaviside, eval_hermite, eval_lambert_w, eval_lgamma,
+    eval_max, eval_min, eval_polygamma, eval_select, eval_spherical_harmonic, eval_tetragamma,
+    eval_trigamma, eval_zeta_deriv,
 };
 use std::sync::Arc;
 
@@ -27,13 +28,127 @@ pub fn get_special_definitions() -> Vec<FunctionDefinition> {
                 )
             },
         },
+        FunctionDefinition {
+            name: "hypot",
+            arity: 2..=2,
+            eval: |args| args[0].hypot(args[1]),
+            derivative: |args, arg_primes| {
+                // d/dx hypot(f, g) = (f*f' + g*g') / hypot(f, g)
+                let f = Arc::clone(&args[0]);
+                let g = Arc::clone(&args[1]);
+                let numerator = Expr::add_expr(
+                    Expr::mul_expr(Expr::unwrap_arc(Arc::clone(&f)), arg_primes[0].clone()),
+                    Expr::mul_expr(Expr::unwrap_arc(Arc::clone(&g)), arg_primes[1].clone()),
+                );
+                Expr::div_expr(
+                    numerator,
+                    Expr::func_multi_from_arcs_symbol(get_symbol(KS.hypot), vec![f, g]),
+                )
+            },
+        },
         FunctionDefinition {
             name: "signum",
             arity: 1..=1,
             eval: |args| args[0].signum(),
-            derivative: |_, _| {
-                // d/dx signum(u) = 0 almost everywhere
-                Expr::number(0.0)
+            derivative: |args, arg_primes| {
+                // d/dx signum(u) = 2 * dirac(u) * u'
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::mul_expr(
+                        Expr::number(2.0),
+                        Expr::func_multi_from_arcs_symbol(get_symbol(KS.dirac), vec![u]),
+                    ),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "heaviside",
+            arity: 1..=1,
+            eval: |args| eval_heaviside(args[0]),
+            derivative: |args, arg_primes| {
+                // d/dx heaviside(u) = dirac(u) * u'
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::func_multi_from_arcs_symbol(get_symbol(KS.dirac), vec![u]),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "dirac",
+            arity: 1..=1,
+            eval: |args| eval_dirac(args[0]),
+            derivative: |args, arg_primes| {
+                // d/dx dirac(u) = dirac_prime(u) * u', kept symbolic
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::func_multi_from_arcs_symbol(get_symbol(KS.dirac_prime), vec![u]),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "min",
+            arity: 2..=2,
+            eval: |args| eval_min(args[0], args[1]),
+            derivative: |args, arg_primes| {
+                // d/dx min(a, b) = heaviside(b - a) * a' + (1 - heaviside(b - a)) * b'
+                let picks_a = step_of_difference(&args[1], &args[0]);
+                gated_sum(picks_a, arg_primes[0].clone(), arg_primes[1].clone())
+            },
+        },
+        FunctionDefinition {
+            name: "max",
+            arity: 2..=2,
+            eval: |args| eval_max(args[0], args[1]),
+            derivative: |args, arg_primes| {
+                // d/dx max(a, b) = heaviside(a - b) * a' + (1 - heaviside(a - b)) * b'
+                let picks_a = step_of_difference(&args[0], &args[1]);
+                gated_sum(picks_a, arg_primes[0].clone(), arg_primes[1].clone())
+            },
+        },
+        FunctionDefinition {
+            name: "clamp",
+            arity: 3..=3,
+            eval: |args| eval_clamp(args[0], args[1], args[2]),
+            derivative: |args, arg_primes| {
+                // d/dx clamp(u, lo, hi) = inside * u' + (1 - above_lo) * lo' + (1 - below_hi) * hi'
+                // with above_lo = heaviside(u - lo), below_hi = heaviside(hi - u) and
+                // inside = above_lo * below_hi. Both edges count as inside, since
+                // heaviside(0) = 1, so the slope is 1 on [lo, hi] and 0 outside it.
+                // The bounds are assumed ordered, lo <= hi.
+                let (u, lo, hi) = (&args[0], &args[1], &args[2]);
+                let above_lo = step_of_difference(u, lo);
+                let below_hi = step_of_difference(hi, u);
+                let inside = Expr::mul_expr(above_lo.clone(), below_hi.clone());
+                Expr::add_expr(
+                    Expr::add_expr(
+                        Expr::mul_expr(inside, arg_primes[0].clone()),
+                        Expr::mul_expr(complement(above_lo), arg_primes[1].clone()),
+                    ),
+                    Expr::mul_expr(complement(below_hi), arg_primes[2].clone()),
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "select",
+            arity: 3..=3,
+            eval: |args| eval_select(args[0], args[1], args[2]),
+            derivative: |args, arg_primes| {
+                // d/dx select(c, a, b) = select(c, a', b'), the condition being piecewise constant
+                let cond = Arc::clone(&args[0]);
+                Expr::func_multi_from_arcs_symbol(
+                    get_symbol(KS.select),
+                    vec![
+                        cond,
+                        Arc::new(arg_primes[1].clone()),
+                        Arc::new(arg_primes[2].clone()),
+                    ],
+                )
             },
         },
         FunctionDefinition {
@@ -768,3 +883,25 @@ pub fn get_special_definitions() -> Vec<FunctionDefinition> {
         },
     ]
 }
+
+/// `heaviside(a - b)`: 1 where `a >= b`, 0 below
+fn step_of_difference(a: &Arc<Expr>, b: &Arc<Expr>) -> Expr {
+    let difference = Expr::sub_expr(
+        Expr::unwrap_arc(Arc::clone(a)),
+        Expr::unwrap_arc(Arc::clone(b)),
+    );
+    Expr::func_multi_from_arcs_symbol(get_symbol(KS.heaviside), vec![Arc::new(difference)])
+}
+
+/// `1 - indicator`
+fn complement(indicator: Expr) -> Expr {
+    Expr::sub_expr(Expr::number(1.0), indicator)
+}
+
+/// `indicator * if_set + (1 - indicator) * if_clear`
+fn gated_sum(indicator: Expr, if_set: Expr, if_clear: Expr) -> Expr {
+    Expr::add_expr(
+        Expr::mul_expr(indicator.clone(), if_set),
+        Expr::mul_expr(complement(indicator), if_clear),
+    )
+}
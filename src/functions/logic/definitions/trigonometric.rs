@@ -106,5 +106,65 @@ pub fn get_trigonometric_definitions() -> Vec<FunctionDefinition> {
                 )
             },
         },
+        // Trigonometric in degrees
+        FunctionDefinition {
+            name: "sind",
+            arity: 1..=1,
+            eval: |args| args[0].to_radians().sin(),
+            derivative: |args, arg_primes| {
+                // d/dx sind(u) = (pi/180) * cosd(u) * u'
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::mul_expr(
+                        radians_per_degree(),
+                        Expr::func_multi_from_arcs_symbol(get_symbol(KS.cosd), vec![u]),
+                    ),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "cosd",
+            arity: 1..=1,
+            eval: |args| args[0].to_radians().cos(),
+            derivative: |args, arg_primes| {
+                // d/dx cosd(u) = -(pi/180) * sind(u) * u'
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::negate(Expr::mul_expr(
+                        radians_per_degree(),
+                        Expr::func_multi_from_arcs_symbol(get_symbol(KS.sind), vec![u]),
+                    )),
+                    u_prime,
+                )
+            },
+        },
+        FunctionDefinition {
+            name: "tand",
+            arity: 1..=1,
+            eval: |args| args[0].to_radians().tan(),
+            derivative: |args, arg_primes| {
+                // d/dx tand(u) = (pi/180) / cosd(u)^2 * u'
+                let u = Arc::clone(&args[0]);
+                let u_prime = arg_primes[0].clone();
+                Expr::mul_expr(
+                    Expr::div_expr(
+                        radians_per_degree(),
+                        Expr::pow(
+                            Expr::func_multi_from_arcs_symbol(get_symbol(KS.cosd), vec![u]),
+                            Expr::number(2.0),
+                        ),
+                    ),
+                    u_prime,
+                )
+            },
+        },
     ]
 }
+
+/// `pi/180`, the chain-rule factor of an angle given in degrees
+fn radians_per_degree() -> Expr {
+    Expr::div_expr(Expr::symbol("pi"), Expr::number(180.0))
+}
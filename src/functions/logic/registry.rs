@@ -1,6 +1,6 @@
 use super::definitions::all_definitions;
 use crate::core::Expr;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::ops::RangeInclusive;
 use std::sync::{Arc, OnceLock};
 
@@ -34,7 +34,7 @@ impl FunctionDefinition {
     }
 }
 
-use crate::core::{InternedSymbol, symb_interned};
+use crate::core::{InternedSymbol, InverseCaveat, symb_interned};
 
 /// Static registry storing all function definitions
 /// Maps symbol ID -> `FunctionDefinition` for fast O(1) lookup
@@ -54,6 +54,63 @@ fn init_registry() -> FxHashMap<u64, FunctionDefinition> {
     map
 }
 
+/// Built-in `(outer, inner)` pairs for which `outer(inner(x))` collapses to `x`.
+const INVERSE_PAIRS: &[(&str, &str, InverseCaveat)] = &[
+    // f(f⁻¹(x)): holds on the domain of the inverse
+    ("sin", "asin", InverseCaveat::RestrictedDomain),
+    ("cos", "acos", InverseCaveat::RestrictedDomain),
+    ("tan", "atan", InverseCaveat::None),
+    ("cot", "acot", InverseCaveat::None),
+    ("sec", "asec", InverseCaveat::RestrictedDomain),
+    ("csc", "acsc", InverseCaveat::RestrictedDomain),
+    ("sinh", "asinh", InverseCaveat::None),
+    ("cosh", "acosh", InverseCaveat::RestrictedDomain),
+    ("tanh", "atanh", InverseCaveat::RestrictedDomain),
+    ("coth", "acoth", InverseCaveat::RestrictedDomain),
+    ("sech", "asech", InverseCaveat::RestrictedDomain),
+    ("csch", "acsch", InverseCaveat::RestrictedDomain),
+    ("exp", "ln", InverseCaveat::RestrictedDomain),
+    // f⁻¹(f(x)): periodic functions only invert on the principal branch. acot, asec and
+    // acsc of their inverses are left alone, since no assumption puts x on that branch
+    ("asin", "sin", InverseCaveat::PrincipalRange),
+    ("acos", "cos", InverseCaveat::PrincipalRange),
+    ("atan", "tan", InverseCaveat::PrincipalRange),
+    ("asinh", "sinh", InverseCaveat::None),
+    ("atanh", "tanh", InverseCaveat::None),
+    ("acoth", "coth", InverseCaveat::RestrictedDomain),
+    ("acsch", "csch", InverseCaveat::RestrictedDomain),
+    ("ln", "exp", InverseCaveat::None),
+];
+
+/// Built-in `(outer, inner)` pairs of an even `inner` whose inverse returns the
+/// non-negative branch, so `outer(inner(x))` is `|x|` for every real `x`.
+const EVEN_INVERSE_PAIRS: &[(&str, &str)] = &[("acosh", "cosh"), ("asech", "sech")];
+
+/// Inverse pairs keyed by `(outer id, inner id)`
+static INVERSES: OnceLock<FxHashMap<(u64, u64), InverseCaveat>> = OnceLock::new();
+
+/// [`EVEN_INVERSE_PAIRS`] as `(outer id, inner id)`
+static EVEN_INVERSES: OnceLock<FxHashSet<(u64, u64)>> = OnceLock::new();
+
+fn init_inverses() -> FxHashMap<(u64, u64), InverseCaveat> {
+    INVERSE_PAIRS
+        .iter()
+        .map(|&(outer, inner, caveat)| {
+            (
+                (symb_interned(outer).id(), symb_interned(inner).id()),
+                caveat,
+            )
+        })
+        .collect()
+}
+
+fn init_even_inverses() -> FxHashSet<(u64, u64)> {
+    EVEN_INVERSE_PAIRS
+        .iter()
+        .map(|&(outer, inner)| (symb_interned(outer).id(), symb_interned(inner).id()))
+        .collect()
+}
+
 /// Central registry for getting function definitions
 pub struct Registry;
 
@@ -62,4 +119,19 @@ impl Registry {
     pub(crate) fn get_by_symbol(sym: &InternedSymbol) -> Option<&'static FunctionDefinition> {
         REGISTRY.get_or_init(init_registry).get(&sym.id())
     }
+
+    /// Caveat for collapsing `outer(inner(x))` to `x`, if the two built-ins are inverses
+    pub(crate) fn inverse_caveat(outer: u64, inner: u64) -> Option<InverseCaveat> {
+        INVERSES
+            .get_or_init(init_inverses)
+            .get(&(outer, inner))
+            .copied()
+    }
+
+    /// Whether `outer(inner(x))` is `|x|` for the built-ins `outer` and `inner`
+    pub(crate) fn inverse_is_abs(outer: u64, inner: u64) -> bool {
+        EVEN_INVERSES
+            .get_or_init(init_even_inverses)
+            .contains(&(outer, inner))
+    }
 }
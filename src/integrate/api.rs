@@ -0,0 +1,211 @@
+//! User-facing integration API.
+//!
+//! This module provides the [`Integrate`] builder and the convenience [`integrate`] function.
+
+use super::logic::engine::Integrator;
+use crate::core::{Context, DiffError, Expr, Symbol, symb};
+use crate::evaluator::ToParamName;
+use crate::parser::{SourceMap, parse};
+use crate::simplification::{CustomBodyMap, simplify_expr};
+use crate::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_NODES};
+use std::collections::HashSet;
+
+/// Builder for indefinite integration
+///
+/// Antiderivatives are returned without an integration constant and simplified.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{Integrate, symb};
+/// let x = symb("x");
+/// let f = Integrate::new().integrate(&x.cos(), &x).unwrap();
+/// assert_eq!(f.to_string(), "sin(x)");
+/// ```
+#[derive(Clone, Default)]
+pub struct Integrate {
+    /// Whether to apply only domain-safe transformations
+    domain_safe: bool,
+    max_depth: Option<usize>,
+    /// Maximum number of nodes in the expression tree
+    max_nodes: Option<usize>,
+    /// Evaluation context
+    context: Option<Context>,
+    /// Known symbols for parsing
+    known_symbols: HashSet<String>,
+}
+
+impl Integrate {
+    /// Create a new integration builder with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable domain-safe mode (skips domain-altering rules)
+    #[inline]
+    #[must_use]
+    pub const fn domain_safe(mut self, safe: bool) -> Self {
+        self.domain_safe = safe;
+        self
+    }
+
+    /// Set the Context for parsing and integration.
+    #[inline]
+    #[must_use]
+    pub fn context(mut self, context: &Context) -> Self {
+        self.context = Some(context.clone());
+        self
+    }
+
+    /// Set maximum AST depth
+    #[inline]
+    #[must_use]
+    pub const fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Set maximum AST node count
+    #[inline]
+    #[must_use]
+    pub const fn max_nodes(mut self, nodes: usize) -> Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Register a variable as constant during integration
+    #[inline]
+    #[must_use]
+    pub fn fixed_var<P: ToParamName>(mut self, var: &P) -> Self {
+        let (_, name) = var.to_param_id_and_name();
+        self.known_symbols.insert(name);
+        self
+    }
+
+    /// Register multiple variables as constants during integration
+    #[inline]
+    #[must_use]
+    pub fn fixed_vars<P: ToParamName>(mut self, vars: &[P]) -> Self {
+        for var in vars {
+            let (_, name) = var.to_param_id_and_name();
+            self.known_symbols.insert(name);
+        }
+        self
+    }
+
+    /// Integrate an expression with respect to a variable
+    ///
+    /// The integrand is simplified first, so equivalent inputs take the same rules.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Integrate, symb};
+    /// let x = symb("x");
+    /// let f = Integrate::new().integrate(&(x * x.exp()), &x).unwrap();
+    /// assert_eq!(f.to_string(), "exp(x)*(-1 + x)");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` if:
+    /// - The variable is also in the fixed variables set
+    /// - Expression depth exceeds `max_depth`
+    /// - Expression node count exceeds `max_nodes`
+    /// - No rule applies to a term ([`DiffError::CannotIntegrate`] names the term)
+    pub fn integrate(&self, expr: &Expr, var: &Symbol) -> Result<Expr, DiffError> {
+        let var_name = var.name().unwrap_or_default();
+        if self.known_symbols.contains(&var_name) {
+            return Err(DiffError::VariableInBothFixedAndDiff { var: var_name });
+        }
+
+        if let Some(max_d) = self.max_depth
+            && expr.max_depth() > max_d
+        {
+            return Err(DiffError::MaxDepthExceeded);
+        }
+        if let Some(max_n) = self.max_nodes
+            && expr.node_count() > max_n
+        {
+            return Err(DiffError::MaxNodesExceeded);
+        }
+
+        let integrand = self.simplify(expr.clone());
+        let integrator = Integrator::new(&var_name, var.to_expr(), self.context.as_ref());
+        let antiderivative = integrator.antiderivative(&integrand)?;
+        Ok(self.simplify(antiderivative))
+    }
+
+    fn simplify(&self, expr: Expr) -> Expr {
+        simplify_expr(
+            expr,
+            self.known_symbols.clone(),
+            CustomBodyMap::default(),
+            self.max_depth,
+            None,
+            self.context.as_ref(),
+            self.domain_safe,
+        )
+    }
+
+    /// Parse and integrate a string formula
+    ///
+    /// # Arguments
+    /// * `formula` - The mathematical expression to integrate
+    /// * `var` - The variable to integrate with respect to
+    /// * `known_symbols` - Known multi-character symbol names for parsing
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::Integrate;
+    /// let result = Integrate::new().integrate_str("alpha*cos(x)", "x", &["alpha"]).unwrap();
+    /// assert_eq!(result, "alpha*sin(x)");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` if parsing fails, the variable is in the known symbols set, or
+    /// integration fails as in [`integrate`](Self::integrate).
+    pub fn integrate_str(
+        &self,
+        formula: &str,
+        var: &str,
+        known_symbols: &[&str],
+    ) -> Result<String, DiffError> {
+        let mut symbols: HashSet<String> = known_symbols.iter().map(ToString::to_string).collect();
+        symbols.extend(self.known_symbols.clone());
+
+        if symbols.contains(var) {
+            return Err(DiffError::VariableInBothFixedAndDiff {
+                var: var.to_owned(),
+            });
+        }
+
+        let ast = parse(formula, &symbols, &HashSet::new(), self.context.as_ref())?;
+        let var_sym = self
+            .context
+            .as_ref()
+            .map_or_else(|| symb(var), |ctx| ctx.symb(var));
+
+        let result = self
+            .integrate(&ast, &var_sym)
+            .map_err(|err| SourceMap::new(formula).locate(err))?;
+        Ok(format!("{result}"))
+    }
+}
+
+/// Integrate a mathematical expression
+///
+/// This function parses a formula, integrates it with respect to a variable and
+/// simplifies the antiderivative. For more control, use the [`Integrate`] builder.
+///
+/// # Arguments
+/// * `formula` - Mathematical expression to integrate
+/// * `var` - Variable to integrate with respect to
+/// * `known_symbols` - Multi-character symbols for parsing
+///
+/// # Errors
+/// Returns `DiffError` if parsing or integration fails.
+pub fn integrate(formula: &str, var: &str, known_symbols: &[&str]) -> Result<String, DiffError> {
+    Integrate::new()
+        .max_depth(DEFAULT_MAX_DEPTH)
+        .max_nodes(DEFAULT_MAX_NODES)
+        .integrate_str(formula, var, known_symbols)
+}
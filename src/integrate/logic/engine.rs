@@ -0,0 +1,294 @@
+//! Rule-based antiderivative search.
+//!
+//! The integrand is taken apart structurally:
+//! - sums term by term, constant factors pulled out of products and quotients;
+//! - powers `u^c` and exponentials `b^u` of a linear argument `u = a*x + b`;
+//! - the table of elementary functions of a linear argument (`sin`, `cos`, `tan`,
+//!   `sinh`, `cosh`, `exp`, `ln`, `sqrt`), dividing by the slope `a`; logarithms from
+//!   `1/u` and `tan` are taken of `|u|` and `|cos(u)|`, so they hold for `u < 0` too;
+//! - products of a polynomial with one other factor by repeated integration by parts.
+//!
+//! Anything else fails with [`DiffError::CannotIntegrate`] naming the term no rule
+//! applied to.
+
+use std::sync::Arc;
+
+use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::{Context, DiffError, Expr, ExprKind, Polynomial};
+
+/// Maximum nesting of integration by parts per antiderivative.
+///
+/// Every step lowers the degree of the polynomial factor, so this bounds the degree of
+/// polynomial factors that can be integrated.
+const MAX_PARTS_DEPTH: usize = 16;
+
+/// Antiderivative search with respect to one variable.
+pub struct Integrator<'ctx> {
+    /// Name of the integration variable
+    var: &'ctx str,
+    /// The integration variable as an expression
+    x: Expr,
+    /// Context used for the derivatives taken during integration by parts
+    context: Option<&'ctx Context>,
+}
+
+impl<'ctx> Integrator<'ctx> {
+    /// Integrate with respect to the symbol `x` named `var`.
+    pub const fn new(var: &'ctx str, x: Expr, context: Option<&'ctx Context>) -> Self {
+        Self { var, x, context }
+    }
+
+    /// An antiderivative of `expr`, unsimplified and without integration constant.
+    pub fn antiderivative(&self, expr: &Expr) -> Result<Expr, DiffError> {
+        self.integrate(expr, MAX_PARTS_DEPTH)
+    }
+
+    fn integrate(&self, expr: &Expr, depth: usize) -> Result<Expr, DiffError> {
+        if !expr.contains_var(self.var) {
+            return Ok(Expr::mul_expr(expr.clone(), self.x.clone()));
+        }
+        match &expr.kind {
+            ExprKind::Symbol(_) => Ok(Expr::div_expr(
+                Expr::pow_static(self.x.clone(), Expr::number(2.0)),
+                Expr::number(2.0),
+            )),
+            ExprKind::Sum(terms) => terms
+                .iter()
+                .map(|term| self.integrate(term, depth))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Expr::sum),
+            ExprKind::Product(factors) => self.product(expr, factors, depth),
+            ExprKind::Div(num, den) if !den.contains_var(self.var) => {
+                Ok(Expr::div_expr(self.integrate(num, depth)?, (**den).clone()))
+            }
+            ExprKind::Div(num, den) => {
+                // n / d integrates as n * d^(-1), keeping exponentials and powers flat
+                let reciprocal = match &den.kind {
+                    ExprKind::FunctionCall { name, args }
+                        if name.id() == KS.exp && args.len() == 1 =>
+                    {
+                        Expr::func_symbol(get_symbol(KS.exp), Expr::negate((*args[0]).clone()))
+                    }
+                    ExprKind::Pow(base, exp) => {
+                        Expr::pow_static((**base).clone(), Expr::negate((**exp).clone()))
+                    }
+                    _ => Expr::pow_static((**den).clone(), Expr::number(-1.0)),
+                };
+                self.integrate(&Expr::mul_expr((**num).clone(), reciprocal), depth)
+            }
+            ExprKind::Pow(base, exp) => self.power(expr, base, exp),
+            ExprKind::FunctionCall { name, args } if args.len() == 1 => {
+                self.elementary(expr, name.id(), &args[0])
+            }
+            ExprKind::Poly(poly) => {
+                let a = self.slope(poly.base()).ok_or_else(|| self.fail(expr))?;
+                let terms = poly
+                    .terms()
+                    .iter()
+                    .map(|&(pow, coeff)| {
+                        let next = f64::from(pow) + 1.0;
+                        Expr::div_expr(
+                            Expr::mul_expr(
+                                Expr::number(coeff),
+                                Expr::pow_static((**poly.base()).clone(), Expr::number(next)),
+                            ),
+                            Expr::mul_expr(Expr::number(next), a.clone()),
+                        )
+                    })
+                    .collect();
+                Ok(Expr::sum(terms))
+            }
+            _ => Err(self.fail(expr)),
+        }
+    }
+
+    /// `∫ base^exp`: the power rule over a linear base, or an exponential with a
+    /// linear exponent.
+    fn power(&self, expr: &Expr, base: &Expr, exp: &Expr) -> Result<Expr, DiffError> {
+        if !exp.contains_var(self.var) {
+            let a = self.slope(base).ok_or_else(|| self.fail(expr))?;
+            if exp.is_neg_one_num() {
+                return Ok(Expr::div_expr(ln_abs(base.clone()), a));
+            }
+            let next = Expr::add_expr(exp.clone(), Expr::number(1.0));
+            return Ok(Expr::div_expr(
+                Expr::pow_static(base.clone(), next.clone()),
+                Expr::mul_expr(next, a),
+            ));
+        }
+        if !base.contains_var(self.var) {
+            let a = self.slope(exp).ok_or_else(|| self.fail(expr))?;
+            return Ok(Expr::div_expr(
+                Expr::pow_static(base.clone(), exp.clone()),
+                Expr::mul_expr(a, ln(base.clone())),
+            ));
+        }
+        Err(self.fail(expr))
+    }
+
+    /// Table entry for the function `name` applied to a linear argument `u`.
+    fn elementary(&self, expr: &Expr, name: u64, u: &Expr) -> Result<Expr, DiffError> {
+        if name == KS.ln
+            && let Some(inner) = abs_arg(u)
+        {
+            // ∫ ln|u| du = u ln|u| - u, on either side of u = 0
+            let a = self.slope(inner).ok_or_else(|| self.fail(expr))?;
+            let primitive = Expr::sub_expr(
+                Expr::mul_expr(inner.clone(), ln_abs(inner.clone())),
+                inner.clone(),
+            );
+            return Ok(Expr::div_expr(primitive, a));
+        }
+        let a = self.slope(u).ok_or_else(|| self.fail(expr))?;
+        let call = |id: u64| Expr::func_symbol(get_symbol(id), u.clone());
+        let primitive = if name == KS.sin {
+            Expr::negate(call(KS.cos))
+        } else if name == KS.cos {
+            call(KS.sin)
+        } else if name == KS.tan {
+            Expr::negate(ln_abs(call(KS.cos)))
+        } else if name == KS.sinh {
+            call(KS.cosh)
+        } else if name == KS.cosh {
+            call(KS.sinh)
+        } else if name == KS.exp {
+            call(KS.exp)
+        } else if name == KS.ln {
+            // Integration by parts: ∫ ln u du = u ln u - u
+            Expr::sub_expr(Expr::mul_expr(u.clone(), call(KS.ln)), u.clone())
+        } else if name == KS.sqrt {
+            Expr::mul_expr(
+                Expr::number(2.0 / 3.0),
+                Expr::pow_static(u.clone(), Expr::number(1.5)),
+            )
+        } else {
+            return Err(self.fail(expr));
+        };
+        Ok(Expr::div_expr(primitive, a))
+    }
+
+    /// `∫ c * f₁ * … * fₙ`: constant factors are pulled out, a single remaining factor
+    /// is integrated directly and several are integrated by parts.
+    fn product(&self, expr: &Expr, factors: &[Arc<Expr>], depth: usize) -> Result<Expr, DiffError> {
+        let (dependent, constant): (Vec<&Arc<Expr>>, Vec<&Arc<Expr>>) = factors
+            .iter()
+            .partition(|factor| factor.contains_var(self.var));
+        let constant = Expr::product(constant.into_iter().map(|c| (**c).clone()).collect());
+
+        let primitive = match dependent.as_slice() {
+            [single] => self.integrate(single, depth)?,
+            _ => self.by_parts(expr, &dependent, depth)?,
+        };
+        Ok(Expr::mul_expr(constant, primitive))
+    }
+
+    /// Integration by parts `∫ u dv = u v - ∫ v du` over a product of non-constant
+    /// factors.
+    ///
+    /// The polynomial part is differentiated away (`u = P`), except against a logarithm,
+    /// which is differentiated instead (`u = ln`). A product of polynomials peels off one
+    /// factor at a time.
+    fn by_parts(
+        &self,
+        expr: &Expr,
+        factors: &[&Arc<Expr>],
+        depth: usize,
+    ) -> Result<Expr, DiffError> {
+        if depth == 0 {
+            return Err(self.fail(expr));
+        }
+        let (polynomial, other): (Vec<Expr>, Vec<Expr>) = factors
+            .iter()
+            .map(|factor| (***factor).clone())
+            .partition(|factor| self.is_polynomial(factor));
+
+        let (u, dv) = match other.as_slice() {
+            // Expand a product of polynomials in the variable itself
+            [] if let Some(poly) =
+                Polynomial::try_from_expr(&Expr::product(polynomial.clone()))
+                && **poly.base() == self.x =>
+            {
+                return self.integrate(&Expr::poly(poly), depth);
+            }
+            [] => {
+                let mut polynomial = polynomial.into_iter();
+                let first = polynomial.next().ok_or_else(|| self.fail(expr))?;
+                (first, Expr::product(polynomial.collect()))
+            }
+            [log] if self.is_log(log) => (log.clone(), Expr::product(polynomial)),
+            [other] => (Expr::product(polynomial), other.clone()),
+            _ => return Err(self.fail(expr)),
+        };
+
+        let v = self.integrate(&dv, depth - 1)?;
+        let du = u.derive(self.var, self.context).simplified()?;
+        let rest = Expr::mul_expr(v.clone(), du).simplified()?;
+        let rest = self.integrate(&rest, depth - 1)?;
+        Ok(Expr::sub_expr(Expr::mul_expr(u, v), rest))
+    }
+
+    /// Whether `expr` is a polynomial in the variable (coefficients may be symbolic).
+    fn is_polynomial(&self, expr: &Expr) -> bool {
+        if !expr.contains_var(self.var) {
+            return true;
+        }
+        match &expr.kind {
+            ExprKind::Symbol(_) => true,
+            ExprKind::Sum(children) | ExprKind::Product(children) => {
+                children.iter().all(|child| self.is_polynomial(child))
+            }
+            ExprKind::Pow(base, exp) => {
+                exp.as_number()
+                    .is_some_and(|n| n >= 0.0 && n.fract() == 0.0)
+                    && self.is_polynomial(base)
+            }
+            ExprKind::Div(num, den) => !den.contains_var(self.var) && self.is_polynomial(num),
+            ExprKind::Poly(poly) => self.is_polynomial(poly.base()),
+            _ => false,
+        }
+    }
+
+    /// Whether `expr` is the natural logarithm of a linear argument or of its absolute
+    /// value.
+    fn is_log(&self, expr: &Expr) -> bool {
+        matches!(
+            &expr.kind,
+            ExprKind::FunctionCall { name, args }
+                if name.id() == KS.ln
+                    && args.len() == 1
+                    && self.slope(abs_arg(&args[0]).unwrap_or(&args[0])).is_some()
+        )
+    }
+
+    /// The slope `a` if `u = a*x + b` is linear in the variable.
+    fn slope(&self, u: &Expr) -> Option<Expr> {
+        let a = u.derive(self.var, self.context).simplified().ok()?;
+        (!a.contains_var(self.var) && !a.is_zero_num()).then_some(a)
+    }
+
+    fn fail(&self, term: &Expr) -> DiffError {
+        DiffError::CannotIntegrate {
+            term: term.to_string(),
+            var: self.var.to_owned(),
+        }
+    }
+}
+
+fn ln(arg: Expr) -> Expr {
+    Expr::func_symbol(get_symbol(KS.ln), arg)
+}
+
+/// `ln(|arg|)`, the antiderivative of `1/arg` on either side of its pole
+fn ln_abs(arg: Expr) -> Expr {
+    ln(Expr::func_symbol(get_symbol(KS.abs), arg))
+}
+
+/// `u` if `expr` is `abs(u)`
+fn abs_arg(expr: &Expr) -> Option<&Expr> {
+    match &expr.kind {
+        ExprKind::FunctionCall { name, args } if name.id() == KS.abs && args.len() == 1 => {
+            Some(&args[0])
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,3 @@
+//! Internal integration logic.
+
+pub(super) mod engine;
@@ -0,0 +1,15 @@
+//! Integration engine - symbolic antiderivatives
+//!
+//! This module provides rule-based indefinite integration, implementing:
+//! - Linearity over sums and constant factors
+//! - The power rule, including `∫ 1/u = ln(u)`
+//! - A table of elementary functions (sin, cos, tan, sinh, cosh, exp, ln, sqrt)
+//! - Linear substitution `u = a*x + b`
+//! - Integration by parts for polynomial × elementary products
+//!
+//! The main entry point is the [`Integrate`](crate::Integrate) builder.
+
+mod api;
+mod logic;
+
+pub use api::*;
@@ -38,7 +38,7 @@
 //! - **Symbolic differentiation**: Automatic derivatives with simplification
 //! - **Vector calculus**: Gradients, Jacobians, and Hessian matrices
 //! - **Custom functions**: User-defined functions with partial derivatives
-//! - **Uncertainty propagation**: Error analysis with covariance matrices
+//! - **Uncertainty propagation**: Error analysis with covariance matrices (`experimental`)
 //!
 //! ## Core APIs
 //!
@@ -126,14 +126,31 @@
 //! ```
 //!
 //! - **`parallel`**: Enables parallel evaluation with Rayon
-//!   - Adds `eval_f64()` for SIMD+parallel evaluation  
-//!   - Enables `evaluate_parallel()` for batch operations
-//!   - Required for high-performance numerical workloads
+//!   - Runs `eval_f64()` with SIMD+parallel evaluation  
+//!   - Runs `evaluate_parallel()` batch operations on the thread pool
+//!   - Adds `*_with_pool` variants that accept a `rayon::ThreadPool`
+//!   - Without it, the same functions run sequentially with identical signatures
+//!
+//! - **`eval-core`**: `eval_core::Program`, a `no_std` + `alloc` interpreter for
+//!   programs compiled on the host and saved with `CompiledEvaluator::to_bytes`
+//!   - Build only the core with `--no-default-features --features eval-core`
+//!   - Every other API needs the default **`std`** feature
 //!
 //! - **`python`**: Python bindings via `PyO3` (separate crate)
 //!   - Type-safe integration with `NumPy` arrays
 //!   - Automatic GIL management for performance
 //!   - See `symb-anafis-python` crate for usage
+//!
+//! - **`experimental`**: APIs that may still change in a minor release
+//!   - Uncertainty propagation: `uncertainty_propagation()`, `CovarianceMatrix`, ...
+//!   - The stable surface is listed in `src/tests/golden/public_api.txt`
+//!
+//! ## API Stability
+//!
+//! Enums that are expected to grow (`DiffError`, `CodeGenLanguage`, `RuleCategory`, ...)
+//! are `#[non_exhaustive]`, so `match` on them needs a wildcard arm. Traits that exist
+//! only to accept arguments of several types (`ToParamName`, `ArcExprExt`) are sealed:
+//! they can be used but not implemented outside the crate.
 
 //! ## Architecture Overview
 //!
@@ -194,28 +211,67 @@
 //! - **Error handling**: All operations return `Result` with descriptive error messages
 //! - **Thread safety**: All public types are `Send + Sync` for parallel usage
 
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+extern crate alloc;
+
+/// Applies `#[cfg(feature = "std")]` to every item: all of the crate except the
+/// evaluation core needs the standard library. Modules are gated one by one instead, so
+/// their `#[macro_export]` macros stay reachable by path.
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $( #[cfg(feature = "std")] $item )*
+    };
+}
+
 // ============================================================================
 // Module Declarations
 // ============================================================================
 
 // Core infrastructure
+#[cfg(feature = "std")]
 mod core;
+#[cfg(feature = "std")]
 mod parser;
 
 // Computation engines
+#[cfg(feature = "std")]
 mod diff;
+#[cfg(feature = "std")]
 mod evaluator;
+#[cfg(feature = "std")]
+mod integrate;
+#[cfg(feature = "std")]
 mod simplification;
 
 // Function and math support
+#[cfg(feature = "std")]
 mod functions;
+#[cfg(feature = "std")]
 mod math;
+#[cfg(feature = "experimental")]
 mod uncertainty;
 
 // User-facing APIs
+#[cfg(feature = "std")]
 mod bindings;
+#[cfg(feature = "std")]
+mod codegen;
+#[cfg(feature = "std")]
 mod convenience;
 
+// Evaluation core, the only part that builds without `std`
+#[cfg(feature = "eval-core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eval-core")))]
+pub mod eval_core;
+#[cfg(all(feature = "std", not(feature = "eval-core")))]
+#[allow(
+    clippy::redundant_pub_crate,
+    reason = "Its submodules are pub(crate) so they stay crate-private when the module is public"
+)]
+mod eval_core;
+
 // ============================================================================
 // Feature Flags Documentation
 // ============================================================================
@@ -223,13 +279,24 @@ mod convenience;
 // SymbAnaFis supports optional features for specialized use cases:
 //
 // - **`parallel`**: Enables parallel evaluation with Rayon
-//   - Adds `eval_f64()` for SIMD+parallel evaluation
-//   - Enables `evaluate_parallel()` for batch operations
+//   - Runs `eval_f64()` / `evaluate_parallel()` on the thread pool (serial otherwise)
+//   - Adds `*_with_pool` variants that accept a `rayon::ThreadPool`
+//
+// - **`argmin`**: `ArgminProblem`, implementing argmin's `CostFunction` and `Gradient`
+//   with a compiled expression and its symbolic gradient
+//
+// - **`std`** (default): everything except the evaluation core. Without it the crate is
+//   `no_std`; `--no-default-features --features eval-core` leaves only `eval_core`
+//
+// - **`eval-core`**: `eval_core::Program`, a `no_std` + `alloc` interpreter for programs
+//   compiled on the host and saved with `CompiledEvaluator::to_bytes`
 //
 // - **`python`**: Python bindings via PyO3 (separate crate)
 //   - Type-safe integration with NumPy arrays
 //   - Automatic GIL management for performance
 //
+// - **`experimental`**: uncertainty propagation, exempt from semver until it settles
+//
 // Add to `Cargo.toml`:
 // ```toml
 // [dependencies]
@@ -240,87 +307,151 @@ mod convenience;
 // Public API Re-exports
 // ============================================================================
 
-// === 1. Foundation & Core Models ===
+cfg_std! {
+    // === 1. Foundation & Core Models ===
+
+    /// The main expression type for building and manipulating mathematical expressions.
+    /// See the [crate documentation](crate) for usage examples.
+    pub use crate::core::{DiffError, Expr, SourceLocation, Span, Symbol, SymbolError};
+
+    /// Policy for automatic conversion of sums into polynomial nodes.
+    pub use crate::core::{PolyConversion, poly_conversion, set_poly_conversion, with_poly_conversion};
+
+    /// Real domain of an expression: intervals, excluded points and unsolved conditions,
+    /// and interval enclosures of its nodes over declared variable domains.
+    pub use crate::core::{Constraint, Domain, Enclosure, EnclosureMap, Interval};
+
+    /// Conditions guarding the branches of a piecewise expression.
+    pub use crate::core::{CompareOp, Condition};
+
+    /// Points limits are taken at, for `Expr::limit`.
+    pub use crate::core::LimitPoint;
+
+    /// Renaming symbols across a set of expressions, validated up front.
+    pub use crate::core::{RenameReport, rename_symbols, rename_symbols_dry_run};
+
+    /// Options for LaTeX output.
+    pub use crate::core::LatexConfig;
+
+    /// Options for plain-text output with `Expr::format_with`.
+    pub use crate::core::{FormatOptions, FunctionCallStyle, MulStyle, PowerOperator};
+
+    /// Options for `MathML` output.
+    pub use crate::core::MathmlConfig;
+
+    /// Host languages for generated source code, and Rust functions returning tuples.
+    pub use codegen::{CodeGenLanguage, to_rust_code_multi};
+
+    /// Mathematical scalar trait for high-performance computation.
+    pub use crate::core::MathScalar;
+
+    /// Dual number type for automatic differentiation.
+    pub use math::Dual;
+
+    /// Functions for creating and managing symbols in the global registry.
+    ///
+    /// ## Copy Semantics
+    /// `Symbol` implements `Copy`, enabling natural mathematical syntax:
+    /// ```rust
+    /// # use symb_anafis::symb;
+    /// let x = symb("x");
+    /// let expr = x + x;  // No .clone() needed!
+    /// ```
+    pub use crate::core::{
+        ArcExprExt, clear_symbols, remove_symbol, symb, symb_get, symb_new, symbol_count,
+        symbol_exists, symbol_names,
+    };
+
+    /// Private symbol namespaces whose names do not collide with the global registry.
+    pub use crate::core::SymbolScope;
 
-/// The main expression type for building and manipulating mathematical expressions.
-/// See the [crate documentation](crate) for usage examples.
-pub use core::{DiffError, Expr, Span, Symbol, SymbolError};
+    // === 2. Ingestion & Rules ===
 
-/// Mathematical scalar trait for high-performance computation.
-pub use core::MathScalar;
+    /// Context system for custom functions and parsing.
+    pub use crate::core::{Context, InverseCaveat, NumericFn, UserFunction};
 
-/// Dual number type for automatic differentiation.
-pub use math::Dual;
+    /// String → AST parsing with context support.
+    pub use parser::{
+        Equation, Intermediate, OpKind, ParseWarning, Parsed, PostfixToken, Program, parse,
+        parse_configured, parse_equation, parse_program, parse_reuse, strip_comments,
+        ParseScratch, ParserOptions, SourceMap,
+    };
 
-/// Functions for creating and managing symbols in the global registry.
-///
-/// ## Copy Semantics
-/// `Symbol` implements `Copy`, enabling natural mathematical syntax:
-/// ```rust
-/// # use symb_anafis::symb;
-/// let x = symb("x");
-/// let expr = x + x;  // No .clone() needed!
-/// ```
-pub use core::{
-    ArcExprExt, clear_symbols, remove_symbol, symb, symb_get, symb_new, symbol_count,
-    symbol_exists, symbol_names,
-};
+    // === 3. Operations & Calculus ===
 
-// === 2. Ingestion & Rules ===
+    /// Fluent APIs for differentiation and simplification.
+    pub use diff::{Diff, diff, diff_implicit_str};
+    pub use integrate::{Integrate, integrate};
+    pub use simplification::{
+        DEFAULT_NODE_REWRITE_BUDGET, RuleCategory, RuleUsage, RuleUsageReport, Simplify, UserRule,
+        simplify,
+    };
 
-/// Context system for custom functions and parsing.
-pub use core::{Context, UserFunction};
+    /// Vector calculus operations for computing gradients, Jacobians, and Hessians.
+    pub use convenience::{
+        Hessian, eval_partial_str, evaluate_str, expand, gradient, gradient_expr, gradient_str,
+        hessian, hessian_expr, hessian_sparse, hessian_sparsity, hessian_str,
+        hessian_with_gradient, jacobian, jacobian_expr, jacobian_sparse, jacobian_sparsity,
+        jacobian_str,
+    };
 
-/// String → AST parsing with context support.
-pub use parser::parse;
+    /// Numeric conditioning analysis and variable scaling suggestions.
+    pub use convenience::{ScalingReport, VariableScaling, WELL_SCALED_DECADES, suggest_scaling};
 
-// === 3. Operations & Calculus ===
+    // === 4. Advanced Analysis ===
 
-/// Fluent APIs for differentiation and simplification.
-pub use diff::{Diff, diff};
-pub use simplification::{Simplify, simplify};
+    /// Uncertainty propagation and error analysis for experimental data (requires the
+    /// `experimental` feature; not covered by semver yet).
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+    pub use uncertainty::{
+        CovEntry, CovarianceMatrix, Uncertainty, propagate_covariance, propagated_variance,
+        relative_uncertainty, uncertainty_propagation,
+    };
 
-/// Vector calculus operations for computing gradients, Jacobians, and Hessians.
-pub use convenience::{
-    evaluate_str, gradient, gradient_str, hessian, hessian_str, jacobian, jacobian_str,
-};
+    pub use crate::core::ExprView;
+    // === 5. High-Performance Evaluation ===
 
-// === 4. Advanced Analysis ===
+    /// High-performance compiled evaluator for repeated numeric computations.
+    pub use evaluator::{
+        CompiledEvaluator, CompiledGradient, EvalOptions, EvaluatorBuilder, ToParamName, VarLookup,
+    };
 
-/// Uncertainty propagation and error analysis for experimental data.
-pub use uncertainty::{
-    CovEntry, CovarianceMatrix, Uncertainty, relative_uncertainty, uncertainty_propagation,
-};
+    /// Batch evaluation with the same API in serial and `parallel` builds.
+    /// The `parallel` feature enables chunked parallel execution with SIMD vectorization.
+    pub use evaluator::{EvalResult, ExprInput, SKIP, Value, VarInput, eval_f64, evaluate_parallel};
 
-pub use core::ExprView;
-// === 5. High-Performance Evaluation ===
+    /// Batch evaluation on an explicit Rayon thread pool (requires `parallel` feature).
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+    pub use evaluator::{eval_f64_with_pool, evaluate_parallel_with_pool};
 
-/// High-performance compiled evaluator for repeated numeric computations.
-pub use evaluator::{CompiledEvaluator, EvaluatorBuilder, ToParamName, VarLookup};
+    // === 6. Ecosystem Integration ===
 
-/// High-performance parallel evaluation (requires `parallel` feature).
-/// Enables automatic chunked parallel execution with SIMD vectorization.
-#[cfg(feature = "parallel")]
-pub use evaluator::eval_f64;
-#[cfg(feature = "parallel")]
-pub use evaluator::{EvalResult, ExprInput, SKIP, Value, VarInput, evaluate_parallel};
+    /// Symbolic objective for `argmin` solvers (requires `argmin` feature).
+    #[cfg(feature = "argmin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "argmin")))]
+    pub use bindings::argmin::ArgminProblem;
+}
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-/// Default maximum AST depth.
-/// This limit prevents stack overflow from deeply nested expressions.
-pub(crate) const DEFAULT_MAX_DEPTH: usize = 100;
+cfg_std! {
+    /// Default maximum AST depth.
+    /// This limit prevents stack overflow from deeply nested expressions.
+    pub(crate) const DEFAULT_MAX_DEPTH: usize = 100;
 
-/// Default maximum AST node count.
-/// This limit prevents memory exhaustion from extremely large expressions.
-pub(crate) const DEFAULT_MAX_NODES: usize = 10_000;
+    /// Default maximum AST node count.
+    /// This limit prevents memory exhaustion from extremely large expressions.
+    pub(crate) const DEFAULT_MAX_NODES: usize = 10_000;
+}
 
 /// Tolerance for floating-point comparisons (used throughout expression operations)
 pub const EPSILON: f64 = 1e-14;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 #[allow(missing_docs)]
 #[allow(clippy::pedantic, clippy::nursery, clippy::restriction)]
 #[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
@@ -5,8 +5,9 @@ pub use super::logic::Dual;
 
 // Crate-internal numerical entry points used by sibling modules.
 pub use super::logic::{
-    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_digamma,
-    eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc, eval_exp_polar, eval_gamma,
-    eval_hermite, eval_lambert_w, eval_lgamma, eval_polygamma, eval_spherical_harmonic,
-    eval_tetragamma, eval_trigamma, eval_zeta, eval_zeta_deriv,
+    bessel_i, bessel_j, bessel_k, bessel_y, eval_assoc_legendre, eval_beta, eval_clamp,
+    eval_digamma, eval_dirac, eval_elliptic_e, eval_elliptic_k, eval_erf, eval_erfc,
+    eval_exp_polar, eval_gamma, eval_heaviside, eval_hermite, eval_lambert_w, eval_lgamma,
+    eval_max, eval_min, eval_polygamma, eval_select, eval_spherical_harmonic, eval_tetragamma,
+    eval_trigamma, eval_zeta, eval_zeta_deriv,
 };
@@ -3,12 +3,17 @@ use crate::core::traits::MathScalar;
 /// Error function erf(x) = (2/√π) ∫₀ˣ e^(-t²) dt
 ///
 /// Uses Taylor series expansion: erf(x) = (2/√π) Σₙ (-1)ⁿ x^(2n+1) / (n!(2n+1))
-/// with Kahan summation for numerical stability.
+/// with Kahan summation for numerical stability. For |x| ≥ 1.5 the series terms grow
+/// before they shrink and the partial sums cancel, so erf is taken as 1 - erfc(|x|)
+/// off the continued fraction instead.
 ///
 /// Reference: DLMF §7.6.1 <https://dlmf.nist.gov/7.6#E1>
 pub fn eval_erf<T: MathScalar>(x: T) -> T {
     let sign = x.signum();
     let x = x.abs();
+    if x >= T::from_f64(1.5).expect("Failed to convert 1.5 to T") {
+        return sign * (T::one() - eval_erfc(x));
+    }
     // PI is available via FloatConst implementation on T
     let pi = T::PI();
     let sqrt_pi = pi.sqrt();
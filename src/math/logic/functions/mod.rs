@@ -7,8 +7,10 @@ mod polynomials;
 mod erf;
 mod gamma;
 mod lambert_w;
+mod piecewise;
 mod polar;
 mod polygamma;
+mod step;
 mod zeta;
 
 // Internal helpers
@@ -20,7 +22,9 @@ pub use elliptic::*;
 pub use erf::*;
 pub use gamma::*;
 pub use lambert_w::*;
+pub use piecewise::*;
 pub use polar::*;
 pub use polygamma::*;
 pub use polynomials::*;
+pub use step::*;
 pub use zeta::*;
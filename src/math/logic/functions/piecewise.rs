@@ -0,0 +1,33 @@
+use crate::core::traits::MathScalar;
+
+/// Smaller of `a` and `b`; NaN if either is NaN
+///
+/// On a tie `a` is returned, which is what the derivative rules assume.
+pub fn eval_min<T: MathScalar>(a: T, b: T) -> T {
+    if b < a || b.is_nan() { b } else { a }
+}
+
+/// Larger of `a` and `b`; NaN if either is NaN
+///
+/// On a tie `a` is returned, which is what the derivative rules assume.
+pub fn eval_max<T: MathScalar>(a: T, b: T) -> T {
+    if b > a || b.is_nan() { b } else { a }
+}
+
+/// `x` limited to `[lo, hi]`, defined as `min(max(x, lo), hi)`
+///
+/// Unlike `f64::clamp` this never panics: with `lo > hi` the result is `hi`.
+pub fn eval_clamp<T: MathScalar>(x: T, lo: T, hi: T) -> T {
+    eval_min(eval_max(x, lo), hi)
+}
+
+/// `a` where `cond` is nonzero, `b` where it is zero; NaN propagates from `cond`
+pub fn eval_select<T: MathScalar>(cond: T, a: T, b: T) -> T {
+    if cond.is_nan() {
+        cond
+    } else if cond == T::zero() {
+        b
+    } else {
+        a
+    }
+}
@@ -0,0 +1,28 @@
+use crate::core::traits::MathScalar;
+
+/// Heaviside step function H(x): 0 for x < 0, 1 for x ≥ 0
+///
+/// Uses the right-continuous convention H(0) = 1. NaN propagates.
+pub fn eval_heaviside<T: MathScalar>(x: T) -> T {
+    if x.is_nan() {
+        x
+    } else if x >= T::zero() {
+        T::one()
+    } else {
+        T::zero()
+    }
+}
+
+/// Dirac delta δ(x) evaluated pointwise
+///
+/// The distribution vanishes away from the origin; at x = 0 the point mass has no
+/// finite value and evaluates to +∞. NaN propagates.
+pub fn eval_dirac<T: MathScalar>(x: T) -> T {
+    if x.is_nan() {
+        x
+    } else if x == T::zero() {
+        T::infinity()
+    } else {
+        T::zero()
+    }
+}
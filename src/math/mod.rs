@@ -31,5 +31,6 @@
 
 mod api;
 mod logic;
+mod series;
 
 pub use api::*;
@@ -0,0 +1,105 @@
+//! Taylor and Maclaurin polynomials of expressions.
+//!
+//! The `k`-th coefficient is `f^(k)(a)/k!`: each derivative is taken from the previous
+//! one with [`Diff::diff_n`], compiled and evaluated at the expansion point. Exact
+//! values (integers, as for `sin` or `ln(1 + x)` at `0`) keep `k!` as a denominator,
+//! so terms print as `x^3/6` rather than `0.16666666666666666*x^3`.
+
+use crate::core::{DiffError, Expr, Symbol};
+use crate::diff::Diff;
+use crate::evaluator::CompiledEvaluator;
+use crate::simplification::Simplify;
+
+/// Orders above this differentiate often enough to be noticeably slow
+const SLOW_ORDER: usize = 10;
+
+impl Expr {
+    /// Taylor polynomial of this expression in `var` around `point`, up to `order`
+    ///
+    /// Builds `Σ f^(k)(a)/k! * (x - a)^k` for `k` in `0..=order`, with each derivative
+    /// evaluated numerically at `a`. Zero coefficients are left out. Orders above 10
+    /// log a `tracing` warning, as every order is a further differentiation.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    ///
+    /// let x = symb("x");
+    /// assert_eq!(Expr::from(x).exp().taylor(&x, 0.0, 3)?.to_string(), "1 + x + x^2/2 + x^3/6");
+    /// assert_eq!(x.pow(2.0).taylor(&x, 1.0, 2)?.to_string(), "1 + 2*(-1 + x) + (-1 + x)^2");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnboundVariable`] if the expression has symbols other than
+    /// `var`, [`DiffError::UnsupportedOperation`] for a non-finite `point` or a derivative
+    /// that is not finite there (`ln(x)` at `0`), and propagates differentiation errors.
+    pub fn taylor(&self, var: &Symbol, point: f64, order: usize) -> Result<Self, DiffError> {
+        if !point.is_finite() {
+            return Err(DiffError::UnsupportedOperation(format!(
+                "Taylor expansion at {point}: the point must be finite"
+            )));
+        }
+        if order > SLOW_ORDER {
+            tracing::warn!(
+                order,
+                "Taylor expansion above order {SLOW_ORDER} takes one differentiation per order and may be slow"
+            );
+        }
+        let (diff, simplify) = (Diff::new(), Simplify::new());
+        let offset = if point == 0.0 {
+            Self::from(*var)
+        } else {
+            Self::from(*var) - point
+        };
+        let mut derivative = self.clone();
+        // `k` and `k!` as floats, for the exponent and the denominator
+        let (mut power, mut factorial) = (0.0, 1.0);
+        let mut terms = Vec::with_capacity(order + 1);
+        for k in 0..=order {
+            if k > 0 {
+                derivative = diff.diff_n(&derivative, var, 1)?;
+                power += 1.0;
+                factorial *= power;
+            }
+            let value = CompiledEvaluator::compile(&derivative, &[var], None)?.evaluate(&[point]);
+            if !value.is_finite() {
+                let name = var.name().unwrap_or_else(|| var.to_expr().to_string());
+                return Err(DiffError::UnsupportedOperation(format!(
+                    "Taylor expansion of {self} at {name} = {point}: derivative {k} is {value}"
+                )));
+            }
+            if value == 0.0 {
+                continue;
+            }
+            let magnitude = if value.fract() == 0.0 {
+                Self::number(value.abs()) * offset.clone().pow(power) / factorial
+            } else {
+                Self::number(value.abs() / factorial) * offset.clone().pow(power)
+            };
+            // Simplified one by one: together, the terms would go over a common denominator
+            let term = simplify.simplify(&magnitude)?;
+            terms.push(if value < 0.0 { -term } else { term });
+        }
+        Ok(Self::sum_no_poly(terms))
+    }
+
+    /// Maclaurin polynomial of this expression in `var` up to `order`: the Taylor
+    /// polynomial around `0`
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, symb};
+    ///
+    /// let x = symb("x");
+    /// let series = Expr::from(x).sin().maclaurin(&x, 5)?;
+    /// assert_eq!(series.to_string(), "x + x^5/120 - x^3/6");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// As [`taylor`](Self::taylor).
+    pub fn maclaurin(&self, var: &Symbol, order: usize) -> Result<Self, DiffError> {
+        self.taylor(var, 0.0, order)
+    }
+}
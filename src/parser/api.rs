@@ -1,8 +1,14 @@
 //! User-facing parser API.
 
-use super::logic::{balance_parentheses, insert_implicit_multiplication, lex, parse_expression};
-use crate::core::{Context, DiffError, Expr};
+pub use super::logic::{ParseScratch, ParserOptions, SourceMap};
+use super::logic::{
+    WhereClause, balance_parentheses, blank_comments, normalize_notation, spaced_calls,
+};
+use crate::Diff;
+use crate::core::{Context, DiffError, Expr, Span, Symbol};
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::hash::BuildHasher;
 
 /// Parse a formula string into an expression AST
@@ -38,6 +44,40 @@ use std::hash::BuildHasher;
 /// println!("Parsed: {}", expr);
 /// ```
 ///
+/// # Comments and Line Breaks
+/// `# ...` comments run to the end of the line and `/* ... */` comments may span
+/// lines; both are ignored. Newlines and indentation are ordinary whitespace, so a
+/// formula may break anywhere a space is allowed. A trailing `\` before a newline is
+/// accepted as an explicit continuation marker and ignored as well. Error spans always
+/// refer to positions in the original `input`, comments included.
+///
+/// ```
+/// use symb_anafis::parse;
+/// use std::collections::HashSet;
+///
+/// let source = "x^2   # quadratic term
+///     + sin(x) /* periodic part */";
+/// let expr = parse(source, &HashSet::new(), &HashSet::new(), None).unwrap();
+/// assert_eq!(expr, parse("x^2 + sin(x)", &HashSet::new(), &HashSet::new(), None).unwrap());
+/// ```
+///
+/// # Where Clauses
+/// `expr where { name = expr; ... }` defines names used in `expr`, and the result has
+/// them substituted. Definitions may use each other in any order and carry clauses of
+/// their own; a clause applies to the whole formula, not to a parenthesized part. A
+/// definition that depends on itself fails with [`DiffError::CircularDefinition`], and
+/// defining a name given in `known_symbols` is an error. `where` is an ordinary name
+/// unless a `{` follows it.
+///
+/// ```
+/// use symb_anafis::parse;
+/// use std::collections::HashSet;
+///
+/// let (symbols, functions) = (HashSet::new(), HashSet::new());
+/// let expr = parse("x^2 + y^2 where { y = sin(x); }", &symbols, &functions, None).unwrap();
+/// assert_eq!(expr, parse("x^2 + sin(x)^2", &symbols, &functions, None).unwrap());
+/// ```
+///
 /// # Note
 /// For most use cases, prefer the higher-level `diff()` or `simplify()` functions,
 /// or the `Diff`/`Simplify` builders which handle parsing automatically.
@@ -47,11 +87,209 @@ use std::hash::BuildHasher;
 /// - The input is empty
 /// - The input contains invalid syntax
 /// - Parentheses are unbalanced
+/// - A `/*` block comment is never closed (the span covers the opening `/*`)
+/// - A `where` clause is malformed or its definitions are circular
 pub fn parse<S: BuildHasher + Clone>(
     input: &str,
     known_symbols: &HashSet<String, S>,
     custom_functions: &HashSet<String, S>,
     context: Option<&Context>,
+) -> Result<Expr, DiffError> {
+    parse_with_scratch(
+        &mut ParseScratch::new(),
+        input,
+        known_symbols,
+        custom_functions,
+        context,
+        &ParserOptions::default(),
+    )
+}
+
+/// The result of [`parse_configured`]
+#[derive(Debug, Clone)]
+pub struct Parsed {
+    /// The parsed expression
+    pub expr: Expr,
+    /// Constructs that may not mean what was intended; empty unless
+    /// [`ParserOptions::warnings`] is set
+    pub warnings: Vec<ParseWarning>,
+    /// Map back to the input; `Some` exactly when [`ParserOptions::source_map`] is set
+    pub source_map: Option<SourceMap>,
+}
+
+/// [`parse`] with the settings in `options`
+///
+/// # Input Conventions
+/// With [`ParserOptions::unicode_operators`], `−`, `·`, `⋅`, `×` and `÷` read as `-`,
+/// `*`, `*`, `*` and `/`. With [`ParserOptions::decimal_comma`], a comma between the
+/// digits of a number is its decimal separator. Error spans still refer to `input`.
+///
+/// ```
+/// use symb_anafis::{ParserOptions, parse, parse_configured};
+/// use std::collections::HashSet;
+///
+/// let options = ParserOptions {
+///     decimal_comma: true,
+///     unicode_operators: true,
+///     ..ParserOptions::default()
+/// };
+/// let (symbols, functions) = (HashSet::new(), HashSet::new());
+/// let parsed = parse_configured("3,5·x − 2", &symbols, &functions, None, &options).unwrap();
+/// assert_eq!(parsed.expr, parse("3.5*x - 2", &symbols, &functions, None).unwrap());
+/// ```
+///
+/// # Exact Literals and Scopes
+/// [`ParserOptions::exact`] reads decimal literals as exact fractions (`0.1` as `1/10`).
+/// With [`ParserOptions::scope`], the symbols of the result belong to that scope, so
+/// the same formula parsed in two scopes gives expressions over distinct variables.
+///
+/// ```
+/// use symb_anafis::{ParserOptions, SymbolScope, parse_configured};
+/// use std::collections::HashSet;
+///
+/// let scope = SymbolScope::new();
+/// let options = ParserOptions {
+///     scope: Some(scope.clone()),
+///     ..ParserOptions::default()
+/// };
+/// let parsed = parse_configured("t^2 + 1", &HashSet::new(), &HashSet::new(), None, &options)?;
+/// assert_eq!(parsed.expr, scope.symb("t").pow(2.0) + 1.0);
+/// # Ok::<(), symb_anafis::DiffError>(())
+/// ```
+///
+/// # Warnings
+/// Juxtaposed parentheses always multiply (`(x+1)(x-1)`, `2(x+1)`), except directly
+/// after a function name, where they are a call. A registered function name followed by
+/// whitespace and a parenthesis is still a call; with [`ParserOptions::warnings`] it is
+/// reported as [`ParseWarning::SpacedFunctionCall`].
+///
+/// ```
+/// use symb_anafis::{ParseWarning, ParserOptions, parse_configured};
+/// use std::collections::HashSet;
+///
+/// let functions = HashSet::from(["f".to_owned()]);
+/// let options = ParserOptions {
+///     warnings: true,
+///     ..ParserOptions::default()
+/// };
+/// let parsed = parse_configured("f (x) + (x+1)(x-1)", &HashSet::new(), &functions, None, &options).unwrap();
+/// assert_eq!(parsed.expr.to_string(), "f(x) + (-1 + x)*(1 + x)");
+/// assert!(matches!(&parsed.warnings[..], [ParseWarning::SpacedFunctionCall { name, .. }] if name == "f"));
+/// ```
+///
+/// # Source Maps
+/// Errors raised later by operations on the expression, such as compiling it or
+/// differentiating it, can be passed through [`SourceMap::locate`] to point at the
+/// part of `input` they are about, even after the expression was simplified.
+///
+/// ```
+/// use symb_anafis::{CompiledEvaluator, ParserOptions, parse_configured};
+/// use std::collections::HashSet;
+///
+/// let functions = HashSet::from(["g".to_owned()]);
+/// let options = ParserOptions {
+///     source_map: true,
+///     ..ParserOptions::default()
+/// };
+/// let parsed = parse_configured("a*g(x)+b", &HashSet::new(), &functions, None, &options)?;
+/// let err = CompiledEvaluator::compile(&parsed.expr, &["a", "b", "x"], None).unwrap_err();
+/// let located = parsed.source_map.unwrap().locate(err);
+/// assert_eq!(located.location().map(|at| at.column()), Some(3));
+/// # Ok::<(), symb_anafis::DiffError>(())
+/// ```
+///
+/// # Errors
+/// Same as [`parse`].
+pub fn parse_configured<S: BuildHasher + Clone>(
+    input: &str,
+    known_symbols: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    context: Option<&Context>,
+    options: &ParserOptions,
+) -> Result<Parsed, DiffError> {
+    let scoped = options
+        .scope
+        .as_ref()
+        .map(|scope| context.cloned().unwrap_or_default().with_scope(scope));
+    let context = scoped.as_ref().or(context);
+    let expr = parse_with_scratch(
+        &mut ParseScratch::new(),
+        input,
+        known_symbols,
+        custom_functions,
+        context,
+        options,
+    )?;
+
+    let warnings = if options.warnings {
+        let source = blank_comments(input)?;
+        context.map_or_else(
+            || spaced_calls(&source, custom_functions),
+            |ctx| {
+                let mut functions = custom_functions.clone();
+                functions.extend(ctx.function_names());
+                spaced_calls(&source, &functions)
+            },
+        )
+    } else {
+        Vec::new()
+    };
+    let source_map = options.source_map.then(|| SourceMap::new(input));
+    Ok(Parsed {
+        expr,
+        warnings,
+        source_map,
+    })
+}
+
+/// [`parse`], reusing token buffers across calls
+///
+/// For parsing many formulas in a row. Identifiers are borrowed from `input` and looked
+/// up in the symbol interner without allocating, and the token buffers in `scratch` are
+/// kept between calls, so once they are warm only genuinely new symbols and the
+/// resulting expression tree allocate. With a `context`, its names are merged into the
+/// sets on every call, as in [`parse`].
+///
+/// # Example
+/// ```
+/// use symb_anafis::{ParseScratch, parse, parse_reuse};
+/// use std::collections::HashSet;
+///
+/// let (symbols, functions) = (HashSet::new(), HashSet::new());
+/// let mut scratch = ParseScratch::new();
+/// for formula in ["x^2 + y", "sin(x) * y", "exp(-x) / y"] {
+///     let expr = parse_reuse(&mut scratch, formula, &symbols, &functions, None).unwrap();
+///     assert_eq!(expr, parse(formula, &symbols, &functions, None).unwrap());
+/// }
+/// ```
+///
+/// # Errors
+/// Same as [`parse`].
+pub fn parse_reuse<S: BuildHasher + Clone>(
+    scratch: &mut ParseScratch,
+    input: &str,
+    known_symbols: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    context: Option<&Context>,
+) -> Result<Expr, DiffError> {
+    parse_with_scratch(
+        scratch,
+        input,
+        known_symbols,
+        custom_functions,
+        context,
+        &ParserOptions::default(),
+    )
+}
+
+/// Shared body of [`parse`], [`parse_configured`] and [`parse_reuse`]
+fn parse_with_scratch<S: BuildHasher + Clone>(
+    scratch: &mut ParseScratch,
+    input: &str,
+    known_symbols: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    context: Option<&Context>,
+    options: &ParserOptions,
 ) -> Result<Expr, DiffError> {
     let symbols_buf = context.map_or_else(
         || None,
@@ -73,13 +311,260 @@ pub fn parse<S: BuildHasher + Clone>(
     );
     let functions_ref = functions_buf.as_ref().unwrap_or(custom_functions);
 
-    if input.trim().is_empty() {
+    let uncommented = blank_comments(input)?;
+    let source = normalize_notation(&uncommented, options);
+    if let Some(clause) = WhereClause::split(&source)? {
+        // Defined names are symbols in every piece, as known symbols are; the pieces
+        // are already normalized.
+        let mut symbols = known_symbols.clone();
+        for (name, span) in clause.names() {
+            if !symbols.insert(name.to_owned()) {
+                return Err(DiffError::invalid_syntax_at(
+                    format!("'{name}' is a known symbol and cannot be defined in a 'where' clause"),
+                    span,
+                ));
+            }
+        }
+        return clause.resolve(|piece| {
+            parse_with_scratch(
+                scratch,
+                piece,
+                &symbols,
+                custom_functions,
+                context,
+                &ParserOptions {
+                    exact: options.exact,
+                    ..ParserOptions::default()
+                },
+            )
+        });
+    }
+    if source.trim().is_empty() {
         return Err(DiffError::EmptyFormula);
     }
 
-    let balanced = balance_parentheses(input);
-    let tokens = lex(&balanced, symbols_ref, functions_ref)?;
-    let tokens_with_mul = insert_implicit_multiplication(tokens, functions_ref);
+    let balanced = balance_parentheses(&source);
+    scratch.parse(
+        &balanced,
+        symbols_ref,
+        functions_ref,
+        context,
+        options.exact,
+    )
+}
+
+/// A note about input that parsed but may not mean what was intended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseWarning {
+    /// A custom function name separated from its parenthesis by whitespace, as in
+    /// `f (x)`: it parses as a call, but reads like the product `f * (x)`.
+    SpacedFunctionCall {
+        /// The function name
+        name: String,
+        /// From the start of the name through the opening parenthesis
+        span: Span,
+    },
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::SpacedFunctionCall { name, span } => write!(
+                f,
+                "'{name} (' is parsed as a call of '{name}'{}; write '{name}(' for a call \
+                 or '{name}*(' for a product",
+                span.display()
+            ),
+        }
+    }
+}
+
+/// Remove `# ...` and `/* ... */` comments and `\` line continuations from `source`
+///
+/// This is the cleaned text that [`parse`] tokenizes. Every removed character becomes
+/// a space and newlines are kept, so byte offsets, and therefore spans, are the same
+/// in the result as in `source`.
+///
+/// # Example
+/// ```
+/// use symb_anafis::strip_comments;
+///
+/// let cleaned = strip_comments("a + b # sum\n/* scale */ * c").unwrap();
+/// assert_eq!(cleaned, "a + b      \n            * c");
+/// ```
+///
+/// # Errors
+/// Returns [`DiffError::InvalidSyntax`] spanning the opening `/*` of an unterminated
+/// block comment.
+pub fn strip_comments(source: &str) -> Result<String, DiffError> {
+    blank_comments(source).map(Cow::into_owned)
+}
+
+/// An intermediate name defined by an assignment in a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intermediate {
+    /// The assigned name
+    pub name: String,
+    /// Its definition, expanded in terms of the program's real variables
+    pub definition: Expr,
+    /// Location of the name in the program source
+    pub span: Span,
+}
+
+/// A parsed program: the final expression plus the intermediates it was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// The final expression, with every intermediate substituted
+    pub expr: Expr,
+    /// Intermediates in definition order
+    pub intermediates: Vec<Intermediate>,
+}
+
+/// Parse a program of semicolon-separated assignments ending in an expression
+///
+/// Assignments `name = expr` introduce intermediate names that later statements may use.
+/// The last statement is the result. Intermediates are substituted, so the returned
+/// expression only contains real variables and can be differentiated or compiled
+/// directly; repeated intermediates end up as shared subexpressions that the compiler
+/// evaluates once.
+///
+/// # Example
+/// ```
+/// use symb_anafis::parse_program;
+///
+/// let program = parse_program("u = x^2 + 1; v = sin(u); v / u", None).unwrap();
+/// assert_eq!(program.intermediates.len(), 2);
+/// assert_eq!(program.intermediates[1].name, "v");
+/// assert_eq!(program.expr.to_string(), "sin(1 + x^2)/(1 + x^2)");
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if a statement fails to parse (spans point into `source`),
+/// if an assignment redefines an intermediate or shadows a variable used earlier
+/// or declared in `context`, or if the program does not end with an expression.
+pub fn parse_program(source: &str, context: Option<&Context>) -> Result<Program, DiffError> {
+    super::logic::parse_program(source, context)
+}
+
+/// An equation `lhs = rhs` between two expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    /// Left-hand side
+    pub lhs: Expr,
+    /// Right-hand side
+    pub rhs: Expr,
+}
+
+impl Equation {
+    /// The equation moved to one side: `lhs - rhs`, which is zero where the equation holds
+    #[must_use]
+    pub fn as_expr_zero(&self) -> Expr {
+        self.lhs.clone() - self.rhs.clone()
+    }
+
+    /// Differentiate both sides with respect to `var`, every other symbol held constant
+    ///
+    /// # Errors
+    /// Returns `DiffError` if either side fails to differentiate.
+    pub fn differentiate_both(&self, var: &Symbol) -> Result<Self, DiffError> {
+        let diff = Diff::new();
+        Ok(Self {
+            lhs: diff.differentiate(&self.lhs, var)?,
+            rhs: diff.differentiate(&self.rhs, var)?,
+        })
+    }
+
+    /// The slope `dy/dx` of the curve defined implicitly by this equation
+    ///
+    /// Writes the equation as `F(x, y) = 0` and returns `-(dF/dx) / (dF/dy)`, with both
+    /// partial derivatives taken holding the other variable constant. The result is only
+    /// meaningful where `dF/dy` is nonzero.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{parse_equation, symb};
+    /// use std::collections::HashSet;
+    ///
+    /// let circle = parse_equation("x^2 + y^2 = 1", &HashSet::new(), &HashSet::new(), None).unwrap();
+    /// let slope = circle.implicit_diff(&symb("y"), &symb("x")).unwrap();
+    /// assert_eq!(slope.to_string(), "-x/y");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`DiffError::UnsupportedExpression`] if the equation does not depend on `y`,
+    /// and propagates differentiation and simplification errors.
+    pub fn implicit_diff(&self, y: &Symbol, x: &Symbol) -> Result<Expr, DiffError> {
+        Diff::new()
+            .implicit(&self.as_expr_zero(), y, x)
+            .map_err(|err| match err {
+                DiffError::DivisionByZero { .. } => DiffError::UnsupportedExpression(format!(
+                    "{self} does not depend on {}",
+                    y.to_expr()
+                )),
+                other => other,
+            })
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
+
+/// Parse an equation `lhs = rhs`, such as `y = x^2 + 1`
+///
+/// Both sides are parsed as by [`parse`], with the same symbols, functions and context.
+///
+/// # Example
+/// ```
+/// use symb_anafis::parse_equation;
+/// use std::collections::HashSet;
+///
+/// let eq = parse_equation("y = x^2 + 1", &HashSet::new(), &HashSet::new(), None).unwrap();
+/// assert_eq!(eq.lhs.to_string(), "y");
+/// assert_eq!(eq.to_string(), "y = 1 + x^2");
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if the input has no `=` or more than one, uses a comparison
+/// operator such as `<=` or `==`, has an empty side, or if either side fails to parse
+/// (spans point into `input`).
+pub fn parse_equation<S: BuildHasher + Clone>(
+    input: &str,
+    known_symbols: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    context: Option<&Context>,
+) -> Result<Equation, DiffError> {
+    super::logic::parse_equation(input, known_symbols, custom_functions, context)
+}
+
+/// Binary operator in a postfix token stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OpKind {
+    /// `a + b`
+    Add,
+    /// `a - b`
+    Sub,
+    /// `a * b`
+    Mul,
+    /// `a / b`
+    Div,
+    /// `a ^ b`
+    Pow,
+}
 
-    parse_expression(&tokens_with_mul, context)
+/// One token of a postfix (reverse Polish) stream, see [`Expr::from_postfix`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostfixToken<'src> {
+    /// Push a numeric literal
+    Number(f64),
+    /// Push a symbol by name
+    Symbol(&'src str),
+    /// Pop two operands and push their combination
+    BinaryOp(OpKind),
+    /// Pop `arity` arguments (first argument deepest) and push the call
+    Function(&'src str, usize),
 }
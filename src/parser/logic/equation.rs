@@ -0,0 +1,65 @@
+//! Equations: two expressions joined by a single `=`.
+//!
+//! Each side is parsed on its own, so error spans are shifted back into the whole input.
+//! Comparison operators (`==`, `<=`, `>=`, `!=`) are rejected with a dedicated error
+//! rather than being read as an `=` next to a stray character.
+
+use super::lexer::blank_comments;
+use super::program::offset_error;
+use crate::core::{Context, DiffError, Span};
+use crate::parser::{Equation, parse};
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+/// The operator separating the two sides.
+const EQUALS: char = '=';
+/// Characters that turn an adjacent `=` into a comparison operator.
+const COMPARISON_PARTS: [char; 4] = ['<', '>', '!', '='];
+
+pub(in super::super) fn parse_equation<S: BuildHasher + Clone>(
+    input: &str,
+    known_symbols: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    context: Option<&Context>,
+) -> Result<Equation, DiffError> {
+    let input = blank_comments(input)?;
+    let Some((lhs, rhs)) = input.split_once(EQUALS) else {
+        return Err(DiffError::invalid_syntax("expected '=' in equation"));
+    };
+    let at = lhs.len();
+    let rhs_start = at + EQUALS.len_utf8();
+
+    if lhs.ends_with(COMPARISON_PARTS) || rhs.starts_with(COMPARISON_PARTS) {
+        let start = if lhs.ends_with(COMPARISON_PARTS) {
+            at - 1
+        } else {
+            at
+        };
+        return Err(DiffError::invalid_syntax_at(
+            "comparison operators are not supported in equations",
+            Span::new(start, start + 2),
+        ));
+    }
+    if let Some(extra) = rhs.find(EQUALS) {
+        return Err(DiffError::invalid_syntax_at(
+            "expected a single '=' per equation",
+            Span::at(rhs_start + extra),
+        ));
+    }
+
+    let side = |source: &str, offset: usize, name: &str| {
+        if source.trim().is_empty() {
+            return Err(DiffError::invalid_syntax_at(
+                format!("missing {name} side of equation"),
+                Span::at(at),
+            ));
+        }
+        parse(source, known_symbols, custom_functions, context)
+            .map_err(|err| offset_error(err, offset))
+    };
+
+    Ok(Equation {
+        lhs: side(lhs, 0, "left-hand")?,
+        rhs: side(rhs, rhs_start, "right-hand")?,
+    })
+}
@@ -2,7 +2,7 @@
 //!
 //! Inserts `*` operators between tokens where multiplication is implied, e.g. `2x` → `2 * x`.
 
-use super::tokens::{Operator, Token};
+use super::tokens::{DIFF_CALL, GROUP_CALL, Operator, PIECEWISE_CALL, Token};
 use std::collections::HashSet;
 use std::hash::BuildHasher;
 
@@ -21,8 +21,12 @@ fn should_insert_mul<'src, S: BuildHasher>(
 
         // Identifier * (
         (Token::Identifier(name), Token::LeftParen) => {
-            // If it's a custom function, do NOT insert multiplication
-            !custom_functions.contains(name.as_ref())
+            // If it's a custom function, diff(...), group(...) or piecewise(...), do NOT
+            // insert multiplication
+            name != DIFF_CALL
+                && name != GROUP_CALL
+                && name != PIECEWISE_CALL
+                && !custom_functions.contains(name.as_ref())
         }
 
         // ) * Function operator: (a) sin(x) → (a) * sin(x)
@@ -57,40 +61,50 @@ fn should_insert_mul<'src, S: BuildHasher>(
 /// - Identifier/Number * (: `x (y)` → `x * (y)` (unless function call)
 ///
 /// Exception: Function followed by ( is NOT multiplication
+#[cfg(test)]
 pub fn insert_implicit_multiplication<'src, S: BuildHasher>(
-    tokens: Vec<Token<'src>>,
+    mut tokens: Vec<Token<'src>>,
     custom_functions: &HashSet<String, S>,
 ) -> Vec<Token<'src>> {
-    if tokens.is_empty() {
-        return tokens;
-    }
+    insert_implicit_multiplication_into(&mut tokens, &mut Vec::new(), custom_functions);
+    tokens
+}
 
-    // Optimization: Check if any insertion is needed before allocating new vector
+/// [`insert_implicit_multiplication`] in place, building into `spare` only when an
+/// insertion is needed and swapping it in; `spare` is left holding the old tokens
+pub fn insert_implicit_multiplication_into<'src, S: BuildHasher>(
+    tokens: &mut Vec<Token<'src>>,
+    spare: &mut Vec<Token<'src>>,
+    custom_functions: &HashSet<String, S>,
+) {
+    // Optimization: Check if any insertion is needed before building a new stream
     let needs_insertion = tokens
         .windows(2)
         .any(|w| should_insert_mul(&w[0], &w[1], custom_functions));
 
     if !needs_insertion {
-        return tokens;
+        return;
     }
 
+    spare.clear();
     #[allow(
         clippy::integer_division,
         reason = "Integer division for capacity estimation in token vector"
     )]
-    let mut result = Vec::with_capacity(tokens.len() * 3 / 2);
-    let mut it = tokens.into_iter().peekable();
+    spare.reserve(tokens.len() * 3 / 2);
+    let mut it = tokens.drain(..).peekable();
 
     while let Some(current) = it.next() {
         let needs_mul = it
             .peek()
             .is_some_and(|next| should_insert_mul(&current, next, custom_functions));
 
-        result.push(current);
+        spare.push(current);
         if needs_mul {
-            result.push(Token::Operator(Operator::Mul));
+            spare.push(Token::Operator(Operator::Mul));
         }
     }
 
-    result
+    drop(it);
+    std::mem::swap(tokens, spare);
 }
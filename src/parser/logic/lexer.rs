@@ -17,9 +17,11 @@
 //!    - Implicit multiplication (e.g., "xsin(y)" → "x * sin(y)") is heuristic-based
 //!    - Users can disambiguate by using explicit operators or declaring `fixed_vars`
 //
-use super::tokens::{Operator, Token};
+use super::tokens::{
+    DIFF_CALL, GROUP_CALL, INF_LITERAL, NAN_LITERAL, Operator, PIECEWISE_CALL, Token,
+};
 use crate::core::known_symbols::is_known_constant;
-use crate::core::{DiffError, Span};
+use crate::core::{CompareOp, DiffError, Span};
 use std::borrow::Cow;
 use std::cmp::{Ordering, Reverse};
 use std::collections::HashSet;
@@ -51,6 +53,12 @@ const BUILTINS: &[&str] = &[
     "acot",
     "asec",
     "acsc",
+    "sind",
+    "cosd",
+    "tand",
+    "asind",
+    "acosd",
+    "atand",
     "ln",
     "exp",
     "log",
@@ -74,9 +82,18 @@ const BUILTINS: &[&str] = &[
     "sinc",
     "abs",
     "signum",
+    "sign",
+    "sgn",
+    "heaviside",
+    "dirac",
+    "min",
+    "max",
+    "clamp",
+    "select",
     "floor",
     "ceil",
     "round",
+    "sum_data",
     "erf",
     "erfc",
     "gamma",
@@ -100,6 +117,7 @@ const BUILTINS: &[&str] = &[
     "elliptic_k",
     "zeta_deriv",
     "atan2",
+    "hypot",
     "spherical_harmonic",
 ];
 
@@ -116,7 +134,7 @@ pub(super) fn get_builtins_set() -> &'static HashSet<&'static str> {
 
 /// Check if a string is a builtin function name (O(1) lookup)
 #[inline]
-fn is_builtin(name: &str) -> bool {
+pub fn is_builtin(name: &str) -> bool {
     get_builtins_set().contains(name)
 }
 
@@ -162,6 +180,76 @@ pub fn balance_parentheses(input: &str) -> Cow<'_, str> {
     }
 }
 
+/// Start of a comment running to the end of the line
+const LINE_COMMENT: &str = "#";
+/// Start of a block comment
+const BLOCK_COMMENT_OPEN: &str = "/*";
+/// End of a block comment
+const BLOCK_COMMENT_CLOSE: &str = "*/";
+/// Explicit line continuation: a trailing backslash before the newline
+const LINE_CONTINUATION: char = '\\';
+
+/// Pass 0: Blank out comments and line continuations
+///
+/// `# ...` runs to the end of the line and `/* ... */` to its closing marker; a `\`
+/// followed only by spaces up to the newline marks a continuation. Each removed byte
+/// becomes a space and newlines are kept, so the result has the same length as `input`
+/// and every later span still points at the original source. Input without comments
+/// is returned borrowed.
+///
+/// # Errors
+/// Returns `InvalidSyntax` spanning the opening `/*` of an unterminated block comment.
+pub fn blank_comments(input: &str) -> Result<Cow<'_, str>, DiffError> {
+    if !input.contains(LINE_COMMENT)
+        && !input.contains(BLOCK_COMMENT_OPEN)
+        && !input.contains(LINE_CONTINUATION)
+    {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut out = input.as_bytes().to_vec();
+    let blank = |bytes: &mut [u8], start: usize, end: usize| {
+        for byte in &mut bytes[start..end] {
+            if *byte != b'\n' && *byte != b'\r' {
+                *byte = b' ';
+            }
+        }
+    };
+
+    let mut pos = 0;
+    while let Some(rest) = input.get(pos..).filter(|rest| !rest.is_empty()) {
+        if rest.starts_with(LINE_COMMENT) {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            blank(&mut out, pos, pos + len);
+            pos += len;
+        } else if let Some(body) = rest.strip_prefix(BLOCK_COMMENT_OPEN) {
+            let Some(close) = body.find(BLOCK_COMMENT_CLOSE) else {
+                return Err(DiffError::invalid_syntax_at(
+                    "unterminated block comment",
+                    Span::new(pos, pos + BLOCK_COMMENT_OPEN.len()),
+                ));
+            };
+            let len = BLOCK_COMMENT_OPEN.len() + close + BLOCK_COMMENT_CLOSE.len();
+            blank(&mut out, pos, pos + len);
+            pos += len;
+        } else if let Some(after) = rest.strip_prefix(LINE_CONTINUATION)
+            && after
+                .trim_start_matches([' ', '\t'])
+                .starts_with(['\n', '\r'])
+        {
+            blank(&mut out, pos, pos + 1);
+            pos += 1;
+        } else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    // Only whole comments were blanked, and those start and end on ASCII bytes
+    Ok(Cow::Owned(
+        String::from_utf8(out).expect("Blanking whole comments keeps UTF-8 valid"),
+    ))
+}
+
 /// Parse a number string to f64
 /// Supports: integers (3), decimals (3.14), scientific notation (1e10, 2.5e-3)
 ///
@@ -173,7 +261,11 @@ pub fn balance_parentheses(input: &str) -> Cow<'_, str> {
 /// decimal separators before calling this function.
 pub(super) fn parse_number(s: &str) -> Result<f64, DiffError> {
     // Remove underscores for parsing (standard in many languages/parsers)
-    let sanitized = s.replace('_', "");
+    let sanitized = if s.contains('_') {
+        Cow::Owned(s.replace('_', ""))
+    } else {
+        Cow::Borrowed(s)
+    };
 
     // Check for multiple decimal separators
     let dot_count = sanitized.chars().filter(|&c| c == '.').count();
@@ -209,6 +301,10 @@ pub(super) enum RawToken<'src> {
     RightParen,
     /// Comma separator
     Comma,
+    /// Comparison: <, <=, >, >=, ==
+    Compare(CompareOp),
+    /// Colon separator
+    Colon,
 }
 
 /// Helper: Scan derivative notation (∂...) with balanced parentheses
@@ -299,19 +395,30 @@ fn scan_derivative_notation(input: &str) -> Result<&str, DiffError> {
 
 /// Pass 1: Scan characters and create raw tokens
 /// Now tracks byte positions for better error reporting
+#[cfg(test)]
+pub(super) fn scan_characters(input: &str) -> Result<Vec<RawToken<'_>>, DiffError> {
+    let mut tokens = Vec::new();
+    scan_characters_into(input, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// [`scan_characters`], appending to a caller-owned buffer
 #[allow(
     clippy::too_many_lines,
     clippy::string_slice,
     reason = "Complex character scanning logic requires comprehensive tokenization and safe slicing is manually verified"
 )]
-pub(super) fn scan_characters(input: &str) -> Result<Vec<RawToken<'_>>, DiffError> {
+pub(super) fn scan_characters_into<'src>(
+    input: &'src str,
+    tokens: &mut Vec<RawToken<'src>>,
+) -> Result<(), DiffError> {
     // Estimate capacity: rough heuristic (input.len() / 2) to minimize reallocations
     // Heuristic: assume average token length is ~2 characters
     #[allow(
         clippy::integer_division,
         reason = "Integer division for capacity estimation in token vector"
     )]
-    let mut tokens = Vec::with_capacity(input.len() / 2);
+    tokens.reserve(input.len() / 2);
     let bytes = input.as_bytes();
     let mut pos = 0; // Current byte position
 
@@ -338,6 +445,30 @@ pub(super) fn scan_characters(input: &str) -> Result<Vec<RawToken<'_>>, DiffErro
                 tokens.push(RawToken::Comma);
                 pos += 1;
             }
+            ':' => {
+                tokens.push(RawToken::Colon);
+                pos += 1;
+            }
+
+            // Comparisons (piecewise conditions); a lone '=' stays invalid
+            '<' | '>' | '=' => {
+                let or_equal = bytes.get(pos + 1) == Some(&b'=');
+                let op = match (ch, or_equal) {
+                    ('<', false) => CompareOp::Lt,
+                    ('<', true) => CompareOp::Le,
+                    ('>', false) => CompareOp::Gt,
+                    ('>', true) => CompareOp::Ge,
+                    (_, true) => CompareOp::Eq,
+                    (_, false) => {
+                        return Err(DiffError::InvalidToken {
+                            token: ch.to_string(),
+                            span: Some(Span::new(pos, pos + 1)),
+                        });
+                    }
+                };
+                tokens.push(RawToken::Compare(op));
+                pos += if or_equal { 2 } else { 1 };
+            }
 
             // Single-char operators
             '+' => {
@@ -451,7 +582,7 @@ pub(super) fn scan_characters(input: &str) -> Result<Vec<RawToken<'_>>, DiffErro
         }
     }
 
-    Ok(tokens)
+    Ok(())
 }
 
 /// Pass 2: Resolve sequences into tokens using context
@@ -460,9 +591,31 @@ pub fn lex<'src, S: BuildHasher>(
     fixed_vars: &HashSet<String, S>,
     custom_functions: &HashSet<String, S>,
 ) -> Result<Vec<Token<'src>>, DiffError> {
-    let raw_tokens = scan_characters(input)?;
+    let mut tokens = Vec::new();
+    lex_into(
+        input,
+        fixed_vars,
+        custom_functions,
+        &mut Vec::new(),
+        &mut tokens,
+    )?;
+    Ok(tokens)
+}
+
+/// [`lex`], reusing `raw_tokens` as scratch space and appending to `tokens`
+///
+/// Identifiers are borrowed from `input`, so with warm buffers lexing does not allocate.
+pub fn lex_into<'src, S: BuildHasher>(
+    input: &'src str,
+    fixed_vars: &HashSet<String, S>,
+    custom_functions: &HashSet<String, S>,
+    raw_tokens: &mut Vec<RawToken<'src>>,
+    tokens: &mut Vec<Token<'src>>,
+) -> Result<(), DiffError> {
+    raw_tokens.clear();
+    scan_characters_into(input, raw_tokens)?;
     // Optimization: Pre-allocate capacity roughly matching raw tokens count
-    let mut tokens = Vec::with_capacity(raw_tokens.len());
+    tokens.reserve(raw_tokens.len());
 
     for i in 0..raw_tokens.len() {
         match &raw_tokens[i] {
@@ -470,6 +623,8 @@ pub fn lex<'src, S: BuildHasher>(
             RawToken::LeftParen => tokens.push(Token::LeftParen),
             RawToken::RightParen => tokens.push(Token::RightParen),
             RawToken::Comma => tokens.push(Token::Comma),
+            RawToken::Compare(op) => tokens.push(Token::Compare(*op)),
+            RawToken::Colon => tokens.push(Token::Colon),
 
             RawToken::Operator(c) => {
                 let op = match c {
@@ -504,12 +659,12 @@ pub fn lex<'src, S: BuildHasher>(
                     Cow::Borrowed(s) => s,
                     Cow::Owned(s) => return Err(DiffError::invalid_token(s.clone())),
                 };
-                resolve_sequence(s, fixed_vars, custom_functions, next_is_paren, &mut tokens);
+                resolve_sequence(s, fixed_vars, custom_functions, next_is_paren, tokens);
             }
         }
     }
 
-    Ok(tokens)
+    Ok(())
 }
 
 /// Resolve a sequence into tokens based on context
@@ -519,7 +674,7 @@ pub fn lex<'src, S: BuildHasher>(
 ///
 /// **Priority Order:**
 /// 1. Exact match in `fixed_vars` (user-declared multi-char variables)
-/// 2. Known mathematical constants (pi, e)
+/// 2. Non-finite literals (`inf`, `nan`) and known mathematical constants (pi, e)
 /// 3. Built-in functions when followed by `(`
 /// 4. Custom functions when followed by `(`
 /// 5. Suffix scan for built-ins (e.g., "xsin" → "x", "sin" if followed by `(`)
@@ -548,8 +703,24 @@ fn resolve_sequence<'src, S: BuildHasher>(
         return;
     }
 
-    // Priority 1.5: Check for known constants (pi, e)
-    if is_known_constant(seq) {
+    // Priority 1.25: Non-finite literals, unless the name is a custom function being called
+    if !(next_is_paren && custom_functions.contains(seq)) {
+        let literal = match seq {
+            INF_LITERAL => Some(f64::INFINITY),
+            NAN_LITERAL => Some(f64::NAN),
+            _ => None,
+        };
+        if let Some(n) = literal {
+            output.push(Token::Number(n));
+            return;
+        }
+    }
+
+    // Priority 1.5: Check for known constants (pi, e) and the diff(...), group(...) and
+    // piecewise(...) calls
+    if is_known_constant(seq)
+        || ((seq == DIFF_CALL || seq == GROUP_CALL || seq == PIECEWISE_CALL) && next_is_paren)
+    {
         output.push(Token::Identifier(Cow::Borrowed(seq)));
         return;
     }
@@ -605,14 +776,7 @@ fn resolve_sequence<'src, S: BuildHasher>(
     if seq.chars().all(is_identifier_continue) {
         // Check if any prefix is a fixed variable
         // Use char_indices for Unicode-safe slicing
-        let char_boundaries: Vec<usize> = seq.char_indices().map(|(i, _)| i).collect();
-        for (char_idx, _byte_idx) in char_boundaries.iter().enumerate() {
-            // Include end of string as a valid slice point
-            let end_byte = if char_idx + 1 < char_boundaries.len() {
-                char_boundaries[char_idx + 1]
-            } else {
-                seq.len()
-            };
+        for end_byte in seq.char_indices().map(|(i, c)| i + c.len_utf8()) {
             let prefix = seq.get(0..end_byte).expect("Checked char boundaries");
             if fixed_vars.contains(prefix) {
                 // Found a fixed variable prefix, split here
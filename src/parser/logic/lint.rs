@@ -0,0 +1,50 @@
+//! Warnings for input that parses but may not mean what was intended.
+//!
+//! Juxtaposed parentheses multiply (`(a+b)(c+d)`, `2(x+1)`), except directly after a
+//! function name, where they are a call. A custom function name separated from its
+//! parenthesis by whitespace (`f (x)`) is still a call, but reads like `f * (x)`, so it
+//! is reported.
+
+use crate::core::Span;
+use crate::parser::ParseWarning;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+/// Whether `c` can continue an identifier
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Every custom function name in `input` followed by whitespace and then `(`
+pub fn spaced_calls<S: BuildHasher>(
+    input: &str,
+    custom_functions: &HashSet<String, S>,
+) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+    let mut prev = None;
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let starts_ident = (c.is_alphabetic() || c == '_') && !prev.is_some_and(is_ident_char);
+        prev = Some(c);
+        if !starts_ident {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(pos, next)) = chars.peek().filter(|&&(_, next)| is_ident_char(next)) {
+            end = pos + next.len_utf8();
+            prev = Some(next);
+            chars.next();
+        }
+        let name = input.get(start..end).unwrap_or_default();
+        let rest = input.get(end..).unwrap_or_default();
+        let after_gap = rest.trim_start();
+        let gap = rest.len() - after_gap.len();
+        if gap > 0 && after_gap.starts_with('(') && custom_functions.contains(name) {
+            warnings.push(ParseWarning::SpacedFunctionCall {
+                name: name.to_owned(),
+                span: Span::new(start, end + gap + 1),
+            });
+        }
+    }
+    warnings
+}
@@ -1,13 +1,29 @@
 //! Internal parser implementation details.
 
+mod equation;
 mod implicit_mul;
 mod lexer;
+mod lint;
+mod notation;
+mod options;
+mod postfix;
 mod pratt;
+mod program;
+mod scratch;
+mod source_map;
 mod tokens;
+mod where_clause;
 
-pub(super) use implicit_mul::insert_implicit_multiplication;
-pub(super) use lexer::{balance_parentheses, lex};
-pub(super) use pratt::parse_expression;
+pub(super) use equation::parse_equation;
+pub use lexer::is_builtin;
+pub(super) use lexer::{balance_parentheses, blank_comments};
+pub(super) use lint::spaced_calls;
+pub(super) use notation::normalize_notation;
+pub use options::ParserOptions;
+pub(super) use program::parse_program;
+pub use scratch::ParseScratch;
+pub use source_map::SourceMap;
+pub(super) use where_clause::WhereClause;
 
 #[cfg(test)]
 mod test;
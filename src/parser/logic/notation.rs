@@ -0,0 +1,73 @@
+//! Optional input conventions: decimal commas and Unicode operator signs.
+//!
+//! Both are rewritten to the ASCII syntax before tokenization. Every replacement has
+//! the byte length of the text it replaces, so error spans still point into the
+//! original input.
+
+use std::borrow::Cow;
+
+use super::options::ParserOptions;
+
+/// Unicode operator signs and their ASCII spelling
+const UNICODE_OPERATORS: &[(char, u8)] = &[
+    ('\u{2212}', b'-'),
+    ('\u{b7}', b'*'),
+    ('\u{22c5}', b'*'),
+    ('\u{d7}', b'*'),
+    ('\u{f7}', b'/'),
+];
+
+/// Whether `text` ends in the digits of a number literal, not of an identifier like `x1`
+fn ends_in_number(text: &str) -> bool {
+    let before = text.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_');
+    before.len() < text.len()
+        && !before.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '\u{3c0}')
+}
+
+/// Rewrite decimal commas and Unicode operators in `source` as `options` asks
+///
+/// A wide operator sign is padded with spaces in front, except a minus sign opening the
+/// exponent of a number, which is padded with zeros behind (`1e−5` becomes `1e-005`).
+pub fn normalize_notation<'src>(source: &'src str, options: &ParserOptions) -> Cow<'src, str> {
+    let has_operators = options.unicode_operators
+        && source.contains(|c: char| UNICODE_OPERATORS.iter().any(|&(sign, _)| sign == c));
+    let has_commas = options.decimal_comma && source.contains(',');
+    if !has_operators && !has_commas {
+        return Cow::Borrowed(source);
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ',' && has_commas {
+            let next_is_digit = chars.peek().is_some_and(char::is_ascii_digit);
+            out.push(if next_is_digit && ends_in_number(&out) {
+                '.'
+            } else {
+                ','
+            });
+            continue;
+        }
+        let ascii = UNICODE_OPERATORS
+            .iter()
+            .find(|&&(sign, _)| has_operators && sign == c)
+            .map(|&(_, ascii)| char::from(ascii));
+        let Some(ascii) = ascii else {
+            out.push(c);
+            continue;
+        };
+        let padding = c.len_utf8() - 1;
+        let in_exponent = ascii == '-'
+            && out
+                .strip_suffix(['e', 'E'])
+                .is_some_and(|mantissa| ends_in_number(mantissa.trim_end_matches('.')));
+        if in_exponent {
+            out.push(ascii);
+            out.extend(std::iter::repeat_n('0', padding));
+        } else {
+            out.extend(std::iter::repeat_n(' ', padding));
+            out.push(ascii);
+        }
+    }
+    Cow::Owned(out)
+}
@@ -0,0 +1,52 @@
+//! Settings of [`parse_configured`](crate::parse_configured) beyond the symbol sets.
+
+use crate::core::SymbolScope;
+
+/// How [`parse_configured`](crate::parse_configured) reads a formula and what it reports
+///
+/// The default reads plain ASCII syntax with floating-point literals in the global
+/// symbol table and reports nothing extra, matching [`parse`](crate::parse).
+///
+/// # Example
+/// ```
+/// use symb_anafis::{ParserOptions, SymbolScope, parse_configured};
+/// use std::collections::HashSet;
+///
+/// let scope = SymbolScope::new();
+/// let options = ParserOptions {
+///     decimal_comma: true,
+///     unicode_operators: true,
+///     scope: Some(scope.clone()),
+///     ..ParserOptions::default()
+/// };
+/// let parsed = parse_configured("2,5·t − 1", &HashSet::new(), &HashSet::new(), None, &options)?;
+/// assert_eq!(parsed.expr, 2.5 * scope.symb("t") - 1.0);
+/// # Ok::<(), symb_anafis::DiffError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Each flag is an independent parser switch"
+)]
+pub struct ParserOptions {
+    /// Read a comma between the digits of a number as its decimal separator, so
+    /// `1,23E-5` is `1.23e-5`. Function arguments then need a space after the comma:
+    /// `max(1, 2)`, since `max(1,2)` reads as `max(1.2)`.
+    pub decimal_comma: bool,
+    /// Accept `−` (U+2212) as `-`, `·`, `⋅` and `×` as `*`, and `÷` as `/`
+    pub unicode_operators: bool,
+    /// Read decimal literals as exact fractions, so `0.1` is `1/10`
+    pub exact: bool,
+    /// Resolve every name in this scope instead of the global symbol table
+    ///
+    /// The same formula parsed in two scopes gives expressions over distinct
+    /// variables. A `context` is still used for its functions and symbol names; its
+    /// own scope, if any, is replaced.
+    pub scope: Option<SymbolScope>,
+    /// Report constructs that parse but may not mean what was intended, in
+    /// [`Parsed::warnings`](crate::Parsed::warnings)
+    pub warnings: bool,
+    /// Return a [`SourceMap`](crate::SourceMap) back to the input, in
+    /// [`Parsed::source_map`](crate::Parsed::source_map)
+    pub source_map: bool,
+}
@@ -0,0 +1,253 @@
+//! Postfix (reverse Polish) construction and emission of expressions.
+//!
+//! Machine-generated token streams are turned into an [`Expr`] with a value stack,
+//! using the same constructors as the infix parser, so both paths build identical trees.
+
+use super::pratt::{derivative_from_call, group_from_call};
+use super::tokens::{DIFF_CALL, GROUP_CALL, Operator, PIECEWISE_CALL};
+use crate::core::{CompareOp, Condition, DiffError, Expr, ExprKind, Span};
+use crate::parser::{OpKind, PostfixToken};
+use std::sync::Arc;
+
+/// Error for the token at `index` in a postfix stream.
+fn token_error(index: usize, msg: impl AsRef<str>) -> DiffError {
+    DiffError::invalid_syntax_at(
+        format!("postfix token {index}: {}", msg.as_ref()),
+        Span::at(index),
+    )
+}
+
+/// Comparisons in the order they are tried when reading a piecewise stream back.
+const COMPARISONS: [CompareOp; 5] = [
+    CompareOp::Lt,
+    CompareOp::Le,
+    CompareOp::Gt,
+    CompareOp::Ge,
+    CompareOp::Eq,
+];
+
+/// Build a piecewise node from `lhs op rhs value` groups followed by the default, where
+/// each `op` is a symbol spelled like the comparison (`<`, `<=`, ...).
+fn piecewise_from_args(index: usize, args: Vec<Expr>) -> Result<Expr, DiffError> {
+    if args.len() % 4 != 1 || args.len() < 5 {
+        return Err(token_error(
+            index,
+            format!(
+                "piecewise takes 4 values per branch and a default, got {}",
+                args.len()
+            ),
+        ));
+    }
+    let mut args = args.into_iter();
+    let mut branches = Vec::new();
+    while args.len() > 1 {
+        let (Some(lhs), Some(op), Some(rhs), Some(value)) =
+            (args.next(), args.next(), args.next(), args.next())
+        else {
+            break;
+        };
+        let op = match &op.kind {
+            ExprKind::Symbol(s) => COMPARISONS.into_iter().find(|c| c.as_str() == s.as_str()),
+            _ => None,
+        }
+        .ok_or_else(|| token_error(index, format!("'{op}' is not a piecewise comparison")))?;
+        branches.push((Condition::new(op, lhs, rhs), value));
+    }
+    let default = args.next().ok_or(DiffError::UnexpectedEndOfInput)?;
+    Ok(Expr::piecewise(branches, default))
+}
+
+/// Build a function call, validating the arity of built-in functions.
+fn function_call(index: usize, name: &str, args: Vec<Expr>) -> Result<Expr, DiffError> {
+    if name == DIFF_CALL {
+        return derivative_from_call(args).map_err(|err| token_error(index, err.to_string()));
+    }
+    if name == GROUP_CALL {
+        return group_from_call(args).map_err(|err| token_error(index, err.to_string()));
+    }
+    if name == PIECEWISE_CALL {
+        return piecewise_from_args(index, args);
+    }
+    match Operator::parse_str(name).filter(Operator::is_function) {
+        Some(op) if args.len() < op.min_arity() => Err(token_error(
+            index,
+            format!(
+                "function '{}' requires at least {} argument(s), got {}",
+                op.to_name(),
+                op.min_arity(),
+                args.len()
+            ),
+        )),
+        Some(op) => Ok(Expr::func_multi(op.to_name(), args)),
+        None => Ok(Expr::func_multi(name, args)),
+    }
+}
+
+impl Expr {
+    /// Build an expression from a postfix (reverse Polish) token stream.
+    ///
+    /// Operands are pushed on a stack; each [`PostfixToken::BinaryOp`] pops two values and
+    /// each [`PostfixToken::Function`] pops as many as its arity. `diff` with 2 or 3
+    /// arguments builds a derivative node, as in the infix syntax.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Expr, OpKind, PostfixToken};
+    ///
+    /// // sin(x) * 2
+    /// let expr = Expr::from_postfix(&[
+    ///     PostfixToken::Symbol("x"),
+    ///     PostfixToken::Function("sin", 1),
+    ///     PostfixToken::Number(2.0),
+    ///     PostfixToken::BinaryOp(OpKind::Mul),
+    /// ])?;
+    /// assert_eq!(expr.to_string(), "2*sin(x)");
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError::EmptyFormula` for an empty stream, and `DiffError::InvalidSyntax`
+    /// with the offending token index (also as the span) when an operator or function
+    /// finds too few operands, a built-in function gets too few arguments, or values are
+    /// left over at the end.
+    pub fn from_postfix(tokens: &[PostfixToken<'_>]) -> Result<Self, DiffError> {
+        // Each entry remembers which token produced it, for leftover diagnostics.
+        let mut stack: Vec<(usize, Self)> = Vec::with_capacity(tokens.len());
+
+        for (index, token) in tokens.iter().enumerate() {
+            let needed = match token {
+                PostfixToken::Number(_) | PostfixToken::Symbol(_) => 0,
+                PostfixToken::BinaryOp(_) => 2,
+                PostfixToken::Function(_, arity) => *arity,
+            };
+            if stack.len() < needed {
+                return Err(token_error(
+                    index,
+                    format!(
+                        "{token:?} needs {needed} operand(s) but the stack holds {}",
+                        stack.len()
+                    ),
+                ));
+            }
+            let mut args: Vec<Self> = stack
+                .drain(stack.len() - needed..)
+                .map(|(_, e)| e)
+                .collect();
+
+            let value = match *token {
+                PostfixToken::Number(n) => Self::number(n),
+                PostfixToken::Symbol(name) => Self::symbol(name),
+                PostfixToken::BinaryOp(op) => {
+                    let (Some(right), Some(left)) = (args.pop(), args.pop()) else {
+                        return Err(token_error(index, "binary operator needs two operands"));
+                    };
+                    match op {
+                        OpKind::Add => Self::add_expr(left, right),
+                        OpKind::Sub => Self::sub_expr(left, right),
+                        OpKind::Mul => Self::mul_expr(left, right),
+                        OpKind::Div => Self::div_expr(left, right),
+                        OpKind::Pow => Self::pow_static(left, right),
+                    }
+                }
+                PostfixToken::Function(name, _) => function_call(index, name, args)?,
+            };
+            stack.push((index, value));
+        }
+
+        match stack.len() {
+            0 => Err(DiffError::EmptyFormula),
+            1 => stack.pop().map(|(_, e)| e).ok_or(DiffError::EmptyFormula),
+            n => Err(token_error(
+                stack[1].0,
+                format!("value is never consumed ({n} values left on the stack, expected one)"),
+            )),
+        }
+    }
+
+    /// Emit this expression as a postfix (reverse Polish) token stream.
+    ///
+    /// N-ary sums and products become left-folded chains of binary operators and
+    /// polynomial nodes are written out term by term, so
+    /// [`Expr::from_postfix`] rebuilds a structurally equal expression. Derivative
+    /// nodes are emitted as `diff(inner, var, order)`, and piecewise nodes as a
+    /// `piecewise` call over `lhs op rhs value` groups and the default, the comparison
+    /// being a symbol such as `<=`. Anonymous symbols have no name and are emitted as an
+    /// empty symbol.
+    #[must_use]
+    pub fn to_postfix(&self) -> Vec<PostfixToken<'_>> {
+        let mut out = Vec::new();
+        self.emit_postfix(&mut out);
+        out
+    }
+
+    /// Append the postfix tokens for `self` to `out`.
+    fn emit_postfix<'expr>(&'expr self, out: &mut Vec<PostfixToken<'expr>>) {
+        /// Left-folded chain `a b op c op ...` for n-ary nodes.
+        fn chain<'expr>(items: &'expr [Arc<Expr>], op: OpKind, out: &mut Vec<PostfixToken<'expr>>) {
+            for (i, item) in items.iter().enumerate() {
+                item.emit_postfix(out);
+                if i > 0 {
+                    out.push(PostfixToken::BinaryOp(op));
+                }
+            }
+        }
+
+        match &self.kind {
+            ExprKind::Number(n) => out.push(PostfixToken::Number(*n)),
+            ExprKind::Symbol(s) => out.push(PostfixToken::Symbol(s.as_str())),
+            ExprKind::FunctionCall { name, args } => {
+                for arg in args {
+                    arg.emit_postfix(out);
+                }
+                out.push(PostfixToken::Function(name.as_str(), args.len()));
+            }
+            ExprKind::Sum(terms) => chain(terms, OpKind::Add, out),
+            ExprKind::Product(factors) => chain(factors, OpKind::Mul, out),
+            ExprKind::Div(num, den) => {
+                num.emit_postfix(out);
+                den.emit_postfix(out);
+                out.push(PostfixToken::BinaryOp(OpKind::Div));
+            }
+            ExprKind::Pow(base, exp) => {
+                base.emit_postfix(out);
+                exp.emit_postfix(out);
+                out.push(PostfixToken::BinaryOp(OpKind::Pow));
+            }
+            ExprKind::Derivative { inner, var, order } => {
+                inner.emit_postfix(out);
+                out.push(PostfixToken::Symbol(var.as_str()));
+                out.push(PostfixToken::Number(f64::from(*order)));
+                out.push(PostfixToken::Function(DIFF_CALL, 3));
+            }
+            ExprKind::Poly(poly) => {
+                for (i, &(pow, coeff)) in poly.terms().iter().enumerate() {
+                    out.push(PostfixToken::Number(coeff));
+                    if pow > 0 {
+                        poly.base().emit_postfix(out);
+                        if pow > 1 {
+                            out.push(PostfixToken::Number(f64::from(pow)));
+                            out.push(PostfixToken::BinaryOp(OpKind::Pow));
+                        }
+                        out.push(PostfixToken::BinaryOp(OpKind::Mul));
+                    }
+                    if i > 0 {
+                        out.push(PostfixToken::BinaryOp(OpKind::Add));
+                    }
+                }
+            }
+            ExprKind::Piecewise { branches, default } => {
+                for (condition, value) in branches {
+                    condition.lhs.emit_postfix(out);
+                    out.push(PostfixToken::Symbol(condition.op.as_str()));
+                    condition.rhs.emit_postfix(out);
+                    value.emit_postfix(out);
+                }
+                default.emit_postfix(out);
+                out.push(PostfixToken::Function(
+                    PIECEWISE_CALL,
+                    4 * branches.len() + 1,
+                ));
+            }
+        }
+    }
+}
@@ -3,16 +3,21 @@
 //! Implements a top-down operator precedence parser with support for
 //! infix operators, prefix operators (unary minus), and function calls.
 
-use super::tokens::{Operator, Token};
-use crate::core::{DiffError, Expr};
+use super::tokens::{DIFF_CALL, GROUP_CALL, Operator, PIECEWISE_CALL, Token};
+use crate::core::{Condition, DiffError, Expr, ExprKind};
 
 use crate::core::Context;
+use crate::core::traits::small_rational;
 
 /// Parse tokens into an AST using Pratt parsing algorithm
+///
+/// With `exact`, decimal literals become the fractions they were written as (`0.1` is
+/// `1/10`) so that the constructors cannot fold them in floating point.
 #[inline]
 pub fn parse_expression(
     tokens: &[Token<'_>],
     context: Option<&Context>,
+    exact: bool,
 ) -> Result<Expr, DiffError> {
     if tokens.is_empty() {
         return Err(DiffError::UnexpectedEndOfInput);
@@ -22,6 +27,7 @@ pub fn parse_expression(
         tokens,
         pos: 0,
         context,
+        exact,
     };
 
     parser.parse_expr(0)
@@ -35,6 +41,8 @@ struct Parser<'tokens, 'src> {
     pos: usize,
     /// Optional context for parsing
     context: Option<&'tokens Context>,
+    /// Whether decimal literals are read as exact fractions
+    exact: bool,
 }
 
 impl<'src> Parser<'_, 'src> {
@@ -152,6 +160,62 @@ impl<'src> Parser<'_, 'src> {
         Ok(args)
     }
 
+    /// Parse the arguments of `piecewise(...)` up to the closing parenthesis: branches
+    /// `lhs op rhs: value`, optionally followed by a default value (NaN when omitted)
+    fn parse_piecewise(&mut self) -> Result<Expr, DiffError> {
+        let mut branches = Vec::new();
+        let mut default = None;
+
+        while !matches!(self.current(), Some(Token::RightParen)) {
+            if default.is_some() {
+                return Err(DiffError::invalid_syntax(
+                    "the piecewise default must be the last argument",
+                ));
+            }
+            let lhs = self.parse_expr(0)?;
+            if let Some(Token::Compare(op)) = self.current() {
+                let op = *op;
+                self.advance();
+                let rhs = self.parse_expr(0)?;
+                if !matches!(self.current(), Some(Token::Colon)) {
+                    return Err(DiffError::UnexpectedToken {
+                        expected: "':' after the piecewise condition".to_owned(),
+                        got: format!("{:?}", self.current()),
+                        span: None,
+                    });
+                }
+                self.advance();
+                let value = self.parse_expr(0)?;
+                branches.push((Condition::new(op, lhs, rhs), value));
+            } else {
+                default = Some(lhs);
+            }
+
+            match self.current() {
+                Some(Token::Comma) => self.advance(),
+                Some(Token::RightParen) => {}
+                _ => {
+                    return Err(DiffError::UnexpectedToken {
+                        expected: ", or )".to_owned(),
+                        got: format!("{:?}", self.current()),
+                        span: None,
+                    });
+                }
+            }
+        }
+        self.advance(); // consume )
+
+        if branches.is_empty() {
+            return Err(DiffError::invalid_syntax(
+                "piecewise needs at least one 'condition: value' branch",
+            ));
+        }
+        Ok(Expr::piecewise(
+            branches,
+            default.unwrap_or_else(|| Expr::number(f64::NAN)),
+        ))
+    }
+
     /// Parse a prefix expression
     #[allow(
         clippy::too_many_lines,
@@ -168,12 +232,26 @@ impl<'src> Parser<'_, 'src> {
         match token {
             Token::Number(n) => {
                 self.advance();
+                if self.exact
+                    && let Some((num, den)) = small_rational(*n)
+                {
+                    #[allow(clippy::cast_precision_loss, reason = "Both parts are below 2^53")]
+                    return Ok(Expr::div_expr(
+                        Expr::number(num as f64),
+                        Expr::number(den as f64),
+                    ));
+                }
                 Ok(Expr::number(*n))
             }
 
             Token::Identifier(name) => {
                 self.advance();
 
+                if name == PIECEWISE_CALL && matches!(self.current(), Some(Token::LeftParen)) {
+                    self.advance(); // consume (
+                    return self.parse_piecewise();
+                }
+
                 // Check if this is a function call
                 if matches!(self.current(), Some(Token::LeftParen)) {
                     // This is a custom function call
@@ -190,6 +268,13 @@ impl<'src> Parser<'_, 'src> {
                         });
                     }
 
+                    if name == DIFF_CALL {
+                        return derivative_from_call(args);
+                    }
+                    if name == GROUP_CALL {
+                        return group_from_call(args);
+                    }
+
                     Ok(Expr::func_multi(name, args))
                 } else if let Some(ctx) = self.context {
                     Ok(ctx.symb(name.as_ref()).to_expr())
@@ -292,6 +377,7 @@ impl<'src> Parser<'_, 'src> {
                             tokens: args,
                             pos: 0,
                             context: self.context,
+                            exact: self.exact,
                         };
                         let mut exprs = Vec::new();
 
@@ -369,3 +455,64 @@ impl<'src> Parser<'_, 'src> {
         }
     }
 }
+
+/// Build a derivative node from `diff(expr, var)` or `diff(expr, var, order)`.
+pub(super) fn derivative_from_call(mut args: Vec<Expr>) -> Result<Expr, DiffError> {
+    if !(2..=3).contains(&args.len()) {
+        return Err(DiffError::InvalidFunctionCall {
+            name: DIFF_CALL.to_owned(),
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let order = match args.get(2).map(|e| &e.kind) {
+        None => 1,
+        Some(ExprKind::Number(n)) if n.fract() == 0.0 && (1.0..=100.0).contains(n) => {
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "Checked integral and within 1..=100"
+            )]
+            let order = *n as u32;
+            order
+        }
+        Some(_) => {
+            return Err(DiffError::invalid_token(
+                "diff order must be an integer between 1 and 100",
+            ));
+        }
+    };
+
+    let ExprKind::Symbol(var) = &args[1].kind else {
+        return Err(DiffError::invalid_token("diff variable must be a symbol"));
+    };
+    let var = var.clone();
+
+    args.truncate(1);
+    let inner = args.pop().ok_or(DiffError::UnexpectedEndOfInput)?;
+    Ok(Expr::derivative_interned(inner, var, order))
+}
+
+/// Build a labeled group from `group(expr)` or `group(expr, Name)`.
+///
+/// The label is `Name`, or `group` when none is given.
+pub(super) fn group_from_call(mut args: Vec<Expr>) -> Result<Expr, DiffError> {
+    if !(1..=2).contains(&args.len()) {
+        return Err(DiffError::InvalidFunctionCall {
+            name: GROUP_CALL.to_owned(),
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let label = match args.get(1).map(|e| &e.kind) {
+        None => GROUP_CALL.to_owned(),
+        Some(ExprKind::Symbol(name)) => name.as_str().to_owned(),
+        Some(_) => return Err(DiffError::invalid_token("group name must be a symbol")),
+    };
+
+    args.truncate(1);
+    let inner = args.pop().ok_or(DiffError::UnexpectedEndOfInput)?;
+    Ok(Expr::labeled(&label, inner))
+}
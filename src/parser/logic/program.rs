@@ -0,0 +1,162 @@
+//! Multi-statement programs: `name = expr;` assignments followed by a final expression.
+//!
+//! Each assignment is parsed on its own and expanded in terms of the real variables
+//! before it is stored, so the final expression never refers to an intermediate name.
+//! Repeated intermediates become repeated subtrees, which the compiler's CSE pass turns
+//! back into a single cached slot.
+
+use super::lexer::blank_comments;
+use crate::core::{Context, DiffError, Expr, PolyConversion, Span, with_poly_conversion};
+use crate::parser::{Intermediate, Program, parse};
+use std::collections::HashSet;
+
+/// Statement separator in program sources.
+const STATEMENT_SEPARATOR: char = ';';
+/// Assignment operator in program sources.
+const ASSIGNMENT: char = '=';
+
+/// Whether `name` is a plain identifier that can be assigned to.
+pub(super) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Shift the span of a parse error by `offset` bytes so it points into the whole source.
+pub(super) fn offset_error(err: DiffError, offset: usize) -> DiffError {
+    let shift = |span: Option<Span>| span.map(|s| Span::new(s.start() + offset, s.end() + offset));
+    match err {
+        DiffError::InvalidSyntax { msg, span } => DiffError::InvalidSyntax {
+            msg,
+            span: shift(span),
+        },
+        DiffError::InvalidNumber { value, span } => DiffError::InvalidNumber {
+            value,
+            span: shift(span),
+        },
+        DiffError::InvalidToken { token, span } => DiffError::InvalidToken {
+            token,
+            span: shift(span),
+        },
+        DiffError::UnexpectedToken {
+            expected,
+            got,
+            span,
+        } => DiffError::UnexpectedToken {
+            expected,
+            got,
+            span: shift(span),
+        },
+        DiffError::AmbiguousSequence {
+            sequence,
+            suggestion,
+            span,
+        } => DiffError::AmbiguousSequence {
+            sequence,
+            suggestion,
+            span: shift(span),
+        },
+        DiffError::CircularDefinition { cycle, span } => DiffError::CircularDefinition {
+            cycle,
+            span: shift(span),
+        },
+        other => other,
+    }
+}
+
+/// Parse one statement body, with byte offset `offset` in the program, and expand the
+/// intermediates defined so far.
+fn parse_statement(
+    body: &str,
+    offset: usize,
+    intermediates: &[Intermediate],
+    context: Option<&Context>,
+) -> Result<Expr, DiffError> {
+    let known: HashSet<String> = intermediates.iter().map(|i| i.name.clone()).collect();
+    // Keep sums plain so the substitution below can see every occurrence.
+    let parsed = with_poly_conversion(PolyConversion::DISABLED, || {
+        parse(body, &known, &HashSet::new(), context)
+    })
+    .map_err(|err| offset_error(err, offset))?;
+
+    Ok(intermediates
+        .iter()
+        .fold(parsed, |acc, i| acc.substitute(&i.name, &i.definition)))
+}
+
+pub(in super::super) fn parse_program(
+    source: &str,
+    context: Option<&Context>,
+) -> Result<Program, DiffError> {
+    let mut intermediates: Vec<Intermediate> = Vec::new();
+    // Names already used as real variables; assigning to one would shadow it.
+    let mut variables: HashSet<String> = context.map(Context::symbol_names_set).unwrap_or_default();
+    let mut result = None;
+    let mut offset = 0;
+    // Comments may contain separators; blanking them keeps every offset below valid
+    let source = blank_comments(source)?;
+
+    for statement in source.split(STATEMENT_SEPARATOR) {
+        let start = offset;
+        offset += statement.len() + STATEMENT_SEPARATOR.len_utf8();
+        if statement.trim().is_empty() {
+            continue;
+        }
+        if result.is_some() {
+            return Err(DiffError::invalid_syntax_at(
+                "only assignments may precede the final expression",
+                Span::new(start, start + statement.len()),
+            ));
+        }
+
+        let Some((lhs, body)) = statement.split_once(ASSIGNMENT) else {
+            result = Some(parse_statement(statement, start, &intermediates, context)?);
+            continue;
+        };
+
+        let body_start = start + lhs.len() + ASSIGNMENT.len_utf8();
+        let name = lhs.trim();
+        let name_start = start + lhs.find(name).unwrap_or(0);
+        let name_span = Span::new(name_start, name_start + name.len());
+        if !is_identifier(name) {
+            return Err(DiffError::invalid_syntax_at(
+                format!("cannot assign to '{name}'"),
+                name_span,
+            ));
+        }
+        if let Some(extra) = body.find(ASSIGNMENT) {
+            return Err(DiffError::invalid_syntax_at(
+                "expected a single '=' per assignment",
+                Span::at(body_start + extra),
+            ));
+        }
+
+        let definition = parse_statement(body, body_start, &intermediates, context)?;
+        variables.extend(definition.variables());
+        if intermediates.iter().any(|i| i.name == name) {
+            return Err(DiffError::invalid_syntax_at(
+                format!("'{name}' is already defined"),
+                name_span,
+            ));
+        }
+        if variables.contains(name) {
+            return Err(DiffError::invalid_syntax_at(
+                format!("assignment to '{name}' would shadow the variable of the same name"),
+                name_span,
+            ));
+        }
+
+        intermediates.push(Intermediate {
+            name: name.to_owned(),
+            definition,
+            span: name_span,
+        });
+    }
+
+    result
+        .map(|expr| Program {
+            expr,
+            intermediates,
+        })
+        .ok_or_else(|| DiffError::invalid_syntax("program must end with an expression"))
+}
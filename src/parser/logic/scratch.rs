@@ -0,0 +1,112 @@
+//! Reusable token buffers for parsing many formulas in a row.
+//!
+//! Tokens borrow identifier names from the source they were lexed from, so a buffer
+//! cannot outlive its input. Between parses the buffers are kept empty and re-typed for
+//! the next input's lifetime, which keeps their allocations.
+
+use super::implicit_mul::insert_implicit_multiplication_into;
+use super::lexer::{RawToken, lex_into};
+use super::pratt::parse_expression;
+use super::tokens::Token;
+use crate::core::{Context, DiffError, Expr};
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::mem::take;
+
+/// Scratch buffers reused across calls to [`parse_reuse`](crate::parse_reuse)
+///
+/// Once the buffers have grown to fit the formulas being parsed, tokenizing performs
+/// no allocations: identifiers are borrowed from the input and looked up in the symbol
+/// interner by `&str`, so only genuinely new symbols allocate.
+#[derive(Debug, Default)]
+pub struct ParseScratch {
+    /// Pass 1 output
+    raw: Vec<RawToken<'static>>,
+    /// Resolved tokens
+    tokens: Vec<Token<'static>>,
+    /// Second buffer for implicit multiplication insertion
+    spare: Vec<Token<'static>>,
+}
+
+/// Empty `buf` and hand its allocation on with a different token lifetime
+///
+/// Both element types are the same token type, so in-place collection keeps the
+/// allocation.
+fn recycle<T, U>(buf: Vec<T>) -> Vec<U> {
+    buf.into_iter().filter_map(|_| None).collect()
+}
+
+impl ParseScratch {
+    /// Create empty scratch buffers
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            raw: Vec::new(),
+            tokens: Vec::new(),
+            spare: Vec::new(),
+        }
+    }
+
+    /// Tokenize and parse `source`, which has already had comments blanked and
+    /// parentheses balanced
+    pub(in crate::parser) fn parse<S: BuildHasher>(
+        &mut self,
+        source: &str,
+        known_symbols: &HashSet<String, S>,
+        custom_functions: &HashSet<String, S>,
+        context: Option<&Context>,
+        exact: bool,
+    ) -> Result<Expr, DiffError> {
+        self.with_tokens(source, known_symbols, custom_functions, |tokens| {
+            parse_expression(tokens, context, exact)
+        })
+    }
+
+    /// Intern every identifier of `source` and return how many there were, without
+    /// building an expression: the identifier path of [`Self::parse`] in isolation
+    #[cfg(test)]
+    pub(crate) fn intern_identifiers<S: BuildHasher>(
+        &mut self,
+        source: &str,
+        known_symbols: &HashSet<String, S>,
+        custom_functions: &HashSet<String, S>,
+    ) -> Result<usize, DiffError> {
+        self.with_tokens(source, known_symbols, custom_functions, |tokens| {
+            let names = tokens.iter().filter_map(|token| match token {
+                Token::Identifier(name) => Some(crate::symb(name)),
+                _ => None,
+            });
+            Ok(names.count())
+        })
+    }
+
+    /// Run `f` on the tokens of `source`, lexed into the reused buffers
+    fn with_tokens<S: BuildHasher, R>(
+        &mut self,
+        source: &str,
+        known_symbols: &HashSet<String, S>,
+        custom_functions: &HashSet<String, S>,
+        f: impl FnOnce(&[Token<'_>]) -> Result<R, DiffError>,
+    ) -> Result<R, DiffError> {
+        let mut raw = recycle(take(&mut self.raw));
+        let mut tokens = recycle(take(&mut self.tokens));
+        let mut spare = recycle(take(&mut self.spare));
+
+        let result = lex_into(
+            source,
+            known_symbols,
+            custom_functions,
+            &mut raw,
+            &mut tokens,
+        )
+        .and_then(|()| {
+            insert_implicit_multiplication_into(&mut tokens, &mut spare, custom_functions);
+            f(&tokens)
+        });
+
+        self.raw = recycle(raw);
+        self.tokens = recycle(tokens);
+        self.spare = recycle(spare);
+        result
+    }
+}
@@ -0,0 +1,129 @@
+//! Locating errors raised after parsing in the formula they came from.
+//!
+//! Differentiation and simplification rebuild every node, so no span survives on the
+//! nodes themselves. Names do: a function or variable keeps its interned name through
+//! every rewrite, so an error naming one is traced back to the first place the name
+//! appears in the source. Errors about the size of a result point at the whole formula.
+
+use super::lexer::{blank_comments, is_identifier_continue};
+use crate::core::{DiffError, SourceLocation, Span};
+use std::sync::Arc;
+
+/// Side table from a parsed formula back to its source text
+///
+/// Returned by [`parse_configured`](crate::parse_configured) with
+/// [`ParserOptions::source_map`](crate::ParserOptions::source_map) set; pass errors of
+/// later operations on the expression through [`locate`](Self::locate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap {
+    source: Arc<str>,
+}
+
+impl SourceMap {
+    /// Source map of `source`, as given to the parser
+    #[must_use]
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+
+    /// The formula this map points into
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Span of the first appearance of the function or variable `name`
+    ///
+    /// An identifier spelled exactly `name` wins; otherwise `name` is found inside a
+    /// longer run split by implicit multiplication (`x` in `xy`). Comments are skipped.
+    #[must_use]
+    pub fn span_of(&self, name: &str) -> Option<Span> {
+        let cleaned = blank_comments(&self.source).ok()?;
+        let runs = identifier_runs(&cleaned);
+        runs.iter()
+            .find(|(_, run)| *run == name)
+            .map(|&(start, _)| Span::new(start, start + name.len()))
+            .or_else(|| {
+                runs.iter().find_map(|&(start, run)| {
+                    let at = start + run.find(name)?;
+                    Some(Span::new(at, at + name.len()))
+                })
+            })
+    }
+
+    /// Span of the whole formula, without surrounding whitespace
+    fn whole(&self) -> Span {
+        let start = self.source.len() - self.source.trim_start().len();
+        Span::new(start, self.source.trim_end().len())
+    }
+
+    /// Attach the origin of `err` in the source, as [`DiffError::Located`]
+    ///
+    /// Errors naming a function or variable (`UnsupportedFunction`, `UnboundVariable`,
+    /// `NonDifferentiable`, `InvalidDataSeries`) point at its first appearance, and
+    /// exceeded limits (`MaxDepthExceeded`, `MaxNodesExceeded`, `DerivativeOrderLimit`,
+    /// `ExpansionTooLarge`, `StackOverflow`) at the whole formula. Other errors, errors
+    /// whose name is not in the source and errors already located are returned as is.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{CompiledEvaluator, ParserOptions, Simplify, parse_configured};
+    /// use std::collections::HashSet;
+    ///
+    /// let options = ParserOptions { source_map: true, ..ParserOptions::default() };
+    /// let parsed = parse_configured("a*x^2 + b", &HashSet::new(), &HashSet::new(), None, &options)?;
+    /// let (expr, map) = (parsed.expr, parsed.source_map.unwrap());
+    /// let expr = Simplify::new().simplify(&expr)?;
+    /// let err = CompiledEvaluator::compile(&expr, &["a", "b"], None).unwrap_err();
+    /// assert_eq!(
+    ///     map.locate(err).to_string(),
+    ///     "Unbound variable: x at column 3: a*x^2 + b\n                                   ^"
+    /// );
+    /// # Ok::<(), symb_anafis::DiffError>(())
+    /// ```
+    #[must_use]
+    pub fn locate(&self, err: DiffError) -> DiffError {
+        let span = match &err {
+            DiffError::UnsupportedFunction(name) | DiffError::UnboundVariable(name) => {
+                self.span_of(name.as_str())
+            }
+            DiffError::NonDifferentiable { function: name, .. }
+            | DiffError::InvalidDataSeries { name, .. } => self.span_of(name),
+            DiffError::MaxDepthExceeded
+            | DiffError::MaxNodesExceeded
+            | DiffError::DerivativeOrderLimit { .. }
+            | DiffError::ExpansionTooLarge { .. }
+            | DiffError::StackOverflow { .. } => Some(self.whole()),
+            _ => None,
+        };
+        match span {
+            Some(span) if span.is_valid() => DiffError::Located {
+                error: Box::new(err),
+                location: Box::new(SourceLocation::new(Arc::clone(&self.source), span)),
+            },
+            _ => err,
+        }
+    }
+}
+
+/// Every identifier in `input` with its byte offset
+fn identifier_runs(input: &str) -> Vec<(usize, &str)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (pos, c) in input.char_indices() {
+        match start {
+            None if c.is_alphabetic() || c == '_' => start = Some(pos),
+            Some(from) if !is_identifier_continue(c) => {
+                runs.push((from, input.get(from..pos).unwrap_or_default()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        runs.push((from, input.get(from..).unwrap_or_default()));
+    }
+    runs
+}
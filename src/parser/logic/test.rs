@@ -105,14 +105,14 @@ fn test_imul_identifier_function() {
 #[test]
 fn test_pratt_parse_number() {
     let tokens = vec![Token::Number(314.0 / 100.0)];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     assert_eq!(ast, Expr::number(314.0 / 100.0));
 }
 
 #[test]
 fn test_pratt_parse_symbol() {
     let tokens = vec![Token::Identifier("x".into())];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     assert_eq!(ast, Expr::symbol("x"));
 }
 
@@ -123,7 +123,7 @@ fn test_pratt_parse_addition() {
         Token::Operator(Operator::Add),
         Token::Number(2.0),
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     // 1 + 2 now combines like terms → 3
     assert!(matches!(ast.kind, ExprKind::Number(n) if (n - 3.0).abs() < 1e-10));
 }
@@ -135,7 +135,7 @@ fn test_pratt_parse_multiplication() {
         Token::Operator(Operator::Mul),
         Token::Number(2.0),
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     assert!(matches!(ast.kind, ExprKind::Product(_)));
 }
 
@@ -146,7 +146,7 @@ fn test_pratt_parse_power() {
         Token::Operator(Operator::Pow),
         Token::Number(2.0),
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     assert!(matches!(ast.kind, ExprKind::Pow(_, _)));
 }
 
@@ -158,7 +158,7 @@ fn test_pratt_parse_function() {
         Token::Identifier("x".into()),
         Token::RightParen,
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
     assert!(matches!(ast.kind, ExprKind::FunctionCall { .. }));
 }
 
@@ -172,7 +172,7 @@ fn test_pratt_precedence() {
         Token::Operator(Operator::Mul),
         Token::Number(3.0),
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
 
     // With like-term combination: Sum([6, x])
     match &ast.kind {
@@ -202,7 +202,7 @@ fn test_pratt_parentheses() {
         Token::Operator(Operator::Mul),
         Token::Number(2.0),
     ];
-    let ast = parse_expression(&tokens, None).unwrap();
+    let ast = parse_expression(&tokens, None, false).unwrap();
 
     // With n-ary Product: Product([Sum([x, 1]), 2])
     match &ast.kind {
@@ -223,7 +223,7 @@ fn test_pratt_parentheses() {
 fn test_pratt_empty_parentheses() {
     // () should be an error, NOT 1.0 or anything else
     let tokens = vec![Token::LeftParen, Token::RightParen];
-    let result = parse_expression(&tokens, None);
+    let result = parse_expression(&tokens, None, false);
     assert!(
         result.is_err(),
         "Empty parentheses should fail to parse, but got: {result:?}"
@@ -428,6 +428,13 @@ fn test_lexer_builtins_operator_sync() {
         "acot",
         "asec",
         "acsc",
+        // Trig in degrees
+        "sind",
+        "cosd",
+        "tand",
+        "asind",
+        "acosd",
+        "atand",
         // Hyperbolic
         "sinh",
         "cosh",
@@ -455,9 +462,18 @@ fn test_lexer_builtins_operator_sync() {
         "sinc",
         "abs",
         "signum",
+        "sign",
+        "sgn",
+        "heaviside",
+        "dirac",
+        "min",
+        "max",
+        "clamp",
+        "select",
         "floor",
         "ceil",
         "round",
+        "sum_data",
         "erf",
         "erfc",
         "gamma",
@@ -482,6 +498,7 @@ fn test_lexer_builtins_operator_sync() {
         "elliptic_k",
         "exp_polar",
         "atan2",
+        "hypot",
     ];
 
     for func_name in test_functions {
@@ -491,12 +508,10 @@ fn test_lexer_builtins_operator_sync() {
             "Operator::parse_str should recognize '{func_name}'"
         );
 
-        if func_name != "sen" && func_name != "sign" && func_name != "sgn" {
-            assert!(
-                builtin_set.contains(func_name),
-                "BUILTINS array missing function '{func_name}' - add it to the BUILTINS array in lexer.rs"
-            );
-        }
+        assert!(
+            builtin_set.contains(func_name),
+            "BUILTINS array missing function '{func_name}' - add it to the BUILTINS array in lexer.rs"
+        );
     }
 
     assert!(!builtin_set.contains("+"));
@@ -5,6 +5,23 @@
 use std::borrow::Cow;
 use std::str::FromStr;
 
+use crate::core::CompareOp;
+
+/// Name of the call syntax for unevaluated derivatives: `diff(f(x), x, n)`
+pub const DIFF_CALL: &str = "diff";
+
+/// Name of the call syntax for labeled groups: `group(rho*v*L/mu, Re)`
+pub const GROUP_CALL: &str = "group";
+
+/// Name of the call syntax for piecewise definitions: `piecewise(x < 0: -x, x)`
+pub const PIECEWISE_CALL: &str = "piecewise";
+
+/// Literal for positive infinity; read as a variable instead when declared as one
+pub const INF_LITERAL: &str = "inf";
+
+/// Literal for NaN; read as a variable instead when declared as one
+pub const NAN_LITERAL: &str = "nan";
+
 /// Token types produced by the lexer
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token<'src> {
@@ -20,6 +37,10 @@ pub enum Token<'src> {
     RightParen,
     /// Argument separator
     Comma,
+    /// Comparison inside a piecewise condition
+    Compare(CompareOp),
+    /// Separator between a piecewise condition and its value
+    Colon,
     /// Derivative notation like d^n f(x)/dx^n
     Derivative {
         /// Derivative order
@@ -43,6 +64,8 @@ impl Token<'_> {
             Self::LeftParen => "'('".to_owned(),
             Self::RightParen => "')'".to_owned(),
             Self::Comma => "','".to_owned(),
+            Self::Compare(op) => format!("'{op}'"),
+            Self::Colon => "':'".to_owned(),
             Self::Derivative {
                 func, var, order, ..
             } => {
@@ -90,6 +113,8 @@ pub enum Operator {
     Atan,
     /// Two-argument inverse tangent function
     Atan2,
+    /// Euclidean norm of two values
+    Hypot,
     /// Inverse cotangent function
     Acot,
     /// Inverse secant function
@@ -97,6 +122,20 @@ pub enum Operator {
     /// Inverse cosecant function
     Acsc,
 
+    // Trigonometric in degrees
+    /// Sine of an angle in degrees
+    Sind,
+    /// Cosine of an angle in degrees
+    Cosd,
+    /// Tangent of an angle in degrees
+    Tand,
+    /// Inverse sine in degrees
+    Asind,
+    /// Inverse cosine in degrees
+    Acosd,
+    /// Inverse tangent in degrees
+    Atand,
+
     // Logarithmic/Exponential
     /// Natural logarithm function
     Ln,
@@ -156,12 +195,26 @@ pub enum Operator {
     Abs,
     /// Signum function
     Signum,
+    /// Heaviside step function
+    Heaviside,
+    /// Dirac delta distribution
+    Dirac,
+    /// Smaller of two values
+    Min,
+    /// Larger of two values
+    Max,
+    /// Value limited to `[lo, hi]`
+    Clamp,
+    /// Branchless select on a 0/1 condition
+    Select,
     /// Floor function
     Floor,
     /// Ceiling function
     Ceil,
     /// Round function
     Round,
+    /// Sum over the points of declared data series
+    SumData,
 
     // Error & Probability (Tier 3)
     /// Error function
@@ -241,9 +294,16 @@ impl Operator {
             Self::Acos => "acos",
             Self::Atan => "atan",
             Self::Atan2 => "atan2",
+            Self::Hypot => "hypot",
             Self::Acot => "acot",
             Self::Asec => "asec",
             Self::Acsc => "acsc",
+            Self::Sind => "sind",
+            Self::Cosd => "cosd",
+            Self::Tand => "tand",
+            Self::Asind => "asind",
+            Self::Acosd => "acosd",
+            Self::Atand => "atand",
             Self::Ln => "ln",
             Self::Exp => "exp",
             Self::Sinh => "sinh",
@@ -267,9 +327,16 @@ impl Operator {
             Self::ExpPolar => "exp_polar",
             Self::Abs => "abs",
             Self::Signum => "signum",
+            Self::Heaviside => "heaviside",
+            Self::Dirac => "dirac",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Clamp => "clamp",
+            Self::Select => "select",
             Self::Floor => "floor",
             Self::Ceil => "ceil",
             Self::Round => "round",
+            Self::SumData => "sum_data",
             Self::Erf => "erf",
             Self::Erfc => "erfc",
             Self::Gamma => "gamma",
@@ -312,9 +379,16 @@ impl Operator {
             "acos" => Some(Self::Acos),
             "atan" => Some(Self::Atan),
             "atan2" => Some(Self::Atan2),
+            "hypot" => Some(Self::Hypot),
             "acot" => Some(Self::Acot),
             "asec" => Some(Self::Asec),
             "acsc" => Some(Self::Acsc),
+            "sind" => Some(Self::Sind),
+            "cosd" => Some(Self::Cosd),
+            "tand" => Some(Self::Tand),
+            "asind" => Some(Self::Asind),
+            "acosd" => Some(Self::Acosd),
+            "atand" => Some(Self::Atand),
             "ln" => Some(Self::Ln),
             "exp" => Some(Self::Exp),
             "sinh" => Some(Self::Sinh),
@@ -338,9 +412,16 @@ impl Operator {
             "exp_polar" => Some(Self::ExpPolar),
             "abs" => Some(Self::Abs),
             "sign" | "sgn" | "signum" => Some(Self::Signum),
+            "heaviside" => Some(Self::Heaviside),
+            "dirac" => Some(Self::Dirac),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "clamp" => Some(Self::Clamp),
+            "select" => Some(Self::Select),
             "floor" => Some(Self::Floor),
             "ceil" => Some(Self::Ceil),
             "round" => Some(Self::Round),
+            "sum_data" => Some(Self::SumData),
             "erf" => Some(Self::Erf),
             "erfc" => Some(Self::Erfc),
             "gamma" => Some(Self::Gamma),
@@ -395,9 +476,16 @@ impl Operator {
             | Self::Acos
             | Self::Atan
             | Self::Atan2
+            | Self::Hypot
             | Self::Acot
             | Self::Asec
             | Self::Acsc
+            | Self::Sind
+            | Self::Cosd
+            | Self::Tand
+            | Self::Asind
+            | Self::Acosd
+            | Self::Atand
             | Self::Ln
             | Self::Exp
             | Self::Log
@@ -421,9 +509,16 @@ impl Operator {
             | Self::Sinc
             | Self::Abs
             | Self::Signum
+            | Self::Heaviside
+            | Self::Dirac
+            | Self::Min
+            | Self::Max
+            | Self::Clamp
+            | Self::Select
             | Self::Floor
             | Self::Ceil
             | Self::Round
+            | Self::SumData
             | Self::Erf
             | Self::Erfc
             | Self::Gamma
@@ -459,6 +554,7 @@ impl Operator {
         match self {
             // Binary functions (require exactly 2 args)
             Self::Atan2
+            | Self::Hypot
             | Self::Polygamma
             | Self::Beta
             | Self::ZetaDeriv
@@ -466,10 +562,12 @@ impl Operator {
             | Self::BesselY
             | Self::BesselI
             | Self::BesselK
-            | Self::Hermite => 2,
+            | Self::Hermite
+            | Self::Min
+            | Self::Max => 2,
 
             // Ternary functions (require exactly 3 args)
-            Self::AssocLegendre => 3,
+            Self::AssocLegendre | Self::Clamp | Self::Select => 3,
 
             // Quaternary functions (require exactly 4 args)
             Self::Ynm => 4,
@@ -0,0 +1,246 @@
+//! `where` clauses: `expr where { name = expr; ... }` names subexpressions of `expr`.
+//!
+//! The clause is split off the source text before it is tokenized, so formulas without
+//! one parse exactly as before, and `where` stays an ordinary identifier unless a `{`
+//! follows it. Each definition is parsed on its own, so it may carry a clause of its
+//! own, and may use the other names of its clause in any order. Definitions are
+//! expanded into one another and then substituted into `expr`; a definition that
+//! reaches itself is a [`DiffError::CircularDefinition`].
+
+use super::lexer::is_identifier_continue;
+use super::program::{is_identifier, offset_error};
+use crate::core::{DiffError, Expr, Span};
+
+/// Keyword introducing the clause
+const KEYWORD: &str = "where";
+/// Separator between definitions
+const SEPARATOR: char = ';';
+/// Operator between a name and its definition
+const ASSIGNMENT: char = '=';
+
+/// One `name = body` definition, with byte offsets into the whole source
+struct Definition<'src> {
+    name: &'src str,
+    name_span: Span,
+    body: &'src str,
+    body_offset: usize,
+}
+
+/// A formula split at its `where` clause
+pub struct WhereClause<'src> {
+    /// The expression the clause applies to
+    expr: &'src str,
+    definitions: Vec<Definition<'src>>,
+}
+
+/// Nesting change of a bracket character
+const fn depth_change(c: char) -> isize {
+    match c {
+        '(' | '[' | '{' => 1,
+        ')' | ']' | '}' => -1,
+        _ => 0,
+    }
+}
+
+/// Byte offset of the `where` keyword of a top-level clause, and of its opening brace
+fn find_keyword(source: &str) -> Option<(usize, usize)> {
+    let mut depth = 0_isize;
+    let mut previous = None;
+    for (pos, c) in source.char_indices() {
+        depth += depth_change(c);
+        let starts_word = !previous.is_some_and(is_identifier_continue);
+        previous = Some(c);
+        if depth != 0 || !starts_word {
+            continue;
+        }
+        let Some(after) = source
+            .get(pos..)
+            .and_then(|rest| rest.strip_prefix(KEYWORD))
+        else {
+            continue;
+        };
+        if after.starts_with(is_identifier_continue) {
+            continue;
+        }
+        let gap = after.len() - after.trim_start().len();
+        if after.trim_start().starts_with('{') {
+            return Some((pos, pos + KEYWORD.len() + gap));
+        }
+    }
+    None
+}
+
+/// Byte offset of the brace closing the one at `open`
+fn matching_brace(source: &str, open: usize) -> Option<usize> {
+    let mut depth = 0_isize;
+    for (pos, c) in source.get(open..)?.char_indices() {
+        depth += depth_change(c);
+        if depth == 0 {
+            return Some(open + pos);
+        }
+    }
+    None
+}
+
+/// Split `block` at separators outside brackets, with the offset of each piece
+fn statements(block: &str, offset: usize) -> Vec<(&str, usize)> {
+    let mut pieces = Vec::new();
+    let (mut depth, mut start) = (0_isize, 0);
+    for (pos, c) in block.char_indices() {
+        depth += depth_change(c);
+        if depth == 0 && c == SEPARATOR {
+            pieces.push((block.get(start..pos).unwrap_or_default(), offset + start));
+            start = pos + SEPARATOR.len_utf8();
+        }
+    }
+    pieces.push((block.get(start..).unwrap_or_default(), offset + start));
+    pieces
+}
+
+impl<'src> WhereClause<'src> {
+    /// Split `source` at a `where` clause outside any bracket, if it has one
+    ///
+    /// # Errors
+    /// Returns `InvalidSyntax` for an unclosed clause, input after it, a clause with no
+    /// expression before it, and definitions that are not `name = expr` or repeat a name.
+    pub fn split(source: &'src str) -> Result<Option<Self>, DiffError> {
+        let Some((keyword, open)) = find_keyword(source) else {
+            return Ok(None);
+        };
+        let keyword_span = Span::new(keyword, keyword + KEYWORD.len());
+        let close = matching_brace(source, open).ok_or_else(|| {
+            DiffError::invalid_syntax_at("'where' clause is never closed", Span::at(open))
+        })?;
+        let trailing = source.get(close + 1..).unwrap_or_default();
+        if !trailing.trim().is_empty() {
+            let start = close + 1 + trailing.len() - trailing.trim_start().len();
+            return Err(DiffError::invalid_syntax_at(
+                "unexpected input after 'where' clause",
+                Span::new(start, source.trim_end().len()),
+            ));
+        }
+        let expr = source.get(..keyword).unwrap_or_default();
+        if expr.trim().is_empty() {
+            return Err(DiffError::invalid_syntax_at(
+                "'where' clause without an expression",
+                keyword_span,
+            ));
+        }
+
+        let block = source.get(open + 1..close).unwrap_or_default();
+        let mut definitions: Vec<Definition<'src>> = Vec::new();
+        for (statement, offset) in statements(block, open + 1) {
+            if statement.trim().is_empty() {
+                continue;
+            }
+            let Some((lhs, body)) = statement.split_once(ASSIGNMENT) else {
+                return Err(DiffError::invalid_syntax_at(
+                    "expected 'name = expression' in 'where' clause",
+                    Span::new(offset, offset + statement.len()),
+                ));
+            };
+            let name = lhs.trim();
+            let name_start = offset + lhs.find(name).unwrap_or(0);
+            let name_span = Span::new(name_start, name_start + name.len());
+            if !is_identifier(name) {
+                return Err(DiffError::invalid_syntax_at(
+                    format!("cannot assign to '{name}'"),
+                    name_span,
+                ));
+            }
+            if definitions.iter().any(|d| d.name == name) {
+                return Err(DiffError::invalid_syntax_at(
+                    format!("'{name}' is already defined"),
+                    name_span,
+                ));
+            }
+            if body.trim().is_empty() {
+                return Err(DiffError::invalid_syntax_at(
+                    format!("'{name}' has no definition"),
+                    name_span,
+                ));
+            }
+            definitions.push(Definition {
+                name,
+                name_span,
+                body,
+                body_offset: offset + lhs.len() + ASSIGNMENT.len_utf8(),
+            });
+        }
+        Ok(Some(Self { expr, definitions }))
+    }
+
+    /// The names the clause defines, with the span of each
+    pub fn names(&self) -> impl Iterator<Item = (&'src str, Span)> + '_ {
+        self.definitions.iter().map(|d| (d.name, d.name_span))
+    }
+
+    /// Parse the expression and definitions with `parse` and substitute the definitions
+    ///
+    /// `parse` gets each piece of source on its own; errors it returns are shifted to
+    /// point into the whole source.
+    ///
+    /// # Errors
+    /// Returns errors from `parse`, and [`DiffError::CircularDefinition`] when a
+    /// definition depends on itself.
+    pub fn resolve(
+        &self,
+        mut parse: impl FnMut(&str) -> Result<Expr, DiffError>,
+    ) -> Result<Expr, DiffError> {
+        let parsed = self
+            .definitions
+            .iter()
+            .map(|d| parse(d.body).map_err(|err| offset_error(err, d.body_offset)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut cache = vec![None; parsed.len()];
+        let expanded = (0..parsed.len())
+            .map(|index| self.expand(index, &parsed, &mut cache, &mut Vec::new()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let expr = parse(self.expr)?;
+        Ok(self
+            .definitions
+            .iter()
+            .zip(&expanded)
+            .fold(expr, |acc, (d, definition)| {
+                acc.substitute(d.name, definition)
+            }))
+    }
+
+    /// Definition `index` with the other definitions it uses substituted, depth first
+    ///
+    /// `path` holds the definitions being expanded above this one.
+    fn expand(
+        &self,
+        index: usize,
+        parsed: &[Expr],
+        expanded: &mut [Option<Expr>],
+        path: &mut Vec<usize>,
+    ) -> Result<Expr, DiffError> {
+        if let Some(done) = &expanded[index] {
+            return Ok(done.clone());
+        }
+        if let Some(start) = path.iter().position(|&on_path| on_path == index) {
+            let cycle = path[start..]
+                .iter()
+                .chain([&index])
+                .map(|&i| self.definitions[i].name.to_owned())
+                .collect();
+            return Err(DiffError::CircularDefinition {
+                cycle,
+                span: Some(self.definitions[index].name_span),
+            });
+        }
+        path.push(index);
+        let used = parsed[index].variables();
+        let mut definition = parsed[index].clone();
+        for (other, d) in self.definitions.iter().enumerate() {
+            if used.contains(d.name) {
+                let inner = self.expand(other, parsed, expanded, path)?;
+                definition = definition.substitute(d.name, &inner);
+            }
+        }
+        path.pop();
+        expanded[index] = Some(definition.clone());
+        Ok(definition)
+    }
+}
@@ -7,3 +7,4 @@ mod api;
 mod logic;
 
 pub use api::*;
+pub use logic::is_builtin;
@@ -1,29 +1,65 @@
-use crate::core::{BodyFn, Context, UserFunction};
+use crate::core::{BodyFn, Context, InverseCaveat, SymbolScope, UserFunction};
 use crate::core::{DiffError, Expr};
 use crate::evaluator::ToParamName;
-use crate::parser::parse;
+use crate::parser::{ParserOptions, SourceMap, parse_configured};
 use crate::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_NODES};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::{HashMap, HashSet};
 use std::string::ToString;
 use std::sync::Arc;
 
-use super::logic::{Simplifier, prettify_roots};
+pub use super::logic::simplify_holding_groups;
+pub use super::logic::{RuleCategory, RuleUsage, RuleUsageReport, UserRule, rationalize_decimals};
+use super::logic::{
+    RuleRegistry, Simplifier, expand_trig_angles, global_registry, prettify_roots,
+    rationalize_denominators,
+};
 /// Type alias for custom body function map (symbolic expansion).
 use crate::core::symb_interned;
 /// Uses std `HashMap` at the API boundary for caller convenience;
 /// converted to `FxHashMap` internally by the engine.
 pub type CustomBodyMap = HashMap<u64, BodyFn>;
 
+/// Default number of rewrites allowed at one node position during a simplification.
+///
+/// Terminating rule chains rewrite a node a handful of times; the budget only trips when
+/// rules keep bouncing a subtree between equivalent forms, bounding that work to
+/// O(budget · nodes).
+pub const DEFAULT_NODE_REWRITE_BUDGET: usize = 64;
+
+/// Name under which [`Simplify::list_rules`] reports the final pass that writes
+/// fractional powers as roots (`x^(1/2)` as `sqrt(x)`).
+const PRETTIFY_ROOTS: &str = "prettify_roots";
+
 /// Builder for simplification operations.
 #[derive(Clone, Default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Each flag is an independent builder switch"
+)]
 pub struct Simplify {
     domain_safe: bool,
+    strict_ieee: bool,
+    rationalize: bool,
+    expand_trig: bool,
+    expand_constants: bool,
+    exact_arithmetic: bool,
+    preserve_groups: bool,
+    parser_options: ParserOptions,
     user_fns: FxHashMap<String, UserFunction>,
     max_depth: Option<usize>,
     max_nodes: Option<usize>,
+    node_budget: Option<usize>,
     context: Option<Context>,
+    scope: Option<SymbolScope>,
     known_symbols: HashSet<String>,
+    integer_vars: FxHashSet<u64>,
+    positive_vars: FxHashSet<u64>,
+    non_negative_vars: FxHashSet<u64>,
+    disabled_rules: Vec<String>,
+    disabled_categories: FxHashSet<RuleCategory>,
+    only_categories: Option<FxHashSet<RuleCategory>>,
+    user_rules: Vec<Arc<dyn UserRule>>,
 }
 
 impl Simplify {
@@ -41,6 +77,75 @@ impl Simplify {
         self
     }
 
+    #[inline]
+    #[must_use]
+    #[doc = "Fold indeterminate forms such as `inf - inf` to NaN instead of leaving them symbolic."]
+    pub const fn strict_ieee(mut self, strict: bool) -> Self {
+        self.strict_ieee = strict;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Rationalize denominators containing square roots: `1/sqrt(x)` becomes \
+             `sqrt(x)/x` and `1/(sqrt(3) - 1)` becomes `(1 + sqrt(3))/2`."]
+    pub const fn rationalize(mut self, rationalize: bool) -> Self {
+        self.rationalize = rationalize;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Expand `sin` and `cos` of sums and double angles in the result: `sin(x + y)` \
+             becomes `sin(x)*cos(y) + cos(x)*sin(y)` and `cos(2*x)` becomes \
+             `cos(x)^2 - sin(x)^2`. The simplifier folds these forms, so the expansion \
+             runs once after it."]
+    pub const fn expand_trig(mut self, expand: bool) -> Self {
+        self.expand_trig = expand;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Replace the defined constants of the context by their definitions before \
+             simplifying, e.g. `hbar` by `h/(2*pi)` after \
+             [`Context::load_physics_constants`]."]
+    pub const fn expand_constants(mut self, expand: bool) -> Self {
+        self.expand_constants = expand;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Keep numeric folding exact: decimal literals are read as the fractions they \
+             were written as (`0.1` is `1/10`) and results without an exact form, such as \
+             `3^(-1)` as a float, stay fractions. Values become `f64` only when evaluated."]
+    pub const fn exact_arithmetic(mut self, exact: bool) -> Self {
+        self.exact_arithmetic = exact;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Hold labeled subexpressions atomic, such as `group(rho*v*L/mu, Re)` or \
+             [`Expr::labeled`]: each is simplified on its own, but no rule merges, \
+             cancels or distributes across its boundary."]
+    pub const fn preserve_groups(mut self, preserve: bool) -> Self {
+        self.preserve_groups = preserve;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Accept decimal commas or Unicode operators in formulas passed as strings, \
+             see [`parse_configured`](crate::parse_configured). Only the notation settings \
+             apply: exactness and the scope come from [`Simplify::exact_arithmetic`] and \
+             [`Simplify::scope`]."]
+    pub fn parser_options(mut self, options: ParserOptions) -> Self {
+        self.parser_options = options;
+        self
+    }
+
     #[inline]
     #[must_use]
     #[doc = "Set the Context for parsing and simplification."]
@@ -49,6 +154,16 @@ impl Simplify {
         self
     }
 
+    #[inline]
+    #[must_use]
+    #[doc = "Parse string formulas in `scope`, and refuse expressions with symbols it cannot \
+             tell apart from its own with [`DiffError::ScopeMismatch`] (see \
+             [`SymbolScope::check`])."]
+    pub fn scope(mut self, scope: &SymbolScope) -> Self {
+        self.scope = Some(scope.clone());
+        self
+    }
+
     #[must_use]
     #[doc = "Register a user-defined function with body and/or partial derivatives."]
     pub fn user_fn(mut self, name: impl Into<String>, def: UserFunction) -> Self {
@@ -72,6 +187,15 @@ impl Simplify {
         self
     }
 
+    #[inline]
+    #[must_use]
+    #[doc = "Set how many times one node position may be rewritten per simplification \
+             (default [`DEFAULT_NODE_REWRITE_BUDGET`])."]
+    pub const fn node_budget(mut self, budget: usize) -> Self {
+        self.node_budget = Some(budget);
+        self
+    }
+
     #[inline]
     #[must_use]
     #[doc = "Register a variable as constant during simplification."]
@@ -92,6 +216,196 @@ impl Simplify {
         self
     }
 
+    #[inline]
+    #[must_use]
+    #[doc = "Assume a variable takes integer values, so `sin(x + 2*pi*n)` reduces to \
+             `sin(x)`."]
+    pub fn integer_var<P: ToParamName>(mut self, var: &P) -> Self {
+        let (id, _) = var.to_param_id_and_name();
+        self.integer_vars.insert(id);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Assume several variables take integer values (see [`integer_var`](Self::integer_var))."]
+    pub fn integer_vars<P: ToParamName>(mut self, vars: &[P]) -> Self {
+        for var in vars {
+            let (id, _) = var.to_param_id_and_name();
+            self.integer_vars.insert(id);
+        }
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Assume these variables are strictly positive, so that `sqrt(x^2)` reduces to \
+             `x` and, under [`domain_safe`](Self::domain_safe), `x/x` to `1` and \
+             `exp(ln(x))` to `x`."]
+    pub fn assume_positive<P: ToParamName>(mut self, vars: &[P]) -> Self {
+        for var in vars {
+            let (id, _) = var.to_param_id_and_name();
+            self.positive_vars.insert(id);
+        }
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    #[doc = "Assume these variables are zero or positive, so that `abs(x)` reduces to `x` \
+             and, under [`domain_safe`](Self::domain_safe), `sqrt(x)*sqrt(y)` to \
+             `sqrt(x*y)`."]
+    pub fn assume_non_negative<P: ToParamName>(mut self, vars: &[P]) -> Self {
+        for var in vars {
+            let (id, _) = var.to_param_id_and_name();
+            self.non_negative_vars.insert(id);
+        }
+        self
+    }
+
+    #[must_use]
+    #[doc = "Skip the rule with this name (see [`list_rules`](Self::list_rules)). \
+             An unknown name makes [`simplify`](Self::simplify) fail with \
+             [`DiffError::UnknownRule`]."]
+    pub fn disable_rule(mut self, name: impl Into<String>) -> Self {
+        self.disabled_rules.push(name.into());
+        self
+    }
+
+    #[must_use]
+    #[doc = "Skip every rule of a category."]
+    pub fn disable_category(mut self, category: RuleCategory) -> Self {
+        self.disabled_categories.insert(category);
+        self
+    }
+
+    #[must_use]
+    #[doc = "Apply only the rules of these categories; disabled rules and categories \
+             stay disabled."]
+    pub fn only_categories(mut self, categories: &[RuleCategory]) -> Self {
+        self.only_categories = Some(categories.iter().copied().collect());
+        self
+    }
+
+    #[must_use]
+    #[doc = "Add a rule of your own, tried with the built-in rules by priority. Its name \
+             must not be taken by another rule, or [`simplify`](Self::simplify) fails with \
+             [`DiffError::DuplicateRule`]; disabling rules and categories applies to it too."]
+    pub fn with_rule(mut self, rule: Box<dyn UserRule>) -> Self {
+        self.user_rules.push(Arc::from(rule));
+        self
+    }
+
+    #[must_use]
+    #[doc = "Skip every rule of a category; the same as \
+             [`disable_category`](Self::disable_category)."]
+    pub fn without_category(self, category: RuleCategory) -> Self {
+        self.disable_category(category)
+    }
+
+    #[must_use]
+    #[doc = "Add several rules of your own at once, as with [`with_rule`](Self::with_rule). \
+             Each is tried by its priority, so one above a built-in rule preempts it."]
+    pub fn with_extra_rules(mut self, rules: Vec<Box<dyn UserRule>>) -> Self {
+        self.user_rules.extend(rules.into_iter().map(Arc::from));
+        self
+    }
+
+    /// Every simplification rule as `(name, category, priority, alters_domain)`, in the
+    /// order they are tried (highest priority first)
+    ///
+    /// Rules that alter the domain are skipped under [`domain_safe`](Self::domain_safe).
+    /// The last entry, `prettify_roots`, is the pass that writes fractional powers as
+    /// roots once the rules are done; its priority is `i32::MIN`.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{RuleCategory, Simplify};
+    /// let rules = Simplify::list_rules();
+    /// assert!(rules.iter().any(|&(name, category, _, _)| {
+    ///     name == "sqrt_power" && category == RuleCategory::Root
+    /// }));
+    /// ```
+    #[must_use]
+    pub fn list_rules() -> Vec<(&'static str, RuleCategory, i32, bool)> {
+        global_registry()
+            .rules
+            .iter()
+            .map(|rule| {
+                (
+                    rule.name(),
+                    rule.category(),
+                    rule.priority(),
+                    rule.alters_domain(),
+                )
+            })
+            .chain([(PRETTIFY_ROOTS, RuleCategory::Root, i32::MIN, false)])
+            .collect()
+    }
+
+    /// Whether the rule selection keeps the rule `name` of `category`
+    fn keeps_rule(&self, name: &str, category: RuleCategory) -> bool {
+        !self.disabled_categories.contains(&category)
+            && self
+                .only_categories
+                .as_ref()
+                .is_none_or(|only| only.contains(&category))
+            && !self.disabled_rules.iter().any(|disabled| disabled == name)
+    }
+
+    /// The registry restricted by the rule selection and extended by the user rules, or
+    /// `None` when it would be the global one
+    fn rule_registry(&self) -> Result<Option<Arc<RuleRegistry>>, DiffError> {
+        if self.disabled_rules.is_empty()
+            && self.disabled_categories.is_empty()
+            && self.only_categories.is_none()
+            && self.user_rules.is_empty()
+        {
+            return Ok(None);
+        }
+        let registry = global_registry();
+        let mut names: FxHashSet<&str> = registry.rules.iter().map(|rule| rule.name()).collect();
+        names.insert(PRETTIFY_ROOTS);
+        for rule in &self.user_rules {
+            if !names.insert(rule.name()) {
+                return Err(DiffError::DuplicateRule {
+                    name: rule.name().to_owned(),
+                });
+            }
+        }
+        if let Some(unknown) = self
+            .disabled_rules
+            .iter()
+            .find(|name| !names.contains(name.as_str()))
+        {
+            return Err(DiffError::UnknownRule {
+                name: unknown.clone(),
+            });
+        }
+        let user_rules: Vec<Arc<dyn UserRule>> = self
+            .user_rules
+            .iter()
+            .filter(|rule| self.keeps_rule(rule.name(), rule.category()))
+            .cloned()
+            .collect();
+        Ok(Some(Arc::new(
+            registry
+                .filtered(|rule| self.keeps_rule(rule.name(), rule.category()))
+                .with_user_rules(&user_rules),
+        )))
+    }
+
+    /// Parser settings for string formulas
+    fn string_parser_options(&self) -> ParserOptions {
+        ParserOptions {
+            exact: self.exact_arithmetic,
+            scope: self.scope.clone(),
+            warnings: false,
+            source_map: false,
+            ..self.parser_options.clone()
+        }
+    }
+
     fn custom_function_names(&self) -> HashSet<String> {
         self.user_fns.keys().cloned().collect()
     }
@@ -108,9 +422,68 @@ impl Simplify {
             .collect()
     }
 
+    fn build_inverses_map(&self) -> FxHashMap<(u64, u64), InverseCaveat> {
+        let mut inverses = FxHashMap::default();
+        for (name, func) in &self.user_fns {
+            add_inverse_pairs(&mut inverses, symb_interned(name).id(), func);
+        }
+        inverses
+    }
+
     /// # Errors
-    /// Returns `DiffError` if expression limits are exceeded.
+    /// Returns `DiffError` if expression limits are exceeded, or
+    /// [`DiffError::UnknownRule`] if a disabled rule does not exist, or
+    /// [`DiffError::DuplicateRule`] if a user rule reuses a rule name, or
+    /// [`DiffError::ScopeMismatch`] if a [scope](Self::scope) is set and `expr` has a
+    /// symbol foreign to it.
     pub fn simplify(&self, expr: &Expr) -> Result<Expr, DiffError> {
+        self.run(expr, None)
+    }
+
+    /// Fully expand `expr`, then simplify it with this builder, keeping the expanded form
+    ///
+    /// [`Expr::expand`] distributes every product and integer power; simplification then
+    /// applies this builder's rules to the expanded terms, and anything it factors back
+    /// (such as a perfect square) is expanded again. With [`max_nodes`](Self::max_nodes)
+    /// set, it also bounds the number of terms of every sum during expansion.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Simplify, symb};
+    ///
+    /// let x = symb("simplify_expand_doc_x");
+    /// let expanded = Simplify::new().expand(&(x + 1.0).pow(2.0)).unwrap();
+    /// assert_eq!(expanded.to_string(), "1 + 2*simplify_expand_doc_x + simplify_expand_doc_x^2");
+    /// ```
+    ///
+    /// # Errors
+    /// As [`simplify`](Self::simplify), and [`DiffError::ExpansionTooLarge`] when an
+    /// expanded sum exceeds the node limit.
+    pub fn expand(&self, expr: &Expr) -> Result<Expr, DiffError> {
+        let limit = self.max_nodes.unwrap_or(usize::MAX);
+        let simplified = self.simplify(&expr.expand_with_limit(limit)?)?;
+        simplified.expand_with_limit(limit)
+    }
+
+    /// [`simplify`](Self::simplify), tallying into `report` how often each rule fired
+    ///
+    /// Run it over a corpus with one report to find the rules that never fire there.
+    ///
+    /// # Errors
+    /// As [`simplify`](Self::simplify).
+    pub fn simplify_recording(
+        &self,
+        expr: &Expr,
+        report: &mut RuleUsageReport,
+    ) -> Result<Expr, DiffError> {
+        self.run(expr, Some(report))
+    }
+
+    fn run(&self, expr: &Expr, report: Option<&mut RuleUsageReport>) -> Result<Expr, DiffError> {
+        let registry = self.rule_registry()?;
+        if let Some(scope) = &self.scope {
+            scope.check(expr)?;
+        }
         if let Some(max_d) = self.max_depth
             && expr.max_depth() > max_d
         {
@@ -122,15 +495,64 @@ impl Simplify {
             return Err(DiffError::MaxNodesExceeded);
         }
 
-        Ok(simplify_expr(
-            expr.clone(),
-            self.known_symbols.clone(),
+        let mut simplifier = configure_simplifier(
             self.build_bodies_map(),
+            self.build_inverses_map(),
             self.max_depth,
             None,
-            None,
+            self.context.as_ref(),
             self.domain_safe,
-        ))
+        );
+        if let Some(budget) = self.node_budget {
+            simplifier = simplifier.with_node_budget(budget);
+        }
+        if let Some(registry) = registry {
+            simplifier = simplifier.with_registry(registry);
+        }
+        simplifier = simplifier
+            .with_strict_ieee(self.strict_ieee)
+            .with_exact_arithmetic(self.exact_arithmetic)
+            .with_integer_vars(self.integer_vars.clone())
+            .with_positive_vars(self.positive_vars.clone())
+            .with_non_negative_vars(self.non_negative_vars.clone());
+        if report.is_some() {
+            simplifier = simplifier.with_usage_recording();
+        }
+        let expr = match &self.context {
+            Some(ctx) if self.expand_constants => expr.substitute_many(&ctx.constant_definitions()),
+            _ => expr.clone(),
+        };
+        let mut result = if self.preserve_groups {
+            simplify_holding_groups(expr, &mut |held| simplifier.simplify(held))
+        } else {
+            simplifier.simplify(expr)
+        };
+        let mut usage = simplifier.take_usage();
+        if self.keeps_rule(PRETTIFY_ROOTS, RuleCategory::Root) {
+            let pretty = prettify_roots(result.clone());
+            if report.is_some() && pretty != result {
+                usage
+                    .entry(PRETTIFY_ROOTS)
+                    .or_default()
+                    .record(&result, &pretty);
+            }
+            result = pretty;
+        }
+        if let Some(report) = report {
+            report.absorb(usage, |name| {
+                self.user_rules
+                    .iter()
+                    .find(|rule| rule.name() == name)
+                    .map_or(RuleCategory::Algebraic, |rule| rule.category())
+            });
+        }
+        if self.expand_trig {
+            result = expand_trig_angles(&result);
+        }
+        if self.rationalize {
+            return Ok(rationalize_denominators(&result));
+        }
+        Ok(result)
     }
 
     /// # Errors
@@ -153,8 +575,17 @@ impl Simplify {
             }
         }
 
-        let ast = parse(formula, &symbols, &custom_functions, self.context.as_ref())?;
-        let result = self.simplify(&ast)?;
+        let ast = parse_configured(
+            formula,
+            &symbols,
+            &custom_functions,
+            self.context.as_ref(),
+            &self.string_parser_options(),
+        )?
+        .expr;
+        let result = self
+            .simplify(&ast)
+            .map_err(|err| SourceMap::new(formula).locate(err))?;
         Ok(format!("{result}"))
     }
 }
@@ -162,23 +593,80 @@ impl Simplify {
 pub fn simplify_expr(
     expr: Expr,
     _known_symbols: HashSet<String>,
-    mut custom_bodies: CustomBodyMap,
+    custom_bodies: CustomBodyMap,
     max_depth: Option<usize>,
     max_iterations: Option<usize>,
     context: Option<&Context>,
     domain_safe: bool,
 ) -> Expr {
+    let mut simplifier = configure_simplifier(
+        custom_bodies,
+        FxHashMap::default(),
+        max_depth,
+        max_iterations,
+        context,
+        domain_safe,
+    );
+    prettify_roots(simplifier.simplify(expr))
+}
+
+/// [`simplify_expr`] with exact rational arithmetic (see [`Simplify::exact_arithmetic`]).
+pub fn simplify_expr_exact(
+    expr: Expr,
+    custom_bodies: CustomBodyMap,
+    max_depth: Option<usize>,
+    context: Option<&Context>,
+    domain_safe: bool,
+) -> Expr {
+    let mut simplifier = configure_simplifier(
+        custom_bodies,
+        FxHashMap::default(),
+        max_depth,
+        None,
+        context,
+        domain_safe,
+    )
+    .with_exact_arithmetic(true);
+    prettify_roots(simplifier.simplify(expr))
+}
+
+/// Record both directions of the inverse pair declared on the function with id `id`.
+fn add_inverse_pairs(
+    inverses: &mut FxHashMap<(u64, u64), InverseCaveat>,
+    id: u64,
+    func: &UserFunction,
+) {
+    if let Some((inverse, caveat)) = &func.inverse {
+        let inverse_id = symb_interned(inverse).id();
+        inverses.insert((id, inverse_id), *caveat);
+        inverses.insert((inverse_id, id), *caveat);
+    }
+}
+
+/// Build a simplifier with the given custom bodies, inverse pairs and limits.
+fn configure_simplifier(
+    mut custom_bodies: CustomBodyMap,
+    mut custom_inverses: FxHashMap<(u64, u64), InverseCaveat>,
+    max_depth: Option<usize>,
+    max_iterations: Option<usize>,
+    context: Option<&Context>,
+    domain_safe: bool,
+) -> Simplifier {
     if let Some(ctx) = context {
         for id in ctx.fn_name_to_id().values() {
-            if let Some(body) = ctx.get_body_by_id(*id) {
-                custom_bodies.insert(*id, Arc::clone(&body));
+            if let Some(func) = ctx.get_user_fn_by_id(*id) {
+                if let Some(body) = &func.body {
+                    custom_bodies.insert(*id, Arc::clone(body));
+                }
+                add_inverse_pairs(&mut custom_inverses, *id, &func);
             }
         }
     }
 
     let mut simplifier = Simplifier::new()
         .with_domain_safe(domain_safe)
-        .with_custom_bodies(custom_bodies);
+        .with_custom_bodies(custom_bodies)
+        .with_custom_inverses(custom_inverses);
 
     if let Some(depth) = max_depth {
         simplifier = simplifier.with_max_depth(depth);
@@ -186,10 +674,7 @@ pub fn simplify_expr(
     if let Some(iters) = max_iterations {
         simplifier = simplifier.with_max_iterations(iters);
     }
-
-    let mut current = simplifier.simplify(expr);
-    current = prettify_roots(current);
-    current
+    simplifier
 }
 
 /// Simplify a mathematical expression
@@ -283,6 +768,9 @@ pub fn simplify_expr(
 /// - **Max depth**: 100 (default)
 /// - **Max nodes**: 10,000 (default)
 /// - **Max iterations**: 1000 simplification passes
+/// - **Node rewrite budget**: each node position is rewritten at most
+///   [`DEFAULT_NODE_REWRITE_BUDGET`] times, which cuts off rules that keep bouncing
+///   a subtree between equivalent forms
 ///
 /// For complex expressions, use the [`Simplify`] builder:
 /// ```
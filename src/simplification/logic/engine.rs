@@ -1,10 +1,12 @@
 //!
 //! Implements bottom-up tree traversal, rule application with memoization,
-//! cycle detection, and configurable limits (iterations, depth).
+//! cycle detection, and configurable limits (iterations, depth, per-node rewrite budget).
 
 use super::rules::{RuleContext, RuleExprKind, RuleRegistry};
-use crate::core::BodyFn;
-use crate::core::{Expr, ExprKind};
+use super::usage::RuleTally;
+use crate::core::{BodyFn, InverseCaveat};
+use crate::core::{Expr, ExprKind, map_piecewise, piecewise_parts};
+use crate::simplification::DEFAULT_NODE_REWRITE_BUDGET;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::HashMap;
 use std::env::var;
@@ -146,6 +148,15 @@ macro_rules! trace_log {
 /// eviction strategy means we never pay a full cold-start penalty anyway.
 const DEFAULT_CACHE_CAPACITY: usize = 100_000;
 
+/// Number of offending (rule, node) pairs reported when a rewrite budget trips.
+const BUDGET_REPORT_LEN: usize = 5;
+
+/// Position of the `index`-th child of the node at `parent` (FNV-style path hash).
+#[inline]
+const fn child_position(parent: u64, index: usize) -> u64 {
+    (parent ^ (index as u64 + 1)).wrapping_mul(0x0100_0000_01b3)
+}
+
 /// Check if tracing is enabled via environment variable (cached)
 fn trace_enabled() -> bool {
     static TRACE: OnceLock<bool> = OnceLock::new();
@@ -181,6 +192,16 @@ pub struct Simplifier {
     /// Deferred drop queue — intermediate expressions are collected here and
     /// freed in a batch between iterations to improve deallocation locality.
     drop_queue: Vec<Arc<Expr>>,
+    /// Maximum rewrites per node position over one `simplify` call
+    node_budget: usize,
+    /// Rewrites so far, keyed by node position (path hash from the root)
+    node_rewrites: FxHashMap<u64, usize>,
+    /// Rewrites per (rule, node hash), collected only when tracing is enabled
+    rule_hits: FxHashMap<(&'static str, u64), usize>,
+    /// Restricted rule set, or `None` for the global registry
+    registry: Option<Arc<RuleRegistry>>,
+    /// Rewrites per rule, collected only when usage recording is on
+    usage: Option<FxHashMap<&'static str, RuleTally>>,
 }
 
 impl Default for Simplifier {
@@ -201,6 +222,11 @@ impl Simplifier {
             context: RuleContext::default(),
             domain_safe: false,
             drop_queue: Vec::new(),
+            node_budget: DEFAULT_NODE_REWRITE_BUDGET,
+            node_rewrites: FxHashMap::default(),
+            rule_hits: FxHashMap::default(),
+            registry: None,
+            usage: None,
         }
     }
 
@@ -216,12 +242,31 @@ impl Simplifier {
         self
     }
 
+    /// Sets how many times a single node position may be rewritten during one
+    /// simplification. Once spent, the node is left as it is for the remaining passes.
+    pub const fn with_node_budget(mut self, node_budget: usize) -> Self {
+        self.node_budget = node_budget;
+        self
+    }
+
     /// Enables or disables domain-safe transformations.
     pub const fn with_domain_safe(mut self, domain_safe: bool) -> Self {
         self.domain_safe = domain_safe;
         self
     }
 
+    /// Enables or disables folding of indeterminate forms such as `inf - inf` to NaN.
+    pub const fn with_strict_ieee(mut self, strict_ieee: bool) -> Self {
+        self.context.strict_ieee = strict_ieee;
+        self
+    }
+
+    /// Enables or disables exact rational arithmetic in numeric folding.
+    pub const fn with_exact_arithmetic(mut self, exact_arithmetic: bool) -> Self {
+        self.context.exact_arithmetic = exact_arithmetic;
+        self
+    }
+
     /// Sets custom function bodies.
     pub fn with_custom_bodies(mut self, custom_bodies: HashMap<u64, BodyFn>) -> Self {
         let fx_map: FxHashMap<u64, _> = custom_bodies.into_iter().collect();
@@ -229,6 +274,51 @@ impl Simplifier {
         self
     }
 
+    /// Sets custom inverse function pairs, keyed by `(outer id, inner id)`.
+    pub fn with_custom_inverses(
+        mut self,
+        custom_inverses: FxHashMap<(u64, u64), InverseCaveat>,
+    ) -> Self {
+        self.context = self.context.with_custom_inverses(custom_inverses);
+        self
+    }
+
+    /// Sets the symbols (by id) assumed to take integer values.
+    pub fn with_integer_vars(mut self, integer_vars: FxHashSet<u64>) -> Self {
+        self.context = self.context.with_integer_vars(integer_vars);
+        self
+    }
+
+    /// Sets the symbols (by id) assumed to be strictly positive.
+    pub fn with_positive_vars(mut self, positive_vars: FxHashSet<u64>) -> Self {
+        self.context = self.context.with_positive_vars(positive_vars);
+        self
+    }
+
+    /// Sets the symbols (by id) assumed to be zero or positive.
+    pub fn with_non_negative_vars(mut self, non_negative_vars: FxHashSet<u64>) -> Self {
+        self.context = self.context.with_non_negative_vars(non_negative_vars);
+        self
+    }
+
+    /// Applies only the rules of `registry` instead of the global registry.
+    pub fn with_registry(mut self, registry: Arc<RuleRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Tallies the rewrites of each rule across every `simplify` call until
+    /// [`take_usage`](Self::take_usage).
+    pub fn with_usage_recording(mut self) -> Self {
+        self.usage = Some(FxHashMap::default());
+        self
+    }
+
+    /// The rewrites tallied since recording started or since the last call.
+    pub fn take_usage(&mut self) -> FxHashMap<&'static str, RuleTally> {
+        self.usage.as_mut().map(take).unwrap_or_default()
+    }
+
     /// Main simplification entry point
     pub fn simplify(&mut self, expr: Expr) -> Expr {
         // Set domain_safe on context once (apply_rules_to_node will only update depth)
@@ -240,6 +330,8 @@ impl Simplifier {
         // `Expr` hash implementation uses the pre-computed hash, so this is still fast (O(1)),
         // but `HashSet` will verify structural equality on collision.
         let mut seen_exprs: FxHashSet<Arc<Expr>> = FxHashSet::default();
+        self.node_rewrites.clear();
+        self.rule_hits.clear();
 
         loop {
             if iterations >= self.max_iterations {
@@ -247,7 +339,7 @@ impl Simplifier {
             }
 
             let original = Arc::clone(&current);
-            current = self.apply_rules_bottom_up(current, 0);
+            current = self.apply_rules_bottom_up(current, 0, 0);
 
             // Use structural equality to check if expression changed
             if *current == *original {
@@ -296,11 +388,24 @@ impl Simplifier {
     ///
     /// The bottom-up approach ensures that rules work on already-simplified sub-expressions,
     /// reducing the need for complex pattern matching in individual rules.
-    fn apply_rules_bottom_up(&mut self, expr: Arc<Expr>, depth: usize) -> Arc<Expr> {
+    fn apply_rules_bottom_up(&mut self, expr: Arc<Expr>, depth: usize, position: u64) -> Arc<Expr> {
         if depth > self.max_depth {
             return expr;
         }
 
+        // A labeled node keeps its label through rebuilds and rewrites of itself
+        let label_source = expr.label().is_some().then(|| Arc::clone(&expr));
+        let simplified = self.simplify_node(expr, depth, position);
+        match label_source {
+            Some(source) if simplified.label().is_none() => {
+                Arc::new(Expr::unwrap_arc(simplified).inherit_label(&source))
+            }
+            _ => simplified,
+        }
+    }
+
+    /// Simplify the children of a node, then the node itself
+    fn simplify_node(&mut self, expr: Arc<Expr>, depth: usize, position: u64) -> Arc<Expr> {
         // Optimized helper: map over children lazily, only allocating if something changed.
         //
         // Performance Strategy:
@@ -312,7 +417,11 @@ impl Simplifier {
         let map_lazy = |items: &[Arc<Expr>], simplifier: &mut Self| -> Option<Vec<Arc<Expr>>> {
             let mut result: Option<Vec<Arc<Expr>>> = None;
             for (i, item) in items.iter().enumerate() {
-                let simplified = simplifier.apply_rules_bottom_up(Arc::clone(item), depth + 1);
+                let simplified = simplifier.apply_rules_bottom_up(
+                    Arc::clone(item),
+                    depth + 1,
+                    child_position(position, i),
+                );
                 if !Arc::ptr_eq(&simplified, item) && result.is_none() {
                     let mut v = Vec::with_capacity(items.len());
                     v.extend(items[..i].iter().cloned());
@@ -330,9 +439,9 @@ impl Simplifier {
             ExprKind::Sum(terms) => {
                 if let Some(v) = map_lazy(terms, self) {
                     let new_expr = Arc::new(Expr::sum_from_arcs(v));
-                    self.apply_rules_to_node(new_expr, depth)
+                    self.apply_rules_to_node(new_expr, depth, position)
                 } else {
-                    self.apply_rules_to_node(expr, depth)
+                    self.apply_rules_to_node(expr, depth, position)
                 }
             }
 
@@ -340,58 +449,103 @@ impl Simplifier {
             ExprKind::Product(factors) => {
                 if let Some(v) = map_lazy(factors, self) {
                     let new_expr = Arc::new(Expr::product_from_arcs(v));
-                    self.apply_rules_to_node(new_expr, depth)
+                    self.apply_rules_to_node(new_expr, depth, position)
                 } else {
-                    self.apply_rules_to_node(expr, depth)
+                    self.apply_rules_to_node(expr, depth, position)
                 }
             }
 
             ExprKind::Div(u, v) => {
-                let u_simplified = self.apply_rules_bottom_up(Arc::clone(u), depth + 1);
-                let v_simplified = self.apply_rules_bottom_up(Arc::clone(v), depth + 1);
+                let u_simplified = self.apply_rules_bottom_up(
+                    Arc::clone(u),
+                    depth + 1,
+                    child_position(position, 0),
+                );
+                let v_simplified = self.apply_rules_bottom_up(
+                    Arc::clone(v),
+                    depth + 1,
+                    child_position(position, 1),
+                );
 
                 if Arc::ptr_eq(&u_simplified, u) && Arc::ptr_eq(&v_simplified, v) {
-                    self.apply_rules_to_node(expr, depth)
+                    self.apply_rules_to_node(expr, depth, position)
                 } else {
                     let new_expr = Arc::new(Expr::div_from_arcs(u_simplified, v_simplified));
-                    self.apply_rules_to_node(new_expr, depth)
+                    self.apply_rules_to_node(new_expr, depth, position)
                 }
             }
             ExprKind::Pow(u, v) => {
-                let u_simplified = self.apply_rules_bottom_up(Arc::clone(u), depth + 1);
-                let v_simplified = self.apply_rules_bottom_up(Arc::clone(v), depth + 1);
+                let u_simplified = self.apply_rules_bottom_up(
+                    Arc::clone(u),
+                    depth + 1,
+                    child_position(position, 0),
+                );
+                let v_simplified = self.apply_rules_bottom_up(
+                    Arc::clone(v),
+                    depth + 1,
+                    child_position(position, 1),
+                );
 
                 if Arc::ptr_eq(&u_simplified, u) && Arc::ptr_eq(&v_simplified, v) {
-                    self.apply_rules_to_node(expr, depth)
+                    self.apply_rules_to_node(expr, depth, position)
                 } else {
                     let new_expr = Arc::new(Expr::pow_from_arcs(u_simplified, v_simplified));
-                    self.apply_rules_to_node(new_expr, depth)
+                    self.apply_rules_to_node(new_expr, depth, position)
                 }
             }
             ExprKind::FunctionCall { name, args } => {
                 if let Some(v) = map_lazy(args, self) {
                     let new_expr = Arc::new(Expr::func_multi_from_arcs(name, v));
-                    self.apply_rules_to_node(new_expr, depth)
+                    self.apply_rules_to_node(new_expr, depth, position)
+                } else {
+                    self.apply_rules_to_node(expr, depth, position)
+                }
+            }
+            // Piecewise - simplify every condition side, value and the default
+            ExprKind::Piecewise { branches, default } => {
+                let parts: Vec<Arc<Expr>> = piecewise_parts(branches, default).cloned().collect();
+                if let Some(v) = map_lazy(&parts, self) {
+                    let mut simplified = v.into_iter();
+                    let new_expr = Arc::new(map_piecewise(branches, default, |_| {
+                        simplified.next().expect("one simplified child per part")
+                    }));
+                    self.apply_rules_to_node(new_expr, depth, position)
                 } else {
-                    self.apply_rules_to_node(expr, depth)
+                    self.apply_rules_to_node(expr, depth, position)
                 }
             }
-            _ => self.apply_rules_to_node(expr, depth),
+            _ => self.apply_rules_to_node(expr, depth, position),
         }
     }
 
     /// Apply all applicable rules to a single node in dependency order
-    fn apply_rules_to_node(&mut self, mut current: Arc<Expr>, depth: usize) -> Arc<Expr> {
+    fn apply_rules_to_node(
+        &mut self,
+        mut current: Arc<Expr>,
+        depth: usize,
+        position: u64,
+    ) -> Arc<Expr> {
         // Update depth in-place (context.domain_safe is already set in simplify())
         self.context.set_depth(depth);
 
+        let mut rewrites = self.node_rewrites.get(&position).copied().unwrap_or(0);
+        if rewrites >= self.node_budget {
+            return current;
+        }
+
         // Get the expression kind once and only check rules that apply to it
         let kind = RuleExprKind::of(current.as_ref());
 
         // Helper macro to apply a rule and update current if successful
         macro_rules! try_apply {
             ($rule:expr) => {
-                if self.context.domain_safe && $rule.alters_domain() {
+                if rewrites >= self.node_budget {
+                    break;
+                }
+                if self.context.domain_safe
+                    && $rule.alters_domain()
+                    && !$rule.domain_assumed(&current, &self.context)
+                {
                     continue;
                 }
 
@@ -404,6 +558,12 @@ impl Simplifier {
                     .or_insert_with(HashKeyedCache::new);
                 if let Some(res) = cache.get(&current) {
                     if let Some(new_expr) = res {
+                        if let Some(usage) = &mut self.usage {
+                            usage
+                                .entry(rule_name)
+                                .or_default()
+                                .record(&current, new_expr);
+                        }
                         current = Arc::clone(new_expr);
                     }
                     // Cached result (Some or None), skip application
@@ -411,8 +571,8 @@ impl Simplifier {
                 }
 
                 // Cheap structural pre-check: skip apply() without caching the failure.
-                // can_apply() is O(1) and cheaper than a cache insert + future lookup.
-                if !$rule.can_apply(&current) {
+                // precondition() is O(1) and cheaper than a cache insert + future lookup.
+                if !$rule.precondition(&current) {
                     continue;
                 }
 
@@ -425,17 +585,34 @@ impl Simplifier {
 
                 if let Some(new_expr) = $rule.apply(&current, &self.context) {
                     trace_log!("[TRACE] {} : {} => {}", rule_name, current, new_expr);
+                    if trace_enabled() {
+                        *self
+                            .rule_hits
+                            .entry((rule_name, current.structural_hash()))
+                            .or_default() += 1;
+                    }
+                    if let Some(usage) = &mut self.usage {
+                        usage
+                            .entry(rule_name)
+                            .or_default()
+                            .record(&current, &new_expr);
+                    }
                     cache.insert(Arc::clone(&current), Some(Arc::clone(&new_expr)));
                     current = new_expr;
+                    rewrites += 1;
                 } else {
                     cache.insert(Arc::clone(&current), None);
                 }
             };
         }
 
+        let custom_registry = self.registry.clone();
+        let registry = custom_registry
+            .as_deref()
+            .unwrap_or_else(|| global_registry());
+
         if kind == RuleExprKind::Function {
             if let ExprKind::FunctionCall { name, .. } = &current.kind {
-                let registry = global_registry();
                 let specific = registry.get_specific_func_rules(name.id());
                 let generic = registry.get_generic_func_rules();
 
@@ -447,16 +624,38 @@ impl Simplifier {
                 }
             } else {
                 // Fallback (should not happen for kind=Function)
-                for rule in global_registry().get_rules_for_kind(kind) {
+                for rule in registry.get_rules_for_kind(kind) {
                     try_apply!(rule);
                 }
             }
         } else {
-            for rule in global_registry().get_rules_for_kind(kind) {
+            for rule in registry.get_rules_for_kind(kind) {
                 try_apply!(rule);
             }
         }
 
+        if rewrites > 0 {
+            self.node_rewrites.insert(position, rewrites);
+            if rewrites >= self.node_budget {
+                self.report_budget_trip(position);
+            }
+        }
         current
     }
+
+    /// Trace the rule/node pairs that fired most often once a node exhausts its budget.
+    fn report_budget_trip(&self, position: u64) {
+        if !trace_enabled() {
+            return;
+        }
+        let mut hits: Vec<_> = self.rule_hits.iter().collect();
+        hits.sort_unstable_by(|a, b| b.1.cmp(a.1));
+        trace_log!(
+            "[DEBUG] Rewrite budget of {} exhausted at node position {position:#018x}; top offenders:",
+            self.node_budget
+        );
+        for ((rule, hash), count) in hits.into_iter().take(BUDGET_REPORT_LEN) {
+            trace_log!("[DEBUG]   {rule} on node {hash:#018x}: {count} rewrites");
+        }
+    }
 }
@@ -0,0 +1,90 @@
+//! Labeled subexpressions held atomic while the rest of an expression simplifies.
+//!
+//! Each outermost labeled node is simplified on its own, with its labeled parts held in
+//! turn, and stands in the surrounding expression as an anonymous placeholder symbol while
+//! that simplifies. The groups go back in at the end, so no rule reaches across a group
+//! boundary: `2*(a*b/c)/a` keeps `a*b/c` instead of cancelling `a` through it.
+
+use crate::core::{Expr, ExprKind, Symbol, map_piecewise};
+use std::sync::Arc;
+
+/// `(placeholder, simplified labeled group)` pairs
+type Groups = Vec<(Expr, Expr)>;
+
+/// Simplify `expr` with `simplify`, holding every labeled subexpression atomic
+pub fn simplify_holding_groups(expr: Expr, simplify: &mut dyn FnMut(Expr) -> Expr) -> Expr {
+    let mut groups = Groups::new();
+    let skeleton = hold_groups(&Arc::new(expr), simplify, &mut groups);
+    let simplified = simplify(Expr::unwrap_arc(skeleton));
+    if groups.is_empty() {
+        simplified
+    } else {
+        simplified.substitute_many(&groups)
+    }
+}
+
+/// `expr` with its outermost labeled nodes simplified into `groups` and replaced by their
+/// placeholders
+fn hold_groups(
+    expr: &Arc<Expr>,
+    simplify: &mut dyn FnMut(Expr) -> Expr,
+    groups: &mut Groups,
+) -> Arc<Expr> {
+    if let Some(label) = expr.label() {
+        let inner = Expr::unwrap_arc(Expr::unlabeled_arc(Arc::clone(expr)));
+        let group = Expr::labeled(label, simplify_holding_groups(inner, simplify));
+        // Equal groups share a placeholder, so the surrounding rules can still combine them
+        let shared = groups
+            .iter()
+            .find(|(_, held)| *held == group && held.label() == group.label());
+        let placeholder = if let Some((placeholder, _)) = shared {
+            placeholder.clone()
+        } else {
+            let placeholder = Symbol::anon().to_expr();
+            groups.push((placeholder.clone(), group));
+            placeholder
+        };
+        return Arc::new(placeholder);
+    }
+
+    let mut children = |items: &[Arc<Expr>]| -> Vec<Arc<Expr>> {
+        items
+            .iter()
+            .map(|item| hold_groups(item, simplify, groups))
+            .collect()
+    };
+    match &expr.kind {
+        ExprKind::Number(_) | ExprKind::Symbol(_) => Arc::clone(expr),
+        ExprKind::Sum(terms) => Arc::new(Expr::sum_from_arcs(children(terms))),
+        ExprKind::Product(factors) => Arc::new(Expr::product_from_arcs(children(factors))),
+        ExprKind::FunctionCall { name, args } => Arc::new(Expr::func_multi_from_arcs_symbol(
+            name.clone(),
+            children(args),
+        )),
+        ExprKind::Div(num, den) => {
+            let num = hold_groups(num, simplify, groups);
+            Arc::new(Expr::div_from_arcs(num, hold_groups(den, simplify, groups)))
+        }
+        ExprKind::Pow(base, exp) => {
+            let base = hold_groups(base, simplify, groups);
+            Arc::new(Expr::pow_from_arcs(
+                base,
+                hold_groups(exp, simplify, groups),
+            ))
+        }
+        ExprKind::Derivative { inner, var, order } => Arc::new(Expr::derivative_interned(
+            Expr::unwrap_arc(hold_groups(inner, simplify, groups)),
+            var.clone(),
+            *order,
+        )),
+        ExprKind::Poly(poly) => {
+            let base = hold_groups(poly.base(), simplify, groups);
+            Arc::new(Expr::new(ExprKind::Poly(poly.with_base(base))))
+        }
+        ExprKind::Piecewise { branches, default } => {
+            Arc::new(map_piecewise(branches, default, |part| {
+                hold_groups(part, simplify, groups)
+            }))
+        }
+    }
+}
@@ -3,10 +3,12 @@
 //! Provides expression manipulation utilities: flattening, normalization,
 //! coefficient extraction, root prettification, and like-term grouping.
 
+use super::rules::RuleContext;
 use crate::EPSILON;
-use crate::core::arc_number;
 use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::traits::small_rational;
 use crate::core::{Expr, ExprKind};
+use crate::core::{arc_number, map_piecewise};
 use std::cmp::Ordering;
 use std::f64::consts::PI;
 use std::sync::Arc;
@@ -130,7 +132,7 @@ pub fn is_three_pi_over_two(expr: &Expr) -> bool {
 /// for algebraic simplification rules.
 pub fn compare_expr(a: &Expr, b: &Expr) -> Ordering {
     use crate::core::ExprKind::{
-        Derivative, Div, FunctionCall, Number, Poly, Pow, Product, Sum, Symbol,
+        Derivative, Div, FunctionCall, Number, Piecewise, Poly, Pow, Product, Sum, Symbol,
     };
     use Ordering;
 
@@ -170,6 +172,7 @@ pub fn compare_expr(a: &Expr, b: &Expr) -> Ordering {
             Div(..) => (35, 0, 0.0),
             Derivative { .. } => (45, 0, 0.0),
             Poly(_) => (25, 0, 0.0), // Poly treated as complex
+            Piecewise { .. } => (48, 0, 0.0),
         }
     }
 
@@ -203,7 +206,7 @@ pub fn compare_expr(a: &Expr, b: &Expr) -> Ordering {
 /// Used for organizing terms in products during simplification.
 pub fn compare_mul_factors(a: &Expr, b: &Expr) -> Ordering {
     use crate::core::ExprKind::{
-        Derivative, Div, FunctionCall, Number, Poly, Pow, Product, Sum, Symbol,
+        Derivative, Div, FunctionCall, Number, Piecewise, Poly, Pow, Product, Sum, Symbol,
     };
     use Ordering;
 
@@ -223,6 +226,7 @@ pub fn compare_mul_factors(a: &Expr, b: &Expr) -> Ordering {
             Sum(..) => 60,
             Derivative { .. } => 45, // After functions, before mul/div
             Poly(_) => 55,           // After products, before sums
+            Piecewise { .. } => 47,  // After derivatives
         }
     }
 
@@ -363,6 +367,11 @@ pub fn prettify_roots(root: Expr) -> Expr {
                 | ExprKind::Derivative { .. } => {
                     results.push(expr);
                 }
+                ExprKind::Piecewise { branches, default } => {
+                    results.push(map_piecewise(branches, default, |part| {
+                        Arc::new(prettify_roots((**part).clone()))
+                    }));
+                }
                 ExprKind::Sum(terms) => {
                     let n = terms.len();
                     work.push(Task::Assemble(expr.clone(), n));
@@ -516,11 +525,11 @@ pub fn prettify_roots(root: Expr) -> Expr {
         .expect("prettify_roots must produce exactly one result")
 }
 
-/// Check if an expression is known to be non-negative for all real values of its variables.
+/// Check if an expression is known to be non-negative for all real values of its variables,
+/// given the sign assumptions of `context`.
 /// This is a conservative check - returns true only when we can prove non-negativity.
-/// Check if expression is known to be non-negative.
 /// Used for safe square root and absolute value simplifications.
-pub fn is_known_non_negative(expr: &Expr) -> bool {
+pub fn is_known_non_negative(expr: &Expr, context: &RuleContext) -> bool {
     // Iterative: every node on the stack must be non-negative for the
     // overall result to be true.  We push children that need checking
     // (Product factors, Sum terms, Pow bases, sqrt args) and return
@@ -535,6 +544,15 @@ pub fn is_known_non_negative(expr: &Expr) -> bool {
                 }
             }
 
+            // Symbols assumed positive or non-negative
+            ExprKind::Symbol(s) => {
+                let id = s.id();
+                if !context.positive_vars.contains(&id) && !context.non_negative_vars.contains(&id)
+                {
+                    return is_known_positive(node, context);
+                }
+            }
+
             // x^2, x^4, x^6, ... are always non-negative
             ExprKind::Pow(base, exp) => {
                 if let ExprKind::Number(n) = &exp.kind {
@@ -555,7 +573,9 @@ pub fn is_known_non_negative(expr: &Expr) -> bool {
                         continue;
                     }
                 }
-                return false;
+                if !is_known_positive(node, context) {
+                    return false;
+                }
             }
 
             // abs(x), exp(x), cosh(x) are always non-negative
@@ -575,14 +595,60 @@ pub fn is_known_non_negative(expr: &Expr) -> bool {
                 stack.extend(args.iter().map(AsRef::as_ref));
             }
 
-            // Division of non-negative by positive is non-negative (but we can't easily check "positive")
-            // Be conservative here
+            // Non-negative over positive
+            ExprKind::Div(num, den) => {
+                if !is_known_positive(den, context) {
+                    return false;
+                }
+                stack.push(num);
+            }
+
             _ => return false,
         }
     }
     true
 }
 
+/// Check if an expression is known to be defined and strictly positive for all real values
+/// of its variables, given the sign assumptions of `context`.
+///
+/// Conservative like [`is_known_non_negative`]: numbers above zero, `pi`, `e`, symbols
+/// assumed positive, and sums, products, quotients and powers of them, `exp` and `cosh`
+/// of any defined argument, and `sqrt`, `cbrt` and `abs` of positive arguments.
+pub fn is_known_positive(expr: &Expr, context: &RuleContext) -> bool {
+    // Defined for every real value of its variables (the argument of `exp`, an exponent)
+    let defined = |e: &Expr| {
+        matches!(e.kind, ExprKind::Number(n) if n.is_finite())
+            || matches!(e.kind, ExprKind::Symbol(_))
+            || is_known_positive(e, context)
+    };
+    match &expr.kind {
+        ExprKind::Number(n) => *n > 0.0,
+        ExprKind::Symbol(s) => {
+            let id = s.id();
+            id == KS.pi || id == KS.e || context.positive_vars.contains(&id)
+        }
+        ExprKind::Sum(terms) | ExprKind::Product(terms) => {
+            terms.iter().all(|term| is_known_positive(term, context))
+        }
+        ExprKind::Div(num, den) => {
+            is_known_positive(num, context) && is_known_positive(den, context)
+        }
+        ExprKind::Pow(base, exp) => is_known_positive(base, context) && defined(exp),
+        ExprKind::FunctionCall { name, args } if args.len() == 1 => {
+            let id = name.id();
+            if id == KS.exp || id == KS.cosh {
+                defined(&args[0])
+            } else if id == KS.sqrt || id == KS.cbrt || id == KS.abs {
+                is_known_positive(&args[0], context)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
 /// Check if an exponent represents a fractional power that requires non-negative base
 /// (i.e., exponents like 1/2, 1/4, 3/2, etc. where denominator is even)
 /// Check if expression represents a fractional root exponent.
@@ -642,6 +708,114 @@ pub const fn gcd(a: i64, b: i64) -> i64 {
     }
 }
 
+/// Distance from an integer, in units in the last place, within which a numeric
+/// exponent is snapped to that integer.
+///
+/// Exponent arithmetic on inexact decimals lands one or two ulps off
+/// (`0.1 * 3.0 * 10.0 = 3.0000000000000004`); four ulps leaves headroom for a couple of
+/// chained operations while never touching exponents a user wrote on purpose.
+pub const EXPONENT_SNAP_ULPS: f64 = 4.0;
+
+/// Nearest integer to `n` if `n` is within [`EXPONENT_SNAP_ULPS`] of it but not equal.
+pub fn snap_exponent(n: f64) -> Option<f64> {
+    let rounded = n.round();
+    // Beyond 2^52 every float is an integer already
+    if !n.is_finite() || rounded.abs() >= 4_503_599_627_370_496.0 {
+        return None;
+    }
+    let distance = (n - rounded).abs();
+    let tolerance = EXPONENT_SNAP_ULPS * f64::EPSILON * rounded.abs().max(1.0);
+    (distance > 0.0 && distance <= tolerance).then_some(rounded)
+}
+
+/// Exact rational value `(numerator, denominator)` of a numeric expression.
+///
+/// Recognises integers, dyadic fractions such as `0.5` or `0.375`, quotients of integers
+/// (`1/3`) and products of those. Decimals with no exact binary form (`0.1`) are rejected.
+/// The denominator is always positive and the fraction is reduced.
+pub fn exact_rational(expr: &Expr) -> Option<(i64, i64)> {
+    /// Largest power of two (as a shift) accepted as the denominator of a float literal.
+    const MAX_DYADIC_SHIFT: u32 = 20;
+    match &expr.kind {
+        ExprKind::Number(n) => {
+            // fract() is NaN for non-finite values, so those never match
+            let shift =
+                (0..=MAX_DYADIC_SHIFT).find(|&k| (n * f64::from(1_u32 << k)).fract() == 0.0)?;
+            let den = 1_i64 << shift;
+            let scaled = n * f64::from(1_u32 << shift);
+            if scaled.abs() >= 9_007_199_254_740_992.0 {
+                return None;
+            }
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "Integral value below 2^53 checked above"
+            )]
+            let num = scaled as i64;
+            Some(reduce_rational(num, den))
+        }
+        ExprKind::Div(num, den) => {
+            let (a, b) = exact_rational(num)?;
+            let (c, d) = exact_rational(den)?;
+            if c == 0 {
+                return None;
+            }
+            rational_product((a, b), (d, c))
+        }
+        ExprKind::Product(factors) => factors
+            .iter()
+            .try_fold((1, 1), |acc, f| rational_product(acc, exact_rational(f)?)),
+        _ => None,
+    }
+}
+
+/// Replace every non-integral number by the fraction it stands for (see
+/// [`small_rational`]), leaving numbers without one untouched.
+pub fn rationalize_decimals(expr: &Expr) -> Expr {
+    expr.map(|node| match &node.kind {
+        ExprKind::Number(n) => small_rational(*n).map_or_else(|| node.clone(), rational_expr),
+        _ => node.clone(),
+    })
+}
+
+/// Reduce a fraction and move its sign to the numerator.
+#[allow(clippy::integer_division, reason = "Dividing out the exact GCD")]
+const fn reduce_rational(num: i64, den: i64) -> (i64, i64) {
+    let g = gcd(num, den);
+    let (num, den) = if g == 0 {
+        (num, den)
+    } else {
+        (num / g, den / g)
+    };
+    if den < 0 { (-num, -den) } else { (num, den) }
+}
+
+/// Product of two rationals, or `None` on overflow.
+pub fn rational_product(a: (i64, i64), b: (i64, i64)) -> Option<(i64, i64)> {
+    let num = a.0.checked_mul(b.0)?;
+    let den = a.1.checked_mul(b.1)?;
+    Some(reduce_rational(num, den))
+}
+
+/// Sum of two rationals, or `None` on overflow.
+pub fn rational_sum(a: (i64, i64), b: (i64, i64)) -> Option<(i64, i64)> {
+    let num = a.0.checked_mul(b.1)?.checked_add(b.0.checked_mul(a.1)?)?;
+    let den = a.1.checked_mul(b.1)?;
+    Some(reduce_rational(num, den))
+}
+
+/// Expression for a reduced rational: a plain number when integral, `p/q` otherwise.
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "Exponent numerators and denominators are small integers"
+)]
+pub fn rational_expr((num, den): (i64, i64)) -> Expr {
+    if den == 1 {
+        Expr::number(num as f64)
+    } else {
+        Expr::div_expr(Expr::number(num as f64), Expr::number(den as f64))
+    }
+}
+
 // FNV-1a constants shared by both term hash entry points.
 
 /// Normalize expression for structural comparison.
@@ -670,6 +844,11 @@ pub fn normalize_for_comparison(root: &Expr) -> Expr {
                 | ExprKind::Derivative { .. } => {
                     results.push(expr.clone());
                 }
+                ExprKind::Piecewise { branches, default } => {
+                    results.push(map_piecewise(branches, default, |part| {
+                        Arc::new(normalize_for_comparison(part))
+                    }));
+                }
                 ExprKind::Sum(terms) => {
                     let n = terms.len();
                     work.push(Task::Assemble(expr, n));
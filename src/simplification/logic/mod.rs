@@ -1,11 +1,20 @@
 //! Internal simplification implementation details.
 
 pub(super) mod engine;
+pub(super) mod groups;
 pub(super) mod helpers;
 pub(super) mod rules;
+mod usage;
 
-pub(super) use engine::Simplifier;
+pub(super) use engine::{Simplifier, global_registry};
+pub use groups::simplify_holding_groups;
 pub(super) use helpers::prettify_roots;
+pub use helpers::rationalize_decimals;
+pub(super) use rules::RuleRegistry;
+pub(super) use rules::root::rationalize_denominators;
+pub(super) use rules::trigonometric::expand_trig_angles;
+pub use rules::{RuleCategory, UserRule};
+pub use usage::{RuleUsage, RuleUsageReport};
 
 #[cfg(test)]
 mod tests;
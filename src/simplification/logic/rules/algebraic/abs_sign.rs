@@ -1,4 +1,6 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use super::{
+    Rule, RuleCategory, RuleContext, RuleExprKind, is_known_non_negative, is_known_positive,
+};
 use crate::core::known_symbols::{KS, get_symbol};
 use crate::core::{Expr, ExprKind};
 use std::sync::Arc;
@@ -9,6 +11,8 @@ rule_arc!(
     95,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.abs),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.abs
@@ -27,6 +31,8 @@ rule_arc!(
     90,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.abs),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.abs
@@ -50,6 +56,8 @@ rule_arc!(
     90,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.abs),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.abs
@@ -83,6 +91,8 @@ rule_arc!(
     85,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.abs),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.abs
@@ -111,12 +121,35 @@ rule_arc!(
     }
 );
 
+rule_arc!(
+    AbsNonNegativeRule,
+    "abs_non_negative",
+    84,
+    Algebraic,
+    &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.abs),
+    |expr: &Expr, context: &RuleContext| {
+        // abs(x) = x for x >= 0, including symbols assumed positive or non-negative
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && name.id() == KS.abs
+            && args.len() == 1
+            && is_known_non_negative(&args[0], context)
+        {
+            return Some(Arc::clone(&args[0]));
+        }
+        None
+    }
+);
+
 rule_arc!(
     AbsPowEvenRule,
     "abs_pow_even",
     85,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(&base.kind, ExprKind::FunctionCall { name, .. } if name.id() == KS.abs)),
     |expr: &Expr, _context: &RuleContext| {
         // abs(x)^n where n is positive even integer -> x^n
         if let ExprKind::Pow(base, exp) = &expr.kind
@@ -150,6 +183,8 @@ rule_arc!(
     95,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if (name.id() == KS.sign || name.id() == KS.sgn)),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && (name.id() == KS.sign || name.id() == KS.sgn)
@@ -173,6 +208,8 @@ rule_arc!(
     90,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if (name.id() == KS.sign || name.id() == KS.sgn)),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && (name.id() == KS.sign || name.id() == KS.sgn)
@@ -194,6 +231,8 @@ rule_arc!(
     85,
     Algebraic,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if (name.id() == KS.sign || name.id() == KS.sgn)),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && (name.id() == KS.sign || name.id() == KS.sgn)
@@ -210,6 +249,27 @@ rule_arc!(
     }
 );
 
+rule_arc!(
+    SignPositiveRule,
+    "sign_positive",
+    84,
+    Algebraic,
+    &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if [KS.sign, KS.sgn, KS.signum].contains(&name.id())),
+    |expr: &Expr, context: &RuleContext| {
+        // sign(x) = 1 for x > 0
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && [KS.sign, KS.sgn, KS.signum].contains(&name.id())
+            && args.len() == 1
+            && is_known_positive(&args[0], context)
+        {
+            return Some(Arc::new(Expr::number(1.0)));
+        }
+        None
+    }
+);
+
 rule_arc!(
     AbsSignMulRule,
     "abs_sign_mul",
@@ -269,3 +329,55 @@ rule_arc!(
         None
     }
 );
+
+rule_arc!(
+    HypotZeroRule,
+    "hypot_zero",
+    90,
+    Algebraic,
+    &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.hypot),
+    |expr: &Expr, _context: &RuleContext| {
+        // hypot(x, 0) = abs(x) and hypot(0, y) = abs(y)
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && name.id() == KS.hypot
+            && args.len() == 2
+        {
+            let other = match (args[0].as_number(), args[1].as_number()) {
+                (_, Some(0.0)) => &args[0],
+                (Some(0.0), _) => &args[1],
+                _ => return None,
+            };
+            return Some(Arc::new(Expr::func_multi_from_arcs_symbol(
+                get_symbol(KS.abs),
+                vec![Arc::clone(other)],
+            )));
+        }
+        None
+    }
+);
+
+rule_arc!(
+    HypotEqualRule,
+    "hypot_equal",
+    90,
+    Algebraic,
+    &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.hypot),
+    |expr: &Expr, _context: &RuleContext| {
+        // hypot(a, a) = abs(a) * sqrt(2)
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && name.id() == KS.hypot
+            && args.len() == 2
+            && args[0] == args[1]
+        {
+            return Some(Arc::new(Expr::product(vec![
+                Expr::func_multi_from_arcs_symbol(get_symbol(KS.abs), vec![Arc::clone(&args[0])]),
+                Expr::func_symbol(get_symbol(KS.sqrt), Expr::number(2.0)),
+            ])));
+        }
+        None
+    }
+);
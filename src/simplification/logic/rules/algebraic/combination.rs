@@ -5,7 +5,8 @@ use super::{
 };
 use crate::EPSILON;
 use crate::core::arc_number;
-use crate::core::{Expr, ExprKind};
+use crate::core::traits::cancels;
+use crate::core::{Expr, ExprKind, piecewise_parts};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 
@@ -56,23 +57,30 @@ rule_arc!(
                 return None;
             }
 
-            // Group terms by their base to find combinable terms
+            // Group terms by their base to find combinable terms, counting the merged terms
             // Use FxHashMap with Arc<Expr> key to avoid cloning
-            let mut term_groups: FxHashMap<Arc<Expr>, f64> = FxHashMap::default();
+            let mut term_groups: FxHashMap<Arc<Expr>, (f64, usize)> = FxHashMap::default();
 
             for term in terms {
                 let (coeff, base) = extract_coeff_arc(term);
-                *term_groups.entry(base).or_insert(0.0) += coeff;
+                let (total, count) = term_groups.entry(base).or_insert((0.0, 0));
+                *total += coeff;
+                *count += 1;
             }
 
-            // If no terms were actually combined, don't change anything
-            if term_groups.len() == terms.len() {
+            // If no terms were actually combined, don't change anything.
+            // A NaN coefficient comes from `inf - inf`, which is left to `infinite_sum`.
+            if term_groups.len() == terms.len() || term_groups.values().any(|(c, _)| c.is_nan()) {
                 return None;
             }
 
             // Build result from combined terms
             let mut result = Vec::new();
-            for (base, coeff) in term_groups {
+            for (mut base, (coeff, count)) in term_groups {
+                // A labeled term merged with others no longer stands on its own
+                if count > 1 {
+                    base = Expr::unlabeled_arc(base);
+                }
                 if coeff == 0.0 {
                     // Drop zero terms
                 } else if (coeff - 1.0).abs() < EPSILON {
@@ -193,38 +201,14 @@ rule_arc!(
     }
 );
 
-// Helper: Extract factor and addends from Product containing Sum
-/// Extracts a factor and addends from a product containing a sum.
-fn extract_product_with_sum(expr: &Expr) -> Option<(Expr, Vec<Expr>)> {
-    if let ExprKind::Product(factors) = &expr.kind {
-        // Look for a Sum among the factors
-        for (i, factor) in factors.iter().enumerate() {
-            if let ExprKind::Sum(addends) = &factor.kind {
-                // Collect all other factors
-                let other_factors: Vec<Expr> = factors
-                    .iter()
-                    .enumerate()
-                    .filter(|(j, _)| *j != i)
-                    .map(|(_, f)| (**f).clone())
-                    .collect();
-
-                let combined_factor = if other_factors.is_empty() {
-                    Expr::number(1.0)
-                } else if other_factors.len() == 1 {
-                    other_factors
-                        .into_iter()
-                        .next()
-                        .expect("Iterator guaranteed to have one element")
-                } else {
-                    Expr::product(other_factors)
-                };
+/// Node budget for a single N-ary distribution; larger expansions are declined.
+pub const MAX_DISTRIBUTION_NODES: usize = 2_000;
 
-                let addends_vec: Vec<Expr> = addends.iter().map(|a| (**a).clone()).collect();
-                return Some((combined_factor, addends_vec));
-            }
-        }
-    }
-    None
+// Helper: Check if expression is a Product with at least one Sum factor
+/// Checks if the expression is a product containing a sum.
+fn is_product_with_sum(expr: &Expr) -> bool {
+    matches!(&expr.kind, ExprKind::Product(factors)
+        if factors.iter().any(|f| matches!(f.kind, ExprKind::Sum(_))))
 }
 
 // Helper: Check if expression contains a variable (not just numbers)
@@ -242,6 +226,9 @@ fn contains_variable(expr: &Expr) -> bool {
             // Check if base contains variables (non-constant polynomial)
             contains_variable(poly.base())
         }
+        ExprKind::Piecewise { branches, default } => {
+            piecewise_parts(branches, default).any(|part| contains_variable(part))
+        }
     }
 }
 
@@ -283,43 +270,84 @@ fn combine_var_parts(a: Expr, b: Expr) -> Expr {
     Expr::product(vec![a, b])
 }
 
-// Helper: Distribute a factor over addends and build canonical terms
-/// Distributes a factor over addends and builds canonical terms.
-fn distribute_factor(factor: &Expr, addends: &[Expr]) -> Vec<Expr> {
-    addends
+// Helper: Multiply a (coefficient, variable part) term by another expression
+/// Multiplies a partial `(coefficient, variable part)` term by `factor`,
+/// converting x*x to x^2 etc.
+fn multiply_term((coeff, var): &(f64, Expr), factor: &Expr) -> (f64, Expr) {
+    let (factor_coeff, factor_var) = extract_coeff(factor);
+    // Exact check for 1.0 to avoid redundant multiplication
+    #[allow(clippy::float_cmp, reason = "Comparing against exact constant 1.0")]
+    let is_one = |e: &Expr| matches!(e.kind, ExprKind::Number(n) if n == 1.0);
+    let combined_var = if is_one(var) {
+        factor_var
+    } else if is_one(&factor_var) {
+        var.clone()
+    } else {
+        combine_var_parts(var.clone(), factor_var)
+    };
+    (coeff * factor_coeff, combined_var)
+}
+
+/// Distributes a product over all of its `Sum` factors in a single pass.
+///
+/// `(a+b)*(c+d)*k` becomes the flat list of canonical terms
+/// `[a*c*k, a*d*k, b*c*k, b*d*k]`, sorted with `compare_expr`. Returns `None` if no
+/// factor is a sum, or if the expanded terms would exceed `max_nodes` nodes in total.
+pub fn distribute_product(factors: &[Arc<Expr>], max_nodes: usize) -> Option<Vec<Expr>> {
+    let (sums, rest): (Vec<_>, Vec<_>) = factors
         .iter()
-        .map(|addend| {
-            // Extract coefficients from both factor and addend
-            let (factor_coeff, factor_var) = extract_coeff(factor);
-            let (addend_coeff, addend_var) = extract_coeff(addend);
-
-            let total_coeff = factor_coeff * addend_coeff;
-
-            // Combine variable parts, converting x*x to x^2 etc.
-            let combined_var = {
-                // Exact check for 1.0 to avoid redundant multiplication
-                #[allow(clippy::float_cmp, reason = "Comparing against exact constant 1.0")]
-                let factor_var_is_one = matches!(factor_var.kind, ExprKind::Number(n) if n == 1.0);
-                #[allow(clippy::float_cmp, reason = "Comparing against exact constant 1.0")]
-                let addend_var_is_one = matches!(addend_var.kind, ExprKind::Number(n) if n == 1.0);
-
-                if factor_var_is_one {
-                    addend_var
-                } else if addend_var_is_one {
-                    factor_var
-                } else {
-                    combine_var_parts(factor_var, addend_var)
-                }
-            };
+        .partition(|f| matches!(f.kind, ExprKind::Sum(_)));
+    let addend_lists: Vec<&[Arc<Expr>]> = sums
+        .iter()
+        .filter_map(|f| match &f.kind {
+            ExprKind::Sum(addends) => Some(addends.as_slice()),
+            _ => None,
+        })
+        .collect();
+    if addend_lists.is_empty() {
+        return None;
+    }
+
+    // Size guard: term count times the largest possible term
+    let mut term_count: usize = 1;
+    let mut term_nodes: usize = rest.iter().map(|f| f.node_count()).sum();
+    for addends in &addend_lists {
+        term_count = term_count.checked_mul(addends.len())?;
+        term_nodes += addends.iter().map(|a| a.node_count()).max().unwrap_or(0);
+    }
+    if term_count.saturating_mul(term_nodes + 1) > max_nodes {
+        return None;
+    }
+
+    let mut common = (1.0, Expr::number(1.0));
+    for factor in rest {
+        common = multiply_term(&common, factor);
+    }
+    let mut partials = vec![common];
+    for addends in addend_lists {
+        partials = partials
+            .iter()
+            .flat_map(|partial| addends.iter().map(|a| multiply_term(partial, a)))
+            .collect();
+    }
 
-            // Build canonical result (coefficient first)
-            if (total_coeff - 1.0).abs() < EPSILON {
-                combined_var
+    // Build canonical terms (coefficient first)
+    let mut terms: Vec<Expr> = partials
+        .into_iter()
+        .map(|(coeff, var)| {
+            #[allow(clippy::float_cmp, reason = "Comparing against exact constant 1.0")]
+            let var_is_one = matches!(var.kind, ExprKind::Number(n) if n == 1.0);
+            if var_is_one {
+                Expr::number(coeff)
+            } else if (coeff - 1.0).abs() < EPSILON {
+                var
             } else {
-                Expr::product(vec![Expr::number(total_coeff), combined_var])
+                Expr::product(vec![Expr::number(coeff), var])
             }
         })
-        .collect()
+        .collect();
+    terms.sort_by(compare_expr);
+    Some(terms)
 }
 
 rule_arc!(
@@ -340,36 +368,38 @@ rule_arc!(
             // First pass: collect hashes of non-product-with-sum terms
             let mut existing_hashes: FxHashSet<u64> = FxHashSet::default();
             for term in terms_vec {
-                if extract_product_with_sum(term).is_none() {
+                if !is_product_with_sum(term) {
                     let (_, base) = extract_coeff_arc(term);
                     existing_hashes.insert(base.term_hash);
                 }
             }
 
-            // Second pass: expand products that would create combinable terms
+            // Second pass: fully expand products that would create combinable terms
             let mut expanded_terms: Vec<Arc<Expr>> = Vec::new();
             let mut did_expand = false;
 
             for term in terms_vec {
-                if let Some((factor, addends)) = extract_product_with_sum(term) {
-                    // factor and addends are Expr (deep copy from helper)
-                    // We can't easily optimize extract_product_with_sum without changing helper signature
-                    // But assume we accept the clone there for now.
-                    if contains_variable(&factor) {
-                        let distributed = distribute_factor(&factor, &addends);
-
-                        // Check if any distributed term would combine with existing terms
-                        let would_combine = distributed.iter().any(|dt| {
-                            let (_, base) = extract_coeff(dt);
-                            let hash = base.term_hash;
-                            existing_hashes.contains(&hash)
-                        });
-
-                        if would_combine {
-                            expanded_terms.extend(distributed.into_iter().map(Arc::new));
-                            did_expand = true;
-                            continue;
-                        }
+                if let ExprKind::Product(factors) = &term.kind
+                    && let Some(first_sum) = factors
+                        .iter()
+                        .position(|f| matches!(f.kind, ExprKind::Sum(_)))
+                    // Something other than the first sum must carry a variable
+                    && factors
+                        .iter()
+                        .enumerate()
+                        .any(|(j, f)| j != first_sum && contains_variable(f))
+                    && let Some(distributed) = distribute_product(factors, MAX_DISTRIBUTION_NODES)
+                {
+                    // Check if any distributed term would combine with existing terms
+                    let would_combine = distributed.iter().any(|dt| {
+                        let (_, base) = extract_coeff(dt);
+                        existing_hashes.contains(&base.term_hash)
+                    });
+
+                    if would_combine {
+                        expanded_terms.extend(distributed.into_iter().map(Arc::new));
+                        did_expand = true;
+                        continue;
                     }
                 }
                 expanded_terms.push(Arc::clone(term));
@@ -406,17 +436,28 @@ rule_arc!(
                 } else {
                     // Sub-group by actual structural base using HashMap (O(n) amortized).
                     // Expr's Hash+PartialEq handles hash collisions via structural equality.
-                    let mut sub_groups: FxHashMap<Arc<Expr>, f64> = FxHashMap::default();
+                    // Each entry holds the running coefficient, the largest magnitude added
+                    // and the number of terms merged
+                    let mut sub_groups: FxHashMap<Arc<Expr>, (f64, f64, usize)> =
+                        FxHashMap::default();
                     for term in &group_terms {
                         let (coeff, var_part) = extract_coeff_arc(term);
-                        *sub_groups.entry(var_part).or_insert(0.0) += coeff;
+                        let (total, scale, count) =
+                            sub_groups.entry(var_part).or_insert((0.0, 0.0, 0));
+                        *total += coeff;
+                        *scale = scale.max(coeff.abs());
+                        *count += 1;
                     }
 
-                    for (base_term, total_coeff) in sub_groups {
-                        if total_coeff.abs() < EPSILON {
+                    for (mut base_term, (total_coeff, scale, count)) in sub_groups {
+                        if cancels(total_coeff, scale) {
                             // Terms cancel out
                             continue;
                         }
+                        // A labeled term merged with others no longer stands on its own
+                        if count > 1 {
+                            base_term = Expr::unlabeled_arc(base_term);
+                        }
 
                         if (total_coeff - 1.0).abs() < EPSILON {
                             combined_terms.push(base_term);
@@ -0,0 +1,98 @@
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind, extract_coeff};
+use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::{Expr, ExprKind};
+use std::sync::Arc;
+
+rule!(
+    ErfNegationRule,
+    "erf_negation",
+    90,
+    Algebraic,
+    &[RuleExprKind::Function],
+    targets: &[KS.erf],
+    |expr: &Expr, _context: &RuleContext| {
+        // erf is odd: erf(-c*u) = -erf(c*u) for a negative leading coefficient
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && name.id() == KS.erf
+            && args.len() == 1
+        {
+            let (coeff, rest) = extract_coeff(&args[0]);
+            if coeff < 0.0 {
+                #[allow(clippy::float_cmp, reason = "Comparing against exact constant -1.0")]
+                let inner = if coeff == -1.0 {
+                    rest
+                } else {
+                    Expr::product(vec![Expr::number(-coeff), rest])
+                };
+                return Some(Expr::product(vec![
+                    Expr::number(-1.0),
+                    Expr::func_symbol(get_symbol(KS.erf), inner),
+                ]));
+            }
+        }
+        None
+    }
+);
+
+rule_with_helpers!(
+    ErfComplementRule,
+    "erf_complement",
+    88,
+    Algebraic,
+    &[RuleExprKind::Sum],
+    helpers: {
+        /// Split `c*erf(u)` or `c*erfc(u)` into the function id, `u` and `c`
+        fn split_erf(term: &Expr) -> Option<(u64, Expr, f64)> {
+            let (coeff, base) = extract_coeff(term);
+            if let ExprKind::FunctionCall { name, args } = &base.kind
+                && (name.id() == KS.erf || name.id() == KS.erfc)
+                && args.len() == 1
+            {
+                return Some((name.id(), (*args[0]).clone(), coeff));
+            }
+            None
+        }
+    },
+    |expr: &Expr, _context: &RuleContext| {
+        // c*erf(u) + c*erfc(u) = c, and c - c*erf(u) = c*erfc(u) (likewise with erf and
+        // erfc swapped). The rewrite only ever folds towards erfc or erf, never spells
+        // erfc out as 1 - erf: that difference cancels catastrophically for large u.
+        let ExprKind::Sum(terms) = &expr.kind else {
+            return None;
+        };
+        #[allow(clippy::float_cmp, reason = "Coefficients must match exactly to cancel")]
+        for (i, term) in terms.iter().enumerate() {
+            let Some((id, arg, coeff)) = split_erf(term) else {
+                continue;
+            };
+            let partner = terms.iter().enumerate().find_map(|(j, other)| {
+                if j == i {
+                    return None;
+                }
+                if let ExprKind::Number(n) = other.kind
+                    && n == -coeff
+                {
+                    let complement = if id == KS.erf { KS.erfc } else { KS.erf };
+                    return Some((j, Expr::product(vec![
+                        Expr::number(n),
+                        Expr::func_symbol(get_symbol(complement), arg.clone()),
+                    ])));
+                }
+                let (other_id, other_arg, other_coeff) = split_erf(other)?;
+                (other_id != id && other_coeff == coeff && other_arg == arg)
+                    .then(|| (j, Expr::number(coeff)))
+            });
+            if let Some((j, replacement)) = partner {
+                let mut rest: Vec<Expr> = terms
+                    .iter()
+                    .enumerate()
+                    .filter(|&(k, _)| k != i && k != j)
+                    .map(|(_, kept)| (**kept).clone())
+                    .collect();
+                rest.push(replacement);
+                return Some(Expr::sum(rest));
+            }
+        }
+        None
+    }
+);
@@ -10,6 +10,8 @@ rule!(
     92,
     Algebraic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Pow(..)) || matches!(den.kind, ExprKind::Pow(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind {
             // Helper to check if a factor is present in an expression
@@ -65,6 +67,9 @@ rule!(
     86,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, exp)
+        if matches!(base.kind, ExprKind::Product(_) | ExprKind::Div(..))
+            && matches!(exp.kind, ExprKind::Number(_))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(base, exp) = &expr.kind {
             // Expand (a*b)^n -> a^n * b^n ONLY if expansion enables simplification
@@ -1,8 +1,12 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind, exprs_equivalent, extract_coeff, gcd};
+use super::{
+    Rule, RuleCategory, RuleContext, RuleExprKind, exact_rational, exprs_equivalent, extract_coeff,
+    gcd, is_known_positive, rational_expr, rational_sum,
+};
 use crate::EPSILON;
 use crate::core::Polynomial;
 use crate::core::arc_number;
-use crate::core::known_symbols::KS;
+use crate::core::known_symbols::{KS, is_known_constant_by_id};
+use crate::core::traits::{is_zero, near_integer};
 use crate::core::{Expr, ExprKind};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
@@ -88,7 +92,7 @@ impl Rule for FractionCancellationRule {
 
             fn is_safe_to_cancel(base: &Expr) -> bool {
                 match &base.kind {
-                    ExprKind::Number(n) => n.abs() > EPSILON,
+                    ExprKind::Number(n) => !is_zero(*n),
                     _ => false,
                 }
             }
@@ -121,15 +125,17 @@ impl Rule for FractionCancellationRule {
 
             // Simplify coefficients
             let ratio = num_coeff / den_coeff;
-            if ratio.abs() < EPSILON {
+            if is_zero(ratio) {
                 return Some(Arc::new(Expr::number(0.0)));
             }
 
-            if (ratio - ratio.round()).abs() < EPSILON {
-                num_coeff = ratio.round();
+            if let Some(n) = near_integer(ratio) {
+                num_coeff = n;
                 den_coeff = 1.0;
-            } else if (1.0 / ratio - (1.0 / ratio).round()).abs() < EPSILON {
-                let inv = (1.0 / ratio).round();
+            } else if let Some(inv) = near_integer(1.0 / ratio)
+                // Above 2^53 every float is an integer; 1/1e23 is not a simpler form of 1e-23
+                && inv.abs() < 9_007_199_254_740_992.0
+            {
                 if inv < 0.0 {
                     num_coeff = -1.0;
                     den_coeff = -inv;
@@ -149,14 +155,26 @@ impl Rule for FractionCancellationRule {
                     let (base_j, exp_j) = get_base_exp(&new_den_factors[j]);
 
                     if exprs_equivalent(&base_i, &base_j) {
-                        if context.domain_safe && !is_safe_to_cancel(&base_i) {
+                        if context.domain_safe
+                            && !is_safe_to_cancel(&base_i)
+                            && !is_known_positive(&base_i, context)
+                        {
                             break;
                         }
 
+                        let exact_diff = match (exact_rational(&exp_i), exact_rational(&exp_j)) {
+                            (Some(a), Some((p, q))) => {
+                                p.checked_neg().and_then(|neg| rational_sum(a, (neg, q)))
+                            }
+                            _ => None,
+                        };
                         let simplified_exp = if let (ExprKind::Number(n1), ExprKind::Number(n2)) =
                             (&exp_i.kind, &exp_j.kind)
                         {
                             Expr::number(n1 - n2)
+                        } else if let Some(diff) = exact_diff {
+                            // e.g. x / x^(1/3) -> x^(2/3) without rounding through 0.666...
+                            rational_expr(diff)
                         } else {
                             Expr::sum_from_arcs(vec![
                                 Arc::clone(&exp_i),
@@ -330,7 +348,7 @@ impl Rule for PerfectSquareRule {
 
     /// Only runs on sums with exactly 2 or 3 terms (quadratic patterns) or Poly with 3 terms.
     /// Most Sums have more terms and would immediately return None — skip them cheaply.
-    fn can_apply(&self, expr: &Arc<Expr>) -> bool {
+    fn precondition(&self, expr: &Expr) -> bool {
         match &expr.kind {
             ExprKind::Sum(terms) => matches!(terms.len(), 2 | 3),
             ExprKind::Poly(p) => p.terms().len() == 3,
@@ -402,14 +420,13 @@ impl Rule for PerfectSquareRule {
                     }
                     ExprKind::Number(n) => {
                         // Check if number is a perfect square (positive)
-                        if *n > 0.0 {
-                            let sqrt_n = n.sqrt();
-                            if (sqrt_n - sqrt_n.round()).abs() < EPSILON {
-                                // It's a perfect square constant, treat as square term (coeff=1, base=sqrt(n))
-                                // We treat '9' as 1*3^2. So coeff=1.0, base=Number(3)
-                                square_terms.push((1.0, Arc::new(Expr::number(sqrt_n.round()))));
-                                continue;
-                            }
+                        if *n > 0.0
+                            && let Some(sqrt_n) = near_integer(n.sqrt())
+                        {
+                            // It's a perfect square constant, treat as square term (coeff=1, base=sqrt(n))
+                            // We treat '9' as 1*3^2. So coeff=1.0, base=Number(3)
+                            square_terms.push((1.0, Arc::new(Expr::number(sqrt_n))));
+                            continue;
                         }
                         // Treat constant as linear term coeff? Or ignore?
                         // If it's not a square term, it must be part of linear term construction?
@@ -456,9 +473,7 @@ impl Rule for PerfectSquareRule {
                 let sqrt_c1 = (*c1).sqrt();
                 let sqrt_c2 = (*c2).sqrt();
 
-                if (sqrt_c1 - sqrt_c1.round()).abs() < EPSILON
-                    && (sqrt_c2 - sqrt_c2.round()).abs() < EPSILON
-                {
+                if near_integer(sqrt_c1).is_some() && near_integer(sqrt_c2).is_some() {
                     let expected_cross_abs = (2.0 * sqrt_c1 * sqrt_c2).abs();
                     let cross_coeff_abs = (*cross_coeff).abs();
 
@@ -536,11 +551,9 @@ rule_arc!(
                 }
             } else if let ExprKind::Number(n) = &e.kind
                 && *n > 0.0
+                && let Some(sqrt_n) = near_integer(n.sqrt())
             {
-                let sqrt_n = n.sqrt();
-                if (sqrt_n - sqrt_n.round()).abs() < EPSILON {
-                    return Some(Arc::new(Expr::number(sqrt_n.round())));
-                }
+                return Some(Arc::new(Expr::number(sqrt_n)));
             }
             None
         }
@@ -561,9 +574,8 @@ rule_arc!(
                 && *n < 0.0
             {
                 let pos_n = -n;
-                let sqrt_n = pos_n.sqrt();
-                if (sqrt_n - sqrt_n.round()).abs() < EPSILON {
-                    return Some(Arc::new(Expr::number(sqrt_n.round())));
+                if let Some(sqrt_n) = near_integer(pos_n.sqrt()) {
+                    return Some(Arc::new(Expr::number(sqrt_n)));
                 }
             }
             None
@@ -1021,6 +1033,11 @@ rule_arc!(
     40,
     Algebraic,
     &[RuleExprKind::Sum, RuleExprKind::Poly],
+    precondition: |expr: &Expr| match &expr.kind {
+        ExprKind::Sum(terms) => terms.len() == 2,
+        ExprKind::Poly(poly) => poly.terms().len() == 2,
+        _ => false,
+    },
     |expr: &Expr, _context: &RuleContext| {
         // Pattern: a^3 + 3a^2b + 3ab^2 + b^3 = (a+b)^3
         // Pattern: a^3 - 3a^2b + 3ab^2 - b^3 = (a-b)^3
@@ -1148,6 +1165,83 @@ rule_arc!(
     }
 );
 
+// =============================================================================
+// RATIONAL FUNCTION SIMPLIFICATION RULE
+// =============================================================================
+
+/// Highest degree a side of a fraction is expanded to by [`RationalSimplificationRule`];
+/// binomial coefficients up to this degree stay exact in `f64`
+const RATIONAL_MAX_DEGREE: u32 = 32;
+
+/// Rule for reducing rational functions of one symbol by the GCD of numerator and
+/// denominator
+///
+/// Both sides are expanded into polynomials in the first symbol of the numerator, so a
+/// denominator already factored into `(x + 1)^2` still meets
+/// `(x^3 + 3*x^2 + 3*x + 1)/(x + 1)^2` → `x + 1`. Dividing out the common factor removes
+/// the points where it vanishes from the domain's exclusions, so the rule alters the
+/// domain.
+pub struct RationalSimplificationRule;
+
+impl Rule for RationalSimplificationRule {
+    fn name(&self) -> &'static str {
+        "rational_simplification"
+    }
+
+    fn priority(&self) -> i32 {
+        // Before FractionCancellationRule (76), which would cancel factors one by one
+        77
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Algebraic
+    }
+
+    fn alters_domain(&self) -> bool {
+        true
+    }
+
+    fn applies_to(&self) -> &'static [RuleExprKind] {
+        &[RuleExprKind::Div]
+    }
+
+    fn precondition(&self, expr: &Expr) -> bool {
+        matches!(&expr.kind, ExprKind::Div(n, d)
+            if !matches!(n.kind, ExprKind::Number(_))
+            && !matches!(d.kind, ExprKind::Number(_)))
+    }
+
+    fn apply(&self, expr: &Arc<Expr>, _context: &RuleContext) -> Option<Arc<Expr>> {
+        let ExprKind::Div(num, den) = &expr.kind else {
+            return None;
+        };
+        let var = num.find_symbol(|s| !is_known_constant_by_id(s.id()))?;
+        let var = Arc::new(Expr::from_interned(var));
+        let num_poly = Polynomial::expand_in(num, &var, RATIONAL_MAX_DEGREE)?;
+        let den_poly = Polynomial::expand_in(den, &var, RATIONAL_MAX_DEGREE)?;
+        if num_poly.is_constant() || den_poly.is_constant() {
+            return None;
+        }
+
+        let gcd = num_poly.gcd(&den_poly)?;
+        if gcd.is_constant() {
+            return None;
+        }
+        let (new_num, num_rem) = num_poly.div_rem(&gcd)?;
+        let (new_den, den_rem) = den_poly.div_rem(&gcd)?;
+        if !num_rem.is_zero() || !den_rem.is_zero() {
+            return None;
+        }
+
+        let new_num_expr = new_num.to_expr();
+        let new_den_expr = new_den.to_expr();
+        if new_den_expr.is_one_num() {
+            return Some(Arc::new(new_num_expr));
+        }
+        Some(Arc::new(Expr::div_expr(new_num_expr, new_den_expr)))
+    }
+}
+
 // =============================================================================
 // POLYNOMIAL GCD SIMPLIFICATION RULE
 // =============================================================================
@@ -1176,7 +1270,7 @@ impl Rule for PolyGcdSimplifyRule {
 
     /// Skip if either side of the division is a plain number — polynomial GCD
     /// would immediately bail out via `is_constant()` checks anyway.
-    fn can_apply(&self, expr: &Arc<Expr>) -> bool {
+    fn precondition(&self, expr: &Expr) -> bool {
         matches!(&expr.kind, ExprKind::Div(n, d)
             if !matches!(n.kind, ExprKind::Number(_))
             && !matches!(d.kind, ExprKind::Number(_)))
@@ -1,9 +1,12 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind, is_known_positive};
 use crate::EPSILON;
 use crate::core::{Expr, ExprKind};
 use std::sync::Arc;
 
-rule_arc!(DivSelfRule, "div_self", 78, Algebraic, &[RuleExprKind::Div], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+rule_arc!(DivSelfRule, "div_self", 78, Algebraic, &[RuleExprKind::Div], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::Div(_, v)
+        if is_known_positive(v, context)),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::Div(u, v) = &expr.kind
         && u == v
     {
@@ -18,6 +21,8 @@ rule_arc!(
     92,
     Algebraic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Div(..)) || matches!(den.kind, ExprKind::Div(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind {
             // Case 1: (a/b)/(c/d) -> (a*d)/(b*c)
@@ -58,6 +63,8 @@ rule_arc!(
     91,
     Algebraic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(&num.kind, ExprKind::Sum(terms) if terms.len() == 2)),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, outer_den) = &expr.kind {
             // Check if num is a Sum containing a Div
@@ -96,6 +103,7 @@ rule_arc!(
     45,
     Algebraic,
     &[RuleExprKind::Sum],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Sum(terms) if terms.len() == 2),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Sum(terms) = &expr.kind
             && terms.len() == 2
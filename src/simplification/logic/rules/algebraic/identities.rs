@@ -1,41 +1,68 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
-use crate::core::known_symbols::KS;
-use crate::core::{Expr, ExprKind};
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind, is_known_positive};
+use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::{Expr, ExprKind, InverseCaveat};
+use crate::functions::Registry;
 use std::sync::Arc;
 
-rule_arc!(ExpLnRule, "exp_ln", 80, Algebraic, &[RuleExprKind::Function], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
-    if let ExprKind::FunctionCall { name, args } = &expr.kind
-        && name.id() == KS.exp
-        && args.len() == 1
-        && let ExprKind::FunctionCall {
-            name: inner_name,
-            args: inner_args,
-        } = &args[0].kind
-        && inner_name.id() == KS.ln
-        && inner_args.len() == 1
-    {
-        return Some(Arc::clone(&inner_args[0]));
+// f(g(x)) = x for inverse pairs, built-in or declared with `UserFunction::inverse`.
+// Pairs with a caveat only collapse outside domain-safe mode. acosh(cosh(x)) and
+// asech(sech(x)) are |x| everywhere, so they collapse to that in either mode.
+rule_arc!(
+    InverseCompositionRule,
+    "inverse_composition",
+    95,
+    Algebraic,
+    &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.len() == 1 && matches!(args[0].kind, ExprKind::FunctionCall { .. })),
+    |expr: &Expr, context: &RuleContext| {
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && args.len() == 1
+            && let ExprKind::FunctionCall {
+                name: inner_name,
+                args: inner_args,
+            } = &args[0].kind
+            && inner_args.len() == 1
+        {
+            let key = (name.id(), inner_name.id());
+            if Registry::inverse_is_abs(key.0, key.1) {
+                return Some(Arc::new(Expr::func_symbol(
+                    get_symbol(KS.abs),
+                    (*inner_args[0]).clone(),
+                )));
+            }
+            let caveat = Registry::inverse_caveat(key.0, key.1)
+                .or_else(|| context.custom_inverses.get(&key).copied())?;
+            if context.domain_safe && caveat != InverseCaveat::None {
+                return None;
+            }
+            return Some(Arc::clone(&inner_args[0]));
+        }
+        None
     }
-    None
-});
+);
 
-rule_arc!(LnExpRule, "ln_exp", 80, Algebraic, &[RuleExprKind::Function], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
-    if let ExprKind::FunctionCall { name, args } = &expr.kind
-        && name.id() == KS.ln
-        && args.len() == 1
-        && let ExprKind::FunctionCall {
-            name: inner_name,
-            args: inner_args,
-        } = &args[0].kind
-        && inner_name.id() == KS.exp
-        && inner_args.len() == 1
-    {
-        return Some(Arc::clone(&inner_args[0]));
-    }
-    None
-});
+/// Argument of the first `ln` among the factors of `expr`, the one the `*_ln` rules
+/// rewrite with
+fn first_ln_factor_arg(expr: &Expr) -> Option<&Expr> {
+    let ExprKind::Product(factors) = &expr.kind else {
+        return None;
+    };
+    factors.iter().find_map(|factor| match &factor.kind {
+        ExprKind::FunctionCall { name, args } if name.id() == KS.ln && args.len() == 1 => {
+            Some(args[0].as_ref())
+        }
+        _ => None,
+    })
+}
 
-rule_arc!(ExpMulLnRule, "exp_mul_ln", 80, Algebraic, &[RuleExprKind::Function], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+rule_arc!(ExpMulLnRule, "exp_mul_ln", 80, Algebraic, &[RuleExprKind::Function], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().and_then(|a| first_ln_factor_arg(a)).is_some_and(|x| is_known_positive(x, context))),
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.exp
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::FunctionCall { name, args } = &expr.kind
         && name.id() == KS.exp
         && args.len() == 1
@@ -73,7 +100,13 @@ rule_arc!(ExpMulLnRule, "exp_mul_ln", 80, Algebraic, &[RuleExprKind::Function],
     None
 });
 
-rule_arc!(EPowLnRule, "e_pow_ln", 85, Algebraic, &[RuleExprKind::Pow], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+rule_arc!(EPowLnRule, "e_pow_ln", 85, Algebraic, &[RuleExprKind::Pow], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(&exp.kind, ExprKind::FunctionCall { name, args }
+            if name.id() == KS.ln && args.len() == 1 && is_known_positive(&args[0], context))),
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(&base.kind, ExprKind::Symbol(s) if s.id() == KS.e)),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::Pow(base, exp) = &expr.kind
         && let ExprKind::Symbol(s) = &base.kind
         && s.id() == KS.e
@@ -86,7 +119,12 @@ rule_arc!(EPowLnRule, "e_pow_ln", 85, Algebraic, &[RuleExprKind::Pow], alters_do
     None
 });
 
-rule_arc!(EPowMulLnRule, "e_pow_mul_ln", 85, Algebraic, &[RuleExprKind::Pow], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+rule_arc!(EPowMulLnRule, "e_pow_mul_ln", 85, Algebraic, &[RuleExprKind::Pow], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if first_ln_factor_arg(exp).is_some_and(|x| is_known_positive(x, context))),
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(&base.kind, ExprKind::Symbol(s) if s.id() == KS.e)),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::Pow(base, exp) = &expr.kind
         && let ExprKind::Symbol(s) = &base.kind
         && s.id() == KS.e
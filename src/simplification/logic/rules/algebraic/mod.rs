@@ -4,6 +4,8 @@ pub mod abs_sign;
 pub mod canonicalization;
 /// Term combination and consolidation rules
 pub mod combination;
+/// Error function identities
+pub mod error_function;
 /// Expression expansion rules
 pub mod expansion;
 /// Factoring and decomposition rules
@@ -21,6 +23,7 @@ pub use rules::get_algebraic_rules;
 
 pub(super) use super::{
     Rule, RuleCategory, RuleContext, RuleExprKind, compare_expr, compare_mul_factors,
-    exprs_equivalent, extract_coeff, extract_coeff_arc, gcd, is_fractional_root_exponent,
-    is_known_non_negative,
+    exact_rational, exprs_equivalent, extract_coeff, extract_coeff_arc, gcd,
+    is_fractional_root_exponent, is_known_non_negative, is_known_positive, rational_expr,
+    rational_product, rational_sum, snap_exponent,
 };
@@ -1,10 +1,11 @@
 use super::{
-    Rule, RuleCategory, RuleContext, RuleExprKind, is_fractional_root_exponent,
-    is_known_non_negative,
+    Rule, RuleCategory, RuleContext, RuleExprKind, exact_rational, is_fractional_root_exponent,
+    is_known_non_negative, rational_expr, rational_product, rational_sum, snap_exponent,
 };
 use crate::EPSILON;
 use crate::core::arc_number;
 use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::traits::near_integer;
 use crate::core::{Expr, ExprKind};
 use std::sync::Arc;
 
@@ -14,6 +15,8 @@ rule!(
     80,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(_u, v) = &expr.kind
             && matches!(v.kind, ExprKind::Number(n) if n == 0.0)
@@ -30,6 +33,8 @@ rule_arc!(
     80,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(u, v) = &expr.kind {
             // Exact check for exponent == 1.0
@@ -43,12 +48,34 @@ rule_arc!(
     }
 );
 
+rule!(
+    ExponentSnapRule,
+    "exponent_snap",
+    85,
+    Algebraic,
+    &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(..))),
+    |expr: &Expr, _context: &RuleContext| {
+        // x^2.0000000000000004 -> x^2, so integer-power rules and codegen apply again
+        if let ExprKind::Pow(u, v) = &expr.kind
+            && let ExprKind::Number(n) = &v.kind
+            && let Some(k) = snap_exponent(*n)
+        {
+            return Some(Expr::pow_from_arcs(Arc::clone(u), arc_number(k)));
+        }
+        None
+    }
+);
+
 rule!(
     PowerPowerRule,
     "power_power",
     75,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(base.kind, ExprKind::Pow(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(u, v) = &expr.kind
             && let ExprKind::Pow(base, exp_inner) = &u.kind
@@ -97,8 +124,14 @@ rule!(
                 }
             }
 
-            // Create new exponent: exp_inner * v using Product
-            let new_exp = Expr::product_from_arcs(vec![Arc::clone(exp_inner), Arc::clone(v)]);
+            // Create new exponent: exp_inner * v, exactly when both are rationals
+            let new_exp = if let (Some(a), Some(b)) = (exact_rational(exp_inner), exact_rational(v))
+                && let Some(product) = rational_product(a, b)
+            {
+                rational_expr(product)
+            } else {
+                Expr::product_from_arcs(vec![Arc::clone(exp_inner), Arc::clone(v)])
+            };
 
             return Some(Expr::pow_from_arcs(Arc::clone(base), Arc::new(new_exp)));
         }
@@ -183,6 +216,8 @@ rule!(
     75,
     Algebraic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Pow(..)) && matches!(den.kind, ExprKind::Pow(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(u, v) = &expr.kind {
             // Check if both numerator and denominator are powers with the same base
@@ -266,8 +301,11 @@ rule_arc!(
                         )));
                     }
                 } else {
-                    // Sum all exponents using n-ary Sum
-                    let sum = Expr::sum_from_arcs(exponents);
+                    // Sum all exponents, exactly when they are all rationals
+                    let exact = exponents
+                        .iter()
+                        .try_fold((0, 1), |acc, e| rational_sum(acc, exact_rational(e)?));
+                    let sum = exact.map_or_else(|| Expr::sum_from_arcs(exponents), rational_expr);
                     result_factors.push(Arc::new(Expr::pow_from_arcs(base, Arc::new(sum))));
                 }
             }
@@ -295,6 +333,8 @@ rule!(
     55,
     Algebraic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Pow(..)) && matches!(den.kind, ExprKind::Pow(..))),
     |expr: &Expr, context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let (ExprKind::Pow(base_num, exp_num), ExprKind::Pow(base_den, exp_den)) =
@@ -302,8 +342,8 @@ rule!(
             && exp_num == exp_den
         {
             if context.domain_safe && is_fractional_root_exponent(exp_num) {
-                let num_non_neg = is_known_non_negative(base_num);
-                let den_non_neg = is_known_non_negative(base_den);
+                let num_non_neg = is_known_non_negative(base_num, context);
+                let den_non_neg = is_known_non_negative(base_den, context);
                 if !(num_non_neg && den_non_neg) {
                     return None;
                 }
@@ -341,8 +381,7 @@ rule_arc!(
                             if let (ExprKind::Number(base_val), ExprKind::Number(exp_val)) =
                                 (&base.kind, &exp.kind)
                             {
-                                let result = base_val.powf(*exp_val);
-                                result.is_finite() && (result - result.round()).abs() < EPSILON
+                                near_integer(base_val.powf(*exp_val)).is_some()
                             } else {
                                 false
                             }
@@ -355,8 +394,8 @@ rule_arc!(
                         }
 
                         if context.domain_safe && is_fractional_root_exponent(exp_1) {
-                            let left_non_neg = is_known_non_negative(base_1);
-                            let right_non_neg = is_known_non_negative(base_2);
+                            let left_non_neg = is_known_non_negative(base_1, context);
+                            let right_non_neg = is_known_non_negative(base_2, context);
                             if !(left_non_neg && right_non_neg) {
                                 continue;
                             }
@@ -399,6 +438,8 @@ rule!(
     90,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(_) | ExprKind::Div(..) | ExprKind::Product(_))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(base, exp) = &expr.kind {
             // Handle negative number exponent: x^-n -> 1/x^n
@@ -451,6 +492,8 @@ rule!(
     88,
     Algebraic,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(base.kind, ExprKind::Div(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(base, exp) = &expr.kind
             && let ExprKind::Div(num, den) = &base.kind
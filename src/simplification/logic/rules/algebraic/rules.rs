@@ -1,7 +1,8 @@
 use super::Rule;
 use super::abs_sign::{
-    AbsAbsRule, AbsNegRule, AbsNumericRule, AbsPowEvenRule, AbsSignMulRule, AbsSquareRule,
-    SignAbsRule, SignNumericRule, SignSignRule,
+    AbsAbsRule, AbsNegRule, AbsNonNegativeRule, AbsNumericRule, AbsPowEvenRule, AbsSignMulRule,
+    AbsSquareRule, HypotEqualRule, HypotZeroRule, SignAbsRule, SignNumericRule, SignPositiveRule,
+    SignSignRule,
 };
 use super::canonicalization::{
     CanonicalizeProductRule, CanonicalizeSumRule, SimplifyNegativeProductRule,
@@ -9,35 +10,36 @@ use super::canonicalization::{
 use super::combination::{
     CombineFactorsRule, CombineLikeTermsInSumRule, CombineTermsRule, ProductDivCombinationRule,
 };
+use super::error_function::{ErfComplementRule, ErfNegationRule};
 use super::expansion::{ExpandPowerForCancellationRule, PowerExpansionRule};
 use super::factoring::{
     CommonPowerFactoringRule, CommonTermFactoringRule, FactorDifferenceOfSquaresRule,
     FractionCancellationRule, NumericGcdFactoringRule, PerfectCubeRule, PerfectSquareRule,
-    PolyGcdSimplifyRule,
+    PolyGcdSimplifyRule, RationalSimplificationRule,
 };
 use super::fractions::{
     AddFractionRule, CombineNestedFractionRule, DivDivRule, DivSelfRule, FractionToEndRule,
 };
-use super::identities::{EPowLnRule, EPowMulLnRule, ExpLnRule, ExpMulLnRule, LnExpRule};
+use super::identities::{EPowLnRule, EPowMulLnRule, ExpMulLnRule, InverseCompositionRule};
 use super::powers::{
-    CommonExponentDivRule, CommonExponentProductRule, NegativeExponentToFractionRule,
-    PowerCollectionRule, PowerDivRule, PowerOfQuotientRule, PowerOneRule, PowerPowerRule,
-    PowerProductRule, PowerZeroRule,
+    CommonExponentDivRule, CommonExponentProductRule, ExponentSnapRule,
+    NegativeExponentToFractionRule, PowerCollectionRule, PowerDivRule, PowerOfQuotientRule,
+    PowerOneRule, PowerPowerRule, PowerProductRule, PowerZeroRule,
 };
 use std::sync::Arc;
 
 /// Get all algebraic rules in priority order
 pub fn get_algebraic_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
     vec![
-        // Exponential/logarithmic identities
-        Arc::new(ExpLnRule),
-        Arc::new(LnExpRule),
+        // Inverse compositions and exponential/logarithmic identities
+        Arc::new(InverseCompositionRule),
         Arc::new(ExpMulLnRule),
         Arc::new(EPowLnRule),
         Arc::new(EPowMulLnRule),
         // Power rules
         Arc::new(PowerZeroRule),
         Arc::new(PowerOneRule),
+        Arc::new(ExponentSnapRule),
         Arc::new(PowerPowerRule),
         Arc::new(PowerProductRule),
         Arc::new(PowerDivRule),
@@ -59,13 +61,21 @@ pub fn get_algebraic_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
         Arc::new(AbsNegRule),
         Arc::new(AbsSquareRule),
         Arc::new(AbsPowEvenRule),
+        Arc::new(AbsNonNegativeRule),
         Arc::new(SignSignRule),
         Arc::new(SignAbsRule),
+        Arc::new(SignPositiveRule),
         Arc::new(AbsSignMulRule),
+        Arc::new(HypotZeroRule),
+        Arc::new(HypotEqualRule),
+        // Error function rules
+        Arc::new(ErfNegationRule),
+        Arc::new(ErfComplementRule),
         // Expansion rules
         Arc::new(ExpandPowerForCancellationRule),
         Arc::new(PowerExpansionRule),
         // Factoring rules
+        Arc::new(RationalSimplificationRule),
         Arc::new(FractionCancellationRule),
         Arc::new(PerfectSquareRule),
         Arc::new(FactorDifferenceOfSquaresRule),
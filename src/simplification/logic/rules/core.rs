@@ -1,20 +1,19 @@
-use crate::core::BodyFn;
 use crate::core::Expr;
 use crate::core::ExprKind;
-use rustc_hash::FxHashMap;
+use crate::core::{BodyFn, InverseCaveat};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::sync::Arc;
 
-/// Macro to define a simplification rule with minimal boilerplate
+/// Shared expansion of the rule macros: the struct and its `Rule` impl.
 ///
-/// Supports 4 forms:
-/// - Basic: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], |expr, ctx| { ... })`
-/// - With targets: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], targets: &["fn"], |expr, ctx| { ... })`
-/// - With `alters_domain`: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], alters_domain: true, |expr, ctx| { ... })`
-/// - Both: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], alters_domain: true, targets: &["fn"], |expr, ctx| { ... })`
-macro_rules! rule {
-    // Basic form
-    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, $logic:expr) => {
+/// The optional parts are bracketed lists so each macro arm only fills in what its form
+/// declares. The `apply` parameters are named by the caller, which keeps them in the
+/// same hygiene context as the body that uses them.
+macro_rules! rule_impl {
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr,
+     alters: [$($alters:expr)?], assumes: [$($assumes:expr)?], targets: [$($targets:expr)?],
+     precondition: [$($pre:expr)?], apply($expr:ident, $context:ident) $body:block) => {
         pub struct $name;
         impl Rule for $name {
             fn name(&self) -> &'static str {
@@ -26,233 +25,166 @@ macro_rules! rule {
             fn category(&self) -> RuleCategory {
                 RuleCategory::$category
             }
+            $(
+                fn alters_domain(&self) -> bool {
+                    $alters
+                }
+            )?
+            $(
+                fn domain_assumed(&self, expr: &Expr, context: &RuleContext) -> bool {
+                    ($assumes)(expr, context)
+                }
+            )?
             fn applies_to(&self) -> &'static [RuleExprKind] {
                 $applies_to
             }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
+            $(
+                fn target_functions(&self) -> Vec<u64> {
+                    $targets.to_vec()
+                }
+            )?
+            $(
+                fn precondition(&self, expr: &Expr) -> bool {
+                    ($pre)(expr)
+                }
+            )?
+            fn apply(&self, $expr: &Arc<Expr>, $context: &RuleContext) -> Option<Arc<Expr>> $body
         }
     };
+}
+
+/// Macro to define a simplification rule with minimal boilerplate
+///
+/// Supports 9 forms:
+/// - Basic: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], |expr, ctx| { ... })`
+/// - With targets: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], targets: &["fn"], |expr, ctx| { ... })`
+/// - With `alters_domain`: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], alters_domain: true, |expr, ctx| { ... })`
+/// - Both: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], alters_domain: true, targets: &["fn"], |expr, ctx| { ... })`
+/// - With a precondition: `rule!(Name, "name", priority, Category, &[RuleExprKind::...], precondition: |expr| ..., |expr, ctx| { ... })`
+/// - With `alters_domain` or targets, followed by a precondition
+/// - With `alters_domain: true, assumes: |expr, ctx| ...`: the assumptions in `ctx` under
+///   which the rule may still run in domain-safe mode, optionally followed by a precondition
+macro_rules! rule {
+    // Basic form
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [], [], $logic);
+    };
     // With targets
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, targets: $targets:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn target_functions(&self) -> Vec<u64> {
-                $targets.to_vec()
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
-        }
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [$targets], [], $logic);
     };
     // With alters_domain
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn alters_domain(&self) -> bool {
-                $alters
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
-        }
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [], [], $logic);
     };
     // With alters_domain AND targets
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, targets: $targets:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn alters_domain(&self) -> bool {
-                $alters
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn target_functions(&self) -> Vec<u64> {
-                $targets.to_vec()
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [$targets], [], $logic);
+    };
+    // With alters_domain AND the assumptions under which it is safe
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, assumes: $assumes:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [$assumes], [], [], $logic);
+    };
+    // With alters_domain, assumptions AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, assumes: $assumes:expr, precondition: $pre:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [$assumes], [], [$pre], $logic);
+    };
+    // With precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, precondition: $pre:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [], [$pre], $logic);
+    };
+    // With alters_domain AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, precondition: $pre:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [], [$pre], $logic);
+    };
+    // With targets AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, targets: $targets:expr, precondition: $pre:expr, $logic:expr) => {
+        rule!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [$targets], [$pre], $logic);
+    };
+    (@build $name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, [$($alters:expr)?], [$($assumes:expr)?], [$($targets:expr)?], [$($pre:expr)?], $logic:expr) => {
+        rule_impl!($name, $rule_name, $priority, $category, $applies_to,
+            alters: [$($alters)?], assumes: [$($assumes)?], targets: [$($targets)?], precondition: [$($pre)?],
+            apply(expr, context) {
                 let _ = context;
                 ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
-        }
+            });
     };
 }
 
 /// Macro to define a simplification rule that returns `Option<Arc<Expr>>` directly.
 /// This avoids unnecessary wrapping when the result is already an Arc.
+/// Takes the same forms as [`rule!`].
 macro_rules! rule_arc {
     // Basic form
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context)
-            }
-        }
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [], [], $logic);
     };
     // With targets
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, targets: $targets:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn target_functions(&self) -> Vec<u64> {
-                $targets.to_vec()
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context)
-            }
-        }
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [$targets], [], $logic);
     };
     // With alters_domain
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn alters_domain(&self) -> bool {
-                $alters
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                ($logic)(expr.as_ref(), context)
-            }
-        }
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [], [], $logic);
     };
     // With alters_domain AND targets
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, targets: $targets:expr, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str {
-                $rule_name
-            }
-            fn priority(&self) -> i32 {
-                $priority
-            }
-            fn category(&self) -> RuleCategory {
-                RuleCategory::$category
-            }
-            fn alters_domain(&self) -> bool {
-                $alters
-            }
-            fn applies_to(&self) -> &'static [RuleExprKind] {
-                $applies_to
-            }
-            fn target_functions(&self) -> Vec<u64> {
-                $targets.to_vec()
-            }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [$targets], [], $logic);
+    };
+    // With alters_domain AND the assumptions under which it is safe
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, assumes: $assumes:expr, $logic:expr) => {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [$assumes], [], [], $logic);
+    };
+    // With alters_domain, assumptions AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, assumes: $assumes:expr, precondition: $pre:expr, $logic:expr) => {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [$assumes], [], [$pre], $logic);
+    };
+    // With precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, precondition: $pre:expr, $logic:expr) => {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [], [$pre], $logic);
+    };
+    // With alters_domain AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, precondition: $pre:expr, $logic:expr) => {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], [], [$pre], $logic);
+    };
+    // With targets AND precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, targets: $targets:expr, precondition: $pre:expr, $logic:expr) => {
+        rule_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], [$targets], [$pre], $logic);
+    };
+    (@build $name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, [$($alters:expr)?], [$($assumes:expr)?], [$($targets:expr)?], [$($pre:expr)?], $logic:expr) => {
+        rule_impl!($name, $rule_name, $priority, $category, $applies_to,
+            alters: [$($alters)?], assumes: [$($assumes)?], targets: [$($targets)?], precondition: [$($pre)?],
+            apply(expr, context) {
                 let _ = context;
                 ($logic)(expr.as_ref(), context)
-            }
-        }
+            });
     };
 }
 
 /// Macro for rules with helpers that return `Option<Arc<Expr>>` directly
 /// This avoids unnecessary wrapping when the result is already an Arc.
+/// A `precondition:` goes before `helpers:`; it cannot call the helpers.
 macro_rules! rule_with_helpers_arc {
     // Basic form
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, helpers: { $($helper:item)* }, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str { $rule_name }
-            fn priority(&self) -> i32 { $priority }
-            fn category(&self) -> RuleCategory { RuleCategory::$category }
-            fn applies_to(&self) -> &'static [RuleExprKind] { $applies_to }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                $($helper)*
-                let _ = context;
-                ($logic)(expr.as_ref(), context)
-            }
-        }
+        rule_with_helpers_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], { $($helper)* }, $logic);
     };
     // With alters_domain
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, helpers: { $($helper:item)* }, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str { $rule_name }
-            fn priority(&self) -> i32 { $priority }
-            fn category(&self) -> RuleCategory { RuleCategory::$category }
-            fn alters_domain(&self) -> bool { $alters }
-            fn applies_to(&self) -> &'static [RuleExprKind] { $applies_to }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
+        rule_with_helpers_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], { $($helper)* }, $logic);
+    };
+    // With precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, precondition: $pre:expr, helpers: { $($helper:item)* }, $logic:expr) => {
+        rule_with_helpers_arc!(@build $name, $rule_name, $priority, $category, $applies_to, [], [$pre], { $($helper)* }, $logic);
+    };
+    (@build $name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, [$($alters:expr)?], [$($pre:expr)?], { $($helper:item)* }, $logic:expr) => {
+        rule_impl!($name, $rule_name, $priority, $category, $applies_to,
+            alters: [$($alters)?], assumes: [], targets: [], precondition: [$($pre)?],
+            apply(expr, context) {
                 $($helper)*
                 let _ = context;
                 ($logic)(expr.as_ref(), context)
-            }
-        }
+            });
     };
 }
 
@@ -260,34 +192,24 @@ macro_rules! rule_with_helpers_arc {
 macro_rules! rule_with_helpers {
     // Basic form
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, helpers: { $($helper:item)* }, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str { $rule_name }
-            fn priority(&self) -> i32 { $priority }
-            fn category(&self) -> RuleCategory { RuleCategory::$category }
-            fn applies_to(&self) -> &'static [RuleExprKind] { $applies_to }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
-                let _ = context;
-                $($helper)*
-                ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
-        }
+        rule_with_helpers!(@build $name, $rule_name, $priority, $category, $applies_to, [], [], { $($helper)* }, $logic);
     };
     // With alters_domain
     ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, alters_domain: $alters:expr, helpers: { $($helper:item)* }, $logic:expr) => {
-        pub struct $name;
-        impl Rule for $name {
-            fn name(&self) -> &'static str { $rule_name }
-            fn priority(&self) -> i32 { $priority }
-            fn category(&self) -> RuleCategory { RuleCategory::$category }
-            fn alters_domain(&self) -> bool { $alters }
-            fn applies_to(&self) -> &'static [RuleExprKind] { $applies_to }
-            fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>> {
+        rule_with_helpers!(@build $name, $rule_name, $priority, $category, $applies_to, [$alters], [], { $($helper)* }, $logic);
+    };
+    // With precondition
+    ($name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, precondition: $pre:expr, helpers: { $($helper:item)* }, $logic:expr) => {
+        rule_with_helpers!(@build $name, $rule_name, $priority, $category, $applies_to, [], [$pre], { $($helper)* }, $logic);
+    };
+    (@build $name:ident, $rule_name:expr, $priority:expr, $category:ident, $applies_to:expr, [$($alters:expr)?], [$($pre:expr)?], { $($helper:item)* }, $logic:expr) => {
+        rule_impl!($name, $rule_name, $priority, $category, $applies_to,
+            alters: [$($alters)?], assumes: [], targets: [], precondition: [$($pre)?],
+            apply(expr, context) {
                 let _ = context;
                 $($helper)*
                 ($logic)(expr.as_ref(), context).map(Arc::new)
-            }
-        }
+            });
     };
 }
 
@@ -313,6 +235,8 @@ pub enum RuleExprKind {
     Derivative,
     /// Polynomial (don't trigger Sum rules)
     Poly,
+    /// Piecewise definition
+    Piecewise,
 }
 
 impl RuleExprKind {
@@ -329,6 +253,7 @@ impl RuleExprKind {
             ExprKind::FunctionCall { .. } => Self::Function,
             ExprKind::Derivative { .. } => Self::Derivative,
             ExprKind::Poly(_) => Self::Poly, // Poly has its own rules, don't trigger Sum rules
+            ExprKind::Piecewise { .. } => Self::Piecewise,
         }
     }
 }
@@ -339,10 +264,6 @@ pub trait Rule {
     fn name(&self) -> &'static str;
     /// Returns the priority of this rule (higher = applied first)
     fn priority(&self) -> i32;
-    #[allow(
-        dead_code,
-        reason = "Legacy for categorizzation maybe useful in the future"
-    )]
     /// Returns the category of this rule
     fn category(&self) -> RuleCategory;
 
@@ -351,6 +272,13 @@ pub trait Rule {
         false
     }
 
+    /// Whether the assumptions in `context` make this domain-altering rule safe at `expr`,
+    /// so it still runs in domain-safe mode (`x/x` when `x` is assumed positive).
+    /// Default: never.
+    fn domain_assumed(&self, _expr: &Expr, _context: &RuleContext) -> bool {
+        false
+    }
+
     /// Which expression kinds this rule can apply to.
     /// Rules will ONLY be checked against expressions matching these kinds.
     /// Default: all kinds (for backwards compatibility during migration)
@@ -367,10 +295,12 @@ pub trait Rule {
 
     /// Cheap structural pre-check called on cache-miss, before `apply()`.
     /// Return `false` to skip this rule without caching the result.
-    /// Implement this for rules whose `apply()` has an O(1) early-return condition —
+    /// Implement this for rules whose `apply()` has an O(1) early-return condition on
+    /// the node itself (a function name, a term count, the kind of a child) —
     /// avoids building the call frame and cache insertion for non-matching expressions.
+    /// It must not reject a node `apply()` would rewrite.
     /// Default: always run (no pre-check).
-    fn can_apply(&self, _expr: &Arc<Expr>) -> bool {
+    fn precondition(&self, _expr: &Expr) -> bool {
         true
     }
 
@@ -379,12 +309,11 @@ pub trait Rule {
     fn apply(&self, expr: &Arc<Expr>, context: &RuleContext) -> Option<Arc<Expr>>;
 }
 
-/// Categories of simplification rules
-#[allow(
-    dead_code,
-    reason = "Legacy for categorizzation maybe useful in the future"
-)]
+/// Categories of simplification rules, for selecting rules with
+/// [`Simplify::disable_category`](crate::Simplify::disable_category) and
+/// [`Simplify::only_categories`](crate::Simplify::only_categories)
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[non_exhaustive]
 pub enum RuleCategory {
     /// Constant folding, identities
     Numeric,
@@ -411,6 +340,7 @@ pub const ALL_EXPR_KINDS: &[RuleExprKind] = &[
     RuleExprKind::Function,
     RuleExprKind::Derivative,
     RuleExprKind::Poly,
+    RuleExprKind::Piecewise,
 ];
 
 /// Context passed to rules during application
@@ -421,8 +351,21 @@ pub struct RuleContext {
     pub depth: usize,
     /// Whether to apply only domain-safe transformations
     pub domain_safe: bool,
+    /// Whether indeterminate forms such as `inf - inf` fold to NaN
+    pub strict_ieee: bool,
+    /// Whether numeric folding must stay exact (decimals become fractions, inexact
+    /// results stay symbolic)
+    pub exact_arithmetic: bool,
     /// Custom function body definitions
     pub custom_bodies: Arc<FxHashMap<u64, BodyFn>>,
+    /// Custom inverse pairs keyed by `(outer id, inner id)`
+    pub custom_inverses: Arc<FxHashMap<(u64, u64), InverseCaveat>>,
+    /// Ids of symbols assumed to take integer values
+    pub integer_vars: Arc<FxHashSet<u64>>,
+    /// Ids of symbols assumed to be strictly positive
+    pub positive_vars: Arc<FxHashSet<u64>>,
+    /// Ids of symbols assumed to be zero or positive
+    pub non_negative_vars: Arc<FxHashSet<u64>>,
 }
 
 impl Debug for RuleContext {
@@ -430,10 +373,19 @@ impl Debug for RuleContext {
         f.debug_struct("RuleContext")
             .field("depth", &self.depth)
             .field("domain_safe", &self.domain_safe)
+            .field("strict_ieee", &self.strict_ieee)
+            .field("exact_arithmetic", &self.exact_arithmetic)
             .field(
                 "custom_bodies",
                 &format!("<{} functions>", self.custom_bodies.len()),
             )
+            .field(
+                "custom_inverses",
+                &format!("<{} pairs>", self.custom_inverses.len()),
+            )
+            .field("integer_vars", &self.integer_vars)
+            .field("positive_vars", &self.positive_vars)
+            .field("non_negative_vars", &self.non_negative_vars)
             .finish()
     }
 }
@@ -450,4 +402,31 @@ impl RuleContext {
         self.custom_bodies = Arc::new(custom_bodies);
         self
     }
+
+    /// Sets the custom inverse function pairs for this context.
+    pub fn with_custom_inverses(
+        mut self,
+        custom_inverses: FxHashMap<(u64, u64), InverseCaveat>,
+    ) -> Self {
+        self.custom_inverses = Arc::new(custom_inverses);
+        self
+    }
+
+    /// Sets the symbols assumed to take integer values.
+    pub fn with_integer_vars(mut self, integer_vars: FxHashSet<u64>) -> Self {
+        self.integer_vars = Arc::new(integer_vars);
+        self
+    }
+
+    /// Sets the symbols assumed to be strictly positive.
+    pub fn with_positive_vars(mut self, positive_vars: FxHashSet<u64>) -> Self {
+        self.positive_vars = Arc::new(positive_vars);
+        self
+    }
+
+    /// Sets the symbols assumed to be zero or positive.
+    pub fn with_non_negative_vars(mut self, non_negative_vars: FxHashSet<u64>) -> Self {
+        self.non_negative_vars = Arc::new(non_negative_vars);
+        self
+    }
 }
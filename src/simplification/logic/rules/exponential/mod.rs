@@ -1,4 +1,4 @@
 pub mod rules;
 pub use rules::get_exponential_rules;
 
-pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind, is_known_positive};
@@ -1,4 +1,4 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind, is_known_positive};
 use crate::EPSILON;
 use crate::core::known_symbols::{KS, get_symbol};
 use crate::core::{Expr, ExprKind};
@@ -11,6 +11,8 @@ rule!(
     Exponential,
     &[RuleExprKind::Function],
     targets: &[KS.ln],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.ln
@@ -75,6 +77,8 @@ rule!(
     Exponential,
     &[RuleExprKind::Function],
     targets: &[KS.exp],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.exp
@@ -91,44 +95,19 @@ rule!(
     }
 );
 
-rule!(ExpLnIdentityRule, "exp_ln_identity", 90, Exponential, &[RuleExprKind::Function], alters_domain: true, targets: &[KS.exp], |expr: &Expr, _context: &RuleContext| {
-    if let ExprKind::FunctionCall { name, args } = &expr.kind
-        && name.id() == KS.exp
-        && args.len() == 1
-        && let ExprKind::FunctionCall {
-            name: inner_name,
-            args: inner_args,
-        } = &args[0].kind
-        && inner_name.id() == KS.ln
-        && inner_args.len() == 1
-    {
-        return Some((*inner_args[0]).clone());
-    }
-    None
-});
-
-rule!(LnExpIdentityRule, "ln_exp_identity", 90, Exponential, &[RuleExprKind::Function], alters_domain: true, targets: &[KS.ln], |expr: &Expr, _context: &RuleContext| {
+rule!(LnEPowIdentityRule, "ln_e_pow_identity", 90, Exponential, &[RuleExprKind::Function], targets: &[KS.ln],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Pow(..)))),
+    |expr: &Expr, _context: &RuleContext| {
+    // ln(e^x) = x; ln(exp(x)) is handled by the generic inverse composition rule
     if let ExprKind::FunctionCall { name, args } = &expr.kind
         && name.id() == KS.ln
         && args.len() == 1
+        && let ExprKind::Pow(base, exp) = &args[0].kind
+        && let ExprKind::Symbol(b) = &base.kind
+        && b.id() == KS.e
     {
-        // Check for ln(exp(x))
-        if let ExprKind::FunctionCall {
-            name: inner_name,
-            args: inner_args,
-        } = &args[0].kind
-            && inner_name.id() == KS.exp
-            && inner_args.len() == 1
-        {
-            return Some((*inner_args[0]).clone());
-        }
-        // Check for ln(e^x)
-        if let ExprKind::Pow(base, exp) = &args[0].kind
-            && let ExprKind::Symbol(b) = &base.kind
-            && b.id() == KS.e
-        {
-            return Some((**exp).clone());
-        }
+        return Some((**exp).clone());
     }
     None
 });
@@ -178,7 +157,7 @@ rule!(
                             let is_positive = matches!(&base.kind,
                                 ExprKind::FunctionCall { name: fn_name, .. }
                                 if fn_name.id() == KS.exp || fn_name.id() == KS.cosh
-                            );
+                            ) || is_known_positive(base, context);
                             if !is_positive {
                                 return None;
                             }
@@ -195,7 +174,8 @@ rule!(
                 }
 
                 // For non-integer or symbolic exponents, only apply in aggressive mode
-                if context.domain_safe {
+                // or for a base assumed positive
+                if context.domain_safe && !is_known_positive(base, context) {
                     return None;
                 }
 
@@ -215,7 +195,7 @@ rule!(
             } = &content.kind
             {
                 if inner_name.id() == KS.sqrt && inner_args.len() == 1 {
-                    if context.domain_safe {
+                    if context.domain_safe && !is_known_positive(&inner_args[0], context) {
                         return None;
                     }
                     return Some(Expr::product(vec![
@@ -229,7 +209,7 @@ rule!(
                 }
                 // log(cbrt(x)) = (1/3) * log(x)
                 if inner_name.id() == KS.cbrt && inner_args.len() == 1 {
-                    if context.domain_safe {
+                    if context.domain_safe && !is_known_positive(&inner_args[0], context) {
                         return None;
                     }
                     return Some(Expr::product(vec![
@@ -327,6 +307,7 @@ fn get_ln_arg(expr: &Expr) -> Option<Expr> {
 }
 
 rule_with_helpers!(LogCombinationRule, "log_combination", 85, Exponential, &[RuleExprKind::Sum],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Sum(terms) if terms.len() == 2),
     helpers: {
         // Helper defined above
     },
@@ -368,8 +349,7 @@ pub fn get_exponential_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
         Arc::new(LnERule),
         Arc::new(ExpZeroRule),
         Arc::new(ExpToEPowRule),
-        Arc::new(ExpLnIdentityRule),
-        Arc::new(LnExpIdentityRule),
+        Arc::new(LnEPowIdentityRule),
         Arc::new(LogPowerRule),
         Arc::new(LogBaseRules),
         Arc::new(LogCombinationRule),
@@ -14,6 +14,8 @@ rule!(
     80,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Sum(..)) && matches!(den.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(numerator, denominator) = &expr.kind {
             #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
@@ -53,6 +55,8 @@ rule!(
     80,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Sum(..)) && matches!(den.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(numerator, denominator) = &expr.kind {
             #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
@@ -138,6 +142,8 @@ rule!(
     80,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Number(..)) && matches!(den.kind, ExprKind::Sum(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(numerator, denominator) = &expr.kind {
             #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
@@ -165,6 +171,8 @@ rule!(
     80,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(numerator, denominator) = &expr.kind {
             // Exact check for numerator 2.0 (csch definition)
@@ -12,6 +12,8 @@ rule!(
     Hyperbolic,
     &[RuleExprKind::Function],
     targets: &[KS.sinh],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sinh
@@ -36,6 +38,8 @@ rule!(
     Hyperbolic,
     &[RuleExprKind::Function],
     targets: &[KS.cosh],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cosh
@@ -60,6 +64,8 @@ rule!(
     Hyperbolic,
     &[RuleExprKind::Function],
     targets: &[KS.sinh],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sinh
@@ -97,6 +103,8 @@ rule!(
     Hyperbolic,
     &[RuleExprKind::Function],
     targets: &[KS.cosh],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cosh
@@ -128,6 +136,8 @@ rule!(
     Hyperbolic,
     &[RuleExprKind::Function],
     targets: &[KS.tanh],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.tanh
@@ -158,78 +168,6 @@ rule!(
     }
 );
 
-rule!(
-    SinhAsinhIdentityRule,
-    "sinh_asinh_identity",
-    95,
-    Hyperbolic,
-    &[RuleExprKind::Function],
-    targets: &[KS.sinh],
-    |expr: &Expr, _context: &RuleContext| {
-        if let ExprKind::FunctionCall { name, args } = &expr.kind
-            && name.id() == KS.sinh
-            && args.len() == 1
-            && let ExprKind::FunctionCall {
-                name: inner_name,
-                args: inner_args,
-            } = &args[0].kind
-            && inner_name.id() == KS.asinh
-            && inner_args.len() == 1
-        {
-            return Some((*inner_args[0]).clone());
-        }
-        None
-    }
-);
-
-rule!(
-    CoshAcoshIdentityRule,
-    "cosh_acosh_identity",
-    95,
-    Hyperbolic,
-    &[RuleExprKind::Function],
-    targets: &[KS.cosh],
-    |expr: &Expr, _context: &RuleContext| {
-        if let ExprKind::FunctionCall { name, args } = &expr.kind
-            && name.id() == KS.cosh
-            && args.len() == 1
-            && let ExprKind::FunctionCall {
-                name: inner_name,
-                args: inner_args,
-            } = &args[0].kind
-            && inner_name.id() == KS.acosh
-            && inner_args.len() == 1
-        {
-            return Some((*inner_args[0]).clone());
-        }
-        None
-    }
-);
-
-rule!(
-    TanhAtanhIdentityRule,
-    "tanh_atanh_identity",
-    95,
-    Hyperbolic,
-    &[RuleExprKind::Function],
-    targets: &[KS.tanh],
-    |expr: &Expr, _context: &RuleContext| {
-        if let ExprKind::FunctionCall { name, args } = &expr.kind
-            && name.id() == KS.tanh
-            && args.len() == 1
-            && let ExprKind::FunctionCall {
-                name: inner_name,
-                args: inner_args,
-            } = &args[0].kind
-            && inner_name.id() == KS.atanh
-            && inner_args.len() == 1
-        {
-            return Some((*inner_args[0]).clone());
-        }
-        None
-    }
-);
-
 // Hyperbolic identity: cosh^2(x) - sinh^2(x) = 1 and related
 rule!(
     HyperbolicIdentityRule,
@@ -237,6 +175,8 @@ rule!(
     95,
     Hyperbolic,
     &[RuleExprKind::Sum, RuleExprKind::Product],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Sum(operands) | ExprKind::Product(operands)
+        if operands.len() == 2),
     |expr: &Expr, _context: &RuleContext| {
         // cosh^2(x) - sinh^2(x) = 1 (as Sum([cosh^2(x), Product([-1, sinh^2(x)])]))
         if let ExprKind::Sum(terms) = &expr.kind
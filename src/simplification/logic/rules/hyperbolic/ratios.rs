@@ -10,6 +10,9 @@ rule!(
     85,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::FunctionCall { .. })
+            && matches!(den.kind, ExprKind::FunctionCall { .. })),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::FunctionCall {
@@ -41,6 +44,9 @@ rule!(
     85,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::FunctionCall { .. })
+            && matches!(den.kind, ExprKind::FunctionCall { .. })),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::FunctionCall {
@@ -72,6 +78,8 @@ rule!(
     85,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::Number(n) = &num.kind
@@ -95,6 +103,8 @@ rule!(
     85,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::Number(n) = &num.kind
@@ -118,6 +128,8 @@ rule!(
     85,
     Hyperbolic,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::Number(n) = &num.kind
@@ -4,9 +4,8 @@ use super::conversions::{
     TanhFromExpRule,
 };
 use super::identities::{
-    CoshAcoshIdentityRule, CoshNegationRule, CoshZeroRule, HyperbolicIdentityRule,
-    HyperbolicTripleAngleRule, SinhAsinhIdentityRule, SinhNegationRule, SinhZeroRule,
-    TanhAtanhIdentityRule, TanhNegationRule,
+    CoshNegationRule, CoshZeroRule, HyperbolicIdentityRule, HyperbolicTripleAngleRule,
+    SinhNegationRule, SinhZeroRule, TanhNegationRule,
 };
 use super::ratios::{
     CoshSinhToCothRule, OneCoshToSechRule, OneSinhToCschRule, OneTanhToCothRule, SinhCoshToTanhRule,
@@ -19,9 +18,6 @@ pub fn get_hyperbolic_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
         // High priority rules first
         Arc::new(SinhZeroRule),
         Arc::new(CoshZeroRule),
-        Arc::new(SinhAsinhIdentityRule),
-        Arc::new(CoshAcoshIdentityRule),
-        Arc::new(TanhAtanhIdentityRule),
         Arc::new(SinhNegationRule),
         Arc::new(CoshNegationRule),
         Arc::new(TanhNegationRule),
@@ -3,14 +3,18 @@
 #[macro_use]
 mod core;
 mod registry;
+mod user;
 
 // Re-exports
 pub(super) use super::helpers::{
-    compare_expr, compare_mul_factors, exprs_equivalent, extract_coeff, extract_coeff_arc, gcd,
-    is_fractional_root_exponent, is_known_non_negative,
+    compare_expr, compare_mul_factors, exact_rational, exprs_equivalent, extract_coeff,
+    extract_coeff_arc, gcd, is_fractional_root_exponent, is_known_non_negative, is_known_positive,
+    rational_expr, rational_product, rational_sum, snap_exponent,
 };
+pub use core::RuleCategory;
 pub(super) use core::*;
-pub(super) use registry::*;
+pub(in crate::simplification) use registry::RuleRegistry;
+pub use user::UserRule;
 
 /// Numeric simplification rules
 pub mod numeric;
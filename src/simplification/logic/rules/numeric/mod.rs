@@ -1,4 +1,4 @@
 pub mod rules;
 pub use rules::get_numeric_rules;
 
-pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind, gcd, rational_expr};
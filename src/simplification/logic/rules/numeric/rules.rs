@@ -1,11 +1,71 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind, gcd, rational_expr};
 use crate::EPSILON;
 use crate::core::Expr;
 use crate::core::ExprKind;
-use crate::core::known_symbols::KS;
+use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::traits::{near_integer, small_rational};
 use crate::functions::Registry;
 use std::sync::Arc;
 
+// ===== Non-finite Propagation Rules (Priority 100) =====
+//
+// Registered ahead of the identity and folding rules so `inf` and `nan` literals follow
+// IEEE 754 arithmetic:
+// - `nan` absorbs sums, products and quotients: `x*nan -> nan`, `x + nan -> nan`
+// - an infinity absorbs finite numbers: `inf + 2 -> inf`
+// - `inf - inf` stays symbolic, or becomes `nan` when the context is `strict_ieee`
+
+rule!(
+    NanPropagationRule,
+    "nan_propagation",
+    100,
+    Numeric,
+    &[RuleExprKind::Sum, RuleExprKind::Product, RuleExprKind::Div],
+    |expr: &Expr, _context: &RuleContext| {
+        let has_nan = match &expr.kind {
+            ExprKind::Sum(operands) | ExprKind::Product(operands) => {
+                operands.iter().any(|o| o.is_nan_num())
+            }
+            ExprKind::Div(u, v) => u.is_nan_num() || v.is_nan_num(),
+            _ => false,
+        };
+        has_nan.then(|| Expr::number(f64::NAN))
+    }
+);
+
+rule!(
+    InfiniteSumRule,
+    "infinite_sum",
+    100,
+    Numeric,
+    &[RuleExprKind::Sum],
+    |expr: &Expr, context: &RuleContext| {
+        if let ExprKind::Sum(terms) = &expr.kind {
+            let mut positive = false;
+            let mut negative = false;
+            let mut all_numeric = true;
+            for term in terms {
+                match term.as_number() {
+                    Some(n) if n.is_infinite() => {
+                        positive |= n > 0.0;
+                        negative |= n < 0.0;
+                    }
+                    Some(_) => {}
+                    None => all_numeric = false,
+                }
+            }
+            return match (positive, negative) {
+                // inf - inf is indeterminate
+                (true, true) => context.strict_ieee.then(|| Expr::number(f64::NAN)),
+                (true, false) if all_numeric => Some(Expr::number(f64::INFINITY)),
+                (false, true) if all_numeric => Some(Expr::number(f64::NEG_INFINITY)),
+                _ => None,
+            };
+        }
+        None
+    }
+);
+
 // ===== Identity Rules for Sum (Priority 100) =====
 
 rule!(
@@ -118,6 +178,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(_, den)
+        if matches!(den.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(u, v) = &expr.kind {
             // Exact check for 1.0 to simplify division
@@ -136,6 +198,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(u, _v) = &expr.kind
             && matches!(&u.kind, ExprKind::Number(n) if *n == 0.0)
@@ -154,6 +218,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(_u, v) = &expr.kind
             && matches!(&v.kind, ExprKind::Number(n) if *n == 0.0)
@@ -170,6 +236,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(_, exp)
+        if matches!(exp.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(u, v) = &expr.kind {
             // Exact check for 1.0 exponent
@@ -188,6 +256,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(base.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(u, v) = &expr.kind
             && matches!(&u.kind, ExprKind::Number(n) if *n == 0.0)
@@ -206,6 +276,8 @@ rule!(
     100,
     Numeric,
     &[RuleExprKind::Pow],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, _)
+        if matches!(base.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Pow(u, _v) = &expr.kind {
             // Exact check for 1.0 base
@@ -218,6 +290,74 @@ rule!(
     }
 );
 
+// `tau` is only a name for `2*pi`; rewriting it up front lets the `pi` rules
+// (trigonometric exact values, cancellation) see through it
+rule!(
+    TauRule,
+    "tau_to_two_pi",
+    100,
+    Numeric,
+    &[RuleExprKind::Symbol],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Symbol(s) if s.id() == KS.tau),
+    |expr: &Expr, _context: &RuleContext| {
+        matches!(&expr.kind, ExprKind::Symbol(s) if s.id() == KS.tau)
+            .then(|| Expr::mul_expr(Expr::number(2.0), Expr::from_interned(get_symbol(KS.pi))))
+    }
+);
+
+// In exact mode a non-integral number stands for the fraction it was written as, so
+// `0.1 + 0.2` folds through the rational rules to `3/10` instead of `0.30000000000000004`
+rule!(
+    ExactDecimalRule,
+    "exact_decimal",
+    100,
+    Numeric,
+    &[RuleExprKind::Number],
+    precondition: |expr: &Expr| matches!(expr.kind, ExprKind::Number(n) if n.fract() != 0.0),
+    |expr: &Expr, context: &RuleContext| {
+        if !context.exact_arithmetic {
+            return None;
+        }
+        let ExprKind::Number(n) = &expr.kind else {
+            return None;
+        };
+        small_rational(*n).map(rational_expr)
+    }
+);
+
+// ===== Piecewise Branch Rule (Priority 100) =====
+//
+// Branches whose condition is decided between two numbers are resolved: a false one can
+// never be taken and is dropped, and a true one ends the chain as the new default.
+
+rule!(
+    PiecewiseBranchRule,
+    "piecewise_branches",
+    100,
+    Numeric,
+    &[RuleExprKind::Piecewise],
+    |expr: &Expr, _context: &RuleContext| {
+        if let ExprKind::Piecewise { branches, default } = &expr.kind {
+            let mut kept = Vec::with_capacity(branches.len());
+            let mut resolved = Arc::clone(default);
+            for (condition, value) in branches {
+                match condition.as_bool() {
+                    Some(false) => {}
+                    Some(true) => {
+                        resolved = Arc::clone(value);
+                        break;
+                    }
+                    None => kept.push((condition.clone(), Arc::clone(value))),
+                }
+            }
+            if kept.len() < branches.len() || !Arc::ptr_eq(&resolved, default) {
+                return Some(Expr::piecewise_from_arcs(kept, resolved));
+            }
+        }
+        None
+    }
+);
+
 // ===== Normalization Rule (Priority 95) =====
 
 rule!(
@@ -226,6 +366,8 @@ rule!(
     95,
     Numeric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(_, den)
+        if matches!(den.kind, ExprKind::Number(_) | ExprKind::Product(_))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind {
             // Check if denominator is negative number
@@ -284,6 +426,11 @@ rule!(
                 }
             }
 
+            // Opposite infinities are left to `infinite_sum`
+            if num_sum.is_nan() {
+                return None;
+            }
+
             // Only fold if we have numeric values that simplified away
             if has_numeric && non_numeric.len() < terms.len() {
                 if non_numeric.is_empty() {
@@ -385,15 +532,15 @@ rule!(
     90,
     Numeric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Number(..)) && matches!(den.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(u, v) = &expr.kind
             && let (ExprKind::Number(a), ExprKind::Number(b)) = (&u.kind, &v.kind)
             && *b != 0.0
+            && let Some(n) = near_integer(a / b)
         {
-            let result = a / b;
-            if (result - result.round()).abs() < EPSILON {
-                return Some(Expr::number(result.round()));
-            }
+            return Some(Expr::number(n));
         }
         None
     }
@@ -405,20 +552,33 @@ rule!(
     90,
     Numeric,
     &[RuleExprKind::Pow],
-    |expr: &Expr, _context: &RuleContext| {
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Pow(base, exp)
+        if matches!(base.kind, ExprKind::Number(..)) && matches!(exp.kind, ExprKind::Number(..))),
+    |expr: &Expr, context: &RuleContext| {
         if let ExprKind::Pow(u, v) = &expr.kind
             && let (ExprKind::Number(a), ExprKind::Number(b)) = (&u.kind, &v.kind)
         {
             let result = a.powf(*b);
-            if !result.is_nan() && !result.is_infinite() {
-                return Some(Expr::number(result));
+            if result.is_nan() || result.is_infinite() {
+                return None;
+            }
+            if context.exact_arithmetic && result.fract() != 0.0 {
+                // Only `n^-k` has an exact fractional value: `1/n^k`
+                let denominator = a.powf(-b);
+                return (*b < 0.0
+                    && denominator.fract() == 0.0
+                    && denominator.abs() < 9_007_199_254_740_992.0)
+                    .then(|| Expr::div_expr(Expr::number(1.0), Expr::number(denominator)));
             }
+            return Some(Expr::number(result));
         }
         None
     }
 );
 
 rule_with_helpers!(FractionSimplifyRule, "fraction_simplify", 80, Numeric, &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::Number(..)) && matches!(den.kind, ExprKind::Number(..))),
     helpers: {
         const fn gcd(mut a: i64, mut b: i64) -> i64 {
             while b != 0 {
@@ -476,22 +636,87 @@ rule_with_helpers!(FractionSimplifyRule, "fraction_simplify", 80, Numeric, &[Rul
     }
 );
 
+rule_with_helpers!(FractionCoefficientGcdRule, "fraction_coefficient_gcd", 80, Numeric, &[RuleExprKind::Div],
+    helpers: {
+        /// Integer every numeric coefficient of `expr` is a multiple of: a number, the
+        /// leading number of a product, or the content of an integer polynomial
+        fn int_content(expr: &Expr) -> Option<i64> {
+            let to_int = |n: f64| {
+                #[allow(clippy::cast_possible_truncation, reason = "Integral value below 2^53 checked first")]
+                (n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0).then_some(n as i64)
+            };
+            match &expr.kind {
+                ExprKind::Number(n) => to_int(*n),
+                ExprKind::Product(factors) => match factors.first().map(|f| &f.kind) {
+                    Some(ExprKind::Number(n)) => to_int(*n),
+                    _ => None,
+                },
+                ExprKind::Poly(poly) => poly
+                    .terms()
+                    .iter()
+                    .try_fold(0, |acc, &(_, c)| Some(gcd(acc, to_int(c)?))),
+                _ => None,
+            }
+        }
+
+        /// `expr` with its coefficients divided by `divisor`, a factor of its content
+        #[allow(clippy::cast_precision_loss, reason = "Contents are below 2^53")]
+        fn divide_content(expr: &Expr, divisor: i64) -> Expr {
+            let divisor = divisor as f64;
+            match &expr.kind {
+                ExprKind::Number(n) => Expr::number(n / divisor),
+                ExprKind::Product(factors) => {
+                    let mut factors = factors.clone();
+                    if let ExprKind::Number(n) = &factors[0].kind {
+                        factors[0] = Arc::new(Expr::number(n / divisor));
+                    }
+                    Expr::product_from_arcs(factors)
+                }
+                ExprKind::Poly(poly) => Expr::poly(poly.div_scalar(divisor)),
+                _ => expr.clone(),
+            }
+        }
+    },
+    |expr: &Expr, _context: &RuleContext| {
+        // `6*x/4 -> 3*x/2`: the cross-multiplication in fraction sums leaves such
+        // coefficients behind (`x/3 + x/6 -> 9*x/18`)
+        if let ExprKind::Div(u, v) = &expr.kind
+            && !matches!((&u.kind, &v.kind), (ExprKind::Number(_), ExprKind::Number(_)))
+            && let (Some(a), Some(b)) = (int_content(u), int_content(v))
+        {
+            let common = gcd(a, b);
+            if common <= 1 {
+                return None;
+            }
+            let numerator = divide_content(u, common);
+            let denominator = divide_content(v, common);
+            #[allow(clippy::float_cmp, reason = "Comparing against exact constant 1.0")]
+            if denominator.as_number() == Some(1.0) {
+                return Some(numerator);
+            }
+            return Some(Expr::div_expr(numerator, denominator));
+        }
+        None
+    }
+);
+
 rule!(
     PerfectSquareRule,
     "perfect_square",
     95,
     Numeric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.sqrt
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sqrt
             && args.len() == 1
             && let ExprKind::Number(n) = &args[0].kind
+            && let Some(s) = near_integer(n.sqrt())
         {
-            let s = n.sqrt();
-            if (s - s.round()).abs() < EPSILON {
-                return Some(Expr::number(s.round()));
-            }
+            return Some(Expr::number(s));
         }
         None
     }
@@ -503,16 +728,17 @@ rule!(
     95,
     Numeric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.cbrt
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cbrt
             && args.len() == 1
             && let ExprKind::Number(n) = &args[0].kind
+            && let Some(c) = near_integer(n.cbrt())
         {
-            let c = n.cbrt();
-            if (c - c.round()).abs() < EPSILON {
-                return Some(Expr::number(c.round()));
-            }
+            return Some(Expr::number(c));
         }
         None
     }
@@ -524,6 +750,8 @@ rule!(
     80,
     Numeric,
     &[RuleExprKind::Product],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Product(factors)
+        if factors.len() == 2),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Product(factors) = &expr.kind
             && factors.len() == 2
@@ -577,8 +805,8 @@ rule!(
 
                 // Only evaluate if result is an integer
                 // This preserves symbolic forms like sqrt(2), ln(10), etc.
-                if (result - result.round()).abs() < EPSILON {
-                    return Some(Expr::number(result.round()));
+                if let Some(n) = near_integer(result) {
+                    return Some(Expr::number(n));
                 }
 
                 // Non-integer result: keep symbolic form
@@ -598,15 +826,20 @@ rule!(
 /// Get all numeric rules in priority order
 pub fn get_numeric_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
     vec![
+        Arc::new(NanPropagationRule),
+        Arc::new(InfiniteSumRule),
         Arc::new(SumIdentityRule),
         Arc::new(ProductZeroRule),
         Arc::new(ProductIdentityRule),
         Arc::new(DivOneRule),
         Arc::new(ZeroDivRule),
+        Arc::new(PiecewiseBranchRule),
         Arc::new(PowZeroRule),
         Arc::new(PowOneRule),
         Arc::new(ZeroPowRule),
         Arc::new(OnePowRule),
+        Arc::new(TauRule),
+        Arc::new(ExactDecimalRule),
         Arc::new(EvaluateNumericFunctionRule), // Integrated evaluation
         Arc::new(NormalizeSignDivRule),
         Arc::new(ConstantFoldSumRule),
@@ -614,6 +847,7 @@ pub fn get_numeric_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
         Arc::new(ConstantFoldDivRule),
         Arc::new(ConstantFoldPowRule),
         Arc::new(FractionSimplifyRule),
+        Arc::new(FractionCoefficientGcdRule),
         Arc::new(PerfectSquareRule),
         Arc::new(PerfectCubeRule),
         Arc::new(ProductHalfRule),
@@ -5,6 +5,7 @@ use super::hyperbolic::get_hyperbolic_rules;
 use super::numeric::get_numeric_rules;
 use super::root::get_root_rules;
 use super::trigonometric::get_trigonometric_rules;
+use super::user::{UserRule, UserRuleAdapter};
 use rustc_hash::FxHashMap;
 use std::cmp::Reverse;
 use std::sync::Arc;
@@ -46,6 +47,34 @@ impl RuleRegistry {
         // Note: Rules are sorted by priority in order_by_dependencies()
     }
 
+    /// A registry holding only the rules of `self` for which `keep` holds, in the same
+    /// order
+    #[must_use]
+    pub fn filtered(&self, keep: impl Fn(&dyn Rule) -> bool) -> Self {
+        let mut registry = Self::new();
+        registry.rules = self
+            .rules
+            .iter()
+            .filter(|rule| keep(rule.as_ref()))
+            .cloned()
+            .collect();
+        registry.build_kind_index();
+        registry
+    }
+
+    /// `self` with the user rules merged in by priority; among equal priorities the
+    /// rules of `self` come first
+    #[must_use]
+    pub fn with_user_rules(mut self, extra: &[Arc<dyn UserRule>]) -> Self {
+        self.rules.extend(extra.iter().map(|rule| {
+            Arc::new(UserRuleAdapter(Arc::clone(rule))) as Arc<dyn Rule + Send + Sync>
+        }));
+        self.rules_by_func.clear();
+        self.generic_func_rules.clear();
+        self.order_by_dependencies();
+        self
+    }
+
     /// Build the kind index after ordering rules
     pub fn order_by_dependencies(&mut self) {
         // Sort by priority descending (higher priority runs first)
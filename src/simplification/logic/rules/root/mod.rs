@@ -1,4 +1,6 @@
 pub mod rules;
-pub use rules::get_root_rules;
+pub use rules::{get_root_rules, rationalize_denominators};
 
-pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+pub(super) use super::{
+    Rule, RuleCategory, RuleContext, RuleExprKind, is_known_non_negative, is_known_positive,
+};
@@ -1,7 +1,10 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use super::{
+    Rule, RuleCategory, RuleContext, RuleExprKind, is_known_non_negative, is_known_positive,
+};
 use crate::EPSILON;
 use crate::core::known_symbols::{KS, get_symbol};
-use crate::core::{Expr, ExprKind};
+use crate::core::traits::near_integer;
+use crate::core::{Expr, ExprKind, piecewise_parts};
 use std::sync::Arc;
 
 rule!(
@@ -10,6 +13,9 @@ rule!(
     85,
     Root,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.sqrt
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Pow(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sqrt
@@ -37,12 +43,7 @@ rule!(
                         if *b == 0.0 {
                             new_exp
                         } else {
-                            let result = a / b;
-                            if (result - result.round()).abs() < EPSILON {
-                                Expr::number(result.round())
-                            } else {
-                                new_exp
-                            }
+                            near_integer(a / b).map_or(new_exp, Expr::number)
                         }
                     } else {
                         new_exp
@@ -72,6 +73,9 @@ rule!(
     85,
     Root,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.cbrt
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Pow(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cbrt
@@ -88,12 +92,7 @@ rule!(
                         if *b == 0.0 {
                             new_exp
                         } else {
-                            let result = a / b;
-                            if (result - result.round()).abs() < EPSILON {
-                                Expr::number(result.round())
-                            } else {
-                                new_exp
-                            }
+                            near_integer(a / b).map_or(new_exp, Expr::number)
                         }
                     } else {
                         new_exp
@@ -115,7 +114,20 @@ rule!(
     }
 );
 
-rule!(SqrtProductRule, "sqrt_product", 56, Root, &[RuleExprKind::Product], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+/// Argument of `expr` if it is a `sqrt` call
+fn sqrt_arg(expr: &Expr) -> Option<&Expr> {
+    match &expr.kind {
+        ExprKind::FunctionCall { name, args } if name.id() == KS.sqrt && args.len() == 1 => {
+            Some(&args[0])
+        }
+        _ => None,
+    }
+}
+
+rule!(SqrtProductRule, "sqrt_product", 56, Root, &[RuleExprKind::Product], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::Product(factors)
+        if factors.iter().filter_map(|f| sqrt_arg(f)).all(|arg| is_known_non_negative(arg, context))),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::Product(factors) = &expr.kind {
         // Check for sqrt(a) * sqrt(b) among factors
         for (i, f1) in factors.iter().enumerate() {
@@ -154,7 +166,14 @@ rule!(SqrtProductRule, "sqrt_product", 56, Root, &[RuleExprKind::Product], alter
     None
 });
 
-rule!(SqrtDivRule, "sqrt_div", 56, Root, &[RuleExprKind::Div], alters_domain: true, |expr: &Expr, _context: &RuleContext| {
+rule!(SqrtDivRule, "sqrt_div", 56, Root, &[RuleExprKind::Div], alters_domain: true,
+    assumes: |expr: &Expr, context: &RuleContext| matches!(&expr.kind, ExprKind::Div(u, v)
+        if sqrt_arg(u).is_some_and(|arg| is_known_non_negative(arg, context))
+            && sqrt_arg(v).is_some_and(|arg| is_known_positive(arg, context))),
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::FunctionCall { .. })
+            && matches!(den.kind, ExprKind::FunctionCall { .. })),
+    |expr: &Expr, _context: &RuleContext| {
     if let ExprKind::Div(u, v) = &expr.kind {
         // Check for sqrt(a) / sqrt(b)
         if let (
@@ -190,6 +209,8 @@ rule!(
     50,
     Root,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, .. }
+        if name.id() == KS.sqrt || name.id() == KS.cbrt),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && args.len() == 1
@@ -216,6 +237,9 @@ rule!(
     84,
     Root,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if name.id() == KS.sqrt
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
     |expr: &Expr, _context: &RuleContext| {
         // sqrt(a * x^2) → |x| * sqrt(a)
         if let ExprKind::FunctionCall { name, args } = &expr.kind
@@ -260,6 +284,189 @@ rule!(
     }
 );
 
+/// Whether `expr` contains a root: a `sqrt`/`cbrt` call or a fractional power.
+fn has_radical(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::FunctionCall { name, args } => {
+            name.id() == KS.sqrt || name.id() == KS.cbrt || args.iter().any(|a| has_radical(a))
+        }
+        ExprKind::Pow(base, exp) => {
+            matches!(exp.kind, ExprKind::Number(n) if n.fract() != 0.0)
+                || !matches!(exp.kind, ExprKind::Number(_)) && has_radical(exp)
+                || has_radical(base)
+        }
+        ExprKind::Sum(children) | ExprKind::Product(children) => {
+            children.iter().any(|c| has_radical(c))
+        }
+        ExprKind::Div(num, den) => has_radical(num) || has_radical(den),
+        ExprKind::Poly(poly) => has_radical(poly.base()),
+        ExprKind::Piecewise { branches, default } => {
+            piecewise_parts(branches, default).any(|part| has_radical(part))
+        }
+        ExprKind::Number(_) | ExprKind::Symbol(_) | ExprKind::Derivative { .. } => false,
+    }
+}
+
+/// `sqrt(u)` with a radical-free radicand.
+fn is_simple_sqrt(expr: &Expr) -> bool {
+    matches!(
+        &expr.kind,
+        ExprKind::FunctionCall { name, args }
+            if name.id() == KS.sqrt && args.len() == 1 && !has_radical(&args[0])
+    )
+}
+
+/// A single square root times radical-free factors: `sqrt(u)`, `-3*a*sqrt(u)`.
+fn is_radical_term(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Product(factors) => {
+            factors.iter().filter(|f| is_simple_sqrt(f)).count() == 1
+                && factors.iter().all(|f| is_simple_sqrt(f) || !has_radical(f))
+        }
+        _ => is_simple_sqrt(expr),
+    }
+}
+
+/// Whether a term is written with a leading minus sign.
+fn is_negative_term(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Number(n) => *n < 0.0,
+        ExprKind::Product(factors) => {
+            matches!(factors.first().map(|f| &f.kind), Some(ExprKind::Number(n)) if *n < 0.0)
+        }
+        _ => false,
+    }
+}
+
+/// `-term`, folding the sign into a leading numeric coefficient.
+fn negate_term(expr: &Expr) -> Expr {
+    match &expr.kind {
+        ExprKind::Number(n) => Expr::number(-n),
+        ExprKind::Product(factors) => match factors.split_first() {
+            Some((first, rest)) if let ExprKind::Number(n) = first.kind => {
+                let mut negated = vec![Expr::number(-n)];
+                negated.extend(rest.iter().map(|f| (**f).clone()));
+                Expr::product(negated)
+            }
+            _ => Expr::product(vec![Expr::number(-1.0), expr.clone()]),
+        },
+        _ => Expr::product(vec![Expr::number(-1.0), expr.clone()]),
+    }
+}
+
+/// Square of a term, with `sqrt(u)^2 = u` taken factor by factor.
+fn square_term(expr: &Expr) -> Expr {
+    match &expr.kind {
+        ExprKind::FunctionCall { args, .. } if is_simple_sqrt(expr) => (*args[0]).clone(),
+        ExprKind::Product(factors) => {
+            Expr::product(factors.iter().map(|f| square_term(f)).collect())
+        }
+        ExprKind::Number(n) => Expr::number(n * n),
+        _ => Expr::pow_static(expr.clone(), Expr::number(2.0)),
+    }
+}
+
+/// The factor that clears the radicals from `den`, and `den` times that factor.
+///
+/// Declines anything but a single square root (times radical-free factors) or a sum of
+/// two terms, at least one of which is such a square root.
+fn rationalizing_factor(den: &Expr) -> Option<(Expr, Expr)> {
+    if is_radical_term(den) {
+        // r*sqrt(u) -> multiply by sqrt(u), leaving r*u
+        let ExprKind::Product(factors) = &den.kind else {
+            return Some((den.clone(), square_term(den)));
+        };
+        let root = factors.iter().find(|f| is_simple_sqrt(f))?;
+        let cleared = factors
+            .iter()
+            .map(|f| {
+                if f == root {
+                    square_term(f)
+                } else {
+                    (**f).clone()
+                }
+            })
+            .collect();
+        return Some(((**root).clone(), Expr::product(cleared)));
+    }
+
+    let ExprKind::Sum(terms) = &den.kind else {
+        return None;
+    };
+    let [first, second] = terms.as_slice() else {
+        return None;
+    };
+    if !terms.iter().all(|t| is_radical_term(t) || !has_radical(t))
+        || !terms.iter().any(|t| is_radical_term(t))
+    {
+        return None;
+    }
+
+    // Lead with a positive radical so the conjugate reads sqrt(a) ± b
+    let key = |t: &Expr| (is_negative_term(t), !is_radical_term(t));
+    let (lead, other) = if key(second) < key(first) {
+        (second, first)
+    } else {
+        (first, second)
+    };
+    // Difference of squares: (a + b)(a - b) = a^2 - b^2
+    let conjugate = Expr::sum(vec![(**lead).clone(), negate_term(other)]);
+    let product = Expr::sum(vec![square_term(lead), negate_term(&square_term(other))]);
+    Some((conjugate, product))
+}
+
+rule!(
+    RationalizeDenominatorRule,
+    "rationalize_denominator",
+    40,
+    Root,
+    &[RuleExprKind::Div],
+    |expr: &Expr, _context: &RuleContext| {
+        // n/sqrt(u) -> n*sqrt(u)/u,  n/(a + sqrt(u)) -> n*(sqrt(u) - a)/(u - a^2)
+        let ExprKind::Div(num, den) = &expr.kind else {
+            return None;
+        };
+        let (factor, den) = rationalizing_factor(den)?;
+        let num = Expr::mul_expr((**num).clone(), factor);
+
+        if den.is_one_num() {
+            return Some(num);
+        }
+        if den.is_neg_one_num() {
+            return Some(negate_term(&num));
+        }
+        // Cancel a numerator factor equal to the new denominator
+        if let ExprKind::Product(factors) = &num.kind
+            && let Some(i) = factors.iter().position(|f| **f == den)
+        {
+            let rest = factors
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, f)| (**f).clone())
+                .collect();
+            return Some(Expr::product(rest));
+        }
+        Some(Expr::div_expr(num, den))
+    }
+);
+
+/// Rationalize every denominator of `expr`, innermost first.
+///
+/// [`RationalizeDenominatorRule`] is not part of [`get_root_rules`]: the engine would
+/// fold `sqrt(x)/x` straight back into `1/sqrt(x)` (and `FractionCancellationRule`
+/// would cancel the conjugate again), so the rule runs once over the finished,
+/// prettified result instead.
+pub fn rationalize_denominators(expr: &Expr) -> Expr {
+    let context = RuleContext::default();
+    expr.map(|node| {
+        let node = Arc::new(node.clone());
+        RationalizeDenominatorRule
+            .apply(&node, &context)
+            .map_or_else(|| (*node).clone(), |out| (*out).clone())
+    })
+}
+
 /// Get all root simplification rules in priority order
 pub fn get_root_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
     vec![
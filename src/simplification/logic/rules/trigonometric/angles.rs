@@ -11,6 +11,7 @@ rule_with_helpers_arc!(
     85,
     Trigonometric,
     &[RuleExprKind::Sum],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Sum(terms) if terms.len() == 2),
     helpers: {
         // Helper to extract negated term
         fn extract_negated_arc(term: &Expr) -> Option<Arc<Expr>> {
@@ -99,6 +100,7 @@ rule_with_helpers_arc!(
     70,
     Trigonometric,
     &[RuleExprKind::Sum],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Sum(terms) if terms.len() == 2),
     helpers: {
         fn get_fn_pow_symbol_arc(expr: &Expr, power: f64) -> Option<(InternedSymbol, Arc<Expr>)> {
             if let ExprKind::Pow(base, exp) = &expr.kind
@@ -300,6 +302,8 @@ rule_arc!(
     90,
     Trigonometric,
     &[RuleExprKind::Product],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Product(factors)
+        if factors.len() == 2),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Product(factors) = &expr.kind
             && factors.len() == 2
@@ -416,3 +420,234 @@ rule_arc!(
         None
     }
 );
+
+/// `expr` scaled by `coeff`, without a unit factor
+fn scaled(coeff: f64, expr: Arc<Expr>) -> Arc<Expr> {
+    if (coeff - 1.0).abs() < EPSILON {
+        expr
+    } else {
+        Arc::new(Expr::product_from_arcs(vec![
+            Arc::new(Expr::number(coeff)),
+            expr,
+        ]))
+    }
+}
+
+/// The sum `terms` with the terms at `used` replaced by `folded`
+fn replace_terms(terms: &[Arc<Expr>], used: &[usize], folded: Arc<Expr>) -> Arc<Expr> {
+    let mut rest: Vec<Arc<Expr>> = terms
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !used.contains(i))
+        .map(|(_, term)| Arc::clone(term))
+        .collect();
+    if rest.is_empty() {
+        return folded;
+    }
+    rest.push(folded);
+    Arc::new(Expr::sum_from_arcs(rest))
+}
+
+rule_with_helpers_arc!(
+    TrigCosSumDifferenceRule,
+    "trig_cos_sum_difference",
+    70,
+    Trigonometric,
+    &[RuleExprKind::Sum],
+    helpers: {
+        // Arguments (a, b) of a product f(a)*f(b) of the trig function f
+        fn trig_pair(expr: &Expr, func: u64) -> Option<(Arc<Expr>, Arc<Expr>)> {
+            if let ExprKind::Product(factors) = &expr.kind
+                && let [first, second] = factors.as_slice()
+                && let ExprKind::FunctionCall { name: n1, args: a1 } = &first.kind
+                && let ExprKind::FunctionCall { name: n2, args: a2 } = &second.kind
+                && n1.id() == func
+                && n2.id() == func
+                && a1.len() == 1
+                && a2.len() == 1
+            {
+                return Some((Arc::clone(&a1[0]), Arc::clone(&a2[0])));
+            }
+            None
+        }
+    },
+    |expr: &Expr, _context: &RuleContext| {
+        // k*cos(x)cos(y) - k*sin(x)sin(y) = k*cos(x+y)
+        // k*cos(x)cos(y) + k*sin(x)sin(y) = k*cos(x-y)
+        // The two terms may sit anywhere in a longer sum
+        let ExprKind::Sum(terms) = &expr.kind else {
+            return None;
+        };
+        for (i, cos_term) in terms.iter().enumerate() {
+            let (c1, r1) = extract_coeff_arc(cos_term);
+            let Some((x, y)) = trig_pair(&r1, KS.cos) else {
+                continue;
+            };
+            for (j, sin_term) in terms.iter().enumerate() {
+                let (c2, r2) = extract_coeff_arc(sin_term);
+                let Some((u, v)) = trig_pair(&r2, KS.sin) else {
+                    continue;
+                };
+                if !((x == u && y == v) || (x == v && y == u)) {
+                    continue;
+                }
+                let angle = if (c1 + c2).abs() < EPSILON {
+                    Expr::sum_from_arcs(vec![x, y])
+                } else if (c1 - c2).abs() < EPSILON {
+                    Expr::sum_from_arcs(vec![
+                        x,
+                        Arc::new(Expr::product_from_arcs(vec![Arc::new(Expr::number(-1.0)), y])),
+                    ])
+                } else {
+                    continue;
+                };
+                let folded = Arc::new(Expr::func_multi_from_arcs_symbol(
+                    get_symbol(KS.cos),
+                    vec![Arc::new(angle)],
+                ));
+                return Some(replace_terms(terms, &[i, j], scaled(c1, folded)));
+            }
+        }
+        None
+    }
+);
+
+rule_with_helpers_arc!(
+    CosDoubleAngleConstantRule,
+    "cos_double_angle_constant",
+    85,
+    Trigonometric,
+    &[RuleExprKind::Sum],
+    helpers: {
+        // Argument of f(x)^2 for the trig function f
+        fn square_arg(expr: &Expr, func: u64) -> Option<Arc<Expr>> {
+            if let ExprKind::Pow(base, exp) = &expr.kind
+                && let ExprKind::FunctionCall { name, args } = &base.kind
+                && name.id() == func
+                && args.len() == 1
+                && matches!(exp.kind, ExprKind::Number(n) if (n - 2.0).abs() < EPSILON)
+            {
+                return Some(Arc::clone(&args[0]));
+            }
+            None
+        }
+    },
+    |expr: &Expr, _context: &RuleContext| {
+        // n - 2n*sin(x)^2 = n*cos(2x)
+        // 2n*cos(x)^2 - n = n*cos(2x)
+        let ExprKind::Sum(terms) = &expr.kind else {
+            return None;
+        };
+        let (i, n) = terms.iter().enumerate().find_map(|(i, term)| match term.kind {
+            ExprKind::Number(n) => Some((i, n)),
+            _ => None,
+        })?;
+        for (j, term) in terms.iter().enumerate() {
+            let (k, rest) = extract_coeff_arc(term);
+            if 2.0_f64.mul_add(n, k).abs() >= EPSILON {
+                continue;
+            }
+            let (arg, coeff) = if let Some(arg) = square_arg(&rest, KS.sin) {
+                (arg, n)
+            } else if let Some(arg) = square_arg(&rest, KS.cos) {
+                (arg, -n)
+            } else {
+                continue;
+            };
+            let doubled = Arc::new(Expr::product_from_arcs(vec![Arc::new(Expr::number(2.0)), arg]));
+            let folded = Arc::new(Expr::func_multi_from_arcs_symbol(
+                get_symbol(KS.cos),
+                vec![doubled],
+            ));
+            return Some(replace_terms(terms, &[i, j], scaled(coeff, folded)));
+        }
+        None
+    }
+);
+
+/// `sin` and `cos` of `angle`, expanded over its sums and double angles
+fn expand_sin_cos(angle: &Arc<Expr>) -> (Arc<Expr>, Arc<Expr>) {
+    let product = |factors: Vec<Arc<Expr>>| Arc::new(Expr::product_from_arcs(factors));
+    let neg = |e: Arc<Expr>| product(vec![Arc::new(Expr::number(-1.0)), e]);
+    if let ExprKind::Sum(terms) = &angle.kind
+        && let [first, rest @ ..] = terms.as_slice()
+        && !rest.is_empty()
+    {
+        // sin(a+b) = sin(a)cos(b) + cos(a)sin(b), cos(a+b) = cos(a)cos(b) - sin(a)sin(b)
+        let rest = if let [only] = rest {
+            Arc::clone(only)
+        } else {
+            Arc::new(Expr::sum_from_arcs(rest.to_vec()))
+        };
+        let (s1, c1) = expand_sin_cos(first);
+        let (s2, c2) = expand_sin_cos(&rest);
+        let sin = Expr::sum_from_arcs(vec![
+            product(vec![Arc::clone(&s1), Arc::clone(&c2)]),
+            product(vec![Arc::clone(&c1), Arc::clone(&s2)]),
+        ]);
+        let cos = Expr::sum_from_arcs(vec![product(vec![c1, c2]), neg(product(vec![s1, s2]))]);
+        return (Arc::new(sin), Arc::new(cos));
+    }
+    if !matches!(angle.kind, ExprKind::Number(_)) {
+        let (coeff, base) = extract_coeff_arc(angle);
+        if (coeff - 2.0).abs() < EPSILON {
+            // sin(2u) = 2 sin(u)cos(u), cos(2u) = cos(u)^2 - sin(u)^2
+            let (s, c) = expand_sin_cos(&base);
+            let two = Arc::new(Expr::number(2.0));
+            let square = |e: Arc<Expr>| Arc::new(Expr::pow_from_arcs(e, Arc::clone(&two)));
+            let sin = product(vec![Arc::clone(&two), Arc::clone(&s), Arc::clone(&c)]);
+            let cos = Expr::sum_from_arcs(vec![square(c), neg(square(s))]);
+            return (sin, Arc::new(cos));
+        }
+        if (coeff + 1.0).abs() < EPSILON {
+            // sin(-u) = -sin(u), cos(-u) = cos(u)
+            let (s, c) = expand_sin_cos(&base);
+            return (neg(s), c);
+        }
+    }
+    let call = |func| {
+        Arc::new(Expr::func_multi_from_arcs_symbol(
+            get_symbol(func),
+            vec![Arc::clone(angle)],
+        ))
+    };
+    (call(KS.sin), call(KS.cos))
+}
+
+rule_arc!(
+    TrigAngleExpansionRule,
+    "trig_angle_expansion",
+    10,
+    Trigonometric,
+    &[RuleExprKind::Function],
+    |expr: &Expr, _context: &RuleContext| {
+        // The inverse of the folding rules: sin/cos of sums and double angles
+        if let ExprKind::FunctionCall { name, args } = &expr.kind
+            && args.len() == 1
+            && (name.id() == KS.sin || name.id() == KS.cos)
+        {
+            let (sin, cos) = expand_sin_cos(&args[0]);
+            let expanded = if name.id() == KS.sin { sin } else { cos };
+            return (*expanded != *expr).then_some(expanded);
+        }
+        None
+    }
+);
+
+/// Expand `sin` and `cos` of sums and double angles everywhere in `expr`.
+///
+/// [`TrigAngleExpansionRule`] is not part of [`get_trigonometric_rules`]: it undoes
+/// [`TrigSumDifferenceRule`], [`TrigCosSumDifferenceRule`] and the double-angle rules,
+/// so the engine would fold its output straight back. Like the rationalization of
+/// denominators, it runs once over the finished result instead.
+///
+/// [`get_trigonometric_rules`]: super::get_trigonometric_rules
+pub fn expand_trig_angles(expr: &Expr) -> Expr {
+    let context = RuleContext::default();
+    expr.map(|node| {
+        let node = Arc::new(node.clone());
+        TrigAngleExpansionRule
+            .apply(&node, &context)
+            .map_or_else(|| (*node).clone(), |out| (*out).clone())
+    })
+}
@@ -1,10 +1,18 @@
-use super::{Rule, RuleCategory, RuleContext, RuleExprKind, approx_eq, get_numeric_value, is_pi};
+use super::{
+    Rule, RuleCategory, RuleContext, RuleExprKind, approx_eq, get_numeric_value,
+    is_multiple_of_two_pi, is_pi,
+};
 use crate::EPSILON;
 use crate::core::known_symbols::{KS, get_symbol};
 use crate::core::{Expr, ExprKind};
 use std::f64::consts::PI;
 use std::sync::Arc;
 
+/// `2k*pi` written with the `pi` symbol; numeric arguments are left to evaluation
+fn is_symbolic_two_pi_multiple(arg: &Expr) -> bool {
+    !matches!(arg.kind, ExprKind::Number(_)) && is_multiple_of_two_pi(arg)
+}
+
 rule_arc!(
     SinZeroRule,
     "sin_zero",
@@ -12,6 +20,8 @@ rule_arc!(
     Trigonometric,
     &[RuleExprKind::Function],
     targets: &[KS.sin],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sin
@@ -36,6 +46,8 @@ rule_arc!(
     Trigonometric,
     &[RuleExprKind::Function],
     targets: &[KS.cos],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cos
@@ -55,6 +67,8 @@ rule_arc!(
     Trigonometric,
     &[RuleExprKind::Function],
     targets: &[KS.tan],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Number(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.tan
@@ -78,7 +92,7 @@ rule_arc!(
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sin
             && args.len() == 1
-            && is_pi(&args[0])
+            && (is_pi(&args[0]) || is_symbolic_two_pi_multiple(&args[0]))
         {
             return Some(Arc::new(Expr::number(0.0)));
         }
@@ -97,9 +111,13 @@ rule_arc!(
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cos
             && args.len() == 1
-            && is_pi(&args[0])
         {
-            return Some(Arc::new(Expr::number(-1.0)));
+            if is_pi(&args[0]) {
+                return Some(Arc::new(Expr::number(-1.0)));
+            }
+            if is_symbolic_two_pi_multiple(&args[0]) {
+                return Some(Arc::new(Expr::number(1.0)));
+            }
         }
         None
     }
@@ -112,6 +130,8 @@ rule_arc!(
     Trigonometric,
     &[RuleExprKind::Function],
     targets: &[KS.sin],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Div(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.sin
@@ -137,6 +157,8 @@ rule_arc!(
     Trigonometric,
     &[RuleExprKind::Function],
     targets: &[KS.cos],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Div(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && name.id() == KS.cos
@@ -294,6 +316,8 @@ rule_arc!(
     85,
     Trigonometric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::Number(n) = &num.kind
@@ -336,6 +360,8 @@ rule_arc!(
     85,
     Trigonometric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, _)
+        if matches!(num.kind, ExprKind::Number(..))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::Number(n) = &num.kind
@@ -377,6 +403,9 @@ rule_arc!(
     85,
     Trigonometric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::FunctionCall { .. })
+            && matches!(den.kind, ExprKind::FunctionCall { .. })),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::FunctionCall {
@@ -408,6 +437,9 @@ rule_arc!(
     85,
     Trigonometric,
     &[RuleExprKind::Div],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::Div(num, den)
+        if matches!(num.kind, ExprKind::FunctionCall { .. })
+            && matches!(den.kind, ExprKind::FunctionCall { .. })),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::Div(num, den) = &expr.kind
             && let ExprKind::FunctionCall {
@@ -5,85 +5,93 @@ use crate::core::known_symbols::{KS, get_symbol};
 use crate::core::{Expr, ExprKind};
 use std::sync::Arc;
 
-rule_arc!(
+rule_with_helpers_arc!(
     PythagoreanIdentityRule,
     "pythagorean_identity",
-    80,
+    88,
     Trigonometric,
     &[RuleExprKind::Sum],
-    |expr: &Expr, _context: &RuleContext| {
-        if let ExprKind::Sum(terms) = &expr.kind
-            && terms.len() == 2
-        {
-            let u = &terms[0];
-            let v = &terms[1];
-
-            // sin^2(x) + cos^2(x) = 1
-            if let (ExprKind::Pow(sin_base, sin_exp), ExprKind::Pow(cos_base, cos_exp)) =
-                (&u.kind, &v.kind)
-                && {
-                    // Exact check for power 2.0 (square)
-                    #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
-                    let is_two = matches!(&sin_exp.kind, ExprKind::Number(n) if *n == 2.0);
-                    is_two
+    helpers: {
+        /// Split `c*sin^2(u)` or `c*cos^2(u)` into the function id, `u` and `c`
+        fn split_square(term: &Expr) -> Option<(u64, Arc<Expr>, Expr)> {
+            fn sin_or_cos(base: &Expr) -> Option<(u64, Arc<Expr>)> {
+                if let ExprKind::FunctionCall { name, args } = &base.kind
+                    && (name.id() == KS.sin || name.id() == KS.cos)
+                    && args.len() == 1
+                {
+                    return Some((name.id(), Arc::clone(&args[0])));
                 }
-                && {
-                    // Exact check for power 2.0 (square)
-                    #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
-                    let is_two = matches!(&cos_exp.kind, ExprKind::Number(n) if *n == 2.0);
-                    is_two
+                None
+            }
+            fn square_of(factor: &Expr) -> Option<(u64, Arc<Expr>)> {
+                if let ExprKind::Pow(base, exp) = &factor.kind
+                    && {
+                        // Exact check for power 2.0 (square)
+                        #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
+                        let is_two = matches!(&exp.kind, ExprKind::Number(n) if *n == 2.0);
+                        is_two
+                    }
+                {
+                    return sin_or_cos(base);
                 }
-                && let (
-                    ExprKind::FunctionCall {
-                        name: sin_name,
-                        args: sin_args,
-                    },
-                    ExprKind::FunctionCall {
-                        name: cos_name,
-                        args: cos_args,
-                    },
-                ) = (&sin_base.kind, &cos_base.kind)
-                && sin_name.id() == KS.sin
-                && cos_name.id() == KS.cos
-                && sin_args.len() == 1
-                && cos_args.len() == 1
-                && sin_args[0] == cos_args[0]
-            {
-                return Some(Arc::new(Expr::number(1.0)));
+                None
             }
 
-            // cos^2(x) + sin^2(x) = 1
-            if let (ExprKind::Pow(cos_base, cos_exp), ExprKind::Pow(sin_base, sin_exp)) =
-                (&u.kind, &v.kind)
-                && {
-                    // Exact check for power 2.0 (square)
-                    #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
-                    let is_two = matches!(&cos_exp.kind, ExprKind::Number(n) if *n == 2.0);
-                    is_two
+            match &term.kind {
+                ExprKind::Pow(..) => {
+                    square_of(term).map(|(id, arg)| (id, arg, Expr::number(1.0)))
                 }
-                && {
-                    // Exact check for power 2.0 (square)
-                    #[allow(clippy::float_cmp, reason = "Comparing against exact constant 2.0")]
-                    let is_two = matches!(&sin_exp.kind, ExprKind::Number(n) if *n == 2.0);
-                    is_two
+                ExprKind::Poly(poly) if poly.terms().len() == 1 && poly.terms()[0].0 == 2 => {
+                    sin_or_cos(poly.base())
+                        .map(|(id, arg)| (id, arg, Expr::number(poly.terms()[0].1)))
+                }
+                ExprKind::Product(factors) => {
+                    let mut found = None;
+                    for (position, factor) in factors.iter().enumerate() {
+                        if let Some(square) = square_of(factor) {
+                            if found.is_some() {
+                                return None;
+                            }
+                            found = Some((position, square));
+                        }
+                    }
+                    let (position, (id, arg)) = found?;
+                    let rest: Vec<Arc<Expr>> = factors
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != position)
+                        .map(|(_, factor)| Arc::clone(factor))
+                        .collect();
+                    Some((id, arg, Expr::product_from_arcs(rest)))
+                }
+                _ => None,
+            }
+        }
+    },
+    |expr: &Expr, _context: &RuleContext| {
+        // c*sin^2(x) + c*cos^2(x) = c, wherever the pair sits in the sum
+        if let ExprKind::Sum(terms) = &expr.kind {
+            let squares: Vec<Option<(u64, Arc<Expr>, Expr)>> =
+                terms.iter().map(|term| split_square(term)).collect();
+            for (i, first) in squares.iter().enumerate() {
+                let Some((first_id, first_arg, first_coeff)) = first else {
+                    continue;
+                };
+                let partner = squares.iter().enumerate().skip(i + 1).find(|(_, other)| {
+                    other.as_ref().is_some_and(|(id, arg, coeff)| {
+                        id != first_id && arg == first_arg && coeff == first_coeff
+                    })
+                });
+                if let Some((j, _)) = partner {
+                    let mut rest: Vec<Arc<Expr>> = terms
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| k != i && k != j)
+                        .map(|(_, term)| Arc::clone(term))
+                        .collect();
+                    rest.push(Arc::new(first_coeff.clone()));
+                    return Some(Arc::new(Expr::sum_from_arcs(rest)));
                 }
-                && let (
-                    ExprKind::FunctionCall {
-                        name: cos_name,
-                        args: cos_args,
-                    },
-                    ExprKind::FunctionCall {
-                        name: sin_name,
-                        args: sin_args,
-                    },
-                ) = (&cos_base.kind, &sin_base.kind)
-                && cos_name.id() == KS.cos
-                && sin_name.id() == KS.sin
-                && cos_args.len() == 1
-                && sin_args.len() == 1
-                && cos_args[0] == sin_args[0]
-            {
-                return Some(Arc::new(Expr::number(1.0)));
             }
         }
         None
@@ -5,8 +5,8 @@ pub mod basic;
 mod helpers;
 /// Trigonometric identities
 pub mod identities;
-/// Inverse trigonometric function rules
-pub mod inverse;
+/// Exact reduction of arguments by multiples of pi
+pub mod periodicity;
 /// Trigonometric transformations
 pub mod transformations;
 /// Triple angle formulas
@@ -14,6 +14,7 @@ pub mod triple_angle;
 
 /// Rule list collector
 pub mod rules;
+pub use angles::expand_trig_angles;
 pub use rules::get_trigonometric_rules;
 
 pub(super) use super::{Rule, RuleCategory, RuleContext, RuleExprKind, extract_coeff_arc};
@@ -0,0 +1,236 @@
+//! Exact reduction of trigonometric arguments by multiples of `pi`.
+//!
+//! An argument is split into its `pi` shift and the rest. The shift is kept as an exact
+//! fraction `num/den * pi`, read only from the `pi` symbol (`pi`, `c*pi` with `2c` an
+//! integer, `c*pi/d` with integers `c`, `d`), so a float such as `6.283185...` never
+//! counts as a period. Products of `pi` with integer-assumed symbols are dropped when
+//! they always span whole periods (`2*pi*n` for `sin`/`cos`, `pi*n` for `tan`).
+//!
+//! After reducing the shift modulo the period, quarter turns fold into phase identities:
+//! `sin(x + pi/2) = cos(x)`, `sin(x + pi) = -sin(x)`, `tan(x + pi/2) = -cot(x)`, …
+
+use super::{Rule, RuleCategory, RuleContext, RuleExprKind};
+use crate::core::known_symbols::{KS, get_symbol};
+use crate::core::{Expr, ExprKind};
+use std::sync::Arc;
+
+/// Largest integer coefficient considered exact (2^53)
+const MAX_EXACT: f64 = 9_007_199_254_740_992.0;
+
+/// A `pi` shift `num/den * pi` with `den > 0`
+#[derive(Clone, Copy)]
+struct Shift {
+    num: i64,
+    den: i64,
+}
+
+impl Shift {
+    fn add(self, other: Self) -> Option<Self> {
+        let num = self
+            .num
+            .checked_mul(other.den)?
+            .checked_add(other.num.checked_mul(self.den)?)?;
+        Some(Self::new(num, self.den.checked_mul(other.den)?))
+    }
+
+    #[allow(clippy::integer_division, reason = "Exact: g divides both parts")]
+    fn new(num: i64, den: i64) -> Self {
+        let g = gcd(num, den).max(1);
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+}
+
+const fn gcd(mut a: i64, mut b: i64) -> i64 {
+    a = a.abs();
+    b = b.abs();
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// Exact integer value of a float, if it has one
+fn exact_int(n: f64) -> Option<i64> {
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Checked to be an integer within the exact f64 range"
+    )]
+    (n.fract() == 0.0 && n.abs() <= MAX_EXACT).then_some(n as i64)
+}
+
+/// How a summand relates to `pi`
+enum Term {
+    /// `num/den * pi`
+    Shift(Shift),
+    /// `k*pi*n1*n2*…` with integer-assumed `n_i`: a whole number of `k*pi` shifts
+    IntegerMultiple(Shift),
+    /// Anything else
+    Other,
+}
+
+fn classify(term: &Expr, context: &RuleContext) -> Term {
+    let is_pi = |e: &Expr| matches!(&e.kind, ExprKind::Symbol(s) if s.id() == KS.pi);
+    match &term.kind {
+        ExprKind::Symbol(s) if s.id() == KS.pi => Term::Shift(Shift::new(1, 1)),
+        ExprKind::Product(factors) => {
+            let mut coeff = 1.0;
+            let mut pis = 0;
+            let mut integers = 0;
+            for factor in factors {
+                match &factor.kind {
+                    ExprKind::Number(n) => coeff *= n,
+                    ExprKind::Symbol(s) if s.id() == KS.pi => pis += 1,
+                    ExprKind::Symbol(s) if context.integer_vars.contains(&s.id()) => {
+                        integers += 1;
+                    }
+                    _ => return Term::Other,
+                }
+            }
+            // Only halves are exact in binary; other fractions come as `c*pi/d`
+            let Some(halves) = (pis == 1).then(|| exact_int(coeff * 2.0)).flatten() else {
+                return Term::Other;
+            };
+            let shift = Shift::new(halves, 2);
+            if integers == 0 {
+                Term::Shift(shift)
+            } else {
+                Term::IntegerMultiple(shift)
+            }
+        }
+        ExprKind::Div(num, den) => {
+            let Some(d) = den.as_number().and_then(exact_int).filter(|d| *d != 0) else {
+                return Term::Other;
+            };
+            let c = if is_pi(num) {
+                Some(1)
+            } else if let ExprKind::Product(factors) = &num.kind
+                && let [c, p] = factors.as_slice()
+                && is_pi(p)
+            {
+                c.as_number().and_then(exact_int)
+            } else {
+                None
+            };
+            c.map_or(Term::Other, |c| {
+                Term::Shift(Shift::new(c * d.signum(), d.abs()))
+            })
+        }
+        _ => Term::Other,
+    }
+}
+
+/// `f(arg + quarter * pi/2)` for `quarter` in `0..4`, as a signed function of `arg`
+fn phase_shift(name: u64, quarter: i64, arg: Expr) -> Expr {
+    let (func, negate) = match (name, quarter) {
+        (id, 0) => (id, false),
+        (id, 2) if id == KS.sin || id == KS.cos => (id, true),
+        (id, 1) if id == KS.sin => (KS.cos, false),
+        (id, 3) if id == KS.sin => (KS.cos, true),
+        (id, 1) if id == KS.cos => (KS.sin, true),
+        (id, 3) if id == KS.cos => (KS.sin, false),
+        // tan has period pi, so only the quarter turn remains
+        _ => (KS.cot, true),
+    };
+    let call = Expr::func_symbol(get_symbol(func), arg);
+    if negate {
+        Expr::product(vec![Expr::number(-1.0), call])
+    } else {
+        call
+    }
+}
+
+rule!(
+    TrigPiShiftRule,
+    "trig_pi_shift",
+    86,
+    Trigonometric,
+    &[RuleExprKind::Function],
+    targets: &[KS.sin, KS.cos, KS.tan],
+    |expr: &Expr, context: &RuleContext| {
+        let ExprKind::FunctionCall { name, args } = &expr.kind else {
+            return None;
+        };
+        let [arg] = args.as_slice() else {
+            return None;
+        };
+        // Fraction combination turns `x + pi/2` into `(2*x + pi)/2`: read through an
+        // integer divisor
+        let (numerator, divisor) = match &arg.kind {
+            ExprKind::Div(num, den) => match den.as_number().and_then(exact_int) {
+                Some(d) if d != 0 => (num, d),
+                _ => (arg, 1),
+            },
+            _ => (arg, 1),
+        };
+        let terms: Vec<Arc<Expr>> = match &numerator.kind {
+            ExprKind::Sum(terms) => terms.clone(),
+            _ => vec![Arc::clone(numerator)],
+        };
+        let over_divisor = |s: Shift| {
+            s.den
+                .checked_mul(divisor.abs())
+                .map(|den| Shift::new(s.num * divisor.signum(), den))
+        };
+        // Period in units of pi
+        let period = if name.id() == KS.tan { 1 } else { 2 };
+
+        let mut rest = Vec::with_capacity(terms.len());
+        let mut shift = Shift::new(0, 1);
+        let mut shifts = 0;
+        let mut dropped = false;
+        for term in &terms {
+            match classify(term, context) {
+                Term::Shift(s) => {
+                    shift = shift.add(over_divisor(s)?)?;
+                    shifts += 1;
+                }
+                Term::IntegerMultiple(k)
+                    if over_divisor(k).is_some_and(|k| k.den == 1 && k.num % period == 0) =>
+                {
+                    dropped = true;
+                }
+                Term::IntegerMultiple(_) | Term::Other => rest.push((**term).clone()),
+            }
+        }
+        if shifts == 0 && !dropped {
+            return None;
+        }
+
+        let reduced = shift.num.rem_euclid(period * shift.den);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "The divisor came from an exact f64 integer"
+        )]
+        let rest = if divisor == 1 {
+            Expr::sum(rest)
+        } else {
+            Expr::div_expr(Expr::sum(rest), Expr::number(divisor as f64))
+        };
+        if (2 * reduced) % shift.den == 0 {
+            #[allow(clippy::integer_division, reason = "Checked to divide exactly")]
+            let quarter = 2 * reduced / shift.den;
+            return Some(phase_shift(name.id(), quarter, rest));
+        }
+        if reduced == shift.num && shifts == 1 && !dropped {
+            // Already in range and not a quarter turn: nothing to do
+            return None;
+        }
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Both parts came from exact f64 integers"
+        )]
+        let shift = Expr::div_expr(
+            Expr::mul_expr(
+                Expr::number(reduced as f64),
+                Expr::from_interned(get_symbol(KS.pi)),
+            ),
+            Expr::number(shift.den as f64),
+        );
+        Some(Expr::func_symbol(name.clone(), Expr::add_expr(rest, shift)))
+    }
+);
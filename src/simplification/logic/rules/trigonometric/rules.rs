@@ -1,7 +1,7 @@
 use super::Rule;
 use super::angles::{
-    CosDoubleAngleDifferenceRule, SinProductToDoubleAngleRule, TrigProductToDoubleAngleRule,
-    TrigSumDifferenceRule,
+    CosDoubleAngleConstantRule, CosDoubleAngleDifferenceRule, SinProductToDoubleAngleRule,
+    TrigCosSumDifferenceRule, TrigProductToDoubleAngleRule, TrigSumDifferenceRule,
 };
 use super::basic::{
     CosPiOverTwoRule, CosPiRule, CosSinToCotRule, CosZeroRule, OneCosToSecRule, OneSinToCscRule,
@@ -10,7 +10,7 @@ use super::basic::{
 use super::identities::{
     PythagoreanComplementsRule, PythagoreanIdentityRule, PythagoreanTangentRule,
 };
-use super::inverse::{InverseTrigCompositionRule, InverseTrigIdentityRule};
+use super::periodicity::TrigPiShiftRule;
 use super::transformations::{
     CofunctionIdentityRule, TrigNegArgRule, TrigPeriodicityRule, TrigReflectionRule,
     TrigThreePiOverTwoRule,
@@ -35,17 +35,18 @@ pub fn get_trigonometric_rules() -> Vec<Arc<dyn Rule + Send + Sync>> {
         Arc::new(PythagoreanComplementsRule),
         Arc::new(PythagoreanTangentRule),
         // Inverse trig functions
-        Arc::new(InverseTrigIdentityRule),
-        Arc::new(InverseTrigCompositionRule),
         // Cofunction, periodicity, reflection, and negation
         Arc::new(CofunctionIdentityRule),
+        Arc::new(TrigPiShiftRule),
         Arc::new(TrigPeriodicityRule),
         Arc::new(TrigReflectionRule),
         Arc::new(TrigThreePiOverTwoRule),
         Arc::new(TrigNegArgRule),
         // Angle-based: double angle, sum/difference, product-to-sum
         Arc::new(CosDoubleAngleDifferenceRule),
+        Arc::new(CosDoubleAngleConstantRule),
         Arc::new(TrigSumDifferenceRule),
+        Arc::new(TrigCosSumDifferenceRule),
         Arc::new(TrigProductToDoubleAngleRule),
         Arc::new(SinProductToDoubleAngleRule),
         // Triple angle formulas
@@ -15,6 +15,8 @@ rule!(
     85,
     Trigonometric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Sum(..)))),
     |expr: &Expr, _context: &RuleContext| {
         // Helper to extract negated term from Product([-1, x])
         fn extract_negated(term: &Expr) -> Option<Expr> {
@@ -115,6 +117,9 @@ rule!(
     85,
     Trigonometric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if (name.id() == KS.sin || name.id() == KS.cos)
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Sum(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let ExprKind::FunctionCall { name, args } = &expr.kind
             && (name.id() == KS.sin || name.id() == KS.cos)
@@ -143,6 +148,9 @@ rule!(
     80,
     Trigonometric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if (name.id() == KS.sin || name.id() == KS.cos)
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Sum(..)))),
     |expr: &Expr, _context: &RuleContext| {
         // Helper to extract negated term from Product([-1, x])
         fn extract_negated(term: &Expr) -> Option<Expr> {
@@ -216,6 +224,9 @@ rule!(
     80,
     Trigonometric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { name, args }
+        if (name.id() == KS.sin || name.id() == KS.cos)
+            && args.first().is_some_and(|a| matches!(a.kind, ExprKind::Sum(..)))),
     |expr: &Expr, _context: &RuleContext| {
         // Helper to extract negated term from Product([-1, x])
         fn extract_negated(term: &Expr) -> Option<Expr> {
@@ -289,6 +300,8 @@ rule!(
     90,
     Trigonometric,
     &[RuleExprKind::Function],
+    precondition: |expr: &Expr| matches!(&expr.kind, ExprKind::FunctionCall { args, .. }
+        if args.first().is_some_and(|a| matches!(a.kind, ExprKind::Product(..)))),
     |expr: &Expr, _context: &RuleContext| {
         if let Some((name, arg)) = get_trig_function(expr) {
             // Check for Product([-1, x]) pattern
@@ -0,0 +1,83 @@
+//! Caller-supplied rules, run by the engine alongside the built-in ones
+
+use super::core::{Rule, RuleCategory, RuleContext};
+use crate::core::{Expr, ExprView};
+use std::sync::Arc;
+
+/// A simplification rule supplied by the caller and registered with
+/// [`Simplify::with_rule`](crate::Simplify::with_rule)
+///
+/// The engine tries it on every node, interleaved with the built-in rules by priority
+/// (see [`Simplify::list_rules`](crate::Simplify::list_rules) for theirs), and keeps
+/// rewriting until no rule applies. Rules are shared between threads, hence the
+/// `Send + Sync` bound.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{Expr, ExprView, Simplify, UserRule, symb};
+///
+/// /// `legendre_p(0, x)` is 1
+/// struct LegendreZero;
+///
+/// impl UserRule for LegendreZero {
+///     fn name(&self) -> &'static str {
+///         "legendre_p_zero"
+///     }
+///     fn priority(&self) -> i32 {
+///         50
+///     }
+///     fn apply(&self, expr: &ExprView<'_>) -> Option<Expr> {
+///         match expr {
+///             ExprView::Function { name: "legendre_p", args }
+///                 if args.len() == 2 && args[0].view().as_number() == Some(0.0) =>
+///             {
+///                 Some(Expr::number(1.0))
+///             }
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let x = symb("user_rule_doc_x");
+/// let expr = Expr::func_multi("legendre_p", vec![Expr::number(0.0), x.into()]) + 2.0;
+/// let result = Simplify::new().with_rule(Box::new(LegendreZero)).simplify(&expr).unwrap();
+/// assert_eq!(result.to_string(), "3");
+/// ```
+pub trait UserRule: Send + Sync {
+    /// Name of the rule, unique among all rules
+    fn name(&self) -> &'static str;
+    /// Priority of the rule (higher = tried first)
+    fn priority(&self) -> i32;
+    /// Category the rule is selected by; defaults to [`RuleCategory::Algebraic`]
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Algebraic
+    }
+    /// Whether the rule changes the domain of the expression; such rules are skipped
+    /// under [`domain_safe`](crate::Simplify::domain_safe)
+    fn alters_domain(&self) -> bool {
+        false
+    }
+    /// Rewrite `expr`, or return `None` to leave it to the other rules
+    fn apply(&self, expr: &ExprView<'_>) -> Option<Expr>;
+}
+
+/// Runs a [`UserRule`] as an engine [`Rule`]
+pub struct UserRuleAdapter(pub Arc<dyn UserRule>);
+
+impl Rule for UserRuleAdapter {
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+    fn priority(&self) -> i32 {
+        self.0.priority()
+    }
+    fn category(&self) -> RuleCategory {
+        self.0.category()
+    }
+    fn alters_domain(&self) -> bool {
+        self.0.alters_domain()
+    }
+    fn apply(&self, expr: &Arc<Expr>, _context: &RuleContext) -> Option<Arc<Expr>> {
+        self.0.apply(&expr.view()).map(Arc::new)
+    }
+}
@@ -5,8 +5,11 @@
 )]
 mod rule_registry_tests {
     use super::super::engine::global_registry;
-    use super::super::rules::{RuleCategory, RuleExprKind};
+    use super::super::rules::{Rule, RuleCategory, RuleContext, RuleExprKind};
+    use crate::core::{Expr, ExprKind};
+    use crate::{Diff, Simplify};
     use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
 
     #[test]
     fn test_rule_registry_loads_all_categories() {
@@ -91,6 +94,101 @@ mod rule_registry_tests {
         assert!(!pow_rules.is_empty(), "Pow should have rules");
         assert!(sum_rules.len() < registry.rules.len());
     }
+
+    /// Every node of `expr`, children first
+    fn collect_nodes(expr: &Arc<Expr>, out: &mut Vec<Arc<Expr>>) {
+        match &expr.kind {
+            ExprKind::Sum(children) | ExprKind::Product(children) => {
+                for child in children {
+                    collect_nodes(child, out);
+                }
+            }
+            ExprKind::FunctionCall { args, .. } => args.iter().for_each(|a| collect_nodes(a, out)),
+            ExprKind::Div(a, b) | ExprKind::Pow(a, b) => {
+                collect_nodes(a, out);
+                collect_nodes(b, out);
+            }
+            _ => {}
+        }
+        out.push(Arc::clone(expr));
+    }
+
+    /// Rules the engine tries on `node`: by kind, and for calls by function name
+    fn candidate_rules(node: &Expr) -> Vec<&'static Arc<dyn Rule + Send + Sync>> {
+        let registry = global_registry();
+        if let ExprKind::FunctionCall { name, .. } = &node.kind {
+            let specific = registry.get_specific_func_rules(name.id());
+            specific
+                .iter()
+                .chain(registry.get_generic_func_rules())
+                .collect()
+        } else {
+            registry
+                .get_rules_for_kind(RuleExprKind::of(node))
+                .iter()
+                .collect()
+        }
+    }
+
+    /// Nodes of parsed, differentiated and simplified forms of a few expressions
+    fn sample_nodes() -> Vec<Arc<Expr>> {
+        let inputs = [
+            "sin(x)^2 + cos(x)^2 + abs(-x) * sign(x)",
+            "sqrt(x^2) / sqrt(4) + cbrt(x^3 * y) - exp(2 * ln(x))",
+            "(x*y)^2 / (x^3 * y) + (a/b)/(c/d) + 1/cos(x) - sinh(-x)/cosh(x)",
+            "ln(1) + exp(0) + sin(0) + cos(pi/2) + 0^3 + 1^x + x^0 + x^1 + 6/4",
+            "(exp(x) + exp(-x))/2 + 2/(exp(x) - exp(-x)) + e^ln(x) + ln(e^y)",
+            "x^3 + y^3 + (x + 1/y)/z + ln(x) + ln(y) + x^-2 + (x/y)^(1/2) + (x/(2*y))^2",
+        ];
+        let empty = HashSet::new();
+        let simplify = Simplify::new();
+        let x = crate::symb("x");
+        let mut nodes = Vec::new();
+        for input in inputs {
+            let expr = crate::parse(input, &empty, &empty, None).unwrap();
+            let diffed = Diff::new()
+                .skip_simplification(true)
+                .differentiate(&expr, &x)
+                .unwrap();
+            let simplified = simplify.simplify(&diffed).unwrap();
+            for form in [expr, diffed, simplified] {
+                collect_nodes(&Arc::new(form), &mut nodes);
+            }
+        }
+        nodes
+    }
+
+    #[test]
+    fn test_preconditions_never_reject_applicable_nodes() {
+        let context = RuleContext::default();
+        for node in sample_nodes() {
+            for rule in candidate_rules(&node) {
+                if !rule.precondition(&node) {
+                    assert!(
+                        rule.apply(&node, &context).is_none(),
+                        "{} rejected {node} but rewrites it",
+                        rule.name()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_preconditions_skip_most_candidates() {
+        let (mut candidates, mut passed) = (0_usize, 0_usize);
+        for node in sample_nodes() {
+            for rule in candidate_rules(&node) {
+                candidates += 1;
+                passed += usize::from(rule.precondition(&node));
+            }
+        }
+        // Skipped candidates never reach apply() or the rule cache
+        assert!(
+            passed * 2 < candidates,
+            "{passed} of {candidates} candidates passed"
+        );
+    }
 }
 
 #[allow(
@@ -202,3 +300,117 @@ mod debug_factoring_logic_tests {
         (coeff, non_numeric)
     }
 }
+
+mod exponent_arithmetic_tests {
+    use super::super::helpers::{
+        exact_rational, rational_expr, rational_product, rational_sum, snap_exponent,
+    };
+    use crate::Expr;
+
+    #[test]
+    fn test_snap_exponent_tolerance() {
+        assert_eq!(snap_exponent(0.1 * 3.0 * 10.0), Some(3.0));
+        assert_eq!(snap_exponent(1.999_999_999_999_999_8), Some(2.0));
+        assert_eq!(snap_exponent(-2.000_000_000_000_000_4), Some(-2.0));
+        // Exact integers and deliberate fractions are untouched
+        assert_eq!(snap_exponent(2.0), None);
+        assert_eq!(snap_exponent(2.000_000_001), None);
+        assert_eq!(snap_exponent(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_exact_rational_forms() {
+        assert_eq!(exact_rational(&Expr::number(4.0)), Some((4, 1)));
+        assert_eq!(exact_rational(&Expr::number(-0.375)), Some((-3, 8)));
+        assert_eq!(exact_rational(&Expr::number(0.1)), None);
+        let two_sixths = Expr::div_expr(Expr::number(2.0), Expr::number(6.0));
+        assert_eq!(exact_rational(&two_sixths), Some((1, 3)));
+        assert_eq!(exact_rational(&Expr::symbol("x")), None);
+    }
+
+    #[test]
+    fn test_rational_arithmetic() {
+        assert_eq!(rational_product((1, 3), (3, 1)), Some((1, 1)));
+        assert_eq!(rational_sum((1, 3), (2, 3)), Some((1, 1)));
+        assert_eq!(rational_sum((1, 2), (-1, 3)), Some((1, 6)));
+        assert_eq!(rational_product((i64::MAX, 1), (2, 1)), None);
+        assert_eq!(rational_expr((2, 1)), Expr::number(2.0));
+        assert_eq!(
+            rational_expr((2, 3)),
+            Expr::div_expr(Expr::number(2.0), Expr::number(3.0))
+        );
+    }
+}
+
+#[allow(
+    clippy::unwrap_used,
+    clippy::panic,
+    reason = "Standard test relaxations"
+)]
+mod distribution_tests {
+    use super::super::helpers::compare_expr;
+    use super::super::rules::algebraic::combination::{
+        CombineLikeTermsInSumRule, MAX_DISTRIBUTION_NODES, distribute_product,
+    };
+    use super::super::rules::{Rule, RuleContext};
+    use crate::core::ExprKind;
+    use crate::{Expr, parse};
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    fn parse_str(input: &str) -> Expr {
+        parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+    }
+
+    fn has_sum_factor(term: &Expr) -> bool {
+        matches!(&term.kind, ExprKind::Product(factors)
+            if factors.iter().any(|f| matches!(f.kind, ExprKind::Sum(_))))
+    }
+
+    #[test]
+    fn test_three_sums_expand_in_one_application() {
+        let expr = parse_str("(a+b)*(c+d)*(e+f) - a*c*e");
+        let expanded = CombineLikeTermsInSumRule
+            .apply(&Arc::new(expr), &RuleContext::default())
+            .unwrap();
+        let ExprKind::Sum(terms) = &expanded.kind else {
+            panic!("expected a sum, got {expanded}");
+        };
+        assert_eq!(terms.len(), 9, "{expanded}");
+        assert!(!terms.iter().any(|t| has_sum_factor(t)), "{expanded}");
+    }
+
+    #[test]
+    fn test_distribution_yields_canonical_terms() {
+        let product = parse_str("(a+b)*(c+d)*(e+f)");
+        let ExprKind::Product(factors) = &product.kind else {
+            panic!("expected a product, got {product}");
+        };
+        let terms = distribute_product(factors, MAX_DISTRIBUTION_NODES).unwrap();
+        assert_eq!(terms.len(), 8);
+        for expected in [
+            "a*c*e", "a*c*f", "a*d*e", "a*d*f", "b*c*e", "b*c*f", "b*d*e", "b*d*f",
+        ] {
+            assert!(terms.contains(&parse_str(expected)), "missing {expected}");
+        }
+        assert!(terms.is_sorted_by(|a, b| compare_expr(a, b) != Ordering::Greater));
+    }
+
+    #[test]
+    fn test_distribution_declines_oversized_expansions() {
+        let product = parse_str("(a+b+c+d)*(e+f+g+h)*(i+j+k+l)*(m+n+o+p)*(q+r+s+t)");
+        let ExprKind::Product(factors) = &product.kind else {
+            panic!("expected a product, got {product}");
+        };
+        assert!(distribute_product(factors, MAX_DISTRIBUTION_NODES).is_none());
+        assert!(distribute_product(&factors[..2], MAX_DISTRIBUTION_NODES).is_some());
+
+        let expr = parse_str("(a+b+c+d)*(e+f+g+h)*(i+j+k+l)*(m+n+o+p)*(q+r+s+t) - a*e*i*m*q");
+        assert!(
+            CombineLikeTermsInSumRule
+                .apply(&Arc::new(expr), &RuleContext::default())
+                .is_none()
+        );
+    }
+}
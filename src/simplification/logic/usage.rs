@@ -0,0 +1,212 @@
+//! How often each simplification rule fires over a corpus of expressions.
+//!
+//! A [`RuleUsageReport`] starts with one zeroed record per built-in rule and absorbs the
+//! tallies of every [`Simplify::simplify_recording`](crate::Simplify::simplify_recording)
+//! call. Rules that never fire over a representative corpus are candidates for pruning
+//! or for leaving out of a minimal rule profile.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use rustc_hash::FxHashMap;
+
+use super::rules::RuleCategory;
+use crate::Simplify;
+use crate::core::Expr;
+
+/// Rewrites made by one rule during one simplification
+#[derive(Debug, Clone, Copy, Default)]
+pub(in crate::simplification) struct RuleTally {
+    fires: usize,
+    nodes_before: usize,
+    nodes_after: usize,
+}
+
+impl RuleTally {
+    /// Count a rewrite of `before` into `after`
+    pub(in crate::simplification) fn record(&mut self, before: &Expr, after: &Expr) {
+        self.fires += 1;
+        self.nodes_before += before.node_count();
+        self.nodes_after += after.node_count();
+    }
+}
+
+/// How often one rule fired, and how it changed the size of the nodes it rewrote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleUsage {
+    /// Rule name, as in [`Simplify::list_rules`]
+    pub name: &'static str,
+    /// Rule category
+    pub category: RuleCategory,
+    /// Number of rewrites, counting rewrites replayed from the rule cache
+    pub fires: usize,
+    /// Total node count of the rewritten nodes before the rewrites
+    pub nodes_before: usize,
+    /// Total node count of the rewritten nodes after the rewrites
+    pub nodes_after: usize,
+}
+
+impl RuleUsage {
+    /// Average change in node count per rewrite, negative when the rule shrinks what it
+    /// rewrites; `0` for a rule that never fired
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Node counts stay far below 2^52"
+    )]
+    pub fn mean_node_delta(&self) -> f64 {
+        if self.fires == 0 {
+            return 0.0;
+        }
+        (self.nodes_after as f64 - self.nodes_before as f64) / self.fires as f64
+    }
+}
+
+/// Per-rule fire counts aggregated over many simplifications
+///
+/// Holds every built-in rule exactly once, sorted by name, including rules that never
+/// fired; user rules join the report the first time they fire.
+///
+/// # Example
+/// ```
+/// use symb_anafis::{RuleUsageReport, Simplify, parse};
+/// use std::collections::HashSet;
+///
+/// let mut report = RuleUsageReport::new();
+/// let simplify = Simplify::new();
+/// for input in ["sin(x)^2 + cos(x)^2", "x + x"] {
+///     let expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+///     simplify.simplify_recording(&expr, &mut report).unwrap();
+/// }
+/// assert_eq!(report.expressions(), 2);
+/// assert!(report.get("pythagorean_identity").is_some_and(|rule| rule.fires > 0));
+/// assert!(report.unused().count() > 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleUsageReport {
+    rules: Vec<RuleUsage>,
+    expressions: usize,
+}
+
+impl Default for RuleUsageReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuleUsageReport {
+    /// A report with a zeroed record for every built-in rule
+    #[must_use]
+    pub fn new() -> Self {
+        let mut rules: Vec<RuleUsage> = Simplify::list_rules()
+            .into_iter()
+            .map(|(name, category, _, _)| RuleUsage {
+                name,
+                category,
+                fires: 0,
+                nodes_before: 0,
+                nodes_after: 0,
+            })
+            .collect();
+        rules.sort_by_key(|rule| rule.name);
+        rules.dedup_by_key(|rule| rule.name);
+        Self {
+            rules,
+            expressions: 0,
+        }
+    }
+
+    /// One record per rule, sorted by name
+    #[must_use]
+    pub fn rules(&self) -> &[RuleUsage] {
+        &self.rules
+    }
+
+    /// The record of the rule `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&RuleUsage> {
+        self.rules
+            .binary_search_by_key(&name, |rule| rule.name)
+            .ok()
+            .map(|index| &self.rules[index])
+    }
+
+    /// The rules that never fired
+    pub fn unused(&self) -> impl Iterator<Item = &RuleUsage> {
+        self.rules.iter().filter(|rule| rule.fires == 0)
+    }
+
+    /// Number of simplifications recorded
+    #[must_use]
+    pub const fn expressions(&self) -> usize {
+        self.expressions
+    }
+
+    /// Add the tallies of one simplification; `category_of` names the category of rules
+    /// not yet in the report
+    pub(in crate::simplification) fn absorb(
+        &mut self,
+        tallies: FxHashMap<&'static str, RuleTally>,
+        category_of: impl Fn(&str) -> RuleCategory,
+    ) {
+        self.expressions += 1;
+        for (name, tally) in tallies {
+            let index = match self.rules.binary_search_by_key(&name, |rule| rule.name) {
+                Ok(index) => index,
+                Err(index) => {
+                    self.rules.insert(
+                        index,
+                        RuleUsage {
+                            name,
+                            category: category_of(name),
+                            fires: 0,
+                            nodes_before: 0,
+                            nodes_after: 0,
+                        },
+                    );
+                    index
+                }
+            };
+            let rule = &mut self.rules[index];
+            rule.fires += tally.fires;
+            rule.nodes_before += tally.nodes_before;
+            rule.nodes_after += tally.nodes_after;
+        }
+    }
+}
+
+const fn category_name(category: RuleCategory) -> &'static str {
+    match category {
+        RuleCategory::Numeric => "numeric",
+        RuleCategory::Algebraic => "algebraic",
+        RuleCategory::Trigonometric => "trigonometric",
+        RuleCategory::Hyperbolic => "hyperbolic",
+        RuleCategory::Exponential => "exponential",
+        RuleCategory::Root => "root",
+    }
+}
+
+/// JSON, one rule per line in name order, so reports diff cleanly
+impl Display for RuleUsageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "{{")?;
+        writeln!(f, "  \"expressions\": {},", self.expressions)?;
+        writeln!(f, "  \"rules\": [")?;
+        for (index, rule) in self.rules.iter().enumerate() {
+            let separator = if index + 1 < self.rules.len() {
+                ","
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "    {{\"name\": \"{}\", \"category\": \"{}\", \"fires\": {}, \"mean_node_delta\": {}}}{separator}",
+                rule.name.escape_default(),
+                category_name(rule.category),
+                rule.fires,
+                rule.mean_node_delta()
+            )?;
+        }
+        writeln!(f, "  ]")?;
+        write!(f, "}}")
+    }
+}
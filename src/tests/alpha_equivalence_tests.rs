@@ -0,0 +1,80 @@
+//! Tests for `Expr::alpha_equivalent`: structural equality up to renaming variables
+
+use crate::{Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn equivalent(a: &str, b: &str) -> bool {
+    let (a, b) = (parse_str(a), parse_str(b));
+    let forward = a.alpha_equivalent(&b);
+    assert_eq!(forward, b.alpha_equivalent(&a), "not symmetric");
+    forward
+}
+
+#[test]
+fn test_renamed_expressions_are_equivalent() {
+    assert!(equivalent("x^2 + x", "y^2 + y"));
+    assert!(equivalent("sin(a) + cos(a)", "sin(z) + cos(z)"));
+    assert!(equivalent("exp(-t^2/2)*t", "exp(-u^2/2)*u"));
+    assert!(equivalent("x^2 + 1", "x^2 + 1"));
+    assert!(equivalent(
+        "piecewise(x < 0: -x, x)",
+        "piecewise(w < 0: -w, w)"
+    ));
+
+    // Derivative variables are renamed along with the others
+    let d = |of: &str, by: &str, order| Expr::derivative(Expr::func("f", symb(of)), by, order);
+    assert!(d("x", "x", 2).alpha_equivalent(&d("y", "y", 2)));
+    assert!(!d("x", "x", 2).alpha_equivalent(&d("y", "x", 2)));
+    assert!(!d("x", "x", 2).alpha_equivalent(&d("x", "x", 1)));
+}
+
+#[test]
+fn test_mapping_must_be_consistent() {
+    assert!(!equivalent("x*y", "x*x"));
+    assert!(!equivalent("sin(a) - b", "sin(b) - b"));
+    // One variable cannot stand for two, in either direction
+    assert!(!equivalent("sin(x) + cos(y)", "sin(z) + cos(z)"));
+    assert!(!equivalent("x^2 + y^3", "y^2 + y^3"));
+}
+
+#[test]
+fn test_renamings_that_reorder_operands() {
+    // Swapping x and y maps one onto the other
+    assert!(equivalent("x^2 + y", "y^2 + x"));
+    // The renamed operands sort differently from the originals
+    assert!(equivalent("x*y^2", "b*a^2"));
+    assert!(equivalent("x + y^2", "b + a^2"));
+    assert!(equivalent("sin(x)*cos(y) + x", "sin(q)*cos(p) + q"));
+    assert!(equivalent(
+        "exp(x + y^2)*(y + x^2)",
+        "exp(b + a^2)*(a + b^2)"
+    ));
+    // The first pairing that fits a term is not always the one the rest needs
+    assert!(equivalent("x*y + x^2", "v*u + v^2"));
+    assert!(!equivalent("x*y + x^2 + y^3", "v*u + v^2 + v^3"));
+}
+
+#[test]
+fn test_different_variable_counts_are_never_equivalent() {
+    assert!(!equivalent("x + y", "x + x"));
+    assert!(!equivalent("a*b*c", "a*b*a"));
+    assert!(!equivalent("x", "2"));
+    assert!(!equivalent("atan2(y, x)", "atan2(y, y)"));
+    let call =
+        |args: &[&str]| Expr::func_multi("f", args.iter().map(|&a| symb(a).into()).collect());
+    assert!(!call(&["x"]).alpha_equivalent(&call(&["x", "y"])));
+}
+
+#[test]
+fn test_fixed_parts_must_match() {
+    // Numbers, function names and constants are not renamed
+    assert!(!equivalent("x^2", "x^3"));
+    assert!(!equivalent("sin(x)", "cos(x)"));
+    assert!(!equivalent("pi*x", "y*x"));
+    assert!(equivalent("pi*x", "pi*y"));
+    assert!(!equivalent("x + 1", "x*1.5"));
+}
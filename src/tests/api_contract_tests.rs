@@ -70,6 +70,18 @@ fn test_expr_number_roundtrip() {
     }
 }
 
+#[test]
+fn test_expr_num_traits_interop() {
+    use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
+
+    assert_eq!(Expr::number(3.0).to_i64(), Some(3));
+    assert_eq!(Expr::number(-2.5).to_f64(), Some(-2.5));
+    assert_eq!(Expr::symbol("x").to_f64(), None);
+    assert_eq!(Expr::from_u64(7), Some(Expr::number(7.0)));
+    assert!(Expr::zero().is_zero() && Expr::one().is_one());
+    assert!(!Expr::symbol("x").is_zero());
+}
+
 #[test]
 fn test_expr_clone_equality() {
     let expr = parser_parse("x^2 + sin(x)", &HashSet::new(), &HashSet::new(), None).unwrap();
@@ -91,7 +91,6 @@ fn parity_custom_functions() {
 
 // 5. Evaluation Parity (Flexible Inputs)
 // Ensure eval_f64 works with both Strings and Symbols in the variable list
-#[cfg(feature = "parallel")]
 #[test]
 fn parity_eval_inputs() {
     use crate::eval_f64;
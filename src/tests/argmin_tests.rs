@@ -0,0 +1,42 @@
+//! `argmin` integration: minimize a parsed formula with its symbolic gradient.
+
+use argmin::core::{CostFunction, Executor, Gradient, State};
+use argmin::solver::linesearch::MoreThuenteLineSearch;
+use argmin::solver::quasinewton::LBFGS;
+use std::collections::HashSet;
+
+use crate::{ArgminProblem, parse, symb};
+
+#[test]
+fn test_lbfgs_minimizes_rosenbrock() {
+    let (x, y) = (symb("x"), symb("y"));
+    let rosenbrock = parse(
+        "(1 - x)^2 + 100*(y - x^2)^2",
+        &HashSet::new(),
+        &HashSet::new(),
+        None,
+    )
+    .unwrap();
+    let problem = ArgminProblem::new(&rosenbrock, &[&x, &y]).unwrap();
+
+    let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+    let result = Executor::new(problem, solver)
+        .configure(|state| state.param(vec![-1.2, 1.0]).max_iters(200))
+        .run()
+        .unwrap();
+
+    let best = result.state().get_best_param().unwrap();
+    assert!((best[0] - 1.0).abs() < 1e-6, "x = {}", best[0]);
+    assert!((best[1] - 1.0).abs() < 1e-6, "y = {}", best[1]);
+}
+
+#[test]
+fn test_problem_rejects_wrong_dimension() {
+    let x = symb("x");
+    let expr = parse("x^2", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let problem = ArgminProblem::new(&expr, &[&x]).unwrap();
+    assert_eq!(problem.dimension(), 1);
+    assert!(problem.cost(&vec![1.0, 2.0]).is_err());
+    assert!(problem.gradient(&vec![]).is_err());
+    assert_eq!(problem.gradient(&vec![3.0]).unwrap(), vec![6.0]);
+}
@@ -0,0 +1,153 @@
+//! Batch evaluation over `ndarray` / `nalgebra` inputs with length-1 broadcasting
+
+use crate::{CompiledEvaluator, Expr, parse};
+use std::collections::HashSet;
+
+fn compiled(input: &str, params: &[&str]) -> CompiledEvaluator {
+    let expr: Expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile(&expr, params, None).unwrap()
+}
+
+const FORMULA: &str = "a*sin(x) + b*x^2 - c";
+
+/// Point-by-point evaluation, repeating length-1 columns by hand
+fn manual(eval: &CompiledEvaluator, columns: &[&[f64]]) -> Vec<f64> {
+    let n_points = columns.iter().map(|c| c.len()).max().unwrap_or(1);
+    (0..n_points)
+        .map(|i| {
+            let point: Vec<f64> = columns
+                .iter()
+                .map(|c| if c.len() == 1 { c[0] } else { c[i] })
+                .collect();
+            eval.evaluate(&point)
+        })
+        .collect()
+}
+
+#[cfg(feature = "ndarray")]
+mod ndarray_inputs {
+    use super::*;
+    use crate::DiffError;
+    use ndarray::{Array1, Array2, ShapeBuilder, array};
+
+    #[test]
+    fn test_mixed_broadcast_shapes_match_manual_loops() {
+        let eval = compiled(FORMULA, &["x", "a", "b", "c"]);
+        let xs = Array1::linspace(-2.0, 2.0, 9);
+        let bs = Array1::linspace(0.5, 1.5, 9);
+        let (a, c) = (array![3.0], array![0.25]);
+
+        let out = eval
+            .eval_ndarray(&[xs.view(), a.view(), bs.view(), c.view()])
+            .unwrap();
+        let expected = manual(
+            &eval,
+            &[
+                xs.as_slice().unwrap(),
+                &[3.0],
+                bs.as_slice().unwrap(),
+                &[0.25],
+            ],
+        );
+        assert_eq!(out.to_vec(), expected);
+
+        // All scalars: one point
+        let out = eval
+            .eval_ndarray(&[array![1.0].view(), a.view(), array![2.0].view(), c.view()])
+            .unwrap();
+        assert_eq!(
+            out.to_vec(),
+            manual(&eval, &[&[1.0], &[3.0], &[2.0], &[0.25]])
+        );
+    }
+
+    #[test]
+    fn test_non_broadcastable_lengths_name_the_shapes() {
+        let eval = compiled("x*y + z", &["x", "y", "z"]);
+        let err = eval
+            .eval_ndarray(&[
+                Array1::zeros(3).view(),
+                array![1.0].view(),
+                Array1::zeros(4).view(),
+            ])
+            .unwrap_err();
+        assert!(
+            matches!(&err, DiffError::EvalBroadcastMismatch { lengths } if lengths == &[3, 1, 4])
+        );
+        assert!(err.to_string().contains("[3, 1, 4]"));
+
+        let err = eval.eval_ndarray(&[array![1.0].view()]).unwrap_err();
+        assert!(matches!(
+            err,
+            DiffError::EvalColumnMismatch {
+                expected: 3,
+                got: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn test_2d_columns_are_parameters_in_either_layout() {
+        let eval = compiled("x*y + z", &["x", "y", "z"]);
+        let rows = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [-1.0, 0.5, 2.0]];
+        let flat: Vec<f64> = rows.iter().flatten().copied().collect();
+        let row_major = Array2::from_shape_vec((3, 3), flat.clone()).unwrap();
+        let mut col_major = Array2::zeros((3, 3).f());
+        col_major.assign(&row_major);
+
+        let expected: Vec<f64> = rows.iter().map(|row| eval.evaluate(row)).collect();
+        assert_eq!(
+            eval.eval_ndarray_2d(row_major.view()).unwrap().to_vec(),
+            expected
+        );
+        assert_eq!(
+            eval.eval_ndarray_2d(col_major.view()).unwrap().to_vec(),
+            expected
+        );
+
+        // Zero points still check the column count
+        let empty = Array2::<f64>::zeros((0, 3));
+        assert!(eval.eval_ndarray_2d(empty.view()).unwrap().is_empty());
+        assert!(eval.eval_ndarray_2d(Array2::zeros((2, 2)).view()).is_err());
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_inputs {
+    use super::*;
+    use nalgebra::{DMatrix, DVector};
+
+    #[test]
+    fn test_vectors_broadcast_like_manual_loops() {
+        let eval = compiled(FORMULA, &["x", "a", "b", "c"]);
+        let xs = DVector::from_fn(7, |i, _| f64::from(u8::try_from(i).unwrap()) * 0.3 - 1.0);
+        let bs = DVector::from_element(7, 2.0);
+        let (a, c) = (
+            DVector::from_element(1, -1.5),
+            DVector::from_element(1, 4.0),
+        );
+
+        let out = eval.eval_nalgebra(&[&xs, &a, &bs, &c]).unwrap();
+        let expected = manual(&eval, &[xs.as_slice(), &[-1.5], bs.as_slice(), &[4.0]]);
+        assert_eq!(out.as_slice(), expected.as_slice());
+
+        let err = eval
+            .eval_nalgebra(&[&xs, &a, &DVector::zeros(2), &c])
+            .unwrap_err();
+        assert!(err.to_string().contains("[7, 1, 2, 1]"));
+    }
+
+    #[test]
+    fn test_matrix_columns_are_parameters() {
+        let eval = compiled("x*y + z", &["x", "y", "z"]);
+        let points = DMatrix::from_row_slice(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let out = eval.eval_nalgebra_matrix(&points).unwrap();
+        assert_eq!(out.as_slice(), &[5.0, 26.0]);
+
+        assert!(
+            eval.eval_nalgebra_matrix(&DMatrix::zeros(0, 3))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
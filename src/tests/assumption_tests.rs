@@ -0,0 +1,84 @@
+//! Simplification under sign assumptions: `Simplify::assume_positive` and
+//! `Simplify::assume_non_negative`
+
+use crate::Simplify;
+
+fn simplify(builder: Simplify, input: &str) -> String {
+    builder.simplify_str(input, &[]).unwrap().to_string()
+}
+
+#[test]
+fn test_sqrt_of_square_needs_positive_variable() {
+    assert_eq!(simplify(Simplify::new(), "sqrt(x^2)"), "abs(x)");
+    assert_eq!(
+        simplify(Simplify::new().assume_positive(&["x"]), "sqrt(x^2)"),
+        "x"
+    );
+    // The assumption is about x only
+    assert_eq!(
+        simplify(Simplify::new().assume_positive(&["y"]), "sqrt(x^2)"),
+        "abs(x)"
+    );
+    assert_eq!(
+        simplify(Simplify::new().assume_non_negative(&["x"]), "sqrt(x^2)"),
+        "x"
+    );
+}
+
+#[test]
+fn test_abs_and_sign_of_assumed_expressions() {
+    let positive = || Simplify::new().assume_positive(&["x"]);
+    assert_eq!(simplify(positive(), "abs(2*x^3)"), "2*x^3");
+    assert_eq!(simplify(positive(), "sign(x*exp(y))"), "1");
+    assert_eq!(simplify(positive(), "ln(x^2)"), "2*ln(x)");
+    // x - 1 may be negative
+    assert_eq!(simplify(positive(), "abs(x - 1)"), "abs(-1 + x)");
+    // Zero has sign 0
+    assert_eq!(
+        simplify(Simplify::new().assume_non_negative(&["x"]), "sign(x)"),
+        "signum(x)"
+    );
+}
+
+#[test]
+fn test_domain_safe_rules_run_under_assumptions() {
+    let safe = || Simplify::new().domain_safe(true);
+    for (input, plain, assumed) in [
+        ("(x*y)/(x*z)", "x*y/(x*z)", "y/z"),
+        ("exp(ln(x))", "exp(ln(x))", "x"),
+        ("exp(2*ln(x))", "exp(2*ln(x))", "x^2"),
+        ("ln(x^3)", "ln(x^3)", "3*ln(x)"),
+        ("ln(sqrt(x))", "ln(sqrt(x))", "ln(x)/2"),
+    ] {
+        assert_eq!(simplify(safe(), input), plain, "{input}");
+        assert_eq!(
+            simplify(safe().assume_positive(&["x"]), input),
+            assumed,
+            "{input}"
+        );
+    }
+
+    // Merging roots needs both radicands non-negative
+    assert_ne!(
+        simplify(safe().assume_non_negative(&["x"]), "sqrt(x)*sqrt(y)"),
+        "sqrt(x*y)"
+    );
+    assert_eq!(
+        simplify(safe().assume_non_negative(&["x", "y"]), "sqrt(x)*sqrt(y)"),
+        "sqrt(x*y)"
+    );
+}
+
+#[test]
+fn test_assumptions_accept_symbols() {
+    let x = crate::symb("x");
+    let expr = x.pow(2.0).sqrt();
+    assert_eq!(
+        Simplify::new()
+            .assume_positive(&[&x])
+            .simplify(&expr)
+            .unwrap()
+            .to_string(),
+        "x"
+    );
+}
@@ -0,0 +1,161 @@
+//! Regression tests: coefficients far from 1 must survive diff, simplify and compilation
+//!
+//! Absolute zero thresholds once turned `1e-15*T^7` into `0`; every magnitude here is a
+//! genuine coefficient and has to come back out with full relative precision.
+
+use crate::{CompiledEvaluator, Expr, diff, parse, simplify};
+use std::collections::HashSet;
+
+const REL_TOL: f64 = 1e-12;
+
+/// Powers of ten from 1e-30 to 1e+30, plus a few physical constants
+fn magnitudes() -> Vec<f64> {
+    let mut out: Vec<f64> = (-30..=30).map(|k| 10f64.powi(k)).collect();
+    // Boltzmann, Planck, elementary charge, Avogadro
+    out.extend([
+        1.380_649e-23,
+        6.626_070_15e-34,
+        1.602_176_634e-19,
+        6.022_140_76e23,
+    ]);
+    out
+}
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Evaluate `formula` through the compiled evaluator with `vars` bound to `values`
+fn compiled(formula: &str, vars: &[&str], values: &[f64]) -> f64 {
+    let expr = parse_str(formula);
+    CompiledEvaluator::compile(&expr, vars, None)
+        .unwrap()
+        .evaluate(values)
+}
+
+fn assert_rel(got: f64, expected: f64, what: &str) {
+    assert!(
+        (got - expected).abs() <= REL_TOL * expected.abs(),
+        "{what}: got {got:e}, expected {expected:e}"
+    );
+}
+
+#[test]
+fn test_diff_keeps_small_and_large_coefficients() {
+    for c in magnitudes() {
+        let derivative = diff(&format!("{c:e}*T^7"), "T", &[], None).unwrap();
+        assert_ne!(derivative, "0", "d/dT {c:e}*T^7");
+        let got = compiled(&derivative, &["T"], &[1.5]);
+        assert_rel(
+            got,
+            7.0 * c * 1.5_f64.powi(6),
+            &format!("d/dT {c:e}*T^7 = {derivative}"),
+        );
+    }
+}
+
+#[test]
+fn test_diff_of_reported_case() {
+    assert_ne!(diff("1.0E-15*T^7", "T", &[], None).unwrap(), "0");
+    assert_ne!(diff("1.0E-10*T^7", "T", &[], None).unwrap(), "0");
+}
+
+#[test]
+fn test_simplify_combines_like_terms_with_small_coefficients() {
+    for c in magnitudes() {
+        let simplified = simplify(&format!("{c:e}*x + {c:e}*x"), &[], None).unwrap();
+        let got = compiled(&simplified, &["x"], &[3.0]);
+        assert_rel(got, 6.0 * c, &format!("{c:e}*x + {c:e}*x = {simplified}"));
+    }
+}
+
+#[test]
+fn test_simplify_cancels_fraction_with_small_coefficient() {
+    for c in magnitudes() {
+        let simplified = simplify(&format!("({c:e}*x)/x"), &[], None).unwrap();
+        let got = compiled(&simplified, &[], &[]);
+        assert_rel(got, c, &format!("({c:e}*x)/x = {simplified}"));
+    }
+}
+
+#[test]
+fn test_simplify_keeps_small_constant_term() {
+    for c in magnitudes() {
+        let simplified = simplify(&format!("{c:e}*x^2 - x*{c:e}*x + {c:e}"), &[], None).unwrap();
+        let got = compiled(&simplified, &["x"], &[2.0]);
+        assert_rel(
+            got,
+            c,
+            &format!("{c:e}*x^2 - x*{c:e}*x + {c:e} = {simplified}"),
+        );
+    }
+}
+
+#[test]
+fn test_polynomial_terms_with_small_coefficients() {
+    for c in magnitudes() {
+        let formula = format!("{c:e}*x^3 + 2*{c:e}*x^2 + {c:e}*x + {c:e}");
+        let simplified = simplify(&formula, &[], None).unwrap();
+        let got = compiled(&simplified, &["x"], &[2.0]);
+        assert_rel(
+            got,
+            c * (8.0 + 8.0 + 2.0 + 1.0),
+            &format!("{formula} = {simplified}"),
+        );
+    }
+}
+
+#[test]
+fn test_compiled_evaluator_keeps_small_constants() {
+    for c in magnitudes() {
+        assert_rel(compiled(&format!("x + {c:e}"), &["x"], &[0.0]), c, "x + c");
+        assert_rel(
+            compiled(&format!("{c:e} + {c:e}"), &[], &[]),
+            2.0 * c,
+            "c + c",
+        );
+        assert_rel(
+            compiled(&format!("{c:e}*x*y"), &["x", "y"], &[2.0, 3.0]),
+            6.0 * c,
+            "c*x*y",
+        );
+    }
+}
+
+#[test]
+fn test_numeric_functions_of_small_arguments_stay_nonzero() {
+    for c in magnitudes().into_iter().filter(|c| *c < 1e-8) {
+        let simplified = simplify(&format!("sqrt({:e})", c * c), &[], None).unwrap();
+        assert_rel(
+            compiled(&simplified, &[], &[]),
+            c,
+            &format!("sqrt(c^2) = {simplified}"),
+        );
+        let simplified = simplify(&format!("sin({c:e})"), &[], None).unwrap();
+        assert_rel(
+            compiled(&simplified, &[], &[]),
+            c.sin(),
+            &format!("sin(c) = {simplified}"),
+        );
+    }
+}
+
+#[test]
+fn test_boltzmann_energy_gradient() {
+    let derivative = diff("3/2 * 1.380649e-23 * T", "T", &[], None).unwrap();
+    assert_rel(
+        compiled(&derivative, &[], &[]),
+        1.5 * 1.380_649e-23,
+        &derivative,
+    );
+}
+
+#[test]
+fn test_rounding_residue_still_cancels() {
+    assert_eq!(simplify("0.1*x + 0.2*x - 0.3*x", &[], None).unwrap(), "0");
+    assert_eq!(simplify("0.1 + 0.2 - 0.3", &[], None).unwrap(), "0");
+    assert_eq!(
+        simplify("1e-20*(0.1*x + 0.2*x - 0.3*x)", &[], None).unwrap(),
+        "0"
+    );
+}
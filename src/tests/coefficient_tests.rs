@@ -0,0 +1,173 @@
+//! Tests for coefficient extraction, collecting terms and degrees in one symbol
+
+use crate::{Expr, diff, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn simplified(input: &str) -> Expr {
+    parse_str(input).simplified().unwrap()
+}
+
+#[test]
+fn test_coefficient_of_single_variable() {
+    let expr = parse_str("3*x^2 + 5*x - 1");
+    assert_eq!(expr.coefficient_of(&parse_str("x^2")), Expr::number(3.0));
+    assert_eq!(expr.coefficient_of(&parse_str("x")), Expr::number(5.0));
+    // The monomial 1 picks nothing out
+    assert_eq!(expr.coefficient_of(&Expr::number(1.0)), expr);
+    // A numeric factor in the monomial divides the coefficient
+    assert_eq!(expr.coefficient_of(&parse_str("2*x^2")), simplified("3/2"));
+}
+
+#[test]
+fn test_coefficient_of_missing_monomial_is_zero() {
+    let expr = parse_str("3*x^2 + 5*x - 1");
+    assert_eq!(expr.coefficient_of(&parse_str("x^3")), Expr::number(0.0));
+    assert_eq!(expr.coefficient_of(&parse_str("y")), Expr::number(0.0));
+    assert_eq!(expr.coefficient_of(&parse_str("x*y")), Expr::number(0.0));
+    // Not a product of symbol powers
+    assert_eq!(expr.coefficient_of(&parse_str("sin(x)")), Expr::number(0.0));
+    assert_eq!(expr.coefficient_of(&parse_str("x + 1")), Expr::number(0.0));
+}
+
+#[test]
+fn test_coefficient_of_multivariable_monomials() {
+    let expr = parse_str("4*x^2*y + 3*x^2 - x*y^2 + a*x*y + 7");
+    assert_eq!(expr.coefficient_of(&parse_str("x^2*y")), Expr::number(4.0));
+    assert_eq!(expr.coefficient_of(&parse_str("x*y^2")), Expr::number(-1.0));
+    // Symbols outside the monomial stay in the coefficient
+    assert_eq!(expr.coefficient_of(&parse_str("x*y")), symb("a").to_expr());
+    assert_eq!(
+        expr.coefficient_of(&parse_str("x^2")),
+        simplified("4*y + 3")
+    );
+}
+
+#[test]
+fn test_coefficient_of_unit_monomials() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = parse_str("x + x*y + 2");
+    assert_eq!(expr.coefficient_of(&x.to_expr()), simplified("1 + y"));
+    assert_eq!(expr.coefficient_of(&(x * y)), Expr::number(1.0));
+}
+
+#[test]
+fn test_coefficient_of_repeated_monomials_after_expansion() {
+    // (x + 1)^2 + 3*x*(x - 2) = 4*x^2 - 4*x + 1
+    let expr = parse_str("(x + 1)^2 + 3*x*(x - 2)");
+    assert_eq!(expr.coefficient_of(&parse_str("x^2")), Expr::number(4.0));
+    assert_eq!(expr.coefficient_of(&parse_str("x")), Expr::number(-4.0));
+    // x*y written three ways sums into one coefficient
+    let expr = parse_str("x*y + y*x*2 + (x*y)*b");
+    assert_eq!(expr.coefficient_of(&parse_str("x*y")), simplified("3 + b"));
+}
+
+#[test]
+fn test_coefficient_of_skips_non_polynomial_terms() {
+    let expr = parse_str("sin(x) + 2*x + x*exp(x)");
+    assert_eq!(expr.coefficient_of(&parse_str("x")), Expr::number(2.0));
+}
+
+#[test]
+fn test_collect_orders_by_degree() {
+    let x = symb("x");
+    let pairs = parse_str("a*x + 3*x^2 - 1 + x^2 + b*x^3").collect_terms(&x);
+    let expected = vec![
+        (symb("b").to_expr(), x.pow(3.0)),
+        (Expr::number(4.0), x.pow(2.0)),
+        (symb("a").to_expr(), x.to_expr()),
+        (Expr::number(-1.0), Expr::number(1.0)),
+    ];
+    assert_eq!(pairs, expected);
+}
+
+#[test]
+fn test_collect_drops_zero_coefficients() {
+    let x = symb("x");
+    let pairs = parse_str("x^4 + (x + 1)*(x - 1) - x^2").collect_terms(&x);
+    assert_eq!(
+        pairs,
+        vec![
+            (Expr::number(1.0), x.pow(4.0)),
+            (Expr::number(-1.0), Expr::number(1.0))
+        ]
+    );
+    assert!(Expr::number(0.0).collect_terms(&x).is_empty());
+}
+
+#[test]
+fn test_collect_keeps_non_polynomial_terms_in_the_constant() {
+    let x = symb("x");
+    let expr = parse_str("sin(x) + 2*x + 5");
+    let pairs = expr.collect_terms(&x);
+    assert_eq!(pairs[0], (Expr::number(2.0), x.to_expr()));
+    assert_eq!(pairs[1], (simplified("sin(x) + 5"), Expr::number(1.0)));
+
+    // The pairs add back up to the expression
+    let rebuilt = Expr::sum(pairs.into_iter().map(|(c, p)| c * p).collect());
+    assert_eq!(rebuilt.simplified().unwrap(), expr.simplified().unwrap());
+}
+
+#[test]
+fn test_collect_groups_symbolic_coefficients() {
+    let x = symb("x");
+    let (a, b, c, d) = (symb("a"), symb("b"), symb("c"), symb("d"));
+    let collected = parse_str("a*x^2 + b*x + c*x^2 + d").collect(&x);
+    assert_eq!(collected, (a + c) * x.pow(2.0) + b * x + d);
+    // Collecting again changes nothing
+    assert_eq!(collected.collect(&x), collected);
+}
+
+#[test]
+fn test_leading_coefficient_of_quadratic() {
+    let x = symb("x");
+    let quadratic = parse_str("a*x^2 + b*x + c");
+    assert_eq!(quadratic.degree_in(&x), Some(2));
+    assert_eq!(quadratic.coefficient(&x, 2), symb("a").to_expr());
+    assert_eq!(quadratic.coefficient(&x, 1), symb("b").to_expr());
+    assert_eq!(quadratic.coefficient(&x, 0), symb("c").to_expr());
+    assert_eq!(quadratic.coefficient(&x, 3), Expr::number(0.0));
+
+    // Written as a product, the leading coefficient is still read off the expansion
+    let factored = parse_str("a*(x - p)*(x - q)");
+    assert_eq!(factored.degree_in(&x), Some(2));
+    assert_eq!(factored.coefficient(&x, 2), symb("a").to_expr());
+    assert_eq!(factored.coefficient(&x, 0), simplified("a*p*q"));
+}
+
+#[test]
+fn test_collect_derivative_of_product() {
+    let x = symb("x");
+    // d/dx [(a*x + b)*(c*x^2 + d)] = 3*a*c*x^2 + 2*b*c*x + a*d
+    let derivative = parse_str(&diff("(a*x + b)*(c*x^2 + d)", "x", &[], None).unwrap());
+    assert_eq!(derivative.degree_in(&x), Some(2));
+    assert_eq!(derivative.coefficient(&x, 2), simplified("3*a*c"));
+    assert_eq!(derivative.coefficient(&x, 1), simplified("2*b*c"));
+    assert_eq!(derivative.coefficient(&x, 0), simplified("a*d"));
+
+    let collected = derivative.collect(&x);
+    assert_eq!(
+        collected,
+        Expr::sum_no_poly(vec![
+            simplified("3*a*c") * x.pow(2.0),
+            simplified("2*b*c") * x,
+            simplified("a*d"),
+        ])
+    );
+}
+
+#[test]
+fn test_non_polynomial_terms_count_as_constant() {
+    let x = symb("x");
+    let expr = parse_str("x^2 + sin(x) + x*exp(x)");
+    assert_eq!(expr.degree_in(&x), None);
+    assert_eq!(expr.coefficient(&x, 2), Expr::number(1.0));
+    assert_eq!(expr.coefficient(&x, 0), simplified("sin(x) + x*exp(x)"));
+    // Cancelling leading terms do not count towards the collected degree
+    let cancelling = parse_str("x^3 + x - x^3");
+    assert_eq!(cancelling.collect(&x).degree_in(&x), Some(1));
+    assert_eq!(cancelling.coefficient(&x, 3), Expr::number(0.0));
+}
@@ -0,0 +1,84 @@
+//! Tests for comments, line breaks and line continuations in formula sources
+
+use crate::{DiffError, Span, parse, parse_program, strip_comments};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Result<crate::Expr, DiffError> {
+    parse(input, &HashSet::new(), &HashSet::new(), None)
+}
+
+#[test]
+fn test_multiline_formula_with_comments_matches_compact_form() {
+    let spread = "\
+        a * x^2      # quadratic term
+          + b * x    /* linear
+                        term */
+          + c        # offset
+          - sin(x) \\
+          / 2        # damping";
+    assert_eq!(
+        parse_plain(spread).unwrap(),
+        parse_plain("a*x^2 + b*x + c - sin(x)/2").unwrap()
+    );
+}
+
+#[test]
+fn test_formula_like_comment_text_is_ignored() {
+    let expr = parse_plain("x + 1 # ) * (y = 2; sin(").unwrap();
+    assert_eq!(expr, parse_plain("x + 1").unwrap());
+
+    let expr = parse_plain("x /* + y) / (z # */ * 2").unwrap();
+    assert_eq!(expr, parse_plain("2*x").unwrap());
+}
+
+#[test]
+fn test_comment_only_input_is_empty() {
+    assert!(matches!(
+        parse_plain("# nothing here\n/* at all */"),
+        Err(DiffError::EmptyFormula)
+    ));
+}
+
+#[test]
+fn test_unterminated_block_comment_reports_opening_span() {
+    match parse_plain("x + 1 /* never closed\n + y") {
+        Err(DiffError::InvalidSyntax { span, .. }) => assert_eq!(span, Some(Span::new(6, 8))),
+        other => panic!("expected InvalidSyntax, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_spans_after_comments_point_into_original_source() {
+    // '$' sits at byte 20, behind a comment with a multi-byte character
+    let source = "x + /* θ is ok */ 2$";
+    assert_eq!(source.find('$'), Some(20));
+    match parse_plain(source) {
+        Err(DiffError::InvalidToken { token, span }) => {
+            assert_eq!(token, "$");
+            assert_eq!(span, Some(Span::new(20, 21)));
+        }
+        other => panic!("expected InvalidToken, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_program_comments_may_contain_separators() {
+    let program = parse_program(
+        "u = x^2;  # u = x*x; not a statement\nv = /* ; */ u + 1;\nv * u",
+        None,
+    )
+    .unwrap();
+    assert_eq!(program.intermediates.len(), 2);
+    assert_eq!(program.intermediates[1].span, Span::new(37, 38));
+    assert_eq!(program.expr, parse_plain("(x^2 + 1) * x^2").unwrap());
+}
+
+#[test]
+fn test_strip_comments_preserves_offsets() {
+    let source = "a # c\n/* é */ b";
+    let cleaned = strip_comments(source).unwrap();
+    assert_eq!(cleaned.len(), source.len());
+    assert_eq!(cleaned, "a    \n         b");
+    assert_eq!(cleaned.find('b'), source.rfind('b'));
+    assert_eq!(strip_comments("x + y").unwrap(), "x + y");
+}
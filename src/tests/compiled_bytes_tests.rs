@@ -0,0 +1,119 @@
+//! Binary round trips of `CompiledEvaluator` through `to_bytes` / `from_bytes`
+
+use crate::{CompiledEvaluator, DiffError, parse};
+use std::collections::HashSet;
+
+const SUITE: &[&str] = &[
+    "2*sin(x) + x^3",
+    "x^7 - 3*x^-2 + sqrt(x) * cos(y)",
+    "exp(-x^2) / (1 + y^2)",
+    "sin(x)*cos(x) + tan(y)",
+    "a + b + c + d + e + x*y*a*b*c",
+    "atan2(y, x) + log(2, x) + besselj(1, x)",
+    "gamma(x + 2) + erf(y) + lambertw(x)",
+    "min(x, 1) + max(y, -1) + abs(x - y)",
+    "piecewise(x < 0: -x, x <= 1: x^2, exp(y))",
+    "ln(x) * x^(3/2) + 1/(exp(y) - 1)",
+    "42",
+];
+
+const POINTS: &[f64] = &[-2.5, -1.0, -0.1, 0.0, 0.3, 1.0, 2.0, 7.5, f64::NAN];
+
+fn compile(input: &str) -> CompiledEvaluator {
+    let expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile_auto(&expr, None).unwrap()
+}
+
+fn reload(eval: &CompiledEvaluator) -> CompiledEvaluator {
+    CompiledEvaluator::from_bytes(&eval.to_bytes()).unwrap()
+}
+
+fn invalid_reason(data: &[u8]) -> (usize, &'static str) {
+    match CompiledEvaluator::from_bytes(data) {
+        Err(DiffError::InvalidEvaluatorBytes { offset, reason }) => (offset, reason),
+        Err(other) => panic!("unexpected error: {other}"),
+        Ok(_) => panic!("corrupt data was accepted"),
+    }
+}
+
+#[test]
+fn test_round_trip_gives_identical_results() {
+    for input in SUITE {
+        let eval = compile(input);
+        let loaded = reload(&eval);
+        assert_eq!(loaded.param_names(), eval.param_names(), "{input}");
+        assert_eq!(loaded.workspace_size(), eval.workspace_size(), "{input}");
+        assert_eq!(loaded.disassemble(), eval.disassemble(), "{input}");
+
+        let n = eval.param_count();
+        for (i, &first) in POINTS.iter().enumerate() {
+            let params: Vec<f64> = (0..n).map(|k| POINTS[(i + k) % POINTS.len()]).collect();
+            let (want, got) = (eval.evaluate(&params), loaded.evaluate(&params));
+            assert_eq!(got.to_bits(), want.to_bits(), "{input} at {first}");
+        }
+    }
+}
+
+#[test]
+fn test_round_trip_is_stable() {
+    for input in SUITE {
+        let bytes = compile(input).to_bytes();
+        assert_eq!(reload(&compile(input)).to_bytes(), bytes, "{input}");
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_batch_evaluation_after_reload() {
+    let eval = compile("x^7 - 3*x^-2 + sqrt(x) * cos(y)");
+    let loaded = reload(&eval);
+    let xs: Vec<f64> = (1..=9).map(f64::from).collect();
+    let ys: Vec<f64> = xs.iter().map(|x| x * 0.25).collect();
+    let mut want = vec![0.0; xs.len()];
+    let mut got = vec![0.0; xs.len()];
+    eval.eval_batch(&[&xs, &ys], &mut want, None).unwrap();
+    loaded.eval_batch(&[&xs, &ys], &mut got, None).unwrap();
+    assert_eq!(got, want);
+}
+
+#[test]
+fn test_rejects_wrong_header() {
+    let mut future = compile("x + 1").to_bytes();
+    assert_eq!(invalid_reason(b"").1, "not a compiled evaluator");
+    assert_eq!(invalid_reason(b"JUNKdata").1, "not a compiled evaluator");
+
+    future[4] = 99;
+    assert_eq!(invalid_reason(&future), (4, "unsupported format version"));
+}
+
+#[test]
+fn test_rejects_truncated_and_trailing_data() {
+    let bytes = compile("sin(x) * y + 3").to_bytes();
+    for len in 6..bytes.len() {
+        assert!(
+            CompiledEvaluator::from_bytes(&bytes[..len]).is_err(),
+            "accepted a prefix of {len} bytes"
+        );
+    }
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(
+        invalid_reason(&longer),
+        (bytes.len(), "trailing bytes after the program")
+    );
+}
+
+#[test]
+fn test_rejects_out_of_range_registers() {
+    // The last instruction ends with its argument register; point it past the workspace
+    let mut corrupt = compile("sin(x)").to_bytes();
+    let at = corrupt.len() - 4;
+    corrupt[at..].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(invalid_reason(&corrupt).1, "register out of range");
+
+    // An unknown tag on the last instruction
+    let mut corrupt = compile("x + y").to_bytes();
+    let at = corrupt.len() - 13;
+    corrupt[at] = u8::MAX;
+    assert_eq!(invalid_reason(&corrupt), (at, "unknown instruction tag"));
+}
@@ -0,0 +1,147 @@
+//! Tests for `CompiledGradient`: shared-program gradients against per-component evaluators
+
+use crate::{CompiledEvaluator, CompiledGradient, Diff, DiffError, Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// One evaluator per partial derivative, the way gradients were compiled before
+fn component_evaluators(expr: &Expr, params: &[&str]) -> Vec<CompiledEvaluator> {
+    let diff = Diff::new();
+    params
+        .iter()
+        .map(|p| {
+            let partial = diff.differentiate(expr, &symb(p)).unwrap();
+            CompiledEvaluator::compile(&partial, params, None).unwrap()
+        })
+        .collect()
+}
+
+fn assert_close(actual: f64, expected: f64, context: &str) {
+    let tol = 1e-12 * expected.abs().max(1.0);
+    assert!(
+        (actual - expected).abs() <= tol || (actual.is_nan() && expected.is_nan()),
+        "{context}: got {actual}, expected {expected}"
+    );
+}
+
+const POINTS: [[f64; 3]; 7] = [
+    [0.5, -1.25, 2.0],
+    [1.0, 2.0, 3.0],
+    [-0.75, 0.3, 1.5],
+    [2.5, 0.1, -0.4],
+    [0.0, 1.0, 0.25],
+    [-1.5, -2.0, 0.75],
+    [0.9, 0.6, 3.5],
+];
+
+#[test]
+fn test_matches_component_evaluators() {
+    let params = ["x", "y", "z"];
+    for input in [
+        "exp(x*y) + sin(x + y)*z",
+        "x*y*z + x^2*y - z/(1 + x^2)",
+        "sqrt(x^2 + y^2 + z^2)",
+        "ln(1 + exp(x*y - z))",
+        "x*y + 3*x",
+        "x^2",
+        "tanh(x*y)*cos(z) + (x*y)^3",
+    ] {
+        let expr = parse_str(input);
+        let gradient = CompiledGradient::compile(&expr, &params, None).unwrap();
+        let components = component_evaluators(&expr, &params);
+        assert_eq!(gradient.param_names(), params);
+
+        let mut out = [0.0; 3];
+        for point in &POINTS {
+            gradient.evaluate(point, &mut out);
+            for (i, component) in components.iter().enumerate() {
+                let context = format!("d/d{} of {input} at {point:?}", params[i]);
+                assert_close(out[i], component.evaluate(point), &context);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shares_subexpressions_across_components() {
+    let params = ["x", "y"];
+    let expr = parse_str("exp(x*y)");
+    let gradient = CompiledGradient::compile(&expr, &params, None).unwrap();
+    let separate: usize = component_evaluators(&expr, &params)
+        .iter()
+        .map(CompiledEvaluator::instruction_count)
+        .sum();
+    // x*y and exp(x*y) are computed once for both partials
+    assert!(
+        gradient.instruction_count() < separate,
+        "{} shared vs {separate} separate",
+        gradient.instruction_count()
+    );
+
+    let mut out = [0.0; 2];
+    gradient.evaluate(&[1.0, 2.0], &mut out);
+    assert_close(out[0], 2.0 * 2.0_f64.exp(), "d/dx");
+    assert_close(out[1], 2.0_f64.exp(), "d/dy");
+}
+
+#[test]
+fn test_eval_batch_matches_evaluate() {
+    let params = ["x", "y", "z"];
+    let expr = parse_str("exp(x*y) + sin(x + y)*z + x*z^2");
+    let gradient = CompiledGradient::compile(&expr, &params, None).unwrap();
+
+    // Seven points: one full SIMD group of four and a scalar tail of three
+    let points: Vec<&[f64]> = POINTS.iter().map(<[f64; 3]>::as_slice).collect();
+    let mut batch = vec![0.0; points.len() * 3];
+    gradient.eval_batch(&points, &mut batch).unwrap();
+
+    let mut single = [0.0; 3];
+    for (point, row) in points.iter().zip(batch.chunks_exact(3)) {
+        gradient.evaluate(point, &mut single);
+        for (i, (&b, &s)) in row.iter().zip(&single).enumerate() {
+            assert_close(b, s, &format!("component {i} at {point:?}"));
+        }
+    }
+
+    let mut short = vec![0.0; 5];
+    assert!(matches!(
+        gradient.eval_batch(&points, &mut short),
+        Err(DiffError::InvalidSyntax { .. })
+    ));
+}
+
+#[test]
+fn test_constant_and_parameter_components() {
+    // d/dx = y is a parameter, d/dy = x + 2 and d/dz = 0 a constant
+    let params = ["x", "y", "z"];
+    let gradient = CompiledGradient::compile(&parse_str("x*y + 2*y"), &params, None).unwrap();
+    let mut out = [f64::NAN; 3];
+    gradient.evaluate(&[3.0, 5.0, 7.0], &mut out);
+    assert_eq!(out, [5.0, 5.0, 0.0]);
+
+    let points: Vec<&[f64]> = vec![&[1.0, 2.0, 0.0]; 5];
+    let mut batch = vec![f64::NAN; 15];
+    gradient.eval_batch(&points, &mut batch).unwrap();
+    for row in batch.chunks_exact(3) {
+        assert_eq!(row, [2.0, 3.0, 0.0]);
+    }
+}
+
+#[test]
+fn test_symbol_params_and_empty_gradient() {
+    let x = symb("cg_sym_x");
+    let y = symb("cg_sym_y");
+    let expr = x.pow(2.0) * y;
+    let gradient = CompiledGradient::compile(&expr, &[&x, &y], None).unwrap();
+    let mut out = [0.0; 2];
+    gradient.evaluate(&[3.0, 2.0], &mut out);
+    assert_eq!(out, [12.0, 9.0]);
+
+    let none: [&str; 0] = [];
+    let gradient = CompiledGradient::compile(&expr, &none, None).unwrap();
+    assert_eq!(gradient.param_count(), 0);
+    gradient.eval_batch(&[&[1.0]], &mut []).unwrap();
+}
@@ -67,7 +67,7 @@ mod api_tests {
     fn test_diff_with_custom_functions() {
         let result = diff("f(x)", "x", &[], Some(&["f"])).unwrap();
         // Custom function derivative should contain f'
-        assert!(result.contains("f'") || result.contains("∂"));
+        assert!(result.contains("f'") || result.contains("diff("));
     }
 
     // --- simplify() function ---
@@ -442,7 +442,7 @@ mod api_tests {
 
         // Result should contain product rule: f'(x)*g(x) + f(x)*g'(x)
         assert!(
-            result.contains("f'") || result.contains("∂"),
+            result.contains("f'") || result.contains("diff("),
             "Expected derivative notation in: {}",
             result
         );
@@ -0,0 +1,106 @@
+//! Tests for the `tau` constant and the physics constants loaded into a `Context`
+
+use crate::{CompiledEvaluator, Context, Expr, Simplify, parse, simplify};
+use std::collections::HashSet;
+use std::f64::consts::TAU;
+
+fn parse_in(input: &str, ctx: &Context) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), Some(ctx)).unwrap()
+}
+
+fn assert_rel_close(got: f64, expected: f64) {
+    assert!(
+        (got - expected).abs() <= 1e-12 * expected.abs(),
+        "got {got}, expected {expected}"
+    );
+}
+
+#[test]
+fn test_tau_identities() {
+    assert_eq!(simplify("tau/2 - pi", &[], None).unwrap(), "0");
+    assert_eq!(simplify("sin(tau)", &[], None).unwrap(), "0");
+    assert_eq!(simplify("cos(tau)", &[], None).unwrap(), "1");
+    assert_eq!(simplify("cos(tau/4)", &[], None).unwrap(), "0");
+    assert_eq!(simplify("sin(tau/4)", &[], None).unwrap(), "1");
+    assert_eq!(simplify("tau", &[], None).unwrap(), "2*pi");
+}
+
+#[test]
+fn test_tau_evaluates() {
+    let expr = parse("tau*x", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let compiled = CompiledEvaluator::compile_auto(&expr, None).unwrap();
+    assert_eq!(compiled.param_names(), ["x"]);
+    assert_rel_close(compiled.evaluate(&[0.5]), TAU / 2.0);
+}
+
+#[test]
+fn test_physics_constants_evaluate_with_codata_values() {
+    let ctx = Context::new().load_physics_constants();
+    assert_eq!(ctx.constant_value("c"), Some(299_792_458.0));
+    assert_eq!(ctx.constant_value("k_B"), Some(1.380_649e-23));
+
+    let expr = parse_in("h/(2*pi*hbar)", &ctx);
+    let compiled = CompiledEvaluator::compile_auto(&expr, Some(&ctx)).unwrap();
+    assert_eq!(compiled.param_count(), 0);
+    assert!((compiled.evaluate(&[]) - 1.0).abs() < 1e-9);
+
+    let energy = parse_in("k_B*N_A*T", &ctx);
+    let compiled = CompiledEvaluator::compile(&energy, &[ctx.symb("T")], Some(&ctx)).unwrap();
+    assert_rel_close(
+        compiled.evaluate(&[300.0]),
+        1.380_649e-23 * 6.022_140_76e23 * 300.0,
+    );
+}
+
+#[test]
+fn test_constant_passed_as_parameter_wins() {
+    let ctx = Context::new().load_physics_constants();
+    let expr = parse_in("c*t", &ctx);
+    let params = [ctx.symb("c"), ctx.symb("t")];
+    let compiled = CompiledEvaluator::compile(&expr, &params, Some(&ctx)).unwrap();
+    assert_rel_close(compiled.evaluate(&[2.0, 3.0]), 6.0);
+}
+
+#[test]
+fn test_constants_stay_symbolic_during_simplification() {
+    let ctx = Context::new().load_physics_constants();
+    let expr = parse_in("hbar/h", &ctx);
+    let kept = Simplify::new().context(&ctx).simplify(&expr).unwrap();
+    assert!(kept.contains_var("hbar"));
+}
+
+#[test]
+fn test_hbar_over_h_with_constant_expansion() {
+    let ctx = Context::new().load_physics_constants();
+    let expr = parse_in("hbar/h", &ctx);
+    let expanded = Simplify::new()
+        .context(&ctx)
+        .expand_constants(true)
+        .simplify(&expr)
+        .unwrap();
+    let expected = Simplify::new()
+        .simplify(&parse_in("1/(2*pi)", &ctx))
+        .unwrap();
+    assert_eq!(expanded, expected);
+}
+
+#[test]
+fn test_constants_do_not_leak_into_other_contexts() {
+    let _loaded = Context::new().load_physics_constants();
+    let other = Context::new();
+    assert_eq!(other.constant_value("c"), None);
+
+    let expr = parse_in("2*c", &other);
+    let compiled = CompiledEvaluator::compile(&expr, &[other.symb("c")], Some(&other)).unwrap();
+    assert_rel_close(compiled.evaluate(&[1.5]), 3.0);
+    assert!(CompiledEvaluator::compile(&expr, &[] as &[&str], Some(&other)).is_err());
+    assert!(CompiledEvaluator::compile(&expr, &[] as &[&str], None).is_err());
+}
+
+#[test]
+fn test_custom_constant_is_removed_with_its_symbol() {
+    let mut ctx = Context::new().with_constant("g0", 9.806_65);
+    assert_eq!(ctx.constant_value("g0"), Some(9.806_65));
+    assert!(ctx.remove_symbol("g0"));
+    assert_eq!(ctx.constant_value("g0"), None);
+}
@@ -0,0 +1,506 @@
+//! Golden-value regression corpus of realistic scientific expressions
+//!
+//! Fixtures live in `corpus/*.toml`, one `[[fixture]]` table per expression:
+//!
+//! - `expr`, `var`, optional `known`: what to differentiate (`known` are multi-character
+//!   symbols held fixed)
+//! - `derivative`: the expected `diff` output; `compare = "numeric"` checks it for numeric
+//!   equivalence at `points` instead of exact equality, for fixtures whose printed form is
+//!   expected to move as rules evolve
+//! - `simplified`: optional expected `simplify` output, always compared exactly
+//!
+//! Exact comparison accepts the expected string itself or any string that parses to the
+//! same tree: the order of terms in a sum and factors in a product follows symbol IDs,
+//! which depend on which tests created symbols first.
+//! - `vars`, `points`, `values`, `derivative_values`: evaluation order, the points, and the
+//!   golden expression and derivative values there (computed independently at high
+//!   precision)
+//! - `tol`: optional relative tolerance, default `1e-12`
+//!
+//! Only the TOML subset the fixtures use is understood: comments, `[[fixture]]` headers
+//! and `key = value` with strings, numbers, booleans and (nested) arrays.
+
+use crate::{CompiledEvaluator, Expr, diff, parse, simplify};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_TOL: f64 = 1e-12;
+
+/// Corpus file name and its contents
+const CORPUS: &[(&str, &str)] = &[
+    ("nasa", include_str!("corpus/nasa.toml")),
+    (
+        "tiny_coefficient",
+        include_str!("corpus/tiny_coefficient.toml"),
+    ),
+    ("arrhenius", include_str!("corpus/arrhenius.toml")),
+    (
+        "error_propagation",
+        include_str!("corpus/error_propagation.toml"),
+    ),
+    ("geometry", include_str!("corpus/geometry.toml")),
+];
+
+// =============================================================================
+// Fixture reader
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Array(Vec<Self>),
+}
+
+type Table = HashMap<String, Value>;
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Reader<'_> {
+    /// Skip whitespace, newlines and comments
+    fn skip_blank(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == '#' {
+                while self.chars.peek().is_some_and(|&c| c != '\n') {
+                    self.chars.next();
+                }
+            } else if c.is_whitespace() {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, want: char) {
+        self.skip_blank();
+        let got = self.chars.next();
+        assert_eq!(got, Some(want), "line {}: expected '{want}'", self.line);
+    }
+
+    fn word(&mut self) -> String {
+        let mut out = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_alphanumeric() || "_-+.".contains(c) {
+                out.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        out
+    }
+
+    fn value(&mut self) -> Value {
+        self.skip_blank();
+        match self.chars.peek() {
+            Some('"') => {
+                self.chars.next();
+                let mut out = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('"') => break,
+                        Some('\\') => out.push(self.chars.next().unwrap()),
+                        Some(c) => out.push(c),
+                        None => panic!("line {}: unterminated string", self.line),
+                    }
+                }
+                Value::Str(out)
+            }
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_blank();
+                    if self.chars.peek() == Some(&']') {
+                        self.chars.next();
+                        break;
+                    }
+                    items.push(self.value());
+                    self.skip_blank();
+                    if self.chars.peek() == Some(&',') {
+                        self.chars.next();
+                    }
+                }
+                Value::Array(items)
+            }
+            _ => match self.word().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                w => Value::Num(
+                    w.replace('_', "")
+                        .parse()
+                        .unwrap_or_else(|_| panic!("line {}: bad value '{w}'", self.line)),
+                ),
+            },
+        }
+    }
+}
+
+/// Read every `[[fixture]]` table from a corpus file
+fn read_fixtures(src: &str) -> Vec<Table> {
+    let mut reader = Reader {
+        chars: src.chars().peekable(),
+        line: 1,
+    };
+    let mut tables: Vec<Table> = Vec::new();
+    loop {
+        reader.skip_blank();
+        match reader.chars.peek() {
+            None => break,
+            Some('[') => {
+                reader.expect('[');
+                reader.expect('[');
+                let header = reader.word();
+                assert_eq!(header, "fixture", "line {}: unknown table", reader.line);
+                reader.expect(']');
+                reader.expect(']');
+                tables.push(Table::new());
+            }
+            Some(_) => {
+                let key = reader.word();
+                reader.expect('=');
+                let value = reader.value();
+                let table = tables
+                    .last_mut()
+                    .unwrap_or_else(|| panic!("line {}: key outside a fixture", reader.line));
+                table.insert(key, value);
+            }
+        }
+    }
+    tables
+}
+
+/// Every fixture expression with its `known` symbols, for tools that run over the corpus
+pub(super) fn corpus_inputs() -> Vec<(String, Vec<String>)> {
+    CORPUS
+        .iter()
+        .flat_map(|(file, src)| {
+            read_fixtures(src).into_iter().map(move |table| {
+                let fixture = Fixture::new(file, &table);
+                let known = fixture
+                    .strs("known")
+                    .into_iter()
+                    .map(str::to_owned)
+                    .collect();
+                (fixture.required_str("expr").to_owned(), known)
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Fixture accessors
+// =============================================================================
+
+struct Fixture<'a> {
+    name: String,
+    table: &'a Table,
+}
+
+impl<'a> Fixture<'a> {
+    fn new(file: &str, table: &'a Table) -> Self {
+        let name = match table.get("name") {
+            Some(Value::Str(s)) => format!("{file}/{s}"),
+            _ => panic!("{file}: fixture without a name"),
+        };
+        Self { name, table }
+    }
+
+    fn str(&self, key: &str) -> Option<&'a str> {
+        match self.table.get(key)? {
+            Value::Str(s) => Some(s),
+            v => panic!("{}: `{key}` should be a string, got {v:?}", self.name),
+        }
+    }
+
+    fn required_str(&self, key: &str) -> &'a str {
+        self.str(key)
+            .unwrap_or_else(|| panic!("{}: missing `{key}`", self.name))
+    }
+
+    fn array(&self, key: &str) -> &'a [Value] {
+        match self.table.get(key) {
+            Some(Value::Array(items)) => items,
+            None => &[],
+            Some(v) => panic!("{}: `{key}` should be an array, got {v:?}", self.name),
+        }
+    }
+
+    fn strs(&self, key: &str) -> Vec<&'a str> {
+        self.array(key)
+            .iter()
+            .map(|v| match v {
+                Value::Str(s) => s.as_str(),
+                _ => panic!("{}: `{key}` should hold strings", self.name),
+            })
+            .collect()
+    }
+
+    fn nums(&self, key: &str) -> Vec<f64> {
+        self.array(key).iter().map(|v| self.num(v, key)).collect()
+    }
+
+    fn points(&self) -> Vec<Vec<f64>> {
+        self.array("points")
+            .iter()
+            .map(|row| match row {
+                Value::Array(cells) => cells.iter().map(|v| self.num(v, "points")).collect(),
+                _ => panic!("{}: `points` should hold arrays", self.name),
+            })
+            .collect()
+    }
+
+    fn num(&self, value: &Value, key: &str) -> f64 {
+        match value {
+            Value::Num(n) => *n,
+            _ => panic!("{}: `{key}` should hold numbers", self.name),
+        }
+    }
+
+    fn tol(&self) -> f64 {
+        self.table
+            .get("tol")
+            .map_or(DEFAULT_TOL, |v| self.num(v, "tol"))
+    }
+
+    fn numeric_compare(&self) -> bool {
+        match self.str("compare") {
+            None | Some("exact") => false,
+            Some("numeric") => true,
+            Some(other) => panic!("{}: unknown compare mode '{other}'", self.name),
+        }
+    }
+}
+
+// =============================================================================
+// Harness
+// =============================================================================
+
+fn all_fixtures() -> Vec<(&'static str, Table)> {
+    CORPUS
+        .iter()
+        .flat_map(|(file, src)| read_fixtures(src).into_iter().map(move |t| (*file, t)))
+        .collect()
+}
+
+fn parse_with(formula: &str, known: &[&str]) -> Expr {
+    let known: HashSet<String> = known.iter().map(ToString::to_string).collect();
+    parse(formula, &known, &HashSet::new(), None).unwrap()
+}
+
+/// Evaluate `formula` at every point through the compiled evaluator
+fn eval_points(formula: &str, known: &[&str], vars: &[&str], points: &[Vec<f64>]) -> Vec<f64> {
+    let expr = parse_with(formula, known);
+    let evaluator = CompiledEvaluator::compile(&expr, vars, None)
+        .unwrap_or_else(|e| panic!("compile {formula}: {e:?}"));
+    points.iter().map(|p| evaluator.evaluate(p)).collect()
+}
+
+fn assert_close(got: &[f64], expected: &[f64], tol: f64, what: &str) {
+    assert_eq!(got.len(), expected.len(), "{what}: point count");
+    for (i, (g, e)) in got.iter().zip(expected).enumerate() {
+        assert!(
+            (g - e).abs() <= tol * e.abs().max(f64::MIN_POSITIVE),
+            "{what} at point {i}: got {g:e}, expected {e:e}"
+        );
+    }
+}
+
+/// Exact comparison up to the order of commutative operands
+fn assert_same_expr(got: &str, expected: &str, known: &[&str], what: &str) {
+    if got != expected {
+        assert_eq!(
+            parse_with(got, known),
+            parse_with(expected, known),
+            "{what}: got {got}, expected {expected}"
+        );
+    }
+}
+
+/// Run one fixture, returning a failure description instead of panicking so the whole
+/// corpus is reported at once
+fn run_fixture(fixture: &Fixture<'_>) -> Result<(), String> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| check_fixture(fixture))).map_err(
+        |payload| {
+            payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(ToString::to_string))
+                .unwrap_or_default()
+        },
+    )
+}
+
+fn check_fixture(fixture: &Fixture<'_>) {
+    let name = &fixture.name;
+    let expr = fixture.required_str("expr");
+    let var = fixture.required_str("var");
+    let known = fixture.strs("known");
+    let vars = fixture.strs("vars");
+    let points = fixture.points();
+    let tol = fixture.tol();
+
+    let derivative = diff(expr, var, &known, None).unwrap_or_else(|e| panic!("{name}: {e:?}"));
+    let expected_derivative = fixture.required_str("derivative");
+    if fixture.numeric_compare() {
+        assert_close(
+            &eval_points(&derivative, &known, &vars, &points),
+            &eval_points(expected_derivative, &known, &vars, &points),
+            tol,
+            &format!("{name}: derivative {derivative} vs {expected_derivative}"),
+        );
+    } else {
+        assert_same_expr(
+            &derivative,
+            expected_derivative,
+            &known,
+            &format!("{name}: derivative"),
+        );
+    }
+
+    if let Some(expected) = fixture.str("simplified") {
+        let simplified = simplify(expr, &known, None).unwrap();
+        assert_same_expr(
+            &simplified,
+            expected,
+            &known,
+            &format!("{name}: simplified"),
+        );
+    }
+
+    assert_close(
+        &eval_points(expr, &known, &vars, &points),
+        &fixture.nums("values"),
+        tol,
+        &format!("{name}: value"),
+    );
+    assert_close(
+        &eval_points(&derivative, &known, &vars, &points),
+        &fixture.nums("derivative_values"),
+        tol,
+        &format!("{name}: derivative value"),
+    );
+}
+
+#[test]
+fn test_corpus() {
+    let fixtures = all_fixtures();
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|(file, table)| run_fixture(&Fixture::new(file, table)).err())
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} corpus fixtures failed:\n{}",
+        failures.len(),
+        fixtures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_corpus_is_well_formed() {
+    let fixtures = all_fixtures();
+    assert!(
+        fixtures.len() >= 30,
+        "corpus has {} fixtures",
+        fixtures.len()
+    );
+
+    let mut names = HashSet::new();
+    for (file, table) in &fixtures {
+        let fixture = Fixture::new(file, table);
+        assert!(
+            names.insert(fixture.name.clone()),
+            "duplicate {}",
+            fixture.name
+        );
+        let vars = fixture.strs("vars");
+        let points = fixture.points();
+        assert!(!points.is_empty(), "{}: no points", fixture.name);
+        for p in &points {
+            assert_eq!(p.len(), vars.len(), "{}: point width", fixture.name);
+        }
+        assert_eq!(
+            fixture.nums("values").len(),
+            points.len(),
+            "{}",
+            fixture.name
+        );
+        assert_eq!(
+            fixture.nums("derivative_values").len(),
+            points.len(),
+            "{}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn test_corpus_covers_reported_tiny_coefficients() {
+    let fixtures = all_fixtures();
+    for reported in ["1.0E-15*T^7", "1.0E-10*T^7"] {
+        assert!(
+            fixtures
+                .iter()
+                .any(|(_, t)| t.get("expr") == Some(&Value::Str(reported.to_owned()))),
+            "missing {reported}"
+        );
+    }
+}
+
+#[test]
+fn test_reader_handles_subset() {
+    let tables = read_fixtures(
+        "# comment\n[[fixture]]\nname = \"a\\\"b\" # trailing\nn = -1.5e-3\nok = true\n\
+         xs = [\n  [1, 2],\n  [3_000.0],\n]\n\n[[fixture]]\nname = \"c\"\n",
+    );
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[0]["name"], Value::Str("a\"b".to_owned()));
+    assert_eq!(tables[0]["n"], Value::Num(-1.5e-3));
+    assert_eq!(tables[0]["ok"], Value::Bool(true));
+    assert_eq!(
+        tables[0]["xs"],
+        Value::Array(vec![
+            Value::Array(vec![Value::Num(1.0), Value::Num(2.0)]),
+            Value::Array(vec![Value::Num(3000.0)]),
+        ])
+    );
+    assert_eq!(tables[1]["name"], Value::Str("c".to_owned()));
+}
+
+#[test]
+fn test_numeric_compare_catches_wrong_derivative() {
+    let table = &read_fixtures(
+        "[[fixture]]\nname = \"wrong\"\nexpr = \"x^3\"\nvar = \"x\"\n\
+         derivative = \"2*x^2\"\ncompare = \"numeric\"\nvars = [\"x\"]\n\
+         points = [[2.0]]\nvalues = [8.0]\nderivative_values = [12.0]\n",
+    )[0];
+    let err = run_fixture(&Fixture::new("inline", table)).unwrap_err();
+    assert!(err.contains("inline/wrong: derivative"), "{err}");
+}
+
+#[test]
+fn test_exact_compare_ignores_operand_order_only() {
+    assert_same_expr(
+        "x*cos(t) - y*sin(t)",
+        "-y*sin(t) + cos(t)*x",
+        &[],
+        "swapped",
+    );
+    let err = std::panic::catch_unwind(|| {
+        assert_same_expr("x*(1 + t)", "x + t*x", &[], "expanded");
+    })
+    .unwrap_err();
+    assert!(
+        err.downcast_ref::<String>()
+            .is_some_and(|e| e.contains("expanded"))
+    );
+}
@@ -0,0 +1,153 @@
+//! Common subexpression extraction: `Expr::extract_cse` and `Expr::extract_cse_many`
+
+use crate::{Expr, Simplify, Symbol, gradient, hessian, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// `expr` with the bindings substituted back, last binding first
+fn recompose(bindings: &[(Symbol, Expr)], expr: &Expr) -> Expr {
+    bindings
+        .iter()
+        .rev()
+        .fold(expr.clone(), |acc, (temp, definition)| {
+            acc.substitute_symbol(temp, definition)
+        })
+}
+
+fn simplified(expr: &Expr) -> Expr {
+    Simplify::new().simplify(expr).unwrap()
+}
+
+/// Every definition refers only to temporaries bound before it
+fn assert_dependency_order(bindings: &[(Symbol, Expr)]) {
+    let temps: Vec<String> = bindings
+        .iter()
+        .map(|(temp, _)| temp.name().unwrap())
+        .collect();
+    for (index, (_, definition)) in bindings.iter().enumerate() {
+        for var in definition.variables() {
+            if let Some(bound) = temps.iter().position(|temp| *temp == var) {
+                assert!(
+                    bound < index,
+                    "{var} used by binding {index} before it is bound"
+                );
+            }
+        }
+    }
+}
+
+fn eval_at(expr: &Expr, point: &[(&str, f64)]) -> f64 {
+    let vars: HashMap<&str, f64> = point.iter().copied().collect();
+    expr.partial_eval(&vars).as_number().unwrap()
+}
+
+#[test]
+fn test_repeated_factor_becomes_one_binding() {
+    let shared = parse_str("exp(-(x - mu)^2/s)");
+    let expr = parse_str("exp(-(x - mu)^2/s)*x + sin(exp(-(x - mu)^2/s)) + y/exp(-(x - mu)^2/s)");
+    let (bindings, rewritten) = expr.extract_cse(2);
+
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].1, shared);
+    let temp = bindings[0].0;
+    assert!(rewritten.variables().contains(&temp.name().unwrap()));
+    let bound_nodes: usize = bindings.iter().map(|(_, def)| def.node_count()).sum();
+    assert!(bound_nodes + rewritten.node_count() < expr.node_count());
+    assert_eq!(recompose(&bindings, &rewritten), expr);
+}
+
+#[test]
+fn test_nested_sharing_orders_bindings() {
+    // (a + b) is shared on its own and inside the shared (a + b)^2
+    let expr = parse_str("sin((a + b)^2) + cos((a + b)^2) + c*(a + b)");
+    let (bindings, rewritten) = expr.extract_cse(2);
+
+    assert_eq!(bindings.len(), 2);
+    assert_eq!(bindings[0].1, parse_str("a + b"));
+    let inner = bindings[0].0;
+    assert_eq!(bindings[1].1, inner.pow(2.0));
+    assert_dependency_order(&bindings);
+    assert_eq!(
+        simplified(&recompose(&bindings, &rewritten)),
+        simplified(&expr)
+    );
+}
+
+#[test]
+fn test_copies_inside_a_shared_subtree_count_once() {
+    // x*y only ever occurs inside the repeated exp(x*y), so binding it gains nothing
+    let expr = parse_str("exp(x*y) + z*exp(x*y)");
+    let (bindings, _) = expr.extract_cse(2);
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].1, parse_str("exp(x*y)"));
+}
+
+#[test]
+fn test_min_occurrences_threshold() {
+    let expr = parse_str("sin(x + y)*z + cos(sin(x + y))");
+    let (bindings, rewritten) = expr.extract_cse(3);
+    assert!(bindings.is_empty());
+    assert_eq!(rewritten, expr);
+
+    // Below 2 behaves as 2
+    assert_eq!(expr.extract_cse(0).0.len(), 1);
+}
+
+#[test]
+fn test_temporary_names_avoid_existing_symbols() {
+    let expr = parse_str("sin(t0 + t1)*z + cos(sin(t0 + t1))");
+    let (bindings, _) = expr.extract_cse(2);
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].0.name().as_deref(), Some("t2"));
+}
+
+#[test]
+fn test_hessian_components_share_temporaries() {
+    let (x, mu, s) = (symb("x"), symb("mu"), symb("s"));
+    let likelihood = parse_str("exp(-(x - mu)^2/s)");
+    let hess: Vec<Expr> = hessian(&likelihood, &[&x, &mu, &s])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    let (bindings, rewritten) = Expr::extract_cse_many(&hess, 2);
+
+    assert_eq!(rewritten.len(), hess.len());
+    let shared = simplified(&likelihood);
+    // Bound once, in terms of earlier temporaries
+    assert!((0..bindings.len()).any(|i| recompose(&bindings[..i], &bindings[i].1) == shared));
+    assert_dependency_order(&bindings);
+    let before: usize = hess.iter().map(Expr::node_count).sum();
+    let after: usize = bindings
+        .iter()
+        .map(|(_, def)| def.node_count())
+        .sum::<usize>()
+        + rewritten.iter().map(Expr::node_count).sum::<usize>();
+    assert!(after < before, "{after} nodes after CSE, {before} before");
+
+    let point = [("x", 0.3), ("mu", -0.2), ("s", 1.7)];
+    for (original, component) in hess.iter().zip(&rewritten) {
+        let recomposed = recompose(&bindings, component);
+        let (want, got) = (eval_at(original, &point), eval_at(&recomposed, &point));
+        assert!((want - got).abs() <= 1e-12 * want.abs().max(1.0));
+    }
+}
+
+#[test]
+fn test_gradient_components_share_one_binding_list() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = parse_str("exp(x*y)*sin(x + y)");
+    let grad = gradient(&expr, &[&x, &y]).unwrap();
+    let (bindings, rewritten) = Expr::extract_cse_many(&grad, 2);
+
+    assert!(!bindings.is_empty());
+    for (original, component) in grad.iter().zip(&rewritten) {
+        assert_eq!(
+            simplified(&recompose(&bindings, component)),
+            simplified(original)
+        );
+    }
+}
@@ -111,3 +111,116 @@ fn test_arity_check() {
     let result = crate::CompiledEvaluator::compile(&expr_bad, &["x"], Some(&ctx));
     assert!(result.is_err(), "Should fail with invalid arity");
 }
+
+/// `2*props(T) + T^2` with `props` known only through a native callback
+fn props_expr() -> (Expr, Context) {
+    use crate::core::UserFunction;
+
+    let props = UserFunction::new(1..=1).numeric(|args| 1.5 * args[0] + 0.25);
+    let ctx = Context::new().with_function("props", props);
+    let t = crate::symb("T");
+    (2.0 * Expr::func("props", t) + t.pow(2.0), ctx)
+}
+
+fn props_reference(t: f64) -> f64 {
+    2.0_f64.mul_add(1.5_f64.mul_add(t, 0.25), t * t)
+}
+
+#[test]
+fn test_compiled_native_callback() {
+    let (expr, ctx) = props_expr();
+    let evaluator = crate::CompiledEvaluator::compile(&expr, &["T"], Some(&ctx))
+        .expect("Should compile through the native callback");
+
+    for t in [-2.0, 0.0, 0.5, 300.0] {
+        let result = evaluator.evaluate(&[t]);
+        assert!(
+            (result - props_reference(t)).abs() < 1e-9,
+            "T = {t}: got {result}"
+        );
+    }
+    assert!(evaluator.disassemble().contains("ext0"));
+}
+
+#[test]
+fn test_compiled_native_callback_multi_argument() {
+    use crate::core::UserFunction;
+
+    let blend = UserFunction::new(3..=3).numeric(|args| args[0] * args[1] - args[2]);
+    let ctx = Context::new().with_function("blend", blend);
+    let (x, y) = (crate::symb("x"), crate::symb("y"));
+    // Both calls share the callback; the repeated one is computed once
+    let expr = Expr::func_multi("blend", vec![x.into(), y.into(), Expr::number(1.0)])
+        + Expr::func_multi("blend", vec![x.into(), y.into(), Expr::number(1.0)]).pow(2.0);
+    let evaluator =
+        crate::CompiledEvaluator::compile(&expr, &["x", "y"], Some(&ctx)).expect("Should compile");
+
+    let (xv, yv) = (3.0, -0.5);
+    let inner = xv * yv - 1.0;
+    assert!((evaluator.evaluate(&[xv, yv]) - (inner + inner * inner)).abs() < 1e-12);
+
+    // Forward mode differentiates the callback numerically: d/dx = y + 2*(xy - 1)*y
+    let (_, dx) = evaluator.eval_dual(&[xv, yv], &[1.0, 0.0]);
+    assert!(
+        (dx - 2.0_f64.mul_add(inner * yv, yv)).abs() < 1e-6,
+        "got {dx}"
+    );
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_compiled_native_callback_batch() {
+    use wide::f64x4;
+
+    let (expr, ctx) = props_expr();
+    let evaluator =
+        crate::CompiledEvaluator::compile(&expr, &["T"], Some(&ctx)).expect("Should compile");
+
+    // Seven points: one full SIMD chunk of four plus a scalar tail
+    let temps: Vec<f64> = (0..7).map(|i| f64::from(i) * 1.25 - 2.0).collect();
+    let mut scalar = vec![0.0; temps.len()];
+    evaluator
+        .eval_batch(&[&temps], &mut scalar, None)
+        .expect("Scalar batch should succeed");
+    let mut workspace = vec![f64x4::splat(0.0); evaluator.workspace_size()];
+    let mut simd = vec![0.0; temps.len()];
+    evaluator
+        .eval_batch(&[&temps], &mut simd, Some(&mut workspace))
+        .expect("SIMD batch should succeed");
+
+    for ((&t, &a), &b) in temps.iter().zip(&scalar).zip(&simd) {
+        assert!(
+            (a - props_reference(t)).abs() < 1e-9,
+            "scalar T = {t}: got {a}"
+        );
+        assert!(
+            (b - props_reference(t)).abs() < 1e-9,
+            "SIMD T = {t}: got {b}"
+        );
+    }
+}
+
+#[test]
+fn test_compiled_native_callback_missing() {
+    let (expr, _) = props_expr();
+    let err = crate::CompiledEvaluator::compile(&expr, &["T"], Some(&Context::new()))
+        .expect_err("props has neither a body nor a callback");
+    assert!(
+        matches!(&err, crate::DiffError::UnsupportedFunction(name) if name.as_str() == "props"),
+        "got {err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "Unsupported function for evaluation: props"
+    );
+}
+
+#[test]
+fn test_compiled_native_callback_not_serialized() {
+    let (expr, ctx) = props_expr();
+    let evaluator =
+        crate::CompiledEvaluator::compile(&expr, &["T"], Some(&ctx)).expect("Should compile");
+    let err = crate::CompiledEvaluator::from_bytes(&evaluator.to_bytes())
+        .expect_err("callbacks are not part of the bytes");
+    assert!(err.to_string().contains("native callback"), "got {err}");
+}
@@ -0,0 +1,189 @@
+//! Sums over data series: `sum_data` compiled against arrays passed at evaluation time
+
+use crate::{
+    CompiledEvaluator, CompiledGradient, Context, Diff, DiffError, EvaluatorBuilder, Expr, parse,
+};
+use std::collections::HashSet;
+
+fn data_context() -> Context {
+    let ctx = Context::new();
+    ctx.declare_data("t");
+    ctx.declare_data("obs");
+    ctx
+}
+
+fn parse_in(input: &str, ctx: &Context) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), Some(ctx)).unwrap()
+}
+
+/// 1000 samples of `2*exp(-0.5*t) + 0.3` with a small deterministic wobble
+fn synthetic() -> (Vec<f64>, Vec<f64>) {
+    let t: Vec<f64> = (0..1000).map(|i| f64::from(i) / 100.0).collect();
+    let obs = t
+        .iter()
+        .map(|&t| 0.01f64.mul_add((37.0 * t).sin(), 2.0f64.mul_add((-0.5 * t).exp(), 0.3)))
+        .collect();
+    (t, obs)
+}
+
+const OBJECTIVE: &str = "sum_data((obs - (a*exp(-b*t) + c))^2)";
+
+#[test]
+fn test_least_squares_objective() {
+    let ctx = data_context();
+    let params = [ctx.symb("a"), ctx.symb("b"), ctx.symb("c")];
+    let eval = CompiledEvaluator::compile(&parse_in(OBJECTIVE, &ctx), &params, Some(&ctx)).unwrap();
+    let (t, obs) = synthetic();
+
+    let p: [f64; 3] = [1.5, 0.4, 0.2];
+    let expected: f64 = t
+        .iter()
+        .zip(&obs)
+        .map(|(&t, &y)| (y - p[0].mul_add((-p[1] * t).exp(), p[2])).powi(2))
+        .sum();
+    let got = eval
+        .evaluate_with_data(&p, &[("t", &t), ("obs", &obs)])
+        .unwrap();
+    assert!(
+        (got - expected).abs() < 1e-9 * expected,
+        "{got} vs {expected}"
+    );
+
+    // Series are matched by name, in any order
+    let swapped = eval
+        .evaluate_with_data(&p, &[("obs", &obs), ("t", &t)])
+        .unwrap();
+    assert_eq!(swapped, got);
+}
+
+#[test]
+fn test_gradient_matches_finite_difference() {
+    let ctx = data_context();
+    let params = [ctx.symb("a"), ctx.symb("b"), ctx.symb("c")];
+    let objective = parse_in(OBJECTIVE, &ctx);
+    let eval = CompiledEvaluator::compile(&objective, &params, Some(&ctx)).unwrap();
+    let gradient = CompiledGradient::compile(&objective, &params, Some(&ctx)).unwrap();
+    assert_eq!(gradient.param_count(), 3);
+    let (t, obs) = synthetic();
+    let data: [(&str, &[f64]); 2] = [("t", &t), ("obs", &obs)];
+
+    let p: [f64; 3] = [1.5, 0.4, 0.2];
+    let mut grad = [0.0; 3];
+    gradient.evaluate_with_data(&p, &data, &mut grad).unwrap();
+    let step = 1e-6;
+    for i in 0..3 {
+        let (mut up, mut down) = (p, p);
+        up[i] += step;
+        down[i] -= step;
+        let central = (eval.evaluate_with_data(&up, &data).unwrap()
+            - eval.evaluate_with_data(&down, &data).unwrap())
+            / (2.0 * step);
+        assert!(
+            (grad[i] - central).abs() < 1e-5 * central.abs().max(1.0),
+            "component {i}: {} vs {central}",
+            grad[i]
+        );
+    }
+}
+
+#[test]
+fn test_differentiation_goes_through_the_sum() {
+    let ctx = data_context();
+    let objective = parse_in("sum_data((obs - a*t)^2)", &ctx);
+    let diff = Diff::new().context(&ctx);
+    let derivative = diff.differentiate(&objective, &ctx.symb("a")).unwrap();
+    assert_eq!(
+        derivative
+            .calls_of(crate::core::known_symbols::KS.sum_data)
+            .len(),
+        1
+    );
+    assert!(matches!(
+        diff.differentiate(&objective, &ctx.symb("t")),
+        Err(DiffError::InvalidDataSeries { name, .. }) if name == "t"
+    ));
+}
+
+#[test]
+fn test_missing_and_ragged_data() {
+    let ctx = data_context();
+    let eval = CompiledEvaluator::compile(
+        &parse_in("sum_data((obs - a*t)^2)", &ctx),
+        &[ctx.symb("a")],
+        Some(&ctx),
+    )
+    .unwrap();
+    let (t, obs) = ([1.0, 2.0, 3.0], [1.0, 2.0]);
+
+    let reason_for = |data: &[(&str, &[f64])]| match eval.evaluate_with_data(&[1.0], data) {
+        Err(DiffError::InvalidDataSeries { name, reason }) => format!("{name}: {reason}"),
+        other => panic!("expected InvalidDataSeries, got {other:?}"),
+    };
+    assert_eq!(reason_for(&[("t", &t)]), "obs: was not passed");
+    assert_eq!(
+        reason_for(&[("t", &t), ("obs", &obs)]),
+        "obs: has 2 points, expected 3 like 't'"
+    );
+    assert_eq!(
+        reason_for(&[("t", &t), ("obs", &t), ("w", &t)]),
+        "w: is not read by this program"
+    );
+    let message = eval
+        .evaluate_with_data(&[1.0], &[("t", &t)])
+        .unwrap_err()
+        .to_string();
+    assert_eq!(message, "Data series 'obs' was not passed");
+}
+
+#[test]
+fn test_data_outside_a_sum_is_rejected() {
+    let ctx = data_context();
+    let a = [ctx.symb("a")];
+    let stray = CompiledEvaluator::compile(&parse_in("sum_data(obs) + a*t", &ctx), &a, Some(&ctx));
+    assert!(matches!(stray, Err(DiffError::InvalidDataSeries { name, .. }) if name == "t"));
+
+    let as_param = CompiledEvaluator::compile(
+        &parse_in("sum_data(obs)", &ctx),
+        &[ctx.symb("obs")],
+        Some(&ctx),
+    );
+    assert!(matches!(as_param, Err(DiffError::InvalidDataSeries { name, .. }) if name == "obs"));
+}
+
+#[test]
+fn test_sums_are_trailing_parameters() {
+    let ctx = data_context();
+    let eval = CompiledEvaluator::compile(
+        &parse_in("a + sum_data(t)", &ctx),
+        &[ctx.symb("a")],
+        Some(&ctx),
+    )
+    .unwrap();
+    assert_eq!(eval.param_names(), ["a", "sum_data(t)"]);
+    assert_eq!(eval.evaluate(&[2.0]), 2.0);
+    assert_eq!(
+        eval.evaluate_with_data(&[2.0], &[("t", &[1.0, 2.0, 3.0])])
+            .unwrap(),
+        8.0
+    );
+    // No points: the sum is empty
+    assert_eq!(eval.evaluate_with_data(&[2.0], &[("t", &[])]).unwrap(), 2.0);
+}
+
+#[test]
+fn test_compensated_summation() {
+    let ctx = data_context();
+    let expr = parse_in("sum_data(t)", &ctx);
+    let t = [1e16, 1.0, -1e16];
+    let plain = CompiledEvaluator::compile(&expr, &[] as &[&str], Some(&ctx)).unwrap();
+    let compensated = EvaluatorBuilder::new(&expr)
+        .context(&ctx)
+        .compensated_sums(true)
+        .build()
+        .unwrap();
+    assert_eq!(plain.evaluate_with_data(&[], &[("t", &t)]).unwrap(), 0.0);
+    assert_eq!(
+        compensated.evaluate_with_data(&[], &[("t", &t)]).unwrap(),
+        1.0
+    );
+}
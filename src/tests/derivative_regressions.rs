@@ -59,3 +59,22 @@ fn test_orbital_denominator_squared() {
         panic!("Derivative is not a division as expected: {}", result);
     }
 }
+
+#[test]
+fn test_atan2_partial_derivatives() {
+    // atan2 is differentiated through each argument independently
+    assert_eq!(
+        diff("atan2(y, x)", "x", &[], None).unwrap(),
+        "-y/(x^2 + y^2)"
+    );
+    assert_eq!(
+        diff("atan2(y, x)", "y", &[], None).unwrap(),
+        "x/(x^2 + y^2)"
+    );
+    // Chain rule applies to both arguments
+    assert_eq!(
+        diff("atan2(y^2, sin(x))", "x", &[], None).unwrap(),
+        "-y^2*cos(x)/(y^4 + sin(x)^2)"
+    );
+    assert_eq!(diff("atan2(x, x)", "x", &[], None).unwrap(), "0");
+}
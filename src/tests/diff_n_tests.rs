@@ -0,0 +1,141 @@
+//! Tests for `Diff::diff_n` and `Diff::diff_str_n`
+
+use crate::{Diff, DiffError, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+#[test]
+fn test_third_derivative_of_sin() {
+    let x = symb("x");
+    let d3 = Diff::new().diff_n(&x.sin(), &x, 3).unwrap();
+    assert_eq!(d3.to_string(), "-cos(x)");
+}
+
+#[test]
+fn test_fourth_derivative_of_quartic() {
+    let x = symb("x");
+    let d4 = Diff::new().diff_n(&x.pow(4.0), &x, 4).unwrap();
+    assert_eq!(d4.to_string(), "24");
+    let d5 = Diff::new().diff_n(&x.pow(4.0), &x, 5).unwrap();
+    assert_eq!(d5.to_string(), "0");
+}
+
+#[test]
+fn test_order_zero_returns_input() {
+    let x = symb("x");
+    let expr = x.sin() * x.exp();
+    assert_eq!(Diff::new().diff_n(&expr, &x, 0).unwrap(), expr);
+}
+
+#[test]
+fn test_order_one_matches_differentiate() {
+    let x = symb("x");
+    let expr = parse("x^3*exp(x)", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let diff = Diff::new();
+    assert_eq!(
+        diff.diff_n(&expr, &x, 1).unwrap(),
+        diff.differentiate(&expr, &x).unwrap()
+    );
+}
+
+#[test]
+fn test_repeated_differentiate_agrees() {
+    let x = symb("x");
+    let expr = parse("exp(2*x)*x", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let diff = Diff::new();
+    let stepwise = (0..3).fold(expr.clone(), |e, _| diff.differentiate(&e, &x).unwrap());
+    assert_eq!(diff.diff_n(&expr, &x, 3).unwrap(), stepwise);
+}
+
+#[test]
+fn test_intermediate_node_limit() {
+    let x = symb("x");
+    let expr = parse("tan(sin(x)*exp(x))", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let result = Diff::new().max_nodes(60).diff_n(&expr, &x, 4);
+    let Err(DiffError::DerivativeOrderLimit { order, limit }) = result else {
+        panic!("expected DerivativeOrderLimit, got {result:?}");
+    };
+    assert!((1..=4).contains(&order));
+    assert_eq!(*limit, DiffError::MaxNodesExceeded);
+}
+
+#[test]
+fn test_intermediate_depth_limit() {
+    let x = symb("x");
+    let expr = parse("sin(sin(x))", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let err = Diff::new().max_depth(4).diff_n(&expr, &x, 5).unwrap_err();
+    let DiffError::DerivativeOrderLimit { order, limit } = &err else {
+        panic!("expected DerivativeOrderLimit, got {err:?}");
+    };
+    assert!((1..=5).contains(order));
+    assert_eq!(**limit, DiffError::MaxDepthExceeded);
+    assert!(
+        err.to_string()
+            .ends_with(&format!("at derivative order {order}"))
+    );
+}
+
+#[test]
+fn test_fifth_derivative_of_sin() {
+    let x = symb("x");
+    let d5 = Diff::new().diff_n(&x.sin(), &x, 5).unwrap();
+    assert_eq!(d5.to_string(), "cos(x)");
+}
+
+#[test]
+fn test_nth_derivative_of_power() {
+    let x = symb("x");
+    for n in 1..=6_u32 {
+        let factorial: u32 = (1..=n).product();
+        let dn = Diff::new()
+            .diff_n(&x.pow(f64::from(n)), &x, n as usize)
+            .unwrap();
+        assert_eq!(dn.to_string(), factorial.to_string(), "d^{n}/dx^{n} x^{n}");
+    }
+    let d2 = Diff::new().diff_n(&x.pow(5.0), &x, 2).unwrap();
+    assert_eq!(d2.to_string(), "20*x^3");
+}
+
+#[test]
+fn test_exp_with_fixed_coefficient() {
+    assert_eq!(
+        Diff::new().diff_str_n("exp(a*x)", "x", &["a"], 3).unwrap(),
+        "a^3*exp(a*x)"
+    );
+}
+
+#[test]
+fn test_high_order_of_polynomial_is_direct() {
+    let x = symb("x");
+    let expr = parse(
+        "x^20 + 3*x^15 - 2*x^11 + x^10 + 7*x^3 + 1",
+        &HashSet::new(),
+        &HashSet::new(),
+        None,
+    )
+    .unwrap();
+    let start = std::time::Instant::now();
+    let d10 = Diff::new().diff_n(&expr, &x, 10).unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+    let stepwise = (0..10).fold(expr, |e, _| Diff::new().differentiate(&e, &x).unwrap());
+    let vars = HashMap::from([("x", 0.7)]);
+    let fast = d10.evaluate(&vars, &HashMap::new()).as_number().unwrap();
+    let slow = stepwise
+        .evaluate(&vars, &HashMap::new())
+        .as_number()
+        .unwrap();
+    assert!((fast - slow).abs() <= 1e-9 * slow.abs(), "{fast} vs {slow}");
+}
+
+#[test]
+fn test_diff_str_n() {
+    assert_eq!(
+        Diff::new().diff_str_n("a*x^3", "x", &["a"], 2).unwrap(),
+        "6*a*x"
+    );
+    assert_eq!(Diff::new().diff_str_n("x^4", "x", &[], 4).unwrap(), "24");
+    assert!(matches!(
+        Diff::new().diff_str_n("a*x", "a", &["a"], 2),
+        Err(DiffError::VariableInBothFixedAndDiff { .. })
+    ));
+}
@@ -0,0 +1,78 @@
+//! Tests for the annotated bytecode listing of `CompiledEvaluator`
+
+use crate::{CompiledEvaluator, parse};
+use std::collections::HashSet;
+
+fn compile(input: &str) -> CompiledEvaluator {
+    let expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile_auto(&expr, None).unwrap()
+}
+
+fn instruction_lines(listing: &str) -> Vec<&str> {
+    listing
+        .lines()
+        .filter(|line| line.trim_start().starts_with("[0"))
+        .collect()
+}
+
+#[test]
+fn test_listing_snapshot() {
+    let listing = compile("2*sin(x) + x^3").disassemble();
+    let expected = "\
+=== Bytecode Disassembly ===
+Parameters (1): [x]
+Result: R4
+
+=== Constants (1) ===
+  k0    R1    = 2
+
+=== Memory Layout ===
+Workspace Size: 5 registers
+Arg Pool Size:  0 entries
+
+=== Instructions (3) ===
+  [0000] Cube       R2 = R0^3                    ; R2 = x^3 (live: 1)
+  [0001] Sin        R3 = sin(R0)                 ; R3 = sin(x) (live: 2)
+  [0002] MulAdd     R4 = R1 * R3 + R2            ; R4 = 2 * sin(x) + x^3 [k0] (live: 1)
+";
+    assert!(listing.starts_with(expected), "{listing}");
+    assert!(listing.contains("=== Instruction Summary ==="));
+}
+
+#[test]
+fn test_writer_matches_string_listing() {
+    let compiled = compile("exp(-x^2/2)/sqrt(2*pi) + y");
+    let mut written = String::new();
+    compiled.disassemble_to_writer(&mut written).unwrap();
+    assert_eq!(written, compiled.disassemble());
+}
+
+#[test]
+fn test_operands_are_parenthesized_by_precedence() {
+    let listing = compile("(x + y)^3 * z").disassemble();
+    let last = *instruction_lines(&listing).last().unwrap();
+    assert!(last.contains("z * (x + y)^3"), "{listing}");
+}
+
+#[test]
+fn test_constant_slots_are_referenced() {
+    let compiled = compile("3*x + 5*y^2");
+    assert_eq!(compiled.constant_count(), 2);
+    let listing = compiled.disassemble();
+    let instructions = instruction_lines(&listing).join("\n");
+    for slot in ["k0", "k1"] {
+        assert!(instructions.contains(slot), "{listing}");
+    }
+    assert!(instructions.contains("3 * x"), "{listing}");
+}
+
+#[test]
+fn test_live_temporaries_end_with_result() {
+    let listing = compile("sin(x)*cos(y) + tan(x)*exp(y)").disassemble();
+    let lines = instruction_lines(&listing);
+    assert!(lines.last().unwrap().ends_with("(live: 1)"), "{listing}");
+    assert!(
+        lines.iter().any(|line| line.ends_with("(live: 2)")),
+        "{listing}"
+    );
+}
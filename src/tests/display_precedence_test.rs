@@ -113,4 +113,54 @@ mod tests {
         println!("Display: {}", display);
         assert_eq!(display, "A/(C*R^2)");
     }
+
+    fn parse_str(input: &str) -> Expr {
+        let custom: HashSet<String> = ["f".to_owned(), "g".to_owned()].into_iter().collect();
+        crate::parser::parse(input, &HashSet::new(), &custom, None).unwrap()
+    }
+
+    #[test]
+    fn test_derivative_display_roundtrip() {
+        // 3 * (d^2 g/dx^2)^2
+        let deriv = Expr::derivative(Expr::func("g", Expr::symbol("x")), "x", 2);
+        let expr = Expr::product(vec![Expr::number(3.0), Expr::pow(deriv, Expr::number(2.0))]);
+        let display = format!("{}", expr);
+        assert_eq!(display, "3*diff(g(x), x, 2)^2");
+        assert_eq!(parse_str(&display), expr);
+        assert_eq!(expr.to_latex(), r"3\left(\frac{d^{2}g}{dx^{2}}\right)^{2}");
+    }
+
+    #[test]
+    fn test_derivative_display_first_order_and_partial() {
+        let f = Expr::func_multi("f", vec![Expr::symbol("x"), Expr::symbol("y")]);
+        let expr = Expr::derivative(f, "y", 1);
+        assert_eq!(format!("{}", expr), "diff(f(x, y), y)");
+        assert_eq!(parse_str("diff(f(x, y), y)"), expr);
+        assert_eq!(expr.to_latex(), r"\frac{\partial f}{\partial y}");
+
+        let compound = Expr::derivative(parse_str("x^2 + g(x)"), "x", 1);
+        assert_eq!(parse_str(&format!("{}", compound)), compound);
+        assert!(
+            compound
+                .to_latex()
+                .starts_with(r"\frac{\partial}{\partial x}\left(")
+        );
+    }
+
+    #[test]
+    fn test_diff_call_rejects_malformed_arguments() {
+        let fixed = HashSet::new();
+        let custom = HashSet::new();
+        for input in [
+            "diff(x)",
+            "diff(x^2, x + 1)",
+            "diff(x^2, x, 1.5)",
+            "diff(x^2, x, 0)",
+        ] {
+            assert!(
+                crate::parser::parse(input, &fixed, &custom, None).is_err(),
+                "{input} should not parse"
+            );
+        }
+    }
 }
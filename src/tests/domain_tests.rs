@@ -0,0 +1,122 @@
+//! `Expr::domain`: solved intervals, excluded points, periodic poles and conditions
+
+use crate::{Constraint, Expr, Interval, parse, symb};
+use std::collections::HashSet;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn domain_str(input: &str) -> String {
+    parse_str(input).domain(&symb("x")).to_string()
+}
+
+#[test]
+fn test_log_over_root_is_open_interval() {
+    let domain = parse_str("ln(x - 2)/sqrt(5 - x)").domain(&symb("x"));
+    assert_eq!(
+        domain.intervals(),
+        &[Interval {
+            lo: 2.0,
+            hi: 5.0,
+            lo_closed: false,
+            hi_closed: false,
+        }]
+    );
+    // The zero of the root sits on the end and opens it instead of being listed
+    assert!(domain.excluded_points().is_empty());
+    assert_eq!(domain.to_string(), "(2, 5)");
+    for (x, inside) in [
+        (3.0, true),
+        (4.999, true),
+        (2.0, false),
+        (5.0, false),
+        (6.0, false),
+    ] {
+        assert_eq!(domain.contains(x), inside, "x = {x}");
+    }
+}
+
+#[test]
+fn test_denominator_zeros_are_excluded_points() {
+    let domain = parse_str("1/(x^2 - 1)").domain(&symb("x"));
+    assert_eq!(domain.intervals(), &[Interval::REALS]);
+    assert_eq!(domain.excluded_points(), &[-1.0, 1.0]);
+    assert_eq!(domain.to_string(), "(-inf, inf) \\ {-1, 1}");
+    assert!(domain.contains(0.0));
+    assert!(!domain.contains(1.0));
+
+    // Roots of a cubic are isolated numerically
+    let cubic = parse_str("1/(x^3 - 6*x^2 + 11*x - 6)").domain(&symb("x"));
+    let roots = cubic.excluded_points();
+    assert_eq!(roots.len(), 3);
+    for (got, want) in roots.iter().zip([1.0, 2.0, 3.0]) {
+        assert!((got - want).abs() < 1e-9, "{got} vs {want}");
+    }
+}
+
+#[test]
+fn test_nested_compositions() {
+    assert_eq!(domain_str("ln(ln(x))"), "(1, inf)");
+    assert_eq!(domain_str("sqrt(ln(x))"), "[1, inf)");
+    assert_eq!(domain_str("1/ln(x)"), "(0, inf) \\ {1}");
+    assert_eq!(domain_str("sqrt(1 - x^2)/x"), "[-1, 1] \\ {0}");
+    assert_eq!(domain_str("asin(x/2) + sqrt(x + 1)"), "[-1, 2]");
+    assert_eq!(domain_str("sqrt(x^3 - x)"), "[-1, 0] U [1, inf)");
+    assert_eq!(domain_str("ln((x - 1)/(x + 1))"), "(-inf, -1) U (1, inf)");
+    assert_eq!(domain_str("sin(x)*exp(x) + x^3"), "(-inf, inf)");
+}
+
+#[test]
+fn test_tan_of_linear_argument_excludes_a_lattice() {
+    let domain = parse_str("tan(2*x + 1)").domain(&symb("x"));
+    let [(offset, period)] = domain.periodic_exclusions() else {
+        panic!("expected one family, got {domain}");
+    };
+    assert!((period - FRAC_PI_2).abs() < 1e-15);
+    let first_pole = (FRAC_PI_2 - 1.0) / 2.0;
+    assert!((offset - first_pole.rem_euclid(FRAC_PI_2)).abs() < 1e-15);
+    for k in [-3.0_f64, 0.0, 1.0, 40.0] {
+        assert!(
+            !domain.contains(k.mul_add(FRAC_PI_2, first_pole)),
+            "k = {k}"
+        );
+    }
+    assert!(domain.contains(0.0));
+
+    // cot and tan of the same argument exclude different families
+    let both = parse_str("tan(x) + cot(x)").domain(&symb("x"));
+    assert_eq!(both.periodic_exclusions().len(), 2);
+    assert!(!both.contains(PI) && !both.contains(FRAC_PI_2) && both.contains(1.0));
+}
+
+#[test]
+fn test_unsolved_constraints_are_checked_pointwise() {
+    let domain = parse_str("ln(sin(x))").domain(&symb("x"));
+    assert_eq!(
+        domain.conditions(),
+        &[Constraint::Positive(parse_str("sin(x)"))]
+    );
+    assert_eq!(domain.to_string(), "(-inf, inf) where sin(x) > 0");
+    assert!(domain.contains(1.0));
+    assert!(!domain.contains(-1.0));
+
+    // Other free symbols leave the condition undecided, which counts as holding
+    let shifted = parse_str("ln(x - a)").domain(&symb("x"));
+    assert_eq!(shifted.conditions().len(), 1);
+    assert!(shifted.contains(-10.0));
+}
+
+#[test]
+fn test_empty_and_degenerate_domains() {
+    let empty = parse_str("ln(-1 - x^2)").domain(&symb("x"));
+    assert!(empty.is_empty());
+    assert!(!empty.contains(0.0));
+    assert_eq!(empty.to_string(), "{}");
+
+    assert_eq!(domain_str("sqrt(-x^2)"), "[0, 0]");
+    assert_eq!(domain_str("ln(-1) + x"), "{}");
+    // Parameters alone never restrict the variable
+    assert_eq!(domain_str("ln(a)*x"), "(-inf, inf)");
+}
@@ -0,0 +1,106 @@
+//! Interval enclosures of expression nodes with `Expr::compute_enclosures`
+
+use crate::{CompiledEvaluator, Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// `steps + 1` evenly spaced points from `lo` to `hi`
+fn grid(lo: f64, hi: f64, steps: u32) -> impl Iterator<Item = f64> {
+    (0..=steps).map(move |i| lo + (hi - lo) * f64::from(i) / f64::from(steps))
+}
+
+#[test]
+fn test_pythagorean_identity_is_tight() {
+    let x = symb("x");
+    let expr = x.sin().pow(2.0) + x.cos().pow(2.0);
+    for (lo, hi) in [(-1.0, 1.0), (0.0, 100.0), (-1e6, 3.0), (0.5, 0.5)] {
+        let root = expr.compute_enclosures(&[(x, lo..=hi)]).root();
+        assert!(root.contains(1.0), "[{lo}, {hi}]: {root}");
+        assert!(root.width() < 1e-12, "[{lo}, {hi}]: {root}");
+    }
+}
+
+#[test]
+fn test_pole_in_domain_is_unbounded() {
+    let x = symb("x");
+    let den = x - 1.0;
+    let expr = 1.0 / den.clone();
+
+    let across = expr.compute_enclosures(&[(x, 0.0..=2.0)]);
+    assert!(!across.root().is_bounded());
+    assert!(!across.get(&den).unwrap().excludes_zero());
+
+    let beside = expr.compute_enclosures(&[(x, 2.0..=3.0)]);
+    assert!(beside.root().is_bounded());
+    assert!(beside.get(&den).unwrap().excludes_zero());
+    assert!(beside.root().contains(0.5) && beside.root().contains(1.0));
+
+    let reciprocal = parse_plain("1/x").compute_enclosures(&[(x, -1.0..=1.0)]);
+    assert!(!reciprocal.root().is_bounded());
+}
+
+#[test]
+fn test_every_node_is_recorded() {
+    let (x, y) = (symb("x"), symb("y"));
+    let inner = x.exp();
+    let term = inner.clone() * y;
+    let expr = term.clone() + y.pow(2.0);
+    let map = expr.compute_enclosures(&[(x, 0.0..=1.0), (y, -2.0..=1.0)]);
+
+    let exp_x = map.get(&inner).unwrap();
+    assert!(exp_x.contains(1.0) && exp_x.contains(1.0_f64.exp()));
+    assert!(exp_x.lo > 0.99 && exp_x.hi < 2.72);
+    assert!(map.get(&term).unwrap().contains(-2.0 * 1.0_f64.exp()));
+    assert!(map.get(&parse_plain("x + 1")).is_none());
+    assert!(map.len() >= 5);
+}
+
+#[test]
+fn test_unknown_symbols_and_functions_are_unbounded() {
+    let x = symb("x");
+    let map = parse_plain("x + z").compute_enclosures(&[(x, 0.0..=1.0)]);
+    assert!(!map.root().is_bounded());
+
+    let map = parse_plain("pi*x + erf(x)").compute_enclosures(&[(x, 0.0..=1.0)]);
+    assert!(map.root().contains(std::f64::consts::PI));
+}
+
+#[test]
+fn test_empty_domain() {
+    let x = symb("x");
+    let map = x.ln().compute_enclosures(&[(x, -2.0..=-1.0)]);
+    assert!(map.root().is_empty());
+}
+
+#[test]
+fn test_bounds_are_conservative() {
+    let (x, y) = (symb("x"), symb("y"));
+    let cases = [
+        ("x^2*y - 3*x + exp(-y)", (-2.0, 1.5), (-1.0, 2.0)),
+        ("sin(3*x)*cos(y) + sqrt(x + 2)", (-2.0, 2.0), (0.0, 10.0)),
+        ("(x - y)^3 / (1 + x^2 + y^2)", (-1.0, 3.0), (-3.0, 1.0)),
+        ("abs(x)*ln(y) + atan(x*y)", (-5.0, 5.0), (0.1, 4.0)),
+        ("max(x, y) - min(x^2, 1) + x^(3/2)", (0.0, 2.0), (-1.0, 1.0)),
+        ("sinh(x)/cosh(y) + 1/(y + 5)", (-1.0, 1.0), (-2.0, 2.0)),
+    ];
+    for (input, (xa, xb), (ya, yb)) in cases {
+        let expr = parse_plain(input);
+        let root = expr
+            .compute_enclosures(&[(x, xa..=xb), (y, ya..=yb)])
+            .root();
+        assert!(root.is_bounded(), "{input}: {root}");
+        let eval = CompiledEvaluator::compile(&expr, &["x", "y"], None).unwrap();
+        for xv in grid(xa, xb, 60) {
+            for yv in grid(ya, yb, 60) {
+                let value = eval.evaluate(&[xv, yv]);
+                assert!(
+                    value.is_nan() || root.contains(value),
+                    "{input} at ({xv}, {yv}) = {value} outside {root}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! erf and erfc: parity and complement simplification, derivative values, compiled evaluation
+
+use crate::evaluator::{FnOp, Instruction};
+use crate::{CompiledEvaluator, Diff, Expr, parse, symb};
+use std::collections::HashSet;
+use std::f64::consts::FRAC_2_SQRT_PI;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn simplified(input: &str) -> String {
+    crate::simplify(input, &[], None).unwrap()
+}
+
+fn derivative_at(input: &str, x: f64) -> f64 {
+    let derivative = Diff::new()
+        .differentiate(&parse_str(input), &symb("x"))
+        .unwrap();
+    CompiledEvaluator::compile(&derivative, &["x"], None)
+        .unwrap()
+        .evaluate(&[x])
+}
+
+const POINTS: [f64; 6] = [-2.0, -0.75, 0.0, 0.3, 1.0, 2.5];
+
+#[test]
+fn test_erf_at_zero() {
+    assert_eq!(simplified("erf(0)"), "0");
+    assert_eq!(simplified("erfc(0)"), "1");
+}
+
+#[test]
+fn test_erf_is_odd() {
+    assert_eq!(simplified("erf(-x)"), simplified("-erf(x)"));
+    assert_eq!(simplified("erf(-2*x)"), simplified("-erf(2*x)"));
+    // erfc has no parity of its own
+    assert!(simplified("erfc(-x)").contains("erfc"));
+}
+
+#[test]
+fn test_erf_erfc_complement() {
+    assert_eq!(simplified("erf(x) + erfc(x)"), "1");
+    assert_eq!(simplified("1 - erf(x)"), simplified("erfc(x)"));
+    assert_eq!(simplified("1 - erfc(x)"), simplified("erf(x)"));
+    assert_eq!(simplified("3 - 3*erf(x)"), simplified("3*erfc(x)"));
+    assert_eq!(simplified("2*erf(y) + 2*erfc(y) + x"), simplified("x + 2"));
+    // Mismatched arguments or coefficients stay apart
+    assert!(simplified("erf(x) + erfc(y)").contains("erfc"));
+    assert!(simplified("2 - erf(x)").contains("erf"));
+}
+
+#[test]
+fn test_erfc_is_not_expanded() {
+    // Spelling erfc(x) out as 1 - erf(x) loses every digit once erf(x) rounds to 1
+    assert!(simplified("erfc(x)").contains("erfc"));
+    let tail = CompiledEvaluator::compile(&parse_str("erfc(x)"), &["x"], None).unwrap();
+    assert!(tail.evaluate(&[6.0]) > 0.0);
+}
+
+#[test]
+fn test_derivative_values() {
+    for x in POINTS {
+        let gaussian = FRAC_2_SQRT_PI * (-x * x).exp();
+        assert!(
+            (derivative_at("erf(x)", x) - gaussian).abs() < 1e-12,
+            "x = {x}"
+        );
+        assert!(
+            (derivative_at("erfc(x)", x) + gaussian).abs() < 1e-12,
+            "x = {x}"
+        );
+
+        // Chain rule through an inner argument u = x^2 + 1, u' = 2x
+        let u = x.mul_add(x, 1.0);
+        let chained = FRAC_2_SQRT_PI * (-u * u).exp() * 2.0 * x;
+        assert!(
+            (derivative_at("erf(x^2 + 1)", x) - chained).abs() < 1e-12,
+            "x = {x}"
+        );
+        assert!(
+            (derivative_at("erfc(x^2 + 1)", x) + chained).abs() < 1e-12,
+            "x = {x}"
+        );
+    }
+}
+
+#[test]
+fn test_derivative_matches_finite_difference() {
+    let step = 1e-6;
+    for input in ["erf(3*x)", "erfc(x)*x", "erf(sin(x))"] {
+        let eval = CompiledEvaluator::compile(&parse_str(input), &["x"], None).unwrap();
+        for x in POINTS {
+            let central = (eval.evaluate(&[x + step]) - eval.evaluate(&[x - step])) / (2.0 * step);
+            assert!(
+                (derivative_at(input, x) - central).abs() < 1e-6,
+                "{input} at x = {x}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_compiled_builtins() {
+    for (input, op) in [("erf(x)", FnOp::Erf), ("erfc(x)", FnOp::Erfc)] {
+        let eval = CompiledEvaluator::compile(&parse_str(input), &["x"], None).unwrap();
+        assert!(
+            eval.instructions
+                .iter()
+                .any(|instr| matches!(instr, Instruction::Builtin1 { op: got, .. } if *got == op)),
+            "{input}"
+        );
+    }
+
+    let erf = CompiledEvaluator::compile(&parse_str("erf(x)"), &["x"], None).unwrap();
+    let erfc = CompiledEvaluator::compile(&parse_str("erfc(x)"), &["x"], None).unwrap();
+    for x in POINTS {
+        assert!(
+            (erf.evaluate(&[x]) + erfc.evaluate(&[x]) - 1.0).abs() < 1e-14,
+            "x = {x}"
+        );
+        assert!(
+            (erf.evaluate(&[-x]) + erf.evaluate(&[x])).abs() < 1e-15,
+            "x = {x}"
+        );
+    }
+    // erf(1) = 0.8427007929497149
+    assert!((erf.evaluate(&[1.0]) - 0.842_700_792_949_714_9).abs() < 1e-14);
+}
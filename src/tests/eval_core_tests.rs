@@ -0,0 +1,93 @@
+//! Host-compiled programs evaluated by the `no_std` evaluation core
+
+use crate::eval_core::{DecodeError, Program};
+use crate::{CompiledEvaluator, DiffError, parse};
+use std::collections::HashSet;
+
+const SUITE: &[&str] = &[
+    "2*sin(x) + x^3",
+    "x^7 - 3*x^-2 + sqrt(x) * cos(y)",
+    "exp(-x^2) / (1 + y^2)",
+    "sin(x)*cos(x) + tan(y) + sinh(x)/cosh(y)",
+    "atan2(y, x) + log(2, x) + erf(y)",
+    "min(x, 1) + max(y, -1) + abs(x - y) + clamp(x, -1, 1)",
+    "piecewise(x < 0: -x, x <= 1: x^2, exp(y))",
+    "ln(x) * x^(3/2) + 1/(exp(y) - 1)",
+    "(x + y)^5 - 4*x*y + x^0.7",
+    "42",
+];
+
+const POINTS: &[f64] = &[-2.5, -1.0, -0.1, 0.0, 0.3, 1.0, 2.0, 7.5];
+
+fn compile(input: &str) -> CompiledEvaluator {
+    let expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile(&expr, &["x", "y"], None).unwrap()
+}
+
+/// The device side, limited to what the core offers without `std`: the program is
+/// loaded from bytes and run in a fixed register array
+fn on_device(bytes: &[u8], inputs: &[f64]) -> Result<f64, DecodeError> {
+    let program = Program::from_bytes(bytes)?;
+    let mut registers = [0.0; 64];
+    Ok(program.evaluate_with(inputs, &mut registers))
+}
+
+#[test]
+fn test_device_matches_host() {
+    for input in SUITE {
+        let eval = compile(input);
+        let bytes = eval.to_bytes();
+        for &x in POINTS {
+            for &y in POINTS {
+                let want = eval.evaluate(&[x, y]);
+                let got = on_device(&bytes, &[x, y]).unwrap();
+                assert!(
+                    got.to_bits() == want.to_bits()
+                        || (got - want).abs() <= 1e-12 * want.abs().max(1.0),
+                    "{input} at ({x}, {y}): device {got}, host {want}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_program_keeps_parameters() {
+    let eval = compile("x - 2*y");
+    let program = Program::from_bytes(&eval.to_bytes()).unwrap();
+    assert_eq!(program.param_names(), ["x", "y"]);
+    assert_eq!(program.workspace_size(), eval.workspace_size());
+    assert!((program.evaluate(&[5.0, 1.0]) - 3.0).abs() < 1e-15);
+    // Missing values count as zero, as on the host
+    assert!((program.evaluate(&[5.0]) - 5.0).abs() < 1e-15);
+}
+
+#[test]
+fn test_unavailable_builtins_are_refused() {
+    for input in ["gamma(x)", "besselj(1, x)", "zeta(x + 2)"] {
+        let bytes = compile(input).to_bytes();
+        let err = Program::from_bytes(&bytes).unwrap_err();
+        assert_eq!(
+            err.reason, "builtin not available in this interpreter",
+            "{input}"
+        );
+        // The host still loads them
+        assert!(CompiledEvaluator::from_bytes(&bytes).is_ok(), "{input}");
+    }
+}
+
+#[test]
+fn test_decode_errors_match_host() {
+    let bytes = compile("sin(x) + y").to_bytes();
+    let mut corrupt = bytes.clone();
+    corrupt[4] = 99;
+    for data in [&bytes[..bytes.len() - 3], &corrupt[..], b"nope"] {
+        let device = Program::from_bytes(data).unwrap_err();
+        let Err(DiffError::InvalidEvaluatorBytes { offset, reason }) =
+            CompiledEvaluator::from_bytes(data)
+        else {
+            panic!("host accepted corrupt data");
+        };
+        assert_eq!((device.offset, device.reason), (offset, reason));
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for the double-double validation evaluator (`CompiledEvaluator::evaluate_dd`).
+use crate::parser::parse;
+use crate::{CompiledEvaluator, Expr};
+use std::collections::HashSet;
+use std::f64::consts::{E, SQRT_2};
+
+fn parse_expr(s: &str) -> Expr {
+    parse(s, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn compile(s: &str, params: &[&str]) -> CompiledEvaluator {
+    CompiledEvaluator::compile(&parse_expr(s), params, None).unwrap()
+}
+
+/// Reference corpus.
+///
+/// References were computed with 200-digit decimal arithmetic from the exact
+/// binary values of the inputs. The first three are well conditioned; the
+/// expanded `(x - 1)^7` cancels catastrophically and `exp` uses the f64 fallback.
+/// (formula, params, point, exact value as hi + lo)
+type Case = (
+    &'static str,
+    &'static [&'static str],
+    &'static [f64],
+    (f64, f64),
+);
+
+const CORPUS: &[Case] = &[
+    (
+        "x^2 + x + 1",
+        &["x"],
+        &[0.1],
+        (1.11, -9.103_828_801_926_283e-17),
+    ),
+    (
+        "sqrt(x)",
+        &["x"],
+        &[2.0],
+        (SQRT_2, -9.667_293_313_452_913e-17),
+    ),
+    (
+        "1/x + 1/y",
+        &["x", "y"],
+        &[3.0, 7.0],
+        (0.476_190_476_190_476_16, 2.643_388_153_869_420_2e-17),
+    ),
+    (
+        "x^7 - 7*x^6 + 21*x^5 - 35*x^4 + 35*x^3 - 21*x^2 + 7*x - 1",
+        &["x"],
+        &[1.001],
+        (9.999_999_999_992_29e-22, 8.980_929_160_716_122e-38),
+    ),
+    ("exp(x)", &["x"], &[1.0], (E, 1.445_646_891_729_250_2e-16)),
+];
+
+#[test]
+fn test_evaluate_dd_recovers_cancellation() {
+    let eval = compile("(a + b) - c", &["a", "b", "c"]);
+    let params = [1e16, 1.0, 1e16];
+
+    assert_eq!(eval.evaluate(&params), 0.0);
+    let (value, error) = eval.evaluate_dd(&params);
+    assert_eq!(value, 1.0);
+    assert!(error < 1e-14, "error estimate too loose: {error}");
+}
+
+#[test]
+fn test_evaluate_dd_polynomial_exact_cancellation() {
+    // (x - 1)^3 expanded; at x = 1 + 2^-30 the exact value is 2^-90, which even the
+    // fused Horner evaluation in double precision misses.
+    let eval = compile("x^3 - 3*x^2 + 3*x - 1", &["x"]);
+    let x = 1.0 + 2.0_f64.powi(-30);
+    let exact = 2.0_f64.powi(-90);
+
+    assert!((eval.evaluate(&[x]) - exact).abs() > exact);
+    let (value, _) = eval.evaluate_dd(&[x]);
+    assert_eq!(value, exact);
+}
+
+#[test]
+fn test_evaluate_dd_matches_rational_reference_to_30_digits() {
+    for &(formula, params, point, (ref_hi, ref_lo)) in &CORPUS[..3] {
+        let result = compile(formula, params).evaluate_dd_full(point);
+        let diff = (result.value.hi - ref_hi) + (result.value.lo - ref_lo);
+        let rel = diff.abs() / ref_hi.abs();
+        assert!(rel < 1e-30, "{formula}: relative error {rel:e}");
+    }
+}
+
+#[test]
+fn test_evaluate_dd_error_estimate_brackets_f64_error() {
+    for &(formula, params, point, (ref_hi, ref_lo)) in CORPUS {
+        let eval = compile(formula, params);
+        let (value, error) = eval.evaluate_dd(point);
+
+        // The estimate bounds the distance between the dd result and the truth.
+        let dd_error = ((value - ref_hi) - ref_lo).abs();
+        assert!(dd_error <= error, "{formula}: {dd_error:e} > {error:e}");
+
+        // Hence the f64 path's true error lies within the estimated bracket.
+        let fast = eval.evaluate(point);
+        let true_f64_error = ((fast - ref_hi) - ref_lo).abs();
+        let estimated = (fast - value).abs();
+        assert!(
+            true_f64_error <= estimated + error && estimated <= true_f64_error + error,
+            "{formula}: true {true_f64_error:e}, estimated {estimated:e} +/- {error:e}"
+        );
+    }
+}
+
+#[test]
+fn test_evaluate_dd_propagates_non_finite() {
+    let eval = compile("1/x", &["x"]);
+    let (value, _) = eval.evaluate_dd(&[0.0]);
+    assert!(value.is_infinite());
+
+    let eval = compile("sqrt(x)", &["x"]);
+    let (value, _) = eval.evaluate_dd(&[-1.0]);
+    assert!(value.is_nan());
+}
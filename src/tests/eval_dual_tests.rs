@@ -0,0 +1,116 @@
+//! Tests for forward-mode dual-number evaluation (`CompiledEvaluator::eval_dual`).
+use crate::parser::parse;
+use crate::{CompiledEvaluator, Diff, Expr, symb};
+use std::collections::HashSet;
+
+fn parse_expr(s: &str) -> Expr {
+    parse(s, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn compile(s: &str, params: &[&str]) -> CompiledEvaluator {
+    CompiledEvaluator::compile(&parse_expr(s), params, None).unwrap()
+}
+
+/// Value of the symbolic partial derivative `d(formula)/d(var)` at `point`.
+fn symbolic_partial(formula: &str, params: &[&str], var: &str, point: &[f64]) -> f64 {
+    let derivative = Diff::new()
+        .differentiate(&parse_expr(formula), &symb(var))
+        .unwrap();
+    CompiledEvaluator::compile(&derivative, params, None)
+        .unwrap()
+        .evaluate(point)
+}
+
+fn assert_close(got: f64, expected: f64, tol: f64, context: &str) {
+    assert!(
+        (got - expected).abs() <= tol * expected.abs().max(1.0),
+        "{context}: got {got}, expected {expected}"
+    );
+}
+
+#[test]
+fn test_dual_rules_of_basic_arithmetic() {
+    let eval = compile("x*y + x/y - y", &["x", "y"]);
+    let (value, dx) = eval.eval_dual(&[3.0, 2.0], &[1.0, 0.0]);
+    assert_close(value, 6.0 + 1.5 - 2.0, 1e-15, "value");
+    assert_close(dx, 2.0 + 0.5, 1e-15, "d/dx");
+    let (_, dy) = eval.eval_dual(&[3.0, 2.0], &[0.0, 1.0]);
+    assert_close(dy, 3.0 - 0.75 - 1.0, 1e-15, "d/dy");
+}
+
+#[test]
+fn test_directional_derivative_is_linear_in_seeds() {
+    let eval = compile("sin(x)*exp(y)", &["x", "y"]);
+    let point = [0.7, -0.3];
+    let (_, dx) = eval.eval_dual(&point, &[1.0, 0.0]);
+    let (_, dy) = eval.eval_dual(&point, &[0.0, 1.0]);
+    let (_, mixed) = eval.eval_dual(&point, &[2.0, -3.0]);
+    assert_close(mixed, 2.0 * dx - 3.0 * dy, 1e-14, "directional");
+}
+
+#[test]
+fn test_dual_matches_symbolic_derivatives() {
+    let cases: &[(&str, f64)] = &[
+        ("x^3 - 2*x^2 + 5", 1.3),
+        ("sqrt(x) + 1/sqrt(x) + x^(3/2) + x^(-3/2)", 2.1),
+        ("1/x^2 + 1/x^3 + x^4 + x^7", 0.8),
+        ("sin(x)*cos(x) + tan(x)", 0.4),
+        ("exp(x^2) + exp(-x^2) + ln(x)", 0.9),
+        ("x^x", 1.7),
+        ("asin(x) + acos(x/2) + atan(x)", 0.3),
+        ("sinh(x) + cosh(x) + tanh(x) + asinh(x)", 0.6),
+        ("erf(x) + gamma(x) + cbrt(x)", 1.4),
+        ("abs(x - 2) * log(2, x)", 3.5),
+        ("atan2(x, 2) + sinc(x)", 0.5),
+        ("1/(exp(x) - 1)", 0.25),
+    ];
+    for &(formula, x) in cases {
+        let (_, d) = compile(formula, &["x"]).eval_dual(&[x], &[1.0]);
+        let expected = symbolic_partial(formula, &["x"], "x", &[x]);
+        assert_close(d, expected, 1e-10, formula);
+    }
+}
+
+#[test]
+fn test_numeric_fallback_for_special_functions() {
+    let formula = "besselj(1, x)";
+    let (_, d) = compile(formula, &["x"]).eval_dual(&[1.2], &[1.0]);
+    let expected = symbolic_partial(formula, &["x"], "x", &[1.2]);
+    assert_close(d, expected, 1e-7, formula);
+
+    // Checked against a central difference of the plain evaluator
+    for formula in ["elliptic_k(x/4)", "zeta(x)"] {
+        let eval = compile(formula, &["x"]);
+        let h = 1e-6;
+        let expected = (eval.evaluate(&[1.2 + h]) - eval.evaluate(&[1.2 - h])) / (2.0 * h);
+        let (_, d) = eval.eval_dual(&[1.2], &[1.0]);
+        assert_close(d, expected, 1e-6, formula);
+    }
+}
+
+#[test]
+fn test_gradient_forward_matches_symbolic_gradient() {
+    let formula = "x^2*y + z*sin(x*y) + exp(z)/y";
+    let params = ["x", "y", "z"];
+    let point = [0.5, 1.5, -0.25];
+    let eval = compile(formula, &params);
+    let gradient = eval.eval_gradient_forward(&point);
+    assert_eq!(gradient.len(), 3);
+    for (var, &partial) in params.iter().zip(&gradient) {
+        let expected = symbolic_partial(formula, &params, var, &point);
+        assert_close(partial, expected, 1e-12, var);
+    }
+    assert_close(
+        eval.eval_dual(&point, &[0.0; 3]).0,
+        eval.evaluate(&point),
+        1e-15,
+        "value",
+    );
+}
+
+#[test]
+fn test_unseeded_singularities_do_not_poison_derivative() {
+    // d/dx of x + sqrt(y) at y = 0 is 1: sqrt's infinite slope is never seeded.
+    let eval = compile("x + sqrt(y)", &["x", "y"]);
+    assert_eq!(eval.eval_gradient_forward(&[1.0, 0.0])[0], 1.0);
+}
@@ -0,0 +1,164 @@
+//! Interval evaluation of compiled expressions with `CompiledEvaluator::eval_interval`
+
+use crate::{CompiledEvaluator, parse};
+use std::collections::HashSet;
+
+fn compiled(input: &str, params: &[&str]) -> CompiledEvaluator {
+    let expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile(&expr, params, None).unwrap()
+}
+
+/// `steps + 1` evenly spaced points from `lo` to `hi`
+fn grid(lo: f64, hi: f64, steps: u32) -> impl Iterator<Item = f64> {
+    (0..=steps).map(move |i| lo + (hi - lo) * f64::from(i) / f64::from(steps))
+}
+
+/// Every defined value on a grid over the box lies inside the interval result
+fn assert_encloses(input: &str, boxes: &[(f64, f64)]) {
+    let params: Vec<&str> = ["x", "y"][..boxes.len()].to_vec();
+    let eval = compiled(input, &params);
+    let (lo, hi) = eval.eval_interval(boxes);
+    assert!(lo <= hi, "{input} over {boxes:?}: [{lo}, {hi}]");
+
+    let xs: Vec<f64> = grid(boxes[0].0, boxes[0].1, 40).collect();
+    let ys: Vec<f64> = boxes
+        .get(1)
+        .map_or_else(|| vec![0.0], |&(a, b)| grid(a, b, 40).collect());
+    for &x in &xs {
+        for &y in &ys {
+            let point = [x, y];
+            let value = eval.evaluate(&point[..boxes.len()]);
+            if value.is_finite() {
+                assert!(
+                    (lo..=hi).contains(&value),
+                    "{input} at ({x}, {y}) = {value} escapes [{lo}, {hi}]"
+                );
+            }
+        }
+    }
+}
+
+/// `[lo, hi]` encloses `[lo_exact, hi_exact]` and overshoots it by at most `1e-12`
+fn assert_tight((lo, hi): (f64, f64), (lo_exact, hi_exact): (f64, f64)) {
+    assert!(
+        (lo_exact - 1e-12..=lo_exact).contains(&lo),
+        "lower bound {lo} for {lo_exact}"
+    );
+    assert!(
+        (hi_exact..=hi_exact + 1e-12).contains(&hi),
+        "upper bound {hi} for {hi_exact}"
+    );
+}
+
+#[test]
+fn test_arithmetic_rules() {
+    let eval = compiled("x + y", &["x", "y"]);
+    assert_tight(
+        eval.eval_interval(&[(1.0, 2.0), (10.0, 20.0)]),
+        (11.0, 22.0),
+    );
+
+    // All four products matter when signs are mixed
+    let eval = compiled("x * y", &["x", "y"]);
+    assert_tight(
+        eval.eval_interval(&[(-2.0, 3.0), (-5.0, 4.0)]),
+        (-15.0, 12.0),
+    );
+
+    // Even powers of a box around zero start at zero
+    let eval = compiled("x^2", &["x"]);
+    assert_tight(eval.eval_interval(&[(-3.0, 2.0)]), (0.0, 9.0));
+
+    // Bounds may be given in either order
+    assert_eq!(
+        compiled("x", &["x"]).eval_interval(&[(2.0, -1.0)]),
+        (-1.0, 2.0)
+    );
+}
+
+#[test]
+fn test_division_through_zero_is_unbounded() {
+    let eval = compiled("1/x", &["x"]);
+    assert_eq!(
+        eval.eval_interval(&[(-1.0, 1.0)]),
+        (f64::NEG_INFINITY, f64::INFINITY)
+    );
+    assert_tight(eval.eval_interval(&[(2.0, 4.0)]), (0.25, 0.5));
+}
+
+#[test]
+fn test_trig_takes_interior_extrema() {
+    // sin reaches its maximum at pi/2, inside [0, 2]
+    let sin = compiled("sin(x)", &["x"]);
+    assert_tight(sin.eval_interval(&[(0.0, 2.0)]), (0.0, 1.0));
+
+    // cos reaches its minimum at pi, inside [3, 4]
+    let cos = compiled("cos(x)", &["x"]);
+    assert_tight(cos.eval_interval(&[(3.0, 4.0)]), (-1.0, 4.0_f64.cos()));
+
+    // A box wider than a period covers [-1, 1]
+    assert_eq!(
+        compiled("cos(x)", &["x"]).eval_interval(&[(0.0, 100.0)]),
+        (-1.0, 1.0)
+    );
+}
+
+#[test]
+fn test_monotone_and_domain_rules() {
+    let exp = compiled("exp(x)", &["x"]);
+    assert_tight(exp.eval_interval(&[(0.0, 1.0)]), (1.0, std::f64::consts::E));
+
+    // ln needs a positive lower bound to be bounded below
+    let ln = compiled("ln(x)", &["x"]);
+    assert_tight(
+        ln.eval_interval(&[(1.0, 2.0)]),
+        (0.0, std::f64::consts::LN_2),
+    );
+    let (lo, hi) = ln.eval_interval(&[(0.0, 1.0)]);
+    assert_eq!(lo, f64::NEG_INFINITY);
+    assert!((0.0..1e-12).contains(&hi));
+    let (lo, hi) = ln.eval_interval(&[(-2.0, -1.0)]);
+    assert!(lo.is_nan() && hi.is_nan());
+}
+
+#[test]
+fn test_functions_without_rules_are_unbounded() {
+    assert_eq!(
+        compiled("erf(x)", &["x"]).eval_interval(&[(0.0, 1.0)]),
+        (f64::NEG_INFINITY, f64::INFINITY)
+    );
+    // The whole line propagates through arithmetic that needs it
+    let (lo, hi) = compiled("1 + gamma(x)", &["x"]).eval_interval(&[(1.0, 2.0)]);
+    assert_eq!((lo, hi), (f64::NEG_INFINITY, f64::INFINITY));
+}
+
+#[test]
+fn test_intervals_contain_sampled_values() {
+    for (input, boxes) in [
+        ("x^3 - 2*x*y + y^2", &[(-1.5, 2.0), (-0.5, 1.0)][..]),
+        ("sin(x)*cos(y) + x/(y + 3)", &[(-4.0, 1.0), (0.5, 2.5)]),
+        ("exp(-x^2) * ln(y)", &[(-1.0, 2.0), (0.5, 3.0)]),
+        ("sqrt(x) + x^0.5 * y", &[(0.0, 4.0), (-1.0, 1.0)]),
+        (
+            "tanh(x) - atan(y) + abs(x - y)",
+            &[(-3.0, 3.0), (-2.0, 5.0)],
+        ),
+        ("1/(1 + x^2)", &[(-10.0, 10.0)]),
+        ("x^-2 + cosh(x)", &[(0.5, 3.0)]),
+        ("sin(100*x) + x^4 - x", &[(-1.0, 1.0)]),
+        ("max(x, y) - min(x, 2*y)", &[(-1.0, 1.0), (-2.0, 0.5)]),
+    ] {
+        assert_encloses(input, boxes);
+    }
+}
+
+#[test]
+fn test_point_boxes_enclose_the_point_value() {
+    let eval = compiled("x*exp(y) - sin(x*y)", &["x", "y"]);
+    for (x, y) in [(0.3, 1.7), (-2.0, 0.25), (1e3, -3.0)] {
+        let value = eval.evaluate(&[x, y]);
+        let (lo, hi) = eval.eval_interval(&[(x, x), (y, y)]);
+        assert!((lo..=hi).contains(&value), "({x}, {y})");
+        assert!(hi - lo <= 1e-12 * value.abs().max(1.0), "[{lo}, {hi}]");
+    }
+}
@@ -0,0 +1,117 @@
+//! Evaluation over column tables: `eval_table` and `eval_table_parallel`
+
+use crate::{CompiledEvaluator, DiffError, Expr, parse};
+use std::collections::HashSet;
+
+fn compiled(input: &str, params: &[&str]) -> CompiledEvaluator {
+    let expr: Expr = parse(input, &HashSet::new(), &HashSet::new(), None).unwrap();
+    CompiledEvaluator::compile(&expr, params, None).unwrap()
+}
+
+const FORMULA: &str = "a*sin(x) + b*x^2 - exp(-a*x)";
+
+/// Columns for `x`, `a` and `b` with `rows` rows
+fn table(rows: usize) -> [Vec<f64>; 3] {
+    let column = |scale: f64, shift: f64| -> Vec<f64> {
+        (0..rows)
+            .map(|i| f64::from(u32::try_from(i).unwrap()).mul_add(scale, shift))
+            .collect()
+    };
+    [column(0.01, -2.0), column(0.003, 0.5), column(-0.002, 1.5)]
+}
+
+/// Row-by-row evaluation
+fn manual(eval: &CompiledEvaluator, columns: &[&[f64]]) -> Vec<f64> {
+    (0..columns[0].len())
+        .map(|row| eval.evaluate(&columns.iter().map(|c| c[row]).collect::<Vec<_>>()))
+        .collect()
+}
+
+#[test]
+fn test_remainder_lanes_match_row_evaluation() {
+    let eval = compiled(FORMULA, &["x", "a", "b"]);
+    // Below, at and past one lane group of four, and with every remainder
+    for rows in [1, 2, 3, 4, 5, 6, 7, 8, 1001] {
+        let [x, a, b] = table(rows);
+        let columns = [x.as_slice(), &a, &b];
+        let mut out = vec![0.0; rows];
+        eval.eval_table(&columns, &mut out).unwrap();
+        // SIMD lanes may round differently from the scalar path in the last bit
+        for (got, want) in out.iter().zip(manual(&eval, &columns)) {
+            assert!(
+                (got - want).abs() <= 1e-12 * want.abs(),
+                "{rows} rows: {got} vs {want}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parallel_matches_serial_for_any_chunk_size() {
+    let eval = compiled(FORMULA, &["x", "a", "b"]);
+    let [x, a, b] = table(2003);
+    let columns = [x.as_slice(), &a, &b];
+    let mut serial = vec![0.0; 2003];
+    eval.eval_table(&columns, &mut serial).unwrap();
+    // Zero and sizes off a multiple of four are rounded up to whole lane groups
+    for chunk_size in [0, 1, 5, 64, 250, 4096] {
+        let mut parallel = vec![0.0; 2003];
+        eval.eval_table_parallel(&columns, &mut parallel, chunk_size)
+            .unwrap();
+        assert_eq!(parallel, serial, "chunk size {chunk_size}");
+    }
+}
+
+#[test]
+fn test_longer_output_keeps_values_past_the_table() {
+    let eval = compiled("x + y", &["x", "y"]);
+    let mut out = [-1.0; 7];
+    eval.eval_table(&[&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]], &mut out)
+        .unwrap();
+    assert_eq!(out, [11.0, 22.0, 33.0, -1.0, -1.0, -1.0, -1.0]);
+
+    // Empty columns touch nothing
+    eval.eval_table_parallel(&[&[], &[]], &mut out, 4).unwrap();
+    assert_eq!(out[0], 11.0);
+
+    // Without parameters every output slot gets the constant
+    let constant = compiled("2 + 3", &[]);
+    let mut out = [0.0; 5];
+    constant.eval_table_parallel(&[], &mut out, 4).unwrap();
+    assert_eq!(out, [5.0; 5]);
+}
+
+#[test]
+fn test_invalid_tables_are_rejected() {
+    let eval = compiled("x*y", &["x", "y"]);
+    let mut out = [0.0; 3];
+
+    let err = eval.eval_table(&[&[1.0, 2.0, 3.0]], &mut out).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            DiffError::EvalColumnMismatch {
+                expected: 2,
+                got: 1
+            }
+        ),
+        "{err:?}"
+    );
+
+    let err = eval
+        .eval_table_parallel(&[&[1.0, 2.0, 3.0], &[1.0, 2.0]], &mut out, 4)
+        .unwrap_err();
+    assert!(
+        matches!(err, DiffError::EvalColumnLengthMismatch),
+        "{err:?}"
+    );
+
+    let err = eval
+        .eval_table(&[&[1.0; 4], &[2.0; 4]], &mut out)
+        .unwrap_err();
+    assert!(
+        matches!(err, DiffError::EvalOutputTooSmall { needed: 4, got: 3 }),
+        "{err:?}"
+    );
+    assert_eq!(out, [0.0; 3]);
+}
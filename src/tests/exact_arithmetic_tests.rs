@@ -0,0 +1,95 @@
+//! Tests for the opt-in exact rational arithmetic of `Simplify` and `Diff`
+
+use crate::{
+    CompiledEvaluator, Diff, Expr, ParserOptions, Simplify, parse, parse_configured, simplify, symb,
+};
+use std::collections::HashSet;
+
+fn exact_simplify(input: &str) -> String {
+    Simplify::new()
+        .exact_arithmetic(true)
+        .simplify_str(input, &[])
+        .unwrap()
+}
+
+fn exact_diff(input: &str) -> String {
+    Diff::new()
+        .exact_arithmetic(true)
+        .diff_str(input, "x", &[])
+        .unwrap()
+}
+
+#[test]
+fn test_thirds_sum_to_one() {
+    assert_eq!(exact_simplify("x/3+x/3+x/3"), "x");
+    assert_eq!(exact_simplify("x/3+x/6"), "x/2");
+    assert_eq!(exact_simplify("2*x/3+x/3"), "x");
+}
+
+#[test]
+fn test_decimal_literals_stay_exact() {
+    assert_eq!(
+        simplify("0.1+0.2", &[], None).unwrap(),
+        "0.30000000000000004"
+    );
+    assert_eq!(exact_simplify("0.1+0.2"), "3/10");
+    assert_eq!(exact_simplify("0.1*x+0.2*x"), "3*x/10");
+    assert_eq!(exact_simplify("2.5e-3*x"), "x/400");
+    assert_eq!(exact_simplify("x^0.5*x^0.25"), "x^(3/4)");
+}
+
+#[test]
+fn test_inexact_folds_stay_fractions() {
+    assert_eq!(simplify("3^(-1)", &[], None).unwrap(), "0.3333333333333333");
+    assert_eq!(exact_simplify("3^(-1)"), "1/3");
+    assert_eq!(exact_simplify("2^(-3)*x"), "x/8");
+}
+
+#[test]
+fn test_fractions_display_exactly() {
+    let simplified = Simplify::new()
+        .exact_arithmetic(true)
+        .simplify(&Expr::number(2.0 / 3.0))
+        .unwrap();
+    assert_eq!(simplified.to_string(), "2/3");
+    assert_eq!(exact_simplify("4/6"), "2/3");
+}
+
+#[test]
+fn test_derivatives_keep_exact_exponents() {
+    assert_eq!(exact_diff("x^(1/3)"), "1/(3*x^(2/3))");
+    assert_eq!(exact_diff("0.1*x^2"), "x/5");
+    assert_eq!(exact_diff("0.3*x^0.3"), "9/(100*x^(7/10))");
+    assert_eq!(exact_diff("0.1*x^3+0.2*x"), "(2 + 3*x^2)/10");
+}
+
+#[test]
+fn test_exact_results_evaluate_as_f64() {
+    // Parsed without exactness: the builder rationalizes the literals itself
+    let expr = parse("0.3*x^0.3", &HashSet::new(), &HashSet::new(), None).unwrap();
+    let derivative = Diff::new()
+        .exact_arithmetic(true)
+        .differentiate(&expr, &symb("x"))
+        .unwrap();
+    assert_eq!(derivative.to_string(), "9/(100*x^(7/10))");
+    let compiled = CompiledEvaluator::compile(&derivative, &["x"], None).unwrap();
+    let expected = 0.09 * 2.0_f64.powf(-0.7);
+    assert!((compiled.evaluate(&[2.0]) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_exact_parser_option_reads_fractions() {
+    let options = ParserOptions {
+        exact: true,
+        ..ParserOptions::default()
+    };
+    let parsed = parse_configured("0.1*x", &HashSet::new(), &HashSet::new(), None, &options)
+        .unwrap()
+        .expr;
+    // The literal is the quotient 1/10, not the nearest double
+    assert!(parsed.to_string().contains("1/10"), "{parsed}");
+    let simplified = Simplify::new().exact_arithmetic(true).simplify(&parsed);
+    assert_eq!(simplified.unwrap().to_string(), "x/10");
+    let plain = parse("0.1*x", &HashSet::new(), &HashSet::new(), None).unwrap();
+    assert_eq!(plain.to_string(), "0.1*x");
+}
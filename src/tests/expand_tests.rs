@@ -0,0 +1,125 @@
+//! Full algebraic expansion with `Expr::expand` and the `expand` string helper
+
+use crate::core::ExprKind;
+use crate::{DiffError, Expr, Simplify, expand, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parsed(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn eval_at(expr: &Expr, vars: &[(&str, f64)]) -> f64 {
+    let vars: HashMap<&str, f64> = vars.iter().copied().collect();
+    match expr.evaluate(&vars, &HashMap::new()).kind {
+        ExprKind::Number(n) => n,
+        ref other => panic!("Expected number, got {other:?}"),
+    }
+}
+
+fn term_count(expr: &Expr) -> usize {
+    match &expr.kind {
+        ExprKind::Sum(terms) => terms.len(),
+        ExprKind::Poly(poly) => poly.terms().len(),
+        _ => 1,
+    }
+}
+
+#[test]
+fn test_trinomial_cube() {
+    let (x, y, z) = (symb("x"), symb("y"), symb("z"));
+    let expanded = (x + y + z).pow(3.0).expand();
+    assert_eq!(term_count(&expanded), 10, "{expanded}");
+    assert_eq!(expanded.coefficient_of(&x.pow(3.0)), Expr::number(1.0));
+    assert_eq!(
+        expanded.coefficient_of(&(x.pow(2.0) * y)),
+        Expr::number(3.0)
+    );
+    assert_eq!(expanded.coefficient_of(&(x * y * z)), Expr::number(6.0));
+}
+
+#[test]
+fn test_binomial_tenth_power() {
+    let x = symb("x");
+    let expanded = parsed("(x+1)^10").expand();
+    assert!(matches!(expanded.kind, ExprKind::Poly(_)), "{expanded}");
+    assert_eq!(expanded.coefficient_of(&x.pow(5.0)), Expr::number(252.0));
+    assert_eq!(expanded.coefficient_of(&x.pow(10.0)), Expr::number(1.0));
+    assert_eq!(term_count(&expanded), 11);
+}
+
+#[test]
+fn test_distributes_and_combines() {
+    assert_eq!(expand("(x+1)*(x-1)", &[], None).unwrap(), "-1 + x^2");
+    assert_eq!(parsed("(x+1)*(x-1) - x^2 + 1").expand(), Expr::number(0.0));
+    assert_eq!(parsed("(x*y)^3").expand(), parsed("x^3*y^3"));
+    assert_eq!(parsed("a*(b+c)").expand(), parsed("a*b + a*c"));
+
+    // Expansion reaches into function arguments
+    assert_eq!(
+        parsed("sin((x+1)^2)").expand().to_string(),
+        "sin(1 + 2*x + x^2)"
+    );
+}
+
+#[test]
+fn test_expansion_preserves_values() {
+    for input in [
+        "(x+2*y)^4 * (x-y)",
+        "(x+1)^3/(x+y)",
+        "(x+y)^2/(x*y)",
+        "exp(x)*(x+y)^2 - (x-1)^5",
+        "(x+y)^-2 * (x+1)^2",
+    ] {
+        let expr = parsed(input);
+        let expanded = expr.expand();
+        for (x, y) in [(0.5, 1.5), (-2.0, 0.25), (3.0, -1.25)] {
+            let vars = [("x", x), ("y", y)];
+            let (before, after) = (eval_at(&expr, &vars), eval_at(&expanded, &vars));
+            assert!(
+                (before - after).abs() <= 1e-9 * before.abs().max(1.0),
+                "{input} -> {expanded} at ({x}, {y}): {before} vs {after}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_term_limit() {
+    let expr = parsed("(x+y+z)^3");
+    assert!(expr.expand_with_limit(10).is_ok());
+    assert!(matches!(
+        expr.expand_with_limit(9),
+        Err(DiffError::ExpansionTooLarge { limit: 9, .. })
+    ));
+    assert!(matches!(
+        parsed("(x+1)^20").expand_with_limit(15),
+        Err(DiffError::ExpansionTooLarge { limit: 15, .. })
+    ));
+}
+
+#[test]
+fn test_trinomial_square_has_six_terms() {
+    let expanded = parsed("(a + b + c)^2").expand();
+    assert_eq!(term_count(&expanded), 6, "{expanded}");
+    assert_eq!(expanded.expand(), expanded);
+
+    let shortcut = Simplify::new().expand(&parsed("(a + b + c)^2")).unwrap();
+    assert_eq!(term_count(&shortcut), 6, "{shortcut}");
+}
+
+#[test]
+fn test_simplify_expand_keeps_expanded_form() {
+    // Plain simplification factors the perfect square back into (1 + x)^2
+    let square = parsed("(x + 1)^2");
+    let expanded = square.expand();
+    assert_eq!(Simplify::new().simplify(&expanded).unwrap(), square);
+
+    let shortcut = Simplify::new().expand(&square).unwrap();
+    assert_eq!(shortcut, expanded);
+    assert_eq!(Simplify::new().expand(&shortcut).unwrap(), shortcut);
+
+    // The builder's rules still apply to the expanded terms: sin^2 + cos^2 = 1
+    let trig = parsed("(sin(x) + cos(x))^2");
+    let shortcut = Simplify::new().expand(&trig).unwrap();
+    assert_eq!(shortcut.to_string(), "1 + sin(2*x)");
+}
@@ -0,0 +1,145 @@
+//! Tests for text output with options: `Expr::format_with` and `Diff::output_format`
+
+use crate::{Diff, Expr, FormatOptions, FunctionCallStyle, MulStyle, PowerOperator, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+const PYTHON: FormatOptions = FormatOptions {
+    power_operator: PowerOperator::DoubleStar,
+    mul_style: MulStyle::Explicit,
+    float_precision: None,
+    function_call_style: FunctionCallStyle::Parentheses,
+};
+
+/// Formatted with the default options, `expr` parses back to itself
+fn assert_round_trip(expr: &Expr) {
+    let text = expr.format_with(&FormatOptions::default());
+    assert_eq!(&parse_str(&text), expr, "{text}");
+}
+
+#[test]
+fn test_default_matches_display() {
+    for input in [
+        "x^2 + 2*x + 1",
+        "a*sin(x)/(b + c)",
+        "-3*x*y + exp(-x^2)",
+        "x^-2 + piecewise(x < 0: 0, x)",
+    ] {
+        let expr = parse_str(input);
+        assert_eq!(
+            expr.format_with(&FormatOptions::default()),
+            expr.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_negative_exponents() {
+    let x = symb("x");
+    let expr = x.pow(-2.0) + x.pow(-0.5) * 3.0;
+    assert_eq!(
+        expr.format_with(&FormatOptions::default()),
+        expr.to_string()
+    );
+    assert_eq!(parse_str("x^-2").format_with(&PYTHON), "x**-2");
+    assert_eq!(parse_str("2^(-x)").format_with(&PYTHON), "2**(-x)");
+    assert_round_trip(&expr);
+    assert_round_trip(&parse_str("a^(-b - 1)"));
+}
+
+#[test]
+fn test_nested_powers() {
+    let x = symb("x");
+    // Display writes (x^2)^3 as x^2^3, which reads back as x^8
+    let nested = Expr::pow_static(x.pow(2.0), Expr::number(3.0));
+    assert_eq!(nested.format_with(&FormatOptions::default()), "(x^2)^3");
+    assert_eq!(nested.format_with(&PYTHON), "(x**2)**3");
+    assert_round_trip(&nested);
+
+    let tower = x.pow(x.pow(2.0));
+    assert_eq!(tower.format_with(&PYTHON), "x**(x**2)");
+    assert_round_trip(&tower);
+    assert_round_trip(&parse_str("(a + b)^(c^d)"));
+}
+
+#[test]
+fn test_float_precision() {
+    let x = symb("x");
+    let tiny = x * 3.586_52e-18;
+    assert_eq!(tiny.to_string(), "0.00000000000000000358652*x");
+    let sci = FormatOptions {
+        float_precision: Some(6),
+        ..FormatOptions::default()
+    };
+    assert_eq!(tiny.format_with(&sci), "3.58652e-18*x");
+    assert_eq!(parse_str(&tiny.format_with(&sci)), tiny);
+
+    // A sum of decimals carries a representation error, which the precision hides
+    let drift = Expr::number(0.1 + 0.2) * x;
+    assert_eq!(drift.to_string(), "0.30000000000000004*x");
+    let three = FormatOptions {
+        float_precision: Some(3),
+        ..FormatOptions::default()
+    };
+    assert_eq!(drift.format_with(&three), "3.00e-1*x");
+    // Integers below 10^10 stay integers
+    assert_eq!((x * 12.0).format_with(&three), "12*x");
+    assert_eq!(Expr::number(-2.5e12).format_with(&three), "-2.50e12");
+}
+
+#[test]
+fn test_mul_and_call_styles() {
+    let spaced = FormatOptions {
+        mul_style: MulStyle::Spaced,
+        ..FormatOptions::default()
+    };
+    let implicit = FormatOptions {
+        mul_style: MulStyle::Implicit,
+        ..FormatOptions::default()
+    };
+    // Single terms: the order of a sum's terms depends on symbol creation order
+    for (input, with_spaces, juxtaposed) in [
+        ("2*x*sin(y)", "2 * x * sin(y)", "2x*sin(y)"),
+        ("3*exp(z)", "3 * exp(z)", "3*exp(z)"),
+        // A coefficient is never joined to a name read as an exponent
+        ("4*e", "4 * e", "4*e"),
+        ("-5*a*b", "-5 * a * b", "-5a*b"),
+    ] {
+        let expr = parse_str(input);
+        assert_eq!(expr.format_with(&spaced), with_spaces);
+        assert_eq!(expr.format_with(&implicit), juxtaposed);
+        assert_eq!(parse_str(juxtaposed), expr);
+    }
+
+    let brackets = FormatOptions {
+        function_call_style: FunctionCallStyle::SquareBrackets,
+        ..FormatOptions::default()
+    };
+    assert_eq!(parse_str("sin(x)^2").format_with(&brackets), "sin[x]^2");
+    assert_eq!(
+        parse_str("atan2(y, x)").format_with(&brackets),
+        "atan2[y, x]"
+    );
+}
+
+#[test]
+fn test_diff_output_format() {
+    let diff = Diff::new().output_format(FormatOptions {
+        float_precision: Some(4),
+        ..PYTHON
+    });
+    assert_eq!(diff.diff_str("x^3/3", "x", &[]).unwrap(), "x**2");
+    assert_eq!(diff.diff_str("1.5e-9*x^2", "x", &[]).unwrap(), "3.000e-9*x");
+    assert_eq!(
+        diff.diff_str_n("sin(x)*x^4", "x", &[], 1).unwrap(),
+        Diff::new()
+            .diff_str_n("sin(x)*x^4", "x", &[], 1)
+            .unwrap()
+            .replace('^', "**")
+    );
+    // Without output options the strings are unchanged
+    assert_eq!(Diff::new().diff_str("x^3", "x", &[]).unwrap(), "3*x^2");
+}
@@ -0,0 +1,112 @@
+//! Tests for `Context::load_function_library`
+
+use crate::{CompiledEvaluator, Context, Diff, DiffError, Expr, parse, symb};
+use std::collections::HashSet;
+
+/// `lib_outer` calls `lib_inner`, which is defined after it and has an explicit partial
+const LIBRARY: &str = r#"
+# Two helpers; the first one calls the second
+[[function]]
+name = "lib_outer"
+params = ["u", "v"]
+body = "u^3 + lib_inner(v)"
+
+[[function]]
+name = 'lib_inner'
+params = ["w",]   # trailing comma
+body = "w*sin(w)"
+
+[function.partials]
+w = "sin(w) + w*cos(w)"
+"#;
+
+/// Parsed with `x` and `y` as the global symbols the evaluators are compiled for
+fn parse_in(formula: &str, ctx: &Context) -> Expr {
+    let _ = (symb("x"), symb("y"));
+    parse(formula, &HashSet::new(), &HashSet::new(), Some(ctx)).unwrap()
+}
+
+fn assert_same_values(got: &Expr, want: &Expr, ctx: &Context) {
+    let (x, y) = (symb("x"), symb("y"));
+    let got = CompiledEvaluator::compile(got, &[&x, &y], Some(ctx)).unwrap();
+    let want = CompiledEvaluator::compile(want, &[&x, &y], None).unwrap();
+    for point in [[0.5, -1.2], [1.5, 0.3], [-2.0, 2.5]] {
+        let (g, w) = (got.evaluate(&point), want.evaluate(&point));
+        assert!((g - w).abs() < 1e-12, "{g} != {w} at {point:?}");
+    }
+}
+
+#[test]
+fn test_library_functions_differentiate_through_each_other() {
+    let ctx = Context::new().load_function_library(LIBRARY).unwrap();
+    assert!(
+        ctx.get_user_fn("lib_outer")
+            .is_some_and(|f| f.has_partial(1))
+    );
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = parse_in("lib_outer(x^2, y)", &ctx);
+    let diff = Diff::new().context(&ctx);
+
+    let dx = diff.differentiate(&expr, &x).unwrap();
+    let dy = diff.differentiate(&expr, &y).unwrap();
+    assert_same_values(&dx, &parse_in("6*x^5", &Context::new()), &ctx);
+    assert_same_values(&dy, &parse_in("sin(y) + y*cos(y)", &Context::new()), &ctx);
+}
+
+#[test]
+fn test_library_functions_expand_when_compiled() {
+    let ctx = Context::new().load_function_library(LIBRARY).unwrap();
+    let expr = parse_in("lib_outer(x, y) / lib_inner(x + y)", &ctx);
+    let expanded = parse_in("(x^3 + y*sin(y)) / ((x + y)*sin(x + y))", &Context::new());
+    assert_same_values(&expr, &expanded, &ctx);
+}
+
+#[test]
+fn test_cyclic_library_is_rejected() {
+    let spec = r#"
+        [[function]]
+        name = "lib_a"
+        params = ["t"]
+        body = "1 + lib_b(t)"
+
+        [[function]]
+        name = "lib_b"
+        params = ["t"]
+        body = "lib_c(2*t)"
+
+        [[function]]
+        name = "lib_c"
+        params = ["t"]
+        body = "lib_a(t)^2"
+    "#;
+    let ctx = Context::new();
+    let err = ctx.clone().load_function_library(spec).unwrap_err();
+    assert!(matches!(
+        &err,
+        DiffError::CyclicFunctionDefinition { cycle } if cycle == &["lib_a", "lib_b", "lib_c", "lib_a"]
+    ));
+    assert!(err.to_string().contains("lib_a -> lib_b -> lib_c -> lib_a"));
+    assert!(!ctx.has_function("lib_a"));
+}
+
+#[test]
+fn test_malformed_library_names_the_line() {
+    for (spec, needle) in [
+        (
+            "[[function]]\nname = \"f\"\nbody = \"x\"\ncolor = \"red\"",
+            "line 4",
+        ),
+        (
+            "[[function]]\nname = \"f\"\nparams = [\"t\"]\nbody = \"t\"\n[function.partials]\ns = \"1\"",
+            "unknown parameter 's'",
+        ),
+        (
+            "[[function]]\nparams = [\"t\"]\nbody = \"t\"",
+            "without a name",
+        ),
+        ("name = \"f\"", "inside a [[function]]"),
+    ] {
+        let err = Context::new().load_function_library(spec).unwrap_err();
+        assert!(err.to_string().contains(needle), "{spec:?}: {err}");
+    }
+}
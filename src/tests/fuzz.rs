@@ -154,5 +154,17 @@ fn eval_tree_manual(expr: &Expr, vars: &HashMap<String, f64>) -> f64 {
             sum
         }
         ExprKind::Derivative { .. } => 0.0,
+        ExprKind::Piecewise { branches, default } => branches
+            .iter()
+            .find(|(c, _)| {
+                c.op.holds(
+                    eval_tree_manual(&c.lhs, vars),
+                    eval_tree_manual(&c.rhs, vars),
+                )
+            })
+            .map_or_else(
+                || eval_tree_manual(default, vars),
+                |(_, value)| eval_tree_manual(value, vars),
+            ),
     }
 }
@@ -0,0 +1,6 @@
+float blob(float x, float y, float z) {
+    float t0 = x * x;
+    float t1 = y * y;
+    float t2 = z * z;
+    return (1.0 + t0 + t1 + t2) * exp(-(t0 + t1 + t2));
+}
@@ -0,0 +1,6 @@
+fn blob(x: f32, y: f32, z: f32) -> f32 {
+    let t0 = x * x;
+    let t1 = y * y;
+    let t2 = z * z;
+    return (1.0 + t0 + t1 + t2) * exp(-(t0 + t1 + t2));
+}
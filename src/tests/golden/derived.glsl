@@ -0,0 +1,5 @@
+float derived(float x, float y, float z) {
+    float t0 = x * x;
+    float t1 = t0 * t0;
+    return 1.0 / (x * t1) + atanh(1.0 / y) + sign(z) * pow(abs(z), 0.33333334) + log(y) * 0.4342945 + 1.0 / cos(x);
+}
@@ -0,0 +1,3 @@
+float gyroid(float x, float y, float z) {
+    return cos(x) * sin(z) + cos(y) * sin(x) + cos(z) * sin(y);
+}
@@ -0,0 +1,3 @@
+fn gyroid(x: f32, y: f32, z: f32) -> f32 {
+    return cos(x) * sin(z) + cos(y) * sin(x) + cos(z) * sin(y);
+}
@@ -0,0 +1,4 @@
+float polar(float x, float y, float z) {
+    float t0 = x * y;
+    return -(sign(t0) * floor(abs(t0) + 0.5)) + z * atan(y, x) + 1.5707964 - atan(z);
+}
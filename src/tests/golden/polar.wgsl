@@ -0,0 +1,4 @@
+fn polar(x: f32, y: f32, z: f32) -> f32 {
+    let t0 = x * y;
+    return -(sign(t0) * floor(abs(t0) + 0.5)) + z * atan2(y, x) + 1.5707964 - atan(z);
+}
@@ -0,0 +1,4 @@
+float torus(float x, float y, float z) {
+    float t0 = -2.0 + sqrt(x * x + y * y);
+    return -0.25 + z * z + t0 * t0;
+}
@@ -0,0 +1,4 @@
+fn torus(x: f32, y: f32, z: f32) -> f32 {
+    let t0 = -2.0 + sqrt(x * x + y * y);
+    return -0.25 + z * z + t0 * t0;
+}
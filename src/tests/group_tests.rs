@@ -0,0 +1,141 @@
+//! Tests for `group(...)` and `Simplify::preserve_groups` / `Diff::preserve_groups`
+
+use crate::{CompiledEvaluator, Diff, Expr, Simplify, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// `rho*v*L/mu` as the simplifier writes it
+fn reynolds() -> Expr {
+    Simplify::new().simplify(&parse_str("rho*v*L/mu")).unwrap()
+}
+
+/// The one subexpression labeled `label`
+fn only_labeled<'expr>(expr: &'expr Expr, label: &str) -> &'expr Expr {
+    let found = expr.find_labeled(label);
+    assert_eq!(found.len(), 1, "expected one '{label}' in {expr}");
+    found[0]
+}
+
+#[test]
+fn test_group_call_labels_its_argument() {
+    let named = parse_str("group(rho*v*L/mu, Re)");
+    assert_eq!(named.label(), Some("Re"));
+    assert_eq!(named, parse_str("rho*v*L/mu"));
+
+    let unnamed = parse_str("2*group(a + b)");
+    assert_eq!(only_labeled(&unnamed, "group"), &parse_str("a + b"));
+}
+
+#[test]
+fn test_group_call_rejects_bad_arguments() {
+    for input in ["group()", "group(a, b, c)", "group(a, 2)"] {
+        assert!(
+            parse(input, &HashSet::new(), &HashSet::new(), None).is_err(),
+            "{input} should not parse"
+        );
+    }
+}
+
+#[test]
+fn test_preserve_groups_keeps_group_as_factor() {
+    let expr = parse_str("2*group(rho*v*L/mu, Re)/rho");
+    let result = Simplify::new()
+        .preserve_groups(true)
+        .simplify(&expr)
+        .unwrap();
+
+    assert_eq!(only_labeled(&result, "Re"), &reynolds());
+    let rho = Expr::from(symb("rho"));
+    let expected = Expr::div_expr(Expr::number(2.0) * Expr::labeled("Re", reynolds()), rho);
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_preserve_groups_simplifies_inside_group() {
+    let expr = parse_str("group(x + x + y*y, G)*z");
+    let result = Simplify::new()
+        .preserve_groups(true)
+        .simplify(&expr)
+        .unwrap();
+    let inner = Simplify::new().simplify(&parse_str("x + x + y*y")).unwrap();
+    assert_eq!(only_labeled(&result, "G"), &inner);
+}
+
+#[test]
+fn test_preserve_groups_combines_equal_groups() {
+    let expr = parse_str("group(a + b, G)*z + 3*group(a + b, G)*z");
+    let result = Simplify::new()
+        .preserve_groups(true)
+        .simplify(&expr)
+        .unwrap();
+    let group = Expr::labeled("G", parse_str("a + b"));
+    assert_eq!(result, Expr::number(4.0) * group * symb("z"));
+    assert_eq!(result.find_labeled("G").len(), 1);
+}
+
+#[test]
+fn test_nested_groups_stay_intact() {
+    let expr = parse_str("group(2*group(a*b/c, Inner)/a, Outer)*a");
+    let result = Simplify::new()
+        .preserve_groups(true)
+        .simplify(&expr)
+        .unwrap();
+
+    let inner = Simplify::new().simplify(&parse_str("a*b/c")).unwrap();
+    assert_eq!(only_labeled(&result, "Inner"), &inner);
+    let outer = only_labeled(&result, "Outer");
+    let held = Expr::number(2.0) * Expr::labeled("Inner", inner);
+    assert_eq!(outer, &Expr::div_expr(held, symb("a").into()));
+}
+
+#[test]
+fn test_without_flag_behavior_is_unchanged() {
+    for input in [
+        "2*group(rho*v*L/mu, Re)/rho",
+        "0.023*group(rho*v*L/mu, Re)^0.8*Pr^0.4",
+    ] {
+        let grouped = parse_str(input);
+        let plain = parse_str(&input.replace("group(", "(").replace(", Re)", ")"));
+        assert_eq!(
+            Simplify::new().simplify(&grouped).unwrap(),
+            Simplify::new().simplify(&plain).unwrap(),
+            "{input}"
+        );
+    }
+
+    let result = Simplify::new()
+        .simplify(&parse_str("2*group(rho*v*L/mu, Re)/rho"))
+        .unwrap();
+    let cancelled = Simplify::new().simplify(&parse_str("2*v*L/mu")).unwrap();
+    assert_eq!(result, cancelled);
+}
+
+#[test]
+fn test_diff_applies_chain_rule_to_group() {
+    let expr = parse_str("0.023*group(rho*v*L/mu, Re)^0.8*Pr^0.4");
+    let v = symb("v");
+    let derivative = Diff::new()
+        .preserve_groups(true)
+        .differentiate(&expr, &v)
+        .unwrap();
+
+    // 0.0184*Pr^0.4*Re^(-0.2) times dRe/dv, which carries the label too
+    let groups = derivative.find_labeled("Re");
+    assert_eq!(groups.len(), 2, "{derivative}");
+    assert!(groups.contains(&&reynolds()));
+    let d_reynolds = Simplify::new().simplify(&parse_str("rho*L/mu")).unwrap();
+    assert!(groups.contains(&&d_reynolds));
+
+    let plain = Diff::new().differentiate(&expr, &v).unwrap();
+    let params = ["rho", "v", "L", "mu", "Pr"];
+    let values = [1.2, 3.0, 0.5, 1.8e-5, 0.7];
+    let (held, reference) = (
+        CompiledEvaluator::compile(&derivative, &params, None).unwrap(),
+        CompiledEvaluator::compile(&plain, &params, None).unwrap(),
+    );
+    let (held, reference) = (held.evaluate(&values), reference.evaluate(&values));
+    assert!((held - reference).abs() <= 1e-9 * reference.abs());
+}
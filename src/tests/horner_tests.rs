@@ -0,0 +1,131 @@
+//! Horner form rewriting and Horner evaluation of polynomial sums in compiled code
+
+use crate::core::ExprKind;
+use crate::{CompiledEvaluator, Expr, PolyConversion, symb, with_poly_conversion};
+use std::collections::HashMap;
+
+fn eval_at(expr: &Expr, vars: &[(&str, f64)]) -> f64 {
+    let vars: HashMap<&str, f64> = vars.iter().copied().collect();
+    match expr.evaluate(&vars, &HashMap::new()).kind {
+        ExprKind::Number(n) => n,
+        ref other => panic!("Expected number, got {other:?}"),
+    }
+}
+
+/// `c0 + c1*x + c2*x^2 + ..` as a plain sum of monomials
+fn monomial_sum(coeffs: &[f64]) -> Expr {
+    let x = Expr::symbol("x");
+    with_poly_conversion(PolyConversion::DISABLED, || {
+        Expr::sum(
+            coeffs
+                .iter()
+                .zip(0_u32..)
+                .map(|(&c, pow)| {
+                    Expr::number(c) * Expr::pow(x.clone(), Expr::number(f64::from(pow)))
+                })
+                .collect(),
+        )
+    })
+}
+
+fn compile_x(expr: &Expr) -> CompiledEvaluator {
+    CompiledEvaluator::compile(expr, &["x"], None).unwrap()
+}
+
+#[test]
+fn test_to_horner_nests_coefficients() {
+    let x = symb("x");
+    let poly = 3.0 * x.pow(3.0) + 2.0 * x.pow(2.0) + x + 5.0;
+    let horner = poly.to_horner(&x).unwrap();
+    assert_eq!(horner.to_string(), "5 + x*(1 + x*(2 + 3*x))");
+    for t in [-2.5, -1.0, 0.0, 0.5, 3.0] {
+        let (direct, nested) = (eval_at(&poly, &[("x", t)]), eval_at(&horner, &[("x", t)]));
+        assert!(
+            (direct - nested).abs() < 1e-12,
+            "x = {t}: {direct} vs {nested}"
+        );
+    }
+}
+
+#[test]
+fn test_to_horner_symbolic_and_sparse_coefficients() {
+    let (x, a) = (symb("x"), symb("a"));
+    let poly = a * x.pow(4.0) + 2.0 * x - a;
+    let horner = poly.to_horner(&x).unwrap();
+    for (t, s) in [(1.5, 2.0), (-0.5, 3.0), (2.0, -1.0)] {
+        let vars = [("x", t), ("a", s)];
+        let (direct, nested) = (eval_at(&poly, &vars), eval_at(&horner, &vars));
+        assert!((direct - nested).abs() < 1e-12, "{direct} vs {nested}");
+    }
+    assert_eq!(Expr::number(0.0).to_horner(&x).unwrap(), Expr::number(0.0));
+}
+
+#[test]
+fn test_to_horner_rejects_non_polynomials() {
+    let x = symb("x");
+    assert!(x.sin().to_horner(&x).is_err());
+    assert!((x.pow(2.0) + 1.0 / x).to_horner(&x).is_err());
+}
+
+#[test]
+fn test_compiled_monomial_sum_uses_horner() {
+    // One multiply-add per degree instead of separate powers, products and additions
+    let cubic = monomial_sum(&[5.0, 1.0, 2.0, 3.0]);
+    assert!(matches!(cubic.kind, ExprKind::Sum(_)));
+    let compiled = compile_x(&cubic);
+    assert_eq!(
+        compiled.instruction_count(),
+        3,
+        "{}",
+        compiled.disassemble()
+    );
+
+    let x = symb("x");
+    let horner = compile_x(&cubic.to_horner(&x).unwrap());
+    assert_eq!(horner.instruction_count(), compiled.instruction_count());
+
+    for t in [-3.0, -0.75, 0.0, 0.4, 2.0, 10.0] {
+        let direct = eval_at(&cubic, &[("x", t)]);
+        for evaluator in [&compiled, &horner] {
+            let value = evaluator.evaluate(&[t]);
+            assert!(
+                (value - direct).abs() <= 1e-12 * direct.abs().max(1.0),
+                "{value} vs {direct}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_compiled_dense_polynomial_needs_fewer_instructions_than_terms() {
+    let coeffs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let sum = monomial_sum(&coeffs);
+    let compiled = compile_x(&sum);
+    // Direct evaluation needs a power and a product per term plus the additions
+    assert!(
+        compiled.instruction_count() < 2 * coeffs.len() - 2,
+        "{}",
+        compiled.disassemble()
+    );
+    for t in [-1.5, -0.2, 0.3, 1.1] {
+        let direct = eval_at(&sum, &[("x", t)]);
+        let value = compiled.evaluate(&[t]);
+        assert!(
+            (value - direct).abs() <= 1e-12 * direct.abs().max(1.0),
+            "{value} vs {direct}"
+        );
+    }
+}
+
+#[test]
+fn test_compiled_sparse_sum_keeps_term_form() {
+    // Horner would spend nine multiply-adds on the zero coefficients of x^10 + x
+    let sparse = monomial_sum(&[0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+    let compiled = compile_x(&sparse);
+    assert!(
+        compiled.instruction_count() <= 3,
+        "{}",
+        compiled.disassemble()
+    );
+    assert!((compiled.evaluate(&[2.0]) - 1026.0).abs() < 1e-9);
+}
@@ -0,0 +1,106 @@
+//! hypot(x, y): two-argument derivative, simplification rules, compiled builtin
+
+use crate::evaluator::{FnOp, Instruction};
+use crate::{CompiledEvaluator, Diff, Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn simplified(input: &str) -> String {
+    crate::simplify(input, &[], None).unwrap()
+}
+
+fn compile(expr: &Expr) -> CompiledEvaluator {
+    CompiledEvaluator::compile(expr, &["x", "y"], None).unwrap()
+}
+
+const POINTS: [(f64, f64); 5] = [
+    (3.0, 4.0),
+    (-1.5, 0.5),
+    (0.2, -2.0),
+    (1e-3, 7.0),
+    (-2.0, -2.0),
+];
+
+#[test]
+fn test_partials_match_finite_difference() {
+    let step = 1e-6;
+    for input in ["hypot(x, y)", "hypot(x^2, sin(y))", "x*hypot(2*x, y + 1)"] {
+        let expr = parse_str(input);
+        let eval = compile(&expr);
+        for var in ["x", "y"] {
+            let partial = compile(&Diff::new().differentiate(&expr, &symb(var)).unwrap());
+            for (x, y) in POINTS {
+                let (dx, dy) = if var == "x" { (step, 0.0) } else { (0.0, step) };
+                let central = (eval.evaluate(&[x + dx, y + dy]) - eval.evaluate(&[x - dx, y - dy]))
+                    / (2.0 * step);
+                assert!(
+                    (partial.evaluate(&[x, y]) - central).abs() < 1e-6,
+                    "d/d{var} {input} at ({x}, {y})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_derivative_closed_form() {
+    // d/dx hypot(x, y) = x / hypot(x, y)
+    let derivative = Diff::new()
+        .differentiate(&parse_str("hypot(x, y)"), &symb("x"))
+        .unwrap();
+    let eval = compile(&derivative);
+    assert!((eval.evaluate(&[3.0, 4.0]) - 0.6).abs() < 1e-15);
+    assert!((eval.evaluate(&[-5.0, 12.0]) + 5.0 / 13.0).abs() < 1e-15);
+}
+
+#[test]
+fn test_simplification_rules() {
+    assert_eq!(simplified("hypot(x, 0)"), "abs(x)");
+    assert_eq!(simplified("hypot(0, y)"), "abs(y)");
+    assert_eq!(simplified("hypot(3, 4)"), "5");
+
+    let equal = parse_str("hypot(a + 1, a + 1)");
+    assert_eq!(
+        crate::Simplify::new().simplify(&equal).unwrap(),
+        crate::Simplify::new()
+            .simplify(&parse_str("abs(a + 1)*sqrt(2)"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_compiled_uses_hypot_builtin() {
+    let eval = compile(&parse_str("hypot(x, y)"));
+    assert!(eval.instructions.iter().any(|instr| matches!(
+        instr,
+        Instruction::Builtin2 {
+            op: FnOp::Hypot,
+            ..
+        }
+    )));
+    assert!(
+        !eval
+            .instructions
+            .iter()
+            .any(|instr| matches!(instr, Instruction::Sqrt { .. })),
+        "{}",
+        eval.disassemble()
+    );
+
+    // No intermediate overflow or underflow, unlike sqrt(x^2 + y^2)
+    for scale in [1e200, 1e-200] {
+        let norm = eval.evaluate(&[3.0 * scale, 4.0 * scale]);
+        assert!((norm / scale - 5.0).abs() < 1e-14, "scale {scale}");
+    }
+}
+
+#[test]
+fn test_hypot_in_shader_code() {
+    let wgsl = parse_str("hypot(x, y)")
+        .to_wgsl("f", &["x", "y"], None)
+        .unwrap();
+    assert!(wgsl.contains("sqrt(x * x + y * y)"), "{wgsl}");
+}
@@ -0,0 +1,95 @@
+//! Tests for `Diff::implicit` and `diff_implicit_str`: slopes of implicitly defined curves
+
+use crate::{
+    CompiledEvaluator, Diff, DiffError, Expr, Simplify, diff_implicit_str, parse, parse_equation,
+    symb,
+};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn slope(relation: &str) -> Expr {
+    Diff::new()
+        .implicit(&parse_plain(relation), &symb("y"), &symb("x"))
+        .unwrap()
+}
+
+#[test]
+fn test_circle() {
+    assert_eq!(slope("x^2 + y^2 - 1").to_string(), "-x/y");
+    assert_eq!(
+        diff_implicit_str("x^2 + y^2 = 1", "y", "x").unwrap(),
+        "-x/y"
+    );
+}
+
+#[test]
+fn test_dependent_variable_comes_first() {
+    // y = x^2: dy/dx = 2*x, while dx/dy = 1/(2*x)
+    assert_eq!(slope("y - x^2").to_string(), "2*x");
+    assert_eq!(diff_implicit_str("y = x^2", "y", "x").unwrap(), "2*x");
+    assert_eq!(diff_implicit_str("y = x^2", "x", "y").unwrap(), "1/(2*x)");
+    let parabola = parse_equation("y = x^2", &HashSet::new(), &HashSet::new(), None).unwrap();
+    assert_eq!(
+        parabola.implicit_diff(&symb("y"), &symb("x")).unwrap(),
+        slope("y - x^2")
+    );
+}
+
+#[test]
+fn test_folium_of_descartes() {
+    // x^3 + y^3 = 3xy: dy/dx = (y - x^2)/(y^2 - x)
+    let result = diff_implicit_str("x^3 + y^3 = 3*x*y", "y", "x").unwrap();
+    let slope = parse_plain(&result);
+    let eval = CompiledEvaluator::compile(&slope, &["x", "y"], None).unwrap();
+    for (x, y) in [(1.5_f64, 1.5_f64), (0.3, 2.0), (-1.0, 0.4)] {
+        let want = x.mul_add(-x, y) / y.mul_add(y, -x);
+        let got = eval.evaluate(&[x, y]);
+        assert!((got - want).abs() < 1e-12, "{result} at ({x}, {y})");
+    }
+}
+
+#[test]
+fn test_result_is_fully_simplified() {
+    for relation in [
+        "x^2 + y^2 - 1",
+        "x^3 + y^3 - 3*x*y",
+        "sin(x*y) + exp(y) - x",
+        "x*y + y^3 - x",
+    ] {
+        let result = slope(relation);
+        assert_eq!(
+            Simplify::new().simplify(&result).unwrap(),
+            result,
+            "{relation}"
+        );
+    }
+}
+
+#[test]
+fn test_relation_without_y_divides_by_zero() {
+    let err = Diff::new()
+        .implicit(&parse_plain("x^2 - 4"), &symb("y"), &symb("x"))
+        .unwrap_err();
+    assert!(matches!(&err, DiffError::DivisionByZero { .. }), "{err:?}");
+    assert_eq!(
+        err.to_string(),
+        "Division by zero: d(-4 + x^2)/dy simplifies to 0"
+    );
+    assert!(matches!(
+        diff_implicit_str("y*0 + x = 2", "y", "x"),
+        Err(DiffError::DivisionByZero { .. })
+    ));
+}
+
+#[test]
+fn test_same_variable_and_parse_errors() {
+    let x = symb("x");
+    assert!(matches!(
+        Diff::new().implicit(&parse_plain("x^2 - 1"), &x, &x),
+        Err(DiffError::UnsupportedOperation(_))
+    ));
+    assert!(diff_implicit_str("x^2 + y^2 <= 1", "y", "x").is_err());
+}
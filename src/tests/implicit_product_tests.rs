@@ -0,0 +1,109 @@
+//! Juxtaposed parentheses: implicit products, function calls and spacing warnings
+
+use crate::core::{Context, ExprKind, Span, UserFunction};
+use crate::{Expr, ParseWarning, ParserOptions, diff, parse, parse_configured};
+use std::collections::HashSet;
+
+fn parse_with(input: &str, functions: &[&str]) -> Expr {
+    let functions: HashSet<String> = functions.iter().map(|&f| f.to_owned()).collect();
+    parse(input, &HashSet::new(), &functions, None).unwrap()
+}
+
+const WARNINGS: ParserOptions = ParserOptions {
+    decimal_comma: false,
+    unicode_operators: false,
+    exact: false,
+    scope: None,
+    warnings: true,
+    source_map: false,
+};
+
+fn warnings(input: &str, functions: &[&str]) -> Vec<ParseWarning> {
+    let functions: HashSet<String> = functions.iter().map(|&f| f.to_owned()).collect();
+    parse_configured(input, &HashSet::new(), &functions, None, &WARNINGS)
+        .unwrap()
+        .warnings
+}
+
+#[test]
+fn test_juxtaposed_parentheses_multiply() {
+    for (implicit, explicit) in [
+        ("(x+1)(x-1)", "(x+1)*(x-1)"),
+        ("2(x+1)", "2*(x+1)"),
+        ("(a+b)(c+d)(e+f)", "(a+b)*(c+d)*(e+f)"),
+        ("((x+1))(x)", "(x+1)*x"),
+        ("(x+1)((x-1)(x+2))", "(x+1)*((x-1)*(x+2))"),
+        ("2 (x+1)", "2*(x+1)"),
+    ] {
+        // Registered function names elsewhere do not change the products
+        assert_eq!(
+            parse_with(implicit, &["f"]),
+            parse_with(explicit, &[]),
+            "{implicit}"
+        );
+    }
+}
+
+#[test]
+fn test_products_differentiate() {
+    assert_eq!(diff("(x+1)(x-1)", "x", &[], None).unwrap(), "2*x");
+    assert_eq!(diff("2(x+1)", "x", &[], None).unwrap(), "2");
+    assert_eq!(
+        diff("(x+1)(x+2)(x+3)", "x", &[], None).unwrap(),
+        diff("(x+1)*(x+2)*(x+3)", "x", &[], None).unwrap()
+    );
+}
+
+#[test]
+fn test_function_calls_need_a_registered_name() {
+    let call = parse_with("f(x+1)", &["f"]);
+    assert!(matches!(call.kind, ExprKind::FunctionCall { .. }), "{call}");
+    assert_eq!(parse_with("f(x+1)", &[]), parse_with("f*(x+1)", &[]));
+
+    // Builtins and registered names still call, with or without a space
+    assert_eq!(parse_with("sin (x)", &[]), parse_with("sin(x)", &[]));
+    assert_eq!(parse_with("f (x+1)", &["f"]), call);
+    // A call result multiplies a following parenthesis
+    assert_eq!(parse_with("f(x)(y)", &["f"]), parse_with("f(x)*y", &["f"]));
+}
+
+#[test]
+fn test_spaced_custom_call_warns() {
+    let found = warnings("1 + f (x)", &["f"]);
+    assert_eq!(
+        found,
+        [ParseWarning::SpacedFunctionCall {
+            name: "f".to_owned(),
+            span: Span::new(4, 7),
+        }]
+    );
+    assert!(found[0].to_string().contains("'f*('"), "{}", found[0]);
+
+    let found = warnings("f\t(x) * my_fn  (y)", &["f", "my_fn"]);
+    assert_eq!(found.len(), 2);
+
+    // No warning without the space, for builtins, or for names that are not functions
+    assert!(warnings("f(x) + (x+1)(x-1)", &["f"]).is_empty());
+    assert!(warnings("sin (x)", &["f"]).is_empty());
+    assert!(warnings("g (x) + ff (x)", &["f"]).is_empty());
+}
+
+#[test]
+fn test_spaced_call_warns_for_context_functions() {
+    let ctx = Context::new().with_function(
+        "impl_prod_fn",
+        UserFunction::new(1..=1).body(|args| 2.0 * (*args[0]).clone()),
+    );
+    let found = parse_configured(
+        "impl_prod_fn (x)",
+        &HashSet::new(),
+        &HashSet::new(),
+        Some(&ctx),
+        &WARNINGS,
+    )
+    .unwrap()
+    .warnings;
+    assert!(
+        matches!(&found[..], [ParseWarning::SpacedFunctionCall { name, .. }] if name == "impl_prod_fn")
+    );
+}
@@ -0,0 +1,246 @@
+//! Tests for `Integrate`: round trips through `diff`, linear substitution and failures
+
+use crate::{Diff, DiffError, Expr, Integrate, diff, integrate, parse, simplify, symb};
+use std::collections::{HashMap, HashSet};
+
+fn antiderivative(formula: &str) -> String {
+    integrate(formula, "x", &[]).unwrap_or_else(|e| panic!("∫ {formula}: {e}"))
+}
+
+fn eval_at(formula: &str, x: f64) -> f64 {
+    let expr = parse(formula, &HashSet::new(), &HashSet::new(), None).unwrap();
+    let vars: HashMap<&str, f64> = [("x", x)].into_iter().collect();
+    expr.evaluate(&vars, &HashMap::new())
+        .as_number()
+        .unwrap_or_else(|| panic!("{formula} is not numeric at x = {x}"))
+}
+
+/// `d/dx ∫ f` must simplify to exactly the same expression as `f`
+fn assert_round_trip(formula: &str) {
+    let primitive = antiderivative(formula);
+    let derivative = diff(&primitive, "x", &[], None).unwrap();
+    assert_eq!(
+        derivative,
+        simplify(formula, &[], None).unwrap(),
+        "d/dx ∫ {formula} = d/dx {primitive}"
+    );
+}
+
+/// `d/dx ∫ f` must agree with `f` numerically where the simplifier leaves two forms
+fn assert_round_trip_numeric(formula: &str) {
+    let primitive = antiderivative(formula);
+    let derivative = diff(&primitive, "x", &[], None).unwrap();
+    for x in [0.3, 0.9, 1.7, 2.4] {
+        let (got, expected) = (eval_at(&derivative, x), eval_at(formula, x));
+        assert!(
+            (got - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+            "d/dx ∫ {formula} = {derivative}: {got} != {expected} at x = {x}"
+        );
+    }
+}
+
+#[test]
+fn test_polynomials_round_trip() {
+    for formula in [
+        "5",
+        "x",
+        "3*x^2",
+        "x^3 + 2*x^2 - x + 5",
+        "a*x^2 + b*x + c",
+        "x^n",
+        "x^-2",
+        "sqrt(x)",
+    ] {
+        assert_round_trip(formula);
+    }
+}
+
+#[test]
+fn test_power_rule_results() {
+    assert_eq!(antiderivative("3*x^2"), "x^3");
+    assert_eq!(antiderivative("1/x"), "ln(abs(x))");
+    assert_eq!(antiderivative("1/x^2"), "-1/x");
+    assert_eq!(antiderivative("5"), "5*x");
+}
+
+#[test]
+fn test_trig_and_exponential_round_trip() {
+    for formula in [
+        "sin(x)",
+        "cos(x)",
+        "tan(x)",
+        "3*sin(x) - 2*cos(x)",
+        "exp(x)",
+        "sinh(x)",
+        "cosh(x)",
+        "2^x",
+        "ln(x)",
+        "sin(x)*cos(x)",
+    ] {
+        assert_round_trip(formula);
+    }
+}
+
+#[test]
+fn test_linear_substitution() {
+    assert_eq!(antiderivative("cos(3*x + 1)"), "sin(1 + 3*x)/3");
+    assert_eq!(antiderivative("exp(2*x)"), "exp(2*x)/2");
+    assert_eq!(antiderivative("1/(2*x + 1)"), "ln(abs(1 + 2*x))/2");
+    assert_eq!(antiderivative("(2*x + 1)^3"), "(1 + 2*x)^4/8");
+    for formula in ["sin(a*x + b)", "exp(-x/2)", "(1 - 3*x)^-2", "ln(2*x + 5)"] {
+        assert_round_trip(formula);
+    }
+}
+
+#[test]
+fn test_integration_by_parts() {
+    for formula in ["x*sin(x)", "x^2*cos(x)", "x*exp(x)", "x*ln(x)"] {
+        assert_round_trip(formula);
+    }
+    for formula in [
+        "x^2*exp(-x)",
+        "x^3*sin(2*x)",
+        "(x + 1)*(x - 2)",
+        "x/(x + 1)",
+    ] {
+        assert_round_trip_numeric(formula);
+    }
+}
+
+#[test]
+fn test_builder_with_symbol() {
+    let x = symb("x");
+    let integrand = Expr::from(x) * x.exp();
+    let primitive = Integrate::new().integrate(&integrand, &x).unwrap();
+    assert_eq!(primitive.to_string(), "exp(x)*(-1 + x)");
+}
+
+#[test]
+fn test_other_variables_are_constants() {
+    assert_eq!(
+        Integrate::new().integrate_str("y*x", "y", &[]).unwrap(),
+        simplify("x*y^2/2", &[], None).unwrap()
+    );
+    assert_eq!(
+        Integrate::new()
+            .fixed_var(&"k")
+            .integrate_str("k*cos(x)", "x", &[])
+            .unwrap(),
+        "k*sin(x)"
+    );
+}
+
+#[test]
+fn test_unsupported_term_is_named() {
+    let err = integrate("x + sin(x)^2", "x", &[]).unwrap_err();
+    assert!(matches!(
+        err,
+        DiffError::CannotIntegrate { ref term, ref var } if term == "sin(x)^2" && var == "x"
+    ));
+    assert!(matches!(
+        integrate("exp(x^2)", "x", &[]),
+        Err(DiffError::CannotIntegrate { ref term, .. }) if term == "exp(x^2)"
+    ));
+}
+
+#[test]
+fn test_fixed_integration_variable_is_rejected() {
+    let result = Integrate::new()
+        .fixed_var(&"x")
+        .integrate_str("x", "x", &[]);
+    assert!(matches!(
+        result,
+        Err(DiffError::VariableInBothFixedAndDiff { .. })
+    ));
+}
+
+#[test]
+fn test_expr_and_diff_entry_points() {
+    let x = symb("x");
+    let diff_builder = Diff::new();
+    let integrand = x.pow(2.0) * 3.0 + x.cos();
+    let primitive = integrand.integrate(&x).unwrap();
+    assert_eq!(primitive, diff_builder.integrate(&integrand, &x).unwrap());
+    assert_eq!(
+        diff_builder.differentiate(&primitive, &x).unwrap(),
+        integrand.simplified().unwrap()
+    );
+
+    let fixed = Diff::new().fixed_var(&"k");
+    assert_eq!(fixed.integrate_str("k*cos(x)", "x").unwrap(), "k*sin(x)");
+    assert!(matches!(
+        fixed.integrate_str("x", "k"),
+        Err(DiffError::VariableInBothFixedAndDiff { .. })
+    ));
+}
+
+/// `Expr::integrate` then `Diff::differentiate` over the elementary rule set
+#[test]
+fn test_expr_integrate_then_differentiate() {
+    let x = symb("x");
+    let diff_builder = Diff::new();
+    for formula in [
+        "7",
+        "x^4 - 3*x + 1",
+        "x^-3",
+        "sqrt(x)",
+        "1/x",
+        "1/(2*x + 1)",
+        "exp(x)",
+        "exp(3*x - 1)",
+        "2^x",
+        "sin(x)",
+        "cos(4*x)",
+        "sin(2*x + 1) + cos(x)",
+        "(2*x + 1)^3",
+        "exp(-x/2)",
+        "x^2*cos(x)",
+        "sinh(x) + cosh(x)",
+        "x*exp(x)",
+        "x*sin(x)",
+        "x*cos(2*x)",
+        "x^2*exp(x)",
+        "ln(x)",
+        "x*ln(x)",
+        "sin(x)*cos(x)",
+        "tan(x)",
+    ] {
+        let integrand = parse(formula, &HashSet::new(), &HashSet::new(), None).unwrap();
+        let primitive = integrand
+            .integrate(&x)
+            .unwrap_or_else(|e| panic!("∫ {formula}: {e}"));
+        let derivative = diff_builder.differentiate(&primitive, &x).unwrap();
+        for point in [0.2, 0.45, 0.8] {
+            let vars: HashMap<&str, f64> = [("x", point)].into_iter().collect();
+            let got = derivative.evaluate(&vars, &HashMap::new()).as_number();
+            let want = integrand.evaluate(&vars, &HashMap::new()).as_number();
+            let (Some(got), Some(want)) = (got, want) else {
+                panic!("d/dx ∫ {formula} = {derivative} is not numeric at x = {point}");
+            };
+            assert!(
+                (got - want).abs() <= 1e-12 * want.abs().max(1.0),
+                "d/dx ∫ {formula} = {derivative}: {got} != {want} at x = {point}"
+            );
+        }
+    }
+}
+
+/// Logarithmic antiderivatives hold on the negative side of their poles too
+#[test]
+fn test_log_antiderivatives_at_negative_points() {
+    assert_eq!(antiderivative("1/x"), "ln(abs(x))");
+    assert_eq!(antiderivative("tan(x)"), "-ln(abs(cos(x)))");
+    for formula in ["1/x", "tan(x)", "1/(2*x + 1)", "x/(x + 1)"] {
+        let primitive = antiderivative(formula);
+        // Where the logarithm's argument is negative, for each formula
+        for x in [-2.5, -1.7] {
+            let h = 1e-6;
+            let slope = (eval_at(&primitive, x + h) - eval_at(&primitive, x - h)) / (2.0 * h);
+            let expected = eval_at(formula, x);
+            assert!(
+                (slope - expected).abs() <= 1e-6 * expected.abs().max(1.0),
+                "d/dx {primitive} at x = {x}: {slope} != {expected}"
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Tests for collapsing compositions of inverse functions, built-in and user-declared
+
+use crate::{Context, Diff, Expr, InverseCaveat, Simplify, UserFunction, symb};
+use std::collections::HashMap;
+
+/// `outer(inner(x))` simplified, in the given mode
+fn collapse(outer: &str, inner: &str, domain_safe: bool) -> Expr {
+    let x = symb("x");
+    Simplify::new()
+        .domain_safe(domain_safe)
+        .simplify(&Expr::func(outer, Expr::func(inner, x)))
+        .unwrap()
+}
+
+const CAVEAT_FREE: &[(&str, &str)] = &[
+    ("tan", "atan"),
+    ("cot", "acot"),
+    ("sinh", "asinh"),
+    ("asinh", "sinh"),
+    ("atanh", "tanh"),
+    ("ln", "exp"),
+];
+
+const CAVEATED: &[(&str, &str)] = &[
+    ("sin", "asin"),
+    ("cos", "acos"),
+    ("sec", "asec"),
+    ("csc", "acsc"),
+    ("cosh", "acosh"),
+    ("tanh", "atanh"),
+    ("coth", "acoth"),
+    ("sech", "asech"),
+    ("csch", "acsch"),
+    ("exp", "ln"),
+    ("asin", "sin"),
+    ("acos", "cos"),
+    ("atan", "tan"),
+    ("acoth", "coth"),
+    ("acsch", "csch"),
+];
+
+#[test]
+fn test_builtin_pairs_collapse() {
+    let x = Expr::from(symb("x"));
+    for (outer, inner) in CAVEAT_FREE.iter().chain(CAVEATED) {
+        assert_eq!(collapse(outer, inner, false), x, "{outer}({inner}(x))");
+    }
+}
+
+#[test]
+fn test_caveat_free_pairs_collapse_in_domain_safe_mode() {
+    let x = Expr::from(symb("x"));
+    for (outer, inner) in CAVEAT_FREE {
+        assert_eq!(collapse(outer, inner, true), x, "{outer}({inner}(x))");
+    }
+}
+
+#[test]
+fn test_caveated_pairs_kept_in_domain_safe_mode() {
+    let x = Expr::from(symb("x"));
+    for (outer, inner) in CAVEATED {
+        assert_ne!(collapse(outer, inner, true), x, "{outer}({inner}(x))");
+    }
+}
+
+#[test]
+fn test_even_pairs_collapse_to_abs() {
+    let abs_x = Expr::func("abs", symb("x"));
+    for domain_safe in [false, true] {
+        for (outer, inner) in [("acosh", "cosh"), ("asech", "sech")] {
+            assert_eq!(
+                collapse(outer, inner, domain_safe),
+                abs_x,
+                "{outer}({inner}(x))"
+            );
+        }
+    }
+    // With x known to be non-negative the absolute value drops out
+    let x = symb("x");
+    let expr = Expr::func("acosh", Expr::func("cosh", x));
+    let result = Simplify::new()
+        .assume_non_negative(&[&x])
+        .simplify(&expr)
+        .unwrap();
+    assert_eq!(result, Expr::from(x));
+}
+
+#[test]
+fn test_pairs_invalid_for_negative_arguments() {
+    let x = symb("x");
+    let vars = HashMap::from([("x", -1.5)]);
+    for (outer, inner) in [
+        ("acosh", "cosh"),
+        ("asech", "sech"),
+        ("acot", "cot"),
+        ("asec", "sec"),
+        ("acsc", "csc"),
+    ] {
+        let expr = Expr::func(outer, Expr::func(inner, x));
+        let want = expr.evaluate(&vars, &HashMap::new()).as_number().unwrap();
+        for domain_safe in [false, true] {
+            let got = collapse(outer, inner, domain_safe)
+                .evaluate(&vars, &HashMap::new())
+                .as_number()
+                .unwrap();
+            assert!(
+                (got - want).abs() < 1e-12,
+                "{outer}({inner}(-1.5)) = {want}, simplified to {got}"
+            );
+        }
+    }
+    // Outside the principal branch acot(cot(x)) is not x
+    assert_eq!(collapse("acot", "cot", false).to_string(), "acot(cot(x))");
+}
+
+#[test]
+fn test_nested_chain_collapses() {
+    let x = symb("x");
+    let chain = Expr::func(
+        "sinh",
+        Expr::func("asinh", Expr::func("ln", Expr::func("exp", x))),
+    );
+    let result = Simplify::new().domain_safe(true).simplify(&chain).unwrap();
+    assert_eq!(result, Expr::from(x));
+}
+
+#[test]
+fn test_non_inverse_pairs_untouched() {
+    let result = collapse("sin", "acos", false);
+    assert_eq!(result.to_string(), "sin(acos(x))");
+}
+
+#[test]
+fn test_custom_pair_both_directions() {
+    let x = Expr::from(symb("x"));
+    let simplify = Simplify::new().user_fn(
+        "f",
+        UserFunction::new(1..=1).inverse("finv", InverseCaveat::None),
+    );
+
+    let forward = Expr::func("f", Expr::func("finv", x.clone()));
+    let backward = Expr::func("finv", Expr::func("f", x.clone()));
+    assert_eq!(simplify.simplify(&forward).unwrap(), x);
+    assert_eq!(simplify.simplify(&backward).unwrap(), x);
+}
+
+#[test]
+fn test_custom_caveated_pair_respects_domain_safe() {
+    let x = Expr::from(symb("x"));
+    let def = UserFunction::new(1..=1).inverse("g", InverseCaveat::PrincipalRange);
+    let expr = Expr::func("g", Expr::func("f", x.clone()));
+
+    let relaxed = Simplify::new().user_fn("f", def.clone());
+    assert_eq!(relaxed.simplify(&expr).unwrap(), x);
+
+    let safe = Simplify::new().domain_safe(true).user_fn("f", def);
+    assert_eq!(safe.simplify(&expr).unwrap().to_string(), "g(f(x))");
+}
+
+#[test]
+fn test_context_pair() {
+    let ctx = Context::new().with_function(
+        "f",
+        UserFunction::new(1..=1).inverse("finv", InverseCaveat::None),
+    );
+    let x = Expr::from(symb("x"));
+    let expr = Expr::func("finv", Expr::func("f", x.clone()));
+    assert_eq!(Simplify::new().context(&ctx).simplify(&expr).unwrap(), x);
+}
+
+#[test]
+fn test_custom_pair_simplified_after_diff() {
+    let x = symb("x");
+    let y = symb("y");
+    // d/dy [y * g(ginv(x))] = g(ginv(x)) = x
+    let expr = Expr::from(y) * Expr::func("g", Expr::func("ginv", x));
+    let derivative = Diff::new()
+        .user_fn(
+            "g",
+            UserFunction::new(1..=1).inverse("ginv", InverseCaveat::None),
+        )
+        .differentiate(&expr, &y)
+        .unwrap();
+    assert_eq!(derivative, Expr::from(x));
+}
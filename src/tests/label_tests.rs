@@ -0,0 +1,101 @@
+//! Tests for labeled subexpressions: `Expr::labeled`, `Expr::find_labeled` and label display
+
+use crate::{CompiledEvaluator, Diff, Expr, LatexConfig, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn eval_at(expr: &Expr, x: f64) -> f64 {
+    expr.partial_eval(&HashMap::from([("x", x)]))
+        .as_number()
+        .unwrap()
+}
+
+#[test]
+fn test_equality_ignores_labels() {
+    let expr = parse_str("x^2 + 1");
+    let labeled = Expr::labeled("A", expr.clone());
+    assert_eq!(labeled, expr);
+    assert_eq!(labeled.structural_hash(), expr.structural_hash());
+    assert_eq!(labeled.label(), Some("A"));
+    assert_eq!(expr.label(), None);
+    assert_eq!(labeled.to_string(), expr.to_string());
+
+    let relabeled = Expr::labeled("B", labeled);
+    assert_eq!(relabeled.label(), Some("B"));
+}
+
+#[test]
+fn test_derivatives_keep_term_labels() {
+    let x = symb("x");
+    let term_a = parse_str("x^2*sin(x)");
+    let term_b = parse_str("exp(2*x) + x");
+    let expr = Expr::labeled("A", term_a.clone()) + Expr::labeled("B", term_b.clone());
+
+    let diff = Diff::new();
+    let derivative = diff.differentiate(&expr, &x).unwrap();
+    for (label, term) in [("A", &term_a), ("B", &term_b)] {
+        let found = derivative.find_labeled(label);
+        assert_eq!(found.len(), 1, "{label} in {derivative}");
+        let expected = diff.differentiate(term, &x).unwrap();
+        for point in [-1.5, 0.25, 2.0] {
+            let got = eval_at(found[0], point);
+            let want = eval_at(&expected, point);
+            assert!(
+                (got - want).abs() < 1e-12,
+                "{label} at {point}: {got} vs {want}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_simplification_keeps_or_drops_labels() {
+    // The labeled node itself is rewritten, so the result keeps the label
+    let expr = Expr::labeled("A", parse_str("x*x")) + parse_str("sin(y)");
+    let simplified = expr.simplified().unwrap();
+    assert_eq!(simplified.find_labeled("A"), vec![&parse_str("x^2")]);
+
+    // Merged into a like term of its parent, the label goes away
+    let expr = Expr::labeled("A", parse_str("x")) + parse_str("2*x");
+    let simplified = expr.simplified().unwrap();
+    assert_eq!(simplified, parse_str("3*x").simplified().unwrap());
+    assert!(simplified.find_labeled("A").is_empty());
+}
+
+#[test]
+fn test_evaluation_ignores_labels() {
+    let plain = parse_str("x^2*sin(x) + exp(x)");
+    let labeled =
+        Expr::labeled("A", parse_str("x^2*sin(x)")) + Expr::labeled("B", parse_str("exp(x)"));
+
+    let plain_eval = CompiledEvaluator::compile(&plain, &["x"], None).unwrap();
+    let labeled_eval = CompiledEvaluator::compile(&labeled, &["x"], None).unwrap();
+    for point in [-2.0, 0.5, 3.0] {
+        let want = plain_eval.evaluate(&[point]);
+        assert!((labeled_eval.evaluate(&[point]) - want).abs() < 1e-12);
+        assert!((eval_at(&labeled, point) - want).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_latex_shows_labels_on_request() {
+    let expr =
+        Expr::labeled("kinetic", parse_str("x^2")) + Expr::labeled("potential", parse_str("y"));
+    let config = LatexConfig {
+        show_labels: true,
+        ..LatexConfig::default()
+    };
+    let shown = expr.to_latex_with(&config);
+    assert!(
+        shown.contains(r"\underbrace{x^{2}}_{\text{kinetic}}"),
+        "{shown}"
+    );
+    assert!(
+        shown.contains(r"\underbrace{y}_{\text{potential}}"),
+        "{shown}"
+    );
+    assert!(!expr.to_latex().contains("underbrace"));
+}
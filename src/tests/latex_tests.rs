@@ -0,0 +1,92 @@
+//! Tests for LaTeX output: `Expr::to_latex` and the `LatexConfig` options
+
+use crate::{Expr, LatexConfig, parse};
+use std::collections::HashSet;
+
+fn latex(input: &str) -> String {
+    parse_str(input).to_latex()
+}
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+const IMPLICIT: LatexConfig = LatexConfig {
+    implicit_multiplication: true,
+    parenthesize_negative_exponents: false,
+    show_labels: false,
+};
+
+const NEGATIVE_PARENS: LatexConfig = LatexConfig {
+    implicit_multiplication: false,
+    parenthesize_negative_exponents: true,
+    show_labels: false,
+};
+
+#[test]
+fn test_fraction_and_power() {
+    assert_eq!(latex("a/b"), r"\frac{a}{b}");
+    assert_eq!(latex("(x + 1)/(x - 1)"), r"\frac{1 + x}{-1 + x}");
+    assert_eq!(latex("x^2"), "x^{2}");
+    assert_eq!(latex("(x + 1)^3"), r"\left(1 + x\right)^{3}");
+}
+
+#[test]
+fn test_named_functions() {
+    assert_eq!(latex("sqrt(x)"), r"\sqrt{x}");
+    assert_eq!(latex("sin(x)"), r"\sin\left(x\right)");
+    assert_eq!(latex("cos(x)"), r"\cos\left(x\right)");
+    assert_eq!(latex("ln(x)"), r"\ln\left(x\right)");
+}
+
+#[test]
+fn test_greek_symbols() {
+    assert_eq!(latex("theta"), r"\theta");
+    assert_eq!(latex("alpha^2"), r"\alpha^{2}");
+    assert_eq!(latex("theta_1"), r"\theta_{1}");
+}
+
+#[test]
+fn test_default_multiplication() {
+    // Numeric coefficients are juxtaposed, other factors use \cdot
+    assert_eq!(latex("2*x^2"), "2x^{2}");
+    assert_eq!(latex("x*sin(x)"), r"x \cdot \sin\left(x\right)");
+    assert_eq!(latex("-3*x*sin(x)"), r"-3x \cdot \sin\left(x\right)");
+}
+
+#[test]
+fn test_implicit_multiplication() {
+    let expr = parse_str("x*sin(x)");
+    assert_eq!(expr.to_latex_with(&IMPLICIT), r"x\,\sin\left(x\right)");
+    let expr = parse_str("-3*x*sin(x)");
+    assert_eq!(expr.to_latex_with(&IMPLICIT), r"-3x\,\sin\left(x\right)");
+    // Inside a sum, after the sign has been pulled out
+    let expr = parse_str("x - 2*x*sin(x)");
+    assert_eq!(expr.to_latex_with(&IMPLICIT), r"x - 2x\,\sin\left(x\right)");
+}
+
+#[test]
+fn test_implicit_multiplication_keeps_cdot_before_digits() {
+    let expr = parse_str("3*2^x");
+    assert_eq!(expr.to_latex(), r"2^{x} \cdot 3");
+    assert_eq!(expr.to_latex_with(&IMPLICIT), r"2^{x} \cdot 3");
+}
+
+#[test]
+fn test_negative_exponents() {
+    let expr = parse_str("x^-2");
+    assert_eq!(expr.to_latex(), "x^{-2}");
+    assert_eq!(expr.to_latex_with(&NEGATIVE_PARENS), r"x^{\left(-2\right)}");
+    let expr = parse_str("x^(-n)");
+    assert_eq!(expr.to_latex_with(&NEGATIVE_PARENS), r"x^{\left(-n\right)}");
+    // Positive exponents are untouched
+    assert_eq!(parse_str("x^2").to_latex_with(&NEGATIVE_PARENS), "x^{2}");
+}
+
+#[test]
+fn test_default_config_matches_to_latex() {
+    for input in ["x*sin(x)/2", "3*x^-1 + y", "alpha*beta - theta^2"] {
+        let expr = parse_str(input);
+        assert_eq!(expr.to_latex_with(&LatexConfig::default()), expr.to_latex());
+    }
+}
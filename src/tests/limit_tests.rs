@@ -0,0 +1,102 @@
+//! Tests for `Expr::limit`: substitution, L'Hôpital's rule, limits at infinity and
+//! limits that are infinite or undetermined
+
+use crate::{DiffError, Expr, LimitPoint, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn limit(input: &str, point: LimitPoint) -> Result<Expr, DiffError> {
+    parse(input, &HashSet::new(), &HashSet::new(), None)
+        .unwrap()
+        .limit(&symb("x"), point)
+}
+
+fn assert_limit(input: &str, point: LimitPoint, expected: f64) {
+    let found = limit(input, point).unwrap_or_else(|err| panic!("{input}: {err}"));
+    let value = found
+        .evaluate(&HashMap::<&str, f64>::new(), &HashMap::new())
+        .as_number()
+        .unwrap_or_else(|| panic!("{input} -> {found}"));
+    assert!(
+        value == expected || (value - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+        "{input} at {point}: got {value}, expected {expected}"
+    );
+}
+
+#[test]
+fn test_zero_over_zero() {
+    let zero = LimitPoint::Finite(0.0);
+    assert_limit("sin(x)/x", zero, 1.0);
+    assert_limit("(1 - cos(x))/x^2", zero, 0.5);
+    assert_limit("(exp(x) - 1 - x)/x^2", zero, 0.5);
+    assert_limit("(tan(x) - x)/x^3", zero, 1.0 / 3.0);
+    assert_limit("(x^2 - 1)/(x - 1)", LimitPoint::Finite(1.0), 2.0);
+}
+
+#[test]
+fn test_at_infinity() {
+    assert_limit("(x^2 + 1)/(2*x^2 - 3)", LimitPoint::PosInf, 0.5);
+    assert_limit("x^3/exp(x)", LimitPoint::PosInf, 0.0);
+    assert_limit("ln(x)/x", LimitPoint::PosInf, 0.0);
+    assert_limit("(1 + 1/x)^x", LimitPoint::PosInf, std::f64::consts::E);
+    assert_limit("atan(x)", LimitPoint::NegInf, -std::f64::consts::FRAC_PI_2);
+}
+
+#[test]
+fn test_one_sided_products_and_powers() {
+    // Only the right of 0 is in the domain of ln
+    assert_limit("x*ln(x)", LimitPoint::Finite(0.0), 0.0);
+    assert_limit("x^x", LimitPoint::Finite(0.0), 1.0);
+    assert_limit("exp(-1/x^2)", LimitPoint::Finite(0.0), 0.0);
+}
+
+#[test]
+fn test_continuous_and_symbolic() {
+    assert_eq!(
+        limit("x^2*sin(x) + 3", LimitPoint::Finite(2.0))
+            .unwrap()
+            .to_string(),
+        "3 + 4*sin(2)"
+    );
+    assert_eq!(
+        limit("sin(a*x)/x", LimitPoint::Finite(0.0))
+            .unwrap()
+            .to_string(),
+        "a"
+    );
+    assert_eq!(
+        limit("a + 1/x", LimitPoint::PosInf).unwrap().to_string(),
+        "a"
+    );
+}
+
+#[test]
+fn test_infinite_limits() {
+    assert_limit("1/x^2", LimitPoint::Finite(0.0), f64::INFINITY);
+    assert_limit("ln(x)", LimitPoint::Finite(0.0), f64::NEG_INFINITY);
+}
+
+#[test]
+fn test_undetermined() {
+    for (input, point) in [
+        ("1/x", LimitPoint::Finite(0.0)),
+        ("sin(1/x)", LimitPoint::Finite(0.0)),
+        ("signum(x)", LimitPoint::Finite(0.0)),
+        ("sin(x)", LimitPoint::PosInf),
+    ] {
+        let err = limit(input, point).unwrap_err();
+        assert!(
+            matches!(&err, DiffError::LimitUndetermined { .. }),
+            "{input}: {err:?}"
+        );
+    }
+    assert_eq!(
+        limit("1/x", LimitPoint::Finite(0.0))
+            .unwrap_err()
+            .to_string(),
+        "Cannot determine the limit of '1/x' as x -> 0"
+    );
+    assert!(matches!(
+        limit("x", LimitPoint::Finite(f64::NAN)),
+        Err(DiffError::UnsupportedOperation(_))
+    ));
+}
@@ -0,0 +1,233 @@
+//! Tests for `MathML` output (`Expr::to_mathml`, `MathmlConfig`) and `Expr::from_mathml`
+
+use crate::{DiffError, Expr, MathmlConfig, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Body of the `<math>` element
+fn content(input: &str) -> String {
+    strip_root(&parse_str(input).to_mathml())
+}
+
+fn presentation(input: &str) -> String {
+    strip_root(&parse_str(input).to_mathml_with(&PRESENTATION))
+}
+
+fn strip_root(xml: &str) -> String {
+    xml.strip_prefix(r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#)
+        .and_then(|rest| rest.strip_suffix("</math>"))
+        .unwrap()
+        .to_owned()
+}
+
+fn round_trip(expr: &Expr) {
+    let xml = expr.to_mathml();
+    let back = Expr::from_mathml(&xml).unwrap();
+    assert_eq!(&back, expr, "round trip through {xml}");
+    assert_eq!(back.to_mathml(), xml);
+}
+
+const PRESENTATION: MathmlConfig = MathmlConfig { presentation: true };
+
+#[test]
+fn test_content_leaves() {
+    assert_eq!(content("x"), "<ci>x</ci>");
+    assert_eq!(content("2.5"), "<cn>2.5</cn>");
+    assert_eq!(content("pi"), "<pi/>");
+    assert_eq!(content("e"), "<exponentiale/>");
+    assert_eq!(
+        strip_root(&Expr::number(f64::NEG_INFINITY).to_mathml()),
+        "<apply><minus/><infinity/></apply>"
+    );
+}
+
+#[test]
+fn test_content_operators() {
+    assert_eq!(
+        content("a/b"),
+        "<apply><divide/><ci>a</ci><ci>b</ci></apply>"
+    );
+    assert_eq!(
+        content("x^3"),
+        "<apply><power/><ci>x</ci><cn>3</cn></apply>"
+    );
+    assert_eq!(content("sqrt(x)"), "<apply><root/><ci>x</ci></apply>");
+    assert_eq!(content("asin(x)"), "<apply><arcsin/><ci>x</ci></apply>");
+    assert_eq!(
+        content("log(2, x)"),
+        "<apply><log/><logbase><cn>2</cn></logbase><ci>x</ci></apply>"
+    );
+    assert_eq!(
+        content("besselj(0, x)"),
+        r#"<apply><ci type="function">besselj</ci><cn>0</cn><ci>x</ci></apply>"#
+    );
+}
+
+#[test]
+fn test_content_escapes_names() {
+    let expr = Expr::symbol("a<b&c");
+    assert_eq!(strip_root(&expr.to_mathml()), "<ci>a&lt;b&amp;c</ci>");
+    round_trip(&expr);
+}
+
+#[test]
+fn test_presentation_precedence() {
+    assert_eq!(
+        presentation("(x + 1)^2"),
+        "<msup><mrow><mo>(</mo><mrow><mn>1</mn><mo>+</mo><mi>x</mi></mrow><mo>)</mo></mrow><mn>2</mn></msup>"
+    );
+    assert_eq!(
+        presentation("a*(b + c)"),
+        "<mrow><mi>a</mi><mo>&#x22C5;</mo><mrow><mo>(</mo><mrow><mi>b</mi><mo>+</mo><mi>c</mi></mrow><mo>)</mo></mrow></mrow>"
+    );
+    assert_eq!(
+        presentation("x - y"),
+        "<mrow><mi>x</mi><mo>-</mo><mi>y</mi></mrow>"
+    );
+}
+
+#[test]
+fn test_presentation_layout() {
+    assert_eq!(presentation("a/b"), "<mfrac><mi>a</mi><mi>b</mi></mfrac>");
+    assert_eq!(presentation("sqrt(x)"), "<msqrt><mi>x</mi></msqrt>");
+    assert_eq!(
+        presentation("abs(x)"),
+        "<mrow><mo>|</mo><mi>x</mi><mo>|</mo></mrow>"
+    );
+    assert_eq!(
+        presentation("3*x"),
+        "<mrow><mn>3</mn><mo>&#x2062;</mo><mi>x</mi></mrow>"
+    );
+    assert_eq!(presentation("alpha"), "<mi>α</mi>");
+    assert_eq!(presentation("pi"), "<mi>&#x3C0;</mi>");
+}
+
+#[test]
+fn test_round_trip_parsed() {
+    for input in [
+        "x",
+        "x + y*z - 3",
+        "sin(x)^2 + cos(x)^2",
+        "exp(-x^2/2)/sqrt(2*pi)",
+        "log(2, x) + log10(y) + ln(z)",
+        "atan(x/y) + acosh(x) + ceil(y) + floor(z)",
+        "abs(x - 1)*sign(x)",
+        "besselj(2, x) + gamma(x)",
+        "x^(1/3) + cbrt(y)",
+        "1e-30*x + 0.1",
+        "e^x + pi",
+    ] {
+        round_trip(&parse_str(input));
+    }
+}
+
+#[test]
+fn test_round_trip_special_nodes() {
+    let x = symb("mathml_rt_x");
+    round_trip(&Expr::derivative(x.sin(), "mathml_rt_x", 2));
+    round_trip(&Expr::derivative(
+        Expr::derivative(x.to_expr(), "mathml_rt_y", 1),
+        "mathml_rt_x",
+        3,
+    ));
+    round_trip(&Expr::number(f64::INFINITY));
+    round_trip(&Expr::number(f64::NEG_INFINITY));
+    round_trip(&Expr::number(-2.5));
+
+    let nan = Expr::from_mathml(&Expr::number(f64::NAN).to_mathml()).unwrap();
+    assert!(nan.as_number().is_some_and(f64::is_nan));
+}
+
+#[test]
+fn test_round_trip_polynomial() {
+    let poly = parse_str("3*x^4 - 2*x + 7").to_poly();
+    let xml = poly.to_mathml();
+    assert!(xml.contains(r#"<apply class="poly">"#), "{xml}");
+    round_trip(&poly);
+}
+
+#[test]
+fn test_reader_accepts_variants() {
+    let xml = r#"<?xml version="1.0"?>
+        <!-- generated elsewhere -->
+        <m:math xmlns:m="http://www.w3.org/1998/Math/MathML">
+          <m:semantics>
+            <m:apply><m:plus/>
+              <m:apply><m:sin/><m:ci> x </m:ci></m:apply>
+              <m:cn>1</m:cn>
+            </m:apply>
+            <m:annotation encoding="text/plain">sin(x) + 1</m:annotation>
+          </m:semantics>
+        </m:math>"#;
+    // Operands keep the written order
+    assert_eq!(Expr::from_mathml(xml).unwrap().to_string(), "sin(x) + 1");
+    assert_eq!(
+        Expr::from_mathml("<apply><minus/><ci>x</ci><cn>1</cn></apply>")
+            .unwrap()
+            .to_string(),
+        parse_str("x - 1").to_string()
+    );
+    assert_eq!(
+        Expr::from_mathml("<apply><root/><degree><cn>3</cn></degree><ci>x</ci></apply>")
+            .unwrap()
+            .to_string(),
+        "cbrt(x)"
+    );
+}
+
+#[test]
+fn test_reader_errors() {
+    assert!(matches!(
+        Expr::from_mathml(""),
+        Err(DiffError::EmptyFormula)
+    ));
+    assert!(matches!(
+        Expr::from_mathml(&parse_str("x + 1").to_mathml_with(&PRESENTATION)),
+        Err(DiffError::InvalidSyntax { .. })
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<apply><plus/><cn>1</cn><matrix/></apply>"),
+        Err(DiffError::InvalidToken { .. })
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<cn>1.2.3</cn>"),
+        Err(DiffError::InvalidNumber { .. })
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<apply><power/><ci>x</ci></apply>"),
+        Err(DiffError::InvalidFunctionCall {
+            expected: 2,
+            got: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<apply><plus/><ci>x</ci>"),
+        Err(DiffError::UnexpectedEndOfInput)
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<ci>x</ci><ci>y</ci>"),
+        Err(DiffError::InvalidSyntax { .. })
+    ));
+    assert!(matches!(
+        Expr::from_mathml("<ci>&bogus;</ci>"),
+        Err(DiffError::InvalidSyntax { .. })
+    ));
+}
+
+#[test]
+fn test_reader_depth_limit() {
+    let depth = crate::DEFAULT_MAX_DEPTH + 1;
+    let xml = format!(
+        "{}<ci>x</ci>{}",
+        "<apply><sin/>".repeat(depth),
+        "</apply>".repeat(depth)
+    );
+    assert!(matches!(
+        Expr::from_mathml(&xml),
+        Err(DiffError::MaxDepthExceeded)
+    ));
+}
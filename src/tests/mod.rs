@@ -5,12 +5,27 @@
 mod actual_division_bug;
 mod advanced_simplification_tests;
 mod advanced_tests;
+mod alpha_equivalence_tests;
 mod api_contract_tests;
 mod api_parity_checks;
+#[cfg(feature = "argmin")]
+mod argmin_tests;
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+mod array_eval_tests;
+mod assumption_tests;
 mod benchmark_tests;
 mod closure_check;
+mod coefficient_magnitude_tests;
+mod coefficient_tests;
+mod comment_tests;
+mod compiled_bytes_tests;
+mod compiled_gradient_tests;
 mod comprehensive_api_tests;
+mod constants_tests;
+mod corpus_tests;
+mod cse_tests;
 mod custom_functions;
+mod data_sum_tests;
 mod debug_applications;
 mod debug_div_hang;
 mod debug_division_structure;
@@ -21,33 +36,91 @@ mod debug_root;
 mod debug_root_issue;
 mod derivative_oracle_tests;
 mod derivative_regressions;
+mod diff_n_tests;
+mod disassembly_tests;
 mod display_precedence_test;
 mod division_bug_verification;
+mod domain_tests;
 mod edge_case_tests;
+mod enclosure_tests;
+mod error_function_tests;
+#[cfg(feature = "parallel")]
+mod eval_batch_parallel_tests;
 mod eval_consistency_tests;
+#[cfg(feature = "eval-core")]
+mod eval_core_tests;
+mod eval_double_double_tests;
+mod eval_dual_tests;
 mod eval_func_tests;
+mod eval_interval_tests;
+mod eval_table_tests;
 mod evaluator_expansion;
+mod exact_arithmetic_tests;
+mod expand_tests;
+mod format_options_tests;
 mod fraction_simplification_tests;
+mod function_library_tests;
 mod fuzz;
 mod fuzz_evaluator;
 mod fuzz_math_modules;
+mod group_tests;
+mod horner_tests;
 mod hyperbolic_conversion_tests;
+mod hypot_tests;
+mod implicit_diff_tests;
+mod implicit_product_tests;
+mod integrate_tests;
 mod integration_tests;
+mod inverse_composition_tests;
+mod label_tests;
+mod latex_tests;
+mod limit_tests;
 mod log_power_tests;
 mod log_simplification_tests;
+mod mathml_tests;
+mod negative_zero_tests;
+mod nonfinite_tests;
 mod normalization_check;
+mod notation_tests;
 mod numerical_accuracy_tests;
+mod parse_equation_tests;
+mod parse_program_tests;
+mod partial_eval_tests;
+mod piecewise_expr_tests;
+mod piecewise_tests;
+mod poly_conversion_tests;
+mod poly_division_tests;
+mod postfix_tests;
 mod power_debug;
 mod power_root_tests;
 mod power_simplification_tests;
 mod precision_audit;
 mod property_tests;
+mod public_api_tests;
+mod rational_simplification_tests;
+mod rationalize_tests;
 mod rc_circuit_bug;
+mod rename_tests;
 mod repro_issues;
 mod repro_simplification_v2;
+mod rule_budget_tests;
+mod rule_selection_tests;
+mod rule_usage_tests;
 mod rust_api_tests;
+mod rust_codegen_tests;
+#[cfg(feature = "serde")]
+mod serde_tests;
+mod series_tests;
+mod shader_codegen_tests;
 mod simplification_tests;
+mod singularity_fallback_tests;
+mod solve_tests;
+mod source_codegen_tests;
+mod source_span_tests;
+mod step_function_tests;
 mod stress_tests;
+mod substitute_tests;
+mod symbol_scope_tests;
 mod test_abs_function;
 mod test_algebraic_extensions;
 mod test_bessel;
@@ -69,3 +142,6 @@ mod tier1_tests;
 mod tier2_tests;
 mod trace_trig;
 mod trig_simplification_tests;
+mod user_rule_tests;
+mod view_tests;
+mod where_clause_tests;
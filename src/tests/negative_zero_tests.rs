@@ -0,0 +1,62 @@
+//! Tests for the `-0.0` policy: expressions hold an unsigned zero, compiled code keeps
+//! IEEE semantics for its inputs
+
+use crate::{CompiledEvaluator, Expr, Simplify, core::ExprKind, parse, simplify};
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_folded_negative_zero_is_zero() {
+    assert_eq!(simplify("x*(-0.0)", &[], None).unwrap(), "0");
+    assert_eq!(simplify("-0.0*x + y", &[], None).unwrap(), "y");
+    assert_eq!(simplify("x + (-0.0)", &[], None).unwrap(), "x");
+
+    let folded = Simplify::new()
+        .simplify(&parse_plain("-0.5 * 0 - 0.0"))
+        .unwrap();
+    assert_eq!(folded.to_string(), "0");
+    assert!(folded.as_number().is_some_and(f64::is_sign_positive));
+}
+
+#[test]
+fn test_negative_zero_number_is_stored_unsigned() {
+    for expr in [Expr::number(-0.0), Expr::new(ExprKind::Number(-0.0))] {
+        assert_eq!(expr.to_string(), "0");
+        assert_eq!(expr.as_number().map(f64::to_bits), Some(0.0_f64.to_bits()));
+    }
+    assert_eq!(Expr::number(-0.0) * Expr::number(-1.0), Expr::number(0.0));
+}
+
+#[test]
+fn test_signed_zeros_hash_equal() {
+    let (neg, pos) = (Expr::number(-0.0), Expr::number(0.0));
+    assert_eq!(neg, pos);
+    assert_eq!(neg.structural_hash(), pos.structural_hash());
+    assert_eq!(hash_of(&neg), hash_of(&pos));
+    assert_eq!(
+        hash_of(&ExprKind::Number(-0.0)),
+        hash_of(&ExprKind::Number(0.0))
+    );
+}
+
+#[test]
+fn test_compiled_code_keeps_ieee_signed_zero() {
+    let x = ["x"];
+    let signum = CompiledEvaluator::compile(&parse_plain("signum(x)"), &x, None).unwrap();
+    assert_eq!(signum.evaluate(&[0.0]).to_bits(), 1.0_f64.to_bits());
+    assert_eq!(signum.evaluate(&[-0.0]).to_bits(), (-1.0_f64).to_bits());
+
+    let recip = CompiledEvaluator::compile(&parse_plain("1/x"), &x, None).unwrap();
+    assert_eq!(recip.evaluate(&[-0.0]), f64::NEG_INFINITY);
+    assert_eq!(recip.evaluate(&[0.0]), f64::INFINITY);
+}
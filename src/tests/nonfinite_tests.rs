@@ -0,0 +1,122 @@
+//! Tests for `inf`/`nan` literals: parsing, display and propagation through simplification
+
+use crate::{CompiledEvaluator, DiffError, Expr, Simplify, parse};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn simplified(input: &str) -> Expr {
+    Simplify::new().simplify(&parse_plain(input)).unwrap()
+}
+
+fn simplified_strict(input: &str) -> Expr {
+    Simplify::new()
+        .strict_ieee(true)
+        .simplify(&parse_plain(input))
+        .unwrap()
+}
+
+#[test]
+fn test_literals_parse_as_numbers() {
+    assert_eq!(parse_plain("inf").as_number(), Some(f64::INFINITY));
+    assert_eq!(parse_plain("-inf").as_number(), Some(f64::NEG_INFINITY));
+    assert!(parse_plain("nan").is_nan_num());
+}
+
+#[test]
+fn test_declared_variables_shadow_literals() {
+    let symbols: HashSet<String> = ["inf".to_owned(), "nan".to_owned()].into();
+    let expr = parse("inf + nan", &symbols, &HashSet::new(), None).unwrap();
+    assert_eq!(expr.variables(), symbols);
+}
+
+#[test]
+fn test_display_round_trip() {
+    for input in [
+        "inf",
+        "-inf",
+        "nan",
+        "inf*x",
+        "x + inf",
+        "-inf + inf",
+        "sin(nan)",
+    ] {
+        let expr = parse_plain(input);
+        let printed = expr.to_string();
+        assert_eq!(parse_plain(&printed), expr, "{input} printed as {printed}");
+    }
+    assert_eq!(Expr::number(f64::INFINITY).to_string(), "inf");
+    assert_eq!(Expr::number(f64::NEG_INFINITY).to_string(), "-inf");
+    assert_eq!(Expr::number(f64::NAN).to_string(), "nan");
+}
+
+#[test]
+fn test_infinity_absorbs_finite_numbers() {
+    assert_eq!(simplified("inf + 1").as_number(), Some(f64::INFINITY));
+    assert_eq!(simplified("2 - inf").as_number(), Some(f64::NEG_INFINITY));
+    assert_eq!(simplified("2*inf").as_number(), Some(f64::INFINITY));
+    // Symbolic terms may themselves be infinite, so they are kept
+    assert_eq!(simplified("x + inf").to_string(), "inf + x");
+}
+
+#[test]
+fn test_inf_minus_inf_stays_symbolic_by_default() {
+    assert_eq!(simplified("inf - inf").to_string(), "-inf + inf");
+    assert_eq!(simplified("x + inf - inf").to_string(), "-inf + inf + x");
+}
+
+#[test]
+fn test_inf_minus_inf_is_nan_when_strict() {
+    assert!(simplified_strict("inf - inf").is_nan_num());
+    assert!(simplified_strict("x + inf - inf").is_nan_num());
+}
+
+#[test]
+fn test_nan_propagates() {
+    for input in [
+        "x*nan", "nan*x*y", "x + nan", "x/nan", "nan/x", "0*nan", "inf*0",
+    ] {
+        assert!(
+            simplified(input).is_nan_num(),
+            "{input} should simplify to nan"
+        );
+    }
+}
+
+#[test]
+fn test_nan_keeps_equality_reflexive() {
+    let nan = Expr::number(f64::NAN);
+    assert_eq!(nan, nan.clone());
+    // Differently signed NaNs are still the same literal
+    assert_eq!(Expr::number(-f64::NAN), nan);
+}
+
+#[test]
+fn test_compile_rejects_nan_literal() {
+    let expr = Expr::product(vec![Expr::number(f64::NAN), Expr::symbol("x")]);
+    let err = CompiledEvaluator::compile(&expr, &["x"], None).unwrap_err();
+    assert!(matches!(err, DiffError::UnsupportedExpression(_)));
+    assert_eq!(
+        err.to_string(),
+        "Unsupported expression: NaN literal (use EvaluatorBuilder::allow_nan to compile it)"
+    );
+}
+
+#[test]
+fn test_compile_allows_nan_with_flag() {
+    let expr = Expr::product(vec![Expr::number(f64::NAN), Expr::symbol("x")]);
+    let compiled = CompiledEvaluator::builder(&expr)
+        .params(["x"])
+        .allow_nan(true)
+        .build()
+        .unwrap();
+    assert!(compiled.evaluate(&[1.0]).is_nan());
+}
+
+#[test]
+fn test_compile_accepts_infinity() {
+    let compiled = CompiledEvaluator::compile(&parse_plain("x + inf"), &["x"], None).unwrap();
+    assert_eq!(compiled.evaluate(&[1.0]), f64::INFINITY);
+}
@@ -0,0 +1,153 @@
+//! Tests for degree-based trigonometry and the optional input conventions of
+//! `ParserOptions`: decimal commas and Unicode operators
+
+use crate::{
+    CompiledEvaluator, Diff, DiffError, Expr, ParserOptions, Simplify, Span, parse,
+    parse_configured,
+};
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+
+const LAB: ParserOptions = ParserOptions {
+    decimal_comma: true,
+    unicode_operators: true,
+    exact: false,
+    scope: None,
+    warnings: false,
+    source_map: false,
+};
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn parse_lab(input: &str) -> Result<Expr, DiffError> {
+    parse_configured(input, &HashSet::new(), &HashSet::new(), None, &LAB).map(|parsed| parsed.expr)
+}
+
+fn compiled_at(expr: &Expr, x: f64) -> f64 {
+    CompiledEvaluator::compile(expr, &["x"], None)
+        .unwrap()
+        .evaluate(&[x])
+}
+
+fn tree_at(expr: &Expr, x: f64) -> f64 {
+    let vars = HashMap::from([("x", x)]);
+    expr.evaluate(&vars, &HashMap::new()).as_number().unwrap()
+}
+
+#[test]
+fn test_decimal_comma_and_unicode_operators() {
+    assert_eq!(parse_lab("3,5·x − 2").unwrap(), parse_plain("3.5*x - 2"));
+    assert_eq!(parse_lab("a × b ÷ c").unwrap(), parse_plain("a*b/c"));
+    assert_eq!(parse_lab("2⋅x").unwrap(), parse_plain("2*x"));
+    assert_eq!(parse_lab("1,23E−5").unwrap(), parse_plain("1.23e-5"));
+    assert_eq!(parse_lab("x^−2").unwrap(), parse_plain("x^-2"));
+    assert_eq!(parse_lab("1e1^x").unwrap(), parse_plain("10^x"));
+}
+
+#[test]
+fn test_decimal_comma_leaves_argument_separators() {
+    assert_eq!(
+        parse_lab("max(1, 2,5)").unwrap(),
+        parse_plain("max(1, 2.5)")
+    );
+    // Digits of an identifier are not a number
+    assert_eq!(parse_lab("max(x1,2)").unwrap(), parse_plain("max(x1, 2)"));
+}
+
+#[test]
+fn test_conventions_are_off_by_default() {
+    assert!(matches!(
+        parse("3·x", &HashSet::new(), &HashSet::new(), None),
+        Err(DiffError::InvalidToken { .. })
+    ));
+    let only_operators = ParserOptions {
+        unicode_operators: true,
+        ..ParserOptions::default()
+    };
+    let parsed = parse_configured(
+        "max(1,5)",
+        &HashSet::new(),
+        &HashSet::new(),
+        None,
+        &only_operators,
+    );
+    assert_eq!(parsed.unwrap().expr, parse_plain("max(1, 5)"));
+}
+
+#[test]
+fn test_spans_refer_to_original_input() {
+    let source = "2 × x − $";
+    match parse_lab(source) {
+        Err(DiffError::InvalidToken { token, span }) => {
+            assert_eq!(token, "$");
+            let start = source.find('$').unwrap();
+            assert_eq!(span, Some(Span::new(start, start + 1)));
+        }
+        other => panic!("expected InvalidToken, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_builders_accept_parser_options() {
+    let derivative = Diff::new()
+        .parser_options(LAB)
+        .diff_str("2,5·x^2 − x", "x", &[])
+        .unwrap();
+    assert_eq!(derivative, "-1 + 5*x");
+    let simplified = Simplify::new()
+        .parser_options(LAB)
+        .simplify_str("x·0,5 + x÷2", &[])
+        .unwrap();
+    assert_eq!(simplified, "x");
+}
+
+#[test]
+fn test_degree_trig_evaluates_in_degrees() {
+    for (input, x, expected) in [
+        ("sind(x)", 90.0, 1.0),
+        ("cosd(x)", 60.0, 0.5),
+        ("tand(x)", 45.0, 1.0),
+        ("asind(x)", 1.0, 90.0),
+        ("acosd(x)", 0.5, 60.0),
+        ("atand(x)", 1.0, 45.0),
+    ] {
+        let expr = parse_plain(input);
+        assert!((compiled_at(&expr, x) - expected).abs() < 1e-12, "{input}");
+        assert!((tree_at(&expr, x) - expected).abs() < 1e-12, "{input}");
+    }
+}
+
+#[test]
+fn test_degree_trig_derivatives() {
+    let x = crate::symb("x");
+    let derivative = |input: &str| Diff::new().differentiate(&parse_plain(input), &x).unwrap();
+
+    // d/dx sind(x) = (pi/180)*cosd(x): zero at 90 degrees, pi/360 at 60
+    let d_sin = derivative("sind(x)");
+    assert!(compiled_at(&d_sin, 90.0).abs() < 1e-15);
+    assert!((compiled_at(&d_sin, 60.0) - PI / 360.0).abs() < 1e-15);
+    assert!((tree_at(&d_sin, 60.0) - PI / 360.0).abs() < 1e-15);
+
+    let d_cos = derivative("cosd(x)");
+    assert!((compiled_at(&d_cos, 90.0) + PI / 180.0).abs() < 1e-15);
+
+    let d_tan = derivative("tand(2*x)");
+    assert!((compiled_at(&d_tan, 0.0) - PI / 90.0).abs() < 1e-15);
+
+    let d_atan = derivative("atand(x)");
+    assert!((compiled_at(&d_atan, 0.0) - 180.0 / PI).abs() < 1e-12);
+    let d_asin = derivative("asind(x)");
+    assert!((compiled_at(&d_asin, 0.0) - 180.0 / PI).abs() < 1e-12);
+    let d_acos = derivative("acosd(x)");
+    assert!((compiled_at(&d_acos, 0.0) + 180.0 / PI).abs() < 1e-12);
+}
+
+#[test]
+fn test_degree_trig_in_shader_code() {
+    let expr = parse_plain("sind(x) + atand(x)");
+    let wgsl = expr.to_wgsl("f", &["x"], None).unwrap();
+    assert!(wgsl.contains("sin(") && wgsl.contains("atan("), "{wgsl}");
+    assert!(!wgsl.contains("sind"), "{wgsl}");
+}
@@ -0,0 +1,112 @@
+//! Tests for `parse_equation` and the `Equation` helpers, implicit differentiation included
+
+use crate::{CompiledEvaluator, DiffError, Expr, Span, parse, parse_equation, symb};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn equation(input: &str) -> crate::Equation {
+    parse_equation(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn equation_err(input: &str) -> DiffError {
+    parse_equation(input, &HashSet::new(), &HashSet::new(), None).unwrap_err()
+}
+
+#[test]
+fn test_equation_sides() {
+    let eq = equation("y = x^2 + 1");
+    assert_eq!(eq.lhs, parse_plain("y"));
+    assert_eq!(eq.rhs, parse_plain("x^2 + 1"));
+    assert_eq!(eq.as_expr_zero(), parse_plain("y - (x^2 + 1)"));
+}
+
+#[test]
+fn test_circle_implicit_diff() {
+    let circle = equation("x^2 + y^2 = 1");
+    let slope = circle.implicit_diff(&symb("y"), &symb("x")).unwrap();
+    assert_eq!(slope.to_string(), "-x/y");
+}
+
+#[test]
+fn test_implicit_diff_matches_explicit_branch() {
+    // x*y + y^3 = x: dy/dx = (1 - y)/(x + 3y^2)
+    let slope = equation("x*y + y^3 = x")
+        .implicit_diff(&symb("y"), &symb("x"))
+        .unwrap();
+    let (x, y) = (0.4_f64, 0.7_f64);
+    let got = CompiledEvaluator::compile(&slope, &["x", "y"], None)
+        .unwrap()
+        .evaluate(&[x, y]);
+    let want = (1.0 - y) / 3.0_f64.mul_add(y * y, x);
+    assert!((got - want).abs() < 1e-12, "{slope}");
+}
+
+#[test]
+fn test_implicit_diff_needs_the_dependent_variable() {
+    let err = equation("x^2 = 4")
+        .implicit_diff(&symb("y"), &symb("x"))
+        .unwrap_err();
+    assert!(matches!(err, DiffError::UnsupportedExpression(_)));
+}
+
+#[test]
+fn test_differentiate_both_sides() {
+    let derived = equation("y = sin(x)*x")
+        .differentiate_both(&symb("x"))
+        .unwrap();
+    assert_eq!(derived.lhs, Expr::number(0.0));
+    assert_eq!(
+        derived.rhs,
+        crate::Diff::new()
+            .differentiate(&parse_plain("sin(x)*x"), &symb("x"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_equation_display() {
+    assert_eq!(equation("x^2 + y^2 = 1").to_string(), "x^2 + y^2 = 1");
+}
+
+#[test]
+fn test_equation_errors() {
+    assert!(matches!(
+        equation_err("x^2 + 1"),
+        DiffError::InvalidSyntax { span: None, .. }
+    ));
+    let extra = equation_err("x = y = z");
+    assert!(matches!(
+        extra,
+        DiffError::InvalidSyntax { span: Some(span), .. } if span == Span::at(6)
+    ));
+    for comparison in ["x <= 1", "x >= 1", "x == 1", "x != 1"] {
+        let err = equation_err(comparison);
+        assert!(
+            matches!(&err, DiffError::InvalidSyntax { msg, span: Some(span) }
+                if msg.contains("comparison") && *span == Span::new(2, 4)),
+            "{comparison}: {err:?}"
+        );
+    }
+    assert!(matches!(
+        equation_err(" = x"),
+        DiffError::InvalidSyntax { span: Some(_), .. }
+    ));
+}
+
+#[test]
+fn test_equation_errors_point_into_input() {
+    // The malformed number sits at bytes 8..13 of the whole input, not of the right-hand side
+    let err = equation_err("y = x + 3.4.5");
+    assert!(matches!(
+        err,
+        DiffError::InvalidNumber { span: Some(span), .. } if span == Span::new(8, 13)
+    ));
+    let err = equation_err("3.4.5 = y");
+    assert!(matches!(
+        err,
+        DiffError::InvalidNumber { span: Some(span), .. } if span == Span::new(0, 5)
+    ));
+}
@@ -0,0 +1,97 @@
+//! Tests for `parse_program`: assignment blocks defining intermediate variables
+
+use crate::{CompiledEvaluator, Context, Diff, DiffError, Span, parse, parse_program, symb};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> crate::Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+#[test]
+fn test_program_substitutes_intermediates() {
+    let program = parse_program("u = x^2 + 1; v = sin(u); v / u", None).unwrap();
+
+    assert_eq!(program.expr, parse_plain("sin(x^2 + 1) / (x^2 + 1)"));
+    let names: Vec<&str> = program
+        .intermediates
+        .iter()
+        .map(|i| i.name.as_str())
+        .collect();
+    assert_eq!(names, ["u", "v"]);
+    // Definitions are expanded in terms of the real variables
+    assert_eq!(
+        program.intermediates[1].definition,
+        parse_plain("sin(x^2 + 1)")
+    );
+    assert_eq!(program.intermediates[1].span, Span::new(13, 14));
+}
+
+#[test]
+fn test_program_differentiates_like_expanded_form() {
+    let program = parse_program("u = x^2 + 1; v = sin(u); v / u", None).unwrap();
+    let expanded = parse_plain("sin(x^2 + 1) / (x^2 + 1)");
+    let x = symb("x");
+
+    let from_program = Diff::new().differentiate(&program.expr, &x).unwrap();
+    let from_expanded = Diff::new().differentiate(&expanded, &x).unwrap();
+    assert_eq!(from_program, from_expanded);
+}
+
+#[test]
+fn test_program_intermediates_compile_to_shared_slots() {
+    let program = parse_program("u = x^2 + 1; v = sin(u); v / u", None).unwrap();
+    let compiled = CompiledEvaluator::compile(&program.expr, &["x"], None).unwrap();
+
+    // x^2 + 1 is computed once: square, add, then the fused sin(u)/u
+    assert_eq!(compiled.instruction_count(), 3);
+    let x = 0.7_f64;
+    let u = x.mul_add(x, 1.0);
+    assert!((compiled.evaluate(&[x]) - u.sin() / u).abs() < 1e-15);
+}
+
+#[test]
+fn test_program_polynomial_in_intermediate() {
+    // Like terms in `u` must not hide `u` from substitution
+    let program = parse_program("u = x + 1; u^2 + 2*u + 1", None).unwrap();
+    assert!(!program.expr.variables().contains("u"));
+    let compiled = CompiledEvaluator::compile(&program.expr, &["x"], None).unwrap();
+    assert!((compiled.evaluate(&[2.0]) - 16.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_program_multichar_intermediate_names() {
+    let program = parse_program("area = w*h; area^2", None).unwrap();
+    assert_eq!(program.expr, parse_plain("(w*h)^2"));
+}
+
+#[test]
+fn test_program_rejects_shadowing_variable() {
+    let err = parse_program("u = x^2; x = u + 1; x", None).unwrap_err();
+    match err {
+        DiffError::InvalidSyntax { span, .. } => assert_eq!(span, Some(Span::new(9, 10))),
+        other => panic!("unexpected error: {other:?}"),
+    }
+
+    // Symbols declared in the context are variables too
+    let ctx = Context::new().with_symbol("k");
+    assert!(parse_program("k = 2; k*x", Some(&ctx)).is_err());
+}
+
+#[test]
+fn test_program_rejects_redefinition() {
+    let err = parse_program("u = x; u = 2*x; u", None).unwrap_err();
+    assert!(matches!(
+        err,
+        DiffError::InvalidSyntax { span: Some(s), .. } if s == Span::new(7, 8)
+    ));
+}
+
+#[test]
+fn test_program_structure_errors() {
+    assert!(parse_program("u = x^2;", None).is_err());
+    assert!(parse_program("u = x; u; u + 1", None).is_err());
+    assert!(parse_program("2*u = x; u", None).is_err());
+    assert!(parse_program("u = v = x; u", None).is_err());
+    // Trailing separators are allowed
+    assert!(parse_program("u = x; u + 1;", None).is_ok());
+}
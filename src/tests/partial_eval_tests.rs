@@ -0,0 +1,124 @@
+//! Tests for `Expr::partial_eval`
+
+use crate::{Context, Expr, eval_partial_str, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+#[test]
+fn test_binds_one_coefficient() {
+    let expr = parse_plain("a*x^2 + b*x");
+    let bound = expr.partial_eval(&HashMap::from([("a", 2.0)]));
+    assert_eq!(bound, parse_plain("2*x^2 + b*x").simplified().unwrap());
+    assert_eq!(bound.to_string(), "2*x^2 + b*x");
+}
+
+#[test]
+fn test_empty_assignment_only_simplifies() {
+    let expr = parse_plain("x + x");
+    assert_eq!(expr.partial_eval(&()).to_string(), "2*x");
+}
+
+#[test]
+fn test_full_assignment_is_a_number() {
+    let expr = parse_plain("a*x^2 + b*x");
+    let bound = expr.partial_eval(&HashMap::from([("a", 2.0), ("b", 3.0), ("x", 0.5)]));
+    assert_eq!(bound.as_number(), Some(2.0));
+}
+
+#[test]
+fn test_folds_bound_function_arguments() {
+    let expr = parse_plain("exp(-E/(k*T))*P");
+    let bound = expr.partial_eval(&HashMap::from([("E", 2.0), ("k", 1.0), ("T", 4.0)]));
+    let expected = (-0.5_f64).exp();
+    assert_eq!(
+        bound,
+        (Expr::number(expected) * parse_plain("P"))
+            .simplified()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_disjoint_passes_match_union() {
+    let expr = parse_plain("a*sin(x*y) + b*y^2 - c/x");
+    let first = HashMap::from([("a", 2.0), ("x", 0.25)]);
+    let second = HashMap::from([("b", -1.5), ("c", 3.0)]);
+    let union: HashMap<&str, f64> = first.iter().chain(&second).map(|(k, v)| (*k, *v)).collect();
+
+    let stepwise = expr.partial_eval(&first).partial_eval(&second);
+    let once = expr.partial_eval(&union);
+    assert_eq!(stepwise, once);
+
+    let reversed = expr.partial_eval(&second).partial_eval(&first);
+    let y = HashMap::from([("y", 1.2)]);
+    let (a, b) = (
+        reversed.evaluate(&y, &HashMap::new()),
+        once.evaluate(&y, &HashMap::new()),
+    );
+    let (a, b) = (a.as_number().unwrap(), b.as_number().unwrap());
+    assert!((a - b).abs() <= 1e-12 * b.abs().max(1.0), "{a} vs {b}");
+}
+
+#[test]
+fn test_symbol_keys_leave_fit_parameters() {
+    let expr = parse_plain("k*T^2 + A*exp(-Ea/(k*T))");
+    let (k, t) = (symb("k"), symb("T"));
+    let reduced = expr.partial_eval(&HashMap::from([(k, 2.0), (t, 0.5)]));
+
+    let mut free: Vec<String> = reduced
+        .variables()
+        .into_iter()
+        .filter(|name| !crate::core::known_symbols::is_known_constant(name))
+        .collect();
+    free.sort();
+    assert_eq!(free, ["A", "Ea"]);
+    // k*T^2 = 0.5 and Ea/(k*T) = Ea
+    assert_eq!(
+        reduced,
+        parse_plain("0.5 + A*exp(-Ea)").simplified().unwrap()
+    );
+    let rest = HashMap::from([("A", 3.0), ("Ea", 0.25)]);
+    let expected = 2.0f64.mul_add(0.25, 3.0 * (-0.25f64 / (2.0 * 0.5)).exp());
+    let value = reduced
+        .evaluate(&rest, &HashMap::new())
+        .as_number()
+        .unwrap();
+    assert!((value - expected).abs() < 1e-15, "{value} vs {expected}");
+}
+
+#[test]
+fn test_symbol_keys_fold_functions_constants_and_polynomials() {
+    let x = symb("x");
+    let folded = parse_plain("sin(x) + pi*y").partial_eval(&HashMap::from([(x, 1.0)]));
+    assert_eq!(
+        folded,
+        (Expr::number(1.0f64.sin()) + Expr::number(std::f64::consts::PI) * symb("y"))
+            .simplified()
+            .unwrap()
+    );
+
+    let poly = parse_plain("(x^3 + 2*x + 1)*y").expand();
+    let bound = poly.partial_eval(&HashMap::from([(x, 2.0)]));
+    assert_eq!(bound.to_string(), "13*y");
+}
+
+#[test]
+fn test_symbol_keys_match_context_symbols() {
+    let ctx = Context::new();
+    let k = ctx.symb("k_ctx_only");
+    let expr = parse("k_ctx_only*x", &HashSet::new(), &HashSet::new(), Some(&ctx)).unwrap();
+    let bound = expr.partial_eval(&HashMap::from([(k, 4.0)]));
+    assert_eq!(bound.to_string(), "4*x");
+}
+
+#[test]
+fn test_eval_partial_str() {
+    assert_eq!(
+        eval_partial_str("k*T^2 + A*exp(-Ea/(k*T))", &[("k", 2.0), ("T", 0.5)]).unwrap(),
+        crate::simplify("0.5 + A*exp(-Ea)", &[], None).unwrap()
+    );
+    assert_eq!(eval_partial_str("x + x", &[]).unwrap(), "2*x");
+}
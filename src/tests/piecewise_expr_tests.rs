@@ -0,0 +1,181 @@
+//! Piecewise expressions: parsing, display, diff, simplification and evaluation
+
+use crate::core::ExprKind;
+use crate::{CompiledEvaluator, Condition, Expr, parse, symb};
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn tree_eval(expr: &Expr, x: f64) -> f64 {
+    let vars: HashMap<&str, f64> = HashMap::from([("x", x)]);
+    expr.evaluate(&vars, &HashMap::new()).as_number().unwrap()
+}
+
+const HUBER: &str = "piecewise(abs(x) <= 1: x^2/2, abs(x) - 1/2)";
+
+#[test]
+fn test_parse_and_display_round_trip() {
+    let expr = parse_str("piecewise(x < 0: -x, x >= 0: x)");
+    let ExprKind::Piecewise { branches, default } = &expr.kind else {
+        panic!("expected a piecewise node, got {expr}");
+    };
+    assert_eq!(branches.len(), 2);
+    // Without a default, no branch holding means NaN
+    assert!(default.as_number().is_some_and(f64::is_nan));
+    assert_eq!(parse_str(&expr.to_string()), expr);
+
+    let x = symb("x");
+    let built = Expr::piecewise(vec![(Condition::lt(x, 0.0), -x.to_expr())], x.pow(2.0));
+    assert_eq!(parse_str(&built.to_string()), built);
+    assert_eq!(
+        built.to_latex(),
+        r"\begin{cases}-x & \text{if } x < 0 \\ x^{2} & \text{otherwise}\end{cases}"
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    for input in [
+        "piecewise()",
+        "piecewise(x < 0)",
+        "piecewise(1, x < 0: 2)",
+        "piecewise(x = 0: 1)",
+    ] {
+        assert!(
+            parse(input, &HashSet::new(), &HashSet::new(), None).is_err(),
+            "{input} should not parse"
+        );
+    }
+}
+
+#[test]
+fn test_evaluation_on_both_sides_of_a_breakpoint() {
+    let relu = parse_str("piecewise(x < 0: 0, x)");
+    let compiled = CompiledEvaluator::compile(&relu, &["x"], None).unwrap();
+    for (x, want) in [
+        (-2.0, 0.0),
+        (-1e-12, 0.0),
+        (0.0, 0.0),
+        (1e-12, 1e-12),
+        (3.0, 3.0),
+    ] {
+        assert_eq!(tree_eval(&relu, x), want, "tree at {x}");
+        assert_eq!(compiled.evaluate(&[x]), want, "compiled at {x}");
+    }
+
+    // The first branch that holds wins, even when a later one holds too
+    let first = parse_str("piecewise(x > 0: 1, x > -1: 2, 3)");
+    assert_eq!(tree_eval(&first, 0.5), 1.0);
+    assert_eq!(tree_eval(&first, -0.5), 2.0);
+    assert_eq!(tree_eval(&first, -5.0), 3.0);
+
+    // NaN never satisfies a condition
+    assert!(tree_eval(&relu, f64::NAN).is_nan());
+    let compiled = CompiledEvaluator::compile(&first, &["x"], None).unwrap();
+    assert_eq!(compiled.evaluate(&[f64::NAN]), 3.0);
+}
+
+#[test]
+fn test_partial_evaluation_keeps_undecided_branches() {
+    let expr = parse_str("piecewise(y < 0: x, x > 1: 2*x, 0)");
+    let vars: HashMap<&str, f64> = HashMap::from([("x", 3.0)]);
+    let partial = expr.evaluate(&vars, &HashMap::new());
+    // x > 1 is decided true, so it becomes the default behind y < 0
+    assert_eq!(partial, parse_str("piecewise(y < 0: 3, 6)"));
+}
+
+#[test]
+fn test_huber_loss_derivative() {
+    let huber = parse_str(HUBER);
+    let slope = huber.diff("x").unwrap();
+    assert!(matches!(slope.kind, ExprKind::Piecewise { .. }), "{slope}");
+
+    let compiled = CompiledEvaluator::compile(&slope, &["x"], None).unwrap();
+    for x in [-3.0, -1.5, -0.4, 0.0, 0.25, 0.9, 2.0, 10.0] {
+        let want = if f64::abs(x) <= 1.0 { x } else { x.signum() };
+        assert!((tree_eval(&slope, x) - want).abs() < 1e-12, "tree at {x}");
+        assert!(
+            (compiled.evaluate(&[x]) - want).abs() < 1e-12,
+            "compiled at {x}"
+        );
+    }
+
+    // A piecewise constant has a zero derivative
+    let step = parse_str("piecewise(x < 0: -1, 1)");
+    assert_eq!(step.diff("x").unwrap(), Expr::number(0.0));
+}
+
+#[test]
+fn test_simplify_drops_unreachable_branches() {
+    // Constant-false branches are dropped
+    let expr = parse_str("piecewise(1 > 2: x, x < 0: 5, 0)")
+        .simplified()
+        .unwrap();
+    assert_eq!(expr, parse_str("piecewise(x < 0: 5, 0)"));
+
+    // A constant-true branch ends the chain as the new default
+    let expr = parse_str("piecewise(x < 0: 5, 2 >= 2: x, 7)")
+        .simplified()
+        .unwrap();
+    assert_eq!(expr, parse_str("piecewise(x < 0: 5, x)"));
+    let expr = parse_str("piecewise(1 == 1: x^2, 0)").simplified().unwrap();
+    assert_eq!(expr, parse_str("x^2"));
+
+    // Conditions are simplified first, so they can become decided
+    let expr = parse_str("piecewise(x - x + 1 < 0: x, sin(x))")
+        .simplified()
+        .unwrap();
+    assert_eq!(expr, parse_str("sin(x)"));
+
+    // Branch values are simplified in place
+    let expr = parse_str("piecewise(x < 0: x + x, 0)")
+        .simplified()
+        .unwrap();
+    let ExprKind::Piecewise { branches, .. } = &expr.kind else {
+        panic!("expected a piecewise node, got {expr}");
+    };
+    assert_eq!(branches[0].1.to_string(), "2*x");
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_simd_batch_matches_tree_for_mixed_branch_lanes() {
+    use wide::f64x4;
+
+    let programs = [
+        parse_str(HUBER),
+        parse_str(HUBER).diff("x").unwrap(),
+        parse_str("piecewise(x < -1: -x, x <= 1: x^2, x == 2: 0, exp(x))"),
+    ];
+    // Each group of four lanes mixes branches, breakpoints and a NaN
+    let xs = [
+        -3.0,
+        0.5,
+        1.0,
+        2.0,
+        -1.0,
+        f64::NAN,
+        0.0,
+        4.0,
+        -0.5,
+        1.5,
+        -1.5,
+    ];
+    for expr in &programs {
+        let eval = CompiledEvaluator::compile(expr, &["x"], None).unwrap();
+        let mut workspace = vec![f64x4::splat(0.0); eval.workspace_size()];
+        let mut out = vec![0.0; xs.len()];
+        eval.eval_batch(&[&xs], &mut out, Some(&mut workspace))
+            .unwrap();
+        for (&x, &got) in xs.iter().zip(&out) {
+            let want = eval.evaluate(&[x]);
+            assert_eq!(got.to_bits(), want.to_bits(), "{expr} at {x}");
+            if !x.is_nan() {
+                assert!((got - tree_eval(expr, x)).abs() < 1e-12, "{expr} at {x}");
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! min, max, clamp and select: compiled picks, fusions and the gated clamp derivative
+
+use crate::evaluator::Instruction;
+use crate::{CompiledEvaluator, Diff, Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn compile(input: &str, params: &[&str]) -> CompiledEvaluator {
+    CompiledEvaluator::compile(&parse_str(input), params, None).unwrap()
+}
+
+/// The clamp as written out by hand
+fn clamp_by_cases(x: f64, lo: f64, hi: f64) -> f64 {
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+/// Boundaries, points just outside them, and interior points of [-1, 2]
+const CLAMP_POINTS: [f64; 10] = [
+    -1.0,
+    2.0,
+    -1.000_000_1,
+    2.000_000_1,
+    -5.0,
+    7.5,
+    0.0,
+    0.25,
+    1.999,
+    -0.999,
+];
+
+fn has_instruction(eval: &CompiledEvaluator, matches: impl Fn(&Instruction) -> bool) -> bool {
+    eval.instructions.iter().any(matches)
+}
+
+#[test]
+fn test_compiled_clamp_matches_definition() {
+    let constant_bounds = compile("clamp(x, -1, 2)", &["x"]);
+    let free_bounds = compile("clamp(x, lo, hi)", &["x", "lo", "hi"]);
+    for eval in [&constant_bounds, &free_bounds] {
+        assert!(has_instruction(eval, |instr| matches!(
+            instr,
+            Instruction::Clamp { .. }
+        )));
+    }
+
+    for x in CLAMP_POINTS {
+        let want = clamp_by_cases(x, -1.0, 2.0);
+        assert_eq!(
+            constant_bounds.evaluate(&[x]).to_bits(),
+            want.to_bits(),
+            "x = {x}"
+        );
+        assert_eq!(
+            free_bounds.evaluate(&[x, -1.0, 2.0]).to_bits(),
+            want.to_bits(),
+            "x = {x}"
+        );
+    }
+}
+
+#[test]
+fn test_min_max_nesting_fuses_to_clamp() {
+    let eval = compile("min(max(x, 0), 1)", &["x"]);
+    assert!(has_instruction(&eval, |instr| matches!(
+        instr,
+        Instruction::Clamp { .. }
+    )));
+    assert!(!has_instruction(&eval, |instr| matches!(
+        instr,
+        Instruction::MinConst { .. } | Instruction::MaxConst { .. }
+    )));
+    for x in [-0.5, 0.0, 0.5, 1.0, 1.5] {
+        assert_eq!(eval.evaluate(&[x]), clamp_by_cases(x, 0.0, 1.0));
+    }
+}
+
+#[test]
+fn test_min_max_against_constant_lower_to_const_picks() {
+    let min = compile("min(x, 3)", &["x"]);
+    let max = compile("max(3, x)", &["x"]);
+    assert!(has_instruction(&min, |instr| matches!(
+        instr,
+        Instruction::MinConst { .. }
+    )));
+    assert!(has_instruction(&max, |instr| matches!(
+        instr,
+        Instruction::MaxConst { .. }
+    )));
+    for x in [-2.0, 3.0, 4.5] {
+        assert_eq!(min.evaluate(&[x]), x.min(3.0));
+        assert_eq!(max.evaluate(&[x]), x.max(3.0));
+    }
+
+    // Two free operands still go through the generic builtin
+    let free = compile("min(x, y)", &["x", "y"]);
+    assert_eq!(free.evaluate(&[2.0, -1.0]), -1.0);
+}
+
+#[test]
+fn test_step_feeding_select_fuses_to_sign_test() {
+    // The step's argument must still be live at the select, as a parameter is
+    let eval = compile("select(heaviside(x), 1 + x, -x)", &["x"]);
+    assert!(has_instruction(&eval, |instr| matches!(
+        instr,
+        Instruction::SelectNonNeg { .. }
+    )));
+    assert!(!has_instruction(&eval, |instr| matches!(
+        instr,
+        Instruction::Select { .. } | Instruction::Builtin1 { .. }
+    )));
+    // heaviside(0) = 1, so the boundary takes the first branch
+    assert_eq!(eval.evaluate(&[0.0]), 1.0);
+    assert_eq!(eval.evaluate(&[-0.5]), 0.5);
+    assert_eq!(eval.evaluate(&[3.0]), 4.0);
+}
+
+#[test]
+fn test_nan_propagates_through_picks() {
+    let clamp = compile("clamp(x, 0, 1)", &["x"]);
+    let min = compile("min(x, y)", &["x", "y"]);
+    let select = compile("select(c, 1, 2)", &["c"]);
+    assert!(clamp.evaluate(&[f64::NAN]).is_nan());
+    assert!(min.evaluate(&[1.0, f64::NAN]).is_nan());
+    assert!(min.evaluate(&[f64::NAN, 1.0]).is_nan());
+    assert!(select.evaluate(&[f64::NAN]).is_nan());
+    assert_eq!(select.evaluate(&[0.0]), 2.0);
+    assert_eq!(select.evaluate(&[-3.0]), 1.0);
+}
+
+#[test]
+fn test_clamp_derivative_is_indicator_gated() {
+    let x = symb("x");
+    let derivative = Diff::new()
+        .differentiate(&parse_str("clamp(x, 0, 1)"), &x)
+        .unwrap();
+    let slope = CompiledEvaluator::compile(&derivative, &["x"], None).unwrap();
+    // 1 on the closed interval, edges included; 0 outside
+    for (point, want) in [(-0.5, 0.0), (0.0, 1.0), (0.5, 1.0), (1.0, 1.0), (1.5, 0.0)] {
+        assert_eq!(slope.evaluate(&[point]), want, "x = {point}");
+    }
+
+    // Outside the interval the slope moves to the bound that is active
+    let lo = symb("lo");
+    let by_lo = Diff::new()
+        .differentiate(&parse_str("clamp(x, lo, hi)"), &lo)
+        .unwrap();
+    let by_lo = CompiledEvaluator::compile(&by_lo, &["x", "lo", "hi"], None).unwrap();
+    assert_eq!(by_lo.evaluate(&[-2.0, -1.0, 2.0]), 1.0);
+    assert_eq!(by_lo.evaluate(&[0.5, -1.0, 2.0]), 0.0);
+}
+
+#[test]
+fn test_forward_mode_passes_the_chosen_tangent() {
+    let eval = compile("clamp(x, -1, 2)*x", &["x"]);
+    // Inside: d(x^2) = 2x; outside: d(hi*x) = hi
+    assert_eq!(eval.eval_dual(&[0.5], &[1.0]), (0.25, 1.0));
+    assert_eq!(eval.eval_dual(&[3.0], &[1.0]), (6.0, 2.0));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_clamp_batch_paths_match_definition() {
+    let eval = compile("clamp(x, -1, 2)", &["x"]);
+    let want: Vec<f64> = CLAMP_POINTS
+        .iter()
+        .map(|&x| clamp_by_cases(x, -1.0, 2.0))
+        .collect();
+
+    let mut scalar = vec![0.0; CLAMP_POINTS.len()];
+    eval.eval_batch(&[&CLAMP_POINTS], &mut scalar, None)
+        .unwrap();
+    assert_eq!(scalar, want);
+
+    let mut workspace = vec![wide::f64x4::splat(0.0); eval.workspace_size()];
+    let mut simd = vec![0.0; CLAMP_POINTS.len()];
+    eval.eval_batch(&[&CLAMP_POINTS], &mut simd, Some(&mut workspace))
+        .unwrap();
+    assert_eq!(simd, want);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_simd_blend_matches_scalar_per_lane() {
+    use wide::f64x4;
+
+    let programs = [
+        compile("clamp(x, lo, hi)", &["x", "lo", "hi"]),
+        compile("select(x, lo, hi)", &["x", "lo", "hi"]),
+        compile("select(heaviside(x), lo, hi)", &["x", "lo", "hi"]),
+        compile(
+            "max(min(x, 2), -1) + min(x, lo) + max(hi, x)",
+            &["x", "lo", "hi"],
+        ),
+    ];
+    // Each group of four lanes mixes branches, ties and a NaN
+    let x = [-3.0, 0.0, f64::NAN, 2.0, 5.0, -1.0, 1.0, -0.0];
+    let lo = [-1.0, 0.0, 0.0, -1.0, 1.0, -1.0, 3.0, 0.0];
+    let hi = [2.0, 1.0, 1.0, 2.0, 4.0, 0.5, 2.0, 1.0];
+
+    for eval in &programs {
+        let mut workspace = vec![f64x4::splat(0.0); eval.workspace_size()];
+        let mut blended = vec![0.0; x.len()];
+        eval.eval_batch_simd(&[&x, &lo, &hi], &mut blended, &mut workspace);
+        for (lane, &got) in blended.iter().enumerate() {
+            let want = eval.evaluate(&[x[lane], lo[lane], hi[lane]]);
+            assert_eq!(
+                got.to_bits(),
+                want.to_bits(),
+                "lane {lane}: {got} vs {want}"
+            );
+        }
+    }
+}
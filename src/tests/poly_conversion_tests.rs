@@ -0,0 +1,131 @@
+//! Automatic `Sum` → `Poly` conversion policy and explicit conversions.
+
+use crate::core::ExprKind;
+use crate::{Diff, Expr, PolyConversion, poly_conversion, symb, with_poly_conversion};
+use std::collections::HashMap;
+
+/// x + x^2 + ... + x^10
+fn ten_term_sum() -> Expr {
+    let x = Expr::symbol("x");
+    Expr::sum(
+        (1..=10)
+            .map(|n| Expr::pow(x.clone(), Expr::number(f64::from(n))))
+            .collect(),
+    )
+}
+
+fn eval_at(expr: &Expr, x: f64) -> f64 {
+    let vars: HashMap<&str, f64> = [("x", x)].into_iter().collect();
+    match expr.evaluate(&vars, &HashMap::new()).kind {
+        ExprKind::Number(n) => n,
+        ref other => panic!("Expected number, got {other:?}"),
+    }
+}
+
+fn contains_poly(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Poly(_) => true,
+        ExprKind::Sum(xs) | ExprKind::Product(xs) | ExprKind::FunctionCall { args: xs, .. } => {
+            xs.iter().any(|x| contains_poly(x))
+        }
+        ExprKind::Div(a, b) | ExprKind::Pow(a, b) => contains_poly(a) || contains_poly(b),
+        ExprKind::Derivative { inner, .. } => contains_poly(inner),
+        ExprKind::Piecewise { branches, default } => {
+            crate::core::piecewise_parts(branches, default).any(|x| contains_poly(x))
+        }
+        ExprKind::Number(_) | ExprKind::Symbol(_) => false,
+    }
+}
+
+#[test]
+fn test_default_policy_converts() {
+    assert_eq!(poly_conversion(), PolyConversion::DEFAULT);
+    assert!(matches!(ten_term_sum().kind, ExprKind::Poly(_)));
+}
+
+#[test]
+fn test_disabled_policy_keeps_sum() {
+    let expr = with_poly_conversion(PolyConversion::DISABLED, ten_term_sum);
+    match &expr.kind {
+        ExprKind::Sum(terms) => assert_eq!(terms.len(), 10),
+        other => panic!("Expected Sum, got {other:?}"),
+    }
+    // Policy is restored afterwards
+    assert_eq!(poly_conversion(), PolyConversion::DEFAULT);
+}
+
+#[test]
+fn test_sum_no_poly() {
+    let x = Expr::symbol("x");
+    let expr = Expr::sum_no_poly(vec![x.clone(), Expr::pow(x, Expr::number(2.0))]);
+    assert!(matches!(expr.kind, ExprKind::Sum(_)));
+}
+
+#[test]
+fn test_thresholds() {
+    let min_terms = PolyConversion {
+        min_terms: 3,
+        ..PolyConversion::DEFAULT
+    };
+    let x = Expr::symbol("x");
+    let two = with_poly_conversion(min_terms, || {
+        Expr::sum(vec![x.clone(), Expr::pow(x.clone(), Expr::number(2.0))])
+    });
+    assert!(matches!(two.kind, ExprKind::Sum(_)));
+
+    let low_degree = PolyConversion {
+        max_degree: 5,
+        ..PolyConversion::DEFAULT
+    };
+    assert!(matches!(
+        with_poly_conversion(low_degree, ten_term_sum).kind,
+        ExprKind::Sum(_)
+    ));
+}
+
+#[test]
+fn test_explicit_conversion_roundtrip() {
+    let plain = with_poly_conversion(PolyConversion::DISABLED, ten_term_sum);
+    let poly = plain.to_poly();
+    assert!(matches!(poly.kind, ExprKind::Poly(_)));
+
+    let back = poly.from_poly();
+    assert!(!contains_poly(&back));
+    match &back.kind {
+        ExprKind::Sum(terms) => assert_eq!(terms.len(), 10),
+        other => panic!("Expected Sum, got {other:?}"),
+    }
+
+    // Mixed bases are left alone
+    let mixed = Expr::sum(vec![Expr::symbol("x"), Expr::symbol("y")]);
+    assert_eq!(mixed.to_poly(), mixed);
+}
+
+#[test]
+fn test_diff_and_simplify_agree_across_representations() {
+    let x = symb("x");
+    let plain = with_poly_conversion(PolyConversion::DISABLED, ten_term_sum);
+    let poly = plain.to_poly();
+
+    let d_poly = Diff::new().differentiate(&poly, &x).unwrap();
+    let d_plain = with_poly_conversion(PolyConversion::DISABLED, || {
+        Diff::new().differentiate(&plain, &x).unwrap()
+    });
+    assert!(!contains_poly(&d_plain), "Got {d_plain}");
+
+    let s_poly = poly.simplified().unwrap();
+    let s_plain = with_poly_conversion(PolyConversion::DISABLED, || plain.simplified().unwrap());
+
+    for point in [-1.5, -0.3, 0.0, 0.7, 2.0] {
+        let (a, b) = (eval_at(&d_poly, point), eval_at(&d_plain, point));
+        assert!(
+            (a - b).abs() <= 1e-12 * a.abs().max(1.0),
+            "{a} vs {b} at {point}"
+        );
+        let (a, b) = (eval_at(&s_poly, point), eval_at(&s_plain, point));
+        assert!(
+            (a - b).abs() <= 1e-12 * a.abs().max(1.0),
+            "{a} vs {b} at {point}"
+        );
+    }
+}
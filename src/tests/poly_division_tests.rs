@@ -0,0 +1,160 @@
+//! Tests for `Expr::div_poly`, `Expr::to_poly_coefficients` and `Expr::degree_in`
+
+use crate::{DiffError, Expr, Symbol, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn simplified(input: &str) -> Expr {
+    parse_str(input).simplified().unwrap()
+}
+
+/// `num = q*den + r` at several points, with `r` of lower degree than `den`
+fn assert_division(num: &Expr, den: &Expr, var: &Symbol, q: &Expr, r: &Expr) {
+    let den_len = den.to_poly_coefficients(var).unwrap().len();
+    assert!(
+        r.to_poly_coefficients(var).unwrap().len() < den_len,
+        "{r} vs {den}"
+    );
+
+    let name = var.name().unwrap();
+    for value in [-2.5, -1.0, 0.0, 0.5, 3.0] {
+        let values = HashMap::from([(name.as_str(), value), ("a", 1.75)]);
+        let lhs = num.partial_eval(&values).as_number().unwrap();
+        let rhs = (q.clone() * den.clone() + r.clone())
+            .partial_eval(&values)
+            .as_number()
+            .unwrap();
+        assert!((lhs - rhs).abs() < 1e-9, "{num} at {value}: {lhs} vs {rhs}");
+    }
+}
+
+#[test]
+fn test_exact_division() {
+    let x = symb("x");
+    let num = parse_str("x^3 - 1");
+    let den = parse_str("x - 1");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert_eq!(q, simplified("x^2 + x + 1"));
+    assert!(r.is_zero_num());
+    assert_division(&num, &den, &x, &q, &r);
+}
+
+#[test]
+fn test_nonzero_remainder() {
+    let x = symb("x");
+    let num = parse_str("x^3 + 2*x + 5");
+    let den = parse_str("x^2 + 1");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert_eq!(q, x.to_expr());
+    assert_eq!(r, simplified("x + 5"));
+    assert_division(&num, &den, &x, &q, &r);
+}
+
+#[test]
+fn test_non_monic_divisor() {
+    let x = symb("x");
+    let num = parse_str("2*x^2 + 3*x + 1");
+    let den = parse_str("2*x + 1");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert_eq!(q, simplified("x + 1"));
+    assert!(r.is_zero_num());
+
+    let num = parse_str("x^2 + 1");
+    let den = parse_str("2*x");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert_eq!(r, Expr::number(1.0));
+    assert_division(&num, &den, &x, &q, &r);
+}
+
+#[test]
+fn test_low_degree_operands() {
+    let x = symb("x");
+
+    // Degree-zero denominator divides every coefficient
+    let num = parse_str("4*x^2 - 2*x + 6");
+    let (q, r) = Expr::div_poly(&num, &Expr::number(2.0), &x).unwrap();
+    assert_eq!(q, simplified("2*x^2 - x + 3"));
+    assert!(r.is_zero_num());
+
+    // Numerator of lower degree is all remainder
+    let num = parse_str("x + 3");
+    let den = parse_str("x^2 - 4");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert!(q.is_zero_num());
+    assert_eq!(r, simplified("x + 3"));
+
+    // Cancelled leading terms lower the degree before dividing
+    let den = parse_str("(x + 1)^2 - x^2");
+    let (q, r) = Expr::div_poly(&parse_str("2*x^2 + x"), &den, &x).unwrap();
+    assert_division(&parse_str("2*x^2 + x"), &den, &x, &q, &r);
+}
+
+#[test]
+fn test_symbolic_coefficients() {
+    let x = symb("x");
+    let num = parse_str("x^2 - a^2");
+    let den = parse_str("x - a");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert!(r.is_zero_num(), "remainder {r}");
+    assert_division(&num, &den, &x, &q, &r);
+
+    let num = parse_str("a*x^2 + x + 1");
+    let den = parse_str("a*x + 1");
+    let (q, r) = Expr::div_poly(&num, &den, &x).unwrap();
+    assert!(!r.contains_var("x"));
+    assert_division(&num, &den, &x, &q, &r);
+}
+
+#[test]
+fn test_errors() {
+    let x = symb("x");
+    for (num, den) in [("sin(x)", "x"), ("x^2", "sqrt(x)"), ("x^2", "x - x")] {
+        let result = Expr::div_poly(&parse_str(num), &parse_str(den), &x);
+        assert!(
+            matches!(result, Err(DiffError::UnsupportedExpression(_))),
+            "{num} / {den}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn test_to_poly_coefficients() {
+    let x = symb("x");
+    let coeffs = parse_str("3*x^2 + a*x - 1")
+        .to_poly_coefficients(&x)
+        .unwrap();
+    assert_eq!(
+        coeffs,
+        vec![Expr::number(-1.0), symb("a").to_expr(), Expr::number(3.0)]
+    );
+    assert!(
+        Expr::number(0.0)
+            .to_poly_coefficients(&x)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(parse_str("exp(x)").to_poly_coefficients(&x).is_err());
+}
+
+#[test]
+fn test_degree_in() {
+    let (x, y) = (symb("x"), symb("y"));
+    assert_eq!(parse_str("x^5 + 3*x^2 - 7").degree_in(&x), Some(5));
+    assert_eq!(simplified("x^5 + 3*x^2 - 7").degree_in(&x), Some(5));
+    assert_eq!(parse_str("sin(x)").degree_in(&x), None);
+    assert_eq!(parse_str("x^2 * y^3").degree_in(&x), Some(2));
+    assert_eq!(parse_str("x^2 * y^3").degree_in(&y), Some(3));
+
+    // Constants in `x`, whatever else they hold
+    assert_eq!(parse_str("7").degree_in(&x), Some(0));
+    assert_eq!(parse_str("exp(y)/y").degree_in(&x), Some(0));
+    assert_eq!(parse_str("(x^2 + 1)^3/(2*y)").degree_in(&x), Some(6));
+
+    for input in ["x^0.5", "x^-1", "1/(1 + x)", "2^x", "x^y"] {
+        assert!(!parse_str(input).is_polynomial_in(&x), "{input}");
+    }
+    assert!(parse_str("(x - 1)*(x + 1)").is_polynomial_in(&x));
+}
@@ -0,0 +1,124 @@
+//! Tests for postfix (RPN) construction and emission
+
+use crate::{Diff, DiffError, Expr, OpKind, PostfixToken, Span, parse, symb};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn token_index(err: &DiffError) -> Option<usize> {
+    match err {
+        DiffError::InvalidSyntax { span: Some(s), .. } => Some(s.start()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_postfix_round_trip_corpus() {
+    let corpus = [
+        "x",
+        "3.5",
+        "x + y - z",
+        "2*x*y^3",
+        "-x/(y + 1)",
+        "sin(x)^2 + cos(x)^2",
+        "exp(-x^2/2)/sqrt(2*pi)",
+        "atan2(y, x) + besselj(2, x)",
+        "x^2 + 2*x + 1",
+        "3*x^5 - x^3 + 7",
+        "diff(f(x), x, 2) + ln(abs(x))",
+        "(a + b)^(c*d) / (a - b)",
+        "piecewise(x < 0: -x, x >= y: x^2, 1)",
+        "piecewise(x == 1: y)",
+    ];
+    for input in corpus {
+        let expr = parse_plain(input);
+        let tokens = expr.to_postfix();
+        let rebuilt = Expr::from_postfix(&tokens).unwrap();
+        assert_eq!(rebuilt, expr, "{input}: {tokens:?}");
+    }
+}
+
+#[test]
+fn test_postfix_builds_expected_tree() {
+    // (x + 1)^2 * sin(y)
+    let tokens = [
+        PostfixToken::Symbol("x"),
+        PostfixToken::Number(1.0),
+        PostfixToken::BinaryOp(OpKind::Add),
+        PostfixToken::Number(2.0),
+        PostfixToken::BinaryOp(OpKind::Pow),
+        PostfixToken::Symbol("y"),
+        PostfixToken::Function("sin", 1),
+        PostfixToken::BinaryOp(OpKind::Mul),
+    ];
+    let expr = Expr::from_postfix(&tokens).unwrap();
+    assert_eq!(expr, parse_plain("(x + 1)^2 * sin(y)"));
+}
+
+#[test]
+fn test_postfix_stack_underflow_names_token() {
+    let tokens = [
+        PostfixToken::Symbol("x"),
+        PostfixToken::BinaryOp(OpKind::Add),
+    ];
+    let err = Expr::from_postfix(&tokens).unwrap_err();
+    assert_eq!(token_index(&err), Some(1));
+    assert!(err.to_string().contains("postfix token 1"), "{err}");
+
+    let tokens = [PostfixToken::Function("atan2", 2)];
+    assert_eq!(
+        token_index(&Expr::from_postfix(&tokens).unwrap_err()),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_postfix_leftover_operands_names_token() {
+    let tokens = [
+        PostfixToken::Symbol("x"),
+        PostfixToken::Symbol("y"),
+        PostfixToken::Symbol("z"),
+        PostfixToken::BinaryOp(OpKind::Mul),
+    ];
+    let err = Expr::from_postfix(&tokens).unwrap_err();
+    // y*z (token 3) is the value nobody consumed
+    assert!(matches!(err, DiffError::InvalidSyntax { span: Some(s), .. } if s == Span::at(3)));
+}
+
+#[test]
+fn test_postfix_validates_builtin_arity() {
+    let tokens = [
+        PostfixToken::Symbol("x"),
+        PostfixToken::Function("atan2", 1),
+    ];
+    assert_eq!(
+        token_index(&Expr::from_postfix(&tokens).unwrap_err()),
+        Some(1)
+    );
+    assert!(matches!(
+        Expr::from_postfix(&[]),
+        Err(DiffError::EmptyFormula)
+    ));
+}
+
+#[test]
+fn test_postfix_output_differentiates() {
+    // x^3 * exp(x)
+    let tokens = [
+        PostfixToken::Symbol("x"),
+        PostfixToken::Number(3.0),
+        PostfixToken::BinaryOp(OpKind::Pow),
+        PostfixToken::Symbol("x"),
+        PostfixToken::Function("exp", 1),
+        PostfixToken::BinaryOp(OpKind::Mul),
+    ];
+    let expr = Expr::from_postfix(&tokens).unwrap();
+    let x = symb("x");
+    let from_postfix = Diff::new().differentiate(&expr, &x).unwrap();
+    let from_infix = Diff::new()
+        .differentiate(&parse_plain("x^3 * exp(x)"), &x)
+        .unwrap();
+    assert_eq!(from_postfix, from_infix);
+}
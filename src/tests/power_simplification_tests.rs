@@ -108,4 +108,102 @@ mod tests {
             panic!("Expected power expression");
         }
     }
+
+    fn simplify(expr: Expr) -> Expr {
+        simplify_expr(
+            expr,
+            HashSet::new(),
+            HashMap::new(),
+            None,
+            None,
+            None,
+            false,
+        )
+    }
+
+    fn exact_exponent(expr: &Expr) -> Option<f64> {
+        match &expr.kind {
+            ExprKind::Pow(_, exp) => match exp.kind {
+                ExprKind::Number(n) => Some(n),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_cube_of_cube_root_is_exact() {
+        // (x^(1/3))^3 -> x, with no 0.9999999999999999 left over
+        let x = Expr::symbol("x");
+        let cube_root = Expr::pow(
+            x.clone(),
+            Expr::div_expr(Expr::number(1.0), Expr::number(3.0)),
+        );
+        let simplified = simplify(Expr::pow(cube_root, Expr::number(3.0)));
+        assert_eq!(simplified, x);
+    }
+
+    #[test]
+    fn test_fourth_power_of_sqrt_is_exact() {
+        // (x^0.5)^4 -> x^2
+        let expr = Expr::pow(
+            Expr::pow(Expr::symbol("x"), Expr::number(0.5)),
+            Expr::number(4.0),
+        );
+        assert_eq!(exact_exponent(&simplify(expr)), Some(2.0));
+    }
+
+    #[test]
+    fn test_near_integer_exponent_is_snapped() {
+        for (raw, expected) in [
+            (0.1 * 3.0 * 10.0, 3.0),
+            (2.0000000000000004, 2.0),
+            (1.9999999999999998, 2.0),
+        ] {
+            let expr = Expr::pow(Expr::symbol("x"), Expr::number(raw));
+            assert_eq!(exact_exponent(&simplify(expr)), Some(expected), "x^{raw}");
+        }
+        // Exponents that are genuinely fractional are left alone
+        let expr = Expr::pow(Expr::symbol("x"), Expr::number(2.001));
+        assert_eq!(exact_exponent(&simplify(expr)), Some(2.001));
+    }
+
+    #[test]
+    fn test_rational_exponent_collection_is_exact() {
+        // x^(1/3) * x^(1/3) * x^(1/3) -> x
+        let x = Expr::symbol("x");
+        let third = || Expr::div_expr(Expr::number(1.0), Expr::number(3.0));
+        let expr = Expr::product(vec![
+            Expr::pow(x.clone(), third()),
+            Expr::pow(x.clone(), third()),
+            Expr::pow(x.clone(), third()),
+        ]);
+        assert_eq!(simplify(expr), x);
+
+        // x / x^(1/3) -> x^(2/3), kept as an exact fraction
+        let expr = Expr::div_expr(x.clone(), Expr::pow(x.clone(), third()));
+        let simplified = simplify(expr);
+        assert_eq!(
+            simplified,
+            Expr::pow(x, Expr::div_expr(Expr::number(2.0), Expr::number(3.0)))
+        );
+    }
+
+    #[test]
+    fn test_snapped_exponent_compiles_to_integer_power() {
+        use crate::CompiledEvaluator;
+
+        let expr = Expr::pow(Expr::symbol("x"), Expr::number(0.1 * 3.0 * 10.0));
+        let compiled = CompiledEvaluator::compile(&simplify(expr), &["x"], None).unwrap();
+        let listing = compiled.disassemble();
+        assert!(listing.contains("^3"), "{listing}");
+        assert!(!listing.contains(" ^ "), "generic pow emitted:\n{listing}");
+
+        let expr = Expr::pow(
+            Expr::pow(Expr::symbol("x"), Expr::number(0.5)),
+            Expr::number(4.0),
+        );
+        let compiled = CompiledEvaluator::compile(&simplify(expr), &["x"], None).unwrap();
+        assert!(compiled.disassemble().contains("Square"));
+    }
 }
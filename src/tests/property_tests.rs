@@ -8,7 +8,7 @@ use crate::{core::ExprKind, diff, parser, simplify};
 // ============================================================
 
 /// Generate random valid expression strings for fuzz testing
-fn random_expr_string(g: &mut Gen) -> String {
+pub(super) fn random_expr_string(g: &mut Gen) -> String {
     let depth = g.size().min(4); // Limit depth to avoid stack overflow
     gen_expr_string_recursive(g, depth)
 }
@@ -49,6 +49,12 @@ fn gen_expr_string_recursive(g: &mut Gen, depth: usize) -> String {
                 let arg = gen_expr_string_recursive(g, depth - 1);
                 format!("{}({})", f, arg)
             }
+            6 if depth > 1 => {
+                // Unresolved derivative of a custom function
+                let arg = gen_expr_string_recursive(g, depth - 1);
+                let order = 1 + u8::arbitrary(g) % 3;
+                format!("diff(f({}), x, {})", arg, order)
+            }
             6 => {
                 // Negation
                 let arg = gen_expr_string_recursive(g, depth - 1);
@@ -0,0 +1,75 @@
+//! Public API snapshot: `tools/public_api.py` lists every public item of a default build
+//! and the listing must match `src/tests/golden/public_api.txt`
+//!
+//! After an intended change to the public surface, rewrite the golden file with
+//! `python3 tools/public_api.py --bless` and review its diff.
+//!
+//! The comparison needs the nightly toolchain and python3, so it is opt-in:
+//! `cargo test public_api -- --ignored`. Once opted in, a missing tool is a failure.
+
+use std::collections::BTreeSet;
+use std::process::Command;
+
+const GOLDEN: &str = include_str!("golden/public_api.txt");
+
+#[test]
+#[ignore = "needs the nightly toolchain and python3; run with `cargo test public_api -- --ignored`"]
+fn test_public_api_matches_snapshot() {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let nightly = Command::new("cargo")
+        .args(["+nightly", "--version"])
+        .current_dir(root)
+        .output();
+    assert!(
+        nightly.is_ok_and(|out| out.status.success()),
+        "the nightly toolchain is needed for rustdoc JSON"
+    );
+    let listing = Command::new("python3")
+        .arg("tools/public_api.py")
+        .current_dir(root)
+        .output()
+        .expect("python3 is needed to list the public API");
+    assert!(
+        listing.status.success(),
+        "{}",
+        String::from_utf8_lossy(&listing.stderr)
+    );
+
+    let current = String::from_utf8(listing.stdout).unwrap();
+    let (current, golden): (BTreeSet<&str>, BTreeSet<&str>) =
+        (current.lines().collect(), GOLDEN.lines().collect());
+    let added: Vec<_> = current.difference(&golden).collect();
+    let removed: Vec<_> = golden.difference(&current).collect();
+    assert!(
+        added.is_empty() && removed.is_empty(),
+        "public API changed; run `python3 tools/public_api.py --bless` if intended\n\
+         added:\n  {}\nremoved:\n  {}",
+        added
+            .iter()
+            .map(|line| **line)
+            .collect::<Vec<_>>()
+            .join("\n  "),
+        removed
+            .iter()
+            .map(|line| **line)
+            .collect::<Vec<_>>()
+            .join("\n  ")
+    );
+}
+
+#[test]
+fn test_snapshot_records_stability_markers() {
+    for line in [
+        "pub trait symb_anafis::ToParamName (sealed)",
+        "pub trait symb_anafis::ArcExprExt (sealed)",
+        "#[non_exhaustive] pub enum symb_anafis::DiffError",
+        "#[non_exhaustive] pub enum symb_anafis::CodeGenLanguage",
+        "#[non_exhaustive] pub enum symb_anafis::ExprView<'expr>",
+    ] {
+        assert!(GOLDEN.lines().any(|golden| golden == line), "{line}");
+    }
+    // Experimental APIs are outside the default build, so outside the snapshot
+    for experimental in ["uncertainty_propagation", "CovarianceMatrix"] {
+        assert!(!GOLDEN.contains(experimental), "{experimental}");
+    }
+}
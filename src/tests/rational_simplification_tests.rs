@@ -0,0 +1,103 @@
+//! Rational functions of one symbol: `Polynomial::gcd` and the rule that divides it out
+
+use crate::core::Polynomial;
+use crate::{Expr, RuleUsageReport, Simplify, parse, symb};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Simplify `input`, returning the result and how often the rule fired
+fn simplify_counting(simplify: &Simplify, input: &str) -> (String, usize) {
+    let mut report = RuleUsageReport::new();
+    let result = simplify
+        .simplify_recording(&parse_str(input), &mut report)
+        .unwrap();
+    let fires = report.get("rational_simplification").unwrap().fires;
+    (result.to_string(), fires)
+}
+
+fn poly_in_x(input: &str) -> Polynomial {
+    let x = Arc::new(Expr::from(symb("x")));
+    Polynomial::expand_in(&parse_str(input), &x, 32).unwrap()
+}
+
+#[test]
+fn test_gcd_of_primitive_parts() {
+    // Integer content is divided out first; the GCD is monic
+    let gcd = poly_in_x("6*x^2 - 6").gcd(&poly_in_x("4*x - 4")).unwrap();
+    assert_eq!(gcd.terms(), &[(0, -1.0), (1, 1.0)]);
+
+    let gcd = poly_in_x("(x + 1)^3")
+        .gcd(&poly_in_x("x^2 + 2*x + 1"))
+        .unwrap();
+    assert_eq!(gcd.terms(), &[(0, 1.0), (1, 2.0), (2, 1.0)]);
+
+    let gcd = poly_in_x("0.5*x^2 - 0.5")
+        .gcd(&poly_in_x("x^2/3 + x/3"))
+        .unwrap();
+    assert_eq!(gcd.terms(), &[(0, 1.0), (1, 1.0)]);
+
+    // Coprime polynomials have a constant GCD
+    let gcd = poly_in_x("x^2 + 1").gcd(&poly_in_x("x + 1")).unwrap();
+    assert!(gcd.is_constant());
+}
+
+#[test]
+fn test_expand_in_rejects_other_symbols_and_functions() {
+    let x = Arc::new(Expr::from(symb("x")));
+    for input in ["x*y + 1", "sin(x) + x", "x^0.5", "x/(x + 1)", "x^40"] {
+        assert!(
+            Polynomial::expand_in(&parse_str(input), &x, 32).is_none(),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_common_factors_are_divided_out() {
+    let simplify = Simplify::new();
+    for input in [
+        "(x^2 - 1)/(x - 1)",
+        "(x^3 + 3*x^2 + 3*x + 1)/(x^2 + 2*x + 1)",
+        "(x^3 + 3*x^2 + 3*x + 1)/(x + 1)^2",
+    ] {
+        let (result, fires) = simplify_counting(&simplify, input);
+        assert_eq!(result, "1 + x", "{input}");
+        assert!(fires > 0, "{input}");
+    }
+    assert_eq!(
+        simplify_counting(&simplify, "(t^3 - 1)/(t^2 - 1)").0,
+        "(1 + (t + t^2))/(1 + t)"
+    );
+    assert_eq!(
+        simplify_counting(&simplify, "(2*x^2 - 2)/(4*x - 4)").0,
+        "(1 + x)/2"
+    );
+}
+
+#[test]
+fn test_non_polynomial_divisions_are_untouched() {
+    let simplify = Simplify::new();
+    for input in [
+        "sin(x)/(x - 1)",
+        "(x^2 - 1)/(x - y)",
+        "exp(x)/(exp(x) + 1)",
+        "(x^2 + 1)/(x + 1)",
+    ] {
+        assert_eq!(simplify_counting(&simplify, input).1, 0, "{input}");
+    }
+    assert_eq!(
+        simplify_counting(&simplify, "(x^2 + 1)/(x + 1)").0,
+        "(1 + x^2)/(1 + x)"
+    );
+}
+
+#[test]
+fn test_domain_safe_mode_skips_the_rule() {
+    let safe = Simplify::new().domain_safe(true);
+    let (_, fires) = simplify_counting(&safe, "(x^3 + 3*x^2 + 3*x + 1)/(x + 1)^2");
+    assert_eq!(fires, 0);
+}
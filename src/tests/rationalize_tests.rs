@@ -0,0 +1,69 @@
+//! Tests for `Simplify::rationalize`: single radicals, conjugates and what is declined
+
+use crate::{Simplify, simplify};
+
+fn rationalized(input: &str) -> String {
+    Simplify::new()
+        .rationalize(true)
+        .simplify_str(input, &[])
+        .unwrap()
+}
+
+#[test]
+fn test_single_radical() {
+    assert_eq!(rationalized("1/sqrt(x)"), "sqrt(x)/x");
+    assert_eq!(rationalized("3/(2*sqrt(x))"), "3*sqrt(x)/(2*x)");
+}
+
+#[test]
+fn test_numeric_conjugate() {
+    assert_eq!(rationalized("1/(sqrt(3) - 1)"), "(1 + sqrt(3))/2");
+    assert_eq!(rationalized("1/(1 + sqrt(2))"), "-1 + sqrt(2)");
+    assert_eq!(rationalized("1/(1 - sqrt(2))"), "-(1 + sqrt(2))");
+}
+
+#[test]
+fn test_symbolic_conjugate() {
+    // Compared through simplify so the canonical order of x and y does not matter
+    assert_eq!(
+        rationalized("a/(sqrt(x) - sqrt(y))"),
+        simplify("a*(sqrt(x) + sqrt(y))/(x - y)", &[], None).unwrap()
+    );
+}
+
+#[test]
+fn test_conjugate_enables_cancellation() {
+    assert_eq!(
+        rationalized("(x - y)/(sqrt(x) - sqrt(y))"),
+        simplify("sqrt(x) + sqrt(y)", &[], None).unwrap()
+    );
+}
+
+#[test]
+fn test_nested_denominator() {
+    assert_eq!(rationalized("sin(1/sqrt(x))"), "sin(sqrt(x)/x)");
+}
+
+#[test]
+fn test_complex_denominators_are_declined() {
+    for input in [
+        "1/(1 + sqrt(x) + sqrt(y))",
+        "1/cbrt(x)",
+        "1/sqrt(1 + sqrt(x))",
+    ] {
+        assert_eq!(
+            rationalized(input),
+            simplify(input, &[], None).unwrap(),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_flag_off_leaves_radicals() {
+    for input in ["1/sqrt(x)", "1/(sqrt(3) - 1)", "a/(sqrt(x) - sqrt(y))"] {
+        let out = Simplify::new().simplify_str(input, &[]).unwrap();
+        let (_, den) = out.split_once('/').expect("still a quotient");
+        assert!(den.contains("sqrt"), "{input} -> {out}");
+    }
+}
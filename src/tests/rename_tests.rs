@@ -0,0 +1,164 @@
+//! Tests for `rename_symbols`: up-front validation, occurrence counts and fingerprints
+
+use crate::{DiffError, Expr, parse, rename_symbols, rename_symbols_dry_run};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// A small model whose temperature symbol is called `t`
+fn model_with(t: &str) -> Vec<Expr> {
+    vec![
+        parse_str(&format!("p*V - n*R*{t}")),
+        Expr::derivative(parse_str(&format!("sin({t})*exp({t}^2)")), t, 2),
+        parse_str("n + R"),
+    ]
+}
+
+fn model() -> Vec<Expr> {
+    model_with("T")
+}
+
+#[test]
+fn test_rename_updates_every_occurrence() {
+    let mut exprs = model();
+    let report = rename_symbols(&mut exprs, &[("T", "T_gas")]).unwrap();
+
+    // The derivative variable counts once next to the two symbols in its body
+    assert_eq!(report.changes(), &[1, 3, 0]);
+    assert_eq!(report.affected().collect::<Vec<_>>(), [0, 1]);
+    assert_eq!(report.total(), 4);
+
+    assert_eq!(exprs, model_with("T_gas"));
+    assert!(exprs.iter().all(|expr| !expr.contains_var("T")));
+}
+
+#[test]
+fn test_rename_leaves_function_names_alone() {
+    let mut exprs = vec![parse_str("sin(sin_x) + sin_x")];
+    rename_symbols(&mut exprs, &[("sin", "s"), ("sin_x", "u")]).unwrap();
+    assert_eq!(exprs[0], parse_str("sin(u) + u"));
+}
+
+#[test]
+fn test_rename_shares_untouched_expressions() {
+    let mut exprs = model();
+    let untouched = exprs[2].id;
+    rename_symbols(&mut exprs, &[("T", "T_gas")]).unwrap();
+    assert_eq!(exprs[2].id, untouched);
+}
+
+#[test]
+fn test_collision_errors_before_touching_anything() {
+    let mut exprs = model();
+    let before = exprs.clone();
+    // The first pair is fine on its own; the second collides with the existing R
+    let err = rename_symbols(&mut exprs, &[("p", "pressure"), ("T", "R")]).unwrap_err();
+    assert!(matches!(
+        &err,
+        DiffError::InvalidRename { from, reason } if from == "T" && reason.contains("'R'")
+    ));
+    assert_eq!(exprs, before);
+}
+
+#[test]
+fn test_inconsistent_mappings_are_refused() {
+    let exprs = model();
+    let refused = |mapping: &[(&str, &str)]| {
+        matches!(
+            rename_symbols_dry_run(&exprs, mapping),
+            Err(DiffError::InvalidRename { .. })
+        )
+    };
+    // Chains and swaps have no unambiguous order
+    assert!(refused(&[("p", "q"), ("q", "r")]));
+    assert!(refused(&[("p", "V"), ("V", "p")]));
+    // Two symbols merged into one name
+    assert!(refused(&[("p", "q"), ("V", "q")]));
+    // One symbol mapped twice
+    assert!(refused(&[("p", "q"), ("p", "r")]));
+    assert!(refused(&[("p", "")]));
+
+    // Identity pairs and symbols missing from the set are harmless
+    let report = rename_symbols_dry_run(&exprs, &[("p", "p"), ("missing", "m")]).unwrap();
+    assert_eq!(report.total(), 0);
+}
+
+#[test]
+fn test_builtin_names_are_refused() {
+    let mut exprs = vec![parse_str("z*x"), parse_str("sin(z)")];
+    let before = exprs.clone();
+    for (to, what) in [
+        ("pi", "constant"),
+        ("e", "constant"),
+        ("tau", "constant"),
+        ("sin", "function"),
+        ("exp", "function"),
+    ] {
+        let err = rename_symbols(&mut exprs, &[("z", to)]).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                DiffError::InvalidRename { from, reason }
+                    if from == "z" && reason.contains(&format!("built-in {what}"))
+            ),
+            "{to}: {err:?}"
+        );
+        assert_eq!(exprs, before);
+    }
+}
+
+#[test]
+fn test_dry_run_matches_rename_without_modifying() {
+    let mut exprs = model();
+    let before = exprs.clone();
+    let preview = rename_symbols_dry_run(&exprs, &[("n", "moles"), ("R", "R_u")]).unwrap();
+    assert_eq!(exprs, before);
+    assert_eq!(preview.affected().collect::<Vec<_>>(), [0, 2]);
+
+    let report = rename_symbols(&mut exprs, &[("n", "moles"), ("R", "R_u")]).unwrap();
+    assert_eq!(report, preview);
+}
+
+#[test]
+fn test_fingerprints_follow_the_renamed_form() {
+    let mut exprs = model();
+    let before: Vec<u64> = exprs.iter().map(Expr::structural_hash).collect();
+    rename_symbols(&mut exprs, &[("T", "T_gas")]).unwrap();
+
+    // Renamed expressions hash as if they had been built with the new name
+    let built: Vec<u64> = model_with("T_gas")
+        .iter()
+        .map(Expr::structural_hash)
+        .collect();
+    assert_eq!(
+        exprs.iter().map(Expr::structural_hash).collect::<Vec<_>>(),
+        built
+    );
+    assert_ne!(exprs[0].structural_hash(), before[0]);
+    assert_ne!(exprs[1].structural_hash(), before[1]);
+    assert_eq!(exprs[2].structural_hash(), before[2]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialized_form_matches_the_renamed_model() {
+    let to_json = |exprs: &[Expr]| -> Vec<String> {
+        exprs
+            .iter()
+            .map(|expr| serde_json::to_string(expr).unwrap())
+            .collect()
+    };
+    let mut exprs = model();
+    let before = to_json(&exprs);
+    rename_symbols(&mut exprs, &[("T", "T_gas")]).unwrap();
+    let after = to_json(&exprs);
+
+    // Terms are re-sorted as for a fresh build, so the documents match the renamed
+    // model exactly; expressions without the symbol serialize unchanged
+    assert_eq!(after, to_json(&model_with("T_gas")));
+    assert_eq!(after[2], before[2]);
+    assert!(after[1].contains("\"var\":\"T_gas\""));
+    assert!(after.iter().all(|json| !json.contains("\"T\"")));
+}
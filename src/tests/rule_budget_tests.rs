@@ -0,0 +1,74 @@
+//! Tests for the per-node rewrite budget of the simplification engine
+
+use crate::{CompiledEvaluator, Expr, Simplify, parse};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Deep alternating sum/quotient tower sharing its subterms at every level.
+fn fraction_tower(depth: usize) -> String {
+    (0..depth).fold("x".to_owned(), |e, _| {
+        format!("(({e})*x + 1)/(({e})*y + 1) + 1/(({e}) + x)")
+    })
+}
+
+/// Assert `a` and `b` agree (to a relative tolerance) at a few points in (x, y).
+fn assert_same_function(a: &Expr, b: &Expr) {
+    let ca = CompiledEvaluator::compile(a, &["x", "y"], None).unwrap();
+    let cb = CompiledEvaluator::compile(b, &["x", "y"], None).unwrap();
+    for point in [[0.3, 1.7], [1.1, 0.4], [2.5, 3.0]] {
+        let (va, vb) = (ca.evaluate(&point), cb.evaluate(&point));
+        assert!(
+            (va - vb).abs() <= 1e-9 * va.abs().max(1.0),
+            "{va} != {vb} at {point:?}"
+        );
+    }
+}
+
+#[test]
+fn test_default_budget_leaves_normal_inputs_unchanged() {
+    let inputs = [
+        "x + x + x",
+        "sin(x)^2 + cos(x)^2",
+        "(x^2 - 1)/(x - 1)",
+        "x^2/(x*y)",
+        "a/b + c/d",
+        "(x + 1)^2 * (x + 1)^3",
+        "exp(ln(x)) + ln(exp(y))",
+        "1/(1/x + 1/y)",
+        "2*sin(x)*cos(x) + x*y/y",
+        "sqrt(x^2)*sqrt(y)^2",
+    ];
+    for input in inputs {
+        let expr = parse_plain(input);
+        let default = Simplify::new().simplify(&expr).unwrap();
+        let unlimited = Simplify::new()
+            .node_budget(usize::MAX)
+            .simplify(&expr)
+            .unwrap();
+        assert_eq!(default.to_string(), unlimited.to_string(), "{input}");
+    }
+}
+
+#[test]
+fn test_tight_budget_result_is_still_correct() {
+    for input in [
+        "1/(1/x + 1/y)",
+        "(x*y + x)/(x*(y + 1))",
+        "x/y + y/x - (x^2 + y^2)/(x*y)",
+    ] {
+        let expr = parse_plain(input);
+        let result = Simplify::new().node_budget(1).simplify(&expr).unwrap();
+        assert_same_function(&expr, &result);
+    }
+}
+
+#[test]
+fn test_fraction_tower_stays_bounded() {
+    // Regression fixture for AddFraction/DivDiv/FractionCancellation interplay paths
+    let expr = parse_plain(&fraction_tower(5));
+    let result = Simplify::new().node_budget(2).simplify(&expr).unwrap();
+    assert_same_function(&expr, &result);
+}
@@ -0,0 +1,98 @@
+//! Selecting simplification rules by name and category on the `Simplify` builder
+
+use crate::{DiffError, RuleCategory, Simplify};
+
+fn simplify(builder: &Simplify, formula: &str) -> String {
+    builder.simplify_str(formula, &["y"]).unwrap()
+}
+
+#[test]
+fn test_list_rules_reports_metadata() {
+    let rules = Simplify::list_rules();
+    let (_, category, _, alters_domain) = *rules
+        .iter()
+        .find(|&&(name, ..)| name == "e_pow_ln")
+        .unwrap();
+    assert_eq!(category, RuleCategory::Algebraic);
+    assert!(alters_domain);
+    assert!(
+        rules.windows(2).all(|pair| pair[0].2 >= pair[1].2),
+        "rules are listed in priority order"
+    );
+    assert_eq!(rules.last().unwrap().0, "prettify_roots");
+}
+
+#[test]
+fn test_disable_rule_keeps_exp_of_ln() {
+    assert_eq!(simplify(&Simplify::new(), "exp(ln(x))"), "x");
+    let builder = Simplify::new().disable_rule("e_pow_ln");
+    assert_eq!(simplify(&builder, "exp(ln(x))"), "exp(ln(x))");
+    // Other rules still run
+    assert_eq!(simplify(&builder, "x + x"), "2*x");
+}
+
+#[test]
+fn test_disable_rule_keeps_fractional_powers_and_factors() {
+    let builder = Simplify::new().disable_rule("prettify_roots");
+    assert_eq!(simplify(&builder, "x^0.5"), "x^0.5");
+    assert_eq!(simplify(&Simplify::new(), "x^0.5"), "sqrt(x)");
+
+    let builder = Simplify::new().disable_rule("factor_difference_of_squares");
+    assert_eq!(simplify(&builder, "x^2 - y^2"), "x^2 - y^2");
+}
+
+#[test]
+fn test_unknown_rule_is_an_error() {
+    let err = Simplify::new()
+        .disable_rule("power_to_sqrt")
+        .simplify_str("x", &[])
+        .unwrap_err();
+    assert!(matches!(err, DiffError::UnknownRule { ref name } if name == "power_to_sqrt"));
+}
+
+#[test]
+fn test_disable_category_skips_all_its_rules() {
+    let formula = "(exp(x) - exp(-x))/2";
+    assert_eq!(simplify(&Simplify::new(), formula), "sinh(x)");
+    let builder = Simplify::new().disable_category(RuleCategory::Hyperbolic);
+    assert!(!simplify(&builder, formula).contains("sinh"));
+
+    let builder = Simplify::new().disable_category(RuleCategory::Root);
+    assert_eq!(simplify(&builder, "x^(1/2)"), "x^(1/2)");
+}
+
+#[test]
+fn test_only_categories() {
+    let builder = Simplify::new().only_categories(&[RuleCategory::Numeric]);
+    assert_eq!(
+        simplify(&builder, "sin(x)^2 + cos(x)^2 + 2 + 3"),
+        "5 + sin(x)^2 + cos(x)^2"
+    );
+    // A disabled category stays disabled when it is also selected
+    let builder = Simplify::new()
+        .only_categories(&[RuleCategory::Trigonometric])
+        .disable_category(RuleCategory::Trigonometric);
+    assert_eq!(
+        simplify(&builder, "sin(x)^2 + cos(x)^2"),
+        "sin(x)^2 + cos(x)^2"
+    );
+}
+
+#[test]
+fn test_rule_selection_with_domain_safe() {
+    // Domain-altering rules stay off under domain_safe whatever else is selected
+    let builder = Simplify::new()
+        .domain_safe(true)
+        .disable_rule("factor_difference_of_squares");
+    assert_eq!(simplify(&builder, "exp(ln(x))"), "exp(ln(x))");
+    assert_eq!(simplify(&builder, "x^2 - y^2"), "x^2 - y^2");
+    assert_eq!(simplify(&builder, "x + x"), "2*x");
+}
+
+#[test]
+fn test_without_category_keeps_other_categories() {
+    let builder = Simplify::new().without_category(RuleCategory::Algebraic);
+    // e_pow_ln is algebraic, ln_e_pow_identity is exponential
+    assert_eq!(simplify(&builder, "exp(ln(x))"), "exp(ln(x))");
+    assert_eq!(simplify(&builder, "ln(e^x)"), "x");
+}
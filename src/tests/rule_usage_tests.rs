@@ -0,0 +1,161 @@
+//! `RuleUsageReport`: per-rule fire counts, and the corpus report that finds dead rules
+//!
+//! `cargo test corpus_rule_usage_report -- --ignored` writes the report over the fixture
+//! corpus and the generated expressions of the property tests to
+//! `target/rule_usage_report.json`.
+
+use super::corpus_tests::corpus_inputs;
+use super::property_tests::random_expr_string;
+use crate::{Diff, Expr, RuleUsageReport, Simplify, parse, symb};
+use quickcheck::Gen;
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn record_all(inputs: &[&str]) -> RuleUsageReport {
+    let mut report = RuleUsageReport::new();
+    let simplify = Simplify::new();
+    for input in inputs {
+        simplify
+            .simplify_recording(&parse_str(input), &mut report)
+            .unwrap();
+    }
+    report
+}
+
+#[test]
+fn test_report_lists_every_registered_rule_once() {
+    let report = RuleUsageReport::new();
+    let listed: HashSet<&str> = Simplify::list_rules()
+        .into_iter()
+        .map(|(name, _, _, _)| name)
+        .collect();
+    assert_eq!(report.rules().len(), listed.len());
+    for pair in report.rules().windows(2) {
+        assert!(pair[0].name < pair[1].name, "{} out of order", pair[1].name);
+    }
+    for rule in report.rules() {
+        assert!(listed.contains(rule.name));
+        assert_eq!(rule.fires, 0);
+    }
+    assert_eq!(report.expressions(), 0);
+    assert_eq!(report.unused().count(), listed.len());
+}
+
+#[test]
+fn test_rules_with_dedicated_tests_fire() {
+    let report = record_all(&[
+        "sin(x)^2 + cos(x)^2",
+        "cosh(x)^2 - sinh(x)^2",
+        "sqrt(x^2)",
+        "ln(x)^3/ln(x)",
+        "exp(ln(x))",
+        "x^(1/2)",
+    ]);
+    assert_eq!(report.expressions(), 6);
+    for name in [
+        "pythagorean_identity",
+        "hyperbolic_identity",
+        "sqrt_power",
+        "fraction_cancellation",
+        "e_pow_ln",
+        "prettify_roots",
+    ] {
+        let rule = report.get(name).unwrap();
+        assert!(rule.fires > 0, "{name} never fired");
+        assert!(rule.mean_node_delta() < 0.0, "{name} grew what it rewrote");
+    }
+    assert_eq!(report.get("pythagorean_identity").unwrap().fires, 1);
+    assert_eq!(report.rules().len(), RuleUsageReport::new().rules().len());
+}
+
+#[test]
+fn test_counts_accumulate_and_match_plain_simplify() {
+    let simplify = Simplify::new();
+    let expr = parse_str("sin(x)^2 + cos(x)^2 + x^2/x");
+    let mut report = RuleUsageReport::new();
+    let first = simplify.simplify_recording(&expr, &mut report).unwrap();
+    let second = simplify.simplify_recording(&expr, &mut report).unwrap();
+    assert_eq!(first, simplify.simplify(&expr).unwrap());
+    assert_eq!(first, second);
+    assert_eq!(report.expressions(), 2);
+    assert_eq!(report.get("pythagorean_identity").unwrap().fires, 2);
+}
+
+#[test]
+fn test_disabled_rule_never_fires() {
+    let simplify = Simplify::new().disable_rule("pythagorean_identity");
+    let mut report = RuleUsageReport::new();
+    simplify
+        .simplify_recording(&parse_str("sin(x)^2 + cos(x)^2"), &mut report)
+        .unwrap();
+    assert_eq!(report.get("pythagorean_identity").unwrap().fires, 0);
+}
+
+#[test]
+fn test_display_is_json() {
+    let report = record_all(&["sin(x)^2 + cos(x)^2"]);
+    let json: serde_json::Value = serde_json::from_str(&report.to_string()).unwrap();
+    assert_eq!(json["expressions"], 1);
+    let rules = json["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), report.rules().len());
+    for (entry, rule) in rules.iter().zip(report.rules()) {
+        assert_eq!(entry["name"], rule.name);
+        assert_eq!(entry["fires"], rule.fires);
+    }
+    let identity = rules
+        .iter()
+        .find(|entry| entry["name"] == "pythagorean_identity")
+        .unwrap();
+    assert_eq!(identity["category"], "trigonometric");
+    assert_eq!(identity["mean_node_delta"], -8.0);
+
+    // The same inputs give the same report
+    assert_eq!(
+        record_all(&["sin(x)^2 + cos(x)^2"]).to_string(),
+        report.to_string()
+    );
+}
+
+#[test]
+#[ignore = "Runs the whole corpus; writes target/rule_usage_report.json"]
+fn corpus_rule_usage_report() {
+    let simplify = Simplify::new();
+    let mut report = RuleUsageReport::new();
+    for (input, known) in corpus_inputs() {
+        let known: HashSet<String> = known.into_iter().collect();
+        let expr = parse(&input, &known, &HashSet::new(), None).unwrap();
+        simplify.simplify_recording(&expr, &mut report).unwrap();
+        // The unsimplified derivatives reach the rules that tidy up differentiation
+        for var in expr.variables() {
+            if known.contains(&var) {
+                continue;
+            }
+            let raw = Diff::new()
+                .skip_simplification(true)
+                .differentiate(&expr, &symb(&var))
+                .unwrap();
+            simplify.simplify_recording(&raw, &mut report).unwrap();
+        }
+    }
+    // A fixed seed keeps the generated expressions, and so the report, stable
+    let mut generator = Gen::from_size_and_seed(4, 0x5eed);
+    for _ in 0..500 {
+        if let Ok(expr) = parse(
+            &random_expr_string(&mut generator),
+            &HashSet::new(),
+            &HashSet::new(),
+            None,
+        ) {
+            // Generated inputs may exceed the simplifier's limits
+            simplify.simplify_recording(&expr, &mut report).ok();
+        }
+    }
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/rule_usage_report.json");
+    std::fs::write(path, report.to_string()).unwrap();
+    assert!(report.expressions() > 0);
+    assert!(report.rules().iter().any(|rule| rule.fires > 0));
+}
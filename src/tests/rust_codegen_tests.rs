@@ -0,0 +1,193 @@
+//! Tests for `Expr::to_rust_code` and `to_rust_code_multi`: generated functions compiled
+//! with rustc and compared against the compiled evaluator, and the shape of the output
+
+use crate::{
+    CodeGenLanguage, CompiledEvaluator, Diff, DiffError, Expr, parse, symb, to_rust_code_multi,
+};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::process::Command;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+const FORMULAS: &[&str] = &[
+    "x^3*sin(y) - 2*exp(-x*y)/(1 + y^2)",
+    "sqrt(x^2 + y^2) + atan2(y, x) - acot(x) + hypot(x, 2*y)",
+    "sec(x) + log10(y) + cbrt(x - y) + x^(-5) + acoth(y + 2)",
+    "asinh(x) + acosh(y + 2) + atanh(x/4) + log2(y) + log(y, 7) + x^2.5",
+    "floor(x*y) + ceil(y) + round(x) + signum(x - y) + abs(x) * tanh(y)",
+    "sind(x*30) + acosd(y/4) + pi*e^x - cos(x*y)^2 + cos(x*y) + tau/y",
+    "(x - y)^7 + (x + y)^(x/3) + 2^y + y^1e10 * 0",
+];
+
+const POINTS: [(f64, f64); 4] = [(0.7, 1.3), (1.9, 0.4), (2.5, 3.25), (0.1, 2.0)];
+
+/// Compile `functions` with a `main` printing each line of `calls`, and run it.
+///
+/// Returns `None` if rustc is not available.
+fn run_rust(functions: &str, calls: &[String]) -> Option<Vec<f64>> {
+    Command::new("rustc").arg("--version").output().ok()?;
+    let mut source = functions.to_owned();
+    source.push_str("\nfn main() {\n");
+    for call in calls {
+        writeln!(source, "    println!(\"{{:?}}\", {call});").unwrap();
+    }
+    source.push_str("}\n");
+
+    let dir = std::env::temp_dir().join(format!(
+        "symb_anafis_to_rust_{}_{}",
+        std::process::id(),
+        calls.len()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (src, bin) = (dir.join("generated.rs"), dir.join("generated"));
+    std::fs::write(&src, &source).unwrap();
+    let build = Command::new("rustc")
+        .args(["--edition", "2024", "-D", "warnings", "-o"])
+        .arg(&bin)
+        .arg(&src)
+        .output()
+        .unwrap();
+    assert!(
+        build.status.success(),
+        "{}\n{source}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+    let run = Command::new(&bin).output().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(run.status.success());
+    Some(
+        String::from_utf8(run.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.trim_matches(|c| c == '(' || c == ')' || c == ','))
+            .flat_map(|line| {
+                line.split(", ")
+                    .map(|v| v.parse().unwrap())
+                    .collect::<Vec<f64>>()
+            })
+            .collect(),
+    )
+}
+
+fn assert_close(got: f64, expected: f64, what: &str) {
+    assert!(
+        (got - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+        "{what}: Rust gives {got}, evaluator {expected}"
+    );
+}
+
+#[test]
+fn test_rust_compiles_and_matches_evaluator() {
+    let mut functions = String::new();
+    let mut calls = Vec::new();
+    for (i, formula) in FORMULAS.iter().enumerate() {
+        let code = parse_plain(formula)
+            .to_rust_code(&format!("f{i}"), &["x", "y"], None)
+            .unwrap();
+        functions.push_str(&code);
+        for (x, y) in POINTS {
+            calls.push(format!("f{i}({x:?}, {y:?})"));
+        }
+    }
+    let Some(printed) = run_rust(&functions, &calls) else {
+        eprintln!("skipping: no rustc on PATH");
+        return;
+    };
+
+    let mut values = printed.into_iter();
+    for formula in FORMULAS {
+        let eval = CompiledEvaluator::compile(&parse_plain(formula), &["x", "y"], None).unwrap();
+        for (x, y) in POINTS {
+            let got = values.next().unwrap();
+            assert_close(
+                got,
+                eval.evaluate(&[x, y]),
+                &format!("{formula} at ({x}, {y})"),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_gradient_tuple_compiles_and_matches_evaluator() {
+    let (x, y) = (symb("x"), symb("y"));
+    let f = parse_plain("exp(-x*y) * sin(x + y) + (x + y)^3");
+    let diff = Diff::new();
+    let (dx, dy) = (
+        diff.differentiate(&f, &x).unwrap(),
+        diff.differentiate(&f, &y).unwrap(),
+    );
+    let code = to_rust_code_multi("grad", &[x, y], &[("dx", &dx), ("dy", &dy)], None).unwrap();
+    assert!(
+        code.starts_with("fn grad(x: f64, y: f64) -> (f64, f64) {\n"),
+        "{code}"
+    );
+    assert!(code.ends_with("    (dx, dy)\n}\n"), "{code}");
+    // Shared subexpressions of the two components are bound once
+    assert_eq!(code.matches("(x * y).exp()").count(), 1, "{code}");
+    assert_eq!(code.matches("let t").count(), 5, "{code}");
+
+    let calls: Vec<String> = POINTS
+        .iter()
+        .map(|(x, y)| format!("grad({x:?}, {y:?})"))
+        .collect();
+    let Some(printed) = run_rust(&code, &calls) else {
+        eprintln!("skipping: no rustc on PATH");
+        return;
+    };
+    let eval_dx = CompiledEvaluator::compile(&dx, &["x", "y"], None).unwrap();
+    let eval_dy = CompiledEvaluator::compile(&dy, &["x", "y"], None).unwrap();
+    for ((px, py), pair) in POINTS.iter().zip(printed.chunks(2)) {
+        assert_close(pair[0], eval_dx.evaluate(&[*px, *py]), "d/dx");
+        assert_close(pair[1], eval_dy.evaluate(&[*px, *py]), "d/dy");
+    }
+}
+
+#[test]
+fn test_powers_constants_and_receivers() {
+    let code = parse_plain("x^2 + x^(-3)*y + y^0.5 + (x + y)^2.5 + sin(x - y) + pi*x")
+        .to_rust_code("f", &["x", "y"], None)
+        .unwrap();
+    for part in [
+        "x.powi(2)",
+        "y / x.powi(3)",
+        "y.sqrt()",
+        "(x + y).powf(2.5)",
+        "(x - y).sin()",
+        "core::f64::consts::PI * x",
+    ] {
+        assert!(code.contains(part), "{part} in {code}");
+    }
+
+    // A literal receiver cannot infer f64, so it is passed to the function instead
+    let code = parse_plain("2^x").to_rust_code("f", &["x"], None).unwrap();
+    assert!(code.contains("f64::powf(2.0, x)"), "{code}");
+    assert_eq!(
+        parse_plain("x + y").to_code(CodeGenLanguage::Rust).unwrap(),
+        "fn f(x: f64, y: f64) -> f64 {\n    x + y\n}\n"
+    );
+}
+
+#[test]
+fn test_invalid_outputs_rejected() {
+    let x = parse_plain("x");
+    let Err(DiffError::UnsupportedExpression(msg)) = to_rust_code_multi(
+        "f",
+        &["x"],
+        &[("x", &x), ("fn", &x), ("a", &x), ("a", &x)],
+        None,
+    ) else {
+        panic!("expected UnsupportedExpression");
+    };
+    assert!(msg.contains("output 'x' is declared twice"), "{msg}");
+    assert!(msg.contains("output 'fn' is reserved in Rust"), "{msg}");
+    assert!(msg.contains("output 'a' is declared twice"), "{msg}");
+
+    assert!(matches!(
+        to_rust_code_multi("f", &["x"], &[], None),
+        Err(DiffError::UnsupportedExpression(msg)) if msg.contains("at least one output")
+    ));
+}
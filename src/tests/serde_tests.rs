@@ -0,0 +1,175 @@
+//! JSON round-trips of expressions through the `serde` feature
+
+use crate::core::{ExprKind, Polynomial};
+use crate::{CompiledEvaluator, Expr, Symbol, parse, remove_symbol, symb, symbol_exists};
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn round_trip(expr: &Expr) -> Expr {
+    let json = serde_json::to_string(expr).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn assert_same_values(original: &Expr, restored: &Expr, params: &[&str]) {
+    let a = CompiledEvaluator::compile(original, params, None).unwrap();
+    let b = CompiledEvaluator::compile(restored, params, None).unwrap();
+    for point in [[0.3, 1.7], [-1.2, 0.4], [2.5, -0.8]] {
+        let point = &point[..params.len()];
+        let (va, vb) = (a.evaluate(point), b.evaluate(point));
+        assert!(
+            va.to_bits() == vb.to_bits() || (va.is_nan() && vb.is_nan()),
+            "{original} at {point:?}: {va} != {vb}"
+        );
+    }
+}
+
+#[test]
+fn test_round_trip_every_kind() {
+    let x = symb("x");
+    let poly = Polynomial::try_from_expr(&parse_str("3*x^2 + 2*x + 1")).unwrap();
+    let cases = [
+        Expr::number(2.5),
+        x.to_expr(),
+        x.sin(),
+        parse_str("x + y"),
+        parse_str("x * y"),
+        parse_str("x / (y + 1)"),
+        parse_str("x ^ y"),
+        Expr::poly(poly),
+        parse_str("piecewise(x < 0: -x, x >= y: x^2, y)"),
+    ];
+    let kinds = [
+        |k: &ExprKind| matches!(k, ExprKind::Number(_)),
+        |k: &ExprKind| matches!(k, ExprKind::Symbol(_)),
+        |k: &ExprKind| matches!(k, ExprKind::FunctionCall { .. }),
+        |k: &ExprKind| matches!(k, ExprKind::Sum(_)),
+        |k: &ExprKind| matches!(k, ExprKind::Product(_)),
+        |k: &ExprKind| matches!(k, ExprKind::Div(..)),
+        |k: &ExprKind| matches!(k, ExprKind::Pow(..)),
+        |k: &ExprKind| matches!(k, ExprKind::Poly(_)),
+        |k: &ExprKind| matches!(k, ExprKind::Piecewise { .. }),
+    ];
+    for (expr, is_kind) in cases.iter().zip(kinds) {
+        assert!(is_kind(&expr.kind), "unexpected kind for {expr}");
+        let restored = round_trip(expr);
+        assert_eq!(&restored, expr);
+        assert_eq!(restored.structural_hash(), expr.structural_hash());
+        assert_same_values(expr, &restored, &["x", "y"]);
+    }
+}
+
+#[test]
+fn test_round_trip_derivative() {
+    let expr = Expr::derivative(parse_str("f(x) * y"), "x", 2);
+    assert!(matches!(expr.kind, ExprKind::Derivative { order: 2, .. }));
+    assert_eq!(round_trip(&expr), expr);
+}
+
+#[test]
+fn test_round_trip_five_levels_deep() {
+    let expr = parse_str("exp(sin(x * (y + cos(x / (1 + y^2))))) - 3");
+    assert!(expr.max_depth() >= 5);
+    let restored = round_trip(&expr);
+    assert_eq!(restored, expr);
+    assert_same_values(&expr, &restored, &["x", "y"]);
+}
+
+#[test]
+fn test_symbols_are_stored_by_name() {
+    let x = symb("serde_test_x");
+    let json = serde_json::to_string(&x.to_expr()).unwrap();
+    assert_eq!(json, r#"{"schema":1,"expr":{"Symbol":"serde_test_x"}}"#);
+
+    let sym: Symbol = serde_json::from_str(r#""serde_test_y""#).unwrap();
+    assert_eq!(sym, symb("serde_test_y"));
+    assert_eq!(serde_json::to_string(&sym).unwrap(), r#""serde_test_y""#);
+}
+
+#[test]
+fn test_anonymous_symbol_is_rejected() {
+    assert!(serde_json::to_string(&Symbol::anon()).is_err());
+    assert!(serde_json::to_string(&Symbol::anon().to_expr()).is_err());
+}
+
+#[test]
+fn test_polynomial_terms_are_normalized() {
+    let json =
+        r#"{"schema":1,"expr":{"Poly":{"base":{"Symbol":"x"},"terms":[[2,1.0],[0,4.0],[1,0.0]]}}}"#;
+    let expr: Expr = serde_json::from_str(json).unwrap();
+    let ExprKind::Poly(poly) = &expr.kind else {
+        panic!("expected a polynomial, got {expr}");
+    };
+    assert_eq!(poly.terms(), &[(0, 4.0), (2, 1.0)]);
+}
+
+#[test]
+fn test_schema_is_stable() {
+    let document = r#"{"schema":1,"expr":{"Div":[{"FunctionCall":{"name":"sin","args":[{"Symbol":"x"}]}},{"Derivative":{"inner":{"Symbol":"y"},"var":"x","order":1}}]}}"#;
+    let expr: Expr = serde_json::from_str(document).unwrap();
+    assert_eq!(
+        expr,
+        Expr::div_expr(
+            parse_str("sin(x)"),
+            Expr::derivative(parse_str("y"), "x", 1)
+        )
+    );
+    assert_eq!(serde_json::to_string(&expr).unwrap(), document);
+}
+
+#[test]
+fn test_unknown_schema_is_rejected() {
+    let future = r#"{"schema":2,"expr":{"Number":1.0}}"#;
+    let err = serde_json::from_str::<Expr>(future).unwrap_err();
+    assert!(
+        err.to_string().contains("unsupported expression schema 2"),
+        "{err}"
+    );
+    assert!(serde_json::from_str::<Expr>(r#"{"Number":1.0}"#).is_err());
+}
+
+/// `sin(sin(..sin(x)..))` with `depth` nodes on the path, built as a JSON value so that
+/// serde_json's own recursion limit for text does not apply
+fn nested_document(depth: usize) -> Value {
+    let mut node = json!({"Symbol": "x"});
+    for _ in 1..depth {
+        node = json!({"FunctionCall": {"name": "sin", "args": [node]}});
+    }
+    json!({"schema": 1, "expr": node})
+}
+
+#[test]
+fn test_deserialization_is_size_limited() {
+    let deepest: Expr = serde_json::from_value(nested_document(100)).unwrap();
+    assert_eq!(deepest.max_depth(), 100);
+    let err = serde_json::from_value::<Expr>(nested_document(101)).unwrap_err();
+    assert!(err.to_string().contains("deeper than 100"), "{err}");
+
+    let terms: Vec<Value> = (0..10_000).map(|i| json!({"Number": i})).collect();
+    let err =
+        serde_json::from_value::<Expr>(json!({"schema": 1, "expr": {"Sum": terms}})).unwrap_err();
+    assert!(err.to_string().contains("more than 10000 nodes"), "{err}");
+
+    // The limits are per document, so a failed read does not shrink the next one
+    assert!(serde_json::from_value::<Expr>(nested_document(100)).is_ok());
+}
+
+#[test]
+fn test_document_loads_into_fresh_symbol_table() {
+    let formula = "serde_fresh_f(serde_fresh_a) * exp(serde_fresh_a)";
+    let json = serde_json::to_string(&parse_str(formula)).unwrap();
+
+    // Forget the names and intern others first, as a new process would
+    assert!(remove_symbol("serde_fresh_a") && remove_symbol("serde_fresh_f"));
+    assert!(!symbol_exists("serde_fresh_a"));
+    let _ = (symb("serde_fresh_b"), symb("serde_fresh_c"));
+
+    let restored: Expr = serde_json::from_str(&json).unwrap();
+    let fresh = parse_str(formula);
+    assert_eq!(restored, fresh);
+    assert_eq!(restored.structural_hash(), fresh.structural_hash());
+    assert!(restored.contains_var_id(symb("serde_fresh_a").id()));
+}
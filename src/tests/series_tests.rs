@@ -0,0 +1,79 @@
+//! Tests for `Expr::taylor` and `Expr::maclaurin`
+
+use crate::{CompiledEvaluator, DiffError, Expr, parse, symb};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+/// Assert that `series` and `expected` agree at a few points around the expansion point
+fn assert_same_polynomial(series: &Expr, expected: &str, around: f64) {
+    let got = CompiledEvaluator::compile(series, &["x"], None).unwrap();
+    let want = CompiledEvaluator::compile(&parse_plain(expected), &["x"], None).unwrap();
+    for offset in [-0.7, -0.2, 0.3, 1.1] {
+        let x = around + offset;
+        let (g, w) = (got.evaluate(&[x]), want.evaluate(&[x]));
+        assert!(
+            (g - w).abs() <= 1e-12 * w.abs().max(1.0),
+            "{series} vs {expected} at {x}: {g} != {w}"
+        );
+    }
+}
+
+#[test]
+fn test_sin_maclaurin() {
+    let x = symb("x");
+    let series = parse_plain("sin(x)").maclaurin(&x, 7).unwrap();
+    assert_same_polynomial(&series, "x - x^3/6 + x^5/120 - x^7/5040", 0.0);
+    // Exact coefficients keep the factorial as a denominator
+    assert_eq!(series.to_string(), "x - x^7/5040 + x^5/120 - x^3/6");
+}
+
+#[test]
+fn test_ln_maclaurin() {
+    let series = parse_plain("ln(1 + x)").maclaurin(&symb("x"), 4).unwrap();
+    assert_same_polynomial(&series, "x - x^2/2 + x^3/3 - x^4/4", 0.0);
+    assert_eq!(series.to_string(), "x - x^4/4 + x^3/3 - x^2/2");
+}
+
+#[test]
+fn test_taylor_around_nonzero_point() {
+    let x = symb("x");
+    let series = parse_plain("exp(x)").taylor(&x, 1.0, 3).unwrap();
+    assert_same_polynomial(&series, "e*(1 + (x - 1) + (x - 1)^2/2 + (x - 1)^3/6)", 1.0);
+
+    // A polynomial is its own Taylor series once the order reaches its degree
+    let cubic = parse_plain("x^3 - 2*x + 5");
+    let series = cubic.taylor(&x, 2.0, 5).unwrap();
+    assert_same_polynomial(&series, "x^3 - 2*x + 5", 2.0);
+    assert_eq!(cubic.taylor(&x, 2.0, 0).unwrap(), Expr::number(9.0));
+}
+
+#[test]
+fn test_high_order() {
+    // Orders above 10 only warn
+    let series = parse_plain("cos(x)").maclaurin(&symb("x"), 12).unwrap();
+    assert_same_polynomial(
+        &series,
+        "1 - x^2/2 + x^4/24 - x^6/720 + x^8/40320 - x^10/3628800 + x^12/479001600",
+        0.0,
+    );
+}
+
+#[test]
+fn test_errors() {
+    let x = symb("x");
+    assert!(matches!(
+        parse_plain("ln(x)").maclaurin(&x, 2),
+        Err(DiffError::UnsupportedOperation(msg)) if msg.contains("derivative 0 is -inf")
+    ));
+    assert!(matches!(
+        parse_plain("a*x").maclaurin(&x, 2),
+        Err(DiffError::UnboundVariable(name)) if name.as_str() == "a"
+    ));
+    assert!(matches!(
+        parse_plain("x").taylor(&x, f64::INFINITY, 2),
+        Err(DiffError::UnsupportedOperation(_))
+    ));
+}
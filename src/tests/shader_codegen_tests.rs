@@ -0,0 +1,467 @@
+//! Tests for the WGSL/GLSL emitters: golden output, syntax sanity, and numeric agreement
+//! between the compiled evaluator and a CPU transcription of the emitted code
+
+use crate::{CompiledEvaluator, Context, DiffError, Expr, UserFunction, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+const XYZ: [&str; 3] = ["x", "y", "z"];
+
+/// Golden file name and the formula it was emitted from, over `x, y, z`
+const GOLDEN: &[(&str, &str)] = &[
+    ("torus", "(sqrt(x^2 + y^2) - 2)^2 + z^2 - 0.25"),
+    ("gyroid", "sin(x)*cos(y) + sin(y)*cos(z) + sin(z)*cos(x)"),
+    ("blob", "exp(-(x^2 + y^2 + z^2)) * (1 + x^2 + y^2 + z^2)"),
+    ("derived", "sec(x) + log10(y) + cbrt(z) + x^(-5) + acoth(y)"),
+    ("polar", "atan2(y, x) * z - round(x*y) + acot(z)"),
+];
+
+fn golden(name: &str, glsl: bool) -> &'static str {
+    match (name, glsl) {
+        ("torus", false) => include_str!("golden/torus.wgsl"),
+        ("torus", true) => include_str!("golden/torus.glsl"),
+        ("gyroid", false) => include_str!("golden/gyroid.wgsl"),
+        ("gyroid", true) => include_str!("golden/gyroid.glsl"),
+        ("blob", false) => include_str!("golden/blob.wgsl"),
+        ("blob", true) => include_str!("golden/blob.glsl"),
+        ("derived", false) => include_str!("golden/derived.wgsl"),
+        ("derived", true) => include_str!("golden/derived.glsl"),
+        ("polar", false) => include_str!("golden/polar.wgsl"),
+        ("polar", true) => include_str!("golden/polar.glsl"),
+        _ => unreachable!("no golden file for {name}"),
+    }
+}
+
+fn emit(name: &str, formula: &str, glsl: bool) -> String {
+    let expr = parse_plain(formula);
+    if glsl {
+        expr.to_glsl(name, &XYZ, None).unwrap()
+    } else {
+        expr.to_wgsl(name, &XYZ, None).unwrap()
+    }
+}
+
+// =============================================================================
+// CPU transcription of the emitted subset
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Num(f32),
+    Ident(String),
+    Punct(char),
+}
+
+fn tokenize(src: &str) -> Vec<Tok> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || chars[i] == 'e'
+                    || (chars[i] == '-' && chars[i - 1] == 'e'))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            out.push(Tok::Num(text.parse().unwrap()));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            out.push(Tok::Ident(chars[start..i].iter().collect()));
+        } else {
+            // `->` in the WGSL header is the only two-character operator
+            if c == '-' && chars.get(i + 1) == Some(&'>') {
+                out.push(Tok::Punct('>'));
+                i += 2;
+                continue;
+            }
+            out.push(Tok::Punct(c));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Evaluates `let`/`float` bindings and the final `return` of one emitted function.
+struct Interp<'a> {
+    toks: &'a [Tok],
+    pos: usize,
+    vars: HashMap<String, f32>,
+}
+
+impl Interp<'_> {
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos)
+    }
+
+    fn next(&mut self) -> Tok {
+        self.pos += 1;
+        self.toks[self.pos - 1].clone()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Tok::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> f32 {
+        let mut acc = self.term();
+        loop {
+            if self.eat('+') {
+                acc += self.term();
+            } else if self.eat('-') {
+                acc -= self.term();
+            } else {
+                return acc;
+            }
+        }
+    }
+
+    fn term(&mut self) -> f32 {
+        let mut acc = self.unary();
+        loop {
+            if self.eat('*') {
+                acc *= self.unary();
+            } else if self.eat('/') {
+                acc /= self.unary();
+            } else {
+                return acc;
+            }
+        }
+    }
+
+    fn unary(&mut self) -> f32 {
+        if self.eat('-') {
+            return -self.unary();
+        }
+        match self.next() {
+            Tok::Num(n) => n,
+            Tok::Punct('(') => {
+                let v = self.expr();
+                assert!(self.eat(')'));
+                v
+            }
+            Tok::Ident(name) if self.eat('(') => {
+                let mut args = vec![self.expr()];
+                while self.eat(',') {
+                    args.push(self.expr());
+                }
+                assert!(self.eat(')'));
+                call(&name, &args)
+            }
+            Tok::Ident(name) => self.vars[&name],
+            other => panic!("unexpected token {other:?}"),
+        }
+    }
+
+    /// Run the function body with parameters bound in `vars`.
+    fn run(mut self) -> f32 {
+        while !self.eat('{') {
+            self.pos += 1;
+        }
+        loop {
+            match self.next() {
+                Tok::Ident(kw) if kw == "return" => {
+                    let v = self.expr();
+                    assert!(self.eat(';'));
+                    return v;
+                }
+                Tok::Ident(kw) if kw == "let" || kw == "float" => {
+                    let Tok::Ident(name) = self.next() else {
+                        panic!("expected a binding name")
+                    };
+                    assert!(self.eat('='));
+                    let v = self.expr();
+                    assert!(self.eat(';'));
+                    self.vars.insert(name, v);
+                }
+                other => panic!("unexpected statement start {other:?}"),
+            }
+        }
+    }
+}
+
+/// Shader builtins as specified for `f32`.
+fn call(name: &str, a: &[f32]) -> f32 {
+    let sign = |x: f32| if x == 0.0 { 0.0 } else { x.signum() };
+    match (name, a.len()) {
+        ("abs", 1) => a[0].abs(),
+        ("sign", 1) => sign(a[0]),
+        ("floor", 1) => a[0].floor(),
+        ("ceil", 1) => a[0].ceil(),
+        ("sqrt", 1) => a[0].sqrt(),
+        ("exp", 1) => a[0].exp(),
+        ("log", 1) => a[0].ln(),
+        ("log2", 1) => a[0].log2(),
+        ("pow", 2) => a[0].powf(a[1]),
+        ("sin", 1) => a[0].sin(),
+        ("cos", 1) => a[0].cos(),
+        ("tan", 1) => a[0].tan(),
+        ("asin", 1) => a[0].asin(),
+        ("acos", 1) => a[0].acos(),
+        ("atan", 1) => a[0].atan(),
+        ("atan2" | "atan", 2) => a[0].atan2(a[1]),
+        ("sinh", 1) => a[0].sinh(),
+        ("cosh", 1) => a[0].cosh(),
+        ("tanh", 1) => a[0].tanh(),
+        ("asinh", 1) => a[0].asinh(),
+        ("acosh", 1) => a[0].acosh(),
+        ("atanh", 1) => a[0].atanh(),
+        _ => panic!("unknown builtin {name}/{}", a.len()),
+    }
+}
+
+fn run_emitted(src: &str, params: &[&str], values: &[f32]) -> f32 {
+    let toks = tokenize(src);
+    let vars = params
+        .iter()
+        .map(|p| (*p).to_owned())
+        .zip(values.iter().copied())
+        .collect();
+    Interp {
+        toks: &toks,
+        pos: 0,
+        vars,
+    }
+    .run()
+}
+
+/// Sample points away from the poles and branch cuts of the golden formulas.
+fn samples() -> Vec<[f64; 3]> {
+    let mut out = Vec::new();
+    for i in 0..6 {
+        for j in 0..4 {
+            let x = -1.7 + 0.61 * f64::from(i);
+            let y = 1.3 + 0.47 * f64::from(j);
+            let z = 0.9 - 0.53 * f64::from(i + j);
+            out.push([x, y, z]);
+        }
+    }
+    out
+}
+
+fn assert_agrees(formula: &str, src: &str) {
+    let expr = parse_plain(formula);
+    let compiled = CompiledEvaluator::compile(&expr, &XYZ, None).unwrap();
+    for point in samples() {
+        let expected = compiled.evaluate(&point);
+        #[allow(clippy::cast_possible_truncation)]
+        let got = f64::from(run_emitted(src, &XYZ, &point.map(|v| v as f32)));
+        let tolerance = 1e-4 * expected.abs().max(1.0);
+        assert!(
+            (got - expected).abs() <= tolerance,
+            "{formula} at {point:?}: shader {got}, evaluator {expected}\n{src}"
+        );
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[test]
+fn test_golden_wgsl() {
+    for (name, formula) in GOLDEN {
+        assert_eq!(
+            emit(name, formula, false),
+            golden(name, false),
+            "{name}.wgsl"
+        );
+    }
+}
+
+#[test]
+fn test_golden_glsl() {
+    for (name, formula) in GOLDEN {
+        assert_eq!(emit(name, formula, true), golden(name, true), "{name}.glsl");
+    }
+}
+
+#[test]
+fn test_emitted_source_is_well_formed() {
+    const KEYWORDS: &[&str] = &["fn", "let", "return", "f32", "float"];
+    const BUILTINS: &[&str] = &[
+        "abs", "sign", "floor", "ceil", "sqrt", "exp", "log", "log2", "pow", "sin", "cos", "tan",
+        "asin", "acos", "atan", "atan2", "sinh", "cosh", "tanh", "asinh", "acosh", "atanh",
+    ];
+    for glsl in [false, true] {
+        for (name, formula) in GOLDEN {
+            let src = emit(name, formula, glsl);
+            let toks = tokenize(&src);
+            let count = |c| toks.iter().filter(|t| **t == Tok::Punct(c)).count();
+            assert_eq!(count('{'), 1, "{src}");
+            assert_eq!(count('}'), 1, "{src}");
+            assert_eq!(count('('), count(')'), "{src}");
+            assert!(src.ends_with("}\n"));
+
+            for (i, tok) in toks.iter().enumerate() {
+                let Tok::Ident(ident) = tok else { continue };
+                let declared = matches!(
+                    toks.get(i.wrapping_sub(1)),
+                    Some(Tok::Ident(kw)) if kw == "let" || kw == "float" || kw == "fn"
+                ) || (!glsl && toks.get(i + 1) == Some(&Tok::Punct(':')));
+                let called = toks.get(i + 1) == Some(&Tok::Punct('('));
+                if declared {
+                    assert!(
+                        !KEYWORDS.contains(&ident.as_str()) && !BUILTINS.contains(&ident.as_str()),
+                        "'{ident}' declared in\n{src}"
+                    );
+                } else if called && ident != name && !KEYWORDS.contains(&ident.as_str()) {
+                    assert!(
+                        BUILTINS.contains(&ident.as_str()),
+                        "'{ident}' called in\n{src}"
+                    );
+                } else {
+                    assert!(
+                        KEYWORDS.contains(&ident.as_str())
+                            || XYZ.contains(&ident.as_str())
+                            || ident == name
+                            || (ident.starts_with('t') && ident[1..].parse::<u32>().is_ok()),
+                        "unexpected identifier '{ident}' in\n{src}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_numeric_agreement() {
+    for glsl in [false, true] {
+        for (name, formula) in GOLDEN {
+            assert_agrees(formula, &emit(name, formula, glsl));
+        }
+    }
+}
+
+#[test]
+fn test_numeric_agreement_derived_functions() {
+    let formulas = [
+        "cot(x) + csc(x) + coth(y) + sech(z) + csch(y)",
+        "asec(y) + acsc(y) + asech(1/y) + acsch(z) + asinh(x) + acosh(y) + atanh(z/4)",
+        "tan(x/4) * sinh(z) / cosh(x) - tanh(y)",
+        "log(y, 7) + log2(y) + ln(y) + abs(x) + floor(z) + ceil(x) + signum(x)",
+        "x^3 - (x*y)^-2 + y^9 + (x - y)^4 + y^2.5 + e^z + 2^x + pi*z",
+        "x^20 / y^15",
+    ];
+    for formula in formulas {
+        assert_agrees(formula, &emit("f", formula, false));
+        assert_agrees(formula, &emit("f", formula, true));
+    }
+}
+
+#[test]
+fn test_shared_subexpressions_bound_once() {
+    let src = emit("f", "sin(x*y)^2 + cos(x*y) + sin(x*y)", false);
+    assert_eq!(src.matches("x * y").count(), 1, "{src}");
+    assert_eq!(src.matches("sin(").count(), 1, "{src}");
+}
+
+#[test]
+fn test_integer_powers_unrolled() {
+    let src = emit("f", "x^3 + y^(-2) + z^1000", false);
+    assert!(!src.contains("pow("), "{src}");
+    assert!(src.contains("x * x * x"), "{src}");
+    assert!(src.contains("1.0 / (y * y)"), "{src}");
+}
+
+#[test]
+fn test_huge_odd_power_keeps_sign() {
+    let src = emit("f", "x^2001", false);
+    assert_eq!(
+        src,
+        "fn f(x: f32, y: f32, z: f32) -> f32 {\n    return sign(x) * pow(abs(x), 2001.0);\n}\n"
+    );
+}
+
+#[test]
+fn test_glsl_two_argument_atan() {
+    let src = emit("f", "atan2(y, x)", true);
+    assert!(src.contains("atan(y, x)"), "{src}");
+}
+
+#[test]
+fn test_temporaries_avoid_parameter_names() {
+    let expr = parse_plain("sin(t0)^2");
+    let src = expr.to_wgsl("f", &["t0"], None).unwrap();
+    assert_eq!(
+        src,
+        "fn f(t0: f32) -> f32 {\n    let t1 = sin(t0);\n    return t1 * t1;\n}\n"
+    );
+}
+
+#[test]
+fn test_context_bodies_are_inlined() {
+    let ctx = Context::new().with_function(
+        "sq",
+        UserFunction::new(1..=1).body(|args| (*args[0]).clone().pow(2.0)),
+    );
+    let expr = Expr::func("sq", symb("x")) + 1.0;
+    let src = expr.to_wgsl("f", &["x"], Some(&ctx)).unwrap();
+    assert!(src.contains("x * x"), "{src}");
+}
+
+#[test]
+fn test_unsupported_constructs_listed_together() {
+    let functions: HashSet<String> = ["f".to_owned(), "g".to_owned()].into();
+    let expr = parse(
+        "erf(x) + f(x) + diff(g(x), x) + inf",
+        &HashSet::new(),
+        &functions,
+        None,
+    )
+    .unwrap();
+    let Err(DiffError::UnsupportedExpression(msg)) = expr.to_wgsl("f_out", &["x"], None) else {
+        panic!("expected UnsupportedExpression");
+    };
+    for part in [
+        "function 'erf' has no WGSL equivalent",
+        "custom function 'f' has no body",
+        "derivative",
+        "literal inf",
+    ] {
+        assert!(msg.contains(part), "missing '{part}' in: {msg}");
+    }
+}
+
+#[test]
+fn test_invalid_identifiers_rejected() {
+    let expr = parse_plain("x + y");
+    let Err(DiffError::UnsupportedExpression(msg)) = expr.to_wgsl("fn", &["let", "y"], None) else {
+        panic!("expected UnsupportedExpression");
+    };
+    assert!(
+        msg.contains("function name 'fn' is reserved in WGSL"),
+        "{msg}"
+    );
+    assert!(msg.contains("parameter 'let' is reserved in WGSL"), "{msg}");
+
+    let Err(DiffError::UnsupportedExpression(msg)) = expr.to_glsl("gl_f", &["x", "x"], None) else {
+        panic!("expected UnsupportedExpression");
+    };
+    assert!(msg.contains("'gl_f' is reserved in GLSL"), "{msg}");
+    assert!(msg.contains("parameter 'x' is declared twice"), "{msg}");
+}
+
+#[test]
+fn test_unbound_variable() {
+    let result = parse_plain("x + w").to_wgsl("f", &["x"], None);
+    assert!(matches!(result, Err(DiffError::UnboundVariable(name)) if name.as_str() == "w"));
+}
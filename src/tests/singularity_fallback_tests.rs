@@ -0,0 +1,139 @@
+//! Tests for `EvalOptions::singularity_fallback`: removable singularities evaluate to
+//! their limits, genuine poles stay non-finite, and the default path is unchanged
+
+use crate::{CompiledEvaluator, EvalOptions, Expr, diff, parse};
+use std::collections::HashSet;
+
+fn parse_str(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn compile_fallback(formula: &str, params: &[&str]) -> CompiledEvaluator {
+    let options = EvalOptions {
+        singularity_fallback: true,
+        ..EvalOptions::default()
+    };
+    CompiledEvaluator::compile_with_options(&parse_str(formula), params, None, options).unwrap()
+}
+
+fn assert_limit(formula: &str, point: &[f64], expected: f64) {
+    let params: &[&str] = if point.len() == 1 {
+        &["x"]
+    } else {
+        &["x", "y"]
+    };
+    let got = compile_fallback(formula, params).evaluate(point);
+    assert!(
+        (got - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+        "{formula} at {point:?}: got {got}, expected {expected}"
+    );
+}
+
+#[test]
+fn test_sinc_derivative_at_zero() {
+    let derivative = diff("sinc(x)", "x", &[], None).unwrap();
+    let plain = CompiledEvaluator::compile(&parse_str(&derivative), &["x"], None).unwrap();
+    assert!(plain.evaluate(&[0.0]).is_nan());
+    assert_limit(&derivative, &[0.0], 0.0);
+}
+
+#[test]
+fn test_one_minus_cos_over_square() {
+    assert_limit("(1 - cos(x))/x^2", &[0.0], 0.5);
+}
+
+#[test]
+fn test_sin_over_x() {
+    assert_limit("sin(x)/x", &[0.0], 1.0);
+    assert_limit("sin(x^2)/x^2", &[0.0], 1.0);
+}
+
+#[test]
+fn test_x_ln_x() {
+    assert_limit("x*ln(x)", &[0.0], 0.0);
+}
+
+#[test]
+fn test_repeated_lhopital() {
+    assert_limit("(exp(x) - 1 - x)/x^2", &[0.0], 0.5);
+    assert_limit("(x - sin(x))/x^3", &[0.0], 1.0 / 6.0);
+}
+
+#[test]
+fn test_singularity_away_from_origin() {
+    assert_limit("(x^2 - 1)/(x - 1)", &[1.0], 2.0);
+}
+
+#[test]
+fn test_multivariable_singularity() {
+    assert_limit("sin(x*y)/(x*y)", &[0.0, 0.0], 1.0);
+    assert_limit("(x - y)/(x - y)", &[2.0, 2.0], 1.0);
+    assert_limit("y*sin(x)/x", &[0.0, 3.0], 3.0);
+}
+
+#[test]
+fn test_genuine_poles_stay_non_finite() {
+    let pole = compile_fallback("1/x", &["x"]);
+    assert_eq!(pole.evaluate(&[0.0]), f64::INFINITY);
+    assert!(
+        !compile_fallback("x/x^2", &["x"])
+            .evaluate(&[0.0])
+            .is_finite()
+    );
+    assert!(
+        !compile_fallback("ln(x)/x", &["x"])
+            .evaluate(&[0.0])
+            .is_finite()
+    );
+    assert!(
+        compile_fallback("sqrt(x)", &["x"])
+            .evaluate(&[-1.0])
+            .is_nan()
+    );
+}
+
+#[test]
+fn test_regular_points_are_unchanged() {
+    let compiled = compile_fallback("(1 - cos(x))/x^2", &["x"]);
+    let plain = CompiledEvaluator::compile(&parse_str("(1 - cos(x))/x^2"), &["x"], None).unwrap();
+    for x in [0.5, -1.25, 3.0] {
+        assert_eq!(
+            compiled.evaluate(&[x]).to_bits(),
+            plain.evaluate(&[x]).to_bits()
+        );
+    }
+}
+
+#[test]
+fn test_default_path_has_no_fallback() {
+    let plain = CompiledEvaluator::compile(&parse_str("x*ln(x)"), &["x"], None).unwrap();
+    assert!(plain.evaluate(&[0.0]).is_nan());
+}
+
+#[test]
+fn test_builder_option() {
+    let expr = parse_str("(1 - cos(x))/x^2");
+    let compiled = CompiledEvaluator::builder(&expr)
+        .params(["x"])
+        .singularity_fallback(true)
+        .build()
+        .unwrap();
+    assert!((compiled.evaluate(&[0.0]) - 0.5).abs() < 1e-12);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_batch_evaluation_uses_fallback() {
+    let compiled = compile_fallback("sin(x)/x + x*ln(x)", &["x"]);
+    let xs = [0.0, 0.5, 0.0, 2.0, 0.0];
+    let mut output = [0.0; 5];
+    compiled.eval_batch(&[&xs], &mut output, None).unwrap();
+    for (x, got) in xs.iter().zip(output) {
+        let expected = if *x == 0.0 {
+            1.0
+        } else {
+            x.sin() / x + x * x.ln()
+        };
+        assert!((got - expected).abs() < 1e-12, "x={x}: {got} vs {expected}");
+    }
+}
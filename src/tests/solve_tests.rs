@@ -0,0 +1,128 @@
+//! Tests for `Expr::solve`: linear and quadratic roots with symbolic coefficients
+
+use crate::{DiffError, Expr, Symbol, parse, symb};
+use std::collections::{HashMap, HashSet};
+
+fn parse_with(input: &str, known: &[&str]) -> Expr {
+    let known: HashSet<String> = known.iter().map(ToString::to_string).collect();
+    parse(input, &known, &HashSet::new(), None).unwrap()
+}
+
+/// Substituting each root back must simplify to zero
+fn assert_roots(expr: &Expr, var: &Symbol, roots: &[Expr]) {
+    for root in roots {
+        let residual = expr.substitute_symbol(var, root).simplified().unwrap();
+        assert!(residual.is_zero_num(), "{expr} at {root} gives {residual}");
+    }
+}
+
+fn unsupported_message(result: Result<Vec<Expr>, DiffError>) -> String {
+    match result {
+        Err(DiffError::UnsupportedExpression(msg)) => msg,
+        other => panic!("expected UnsupportedExpression, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_linear_with_symbolic_coefficient() {
+    let x = symb("x");
+    let expr = parse_with("2*x + a", &[]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0], parse_with("-a/2", &[]).simplified().unwrap());
+    assert_roots(&expr, &x, &roots);
+}
+
+#[test]
+fn test_linear_with_fixed_vars() {
+    let x = symb("x");
+    let expr = parse_with("alpha*x - beta", &["alpha", "beta"]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_roots(&expr, &x, &roots);
+}
+
+#[test]
+fn test_general_quadratic() {
+    let x = symb("x");
+    let expr = parse_with("a*x^2 + b*x + c", &[]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 2);
+    assert_ne!(roots[0], roots[1]);
+    for root in &roots {
+        assert!(!root.contains_var("x"));
+        assert!(root.contains_var("a") && root.contains_var("b") && root.contains_var("c"));
+    }
+
+    // The simplifier does not expand the squared binomial, so check the residual
+    // numerically over several coefficient sets (including a < 0)
+    for (a, b, c) in [(1.0, -3.0, 2.0), (2.0, 1.0, -6.0), (-1.5, 0.5, 4.0)] {
+        let values = HashMap::from([("a", a), ("b", b), ("c", c)]);
+        for root in &roots {
+            let residual = expr.substitute_symbol(&x, root).partial_eval(&values);
+            let residual = residual.as_number().unwrap();
+            assert!(residual.abs() < 1e-12, "a={a} b={b} c={c}: {residual}");
+        }
+    }
+}
+
+#[test]
+fn test_symbolic_roots_substitute_to_zero() {
+    let x = symb("x");
+    let expr = parse_with("x^2 - a", &[]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 2);
+    assert_roots(&expr, &x, &roots);
+
+    let expr = parse_with("a*x^2 - 1", &[]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 2);
+    assert_roots(&expr, &x, &roots);
+}
+
+#[test]
+fn test_numeric_quadratics() {
+    let x = symb("x");
+    let roots = parse_with("x^2 - 4", &[]).solve(&x).unwrap();
+    assert_eq!(roots, vec![Expr::number(-2.0), Expr::number(2.0)]);
+
+    let roots = parse_with("x^2 - 5*x + 6", &[]).solve(&x).unwrap();
+    assert_eq!(roots, vec![Expr::number(2.0), Expr::number(3.0)]);
+
+    // Ascending even with a negative leading coefficient
+    let roots = parse_with("4 - x^2", &[]).solve(&x).unwrap();
+    assert_eq!(roots, vec![Expr::number(-2.0), Expr::number(2.0)]);
+
+    // Double root and no real roots
+    let roots = parse_with("x^2 - 2*x + 1", &[]).solve(&x).unwrap();
+    assert_eq!(roots, vec![Expr::number(1.0)]);
+    assert!(parse_with("x^2 + 1", &[]).solve(&x).unwrap().is_empty());
+}
+
+#[test]
+fn test_cancelling_terms_lower_degree() {
+    let x = symb("x");
+    let expr = parse_with("(x + 1)^2 - x^2", &[]);
+    let roots = expr.solve(&x).unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].to_string(), "-1/2");
+}
+
+#[test]
+fn test_unsupported_degree() {
+    let x = symb("x");
+    let msg = unsupported_message(parse_with("x^3 + x", &[]).solve(&x));
+    assert!(msg.contains("degree 3"), "{msg}");
+
+    let msg = unsupported_message(parse_with("a + 1", &[]).solve(&x));
+    assert!(msg.contains("degree 0"), "{msg}");
+}
+
+#[test]
+fn test_not_polynomial() {
+    let x = symb("x");
+    for input in ["sin(x) + 1", "1/x + 2", "x^0.5 - 1", "2^x - 4"] {
+        let msg = unsupported_message(parse_with(input, &[]).solve(&x));
+        assert!(msg.contains("not a polynomial in x"), "{input}: {msg}");
+    }
+}
@@ -0,0 +1,209 @@
+//! Tests for `Expr::to_code`: C output compiled with the system C compiler and compared
+//! against the compiled evaluator, and the shape of the Python, Julia and Fortran output
+
+use crate::{CodeGenLanguage, CompiledEvaluator, DiffError, Expr, parse};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::process::Command;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+const FORMULAS: &[&str] = &[
+    "x^3*sin(y) - 2*exp(-x*y)/(1 + y^2)",
+    "sqrt(x^2 + y^2) + atan2(y, x) - acot(x) + hypot(x, 2*y)",
+    "sec(x) + log10(y) + cbrt(x - y) + x^(-5) + acoth(y + 2)",
+    "asinh(x) + acosh(y + 2) + atanh(x/4) + log2(y) + log(y, 7) + x^2.5",
+    "floor(x*y) + ceil(y) + round(x) + signum(x - y) + abs(x) * tanh(y)",
+    "sind(x*30) + acosd(y/4) + pi*e^x - cos(x*y)^2 + cos(x*y)",
+];
+
+const POINTS: [(f64, f64); 4] = [(0.7, 1.3), (1.9, 0.4), (2.5, 3.25), (0.1, 2.0)];
+
+#[test]
+fn test_c_compiles_and_matches_evaluator() {
+    let Ok(cc) = Command::new("cc").arg("--version").output() else {
+        eprintln!("skipping: no C compiler on PATH");
+        return;
+    };
+    assert!(cc.status.success());
+
+    let mut source = String::new();
+    let mut main = String::from("#include <stdio.h>\n\nint main(void) {\n");
+    for (i, formula) in FORMULAS.iter().enumerate() {
+        let code = parse_plain(formula)
+            .to_code_with(CodeGenLanguage::C, &format!("f{i}"), &["x", "y"], None)
+            .unwrap();
+        source.push_str(&code);
+        source.push('\n');
+        for (x, y) in POINTS {
+            writeln!(main, "    printf(\"%.17g\\n\", f{i}({x:?}, {y:?}));").unwrap();
+        }
+    }
+    main.push_str("    return 0;\n}\n");
+    source.push_str(&main);
+
+    let dir = std::env::temp_dir().join(format!("symb_anafis_to_code_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (src, bin) = (dir.join("generated.c"), dir.join("generated"));
+    std::fs::write(&src, &source).unwrap();
+    let build = Command::new("cc")
+        .args(["-std=c11", "-Wall", "-Werror", "-o"])
+        .arg(&bin)
+        .arg(&src)
+        .arg("-lm")
+        .output()
+        .unwrap();
+    assert!(
+        build.status.success(),
+        "{}\n{source}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+    let run = Command::new(&bin).output().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(run.status.success());
+
+    let printed: Vec<f64> = String::from_utf8(run.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    let mut values = printed.iter();
+    for formula in FORMULAS {
+        let eval = CompiledEvaluator::compile(&parse_plain(formula), &["x", "y"], None).unwrap();
+        for (x, y) in POINTS {
+            let (got, expected) = (*values.next().unwrap(), eval.evaluate(&[x, y]));
+            assert!(
+                (got - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+                "{formula} at ({x}, {y}): C gives {got}, evaluator {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_signature_lists_variables_alphabetically() {
+    let expr = parse_plain("y*sin(x) + z");
+    assert_eq!(
+        expr.to_code(CodeGenLanguage::C).unwrap(),
+        "#include <math.h>\n\ndouble f(double x, double y, double z) {\n    return z + y * sin(x);\n}\n"
+    );
+    assert_eq!(
+        expr.to_code(CodeGenLanguage::Python).unwrap(),
+        "import numpy as np\n\n\ndef f(x, y, z):\n    return z + y * np.sin(x)\n"
+    );
+    assert_eq!(
+        expr.to_code(CodeGenLanguage::Julia).unwrap(),
+        "function f(x, y, z)\n    return z + y * sin(x)\nend\n"
+    );
+    assert_eq!(
+        expr.to_code(CodeGenLanguage::Fortran90).unwrap(),
+        "pure function f(x, y, z) result(res)\n    implicit none\n    \
+         real(8), intent(in) :: x, y, z\n    real(8) :: res\n    \
+         res = z + y * sin(x)\nend function f\n"
+    );
+    // Constants are literals, not parameters
+    let constant = parse_plain("pi*r^2").to_code(CodeGenLanguage::C).unwrap();
+    assert!(constant.contains("double f(double r)"), "{constant}");
+}
+
+#[test]
+fn test_language_specific_spellings() {
+    let expr = parse_plain("x^2.5 + asin(y) + atan2(y, x) + floor(x)");
+    let python = expr.to_code(CodeGenLanguage::Python).unwrap();
+    for part in [
+        "np.power(x, 2.5)",
+        "np.arcsin(y)",
+        "np.arctan2(y, x)",
+        "np.floor(x)",
+    ] {
+        assert!(python.contains(part), "{part} in {python}");
+    }
+    let julia = expr.to_code(CodeGenLanguage::Julia).unwrap();
+    for part in ["(x ^ 2.5)", "asin(y)", "atan(y, x)", "floor(x)"] {
+        assert!(julia.contains(part), "{part} in {julia}");
+    }
+    let fortran = expr.to_code(CodeGenLanguage::Fortran90).unwrap();
+    for part in ["(x ** 2.5d0)", "asin(y)", "atan2(y, x)", "modulo(x, 1.0d0)"] {
+        assert!(fortran.contains(part), "{part} in {fortran}");
+    }
+}
+
+#[test]
+fn test_fortran_literals_and_continuation_lines() {
+    let expr = parse_plain("1e-7*x - 3*x*y*sin(x)^2 + (x + y)^3 + exp(x*y)/(1 + x^2 + y^2)");
+    let fortran = expr.to_code(CodeGenLanguage::Fortran90).unwrap();
+    assert!(fortran.contains("1d-7"), "{fortran}");
+    // A unary minus never follows an operator
+    assert!(
+        !fortran.contains("* -") && !fortran.contains("/ -"),
+        "{fortran}"
+    );
+    assert!(fortran.contains("real(8) :: t0"), "{fortran}");
+
+    let long = parse_plain(
+        &(1..=40)
+            .map(|i| format!("{i}*sin({i}*x)"))
+            .collect::<Vec<_>>()
+            .join(" + "),
+    );
+    let fortran = long.to_code(CodeGenLanguage::Fortran90).unwrap();
+    assert!(fortran.lines().all(|line| line.len() <= 132), "{fortran}");
+    assert!(fortran.contains(" &\n"), "{fortran}");
+}
+
+#[test]
+fn test_reserved_names_per_language() {
+    let expr = parse_plain("x + y");
+    for (lang, name) in [
+        (CodeGenLanguage::C, "double"),
+        (CodeGenLanguage::Python, "lambda"),
+        (CodeGenLanguage::Julia, "end"),
+        (CodeGenLanguage::Fortran90, "res"),
+    ] {
+        let Err(DiffError::UnsupportedExpression(msg)) =
+            expr.to_code_with(lang, "f", &[name, "y"], None)
+        else {
+            panic!("expected UnsupportedExpression for {name}");
+        };
+        assert!(
+            msg.contains(&format!("parameter '{name}' is reserved")),
+            "{msg}"
+        );
+    }
+}
+
+#[test]
+fn test_unsupported_functions_rejected() {
+    let result = parse_plain("gamma(x)").to_code(CodeGenLanguage::Julia);
+    assert!(
+        matches!(&result, Err(DiffError::UnsupportedExpression(msg)) if msg.contains("'gamma' has no Julia equivalent")),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn test_infix_power_operands_parenthesised() {
+    let cases = [
+        ("(a+b)^c", "((a + b) ^ c)", "((a + b) ** c)"),
+        ("y^(x^2)", "(y ^ (x * x))", "(y ** (x * x))"),
+        ("y^(a+b)", "(y ^ (a + b))", "(y ** (a + b))"),
+        ("y^(1/3)", "(y ^ (1.0 / 3.0))", "(y ** (1.0d0 / 3.0d0))"),
+        ("y^(-a)", "(y ^ (-a))", "(y ** (-a))"),
+        ("(-a)^y", "((-a) ^ y)", "((-a) ** y)"),
+    ];
+    for (formula, julia, fortran) in cases {
+        let expr = parse_plain(formula);
+        let code = expr.to_code(CodeGenLanguage::Julia).unwrap();
+        assert!(
+            code.contains(&format!("return {julia}\n")),
+            "{formula}: {code}"
+        );
+        let code = expr.to_code(CodeGenLanguage::Fortran90).unwrap();
+        assert!(
+            code.contains(&format!("res = {fortran}\n")),
+            "{formula}: {code}"
+        );
+    }
+}
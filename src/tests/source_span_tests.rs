@@ -0,0 +1,141 @@
+//! Tests for source locations of errors raised after parsing: `ParserOptions::source_map`,
+//! `SourceMap::locate` and the string entry points that attach them
+
+use crate::{
+    CompiledEvaluator, Diff, DiffError, Expr, ParserOptions, Simplify, SourceMap, Span,
+    diff_implicit_str, parse_configured,
+};
+use std::collections::HashSet;
+
+fn parse_mapped(input: &str, functions: &[&str]) -> (Expr, SourceMap) {
+    let functions: HashSet<String> = functions.iter().map(ToString::to_string).collect();
+    let options = ParserOptions {
+        source_map: true,
+        ..ParserOptions::default()
+    };
+    let parsed = parse_configured(input, &HashSet::new(), &functions, None, &options).unwrap();
+    (parsed.expr, parsed.source_map.unwrap())
+}
+
+/// Parse, simplify, compile over `params` and locate the compile error
+fn compile_error(input: &str, functions: &[&str], params: &[&str]) -> DiffError {
+    let (expr, map) = parse_mapped(input, functions);
+    let simplified = Simplify::new().simplify(&expr).unwrap();
+    assert_ne!(simplified, expr, "{input} should change when simplified");
+    map.locate(CompiledEvaluator::compile(&simplified, params, None).unwrap_err())
+}
+
+#[test]
+fn test_unsupported_function_after_simplification() {
+    let err = compile_error("a*g(x) + b + sin(x)^2 + cos(x)^2", &["g"], &["a", "b", "x"]);
+    assert!(
+        matches!(err.unlocated(), DiffError::UnsupportedFunction(name) if name.as_str() == "g")
+    );
+    let location = err.location().unwrap();
+    assert_eq!((location.line(), location.column()), (1, 3));
+    assert_eq!(location.span(), Span::new(2, 3));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Unsupported function for evaluation: g at column 3: {}\n{}^",
+            "a*g(x) + b + sin(x)^2 + cos(x)^2",
+            " ".repeat(54)
+        )
+    );
+}
+
+#[test]
+fn test_unbound_variable_after_simplification() {
+    let err = compile_error("a*x^2 + speed + sin(a)^2 + cos(a)^2", &[], &["a", "x"]);
+    assert!(
+        matches!(err.unlocated(), DiffError::UnboundVariable(name) if name.as_str() == "speed")
+    );
+    assert_eq!(err.location().unwrap().span(), Span::new(8, 13));
+    assert!(err.to_string().ends_with("^^^^^"), "{err}");
+
+    // A name split off by implicit multiplication is found inside its run
+    let err = compile_error("3 + ysin(x) + sin(x)^2 + cos(x)^2", &[], &["x"]);
+    assert_eq!(err.location().unwrap().span(), Span::new(4, 5));
+}
+
+#[test]
+fn test_non_differentiable_after_simplification() {
+    let (expr, map) = parse_mapped("x^2 + abs(x - 1) + x", &[]);
+    let simplified = Simplify::new().simplify(&expr).unwrap();
+    let err = map.locate(
+        Diff::new()
+            .domain_safe(true)
+            .differentiate(&simplified, &crate::symb("x"))
+            .unwrap_err(),
+    );
+    assert!(
+        matches!(err.unlocated(), DiffError::NonDifferentiable { function, .. } if function == "abs")
+    );
+    assert_eq!(err.location().unwrap().column(), 7);
+
+    // diff_str locates on its own
+    let err = Diff::new()
+        .domain_safe(true)
+        .diff_str("x^2 + abs(x - 1)", "x", &[])
+        .unwrap_err();
+    assert_eq!(err.location().unwrap().span(), Span::new(6, 9));
+}
+
+#[test]
+fn test_limits_point_at_whole_formula() {
+    let err = Diff::new()
+        .max_depth(3)
+        .diff_str("  sin(cos(tan(x)))  ", "x", &[])
+        .unwrap_err();
+    assert!(matches!(err.unlocated(), DiffError::MaxDepthExceeded));
+    assert_eq!(err.location().unwrap().span(), Span::new(2, 18));
+
+    let err = Simplify::new()
+        .max_nodes(4)
+        .simplify_str("a + b*c + d*e", &[])
+        .unwrap_err();
+    assert!(matches!(err.unlocated(), DiffError::MaxNodesExceeded));
+    assert_eq!(err.location().unwrap().span(), Span::new(0, 13));
+
+    let err = Diff::new()
+        .max_nodes(40)
+        .diff_str_n("exp(x^2)", "x", &[], 6)
+        .unwrap_err();
+    assert!(matches!(
+        err.unlocated(),
+        DiffError::DerivativeOrderLimit { .. }
+    ));
+    assert!(err.location().is_some());
+}
+
+#[test]
+fn test_multiline_source_skips_comments() {
+    let err = compile_error(
+        "# w is not a parameter\na*w\n  + sin(a)^2 + cos(a)^2",
+        &[],
+        &["a"],
+    );
+    let location = err.location().unwrap();
+    assert_eq!((location.line(), location.column()), (2, 3));
+    let rendered = err.to_string();
+    assert!(
+        rendered.starts_with("Unbound variable: w at line 2, column 3: a*w\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_errors_without_origin_are_unchanged() {
+    let err = diff_implicit_str("x^2 = 4", "y", "x").unwrap_err();
+    assert!(matches!(err, DiffError::DivisionByZero { .. }), "{err:?}");
+    assert!(err.location().is_none());
+    assert_eq!(err.unlocated(), &err);
+
+    // Located once, an error is not wrapped again
+    let map = SourceMap::new("x + y");
+    let located = map.locate(DiffError::MaxNodesExceeded);
+    assert_eq!(map.locate(located.clone()), located);
+    // A name absent from the source has nothing to point at
+    let unbound = DiffError::UnboundVariable(crate::core::symb_interned("zeta"));
+    assert_eq!(map.locate(unbound.clone()), unbound);
+}
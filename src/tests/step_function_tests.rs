@@ -0,0 +1,114 @@
+//! Sign, Heaviside step and Dirac delta: generalized derivatives and evaluation
+
+use crate::core::ExprKind;
+use crate::{CompiledEvaluator, Diff, DiffError, Expr, diff, symb};
+use std::collections::HashMap;
+
+fn eval_at(expr: &Expr, x: f64) -> f64 {
+    let vars: HashMap<&str, f64> = [("x", x)].into_iter().collect();
+    match expr.evaluate(&vars, &HashMap::new()).kind {
+        ExprKind::Number(n) => n,
+        ref other => panic!("Expected number, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_sign_derivative_is_twice_dirac() {
+    assert_eq!(diff("sign(x)", "x", &[], None).unwrap(), "2*dirac(x)");
+    assert_eq!(diff("sgn(3*x)", "x", &[], None).unwrap(), "6*dirac(3*x)");
+}
+
+#[test]
+fn test_heaviside_derivative_is_dirac() {
+    assert_eq!(diff("heaviside(x)", "x", &[], None).unwrap(), "dirac(x)");
+    assert_eq!(
+        diff("heaviside(sin(x))", "x", &[], None).unwrap(),
+        "dirac(sin(x))*cos(x)"
+    );
+}
+
+#[test]
+fn test_heaviside_chain_rule_on_quadratic() {
+    let x = symb("x");
+    let step = (x.pow(2.0) - 1.0).heaviside();
+    let derivative = Diff::new()
+        .skip_simplification(true)
+        .differentiate(&step, &x)
+        .unwrap();
+    let ExprKind::Product(factors) = &derivative.kind else {
+        panic!("Expected dirac(x^2 - 1) * d/dx(x^2 - 1), got {derivative}");
+    };
+    assert!(factors.iter().any(|factor| matches!(
+        &factor.kind,
+        ExprKind::FunctionCall { name, args } if name.as_str() == "dirac" && *args[0] == x.pow(2.0) - 1.0
+    )));
+
+    // The delta only fires on x^2 = 1, so the derivative vanishes elsewhere
+    let simplified = Diff::new().differentiate(&step, &x).unwrap();
+    assert_eq!(simplified.to_string(), "2*x*dirac((-1 + x)*(1 + x))");
+    assert_eq!(eval_at(&simplified, 0.5), 0.0);
+    assert_eq!(eval_at(&simplified, 1.0), f64::INFINITY);
+}
+
+#[test]
+fn test_abs_second_derivative_carries_delta() {
+    let x = symb("x");
+    let second = Diff::new().diff_n(&x.abs(), &x, 2).unwrap();
+    assert_eq!(second.to_string(), "2*dirac(x)");
+}
+
+#[test]
+fn test_dirac_derivative_stays_symbolic() {
+    assert_eq!(diff("dirac(x)", "x", &[], None).unwrap(), "dirac_prime(x)");
+    assert_eq!(diff("dirac(2)", "x", &[], None).unwrap(), "0");
+}
+
+#[test]
+fn test_dirac_derivative_rejected_when_domain_safe() {
+    let safe = Diff::new().domain_safe(true);
+    assert!(matches!(
+        safe.diff_str("dirac(x)", "x", &[]),
+        Err(DiffError::UnsupportedOperation(_))
+    ));
+    let x = symb("x");
+    assert!(matches!(
+        safe.diff_n(&x.heaviside(), &x, 2),
+        Err(DiffError::UnsupportedOperation(_))
+    ));
+    // A first derivative of the step still only needs the delta itself
+    assert_eq!(
+        safe.diff_str("heaviside(x^2 - 1)", "x", &[]).unwrap(),
+        "2*x*dirac((-1 + x)*(1 + x))"
+    );
+}
+
+#[test]
+fn test_step_functions_evaluate_pointwise() {
+    let x = symb("x");
+    for (value, sign, step, delta) in [
+        (-2.0, -1.0, 0.0, 0.0),
+        (0.0, 1.0, 1.0, f64::INFINITY),
+        (3.0, 1.0, 1.0, 0.0),
+    ] {
+        assert_eq!(eval_at(&x.signum(), value), sign);
+        assert_eq!(eval_at(&x.heaviside(), value), step);
+        assert_eq!(eval_at(&x.dirac(), value), delta);
+    }
+    assert!(eval_at(&x.heaviside(), f64::NAN).is_nan());
+}
+
+#[test]
+fn test_step_functions_compile() {
+    let x = symb("x");
+    let expr = x.heaviside() * 3.0 + x.dirac() + x.signum();
+    let eval = CompiledEvaluator::compile(&expr, &["x"], None).unwrap();
+    assert_eq!(eval.evaluate(&[-1.0]), -1.0);
+    assert_eq!(eval.evaluate(&[2.0]), 4.0);
+    assert_eq!(eval.evaluate(&[0.0]), f64::INFINITY);
+    assert!(eval.evaluate(&[f64::NAN]).is_nan());
+
+    // Constant arguments fold at compile time with the same convention
+    let folded = Expr::number(0.0).heaviside() + x;
+    let eval = CompiledEvaluator::compile(&folded, &["x"], None).unwrap();
+    assert_eq!(eval.evaluate(&[2.0]), 3.0);
+}
@@ -0,0 +1,286 @@
+//! Tests for `Expr::substitute` and its variants: every `ExprKind`, nested, simultaneous
+//! and self-referencing replacements
+
+use crate::core::ExprKind;
+use crate::{Context, Expr, parse, symb};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+fn parse_plain(input: &str) -> Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+fn parse_with_functions(input: &str, functions: &[&str]) -> Expr {
+    let functions: HashSet<String> = functions.iter().map(|f| (*f).to_owned()).collect();
+    parse(input, &HashSet::new(), &functions, None).unwrap()
+}
+
+fn eval(expr: &Expr, vars: &[(&str, f64)]) -> f64 {
+    let vars: HashMap<&str, f64> = vars.iter().copied().collect();
+    match expr.evaluate(&vars, &HashMap::new()).kind {
+        ExprKind::Number(n) => n,
+        ref other => panic!("Expected number, got {other:?}"),
+    }
+}
+
+fn assert_close(got: f64, expected: f64) {
+    assert!(
+        (got - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+        "got {got}, expected {expected}"
+    );
+}
+
+#[test]
+fn test_number_is_unchanged() {
+    let expr = Expr::number(4.5);
+    assert_eq!(expr.substitute("x", &symb("y").into()), expr);
+}
+
+#[test]
+fn test_symbol() {
+    let replaced = Expr::symbol("x").substitute("x", &parse_plain("a*t + b"));
+    assert_eq!(replaced, parse_plain("a*t + b"));
+    // Other symbols are left alone
+    assert_eq!(
+        Expr::symbol("y").substitute("x", &Expr::number(1.0)),
+        Expr::symbol("y")
+    );
+}
+
+#[test]
+fn test_sum_and_product() {
+    let expr = parse_plain("x + y + 3*x*z");
+    let replaced = expr.substitute("x", &Expr::number(2.0));
+    assert!(!replaced.contains_var("x"));
+    assert_close(eval(&replaced, &[("y", 5.0), ("z", 7.0)]), 2.0 + 5.0 + 42.0);
+}
+
+#[test]
+fn test_div_and_pow() {
+    let expr = parse_plain("x/(1 + y) + y^x + x^y");
+    let replaced = expr.substitute("x", &parse_plain("t^2"));
+    assert!(!replaced.contains_var("x"));
+    let (t, y) = (1.5_f64, 0.5_f64);
+    let x = t * t;
+    assert_close(
+        eval(&replaced, &[("t", t), ("y", y)]),
+        x / (1.0 + y) + y.powf(x) + x.powf(y),
+    );
+}
+
+#[test]
+fn test_function_calls() {
+    let replaced =
+        parse_plain("sin(x) + atan2(x, y) + besselj(2, x)").substitute("x", &parse_plain("2*t"));
+    assert!(!replaced.contains_var("x"));
+    let expected = 3.0_f64.sin() + 3.0_f64.atan2(0.25) + eval(&parse_plain("besselj(2, 3)"), &[]);
+    assert_close(eval(&replaced, &[("t", 1.5), ("y", 0.25)]), expected);
+}
+
+#[test]
+fn test_inside_custom_function_argument() {
+    let expr = parse_with_functions("f(x, y) + g(x^2)", &["f", "g"]);
+    let replaced = expr.substitute("x", &parse_plain("a + 1"));
+    assert_eq!(
+        replaced,
+        parse_with_functions("f(a + 1, y) + g((a + 1)^2)", &["f", "g"])
+    );
+}
+
+#[test]
+fn test_derivative_inner_is_substituted() {
+    let expr = parse_with_functions("diff(f(x, y), x)", &["f"]);
+    let replaced = expr.substitute("y", &Expr::number(3.0));
+    let ExprKind::Derivative { inner, var, order } = &replaced.kind else {
+        panic!("expected a derivative, got {replaced}");
+    };
+    assert_eq!(var.as_str(), "x");
+    assert_eq!(*order, 1);
+    assert_eq!(**inner, parse_with_functions("f(x, 3)", &["f"]));
+}
+
+#[test]
+fn test_derivative_variable_renamed_by_symbol() {
+    let expr = parse_with_functions("diff(f(x), x, 2)", &["f"]);
+    let replaced = expr.substitute("x", &symb("u").into());
+    assert_eq!(replaced, parse_with_functions("diff(f(u), u, 2)", &["f"]));
+}
+
+#[test]
+fn test_polynomial_over_variable_is_rebuilt() {
+    let expr = parse_plain("x^3 + 2*x^2 - x + 5").to_poly();
+    assert!(matches!(expr.kind, ExprKind::Poly(_)));
+    let replaced = expr.substitute("x", &parse_plain("t - 1"));
+    assert!(!replaced.contains_var("x"));
+    let x = 2.5_f64 - 1.0;
+    assert_close(
+        eval(&replaced, &[("t", 2.5)]),
+        x.powi(3) + 2.0 * x * x - x + 5.0,
+    );
+}
+
+#[test]
+fn test_polynomial_over_other_variable_is_shared() {
+    let expr = parse_plain("y^3 + 2*y^2 - y + 5").to_poly();
+    assert_eq!(expr.substitute("x", &Expr::number(1.0)), expr);
+}
+
+#[test]
+fn test_replacement_containing_the_variable() {
+    let expr = parse_plain("x^2 + sin(x)");
+    let replaced = expr.substitute("x", &parse_plain("x + 1"));
+    assert!(replaced.contains_var("x"));
+    let x = 0.7_f64 + 1.0;
+    assert_close(eval(&replaced, &[("x", 0.7)]), x * x + x.sin());
+}
+
+#[test]
+fn test_nested_substitutions() {
+    // x -> a*t + b, then t -> 2*s, then a, b -> numbers
+    let expr = parse_plain("exp(x) * x");
+    let replaced = expr
+        .substitute("x", &parse_plain("a*t + b"))
+        .substitute("t", &parse_plain("2*s"))
+        .substitute("a", &Expr::number(0.5))
+        .substitute("b", &Expr::number(-1.0));
+    let x = 0.5 * 2.0 * 3.0 - 1.0_f64;
+    assert_close(eval(&replaced, &[("s", 3.0)]), x.exp() * x);
+}
+
+#[test]
+fn test_substitution_into_derivative_result() {
+    let derivative = parse_plain("x^3 * sin(x)").diff("x").unwrap();
+    let replaced = derivative.substitute("x", &parse_plain("a*t + b"));
+    let x = 0.3_f64 * 2.0 + 0.1;
+    assert_close(
+        eval(&replaced, &[("a", 0.3), ("t", 2.0), ("b", 0.1)]),
+        3.0 * x * x * x.sin() + x.powi(3) * x.cos(),
+    );
+}
+
+#[test]
+fn test_unchanged_subtrees_are_shared() {
+    let expr = parse_plain("sin(y)*cos(y) + x");
+    let ExprKind::Sum(before) = &expr.kind else {
+        panic!("expected a sum");
+    };
+    let replaced = expr.substitute("x", &Expr::number(2.0));
+    let ExprKind::Sum(after) = &replaced.kind else {
+        panic!("expected a sum");
+    };
+    let untouched = before.iter().find(|t| !t.contains_var("x")).unwrap();
+    assert!(after.iter().any(|t| Arc::ptr_eq(t, untouched)));
+}
+
+#[test]
+fn test_matches_context_symbols_by_name() {
+    let ctx = Context::new().with_symbol("x");
+    let expr = parse("x^2 + 1", &HashSet::new(), &HashSet::new(), Some(&ctx)).unwrap();
+    let replaced = expr.substitute("x", &Expr::number(3.0));
+    assert_close(eval(&replaced, &[]), 10.0);
+}
+
+#[test]
+fn test_substitute_expr_replaces_subexpression() {
+    let expr = parse_plain("sin(x)^2 + 3*sin(x) + cos(x)");
+    let replaced = expr.substitute_expr(&parse_plain("sin(x)"), &symb("u").into());
+    assert!(replaced.contains_var("x")); // cos(x) is untouched
+    assert_eq!(replaced, parse_plain("u^2 + 3*u + cos(x)"));
+}
+
+#[test]
+fn test_substitute_expr_inside_custom_function_argument() {
+    let expr = parse_with_functions("f(x*y, 2) + g(x*y)", &["f", "g"]);
+    let replaced = expr.substitute_expr(&parse_plain("x*y"), &symb("w").into());
+    assert_eq!(
+        replaced,
+        parse_with_functions("f(w, 2) + g(w)", &["f", "g"])
+    );
+}
+
+#[test]
+fn test_substitute_symbol_into_derivative_result() {
+    let (x, r, theta) = (symb("x"), symb("r"), symb("theta"));
+    let derivative = parse_plain("x^2 * exp(x)").diff("x").unwrap();
+    let polar = r * theta.cos();
+    let replaced = derivative.substitute_symbol(&x, &polar);
+    assert!(!replaced.contains_var("x"));
+    let x = 1.5 * 0.4_f64.cos();
+    assert_close(
+        eval(&replaced, &[("r", 1.5), ("theta", 0.4)]),
+        (2.0 * x + x * x) * x.exp(),
+    );
+}
+
+#[test]
+fn test_substitute_symbol_matches_by_identity() {
+    // A context creates its own symbol for a name the global registry does not know yet
+    let ctx = Context::new().with_symbol("subst_ctx_q");
+    let local = parse(
+        "subst_ctx_q + 1",
+        &HashSet::new(),
+        &HashSet::new(),
+        Some(&ctx),
+    )
+    .unwrap();
+    let global = symb("subst_ctx_q");
+    assert_eq!(local.substitute_symbol(&global, &Expr::number(2.0)), local);
+    let local_sym = ctx.symb("subst_ctx_q");
+    assert_close(
+        eval(
+            &local.substitute_symbol(&local_sym, &Expr::number(2.0)),
+            &[],
+        ),
+        3.0,
+    );
+}
+
+#[test]
+fn test_substitute_expr_without_occurrence_is_noop() {
+    let expr = parse_plain("x^2 + sin(y)");
+    let target = parse_plain("cos(y)");
+    let replaced = expr.substitute_expr(&target, &Expr::number(1.0));
+    assert_eq!(replaced, expr);
+    assert_eq!(replaced.structural_hash(), expr.structural_hash());
+    assert_eq!(expr.substitute_many(&[(target, Expr::number(1.0))]), expr);
+}
+
+#[test]
+fn test_substitute_expr_inside_polynomial() {
+    let expr = parse_plain("3*x^2 + x + 1").to_poly();
+    assert!(matches!(expr.kind, ExprKind::Poly(_)));
+    let replaced = expr.substitute_expr(&parse_plain("x^2"), &symb("s").into());
+    assert!(replaced.contains_var("s"));
+    assert_close(
+        eval(&replaced, &[("x", 2.0), ("s", 10.0)]),
+        30.0 + 2.0 + 1.0,
+    );
+    // The whole polynomial is itself a target
+    assert_eq!(
+        expr.substitute_expr(&expr, &Expr::number(0.0)),
+        Expr::number(0.0)
+    );
+}
+
+#[test]
+fn test_substitute_many_is_simultaneous() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = x.pow(3.0) + x.sin() * y;
+    let swapped = expr.substitute_many(&[(x.into(), y.into()), (y.into(), x.into())]);
+    // Sequential substitution would collapse both to one variable
+    assert_eq!(swapped, y.pow(3.0) + y.sin() * x);
+    assert_eq!(
+        swapped.structural_hash(),
+        (y.pow(3.0) + y.sin() * x).structural_hash()
+    );
+}
+
+#[test]
+fn test_substitute_many_renames_derivative_variable() {
+    let expr = parse_with_functions("diff(f(x, y), x)", &["f"]);
+    let replaced = expr.substitute_many(&[
+        (symb("x").into(), symb("y").into()),
+        (symb("y").into(), symb("x").into()),
+    ]);
+    assert_eq!(replaced, parse_with_functions("diff(f(y, x), y)", &["f"]));
+}
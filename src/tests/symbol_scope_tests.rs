@@ -0,0 +1,131 @@
+//! Tests for `SymbolScope`: private symbol namespaces and the entry points that take one
+
+use crate::{
+    Diff, DiffError, Expr, ParserOptions, Simplify, SymbolScope, parse, parse_configured, symb,
+    symbol_count, symbol_exists,
+};
+use std::collections::HashSet;
+
+fn parse_scoped(input: &str, scope: &SymbolScope) -> Expr {
+    let options = ParserOptions {
+        scope: Some(scope.clone()),
+        ..ParserOptions::default()
+    };
+    parse_configured(input, &HashSet::new(), &HashSet::new(), None, &options)
+        .unwrap()
+        .expr
+}
+
+#[test]
+fn test_scopes_define_distinct_symbols() {
+    let (a, b) = (SymbolScope::new(), SymbolScope::new());
+    let (xa, xb) = (a.symb("x"), b.symb("x"));
+    assert_ne!(xa, xb);
+    assert_ne!(xa, symb("x"));
+    assert_eq!(a.symb("x"), xa);
+    assert_eq!(a.get("x"), Some(xa));
+    assert!(a.contains(&xa) && !a.contains(&xb) && !a.contains(&symb("x")));
+    assert_eq!(xa.name().as_deref(), Some("x"));
+
+    // Parsing resolves names in the scope; built-in constants stay shared
+    let (pa, pb) = (parse_scoped("x^2 + pi", &a), parse_scoped("x^2 + pi", &b));
+    assert_eq!(pa, xa.pow(2.0) + symb("pi"));
+    assert_ne!(pa, pb);
+    assert_eq!(pa.to_string(), pb.to_string());
+    assert_ne!(
+        pa,
+        parse("x^2 + pi", &HashSet::new(), &HashSet::new(), None).unwrap()
+    );
+    assert_eq!(a.len(), 1);
+}
+
+#[test]
+fn test_scoped_symbols_stay_out_of_global_table() {
+    let name = "symbol_scope_test_only_name";
+    let scope = SymbolScope::new();
+    let before = symbol_count();
+    for i in 0..200 {
+        let _ = scope.symb(&format!("{name}_{i}"));
+    }
+    // Other tests only add global symbols meanwhile, never 200 scoped ones
+    assert!(symbol_count().saturating_sub(before) < 200);
+    assert!(!symbol_exists(&format!("{name}_0")));
+    assert_eq!(scope.len(), 200);
+}
+
+#[test]
+fn test_drop_releases_names() {
+    let scope = SymbolScope::new();
+    let t = scope.symb("t");
+    let expr = t.pow(2.0);
+    let clone = scope.clone();
+    drop(scope);
+    assert_eq!(
+        t.name().as_deref(),
+        Some("t"),
+        "a clone keeps the scope alive"
+    );
+
+    drop(clone);
+    assert_eq!(t.name(), None);
+    assert!(symb("t").name().is_some());
+    // Expressions keep their names
+    assert_eq!(expr.to_string(), "t^2");
+}
+
+#[test]
+fn test_mixing_scopes_is_an_error() {
+    let (a, b) = (SymbolScope::new(), SymbolScope::new());
+    let t = a.symb("t");
+    let mixed = t * b.symb("t") + 1.0;
+
+    let err = Diff::new().scope(&a).differentiate(&mixed, &t).unwrap_err();
+    assert_eq!(
+        err,
+        DiffError::ScopeMismatch {
+            name: "t".to_owned()
+        }
+    );
+    let err = Simplify::new().scope(&a).simplify(&mixed).unwrap_err();
+    assert!(matches!(err, DiffError::ScopeMismatch { .. }));
+    // A global symbol named like a scoped one is just as ambiguous
+    assert!(a.check(&(t + symb("t"))).is_err());
+    // Differentiating by a symbol of another scope
+    let own = t.pow(2.0);
+    assert!(
+        Diff::new()
+            .scope(&a)
+            .differentiate(&own, &b.symb("t"))
+            .is_err()
+    );
+
+    // Global symbols with other names and the scope's own pass
+    let with_global = t * symb("symbol_scope_test_k");
+    assert!(a.check(&with_global).is_ok());
+    assert_eq!(
+        Diff::new()
+            .scope(&a)
+            .differentiate(&with_global, &t)
+            .unwrap()
+            .to_string(),
+        "symbol_scope_test_k"
+    );
+}
+
+#[test]
+fn test_string_entry_points_parse_in_scope() {
+    let scope = SymbolScope::new();
+    let result = Diff::new()
+        .scope(&scope)
+        .diff_str("a*x^3", "x", &[])
+        .unwrap();
+    assert_eq!(result, "3*a*x^2");
+    assert!(scope.get("x").is_some() && scope.get("a").is_some());
+
+    let result = Simplify::new()
+        .scope(&scope)
+        .simplify_str("x*x + sin(y)^2 + cos(y)^2", &[])
+        .unwrap();
+    assert_eq!(result, "1 + x^2");
+    assert!(scope.get("y").is_some());
+}
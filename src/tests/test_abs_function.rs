@@ -43,3 +43,59 @@ fn test_abs_in_product() {
         simplified
     );
 }
+
+fn parse_plain(input: &str) -> crate::Expr {
+    parse(input, &HashSet::new(), &HashSet::new(), None).unwrap()
+}
+
+#[test]
+fn test_abs_derivative_applies_chain_rule() {
+    let diff = crate::Diff::new();
+    assert_eq!(
+        diff.diff_str("abs(sin(x))", "x", &[]).unwrap(),
+        simplify("signum(sin(x)) * cos(x)", &[], None).unwrap()
+    );
+    assert_eq!(diff.diff_str("abs(x)", "x", &[]).unwrap(), "signum(x)");
+    // An argument free of the variable is a constant
+    assert_eq!(diff.diff_str("abs(y) * x", "x", &[]).unwrap(), "abs(y)");
+}
+
+#[test]
+fn test_domain_safe_rejects_abs_derivative() {
+    let diff = crate::Diff::new().domain_safe(true);
+    match diff
+        .diff_str("x^2 + abs(x - 1)", "x", &[])
+        .as_ref()
+        .map_err(crate::DiffError::unlocated)
+    {
+        Err(crate::DiffError::NonDifferentiable { function, note }) => {
+            assert_eq!(function, "abs");
+            assert!(note.contains("u = 0"), "{note}");
+        }
+        other => panic!("expected NonDifferentiable, got {other:?}"),
+    }
+    // abs of an argument not depending on the variable is still fine
+    assert_eq!(diff.diff_str("abs(y) * x", "x", &[]).unwrap(), "abs(y)");
+}
+
+#[test]
+fn test_abs_simplification_rules() {
+    for (input, expected) in [
+        ("abs(x^2)", "x^2"),
+        ("abs(x^4)", "x^4"),
+        ("abs(-x)", "abs(x)"),
+        ("abs(abs(x))", "abs(x)"),
+        ("abs(-abs(x))", "abs(x)"),
+    ] {
+        assert_eq!(simplify(input, &[], None).unwrap(), expected, "{input}");
+    }
+}
+
+#[test]
+fn test_compiled_abs_matches_f64_abs() {
+    let expr = parse_plain("abs(x - 2)");
+    let compiled = crate::CompiledEvaluator::compile(&expr, &["x"], None).unwrap();
+    for x in [-3.5, 0.0, 2.0, 7.25] {
+        assert!((compiled.evaluate(&[x]) - (x - 2.0_f64).abs()).abs() < 1e-15);
+    }
+}
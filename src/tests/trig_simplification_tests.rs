@@ -1,5 +1,5 @@
 use crate::simplification::simplify_expr;
-use crate::{Expr, core::ExprKind};
+use crate::{Expr, Simplify, core::ExprKind, symb};
 use std::collections::{HashMap, HashSet};
 use std::f64::consts::PI;
 #[test]
@@ -208,6 +208,30 @@ fn test_pythagorean_identities() {
     }
 }
 
+#[test]
+fn test_pythagorean_identity_with_coefficients() {
+    let simplify = |input: &str| Simplify::new().simplify_str(input, &[]).unwrap();
+
+    assert_eq!(simplify("sin(a*x + b)^2 + cos(a*x + b)^2"), "1");
+    assert_eq!(simplify("2*sin(x)^2 + 2*cos(x)^2"), "2");
+    assert_eq!(simplify("2*sin(a*x + b)^2 + 2*cos(a*x + b)^2"), "2");
+    assert_eq!(simplify("-3*sin(x)^2 - 3*cos(x)^2"), "-3");
+    assert_eq!(simplify("sin(x)^2/2 + cos(x)^2/2"), "1/2");
+    // The pair may sit among other terms
+    assert_eq!(simplify("sin(x)^2 + y + cos(x)^2"), simplify("1 + y"));
+    assert_eq!(simplify("a*sin(x)^2 + b + a*cos(x)^2"), simplify("a + b"));
+    // Different coefficients or arguments are not an identity
+    let without_rule = |input: &str| {
+        Simplify::new()
+            .disable_rule("pythagorean_identity")
+            .simplify_str(input, &[])
+            .unwrap()
+    };
+    for input in ["3*sin(x)^2 + 2*cos(x)^2", "sin(x)^2 + cos(y)^2"] {
+        assert_eq!(simplify(input), without_rule(input), "{input}");
+    }
+}
+
 #[test]
 fn test_cofunction_identities() {
     // sin(pi/2 - x) = cos(x) represented as Sum([pi/2, Product([-1, x])])
@@ -349,6 +373,56 @@ fn test_trig_periodicity_general() {
     }
 }
 
+fn simplify_periodic(input: &str) -> String {
+    Simplify::new()
+        .integer_var(&symb("n"))
+        .simplify_str(input, &[])
+        .unwrap()
+}
+
+#[test]
+fn test_trig_exact_period_reduction() {
+    assert_eq!(simplify_periodic("sin(x + 6*pi)"), "sin(x)");
+    assert_eq!(simplify_periodic("cos(x - 4*pi)"), "cos(x)");
+    assert_eq!(simplify_periodic("sin(x + y + 4*pi)"), "sin(x + y)");
+    assert_eq!(simplify_periodic("tan(x + 3*pi)"), "tan(x)");
+    assert_eq!(simplify_periodic("sin(x + 5*pi/2)"), "cos(x)");
+    // Reduced but not a quarter turn
+    assert_eq!(
+        simplify_periodic("sin(x + 7*pi/3)"),
+        simplify_periodic("sin(x + pi/3)")
+    );
+}
+
+#[test]
+fn test_trig_integer_multiple_of_period() {
+    assert_eq!(simplify_periodic("cos(2*pi*n + phi)"), "cos(phi)");
+    assert_eq!(simplify_periodic("sin(x - 4*pi*n)"), "sin(x)");
+    assert_eq!(simplify_periodic("tan(pi*n + x)"), "tan(x)");
+
+    // cos(pi*n + x) = (-1)^n*cos(x) depends on the parity of n
+    assert_eq!(simplify_periodic("cos(pi*n + x)"), "cos(x + n*pi)");
+    // Without the integer assumption nothing is known about k
+    assert_eq!(simplify_periodic("cos(2*pi*k + phi)"), "cos(phi + 2*k*pi)");
+}
+
+#[test]
+fn test_trig_phase_shifts() {
+    assert_eq!(simplify_periodic("sin(x + pi)"), "-sin(x)");
+    assert_eq!(simplify_periodic("cos(x + pi)"), "-cos(x)");
+    assert_eq!(simplify_periodic("sin(x + pi/2)"), "cos(x)");
+    assert_eq!(simplify_periodic("cos(x + pi/2)"), "-sin(x)");
+    assert_eq!(simplify_periodic("sin(x + 3*pi/2)"), "-cos(x)");
+    assert_eq!(simplify_periodic("cos(x + 3*pi/2)"), "sin(x)");
+    assert_eq!(simplify_periodic("tan(x + pi/2)"), "-cot(x)");
+}
+
+#[test]
+fn test_trig_inexact_shift_untouched() {
+    assert_eq!(simplify_periodic("sin(x + 6.1*pi)"), "sin(6.1*pi + x)");
+    assert_eq!(simplify_periodic("sin(x + pi/3)"), "sin((pi + 3*x)/3)");
+}
+
 #[test]
 fn test_trig_reflection_shifts() {
     // sin(pi - x) = sin(x) represented as Sum([pi, Product([-1, x])])
@@ -593,3 +667,74 @@ fn test_double_angle_formulas() {
         panic!("Expected tan(2*x), got {:?}", simplified);
     }
 }
+
+fn simplify_trig(input: &str) -> String {
+    crate::simplify(input, &[], None).unwrap()
+}
+
+#[test]
+fn test_derivative_of_sin_cos_folds_to_double_angle() {
+    assert_eq!(
+        crate::diff("sin(x)*cos(x)", "x", &[], None).unwrap(),
+        "cos(2*x)"
+    );
+}
+
+#[test]
+fn test_cos_angle_addition_folding() {
+    // Compared with the simplified expected form: the term order inside the
+    // argument follows the symbol ordering
+    let folds = [
+        ("cos(x)*cos(y) - sin(x)*sin(y)", "cos(x + y)"),
+        ("-sin(y)*sin(x) + cos(y)*cos(x)", "cos(x + y)"),
+        ("3*cos(a)*cos(b) - 3*sin(a)*sin(b)", "3*cos(a + b)"),
+        ("z + cos(x)*cos(y) - sin(x)*sin(y)", "z + cos(x + y)"),
+    ];
+    for (input, folded) in folds {
+        assert_eq!(simplify_trig(input), simplify_trig(folded), "{input}");
+    }
+    // cos is even, so either sign of the difference is the folded form
+    let difference = simplify_trig("sin(x)*sin(y) + cos(x)*cos(y)");
+    assert!(
+        [simplify_trig("cos(x - y)"), simplify_trig("cos(y - x)")].contains(&difference),
+        "{difference}"
+    );
+    // Mismatched coefficients are left alone
+    let kept = simplify_trig("cos(x)*cos(y) - 2*sin(x)*sin(y)");
+    assert!(
+        kept.contains("cos(x)*cos(y)") || kept.contains("cos(y)*cos(x)"),
+        "{kept}"
+    );
+}
+
+#[test]
+fn test_cos_double_angle_with_constant() {
+    assert_eq!(simplify_trig("1 - 2*sin(x)^2"), "cos(2*x)");
+    assert_eq!(simplify_trig("-2*sin(x)^2 + 1"), "cos(2*x)");
+    assert_eq!(simplify_trig("2*cos(x)^2 - 1"), "cos(2*x)");
+    assert_eq!(simplify_trig("3 - 6*sin(t)^2"), "3*cos(2*t)");
+    assert_eq!(simplify_trig("1 - 2*cos(x)^2"), "-cos(2*x)");
+    assert_eq!(simplify_trig("2 - 2*sin(x)^2"), "2*cos(x)^2");
+}
+
+#[test]
+fn test_expand_trig_unfolds_angles() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expand = |expr: Expr| Simplify::new().expand_trig(true).simplify(&expr).unwrap();
+    let (sx, cx, sy, cy) = (x.sin(), x.cos(), y.sin(), y.cos());
+    assert_eq!(
+        expand((x + y).sin()),
+        sx.clone() * cy.clone() + cx.clone() * sy.clone()
+    );
+    assert_eq!(expand((x + y).cos()), cx.clone() * cy - sx.clone() * sy);
+    assert_eq!(expand((2.0 * x).sin()), 2.0 * sx.clone() * cx.clone());
+    assert_eq!(expand((2.0 * x).cos()), cx.pow(2.0) - sx.pow(2.0));
+    // The folding rules undo the expansion
+    for input in ["sin(x + y)", "cos(x + y)", "sin(2*x)", "cos(2*x)"] {
+        let expanded = Simplify::new()
+            .expand_trig(true)
+            .simplify_str(input, &[])
+            .unwrap();
+        assert_eq!(simplify_trig(&expanded), simplify_trig(input), "{input}");
+    }
+}
@@ -0,0 +1,189 @@
+//! Caller-supplied simplification rules registered with `Simplify::with_rule`
+
+use crate::{DiffError, Expr, ExprView, RuleCategory, Simplify, UserRule, symb};
+
+/// `myfunc(u)*myfunc(u)`, which the constructors store as `myfunc(u)^2`, becomes
+/// `myfunc2(u)`
+struct SquareMyfunc;
+
+impl UserRule for SquareMyfunc {
+    fn name(&self) -> &'static str {
+        "square_myfunc"
+    }
+    fn priority(&self) -> i32 {
+        90
+    }
+    fn apply(&self, expr: &ExprView<'_>) -> Option<Expr> {
+        let ExprView::Pow(base, exp) = expr else {
+            return None;
+        };
+        if exp.view().as_number() != Some(2.0) {
+            return None;
+        }
+        match base.view() {
+            ExprView::Function {
+                name: "myfunc",
+                args,
+            } if args.len() == 1 => Some(Expr::func("myfunc2", (*args[0]).clone())),
+            _ => None,
+        }
+    }
+}
+
+/// A rule that claims the name of a built-in one
+struct Impostor;
+
+impl UserRule for Impostor {
+    fn name(&self) -> &'static str {
+        "sqrt_power"
+    }
+    fn priority(&self) -> i32 {
+        0
+    }
+    fn apply(&self, _expr: &ExprView<'_>) -> Option<Expr> {
+        None
+    }
+}
+
+/// Any two-term sum `a + b` becomes `pair(a, b)`, at a chosen priority
+struct PairSums(i32);
+
+impl UserRule for PairSums {
+    fn name(&self) -> &'static str {
+        "pair_sums"
+    }
+    fn priority(&self) -> i32 {
+        self.0
+    }
+    fn apply(&self, expr: &ExprView<'_>) -> Option<Expr> {
+        match expr {
+            ExprView::Sum(terms) if terms.len() == 2 => Some(Expr::func_multi(
+                "pair",
+                terms.iter().map(|term| (**term).clone()).collect(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn myfunc_squared(arg: Expr) -> Expr {
+    let f = Expr::func("myfunc", arg);
+    f.clone() * f
+}
+
+#[test]
+fn test_user_rule_rewrites_product() {
+    let x = symb("x");
+    let result = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .simplify(&myfunc_squared(x.into()))
+        .unwrap();
+    assert_eq!(result.to_string(), "myfunc2(x)");
+}
+
+#[test]
+fn test_user_rule_fires_in_nested_expressions() {
+    let (x, y) = (symb("x"), symb("y"));
+    let expr = myfunc_squared(x.into()).sin() + y * myfunc_squared(y.pow(2.0)) + 1.0;
+    let result = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .simplify(&expr)
+        .unwrap();
+    let expected = Expr::func("myfunc2", x).sin() + y * Expr::func("myfunc2", y.pow(2.0)) + 1.0;
+    assert_eq!(result, expected, "got {result}");
+}
+
+#[test]
+fn test_user_rule_interleaves_with_builtins() {
+    // The argument only matches after the built-in rules fold it to a single symbol
+    let x = symb("x");
+    let arg = x + x - x;
+    let result = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .simplify(&myfunc_squared(arg))
+        .unwrap();
+    assert_eq!(result.to_string(), "myfunc2(x)");
+}
+
+#[test]
+fn test_user_rule_follows_rule_selection() {
+    let x = symb("x");
+    let expr = myfunc_squared(x.into());
+    let disabled = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .disable_rule("square_myfunc")
+        .simplify(&expr)
+        .unwrap();
+    assert_eq!(disabled.to_string(), "myfunc(x)^2");
+
+    let other_category = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .only_categories(&[RuleCategory::Trigonometric])
+        .simplify(&expr)
+        .unwrap();
+    assert_eq!(other_category.to_string(), "myfunc(x)^2");
+}
+
+#[test]
+fn test_user_rule_name_must_be_unique() {
+    let x = symb("x");
+    let error = Simplify::new()
+        .with_rule(Box::new(Impostor))
+        .simplify(&x.into())
+        .unwrap_err();
+    assert_eq!(
+        error,
+        DiffError::DuplicateRule {
+            name: "sqrt_power".to_owned()
+        }
+    );
+
+    let twice = Simplify::new()
+        .with_rule(Box::new(SquareMyfunc))
+        .with_rule(Box::new(SquareMyfunc))
+        .simplify(&x.into());
+    assert!(matches!(twice, Err(DiffError::DuplicateRule { .. })));
+}
+
+#[test]
+fn test_user_rule_shared_across_threads() {
+    let simplify = Simplify::new().with_rule(Box::new(SquareMyfunc));
+    let results: Vec<String> = std::thread::scope(|scope| {
+        let handles: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|name| {
+                let simplify = &simplify;
+                scope.spawn(move || {
+                    simplify
+                        .simplify(&myfunc_squared(symb(name).into()))
+                        .unwrap()
+                        .to_string()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(results, ["myfunc2(a)", "myfunc2(b)"]);
+}
+
+#[test]
+fn test_extra_rules_preempt_lower_priority_builtins() {
+    // factor_difference_of_squares has priority 46
+    let run = |priority| {
+        Simplify::new()
+            .with_extra_rules(vec![Box::new(PairSums(priority)), Box::new(SquareMyfunc)])
+            .simplify_str("x^2 - y^2", &["y"])
+            .unwrap()
+    };
+    assert_eq!(run(50), "pair(x^2, -y^2)");
+    // Below it, the sum is factored first and only the factors are paired
+    let factored = run(40);
+    assert!(
+        factored.contains("pair(x, y)") && factored.contains("pair(x, -y)"),
+        "got {factored}"
+    );
+
+    let both = Simplify::new().with_extra_rules(vec![Box::new(SquareMyfunc)]);
+    let result = both.simplify(&myfunc_squared(symb("x").into())).unwrap();
+    assert_eq!(result.to_string(), "myfunc2(x)");
+}
@@ -0,0 +1,95 @@
+//! Tests for walking expressions through the public `ExprView` API
+
+use crate::core::ExprKind;
+use crate::{Condition, Expr, ExprView, symb};
+
+/// Rebuild `expr` from its view alone, the way downstream serializers would walk it.
+fn rebuild(expr: &Expr) -> Expr {
+    let children = |items: &[std::sync::Arc<Expr>]| items.iter().map(|e| rebuild(e)).collect();
+    match expr.view() {
+        ExprView::Number(n) => Expr::number(n),
+        ExprView::Symbol(name) => Expr::symbol(name.as_ref()),
+        ExprView::Function { name, args } => Expr::func_multi(name, children(args)),
+        ExprView::Sum(terms) => Expr::sum(children(&terms)),
+        ExprView::Product(factors) => Expr::product(children(&factors)),
+        ExprView::Div(num, den) => Expr::div_expr(rebuild(num), rebuild(den)),
+        ExprView::Pow(base, exp) => Expr::pow_static(rebuild(base), rebuild(exp)),
+        ExprView::Derivative { inner, var, order } => Expr::derivative(rebuild(inner), var, order),
+        ExprView::Piecewise { branches, default } => Expr::piecewise(
+            branches
+                .iter()
+                .map(|(c, value)| {
+                    let condition = Condition::new(c.op, rebuild(&c.lhs), rebuild(&c.rhs));
+                    (condition, rebuild(value))
+                })
+                .collect(),
+            rebuild(default),
+        ),
+    }
+}
+
+#[test]
+fn test_view_round_trip_covers_every_variant() {
+    let x = symb("view_rt_x");
+    let y = symb("view_rt_y");
+    let exprs = [
+        x.pow(2.0) + 2.0 * x + 1.0, // stored as a polynomial
+        x.sin() * y.exp() / (x + y),
+        Expr::func_multi("atan2", vec![y.to_expr(), x.to_expr()]),
+        Expr::derivative(Expr::func_multi("f", vec![x.to_expr()]), "view_rt_x", 2),
+        (x + y).pow(y) - Expr::number(3.5),
+        Expr::piecewise(vec![(Condition::lt(x, y), x.sin())], y.to_expr()),
+    ];
+    for expr in &exprs {
+        assert_eq!(&rebuild(expr), expr, "round trip of {expr}");
+    }
+}
+
+#[test]
+fn test_view_accessors() {
+    let x = symb("view_acc_x");
+    let y = symb("view_acc_y");
+
+    let pow = x.pow(y);
+    let (base, exp) = pow
+        .view()
+        .as_pow()
+        .map(|(b, e)| (b.to_string(), e.to_string()))
+        .unwrap();
+    assert_eq!((base.as_str(), exp.as_str()), ("view_acc_x", "view_acc_y"));
+
+    let div = x / y;
+    assert!(div.view().as_div().is_some());
+    assert!(div.view().as_pow().is_none());
+
+    let call = Expr::func_multi("atan2", vec![y.to_expr(), x.to_expr()]);
+    let view = call.view();
+    let (name, args) = view.as_function().unwrap();
+    assert_eq!(name, "atan2");
+    assert_eq!(args.len(), 2);
+
+    let deriv = Expr::derivative(x.sin(), "view_acc_x", 3);
+    let view = deriv.view();
+    let (inner, var, order) = view.as_derivative().unwrap();
+    assert_eq!(
+        (inner.to_string().as_str(), var, order),
+        ("sin(view_acc_x)", "view_acc_x", 3)
+    );
+
+    let product = x * y;
+    assert_eq!(product.view().as_product().map(<[_]>::len), Some(2));
+}
+
+#[test]
+fn test_polynomial_is_viewed_as_sum() {
+    let x = symb("view_poly_x");
+    let poly = x.pow(3.0) + 2.0 * x;
+    assert!(matches!(poly.kind, ExprKind::Poly(_)));
+    let view = poly.view();
+    let terms = view.as_sum().unwrap();
+    assert_eq!(terms.len(), 2);
+    assert_eq!(
+        Expr::sum(terms.iter().map(|t| t.as_ref().clone()).collect()),
+        poly
+    );
+}
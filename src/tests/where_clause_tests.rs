@@ -0,0 +1,108 @@
+//! Tests for `where` clauses: `expr where { name = expr; ... }` in every parse entry point
+
+use crate::{Diff, DiffError, Expr, Span, parse};
+use std::collections::HashSet;
+
+fn parse_plain(input: &str) -> Result<Expr, DiffError> {
+    parse(input, &HashSet::new(), &HashSet::new(), None)
+}
+
+#[test]
+fn test_definitions_are_substituted() {
+    assert_eq!(
+        parse_plain("x^2 + y^2 where { y = sin(x); }").unwrap(),
+        parse_plain("x^2 + sin(x)^2").unwrap()
+    );
+    // The last separator is optional, and definitions may use each other in any order
+    assert_eq!(
+        parse_plain("a + b where { a = b^2; b = x + 1 }").unwrap(),
+        parse_plain("(x + 1)^2 + (x + 1)").unwrap()
+    );
+    // Defined names are symbols, however long
+    assert_eq!(
+        parse_plain("speed*t where { speed = 3*x }").unwrap(),
+        parse_plain("3*x*t").unwrap()
+    );
+    assert_eq!(
+        Diff::new()
+            .diff_str("x*y where { y = x^2 }", "x", &[])
+            .unwrap(),
+        "3*x^2"
+    );
+}
+
+#[test]
+fn test_nested_clauses() {
+    assert_eq!(
+        parse_plain("u*v where { u = w + 1 where { w = x^2; }; v = cos(x) }").unwrap(),
+        parse_plain("(x^2 + 1)*cos(x)").unwrap()
+    );
+    // An inner clause may use names of the outer one
+    assert_eq!(
+        parse_plain("y where { y = 2*z where { z = k }; k = sin(x) }").unwrap(),
+        parse_plain("2*sin(x)").unwrap()
+    );
+}
+
+#[test]
+fn test_circular_definitions() {
+    let err = parse_plain("x + y where { y = y + 1; }").unwrap_err();
+    assert_eq!(
+        err,
+        DiffError::CircularDefinition {
+            cycle: vec!["y".to_owned(), "y".to_owned()],
+            span: Some(Span::new(14, 15)),
+        }
+    );
+    let err = parse_plain("a where { a = b; b = c; c = 2*a }").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Circular definition: a -> b -> c -> a at position 11"
+    );
+    // Spans of a nested clause point into the whole formula
+    let err = parse_plain("u where { u = v where { v = v^2 } }").unwrap_err();
+    assert!(
+        matches!(err, DiffError::CircularDefinition { span: Some(span), .. } if span == Span::new(24, 25)),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn test_known_symbol_shadowing_a_definition() {
+    let fixed: HashSet<String> = HashSet::from(["y".to_owned()]);
+    let err = parse("x*y where { y = x }", &fixed, &HashSet::new(), None).unwrap_err();
+    assert!(
+        matches!(&err, DiffError::InvalidSyntax { msg, span: Some(span) }
+            if msg.contains("'y' is a known symbol") && *span == Span::new(12, 13)),
+        "{err:?}"
+    );
+    // So is a name an enclosing clause already defines
+    assert!(parse_plain("y where { y = z where { z = 1; y = 2 }; z = 3 }").is_err());
+    // Known symbols that are not defined keep their meaning
+    let fixed: HashSet<String> = HashSet::from(["rate".to_owned()]);
+    assert_eq!(
+        parse("rate*y where { y = x }", &fixed, &HashSet::new(), None).unwrap(),
+        parse("rate*x", &fixed, &HashSet::new(), None).unwrap()
+    );
+}
+
+#[test]
+fn test_without_clause_and_malformed() {
+    // `where` is an ordinary name unless a brace follows
+    assert_eq!(parse_plain("2*where").unwrap().to_string(), "2*where");
+    for (input, message) in [
+        ("x where { y = 1", "never closed"),
+        ("x where { y = 1 } + 2", "unexpected input after"),
+        ("where { y = 1 }", "without an expression"),
+        ("x where { 2 = 1 }", "cannot assign to '2'"),
+        ("x where { y = 1; y = 2 }", "'y' is already defined"),
+        ("x where { y }", "expected 'name = expression'"),
+        ("x + y where { y = }", "'y' has no definition"),
+    ] {
+        let err = parse_plain(input).unwrap_err();
+        assert!(
+            matches!(&err, DiffError::InvalidSyntax { msg, .. } if msg.contains(message)),
+            "{input}: {err:?}"
+        );
+    }
+}
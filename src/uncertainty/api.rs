@@ -1,7 +1,10 @@
-use super::logic::compute_uncertainty_terms;
+use super::logic::{
+    compute_covariance_terms, compute_uncertainty_terms, reject_carried_uncertainty,
+};
 use crate::core::known_symbols::{KS, get_symbol};
-use crate::core::{Context, DiffError, Expr};
+use crate::core::{Context, DiffError, Expr, symb};
 use crate::diff::Diff;
+use std::borrow::Cow;
 
 #[cfg(feature = "parallel")]
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
@@ -13,6 +16,8 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 pub struct Uncertainty<'ctx> {
     context: Option<&'ctx Context>,
     covariance: Option<&'ctx CovarianceMatrix>,
+    #[cfg(feature = "parallel")]
+    pool: Option<&'ctx rayon::ThreadPool>,
 }
 
 impl<'ctx> Uncertainty<'ctx> {
@@ -38,22 +43,213 @@ impl<'ctx> Uncertainty<'ctx> {
         self
     }
 
+    /// Compute the partial derivatives on a specific Rayon thread pool
+    /// instead of the global one (requires `parallel` feature)
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    pub const fn thread_pool(mut self, pool: &'ctx rayon::ThreadPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
     /// Propagate uncertainties through the expression
     ///
     /// # Errors
-    /// Returns `DiffError` if differentiation fails or matrix dimensions mismatch.
+    /// Returns `DiffError` if differentiation fails or matrix dimensions mismatch, or
+    /// `DiffError::UncertaintyAlreadyPropagated` if `expr` already contains the
+    /// uncertainty of one of `variables` (see [`Symbol::as_uncertainty_of`](crate::Symbol::as_uncertainty_of)).
     pub fn propagate(&self, expr: &Expr, variables: &[&str]) -> Result<Expr, DiffError> {
+        if variables.is_empty() {
+            return Ok(Expr::number(0.0));
+        }
+        reject_carried_uncertainty(expr, variables)?;
+
+        let diff = self.diff();
+        let partials = self.partials(&diff, expr, variables)?;
+        self.std_dev(&partials, variables)
+    }
+
+    /// Variance `σ_f²` of the expression, the square of [`propagate`](Self::propagate)
+    ///
+    /// The result is the simplified symbolic sum Σᵢ Σⱼ (∂f/∂xᵢ)(∂f/∂xⱼ) Cov(xᵢ, xⱼ), so it
+    /// can be inspected, transformed further, or compiled with
+    /// [`CompiledEvaluator`](crate::CompiledEvaluator) for numeric evaluation.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Uncertainty, symb};
+    /// let (x, y) = (symb("x"), symb("y"));
+    /// let variance = Uncertainty::new().variance(&(x + y), &["x", "y"]).unwrap();
+    /// assert_eq!(variance.to_string(), "sigma_x^2 + sigma_y^2");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`propagate`](Self::propagate).
+    pub fn variance(&self, expr: &Expr, variables: &[&str]) -> Result<Expr, DiffError> {
+        if variables.is_empty() {
+            return Ok(Expr::number(0.0));
+        }
+        reject_carried_uncertainty(expr, variables)?;
+
+        let diff = self.diff();
+        let partials = self.partials(&diff, expr, variables)?;
+        let cov = self.resolve_covariance(variables)?;
+        Expr::sum(compute_uncertainty_terms(&partials, &cov, variables.len())?).simplified()
+    }
+
+    /// Covariance matrix of several outputs, `J Σ Jᵀ`
+    ///
+    /// `J` is the Jacobian of `exprs` with respect to `variables` and `Σ` the input
+    /// covariance. Entry `[a][b]` is the symbolic covariance of `exprs[a]` and
+    /// `exprs[b]`; the diagonal holds their variances, as from
+    /// [`variance`](Self::variance). The matrix is symmetric.
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`propagate`](Self::propagate), for
+    /// any of the outputs.
+    pub fn output_covariance(
+        &self,
+        exprs: &[Expr],
+        variables: &[&str],
+    ) -> Result<Vec<Vec<Expr>>, DiffError> {
+        if variables.is_empty() {
+            return Ok(vec![vec![Expr::number(0.0); exprs.len()]; exprs.len()]);
+        }
+        for expr in exprs {
+            reject_carried_uncertainty(expr, variables)?;
+        }
+
+        let diff = self.diff();
+        let jacobian = exprs
+            .iter()
+            .map(|expr| self.partials(&diff, expr, variables))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cov = self.resolve_covariance(variables)?;
         let n = variables.len();
-        if n == 0 {
+
+        let mut upper: Vec<Vec<Expr>> = Vec::with_capacity(exprs.len());
+        for (a, row_a) in jacobian.iter().enumerate() {
+            let mut row = Vec::with_capacity(exprs.len() - a);
+            row.push(Expr::sum(compute_uncertainty_terms(row_a, &cov, n)?).simplified()?);
+            for row_b in &jacobian[a + 1..] {
+                let terms = compute_covariance_terms(row_a, row_b, &cov, n)?;
+                row.push(Expr::sum(terms).simplified()?);
+            }
+            upper.push(row);
+        }
+        Ok((0..exprs.len())
+            .map(|a| {
+                (0..exprs.len())
+                    .map(|b| {
+                        let (i, j) = if a <= b { (a, b) } else { (b, a) };
+                        upper[i][j - i].clone()
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Propagate uncertainties through a composition `f(u₁(x), …, uₖ(x), x)` in one step
+    ///
+    /// `inner` defines each intermediate `uₖ` in terms of `variables`. The gradient of the
+    /// composition is assembled with the chain rule,
+    /// ∂f/∂xᵢ = ∂f/∂xᵢ|ᵤ + Σₖ (∂f/∂uₖ)(∂uₖ/∂xᵢ), so the result equals propagating through
+    /// `f` with every `uₖ` substituted. Feeding the output of one propagation into another
+    /// instead would treat `σ_u` as independent of `σ_x` and lose their correlation.
+    ///
+    /// # Example
+    /// ```
+    /// use symb_anafis::{Uncertainty, symb};
+    /// let (x, u) = (symb("x"), symb("u"));
+    /// let g = x.pow(2.0);
+    /// let sigma = Uncertainty::new()
+    ///     .propagate_composed(&u.sin(), &[("u", &g)], &["x"])
+    ///     .unwrap();
+    /// assert!(sigma.to_string().contains("sigma_x"));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `DiffError` for the same reasons as [`propagate`](Self::propagate), or
+    /// `DiffError::UnsupportedOperation` if an intermediate is itself a propagation
+    /// variable or an intermediate definition refers to another intermediate.
+    pub fn propagate_composed(
+        &self,
+        outer: &Expr,
+        inner: &[(&str, &Expr)],
+        variables: &[&str],
+    ) -> Result<Expr, DiffError> {
+        if variables.is_empty() {
             return Ok(Expr::number(0.0));
         }
+        reject_carried_uncertainty(outer, variables)?;
+        for &(name, definition) in inner {
+            if variables.contains(&name) {
+                return Err(DiffError::UnsupportedOperation(format!(
+                    "Intermediate '{name}' is also a propagation variable"
+                )));
+            }
+            if let Some(&(other, _)) = inner
+                .iter()
+                .find(|(other, _)| definition.contains_var(other))
+            {
+                return Err(DiffError::UnsupportedOperation(format!(
+                    "Definition of '{name}' refers to intermediate '{other}'"
+                )));
+            }
+            reject_carried_uncertainty(definition, variables)?;
+        }
+
+        let substitute_all = |e: &Expr| {
+            inner.iter().fold(e.clone(), |acc, &(name, definition)| {
+                acc.substitute(name, definition)
+            })
+        };
+
+        let diff = self.diff();
+        let names: Vec<&str> = inner.iter().map(|&(name, _)| name).collect();
+        let outer_partials: Vec<Expr> = self
+            .partials(&diff, outer, &names)?
+            .iter()
+            .map(substitute_all)
+            .collect();
+        let direct = self.partials(&diff, outer, variables)?;
 
-        // Compute all partial derivatives using the provided context
-        let mut diff = Diff::new();
-        if let Some(ctx) = self.context {
-            diff = diff.context(ctx);
+        let mut gradient = Vec::with_capacity(variables.len());
+        for (i, direct_i) in direct.iter().enumerate() {
+            let mut terms = vec![substitute_all(direct_i)];
+            for (k, &(_, definition)) in inner.iter().enumerate() {
+                let inner_partial = diff.differentiate_by_name(definition, variables[i])?;
+                if !inner_partial.is_zero_num() && !outer_partials[k].is_zero_num() {
+                    terms.push(Expr::mul_expr(outer_partials[k].clone(), inner_partial));
+                }
+            }
+            gradient.push(Expr::sum(terms).simplified()?);
         }
 
+        self.std_dev(&gradient, variables)
+    }
+
+    /// Differentiation builder sharing this propagation's context
+    fn diff(&self) -> Diff {
+        let diff = Diff::new();
+        match self.context {
+            Some(ctx) => diff.context(ctx),
+            None => diff,
+        }
+    }
+
+    /// Simplified partial derivatives of `expr` with respect to each of `variables`
+    #[cfg_attr(
+        not(feature = "parallel"),
+        allow(clippy::unused_self, reason = "self only carries the thread pool")
+    )]
+    fn partials(
+        &self,
+        diff: &Diff,
+        expr: &Expr,
+        variables: &[&str],
+    ) -> Result<Vec<Expr>, DiffError> {
         #[cfg(not(feature = "parallel"))]
         let partials: Result<Vec<Expr>, DiffError> = variables
             .iter()
@@ -64,33 +260,43 @@ impl<'ctx> Uncertainty<'ctx> {
             .collect();
 
         #[cfg(feature = "parallel")]
-        let partials: Result<Vec<Expr>, DiffError> = variables
-            .par_iter()
-            .map(|&var| {
-                let partial = diff.differentiate_by_name(expr, var)?;
-                partial.simplified()
-            })
-            .collect();
+        let partials: Result<Vec<Expr>, DiffError> = {
+            let compute = || {
+                variables
+                    .par_iter()
+                    .map(|&var| {
+                        let partial = diff.differentiate_by_name(expr, var)?;
+                        partial.simplified()
+                    })
+                    .collect()
+            };
+            self.pool.map_or_else(compute, |pool| pool.install(compute))
+        };
 
-        let partials = partials?;
+        partials
+    }
 
-        // Get or create covariance matrix
-        let default_cov;
-        let cov = if let Some(c) = self.covariance {
-            if c.dim() != n {
-                return Err(DiffError::UnsupportedOperation(format!(
-                    "Covariance matrix dimension ({}) doesn't match number of variables ({})",
-                    c.dim(),
-                    n
-                )));
-            }
-            c
-        } else {
-            default_cov = CovarianceMatrix::diagonal_symbolic(variables);
-            &default_cov
-        };
+    /// The covariance matrix set on the builder, or a symbolic diagonal one
+    fn resolve_covariance(
+        &self,
+        variables: &[&str],
+    ) -> Result<Cow<'ctx, CovarianceMatrix>, DiffError> {
+        let n = variables.len();
+        match self.covariance {
+            Some(c) if c.dim() != n => Err(DiffError::UnsupportedOperation(format!(
+                "Covariance matrix dimension ({}) doesn't match number of variables ({})",
+                c.dim(),
+                n
+            ))),
+            Some(c) => Ok(Cow::Borrowed(c)),
+            None => Ok(Cow::Owned(CovarianceMatrix::diagonal_symbolic(variables))),
+        }
+    }
 
-        let terms = compute_uncertainty_terms(&partials, cov, n)?;
+    /// `σ_f` from the gradient of f with respect to `variables`
+    fn std_dev(&self, partials: &[Expr], variables: &[&str]) -> Result<Expr, DiffError> {
+        let cov = self.resolve_covariance(variables)?;
+        let terms = compute_uncertainty_terms(partials, &cov, variables.len())?;
 
         let variance = Expr::sum(terms);
         let simplified_variance = variance.simplified()?;
@@ -126,6 +332,53 @@ pub fn uncertainty_propagation(
     builder.propagate(expr, variables)
 }
 
+/// Compute the propagated variance expression
+///
+/// Returns `σ_f²` = Σᵢ Σⱼ (∂f/∂xᵢ)(∂f/∂xⱼ) Cov(xᵢ, xⱼ) as a simplified expression, the
+/// square of [`uncertainty_propagation`]. See [`Uncertainty::variance`].
+///
+/// # Errors
+/// Returns `DiffError` if differentiation fails or matrix dimensions mismatch.
+pub fn propagated_variance(
+    expr: &Expr,
+    variables: &[&str],
+    covariance: Option<&CovarianceMatrix>,
+) -> Result<Expr, DiffError> {
+    let mut builder = Uncertainty::new();
+    if let Some(cov) = covariance {
+        builder = builder.covariance(cov);
+    }
+    builder.variance(expr, variables)
+}
+
+/// Compute the covariance matrix of several outputs, `J Σ Jᵀ`
+///
+/// `J` is the Jacobian of `exprs` with respect to `variables` and `Σ` the input
+/// covariance (symbolic and diagonal if `None`). See [`Uncertainty::output_covariance`].
+///
+/// # Example
+/// ```
+/// use symb_anafis::{CovEntry, CovarianceMatrix, propagate_covariance, symb};
+/// let (x, y) = (symb("x"), symb("y"));
+/// let cov = CovarianceMatrix::diagonal(vec![CovEntry::Num(1.0), CovEntry::Num(4.0)]);
+/// let out = propagate_covariance(&[x + y, x - y], &["x", "y"], Some(&cov)).unwrap();
+/// assert_eq!(out[0][1].as_number(), Some(-3.0));
+/// ```
+///
+/// # Errors
+/// Returns `DiffError` if differentiation fails or matrix dimensions mismatch.
+pub fn propagate_covariance(
+    exprs: &[Expr],
+    variables: &[&str],
+    covariance: Option<&CovarianceMatrix>,
+) -> Result<Vec<Vec<Expr>>, DiffError> {
+    let mut builder = Uncertainty::new();
+    if let Some(cov) = covariance {
+        builder = builder.covariance(cov);
+    }
+    builder.output_covariance(exprs, variables)
+}
+
 /// Compute relative uncertainty expression: `σ_f` / |f|
 ///
 /// Returns the symbolic expression for the relative uncertainty.
@@ -173,7 +426,7 @@ impl CovEntry {
     #[must_use]
     pub fn is_zero(&self) -> bool {
         match self {
-            Self::Num(n) => n.abs() < EPSILON,
+            Self::Num(n) => crate::core::traits::is_zero(*n),
             Self::Symbolic(e) => e.is_zero_num(),
         }
     }
@@ -224,7 +477,7 @@ impl CovarianceMatrix {
         for (i, row_i) in entries.iter().enumerate() {
             for (j, entry_ij) in row_i.iter().enumerate().skip(i.saturating_add(1)) {
                 if let (CovEntry::Num(a), CovEntry::Num(b)) = (entry_ij, &entries[j][i])
-                    && (a - b).abs() >= EPSILON
+                    && (a - b).abs() > EPSILON * a.abs().max(b.abs())
                 {
                     return Err(DiffError::UnsupportedOperation(format!(
                         "Covariance matrix must be symmetric: Cov[{i}][{j}]={a} != Cov[{j}][{i}]={b}"
@@ -254,8 +507,8 @@ impl CovarianceMatrix {
         let n = var_names.len();
         let mut entries = vec![vec![CovEntry::Num(0.0); n]; n];
         for (i, name) in var_names.iter().enumerate() {
-            let sigma_sq =
-                Expr::pow_static(Expr::symbol(format!("sigma_{name}")), Expr::number(2.0));
+            let sigma = symb(&format!("sigma_{name}")).as_uncertainty_of(name);
+            let sigma_sq = Expr::pow_static(sigma.to_expr(), Expr::number(2.0));
             entries[i][i] = CovEntry::Symbolic(sigma_sq);
         }
         Self { entries }
@@ -0,0 +1,49 @@
+use crate::core::{DiffError, Expr};
+use crate::core::{ExprKind, InternedSymbol, piecewise_parts, uncertainty_target};
+
+/// Variable whose uncertainty `sym` carries, if any
+///
+/// A symbol carries the uncertainty of `x` if it was marked with
+/// `Symbol::as_uncertainty_of("x")`, or if it is named `sigma_x` — the name the
+/// default diagonal covariance matrix uses.
+fn carried_variable<'var>(sym: &InternedSymbol, variables: &[&'var str]) -> Option<&'var str> {
+    if let Some(target) = uncertainty_target(sym.key()) {
+        return variables.iter().copied().find(|v| *v == &*target);
+    }
+    let rest = sym.name()?.strip_prefix("sigma_")?;
+    variables.iter().copied().find(|v| *v == rest)
+}
+
+/// Refuse expressions that already carry the uncertainty of one of `variables`
+///
+/// # Errors
+/// Returns `DiffError::UncertaintyAlreadyPropagated` naming the first offending symbol.
+pub fn reject_carried_uncertainty(expr: &Expr, variables: &[&str]) -> Result<(), DiffError> {
+    let mut stack = vec![expr];
+    while let Some(node) = stack.pop() {
+        match &node.kind {
+            ExprKind::Number(_) => {}
+            ExprKind::Symbol(sym) => {
+                if let Some(var) = carried_variable(sym, variables) {
+                    return Err(DiffError::UncertaintyAlreadyPropagated {
+                        var: var.to_owned(),
+                        symbol: sym.to_string(),
+                    });
+                }
+            }
+            ExprKind::FunctionCall { args, .. } | ExprKind::Sum(args) | ExprKind::Product(args) => {
+                stack.extend(args.iter().map(AsRef::as_ref));
+            }
+            ExprKind::Div(l, r) | ExprKind::Pow(l, r) => {
+                stack.push(l);
+                stack.push(r);
+            }
+            ExprKind::Derivative { inner, .. } => stack.push(inner),
+            ExprKind::Poly(poly) => stack.push(poly.base()),
+            ExprKind::Piecewise { branches, default } => {
+                stack.extend(piecewise_parts(branches, default).map(AsRef::as_ref));
+            }
+        }
+    }
+    Ok(())
+}
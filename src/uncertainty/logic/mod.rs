@@ -1,7 +1,9 @@
+pub(super) mod carried;
 pub(super) mod propagate;
 
 pub(super) use super::CovarianceMatrix;
-pub(super) use propagate::compute_uncertainty_terms;
+pub(super) use carried::reject_carried_uncertainty;
+pub(super) use propagate::{compute_covariance_terms, compute_uncertainty_terms};
 
 #[cfg(test)]
 mod tests;
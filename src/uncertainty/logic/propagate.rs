@@ -44,3 +44,47 @@ pub fn compute_uncertainty_terms(
     }
     Ok(terms)
 }
+
+/// Compute the terms of the covariance of two outputs f and g
+/// Cov(f, g) = Σᵢ Σⱼ (∂f/∂xᵢ)(∂g/∂xⱼ) Cov(xᵢ, xⱼ)
+///
+/// # Arguments
+/// * `left` - The simplified partial derivatives of f
+/// * `right` - The simplified partial derivatives of g
+/// * `cov` - The covariance matrix Cov(xᵢ, xⱼ)
+/// * `n` - Number of variables
+///
+/// # Returns
+/// A list of terms to be summed up for the covariance
+pub fn compute_covariance_terms(
+    left: &[Expr],
+    right: &[Expr],
+    cov: &CovarianceMatrix,
+    n: usize,
+) -> Result<Vec<Expr>, DiffError> {
+    let mut terms: Vec<Expr> = Vec::new();
+    for (i, left_i) in left.iter().enumerate().take(n) {
+        if left_i.is_zero_num() {
+            continue;
+        }
+        for (j, right_j) in right.iter().enumerate().take(n) {
+            if right_j.is_zero_num() {
+                continue;
+            }
+
+            let cov_entry = cov.get(i, j).ok_or_else(|| {
+                DiffError::UnsupportedOperation("Covariance matrix access out of bounds".to_owned())
+            })?;
+
+            if cov_entry.is_zero() {
+                continue;
+            }
+
+            terms.push(Expr::mul_expr(
+                Expr::mul_expr(left_i.clone(), right_j.clone()),
+                cov_entry.to_expr(),
+            ));
+        }
+    }
+    Ok(terms)
+}
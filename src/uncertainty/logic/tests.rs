@@ -1,5 +1,9 @@
-use super::super::api::{CovEntry, CovarianceMatrix, uncertainty_propagation};
-use crate::symb;
+use super::super::api::{
+    CovEntry, CovarianceMatrix, Uncertainty, propagate_covariance, propagated_variance,
+    uncertainty_propagation,
+};
+use crate::{CompiledEvaluator, DiffError, Expr, symb};
+use std::collections::HashMap;
 
 #[test]
 fn test_simple_sum_uncorrelated() {
@@ -62,3 +66,258 @@ fn test_single_variable() {
     let display = format!("{result}");
     assert!(!display.is_empty());
 }
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_propagate_on_injected_pool_matches_global() {
+    let x = symb("x");
+    let y = symb("y");
+    let expr = x * y.sin();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .expect("Should build pool");
+
+    let global = Uncertainty::new()
+        .propagate(&expr, &["x", "y"])
+        .expect("global pool");
+    let pooled = Uncertainty::new()
+        .thread_pool(&pool)
+        .propagate(&expr, &["x", "y"])
+        .expect("injected pool");
+    assert_eq!(global, pooled);
+}
+
+#[test]
+fn test_double_propagation_is_rejected() {
+    let x = symb("x");
+    let y = symb("y");
+    let sigma = uncertainty_propagation(&(x * y), &["x", "y"], None).expect("first pass");
+
+    let again = uncertainty_propagation(&sigma, &["x", "y"], None);
+    assert!(matches!(
+        again,
+        Err(DiffError::UncertaintyAlreadyPropagated { ref var, ref symbol })
+            if *symbol == format!("sigma_{var}")
+    ));
+}
+
+#[test]
+fn test_marked_uncertainty_symbol_is_rejected() {
+    let x = symb("x");
+    let dx = symb("unc_test_dx").as_uncertainty_of("x");
+    let expr = x.pow(2.0) + dx;
+
+    let result = uncertainty_propagation(&expr, &["x"], None);
+    assert!(matches!(
+        result,
+        Err(DiffError::UncertaintyAlreadyPropagated { ref symbol, .. }) if symbol == "unc_test_dx"
+    ));
+    // Uncertainties of other variables are ordinary parameters
+    assert!(uncertainty_propagation(&expr, &["unc_test_other"], None).is_ok());
+}
+
+#[test]
+fn test_sigma_of_other_variable_is_a_parameter() {
+    let x = symb("x");
+    let expr = x * symb("sigma_t");
+    assert!(uncertainty_propagation(&expr, &["x"], None).is_ok());
+}
+
+#[test]
+fn test_symbolic_covariance_is_marked() {
+    let cov = CovarianceMatrix::diagonal_symbolic(&["unc_test_marked"]);
+    let entry = cov.get(0, 0).expect("diagonal entry").to_expr();
+    let sigma = entry.variables().into_iter().next().expect("sigma symbol");
+    assert_eq!(
+        symb(&sigma).uncertainty_of().as_deref(),
+        Some("unc_test_marked")
+    );
+}
+
+#[test]
+fn test_composed_matches_direct_propagation() {
+    let x = symb("x");
+    let y = symb("y");
+    let u = symb("u");
+    let inner = x.pow(2.0) * y;
+    let outer = u.sin();
+
+    let composed = Uncertainty::new()
+        .propagate_composed(&outer, &[("u", &inner)], &["x", "y"])
+        .expect("composed");
+    let direct =
+        uncertainty_propagation(&outer.substitute("u", &inner), &["x", "y"], None).expect("direct");
+    assert_eq!(composed, direct);
+}
+
+#[test]
+fn test_composed_with_direct_dependence() {
+    // The outer function depends on y both through u and directly
+    let x = symb("x");
+    let y = symb("y");
+    let u = symb("u");
+    let inner = x.pow(2.0) * y;
+    let outer = u.sin() + u * y;
+
+    let composed = Uncertainty::new()
+        .propagate_composed(&outer, &[("u", &inner)], &["x", "y"])
+        .expect("composed");
+    let direct =
+        uncertainty_propagation(&outer.substitute("u", &inner), &["x", "y"], None).expect("direct");
+
+    for (xv, yv) in [(0.7, -0.3), (1.2, 0.4), (-2.0, 1.5)] {
+        let vars: HashMap<&str, f64> = [("x", xv), ("y", yv), ("sigma_x", 0.1), ("sigma_y", 0.2)]
+            .into_iter()
+            .collect();
+        let eval = |expr: &Expr| {
+            expr.evaluate(&vars, &HashMap::new())
+                .as_number()
+                .expect("numeric")
+        };
+        let (got, expected) = (eval(&composed), eval(&direct));
+        assert!(
+            (got - expected).abs() <= 1e-12 * expected.abs(),
+            "{got} != {expected} at x={xv}, y={yv}"
+        );
+    }
+}
+
+#[test]
+fn test_composed_through_several_intermediates() {
+    let x = symb("x");
+    let y = symb("y");
+    let outer = symb("p") * symb("q").exp();
+    let gp = x + y;
+    let gq = x * y;
+
+    let cov = CovarianceMatrix::diagonal(vec![CovEntry::Num(0.01), CovEntry::Num(0.04)]);
+    let composed = Uncertainty::new()
+        .covariance(&cov)
+        .propagate_composed(&outer, &[("p", &gp), ("q", &gq)], &["x", "y"])
+        .expect("composed");
+    let direct = Uncertainty::new()
+        .covariance(&cov)
+        .propagate(
+            &outer.substitute("p", &gp).substitute("q", &gq),
+            &["x", "y"],
+        )
+        .expect("direct");
+
+    let vars: HashMap<&str, f64> = [("x", 0.7), ("y", -0.3)].into_iter().collect();
+    let eval = |expr: &Expr| {
+        expr.evaluate(&vars, &HashMap::new())
+            .as_number()
+            .expect("numeric")
+    };
+    assert!((eval(&composed) - eval(&direct)).abs() < 1e-12);
+}
+
+#[test]
+fn test_composed_rejects_nested_intermediates() {
+    let x = symb("x");
+    let (u, v) = (symb("u"), symb("v"));
+    let gu = x.sin();
+    let gv = u.pow(2.0);
+    let nested = Uncertainty::new().propagate_composed(&(u + v), &[("u", &gu), ("v", &gv)], &["x"]);
+    assert!(matches!(nested, Err(DiffError::UnsupportedOperation(_))));
+
+    let shadowed = Uncertainty::new().propagate_composed(&x.sin(), &[("x", &gu)], &["x"]);
+    assert!(matches!(shadowed, Err(DiffError::UnsupportedOperation(_))));
+}
+
+/// 2×2 covariance with standard deviations `sigma_x`, `sigma_y` and correlation `rho`
+fn correlated_cov() -> CovarianceMatrix {
+    let (sx, sy, rho) = (symb("sigma_x"), symb("sigma_y"), symb("rho"));
+    let cross = CovEntry::Symbolic(rho * sx * sy);
+    CovarianceMatrix::new(vec![
+        vec![CovEntry::Symbolic(sx.pow(2.0)), cross.clone()],
+        vec![cross, CovEntry::Symbolic(sy.pow(2.0))],
+    ])
+    .expect("valid covariance")
+}
+
+#[test]
+fn test_variance_of_correlated_product() {
+    let (x, y) = (symb("x"), symb("y"));
+    let variance =
+        propagated_variance(&(x * y), &["x", "y"], Some(&correlated_cov())).expect("variance");
+    let sigma =
+        uncertainty_propagation(&(x * y), &["x", "y"], Some(&correlated_cov())).expect("sigma");
+
+    let params = ["x", "y", "sigma_x", "sigma_y", "rho"];
+    let variance_eval = CompiledEvaluator::compile(&variance, &params, None).expect("compile");
+    let sigma_eval = CompiledEvaluator::compile(&sigma, &params, None).expect("compile");
+    for [xv, yv, sx, sy, rho] in [
+        [2.0_f64, 3.0, 0.1, 0.2, 0.5],
+        [-1.5, 0.7, 0.3, 0.05, -0.8],
+        [4.0, -2.0, 0.2, 0.2, 0.0],
+    ] {
+        // Var(xy) ≈ y²σx² + x²σy² + 2xyρσxσy
+        let expected = (2.0 * xv * yv * rho * sx).mul_add(sy, (yv * sx).hypot(xv * sy).powi(2));
+        let got = variance_eval.evaluate(&[xv, yv, sx, sy, rho]);
+        assert!(
+            (got - expected).abs() <= 1e-12 * expected,
+            "{got} != {expected}"
+        );
+        let std_dev = sigma_eval.evaluate(&[xv, yv, sx, sy, rho]);
+        assert!(std_dev.mul_add(std_dev, -expected).abs() <= 1e-12 * expected);
+    }
+}
+
+#[test]
+fn test_covariance_of_two_outputs() {
+    let (x, y) = (symb("x"), symb("y"));
+    let outputs = [x * y, x / y];
+    let out = Uncertainty::new()
+        .covariance(&correlated_cov())
+        .output_covariance(&outputs, &["x", "y"])
+        .expect("output covariance");
+    assert_eq!(out.len(), 2);
+    assert_eq!(out[0][1], out[1][0]);
+    assert_eq!(
+        out[1][1],
+        propagated_variance(&outputs[1], &["x", "y"], Some(&correlated_cov())).expect("variance")
+    );
+
+    let params = ["x", "y", "sigma_x", "sigma_y", "rho"];
+    let point = [2.0_f64, 4.0, 0.1, 0.3, 0.25];
+    let [xv, yv, sx, sy, rho] = point;
+    // J = [[y, x], [1/y, -x/y²]]
+    let jacobian = [[yv, xv], [1.0 / yv, -xv / (yv * yv)]];
+    let sigma = [[sx * sx, rho * sx * sy], [rho * sx * sy, sy * sy]];
+    for a in 0..2 {
+        for b in 0..2 {
+            let mut expected = 0.0;
+            for i in 0..2 {
+                for j in 0..2 {
+                    expected = (jacobian[a][i] * sigma[i][j]).mul_add(jacobian[b][j], expected);
+                }
+            }
+            let got = CompiledEvaluator::compile(&out[a][b], &params, None)
+                .expect("compile")
+                .evaluate(&point);
+            assert!(
+                (got - expected).abs() <= 1e-12 * expected.abs().max(1e-3),
+                "[{a}][{b}]: {got} != {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_covariance_of_numeric_inputs() {
+    let (x, y) = (symb("x"), symb("y"));
+    let cov = CovarianceMatrix::new(vec![
+        vec![CovEntry::Num(1.0), CovEntry::Num(0.5)],
+        vec![CovEntry::Num(0.5), CovEntry::Num(4.0)],
+    ])
+    .expect("valid covariance");
+    let out = propagate_covariance(&[x + y, x - y], &["x", "y"], Some(&cov)).expect("covariance");
+    let numbers: Vec<Vec<Option<f64>>> = out
+        .iter()
+        .map(|row| row.iter().map(Expr::as_number).collect())
+        .collect();
+    assert_eq!(numbers, [[Some(6.0), Some(-3.0)], [Some(-3.0), Some(4.0)]]);
+}
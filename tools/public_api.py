@@ -0,0 +1,335 @@
+#!/usr/bin/env python3
+"""
+Public API listing of the symb_anafis crate, one item per line.
+
+Builds the rustdoc JSON of the library with the nightly toolchain (default features) and
+prints every item a downstream crate can name: modules, types, variants, public fields,
+inherent methods, trait methods and implementations of this crate's traits. The snapshot
+test in src/tests/public_api_tests.rs compares this output with
+src/tests/golden/public_api.txt.
+
+Usage:
+    python3 tools/public_api.py            # print the listing
+    python3 tools/public_api.py --bless    # rewrite the golden file
+"""
+
+import json
+import subprocess
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+GOLDEN = ROOT / "src" / "tests" / "golden" / "public_api.txt"
+TARGET_DIR = ROOT / "target" / "public-api"
+
+
+def build_json():
+    subprocess.run(
+        [
+            "cargo", "+nightly", "rustdoc", "--lib", "--quiet",
+            "--target-dir", str(TARGET_DIR),
+            "--", "-Z", "unstable-options", "--output-format", "json",
+        ],
+        cwd=ROOT,
+        check=True,
+    )
+    with open(TARGET_DIR / "doc" / "symb_anafis.json", encoding="utf-8") as f:
+        return json.load(f)
+
+
+class Lister:
+    def __init__(self, doc):
+        self.doc = doc
+        self.index = doc["index"]
+        self.lines = set()
+        self.seen = set()
+
+    def item(self, item_id):
+        return self.index.get(str(item_id))
+
+    # ---- rendering -------------------------------------------------------------
+
+    def path_name(self, path):
+        # The last segment only: source paths of re-exported items are private
+        name = path.get("path") or path.get("name") or "_"
+        return name.rsplit("::", 1)[-1]
+
+    def generic_args(self, args):
+        if not args:
+            return ""
+        if "angle_bracketed" in args:
+            ab = args["angle_bracketed"]
+            parts = []
+            for arg in ab.get("args", []):
+                if "type" in arg:
+                    parts.append(self.ty(arg["type"]))
+                elif "lifetime" in arg:
+                    parts.append(arg["lifetime"])
+                elif "const" in arg:
+                    parts.append(arg["const"].get("expr", "_"))
+                else:
+                    parts.append("_")
+            for c in ab.get("constraints", []):
+                binding = c.get("binding", {})
+                if "equality" in binding:
+                    eq = binding["equality"]
+                    value = self.ty(eq["type"]) if "type" in eq else "_"
+                    parts.append(f"{c['name']} = {value}")
+            return f"<{', '.join(parts)}>" if parts else ""
+        if "parenthesized" in args:
+            p = args["parenthesized"]
+            inputs = ", ".join(self.ty(t) for t in p.get("inputs", []))
+            out = f" -> {self.ty(p['output'])}" if p.get("output") else ""
+            return f"({inputs}){out}"
+        return ""
+
+    def bound(self, b):
+        if "trait_bound" in b:
+            tb = b["trait_bound"]
+            modifier = "?" if tb.get("modifier") == "maybe" else ""
+            t = tb["trait"]
+            return f"{modifier}{self.path_name(t)}{self.generic_args(t.get('args'))}"
+        if "outlives" in b:
+            return b["outlives"]
+        return "_"
+
+    def ty(self, t):
+        if t is None:
+            return "()"
+        if "resolved_path" in t:
+            p = t["resolved_path"]
+            return f"{self.path_name(p)}{self.generic_args(p.get('args'))}"
+        if "primitive" in t:
+            return t["primitive"]
+        if "generic" in t:
+            return t["generic"]
+        if "borrowed_ref" in t:
+            r = t["borrowed_ref"]
+            lifetime = f"{r['lifetime']} " if r.get("lifetime") else ""
+            mutable = "mut " if r.get("is_mutable") else ""
+            return f"&{lifetime}{mutable}{self.ty(r['type'])}"
+        if "raw_pointer" in t:
+            r = t["raw_pointer"]
+            return f"*{'mut' if r.get('is_mutable') else 'const'} {self.ty(r['type'])}"
+        if "slice" in t:
+            return f"[{self.ty(t['slice'])}]"
+        if "array" in t:
+            return f"[{self.ty(t['array']['type'])}; {t['array']['len']}]"
+        if "tuple" in t:
+            return f"({', '.join(self.ty(x) for x in t['tuple'])})"
+        if "impl_trait" in t:
+            return "impl " + " + ".join(self.bound(b) for b in t["impl_trait"])
+        if "dyn_trait" in t:
+            traits = []
+            for pt in t["dyn_trait"].get("traits", []):
+                tr = pt["trait"]
+                traits.append(f"{self.path_name(tr)}{self.generic_args(tr.get('args'))}")
+            lifetime = t["dyn_trait"].get("lifetime")
+            if lifetime:
+                traits.append(lifetime)
+            return "dyn " + " + ".join(traits)
+        if "qualified_path" in t:
+            q = t["qualified_path"]
+            trait = q.get("trait")
+            self_ty = self.ty(q["self_type"])
+            if trait:
+                return f"<{self_ty} as {self.path_name(trait)}>::{q['name']}"
+            return f"{self_ty}::{q['name']}"
+        if "function_pointer" in t:
+            fp = t["function_pointer"]
+            sig = fp.get("sig") or fp.get("decl", {})
+            inputs = ", ".join(self.ty(x[1]) for x in sig.get("inputs", []))
+            out = f" -> {self.ty(sig['output'])}" if sig.get("output") else ""
+            return f"fn({inputs}){out}"
+        if "infer" in t:
+            return "_"
+        return "_"
+
+    def generics(self, generics):
+        params = []
+        for p in generics.get("params", []):
+            kind = p.get("kind", {})
+            if "type" in kind:
+                if kind["type"].get("is_synthetic"):
+                    continue
+                bounds = kind["type"].get("bounds", [])
+                rendered = p["name"]
+                if bounds:
+                    rendered += ": " + " + ".join(self.bound(b) for b in bounds)
+                params.append(rendered)
+            elif "lifetime" in kind:
+                params.append(p["name"])
+            elif "const" in kind:
+                params.append(f"const {p['name']}: {self.ty(kind['const']['type'])}")
+        return f"<{', '.join(params)}>" if params else ""
+
+    def where_clause(self, generics):
+        preds = []
+        for w in generics.get("where_predicates", []):
+            if "bound_predicate" in w:
+                bp = w["bound_predicate"]
+                bounds = " + ".join(self.bound(b) for b in bp.get("bounds", []))
+                preds.append(f"{self.ty(bp['type'])}: {bounds}")
+        return f" where {', '.join(preds)}" if preds else ""
+
+    def signature(self, path, func):
+        sig = func.get("sig") or func.get("decl", {})
+        header = func.get("header", {})
+        prefix = ""
+        if header.get("is_const"):
+            prefix += "const "
+        if header.get("is_unsafe"):
+            prefix += "unsafe "
+        inputs = []
+        for arg_name, arg_ty in sig.get("inputs", []):
+            if arg_name == "self":
+                rendered = self.ty(arg_ty)
+                inputs.append(
+                    {"&Self": "&self", "&mut Self": "&mut self", "Self": "self"}.get(
+                        rendered, f"self: {rendered}"
+                    )
+                )
+            else:
+                inputs.append(f"{arg_name}: {self.ty(arg_ty)}")
+        out = f" -> {self.ty(sig['output'])}" if sig.get("output") else ""
+        generics = func.get("generics", {})
+        return (
+            f"{prefix}fn {path}{self.generics(generics)}({', '.join(inputs)}){out}"
+            f"{self.where_clause(generics)}"
+        )
+
+    # ---- walking ---------------------------------------------------------------
+
+    def attrs(self, item):
+        rendered = []
+        for a in item.get("attrs", []):
+            text = a if isinstance(a, str) else json.dumps(a)
+            if "non_exhaustive" in text:
+                rendered.append("#[non_exhaustive] ")
+        return "".join(rendered)
+
+    def add(self, line):
+        self.lines.add(line)
+
+    def walk(self, item_id, path):
+        item = self.item(item_id)
+        if item is None:
+            return
+        key = (str(item_id), path)
+        if key in self.seen:
+            return
+        self.seen.add(key)
+        inner = item["inner"]
+        kind = next(iter(inner))
+        data = inner[kind]
+        attrs = self.attrs(item)
+
+        if kind == "module":
+            if path != "symb_anafis":
+                self.add(f"pub mod {path}")
+            for child in data.get("items", []):
+                self.walk_child(child, path)
+        elif kind == "struct":
+            self.add(f"{attrs}pub struct {path}{self.generics(data.get('generics', {}))}")
+            fields = data.get("kind", {})
+            for field_id in (fields.get("plain", {}) or {}).get("fields", []):
+                field = self.item(field_id)
+                if field and field.get("visibility") == "public":
+                    self.add(f"pub {path}::{field['name']}: {self.ty(field['inner']['struct_field'])}")
+            self.walk_impls(data.get("impls", []), path)
+        elif kind == "enum":
+            self.add(f"{attrs}pub enum {path}{self.generics(data.get('generics', {}))}")
+            for variant_id in data.get("variants", []):
+                variant = self.item(variant_id)
+                if variant:
+                    self.add(f"{self.attrs(variant)}pub {path}::{variant['name']}")
+            self.walk_impls(data.get("impls", []), path)
+        elif kind == "trait":
+            sealed = " (sealed)" if self.is_sealed(data) else ""
+            self.add(f"pub trait {path}{self.generics(data.get('generics', {}))}{sealed}")
+            for child_id in data.get("items", []):
+                child = self.item(child_id)
+                if child and "function" in child["inner"]:
+                    self.add(self.signature(f"{path}::{child['name']}", child["inner"]["function"]))
+            for impl_id in data.get("implementations", []):
+                impl_item = self.item(impl_id)
+                if impl_item:
+                    impl = impl_item["inner"]["impl"]
+                    generics = self.generics(impl.get("generics", {}))
+                    self.add(f"impl{generics} {path} for {self.ty(impl['for'])}")
+        elif kind == "function":
+            self.add(f"pub {self.signature(path, data)}")
+        elif kind == "constant":
+            self.add(f"pub const {path}: {self.ty(data.get('type'))}")
+        elif kind == "static":
+            self.add(f"pub static {path}: {self.ty(data.get('type'))}")
+        elif kind == "type_alias":
+            self.add(f"pub type {path} = {self.ty(data.get('type'))}")
+        elif kind in ("macro", "proc_macro"):
+            self.add(f"pub macro {path}!")
+
+    def is_sealed(self, trait):
+        # A supertrait from a private module cannot be named, so it cannot be implemented
+        for b in trait.get("bounds", []):
+            tb = b.get("trait_bound")
+            if tb and "Sealed" in self.path_name(tb["trait"]):
+                return True
+        return False
+
+    def walk_child(self, child_id, parent):
+        child = self.item(child_id)
+        if child is None or child.get("visibility") != "public":
+            return
+        inner = child["inner"]
+        if "use" in inner:
+            use = inner["use"]
+            if use.get("id") is None:
+                return
+            if use.get("is_glob"):
+                target = self.item(use["id"])
+                if target and "module" in target["inner"]:
+                    for grandchild in target["inner"]["module"].get("items", []):
+                        self.walk_child(grandchild, parent)
+                return
+            self.walk(use["id"], f"{parent}::{use['name']}")
+            return
+        if child.get("name"):
+            self.walk(child_id, f"{parent}::{child['name']}")
+
+    def walk_impls(self, impl_ids, path):
+        for impl_id in impl_ids:
+            impl_item = self.item(impl_id)
+            if impl_item is None:
+                continue
+            impl = impl_item["inner"]["impl"]
+            if impl.get("trait") is not None or impl.get("is_synthetic") or impl.get("blanket_impl"):
+                continue
+            for method_id in impl.get("items", []):
+                method = self.item(method_id)
+                if method is None or method.get("visibility") != "public":
+                    continue
+                if "function" in method["inner"]:
+                    method_path = f"{path}::{method['name']}"
+                    self.add(f"pub {self.signature(method_path, method['inner']['function'])}")
+                elif "assoc_const" in method["inner"] or "constant" in method["inner"]:
+                    self.add(f"pub const {path}::{method['name']}")
+
+
+def listing():
+    doc = build_json()
+    lister = Lister(doc)
+    lister.walk(doc["root"], "symb_anafis")
+    return "\n".join(sorted(lister.lines)) + "\n"
+
+
+def main():
+    text = listing()
+    if "--bless" in sys.argv[1:]:
+        GOLDEN.write_text(text, encoding="utf-8")
+        print(f"wrote {GOLDEN.relative_to(ROOT)} ({text.count(chr(10))} items)")
+    else:
+        sys.stdout.write(text)
+
+
+if __name__ == "__main__":
+    main()